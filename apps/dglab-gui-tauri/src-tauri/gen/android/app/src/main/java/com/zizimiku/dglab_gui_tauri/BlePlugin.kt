@@ -0,0 +1,59 @@
+package com.zizimiku.dglab_gui_tauri
+
+import android.Manifest
+import android.app.Activity
+import android.content.Intent
+import android.os.Build
+import androidx.core.content.ContextCompat
+import app.tauri.annotation.Command
+import app.tauri.annotation.InvokeArg
+import app.tauri.annotation.Permission
+import app.tauri.annotation.TauriPlugin
+import app.tauri.plugin.Invoke
+import app.tauri.plugin.JSObject
+import app.tauri.plugin.Plugin
+
+/** `setForeground` 的参数 */
+@InvokeArg
+class ForegroundArgs {
+    var active: Boolean = false
+}
+
+/**
+ * BLE 权限插件
+ *
+ * 权限检查和请求使用 Plugin 自带的 checkPermissions / requestPermissions，
+ * 对应的权限在 AndroidManifest.xml 中声明：Android 12 起只需要 `bluetooth`，11 及以下只需要 `location`。
+ * 同时负责启动和停止后台输出时的前台服务（见 [OutputService]）。
+ */
+@TauriPlugin(
+    permissions = [
+        Permission(
+            strings = [Manifest.permission.BLUETOOTH_SCAN, Manifest.permission.BLUETOOTH_CONNECT],
+            alias = "bluetooth"
+        ),
+        Permission(strings = [Manifest.permission.ACCESS_FINE_LOCATION], alias = "location")
+    ]
+)
+class BlePlugin(private val activity: Activity) : Plugin(activity) {
+    /** 系统 API 级别，用于判断需要哪些权限 */
+    @Command
+    fun sdkVersion(invoke: Invoke) {
+        val ret = JSObject()
+        ret.put("sdkInt", Build.VERSION.SDK_INT)
+        invoke.resolve(ret)
+    }
+
+    /** 启动或停止前台服务 */
+    @Command
+    fun setForeground(invoke: Invoke) {
+        val args = invoke.parseArgs(ForegroundArgs::class.java)
+        val intent = Intent(activity, OutputService::class.java)
+        if (args.active) {
+            ContextCompat.startForegroundService(activity, intent)
+        } else {
+            activity.stopService(intent)
+        }
+        invoke.resolve()
+    }
+}
@@ -0,0 +1,57 @@
+package com.zizimiku.dglab_gui_tauri
+
+import android.app.NotificationChannel
+import android.app.NotificationManager
+import android.app.PendingIntent
+import android.app.Service
+import android.content.Intent
+import android.content.pm.ServiceInfo
+import android.os.Build
+import android.os.IBinder
+import androidx.core.app.NotificationCompat
+
+/**
+ * 后台输出时的前台服务
+ *
+ * 会话运行期间应用切到后台时启动，显示常驻通知，避免进程被系统回收后设备停在最后的强度。
+ * 服务本身不做任何事，输出仍由 Rust 端的会话运行时负责；回到前台时停止。
+ */
+class OutputService : Service() {
+    override fun onBind(intent: Intent?): IBinder? = null
+
+    override fun onStartCommand(intent: Intent?, flags: Int, startId: Int): Int {
+        val manager = getSystemService(NotificationManager::class.java)
+        if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.O) {
+            manager.createNotificationChannel(
+                NotificationChannel(CHANNEL_ID, "后台输出", NotificationManager.IMPORTANCE_LOW)
+            )
+        }
+
+        // 点击通知回到应用
+        val launch = packageManager.getLaunchIntentForPackage(packageName)
+        val content = PendingIntent.getActivity(this, 0, launch, PendingIntent.FLAG_IMMUTABLE)
+        val notification = NotificationCompat.Builder(this, CHANNEL_ID)
+            .setContentTitle("DG-LAB 正在输出")
+            .setContentText("点击返回应用暂停或停止")
+            .setSmallIcon(R.mipmap.ic_launcher)
+            .setContentIntent(content)
+            .setOngoing(true)
+            .build()
+
+        if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.Q) {
+            startForeground(
+                NOTIFICATION_ID,
+                notification,
+                ServiceInfo.FOREGROUND_SERVICE_TYPE_CONNECTED_DEVICE
+            )
+        } else {
+            startForeground(NOTIFICATION_ID, notification)
+        }
+        return START_NOT_STICKY
+    }
+
+    companion object {
+        private const val CHANNEL_ID = "output"
+        private const val NOTIFICATION_ID = 1
+    }
+}
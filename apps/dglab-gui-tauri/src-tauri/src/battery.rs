@@ -0,0 +1,61 @@
+//! 电量转发
+//!
+//! 订阅会话事件，将设备电量、估算的剩余使用时间和低电量限制转发给前端。
+
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::broadcast;
+use tracing::debug;
+
+use dglab_core::session::SessionEvent;
+
+use crate::events::{
+    event_names, DeviceBatteryLimitedEvent, DeviceBatteryUpdatedEvent, DevicePowerChangedEvent,
+};
+use crate::state::AppState;
+
+/// 启动电量转发任务
+pub fn spawn_listener(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        let mut events = state.session_manager.read().await.subscribe_events();
+
+        loop {
+            match events.recv().await {
+                Ok(SessionEvent::Battery(device_id, battery, remaining)) => {
+                    let _ = app.emit(
+                        event_names::DEVICE_BATTERY_UPDATED,
+                        DeviceBatteryUpdatedEvent {
+                            device_id,
+                            battery,
+                            remaining_secs: remaining.map(|d| d.as_secs()),
+                        },
+                    );
+                }
+                Ok(SessionEvent::BatteryLimited(device_id, action)) => {
+                    // 限制可能已降低强度
+                    let manager = state.session_manager.read().await;
+                    if let Some(device) = manager.get_device(&device_id).await {
+                        let info = device.read().await.info();
+                        let _ = app.emit(
+                            event_names::DEVICE_POWER_CHANGED,
+                            DevicePowerChangedEvent {
+                                device_id: device_id.clone(),
+                                power_a: info.power_a,
+                                power_b: info.power_b,
+                            },
+                        );
+                    }
+                    let _ = app.emit(
+                        event_names::DEVICE_BATTERY_LIMITED,
+                        DeviceBatteryLimitedEvent { device_id, action },
+                    );
+                }
+                Ok(_) => {}
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    debug!("Battery listener lagged by {} events", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
@@ -0,0 +1,29 @@
+//! 窗口/会话状态持久化命令
+
+use tauri::State;
+
+use crate::persist::{self, SavedAppState};
+use crate::state::AppState;
+
+/// 获取上次保存的状态（启动时用于恢复设备、预设和强度）
+///
+/// 已超过有效期的 WiFi clientId 不会返回。
+#[tauri::command]
+pub async fn load_app_state(state: State<'_, AppState>) -> Result<SavedAppState, String> {
+    Ok(state.app_state.lock().await.clone())
+}
+
+/// 保存当前状态，返回保存后的状态
+///
+/// `last_preset` 记录前端选中但尚未应用的预设；通道强度取自最近连接的设备。
+/// 退出时会自动保存，前端也可在关键操作后主动调用。
+#[tauri::command]
+pub async fn save_app_state(
+    state: State<'_, AppState>,
+    last_preset: Option<String>,
+) -> Result<SavedAppState, String> {
+    if let Some(preset) = last_preset {
+        state.app_state.lock().await.last_preset = Some(preset);
+    }
+    persist::save(&state).await
+}
@@ -3,16 +3,20 @@
 use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Emitter, State};
 use tracing::{debug, info};
+use ts_rs::TS;
 
 use dglab_core::device::traits::DeviceInfo;
 use dglab_core::device::DeviceState;
-use dglab_protocol::ble::BleManager;
+use dglab_protocol::ble::{AdapterSelector, BleAvailability, BleManager};
 
 use crate::events::{event_names, DeviceStateChangedEvent};
+use crate::mobile::BlePermissions;
+use crate::persist::SavedDevice;
 use crate::state::AppState;
 
 /// 扫描到的设备信息
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
 pub struct ScannedDevice {
     /// 设备 ID
     pub id: String,
@@ -22,18 +26,77 @@ pub struct ScannedDevice {
     pub rssi: Option<i16>,
     /// 设备地址
     pub address: String,
+    /// 扫描到该设备的蓝牙适配器
+    pub adapter: String,
+}
+
+/// 蓝牙适配器
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct BluetoothAdapter {
+    /// 序号
+    pub index: usize,
+    /// 名称
+    pub name: String,
+}
+
+/// 创建 BLE 管理器（`adapter` 为适配器序号或名称，未指定时使用第一个）
+async fn new_ble_manager(adapter: Option<&str>) -> Result<BleManager, String> {
+    let selector = adapter
+        .map(|a| a.parse::<AdapterSelector>())
+        .transpose()
+        .map_err(|e| e.to_string())?;
+
+    BleManager::with_adapter(selector.as_ref())
+        .await
+        .map_err(|e| {
+            let error_msg = format!("创建蓝牙管理器失败: {}. 请检查蓝牙是否已启用", e);
+            tracing::error!("{}", error_msg);
+            error_msg
+        })
+}
+
+/// 列出蓝牙适配器
+#[tauri::command]
+pub async fn list_adapters() -> Result<Vec<BluetoothAdapter>, String> {
+    let adapters = BleManager::list_adapters()
+        .await
+        .map_err(|e| format!("获取蓝牙适配器失败: {}", e))?;
+
+    Ok(adapters
+        .into_iter()
+        .map(|a| BluetoothAdapter {
+            index: a.index,
+            name: a.name,
+        })
+        .collect())
+}
+
+/// 探测蓝牙可用性
+///
+/// 没有蓝牙时前端禁用扫描，WiFi 连接不受影响。
+#[tauri::command]
+pub async fn get_ble_availability() -> Result<BleAvailability, String> {
+    let availability = BleManager::availability().await;
+    debug!("Bluetooth availability: {}", availability);
+    Ok(availability)
 }
 
 /// 扫描 BLE 设备
 #[tauri::command]
-pub async fn scan_ble_devices(timeout_secs: Option<u64>) -> Result<Vec<ScannedDevice>, String> {
-    info!("Starting BLE device scan, timeout: {:?}", timeout_secs);
+pub async fn scan_ble_devices(
+    permissions: State<'_, BlePermissions>,
+    timeout_secs: Option<u64>,
+    adapter: Option<String>,
+) -> Result<Vec<ScannedDevice>, String> {
+    info!(
+        "Starting BLE device scan, timeout: {:?}, adapter: {:?}",
+        timeout_secs, adapter
+    );
 
-    let manager = BleManager::new().await.map_err(|e| {
-        let error_msg = format!("创建蓝牙管理器失败: {}. 请检查蓝牙是否已启用", e);
-        tracing::error!("{}", error_msg);
-        error_msg
-    })?;
+    permissions.ensure().await?;
+
+    let manager = new_ble_manager(adapter.as_deref()).await?;
 
     manager.start_scan().await.map_err(|e| {
         let error_msg = format!("启动扫描失败: {}. 请检查蓝牙权限", e);
@@ -64,6 +127,7 @@ pub async fn scan_ble_devices(timeout_secs: Option<u64>) -> Result<Vec<ScannedDe
             name: r.name,
             rssi: r.rssi,
             address: r.address,
+            adapter: r.adapter,
         })
         .collect();
 
@@ -76,14 +140,18 @@ pub async fn scan_ble_devices(timeout_secs: Option<u64>) -> Result<Vec<ScannedDe
 pub async fn connect_ble_device(
     app: AppHandle,
     state: State<'_, AppState>,
+    permissions: State<'_, BlePermissions>,
     device_id: String,
     device_name: String,
+    adapter: Option<String>,
 ) -> Result<DeviceInfo, String> {
     use dglab_core::device::{CoyoteDevice, Device};
     use std::sync::Arc;
 
     info!("Connecting to BLE device: {} ({})", device_name, device_id);
 
+    permissions.ensure().await?;
+
     // 检查是否已连接设备
     {
         let manager = state.session_manager.read().await;
@@ -96,11 +164,7 @@ pub async fn connect_ble_device(
     }
 
     // 创建新的 BLE manager（最稳定的方案）
-    let ble_manager = Arc::new(BleManager::new().await.map_err(|e| {
-        let error_msg = format!("创建蓝牙管理器失败: {}. 请检查蓝牙是否已启用", e);
-        tracing::error!("{}", error_msg);
-        error_msg
-    })?);
+    let ble_manager = Arc::new(new_ble_manager(adapter.as_deref()).await?);
 
     // 先扫描，确保能找到设备
     info!("Starting quick scan to find device...");
@@ -166,6 +230,22 @@ pub async fn connect_ble_device(
             tracing::error!("{}", error_msg);
             error_msg
         })?;
+
+        // 监听适配器事件：设备断开后重新出现时自动重连
+        match ble_manager.watch().await {
+            Ok(events) => {
+                let watcher = manager.watch_adapter(events);
+                if let Some(previous) = state
+                    .adapter_watchers
+                    .lock()
+                    .await
+                    .insert(device_id.clone(), watcher)
+                {
+                    previous.abort();
+                }
+            }
+            Err(e) => tracing::warn!("Failed to watch adapter events: {}", e),
+        }
     }
 
     // 发送状态变更事件
@@ -177,6 +257,12 @@ pub async fn connect_ble_device(
         },
     );
 
+    state.app_state.lock().await.last_device = Some(SavedDevice {
+        id: device_id.clone(),
+        name: device_name,
+        wifi: false,
+    });
+
     info!("Successfully connected to device: {}", device_id);
     Ok(info)
 }
@@ -243,6 +329,15 @@ pub async fn disconnect_device(
         let mut managers = state.ble_managers.write().await;
         managers.remove(&device_id);
     }
+    if let Some(watcher) = state.adapter_watchers.lock().await.remove(&device_id) {
+        watcher.abort();
+    }
+
+    // 清理运行时波形
+    {
+        let runtime = state.runtime.lock().await;
+        runtime.clear_device(&device_id).await;
+    }
 
     // 发送状态变更事件
     let _ = app.emit(
@@ -266,13 +361,10 @@ pub async fn get_device_info(
     debug!("Getting device info: {}", device_id);
 
     let manager = state.session_manager.read().await;
-    let device = manager
-        .get_device(&device_id)
+    manager
+        .device_info(&device_id)
         .await
-        .ok_or_else(|| format!("Device not found: {}", device_id))?;
-
-    let dev = device.read().await;
-    Ok(dev.info())
+        .ok_or_else(|| format!("Device not found: {}", device_id))
 }
 
 /// 获取设备状态
@@ -0,0 +1,35 @@
+//! 反馈按钮映射命令
+
+use tauri::State;
+use tracing::info;
+
+use dglab_core::feedback::FeedbackMapping;
+
+use crate::state::AppState;
+
+/// 获取反馈按钮映射
+#[tauri::command]
+pub async fn get_feedback_mapping(state: State<'_, AppState>) -> Result<FeedbackMapping, String> {
+    Ok(state.feedback_router.lock().await.mapping().clone())
+}
+
+/// 设置反馈按钮映射并保存到配置文件
+#[tauri::command]
+pub async fn set_feedback_mapping(
+    state: State<'_, AppState>,
+    mapping: FeedbackMapping,
+) -> Result<(), String> {
+    info!(
+        "Updating feedback mapping ({} buttons)",
+        mapping.actions.len()
+    );
+
+    let path = FeedbackMapping::default_path().map_err(|e| e.to_string())?;
+    mapping
+        .save(&path)
+        .await
+        .map_err(|e| format!("Failed to save feedback mapping: {}", e))?;
+
+    state.feedback_router.lock().await.set_mapping(mapping);
+    Ok(())
+}
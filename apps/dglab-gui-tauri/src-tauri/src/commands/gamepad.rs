@@ -0,0 +1,83 @@
+//! 手柄控制命令
+
+use tauri::{AppHandle, State};
+use tracing::info;
+
+use dglab_core::gamepad::GamepadMapping;
+
+use crate::gamepad;
+use crate::state::AppState;
+
+/// 开启手柄控制（已开启时切换到新设备）
+#[tauri::command]
+pub async fn start_gamepad(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    device_id: String,
+) -> Result<(), String> {
+    {
+        let manager = state.session_manager.read().await;
+        if manager.get_device(&device_id).await.is_none() {
+            return Err(format!("Device not found: {}", device_id));
+        }
+    }
+
+    let mut service = state.gamepad_service.lock().await;
+    if let Some((_, task)) = service.take() {
+        task.abort();
+    }
+    let task = gamepad::start(app, device_id.clone())
+        .map_err(|e| format!("Failed to start gamepad: {}", e))?;
+    *service = Some((device_id, task));
+    Ok(())
+}
+
+/// 关闭手柄控制，返回是否有服务被关闭
+#[tauri::command]
+pub async fn stop_gamepad(state: State<'_, AppState>) -> Result<bool, String> {
+    let Some((device_id, task)) = state.gamepad_service.lock().await.take() else {
+        return Ok(false);
+    };
+    info!("Stopping gamepad control on device {}", device_id);
+    task.abort();
+    Ok(true)
+}
+
+/// 获取手柄控制的设备 ID，未开启时为空
+#[tauri::command]
+pub async fn get_gamepad_device(state: State<'_, AppState>) -> Result<Option<String>, String> {
+    Ok(state
+        .gamepad_service
+        .lock()
+        .await
+        .as_ref()
+        .map(|(device_id, _)| device_id.clone()))
+}
+
+/// 获取手柄映射
+#[tauri::command]
+pub async fn get_gamepad_mapping(state: State<'_, AppState>) -> Result<GamepadMapping, String> {
+    Ok(state.gamepad_controller.lock().await.mapping().clone())
+}
+
+/// 设置手柄映射并保存到配置文件，运行中的手柄控制立即生效
+#[tauri::command]
+pub async fn set_gamepad_mapping(
+    state: State<'_, AppState>,
+    mapping: GamepadMapping,
+) -> Result<(), String> {
+    info!(
+        "Updating gamepad mapping ({} axes, {} buttons)",
+        mapping.axes.len(),
+        mapping.buttons.len()
+    );
+
+    let path = GamepadMapping::default_path().map_err(|e| e.to_string())?;
+    mapping
+        .save(&path)
+        .await
+        .map_err(|e| format!("Failed to save gamepad mapping: {}", e))?;
+
+    state.gamepad_controller.lock().await.set_mapping(mapping);
+    Ok(())
+}
@@ -0,0 +1,58 @@
+//! 全局快捷键命令
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+use tracing::info;
+use ts_rs::TS;
+
+use dglab_core::config::HotkeyConfig;
+
+use crate::hotkeys::{self, HotkeyError};
+use crate::state::AppState;
+
+/// 快捷键配置和注册状态
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct HotkeyStatus {
+    /// 快捷键配置
+    pub config: HotkeyConfig,
+    /// 已注册的快捷键数量
+    pub registered: usize,
+    /// 注册失败的快捷键
+    pub errors: Vec<HotkeyError>,
+}
+
+/// 当前快捷键状态
+async fn status(state: &AppState) -> HotkeyStatus {
+    let registry = state.hotkeys.lock().await;
+    HotkeyStatus {
+        config: state.config.config().hotkeys,
+        registered: registry.registered(),
+        errors: registry.errors().to_vec(),
+    }
+}
+
+/// 获取快捷键配置和注册状态
+#[tauri::command]
+pub async fn get_hotkeys(state: State<'_, AppState>) -> Result<HotkeyStatus, String> {
+    Ok(status(&state).await)
+}
+
+/// 校验并保存快捷键配置，立即重新注册并返回注册状态
+#[tauri::command]
+pub async fn set_hotkeys(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    config: HotkeyConfig,
+) -> Result<HotkeyStatus, String> {
+    info!("Updating hotkeys");
+
+    state
+        .config
+        .update(|c| c.hotkeys = config)
+        .await
+        .map_err(|e| format!("Failed to save hotkeys: {}", e))?;
+    hotkeys::apply(&app, &state.config.config().hotkeys).await;
+
+    Ok(status(&state).await)
+}
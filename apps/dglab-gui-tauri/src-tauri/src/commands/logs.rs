@@ -0,0 +1,47 @@
+//! 日志查看命令
+
+use std::str::FromStr;
+
+use tauri::State;
+use tracing::{info, Level};
+
+use crate::logs::LogLine;
+use crate::state::AppState;
+
+/// 获取最近的日志（从旧到新）
+///
+/// `min_level` 为最低级别（如 `warn`），`limit` 为最多返回的行数。
+#[tauri::command]
+pub async fn get_logs(
+    state: State<'_, AppState>,
+    min_level: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<LogLine>, String> {
+    let min_level = min_level
+        .map(|level| Level::from_str(&level).map_err(|_| format!("Invalid log level: {}", level)))
+        .transpose()?;
+    Ok(state.logs.lines(min_level, limit))
+}
+
+/// 清空日志
+#[tauri::command]
+pub async fn clear_logs(state: State<'_, AppState>) -> Result<(), String> {
+    state.logs.clear();
+    Ok(())
+}
+
+/// 获取当前日志过滤规则
+#[tauri::command]
+pub async fn get_log_level(state: State<'_, AppState>) -> Result<String, String> {
+    Ok(state.logs.filter())
+}
+
+/// 设置日志过滤规则
+///
+/// 接受级别（如 `debug`）或 `EnvFilter` 规则（如 `info,dglab=trace`），立即生效。
+#[tauri::command]
+pub async fn set_log_level(state: State<'_, AppState>, level: String) -> Result<String, String> {
+    state.logs.set_filter(&level)?;
+    info!("Log filter set to {}", level);
+    Ok(state.logs.filter())
+}
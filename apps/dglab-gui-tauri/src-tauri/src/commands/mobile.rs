@@ -0,0 +1,27 @@
+//! 移动端相关命令
+
+use tauri::State;
+use tracing::info;
+
+use crate::mobile::{BlePermissionStatus, BlePermissions};
+
+/// 获取 BLE 权限状态
+#[tauri::command]
+pub async fn get_ble_permissions(
+    permissions: State<'_, BlePermissions>,
+) -> Result<BlePermissionStatus, String> {
+    permissions.status().await
+}
+
+/// 请求 BLE 权限（Android 会弹出系统授权对话框）
+#[tauri::command]
+pub async fn request_ble_permissions(
+    permissions: State<'_, BlePermissions>,
+) -> Result<BlePermissionStatus, String> {
+    let status = permissions.request().await?;
+    info!(
+        "Bluetooth permissions: bluetooth={:?}, location={:?}",
+        status.bluetooth, status.location
+    );
+    Ok(status)
+}
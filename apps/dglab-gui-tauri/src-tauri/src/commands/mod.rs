@@ -1,6 +1,17 @@
 //! Tauri 命令模块
 
+pub mod app_state;
 pub mod device;
+pub mod feedback;
+pub mod gamepad;
+pub mod hotkeys;
+pub mod logs;
+pub mod mobile;
 pub mod power;
+pub mod preset;
+pub mod runtime;
+pub mod schedule;
 pub mod session;
+pub mod settings;
+pub mod waveform;
 pub mod wifi;
@@ -1,9 +1,11 @@
 //! 功率控制相关命令
 
+use std::time::Duration;
+
 use tauri::{AppHandle, Emitter, State};
-use tracing::{debug, info};
+use tracing::info;
 
-use dglab_core::device::DeviceState;
+use dglab_core::device::{ChannelLink, DeviceLimits, DeviceState, Easing, PowerCurve};
 
 use crate::events::{event_names, DevicePowerChangedEvent, DeviceStateChangedEvent};
 use crate::state::AppState;
@@ -28,12 +30,203 @@ pub async fn set_power(
         .await
         .ok_or_else(|| format!("Device not found: {}", device_id))?;
 
-    let mut dev = device.write().await;
-    dev.set_power(channel, power)
+    // 手动设置强度时打断该通道上的渐变
+    let _ = manager.cancel_ramp(&device_id, channel);
+
+    // 设备已校准时按校准曲线换算
+    let _ = manager
+        .set_power(&device_id, channel, power)
         .await
         .map_err(|e| format!("Failed to set power: {}", e))?;
 
     // 获取当前功率状态并发送事件
+    let info = device.read().await.info();
+    let _ = app.emit(
+        event_names::DEVICE_POWER_CHANGED,
+        DevicePowerChangedEvent {
+            device_id: device_id.clone(),
+            power_a: info.power_a,
+            power_b: info.power_b,
+        },
+    );
+
+    Ok(())
+}
+
+/// 获取设备的强度校准曲线（未校准时返回 `None`）
+#[tauri::command]
+pub async fn get_calibration(
+    state: State<'_, AppState>,
+    device_id: String,
+) -> Result<Option<PowerCurve>, String> {
+    let manager = state.session_manager.read().await;
+    let device = manager
+        .get_device(&device_id)
+        .await
+        .ok_or_else(|| format!("Device not found: {}", device_id))?;
+
+    let name = device.read().await.name().to_string();
+    Ok(manager.calibration(&device_id, &name))
+}
+
+/// 校准过程中试用原始强度（不经过校准曲线，仍受安全限制约束）
+#[tauri::command]
+pub async fn calibration_try(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    device_id: String,
+    channel: u8,
+    raw: u8,
+) -> Result<(), String> {
+    let manager = state.session_manager.read().await;
+    let device = manager
+        .get_device(&device_id)
+        .await
+        .ok_or_else(|| format!("Device not found: {}", device_id))?;
+
+    let _ = manager.cancel_ramp(&device_id, channel);
+
+    let _ = manager
+        .set_raw_power(&device_id, channel, raw)
+        .await
+        .map_err(|e| format!("Failed to set power: {}", e))?;
+
+    let info = device.read().await.info();
+    let _ = app.emit(
+        event_names::DEVICE_POWER_CHANGED,
+        DevicePowerChangedEvent {
+            device_id: device_id.clone(),
+            power_a: info.power_a,
+            power_b: info.power_b,
+        },
+    );
+
+    Ok(())
+}
+
+/// 保存设备的强度校准曲线（`points` 为 `None` 时清除）
+///
+/// 校准点为 `[百分比, 原始强度]`，保存到配置文件的常用设备中。
+#[tauri::command]
+pub async fn save_calibration(
+    state: State<'_, AppState>,
+    device_id: String,
+    points: Option<Vec<(u8, u8)>>,
+) -> Result<Option<PowerCurve>, String> {
+    let curve = points
+        .map(PowerCurve::new)
+        .transpose()
+        .map_err(|e| format!("Invalid calibration: {}", e))?;
+    info!(
+        "Saving calibration for {}: {}",
+        device_id,
+        curve
+            .as_ref()
+            .map_or_else(|| "none".to_string(), ToString::to_string)
+    );
+
+    let mut config = state.config.config();
+    config.set_calibration(&device_id, curve.clone());
+    state
+        .config
+        .set(config)
+        .await
+        .map_err(|e| format!("Failed to save settings: {}", e))?;
+
+    state
+        .session_manager
+        .read()
+        .await
+        .set_calibration(&device_id, curve.clone());
+    Ok(curve)
+}
+
+/// 在 `duration_ms` 内把通道强度平滑渐变到 `target`
+///
+/// 立即返回，渐变在后台进行，每步发送功率变更事件。同一通道上的旧渐变会被取消。
+#[tauri::command]
+pub async fn ramp_power(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    device_id: String,
+    channel: u8,
+    target: u8,
+    duration_ms: u64,
+    easing: Option<Easing>,
+) -> Result<(), String> {
+    let manager = state.session_manager.read().await;
+    let event_device_id = device_id.clone();
+    manager
+        .start_ramp(
+            &device_id,
+            channel,
+            target,
+            Duration::from_millis(duration_ms),
+            easing.unwrap_or_default(),
+            move |dev| {
+                let _ = app.emit(
+                    event_names::DEVICE_POWER_CHANGED,
+                    DevicePowerChangedEvent {
+                        device_id: event_device_id.clone(),
+                        power_a: dev.get_power(0),
+                        power_b: dev.get_power(1),
+                    },
+                );
+            },
+        )
+        .await
+        .map_err(|e| format!("Failed to start power ramp: {}", e))
+}
+
+/// 取消通道上进行中的强度渐变，返回是否有渐变被取消
+#[tauri::command]
+pub async fn cancel_ramp(
+    state: State<'_, AppState>,
+    device_id: String,
+    channel: u8,
+) -> Result<bool, String> {
+    let manager = state.session_manager.read().await;
+    Ok(manager.cancel_ramp(&device_id, channel))
+}
+
+/// 获取设备输出限制
+#[tauri::command]
+pub async fn get_device_limits(
+    state: State<'_, AppState>,
+    device_id: String,
+) -> Result<DeviceLimits, String> {
+    let manager = state.session_manager.read().await;
+    let device = manager
+        .get_device(&device_id)
+        .await
+        .ok_or_else(|| format!("Device not found: {}", device_id))?;
+
+    let dev = device.read().await;
+    Ok(dev.limits())
+}
+
+/// 设置设备输出限制（软上限与平衡参数）
+#[tauri::command]
+pub async fn set_device_limits(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    device_id: String,
+    limits: DeviceLimits,
+) -> Result<(), String> {
+    info!("Setting limits for device {}: {:?}", device_id, limits);
+
+    let manager = state.session_manager.read().await;
+    let device = manager
+        .get_device(&device_id)
+        .await
+        .ok_or_else(|| format!("Device not found: {}", device_id))?;
+
+    let mut dev = device.write().await;
+    dev.set_limits(limits)
+        .await
+        .map_err(|e| format!("Failed to set limits: {}", e))?;
+
+    // 强度可能被下调到新上限
     let info = dev.info();
     let _ = app.emit(
         event_names::DEVICE_POWER_CHANGED,
@@ -47,14 +240,70 @@ pub async fn set_power(
     Ok(())
 }
 
-/// 开始设备输出
+/// 获取通道联动配置
 #[tauri::command]
-pub async fn start_device(
+pub async fn get_channel_link(
+    state: State<'_, AppState>,
+    device_id: String,
+) -> Result<Option<ChannelLink>, String> {
+    let manager = state.session_manager.read().await;
+    let device = manager
+        .get_device(&device_id)
+        .await
+        .ok_or_else(|| format!("Device not found: {}", device_id))?;
+
+    let dev = device.read().await;
+    Ok(dev.channel_link())
+}
+
+/// 设置通道联动（`None` 关闭联动）
+#[tauri::command]
+pub async fn set_channel_link(
     app: AppHandle,
     state: State<'_, AppState>,
     device_id: String,
+    link: Option<ChannelLink>,
 ) -> Result<(), String> {
-    info!("Starting device: {}", device_id);
+    info!("Setting channel link for device {}: {:?}", device_id, link);
+
+    let manager = state.session_manager.read().await;
+    // 经会话设置，联动的 B 通道受安全限制约束
+    manager
+        .set_channel_link(&device_id, link)
+        .await
+        .map_err(|e| format!("Failed to set channel link: {}", e))?;
+    let device = manager
+        .get_device(&device_id)
+        .await
+        .ok_or_else(|| format!("Device not found: {}", device_id))?;
+
+    // 开启联动会立即同步 B 通道
+    let info = device.read().await.info();
+    let _ = app.emit(
+        event_names::DEVICE_POWER_CHANGED,
+        DevicePowerChangedEvent {
+            device_id: device_id.clone(),
+            power_a: info.power_a,
+            power_b: info.power_b,
+        },
+    );
+
+    Ok(())
+}
+
+/// 启用或禁用通道（禁用时强度归零、波形静默，直到重新启用）
+#[tauri::command]
+pub async fn set_channel_enabled(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    device_id: String,
+    channel: u8,
+    enabled: bool,
+) -> Result<(), String> {
+    info!(
+        "Setting channel {} enabled for device {}: {}",
+        channel, device_id, enabled
+    );
 
     let manager = state.session_manager.read().await;
     let device = manager
@@ -63,7 +312,36 @@ pub async fn start_device(
         .ok_or_else(|| format!("Device not found: {}", device_id))?;
 
     let mut dev = device.write().await;
-    dev.start()
+    dev.set_channel_enabled(channel, enabled)
+        .await
+        .map_err(|e| format!("Failed to set channel enabled: {}", e))?;
+
+    // 禁用会立即将通道归零
+    let info = dev.info();
+    let _ = app.emit(
+        event_names::DEVICE_POWER_CHANGED,
+        DevicePowerChangedEvent {
+            device_id: device_id.clone(),
+            power_a: info.power_a,
+            power_b: info.power_b,
+        },
+    );
+
+    Ok(())
+}
+
+/// 开始设备输出
+#[tauri::command]
+pub async fn start_device(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    device_id: String,
+) -> Result<(), String> {
+    info!("Starting device: {}", device_id);
+
+    let manager = state.session_manager.read().await;
+    manager
+        .start(&device_id)
         .await
         .map_err(|e| format!("Failed to start device: {}", e))?;
 
@@ -89,13 +367,8 @@ pub async fn stop_device(
     info!("Stopping device: {}", device_id);
 
     let manager = state.session_manager.read().await;
-    let device = manager
-        .get_device(&device_id)
-        .await
-        .ok_or_else(|| format!("Device not found: {}", device_id))?;
-
-    let mut dev = device.write().await;
-    dev.stop()
+    manager
+        .stop(&device_id)
         .await
         .map_err(|e| format!("Failed to stop device: {}", e))?;
 
@@ -111,33 +384,54 @@ pub async fn stop_device(
     Ok(())
 }
 
-/// 紧急停止（设置所有通道功率为 0 并停止）
+/// 忽略或恢复设备的低电量限制
 #[tauri::command]
-pub async fn emergency_stop(
+pub async fn set_battery_override(
     app: AppHandle,
     state: State<'_, AppState>,
     device_id: String,
+    overridden: bool,
 ) -> Result<(), String> {
-    info!("Emergency stop for device: {}", device_id);
+    info!(
+        "Setting low battery override for device {}: {}",
+        device_id, overridden
+    );
 
     let manager = state.session_manager.read().await;
-    let device = manager
-        .get_device(&device_id)
+    manager
+        .set_battery_override(&device_id, overridden)
         .await
-        .ok_or_else(|| format!("Device not found: {}", device_id))?;
-
-    let mut dev = device.write().await;
+        .map_err(|e| format!("Failed to set battery override: {}", e))?;
 
-    // 设置所有通道为 0
-    if let Err(e) = dev.set_power(0, 0).await {
-        debug!("Failed to set channel A to 0: {}", e);
-    }
-    if let Err(e) = dev.set_power(1, 0).await {
-        debug!("Failed to set channel B to 0: {}", e);
+    // 恢复限制时强度可能已降低
+    if let Some(device) = manager.get_device(&device_id).await {
+        let info = device.read().await.info();
+        let _ = app.emit(
+            event_names::DEVICE_POWER_CHANGED,
+            DevicePowerChangedEvent {
+                device_id,
+                power_a: info.power_a,
+                power_b: info.power_b,
+            },
+        );
     }
 
-    // 停止设备
-    dev.stop()
+    Ok(())
+}
+
+/// 紧急停止（设置所有通道功率为 0 并停止）
+#[tauri::command]
+pub async fn emergency_stop(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    device_id: String,
+) -> Result<(), String> {
+    info!("Emergency stop for device: {}", device_id);
+
+    // 取消渐变、两通道归零并停止设备，同时写入事件日志
+    let manager = state.session_manager.read().await;
+    manager
+        .emergency_stop(&device_id)
         .await
         .map_err(|e| format!("Failed to stop device: {}", e))?;
 
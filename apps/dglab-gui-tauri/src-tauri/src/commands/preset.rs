@@ -0,0 +1,347 @@
+//! 预设相关命令
+//!
+//! 预设的增删改查直接操作 `AppState` 中的预设管理器，修改后立即写入预设文件。
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, State};
+use tracing::info;
+use ts_rs::TS;
+
+use dglab_core::device::traits::DeviceInfo;
+use dglab_core::error::CoreError;
+use dglab_core::preset::{Preset, PresetIssue, PresetManager, PresetQuery, SharedPattern};
+
+use crate::events::{event_names, DevicePowerChangedEvent};
+use crate::state::AppState;
+
+/// 预设命令错误
+///
+/// 序列化为 `{ kind, ... }`，前端按 `kind` 区分：`invalid` 时按 `issues[].field` 标出对应输入框。
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+#[ts(export)]
+pub enum PresetError {
+    /// 预设校验失败
+    Invalid {
+        /// 所有校验问题
+        issues: Vec<PresetIssue>,
+    },
+    /// 预设不存在
+    NotFound {
+        /// 预设 ID
+        id: String,
+    },
+    /// 其他错误（如写入文件失败）
+    Failed {
+        /// 错误信息
+        message: String,
+    },
+}
+
+impl PresetError {
+    fn failed(context: &str, e: impl std::fmt::Display) -> Self {
+        Self::Failed {
+            message: format!("{}: {}", context, e),
+        }
+    }
+
+    /// 解析继承失败（父预设不存在时为 `notFound`）
+    fn resolve(e: CoreError) -> Self {
+        match e {
+            CoreError::PresetNotFound(id) => Self::NotFound { id },
+            e => Self::failed("Failed to resolve preset", e),
+        }
+    }
+}
+
+/// 分享码导入结果
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ImportedShare {
+    /// 导入的内容：`preset` 或 `waveform`
+    pub kind: String,
+    /// 预设或波形名称
+    pub name: String,
+    /// 预设 ID（导入波形时为空）
+    pub preset_id: Option<String>,
+}
+
+/// 预设的有效配置，解析失败（父预设缺失）时返回预设本身
+fn effective(presets: &PresetManager, preset: &Preset) -> Preset {
+    presets
+        .resolve(&preset.id)
+        .unwrap_or_else(|_| preset.clone())
+}
+
+/// 获取所有预设的有效配置（按名称排序）
+#[tauri::command]
+pub async fn list_presets(state: State<'_, AppState>) -> Result<Vec<Preset>, PresetError> {
+    let presets = state.preset_manager.read().await;
+    Ok(presets
+        .list_presets()
+        .into_iter()
+        .map(|preset| effective(&presets, preset))
+        .collect())
+}
+
+/// 按条件搜索预设，返回有效配置（按名称排序）
+#[tauri::command]
+pub async fn search_presets(
+    state: State<'_, AppState>,
+    query: PresetQuery,
+) -> Result<Vec<Preset>, PresetError> {
+    let presets = state.preset_manager.read().await;
+    Ok(presets
+        .search(&query)
+        .into_iter()
+        .map(|preset| effective(&presets, preset))
+        .collect())
+}
+
+/// 获取所有预设分类
+#[tauri::command]
+pub async fn list_preset_categories(
+    state: State<'_, AppState>,
+) -> Result<Vec<String>, PresetError> {
+    Ok(state.preset_manager.read().await.categories())
+}
+
+/// 获取预设
+#[tauri::command]
+pub async fn get_preset(state: State<'_, AppState>, id: String) -> Result<Preset, PresetError> {
+    state
+        .preset_manager
+        .read()
+        .await
+        .get_preset(&id)
+        .cloned()
+        .ok_or(PresetError::NotFound { id })
+}
+
+/// 获取预设的有效配置（合并父预设后的结果，编辑和显示都使用有效配置）
+#[tauri::command]
+pub async fn get_effective_preset(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<Preset, PresetError> {
+    state
+        .preset_manager
+        .read()
+        .await
+        .resolve(&id)
+        .map_err(PresetError::resolve)
+}
+
+/// 保存预设，返回保存后的预设
+///
+/// ID 为空或不存在时新建，否则覆盖同 ID 的预设；名称不能与其他预设重复。
+/// 传入的是编辑后的有效配置，与父预设不同的字段自动记入 `overrides`。
+#[tauri::command]
+pub async fn save_preset(
+    state: State<'_, AppState>,
+    mut preset: Preset,
+) -> Result<Preset, PresetError> {
+    if preset.id.trim().is_empty() {
+        preset.id = uuid::Uuid::new_v4().to_string();
+    }
+    info!("Saving preset '{}' ({})", preset.name, preset.id);
+
+    let mut presets = state.preset_manager.write().await;
+    let mut issues = preset.issues();
+    if presets
+        .find_preset_by_name(&preset.name)
+        .is_some_and(|other| other.id != preset.id)
+    {
+        issues.push(PresetIssue {
+            field: "name".to_string(),
+            message: format!("Preset name '{}' is already used", preset.name),
+        });
+    }
+    if let Err(e) = presets
+        .check_parent(&preset)
+        .and_then(|()| presets.record_overrides(&mut preset))
+    {
+        issues.push(PresetIssue {
+            field: "parent".to_string(),
+            message: e.to_string(),
+        });
+    }
+    if !issues.is_empty() {
+        return Err(PresetError::Invalid { issues });
+    }
+
+    preset.touch();
+    let result = if let Some(existing) = presets.get_preset(&preset.id) {
+        preset.created_at = existing.created_at;
+        presets.update_preset(preset.clone())
+    } else {
+        presets.add_preset(preset.clone())
+    };
+    result.map_err(|e| PresetError::failed("Failed to save preset", e))?;
+    presets
+        .save_preset(&preset.id)
+        .await
+        .map_err(|e| PresetError::failed("Failed to write preset file", e))?;
+
+    Ok(preset)
+}
+
+/// 删除预设及其文件
+#[tauri::command]
+pub async fn delete_preset(state: State<'_, AppState>, id: String) -> Result<(), PresetError> {
+    info!("Deleting preset {}", id);
+
+    let mut presets = state.preset_manager.write().await;
+    let removed = presets.get_preset(&id).cloned();
+    match presets.remove_preset(&id) {
+        Ok(()) => {}
+        Err(CoreError::PresetNotFound(_)) => return Err(PresetError::NotFound { id }),
+        Err(e) => return Err(PresetError::failed("Failed to delete preset", e)),
+    }
+    if let Err(e) = presets.delete_preset_file(&id).await {
+        // 删除文件失败时放回内存，与磁盘保持一致
+        if let Some(preset) = removed {
+            let _ = presets.add_preset(preset);
+        }
+        return Err(PresetError::failed("Failed to delete preset file", e));
+    }
+    Ok(())
+}
+
+/// 生成预设的分享码（按名称引用的波形内嵌到分享码中）
+#[tauri::command]
+pub async fn share_preset(state: State<'_, AppState>, id: String) -> Result<String, PresetError> {
+    let preset = state
+        .preset_manager
+        .read()
+        .await
+        .resolve(&id)
+        .map_err(PresetError::resolve)?;
+    let library = state.session_manager.read().await.waveform_library().await;
+
+    SharedPattern::preset(&preset, &library)
+        .and_then(|pattern| pattern.encode())
+        .map_err(|e| PresetError::failed("Failed to share preset", e))
+}
+
+/// 生成波形库中波形的分享码
+#[tauri::command]
+pub async fn share_waveform(
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<String, PresetError> {
+    let waveform = state
+        .session_manager
+        .read()
+        .await
+        .resolve_waveform(&name)
+        .await
+        .map_err(|e| PresetError::failed("Failed to share waveform", e))?;
+
+    SharedPattern::waveform(&waveform)
+        .encode()
+        .map_err(|e| PresetError::failed("Failed to share waveform", e))
+}
+
+/// 导入分享码
+///
+/// 预设保存到预设目录，波形保存到波形库目录。`name` 用于重命名导入的内容；
+/// 预设名称已被使用时返回 `invalid`，前端可提示用户改名后重新导入。
+#[tauri::command]
+pub async fn import_share_code(
+    state: State<'_, AppState>,
+    code: String,
+    name: Option<String>,
+) -> Result<ImportedShare, PresetError> {
+    let mut pattern = SharedPattern::decode(&code).map_err(|e| PresetError::Failed {
+        message: e.to_string(),
+    })?;
+    if let Some(name) = name.filter(|name| !name.trim().is_empty()) {
+        match &mut pattern {
+            SharedPattern::Preset(preset) => preset.name = name,
+            SharedPattern::Waveform(file) => file.name = name,
+        }
+    }
+    info!("Importing shared pattern '{}'", pattern.name());
+
+    match pattern {
+        SharedPattern::Preset(preset) => {
+            // 同 `save_preset`：校验预设并检查名称是否重复
+            let preset = save_preset(state, preset).await?;
+            Ok(ImportedShare {
+                kind: "preset".to_string(),
+                name: preset.name,
+                preset_id: Some(preset.id),
+            })
+        }
+        SharedPattern::Waveform(file) => {
+            let manager = state.session_manager.read().await;
+            let mut library = manager.waveform_library().await;
+            let waveform = file
+                .to_waveform()
+                .map_err(|e| PresetError::failed("Invalid waveform", e))?;
+            let path = library
+                .user_path(&file.name)
+                .ok_or_else(|| PresetError::Failed {
+                    message: "Waveform library has no directory".to_string(),
+                })?;
+            file.save(&path)
+                .await
+                .map_err(|e| PresetError::failed("Failed to write waveform file", e))?;
+
+            library.insert(waveform);
+            manager.set_waveform_library(library).await;
+            Ok(ImportedShare {
+                kind: "waveform".to_string(),
+                name: file.name,
+                preset_id: None,
+            })
+        }
+    }
+}
+
+/// 将预设应用到设备
+///
+/// 设置各通道最大强度（V3 为 BF 软上限）、波形和初始强度，返回应用后的设备信息。
+/// 标记为需要确认的预设，前端确认后以 `confirmed = true` 重新调用。
+#[tauri::command]
+pub async fn apply_preset(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    device_id: String,
+    preset_id: String,
+    confirmed: Option<bool>,
+) -> Result<DeviceInfo, String> {
+    info!("Applying preset {} to device {}", preset_id, device_id);
+
+    let preset = {
+        let presets = state.preset_manager.read().await;
+        presets.resolve(&preset_id).map_err(|e| e.to_string())?
+    };
+
+    let manager = state.session_manager.read().await;
+    let result = if confirmed.unwrap_or(false) {
+        manager.apply_preset_confirmed(&device_id, &preset).await
+    } else {
+        manager.apply_preset(&device_id, &preset).await
+    };
+    result.map_err(|e| format!("Failed to apply preset: {}", e))?;
+    state.app_state.lock().await.last_preset = Some(preset_id.clone());
+
+    let device = manager
+        .get_device(&device_id)
+        .await
+        .ok_or_else(|| format!("Device not found: {}", device_id))?;
+    let info = device.read().await.info();
+
+    let _ = app.emit(
+        event_names::DEVICE_POWER_CHANGED,
+        DevicePowerChangedEvent {
+            device_id: device_id.clone(),
+            power_a: info.power_a,
+            power_b: info.power_b,
+        },
+    );
+
+    Ok(info)
+}
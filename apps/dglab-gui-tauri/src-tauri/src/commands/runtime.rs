@@ -0,0 +1,138 @@
+//! 会话运行时相关命令
+
+use tauri::{AppHandle, Emitter, State};
+use tracing::{debug, info};
+
+use dglab_core::waveform::Waveform;
+
+use crate::events::{event_names, RuntimeStatusChangedEvent};
+use crate::runtime::RuntimeStatus;
+use crate::state::AppState;
+
+/// 发送运行时状态变更事件
+fn emit_status(app: &AppHandle, status: RuntimeStatus) {
+    let _ = app.emit(
+        event_names::RUNTIME_STATUS_CHANGED,
+        RuntimeStatusChangedEvent { status },
+    );
+}
+
+/// 启动会话运行时
+#[tauri::command]
+pub async fn start_session_runtime(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<RuntimeStatus, String> {
+    info!("Starting session runtime");
+
+    let mut runtime = state.runtime.lock().await;
+    runtime.start(app.clone());
+
+    let status = runtime.status();
+    emit_status(&app, status);
+    Ok(status)
+}
+
+/// 停止会话运行时
+#[tauri::command]
+pub async fn stop_session_runtime(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<RuntimeStatus, String> {
+    info!("Stopping session runtime");
+
+    let mut runtime = state.runtime.lock().await;
+    runtime.stop();
+
+    let status = runtime.status();
+    emit_status(&app, status);
+    Ok(status)
+}
+
+/// 暂停会话运行时（保持心跳，暂停波形推进）
+#[tauri::command]
+pub async fn pause_session_runtime(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<RuntimeStatus, String> {
+    info!("Pausing session runtime");
+
+    let runtime = state.runtime.lock().await;
+    if runtime.status() == RuntimeStatus::Stopped {
+        return Err("会话运行时未启动".to_string());
+    }
+    runtime.pause();
+
+    let status = runtime.status();
+    emit_status(&app, status);
+    Ok(status)
+}
+
+/// 恢复会话运行时
+#[tauri::command]
+pub async fn resume_session_runtime(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<RuntimeStatus, String> {
+    info!("Resuming session runtime");
+
+    let runtime = state.runtime.lock().await;
+    if runtime.status() == RuntimeStatus::Stopped {
+        return Err("会话运行时未启动".to_string());
+    }
+    runtime.resume();
+
+    let status = runtime.status();
+    emit_status(&app, status);
+    Ok(status)
+}
+
+/// 获取会话运行时状态
+#[tauri::command]
+pub async fn get_session_runtime_status(
+    state: State<'_, AppState>,
+) -> Result<RuntimeStatus, String> {
+    debug!("Getting session runtime status");
+
+    let runtime = state.runtime.lock().await;
+    Ok(runtime.status())
+}
+
+/// 为设备通道设置由运行时驱动的波形
+#[tauri::command]
+pub async fn set_runtime_waveform(
+    state: State<'_, AppState>,
+    device_id: String,
+    channel: u8,
+    waveform: Waveform,
+) -> Result<(), String> {
+    info!(
+        "Setting runtime waveform for device {}, channel {}: {}",
+        device_id, channel, waveform.name
+    );
+
+    if channel > 1 {
+        return Err(format!("Invalid channel: {}", channel));
+    }
+
+    let runtime = state.runtime.lock().await;
+    runtime.set_generator(&device_id, channel, waveform).await;
+    Ok(())
+}
+
+/// 清除设备通道的运行时波形
+#[tauri::command]
+pub async fn clear_runtime_waveform(
+    state: State<'_, AppState>,
+    device_id: String,
+    channel: u8,
+) -> Result<(), String> {
+    info!(
+        "Clearing runtime waveform for device {}, channel {}",
+        device_id, channel
+    );
+
+    let runtime = state.runtime.lock().await;
+    runtime.clear_generator(&device_id, channel).await;
+    Ok(())
+}
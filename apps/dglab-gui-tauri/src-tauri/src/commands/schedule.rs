@@ -0,0 +1,81 @@
+//! 预设定时命令
+//!
+//! 定时条目保存在配置文件中，修改后调度器自动重新计算触发时间。
+
+use tauri::State;
+use tracing::info;
+
+use dglab_core::config::AppConfig;
+use dglab_core::preset::ScheduleEntry;
+
+use crate::state::AppState;
+
+/// 获取所有定时
+#[tauri::command]
+pub async fn list_schedules(state: State<'_, AppState>) -> Result<Vec<ScheduleEntry>, String> {
+    Ok(state.config.config().schedules)
+}
+
+/// 添加或替换同名定时，返回保存后的定时列表
+#[tauri::command]
+pub async fn set_schedule(
+    state: State<'_, AppState>,
+    entry: ScheduleEntry,
+) -> Result<Vec<ScheduleEntry>, String> {
+    info!("Setting schedule '{}': {}", entry.name, entry.trigger);
+
+    if state
+        .preset_manager
+        .read()
+        .await
+        .find_preset_by_name(&entry.preset)
+        .is_none()
+    {
+        return Err(format!("Preset not found: {}", entry.preset));
+    }
+
+    let mut config = state.config.config();
+    config.set_schedule(entry);
+    save(&state, config).await
+}
+
+/// 删除定时，返回保存后的定时列表
+#[tauri::command]
+pub async fn remove_schedule(
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<Vec<ScheduleEntry>, String> {
+    info!("Removing schedule '{}'", name);
+
+    let mut config = state.config.config();
+    if !config.remove_schedule(&name) {
+        return Err(format!("Schedule not found: {}", name));
+    }
+    save(&state, config).await
+}
+
+/// 启用或禁用定时，返回保存后的定时列表
+#[tauri::command]
+pub async fn set_schedule_enabled(
+    state: State<'_, AppState>,
+    name: String,
+    enabled: bool,
+) -> Result<Vec<ScheduleEntry>, String> {
+    info!("Setting schedule '{}' enabled: {}", name, enabled);
+
+    let mut config = state.config.config();
+    if !config.set_schedule_enabled(&name, enabled) {
+        return Err(format!("Schedule not found: {}", name));
+    }
+    save(&state, config).await
+}
+
+/// 保存配置并返回定时列表
+async fn save(state: &AppState, config: AppConfig) -> Result<Vec<ScheduleEntry>, String> {
+    state
+        .config
+        .set(config)
+        .await
+        .map_err(|e| format!("Failed to save schedules: {}", e))?;
+    Ok(state.config.config().schedules)
+}
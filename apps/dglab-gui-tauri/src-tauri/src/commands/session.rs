@@ -1,13 +1,25 @@
 //! 会话管理相关命令
 
+use std::collections::HashMap;
+use std::time::Duration;
+
 use serde::{Deserialize, Serialize};
-use tauri::State;
-use tracing::debug;
+use tauri::{AppHandle, Emitter, State};
+use tracing::{debug, info, warn};
+use ts_rs::TS;
+
+use dglab_core::session::{
+    parse_stop_time, DeviceStats, EventLog, LogEntry, LogFilter, SessionSnapshot,
+    SnapshotTransport, DEFAULT_RESTORE_RAMP,
+};
 
+use crate::commands::device;
+use crate::events::{event_names, DevicePowerChangedEvent};
 use crate::state::AppState;
 
 /// 会话信息
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
 pub struct SessionInfo {
     /// 会话 ID
     pub id: String,
@@ -19,6 +31,75 @@ pub struct SessionInfo {
     pub total_devices: usize,
 }
 
+/// 快照中的设备
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct SnapshotDevice {
+    /// 设备 ID
+    pub id: String,
+    /// 设备名称
+    pub name: String,
+    /// 是否需在 APP 中重新扫码连接（WiFi 设备）
+    pub wifi: bool,
+    /// A 通道强度
+    pub power_a: u8,
+    /// B 通道强度
+    pub power_b: u8,
+    /// 保存时是否正在输出
+    pub running: bool,
+}
+
+/// 可恢复的上次会话
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct SessionSnapshotInfo {
+    /// 原会话 ID
+    pub session_id: String,
+    /// 保存时间（RFC 3339）
+    pub saved_at: String,
+    /// 会话已运行的时长（秒）
+    #[ts(type = "number")]
+    pub elapsed_secs: u64,
+    /// 会话定时的剩余时长（秒）
+    #[ts(type = "number | null")]
+    pub timer_remaining_secs: Option<u64>,
+    /// 会话中的设备
+    pub devices: Vec<SnapshotDevice>,
+}
+
+impl From<&SessionSnapshot> for SessionSnapshotInfo {
+    fn from(snapshot: &SessionSnapshot) -> Self {
+        Self {
+            session_id: snapshot.session_id.clone(),
+            saved_at: snapshot.saved_at.to_rfc3339(),
+            elapsed_secs: snapshot.elapsed().as_secs(),
+            timer_remaining_secs: snapshot.timer_remaining_secs,
+            devices: snapshot
+                .devices
+                .iter()
+                .map(|device| SnapshotDevice {
+                    id: device.id.clone(),
+                    name: device.name.clone(),
+                    wifi: device.transport == SnapshotTransport::Wifi,
+                    power_a: device.power[0],
+                    power_b: device.power[1],
+                    running: device.running,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// 单个设备的恢复结果
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ResumeResult {
+    /// 设备 ID
+    pub device_id: String,
+    /// 失败原因，成功时为空
+    pub error: Option<String>,
+}
+
 /// 获取会话信息
 #[tauri::command]
 pub async fn get_session_info(state: State<'_, AppState>) -> Result<SessionInfo, String> {
@@ -45,3 +126,196 @@ pub async fn list_devices(state: State<'_, AppState>) -> Result<Vec<String>, Str
 
     Ok(device_ids)
 }
+
+/// 设置会话定时，返回停止时间（RFC 3339）
+///
+/// `duration_secs` 为最长运行时长，`stop_at` 为停止时间（RFC 3339 或本地 `HH:MM`），二选一。
+/// 到时后所有设备强度渐变归零并停止输出。
+#[tauri::command]
+pub async fn set_session_timer(
+    state: State<'_, AppState>,
+    duration_secs: Option<u64>,
+    stop_at: Option<String>,
+) -> Result<String, String> {
+    let manager = state.session_manager.read().await;
+    let result = match (duration_secs, stop_at) {
+        (Some(secs), None) => manager.set_timer_duration(Duration::from_secs(secs)),
+        (None, Some(stop_at)) => parse_stop_time(&stop_at).and_then(|t| manager.set_timer(t)),
+        _ => return Err("Specify either duration_secs or stop_at".to_string()),
+    };
+    result.map_err(|e| format!("Failed to set session timer: {}", e))?;
+
+    manager
+        .timer_deadline()
+        .map(|t| t.to_rfc3339())
+        .ok_or_else(|| "Session timer not running".to_string())
+}
+
+/// 取消会话定时，返回是否有定时被取消
+#[tauri::command]
+pub async fn cancel_session_timer(state: State<'_, AppState>) -> Result<bool, String> {
+    let manager = state.session_manager.read().await;
+    Ok(manager.cancel_timer())
+}
+
+/// 获取会话停止时间（RFC 3339），未设置时为空
+#[tauri::command]
+pub async fn get_session_timer(state: State<'_, AppState>) -> Result<Option<String>, String> {
+    let manager = state.session_manager.read().await;
+    Ok(manager.timer_deadline().map(|t| t.to_rfc3339()))
+}
+
+/// 查询当前会话的事件日志
+///
+/// `device_id` 只保留该设备的记录，`limit` 只返回最后 N 条。
+#[tauri::command]
+pub async fn get_event_log(
+    state: State<'_, AppState>,
+    device_id: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<LogEntry>, String> {
+    let manager = state.session_manager.read().await;
+    let Some(log) = manager.event_log() else {
+        return Ok(Vec::new());
+    };
+    if !log.path().exists() {
+        return Ok(Vec::new());
+    }
+
+    let filter = LogFilter {
+        device_id,
+        limit,
+        ..Default::default()
+    };
+    EventLog::read(log.path(), &filter).map_err(|e| format!("Failed to read event log: {}", e))
+}
+
+/// 获取会话统计（仪表盘）
+///
+/// 指定 `device_id` 时只返回该设备；已移出会话的设备保留最后的统计。
+#[tauri::command]
+pub async fn get_session_stats(
+    state: State<'_, AppState>,
+    device_id: Option<String>,
+) -> Result<Vec<DeviceStats>, String> {
+    let manager = state.session_manager.read().await;
+    Ok(match device_id {
+        Some(id) => manager.device_stats(&id).into_iter().collect(),
+        None => manager.stats(),
+    })
+}
+
+/// 获取上次退出时保存的会话快照，没有可恢复的会话时为空
+#[tauri::command]
+pub async fn load_session_snapshot(
+    state: State<'_, AppState>,
+) -> Result<Option<SessionSnapshotInfo>, String> {
+    Ok(state
+        .session_snapshot
+        .lock()
+        .await
+        .as_ref()
+        .map(SessionSnapshotInfo::from))
+}
+
+/// 恢复上次会话（已取得用户确认），返回各设备的恢复结果
+///
+/// 重新连接快照中不在会话内的蓝牙设备，恢复波形和输出状态后，强度从 0 在 `ramp_secs`
+/// 秒（默认 10 秒）内渐变到保存的强度。WiFi 设备需先通过 `wifi_connect` 重新绑定 APP。
+/// 至少一台设备恢复成功后删除快照，全部失败时保留快照，可以重试。
+#[tauri::command]
+pub async fn resume_session(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    ramp_secs: Option<u64>,
+) -> Result<Vec<ResumeResult>, String> {
+    let snapshot = state
+        .session_snapshot
+        .lock()
+        .await
+        .take()
+        .ok_or_else(|| "No session snapshot to resume".to_string())?;
+    info!(
+        "Resuming session {} ({} devices)",
+        snapshot.session_id,
+        snapshot.devices.len()
+    );
+
+    // 重新连接蓝牙设备
+    let connected = state.session_manager.read().await.list_devices().await;
+    let mut errors = HashMap::new();
+    for saved in &snapshot.devices {
+        if connected.contains(&saved.id) {
+            continue;
+        }
+        let result = match saved.transport {
+            SnapshotTransport::Ble => device::connect_ble_device(
+                app.clone(),
+                state.clone(),
+                saved.id.clone(),
+                saved.name.clone(),
+                None,
+            )
+            .await
+            .map(|_| ()),
+            SnapshotTransport::Wifi => Err("WiFi 设备需在 APP 中重新扫码连接".to_string()),
+            SnapshotTransport::Other => Err("该设备需手动重新连接".to_string()),
+        };
+        if let Err(e) = result {
+            let _ = errors.insert(saved.id.clone(), e);
+        }
+    }
+
+    let ramp = ramp_secs.map_or(DEFAULT_RESTORE_RAMP, Duration::from_secs);
+    let results = {
+        let manager = state.session_manager.read().await;
+        manager
+            .restore_snapshot(&snapshot, ramp, move |dev| {
+                let _ = app.emit(
+                    event_names::DEVICE_POWER_CHANGED,
+                    DevicePowerChangedEvent {
+                        device_id: dev.id().to_string(),
+                        power_a: dev.get_power(0),
+                        power_b: dev.get_power(1),
+                    },
+                );
+            })
+            .await
+    };
+
+    // 至少恢复了一台设备才删除快照，全部失败时保留以便重试
+    let restored = snapshot.devices.iter().any(|saved| {
+        !errors.contains_key(&saved.id) && matches!(results.get(&saved.id), Some(Ok(())))
+    });
+    if restored {
+        if let Err(e) = SessionSnapshot::remove(&state.session_snapshot_path).await {
+            warn!("Failed to remove session snapshot: {}", e);
+        }
+    } else {
+        warn!("No device restored, keeping session snapshot");
+        *state.session_snapshot.lock().await = Some(snapshot.clone());
+    }
+
+    Ok(snapshot
+        .devices
+        .iter()
+        .map(|saved| ResumeResult {
+            device_id: saved.id.clone(),
+            error: errors.remove(&saved.id).or_else(|| {
+                results
+                    .get(&saved.id)
+                    .and_then(|result| result.as_ref().err())
+                    .map(|e| e.to_string())
+            }),
+        })
+        .collect())
+}
+
+/// 放弃恢复上次会话并删除快照
+#[tauri::command]
+pub async fn discard_session_snapshot(state: State<'_, AppState>) -> Result<(), String> {
+    let _ = state.session_snapshot.lock().await.take();
+    SessionSnapshot::remove(&state.session_snapshot_path)
+        .await
+        .map_err(|e| format!("Failed to remove session snapshot: {}", e))
+}
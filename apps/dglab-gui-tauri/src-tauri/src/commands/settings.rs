@@ -0,0 +1,33 @@
+//! 应用设置命令
+
+use tauri::State;
+use tracing::info;
+
+use dglab_core::config::AppConfig;
+
+use crate::state::AppState;
+
+/// 获取应用设置
+#[tauri::command]
+pub async fn get_settings(state: State<'_, AppState>) -> Result<AppConfig, String> {
+    Ok(state.config.config())
+}
+
+/// 校验并保存应用设置，返回保存后的设置
+///
+/// 保存后通过 `settings:changed` 事件通知前端。
+#[tauri::command]
+pub async fn set_settings(
+    state: State<'_, AppState>,
+    settings: AppConfig,
+) -> Result<AppConfig, String> {
+    info!("Updating settings");
+
+    state
+        .config
+        .set(settings)
+        .await
+        .map_err(|e| format!("Failed to save settings: {}", e))?;
+
+    Ok(state.config.config())
+}
@@ -0,0 +1,95 @@
+//! 波形相关命令
+
+use serde::{Deserialize, Serialize};
+use tauri::State;
+use tracing::{debug, info};
+use ts_rs::TS;
+
+use dglab_core::waveform::Waveform;
+
+use crate::state::AppState;
+
+/// 默认预览时长（毫秒）
+const DEFAULT_PREVIEW_DURATION_MS: u64 = 5000;
+
+/// 默认预览采样间隔（毫秒）
+const DEFAULT_PREVIEW_STEP_MS: u64 = 25;
+
+/// 波形预览采样点
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct WaveformPoint {
+    /// 时间（毫秒）
+    #[ts(type = "number")]
+    pub time_ms: u64,
+    /// 强度 (0-100)
+    pub intensity: u8,
+}
+
+/// 列出可选波形（内置预设和波形库中的用户波形）
+#[tauri::command]
+pub async fn list_waveform_presets(state: State<'_, AppState>) -> Result<Vec<Waveform>, String> {
+    debug!("Listing waveform presets");
+
+    let manager = state.session_manager.read().await;
+    Ok(manager.list_waveforms().await)
+}
+
+/// 为设备通道设置波形
+///
+/// `waveform` 可以是 `list_waveform_presets` 返回的波形，也可以是前端调整过参数的波形。
+#[tauri::command]
+pub async fn set_waveform(
+    state: State<'_, AppState>,
+    device_id: String,
+    channel: u8,
+    waveform: Waveform,
+) -> Result<(), String> {
+    info!(
+        "Setting waveform for device {}, channel {}: {}",
+        device_id, channel, waveform.name
+    );
+
+    if channel > 1 {
+        return Err(format!("Invalid channel: {}", channel));
+    }
+    if waveform.params.min_power > waveform.params.max_power {
+        return Err(format!(
+            "最小强度 {} 不能大于最大强度 {}",
+            waveform.params.min_power, waveform.params.max_power
+        ));
+    }
+
+    let manager = state.session_manager.read().await;
+    manager
+        .set_waveform(&device_id, channel, waveform.to_device_config())
+        .await
+        .map_err(|e| format!("Failed to set waveform: {}", e))
+}
+
+/// 采样波形用于绘制预览曲线
+#[tauri::command]
+pub async fn preview_waveform(
+    waveform: Waveform,
+    duration_ms: Option<u64>,
+    step_ms: Option<u64>,
+) -> Result<Vec<WaveformPoint>, String> {
+    if waveform.params.min_power > waveform.params.max_power {
+        return Err(format!(
+            "最小强度 {} 不能大于最大强度 {}",
+            waveform.params.min_power, waveform.params.max_power
+        ));
+    }
+    if waveform.params.period_ms == 0 {
+        return Err("周期必须大于 0".to_string());
+    }
+
+    let points = waveform.preview(
+        duration_ms.unwrap_or(DEFAULT_PREVIEW_DURATION_MS),
+        step_ms.unwrap_or(DEFAULT_PREVIEW_STEP_MS),
+    );
+    Ok(points
+        .into_iter()
+        .map(|(time_ms, intensity)| WaveformPoint { time_ms, intensity })
+        .collect())
+}
@@ -3,21 +3,32 @@
 use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Emitter, State};
 use tracing::{debug, info, warn};
+use ts_rs::TS;
 
 use dglab_core::device::{Device, WsCoyoteDevice};
+use dglab_core::session::{remote, RemoteInvite, RemoteRole, DEFAULT_JOIN_TIMEOUT};
+use dglab_protocol::wifi::ServerAddress;
 
 use crate::events::{event_names, DeviceStateChangedEvent};
+use crate::persist::{SavedDevice, SavedWifi};
 use crate::state::AppState;
 
 /// WiFi 连接请求
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
 pub struct WifiConnectRequest {
     /// 自定义服务器地址（可选，默认使用官方服务器）
+    #[ts(optional)]
     pub server_url: Option<String>,
+    /// 接受自签名证书（仅用于局域网 wss 服务器）
+    #[serde(default)]
+    #[ts(optional, as = "Option<bool>")]
+    pub accept_invalid_certs: bool,
 }
 
 /// WiFi 连接响应
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
 pub struct WifiConnectResponse {
     /// 设备 ID
     pub device_id: String,
@@ -41,14 +52,18 @@ pub async fn wifi_connect(
     let device_id = uuid::Uuid::new_v4().to_string();
     let device_name = "WiFi-Coyote".to_string();
 
-    // 创建 WiFi 设备
-    let mut wifi_device = if let Some(server) = request.server_url {
-        info!("Using custom server: {}", server);
-        WsCoyoteDevice::with_server(device_id.clone(), device_name.clone(), server)
-    } else {
-        info!("Using official server");
-        WsCoyoteDevice::new(device_id.clone(), device_name.clone())
+    // 创建 WiFi 设备（未指定服务器时使用设置中的默认服务器）
+    let config = state.config.config();
+    let server = match request.server_url {
+        Some(server) => ServerAddress::parse(&server)
+            .map_err(|e| e.to_string())?
+            .accept_invalid_certs(request.accept_invalid_certs),
+        None => config.server_address().map_err(|e| e.to_string())?,
     };
+    info!("Using server: {}", server);
+    let mut wifi_device =
+        WsCoyoteDevice::with_server(device_id.clone(), device_name.clone(), server);
+    wifi_device.set_reconnect_policy(config.reconnect.policy());
 
     // 连接到服务器
     wifi_device
@@ -72,6 +87,21 @@ pub async fn wifi_connect(
 
     info!("WiFi device created with QR URL: {}", qr_url);
 
+    // 记录 clientId，重启后在有效期内可恢复
+    let wifi = wifi_device.client_id().await.map(|client_id| SavedWifi {
+        server: wifi_device.server_url().to_string(),
+        client_id,
+    });
+    {
+        let mut saved = state.app_state.lock().await;
+        saved.last_device = Some(SavedDevice {
+            id: device_id.clone(),
+            name: device_name.clone(),
+            wifi: true,
+        });
+        saved.wifi = wifi;
+    }
+
     // 添加到会话管理器
     let manager = state.session_manager.write().await;
     manager
@@ -95,6 +125,82 @@ pub async fn wifi_connect(
     })
 }
 
+/// 加入远程会话请求
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct RemoteJoinRequest {
+    /// 邀请链接（对方 `dglab bridge --local` 显示的二维码链接）
+    pub invite: String,
+    /// 以观察端加入，只查看对方设备的强度
+    #[serde(default)]
+    #[ts(optional, as = "Option<bool>")]
+    pub viewer: bool,
+    /// 对方服务器的 PIN
+    #[ts(optional)]
+    pub pin: Option<String>,
+    /// 接受自签名证书（仅用于局域网 wss 服务器）
+    #[serde(default)]
+    #[ts(optional, as = "Option<bool>")]
+    pub accept_invalid_certs: bool,
+}
+
+/// 加入远程会话响应
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct RemoteJoinResponse {
+    /// 设备 ID
+    pub device_id: String,
+    /// 是否为观察端（强度和波形操作会被拒绝）
+    pub viewer: bool,
+}
+
+/// 加入远程会话
+///
+/// 控制端绑定成功、观察端连接成功后把设备加入会话，之后与其他设备一样控制
+#[tauri::command]
+pub async fn remote_join(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    request: RemoteJoinRequest,
+) -> Result<RemoteJoinResponse, String> {
+    let mut invite = RemoteInvite::parse(&request.invite)
+        .map_err(|e| e.to_string())?
+        .accept_invalid_certs(request.accept_invalid_certs);
+    if let Some(pin) = request.pin.filter(|pin| !pin.is_empty()) {
+        invite = invite.with_pin(pin);
+    }
+    let role = if request.viewer {
+        RemoteRole::Viewer
+    } else {
+        RemoteRole::Controller
+    };
+    info!("Joining remote session {} as {}", invite.server(), role);
+
+    let device = remote::join(&invite, role, DEFAULT_JOIN_TIMEOUT)
+        .await
+        .map_err(|e| format!("Failed to join remote session: {}", e))?;
+    let device_id = device.id().to_string();
+
+    let manager = state.session_manager.write().await;
+    manager
+        .add_device(Box::new(device))
+        .await
+        .map_err(|e| format!("Failed to add device to session: {}", e))?;
+
+    let _ = app.emit(
+        event_names::DEVICE_STATE_CHANGED,
+        DeviceStateChangedEvent {
+            device_id: device_id.clone(),
+            state: dglab_core::device::DeviceState::Connected,
+        },
+    );
+
+    Ok(RemoteJoinResponse {
+        device_id,
+        viewer: request.viewer,
+    })
+}
+
 /// 检查 WiFi 设备绑定状态
 #[tauri::command]
 pub async fn wifi_check_binding(
@@ -104,19 +210,20 @@ pub async fn wifi_check_binding(
     debug!("Checking WiFi binding status for: {}", device_id);
 
     let manager = state.session_manager.read().await;
-    let device = manager
-        .get_device(&device_id)
+    manager
+        .is_bound(&device_id)
         .await
-        .ok_or_else(|| format!("Device not found: {}", device_id))?;
-
-    let dev = device.read().await;
-    let state = dev.state();
+        .map_err(|e| e.to_string())
+}
 
-    // 如果已连接说明绑定成功
-    Ok(matches!(
-        state,
-        dglab_core::device::DeviceState::Connected | dglab_core::device::DeviceState::Running
-    ))
+/// 获取 WiFi 设备的二维码 URL（重新显示二维码或服务器重连后使用）
+#[tauri::command]
+pub async fn wifi_qr_url(
+    state: State<'_, AppState>,
+    device_id: String,
+) -> Result<Option<String>, String> {
+    let manager = state.session_manager.read().await;
+    manager.qr_url(&device_id).await.map_err(|e| e.to_string())
 }
 
 /// 取消 WiFi 连接（断开并移除设备）
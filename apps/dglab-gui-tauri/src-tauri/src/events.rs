@@ -1,12 +1,20 @@
 //! 设备事件定义
 
 use serde::{Deserialize, Serialize};
+use ts_rs::TS;
 
+use dglab_core::config::{AppConfig, HotkeyAction};
 use dglab_core::device::traits::DeviceInfo;
 use dglab_core::device::DeviceState;
+use dglab_core::feedback::FeedbackAction;
+use dglab_core::session::BatteryAction;
+use dglab_protocol::wifi::FeedbackButton;
+
+use crate::runtime::RuntimeStatus;
 
 /// 设备状态变更事件
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
 pub struct DeviceStateChangedEvent {
     /// 设备 ID
     pub device_id: String,
@@ -14,8 +22,27 @@ pub struct DeviceStateChangedEvent {
     pub state: DeviceState,
 }
 
+/// 适配器发现设备事件
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DeviceDiscoveredEvent {
+    /// 设备 ID
+    pub device_id: String,
+    /// 设备名称
+    pub name: String,
+}
+
+/// 设备断开事件（链路断开，设备仍在会话中等待重连）
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DeviceLostEvent {
+    /// 设备 ID
+    pub device_id: String,
+}
+
 /// 设备功率变更事件
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
 pub struct DevicePowerChangedEvent {
     /// 设备 ID
     pub device_id: String,
@@ -26,8 +53,9 @@ pub struct DevicePowerChangedEvent {
 }
 
 /// 设备信息更新事件
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[allow(dead_code)]
+#[ts(export)]
 pub struct DeviceInfoUpdatedEvent {
     /// 设备 ID
     pub device_id: String,
@@ -36,18 +64,32 @@ pub struct DeviceInfoUpdatedEvent {
 }
 
 /// 设备电池电量更新事件
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[allow(dead_code)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
 pub struct DeviceBatteryUpdatedEvent {
     /// 设备 ID
     pub device_id: String,
     /// 电池电量 (0-100)
     pub battery: u8,
+    /// 按放电速率估算的剩余使用时间（秒），放电数据不足时为空
+    #[ts(type = "number | null")]
+    pub remaining_secs: Option<u64>,
+}
+
+/// 设备低电量限制变化事件
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DeviceBatteryLimitedEvent {
+    /// 设备 ID
+    pub device_id: String,
+    /// 按电量和策略得到的限制
+    pub action: BatteryAction,
 }
 
 /// 设备错误事件
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[allow(dead_code)]
+#[ts(export)]
 pub struct DeviceErrorEvent {
     /// 设备 ID
     pub device_id: String,
@@ -55,17 +97,109 @@ pub struct DeviceErrorEvent {
     pub error: String,
 }
 
+/// 设备 BLE 链路质量事件
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DeviceLinkQualityEvent {
+    /// 设备 ID
+    pub device_id: String,
+    /// 信号强度（dBm）
+    pub rssi: i16,
+    /// 是否低于弱信号阈值
+    pub weak: bool,
+}
+
+/// APP 反馈按钮事件
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DeviceFeedbackEvent {
+    /// 设备 ID
+    pub device_id: String,
+    /// 按钮
+    pub button: FeedbackButton,
+    /// 执行的动作（未映射时为空）
+    pub action: Option<FeedbackAction>,
+}
+
+/// 会话运行时状态变更事件
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct RuntimeStatusChangedEvent {
+    /// 新状态
+    pub status: RuntimeStatus,
+}
+
+/// 应用设置变更事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettingsChangedEvent {
+    /// 新设置
+    pub settings: AppConfig,
+}
+
+/// 会话定时事件
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct SessionTimerEvent {
+    /// 剩余秒数
+    #[ts(type = "number")]
+    pub remaining_secs: u64,
+    /// 是否已到时（设备已归零并停止）
+    pub expired: bool,
+}
+
+/// 预设定时触发事件
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ScheduleFiredEvent {
+    /// 定时名称
+    pub schedule: String,
+    /// 预设名称
+    pub preset: String,
+    /// 已应用预设的设备
+    pub devices: Vec<String>,
+    /// 应用失败时的错误信息
+    pub error: Option<String>,
+}
+
+/// 全局快捷键触发事件
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct HotkeyTriggeredEvent {
+    /// 执行的动作
+    pub action: HotkeyAction,
+}
+
 /// 事件名称常量
 #[allow(dead_code)]
 pub mod event_names {
     /// 设备状态变更
     pub const DEVICE_STATE_CHANGED: &str = "device:state_changed";
+    /// 适配器发现设备
+    pub const DEVICE_DISCOVERED: &str = "device:discovered";
+    /// 设备链路断开
+    pub const DEVICE_LOST: &str = "device:lost";
     /// 设备功率变更
     pub const DEVICE_POWER_CHANGED: &str = "device:power_changed";
     /// 设备信息更新
     pub const DEVICE_INFO_UPDATED: &str = "device:info_updated";
     /// 设备电池电量更新
     pub const DEVICE_BATTERY_UPDATED: &str = "device:battery_updated";
+    /// 设备低电量限制变化
+    pub const DEVICE_BATTERY_LIMITED: &str = "device:battery_limited";
     /// 设备错误
     pub const DEVICE_ERROR: &str = "device:error";
+    /// 设备 BLE 链路质量
+    pub const DEVICE_LINK_QUALITY: &str = "device:link_quality";
+    /// APP 反馈按钮
+    pub const DEVICE_FEEDBACK: &str = "device:feedback";
+    /// 会话运行时状态变更
+    pub const RUNTIME_STATUS_CHANGED: &str = "runtime:status_changed";
+    /// 应用设置变更
+    pub const SETTINGS_CHANGED: &str = "settings:changed";
+    /// 会话定时警告或到时
+    pub const SESSION_TIMER: &str = "session:timer";
+    /// 预设定时触发
+    pub const SCHEDULE_FIRED: &str = "schedule:fired";
+    /// 全局快捷键触发
+    pub const HOTKEY_TRIGGERED: &str = "hotkey:triggered";
 }
@@ -0,0 +1,79 @@
+//! APP 反馈按钮监听
+//!
+//! 订阅会话事件，将反馈按钮交给路由器执行，并把结果通知前端。
+
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::broadcast;
+use tracing::{debug, warn};
+
+use dglab_core::feedback::FeedbackMapping;
+use dglab_core::session::SessionEvent;
+
+use crate::events::{event_names, DeviceFeedbackEvent, DevicePowerChangedEvent};
+use crate::state::AppState;
+
+/// 加载反馈按钮映射并启动监听任务
+pub fn spawn_listener(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+
+        match FeedbackMapping::default_path() {
+            Ok(path) => match FeedbackMapping::load(&path).await {
+                Ok(mapping) => state.feedback_router.lock().await.set_mapping(mapping),
+                Err(e) => warn!("Failed to load feedback mapping: {}", e),
+            },
+            Err(e) => warn!("Failed to resolve feedback mapping path: {}", e),
+        }
+
+        let mut events = state.session_manager.read().await.subscribe_events();
+
+        loop {
+            let (device_id, button) = match events.recv().await {
+                Ok(SessionEvent::Feedback(device_id, button)) => (device_id, button),
+                Ok(_) => continue,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    debug!("Feedback listener lagged by {} events", skipped);
+                    continue;
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            };
+
+            let manager = state.session_manager.read().await;
+            let presets = state.preset_manager.read().await;
+            let action = match state
+                .feedback_router
+                .lock()
+                .await
+                .dispatch(&manager, &presets, &device_id, button)
+                .await
+            {
+                Ok(action) => action,
+                Err(e) => {
+                    warn!("Failed to handle feedback {:?}: {}", button, e);
+                    None
+                }
+            };
+
+            if let Some(device) = manager.get_device(&device_id).await {
+                let info = device.read().await.info();
+                let _ = app.emit(
+                    event_names::DEVICE_POWER_CHANGED,
+                    DevicePowerChangedEvent {
+                        device_id: device_id.clone(),
+                        power_a: info.power_a,
+                        power_b: info.power_b,
+                    },
+                );
+            }
+
+            let _ = app.emit(
+                event_names::DEVICE_FEEDBACK,
+                DeviceFeedbackEvent {
+                    device_id,
+                    button,
+                    action,
+                },
+            );
+        }
+    });
+}
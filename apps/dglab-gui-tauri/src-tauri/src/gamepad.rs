@@ -0,0 +1,72 @@
+//! 手柄输入服务
+//!
+//! 启动时加载手柄映射；开启后在后台读取手柄输入并作用于指定设备，
+//! 强度变化时通知前端。服务一直运行到前端关闭或手柄输入线程退出。
+
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::{info, warn};
+
+use dglab_core::gamepad::{GamepadInput, GamepadMapping};
+
+use crate::events::{event_names, DevicePowerChangedEvent};
+use crate::state::AppState;
+
+/// 加载手柄映射
+pub fn spawn_loader(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+
+        match GamepadMapping::default_path() {
+            Ok(path) => match GamepadMapping::load(&path).await {
+                Ok(mapping) => state.gamepad_controller.lock().await.set_mapping(mapping),
+                Err(e) => warn!("Failed to load gamepad mapping: {}", e),
+            },
+            Err(e) => warn!("Failed to resolve gamepad mapping path: {}", e),
+        }
+    });
+}
+
+/// 开始读取手柄输入并控制 `device_id`
+pub fn start(app: AppHandle, device_id: String) -> Result<JoinHandle<()>, String> {
+    let mut input = GamepadInput::start().map_err(|e| e.to_string())?;
+    info!("Gamepad control started on device {}", device_id);
+
+    Ok(tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        let mut last_power = None;
+
+        while let Some(event) = input.next().await {
+            let manager = state.session_manager.read().await;
+            let presets = state.preset_manager.read().await;
+            if let Err(e) = state
+                .gamepad_controller
+                .lock()
+                .await
+                .handle(&manager, &presets, &device_id, event)
+                .await
+            {
+                warn!("Gamepad action failed: {}", e);
+            }
+
+            let Some(device) = manager.get_device(&device_id).await else {
+                continue;
+            };
+            let info = device.read().await.info();
+            let power = (info.power_a, info.power_b);
+            if last_power != Some(power) {
+                last_power = Some(power);
+                let _ = app.emit(
+                    event_names::DEVICE_POWER_CHANGED,
+                    DevicePowerChangedEvent {
+                        device_id: device_id.clone(),
+                        power_a: info.power_a,
+                        power_b: info.power_b,
+                    },
+                );
+            }
+        }
+
+        info!("Gamepad control stopped on device {}", device_id);
+    }))
+}
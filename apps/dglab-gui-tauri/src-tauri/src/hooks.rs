@@ -0,0 +1,42 @@
+//! 事件钩子
+//!
+//! 订阅会话事件，按配置文件 `[hooks]` 段执行命令；配置变更后立即生效。
+
+use tauri::{AppHandle, Manager};
+use tokio::sync::broadcast;
+use tracing::debug;
+
+use dglab_core::hooks::HookRunner;
+
+use crate::state::AppState;
+
+/// 启动事件钩子任务
+pub fn spawn_listener(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        let mut events = state.session_manager.read().await.subscribe_events();
+        let mut config = state.config.subscribe();
+        let mut runner = HookRunner::new(config.borrow().hooks.clone());
+
+        loop {
+            tokio::select! {
+                event = events.recv() => match event {
+                    Ok(event) => {
+                        let _ = runner.handle(&event);
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        debug!("Hook listener lagged by {} events", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+                changed = config.changed() => {
+                    if changed.is_err() {
+                        break;
+                    }
+                    let hooks = config.borrow_and_update().hooks.clone();
+                    runner.set_config(hooks);
+                }
+            }
+        }
+    });
+}
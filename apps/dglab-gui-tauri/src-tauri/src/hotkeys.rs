@@ -0,0 +1,222 @@
+//! 全局快捷键
+//!
+//! 按配置注册全局快捷键，窗口不在前台时也能调节所有设备的强度、暂停运行时或紧急停止。
+//! 配置变化时重新注册。
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use tauri::plugin::TauriPlugin;
+use tauri::{AppHandle, Emitter, Manager, Wry};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+use tracing::{debug, info, warn};
+use ts_rs::TS;
+
+use dglab_core::config::{HotkeyAction, HotkeyConfig};
+use dglab_core::device::{Device, DeviceState};
+
+use crate::events::{
+    event_names, DevicePowerChangedEvent, DeviceStateChangedEvent, HotkeyTriggeredEvent,
+    RuntimeStatusChangedEvent,
+};
+use crate::runtime::RuntimeStatus;
+use crate::state::AppState;
+
+/// 注册失败的快捷键
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct HotkeyError {
+    /// 快捷键
+    pub shortcut: String,
+    /// 错误信息
+    pub message: String,
+}
+
+/// 快捷键注册状态
+#[derive(Debug, Default)]
+pub struct HotkeyRegistry {
+    /// 已注册的快捷键（快捷键 ID → 动作）
+    actions: HashMap<u32, HotkeyAction>,
+    /// 注册失败的快捷键
+    errors: Vec<HotkeyError>,
+    /// 最近一次注册使用的配置
+    applied: Option<HotkeyConfig>,
+}
+
+impl HotkeyRegistry {
+    /// 已注册的快捷键数量
+    pub fn registered(&self) -> usize {
+        self.actions.len()
+    }
+
+    /// 注册失败的快捷键
+    pub fn errors(&self) -> &[HotkeyError] {
+        &self.errors
+    }
+}
+
+/// 创建全局快捷键插件
+pub fn plugin() -> TauriPlugin<Wry> {
+    tauri_plugin_global_shortcut::Builder::new()
+        .with_handler(|app, shortcut, event| {
+            if event.state() == ShortcutState::Pressed {
+                let app = app.clone();
+                let id = shortcut.id();
+                tauri::async_runtime::spawn(async move {
+                    let action = app
+                        .state::<AppState>()
+                        .hotkeys
+                        .lock()
+                        .await
+                        .actions
+                        .get(&id)
+                        .copied();
+                    if let Some(action) = action {
+                        handle_action(&app, action).await;
+                    }
+                });
+            }
+        })
+        .build()
+}
+
+/// 按配置重新注册全局快捷键，配置未变化时跳过
+pub async fn apply(app: &AppHandle, config: &HotkeyConfig) {
+    let state = app.state::<AppState>();
+    let mut registry = state.hotkeys.lock().await;
+    if registry.applied.as_ref() == Some(config) {
+        return;
+    }
+
+    let shortcuts = app.global_shortcut();
+    if let Err(e) = shortcuts.unregister_all() {
+        warn!("Failed to unregister hotkeys: {}", e);
+    }
+    registry.actions.clear();
+    registry.errors.clear();
+    registry.applied = Some(config.clone());
+
+    if !config.enabled {
+        return;
+    }
+
+    for binding in &config.bindings {
+        let result = binding
+            .shortcut
+            .parse::<Shortcut>()
+            .map_err(|e| e.to_string())
+            .and_then(|shortcut| {
+                shortcuts
+                    .register(shortcut)
+                    .map(|()| shortcut.id())
+                    .map_err(|e| e.to_string())
+            });
+        match result {
+            Ok(id) => {
+                let _ = registry.actions.insert(id, binding.action);
+            }
+            Err(message) => {
+                warn!(
+                    "Failed to register hotkey '{}': {}",
+                    binding.shortcut, message
+                );
+                registry.errors.push(HotkeyError {
+                    shortcut: binding.shortcut.clone(),
+                    message,
+                });
+            }
+        }
+    }
+    info!("Registered {} hotkeys", registry.actions.len());
+}
+
+/// 注册快捷键并在配置变化时重新注册
+pub fn spawn_listener(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut changes = app.state::<AppState>().config.subscribe();
+        loop {
+            let hotkeys = changes.borrow_and_update().hotkeys.clone();
+            apply(&app, &hotkeys).await;
+            if changes.changed().await.is_err() {
+                break;
+            }
+        }
+    });
+}
+
+/// 执行快捷键动作（作用于会话中的所有设备）
+async fn handle_action(app: &AppHandle, action: HotkeyAction) {
+    debug!("Hotkey action: {}", action);
+    let state = app.state::<AppState>();
+
+    match action {
+        HotkeyAction::Pause => {
+            let runtime = state.runtime.lock().await;
+            match runtime.status() {
+                RuntimeStatus::Stopped => return,
+                RuntimeStatus::Paused => runtime.resume(),
+                RuntimeStatus::Running => runtime.pause(),
+            }
+            let _ = app.emit(
+                event_names::RUNTIME_STATUS_CHANGED,
+                RuntimeStatusChangedEvent {
+                    status: runtime.status(),
+                },
+            );
+        }
+        HotkeyAction::EmergencyStop => {
+            let manager = state.session_manager.read().await;
+            for device_id in manager.list_devices().await {
+                if let Err(e) = manager.emergency_stop(&device_id).await {
+                    warn!("Hotkey emergency stop failed for {}: {}", device_id, e);
+                    continue;
+                }
+                let _ = app.emit(
+                    event_names::DEVICE_POWER_CHANGED,
+                    DevicePowerChangedEvent {
+                        device_id: device_id.clone(),
+                        power_a: 0,
+                        power_b: 0,
+                    },
+                );
+                let _ = app.emit(
+                    event_names::DEVICE_STATE_CHANGED,
+                    DeviceStateChangedEvent {
+                        device_id,
+                        state: DeviceState::Connected,
+                    },
+                );
+            }
+        }
+        _ => {
+            let Some((channel, direction)) = action.power_step() else {
+                return;
+            };
+            let delta = i16::from(direction) * i16::from(state.config.config().hotkeys.step);
+            let manager = state.session_manager.read().await;
+            for device_id in manager.list_devices().await {
+                if let Err(e) = manager.adjust_power(&device_id, channel, delta).await {
+                    warn!("Hotkey power step failed for {}: {}", device_id, e);
+                    continue;
+                }
+                let Some(device) = manager.get_device(&device_id).await else {
+                    continue;
+                };
+                let info = device.read().await.info();
+                let _ = app.emit(
+                    event_names::DEVICE_POWER_CHANGED,
+                    DevicePowerChangedEvent {
+                        device_id,
+                        power_a: info.power_a,
+                        power_b: info.power_b,
+                    },
+                );
+            }
+        }
+    }
+
+    let _ = app.emit(
+        event_names::HOTKEY_TRIGGERED,
+        HotkeyTriggeredEvent { action },
+    );
+}
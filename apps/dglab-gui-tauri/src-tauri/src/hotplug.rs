@@ -0,0 +1,80 @@
+//! 设备热插拔转发
+//!
+//! 订阅会话事件，把适配器发现和断开的设备通知前端。会话中的设备断开后开始扫描，
+//! 设备重新出现时由会话管理器自动重连，重连成功后停止扫描。
+
+use std::collections::HashSet;
+
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::broadcast;
+use tracing::{debug, warn};
+
+use dglab_core::device::DeviceState;
+use dglab_core::session::SessionEvent;
+
+use crate::events::{event_names, DeviceDiscoveredEvent, DeviceLostEvent, DeviceStateChangedEvent};
+use crate::state::AppState;
+
+/// 启动热插拔转发任务
+pub fn spawn_listener(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        let mut events = state.session_manager.read().await.subscribe_events();
+        // 已断开、等待重新出现的设备
+        let mut lost = HashSet::new();
+
+        loop {
+            match events.recv().await {
+                Ok(SessionEvent::DeviceDiscovered(device_id, name)) => {
+                    let _ = app.emit(
+                        event_names::DEVICE_DISCOVERED,
+                        DeviceDiscoveredEvent { device_id, name },
+                    );
+                }
+                Ok(SessionEvent::DeviceLost(device_id)) => {
+                    let manager = state.ble_managers.read().await.get(&device_id).cloned();
+                    if let Some(manager) = manager {
+                        if let Err(e) = manager.start_scan().await {
+                            warn!("Failed to start scan for {}: {}", device_id, e);
+                        }
+                        let _ = lost.insert(device_id.clone());
+                    }
+                    let _ = app.emit(
+                        event_names::DEVICE_LOST,
+                        DeviceLostEvent {
+                            device_id: device_id.clone(),
+                        },
+                    );
+                }
+                Ok(SessionEvent::DeviceStateChanged(device_id, device_state))
+                    if lost.contains(&device_id) =>
+                {
+                    let _ = app.emit(
+                        event_names::DEVICE_STATE_CHANGED,
+                        DeviceStateChangedEvent {
+                            device_id: device_id.clone(),
+                            state: device_state,
+                        },
+                    );
+                    if device_state != DeviceState::Connected {
+                        continue;
+                    }
+
+                    let _ = lost.remove(&device_id);
+                    let manager = state.ble_managers.read().await.get(&device_id).cloned();
+                    if let Some(manager) = manager {
+                        let _ = manager.stop_scan().await;
+                    }
+                }
+                Ok(SessionEvent::DeviceRemoved(device_id)) => {
+                    let _ = lost.remove(&device_id);
+                }
+                Ok(_) => continue,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    debug!("Hotplug listener lagged by {} events", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
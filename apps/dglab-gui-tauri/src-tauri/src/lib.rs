@@ -1,37 +1,134 @@
 //! DG-LAB Tauri GUI 应用
 
+mod battery;
 mod commands;
 mod events;
+mod feedback;
+mod gamepad;
+mod hooks;
+mod hotkeys;
+mod hotplug;
+mod link;
+mod logs;
+mod mobile;
+mod persist;
+mod runtime;
+mod schedule;
+mod settings;
 mod state;
+mod timer;
 
-use tracing_subscriber::{fmt, prelude::*, EnvFilter};
+use std::sync::Arc;
 
+use dglab_core::waveform::WaveformLibrary;
+use tauri::{Manager, RunEvent};
+use tracing::warn;
+use tracing_subscriber::{fmt, prelude::*, reload, EnvFilter};
+
+use crate::logs::{LogCapture, DEFAULT_LOG_FILTER};
 use crate::state::AppState;
 
 /// 初始化日志系统
-fn init_logging() {
+///
+/// 日志同时输出到 stderr 和内存缓冲区，过滤规则可在运行时修改。
+fn init_logging() -> Arc<LogCapture> {
+    let capture = Arc::new(LogCapture::default());
+    let filter = std::env::var(EnvFilter::DEFAULT_ENV)
+        .ok()
+        .filter(|filter| EnvFilter::try_new(filter).is_ok())
+        .unwrap_or_else(|| DEFAULT_LOG_FILTER.to_string());
+    let (filter_layer, handle) = reload::Layer::new(EnvFilter::new(&filter));
+
     tracing_subscriber::registry()
+        .with(filter_layer)
         .with(fmt::layer())
-        .with(
-            EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| EnvFilter::new("info,dglab=debug")),
-        )
+        .with(LogCapture::layer(&capture))
         .init();
+
+    capture.attach_filter(handle, filter);
+    capture
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     // 初始化日志
-    init_logging();
+    let logs = init_logging();
 
     // 创建应用状态
-    let app_state = AppState::new();
+    let app_state = AppState::new(logs);
 
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
+        .plugin(hotkeys::plugin())
+        .plugin(mobile::plugin())
         .manage(app_state)
+        .setup(|app| {
+            // 后台加载预设
+            let preset_manager = app.state::<AppState>().preset_manager.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = preset_manager.write().await.initialize().await {
+                    warn!("Failed to load presets: {}", e);
+                }
+            });
+
+            // 后台加载波形库
+            let session_manager = app.state::<AppState>().session_manager.clone();
+            tauri::async_runtime::spawn(async move {
+                let mut library = match WaveformLibrary::default_dir() {
+                    Ok(library) => library,
+                    Err(e) => {
+                        warn!("Failed to resolve waveform directory: {}", e);
+                        return;
+                    }
+                };
+                if let Err(e) = library.load().await {
+                    warn!("Failed to load waveform library: {}", e);
+                }
+                session_manager
+                    .read()
+                    .await
+                    .set_waveform_library(library)
+                    .await;
+            });
+
+            // 监听 APP 反馈按钮
+            feedback::spawn_listener(app.handle().clone());
+
+            // 转发电量和剩余使用时间
+            battery::spawn_listener(app.handle().clone());
+
+            // 转发 BLE 链路质量
+            link::spawn_listener(app.handle().clone());
+
+            // 转发设备热插拔
+            hotplug::spawn_listener(app.handle().clone());
+
+            // 加载配置并转发变更
+            settings::spawn_listener(app.handle().clone());
+
+            // 转发会话定时
+            timer::spawn_listener(app.handle().clone());
+
+            // 注册全局快捷键
+            hotkeys::spawn_listener(app.handle().clone());
+
+            // 执行事件钩子
+            hooks::spawn_listener(app.handle().clone());
+
+            // 启动预设调度并转发触发事件
+            schedule::spawn_listener(app.handle().clone());
+
+            // 加载手柄映射
+            gamepad::spawn_loader(app.handle().clone());
+
+            // 加载上次保存的窗口/会话状态
+            persist::spawn_loader(app.handle().clone());
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             // Device commands
+            commands::device::list_adapters,
+            commands::device::get_ble_availability,
             commands::device::scan_ble_devices,
             commands::device::connect_ble_device,
             commands::device::connect_device,
@@ -40,17 +137,105 @@ pub fn run() {
             commands::device::get_device_state,
             // Power commands
             commands::power::set_power,
+            commands::power::get_calibration,
+            commands::power::calibration_try,
+            commands::power::save_calibration,
+            commands::power::ramp_power,
+            commands::power::cancel_ramp,
+            commands::power::get_device_limits,
+            commands::power::set_device_limits,
+            commands::power::get_channel_link,
+            commands::power::set_channel_link,
+            commands::power::set_channel_enabled,
             commands::power::start_device,
             commands::power::stop_device,
+            commands::power::set_battery_override,
             commands::power::emergency_stop,
             // Session commands
             commands::session::get_session_info,
             commands::session::list_devices,
+            commands::session::set_session_timer,
+            commands::session::cancel_session_timer,
+            commands::session::get_session_timer,
+            commands::session::get_event_log,
+            commands::session::get_session_stats,
+            commands::session::load_session_snapshot,
+            commands::session::resume_session,
+            commands::session::discard_session_snapshot,
+            // Log commands
+            commands::logs::get_logs,
+            commands::logs::clear_logs,
+            commands::logs::get_log_level,
+            commands::logs::set_log_level,
+            // Settings commands
+            commands::settings::get_settings,
+            commands::settings::set_settings,
+            // Hotkey commands
+            commands::hotkeys::get_hotkeys,
+            commands::hotkeys::set_hotkeys,
+            // Feedback commands
+            commands::feedback::get_feedback_mapping,
+            commands::feedback::set_feedback_mapping,
+            // Gamepad commands
+            commands::gamepad::start_gamepad,
+            commands::gamepad::stop_gamepad,
+            commands::gamepad::get_gamepad_device,
+            commands::gamepad::get_gamepad_mapping,
+            commands::gamepad::set_gamepad_mapping,
+            // Preset commands
+            commands::preset::list_presets,
+            commands::preset::search_presets,
+            commands::preset::list_preset_categories,
+            commands::preset::get_preset,
+            commands::preset::get_effective_preset,
+            commands::preset::save_preset,
+            commands::preset::delete_preset,
+            commands::preset::apply_preset,
+            commands::preset::share_preset,
+            commands::preset::share_waveform,
+            commands::preset::import_share_code,
+            // Schedule commands
+            commands::schedule::list_schedules,
+            commands::schedule::set_schedule,
+            commands::schedule::remove_schedule,
+            commands::schedule::set_schedule_enabled,
+            // Runtime commands
+            commands::runtime::start_session_runtime,
+            commands::runtime::stop_session_runtime,
+            commands::runtime::pause_session_runtime,
+            commands::runtime::resume_session_runtime,
+            commands::runtime::get_session_runtime_status,
+            commands::runtime::set_runtime_waveform,
+            commands::runtime::clear_runtime_waveform,
+            // Waveform commands
+            commands::waveform::list_waveform_presets,
+            commands::waveform::set_waveform,
+            commands::waveform::preview_waveform,
             // WiFi commands
             commands::wifi::wifi_connect,
             commands::wifi::wifi_check_binding,
+            commands::wifi::wifi_qr_url,
             commands::wifi::wifi_cancel,
+            commands::wifi::remote_join,
+            // Mobile commands
+            commands::mobile::get_ble_permissions,
+            commands::mobile::request_ble_permissions,
+            // App state commands
+            commands::app_state::load_app_state,
+            commands::app_state::save_app_state,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            match event {
+                // 退出时保存窗口/会话状态
+                RunEvent::Exit => persist::save_on_exit(app),
+                // 移动端切到后台时窗口失去焦点
+                RunEvent::WindowEvent {
+                    event: tauri::WindowEvent::Focused(focused),
+                    ..
+                } => mobile::on_focus_changed(app, focused),
+                _ => {}
+            }
+        });
 }
@@ -0,0 +1,58 @@
+//! BLE 链路质量转发
+//!
+//! 订阅会话事件，将设备 RSSI 与弱信号告警转发给前端。
+
+use std::collections::HashSet;
+
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::broadcast;
+use tracing::debug;
+
+use dglab_core::session::SessionEvent;
+
+use crate::events::{event_names, DeviceLinkQualityEvent};
+use crate::state::AppState;
+
+/// 启动链路质量转发任务
+pub fn spawn_listener(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        let mut events = state.session_manager.read().await.subscribe_events();
+        // 处于弱信号状态的设备
+        let mut weak_devices = HashSet::new();
+
+        loop {
+            let (device_id, rssi) = match events.recv().await {
+                Ok(SessionEvent::LinkQuality(device_id, rssi)) => (device_id, rssi),
+                Ok(SessionEvent::WeakSignal(device_id, weak)) => {
+                    if weak {
+                        let _ = weak_devices.insert(device_id);
+                    } else {
+                        let _ = weak_devices.remove(&device_id);
+                    }
+                    continue;
+                }
+                Ok(SessionEvent::DeviceRemoved(device_id)) => {
+                    let _ = weak_devices.remove(&device_id);
+                    continue;
+                }
+                Ok(_) => continue,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    debug!("Link quality listener lagged by {} events", skipped);
+                    continue;
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            };
+
+            let weak = weak_devices.contains(&device_id);
+            let _ = app.emit(
+                event_names::DEVICE_LINK_QUALITY,
+                DeviceLinkQualityEvent {
+                    device_id,
+                    rssi,
+                    weak,
+                },
+            );
+        }
+    });
+}
@@ -0,0 +1,205 @@
+//! 日志捕获
+//!
+//! 在内存中保留最近的日志行，供前端查看和导出诊断信息；日志级别可在运行时调整。
+
+use std::collections::VecDeque;
+use std::fmt::{self, Write as _};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::{reload, EnvFilter, Registry};
+use ts_rs::TS;
+
+/// 默认保留的日志行数
+pub const DEFAULT_LOG_CAPACITY: usize = 1000;
+
+/// 默认日志过滤规则（未设置 `RUST_LOG` 时使用）
+pub const DEFAULT_LOG_FILTER: &str = "info,dglab=debug";
+
+/// 日志过滤器的重载句柄
+pub type FilterHandle = reload::Handle<EnvFilter, Registry>;
+
+/// 一行日志
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct LogLine {
+    /// 时间戳（Unix 毫秒）
+    #[ts(type = "number")]
+    pub timestamp_ms: u64,
+    /// 级别（TRACE/DEBUG/INFO/WARN/ERROR）
+    pub level: String,
+    /// 日志来源模块
+    pub target: String,
+    /// 日志内容（附带的字段以 `key=value` 形式追加）
+    pub message: String,
+}
+
+/// 日志捕获
+///
+/// 环形缓冲区满后丢弃最早的日志行。
+pub struct LogCapture {
+    /// 最近的日志行
+    lines: Mutex<VecDeque<LogLine>>,
+    /// 最多保留的行数
+    capacity: usize,
+    /// 当前过滤规则
+    filter: Mutex<String>,
+    /// 过滤器重载句柄（初始化日志系统后设置）
+    handle: OnceLock<FilterHandle>,
+}
+
+impl LogCapture {
+    /// 创建日志捕获，最多保留 `capacity` 行
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            lines: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity,
+            filter: Mutex::new(DEFAULT_LOG_FILTER.to_string()),
+            handle: OnceLock::new(),
+        }
+    }
+
+    /// 记录过滤器重载句柄和初始过滤规则
+    pub fn attach_filter(&self, handle: FilterHandle, filter: String) {
+        *lock(&self.filter) = filter;
+        let _ = self.handle.set(handle);
+    }
+
+    /// 获取日志行（从旧到新）
+    ///
+    /// `min_level` 只保留不低于该级别的日志，`limit` 只返回最近的若干行。
+    pub fn lines(&self, min_level: Option<Level>, limit: Option<usize>) -> Vec<LogLine> {
+        let lines = lock(&self.lines);
+        let mut matched: Vec<_> = lines
+            .iter()
+            .filter(|line| match min_level {
+                // tracing 中越详细的级别越大
+                Some(min) => Level::from_str(&line.level).is_ok_and(|level| level <= min),
+                None => true,
+            })
+            .cloned()
+            .collect();
+        if let Some(limit) = limit {
+            let skip = matched.len().saturating_sub(limit);
+            let _ = matched.drain(..skip);
+        }
+        matched
+    }
+
+    /// 清空日志
+    pub fn clear(&self) {
+        lock(&self.lines).clear();
+    }
+
+    /// 当前过滤规则
+    pub fn filter(&self) -> String {
+        lock(&self.filter).clone()
+    }
+
+    /// 设置过滤规则（`EnvFilter` 语法，如 `debug` 或 `info,dglab=trace`）
+    pub fn set_filter(&self, filter: &str) -> Result<(), String> {
+        let env_filter =
+            EnvFilter::try_new(filter).map_err(|e| format!("Invalid log filter: {}", e))?;
+        let handle = self
+            .handle
+            .get()
+            .ok_or_else(|| "Logging not initialized".to_string())?;
+        handle
+            .reload(env_filter)
+            .map_err(|e| format!("Failed to set log filter: {}", e))?;
+        *lock(&self.filter) = filter.to_string();
+        Ok(())
+    }
+
+    /// 创建写入本缓冲区的 tracing 层
+    pub fn layer(capture: &Arc<Self>) -> CaptureLayer {
+        CaptureLayer {
+            capture: capture.clone(),
+        }
+    }
+
+    /// 追加一行日志
+    fn push(&self, line: LogLine) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut lines = lock(&self.lines);
+        while lines.len() >= self.capacity {
+            let _ = lines.pop_front();
+        }
+        lines.push_back(line);
+    }
+}
+
+impl Default for LogCapture {
+    fn default() -> Self {
+        Self::new(DEFAULT_LOG_CAPACITY)
+    }
+}
+
+/// 加锁（锁中毒时继续使用内部数据）
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// 把日志事件写入 [`LogCapture`] 的 tracing 层
+pub struct CaptureLayer {
+    /// 日志捕获
+    capture: Arc<LogCapture>,
+}
+
+impl<S: Subscriber> Layer<S> for CaptureLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+
+        let metadata = event.metadata();
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default();
+
+        self.capture.push(LogLine {
+            timestamp_ms,
+            level: metadata.level().to_string(),
+            target: metadata.target().to_string(),
+            message: visitor.message,
+        });
+    }
+}
+
+/// 提取日志内容和字段
+#[derive(Default)]
+struct MessageVisitor {
+    /// 日志内容
+    message: String,
+}
+
+impl MessageVisitor {
+    /// 追加 `key=value` 字段
+    fn push_field(&mut self, field: &Field, value: fmt::Arguments<'_>) {
+        if field.name() == "message" {
+            let fields = std::mem::take(&mut self.message);
+            let _ = write!(self.message, "{}{}", value, fields);
+        } else {
+            let _ = write!(self.message, " {}={}", field.name(), value);
+        }
+    }
+}
+
+impl Visit for MessageVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.push_field(field, format_args!("{}", value));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.push_field(field, format_args!("{:?}", value));
+    }
+}
@@ -0,0 +1,271 @@
+//! 移动端支持
+//!
+//! Android 12 及以上扫描和连接 BLE 需要运行时授予 `BLUETOOTH_SCAN`/`BLUETOOTH_CONNECT`
+//! （`BLUETOOTH_SCAN` 声明了 `neverForLocation`，不需要定位），Android 11 及以下没有蓝牙运行时权限，
+//! 扫描需要定位权限。权限在 `gen/android/app/src/main/AndroidManifest.xml` 中声明，通过原生插件
+//! `BlePlugin`（见 `gen/android/.../BlePlugin.kt`）检查和请求；iOS 由系统在首次使用蓝牙时弹窗，
+//! 桌面端无需授权。
+//!
+//! 应用切到后台时 webview 会被暂停，会话运行时继续输出（见 [`crate::runtime`]），
+//! 只是不再向前端推送强度事件，回到前台后补发一次。Android 上会话运行期间切到后台时启动前台服务
+//! （`OutputService`），避免进程被系统回收后输出停在最后的强度。
+
+use serde::{Deserialize, Serialize};
+use tauri::plugin::{Builder, TauriPlugin};
+use tauri::{Manager, Wry};
+use tracing::warn;
+use ts_rs::TS;
+
+use crate::runtime::RuntimeStatus;
+use crate::state::AppState;
+
+#[cfg(target_os = "android")]
+use tauri::plugin::PluginHandle;
+
+/// Android 原生插件所在的包
+#[cfg(target_os = "android")]
+const ANDROID_PLUGIN_PACKAGE: &str = "com.zizimiku.dglab_gui_tauri";
+
+/// 单项权限状态（与 Tauri 移动端插件的权限状态一致）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "kebab-case")]
+#[ts(export)]
+pub enum PermissionState {
+    /// 已授权
+    Granted,
+    /// 已拒绝（需要到系统设置中开启）
+    Denied,
+    /// 尚未询问
+    Prompt,
+    /// 尚未询问，应先向用户说明用途
+    PromptWithRationale,
+    /// 当前平台不需要授权
+    NotRequired,
+}
+
+impl PermissionState {
+    /// 是否可以使用
+    pub fn is_allowed(self) -> bool {
+        matches!(self, Self::Granted | Self::NotRequired)
+    }
+}
+
+/// BLE 权限状态
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct BlePermissionStatus {
+    /// 平台（`android`、`ios`、`linux` 等）
+    pub platform: String,
+    /// 蓝牙扫描和连接权限
+    pub bluetooth: PermissionState,
+    /// 定位权限（Android 11 及以下扫描 BLE 需要）
+    pub location: PermissionState,
+    /// 是否可以扫描和连接
+    pub ready: bool,
+}
+
+impl BlePermissionStatus {
+    fn new(bluetooth: PermissionState, location: PermissionState) -> Self {
+        Self {
+            platform: std::env::consts::OS.to_string(),
+            bluetooth,
+            location,
+            ready: bluetooth.is_allowed() && location.is_allowed(),
+        }
+    }
+}
+
+/// Android 插件 `checkPermissions`/`requestPermissions` 的返回值
+#[cfg(target_os = "android")]
+#[derive(Debug, Deserialize)]
+struct AndroidPermissions {
+    bluetooth: PermissionState,
+    location: PermissionState,
+}
+
+/// `requestPermissions` 的参数（权限别名）
+#[cfg(target_os = "android")]
+#[derive(Debug, Serialize)]
+struct RequestPermissions {
+    permissions: Vec<&'static str>,
+}
+
+/// `sdkVersion` 的返回值
+#[cfg(target_os = "android")]
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SdkVersion {
+    sdk_int: u32,
+}
+
+/// `setForeground` 的参数
+#[cfg(target_os = "android")]
+#[derive(Debug, Serialize)]
+struct SetForeground {
+    active: bool,
+}
+
+/// Android 12 的 API 级别，从这一版起蓝牙改用运行时权限，扫描不再需要定位
+#[cfg(target_os = "android")]
+const ANDROID_12_SDK: u32 = 31;
+
+/// BLE 权限
+pub struct BlePermissions {
+    /// 原生插件句柄
+    #[cfg(target_os = "android")]
+    handle: PluginHandle<Wry>,
+}
+
+impl BlePermissions {
+    /// 当前权限状态
+    pub async fn status(&self) -> Result<BlePermissionStatus, String> {
+        #[cfg(target_os = "android")]
+        {
+            self.run_plugin("checkPermissions", None).await
+        }
+        #[cfg(not(target_os = "android"))]
+        {
+            Ok(Self::not_required())
+        }
+    }
+
+    /// 请求尚未授予的权限（Android 会弹出系统对话框，等待用户选择后返回）
+    pub async fn request(&self) -> Result<BlePermissionStatus, String> {
+        #[cfg(target_os = "android")]
+        {
+            // 只请求当前系统版本需要的权限
+            let alias = if self.sdk_int().await? >= ANDROID_12_SDK {
+                "bluetooth"
+            } else {
+                "location"
+            };
+            let request = RequestPermissions {
+                permissions: vec![alias],
+            };
+            self.run_plugin("requestPermissions", Some(request)).await
+        }
+        #[cfg(not(target_os = "android"))]
+        {
+            Ok(Self::not_required())
+        }
+    }
+
+    /// 扫描或连接前检查权限，未授权时返回提示
+    pub async fn ensure(&self) -> Result<(), String> {
+        let status = self.status().await?;
+        if status.ready {
+            return Ok(());
+        }
+        Err(format!(
+            "缺少蓝牙权限（蓝牙: {:?}, 定位: {:?}），请先授权",
+            status.bluetooth, status.location
+        ))
+    }
+
+    /// 桌面端和 iOS 不需要提前授权
+    #[cfg(not(target_os = "android"))]
+    fn not_required() -> BlePermissionStatus {
+        BlePermissionStatus::new(PermissionState::NotRequired, PermissionState::NotRequired)
+    }
+
+    /// 启动或停止 Android 前台服务（其它平台忽略）
+    pub async fn set_foreground(&self, active: bool) -> Result<(), String> {
+        #[cfg(target_os = "android")]
+        {
+            let handle = self.handle.clone();
+            tauri::async_runtime::spawn_blocking(move || {
+                handle.run_mobile_plugin::<()>("setForeground", SetForeground { active })
+            })
+            .await
+            .map_err(|e| e.to_string())?
+            .map_err(|e| format!("Failed to toggle foreground service: {}", e))
+        }
+        #[cfg(not(target_os = "android"))]
+        {
+            let _ = active;
+            Ok(())
+        }
+    }
+
+    /// 系统 API 级别
+    #[cfg(target_os = "android")]
+    async fn sdk_int(&self) -> Result<u32, String> {
+        let handle = self.handle.clone();
+        let version = tauri::async_runtime::spawn_blocking(move || {
+            handle.run_mobile_plugin::<SdkVersion>("sdkVersion", ())
+        })
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("Failed to query Android version: {}", e))?;
+        Ok(version.sdk_int)
+    }
+
+    /// 调用原生插件（阻塞直到用户响应，放到阻塞线程执行）
+    #[cfg(target_os = "android")]
+    async fn run_plugin(
+        &self,
+        command: &'static str,
+        request: Option<RequestPermissions>,
+    ) -> Result<BlePermissionStatus, String> {
+        let handle = self.handle.clone();
+        let permissions = tauri::async_runtime::spawn_blocking(move || match request {
+            Some(request) => handle.run_mobile_plugin::<AndroidPermissions>(command, request),
+            None => handle.run_mobile_plugin::<AndroidPermissions>(command, ()),
+        })
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("Failed to query Bluetooth permissions: {}", e))?;
+        // 各版本只需要其中一项权限，另一项按不需要处理
+        let status = if self.sdk_int().await? >= ANDROID_12_SDK {
+            BlePermissionStatus::new(permissions.bluetooth, PermissionState::NotRequired)
+        } else {
+            BlePermissionStatus::new(PermissionState::NotRequired, permissions.location)
+        };
+        Ok(status)
+    }
+}
+
+/// 创建移动端插件（注册原生插件并管理 [`BlePermissions`]）
+pub fn plugin() -> TauriPlugin<Wry> {
+    Builder::new("dglab-ble")
+        .setup(|app, _api| {
+            #[cfg(target_os = "android")]
+            let permissions = BlePermissions {
+                handle: _api.register_android_plugin(ANDROID_PLUGIN_PACKAGE, "BlePlugin")?,
+            };
+            #[cfg(not(target_os = "android"))]
+            let permissions = BlePermissions {};
+            app.manage(permissions);
+            Ok(())
+        })
+        .build()
+}
+
+/// 主窗口焦点变化（移动端失去焦点即切到后台，桌面端 webview 不会暂停，忽略）
+///
+/// 会话运行期间切到后台时启动前台服务，回到前台时停止。
+pub fn on_focus_changed(app: &tauri::AppHandle, focused: bool) {
+    if !cfg!(mobile) {
+        return;
+    }
+    let backgrounded = !focused;
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let runtime = app.state::<AppState>().runtime.clone();
+        let running = {
+            let runtime = runtime.lock().await;
+            runtime.set_backgrounded(backgrounded);
+            runtime.status() != RuntimeStatus::Stopped
+        };
+        if backgrounded && !running {
+            return;
+        }
+        if let Err(e) = app
+            .state::<BlePermissions>()
+            .set_foreground(backgrounded)
+            .await
+        {
+            warn!("{}", e);
+        }
+    });
+}
@@ -0,0 +1,194 @@
+//! 窗口/会话状态持久化
+//!
+//! 保存最近连接的设备、最近应用的预设、通道强度和 WiFi clientId，退出时写入
+//! 配置目录下的 `app_state.json`，下次启动时由前端通过 `load_app_state` 读取并恢复。
+//! 会话快照（设备、强度、波形和定时）同时写入 `session_snapshot.json`，由前端经用户确认后
+//! 通过 `resume_session` 恢复。
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use tracing::{debug, warn};
+use ts_rs::TS;
+
+use dglab_core::config::ConfigManager;
+use dglab_core::session::SessionSnapshot;
+
+use crate::state::AppState;
+
+/// 状态文件名（与配置文件同目录）
+const STATE_FILE: &str = "app_state.json";
+
+/// WiFi clientId 的有效期
+///
+/// 服务器在连接断开后不再保留 clientId，超过该时长的 clientId 视为失效，不再恢复。
+pub const WIFI_CLIENT_ID_TTL: Duration = Duration::from_secs(300);
+
+/// 最近连接的设备
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct SavedDevice {
+    /// 设备 ID（BLE 地址或 WiFi 设备 ID）
+    pub id: String,
+    /// 设备名称
+    pub name: String,
+    /// 是否为 WiFi 设备
+    #[serde(default)]
+    pub wifi: bool,
+}
+
+/// WiFi 连接信息
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct SavedWifi {
+    /// 服务器地址
+    pub server: String,
+    /// 服务器分配的 clientId
+    pub client_id: String,
+}
+
+/// 持久化的应用状态
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(default)]
+#[ts(export)]
+pub struct SavedAppState {
+    /// 最近连接的设备
+    pub last_device: Option<SavedDevice>,
+    /// 最近应用的预设 ID
+    pub last_preset: Option<String>,
+    /// A 通道强度
+    pub power_a: u8,
+    /// B 通道强度
+    pub power_b: u8,
+    /// WiFi 连接信息（已失效时为 `None`）
+    pub wifi: Option<SavedWifi>,
+    /// 保存时间（Unix 秒）
+    #[ts(type = "number")]
+    pub saved_at: u64,
+}
+
+impl SavedAppState {
+    /// 默认状态文件路径
+    pub fn default_path() -> PathBuf {
+        match ConfigManager::default_config_path() {
+            Ok(path) => path.with_file_name(STATE_FILE),
+            Err(e) => {
+                warn!("Failed to resolve config path: {}, using temp dir", e);
+                std::env::temp_dir().join("dglab").join(STATE_FILE)
+            }
+        }
+    }
+
+    /// 加载状态，文件不存在或无法解析时返回默认状态
+    ///
+    /// 超过 [`WIFI_CLIENT_ID_TTL`] 的 WiFi clientId 被丢弃。
+    pub async fn load(path: &Path) -> Self {
+        let content = match tokio::fs::read_to_string(path).await {
+            Ok(content) => content,
+            Err(e) => {
+                debug!("No saved app state at {}: {}", path.display(), e);
+                return Self::default();
+            }
+        };
+
+        let mut state: Self = match serde_json::from_str(&content) {
+            Ok(state) => state,
+            Err(e) => {
+                warn!("Failed to parse app state {}: {}", path.display(), e);
+                return Self::default();
+            }
+        };
+
+        if now().saturating_sub(state.saved_at) > WIFI_CLIENT_ID_TTL.as_secs() {
+            state.wifi = None;
+        }
+        state
+    }
+
+    /// 写入状态文件（记录保存时间）
+    pub async fn save(&mut self, path: &Path) -> std::io::Result<()> {
+        self.saved_at = now();
+        if let Some(dir) = path.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        let content = serde_json::to_string_pretty(self)?;
+        tokio::fs::write(path, content).await
+    }
+}
+
+/// 当前 Unix 时间（秒）
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// 用当前会话刷新状态中的通道强度并写入文件
+pub async fn save(state: &AppState) -> Result<SavedAppState, String> {
+    let mut saved = state.app_state.lock().await;
+
+    let last_id = saved.last_device.as_ref().map(|device| device.id.clone());
+    if let Some(id) = last_id {
+        let manager = state.session_manager.read().await;
+        if let Some(device) = manager.get_device(&id).await {
+            let dev = device.read().await;
+            saved.power_a = dev.get_power(0);
+            saved.power_b = dev.get_power(1);
+        }
+    }
+
+    saved
+        .save(&state.app_state_path)
+        .await
+        .map_err(|e| format!("Failed to save app state: {}", e))?;
+    debug!("App state saved to {}", state.app_state_path.display());
+    Ok(saved.clone())
+}
+
+/// 保存会话快照，会话中没有设备时删除旧快照
+///
+/// 尚未恢复的旧快照在会话为空时保留，避免未确认恢复就退出时丢失。
+pub async fn save_snapshot(state: &AppState) -> Result<(), String> {
+    let snapshot = state.session_manager.read().await.snapshot().await;
+    let path = &state.session_snapshot_path;
+
+    let result = if !snapshot.is_empty() {
+        snapshot.save(path).await
+    } else if state.session_snapshot.lock().await.is_none() {
+        SessionSnapshot::remove(path).await
+    } else {
+        Ok(())
+    };
+    result.map_err(|e| format!("Failed to save session snapshot: {}", e))
+}
+
+/// 启动时加载保存的状态和会话快照
+pub fn spawn_loader(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        let saved = SavedAppState::load(&state.app_state_path).await;
+        *state.app_state.lock().await = saved;
+
+        match SessionSnapshot::load(&state.session_snapshot_path).await {
+            Ok(snapshot) => {
+                *state.session_snapshot.lock().await = snapshot.filter(|s| !s.is_empty());
+            }
+            Err(e) => warn!("Failed to load session snapshot: {}", e),
+        }
+    });
+}
+
+/// 退出时保存状态和会话快照
+pub fn save_on_exit(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    tauri::async_runtime::block_on(async {
+        if let Err(e) = save(&state).await {
+            warn!("{}", e);
+        }
+        if let Err(e) = save_snapshot(&state).await {
+            warn!("{}", e);
+        }
+    });
+}
@@ -0,0 +1,341 @@
+//! 会话后台运行时
+//!
+//! 周期性驱动会话中的设备：发送心跳、推进波形生成器、重连出错的设备。
+//! 运行时独立于前端，webview 空闲时强度模式仍会持续输出。
+//!
+//! 移动端切到后台时 webview 暂停，运行时照常输出，但强度变化只记录不推送，回到前台后
+//! 为这些设备补发一次当前强度。进程被系统挂起后恢复时，单次推进的时长有上限，
+//! 避免波形跳变。
+
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::{Mutex, RwLock};
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+use tracing::{debug, info, warn};
+use ts_rs::TS;
+
+use dglab_core::device::{Device, DeviceState};
+use dglab_core::session::SessionManager;
+use dglab_core::waveform::{Waveform, WaveformGenerator};
+
+use crate::events::{event_names, DevicePowerChangedEvent, DeviceStateChangedEvent};
+
+/// 默认 tick 间隔（毫秒），与 V3 协议 B0 输出周期一致
+pub const DEFAULT_TICK_MS: u64 = 100;
+
+/// 心跳间隔
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(dglab_protocol::wifi::HEARTBEAT_INTERVAL);
+
+/// 出错设备的重连间隔
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// 单次 tick 推进波形的最大时长（毫秒），进程被挂起后恢复时不一次性跳过
+const MAX_TICK_DELTA_MS: u64 = 1000;
+
+/// 运行时状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum RuntimeStatus {
+    /// 未运行
+    Stopped,
+    /// 运行中
+    Running,
+    /// 已暂停（保持心跳和重连，不推进波形）
+    Paused,
+}
+
+/// 波形生成器映射（设备 ID, 通道）→ 生成器
+type GeneratorMap = HashMap<(String, u8), WaveformGenerator>;
+
+/// 会话后台运行时
+pub struct SessionRuntime {
+    /// 会话管理器
+    session_manager: Arc<RwLock<SessionManager>>,
+    /// 各设备通道的波形生成器
+    generators: Arc<Mutex<GeneratorMap>>,
+    /// 是否暂停
+    paused: Arc<AtomicBool>,
+    /// 应用是否在后台（不推送强度事件）
+    backgrounded: Arc<AtomicBool>,
+    /// tick 间隔
+    tick_interval: Duration,
+    /// 后台任务句柄
+    task: Option<JoinHandle<()>>,
+}
+
+impl SessionRuntime {
+    /// 创建新的运行时
+    pub fn new(session_manager: Arc<RwLock<SessionManager>>) -> Self {
+        Self {
+            session_manager,
+            generators: Arc::new(Mutex::new(HashMap::new())),
+            paused: Arc::new(AtomicBool::new(false)),
+            backgrounded: Arc::new(AtomicBool::new(false)),
+            tick_interval: Duration::from_millis(DEFAULT_TICK_MS),
+            task: None,
+        }
+    }
+
+    /// 获取当前状态
+    pub fn status(&self) -> RuntimeStatus {
+        match &self.task {
+            Some(handle) if !handle.is_finished() => {
+                if self.paused.load(Ordering::Relaxed) {
+                    RuntimeStatus::Paused
+                } else {
+                    RuntimeStatus::Running
+                }
+            }
+            _ => RuntimeStatus::Stopped,
+        }
+    }
+
+    /// 启动运行时（已运行时仅取消暂停）
+    pub fn start(&mut self, app: AppHandle) {
+        self.paused.store(false, Ordering::Relaxed);
+
+        if self.status() != RuntimeStatus::Stopped {
+            return;
+        }
+
+        info!(
+            "Starting session runtime, tick interval: {:?}",
+            self.tick_interval
+        );
+
+        let session_manager = self.session_manager.clone();
+        let generators = self.generators.clone();
+        let paused = self.paused.clone();
+        let backgrounded = self.backgrounded.clone();
+        let tick_interval = self.tick_interval;
+
+        self.task = Some(tokio::spawn(async move {
+            Self::run_loop(
+                app,
+                session_manager,
+                generators,
+                paused,
+                backgrounded,
+                tick_interval,
+            )
+            .await;
+        }));
+    }
+
+    /// 停止运行时
+    pub fn stop(&mut self) {
+        if let Some(handle) = self.task.take() {
+            info!("Stopping session runtime");
+            handle.abort();
+        }
+        self.paused.store(false, Ordering::Relaxed);
+    }
+
+    /// 暂停波形推进
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::Relaxed);
+    }
+
+    /// 恢复波形推进
+    pub fn resume(&self) {
+        self.paused.store(false, Ordering::Relaxed);
+    }
+
+    /// 应用切到后台或回到前台
+    pub fn set_backgrounded(&self, backgrounded: bool) {
+        if self.backgrounded.swap(backgrounded, Ordering::Relaxed) != backgrounded {
+            debug!("Session runtime backgrounded: {}", backgrounded);
+        }
+    }
+
+    /// 为设备通道设置波形生成器
+    pub async fn set_generator(&self, device_id: &str, channel: u8, waveform: Waveform) {
+        let mut generator = WaveformGenerator::with_waveform(waveform);
+        generator.start();
+
+        let mut generators = self.generators.lock().await;
+        let _ = generators.insert((device_id.to_string(), channel), generator);
+    }
+
+    /// 清除设备通道的波形生成器
+    pub async fn clear_generator(&self, device_id: &str, channel: u8) {
+        let mut generators = self.generators.lock().await;
+        let _ = generators.remove(&(device_id.to_string(), channel));
+    }
+
+    /// 清除设备的所有波形生成器
+    pub async fn clear_device(&self, device_id: &str) {
+        let mut generators = self.generators.lock().await;
+        generators.retain(|(id, _), _| id != device_id);
+    }
+
+    /// 重连出错的设备，只持有该设备的写锁
+    async fn reconnect(app: AppHandle, device_id: String, device: Arc<RwLock<Box<dyn Device>>>) {
+        debug!("Reconnecting device: {}", device_id);
+        let mut dev = device.write().await;
+        match dev.connect().await {
+            Ok(()) => {
+                info!("Device reconnected: {}", device_id);
+                let _ = app.emit(
+                    event_names::DEVICE_STATE_CHANGED,
+                    DeviceStateChangedEvent {
+                        device_id,
+                        state: dev.state(),
+                    },
+                );
+            }
+            Err(e) => warn!("Failed to reconnect device {}: {}", device_id, e),
+        }
+    }
+
+    /// 后台循环
+    async fn run_loop(
+        app: AppHandle,
+        session_manager: Arc<RwLock<SessionManager>>,
+        generators: Arc<Mutex<GeneratorMap>>,
+        paused: Arc<AtomicBool>,
+        backgrounded: Arc<AtomicBool>,
+        tick_interval: Duration,
+    ) {
+        let mut interval = tokio::time::interval(tick_interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+        let mut last_tick = Instant::now();
+        let mut last_heartbeat = Instant::now();
+        let mut last_reconnect = Instant::now();
+        let mut reconnecting: HashMap<String, JoinHandle<()>> = HashMap::new();
+        // 在后台期间强度有变化、回到前台后需要补发的设备
+        let mut stale = HashSet::new();
+
+        loop {
+            interval.tick().await;
+
+            let now = Instant::now();
+            let delta_ms =
+                (now.duration_since(last_tick).as_millis() as u64).min(MAX_TICK_DELTA_MS);
+            last_tick = now;
+
+            let heartbeat_due = now.duration_since(last_heartbeat) >= HEARTBEAT_INTERVAL;
+            if heartbeat_due {
+                last_heartbeat = now;
+            }
+
+            let reconnect_due = now.duration_since(last_reconnect) >= RECONNECT_INTERVAL;
+            if reconnect_due {
+                last_reconnect = now;
+            }
+
+            let is_paused = paused.load(Ordering::Relaxed);
+            let is_backgrounded = backgrounded.load(Ordering::Relaxed);
+
+            // 只在收集设备时持有会话锁，重连在单独的任务中进行，不阻塞其他命令和设备
+            let devices = {
+                let manager = session_manager.read().await;
+                let mut devices = Vec::new();
+                for device_id in manager.list_devices().await {
+                    if let Some(device) = manager.get_device(&device_id).await {
+                        devices.push((device_id, device));
+                    }
+                }
+                devices
+            };
+            reconnecting.retain(|_, task| !task.is_finished());
+
+            for (device_id, device) in devices {
+                // 重连任务持有设备写锁，期间跳过该设备
+                if reconnecting.contains_key(&device_id) {
+                    continue;
+                }
+                let mut dev = device.write().await;
+
+                match dev.state() {
+                    DeviceState::Error if reconnect_due => {
+                        drop(dev);
+                        let task =
+                            tokio::spawn(Self::reconnect(app.clone(), device_id.clone(), device));
+                        let _ = reconnecting.insert(device_id, task);
+                        continue;
+                    }
+                    DeviceState::Connected | DeviceState::Running if heartbeat_due => {
+                        if let Err(e) = dev.heartbeat().await {
+                            warn!("Heartbeat failed for device {}: {}", device_id, e);
+                        }
+                    }
+                    _ => {}
+                }
+
+                if !is_backgrounded && stale.remove(&device_id) {
+                    let _ = app.emit(
+                        event_names::DEVICE_POWER_CHANGED,
+                        DevicePowerChangedEvent {
+                            device_id: device_id.clone(),
+                            power_a: dev.get_power(0),
+                            power_b: dev.get_power(1),
+                        },
+                    );
+                }
+
+                if is_paused || dev.state() != DeviceState::Running {
+                    continue;
+                }
+                let name = dev.name().to_string();
+                let current = [dev.get_power(0), dev.get_power(1)];
+                drop(dev);
+
+                // 强度经会话管理器写入，受校准和安全限制约束
+                let manager = session_manager.read().await;
+                let mut changed = false;
+                let mut generators = generators.lock().await;
+                for channel in 0..2u8 {
+                    let Some(generator) = generators.get_mut(&(device_id.clone(), channel)) else {
+                        continue;
+                    };
+
+                    let power = generator.update(delta_ms);
+                    if manager.resolve_power(&device_id, &name, channel, power)
+                        == current[channel as usize]
+                    {
+                        continue;
+                    }
+
+                    match manager.set_power(&device_id, channel, power).await {
+                        Ok(_) => changed = true,
+                        Err(e) => warn!(
+                            "Failed to apply waveform power on {} channel {}: {}",
+                            device_id, channel, e
+                        ),
+                    }
+                }
+                drop(generators);
+                drop(manager);
+
+                if changed && is_backgrounded {
+                    let _ = stale.insert(device_id.clone());
+                } else if changed {
+                    let dev = device.read().await;
+                    let _ = app.emit(
+                        event_names::DEVICE_POWER_CHANGED,
+                        DevicePowerChangedEvent {
+                            device_id: device_id.clone(),
+                            power_a: dev.get_power(0),
+                            power_b: dev.get_power(1),
+                        },
+                    );
+                }
+            }
+        }
+    }
+}
+
+impl Drop for SessionRuntime {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
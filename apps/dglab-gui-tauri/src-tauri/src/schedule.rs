@@ -0,0 +1,75 @@
+//! 预设定时转发
+//!
+//! 启动预设调度器，定时触发后通知前端并同步已应用预设的设备强度。
+
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::broadcast;
+use tracing::debug;
+
+use dglab_core::preset::ScheduleEvent;
+
+use crate::events::{event_names, DevicePowerChangedEvent, ScheduleFiredEvent};
+use crate::state::AppState;
+
+/// 启动预设调度和转发任务
+pub fn spawn_listener(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        let mut events = state.scheduler.subscribe();
+        state.scheduler.start(
+            state.config.subscribe(),
+            state.session_manager.clone(),
+            state.preset_manager.clone(),
+        );
+
+        loop {
+            let event = match events.recv().await {
+                Ok(ScheduleEvent::Fired {
+                    schedule,
+                    preset,
+                    devices,
+                }) => {
+                    let manager = state.session_manager.read().await;
+                    for device_id in &devices {
+                        let Some(device) = manager.get_device(device_id).await else {
+                            continue;
+                        };
+                        let dev = device.read().await;
+                        let _ = app.emit(
+                            event_names::DEVICE_POWER_CHANGED,
+                            DevicePowerChangedEvent {
+                                device_id: device_id.clone(),
+                                power_a: dev.get_power(0),
+                                power_b: dev.get_power(1),
+                            },
+                        );
+                    }
+
+                    ScheduleFiredEvent {
+                        schedule,
+                        preset,
+                        devices,
+                        error: None,
+                    }
+                }
+                Ok(ScheduleEvent::Failed {
+                    schedule,
+                    preset,
+                    error,
+                }) => ScheduleFiredEvent {
+                    schedule,
+                    preset,
+                    devices: Vec::new(),
+                    error: Some(error),
+                },
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    debug!("Schedule listener lagged by {} events", skipped);
+                    continue;
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            };
+
+            let _ = app.emit(event_names::SCHEDULE_FIRED, event);
+        }
+    });
+}
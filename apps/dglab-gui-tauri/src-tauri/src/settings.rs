@@ -0,0 +1,51 @@
+//! 配置变化转发
+//!
+//! 加载配置文件并定期检查外部修改，配置变化时通知前端并更新会话的强度校准。
+//! 配置文件无效时不回退到默认的安全限制，在修复前禁止输出。
+
+use std::time::Duration;
+
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::error;
+
+use dglab_core::config::SafetyConfig;
+
+use crate::events::{event_names, SettingsChangedEvent};
+use crate::state::AppState;
+
+/// 配置文件检查间隔
+const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// 加载配置并启动转发任务
+pub fn spawn_listener(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let config = app.state::<AppState>().config.clone();
+        let session_manager = app.state::<AppState>().session_manager.clone();
+        match config.load().await {
+            Ok(()) => session_manager.read().await.load_config(&config.config()),
+            Err(e) => {
+                error!(
+                    "Invalid config file {}: {}, output is disabled until it is fixed",
+                    config.path().display(),
+                    e
+                );
+                session_manager
+                    .read()
+                    .await
+                    .set_safety(SafetyConfig::locked());
+            }
+        }
+
+        let mut changes = config.subscribe();
+        let _watch = config.watch(WATCH_INTERVAL);
+
+        while changes.changed().await.is_ok() {
+            let settings = changes.borrow_and_update().clone();
+            session_manager.read().await.load_config(&settings);
+            let _ = app.emit(
+                event_names::SETTINGS_CHANGED,
+                SettingsChangedEvent { settings },
+            );
+        }
+    });
+}
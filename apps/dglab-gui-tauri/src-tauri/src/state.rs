@@ -1,32 +1,113 @@
 //! 应用状态管理
 
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tauri::async_runtime::JoinHandle;
+use tokio::sync::{Mutex, RwLock};
+use tracing::warn;
 
-use dglab_core::session::SessionManager;
+use dglab_core::config::ConfigManager;
+use dglab_core::feedback::FeedbackRouter;
+use dglab_core::gamepad::GamepadController;
+use dglab_core::preset::{PresetManager, PresetScheduler};
+use dglab_core::session::{EventLog, SessionManager, SessionSnapshot};
 use dglab_protocol::ble::BleManager;
 
+use crate::hotkeys::HotkeyRegistry;
+use crate::logs::LogCapture;
+use crate::persist::SavedAppState;
+use crate::runtime::SessionRuntime;
+
 /// 应用状态
 pub struct AppState {
     /// 会话管理器
     pub session_manager: Arc<RwLock<SessionManager>>,
     /// BLE 管理器（保持连接）
     pub ble_managers: Arc<RwLock<HashMap<String, Arc<BleManager>>>>,
+    /// 适配器事件监听任务（设备 ID → 任务句柄）
+    pub adapter_watchers: Arc<Mutex<HashMap<String, tokio::task::JoinHandle<()>>>>,
+    /// 会话后台运行时
+    pub runtime: Arc<Mutex<SessionRuntime>>,
+    /// 预设管理器
+    pub preset_manager: Arc<RwLock<PresetManager>>,
+    /// APP 反馈按钮路由器
+    pub feedback_router: Arc<Mutex<FeedbackRouter>>,
+    /// 手柄控制器
+    pub gamepad_controller: Arc<Mutex<GamepadController>>,
+    /// 手柄控制服务（控制的设备 ID，后台任务）
+    pub gamepad_service: Arc<Mutex<Option<(String, JoinHandle<()>)>>>,
+    /// 应用配置
+    pub config: Arc<ConfigManager>,
+    /// 预设调度器
+    pub scheduler: Arc<PresetScheduler>,
+    /// 全局快捷键注册状态
+    pub hotkeys: Arc<Mutex<HotkeyRegistry>>,
+    /// 最近的日志
+    pub logs: Arc<LogCapture>,
+    /// 跨重启保存的窗口/会话状态
+    pub app_state: Arc<Mutex<SavedAppState>>,
+    /// 状态文件路径
+    pub app_state_path: PathBuf,
+    /// 上次退出时保存、尚未恢复或放弃的会话快照
+    pub session_snapshot: Arc<Mutex<Option<SessionSnapshot>>>,
+    /// 会话快照文件路径
+    pub session_snapshot_path: PathBuf,
 }
 
 impl AppState {
     /// 创建新的应用状态
-    pub fn new() -> Self {
+    pub fn new(logs: Arc<LogCapture>) -> Self {
+        let session_manager = match EventLog::default_dir() {
+            Ok(dir) => SessionManager::with_event_log(&dir),
+            Err(e) => {
+                warn!(
+                    "Failed to resolve event log directory: {}, logging disabled",
+                    e
+                );
+                SessionManager::new()
+            }
+        };
+        let session_manager = Arc::new(RwLock::new(session_manager));
+        let runtime = SessionRuntime::new(session_manager.clone());
+        let preset_manager = PresetManager::default_dir().unwrap_or_else(|e| {
+            warn!("Failed to resolve preset directory: {}, using temp dir", e);
+            PresetManager::new(std::env::temp_dir().join("dglab").join("presets"))
+        });
+        let config = ConfigManager::default_path().unwrap_or_else(|e| {
+            warn!("Failed to resolve config path: {}, using temp dir", e);
+            ConfigManager::new(std::env::temp_dir().join("dglab").join("config.toml"))
+        });
+        let session_snapshot_path = SessionSnapshot::default_path().unwrap_or_else(|e| {
+            warn!("Failed to resolve snapshot path: {}, using temp dir", e);
+            std::env::temp_dir()
+                .join("dglab")
+                .join("session_snapshot.json")
+        });
+
         Self {
-            session_manager: Arc::new(RwLock::new(SessionManager::new())),
+            session_manager,
             ble_managers: Arc::new(RwLock::new(HashMap::new())),
+            adapter_watchers: Arc::new(Mutex::new(HashMap::new())),
+            runtime: Arc::new(Mutex::new(runtime)),
+            preset_manager: Arc::new(RwLock::new(preset_manager)),
+            feedback_router: Arc::new(Mutex::new(FeedbackRouter::default())),
+            gamepad_controller: Arc::new(Mutex::new(GamepadController::default())),
+            gamepad_service: Arc::new(Mutex::new(None)),
+            config: Arc::new(config),
+            scheduler: Arc::new(PresetScheduler::new()),
+            hotkeys: Arc::new(Mutex::new(HotkeyRegistry::default())),
+            logs,
+            app_state: Arc::new(Mutex::new(SavedAppState::default())),
+            app_state_path: SavedAppState::default_path(),
+            session_snapshot: Arc::new(Mutex::new(None)),
+            session_snapshot_path,
         }
     }
 }
 
 impl Default for AppState {
     fn default() -> Self {
-        Self::new()
+        Self::new(Arc::default())
     }
 }
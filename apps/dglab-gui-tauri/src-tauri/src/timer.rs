@@ -0,0 +1,73 @@
+//! 会话定时转发
+//!
+//! 订阅会话事件，将定时警告和到时通知转发给前端，到时后同步各设备的强度和状态。
+
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::broadcast;
+use tracing::debug;
+
+use dglab_core::session::SessionEvent;
+
+use crate::events::{
+    event_names, DevicePowerChangedEvent, DeviceStateChangedEvent, SessionTimerEvent,
+};
+use crate::state::AppState;
+
+/// 启动会话定时转发任务
+pub fn spawn_listener(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        let mut events = state.session_manager.read().await.subscribe_events();
+
+        loop {
+            match events.recv().await {
+                Ok(SessionEvent::TimerWarning(remaining)) => {
+                    let _ = app.emit(
+                        event_names::SESSION_TIMER,
+                        SessionTimerEvent {
+                            remaining_secs: remaining.as_secs(),
+                            expired: false,
+                        },
+                    );
+                }
+                Ok(SessionEvent::TimerExpired) => {
+                    let manager = state.session_manager.read().await;
+                    for device_id in manager.list_devices().await {
+                        let Some(device) = manager.get_device(&device_id).await else {
+                            continue;
+                        };
+                        let dev = device.read().await;
+                        let _ = app.emit(
+                            event_names::DEVICE_POWER_CHANGED,
+                            DevicePowerChangedEvent {
+                                device_id: device_id.clone(),
+                                power_a: dev.get_power(0),
+                                power_b: dev.get_power(1),
+                            },
+                        );
+                        let _ = app.emit(
+                            event_names::DEVICE_STATE_CHANGED,
+                            DeviceStateChangedEvent {
+                                device_id,
+                                state: dev.state(),
+                            },
+                        );
+                    }
+
+                    let _ = app.emit(
+                        event_names::SESSION_TIMER,
+                        SessionTimerEvent {
+                            remaining_secs: 0,
+                            expired: true,
+                        },
+                    );
+                }
+                Ok(_) => continue,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    debug!("Session timer listener lagged by {} events", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
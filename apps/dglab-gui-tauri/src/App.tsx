@@ -5,6 +5,7 @@ import { DeviceScanner } from "./pages/DeviceScanner";
 import { PowerControl } from "./pages/PowerControl";
 import { WaveformGenerator } from "./pages/WaveformGenerator";
 import { PresetManager } from "./pages/PresetManager";
+import { SessionResumeDialog } from "./components/SessionResumeDialog";
 import { useAppStore } from "./stores/appStore";
 import { useDeviceEvents } from "./hooks/useDeviceEvents";
 import { useEffect } from "react";
@@ -33,6 +34,7 @@ function App() {
           <Route path="/presets" element={<PresetManager />} />
           <Route path="*" element={<Navigate to="/" replace />} />
         </Routes>
+        <SessionResumeDialog />
         <Toaster 
           position="top-right" 
           theme={theme as "light" | "dark"}
@@ -0,0 +1,116 @@
+import { useEffect, useState } from "react";
+import {
+  AlertDialog,
+  AlertDialogAction,
+  AlertDialogCancel,
+  AlertDialogContent,
+  AlertDialogDescription,
+  AlertDialogFooter,
+  AlertDialogHeader,
+  AlertDialogTitle,
+} from "@/components/ui/alert-dialog";
+import { Badge } from "@/components/ui/badge";
+import { toast } from "@/lib/toast";
+import * as api from "@/lib/api";
+import { useDeviceStore } from "@/stores/deviceStore";
+import { DeviceState } from "@/types/device";
+import type { SessionSnapshotInfo } from "@/types/session";
+
+/** 恢复强度的渐变时长（秒） */
+const RESTORE_RAMP_SECS = 10;
+
+/** 格式化时长（秒） */
+function formatDuration(secs: number): string {
+  const minutes = Math.floor(secs / 60);
+  return minutes > 0 ? `${minutes} 分 ${secs % 60} 秒` : `${secs} 秒`;
+}
+
+/**
+ * 启动时提示恢复上次会话
+ *
+ * 确认后重新连接设备，强度从 0 渐变到上次的强度。
+ */
+export function SessionResumeDialog() {
+  const [snapshot, setSnapshot] = useState<SessionSnapshotInfo | null>(null);
+
+  useEffect(() => {
+    api
+      .loadSessionSnapshot()
+      .then(setSnapshot)
+      .catch((error) => console.error("Load session snapshot failed:", error));
+  }, []);
+
+  const handleResume = async () => {
+    if (!snapshot) return;
+    setSnapshot(null);
+
+    const loadingToast = toast.loading("正在恢复上次会话...");
+    try {
+      const results = await api.resumeSession(RESTORE_RAMP_SECS);
+      toast.dismiss(loadingToast);
+
+      const restored = results.find((r) => r.error === null);
+      if (restored) {
+        const [info, state] = await Promise.all([
+          api.getDeviceInfo(restored.device_id),
+          api.getDeviceState(restored.device_id),
+        ]);
+        useDeviceStore.setState({
+          currentDevice: info,
+          deviceState: state,
+          isConnected: state !== DeviceState.Disconnected,
+        });
+        toast.success("会话已恢复", `强度将在 ${RESTORE_RAMP_SECS} 秒内逐渐恢复`);
+      }
+      for (const result of results.filter((r) => r.error !== null)) {
+        toast.warning(`设备 ${result.device_id} 恢复失败`, result.error ?? undefined);
+      }
+    } catch (error) {
+      toast.dismiss(loadingToast);
+      toast.error("恢复会话失败", error instanceof Error ? error.message : String(error));
+      console.error("Resume session failed:", error);
+    }
+  };
+
+  const handleDiscard = async () => {
+    setSnapshot(null);
+    try {
+      await api.discardSessionSnapshot();
+    } catch (error) {
+      console.error("Discard session snapshot failed:", error);
+    }
+  };
+
+  return (
+    <AlertDialog open={snapshot !== null} onOpenChange={(open) => !open && setSnapshot(null)}>
+      <AlertDialogContent>
+        <AlertDialogHeader>
+          <AlertDialogTitle>恢复上次会话？</AlertDialogTitle>
+          <AlertDialogDescription>
+            上次会话已运行 {formatDuration(snapshot?.elapsed_secs ?? 0)}
+            {snapshot?.timer_remaining_secs != null &&
+              `，定时剩余 ${formatDuration(snapshot.timer_remaining_secs)}`}
+            。恢复后强度将从 0 在 {RESTORE_RAMP_SECS} 秒内逐渐回到上次的强度。
+          </AlertDialogDescription>
+        </AlertDialogHeader>
+        <div className="space-y-2">
+          {snapshot?.devices.map((device) => (
+            <div key={device.id} className="flex items-center justify-between text-sm">
+              <span>{device.name}</span>
+              <div className="flex items-center gap-2">
+                <span className="text-muted-foreground">
+                  A {device.power_a} / B {device.power_b}
+                </span>
+                {device.wifi && <Badge variant="outline">需重新扫码</Badge>}
+              </div>
+            </div>
+          ))}
+        </div>
+        <AlertDialogFooter>
+          <AlertDialogCancel onClick={handleDiscard}>放弃</AlertDialogCancel>
+          <AlertDialogAction onClick={handleResume}>恢复</AlertDialogAction>
+        </AlertDialogFooter>
+      </AlertDialogContent>
+    </AlertDialog>
+  );
+}
@@ -5,7 +5,7 @@ import { Button } from "@/components/ui/button";
 import { Input } from "@/components/ui/input";
 import { Label } from "@/components/ui/label";
 import { Separator } from "@/components/ui/separator";
-import { Wifi, Loader2, CheckCircle2, XCircle, Copy, Check } from "lucide-react";
+import { Wifi, Loader2, CheckCircle2, XCircle, Copy, Check, Users, Eye } from "lucide-react";
 import { toast } from "@/lib/toast";
 import * as api from "@/lib/api";
 import type { WifiConnectResponse } from "@/types/device";
@@ -21,6 +21,9 @@ export function WifiConnector({ onConnected, onCancel }: WifiConnectorProps) {
   const [connectionInfo, setConnectionInfo] = useState<WifiConnectResponse | null>(null);
   const [isBound, setIsBound] = useState(false);
   const [copied, setCopied] = useState(false);
+  const [invite, setInvite] = useState("");
+  const [invitePin, setInvitePin] = useState("");
+  const [isJoining, setIsJoining] = useState(false);
   const checkIntervalRef = useRef<number | null>(null);
 
   // 轮询检查绑定状态
@@ -69,6 +72,24 @@ export function WifiConnector({ onConnected, onCancel }: WifiConnectorProps) {
     }
   };
 
+  const handleJoin = async (viewer: boolean) => {
+    setIsJoining(true);
+    try {
+      const response = await api.remoteJoin({
+        invite: invite.trim(),
+        viewer,
+        pin: invitePin || undefined,
+      });
+      toast.success(viewer ? "已作为观察端加入" : "已加入远程会话");
+      onConnected?.(response.device_id);
+    } catch (error) {
+      console.error("Failed to join remote session:", error);
+      toast.error(`加入失败: ${error}`);
+    } finally {
+      setIsJoining(false);
+    }
+  };
+
   const handleCancel = async () => {
     if (connectionInfo) {
       try {
@@ -250,6 +271,54 @@ export function WifiConnector({ onConnected, onCancel }: WifiConnectorProps) {
             <li>等待连接建立</li>
           </ol>
         </div>
+
+        <Separator />
+
+        {/* 远程会话 */}
+        <div className="space-y-2">
+          <Label htmlFor="remote-invite">加入远程会话</Label>
+          <Input
+            id="remote-invite"
+            placeholder="ws://192.168.1.20:9999/…"
+            value={invite}
+            onChange={(e) => setInvite(e.target.value)}
+            disabled={isJoining}
+            className="font-mono text-xs"
+          />
+          <Input
+            placeholder="PIN（对方启用时必填）"
+            value={invitePin}
+            onChange={(e) => setInvitePin(e.target.value)}
+            disabled={isJoining}
+          />
+          <div className="flex gap-2">
+            <Button
+              variant="outline"
+              onClick={() => handleJoin(false)}
+              disabled={isJoining || !invite.trim()}
+              className="flex-1"
+            >
+              {isJoining ? (
+                <Loader2 className="mr-2 h-4 w-4 animate-spin" />
+              ) : (
+                <Users className="mr-2 h-4 w-4" />
+              )}
+              控制
+            </Button>
+            <Button
+              variant="outline"
+              onClick={() => handleJoin(true)}
+              disabled={isJoining || !invite.trim()}
+              className="flex-1"
+            >
+              <Eye className="mr-2 h-4 w-4" />
+              仅观察
+            </Button>
+          </div>
+          <p className="text-xs text-muted-foreground">
+            粘贴对方 dglab bridge --local 显示的链接，控制或查看对方连接的设备
+          </p>
+        </div>
       </CardContent>
     </Card>
   );
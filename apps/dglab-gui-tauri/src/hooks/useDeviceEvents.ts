@@ -11,6 +11,7 @@ import {
   DevicePowerChangedEvent,
   DeviceInfoUpdatedEvent,
   DeviceBatteryUpdatedEvent,
+  DeviceBatteryLimitedEvent,
   DeviceErrorEvent,
 } from "../types/events";
 import { toast } from "../lib/toast";
@@ -24,6 +25,8 @@ export function useDeviceEvents() {
     setDeviceState,
     updateDeviceInfo,
     setCurrentDevice,
+    setBatteryLimit,
+    setBatteryOverride,
   } = useDeviceStore();
 
   useEffect(() => {
@@ -66,9 +69,35 @@ export function useDeviceEvents() {
       listen<DeviceBatteryUpdatedEvent>(
         EVENT_NAMES.DEVICE_BATTERY_UPDATED,
         (event) => {
-          const { device_id, battery } = event.payload;
+          const { device_id, battery, remaining_secs } = event.payload;
           if (currentDevice && currentDevice.id === device_id) {
-            updateDeviceInfo({ battery_level: battery });
+            updateDeviceInfo({
+              battery_level: battery,
+              battery_remaining_secs: remaining_secs ?? undefined,
+            });
+          }
+        }
+      ),
+
+      // 设备低电量限制
+      listen<DeviceBatteryLimitedEvent>(
+        EVENT_NAMES.DEVICE_BATTERY_LIMITED,
+        (event) => {
+          const { device_id, action } = event.payload;
+          if (!currentDevice || currentDevice.id !== device_id) {
+            return;
+          }
+          setBatteryLimit(action);
+          const override = {
+            label: "忽略限制",
+            onClick: () => {
+              setBatteryOverride(true).catch(() => {});
+            },
+          };
+          if (action === "Pause") {
+            toast.warning("电量过低", "已暂停输出", override);
+          } else if (action !== "None") {
+            toast.warning("电量偏低", `强度上限已限制为 ${action.Cap}`, override);
           }
         }
       ),
@@ -88,5 +117,12 @@ export function useDeviceEvents() {
         promise.then((unlisten) => unlisten());
       });
     };
-  }, [currentDevice, setDeviceState, updateDeviceInfo, setCurrentDevice]);
+  }, [
+    currentDevice,
+    setDeviceState,
+    updateDeviceInfo,
+    setCurrentDevice,
+    setBatteryLimit,
+    setBatteryOverride,
+  ]);
 }
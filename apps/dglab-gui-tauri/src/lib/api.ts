@@ -4,22 +4,75 @@
 
 import { invoke } from "@tauri-apps/api/core";
 import type {
+  AppConfig,
+  BleAvailability,
+  BlePermissionStatus,
+  BluetoothAdapter,
+  ChannelLink,
   DeviceInfo,
+  DeviceLimits,
   DeviceState,
+  DeviceStats,
+  Easing,
+  FeedbackMapping,
+  GamepadMapping,
+  HotkeyConfig,
+  HotkeyStatus,
+  ImportedShare,
+  LogEntry,
+  LogLine,
+  Preset,
+  PresetQuery,
+  ResumeResult,
+  RuntimeStatus,
+  SavedAppState,
   ScannedDevice,
+  ScheduleEntry,
   SessionInfo,
+  SessionSnapshotInfo,
+  Waveform,
+  WaveformPoint,
+  RemoteJoinRequest,
+  RemoteJoinResponse,
   WifiConnectRequest,
   WifiConnectResponse,
 } from "../types";
 
 /** 扫描 BLE 设备 */
-export async function scanBleDevices(timeoutSecs?: number): Promise<ScannedDevice[]> {
-  return await invoke<ScannedDevice[]>("scan_ble_devices", { timeoutSecs });
+export async function scanBleDevices(
+  timeoutSecs?: number,
+  adapter?: string
+): Promise<ScannedDevice[]> {
+  return await invoke<ScannedDevice[]>("scan_ble_devices", { timeoutSecs, adapter });
 }
 
-/** 连接 BLE 设备（扫描后首次连接） */
-export async function connectBleDevice(deviceId: string, deviceName: string): Promise<DeviceInfo> {
-  return await invoke<DeviceInfo>("connect_ble_device", { deviceId, deviceName });
+/** 探测蓝牙可用性（没有蓝牙时只能使用 WiFi 连接） */
+export async function getBleAvailability(): Promise<BleAvailability> {
+  return await invoke<BleAvailability>("get_ble_availability");
+}
+
+/** 获取 BLE 权限状态（桌面端和 iOS 始终为 not-required） */
+export async function getBlePermissions(): Promise<BlePermissionStatus> {
+  return await invoke<BlePermissionStatus>("get_ble_permissions");
+}
+
+/** 请求 BLE 权限（Android 弹出系统授权对话框） */
+export async function requestBlePermissions(): Promise<BlePermissionStatus> {
+  return await invoke<BlePermissionStatus>("request_ble_permissions");
+}
+
+/** 列出蓝牙适配器 */
+export async function listAdapters(): Promise<BluetoothAdapter[]> {
+  return await invoke<BluetoothAdapter[]>("list_adapters");
+}
+
+/** 连接 BLE 设备（扫描后首次连接），adapter 应与扫描时使用的适配器一致 */
+export async function connectBleDevice(
+  deviceId: string,
+  deviceName: string,
+  adapter?: string
+): Promise<DeviceInfo> {
+  return await invoke<DeviceInfo>("connect_ble_device", { deviceId, deviceName, adapter });
 }
 
 /** 连接设备（重新连接已存在的设备） */
@@ -54,6 +107,69 @@ export async function setPower(
   return await invoke<void>("set_power", { deviceId, channel: channelNum, power });
 }
 
+/** 获取设备的强度校准点 [百分比, 原始强度]，未校准时返回 null */
+export async function getCalibration(deviceId: string): Promise<[number, number][] | null> {
+  return await invoke<[number, number][] | null>("get_calibration", { deviceId });
+}
+
+/** 校准过程中试用原始强度（不经过校准曲线） */
+export async function calibrationTry(deviceId: string, channel: number, raw: number): Promise<void> {
+  return await invoke<void>("calibration_try", { deviceId, channel, raw });
+}
+
+/** 保存设备的强度校准点，传 null 清除校准 */
+export async function saveCalibration(
+  deviceId: string,
+  points: [number, number][] | null
+): Promise<[number, number][] | null> {
+  return await invoke<[number, number][] | null>("save_calibration", { deviceId, points });
+}
+
+/** 在 durationMs 内把通道强度平滑渐变到 target（后台进行，通过功率变更事件同步） */
+export async function rampPower(
+  deviceId: string,
+  channel: number,
+  target: number,
+  durationMs: number,
+  easing?: Easing
+): Promise<void> {
+  return await invoke<void>("ramp_power", { deviceId, channel, target, durationMs, easing });
+}
+
+/** 取消通道上进行中的强度渐变，返回是否有渐变被取消 */
+export async function cancelRamp(deviceId: string, channel: number): Promise<boolean> {
+  return await invoke<boolean>("cancel_ramp", { deviceId, channel });
+}
+
+/** 获取设备输出限制 */
+export async function getDeviceLimits(deviceId: string): Promise<DeviceLimits> {
+  return await invoke<DeviceLimits>("get_device_limits", { deviceId });
+}
+
+/** 设置设备输出限制（软上限与平衡参数） */
+export async function setDeviceLimits(deviceId: string, limits: DeviceLimits): Promise<void> {
+  return await invoke<void>("set_device_limits", { deviceId, limits });
+}
+
+/** 获取通道联动配置，未开启时返回 null */
+export async function getChannelLink(deviceId: string): Promise<ChannelLink | null> {
+  return await invoke<ChannelLink | null>("get_channel_link", { deviceId });
+}
+
+/** 设置通道联动（传 null 关闭） */
+export async function setChannelLink(deviceId: string, link: ChannelLink | null): Promise<void> {
+  return await invoke<void>("set_channel_link", { deviceId, link });
+}
+
+/** 启用或禁用通道（禁用时强度归零、波形静默） */
+export async function setChannelEnabled(
+  deviceId: string,
+  channel: number,
+  enabled: boolean
+): Promise<void> {
+  return await invoke<void>("set_channel_enabled", { deviceId, channel, enabled });
+}
+
 /** 开始设备输出 */
 export async function startDevice(deviceId: string): Promise<void> {
   return await invoke<void>("start_device", { deviceId });
@@ -64,6 +180,14 @@ export async function stopDevice(deviceId: string): Promise<void> {
   return await invoke<void>("stop_device", { deviceId });
 }
 
+/** 忽略或恢复设备的低电量限制 */
+export async function setBatteryOverride(
+  deviceId: string,
+  overridden: boolean
+): Promise<void> {
+  return await invoke<void>("set_battery_override", { deviceId, overridden });
+}
+
 /** 紧急停止 */
 export async function emergencyStop(deviceId: string): Promise<void> {
   return await invoke<void>("emergency_stop", { deviceId });
@@ -79,6 +203,297 @@ export async function listDevices(): Promise<string[]> {
   return await invoke<string[]>("list_devices");
 }
 
+/** 设置会话最长运行时长（秒），返回停止时间（RFC 3339） */
+export async function setSessionTimer(durationSecs: number): Promise<string> {
+  return await invoke<string>("set_session_timer", { durationSecs });
+}
+
+/** 设置会话停止时间（RFC 3339 或本地 HH:MM），返回停止时间（RFC 3339） */
+export async function setSessionStopTime(stopAt: string): Promise<string> {
+  return await invoke<string>("set_session_timer", { stopAt });
+}
+
+/** 取消会话定时，返回是否有定时被取消 */
+export async function cancelSessionTimer(): Promise<boolean> {
+  return await invoke<boolean>("cancel_session_timer");
+}
+
+/** 获取会话停止时间（RFC 3339），未设置时为 null */
+export async function getSessionTimer(): Promise<string | null> {
+  return await invoke<string | null>("get_session_timer");
+}
+
+/** 查询当前会话的事件日志 */
+export async function getEventLog(deviceId?: string, limit?: number): Promise<LogEntry[]> {
+  return await invoke<LogEntry[]>("get_event_log", { deviceId, limit });
+}
+
+/** 获取会话统计，指定设备时只返回该设备 */
+export async function getSessionStats(deviceId?: string): Promise<DeviceStats[]> {
+  return await invoke<DeviceStats[]>("get_session_stats", { deviceId });
+}
+
+/** 获取上次退出时保存的会话快照，没有可恢复的会话时为 null */
+export async function loadSessionSnapshot(): Promise<SessionSnapshotInfo | null> {
+  return await invoke<SessionSnapshotInfo | null>("load_session_snapshot");
+}
+
+/** 恢复上次会话（需先取得用户确认），强度在 rampSecs 秒内从 0 渐变恢复 */
+export async function resumeSession(rampSecs?: number): Promise<ResumeResult[]> {
+  return await invoke<ResumeResult[]>("resume_session", { rampSecs });
+}
+
+/** 放弃恢复上次会话 */
+export async function discardSessionSnapshot(): Promise<void> {
+  return await invoke<void>("discard_session_snapshot");
+}
+
+// ========== Log API ==========
+
+/** 获取最近的应用日志（从旧到新），可按最低级别过滤 */
+export async function getLogs(minLevel?: string, limit?: number): Promise<LogLine[]> {
+  return await invoke<LogLine[]>("get_logs", { minLevel, limit });
+}
+
+/** 清空应用日志 */
+export async function clearLogs(): Promise<void> {
+  await invoke("clear_logs");
+}
+
+/** 获取当前日志过滤规则 */
+export async function getLogLevel(): Promise<string> {
+  return await invoke<string>("get_log_level");
+}
+
+/** 设置日志过滤规则（如 "debug" 或 "info,dglab=trace"），返回生效的规则 */
+export async function setLogLevel(level: string): Promise<string> {
+  return await invoke<string>("set_log_level", { level });
+}
+
+// ========== Preset API ==========
+
+/** 获取所有预设的有效配置（按名称排序） */
+export async function listPresets(): Promise<Preset[]> {
+  return await invoke<Preset[]>("list_presets");
+}
+
+/** 按条件搜索预设，返回有效配置（按名称排序） */
+export async function searchPresets(query: PresetQuery): Promise<Preset[]> {
+  return await invoke<Preset[]>("search_presets", { query });
+}
+
+/** 获取所有预设分类 */
+export async function listPresetCategories(): Promise<string[]> {
+  return await invoke<string[]>("list_preset_categories");
+}
+
+/** 获取预设 */
+export async function getPreset(id: string): Promise<Preset> {
+  return await invoke<Preset>("get_preset", { id });
+}
+
+/** 获取预设的有效配置（合并父预设后的结果，用于显示和预览） */
+export async function getEffectivePreset(id: string): Promise<Preset> {
+  return await invoke<Preset>("get_effective_preset", { id });
+}
+
+/**
+ * 保存预设，返回保存后的预设（id 为空时新建）
+ *
+ * 传入编辑后的有效配置，与父预设不同的字段由后端记入 `overrides`。
+ *
+ * 失败时抛出 `PresetError`，校验失败为 `{ kind: "invalid", issues }`。
+ */
+export async function savePreset(preset: Preset): Promise<Preset> {
+  return await invoke<Preset>("save_preset", { preset });
+}
+
+/** 删除预设及其文件 */
+export async function deletePreset(id: string): Promise<void> {
+  await invoke("delete_preset", { id });
+}
+
+/**
+ * 将预设应用到设备，返回应用后的设备信息
+ *
+ * 需要确认的预设（见 `requiresConfirmation`）须在用户确认后传入 `confirmed = true`，否则会被拒绝。
+ */
+export async function applyPreset(
+  deviceId: string,
+  presetId: string,
+  confirmed = false
+): Promise<DeviceInfo> {
+  return await invoke<DeviceInfo>("apply_preset", { deviceId, presetId, confirmed });
+}
+
+/** 生成预设的分享码（按名称引用的波形会内嵌） */
+export async function sharePreset(id: string): Promise<string> {
+  return await invoke<string>("share_preset", { id });
+}
+
+/** 生成波形库中波形的分享码 */
+export async function shareWaveform(name: string): Promise<string> {
+  return await invoke<string>("share_waveform", { name });
+}
+
+/**
+ * 导入分享码（预设保存到预设目录，波形保存到波形库）
+ *
+ * 预设名称已被使用时抛出 `{ kind: "invalid", issues }`，可传入 `name` 改名后重新导入。
+ */
+export async function importShareCode(code: string, name?: string): Promise<ImportedShare> {
+  return await invoke<ImportedShare>("import_share_code", { code, name });
+}
+
+// ========== Schedule API ==========
+
+/** 获取所有预设定时 */
+export async function listSchedules(): Promise<ScheduleEntry[]> {
+  return await invoke<ScheduleEntry[]>("list_schedules");
+}
+
+/** 添加或替换同名预设定时，返回保存后的定时列表 */
+export async function setSchedule(entry: ScheduleEntry): Promise<ScheduleEntry[]> {
+  return await invoke<ScheduleEntry[]>("set_schedule", { entry });
+}
+
+/** 删除预设定时，返回保存后的定时列表 */
+export async function removeSchedule(name: string): Promise<ScheduleEntry[]> {
+  return await invoke<ScheduleEntry[]>("remove_schedule", { name });
+}
+
+/** 启用或禁用预设定时，返回保存后的定时列表 */
+export async function setScheduleEnabled(name: string, enabled: boolean): Promise<ScheduleEntry[]> {
+  return await invoke<ScheduleEntry[]>("set_schedule_enabled", { name, enabled });
+}
+
+// ========== Feedback API ==========
+
+/** 获取 APP 反馈按钮映射 */
+export async function getFeedbackMapping(): Promise<FeedbackMapping> {
+  return await invoke<FeedbackMapping>("get_feedback_mapping");
+}
+
+/** 设置并保存 APP 反馈按钮映射 */
+export async function setFeedbackMapping(mapping: FeedbackMapping): Promise<void> {
+  return await invoke<void>("set_feedback_mapping", { mapping });
+}
+
+// ========== Gamepad API ==========
+
+/** 开启手柄控制 */
+export async function startGamepad(deviceId: string): Promise<void> {
+  return await invoke<void>("start_gamepad", { deviceId });
+}
+
+/** 关闭手柄控制 */
+export async function stopGamepad(): Promise<boolean> {
+  return await invoke<boolean>("stop_gamepad");
+}
+
+/** 获取手柄控制的设备 ID */
+export async function getGamepadDevice(): Promise<string | null> {
+  return await invoke<string | null>("get_gamepad_device");
+}
+
+/** 获取手柄映射 */
+export async function getGamepadMapping(): Promise<GamepadMapping> {
+  return await invoke<GamepadMapping>("get_gamepad_mapping");
+}
+
+/** 设置并保存手柄映射 */
+export async function setGamepadMapping(mapping: GamepadMapping): Promise<void> {
+  return await invoke<void>("set_gamepad_mapping", { mapping });
+}
+
+// ========== Runtime API ==========
+
+/** 启动会话运行时 */
+export async function startSessionRuntime(): Promise<RuntimeStatus> {
+  return await invoke<RuntimeStatus>("start_session_runtime");
+}
+
+/** 停止会话运行时 */
+export async function stopSessionRuntime(): Promise<RuntimeStatus> {
+  return await invoke<RuntimeStatus>("stop_session_runtime");
+}
+
+/** 暂停会话运行时 */
+export async function pauseSessionRuntime(): Promise<RuntimeStatus> {
+  return await invoke<RuntimeStatus>("pause_session_runtime");
+}
+
+/** 恢复会话运行时 */
+export async function resumeSessionRuntime(): Promise<RuntimeStatus> {
+  return await invoke<RuntimeStatus>("resume_session_runtime");
+}
+
+/** 获取会话运行时状态 */
+export async function getSessionRuntimeStatus(): Promise<RuntimeStatus> {
+  return await invoke<RuntimeStatus>("get_session_runtime_status");
+}
+
+/** 为设备通道设置运行时波形 */
+export async function setRuntimeWaveform(
+  deviceId: string,
+  channel: number,
+  waveform: Waveform
+): Promise<void> {
+  return await invoke<void>("set_runtime_waveform", { deviceId, channel, waveform });
+}
+
+/** 清除设备通道的运行时波形 */
+export async function clearRuntimeWaveform(deviceId: string, channel: number): Promise<void> {
+  return await invoke<void>("clear_runtime_waveform", { deviceId, channel });
+}
+
+// ========== Waveform API ==========
+
+/** 列出可选波形（内置预设和用户波形） */
+export async function listWaveformPresets(): Promise<Waveform[]> {
+  return await invoke<Waveform[]>("list_waveform_presets");
+}
+
+/** 为设备通道设置波形 */
+export async function setWaveform(
+  deviceId: string,
+  channel: number,
+  waveform: Waveform
+): Promise<void> {
+  return await invoke<void>("set_waveform", { deviceId, channel, waveform });
+}
+
+/** 采样波形用于预览（默认 5 秒、每 25ms 一个点） */
+export async function previewWaveform(
+  waveform: Waveform,
+  durationMs?: number,
+  stepMs?: number
+): Promise<WaveformPoint[]> {
+  return await invoke<WaveformPoint[]>("preview_waveform", { waveform, durationMs, stepMs });
+}
+
+// ========== Settings API ==========
+
+/** 获取应用设置 */
+export async function getSettings(): Promise<AppConfig> {
+  return await invoke<AppConfig>("get_settings");
+}
+
+/** 保存应用设置 */
+export async function setSettings(settings: AppConfig): Promise<AppConfig> {
+  return await invoke<AppConfig>("set_settings", { settings });
+}
+
+/** 获取全局快捷键配置和注册状态 */
+export async function getHotkeys(): Promise<HotkeyStatus> {
+  return await invoke<HotkeyStatus>("get_hotkeys");
+}
+
+/** 保存全局快捷键配置并重新注册 */
+export async function setHotkeys(config: HotkeyConfig): Promise<HotkeyStatus> {
+  return await invoke<HotkeyStatus>("set_hotkeys", { config });
+}
+
 // ========== WiFi API ==========
 
 /** 连接 WiFi 设备 */
@@ -91,7 +506,29 @@ export async function wifiCheckBinding(deviceId: string): Promise<boolean> {
   return await invoke<boolean>("wifi_check_binding", { deviceId });
 }
 
+/** 获取 WiFi 设备的二维码 URL（尚未分配时为 null） */
+export async function wifiQrUrl(deviceId: string): Promise<string | null> {
+  return await invoke<string | null>("wifi_qr_url", { deviceId });
+}
+
 /** 取消 WiFi 连接 */
 export async function wifiCancel(deviceId: string): Promise<void> {
   return await invoke<void>("wifi_cancel", { deviceId });
 }
+
+/** 凭邀请链接加入对方的远程会话 */
+export async function remoteJoin(request: RemoteJoinRequest): Promise<RemoteJoinResponse> {
+  return await invoke<RemoteJoinResponse>("remote_join", { request });
+}
+
+// ========== App State API ==========
+
+/** 获取上次保存的窗口/会话状态 */
+export async function loadAppState(): Promise<SavedAppState> {
+  return await invoke<SavedAppState>("load_app_state");
+}
+
+/** 保存当前状态（可同时记录选中的预设），退出时也会自动保存 */
+export async function saveAppState(lastPreset?: string): Promise<SavedAppState> {
+  return await invoke<SavedAppState>("save_app_state", { lastPreset });
+}
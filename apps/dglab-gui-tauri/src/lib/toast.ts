@@ -19,10 +19,15 @@ export const toast = {
     });
   },
 
-  warning: (message: string, description?: string) => {
+  warning: (
+    message: string,
+    description?: string,
+    action?: { label: string; onClick: () => void }
+  ) => {
     sonnerToast.warning(message, {
       description,
-      duration: 4000,
+      duration: action ? 8000 : 4000,
+      action,
     });
   },
 
@@ -1,4 +1,4 @@
-import { useState } from "react";
+import { useEffect, useState } from "react";
 import { useNavigate } from "react-router-dom";
 import { useDeviceStore } from "@/stores/deviceStore";
 import { Card, CardContent, CardDescription, CardHeader, CardTitle } from "@/components/ui/card";
@@ -8,13 +8,49 @@ import { Separator } from "@/components/ui/separator";
 import { Tabs, TabsList, TabsTrigger, TabsContent } from "@/components/ui/tabs";
 import { Bluetooth, Wifi, Loader2, SignalHigh, SignalMedium, SignalLow, ArrowLeft } from "lucide-react";
 import { WifiConnector } from "@/components/WifiConnector";
-import type { ScannedDevice } from "@/types/device";
+import * as api from "@/lib/api";
+import type { BleAvailability, ScannedDevice } from "@/types/device";
 
 export function DeviceScanner() {
   const navigate = useNavigate();
   const { isScanning, scannedDevices, scanDevices, connectToDevice } = useDeviceStore();
   const [connectingId, setConnectingId] = useState<string | null>(null);
   const [activeTab, setActiveTab] = useState<"ble" | "wifi">("ble");
+  const [bleAvailability, setBleAvailability] = useState<BleAvailability | null>(null);
+  const [permissionDenied, setPermissionDenied] = useState(false);
+
+  // 没有蓝牙时默认切换到 WiFi
+  useEffect(() => {
+    api
+      .getBleAvailability()
+      .then((availability) => {
+        setBleAvailability(availability);
+        if (availability.status !== "available") {
+          setActiveTab("wifi");
+        }
+      })
+      .catch((error) => console.error("Failed to probe Bluetooth:", error));
+  }, []);
+
+  const bleUnavailable = bleAvailability !== null && bleAvailability.status !== "available";
+
+  // 移动端扫描前先申请蓝牙权限
+  const handleScan = async () => {
+    try {
+      let permissions = await api.getBlePermissions();
+      if (!permissions.ready) {
+        permissions = await api.requestBlePermissions();
+      }
+      if (!permissions.ready) {
+        setPermissionDenied(true);
+        return;
+      }
+      setPermissionDenied(false);
+    } catch (error) {
+      console.error("Failed to request Bluetooth permissions:", error);
+    }
+    await scanDevices();
+  };
 
   const handleConnect = async (device: ScannedDevice) => {
     setConnectingId(device.id);
@@ -33,7 +69,7 @@ export function DeviceScanner() {
     navigate("/");
   };
 
-  const getSignalIcon = (rssi?: number) => {
+  const getSignalIcon = (rssi: number | null) => {
     if (!rssi) return <SignalLow className="h-4 w-4" />;
     if (rssi > -60) return <SignalHigh className="h-4 w-4 text-green-500" />;
     if (rssi > -80) return <SignalMedium className="h-4 w-4 text-yellow-500" />;
@@ -83,10 +119,22 @@ export function DeviceScanner() {
             <Card>
               <CardHeader>
                 <CardTitle>蓝牙扫描</CardTitle>
-                <CardDescription>点击按钮开始扫描附近的 BLE 设备</CardDescription>
+                <CardDescription>
+                  {bleAvailability?.status === "no_adapter"
+                    ? "未找到蓝牙适配器，请使用 WiFi 连接"
+                    : bleAvailability?.status === "unavailable"
+                      ? `蓝牙不可用（${bleAvailability.reason}），请使用 WiFi 连接`
+                      : permissionDenied
+                        ? "未获得蓝牙权限，请在系统设置中允许附近设备（及定位）权限"
+                        : "点击按钮开始扫描附近的 BLE 设备"}
+                </CardDescription>
               </CardHeader>
               <CardContent>
-                <Button onClick={scanDevices} disabled={isScanning} className="w-full">
+                <Button
+                  onClick={handleScan}
+                  disabled={isScanning || bleUnavailable}
+                  className="w-full"
+                >
                   {isScanning ? (
                     <>
                       <Loader2 className="mr-2 h-4 w-4 animate-spin" />
@@ -139,7 +187,7 @@ export function DeviceScanner() {
                             <p className="text-sm text-muted-foreground font-mono">
                               {device.id}
                             </p>
-                            {device.rssi !== undefined && (
+                            {device.rssi !== null && (
                               <p className="text-xs text-muted-foreground mt-1">
                                 信号强度: {device.rssi} dBm
                               </p>
@@ -6,7 +6,15 @@ import { Button } from "@/components/ui/button";
 import { Slider } from "@/components/ui/slider";
 import { Badge } from "@/components/ui/badge";
 import { Separator } from "@/components/ui/separator";
-import { ArrowLeft, Play, Square, AlertCircle, Zap, Wand2 } from "lucide-react";
+import {
+  ArrowLeft,
+  Play,
+  Square,
+  AlertCircle,
+  BatteryLow,
+  Zap,
+  Wand2,
+} from "lucide-react";
 
 export function PowerControl() {
   const navigate = useNavigate();
@@ -16,10 +24,15 @@ export function PowerControl() {
     powerA,
     powerB,
     setPower,
+    setChannelEnabled,
     startDevice,
     stopDevice,
     emergencyStop,
+    batteryLimit,
+    batteryOverride,
+    setBatteryOverride,
   } = useDeviceStore();
+  const batteryOverridden = batteryOverride !== null;
 
   const [localPowerA, setLocalPowerA] = useState(powerA);
   const [localPowerB, setLocalPowerB] = useState(powerB);
@@ -108,16 +121,59 @@ export function PowerControl() {
 
       <Separator />
 
+      {/* Low Battery Limit */}
+      {batteryLimit !== "None" && (
+        <Card className="border-orange-500/50 bg-orange-50 dark:bg-orange-950/20">
+          <CardContent className="pt-6">
+            <div className="flex items-center gap-3">
+              <BatteryLow className="h-5 w-5 text-orange-600 dark:text-orange-500 flex-shrink-0" />
+              <div className="flex-1 space-y-1">
+                <p className="text-sm font-medium text-orange-800 dark:text-orange-200">
+                  {batteryLimit === "Pause"
+                    ? "电量过低，已暂停输出"
+                    : `电量偏低，强度上限已限制为 ${batteryLimit.Cap}`}
+                </p>
+                {batteryOverridden && (
+                  <p className="text-sm text-orange-700 dark:text-orange-300">
+                    已忽略限制，电量继续下降到更严格的级别时会重新限制
+                  </p>
+                )}
+              </div>
+              <Button
+                variant="outline"
+                size="sm"
+                onClick={() => setBatteryOverride(!batteryOverridden).catch(() => {})}
+              >
+                {batteryOverridden ? "恢复限制" : "忽略限制"}
+              </Button>
+            </div>
+          </CardContent>
+        </Card>
+      )}
+
       {/* Power Control Cards */}
       <div className="grid gap-6 md:grid-cols-2">
         {/* Channel A */}
         <Card>
           <CardHeader>
-            <CardTitle className="flex items-center gap-2">
-              <Zap className="h-5 w-5 text-blue-500" />
-              通道 A
-            </CardTitle>
-            <CardDescription>调节通道 A 的输出功率</CardDescription>
+            <div className="flex items-center justify-between">
+              <CardTitle className="flex items-center gap-2">
+                <Zap className="h-5 w-5 text-blue-500" />
+                通道 A
+              </CardTitle>
+              <Button
+                variant={currentDevice.enabled_a ? "outline" : "secondary"}
+                size="sm"
+                onClick={() =>
+                  setChannelEnabled("A", !currentDevice.enabled_a).catch(() => {})
+                }
+              >
+                {currentDevice.enabled_a ? "禁用" : "启用"}
+              </Button>
+            </div>
+            <CardDescription>
+              {currentDevice.enabled_a ? "调节通道 A 的输出功率" : "通道 A 已禁用"}
+            </CardDescription>
           </CardHeader>
           <CardContent className="space-y-6">
             <div className="space-y-4">
@@ -132,7 +188,7 @@ export function PowerControl() {
                 onValueChange={handlePowerChangeA}
                 max={200}
                 step={1}
-                disabled={!isConnected}
+                disabled={!isConnected || !currentDevice.enabled_a}
                 className="w-full"
               />
               <div className="flex justify-between text-xs text-muted-foreground">
@@ -147,11 +203,24 @@ export function PowerControl() {
         {/* Channel B */}
         <Card>
           <CardHeader>
-            <CardTitle className="flex items-center gap-2">
-              <Zap className="h-5 w-5 text-purple-500" />
-              通道 B
-            </CardTitle>
-            <CardDescription>调节通道 B 的输出功率</CardDescription>
+            <div className="flex items-center justify-between">
+              <CardTitle className="flex items-center gap-2">
+                <Zap className="h-5 w-5 text-purple-500" />
+                通道 B
+              </CardTitle>
+              <Button
+                variant={currentDevice.enabled_b ? "outline" : "secondary"}
+                size="sm"
+                onClick={() =>
+                  setChannelEnabled("B", !currentDevice.enabled_b).catch(() => {})
+                }
+              >
+                {currentDevice.enabled_b ? "禁用" : "启用"}
+              </Button>
+            </div>
+            <CardDescription>
+              {currentDevice.enabled_b ? "调节通道 B 的输出功率" : "通道 B 已禁用"}
+            </CardDescription>
           </CardHeader>
           <CardContent className="space-y-6">
             <div className="space-y-4">
@@ -166,7 +235,7 @@ export function PowerControl() {
                 onValueChange={handlePowerChangeB}
                 max={200}
                 step={1}
-                disabled={!isConnected}
+                disabled={!isConnected || !currentDevice.enabled_b}
                 className="w-full"
               />
               <div className="flex justify-between text-xs text-muted-foreground">
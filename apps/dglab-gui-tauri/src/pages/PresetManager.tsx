@@ -1,7 +1,14 @@
-import { useState } from "react";
+import { useEffect, useState } from "react";
 import { useNavigate } from "react-router-dom";
 import { usePresetStore } from "@/stores/presetStore";
-import type { Preset } from "@/types/preset";
+import {
+  INHERITABLE_FIELDS,
+  overridesField,
+  presetErrorMessage,
+  type Preset,
+} from "@/types/preset";
+import * as api from "@/lib/api";
+import { toast } from "@/lib/toast";
 import { Card, CardContent, CardDescription, CardHeader, CardTitle } from "@/components/ui/card";
 import { Button } from "@/components/ui/button";
 import { Input } from "@/components/ui/input";
@@ -31,7 +38,8 @@ import { ArrowLeft, Plus, Play, Trash2, Save } from "lucide-react";
 
 export function PresetManager() {
   const navigate = useNavigate();
-  const { presets, addPreset, deletePreset, setCurrentPreset } = usePresetStore();
+  const { presets, setPresets, addPreset, deletePreset, setCurrentPreset, findPresetById } =
+    usePresetStore();
   
   const [isCreateDialogOpen, setIsCreateDialogOpen] = useState(false);
   const [deletePresetId, setDeletePresetId] = useState<string | null>(null);
@@ -39,12 +47,20 @@ export function PresetManager() {
   const [newPresetName, setNewPresetName] = useState("");
   const [newPresetDescription, setNewPresetDescription] = useState("");
 
-  const handleCreatePreset = () => {
+  // 列表显示合并父预设后的有效配置
+  useEffect(() => {
+    api
+      .listPresets()
+      .then(setPresets)
+      .catch((error) => console.error("Failed to load presets:", error));
+  }, [setPresets]);
+
+  const handleCreatePreset = async () => {
     if (!newPresetName.trim()) return;
 
     const now = new Date().toISOString();
     const newPreset: Preset = {
-      id: Date.now().toString(),
+      id: "",
       name: newPresetName,
       description: newPresetDescription,
       created_at: now,
@@ -62,22 +78,41 @@ export function PresetManager() {
       settings: {},
     };
 
-    addPreset(newPreset);
-    setIsCreateDialogOpen(false);
-    setNewPresetName("");
-    setNewPresetDescription("");
+    try {
+      addPreset(await api.savePreset(newPreset));
+      setIsCreateDialogOpen(false);
+      setNewPresetName("");
+      setNewPresetDescription("");
+    } catch (error) {
+      toast.error("创建预设失败", presetErrorMessage(error));
+    }
   };
 
-  const handleLoadPreset = (preset: Preset) => {
-    setCurrentPreset(preset);
-    navigate("/control");
+  const handleLoadPreset = async (preset: Preset) => {
+    try {
+      setCurrentPreset(await api.getEffectivePreset(preset.id));
+      navigate("/control");
+    } catch (error) {
+      toast.error("加载预设失败", presetErrorMessage(error));
+    }
   };
 
-  const handleDeletePreset = (id: string) => {
-    deletePreset(id);
+  const handleDeletePreset = async (id: string) => {
     setDeletePresetId(null);
+    try {
+      await api.deletePreset(id);
+      deletePreset(id);
+    } catch (error) {
+      toast.error("删除预设失败", presetErrorMessage(error));
+    }
   };
 
+  /** 继承的字段用次要样式显示 */
+  const channelVariant = (preset: Preset, channel: "channel_a" | "channel_b") =>
+    overridesField(preset, `${channel}.min_power`) || overridesField(preset, `${channel}.max_power`)
+      ? "outline"
+      : "secondary";
+
   return (
     <div className="container mx-auto p-6 space-y-6">
       {/* Header */}
@@ -169,6 +204,16 @@ export function PresetManager() {
                         {preset.description || "无描述"}
                       </CardDescription>
                     </div>
+                    {preset.parent && (
+                      <Badge
+                        variant="secondary"
+                        title={`覆盖 ${
+                          INHERITABLE_FIELDS.filter((field) => overridesField(preset, field)).length
+                        }/${INHERITABLE_FIELDS.length} 项`}
+                      >
+                        继承 {findPresetById(preset.parent)?.name ?? "未知预设"}
+                      </Badge>
+                    )}
                   </div>
                 </CardHeader>
                 <CardContent>
@@ -177,11 +222,11 @@ export function PresetManager() {
                     <div className="grid grid-cols-2 gap-2 text-sm">
                       <div className="space-y-1">
                         <p className="font-medium">通道 A</p>
-                        <Badge variant="outline">{preset.channel_a.min_power}-{preset.channel_a.max_power}</Badge>
+                        <Badge variant={channelVariant(preset, "channel_a")}>{preset.channel_a.min_power}-{preset.channel_a.max_power}</Badge>
                       </div>
                       <div className="space-y-1">
                         <p className="font-medium">通道 B</p>
-                        <Badge variant="outline">{preset.channel_b.min_power}-{preset.channel_b.max_power}</Badge>
+                        <Badge variant={channelVariant(preset, "channel_b")}>{preset.channel_b.min_power}-{preset.channel_b.max_power}</Badge>
                       </div>
                     </div>
 
@@ -8,6 +8,7 @@ import {
   DeviceState,
   ScannedDevice,
 } from "../types/device";
+import type { BatteryAction } from "../types/session";
 import * as api from "../lib/api";
 import { toast } from "../lib/toast";
 
@@ -27,6 +28,12 @@ interface DeviceStore {
   powerB: number;
   /** 是否已连接 */
   isConnected: boolean;
+  /** 扫描使用的蓝牙适配器（序号或名称，null 表示第一个） */
+  adapter: string | null;
+  /** 按电量得到的低电量限制 */
+  batteryLimit: BatteryAction;
+  /** 用户忽略的低电量限制（限制变得更严格后失效） */
+  batteryOverride: BatteryAction | null;
 
   // Actions
   /** 设置当前设备 */
@@ -39,10 +46,14 @@ interface DeviceStore {
   clearScannedDevices: () => void;
   /** 设置扫描状态 */
   setScanning: (scanning: boolean) => void;
+  /** 选择蓝牙适配器 */
+  setAdapter: (adapter: string | null) => void;
   /** 设置设备状态 */
   setDeviceState: (state: DeviceState) => void;
   /** 更新设备信息 */
   updateDeviceInfo: (info: Partial<DeviceInfo>) => void;
+  /** 更新低电量限制（比忽略的限制更严格时取消忽略，与后端一致） */
+  setBatteryLimit: (action: BatteryAction) => void;
   /** 重置状态 */
   reset: () => void;
 
@@ -55,12 +66,16 @@ interface DeviceStore {
   disconnectDevice: () => Promise<void>;
   /** 设置通道功率 */
   setPower: (channel: "A" | "B", power: number) => Promise<void>;
+  /** 启用或禁用通道 */
+  setChannelEnabled: (channel: "A" | "B", enabled: boolean) => Promise<void>;
   /** 启动设备 */
   startDevice: () => Promise<void>;
   /** 停止设备 */
   stopDevice: () => Promise<void>;
   /** 紧急停止 */
   emergencyStop: () => Promise<void>;
+  /** 忽略或恢复低电量限制 */
+  setBatteryOverride: (overridden: boolean) => Promise<void>;
 }
 
 const initialState = {
@@ -71,8 +86,18 @@ const initialState = {
   powerA: 0,
   powerB: 0,
   isConnected: false,
+  adapter: null,
+  batteryLimit: "None" as BatteryAction,
+  batteryOverride: null,
 };
 
+/** 低电量限制的强度上限，不限制时为 Infinity */
+function batteryCap(action: BatteryAction): number {
+  if (action === "None") return Infinity;
+  if (action === "Pause") return 0;
+  return action.Cap;
+}
+
 export const useDeviceStore = create<DeviceStore>((set, get) => ({
   ...initialState,
 
@@ -97,6 +122,8 @@ export const useDeviceStore = create<DeviceStore>((set, get) => ({
 
   setScanning: (scanning) => set({ isScanning: scanning }),
 
+  setAdapter: (adapter) => set({ adapter }),
+
   setDeviceState: (state) => set({ deviceState: state }),
 
   updateDeviceInfo: (info) =>
@@ -106,6 +133,16 @@ export const useDeviceStore = create<DeviceStore>((set, get) => ({
         : null,
     })),
 
+  setBatteryLimit: (action) =>
+    set((state) => ({
+      batteryLimit: action,
+      batteryOverride:
+        state.batteryOverride !== null &&
+        batteryCap(action) < batteryCap(state.batteryOverride)
+          ? null
+          : state.batteryOverride,
+    })),
+
   reset: () => set(initialState),
 
   // API Actions
@@ -113,7 +150,7 @@ export const useDeviceStore = create<DeviceStore>((set, get) => ({
     set({ isScanning: true, scannedDevices: [] });
     const loadingToast = toast.loading("正在扫描设备...");
     try {
-      const devices = await api.scanBleDevices();
+      const devices = await api.scanBleDevices(undefined, get().adapter ?? undefined);
       set({ scannedDevices: devices });
       toast.dismiss(loadingToast);
       if (devices.length === 0) {
@@ -132,7 +169,7 @@ export const useDeviceStore = create<DeviceStore>((set, get) => ({
   },
 
   connectToDevice: async (deviceId: string, deviceName: string) => {
-    const { isConnected } = get();
+    const { isConnected, scannedDevices } = get();
     if (isConnected) {
       toast.info("设备已连接", "请先断开当前设备");
       return;
@@ -141,7 +178,9 @@ export const useDeviceStore = create<DeviceStore>((set, get) => ({
     set({ deviceState: DeviceState.Connecting });
     const loadingToast = toast.loading("正在连接设备...");
     try {
-      await api.connectBleDevice(deviceId, deviceName);
+      // 使用扫描到该设备的适配器连接
+      const adapter = scannedDevices.find((d) => d.id === deviceId)?.adapter;
+      await api.connectBleDevice(deviceId, deviceName, adapter);
       const deviceInfo = await api.getDeviceInfo(deviceId);
       set({
         currentDevice: deviceInfo,
@@ -209,6 +248,34 @@ export const useDeviceStore = create<DeviceStore>((set, get) => ({
     }
   },
 
+  setChannelEnabled: async (channel: "A" | "B", enabled: boolean) => {
+    const { currentDevice } = get();
+    if (!currentDevice) {
+      toast.error("设置失败", "未连接设备");
+      throw new Error("No device connected");
+    }
+
+    try {
+      await api.setChannelEnabled(currentDevice.id, channel === "A" ? 0 : 1, enabled);
+      // 禁用时后端会把强度归零
+      set((state) => ({
+        ...(enabled ? {} : { [channel === "A" ? "powerA" : "powerB"]: 0 }),
+        currentDevice: state.currentDevice
+          ? {
+              ...state.currentDevice,
+              [channel === "A" ? "enabled_a" : "enabled_b"]: enabled,
+              ...(enabled ? {} : { [channel === "A" ? "power_a" : "power_b"]: 0 }),
+            }
+          : null,
+      }));
+      toast.success(`通道 ${channel} 已${enabled ? "启用" : "禁用"}`);
+    } catch (error) {
+      toast.error("设置通道失败", error instanceof Error ? error.message : "未知错误");
+      console.error("Set channel enabled failed:", error);
+      throw error;
+    }
+  },
+
   startDevice: async () => {
     const { currentDevice } = get();
     if (!currentDevice) {
@@ -260,4 +327,23 @@ export const useDeviceStore = create<DeviceStore>((set, get) => ({
       throw error;
     }
   },
+
+  setBatteryOverride: async (overridden: boolean) => {
+    const { currentDevice } = get();
+    if (!currentDevice) return;
+
+    try {
+      await api.setBatteryOverride(currentDevice.id, overridden);
+      set({ batteryOverride: overridden ? get().batteryLimit : null });
+      if (overridden) {
+        toast.warning("已忽略低电量限制", "电量继续下降到更严格的级别时会重新限制");
+      } else {
+        toast.success("已恢复低电量限制");
+      }
+    } catch (error) {
+      toast.error("设置失败", error instanceof Error ? error.message : "未知错误");
+      console.error("Set battery override failed:", error);
+      throw error;
+    }
+  },
 }));
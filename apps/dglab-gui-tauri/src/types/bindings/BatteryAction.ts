@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 低电量限制
+ */
+export type BatteryAction = "None" | { "Cap": number } | "Pause";
@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 蓝牙可用性
+ *
+ * 没有蓝牙的机器上 BLE 功能不可用，WiFi 功能不受影响，界面据此禁用蓝牙相关操作。
+ */
+export type BleAvailability = { "status": "available", 
+/**
+ * 适配器数量
+ */
+adapters: number, } | { "status": "no_adapter" } | { "status": "unavailable", 
+/**
+ * 原因
+ */
+reason: string, };
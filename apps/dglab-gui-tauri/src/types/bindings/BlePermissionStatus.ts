@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PermissionState } from "./PermissionState";
+
+/**
+ * BLE 权限状态
+ */
+export type BlePermissionStatus = { 
+/**
+ * 平台（`android`、`ios`、`linux` 等）
+ */
+platform: string, 
+/**
+ * 蓝牙扫描和连接权限
+ */
+bluetooth: PermissionState, 
+/**
+ * 定位权限（Android 11 及以下扫描 BLE 需要）
+ */
+location: PermissionState, 
+/**
+ * 是否可以扫描和连接
+ */
+ready: boolean, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 蓝牙适配器
+ */
+export type BluetoothAdapter = { 
+/**
+ * 序号
+ */
+index: number, 
+/**
+ * 名称
+ */
+name: string, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BatteryAction } from "./BatteryAction";
+
+/**
+ * 设备低电量限制变化事件
+ */
+export type DeviceBatteryLimitedEvent = { 
+/**
+ * 设备 ID
+ */
+device_id: string, 
+/**
+ * 按电量和策略得到的限制
+ */
+action: BatteryAction, };
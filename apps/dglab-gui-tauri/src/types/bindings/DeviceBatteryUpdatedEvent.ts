@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 设备电池电量更新事件
+ */
+export type DeviceBatteryUpdatedEvent = { 
+/**
+ * 设备 ID
+ */
+device_id: string, 
+/**
+ * 电池电量 (0-100)
+ */
+battery: number, 
+/**
+ * 按放电速率估算的剩余使用时间（秒），放电数据不足时为空
+ */
+remaining_secs: number | null, };
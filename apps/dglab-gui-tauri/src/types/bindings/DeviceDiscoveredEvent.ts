@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 适配器发现设备事件
+ */
+export type DeviceDiscoveredEvent = { 
+/**
+ * 设备 ID
+ */
+device_id: string, 
+/**
+ * 设备名称
+ */
+name: string, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 设备错误事件
+ */
+export type DeviceErrorEvent = { 
+/**
+ * 设备 ID
+ */
+device_id: string, 
+/**
+ * 错误信息
+ */
+error: string, };
@@ -0,0 +1,58 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BatteryAction } from "./BatteryAction";
+import type { DeviceInfo } from "./DeviceInfo";
+import type { DeviceState } from "./DeviceState";
+import type { FeedbackButton } from "./FeedbackButton";
+
+/**
+ * 设备事件
+ */
+export type DeviceEvent = { "StateChanged": DeviceState } | { "PowerChanged": { 
+/**
+ * 通道编号 (0=A, 1=B)
+ */
+channel: number, 
+/**
+ * 强度值 (0-100)
+ */
+power: number, } } | { "PowerRejected": { 
+/**
+ * 通道编号 (0=A, 1=B)
+ */
+channel: number, 
+/**
+ * 请求强度
+ */
+requested: number, 
+/**
+ * 设备实际强度
+ */
+actual: number, } } | { "MaxPowerChanged": { 
+/**
+ * A 通道强度上限
+ */
+max_power_a: number, 
+/**
+ * B 通道强度上限
+ */
+max_power_b: number, } } | { "StatusReport": { 
+/**
+ * A 通道强度
+ */
+power_a: number, 
+/**
+ * B 通道强度
+ */
+power_b: number, } } | { "WaveformChanged": { 
+/**
+ * 通道编号 (0=A, 1=B)
+ */
+channel: number, } } | { "InfoUpdated": DeviceInfo } | { "BatteryUpdated": number } | { "LinkQuality": number } | { "WeakSignal": { 
+/**
+ * 信号强度（dBm）
+ */
+rssi: number, 
+/**
+ * 是否低于弱信号阈值（false 表示已恢复）
+ */
+weak: boolean, } } | "Started" | "Stopped" | "Heartbeat" | { "Feedback": FeedbackButton } | { "BatteryLimited": BatteryAction } | { "Error": string };
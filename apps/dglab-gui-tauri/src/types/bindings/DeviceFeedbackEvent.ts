@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FeedbackAction } from "./FeedbackAction";
+import type { FeedbackButton } from "./FeedbackButton";
+
+/**
+ * APP 反馈按钮事件
+ */
+export type DeviceFeedbackEvent = { 
+/**
+ * 设备 ID
+ */
+device_id: string, 
+/**
+ * 按钮
+ */
+button: FeedbackButton, 
+/**
+ * 执行的动作（未映射时为空）
+ */
+action: FeedbackAction | null, };
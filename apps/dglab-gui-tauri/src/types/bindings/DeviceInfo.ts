@@ -0,0 +1,62 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 设备信息
+ */
+export type DeviceInfo = { 
+/**
+ * 设备 ID
+ */
+id: string, 
+/**
+ * 设备名称
+ */
+name: string, 
+/**
+ * 设备类型
+ */
+device_type: string, 
+/**
+ * 固件版本
+ */
+firmware_version: string, 
+/**
+ * 硬件版本
+ */
+hardware_version: string, 
+/**
+ * 电池电量 (0-100)
+ */
+battery_level: number, 
+/**
+ * 按会话电量历史估算的剩余使用时间（秒），放电数据不足时为空
+ *
+ * 设备自身不跟踪电量历史，由 [`SessionManager::device_info`] 填充。
+ *
+ * [`SessionManager::device_info`]: crate::session::SessionManager::device_info
+ */
+battery_remaining_secs?: number | null, 
+/**
+ * 通道 A 当前强度
+ */
+power_a: number, 
+/**
+ * 通道 B 当前强度
+ */
+power_b: number, 
+/**
+ * 通道 A 最大强度
+ */
+max_power_a: number, 
+/**
+ * 通道 B 最大强度
+ */
+max_power_b: number, 
+/**
+ * 通道 A 是否启用
+ */
+enabled_a: boolean, 
+/**
+ * 通道 B 是否启用
+ */
+enabled_b: boolean, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DeviceInfo } from "./DeviceInfo";
+
+/**
+ * 设备信息更新事件
+ */
+export type DeviceInfoUpdatedEvent = { 
+/**
+ * 设备 ID
+ */
+device_id: string, 
+/**
+ * 设备信息
+ */
+info: DeviceInfo, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 设备 BLE 链路质量事件
+ */
+export type DeviceLinkQualityEvent = { 
+/**
+ * 设备 ID
+ */
+device_id: string, 
+/**
+ * 信号强度（dBm）
+ */
+rssi: number, 
+/**
+ * 是否低于弱信号阈值
+ */
+weak: boolean, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 设备断开事件（链路断开，设备仍在会话中等待重连）
+ */
+export type DeviceLostEvent = { 
+/**
+ * 设备 ID
+ */
+device_id: string, };
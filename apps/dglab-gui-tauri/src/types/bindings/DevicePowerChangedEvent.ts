@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 设备功率变更事件
+ */
+export type DevicePowerChangedEvent = { 
+/**
+ * 设备 ID
+ */
+device_id: string, 
+/**
+ * 通道 A 功率
+ */
+power_a: number, 
+/**
+ * 通道 B 功率
+ */
+power_b: number, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 设备状态
+ */
+export enum DeviceState { "Disconnected" = "Disconnected", "Connecting" = "Connecting", "Connected" = "Connected", "Running" = "Running", "Error" = "Error" }
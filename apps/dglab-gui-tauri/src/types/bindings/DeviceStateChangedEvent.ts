@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DeviceState } from "./DeviceState";
+
+/**
+ * 设备状态变更事件
+ */
+export type DeviceStateChangedEvent = { 
+/**
+ * 设备 ID
+ */
+device_id: string, 
+/**
+ * 新状态
+ */
+state: DeviceState, };
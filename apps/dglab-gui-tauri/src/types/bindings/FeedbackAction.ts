@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 反馈按钮触发的动作
+ *
+ * 强度类动作作用于按钮所属通道（A0~A4 → A 通道，B0~B4 → B 通道）。
+ */
+export type FeedbackAction = { "action": "adjust_power", 
+/**
+ * 强度变化量
+ */
+delta: number, } | { "action": "set_power", 
+/**
+ * 目标强度
+ */
+power: number, } | { "action": "emergency_stop" } | { "action": "next_preset" } | { "action": "previous_preset" };
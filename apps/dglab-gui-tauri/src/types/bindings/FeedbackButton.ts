@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * APP 反馈按钮
+ */
+export type FeedbackButton = "A0" | "A1" | "A2" | "A3" | "A4" | "B0" | "B1" | "B2" | "B3" | "B4";
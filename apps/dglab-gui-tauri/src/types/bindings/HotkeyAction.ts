@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 快捷键动作
+ */
+export type HotkeyAction = "increase_a" | "decrease_a" | "increase_b" | "decrease_b" | "pause" | "emergency_stop";
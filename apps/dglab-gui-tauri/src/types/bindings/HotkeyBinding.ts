@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { HotkeyAction } from "./HotkeyAction";
+
+/**
+ * 快捷键绑定
+ */
+export type HotkeyBinding = { 
+/**
+ * 快捷键，如 `CommandOrControl+Alt+Up`
+ */
+shortcut: string, 
+/**
+ * 动作
+ */
+action: HotkeyAction, };
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { HotkeyBinding } from "./HotkeyBinding";
+
+/**
+ * 全局快捷键配置（配置文件 `[hotkeys]` 段）
+ */
+export type HotkeyConfig = { 
+/**
+ * 是否注册全局快捷键
+ */
+enabled: boolean, 
+/**
+ * 每次调节的强度
+ */
+step: number, 
+/**
+ * 快捷键绑定
+ */
+bindings: Array<HotkeyBinding>, };
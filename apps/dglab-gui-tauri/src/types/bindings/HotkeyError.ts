@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 注册失败的快捷键
+ */
+export type HotkeyError = { 
+/**
+ * 快捷键
+ */
+shortcut: string, 
+/**
+ * 错误信息
+ */
+message: string, };
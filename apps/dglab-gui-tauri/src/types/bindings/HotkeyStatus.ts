@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { HotkeyConfig } from "./HotkeyConfig";
+import type { HotkeyError } from "./HotkeyError";
+
+/**
+ * 快捷键配置和注册状态
+ */
+export type HotkeyStatus = { 
+/**
+ * 快捷键配置
+ */
+config: HotkeyConfig, 
+/**
+ * 已注册的快捷键数量
+ */
+registered: number, 
+/**
+ * 注册失败的快捷键
+ */
+errors: Array<HotkeyError>, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { HotkeyAction } from "./HotkeyAction";
+
+/**
+ * 全局快捷键触发事件
+ */
+export type HotkeyTriggeredEvent = { 
+/**
+ * 执行的动作
+ */
+action: HotkeyAction, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 分享码导入结果
+ */
+export type ImportedShare = { 
+/**
+ * 导入的内容：`preset` 或 `waveform`
+ */
+kind: string, 
+/**
+ * 预设或波形名称
+ */
+name: string, 
+/**
+ * 预设 ID（导入波形时为空）
+ */
+preset_id: string | null, };
@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 一行日志
+ */
+export type LogLine = { 
+/**
+ * 时间戳（Unix 毫秒）
+ */
+timestamp_ms: number, 
+/**
+ * 级别（TRACE/DEBUG/INFO/WARN/ERROR）
+ */
+level: string, 
+/**
+ * 日志来源模块
+ */
+target: string, 
+/**
+ * 日志内容（附带的字段以 `key=value` 形式追加）
+ */
+message: string, };
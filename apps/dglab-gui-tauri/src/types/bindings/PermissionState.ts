@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 单项权限状态（与 Tauri 移动端插件的权限状态一致）
+ */
+export type PermissionState = "granted" | "denied" | "prompt" | "prompt-with-rationale" | "not-required";
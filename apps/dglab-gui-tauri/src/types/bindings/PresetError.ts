@@ -0,0 +1,21 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PresetIssue } from "./PresetIssue";
+
+/**
+ * 预设命令错误
+ *
+ * 序列化为 `{ kind, ... }`，前端按 `kind` 区分：`invalid` 时按 `issues[].field` 标出对应输入框。
+ */
+export type PresetError = { "kind": "invalid", 
+/**
+ * 所有校验问题
+ */
+issues: Array<PresetIssue>, } | { "kind": "notFound", 
+/**
+ * 预设 ID
+ */
+id: string, } | { "kind": "failed", 
+/**
+ * 错误信息
+ */
+message: string, };
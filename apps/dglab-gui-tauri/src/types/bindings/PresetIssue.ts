@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 预设校验问题
+ *
+ * `field` 为出错字段的路径（如 `channel_a.max_power`），便于界面定位到对应输入框。
+ */
+export type PresetIssue = { 
+/**
+ * 字段路径
+ */
+field: string, 
+/**
+ * 问题描述
+ */
+message: string, };
@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 加入远程会话请求
+ */
+export type RemoteJoinRequest = { 
+/**
+ * 邀请链接（对方 `dglab bridge --local` 显示的二维码链接）
+ */
+invite: string, 
+/**
+ * 以观察端加入，只查看对方设备的强度
+ */
+viewer?: boolean, 
+/**
+ * 对方服务器的 PIN
+ */
+pin?: string, 
+/**
+ * 接受自签名证书（仅用于局域网 wss 服务器）
+ */
+accept_invalid_certs?: boolean, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 加入远程会话响应
+ */
+export type RemoteJoinResponse = { 
+/**
+ * 设备 ID
+ */
+device_id: string, 
+/**
+ * 是否为观察端（强度和波形操作会被拒绝）
+ */
+viewer: boolean, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 单个设备的恢复结果
+ */
+export type ResumeResult = { 
+/**
+ * 设备 ID
+ */
+device_id: string, 
+/**
+ * 失败原因，成功时为空
+ */
+error: string | null, };
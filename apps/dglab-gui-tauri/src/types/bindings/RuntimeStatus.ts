@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 运行时状态
+ */
+export type RuntimeStatus = "stopped" | "running" | "paused";
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RuntimeStatus } from "./RuntimeStatus";
+
+/**
+ * 会话运行时状态变更事件
+ */
+export type RuntimeStatusChangedEvent = { 
+/**
+ * 新状态
+ */
+status: RuntimeStatus, };
@@ -0,0 +1,32 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SavedDevice } from "./SavedDevice";
+import type { SavedWifi } from "./SavedWifi";
+
+/**
+ * 持久化的应用状态
+ */
+export type SavedAppState = { 
+/**
+ * 最近连接的设备
+ */
+last_device: SavedDevice | null, 
+/**
+ * 最近应用的预设 ID
+ */
+last_preset: string | null, 
+/**
+ * A 通道强度
+ */
+power_a: number, 
+/**
+ * B 通道强度
+ */
+power_b: number, 
+/**
+ * WiFi 连接信息（已失效时为 `None`）
+ */
+wifi: SavedWifi | null, 
+/**
+ * 保存时间（Unix 秒）
+ */
+saved_at: number, };
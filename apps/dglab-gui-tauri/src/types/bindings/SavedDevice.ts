@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 最近连接的设备
+ */
+export type SavedDevice = { 
+/**
+ * 设备 ID（BLE 地址或 WiFi 设备 ID）
+ */
+id: string, 
+/**
+ * 设备名称
+ */
+name: string, 
+/**
+ * 是否为 WiFi 设备
+ */
+wifi: boolean, };
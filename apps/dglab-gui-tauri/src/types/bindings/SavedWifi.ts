@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * WiFi 连接信息
+ */
+export type SavedWifi = { 
+/**
+ * 服务器地址
+ */
+server: string, 
+/**
+ * 服务器分配的 clientId
+ */
+client_id: string, };
@@ -0,0 +1,26 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 扫描到的设备信息
+ */
+export type ScannedDevice = { 
+/**
+ * 设备 ID
+ */
+id: string, 
+/**
+ * 设备名称
+ */
+name: string, 
+/**
+ * 信号强度 (RSSI)
+ */
+rssi: number | null, 
+/**
+ * 设备地址
+ */
+address: string, 
+/**
+ * 扫描到该设备的蓝牙适配器
+ */
+adapter: string, };
@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 预设定时触发事件
+ */
+export type ScheduleFiredEvent = { 
+/**
+ * 定时名称
+ */
+schedule: string, 
+/**
+ * 预设名称
+ */
+preset: string, 
+/**
+ * 已应用预设的设备
+ */
+devices: Array<string>, 
+/**
+ * 应用失败时的错误信息
+ */
+error: string | null, };
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BatteryAction } from "./BatteryAction";
+import type { DeviceState } from "./DeviceState";
+import type { FeedbackButton } from "./FeedbackButton";
+
+/**
+ * 会话事件
+ */
+export type SessionEvent = { "DeviceAdded": string } | { "DeviceRemoved": string } | { "DeviceStateChanged": [string, DeviceState] } | { "DeviceDiscovered": [string, string] } | { "DeviceLost": string } | { "Feedback": [string, FeedbackButton] } | { "LinkQuality": [string, number] } | { "WeakSignal": [string, boolean] } | { "Battery": [string, number, { secs: number, nanos: number } | null] } | { "MaxPowerChanged": [string, number, number] } | { "BatteryLimited": [string, BatteryAction] } | { "EmergencyStop": string } | { "TimerWarning": { secs: number, nanos: number } } | "TimerExpired" | { "Error": string };
@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 会话信息
+ */
+export type SessionInfo = { 
+/**
+ * 会话 ID
+ */
+id: string, 
+/**
+ * 会话创建时间
+ */
+created_at: string, 
+/**
+ * 活动设备数量
+ */
+active_devices: number, 
+/**
+ * 总设备数量
+ */
+total_devices: number, };
@@ -0,0 +1,27 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SnapshotDevice } from "./SnapshotDevice";
+
+/**
+ * 可恢复的上次会话
+ */
+export type SessionSnapshotInfo = { 
+/**
+ * 原会话 ID
+ */
+session_id: string, 
+/**
+ * 保存时间（RFC 3339）
+ */
+saved_at: string, 
+/**
+ * 会话已运行的时长（秒）
+ */
+elapsed_secs: number, 
+/**
+ * 会话定时的剩余时长（秒）
+ */
+timer_remaining_secs: number | null, 
+/**
+ * 会话中的设备
+ */
+devices: Array<SnapshotDevice>, };
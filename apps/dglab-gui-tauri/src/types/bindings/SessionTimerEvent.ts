@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 会话定时事件
+ */
+export type SessionTimerEvent = { 
+/**
+ * 剩余秒数
+ */
+remaining_secs: number, 
+/**
+ * 是否已到时（设备已归零并停止）
+ */
+expired: boolean, };
@@ -0,0 +1,30 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 快照中的设备
+ */
+export type SnapshotDevice = { 
+/**
+ * 设备 ID
+ */
+id: string, 
+/**
+ * 设备名称
+ */
+name: string, 
+/**
+ * 是否需在 APP 中重新扫码连接（WiFi 设备）
+ */
+wifi: boolean, 
+/**
+ * A 通道强度
+ */
+power_a: number, 
+/**
+ * B 通道强度
+ */
+power_b: number, 
+/**
+ * 保存时是否正在输出
+ */
+running: boolean, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 波形预览采样点
+ */
+export type WaveformPoint = { 
+/**
+ * 时间（毫秒）
+ */
+time_ms: number, 
+/**
+ * 强度 (0-100)
+ */
+intensity: number, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * WiFi 连接请求
+ */
+export type WifiConnectRequest = { 
+/**
+ * 自定义服务器地址（可选，默认使用官方服务器）
+ */
+server_url?: string, 
+/**
+ * 接受自签名证书（仅用于局域网 wss 服务器）
+ */
+accept_invalid_certs?: boolean, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * WiFi 连接响应
+ */
+export type WifiConnectResponse = { 
+/**
+ * 设备 ID
+ */
+device_id: string, 
+/**
+ * 设备名称
+ */
+device_name: string, 
+/**
+ * 二维码 URL
+ */
+qr_url: string, };
@@ -2,43 +2,18 @@
  * Device types matching Rust backend
  */
 
-/** 设备状态 */
-export enum DeviceState {
-  /** 已断开 */
-  Disconnected = "Disconnected",
-  /** 连接中 */
-  Connecting = "Connecting",
-  /** 已连接 */
-  Connected = "Connected",
-  /** 运行中 */
-  Running = "Running",
-  /** 错误 */
-  Error = "Error",
-}
-
-/** 设备信息 */
-export interface DeviceInfo {
-  /** 设备 ID */
-  id: string;
-  /** 设备名称 */
-  name: string;
-  /** 设备类型 */
-  device_type: string;
-  /** 固件版本 */
-  firmware_version: string;
-  /** 硬件版本 */
-  hardware_version: string;
-  /** 电池电量 (0-100) */
-  battery_level: number;
-  /** 通道 A 当前强度 */
-  power_a: number;
-  /** 通道 B 当前强度 */
-  power_b: number;
-  /** 通道 A 最大强度 */
-  max_power_a: number;
-  /** 通道 B 最大强度 */
-  max_power_b: number;
-}
+export { DeviceState } from "./bindings/DeviceState";
+export type { DeviceInfo } from "./bindings/DeviceInfo";
+export type { DeviceEvent } from "./bindings/DeviceEvent";
+export type { ScannedDevice } from "./bindings/ScannedDevice";
+export type { BluetoothAdapter } from "./bindings/BluetoothAdapter";
+export type { BleAvailability } from "./bindings/BleAvailability";
+export type { BlePermissionStatus } from "./bindings/BlePermissionStatus";
+export type { PermissionState } from "./bindings/PermissionState";
+export type { WifiConnectRequest } from "./bindings/WifiConnectRequest";
+export type { WifiConnectResponse } from "./bindings/WifiConnectResponse";
+export type { RemoteJoinRequest } from "./bindings/RemoteJoinRequest";
+export type { RemoteJoinResponse } from "./bindings/RemoteJoinResponse";
 
 /** 设备配置 */
 export interface DeviceConfig {
@@ -56,38 +31,25 @@ export interface DeviceConfig {
   safety_limit?: number;
 }
 
-/** 设备事件 */
-export type DeviceEvent =
-  | { type: "StateChanged"; state: DeviceState }
-  | { type: "PowerChanged"; power_a: number; power_b: number }
-  | { type: "InfoUpdated"; info: DeviceInfo }
-  | { type: "BatteryUpdated"; battery_level: number }
-  | { type: "Error"; message: string };
-
-/** 扫描到的设备 */
-export interface ScannedDevice {
-  /** 设备 ID */
-  id: string;
-  /** 设备名称 */
-  name: string;
-  /** 信号强度 (RSSI) */
-  rssi?: number;
-  /** 设备地址 */
-  address: string;
+/** 设备输出限制（V3 BF 指令） */
+export interface DeviceLimits {
+  /** A 通道强度软上限 (0~200) */
+  soft_limit_a: number;
+  /** B 通道强度软上限 (0~200) */
+  soft_limit_b: number;
+  /** 波形频率平衡参数 (0~255) */
+  freq_balance: number;
+  /** 波形强度平衡参数 (0~255) */
+  intensity_balance: number;
 }
 
-/** WiFi 连接请求 */
-export interface WifiConnectRequest {
-  /** 自定义服务器地址（可选） */
-  server_url?: string;
+/** 通道联动：设置 A 通道时按 B = round(A × ratio) + offset 同步 B 通道 */
+export interface ChannelLink {
+  /** 比例系数（非负） */
+  ratio: number;
+  /** 偏移量 */
+  offset: number;
 }
 
-/** WiFi 连接响应 */
-export interface WifiConnectResponse {
-  /** 设备 ID */
-  device_id: string;
-  /** 设备名称 */
-  device_name: string;
-  /** 二维码 URL */
-  qr_url: string;
-}
+/** 强度渐变缓动曲线 */
+export type Easing = "linear" | "ease-in" | "ease-out" | "ease-in-out";
@@ -2,44 +2,43 @@
  * Tauri event types and names
  */
 
-import { DeviceInfo, DeviceState } from "./device";
+import type { AppConfig } from "./settings";
 
-/** 设备状态变更事件 */
-export interface DeviceStateChangedEvent {
-  device_id: string;
-  state: DeviceState;
-}
-
-/** 设备功率变更事件 */
-export interface DevicePowerChangedEvent {
-  device_id: string;
-  power_a: number;
-  power_b: number;
-}
-
-/** 设备信息更新事件 */
-export interface DeviceInfoUpdatedEvent {
-  device_id: string;
-  info: DeviceInfo;
-}
-
-/** 设备电池电量更新事件 */
-export interface DeviceBatteryUpdatedEvent {
-  device_id: string;
-  battery: number;
-}
+export type { DeviceStateChangedEvent } from "./bindings/DeviceStateChangedEvent";
+export type { DeviceDiscoveredEvent } from "./bindings/DeviceDiscoveredEvent";
+export type { DeviceLostEvent } from "./bindings/DeviceLostEvent";
+export type { DevicePowerChangedEvent } from "./bindings/DevicePowerChangedEvent";
+export type { DeviceInfoUpdatedEvent } from "./bindings/DeviceInfoUpdatedEvent";
+export type { DeviceBatteryUpdatedEvent } from "./bindings/DeviceBatteryUpdatedEvent";
+export type { DeviceBatteryLimitedEvent } from "./bindings/DeviceBatteryLimitedEvent";
+export type { DeviceErrorEvent } from "./bindings/DeviceErrorEvent";
+export type { DeviceLinkQualityEvent } from "./bindings/DeviceLinkQualityEvent";
+export type { DeviceFeedbackEvent } from "./bindings/DeviceFeedbackEvent";
+export type { RuntimeStatusChangedEvent } from "./bindings/RuntimeStatusChangedEvent";
+export type { SessionTimerEvent } from "./bindings/SessionTimerEvent";
+export type { ScheduleFiredEvent } from "./bindings/ScheduleFiredEvent";
+export type { HotkeyTriggeredEvent } from "./bindings/HotkeyTriggeredEvent";
 
-/** 设备错误事件 */
-export interface DeviceErrorEvent {
-  device_id: string;
-  error: string;
+/** 应用设置变更事件 */
+export interface SettingsChangedEvent {
+  settings: AppConfig;
 }
 
 /** 事件名称常量 */
 export const EVENT_NAMES = {
   DEVICE_STATE_CHANGED: "device:state_changed",
+  DEVICE_DISCOVERED: "device:discovered",
+  DEVICE_LOST: "device:lost",
   DEVICE_POWER_CHANGED: "device:power_changed",
   DEVICE_INFO_UPDATED: "device:info_updated",
   DEVICE_BATTERY_UPDATED: "device:battery_updated",
+  DEVICE_BATTERY_LIMITED: "device:battery_limited",
   DEVICE_ERROR: "device:error",
+  DEVICE_LINK_QUALITY: "device:link_quality",
+  DEVICE_FEEDBACK: "device:feedback",
+  RUNTIME_STATUS_CHANGED: "runtime:status_changed",
+  SETTINGS_CHANGED: "settings:changed",
+  SESSION_TIMER: "session:timer",
+  SCHEDULE_FIRED: "schedule:fired",
+  HOTKEY_TRIGGERED: "hotkey:triggered",
 } as const;
@@ -0,0 +1,14 @@
+/**
+ * Feedback button types matching Rust backend
+ */
+
+import type { FeedbackAction } from "./bindings/FeedbackAction";
+import type { FeedbackButton } from "./bindings/FeedbackButton";
+
+export type { FeedbackAction, FeedbackButton };
+
+/** 反馈按钮映射 */
+export interface FeedbackMapping {
+  /** 按钮动作 */
+  actions: Partial<Record<FeedbackButton, FeedbackAction>>;
+}
@@ -0,0 +1,58 @@
+/**
+ * Gamepad types matching Rust backend
+ */
+
+import type { Easing } from "./device";
+import type { FeedbackAction } from "./feedback";
+
+/** 手柄轴 */
+export type GamepadAxis =
+  | "left_stick_x"
+  | "left_stick_y"
+  | "right_stick_x"
+  | "right_stick_y"
+  | "left_trigger"
+  | "right_trigger";
+
+/** 手柄按键（按位置命名） */
+export type GamepadButton =
+  | "south"
+  | "east"
+  | "north"
+  | "west"
+  | "left_bumper"
+  | "right_bumper"
+  | "select"
+  | "start"
+  | "d_pad_up"
+  | "d_pad_down"
+  | "d_pad_left"
+  | "d_pad_right";
+
+/** 轴控制的目标 */
+export type AxisTarget =
+  | { target: "power"; channel: number }
+  | { target: "waveform_intensity"; channel: number };
+
+/** 轴映射 */
+export type AxisMapping = {
+  /** 轴 */
+  axis: GamepadAxis;
+  /** 死区 (0~1) */
+  dead_zone?: number;
+  /** 响应曲线 */
+  curve?: Easing;
+  /** 反向 */
+  invert?: boolean;
+} & AxisTarget;
+
+/** 按键绑定（channel 为空时强度类动作作用于两个通道） */
+export type ButtonBinding = FeedbackAction & { channel?: number };
+
+/** 手柄映射 */
+export interface GamepadMapping {
+  /** 轴映射 */
+  axes: AxisMapping[];
+  /** 按键绑定 */
+  buttons: Partial<Record<GamepadButton, ButtonBinding>>;
+}
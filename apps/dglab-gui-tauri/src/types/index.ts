@@ -4,6 +4,10 @@
 
 export * from "./common";
 export * from "./device";
+export * from "./feedback";
+export * from "./gamepad";
+export * from "./logs";
 export * from "./waveform";
 export * from "./preset";
 export * from "./session";
+export * from "./settings";
@@ -0,0 +1,8 @@
+/**
+ * Application log types matching Rust backend
+ */
+
+/** 日志级别 */
+export type LogLevel = "TRACE" | "DEBUG" | "INFO" | "WARN" | "ERROR";
+
+export type { LogLine } from "./bindings/LogLine";
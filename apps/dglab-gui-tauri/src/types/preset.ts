@@ -2,7 +2,13 @@
  * Preset types matching Rust backend
  */
 
-import type { Waveform } from "./waveform";
+import type { Modulation, Waveform } from "./waveform";
+
+import type { PresetError } from "./bindings/PresetError";
+
+export type { ImportedShare } from "./bindings/ImportedShare";
+export type { PresetError };
+export type { PresetIssue } from "./bindings/PresetIssue";
 
 /** 通道配置 */
 export interface PresetChannelConfig {
@@ -14,6 +20,20 @@ export interface PresetChannelConfig {
   max_power: number;
   /** 波形 */
   waveform?: Waveform;
+  /** 波形库中的波形名称（waveform 为空时使用） */
+  waveform_name?: string;
+  /** 调制层（叠加在波形自带的调制层之后） */
+  modulation?: Modulation[];
+}
+
+/** 预设安全设置 */
+export interface PresetSafety {
+  /** 强度绝对上限 */
+  max_power?: number;
+  /** 强度最大上升速率（每秒） */
+  max_ramp_rate?: number;
+  /** 高强度预设，应用前需要用户确认 */
+  require_confirmation: boolean;
 }
 
 /** 设备预设 */
@@ -34,6 +54,73 @@ export interface Preset {
   channel_b: PresetChannelConfig;
   /** 全局设置 */
   settings: Record<string, string>;
+  /** 安全设置（默认设置时不存在） */
+  safety?: PresetSafety;
+  /** 标签（没有标签时不存在） */
+  tags?: string[];
+  /** 分类 */
+  category?: string;
+  /** 父预设 ID，未覆盖的字段从父预设继承 */
+  parent?: string;
+  /** 覆盖父预设的字段路径（如 "channel_a.max_power"、"safety"） */
+  overrides?: string[];
+}
+
+/** 预设搜索条件（各条件同时满足，名称、标签和分类忽略大小写） */
+export interface PresetQuery {
+  /** 名称或描述包含的文本 */
+  text?: string;
+  /** 必须带有的标签（全部满足） */
+  tags?: string[];
+  /** 分类 */
+  category?: string;
+  /** 最高强度下限 */
+  min_power?: number;
+  /** 最高强度上限 */
+  max_power?: number;
+}
+
+/** 预设命令错误的提示文字 */
+export function presetErrorMessage(error: unknown): string {
+  const e = error as PresetError;
+  switch (e?.kind) {
+    case "invalid":
+      return e.issues.map((issue) => issue.message).join("\n");
+    case "notFound":
+      return `预设不存在：${e.id}`;
+    case "failed":
+      return e.message;
+    default:
+      return String(error);
+  }
+}
+
+/** 可以从父预设继承的字段 */
+export const INHERITABLE_FIELDS = [
+  "channel_a.enabled",
+  "channel_a.min_power",
+  "channel_a.max_power",
+  "channel_a.waveform",
+  "channel_a.modulation",
+  "channel_b.enabled",
+  "channel_b.min_power",
+  "channel_b.max_power",
+  "channel_b.waveform",
+  "channel_b.modulation",
+  "safety.max_power",
+  "safety.max_ramp_rate",
+  "safety.require_confirmation",
+] as const;
+
+/** 字段是否使用预设自己的值（没有父预设时所有字段都使用自己的值） */
+export function overridesField(preset: Preset, field: string): boolean {
+  const overrides = preset.overrides ?? [];
+  return !preset.parent || overrides.some((o) => field === o || field.startsWith(`${o}.`));
+}
+
+/** 应用前是否需要用户确认 */
+export function requiresConfirmation(preset: Preset): boolean {
+  return preset.safety?.require_confirmation ?? false;
 }
 
 /** 默认通道配置 */
@@ -42,3 +129,15 @@ export const defaultChannelConfig: PresetChannelConfig = {
   min_power: 0,
   max_power: 50,
 };
+
+/** 预设定时（每天的本地时间 `at` 或固定间隔 `every` 二选一） */
+export type ScheduleEntry = {
+  /** 定时名称（唯一） */
+  name: string;
+  /** 预设名称 */
+  preset: string;
+  /** 设备 ID（不设置表示所有设备） */
+  device?: string;
+  /** 是否启用 */
+  enabled: boolean;
+} & ({ at: string } | { every: string });
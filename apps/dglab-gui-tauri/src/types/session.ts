@@ -4,21 +4,56 @@
 
 import type { DeviceState } from "./device";
 
-/** 会话事件 */
-export type SessionEvent =
-  | { type: "DeviceAdded"; device_id: string }
-  | { type: "DeviceRemoved"; device_id: string }
-  | { type: "DeviceStateChanged"; device_id: string; state: DeviceState }
-  | { type: "Error"; message: string };
+export type { SessionEvent } from "./bindings/SessionEvent";
+export type { BatteryAction } from "./bindings/BatteryAction";
+export type { SessionInfo } from "./bindings/SessionInfo";
+export type { SnapshotDevice } from "./bindings/SnapshotDevice";
+export type { SessionSnapshotInfo } from "./bindings/SessionSnapshotInfo";
+export type { ResumeResult } from "./bindings/ResumeResult";
 
-/** 会话信息 */
-export interface SessionInfo {
+/** 事件日志事件 */
+export type LogEvent =
+  | { event: "device_added" }
+  | { event: "device_removed" }
+  | { event: "state_changed"; state: DeviceState }
+  | { event: "power_changed"; channel: number; power: number }
+  | { event: "emergency_stop" }
+  | { event: "timer_expired" }
+  | { event: "error"; message: string };
+
+/** 事件日志记录 */
+export type LogEntry = {
+  /** 时间（RFC 3339） */
+  timestamp: string;
   /** 会话 ID */
-  id: string;
-  /** 会话创建时间 */
-  created_at: string;
-  /** 活动设备数量 */
-  active_devices: number;
-  /** 总设备数量 */
-  total_devices: number;
+  session_id: string;
+  /** 设备 ID（会话级事件为空） */
+  device_id?: string;
+} & LogEvent;
+
+/** 单通道统计 */
+export interface ChannelStats {
+  /** 峰值强度 */
+  peak_power: number;
+  /** 时间加权平均强度 */
+  average_power: number;
+  /** 各强度下的累计时长（强度 → 毫秒） */
+  time_at_power: Record<string, number>;
 }
+
+/** 设备会话统计 */
+export interface DeviceStats {
+  /** 设备 ID */
+  device_id: string;
+  /** 累计统计时长（毫秒） */
+  tracked_ms: number;
+  /** 紧急停止次数 */
+  emergency_stops: number;
+  /** A、B 通道统计 */
+  channels: [ChannelStats, ChannelStats];
+}
+
+export type { RuntimeStatus } from "./bindings/RuntimeStatus";
+export type { SavedDevice } from "./bindings/SavedDevice";
+export type { SavedWifi } from "./bindings/SavedWifi";
+export type { SavedAppState } from "./bindings/SavedAppState";
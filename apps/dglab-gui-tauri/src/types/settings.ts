@@ -0,0 +1,102 @@
+/**
+ * Application settings types matching Rust backend
+ */
+
+import type { HotkeyConfig } from "./bindings/HotkeyConfig";
+import type { ScheduleEntry } from "./preset";
+
+export type { HotkeyAction } from "./bindings/HotkeyAction";
+export type { HotkeyBinding } from "./bindings/HotkeyBinding";
+export type { HotkeyError } from "./bindings/HotkeyError";
+export type { HotkeyStatus } from "./bindings/HotkeyStatus";
+export type { HotkeyConfig };
+
+/** 服务器设置 */
+export interface ServerConfig {
+  /** 默认 WebSocket 服务器 URL */
+  url: string;
+  /** 是否接受无效 TLS 证书 */
+  accept_invalid_certs: boolean;
+}
+
+/** 低电量输出限制 */
+export interface BatteryPolicy {
+  /** 电量低于该值（%）时限制强度上限（null 表示不限制） */
+  cap_below: number | null;
+  /** 限制后的强度上限 */
+  cap_power: number;
+  /** 电量低于该值（%）时暂停输出（null 表示不暂停） */
+  pause_below: number | null;
+}
+
+/** 强度安全上限 */
+export interface SafetyConfig {
+  max_power_a: number;
+  max_power_b: number;
+  battery: BatteryPolicy;
+}
+
+/** WebSocket 重连设置 */
+export interface ReconnectConfig {
+  enabled: boolean;
+  /** 最大重连次数（null 表示不限） */
+  max_attempts: number | null;
+  initial_delay_ms: number;
+  max_delay_ms: number;
+}
+
+/** 常用设备 */
+export interface FavoriteDevice {
+  id: string;
+  name: string;
+  /** 强度校准曲线：[百分比, 原始强度] 校准点 */
+  calibration?: [number, number][];
+}
+
+/** MQTT 集成设置 */
+export interface MqttConfig {
+  /** Broker 地址（host、host:port 或 mqtt://host:port） */
+  broker: string;
+  client_id: string;
+  topic_prefix: string;
+  username?: string;
+  password?: string;
+  keep_alive_secs: number;
+  publish_interval_ms: number;
+}
+
+/** Webhook 触发设置 */
+export interface WebhookConfig {
+  /** 监听地址（ip:port） */
+  bind: string;
+}
+
+/** 事件钩子（各事件执行的 shell 命令） */
+export interface HooksConfig {
+  timeout_secs: number;
+  battery_low_threshold: number;
+  on_connect?: string[];
+  on_disconnect?: string[];
+  on_feedback?: string[];
+  on_battery_low?: string[];
+  on_emergency_stop?: string[];
+}
+
+/** 应用设置（CLI 与 GUI 共用） */
+export interface AppConfig {
+  log_level: string;
+  server: ServerConfig;
+  safety: SafetyConfig;
+  reconnect: ReconnectConfig;
+  favorite_devices: FavoriteDevice[];
+  /** 预设定时 */
+  schedules: ScheduleEntry[];
+  /** MQTT 集成（未配置时不存在） */
+  mqtt?: MqttConfig;
+  /** Webhook 触发（未配置时不存在） */
+  webhook?: WebhookConfig;
+  /** 全局快捷键（默认配置时不存在） */
+  hotkeys?: HotkeyConfig;
+  /** 事件钩子（默认配置时不存在） */
+  hooks?: HooksConfig;
+}
@@ -52,8 +52,40 @@ export interface Waveform {
   params: WaveformParams;
   /** 自定义数据点 */
   custom_points?: Array<[number, number]>;
+  /** V3 原始帧（每帧 100ms，循环播放） */
+  frames?: WaveformFrame[];
+  /** 调制层（依次叠加在基础波形上） */
+  modulation?: Modulation[];
 }
 
+/** 调制目标 */
+export type ModulationTarget = 'intensity' | 'frequency';
+
+/** LFO 形状 */
+export type LfoShape = 'sine' | 'triangle' | 'square' | 'sawtooth';
+
+/** 调制层（LFO） */
+export interface Modulation {
+  /** 调制目标 */
+  target: ModulationTarget;
+  /** LFO 形状 */
+  shape: LfoShape;
+  /** LFO 周期（毫秒，100-60000） */
+  rate_ms: number;
+  /** 调制深度 (0-100%) */
+  depth: number;
+}
+
+/** V3 波形帧（4 组频率 + 4 组强度） */
+export interface WaveformFrame {
+  /** 频率 (10-240) */
+  frequency: [number, number, number, number];
+  /** 强度 (0-100) */
+  intensity: [number, number, number, number];
+}
+
+export type { WaveformPoint } from "./bindings/WaveformPoint";
+
 /** 默认波形参数 */
 export const defaultWaveformParams: WaveformParams = {
   waveform_type: WaveformType.Continuous,
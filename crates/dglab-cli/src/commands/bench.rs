@@ -0,0 +1,230 @@
+//! BLE 吞吐量测试命令
+//!
+//! 按指定的写入方式和 B0 间隔连续发送静默帧，统计发送间隔抖动和写入耗时；
+//! 每隔若干帧发送一个带序列号的探测帧，根据 B1 回应测量往返延迟并统计丢帧。
+//! 测试期间软上限设为 0，不会产生输出。
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use clap::Parser;
+use clap_complete::engine::ArgValueCandidates;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+use tracing::{debug, info, warn};
+
+use dglab_protocol::ble::{ConnectionPriority, LinkOptions, WriteMode};
+use dglab_protocol::v3::{B0Command, BFCommand, NotifyMessage, WaveformData};
+
+use super::completions::device_candidates;
+use super::DglabCli;
+use crate::error::{CliError, Result};
+
+/// 序列号范围（0 表示不需要回应）
+const MAX_SEQUENCE: u8 = 0x0F;
+
+/// 吞吐量测试参数
+#[derive(Parser, Debug)]
+pub struct BenchArgs {
+    /// 设备名称（如：47L121000）
+    #[arg(add = ArgValueCandidates::new(device_candidates))]
+    device: String,
+
+    /// 测试时长（秒）
+    #[arg(long, default_value = "10")]
+    duration: u64,
+
+    /// 写入方式（without-response / with-response）
+    #[arg(long, default_value = "without-response")]
+    write_mode: WriteMode,
+
+    /// B0 发送间隔（毫秒，50~250）
+    #[arg(long, default_value = "100")]
+    interval: u64,
+
+    /// 每隔多少帧发送一次延迟探测帧
+    #[arg(long, default_value = "5", value_parser = clap::value_parser!(u32).range(1..))]
+    probe_every: u32,
+
+    /// 探测帧等待 B1 回应的超时（毫秒）
+    #[arg(long, default_value = "500")]
+    timeout_ms: u64,
+
+    /// 连接优先级（balanced / high / low-power）
+    #[arg(long, default_value = "high")]
+    priority: ConnectionPriority,
+}
+
+/// 延迟探测统计
+#[derive(Debug, Default)]
+struct ProbeStats {
+    /// 已发送的探测帧
+    sent: u32,
+    /// 超时未回应的探测帧
+    dropped: u32,
+    /// 往返延迟
+    round_trips: Vec<Duration>,
+}
+
+impl ProbeStats {
+    /// 打印统计结果
+    fn print(&self) {
+        let answered = self.round_trips.len();
+        let drop_rate = match self.sent {
+            0 => 0.0,
+            sent => self.dropped as f64 * 100.0 / sent as f64,
+        };
+        println!(
+            "Probes:        {} sent, {} answered, {} dropped ({:.1}%)",
+            self.sent, answered, self.dropped, drop_rate
+        );
+
+        let (Some(min), Some(max)) = (self.round_trips.iter().min(), self.round_trips.iter().max())
+        else {
+            return;
+        };
+        let mean = self.round_trips.iter().sum::<Duration>() / answered as u32;
+        println!(
+            "Round trip:    min {} ms, mean {} ms, max {} ms",
+            min.as_millis(),
+            mean.as_millis(),
+            max.as_millis()
+        );
+    }
+}
+
+/// 执行吞吐量测试
+pub async fn execute(app: &mut DglabCli, args: BenchArgs) -> Result<()> {
+    let ble_manager = app.get_or_init_ble().await?.clone();
+
+    println!("Scanning for {}...", args.device);
+    ble_manager.start_scan().await?;
+    tokio::time::sleep(Duration::from_secs(3)).await;
+    ble_manager.stop_scan().await?;
+    let target = ble_manager
+        .get_scan_results()
+        .await?
+        .into_iter()
+        .find(|d| d.name.contains(args.device.as_str()) || d.id == args.device)
+        .ok_or_else(|| CliError::DeviceNotFound(args.device.clone()))?;
+
+    info!("Benchmarking {} ({})", target.name, target.id);
+    // 测试使用指定的写入方式，不自动切换
+    ble_manager.set_link_options(LinkOptions {
+        priority: args.priority,
+        write_fallback: false,
+    });
+    let device = ble_manager.connect(&target.id).await?;
+    device.set_write_mode(args.write_mode);
+    device.set_b0_interval(Duration::from_millis(args.interval))?;
+
+    // 软上限归零，测试期间设备不会输出
+    let bf = BFCommand {
+        soft_limit_a: 0,
+        soft_limit_b: 0,
+        ..BFCommand::default_config()
+    };
+    device.send(&bf.encode()).await?;
+    device.reset_write_stats();
+
+    // 转发 B1 回应的序列号
+    let (b1_tx, mut b1_rx) = mpsc::unbounded_channel();
+    let receiver = device.clone();
+    let receive_task = tokio::spawn(async move {
+        while let Ok(data) = receiver.receive().await {
+            if let NotifyMessage::Strength(response) = NotifyMessage::parse(&data) {
+                if b1_tx.send((response.sequence, Instant::now())).is_err() {
+                    break;
+                }
+            }
+        }
+    });
+
+    println!(
+        "Running for {}s: {} writes, {} ms interval, probe every {} frames",
+        args.duration, args.write_mode, args.interval, args.probe_every
+    );
+
+    let interval = device.b0_interval();
+    let timeout = Duration::from_millis(args.timeout_ms);
+    let deadline = Instant::now() + Duration::from_secs(args.duration);
+    let mut ticker = tokio::time::interval(interval);
+    let mut pending: HashMap<u8, Instant> = HashMap::new();
+    let mut probes = ProbeStats::default();
+    let mut frame = 0u32;
+    let mut sequence = 0u8;
+
+    while Instant::now() < deadline {
+        tokio::select! {
+            _ = ticker.tick() => {}
+            Some((seq, at)) = b1_rx.recv() => {
+                if let Some(sent_at) = pending.remove(&seq) {
+                    probes.round_trips.push(at.saturating_duration_since(sent_at));
+                }
+                continue;
+            }
+        }
+
+        // 超时的探测帧计为丢帧
+        let now = Instant::now();
+        pending.retain(|_, sent_at| {
+            let alive = now.saturating_duration_since(*sent_at) < timeout;
+            if !alive {
+                probes.dropped += 1;
+            }
+            alive
+        });
+
+        frame += 1;
+        let command = if frame % args.probe_every == 0 {
+            sequence = sequence % MAX_SEQUENCE + 1;
+            if pending.insert(sequence, now).is_some() {
+                probes.dropped += 1;
+            }
+            probes.sent += 1;
+            B0Command {
+                sequence,
+                ..B0Command::zero()
+            }
+        } else {
+            B0Command::waveform_only(WaveformData::silent(), WaveformData::silent())
+        };
+
+        if let Err(e) = device.send(&command.encode()).await {
+            debug!("Benchmark write failed: {}", e);
+        }
+    }
+    probes.dropped += pending.len() as u32;
+
+    receive_task.abort();
+    if let Err(e) = device.send(&B0Command::zero().encode()).await {
+        warn!("Failed to send zero frame: {}", e);
+    }
+    if let Err(e) = device.disconnect().await {
+        warn!("Failed to disconnect: {}", e);
+    }
+
+    let stats = device.write_stats();
+    println!("\nBenchmark: {} ({})", target.name, target.id);
+    println!("{}", "-".repeat(50));
+    println!("Link:          {}", device.link_params());
+    println!(
+        "Frames:        {} sent, {} failed writes",
+        stats.frames, stats.failed
+    );
+    println!(
+        "Interval:      mean {} ms, jitter {} ms, max {} ms (target {} ms)",
+        stats.mean_interval.as_millis(),
+        stats.jitter.as_millis(),
+        stats.max_interval.as_millis(),
+        interval.as_millis()
+    );
+    println!(
+        "Write time:    mean {} ms, max {} ms",
+        stats.mean_write.as_millis(),
+        stats.max_write.as_millis()
+    );
+    probes.print();
+
+    Ok(())
+}
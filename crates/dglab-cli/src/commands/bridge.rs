@@ -1,73 +1,194 @@
 //! 桥接模式命令
 //!
 //! 通过 BLE 连接设备并同时连接到 WebSocket 服务器，充当 APP 角色
+//!
+//! `--local` 在本机启动 WebSocket 服务器并通过它桥接，二维码指向局域网地址，
+//! 同一局域网内扫码即可控制，不依赖公共服务器。
+//!
+//! 运行期间定期打印状态摘要（绑定的控制器、最近的强度指令、主机强度、消息速率和心跳延迟），
+//! `--verbose-frames` 逐条打印转发的 WebSocket 消息。
+//!
+//! `--daemon` 模式下无人值守运行：自动扫描并连接设备，断线后自动重连，
+//! 通过状态文件报告健康状况，收到 SIGTERM / Ctrl+C 时先将输出归零再退出。
+//! `--metrics` 同时以 Prometheus 格式导出设备指标。
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use clap::Args;
-use tracing::{error, info};
+use clap_complete::engine::ArgValueCandidates;
+use serde::Serialize;
+use tracing::{error, info, warn};
 
+use crate::commands::completions::device_candidates;
 use crate::commands::DglabCli;
 use crate::error::{CliError, Result};
 
-use dglab_core::device::{BleWsBridgeDevice, Device};
-use dglab_protocol::wifi::OFFICIAL_SERVER;
+use dglab_core::device::{
+    BleWsBridgeDevice, BridgeFrame, BridgeMetrics, BridgeStatus, Device, FrameDirection,
+};
+use dglab_core::metrics::{self, MetricsServer, DEFAULT_METRICS_BIND};
+use dglab_protocol::ble::{BleManager, ScanResult};
+use dglab_protocol::wifi::{
+    MessageValidation, ReconnectPolicy, ServerAddress, ServerAuth, ServerEvent, ServerTls, WsServer,
+};
+
+/// 本机服务器默认监听地址
+const DEFAULT_LOCAL_ADDR: &str = "0.0.0.0:9999";
 
 /// 桥接模式参数
 #[derive(Debug, Args)]
 pub struct BridgeArgs {
-    /// 设备名称（如：47L121000）
+    /// 设备名称（如：47L121000），可重复指定以桥接多台主机
+    ///
+    /// 第 n 台主机的 A/B 通道在控制消息中编号为 2n-1 / 2n。
+    /// 守护进程模式下未指定时使用配置文件中的常用设备。
+    #[arg(
+        short,
+        long,
+        required_unless_present = "daemon",
+        add = ArgValueCandidates::new(device_candidates)
+    )]
+    pub device: Vec<String>,
+
+    /// WebSocket 服务器地址（ws://、wss:// 或 host:port，默认使用配置文件中的地址）
     #[arg(short, long)]
-    pub device: String,
+    pub server: Option<ServerAddress>,
+
+    /// 在本机启动 WebSocket 服务器并通过它桥接（可指定监听地址，默认 0.0.0.0:9999）
+    #[arg(
+        long,
+        value_name = "ADDR",
+        num_args = 0..=1,
+        default_missing_value = DEFAULT_LOCAL_ADDR,
+        conflicts_with_all = ["server", "daemon"]
+    )]
+    pub local: Option<String>,
+
+    /// 本机服务器的授权 PIN：其他控制端需携带此 PIN 才能转发控制消息（APP 扫码无需 PIN）
+    #[arg(long, requires = "local")]
+    pub pin: Option<String>,
+
+    /// 本机服务器的消息校验方式：off 不校验，strict 拒绝不合法的消息，sanitize 清理后转发
+    #[arg(long, value_name = "MODE", default_value_t = MessageValidation::Off, requires = "local")]
+    pub validate: MessageValidation,
+
+    /// 本机服务器的 TLS 证书（PEM，可含证书链），与 --tls-key 一起使用时服务器改为 wss://
+    #[arg(long, value_name = "PATH", requires_all = ["local", "tls_key"])]
+    pub tls_cert: Option<PathBuf>,
+
+    /// 本机服务器的 TLS 私钥（PEM）
+    #[arg(long, value_name = "PATH", requires = "tls_cert")]
+    pub tls_key: Option<PathBuf>,
 
-    /// WebSocket 服务器地址
-    #[arg(short, long, default_value = OFFICIAL_SERVER)]
-    pub server: String,
+    /// 接受自签名证书（仅用于局域网 wss 服务器）
+    #[arg(long)]
+    pub insecure: bool,
 
     /// 详细输出
     #[arg(short, long)]
     pub verbose: bool,
+
+    /// 打印每条转发的 WebSocket 消息
+    #[arg(long)]
+    pub verbose_frames: bool,
+
+    /// 状态摘要打印间隔（秒，0 表示不打印）
+    #[arg(long, default_value = "10")]
+    pub status_interval: u64,
+
+    /// 以守护进程模式运行（无人值守，自动扫描、连接和重连）
+    #[arg(long)]
+    pub daemon: bool,
+
+    /// 守护进程状态文件路径（JSON，定期更新）
+    #[arg(long, requires = "daemon")]
+    pub status_file: Option<PathBuf>,
+
+    /// 守护进程健康检查及重试间隔（秒）
+    #[arg(long, default_value = "5")]
+    pub check_interval: u64,
+
+    /// 守护进程导出 Prometheus 指标（可指定监听地址，默认 127.0.0.1:9464）
+    #[arg(
+        long,
+        value_name = "ADDR",
+        num_args = 0..=1,
+        default_missing_value = DEFAULT_METRICS_BIND,
+        requires = "daemon"
+    )]
+    pub metrics: Option<String>,
 }
 
 /// 执行桥接模式
 pub async fn execute(cli: &mut DglabCli, args: BridgeArgs) -> Result<()> {
+    if args.daemon {
+        return run_daemon(cli, args).await;
+    }
+
     println!("🌉 启动 BLE-WebSocket 桥接模式");
     println!();
 
     // 1. 先扫描 BLE 设备（找到目标设备）
     println!("📡 步骤 1: 扫描 BLE 设备...");
-    let ble_manager = cli
-        .ble_manager()
-        .ok_or_else(|| CliError::Other("BLE manager not initialized".to_string()))?;
+    let ble_manager = cli.get_or_init_ble().await?.clone();
 
     ble_manager.start_scan().await?;
     tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
 
     let scan_results = ble_manager.get_scan_results().await?;
-    let target_device = scan_results
-        .iter()
-        .find(|d| d.name.contains(&args.device))
-        .ok_or_else(|| CliError::DeviceNotFound(args.device.clone()))?;
+    let mut target_devices = Vec::with_capacity(args.device.len());
+    for name in &args.device {
+        let target_device = scan_results
+            .iter()
+            .find(|d| {
+                d.name.contains(name.as_str())
+                    && !target_devices.iter().any(|t: &&ScanResult| t.id == d.id)
+            })
+            .ok_or_else(|| CliError::DeviceNotFound(name.clone()))?;
 
-    println!("✓ 找到设备: {} ({})", target_device.name, target_device.id);
+        println!("✓ 找到设备: {} ({})", target_device.name, target_device.id);
+        target_devices.push(target_device);
+    }
     println!();
 
     // 2. 创建桥接设备
     println!("🔧 步骤 2: 创建桥接设备...");
-    let mut bridge_device = if args.server == OFFICIAL_SERVER {
-        BleWsBridgeDevice::new(
-            format!("bridge-{}", target_device.id),
-            format!("Bridge-{}", target_device.name),
-            target_device.id.clone(),
-            target_device.name.clone(),
-        )
-    } else {
-        BleWsBridgeDevice::with_server(
-            format!("bridge-{}", target_device.id),
-            format!("Bridge-{}", target_device.name),
-            target_device.id.clone(),
-            target_device.name.clone(),
-            args.server.clone(),
-        )
+    let config = cli.config();
+    let (server, qr_server) = match &args.local {
+        Some(bind_addr) => {
+            let tls = match (&args.tls_cert, &args.tls_key) {
+                (Some(cert), Some(key)) => Some(
+                    ServerTls::from_files(cert, key).map_err(|e| CliError::Other(e.to_string()))?,
+                ),
+                _ => None,
+            };
+            let (server, qr_server) =
+                start_local_server(bind_addr, args.pin.as_deref(), tls, args.validate).await?;
+            println!("✓ 本机服务器已启动: {}", qr_server);
+            (server, Some(qr_server))
+        }
+        None => match &args.server {
+            Some(server) => (server.clone().accept_invalid_certs(args.insecure), None),
+            None => (config.server_address()?, None),
+        },
     };
+    let primary = target_devices[0];
+    let mut bridge_device = BleWsBridgeDevice::with_units(
+        format!("bridge-{}", primary.id),
+        format!("Bridge-{}", primary.name),
+        target_devices
+            .iter()
+            .map(|d| (d.id.clone(), d.name.clone()))
+            .collect(),
+        server.clone(),
+    )?;
+    bridge_device.set_reconnect_policy(config.reconnect.policy());
+    if let Some(qr_server) = &qr_server {
+        bridge_device.set_qr_server(qr_server.clone());
+    }
 
     // 3. 连接 WebSocket 服务器（先连接，立即显示二维码）
     println!("🌐 步骤 3: 连接 WebSocket 服务器...");
@@ -87,6 +208,11 @@ pub async fn execute(cli: &mut DglabCli, args: BridgeArgs) -> Result<()> {
         display_qr_code(&qr_url);
 
         println!();
+        if qr_server.is_some() {
+            println!("🤝 局域网内的其他 dglab 也可以用此链接加入（--viewer 只查看）：");
+            println!("  dglab remote '{}'", qr_url);
+            println!();
+        }
     } else {
         error!("无法获取二维码 URL");
         return Err(CliError::Other("Failed to get QR URL".to_string()));
@@ -95,9 +221,13 @@ pub async fn execute(cli: &mut DglabCli, args: BridgeArgs) -> Result<()> {
     // 5. 连接 BLE 设备（二维码显示后再连）
     println!("📲 步骤 5: 连接 BLE 设备...");
 
-    let protocol_device = ble_manager.connect(&target_device.id).await?;
-    bridge_device.connect_ble(protocol_device).await?;
-    println!("✓ BLE 设备已连接");
+    for (unit, target_device) in target_devices.iter().enumerate() {
+        let protocol_device = ble_manager.connect(&target_device.id).await?;
+        bridge_device
+            .connect_ble_unit(unit, protocol_device)
+            .await?;
+        println!("✓ BLE 设备已连接: {}", target_device.name);
+    }
     println!();
 
     // 6. 等待控制器连接
@@ -112,8 +242,18 @@ pub async fn execute(cli: &mut DglabCli, args: BridgeArgs) -> Result<()> {
     println!("✅ 桥接模式已启动！");
     println!();
     println!("📊 实时状态：");
-    println!("  • BLE 设备: {}", target_device.name);
-    println!("  • WebSocket: {}", args.server);
+    for (unit, target_device) in target_devices.iter().enumerate() {
+        println!(
+            "  • BLE 设备: {}（通道 {}/{}）",
+            target_device.name,
+            unit * 2 + 1,
+            unit * 2 + 2
+        );
+    }
+    match &qr_server {
+        Some(qr_server) => println!("  • WebSocket: {}（本机服务器）", qr_server),
+        None => println!("  • WebSocket: {}", server),
+    }
     println!();
     println!("💡 提示：");
     println!("  • 第三方控制器可以通过 WebSocket 发送控制指令");
@@ -122,12 +262,33 @@ pub async fn execute(cli: &mut DglabCli, args: BridgeArgs) -> Result<()> {
     println!("  • 按 Ctrl+C 停止");
     println!();
 
-    // 订阅设备事件
+    // 订阅设备事件和转发的消息
     let mut events = bridge_device.subscribe_events();
+    let mut frames = bridge_device.subscribe_frames();
+    let status_interval = Duration::from_secs(args.status_interval.max(1));
+    let mut status_tick = tokio::time::interval_at(
+        tokio::time::Instant::now() + status_interval,
+        status_interval,
+    );
+    let mut rate = MessageRate::new(&bridge_device.metrics());
 
     // 监听事件
     loop {
         tokio::select! {
+            frame = frames.recv(), if args.verbose_frames => {
+                match frame {
+                    Ok(frame) => print_frame(&frame),
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                        println!("… 跳过 {} 条消息", skipped);
+                    }
+                    Err(_) => {}
+                }
+            }
+            _ = status_tick.tick(), if args.status_interval > 0 => {
+                let metrics = bridge_device.metrics();
+                let per_sec = rate.update(&metrics);
+                println!("{}", format_summary(&bridge_device.status(), &metrics, per_sec));
+            }
             event = events.recv() => {
                 if let Ok(event) = event {
                     match event {
@@ -165,6 +326,159 @@ pub async fn execute(cli: &mut DglabCli, args: BridgeArgs) -> Result<()> {
     Ok(())
 }
 
+/// 消息速率统计
+struct MessageRate {
+    /// 上次统计时的消息总数
+    total: u64,
+    /// 上次统计时间
+    at: Instant,
+}
+
+impl MessageRate {
+    fn new(metrics: &BridgeMetrics) -> Self {
+        Self {
+            total: metrics.inbound_messages + metrics.outbound_messages,
+            at: Instant::now(),
+        }
+    }
+
+    /// 返回自上次统计以来每秒转发的消息数
+    fn update(&mut self, metrics: &BridgeMetrics) -> f64 {
+        let total = metrics.inbound_messages + metrics.outbound_messages;
+        let elapsed = self.at.elapsed().as_secs_f64();
+        let rate = if elapsed > 0.0 {
+            total.saturating_sub(self.total) as f64 / elapsed
+        } else {
+            0.0
+        };
+        self.total = total;
+        self.at = Instant::now();
+        rate
+    }
+}
+
+/// 格式化状态摘要
+fn format_summary(status: &BridgeStatus, metrics: &BridgeMetrics, per_sec: f64) -> String {
+    let controller = match (&status.controller, status.reconnect_attempt) {
+        (_, Some(attempt)) => format!("重连中（第 {} 次）", attempt),
+        (Some(controller), None) => controller.clone(),
+        (None, None) => "未绑定".to_string(),
+    };
+    let power = metrics
+        .unit_power
+        .iter()
+        .enumerate()
+        .map(|(unit, power)| match power {
+            Some((a, b)) => format!("#{} A={} B={}", unit + 1, a, b),
+            None => format!("#{} -", unit + 1),
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    let latency = metrics
+        .ws_latency_ms
+        .map_or_else(|| "-".to_string(), |ms| format!("{}ms", ms));
+
+    format!(
+        "📊 控制器: {} | 指令: {} | 强度: {} | 消息: {:.1}/s | 延迟: {}",
+        controller,
+        metrics.last_strength.as_deref().unwrap_or("-"),
+        power,
+        per_sec,
+        latency
+    )
+}
+
+/// 打印转发的消息
+fn print_frame(frame: &BridgeFrame) {
+    let arrow = match frame.direction {
+        FrameDirection::Inbound => "⬇ 控制器",
+        FrameDirection::Outbound => "⬆ 主机",
+    };
+    println!("{}: {}", arrow, frame.message);
+}
+
+/// 在本机启动 WebSocket 服务器
+///
+/// 返回桥接自身连接用的本机地址和写入二维码的局域网地址。
+/// 设置 `pin` 时启用授权，被拒绝的客户端打印警告。桥接自身不带 PIN 连接，
+/// 控制端必须带 PIN 才能与桥接互发控制消息。
+/// 设置 `tls` 时使用 `wss://`，桥接自身连接本机时不校验证书（可能是自签名证书）。
+/// 启用消息校验时打印被拒绝的消息。
+async fn start_local_server(
+    bind_addr: &str,
+    pin: Option<&str>,
+    tls: Option<ServerTls>,
+    validation: MessageValidation,
+) -> Result<(ServerAddress, ServerAddress)> {
+    let mut server = WsServer::new(bind_addr.to_string()).with_validation(validation);
+    if let Some(tls) = tls {
+        server = server.with_tls(tls);
+    }
+    if let Some(pin) = pin {
+        server = server.with_auth(ServerAuth::pin(pin));
+    }
+    if pin.is_some() || validation != MessageValidation::Off {
+        let mut events = server.subscribe_events();
+        tokio::spawn(async move {
+            loop {
+                match events.recv().await {
+                    Ok(ServerEvent::AuthDenied { client, reason }) => {
+                        println!("⛔ 已拒绝 {}: {}", client, reason);
+                    }
+                    Ok(ServerEvent::MessageRejected { client, reason }) => {
+                        println!("⚠️  已丢弃来自 {} 的消息: {}", client, reason);
+                    }
+                    Ok(_) | Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => {}
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+    }
+    let listener = server
+        .bind()
+        .await
+        .map_err(|e| CliError::Other(e.to_string()))?;
+    let local = listener.local_addr()?;
+    let scheme = server.scheme();
+    tokio::spawn(async move {
+        if let Err(e) = server.serve(listener).await {
+            error!("Local WebSocket server stopped: {}", e);
+        }
+    });
+
+    let (connect_ip, qr_ip) = if local.ip().is_unspecified() {
+        let lan = lan_ip().unwrap_or_else(|| {
+            warn!("Could not determine LAN address, QR code will use 127.0.0.1");
+            IpAddr::V4(Ipv4Addr::LOCALHOST)
+        });
+        (IpAddr::V4(Ipv4Addr::LOCALHOST), lan)
+    } else {
+        (local.ip(), local.ip())
+    };
+
+    let parse = |ip: IpAddr| {
+        ServerAddress::parse(&format!(
+            "{}://{}",
+            scheme,
+            SocketAddr::new(ip, local.port())
+        ))
+        .map_err(|e| CliError::Other(e.to_string()))
+    };
+    let connect = parse(connect_ip)?.accept_invalid_certs(scheme == "wss");
+    Ok((connect, parse(qr_ip)?))
+}
+
+/// 本机局域网 IP（查询到公网的路由所用的本地地址，不发送数据）
+fn lan_ip() -> Option<IpAddr> {
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("8.8.8.8:80").ok()?;
+    socket
+        .local_addr()
+        .ok()
+        .map(|addr| addr.ip())
+        .filter(|ip| !ip.is_loopback() && !ip.is_unspecified())
+}
+
 /// 显示 ASCII 二维码
 fn display_qr_code(url: &str) {
     use qrcode::QrCode;
@@ -183,3 +497,384 @@ fn display_qr_code(url: &str) {
         }
     }
 }
+
+/// 守护进程运行阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum DaemonPhase {
+    /// 扫描 BLE 设备
+    Scanning,
+    /// 连接 WebSocket 服务器并等待绑定
+    Connecting,
+    /// 桥接运行中
+    Running,
+    /// 连接失败，等待重试
+    Retrying,
+    /// 已停止
+    Stopped,
+}
+
+/// BLE 主机状态
+#[derive(Debug, Clone, Serialize)]
+struct UnitStatus {
+    /// 配置的设备名称
+    name: String,
+    /// 扫描到的设备 ID
+    id: Option<String>,
+    /// BLE 是否已连接
+    connected: bool,
+}
+
+/// 守护进程健康状态（写入状态文件）
+#[derive(Debug, Clone, Serialize)]
+struct DaemonStatus {
+    /// 进程 ID
+    pid: u32,
+    /// 运行阶段
+    phase: DaemonPhase,
+    /// WebSocket 服务器
+    server: String,
+    /// 二维码 URL
+    qr_url: Option<String>,
+    /// WebSocket 会话状态
+    bridge: BridgeStatus,
+    /// 各 BLE 主机状态
+    units: Vec<UnitStatus>,
+    /// 最近一次错误
+    last_error: Option<String>,
+    /// 更新时间（Unix 时间戳，秒）
+    updated_at: u64,
+}
+
+/// 守护进程状态报告
+struct StatusReporter {
+    status: DaemonStatus,
+    path: Option<PathBuf>,
+}
+
+impl StatusReporter {
+    fn new(names: &[String], server: &ServerAddress, path: Option<PathBuf>) -> Self {
+        let status = DaemonStatus {
+            pid: std::process::id(),
+            phase: DaemonPhase::Scanning,
+            server: server.to_string(),
+            qr_url: None,
+            bridge: BridgeStatus::default(),
+            units: names
+                .iter()
+                .map(|name| UnitStatus {
+                    name: name.clone(),
+                    id: None,
+                    connected: false,
+                })
+                .collect(),
+            last_error: None,
+            updated_at: 0,
+        };
+        Self { status, path }
+    }
+
+    /// 切换运行阶段并写入状态文件
+    async fn set_phase(&mut self, phase: DaemonPhase) {
+        if self.status.phase != phase {
+            info!("Bridge daemon: {:?}", phase);
+        }
+        self.status.phase = phase;
+        self.write().await;
+    }
+
+    /// 记录错误并写入状态文件
+    async fn set_error(&mut self, err: &CliError) {
+        self.status.last_error = Some(err.to_string());
+        self.status.phase = DaemonPhase::Retrying;
+        self.status.bridge = BridgeStatus::default();
+        self.status.qr_url = None;
+        for unit in &mut self.status.units {
+            unit.connected = false;
+        }
+        self.write().await;
+    }
+
+    /// 写入状态文件（先写临时文件再重命名，避免读到不完整的内容）
+    async fn write(&mut self) {
+        self.status.updated_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+
+        let Some(path) = &self.path else {
+            return;
+        };
+        if let Err(e) = write_status_file(path, &self.status).await {
+            warn!("Failed to write status file {:?}: {}", path, e);
+        }
+    }
+}
+
+async fn write_status_file(path: &Path, status: &DaemonStatus) -> Result<()> {
+    let content = serde_json::to_string_pretty(status)
+        .map_err(|e| CliError::Other(format!("Failed to serialize status: {}", e)))?;
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let tmp = path.with_extension("tmp");
+    tokio::fs::write(&tmp, content).await?;
+    tokio::fs::rename(&tmp, path).await?;
+    Ok(())
+}
+
+/// 以守护进程模式运行桥接
+async fn run_daemon(cli: &mut DglabCli, args: BridgeArgs) -> Result<()> {
+    let config = cli.config();
+    let names = if args.device.is_empty() {
+        config
+            .favorite_devices
+            .iter()
+            .map(|d| d.id.clone())
+            .collect::<Vec<_>>()
+    } else {
+        args.device.clone()
+    };
+    if names.is_empty() {
+        return Err(CliError::InvalidInput(
+            "No device given, use --device or add favorite_devices to the config file".to_string(),
+        ));
+    }
+
+    let server = match &args.server {
+        Some(server) => server.clone().accept_invalid_certs(args.insecure),
+        None => config.server_address()?,
+    };
+    let ble_manager = cli.get_or_init_ble().await?.clone();
+    let interval = Duration::from_secs(args.check_interval.max(1));
+    let policy = config.reconnect.policy();
+
+    info!(
+        "Starting bridge daemon for {:?} via {} (pid {})",
+        names,
+        server,
+        std::process::id()
+    );
+
+    // 监听失败时直接退出，不在无人值守时静默丢失指标
+    let metrics_task = match &args.metrics {
+        Some(bind) => {
+            let listener = MetricsServer::new(bind)?.bind().await?;
+            Some(tokio::spawn(async move {
+                if let Err(e) = metrics::serve(listener, metrics::global()).await {
+                    error!("Metrics endpoint stopped: {}", e);
+                }
+            }))
+        }
+        None => None,
+    };
+
+    let mut reporter = StatusReporter::new(&names, &server, args.status_file.clone());
+    let mut bridge: Option<BleWsBridgeDevice> = None;
+
+    let shutdown = shutdown_signal();
+    tokio::pin!(shutdown);
+
+    loop {
+        let session = run_session(
+            &ble_manager,
+            &names,
+            &server,
+            policy,
+            interval,
+            &mut bridge,
+            &mut reporter,
+        );
+
+        tokio::select! {
+            result = session => {
+                if let Err(e) = result {
+                    error!("Bridge session failed: {}", e);
+                    reporter.set_error(&e).await;
+                }
+                shutdown_bridge(&mut bridge).await;
+
+                tokio::select! {
+                    _ = tokio::time::sleep(interval) => {}
+                    _ = &mut shutdown => break,
+                }
+            }
+            _ = &mut shutdown => break,
+        }
+    }
+
+    info!("Received shutdown signal, zeroing output");
+    shutdown_bridge(&mut bridge).await;
+    reporter.set_phase(DaemonPhase::Stopped).await;
+    if let Some(task) = metrics_task {
+        task.abort();
+    }
+    info!("Bridge daemon stopped");
+    Ok(())
+}
+
+/// 运行一次桥接会话：扫描、连接并持续检查健康状况
+///
+/// 只在出错时返回，WebSocket 断线由客户端自动重连，BLE 断线在此处重连。
+async fn run_session(
+    ble_manager: &Arc<BleManager>,
+    names: &[String],
+    server: &ServerAddress,
+    policy: ReconnectPolicy,
+    interval: Duration,
+    slot: &mut Option<BleWsBridgeDevice>,
+    reporter: &mut StatusReporter,
+) -> Result<()> {
+    // 1. 扫描直到找到所有设备
+    reporter.set_phase(DaemonPhase::Scanning).await;
+    let targets = loop {
+        match scan_targets(ble_manager, names).await {
+            Ok(targets) => break targets,
+            Err(e) => {
+                warn!("{}, rescanning in {:?}", e, interval);
+                tokio::time::sleep(interval).await;
+            }
+        }
+    };
+    for (unit, target) in reporter.status.units.iter_mut().zip(&targets) {
+        unit.id = Some(target.id.clone());
+    }
+
+    // 2. 连接 WebSocket 服务器
+    reporter.set_phase(DaemonPhase::Connecting).await;
+    let mut device = BleWsBridgeDevice::with_units(
+        format!("bridge-{}", targets[0].id),
+        format!("Bridge-{}", targets[0].name),
+        targets
+            .iter()
+            .map(|d| (d.id.clone(), d.name.clone()))
+            .collect(),
+        server.clone(),
+    )?;
+    device.set_reconnect_policy(policy);
+    let bridge = slot.insert(device);
+    bridge.connect().await?;
+    reporter.status.qr_url = bridge.qr_url().await;
+    if let Some(qr_url) = &reporter.status.qr_url {
+        info!("QR URL: {}", qr_url);
+    }
+
+    // 3. 连接 BLE 设备并启动
+    for (unit, target) in targets.iter().enumerate() {
+        let protocol_device = ble_manager.connect(&target.id).await?;
+        bridge.connect_ble_unit(unit, protocol_device).await?;
+    }
+    bridge.start().await?;
+    reporter.status.last_error = None;
+    reporter.set_phase(DaemonPhase::Running).await;
+
+    // 4. 健康检查
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        let _ = ticker.tick().await;
+
+        let status = bridge.status();
+        if status.is_lost() {
+            return Err(CliError::Other("WebSocket connection lost".to_string()));
+        }
+        reporter.status.bridge = status;
+
+        for (unit, target) in targets.iter().enumerate() {
+            let mut connected = bridge.is_unit_connected(unit).await;
+            if !connected {
+                warn!("BLE device {} disconnected, reconnecting", target.name);
+                match reconnect_unit(ble_manager, bridge, unit, target).await {
+                    Ok(()) => {
+                        info!("BLE device {} reconnected", target.name);
+                        connected = true;
+                    }
+                    Err(e) => {
+                        warn!("Failed to reconnect BLE device {}: {}", target.name, e);
+                        reporter.status.last_error = Some(e.to_string());
+                    }
+                }
+            }
+            reporter.status.units[unit].connected = connected;
+        }
+
+        reporter.write().await;
+    }
+}
+
+/// 扫描并按名称匹配所有目标设备
+async fn scan_targets(ble_manager: &BleManager, names: &[String]) -> Result<Vec<ScanResult>> {
+    ble_manager.start_scan().await?;
+    tokio::time::sleep(Duration::from_secs(3)).await;
+    let scan_results = ble_manager.get_scan_results().await?;
+
+    let mut targets: Vec<ScanResult> = Vec::with_capacity(names.len());
+    for name in names {
+        let target = scan_results
+            .iter()
+            .find(|d| {
+                (d.name.contains(name.as_str()) || d.id == *name)
+                    && !targets.iter().any(|t| t.id == d.id)
+            })
+            .ok_or_else(|| CliError::DeviceNotFound(name.clone()))?;
+        info!("Found device: {} ({})", target.name, target.id);
+        targets.push(target.clone());
+    }
+    Ok(targets)
+}
+
+/// 重新连接断开的 BLE 主机（直接连接失败时重新扫描一次）
+async fn reconnect_unit(
+    ble_manager: &BleManager,
+    bridge: &BleWsBridgeDevice,
+    unit: usize,
+    target: &ScanResult,
+) -> Result<()> {
+    let protocol_device = match ble_manager.connect(&target.id).await {
+        Ok(device) => device,
+        Err(e) => {
+            warn!("Direct reconnect failed ({}), rescanning", e);
+            ble_manager.start_scan().await?;
+            tokio::time::sleep(Duration::from_secs(3)).await;
+            ble_manager.connect(&target.id).await?
+        }
+    };
+    bridge.connect_ble_unit(unit, protocol_device).await?;
+    Ok(())
+}
+
+/// 将输出归零并断开桥接设备
+async fn shutdown_bridge(slot: &mut Option<BleWsBridgeDevice>) {
+    let Some(mut bridge) = slot.take() else {
+        return;
+    };
+    if let Err(e) = bridge.zero_output().await {
+        error!("Failed to zero output: {}", e);
+    }
+    if let Err(e) = bridge.stop().await {
+        warn!("Failed to stop bridge: {}", e);
+    }
+    if let Err(e) = bridge.disconnect().await {
+        warn!("Failed to disconnect bridge: {}", e);
+    }
+}
+
+/// 等待 Ctrl+C 或 SIGTERM
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = terminate.recv() => {}
+                }
+                return;
+            }
+            Err(e) => warn!("Failed to install SIGTERM handler: {}", e),
+        }
+    }
+
+    let _ = tokio::signal::ctrl_c().await;
+}
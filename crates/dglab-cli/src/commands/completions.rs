@@ -0,0 +1,95 @@
+//! Shell 补全和 man 手册生成
+//!
+//! `dglab completions <shell>` 输出静态补全脚本，适合打包时安装；
+//! 动态补全（预设名称、常用设备 ID）通过环境变量注册：
+//!
+//! ```bash
+//! source <(COMPLETE=bash dglab)
+//! ```
+//!
+//! 动态补全在补全时同步读取预设目录和配置文件，不初始化会话和蓝牙。
+
+use std::io;
+use std::path::PathBuf;
+
+use clap::{Command, Parser};
+use clap_complete::engine::CompletionCandidate;
+use clap_complete::Shell;
+
+use crate::error::Result;
+use dglab_core::config::{AppConfig, ConfigManager};
+use dglab_core::preset::{Preset, PresetManager};
+
+/// 补全脚本参数
+#[derive(Parser, Debug)]
+pub struct CompletionsArgs {
+    /// 目标 shell
+    shell: Shell,
+}
+
+/// man 手册参数
+#[derive(Parser, Debug)]
+pub struct ManpagesArgs {
+    /// 输出目录（每个子命令生成一个 .1 文件）
+    dir: PathBuf,
+}
+
+/// 输出静态补全脚本到标准输出
+pub fn completions(mut cmd: Command, args: CompletionsArgs) -> Result<()> {
+    let name = cmd.get_name().to_string();
+    clap_complete::generate(args.shell, &mut cmd, name, &mut io::stdout());
+    Ok(())
+}
+
+/// 生成 man 手册
+pub fn manpages(cmd: Command, args: ManpagesArgs) -> Result<()> {
+    std::fs::create_dir_all(&args.dir)?;
+    clap_mangen::generate_to(cmd, &args.dir)?;
+    println!("Man pages written to {}", args.dir.display());
+    Ok(())
+}
+
+/// 补全预设名称
+pub fn preset_candidates() -> Vec<CompletionCandidate> {
+    let Ok(dir) = PresetManager::default_storage_dir() else {
+        return Vec::new();
+    };
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut presets: Vec<Preset> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("json"))
+        .filter_map(|path| std::fs::read_to_string(path).ok())
+        .filter_map(|content| serde_json::from_str(&content).ok())
+        .collect();
+    presets.sort_by(|a, b| a.name.cmp(&b.name));
+
+    presets
+        .into_iter()
+        .map(|preset| {
+            let help = (!preset.description.is_empty()).then(|| preset.description.into());
+            CompletionCandidate::new(preset.name).help(help)
+        })
+        .collect()
+}
+
+/// 补全常用设备 ID（配置文件中的 `[[favorite_devices]]`）
+pub fn device_candidates() -> Vec<CompletionCandidate> {
+    let config = ConfigManager::default_config_path()
+        .ok()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|content| AppConfig::from_toml_str(&content).ok())
+        .unwrap_or_default();
+
+    config
+        .favorite_devices
+        .into_iter()
+        .map(|device| {
+            let help = (!device.name.is_empty()).then(|| device.name.into());
+            CompletionCandidate::new(device.id).help(help)
+        })
+        .collect()
+}
@@ -0,0 +1,221 @@
+//! 配置文件命令
+//!
+//! `dglab config get/set/list` 按点分路径（如 `safety.max_power_a`）读写配置项，
+//! `dglab config edit` 用 `$VISUAL`/`$EDITOR` 打开配置文件。写入前都会校验配置并显示改动，
+//! 无效的值不会写入文件，配置文件中的注释和格式保持不变。
+
+use std::io::{self, Write};
+use std::path::Path;
+use std::process::Command;
+
+use clap::Parser;
+
+use dglab_core::config::keys::set_toml_value;
+use dglab_core::config::{AppConfig, ConfigManager};
+
+use crate::error::{CliError, Result};
+
+/// 配置文件子命令
+#[derive(Parser, Debug)]
+pub struct ConfigArgs {
+    #[command(subcommand)]
+    command: ConfigCommand,
+}
+
+/// 配置子命令
+#[derive(Parser, Debug)]
+enum ConfigCommand {
+    /// 显示配置文件路径
+    Path,
+    /// 列出所有配置项（包括默认值）
+    List,
+    /// 读取配置项
+    Get {
+        /// 配置项路径（如 safety.max_power_a）
+        key: String,
+    },
+    /// 修改配置项
+    Set {
+        /// 配置项路径（如 safety.max_power_a）
+        key: String,
+        /// 新的值（TOML 语法，无法解析时视为字符串）
+        value: String,
+    },
+    /// 用编辑器打开配置文件
+    Edit {
+        /// 不确认直接写入
+        #[arg(short, long)]
+        yes: bool,
+    },
+}
+
+/// 执行配置命令（不需要会话，配置文件无效时也可以编辑）
+pub async fn execute(args: ConfigArgs) -> Result<()> {
+    let manager = ConfigManager::default_path()?;
+
+    match args.command {
+        ConfigCommand::Path => {
+            println!("{}", manager.path().display());
+        }
+        ConfigCommand::List => {
+            manager.load().await?;
+            for (key, value) in manager.config().entries()? {
+                println!("{} = {}", key, value);
+            }
+        }
+        ConfigCommand::Get { key } => {
+            manager.load().await?;
+            println!("{}", manager.config().get_value(&key)?);
+        }
+        ConfigCommand::Set { key, value } => {
+            manager.load().await?;
+            let original = if manager.path().exists() {
+                tokio::fs::read_to_string(manager.path()).await?
+            } else {
+                String::new()
+            };
+            let (updated, content) = set_toml_value(&original, &key, &value)?;
+            if updated == manager.config() {
+                println!("{} is already {}", key, updated.get_value(&key)?);
+                return Ok(());
+            }
+
+            print_diff(&original, &content);
+            let _ = manager.set_toml(&content).await?;
+            println!("Saved {}", manager.path().display());
+        }
+        ConfigCommand::Edit { yes } => edit(&manager, yes).await?,
+    }
+
+    Ok(())
+}
+
+/// 在编辑器中修改配置文件，校验通过并确认后写入
+async fn edit(manager: &ConfigManager, yes: bool) -> Result<()> {
+    let original = if manager.path().exists() {
+        tokio::fs::read_to_string(manager.path()).await?
+    } else {
+        AppConfig::default().to_toml_string()?
+    };
+
+    // 在临时文件中编辑，取消或校验失败时不影响原文件；临时文件在离开作用域时删除
+    let mut temp = tempfile::Builder::new()
+        .prefix("dglab-config-")
+        .suffix(".toml")
+        .tempfile()?;
+    temp.write_all(original.as_bytes())?;
+    temp.flush()?;
+
+    let Some(content) = edit_until_valid(temp.path(), &original)? else {
+        println!("No changes");
+        return Ok(());
+    };
+
+    print_diff(&original, &content);
+    if !yes && !confirm("Write changes?")? {
+        println!("Discarded");
+        return Ok(());
+    }
+
+    let _ = manager.set_toml(&content).await?;
+    println!("Saved {}", manager.path().display());
+    Ok(())
+}
+
+/// 反复打开编辑器直到内容有效，未修改或放弃时返回 `None`
+fn edit_until_valid(temp: &Path, original: &str) -> Result<Option<String>> {
+    loop {
+        open_editor(temp)?;
+        let content = std::fs::read_to_string(temp)?;
+        if content == original {
+            return Ok(None);
+        }
+
+        match AppConfig::from_toml_str(&content) {
+            Ok(_) => return Ok(Some(content)),
+            Err(e) => {
+                eprintln!("{}", e);
+                if !confirm("Edit again?")? {
+                    return Ok(None);
+                }
+            }
+        }
+    }
+}
+
+/// 打开编辑器并等待退出
+fn open_editor(path: &Path) -> Result<()> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .ok()
+        .filter(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| default_editor().to_string());
+
+    // 编辑器可以带参数，如 `code --wait`
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or(default_editor());
+    let status = Command::new(program)
+        .args(parts)
+        .arg(path)
+        .status()
+        .map_err(|e| CliError::InvalidInput(format!("Failed to run editor '{}': {}", editor, e)))?;
+
+    if !status.success() {
+        return Err(CliError::InvalidInput(format!(
+            "Editor '{}' exited with {}",
+            editor, status
+        )));
+    }
+    Ok(())
+}
+
+/// 未设置 `$VISUAL`/`$EDITOR` 时使用的编辑器
+fn default_editor() -> &'static str {
+    if cfg!(windows) {
+        "notepad"
+    } else {
+        "vi"
+    }
+}
+
+/// 询问用户，输入 y/yes 时返回 true
+fn confirm(question: &str) -> Result<bool> {
+    print!("{} [y/N]: ", question);
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    let _ = io::stdin().read_line(&mut input)?;
+    Ok(matches!(input.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// 按行显示改动（`-` 删除，`+` 新增，未改动的行不显示）
+fn print_diff(old: &str, new: &str) {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // 最长公共子序列
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            println!("- {}", old[i]);
+            i += 1;
+        } else {
+            println!("+ {}", new[j]);
+            j += 1;
+        }
+    }
+}
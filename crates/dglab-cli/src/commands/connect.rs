@@ -1,15 +1,27 @@
 //! 连接设备命令
 
+use std::time::Duration;
+
 use clap::Parser;
+use clap_complete::engine::ArgValueCandidates;
 use tracing::info;
 
+use super::completions::device_candidates;
 use super::DglabCli;
-use dglab_core::device::{CoyoteDevice, Device};
+use crate::progress::Progress;
+use dglab_core::device::simulator::{is_simulated_id, simulated_devices, SIMULATED_DEVICE_PREFIX};
+use dglab_core::device::{CoyoteDevice, Device, DeviceConfig, MockCoyoteDevice, DRY_RUN_DEVICE_ID};
+use dglab_protocol::ble::{ConnectionPriority, LinkOptions, WriteMode};
+use dglab_protocol::error::ProtocolError;
+
+/// 查找设备时的扫描时长
+const SCAN_DURATION: Duration = Duration::from_secs(3);
 
 /// 连接设备参数
 #[derive(Parser, Debug)]
 pub struct ConnectArgs {
     /// 设备 ID
+    #[arg(add = ArgValueCandidates::new(device_candidates))]
     device_id: Option<String>,
 
     /// 设备名称（模糊匹配）
@@ -19,6 +31,26 @@ pub struct ConnectArgs {
     /// 断开连接
     #[arg(short, long)]
     disconnect: bool,
+
+    /// 连接模拟设备（不使用蓝牙）
+    #[arg(long)]
+    simulated: bool,
+
+    /// BLE 写入方式（without-response / with-response），容易丢帧的适配器可改用 with-response
+    #[arg(long)]
+    write_mode: Option<WriteMode>,
+
+    /// B0 发送间隔（毫秒，50~250，默认 100）
+    #[arg(long, value_name = "MS")]
+    b0_interval: Option<u64>,
+
+    /// 连接优先级（balanced / high / low-power，默认 balanced）
+    #[arg(long)]
+    priority: Option<ConnectionPriority>,
+
+    /// high 优先级未能生效时改用有响应写入（仅 Linux）
+    #[arg(long, conflicts_with = "write_mode")]
+    write_fallback: bool,
 }
 
 /// 执行连接命令
@@ -30,24 +62,50 @@ pub async fn execute(app: &mut DglabCli, args: ConnectArgs) -> crate::error::Res
             println!("Disconnected from device: {}", device_id);
         } else {
             info!("Disconnecting all devices");
-            app.session_manager().disconnect_all().await?;
-            println!("Disconnected from all devices");
+            for (device_id, result) in app.session_manager().disconnect_all().await {
+                match result {
+                    Ok(()) => println!("Disconnected from device: {}", device_id),
+                    Err(e) => println!("Failed to disconnect {}: {}", device_id, e),
+                }
+            }
         }
         return Ok(());
     }
 
-    // 先扫描获取设备列表
-    info!("Scanning for devices...");
-
-    let ble_manager = app
-        .ble_manager()
-        .expect("BLE manager should be initialized");
-
-    ble_manager.start_scan().await?;
-    tokio::time::sleep(std::time::Duration::from_secs(3)).await;
-    ble_manager.stop_scan().await?;
+    if app.dry_run().is_some() {
+        println!(
+            "Dry run: using device '{}', no BLE connection is made",
+            DRY_RUN_DEVICE_ID
+        );
+        return Ok(());
+    }
 
-    let results = ble_manager.get_scan_results().await?;
+    // 先扫描获取设备列表（模拟设备 ID 可直接连接）
+    let simulated = args.simulated || args.device_id.as_deref().is_some_and(is_simulated_id);
+    let results = if simulated {
+        // 至少包含指定的模拟设备
+        let count = args
+            .device_id
+            .as_deref()
+            .and_then(|id| id.strip_prefix(SIMULATED_DEVICE_PREFIX))
+            .and_then(|n| n.parse().ok())
+            .unwrap_or(1);
+        simulated_devices(count)
+    } else {
+        info!("Scanning for devices...");
+
+        let ble_manager = app.get_or_init_ble().await?;
+
+        let progress = Progress::spinner("Scanning for devices (Ctrl-C to cancel)...");
+        match ble_manager.scan_for(SCAN_DURATION, progress.token()).await {
+            Err(ProtocolError::Cancelled) => {
+                drop(progress);
+                println!("Scan cancelled");
+                return Ok(());
+            }
+            result => result?,
+        }
+    };
 
     if results.is_empty() {
         println!("No devices found");
@@ -97,16 +155,66 @@ pub async fn execute(app: &mut DglabCli, args: ConnectArgs) -> crate::error::Res
         device_info.name, device_info.id
     );
 
-    // 连接设备
-    let device = ble_manager.connect(&device_info.id).await?;
-    let mut coyote = CoyoteDevice::new(device_info.id.clone(), device_info.name.clone());
-    coyote.set_protocol_device(device);
-    coyote.connect().await?;
+    // 连接设备，Ctrl-C 时断开已建立的连接
+    let progress = Progress::spinner(format!(
+        "Connecting to {} (Ctrl-C to cancel)...",
+        device_info.name
+    ));
+    let device: Box<dyn Device> = if simulated {
+        let mut mock = MockCoyoteDevice::new(device_info.id.clone(), device_info.name.clone());
+        mock.connect().await?;
+        Box::new(mock)
+    } else {
+        // 先按配置创建设备，输出间隔无效时不必建立连接
+        let mut coyote = CoyoteDevice::from_config(&DeviceConfig {
+            id: device_info.id.clone(),
+            name: device_info.name.clone(),
+            connection_type: "ble".to_string(),
+            address: None,
+            auto_reconnect: false,
+            safety_limit: None,
+            output_interval_ms: args.b0_interval,
+        })?;
+        let ble_manager = app.get_or_init_ble().await?;
+        ble_manager.set_link_options(LinkOptions {
+            priority: args.priority.unwrap_or_default(),
+            write_fallback: args.write_fallback,
+        });
+        let device = match ble_manager
+            .connect_cancellable(&device_info.id, progress.token())
+            .await
+        {
+            Err(ProtocolError::Cancelled) => {
+                drop(progress);
+                println!("Connection cancelled");
+                return Ok(());
+            }
+            result => result?,
+        };
+        if let Some(mode) = args.write_mode {
+            device.set_write_mode(mode);
+        }
+        info!("Link: {}", device.link_params());
+        coyote.set_protocol_device(device);
+        // 读取设备信息和启动后台任务
+        progress.set_message(format!("Initializing {}...", device_info.name));
+        let Some(result) = progress.run(coyote.connect()).await else {
+            drop(progress);
+            let _ = coyote.disconnect().await;
+            println!("Connection cancelled");
+            return Ok(());
+        };
+        result?;
+        Box::new(coyote)
+    };
 
     // 添加到会话管理器
-    app.session_manager().add_device(Box::new(coyote)).await?;
+    app.session_manager().add_device(device).await?;
 
-    println!("Connected to: {} ({})", device_info.name, device_info.id);
+    progress.finish(format!(
+        "Connected to: {} ({})",
+        device_info.name, device_info.id
+    ));
 
     Ok(())
 }
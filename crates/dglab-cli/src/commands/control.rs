@@ -1,17 +1,40 @@
 //! 控制设备命令
 
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
 use clap::Parser;
-use tracing::{debug, info};
+use clap_complete::engine::ArgValueCandidates;
+use dglab_core::config::AppConfig;
+use dglab_core::device::traits::DeviceInfo;
+use dglab_core::device::{
+    ChannelLink, Device, DeviceLimits, Easing, LatencyStats, PowerCurve, MAX_PERCENT,
+};
+use dglab_core::gamepad::{AxisTarget, GamepadController, GamepadInput, GamepadMapping};
+use dglab_core::sensor::{spawn_listener, SensorController, SensorInput, SensorMapping};
+use dglab_core::session::{DeviceStats, SessionManager};
+use dglab_core::tempo::{TempoClock, TempoPattern, TempoSource};
+use dglab_core::waveform::stereo::DEFAULT_PHASE_OFFSET;
+use dglab_core::waveform::{StereoMode, StereoOutput, StereoPattern};
+use tokio::sync::{broadcast, RwLock};
+use tracing::{info, warn};
 
+use super::completions::device_candidates;
 use super::DglabCli;
+use crate::error::CliError;
+
+/// 等待渐变结束时的检查间隔
+const RAMP_POLL_INTERVAL: Duration = Duration::from_millis(50);
 
 /// 控制设备参数
 #[derive(Parser, Debug)]
 pub struct ControlArgs {
     /// 设备 ID（如果不指定，使用第一个设备）
+    #[arg(add = ArgValueCandidates::new(device_candidates))]
     device_id: Option<String>,
 
-    /// 通道 A 强度
+    /// 通道 A 强度（设备已校准时为百分比 0~100）
     #[arg(long = "a")]
     power_a: Option<u8>,
 
@@ -23,6 +46,22 @@ pub struct ControlArgs {
     #[arg(short, long)]
     power: Option<u8>,
 
+    /// 开启通道联动：设置 A 通道时按 B = A × RATIO + OFFSET 同步 B 通道
+    #[arg(long, value_name = "RATIO[:OFFSET]", conflicts_with = "unlink")]
+    link: Option<ChannelLink>,
+
+    /// 关闭通道联动
+    #[arg(long)]
+    unlink: bool,
+
+    /// 禁用通道（a / b）：强度归零、波形静默，之后的强度指令不再生效
+    #[arg(long, value_name = "CHANNEL", conflicts_with = "enable")]
+    disable: Option<String>,
+
+    /// 重新启用通道（a / b）
+    #[arg(long, value_name = "CHANNEL")]
+    enable: Option<String>,
+
     /// 开始输出
     #[arg(long)]
     start: bool,
@@ -34,10 +73,148 @@ pub struct ControlArgs {
     /// 显示设备状态
     #[arg(short, long)]
     status: bool,
+
+    /// 进入交互模式（REPL）
+    #[arg(short, long)]
+    interactive: bool,
+
+    /// 用手柄控制设备，Ctrl+C 退出
+    #[arg(long, conflicts_with = "interactive")]
+    gamepad: bool,
+
+    /// 手柄映射文件（默认 ~/.config/dglab/gamepad.json，不存在时使用默认映射）
+    #[arg(long, value_name = "FILE", requires = "gamepad")]
+    gamepad_mapping: Option<PathBuf>,
+
+    /// 用无线传感器（47L120100）控制设备，可指定传感器 ID，默认使用扫描到的第一个传感器，Ctrl+C 退出
+    #[arg(
+        long,
+        value_name = "SENSOR_ID",
+        num_args = 0..=1,
+        default_missing_value = "",
+        conflicts_with_all = ["interactive", "gamepad"]
+    )]
+    sensor: Option<String>,
+
+    /// 传感器映射（输入:目标:通道[:下限:上限]，如 motion:power:a，可重复；默认 analog:power:a）
+    #[arg(long, value_name = "SPEC", requires = "sensor")]
+    sensor_map: Vec<SensorMapping>,
+
+    #[command(subcommand)]
+    command: Option<ControlCommand>,
+}
+
+/// 控制子命令
+#[derive(Parser, Debug)]
+enum ControlCommand {
+    /// 查看或设置输出限制（V3 BF 软上限与平衡参数），不带参数时显示当前值
+    Limits {
+        /// A 通道强度软上限 (0~200)
+        #[arg(long = "a")]
+        soft_limit_a: Option<u8>,
+        /// B 通道强度软上限 (0~200)
+        #[arg(long = "b")]
+        soft_limit_b: Option<u8>,
+        /// 波形频率平衡参数 (0~255)
+        #[arg(long)]
+        freq_balance: Option<u8>,
+        /// 波形强度平衡参数 (0~255)
+        #[arg(long)]
+        intensity_balance: Option<u8>,
+    },
+    /// 显示本次会话的统计（各强度停留时长、峰值、平均强度、紧急停止次数）
+    Stats,
+    /// 测量 B1 往返时延：发送带序列号的 B0，等待设备以同序号回应（仅 V3 BLE 设备）
+    Ping {
+        /// 探测次数
+        #[arg(short, long, default_value_t = 10)]
+        count: u32,
+        /// 探测间隔（毫秒），超过该时间未收到回应视为超时
+        #[arg(long, default_value_t = 200, value_name = "MS")]
+        interval: u64,
+    },
+    /// 在指定时长内把强度平滑渐变到目标值，Ctrl+C 取消（强度停留在当前值）
+    Ramp {
+        /// 通道 (a / b / both)
+        channel: String,
+        /// 目标强度（设备已校准时为百分比 0~100）
+        target: u8,
+        /// 渐变时长（毫秒）
+        #[arg(long, default_value_t = 2000, value_name = "MS")]
+        duration: u64,
+        /// 缓动曲线 (linear / ease-in / ease-out / ease-in-out)
+        #[arg(short, long, default_value_t = Easing::Linear)]
+        easing: Easing,
+    },
+    /// 按节拍播放波形：周期锁定到节拍，每隔若干小节切换到下一个波形，Ctrl+C 停止
+    Tempo {
+        /// 波形名称（内置或波形库），按顺序循环
+        #[arg(required = true)]
+        waveforms: Vec<String>,
+        /// 通道 (a / b / both)
+        #[arg(short, long, default_value = "both")]
+        channel: String,
+        /// 节拍来源（internal / midi / link），默认使用配置文件 [tempo] 段
+        #[arg(long)]
+        source: Option<TempoSource>,
+        /// 内部时钟的 BPM（外部时钟到达前也使用该值）
+        #[arg(long)]
+        bpm: Option<u16>,
+        /// MIDI 输入端口（名称部分匹配）
+        #[arg(long, value_name = "NAME")]
+        midi_port: Option<String>,
+        /// 波形周期（拍）
+        #[arg(long, default_value_t = 1.0)]
+        beats: f64,
+        /// 每个波形播放的小节数
+        #[arg(long, default_value_t = 4)]
+        bars: u32,
+    },
+    /// 立体声编排：两台设备（或同一设备的 A/B 通道）交替或追逐输出，Ctrl+C 停止
+    Stereo {
+        /// 模式 (ping-pong / rotate / chase)
+        mode: StereoMode,
+        /// 峰值强度（设备已校准时为百分比 0~100）
+        power: u8,
+        /// 周期（毫秒）
+        #[arg(long, default_value_t = 2000, value_name = "MS")]
+        period: u32,
+        /// 右路落后左路的相位（度）
+        #[arg(long, default_value_t = DEFAULT_PHASE_OFFSET, value_name = "DEGREES")]
+        phase: u16,
+        /// 交叉淡化（0~100%）
+        #[arg(long, default_value_t = 0, value_name = "PERCENT")]
+        crossfade: u8,
+        /// 作为右路的第二台设备（两台设备各自的两个通道同时输出），不指定时 A 通道为左路、B 通道为右路
+        #[arg(long, value_name = "DEVICE", add = ArgValueCandidates::new(device_candidates))]
+        with: Option<String>,
+        /// 两路使用的波形名称（内置或波形库）
+        #[arg(short, long)]
+        waveform: Option<String>,
+    },
+    /// 引导式强度校准：逐级调整到合适的体感，保存为逻辑 0~100% 到原始强度的映射
+    Calibrate {
+        /// 校准时输出的通道 (a / b)
+        #[arg(default_value = "a")]
+        channel: String,
+        /// 校准的百分比级别（逗号分隔，最后一级须为 100）
+        #[arg(long, value_delimiter = ',', default_values_t = [25, 50, 75, 100])]
+        levels: Vec<u8>,
+        /// 显示当前校准曲线
+        #[arg(long, conflicts_with = "clear")]
+        show: bool,
+        /// 清除校准，恢复使用原始强度
+        #[arg(long)]
+        clear: bool,
+    },
 }
 
 /// 执行控制命令
 pub async fn execute(app: &mut DglabCli, args: ControlArgs) -> crate::error::Result<()> {
+    if args.interactive {
+        return super::repl::run(app, args.device_id).await;
+    }
+
     // 获取设备
     let device_ids = app.session_manager().list_devices().await;
 
@@ -53,8 +230,284 @@ pub async fn execute(app: &mut DglabCli, args: ControlArgs) -> crate::error::Res
         return Ok(());
     };
 
+    let config = app.config();
+    let name = device.read().await.name().to_string();
+    let calibration = app.session_manager().calibration(&device_id, &name);
+
+    if args.gamepad {
+        return run_gamepad(app, &device_id, args.gamepad_mapping.as_deref()).await;
+    }
+    if let Some(sensor_id) = args.sensor {
+        return run_sensor(app, &device_id, &sensor_id, args.sensor_map).await;
+    }
+
+    if let Some(ControlCommand::Stats) = &args.command {
+        match app.session_manager().device_stats(&device_id) {
+            Some(stats) => print_stats(&stats),
+            None => println!("No stats for device {}", device_id),
+        }
+        return Ok(());
+    }
+
+    if let Some(ControlCommand::Ping { count, interval }) = &args.command {
+        return ping(&device, *count, Duration::from_millis(*interval)).await;
+    }
+
+    if let Some(ControlCommand::Calibrate {
+        channel,
+        levels,
+        show,
+        clear,
+    }) = &args.command
+    {
+        if *show {
+            match &calibration {
+                Some(curve) => println!("Calibration for {}: {}", device_id, curve),
+                None => println!("Device {} is not calibrated", device_id),
+            }
+            return Ok(());
+        }
+        if *clear {
+            let mut config = config;
+            config.set_calibration(&device_id, None);
+            config.set_calibration(&name, None);
+            app.config_manager().set(config).await?;
+            app.session_manager().set_calibration(&device_id, None);
+            app.session_manager().set_calibration(&name, None);
+            println!("Calibration cleared for {}", device_id);
+            return Ok(());
+        }
+
+        let channel = super::repl::parse_channel(channel, false)
+            .map_err(CliError::InvalidInput)?
+            .unwrap_or(0);
+        let curve = calibrate(app, &device_id, channel, levels, &config).await?;
+        let Some(curve) = curve else {
+            println!("Calibration aborted, nothing saved");
+            return Ok(());
+        };
+
+        let mut config = config;
+        config.set_calibration(&device_id, Some(curve.clone()));
+        app.config_manager().set(config).await?;
+        app.session_manager()
+            .set_calibration(&device_id, Some(curve.clone()));
+        println!("Calibration saved for {}: {}", device_id, curve);
+        return Ok(());
+    }
+
+    // 渐变在会话后台任务中逐步获取写锁，不能在此之前持有设备
+    if let Some(ControlCommand::Ramp {
+        channel,
+        target,
+        duration,
+        easing,
+    }) = &args.command
+    {
+        let channels =
+            match super::repl::parse_channel(channel, true).map_err(CliError::InvalidInput)? {
+                Some(channel) => vec![channel],
+                None => vec![0, 1],
+            };
+        let duration = Duration::from_millis(*duration);
+        let session = app.session_manager();
+        for &channel in &channels {
+            session
+                .start_ramp(&device_id, channel, *target, duration, *easing, |_| {})
+                .await?;
+        }
+
+        let ctrl_c = tokio::signal::ctrl_c();
+        tokio::pin!(ctrl_c);
+        while channels.iter().any(|&c| session.is_ramping(&device_id, c)) {
+            tokio::select! {
+                _ = tokio::time::sleep(RAMP_POLL_INTERVAL) => {}
+                _ = &mut ctrl_c => {
+                    for &channel in &channels {
+                        let _ = session.cancel_ramp(&device_id, channel);
+                    }
+                    println!("Ramp cancelled");
+                    break;
+                }
+            }
+        }
+
+        let dev = device.read().await;
+        println!("Channels now A={} B={}", dev.get_power(0), dev.get_power(1));
+        return Ok(());
+    }
+
+    if let Some(ControlCommand::Tempo {
+        waveforms,
+        channel,
+        source,
+        bpm,
+        midi_port,
+        beats,
+        bars,
+    }) = args.command
+    {
+        let channels =
+            match super::repl::parse_channel(&channel, true).map_err(CliError::InvalidInput)? {
+                Some(channel) => vec![channel],
+                None => vec![0, 1],
+            };
+        let mut tempo = config.tempo.clone();
+        if let Some(source) = source {
+            tempo.source = source;
+        }
+        if let Some(bpm) = bpm {
+            tempo.bpm = bpm;
+        }
+        if midi_port.is_some() {
+            tempo.midi_port = midi_port;
+        }
+
+        let session = app.session_manager();
+        let mut resolved = Vec::with_capacity(waveforms.len());
+        for name in &waveforms {
+            resolved.push(session.resolve_waveform(name).await?);
+        }
+        let pattern = TempoPattern {
+            waveforms: resolved,
+            beats_per_cycle: beats,
+            bars_per_waveform: bars,
+            beats_per_bar: u32::from(tempo.beats_per_bar),
+        };
+        pattern.validate()?;
+
+        let clock = TempoClock::start(&tempo)?;
+        println!(
+            "Following {} tempo ({} BPM), {} waveform(s) every {} bar(s), press Ctrl+C to stop",
+            clock.source(),
+            clock.state().bpm.round(),
+            waveforms.len(),
+            bars
+        );
+        session.start(&device_id).await?;
+
+        let result = tokio::select! {
+            result = pattern.run(session, &device_id, &channels, clock.subscribe()) => {
+                result.map_err(CliError::from)
+            }
+            _ = tokio::signal::ctrl_c() => Ok(()),
+        };
+        session.stop(&device_id).await?;
+        println!("Stopped");
+        return result;
+    }
+
+    if let Some(ControlCommand::Stereo {
+        mode,
+        power,
+        period,
+        phase,
+        crossfade,
+        with,
+        waveform,
+    }) = args.command
+    {
+        let pattern = StereoPattern {
+            mode,
+            period_ms: period,
+            phase_offset: phase,
+            crossfade,
+        };
+        pattern.validate()?;
+
+        let session = app.session_manager();
+        let outputs = match &with {
+            Some(other) if *other == device_id => {
+                return Err(CliError::InvalidInput(
+                    "--with needs a different device".to_string(),
+                ));
+            }
+            Some(other) => {
+                if session.get_device(other).await.is_none() {
+                    return Err(CliError::DeviceNotFound(other.clone()));
+                }
+                [
+                    StereoOutput::device(&device_id),
+                    StereoOutput::device(other),
+                ]
+            }
+            None => StereoOutput::split(&device_id),
+        };
+        let devices: Vec<&str> = match &with {
+            Some(other) => vec![device_id.as_str(), other.as_str()],
+            None => vec![device_id.as_str()],
+        };
+
+        if let Some(name) = &waveform {
+            let config = session.resolve_waveform(name).await?.to_device_config();
+            for output in &outputs {
+                for &channel in output.channels() {
+                    session
+                        .set_waveform(&output.device_id, channel, config.clone())
+                        .await?;
+                }
+            }
+        }
+
+        println!(
+            "Playing {} on {} / {} (peak {}, period {}ms), press Ctrl+C to stop",
+            mode, outputs[0], outputs[1], power, period
+        );
+        for id in &devices {
+            session.start(id).await?;
+        }
+
+        let result = tokio::select! {
+            result = pattern.run(session, &outputs, power) => result.map_err(CliError::from),
+            _ = tokio::signal::ctrl_c() => Ok(()),
+        };
+        // 所有设备都要归零并停止输出，清理失败只记录日志，不影响其余设备
+        for id in &devices {
+            for channel in 0..2 {
+                if let Err(e) = session.set_power(id, channel, 0).await {
+                    warn!("Failed to zero channel {} of {}: {}", channel, id, e);
+                }
+            }
+            if let Err(e) = session.stop(id).await {
+                warn!("Failed to stop {}: {}", id, e);
+            }
+        }
+        println!("Stopped");
+        return result;
+    }
+
     let mut dev = device.write().await;
 
+    if let Some(ControlCommand::Limits {
+        soft_limit_a,
+        soft_limit_b,
+        freq_balance,
+        intensity_balance,
+    }) = args.command
+    {
+        let current = dev.limits();
+        if soft_limit_a.is_none()
+            && soft_limit_b.is_none()
+            && freq_balance.is_none()
+            && intensity_balance.is_none()
+        {
+            print_limits(&current);
+            return Ok(());
+        }
+
+        // 未指定的参数保持当前值
+        let limits = DeviceLimits::new(
+            soft_limit_a.unwrap_or(current.soft_limit_a),
+            soft_limit_b.unwrap_or(current.soft_limit_b),
+            freq_balance.unwrap_or(current.freq_balance),
+            intensity_balance.unwrap_or(current.intensity_balance),
+        );
+        info!("Setting device limits: {:?}", limits);
+        dev.set_limits(limits).await?;
+        print_limits(&dev.limits());
+        return Ok(());
+    }
+
     if args.status {
         let info = dev.info();
         println!("\nDevice Status:");
@@ -62,43 +515,478 @@ pub async fn execute(app: &mut DglabCli, args: ControlArgs) -> crate::error::Res
         println!("ID:      {}", info.id);
         println!("Name:    {}", info.name);
         println!("State:   {:?}", dev.state());
-        println!("Power A: {} / {}", info.power_a, info.max_power_a);
-        println!("Power B: {} / {}", info.power_b, info.max_power_b);
+        print_power(&info);
+        match dev.channel_link() {
+            Some(link) => println!("Link:    {}", link),
+            None => println!("Link:    off"),
+        }
+        if let Some(curve) = &calibration {
+            println!("Calib:   {}", curve);
+        }
         println!("Battery: {}%", info.battery_level);
+        if let Some(params) = dev.link_params() {
+            println!("BLE:     {}", params);
+        }
+        if let Some(stats) = dev.latency() {
+            println!(
+                "Latency: {:.1} ms (jitter {:.1} ms)",
+                as_ms(stats.mean),
+                as_ms(stats.jitter)
+            );
+        }
+        if let Some(errors) = dev.error_stats().filter(|e| e.errors > 0) {
+            println!(
+                "Errors:  {} ({} coalesced)",
+                errors.errors, errors.suppressed
+            );
+            if let Some(last) = &errors.last_error {
+                println!("         last: {}", last);
+            }
+        }
+        print_versions(&info);
         return Ok(());
     }
 
+    // 先更新通道启用状态，禁用的通道不受后续强度设置影响
+    for (channel, enabled) in [(&args.disable, false), (&args.enable, true)] {
+        let Some(channel) = channel else {
+            continue;
+        };
+        let channel = super::repl::parse_channel(channel, false)
+            .map_err(CliError::InvalidInput)?
+            .unwrap_or_default();
+        dev.set_channel_enabled(channel, enabled).await?;
+        println!(
+            "Channel {} {}",
+            if channel == 0 { "A" } else { "B" },
+            if enabled { "enabled" } else { "disabled" }
+        );
+    }
+
+    // 以下操作经会话执行，由会话统一换算校准并按安全限制截断
+    drop(dev);
+    let session = app.session_manager();
+
+    // 先更新联动配置，后续设置 A 通道时按新规则同步 B 通道
+    if let Some(link) = args.link {
+        info!("Enabling channel link: {:?}", link);
+        session.set_channel_link(&device_id, Some(link)).await?;
+        println!("Channel link enabled: {}", link);
+    } else if args.unlink {
+        info!("Disabling channel link");
+        session.set_channel_link(&device_id, None).await?;
+        println!("Channel link disabled");
+    }
+
     if args.start {
-        info!("Starting device output");
-        dev.start().await?;
+        session.start(&device_id).await?;
         println!("Device output started");
     }
 
     if args.stop {
-        info!("Stopping device output");
-        dev.stop().await?;
+        session.stop(&device_id).await?;
         println!("Device output stopped");
     }
 
-    // 设置强度
     if let Some(power) = args.power {
-        debug!("Setting both channels to {}", power);
-        dev.set_power(0, power).await?;
-        dev.set_power(1, power).await?;
-        println!("Set both channels to {}", power);
+        let power_a = session.set_power(&device_id, 0, power).await?;
+        let power_b = session.set_power(&device_id, 1, power).await?;
+        println!("Set channels to A={} B={}", power_a, power_b);
     } else {
         if let Some(power) = args.power_a {
-            debug!("Setting channel A to {}", power);
-            dev.set_power(0, power).await?;
+            let power = session.set_power(&device_id, 0, power).await?;
             println!("Set channel A to {}", power);
         }
 
         if let Some(power) = args.power_b {
-            debug!("Setting channel B to {}", power);
-            dev.set_power(1, power).await?;
+            let power = session.set_power(&device_id, 1, power).await?;
             println!("Set channel B to {}", power);
         }
     }
 
     Ok(())
 }
+
+/// 手柄控制：读取手柄输入并作用于设备，直到 Ctrl+C
+async fn run_gamepad(
+    app: &DglabCli,
+    device_id: &str,
+    mapping_path: Option<&Path>,
+) -> crate::error::Result<()> {
+    let path = match mapping_path {
+        Some(path) => path.to_path_buf(),
+        None => GamepadMapping::default_path()?,
+    };
+    let mapping = GamepadMapping::load(&path).await?;
+
+    // 按配置文件的安全限制收紧设备上限，轴和按键的输出都不会超过
+    limit_by_config(app, device_id).await?;
+
+    let mut input = GamepadInput::start()?;
+    let mut controller = GamepadController::new(mapping);
+    println!(
+        "Gamepad control active on {} (mapping: {}), press Ctrl+C to stop",
+        device_id,
+        path.display()
+    );
+
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+    loop {
+        tokio::select! {
+            event = input.next() => {
+                let Some(event) = event else {
+                    break;
+                };
+                if let Err(e) = controller
+                    .handle(app.session_manager(), app.preset_manager(), device_id, event)
+                    .await
+                {
+                    warn!("Gamepad action failed: {}", e);
+                }
+            }
+            _ = &mut ctrl_c => break,
+        }
+    }
+
+    println!("Gamepad control stopped");
+    Ok(())
+}
+
+/// 按配置文件的安全限制收紧设备的通道上限
+async fn limit_by_config(app: &DglabCli, device_id: &str) -> crate::error::Result<()> {
+    let config = app.config();
+    if let Some(device) = app.session_manager().get_device(device_id).await {
+        let mut dev = device.write().await;
+        let info = dev.info();
+        for (channel, max_power) in [(0, info.max_power_a), (1, info.max_power_b)] {
+            let limit = config.clamp_power(channel, max_power);
+            if limit < max_power {
+                info!("Limiting channel {} to {} by config", channel, limit);
+                dev.set_max_power(channel, limit).await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 传感器控制：读取无线传感器的读数并按映射作用于设备，直到 Ctrl+C 或传感器断开
+async fn run_sensor(
+    app: &mut DglabCli,
+    device_id: &str,
+    sensor_id: &str,
+    mappings: Vec<SensorMapping>,
+) -> crate::error::Result<()> {
+    let mappings = if mappings.is_empty() {
+        vec![SensorMapping::new(
+            SensorInput::Analog,
+            AxisTarget::Power { channel: 0 },
+        )]
+    } else {
+        mappings
+    };
+    let mut controller = SensorController::new(mappings)?;
+    limit_by_config(app, device_id).await?;
+
+    let ble_manager = app.get_or_init_ble().await?.clone();
+    info!("Scanning for sensors...");
+    ble_manager.start_scan().await?;
+    tokio::time::sleep(Duration::from_secs(3)).await;
+    ble_manager.stop_scan().await?;
+    let results = ble_manager.get_scan_results().await?;
+    let Some(found) = results
+        .iter()
+        .filter(|r| r.is_sensor())
+        .find(|r| sensor_id.is_empty() || r.id == sensor_id)
+    else {
+        println!("No sensor found");
+        return Ok(());
+    };
+
+    let sensor = ble_manager.connect_sensor(&found.id).await?;
+    let (tx, mut readings) = broadcast::channel(64);
+    let listener = spawn_listener(sensor.clone(), tx);
+    println!(
+        "Sensor {} controlling {} ({}), press Ctrl+C to stop",
+        found.name,
+        device_id,
+        controller
+            .mappings()
+            .iter()
+            .map(|m| format!("{} -> {:?}", m.input, m.target))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+    loop {
+        tokio::select! {
+            event = readings.recv() => {
+                let event = match event {
+                    Ok(event) => event,
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => {
+                        println!("Sensor disconnected");
+                        break;
+                    }
+                };
+                if let Err(e) = controller
+                    .handle(app.session_manager(), device_id, &event.reading)
+                    .await
+                {
+                    warn!("Sensor action failed: {}", e);
+                }
+            }
+            _ = &mut ctrl_c => break,
+        }
+    }
+
+    // 传感器断开或 Ctrl+C 后两个通道归零并停止输出，清理失败只记录日志
+    listener.abort();
+    let session = app.session_manager();
+    for channel in 0..2 {
+        if let Err(e) = session.set_power(device_id, channel, 0).await {
+            warn!("Failed to zero channel {} of {}: {}", channel, device_id, e);
+        }
+    }
+    if let Err(e) = session.stop(device_id).await {
+        warn!("Failed to stop {}: {}", device_id, e);
+    }
+    if let Err(e) = sensor.disconnect().await {
+        warn!("Failed to disconnect sensor: {}", e);
+    }
+    println!("Sensor control stopped");
+    Ok(())
+}
+
+/// 引导式校准：在每个百分比级别调整原始强度直到体感合适
+///
+/// 返回校准曲线，用户中止时返回 `None`。结束时把通道强度归零。
+async fn calibrate(
+    app: &DglabCli,
+    device_id: &str,
+    channel: u8,
+    levels: &[u8],
+    config: &AppConfig,
+) -> crate::error::Result<Option<PowerCurve>> {
+    let mut levels: Vec<u8> = levels.iter().copied().filter(|&l| l > 0).collect();
+    levels.sort_unstable();
+    levels.dedup();
+    if levels.last() != Some(&MAX_PERCENT) {
+        return Err(CliError::InvalidInput(
+            "Calibration levels must end at 100".to_string(),
+        ));
+    }
+
+    let device = app
+        .session_manager()
+        .get_device(device_id)
+        .await
+        .ok_or_else(|| CliError::DeviceNotFound(device_id.to_string()))?;
+    let info = device.read().await.info();
+    let max_raw = if channel == 0 {
+        info.max_power_a
+    } else {
+        info.max_power_b
+    };
+    let max_raw = config.clamp_power(channel, max_raw);
+
+    println!(
+        "Calibrating channel {}",
+        if channel == 0 { "A" } else { "B" }
+    );
+    println!("For each level, adjust the strength until it feels right.");
+    println!("  <Enter>  accept the current strength");
+    println!("  <number> try another raw strength (0~{})", max_raw);
+    println!("  q        abort");
+
+    let session = app.session_manager();
+    session.start(device_id).await?;
+    let result = calibrate_levels(session, device_id, channel, &levels, max_raw).await;
+    let _ = session.set_raw_power(device_id, channel, 0).await?;
+
+    let points = match result? {
+        Some(points) => points,
+        None => return Ok(None),
+    };
+    let points = std::iter::once((0, 0)).chain(points).collect();
+    Ok(Some(PowerCurve::new(points)?))
+}
+
+/// 逐级询问原始强度（不经过校准曲线，仍受会话安全限制约束）
+async fn calibrate_levels(
+    session: &SessionManager,
+    device_id: &str,
+    channel: u8,
+    levels: &[u8],
+    max_raw: u8,
+) -> crate::error::Result<Option<Vec<(u8, u8)>>> {
+    let mut points: Vec<(u8, u8)> = Vec::with_capacity(levels.len());
+    for &level in levels {
+        // 建议值：线性估计，且不低于上一级
+        let floor = points.last().map_or(0, |&(_, raw)| raw);
+        let mut raw = ((max_raw as u32 * level as u32) / MAX_PERCENT as u32) as u8;
+        raw = raw.max(floor);
+
+        loop {
+            let _ = session.set_raw_power(device_id, channel, raw).await?;
+            print!("{:>3}% -> strength {}: ", level, raw);
+            io::stdout().flush()?;
+
+            let mut input = String::new();
+            let _ = io::stdin().read_line(&mut input)?;
+            match input.trim() {
+                "" => break,
+                "q" | "Q" => return Ok(None),
+                value => match value.parse::<u8>() {
+                    Ok(value) if value > max_raw => println!("Maximum is {}", max_raw),
+                    Ok(value) if value < floor => {
+                        println!("Must not be lower than the previous level ({})", floor)
+                    }
+                    Ok(value) => raw = value,
+                    Err(_) => println!("Enter a number, an empty line or 'q'"),
+                },
+            }
+        }
+        points.push((level, raw));
+    }
+    Ok(Some(points))
+}
+
+/// 打印两个通道的强度，禁用的通道附加标记
+pub(super) fn print_power(info: &DeviceInfo) {
+    let disabled = |enabled: bool| if enabled { "" } else { " (disabled)" };
+    println!(
+        "Power A: {} / {}{}",
+        info.power_a,
+        info.max_power_a,
+        disabled(info.enabled_a)
+    );
+    println!(
+        "Power B: {} / {}{}",
+        info.power_b,
+        info.max_power_b,
+        disabled(info.enabled_b)
+    );
+}
+
+/// 打印固件和硬件版本，固件不受支持时附加警告
+pub(super) fn print_versions(info: &DeviceInfo) {
+    if !info.firmware_version.is_empty() {
+        match info.firmware_supported() {
+            Some(false) => println!(
+                "Firmware: {} (unsupported, some features may not work)",
+                info.firmware_version
+            ),
+            _ => println!("Firmware: {}", info.firmware_version),
+        }
+    }
+    if !info.hardware_version.is_empty() {
+        println!("Hardware: {}", info.hardware_version);
+    }
+}
+
+/// 打印输出限制
+fn print_limits(limits: &DeviceLimits) {
+    println!("\nDevice Limits:");
+    println!("{}", "-".repeat(40));
+    println!("Soft limit A:      {}", limits.soft_limit_a);
+    println!("Soft limit B:      {}", limits.soft_limit_b);
+    println!("Freq balance:      {}", limits.freq_balance);
+    println!("Intensity balance: {}", limits.intensity_balance);
+}
+
+/// 统计中列出的强度档位数量（按停留时长排序）
+const STATS_TOP_LEVELS: usize = 5;
+
+/// 打印设备会话统计
+pub(super) fn print_stats(stats: &DeviceStats) {
+    println!("\nSession Stats: {}", stats.device_id);
+    println!("{}", "-".repeat(40));
+    println!("Tracked:         {}", format_ms(stats.tracked_ms));
+    println!("Emergency stops: {}", stats.emergency_stops);
+    if let (Some(first), Some(last)) = (stats.battery.first(), stats.battery.last()) {
+        let remaining = stats
+            .battery_remaining_ms
+            .map_or_else(String::new, |ms| format!(", ~{} remaining", format_ms(ms)));
+        println!(
+            "Battery:         {}% -> {}%{}",
+            first.level, last.level, remaining
+        );
+    }
+    for (name, channel) in ["A", "B"].iter().zip(&stats.channels) {
+        println!(
+            "Channel {}:       peak {}, average {:.1}",
+            name, channel.peak_power, channel.average_power
+        );
+        let mut levels: Vec<_> = channel.time_at_power.iter().collect();
+        levels.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+        for (power, ms) in levels.into_iter().take(STATS_TOP_LEVELS) {
+            println!("  {:>3}: {}", power, format_ms(*ms));
+        }
+    }
+}
+
+/// 逐次发起时延探测并打印每次结果和汇总统计
+async fn ping(
+    device: &RwLock<Box<dyn Device>>,
+    count: u32,
+    interval: Duration,
+) -> crate::error::Result<()> {
+    let mut samples = device.read().await.latency().map_or(0, |s| s.samples);
+    let mut replies = 0;
+
+    for n in 1..=count {
+        device.write().await.probe_latency().await?;
+        tokio::time::sleep(interval).await;
+
+        match device.read().await.latency() {
+            Some(stats) if stats.samples > samples => {
+                samples = stats.samples;
+                replies += 1;
+                println!("B1 reply {}: {:.1} ms", n, as_ms(stats.last));
+            }
+            _ => println!("B1 reply {}: timeout", n),
+        }
+    }
+
+    let lost = count - replies;
+    println!(
+        "\n{} probes, {} replies, {:.0}% lost",
+        count,
+        replies,
+        f64::from(lost) * 100.0 / f64::from(count.max(1))
+    );
+    if let Some(stats) = device.read().await.latency() {
+        print_latency(&stats);
+    }
+    Ok(())
+}
+
+/// 打印时延统计
+fn print_latency(stats: &LatencyStats) {
+    println!(
+        "Latency min/avg/max/jitter = {:.1}/{:.1}/{:.1}/{:.1} ms ({} samples)",
+        as_ms(stats.min),
+        as_ms(stats.mean),
+        as_ms(stats.max),
+        as_ms(stats.jitter),
+        stats.samples
+    );
+}
+
+/// 时长转为毫秒（小数）
+fn as_ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// 格式化毫秒时长，如 `1h02m03s`、`4.5s`
+fn format_ms(ms: u64) -> String {
+    let secs = ms / 1000;
+    match secs {
+        0..=59 => format!("{:.1}s", ms as f64 / 1000.0),
+        60..=3599 => format!("{}m{:02}s", secs / 60, secs % 60),
+        _ => format!("{}h{:02}m{:02}s", secs / 3600, secs % 3600 / 60, secs % 60),
+    }
+}
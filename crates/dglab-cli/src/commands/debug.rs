@@ -0,0 +1,69 @@
+//! 调试工具命令
+//!
+//! `dglab debug decode <file>` 按 V3 协议解码 `--ble-log`（或环境变量
+//! `DGLAB_BLE_LOG`）记录的原始 BLE 流量。
+
+use std::path::PathBuf;
+
+use clap::Parser;
+
+use dglab_protocol::ble::traffic::{describe_frame, read_log};
+
+use crate::error::Result;
+
+/// 调试工具子命令
+#[derive(Parser, Debug)]
+pub struct DebugArgs {
+    #[command(subcommand)]
+    command: DebugCommand,
+}
+
+/// 调试子命令
+#[derive(Parser, Debug)]
+enum DebugCommand {
+    /// 解码 BLE 流量记录文件
+    Decode {
+        /// 记录文件（JSONL）
+        file: PathBuf,
+        /// 只显示该设备的记录
+        #[arg(long)]
+        device: Option<String>,
+        /// 同时显示原始 HEX 数据
+        #[arg(long)]
+        raw: bool,
+    },
+}
+
+/// 执行调试命令（不需要配置和会话）
+pub fn execute(args: DebugArgs) -> Result<()> {
+    match args.command {
+        DebugCommand::Decode { file, device, raw } => {
+            let records = read_log(&file)?;
+            let start = records.first().map_or(0, |record| record.timestamp_ms);
+            let mut shown = 0;
+
+            for record in records
+                .iter()
+                .filter(|record| device.as_ref().map_or(true, |id| record.device == *id))
+            {
+                let elapsed = record.timestamp_ms.saturating_sub(start) as f64 / 1000.0;
+                let text = match record.bytes() {
+                    Ok(data) => describe_frame(&data),
+                    Err(e) => format!("<{}>", e),
+                };
+                println!(
+                    "[{:>8.3}s] {} {} {}",
+                    elapsed, record.device, record.direction, text
+                );
+                if raw {
+                    println!("{:>12}{}", "", record.data);
+                }
+                shown += 1;
+            }
+
+            println!("\n{} frame(s) from {}", shown, file.display());
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,349 @@
+//! 环境诊断命令
+//!
+//! `dglab doctor` 依次检查配置文件、配置目录是否可写、蓝牙适配器及扫描权限、
+//! 能否调整连接优先级（Linux）、官方（或配置的）WebSocket 服务器是否可达，
+//! 并针对失败项给出处理建议。
+
+use std::fmt;
+use std::path::Path;
+use std::time::Duration;
+
+use clap::Parser;
+
+use dglab_core::config::{AppConfig, ConfigManager};
+use dglab_protocol::ble::{link, AdapterSelector, BleManager};
+use dglab_protocol::wifi::WsClient;
+
+use crate::error::{CliError, Result};
+
+/// 服务器连接和分配 clientId 的超时
+const SERVER_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 扫描权限检查的扫描时长
+const SCAN_PROBE: Duration = Duration::from_millis(500);
+
+/// 环境诊断参数
+#[derive(Parser, Debug)]
+pub struct DoctorArgs {
+    /// 跳过蓝牙检查
+    #[arg(long)]
+    no_ble: bool,
+
+    /// 跳过服务器连通性检查
+    #[arg(long)]
+    no_network: bool,
+}
+
+/// 检查结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Ok,
+    Warn,
+    Fail,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mark = match self {
+            Self::Ok => "✓",
+            Self::Warn => "!",
+            Self::Fail => "✗",
+        };
+        f.write_str(mark)
+    }
+}
+
+/// 单项检查
+struct Check {
+    /// 检查项
+    name: &'static str,
+    /// 结果
+    status: Status,
+    /// 说明
+    detail: String,
+    /// 处理建议
+    fix: Option<String>,
+}
+
+impl Check {
+    fn ok(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Ok,
+            detail: detail.into(),
+            fix: None,
+        }
+    }
+
+    fn warn(name: &'static str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Warn,
+            detail: detail.into(),
+            fix: Some(fix.into()),
+        }
+    }
+
+    fn fail(name: &'static str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Fail,
+            detail: detail.into(),
+            fix: Some(fix.into()),
+        }
+    }
+
+    fn print(&self) {
+        println!("{} {:<14} {}", self.status, self.name, self.detail);
+        if let Some(fix) = &self.fix {
+            for line in fix.lines() {
+                println!("  {:<14} → {}", "", line);
+            }
+        }
+    }
+}
+
+/// 执行环境诊断，有失败项时返回错误
+///
+/// 不需要会话，配置文件无效时作为失败项报告，其余检查使用默认配置继续。
+pub async fn execute(
+    args: DoctorArgs,
+    adapter: Option<&AdapterSelector>,
+    dry_run: bool,
+) -> Result<()> {
+    let path = ConfigManager::default_path()?.path().to_path_buf();
+    let mut checks = Vec::new();
+
+    let config = check_config(&path, &mut checks).await;
+    checks.push(check_config_dir(&path).await);
+
+    if !args.no_ble {
+        if dry_run {
+            checks.push(Check::warn(
+                "Bluetooth",
+                "skipped in dry run",
+                "Run without --dry-run to check the Bluetooth adapter",
+            ));
+        } else {
+            check_bluetooth(adapter, &mut checks).await;
+        }
+    }
+
+    if !args.no_network {
+        checks.push(check_server(&config).await);
+    }
+
+    for check in &checks {
+        check.print();
+    }
+
+    let failed = checks.iter().filter(|c| c.status == Status::Fail).count();
+    let warned = checks.iter().filter(|c| c.status == Status::Warn).count();
+    println!();
+    if failed > 0 {
+        return Err(CliError::Other(format!(
+            "{} check(s) failed, {} warning(s)",
+            failed, warned
+        )));
+    }
+    println!("All checks passed ({} warning(s))", warned);
+    Ok(())
+}
+
+/// 检查配置文件能否解析，返回用于后续检查的配置
+async fn check_config(path: &Path, checks: &mut Vec<Check>) -> AppConfig {
+    if !path.exists() {
+        checks.push(Check::ok(
+            "Config",
+            format!("{} not found, using defaults", path.display()),
+        ));
+        return AppConfig::default();
+    }
+
+    let result = match tokio::fs::read_to_string(path).await {
+        Ok(content) => AppConfig::from_toml_str(&content).map_err(|e| e.to_string()),
+        Err(e) => Err(e.to_string()),
+    };
+    match result {
+        Ok(config) => {
+            checks.push(Check::ok("Config", path.display().to_string()));
+            config
+        }
+        Err(e) => {
+            checks.push(Check::fail(
+                "Config",
+                format!("{}: {}", path.display(), e),
+                "Fix the reported field or delete the file to fall back to defaults",
+            ));
+            AppConfig::default()
+        }
+    }
+}
+
+/// 检查配置目录是否可写（GUI 设置、校准和预设定时会写回配置文件）
+async fn check_config_dir(path: &Path) -> Check {
+    const NAME: &str = "Config dir";
+
+    let Some(dir) = path.parent() else {
+        return Check::fail(NAME, "config path has no parent directory", "Check $HOME");
+    };
+    let probe = dir.join(".dglab-doctor");
+    let result = async {
+        tokio::fs::create_dir_all(dir).await?;
+        tokio::fs::write(&probe, b"").await?;
+        tokio::fs::remove_file(&probe).await
+    }
+    .await;
+
+    match result {
+        Ok(()) => Check::ok(NAME, format!("{} is writable", dir.display())),
+        Err(e) => Check::fail(
+            NAME,
+            format!("{}: {}", dir.display(), e),
+            format!(
+                "Make sure {} exists and is owned by the current user",
+                dir.display()
+            ),
+        ),
+    }
+}
+
+/// 检查蓝牙适配器和扫描权限
+async fn check_bluetooth(adapter: Option<&AdapterSelector>, checks: &mut Vec<Check>) {
+    const NAME: &str = "Bluetooth";
+
+    let adapters = match BleManager::list_adapters().await {
+        Ok(adapters) => adapters,
+        Err(e) => {
+            checks.push(Check::fail(NAME, e.to_string(), bluetooth_service_fix()));
+            return;
+        }
+    };
+    if adapters.is_empty() {
+        checks.push(Check::fail(
+            NAME,
+            "no Bluetooth adapter found",
+            format!(
+                "Plug in a Bluetooth 4.0+ adapter or enable the built-in one\n{}",
+                bluetooth_service_fix()
+            ),
+        ));
+        return;
+    }
+    checks.push(Check::ok(
+        NAME,
+        adapters
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", "),
+    ));
+
+    // 创建管理器并短暂扫描，检查适配器选择、电源和权限
+    const SCAN: &str = "BLE scan";
+    let manager = match BleManager::with_adapter(adapter).await {
+        Ok(manager) => manager,
+        Err(e) => {
+            checks.push(Check::fail(
+                SCAN,
+                e.to_string(),
+                "Check --adapter against `dglab scan --adapters`",
+            ));
+            return;
+        }
+    };
+    let result = async {
+        manager.start_scan().await?;
+        tokio::time::sleep(SCAN_PROBE).await;
+        manager.stop_scan().await
+    }
+    .await;
+    checks.push(match result {
+        Ok(()) => Check::ok(
+            SCAN,
+            format!("scanning works on {}", manager.adapter_info()),
+        ),
+        Err(e) => Check::fail(SCAN, e.to_string(), bluetooth_permission_fix()),
+    });
+
+    // BlueZ 不提供调整连接间隔的接口，只能通过 debugfs
+    if cfg!(target_os = "linux") {
+        const PRIORITY: &str = "BLE priority";
+        checks.push(if link::priority_supported().await {
+            Check::ok(PRIORITY, "connection interval can be tuned")
+        } else {
+            Check::warn(
+                PRIORITY,
+                "debugfs not writable, --priority high has no effect",
+                "Mount debugfs and run as root: sudo mount -t debugfs none /sys/kernel/debug\n\
+                 Or keep the default --priority balanced (add --write-fallback to use with-response writes)",
+            )
+        });
+
+        // MTU 由 BlueZ 在连接时交换，上限取决于 main.conf
+        const MTU: &str = "BLE MTU";
+        checks.push(Check::ok(
+            MTU,
+            match link::exchange_mtu_setting().await {
+                Some(mtu) => format!("BlueZ requests MTU {} (ExchangeMTU in main.conf)", mtu),
+                None => "BlueZ requests its default MTU on connect".to_string(),
+            },
+        ));
+    }
+}
+
+/// 蓝牙服务不可用时的处理建议
+fn bluetooth_service_fix() -> &'static str {
+    if cfg!(target_os = "linux") {
+        "Start BlueZ: sudo systemctl enable --now bluetooth\nUnblock the radio: rfkill unblock bluetooth"
+    } else if cfg!(target_os = "macos") {
+        "Turn Bluetooth on in Control Center"
+    } else if cfg!(target_os = "windows") {
+        "Turn Bluetooth on in Settings > Bluetooth & devices (requires Windows 10 1803+)"
+    } else {
+        "Make sure the system Bluetooth service is running"
+    }
+}
+
+/// 扫描失败（多为权限问题）时的处理建议
+fn bluetooth_permission_fix() -> &'static str {
+    if cfg!(target_os = "linux") {
+        "Power the adapter on: bluetoothctl power on\nAllow D-Bus access to BlueZ: sudo usermod -aG bluetooth $USER, then log in again"
+    } else if cfg!(target_os = "macos") {
+        "Allow your terminal in System Settings > Privacy & Security > Bluetooth, then restart it"
+    } else if cfg!(target_os = "windows") {
+        "Allow apps to access Bluetooth in Settings > Privacy & security > Radios"
+    } else {
+        "Check that the current user may use Bluetooth"
+    }
+}
+
+/// 检查服务器能否连接、分配 clientId 并响应心跳
+async fn check_server(config: &AppConfig) -> Check {
+    const NAME: &str = "Server";
+
+    let address = match config.server_address() {
+        Ok(address) => address,
+        Err(e) => return Check::fail(NAME, e.to_string(), "Fix [server] url in the config file"),
+    };
+
+    match WsClient::probe(&address, SERVER_TIMEOUT).await {
+        Ok(report) if report.is_ready() => Check::ok(NAME, report.to_string()),
+        Ok(report) if report.client_id.is_none() => Check::fail(
+            NAME,
+            report.to_string(),
+            "Check that the URL points to a DG-LAB WebSocket server",
+        ),
+        Ok(report) => Check::warn(
+            NAME,
+            report.to_string(),
+            "The server may drop idle connections, run `dglab wifi test` for details",
+        ),
+        Err(e) => Check::fail(
+            NAME,
+            format!("{}: {}", address, e),
+            "Check the internet connection, proxy and firewall\n\
+             For a self-signed LAN server set accept_invalid_certs = true in [server]",
+        ),
+    }
+}
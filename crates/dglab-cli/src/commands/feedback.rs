@@ -0,0 +1,80 @@
+//! 反馈按钮映射命令
+
+use clap::Parser;
+
+use dglab_core::feedback::{parse_button, FeedbackAction, FeedbackMapping};
+use dglab_protocol::wifi::FeedbackButton;
+
+use super::DglabCli;
+
+/// 反馈按钮映射子命令
+#[derive(Parser, Debug)]
+pub struct FeedbackArgs {
+    #[command(subcommand)]
+    command: FeedbackCommand,
+}
+
+/// 反馈按钮子命令
+#[derive(Parser, Debug)]
+enum FeedbackCommand {
+    /// 显示当前映射
+    Show,
+    /// 设置按钮动作
+    Set {
+        /// 按钮（A0~A4、B0~B4）
+        button: String,
+        /// 动作（+5、-5、set:50、stop、next、prev）
+        #[arg(allow_hyphen_values = true)]
+        action: String,
+    },
+    /// 移除按钮动作
+    Unset {
+        /// 按钮（A0~A4、B0~B4）
+        button: String,
+    },
+    /// 恢复默认映射
+    Reset,
+}
+
+/// 执行反馈按钮映射命令
+pub async fn execute(_app: &mut DglabCli, args: FeedbackArgs) -> crate::error::Result<()> {
+    let path = FeedbackMapping::default_path()?;
+    let mut mapping = FeedbackMapping::load(&path).await?;
+
+    match args.command {
+        FeedbackCommand::Show => {
+            println!("\nFeedback mapping ({}):", path.display());
+            println!("{}", "-".repeat(50));
+
+            for button in FeedbackButton::ALL {
+                match mapping.get(button) {
+                    Some(action) => println!("  {:?}  {}", button, action),
+                    None => println!("  {:?}  -", button),
+                }
+            }
+            println!();
+            return Ok(());
+        }
+
+        FeedbackCommand::Set { button, action } => {
+            let button = parse_button(&button)?;
+            let action: FeedbackAction = action.parse()?;
+            mapping.set(button, action);
+            println!("{:?} -> {}", button, action);
+        }
+
+        FeedbackCommand::Unset { button } => {
+            let button = parse_button(&button)?;
+            mapping.remove(button);
+            println!("{:?} unmapped", button);
+        }
+
+        FeedbackCommand::Reset => {
+            mapping = FeedbackMapping::default();
+            println!("Feedback mapping reset to defaults");
+        }
+    }
+
+    mapping.save(&path).await?;
+    Ok(())
+}
@@ -0,0 +1,175 @@
+//! 单次输出命令
+//!
+//! 连接设备（或使用会话中已有的设备），以指定强度和波形输出一段时间，
+//! 结束时强度渐变归零、停止输出并断开本次建立的连接，不进入交互模式。
+
+use std::time::Duration;
+
+use clap::Args;
+use clap_complete::engine::ArgValueCandidates;
+
+use crate::commands::completions::device_candidates;
+use crate::commands::DglabCli;
+use crate::error::{CliError, Result};
+
+use dglab_core::device::simulator::{is_simulated_id, SIMULATED_DEVICE_PREFIX};
+use dglab_core::device::Easing;
+use dglab_core::waveform::Waveform;
+
+/// 等待渐变结束时的检查间隔
+const RAMP_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// 单次输出参数
+#[derive(Debug, Args)]
+pub struct FireArgs {
+    /// 通道 (a / b / both)
+    #[arg(short, long, default_value = "a")]
+    pub channel: String,
+
+    /// 强度（设备已校准时为百分比 0~100）
+    #[arg(short, long)]
+    pub power: u8,
+
+    /// 输出时长（如 3s、1m30s）
+    #[arg(long, default_value = "3s", value_parser = crate::parse_duration_arg)]
+    pub duration: Duration,
+
+    /// 波形名称（内置或波形库），未指定时使用设备当前波形
+    #[arg(short, long)]
+    pub waveform: Option<String>,
+
+    /// 结束时强度渐变归零的时长（毫秒）
+    #[arg(long, default_value_t = 1000, value_name = "MS")]
+    pub ramp_down: u64,
+
+    /// 设备名称或 ID；未指定时使用会话中已有的设备或配置文件中的第一个常用设备
+    #[arg(long, add = ArgValueCandidates::new(device_candidates))]
+    pub device: Option<String>,
+
+    /// 使用模拟设备（不使用蓝牙，未指定 --device 时为 sim-1）
+    #[arg(long)]
+    pub simulated: bool,
+}
+
+/// 执行单次输出
+pub async fn execute(cli: &mut DglabCli, args: FireArgs) -> Result<()> {
+    let channels =
+        match super::repl::parse_channel(&args.channel, true).map_err(CliError::InvalidInput)? {
+            Some(channel) => vec![channel],
+            None => vec![0, 1],
+        };
+    // 连接前先解析波形，名称错误时不必连接设备
+    let waveform = match &args.waveform {
+        Some(name) => Some(cli.waveform_library().resolve(name)?),
+        None => None,
+    };
+
+    // 试运行设备等已在会话中的设备直接使用，不断开
+    let existing = cli.session_manager().list_devices().await;
+    let reused = match &args.device {
+        Some(name) if cli.dry_run().is_none() => existing.iter().find(|id| *id == name).cloned(),
+        _ => existing.first().cloned(),
+    };
+    let device_id = match reused {
+        Some(id) => id,
+        None => {
+            let name = match args.device.clone() {
+                Some(name) => name,
+                None if args.simulated => format!("{}1", SIMULATED_DEVICE_PREFIX),
+                None => cli
+                    .config()
+                    .favorite_devices
+                    .first()
+                    .map(|d| d.id.clone())
+                    .ok_or_else(|| {
+                        CliError::InvalidInput(
+                            "No device given, use --device or add favorite_devices to the config file"
+                                .to_string(),
+                        )
+                    })?,
+            };
+            if args.simulated && !is_simulated_id(&name) {
+                return Err(CliError::InvalidInput(format!(
+                    "'{}' is not a simulated device, use an ID like sim-1",
+                    name
+                )));
+            }
+            super::mqtt::connect_devices(cli, std::slice::from_ref(&name))
+                .await?
+                .into_iter()
+                .next()
+                .ok_or(CliError::DeviceNotFound(name))?
+        }
+    };
+    let connected = !existing.contains(&device_id);
+
+    let result = fire(cli, &device_id, &channels, waveform, &args).await;
+
+    // 无论输出是否成功都停止输出，并断开本次建立的连接
+    let session = cli.session_manager();
+    let _ = session.stop(&device_id).await;
+    if connected {
+        let _ = session.remove_device(&device_id).await;
+    }
+    result
+}
+
+/// 输出一段时间后渐变归零，Ctrl+C 提前结束输出
+async fn fire(
+    cli: &DglabCli,
+    device_id: &str,
+    channels: &[u8],
+    waveform: Option<Waveform>,
+    args: &FireArgs,
+) -> Result<()> {
+    let session = cli.session_manager();
+    if let Some(waveform) = &waveform {
+        for &channel in channels {
+            session
+                .set_waveform(device_id, channel, waveform.to_device_config())
+                .await?;
+        }
+    }
+    for &channel in channels {
+        let _ = session.set_power(device_id, channel, args.power).await?;
+    }
+    session.start(device_id).await?;
+
+    println!(
+        "Firing {} on {} at {} for {:?}{}, press Ctrl+C to stop early",
+        args.channel.to_uppercase(),
+        device_id,
+        args.power,
+        args.duration,
+        waveform
+            .as_ref()
+            .map(|w| format!(" ({})", w.name))
+            .unwrap_or_default()
+    );
+    tokio::select! {
+        _ = tokio::time::sleep(args.duration) => {}
+        _ = tokio::signal::ctrl_c() => println!("Stopping early"),
+    }
+
+    let ramp_down = Duration::from_millis(args.ramp_down);
+    for &channel in channels {
+        session
+            .start_ramp(device_id, channel, 0, ramp_down, Easing::EaseOut, |_| {})
+            .await?;
+    }
+    while channels.iter().any(|&c| session.is_ramping(device_id, c)) {
+        tokio::select! {
+            _ = tokio::time::sleep(RAMP_POLL_INTERVAL) => {}
+            // 再次按下 Ctrl+C 时立即归零
+            _ = tokio::signal::ctrl_c() => {
+                for &channel in channels {
+                    let _ = session.cancel_ramp(device_id, channel);
+                    let _ = session.set_power(device_id, channel, 0).await;
+                }
+                break;
+            }
+        }
+    }
+    println!("Done");
+    Ok(())
+}
@@ -0,0 +1,124 @@
+//! 事件日志命令
+
+use std::path::{Path, PathBuf};
+
+use chrono::Local;
+use clap::Parser;
+
+use dglab_core::session::{EventLog, LogFilter};
+
+use super::DglabCli;
+use crate::error::{CliError, Result};
+
+/// 事件日志子命令
+#[derive(Parser, Debug)]
+pub struct LogArgs {
+    #[command(subcommand)]
+    command: LogCommand,
+}
+
+/// 日志子命令
+#[derive(Parser, Debug)]
+enum LogCommand {
+    /// 列出所有会话日志
+    List,
+    /// 显示会话日志（默认为上一个会话）
+    Show {
+        /// 会话日志文件名或会话 ID 前缀
+        session: Option<String>,
+        /// 只显示该设备的记录
+        #[arg(long)]
+        device: Option<String>,
+        /// 只显示最后 N 条
+        #[arg(short = 'n', long)]
+        limit: Option<usize>,
+    },
+}
+
+/// 执行日志命令
+pub async fn execute(app: &mut DglabCli, args: LogArgs) -> Result<()> {
+    let dir = EventLog::default_dir()?;
+    // 当前会话的日志在本命令中不会写入，查询时排除
+    let current = app
+        .session_manager()
+        .event_log()
+        .map(|log| log.path().to_path_buf());
+    let logs: Vec<PathBuf> = EventLog::list(&dir)?
+        .into_iter()
+        .filter(|path| Some(path) != current.as_ref())
+        .collect();
+
+    match args.command {
+        LogCommand::List => {
+            println!("\nSession logs ({}):", logs.len());
+            println!("{}", "-".repeat(50));
+
+            if logs.is_empty() {
+                println!("No session logs found in {}", dir.display());
+            }
+            for path in &logs {
+                let entries = EventLog::read(path, &LogFilter::default())?;
+                println!("  {} ({} entries)", file_stem(path), entries.len());
+            }
+        }
+
+        LogCommand::Show {
+            session,
+            device,
+            limit,
+        } => {
+            let path = match &session {
+                Some(session) => logs
+                    .iter()
+                    .rev()
+                    .find(|path| {
+                        let stem = file_stem(path);
+                        stem == *session
+                            || stem
+                                .split('-')
+                                .nth(2)
+                                .is_some_and(|id| id.starts_with(session.as_str()))
+                    })
+                    .ok_or_else(|| {
+                        CliError::InvalidInput(format!("Session log not found: {}", session))
+                    })?,
+                None => logs
+                    .last()
+                    .ok_or_else(|| CliError::InvalidInput("No session logs found".to_string()))?,
+            };
+
+            let filter = LogFilter {
+                device_id: device,
+                limit,
+                ..Default::default()
+            };
+            let entries = EventLog::read(path, &filter)?;
+
+            println!("\nSession log: {}", file_stem(path));
+            println!("{}", "-".repeat(50));
+            if entries.is_empty() {
+                println!("No entries");
+            }
+            for entry in entries {
+                println!(
+                    "  {}  {:<12} {}",
+                    entry
+                        .timestamp
+                        .with_timezone(&Local)
+                        .format("%Y-%m-%d %H:%M:%S"),
+                    entry.device_id.as_deref().unwrap_or("-"),
+                    entry.event
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// 日志文件名（不含扩展名）
+fn file_stem(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
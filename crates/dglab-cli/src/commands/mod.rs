@@ -2,25 +2,56 @@
 
 use std::sync::Arc;
 
-use crate::error::Result;
+use tokio::sync::broadcast;
+use tracing::warn;
+
+use crate::error::{CliError, Result};
+use dglab_core::config::{AppConfig, ConfigManager};
+use dglab_core::device::{dry_run_device, DryRunRecorder, DryRunTransport, DRY_RUN_DEVICE_ID};
 use dglab_core::preset::PresetManager;
-use dglab_core::session::SessionManager;
-use dglab_protocol::ble::BleManager;
+use dglab_core::session::{EventLog, SessionManager};
+use dglab_core::waveform::WaveformLibrary;
+use dglab_protocol::ble::{AdapterSelector, BleManager};
 
+pub mod bench;
 pub mod bridge;
+pub mod completions;
+pub mod config;
 pub mod connect;
 pub mod control;
+pub mod debug;
+pub mod doctor;
+pub mod feedback;
+pub mod fire;
+pub mod log;
+pub mod mqtt;
 pub mod preset;
+pub mod remote;
+pub mod repl;
 pub mod scan;
 pub mod script;
+pub mod waveform;
+pub mod webhook;
 pub mod wifi;
 
+pub use bench::BenchArgs;
 pub use bridge::BridgeArgs;
+pub use completions::{CompletionsArgs, ManpagesArgs};
+pub use config::ConfigArgs;
 pub use connect::ConnectArgs;
 pub use control::ControlArgs;
+pub use debug::DebugArgs;
+pub use doctor::DoctorArgs;
+pub use feedback::FeedbackArgs;
+pub use fire::FireArgs;
+pub use log::LogArgs;
+pub use mqtt::MqttArgs;
 pub use preset::PresetArgs;
+pub use remote::RemoteArgs;
 pub use scan::ScanArgs;
 pub use script::ScriptArgs;
+pub use waveform::WaveformArgs;
+pub use webhook::WebhookArgs;
 pub use wifi::WifiArgs;
 
 /// CLI 应用
@@ -31,41 +62,125 @@ pub struct DglabCli {
     session_manager: SessionManager,
     /// 预设管理器
     preset_manager: PresetManager,
+    /// 波形库
+    waveform_library: WaveformLibrary,
+    /// 配置
+    config: ConfigManager,
+    /// 试运行记录器（`--dry-run` 时设置）
+    dry_run: Option<DryRunRecorder>,
+    /// 使用的蓝牙适配器（`--adapter`，未指定时使用第一个）
+    adapter: Option<AdapterSelector>,
 }
 
 impl DglabCli {
     /// 创建新的 CLI 应用（不初始化 BLE）
-    pub async fn new() -> Result<Self> {
-        let session_manager = SessionManager::new();
+    pub async fn new(config: ConfigManager) -> Result<Self> {
+        // 找不到数据目录时不记录事件日志，不影响控制
+        let session_manager = match EventLog::default_dir() {
+            Ok(dir) => SessionManager::with_event_log(&dir),
+            Err(e) => {
+                warn!("Event log disabled: {}", e);
+                SessionManager::new()
+            }
+        };
         let mut preset_manager = PresetManager::default_dir()?;
         preset_manager.initialize().await?;
 
+        let mut waveform_library = WaveformLibrary::default_dir()?;
+        waveform_library.load().await?;
+        session_manager
+            .set_waveform_library(waveform_library.clone())
+            .await;
+        session_manager.load_config(&config.config());
+
         Ok(Self {
             ble_manager: None,
             session_manager,
             preset_manager,
+            waveform_library,
+            config,
+            dry_run: None,
+            adapter: None,
         })
     }
 
+    /// 启用试运行
+    ///
+    /// 添加一个已连接的试运行设备，之后的命令照常控制它，将要发送的帧打印到标准输出，
+    /// 不访问蓝牙或服务器。
+    pub async fn enable_dry_run(&mut self, transport: DryRunTransport) -> Result<()> {
+        let recorder = DryRunRecorder::default();
+        let mut frames = recorder.subscribe();
+        let _ = tokio::spawn(async move {
+            loop {
+                match frames.recv().await {
+                    Ok(frame) => println!("{}", frame),
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        println!("... {} frames not shown", n);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+
+        let mut device = dry_run_device(
+            transport,
+            DRY_RUN_DEVICE_ID.to_string(),
+            format!("Dry run ({})", transport),
+            recorder.clone(),
+        );
+        device.connect().await?;
+        self.session_manager.add_device(device).await?;
+        self.dry_run = Some(recorder);
+
+        println!(
+            "Dry run ({}): nothing will be sent, frames are printed instead",
+            transport
+        );
+        Ok(())
+    }
+
+    /// 试运行记录器（未启用试运行时为 `None`）
+    pub fn dry_run(&self) -> Option<&DryRunRecorder> {
+        self.dry_run.as_ref()
+    }
+
+    /// 试运行时拒绝需要真实硬件的命令
+    fn require_hardware(&self, command: &str) -> Result<()> {
+        if self.dry_run.is_some() {
+            return Err(CliError::InvalidInput(format!(
+                "'{}' needs real hardware and does not support --dry-run",
+                command
+            )));
+        }
+        Ok(())
+    }
+
+    /// 选择蓝牙适配器（需在初始化 BLE 之前调用）
+    pub fn set_adapter(&mut self, adapter: AdapterSelector) {
+        self.adapter = Some(adapter);
+    }
+
     /// 获取或初始化 BLE 管理器
+    ///
+    /// 只有真正需要蓝牙的命令才调用，没有蓝牙的机器上 WiFi 和预设命令照常可用。
     async fn get_or_init_ble(&mut self) -> Result<&Arc<BleManager>> {
         if self.ble_manager.is_none() {
-            self.ble_manager = Some(Arc::new(BleManager::new().await?));
+            let manager = BleManager::with_adapter(self.adapter.as_ref())
+                .await
+                .map_err(|e| CliError::BleUnavailable(e.to_string()))?;
+            self.ble_manager = Some(Arc::new(manager));
         }
         Ok(self.ble_manager.as_ref().unwrap())
     }
 
-    /// 扫描设备
+    /// 扫描设备（扫描真实设备时才初始化 BLE）
     pub async fn scan(&mut self, args: ScanArgs) -> Result<()> {
-        // 延迟初始化 BLE
-        self.get_or_init_ble().await?;
         scan::execute(self, args).await
     }
 
-    /// 连接设备
+    /// 连接设备（扫描真实设备时才初始化 BLE）
     pub async fn connect(&mut self, args: ConnectArgs) -> Result<()> {
-        // 延迟初始化 BLE
-        self.get_or_init_ble().await?;
         connect::execute(self, args).await
     }
 
@@ -74,6 +189,21 @@ impl DglabCli {
         control::execute(self, args).await
     }
 
+    /// 反馈按钮映射
+    pub async fn feedback(&mut self, args: FeedbackArgs) -> Result<()> {
+        feedback::execute(self, args).await
+    }
+
+    /// 单次输出（连接设备时才初始化 BLE）
+    pub async fn fire(&mut self, args: FireArgs) -> Result<()> {
+        fire::execute(self, args).await
+    }
+
+    /// 事件日志
+    pub async fn log(&mut self, args: LogArgs) -> Result<()> {
+        log::execute(self, args).await
+    }
+
     /// 预设管理
     pub async fn preset(&mut self, args: PresetArgs) -> Result<()> {
         preset::execute(self, args).await
@@ -84,6 +214,11 @@ impl DglabCli {
         script::execute(self, args).await
     }
 
+    /// 波形库管理
+    pub async fn waveform(&mut self, args: WaveformArgs) -> Result<()> {
+        waveform::execute(self, args).await
+    }
+
     /// 运行 TUI
     pub async fn run_tui(&mut self) -> Result<()> {
         crate::tui::run(self).await
@@ -96,14 +231,30 @@ impl DglabCli {
 
     /// 桥接模式
     pub async fn bridge(&mut self, args: BridgeArgs) -> Result<()> {
-        // 延迟初始化 BLE
-        self.get_or_init_ble().await?;
+        self.require_hardware("bridge")?;
         bridge::execute(self, args).await
     }
 
-    /// 获取 BLE 管理器
-    pub fn ble_manager(&self) -> Option<&Arc<BleManager>> {
-        self.ble_manager.as_ref()
+    /// 加入远程会话
+    pub async fn remote(&mut self, args: RemoteArgs) -> Result<()> {
+        self.require_hardware("remote")?;
+        remote::execute(self, args).await
+    }
+
+    /// BLE 吞吐量测试
+    pub async fn bench(&mut self, args: BenchArgs) -> Result<()> {
+        self.require_hardware("bench")?;
+        bench::execute(self, args).await
+    }
+
+    /// MQTT 集成（连接设备时才初始化 BLE）
+    pub async fn mqtt(&mut self, args: MqttArgs) -> Result<()> {
+        mqtt::execute(self, args).await
+    }
+
+    /// Webhook 触发（连接设备时才初始化 BLE）
+    pub async fn webhook(&mut self, args: WebhookArgs) -> Result<()> {
+        webhook::execute(self, args).await
     }
 
     /// 获取会话管理器
@@ -120,4 +271,27 @@ impl DglabCli {
     pub fn preset_manager_mut(&mut self) -> &mut PresetManager {
         &mut self.preset_manager
     }
+
+    /// 获取当前配置
+    pub fn config(&self) -> AppConfig {
+        self.config.config()
+    }
+
+    /// 获取配置管理器
+    pub fn config_manager(&self) -> &ConfigManager {
+        &self.config
+    }
+
+    /// 获取波形库
+    pub fn waveform_library(&self) -> &WaveformLibrary {
+        &self.waveform_library
+    }
+
+    /// 更新波形库（同步到会话管理器）
+    pub async fn set_waveform_library(&mut self, library: WaveformLibrary) {
+        self.session_manager
+            .set_waveform_library(library.clone())
+            .await;
+        self.waveform_library = library;
+    }
 }
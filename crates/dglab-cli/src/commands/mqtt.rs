@@ -0,0 +1,179 @@
+//! MQTT 集成命令
+//!
+//! 连接指定的 BLE 设备后接入 MQTT broker，订阅强度、波形和急停主题，
+//! 并发布状态、电量和强度，供 Home Assistant 等家庭自动化系统使用。
+//! 主题格式见 `dglab_core::mqtt::topics`。
+
+use std::time::Duration;
+
+use clap::Args;
+use clap_complete::engine::ArgValueCandidates;
+use tracing::info;
+
+use crate::commands::completions::device_candidates;
+use crate::commands::DglabCli;
+use crate::error::{CliError, Result};
+
+use dglab_core::device::simulator::{is_simulated_id, simulated_devices, SIMULATED_DEVICE_PREFIX};
+use dglab_core::device::{CoyoteDevice, Device, MockCoyoteDevice};
+use dglab_core::mqtt::{MqttBridge, MqttConfig};
+
+/// MQTT 集成参数
+#[derive(Debug, Args)]
+pub struct MqttArgs {
+    /// Broker 地址（host、host:port 或 mqtt://host:port，默认使用配置文件 [mqtt] 段）
+    #[arg(short, long)]
+    pub broker: Option<String>,
+
+    /// 设备名称或 ID，可重复指定；未指定时使用配置文件中的常用设备
+    #[arg(short, long, add = ArgValueCandidates::new(device_candidates))]
+    pub device: Vec<String>,
+
+    /// 主题前缀（默认 dglab）
+    #[arg(long)]
+    pub prefix: Option<String>,
+
+    /// 客户端 ID
+    #[arg(long)]
+    pub client_id: Option<String>,
+
+    /// 用户名
+    #[arg(short, long)]
+    pub username: Option<String>,
+
+    /// 密码
+    #[arg(short, long, requires = "username")]
+    pub password: Option<String>,
+}
+
+impl MqttArgs {
+    /// 合并命令行参数和配置文件
+    fn config(&self, config: Option<MqttConfig>) -> Result<MqttConfig> {
+        let mut config = match (&self.broker, config) {
+            (Some(broker), Some(config)) => MqttConfig {
+                broker: broker.clone(),
+                ..config
+            },
+            (Some(broker), None) => MqttConfig::new(broker.clone()),
+            (None, Some(config)) => config,
+            (None, None) => {
+                return Err(CliError::InvalidInput(
+                    "No MQTT broker given, use --broker or add [mqtt] to the config file"
+                        .to_string(),
+                ))
+            }
+        };
+
+        if let Some(prefix) = &self.prefix {
+            config.topic_prefix = prefix.clone();
+        }
+        if let Some(client_id) = &self.client_id {
+            config.client_id = client_id.clone();
+        }
+        if let Some(username) = &self.username {
+            config.username = Some(username.clone());
+            config.password = self.password.clone();
+        }
+        Ok(config)
+    }
+}
+
+/// 执行 MQTT 集成
+pub async fn execute(cli: &mut DglabCli, args: MqttArgs) -> Result<()> {
+    let app_config = cli.config();
+    let bridge =
+        MqttBridge::new(args.config(app_config.mqtt.clone())?)?.with_access(&app_config.access);
+
+    if cli.dry_run().is_none() {
+        let names = if args.device.is_empty() {
+            app_config
+                .favorite_devices
+                .iter()
+                .map(|d| d.id.clone())
+                .collect::<Vec<_>>()
+        } else {
+            args.device.clone()
+        };
+        if names.is_empty() {
+            return Err(CliError::InvalidInput(
+                "No device given, use --device or add favorite_devices to the config file"
+                    .to_string(),
+            ));
+        }
+        let _ = connect_devices(cli, &names).await?;
+    }
+
+    let config = bridge.config();
+    println!(
+        "MQTT: {} (topics {}/<device>/..., access control {}), press Ctrl+C to stop",
+        config.broker,
+        config.prefix(),
+        if bridge.access_enabled() { "on" } else { "off" }
+    );
+    for device_id in cli.session_manager().list_devices().await {
+        println!("  • {}", device_id);
+    }
+
+    let result = tokio::select! {
+        result = bridge.run(cli.session_manager()) => result.map_err(CliError::from),
+        _ = tokio::signal::ctrl_c() => {
+            println!();
+            println!("Stopping");
+            Ok(())
+        }
+    };
+
+    // 失败的设备已逐个记录日志
+    let _ = cli.session_manager().stop_all().await;
+    result
+}
+
+/// 扫描并连接设备，加入会话，按 `names` 的顺序返回设备 ID
+///
+/// 模拟设备 ID（`sim-1`、`sim-2`……）直接连接模拟设备，全部为模拟设备时不使用蓝牙。
+pub(super) async fn connect_devices(cli: &mut DglabCli, names: &[String]) -> Result<Vec<String>> {
+    let scan_results = if names.iter().all(|name| is_simulated_id(name)) {
+        Vec::new()
+    } else {
+        let ble_manager = cli.get_or_init_ble().await?.clone();
+        info!("Scanning for devices...");
+        ble_manager.start_scan().await?;
+        tokio::time::sleep(Duration::from_secs(3)).await;
+        ble_manager.stop_scan().await?;
+        ble_manager.get_scan_results().await?
+    };
+
+    let mut connected = Vec::with_capacity(names.len());
+    for name in names {
+        let device: Box<dyn Device> = if is_simulated_id(name) {
+            let count = name
+                .strip_prefix(SIMULATED_DEVICE_PREFIX)
+                .and_then(|n| n.parse().ok())
+                .unwrap_or(0);
+            let target = simulated_devices(count)
+                .into_iter()
+                .find(|d| d.id == *name)
+                .ok_or_else(|| CliError::DeviceNotFound(name.clone()))?;
+            let mut mock = MockCoyoteDevice::new(target.id, target.name);
+            mock.connect().await?;
+            Box::new(mock)
+        } else {
+            let target = scan_results
+                .iter()
+                .find(|d| d.name.contains(name.as_str()) || d.id == *name)
+                .ok_or_else(|| CliError::DeviceNotFound(name.clone()))?;
+            let ble_manager = cli.get_or_init_ble().await?.clone();
+            let protocol_device = ble_manager.connect(&target.id).await?;
+            let mut coyote = CoyoteDevice::new(target.id.clone(), target.name.clone());
+            coyote.set_protocol_device(protocol_device);
+            coyote.connect().await?;
+            Box::new(coyote)
+        };
+
+        let (id, name) = (device.id().to_string(), device.name().to_string());
+        cli.session_manager().add_device(device).await?;
+        println!("Connected to: {} ({})", name, id);
+        connected.push(id);
+    }
+    Ok(connected)
+}
@@ -1,8 +1,23 @@
 //! 预设管理命令
 
+use std::path::PathBuf;
+
+use chrono::Local;
 use clap::Parser;
+use clap_complete::engine::ArgValueCandidates;
+use qrcode::{render::unicode, QrCode};
+use tokio::sync::broadcast;
 use tracing::info;
 
+use dglab_core::preset::{
+    Preset, PresetChannelConfig, PresetQuery, PresetSafety, PresetScheduler, ScheduleEntry,
+    ScheduleEvent, ScheduleTrigger, SharedPattern,
+};
+use dglab_core::waveform::{Modulation, PulseFile};
+
+use crate::error::CliError;
+
+use super::completions::{device_candidates, preset_candidates};
 use super::DglabCli;
 
 /// 预设管理子命令
@@ -15,17 +30,40 @@ pub struct PresetArgs {
 /// 预设子命令
 #[derive(Parser, Debug)]
 enum PresetCommand {
-    /// 列出所有预设
-    List,
+    /// 列出预设（可按文本、标签、分类和强度筛选）
+    List {
+        /// 名称或描述包含的文本
+        #[arg(short, long)]
+        search: Option<String>,
+        /// 必须带有的标签（可重复，全部满足）
+        #[arg(short, long)]
+        tag: Vec<String>,
+        /// 分类
+        #[arg(short, long)]
+        category: Option<String>,
+        /// 最高强度下限
+        #[arg(long)]
+        min_power: Option<u8>,
+        /// 最高强度上限
+        #[arg(long)]
+        max_power: Option<u8>,
+    },
     /// 显示预设详情
-    Show { name: String },
+    Show {
+        #[arg(add = ArgValueCandidates::new(preset_candidates))]
+        name: String,
+    },
     /// 应用预设
     Apply {
         /// 预设名称
+        #[arg(add = ArgValueCandidates::new(preset_candidates))]
         name: String,
         /// 设备 ID
-        #[arg(short, long)]
+        #[arg(short, long, add = ArgValueCandidates::new(device_candidates))]
         device: Option<String>,
+        /// 跳过高强度预设的确认提示
+        #[arg(short, long)]
+        yes: bool,
     },
     /// 创建新预设
     Create {
@@ -34,31 +72,184 @@ enum PresetCommand {
         /// 预设描述
         #[arg(short, long)]
         description: Option<String>,
+        /// 父预设名称，只保存本命令指定的字段，其余从父预设继承
+        #[arg(short, long, add = ArgValueCandidates::new(preset_candidates))]
+        parent: Option<String>,
         /// 通道 A 最大强度
         #[arg(long = "a")]
         power_a: Option<u8>,
         /// 通道 B 最大强度
         #[arg(long = "b")]
         power_b: Option<u8>,
+        /// 通道 A 波形（波形库中的名称）
+        #[arg(long)]
+        waveform_a: Option<String>,
+        /// 通道 B 波形（波形库中的名称）
+        #[arg(long)]
+        waveform_b: Option<String>,
+        /// 通道 A 调制层（目标:形状:周期ms:深度，如 intensity:sine:4000:50，可重复）
+        #[arg(long, value_name = "SPEC")]
+        modulation_a: Vec<Modulation>,
+        /// 通道 B 调制层（可重复）
+        #[arg(long, value_name = "SPEC")]
+        modulation_b: Vec<Modulation>,
+        /// 强度绝对上限（两个通道）
+        #[arg(long)]
+        limit: Option<u8>,
+        /// 强度最大上升速率（每秒）
+        #[arg(long)]
+        ramp_rate: Option<u8>,
+        /// 标记为高强度预设，应用前需要确认
+        #[arg(long)]
+        confirm: bool,
+        /// 标签（可重复）
+        #[arg(short, long)]
+        tag: Vec<String>,
+        /// 分类
+        #[arg(long)]
+        category: Option<String>,
+    },
+    /// 修改预设的标签和分类
+    Tag {
+        /// 预设名称
+        #[arg(add = ArgValueCandidates::new(preset_candidates))]
+        name: String,
+        /// 添加标签（可重复）
+        #[arg(short, long)]
+        add: Vec<String>,
+        /// 移除标签（可重复）
+        #[arg(short, long)]
+        remove: Vec<String>,
+        /// 设置分类（空字符串清除）
+        #[arg(short, long)]
+        category: Option<String>,
+    },
+    /// 从 APP 格式波形 JSON 文件（8 字节 HEX 数组）创建预设，波形内嵌在预设中
+    ImportPulse {
+        /// 波形 JSON 文件
+        file: PathBuf,
+        /// 预设名称（默认使用文件中的名称或文件名）
+        #[arg(short, long)]
+        name: Option<String>,
+        /// 预设描述
+        #[arg(short, long)]
+        description: Option<String>,
+        /// 通道 A 最大强度
+        #[arg(long = "a")]
+        power_a: Option<u8>,
+        /// 通道 B 最大强度
+        #[arg(long = "b")]
+        power_b: Option<u8>,
+        /// 覆盖同名预设
+        #[arg(long)]
+        force: bool,
+    },
+    /// 生成预设（或波形）的分享码，可在聊天中直接发送
+    Share {
+        /// 预设名称（指定 --waveform 时为波形名称）
+        #[arg(add = ArgValueCandidates::new(preset_candidates))]
+        name: String,
+        /// 分享波形库中的波形而不是预设
+        #[arg(short, long)]
+        waveform: bool,
+        /// 同时显示二维码
+        #[arg(long)]
+        qr: bool,
+    },
+    /// 导入分享码（预设保存到预设目录，波形保存到波形库）
+    ImportCode {
+        /// 分享码（以 dglab1: 开头）
+        code: String,
+        /// 重命名导入的预设或波形
+        #[arg(short, long)]
+        name: Option<String>,
+        /// 覆盖同名预设或波形
+        #[arg(long)]
+        force: bool,
     },
     /// 删除预设
-    Delete { name: String },
+    Delete {
+        #[arg(add = ArgValueCandidates::new(preset_candidates))]
+        name: String,
+    },
+    /// 管理预设定时（保存在配置文件中）
+    Schedule {
+        #[command(subcommand)]
+        command: ScheduleCommand,
+    },
+}
+
+/// 预设定时子命令
+#[derive(Parser, Debug)]
+enum ScheduleCommand {
+    /// 列出定时及下一次触发时间
+    List,
+    /// 添加定时（同名定时会被替换）
+    Add {
+        /// 定时名称
+        name: String,
+        /// 预设名称
+        #[arg(short, long, add = ArgValueCandidates::new(preset_candidates))]
+        preset: String,
+        /// 每天的本地时间（HH:MM）
+        #[arg(long, value_name = "HH:MM", required_unless_present = "every")]
+        at: Option<String>,
+        /// 触发间隔（如 20m、1h30m）
+        #[arg(long, value_name = "DURATION", conflicts_with = "at")]
+        every: Option<String>,
+        /// 设备 ID（默认所有设备）
+        #[arg(long, add = ArgValueCandidates::new(device_candidates))]
+        device: Option<String>,
+        /// 添加后保持禁用
+        #[arg(long)]
+        disabled: bool,
+    },
+    /// 删除定时
+    Remove { name: String },
+    /// 启用定时
+    Enable { name: String },
+    /// 禁用定时
+    Disable { name: String },
+    /// 连接设备后按定时应用预设，Ctrl+C 退出并停止输出
+    Run {
+        /// 设备名称或 ID，可重复指定；未指定时使用配置文件中的常用设备
+        #[arg(short, long, add = ArgValueCandidates::new(device_candidates))]
+        device: Vec<String>,
+    },
 }
 
 /// 执行预设命令
 pub async fn execute(app: &mut DglabCli, args: PresetArgs) -> crate::error::Result<()> {
     match args.command {
-        PresetCommand::List => {
-            let presets = app.preset_manager().list_presets();
+        PresetCommand::List {
+            search,
+            tag,
+            category,
+            min_power,
+            max_power,
+        } => {
+            let query = PresetQuery {
+                text: search,
+                tags: tag,
+                category,
+                min_power,
+                max_power,
+            };
+            let filtered = query != PresetQuery::default();
+            let presets = app.preset_manager().search(&query);
 
             println!("\nPresets ({}):", presets.len());
             println!("{}", "-".repeat(50));
 
             if presets.is_empty() {
-                println!("No presets found");
+                if filtered {
+                    println!("No presets match the filter");
+                } else {
+                    println!("No presets found");
+                }
             } else {
                 for preset in presets {
-                    println!("  - {}", preset.name);
+                    println!("  - {}{}", preset.name, format_labels(preset));
                     if !preset.description.is_empty() {
                         println!("    {}", preset.description);
                     }
@@ -67,30 +258,56 @@ pub async fn execute(app: &mut DglabCli, args: PresetArgs) -> crate::error::Resu
         }
 
         PresetCommand::Show { name } => {
-            if let Some(preset) = app.preset_manager().find_preset_by_name(&name) {
+            if app.preset_manager().find_preset_by_name(&name).is_some() {
+                // 显示继承后的有效配置
+                let preset = &app.preset_manager().resolve_by_name(&name)?;
                 println!("\nPreset: {}", preset.name);
                 println!("{}", "-".repeat(50));
                 println!("Description: {}", preset.description);
+                if let Some(parent) = &preset.parent {
+                    let parent = app
+                        .preset_manager()
+                        .get_preset(parent)
+                        .map_or(parent.as_str(), |p| p.name.as_str());
+                    println!("Parent:      {}", parent);
+                    if !preset.overrides.is_empty() {
+                        println!("Overrides:   {}", preset.overrides.join(", "));
+                    }
+                }
+                if let Some(category) = &preset.category {
+                    println!("Category:    {}", category);
+                }
+                if !preset.tags.is_empty() {
+                    println!("Tags:        {}", preset.tags.join(", "));
+                }
                 println!("Created:     {}", preset.created_at);
                 println!("Updated:     {}", preset.updated_at);
                 println!("\nChannel A:");
                 println!("  Enabled:   {}", preset.channel_a.enabled);
                 println!("  Max Power: {}", preset.channel_a.max_power);
+                print_waveform(&preset.channel_a);
                 println!("\nChannel B:");
                 println!("  Enabled:   {}", preset.channel_b.enabled);
                 println!("  Max Power: {}", preset.channel_b.max_power);
+                print_waveform(&preset.channel_b);
+                print_safety(&preset.safety);
             } else {
                 println!("Preset not found: {}", name);
             }
         }
 
-        PresetCommand::Apply { name, device } => {
+        PresetCommand::Apply { name, device, yes } => {
             info!("Applying preset: {}", name);
 
-            let Some(preset) = app.preset_manager().find_preset_by_name(&name) else {
+            if app.preset_manager().find_preset_by_name(&name).is_none() {
                 println!("Preset not found: {}", name);
                 return Ok(());
-            };
+            }
+            let preset = &app.preset_manager().resolve_by_name(&name)?;
+            if preset.requires_confirmation() && !yes && !confirm(preset)? {
+                println!("Cancelled");
+                return Ok(());
+            }
 
             // 获取设备
             let device_ids = app.session_manager().list_devices().await;
@@ -101,20 +318,10 @@ pub async fn execute(app: &mut DglabCli, args: PresetArgs) -> crate::error::Resu
                 return Ok(());
             };
 
-            let Some(dev) = app.session_manager().get_device(&device_id).await else {
-                println!("Device not found: {}", device_id);
-                return Ok(());
-            };
-
-            let mut dev = dev.write().await;
-
-            // 应用预设（这里只是设置最大强度的示例）
-            if preset.channel_a.enabled {
-                dev.set_power(0, preset.channel_a.max_power).await?;
-            }
-            if preset.channel_b.enabled {
-                dev.set_power(1, preset.channel_b.max_power).await?;
-            }
+            // 设置各通道上限（V3 为 BF 软上限）、波形与初始强度
+            app.session_manager()
+                .apply_preset_confirmed(&device_id, preset)
+                .await?;
 
             println!("Applied preset '{}' to device '{}'", name, device_id);
         }
@@ -122,8 +329,18 @@ pub async fn execute(app: &mut DglabCli, args: PresetArgs) -> crate::error::Resu
         PresetCommand::Create {
             name,
             description,
+            parent,
             power_a,
             power_b,
+            waveform_a,
+            waveform_b,
+            modulation_a,
+            modulation_b,
+            limit,
+            ramp_rate,
+            confirm,
+            tag,
+            category,
         } => {
             info!("Creating preset: {}", name);
 
@@ -132,14 +349,57 @@ pub async fn execute(app: &mut DglabCli, args: PresetArgs) -> crate::error::Resu
                 return Ok(());
             }
 
-            let mut preset =
-                dglab_core::preset::Preset::new(name.clone(), description.unwrap_or_default());
+            let mut preset = Preset::new(name.clone(), description.unwrap_or_default());
+            if let Some(parent) = parent {
+                let parent = app
+                    .preset_manager()
+                    .find_preset_by_name(&parent)
+                    .ok_or_else(|| {
+                        CliError::InvalidInput(format!("Preset not found: {}", parent))
+                    })?;
+                preset.parent = Some(parent.id.clone());
+            }
 
             if let Some(p) = power_a {
-                preset.channel_a.max_power = p;
+                preset.set_max_power(0, p);
             }
             if let Some(p) = power_b {
-                preset.channel_b.max_power = p;
+                preset.set_max_power(1, p);
+            }
+
+            preset.safety = PresetSafety {
+                max_power: limit,
+                max_ramp_rate: ramp_rate,
+                require_confirmation: confirm,
+            };
+            // 继承时只覆盖命令行指定的字段
+            for (field, given) in [
+                ("safety.max_power", limit.is_some()),
+                ("safety.max_ramp_rate", ramp_rate.is_some()),
+                ("safety.require_confirmation", confirm),
+                ("channel_a.waveform", waveform_a.is_some()),
+                ("channel_b.waveform", waveform_b.is_some()),
+                ("channel_a.modulation", !modulation_a.is_empty()),
+                ("channel_b.modulation", !modulation_b.is_empty()),
+            ] {
+                if given {
+                    preset.set_override(field);
+                }
+            }
+            preset.safety.validate()?;
+            preset.set_tags(&tag);
+            preset.category = category.filter(|c| !c.trim().is_empty());
+
+            // 按名称引用波形库中的波形，应用预设时再解析
+            for (config, waveform, modulation) in [
+                (&mut preset.channel_a, waveform_a, modulation_a),
+                (&mut preset.channel_b, waveform_b, modulation_b),
+            ] {
+                if let Some(waveform) = waveform {
+                    let _ = app.waveform_library().resolve(&waveform)?;
+                    config.waveform_name = Some(waveform);
+                }
+                config.modulation = modulation;
             }
 
             // 添加到管理器
@@ -152,6 +412,180 @@ pub async fn execute(app: &mut DglabCli, args: PresetArgs) -> crate::error::Resu
             println!("Preset created: {}", name);
         }
 
+        PresetCommand::ImportPulse {
+            file,
+            name,
+            description,
+            power_a,
+            power_b,
+            force,
+        } => {
+            let pulses = PulseFile::load(&file).await?;
+            let name = name
+                .or_else(|| pulses.name.clone())
+                .or_else(|| {
+                    file.file_stem()
+                        .map(|stem| stem.to_string_lossy().into_owned())
+                })
+                .ok_or_else(|| CliError::InvalidInput("Preset name required".to_string()))?;
+            info!("Importing pulse file {:?} as preset {}", file, name);
+
+            let existing = app
+                .preset_manager()
+                .find_preset_by_name(&name)
+                .map(|preset| preset.id.clone());
+            if existing.is_some() && !force {
+                return Err(CliError::InvalidInput(format!(
+                    "Preset '{}' already exists, use --force to overwrite",
+                    name
+                )));
+            }
+
+            let mut preset = Preset::new(name.clone(), description.unwrap_or_default());
+            for (channel, config, power) in [
+                (0u8, &mut preset.channel_a, power_a),
+                (1u8, &mut preset.channel_b, power_b),
+            ] {
+                if let Some(power) = power {
+                    config.max_power = power;
+                }
+                // 文件中没有数据的通道不输出
+                config.waveform = pulses.to_waveform(channel, &name)?;
+                config.enabled = config.waveform.is_some();
+            }
+
+            match existing {
+                Some(id) => {
+                    preset.id = id;
+                    app.preset_manager_mut().update_preset(preset.clone())?;
+                }
+                None => app.preset_manager_mut().add_preset(preset.clone())?,
+            }
+            app.preset_manager().save_preset(&preset.id).await?;
+
+            println!(
+                "Imported preset '{}' (A: {} frames, B: {} frames)",
+                name,
+                pulses.channel_a.len(),
+                pulses.channel_b.len()
+            );
+        }
+
+        PresetCommand::Share { name, waveform, qr } => {
+            let pattern = if waveform {
+                SharedPattern::waveform(&app.waveform_library().resolve(&name)?)
+            } else {
+                let preset = app.preset_manager().resolve_by_name(&name)?;
+                SharedPattern::preset(&preset, app.waveform_library())?
+            };
+            let code = pattern.encode()?;
+
+            if qr {
+                match QrCode::new(&code) {
+                    Ok(qr_code) => {
+                        let qr_string = qr_code
+                            .render::<unicode::Dense1x2>()
+                            .dark_color(unicode::Dense1x2::Light)
+                            .light_color(unicode::Dense1x2::Dark)
+                            .build();
+                        println!("{}", qr_string);
+                    }
+                    Err(e) => println!("Share code is too long for a QR code: {}", e),
+                }
+            }
+            println!("{}", code);
+        }
+
+        PresetCommand::ImportCode { code, name, force } => {
+            let mut pattern = SharedPattern::decode(&code)?;
+            if let Some(name) = name {
+                match &mut pattern {
+                    SharedPattern::Preset(preset) => preset.name = name,
+                    SharedPattern::Waveform(file) => file.name = name,
+                }
+            }
+
+            match pattern {
+                SharedPattern::Preset(mut preset) => {
+                    let existing = app
+                        .preset_manager()
+                        .find_preset_by_name(&preset.name)
+                        .map(|preset| preset.id.clone());
+                    if existing.is_some() && !force {
+                        return Err(CliError::InvalidInput(format!(
+                            "Preset '{}' already exists, use --force to overwrite",
+                            preset.name
+                        )));
+                    }
+
+                    match existing {
+                        Some(id) => {
+                            preset.id = id;
+                            app.preset_manager_mut().update_preset(preset.clone())?;
+                        }
+                        None => app.preset_manager_mut().add_preset(preset.clone())?,
+                    }
+                    app.preset_manager().save_preset(&preset.id).await?;
+
+                    println!("Imported preset '{}'", preset.name);
+                }
+                SharedPattern::Waveform(file) => {
+                    let waveform = file.to_waveform()?;
+                    let mut library = app.waveform_library().clone();
+                    let path = library.user_path(&file.name).ok_or_else(|| {
+                        CliError::Other("Waveform library has no directory".to_string())
+                    })?;
+                    if path.exists() && !force {
+                        return Err(CliError::InvalidInput(format!(
+                            "{} already exists, use --force to overwrite",
+                            path.display()
+                        )));
+                    }
+
+                    file.save(&path).await?;
+                    info!("Saved shared waveform '{}' to {:?}", file.name, path);
+
+                    library.insert(waveform);
+                    app.set_waveform_library(library).await;
+
+                    println!("Imported waveform '{}' to {}", file.name, path.display());
+                }
+            }
+        }
+
+        PresetCommand::Tag {
+            name,
+            add,
+            remove,
+            category,
+        } => {
+            let Some(preset) = app.preset_manager().find_preset_by_name(&name) else {
+                println!("Preset not found: {}", name);
+                return Ok(());
+            };
+
+            let mut preset = preset.clone();
+            let tags: Vec<String> = preset
+                .tags
+                .iter()
+                .filter(|t| !remove.iter().any(|r| r.trim().eq_ignore_ascii_case(t)))
+                .chain(&add)
+                .cloned()
+                .collect();
+            preset.set_tags(&tags);
+            if let Some(category) = category {
+                let category = category.trim();
+                preset.category = (!category.is_empty()).then(|| category.to_string());
+            }
+
+            let preset_id = preset.id.clone();
+            let labels = format_labels(&preset);
+            app.preset_manager_mut().update_preset(preset)?;
+            app.preset_manager().save_preset(&preset_id).await?;
+
+            println!("Preset updated: {}{}", name, labels);
+        }
+
         PresetCommand::Delete { name } => {
             info!("Deleting preset: {}", name);
 
@@ -170,7 +604,246 @@ pub async fn execute(app: &mut DglabCli, args: PresetArgs) -> crate::error::Resu
 
             println!("Preset deleted: {}", name);
         }
+
+        PresetCommand::Schedule { command } => schedule(app, command).await?,
+    }
+
+    Ok(())
+}
+
+/// 执行预设定时命令
+async fn schedule(app: &mut DglabCli, command: ScheduleCommand) -> crate::error::Result<()> {
+    let mut config = app.config();
+
+    match command {
+        ScheduleCommand::List => {
+            println!("\nSchedules ({}):", config.schedules.len());
+            println!("{}", "-".repeat(50));
+
+            if config.schedules.is_empty() {
+                println!("No schedules configured");
+            }
+            let now = Local::now();
+            for entry in &config.schedules {
+                let device = entry.device.as_deref().unwrap_or("all devices");
+                println!(
+                    "  - {} [{}]: '{}' {} on {}",
+                    entry.name,
+                    if entry.enabled { "enabled" } else { "disabled" },
+                    entry.preset,
+                    entry.trigger,
+                    device
+                );
+                if let (true, Ok(next)) = (entry.enabled, entry.trigger.next_after(now)) {
+                    println!("    Next: {}", next.format("%Y-%m-%d %H:%M:%S"));
+                }
+            }
+            return Ok(());
+        }
+
+        ScheduleCommand::Add {
+            name,
+            preset,
+            at,
+            every,
+            device,
+            disabled,
+        } => {
+            if app.preset_manager().find_preset_by_name(&preset).is_none() {
+                return Err(CliError::InvalidInput(format!(
+                    "Preset not found: {}",
+                    preset
+                )));
+            }
+            let trigger = match (at, every) {
+                (Some(at), _) => ScheduleTrigger::At(at),
+                (None, Some(every)) => ScheduleTrigger::Every(every),
+                (None, None) => {
+                    return Err(CliError::InvalidInput(
+                        "Either --at or --every is required".to_string(),
+                    ))
+                }
+            };
+
+            let mut entry = ScheduleEntry::new(name.clone(), preset, trigger);
+            entry.device = device;
+            entry.enabled = !disabled;
+            entry.validate()?;
+            println!("Schedule '{}': {}", name, entry.trigger);
+            config.set_schedule(entry);
+        }
+
+        ScheduleCommand::Remove { name } => {
+            if !config.remove_schedule(&name) {
+                println!("Schedule not found: {}", name);
+                return Ok(());
+            }
+            println!("Schedule removed: {}", name);
+        }
+
+        ScheduleCommand::Enable { name } | ScheduleCommand::Disable { name }
+            if config.schedule(&name).is_none() =>
+        {
+            println!("Schedule not found: {}", name);
+            return Ok(());
+        }
+
+        ScheduleCommand::Enable { name } => {
+            let _ = config.set_schedule_enabled(&name, true);
+            println!("Schedule enabled: {}", name);
+        }
+
+        ScheduleCommand::Disable { name } => {
+            let _ = config.set_schedule_enabled(&name, false);
+            println!("Schedule disabled: {}", name);
+        }
+
+        ScheduleCommand::Run { device } => return run_schedules(app, device).await,
+    }
+
+    app.config_manager().set(config).await?;
+    Ok(())
+}
+
+/// 连接设备并运行预设调度，直到 Ctrl+C
+async fn run_schedules(app: &mut DglabCli, device: Vec<String>) -> crate::error::Result<()> {
+    let config = app.config();
+    let enabled = config.schedules.iter().filter(|e| e.enabled).count();
+    if enabled == 0 {
+        return Err(CliError::InvalidInput(
+            "No enabled schedules, add one with 'preset schedule add'".to_string(),
+        ));
+    }
+
+    if app.dry_run().is_none() {
+        let names = if device.is_empty() {
+            config
+                .favorite_devices
+                .iter()
+                .map(|d| d.id.clone())
+                .collect::<Vec<_>>()
+        } else {
+            device
+        };
+        if names.is_empty() {
+            return Err(CliError::InvalidInput(
+                "No device given, use --device or add favorite_devices to the config file"
+                    .to_string(),
+            ));
+        }
+        let _ = super::mqtt::connect_devices(app, &names).await?;
+    }
+
+    println!("Running {} schedule(s), press Ctrl+C to stop", enabled);
+    let scheduler = PresetScheduler::new();
+    let mut events = scheduler.subscribe();
+    let report = async {
+        loop {
+            match events.recv().await {
+                Ok(ScheduleEvent::Fired {
+                    schedule,
+                    preset,
+                    devices,
+                }) => println!(
+                    "[{}] {}: applied '{}' to {}",
+                    Local::now().format("%H:%M:%S"),
+                    schedule,
+                    preset,
+                    devices.join(", ")
+                ),
+                Ok(ScheduleEvent::Failed {
+                    schedule,
+                    preset,
+                    error,
+                }) => println!(
+                    "[{}] {}: failed to apply '{}': {}",
+                    Local::now().format("%H:%M:%S"),
+                    schedule,
+                    preset,
+                    error
+                ),
+                Err(broadcast::error::RecvError::Lagged(_)) => {}
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    };
+
+    tokio::select! {
+        _ = scheduler.run(
+            app.config_manager().subscribe(),
+            app.session_manager(),
+            app.preset_manager(),
+        ) => {}
+        _ = report => {}
+        _ = tokio::signal::ctrl_c() => {
+            println!();
+            println!("Stopping");
+        }
     }
 
+    // 失败的设备已逐个记录日志
+    let _ = app.session_manager().stop_all().await;
     Ok(())
 }
+
+/// 显示通道波形
+fn print_waveform(config: &PresetChannelConfig) {
+    match (&config.waveform, &config.waveform_name) {
+        (Some(waveform), _) => println!("  Waveform:  {}", waveform.name),
+        (None, Some(name)) => println!("  Waveform:  {} (library)", name),
+        (None, None) => {}
+    }
+    for modulation in &config.modulation {
+        println!("  Modulate:  {}", modulation);
+    }
+}
+
+/// 显示安全设置
+fn print_safety(safety: &PresetSafety) {
+    if safety.is_default() {
+        return;
+    }
+    println!("\nSafety:");
+    if let Some(max_power) = safety.max_power {
+        println!("  Limit:     {}", max_power);
+    }
+    if let Some(rate) = safety.max_ramp_rate {
+        println!("  Ramp Rate: {}/s", rate);
+    }
+    if safety.require_confirmation {
+        println!("  Requires confirmation");
+    }
+}
+
+/// 列表中显示的分类和标签，如 ` [Relax] #night #slow`
+fn format_labels(preset: &Preset) -> String {
+    let mut labels = String::new();
+    if preset.parent.is_some() {
+        labels.push_str(" (inherited)");
+    }
+    if let Some(category) = &preset.category {
+        labels.push_str(&format!(" [{}]", category));
+    }
+    for tag in &preset.tags {
+        labels.push_str(&format!(" #{}", tag));
+    }
+    labels
+}
+
+/// 提示用户确认应用高强度预设
+fn confirm(preset: &Preset) -> crate::error::Result<bool> {
+    use std::io::{self, Write};
+
+    println!(
+        "Preset '{}' is marked as high intensity (A max {}, B max {})",
+        preset.name,
+        preset.safety.clamp(preset.channel_a.max_power),
+        preset.safety.clamp(preset.channel_b.max_power)
+    );
+    print!("Apply it? [y/N]: ");
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    let _ = io::stdin().read_line(&mut input)?;
+    Ok(matches!(input.trim().to_lowercase().as_str(), "y" | "yes"))
+}
@@ -0,0 +1,82 @@
+//! 远程会话命令
+//!
+//! 凭 `dglab bridge --local` 显示的邀请链接加入对方的本机服务器：控制端进入交互式控制，
+//! 强度、波形等命令作用于对方的主机；`--viewer` 以观察端加入，只打印对方主机的强度。
+
+use std::time::Duration;
+
+use clap::Args;
+
+use crate::commands::DglabCli;
+use crate::error::Result;
+
+use dglab_core::device::{Device, DeviceEvent};
+use dglab_core::session::{remote, RemoteInvite, RemoteRole, DEFAULT_JOIN_TIMEOUT};
+
+/// 远程会话参数
+#[derive(Debug, Args)]
+pub struct RemoteArgs {
+    /// 邀请链接（桥接显示的二维码链接，或 ws://host:port/<clientId>）
+    pub invite: RemoteInvite,
+
+    /// 以观察端加入，只查看对方主机的强度
+    #[arg(long)]
+    pub viewer: bool,
+
+    /// 对方服务器的 PIN（对方启用 --pin 时必须提供）
+    #[arg(long)]
+    pub pin: Option<String>,
+
+    /// 接受自签名证书（仅用于局域网 wss 服务器）
+    #[arg(long)]
+    pub insecure: bool,
+
+    /// 连接和绑定超时（秒）
+    #[arg(long, default_value_t = DEFAULT_JOIN_TIMEOUT.as_secs())]
+    pub timeout: u64,
+}
+
+/// 加入远程会话
+pub async fn execute(cli: &mut DglabCli, args: RemoteArgs) -> Result<()> {
+    let mut invite = args.invite.accept_invalid_certs(args.insecure);
+    if let Some(pin) = args.pin {
+        invite = invite.with_pin(pin);
+    }
+    let role = if args.viewer {
+        RemoteRole::Viewer
+    } else {
+        RemoteRole::Controller
+    };
+
+    println!("Joining {} as {}...", invite.server(), role);
+    let device = remote::join(&invite, role, Duration::from_secs(args.timeout)).await?;
+    let device_id = device.id().to_string();
+    let mut events = device.subscribe_events();
+    cli.session_manager().add_device(Box::new(device)).await?;
+
+    if role == RemoteRole::Controller {
+        println!(
+            "Joined, commands now control the partner's device ({})",
+            device_id
+        );
+        return super::repl::run(cli, Some(device_id)).await;
+    }
+
+    println!("Joined as viewer, press Ctrl+C to leave");
+    loop {
+        tokio::select! {
+            event = events.recv() => match event {
+                Ok(DeviceEvent::StatusReport { power_a, power_b }) => {
+                    println!("A={:3}  B={:3}", power_a, power_b);
+                }
+                Ok(DeviceEvent::Error(message)) => println!("Error: {}", message),
+                Ok(_) | Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => {}
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            },
+            _ = tokio::signal::ctrl_c() => break,
+        }
+    }
+
+    cli.session_manager().remove_device(&device_id).await?;
+    Ok(())
+}
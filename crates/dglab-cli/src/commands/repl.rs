@@ -0,0 +1,540 @@
+//! 交互式控制（`dglab control --interactive`）
+
+use clap::Parser;
+use rustyline::completion::{Completer, Pair};
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
+use tracing::debug;
+
+use dglab_core::device::{ChannelLink, Device, MockDevice};
+
+use super::{ConnectArgs, DglabCli};
+use crate::error::{CliError, Result};
+
+/// 顶层命令
+const COMMANDS: &[&str] = &[
+    "help", "status", "stats", "devices", "use", "connect", "mock", "power", "wave", "link",
+    "channel", "battery", "start", "stop", "quit",
+];
+
+/// 帮助信息
+const HELP: &str = "\
+Commands:
+  power <a|b|both> <0-200>   Set channel strength
+  wave <a|b> <name>          Apply a waveform from the library
+  link <ratio[:offset]|off>  Link channel B to channel A
+  channel <a|b> <on|off>     Enable or disable a channel
+  battery <override|enforce> Ignore or restore the low battery limit
+  start / stop               Start or stop output
+  status                     Show device status
+  stats                      Show session statistics for all devices
+  devices                    List devices in the session
+  use <device-id>            Select the device to control
+  connect [args]             Scan and connect a BLE device (same flags as 'dglab connect')
+  mock                       Add a mock device for testing
+  quit                       Stop all devices and exit";
+
+/// REPL 命令
+#[derive(Debug, PartialEq)]
+enum ReplCommand {
+    /// 显示帮助
+    Help,
+    /// 显示设备状态
+    Status,
+    /// 显示会话统计
+    Stats,
+    /// 列出设备
+    Devices,
+    /// 切换当前设备
+    Use(String),
+    /// 连接 BLE 设备（参数同 `dglab connect`）
+    Connect(Vec<String>),
+    /// 添加模拟设备
+    Mock,
+    /// 设置强度（`None` 表示两个通道）
+    Power { channel: Option<u8>, power: u8 },
+    /// 应用波形库中的波形
+    Wave { channel: u8, name: String },
+    /// 设置通道联动
+    Link(Option<ChannelLink>),
+    /// 启用或禁用通道
+    Channel { channel: u8, enabled: bool },
+    /// 忽略或恢复低电量限制
+    BatteryOverride(bool),
+    /// 开始输出
+    Start,
+    /// 停止输出
+    Stop,
+    /// 退出
+    Quit,
+}
+
+/// 解析一行输入，空行返回 `None`
+fn parse_command(line: &str) -> std::result::Result<Option<ReplCommand>, String> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let Some((&command, args)) = words.split_first() else {
+        return Ok(None);
+    };
+
+    let command = match (command.to_lowercase().as_str(), args) {
+        ("help" | "?", _) => ReplCommand::Help,
+        ("status", []) => ReplCommand::Status,
+        ("stats", []) => ReplCommand::Stats,
+        ("devices", []) => ReplCommand::Devices,
+        ("use", [id]) => ReplCommand::Use(id.to_string()),
+        ("connect", args) => ReplCommand::Connect(args.iter().map(|s| s.to_string()).collect()),
+        ("mock", []) => ReplCommand::Mock,
+        ("power", [channel, power]) => ReplCommand::Power {
+            channel: parse_channel(channel, true)?,
+            power: power
+                .parse()
+                .map_err(|_| format!("Invalid power: {}", power))?,
+        },
+        ("power", [power]) => ReplCommand::Power {
+            channel: None,
+            power: power
+                .parse()
+                .map_err(|_| format!("Invalid power: {}", power))?,
+        },
+        ("wave", [channel, name]) => ReplCommand::Wave {
+            channel: parse_channel(channel, false)?.unwrap_or_default(),
+            name: name.to_string(),
+        },
+        ("link", ["off"]) => ReplCommand::Link(None),
+        ("link", [link]) => ReplCommand::Link(Some(link.parse().map_err(|e| format!("{}", e))?)),
+        ("channel", [channel, state]) => ReplCommand::Channel {
+            channel: parse_channel(channel, false)?.unwrap_or_default(),
+            enabled: match state.to_lowercase().as_str() {
+                "on" => true,
+                "off" => false,
+                _ => return Err(format!("Invalid channel state: {}", state)),
+            },
+        },
+        ("battery", ["override"]) => ReplCommand::BatteryOverride(true),
+        ("battery", ["enforce"]) => ReplCommand::BatteryOverride(false),
+        ("start", []) => ReplCommand::Start,
+        ("stop", []) => ReplCommand::Stop,
+        ("quit" | "exit", []) => ReplCommand::Quit,
+        (other, _) if COMMANDS.contains(&other) => {
+            return Err(format!("Invalid arguments for '{}'. Type 'help'.", other))
+        }
+        (other, _) => return Err(format!("Unknown command: {}. Type 'help'.", other)),
+    };
+
+    Ok(Some(command))
+}
+
+/// 解析通道（`a`/`b`，`allow_both` 时接受 `both`）
+pub(super) fn parse_channel(s: &str, allow_both: bool) -> std::result::Result<Option<u8>, String> {
+    match s.to_lowercase().as_str() {
+        "a" | "0" => Ok(Some(0)),
+        "b" | "1" => Ok(Some(1)),
+        "both" | "ab" if allow_both => Ok(None),
+        _ => Err(format!("Invalid channel: {}", s)),
+    }
+}
+
+/// 补全与行编辑辅助
+struct ReplHelper {
+    /// 会话中的设备 ID
+    device_ids: Vec<String>,
+    /// 波形库中的波形名称
+    waveforms: Vec<String>,
+}
+
+impl Completer for ReplHelper {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let line = &line[..pos];
+        let start = line.rfind(char::is_whitespace).map_or(0, |i| i + 1);
+        let word = line[start..].to_lowercase();
+        let args: Vec<&str> = line[..start].split_whitespace().collect();
+
+        let options: Vec<&str> = match args.as_slice() {
+            [] => COMMANDS.to_vec(),
+            ["use"] => self.device_ids.iter().map(String::as_str).collect(),
+            ["power"] => vec!["a", "b", "both"],
+            ["wave"] => vec!["a", "b"],
+            ["wave", _] => self.waveforms.iter().map(String::as_str).collect(),
+            ["link"] => vec!["off"],
+            ["channel"] => vec!["a", "b"],
+            ["channel", _] => vec!["on", "off"],
+            _ => Vec::new(),
+        };
+
+        let candidates = options
+            .into_iter()
+            .filter(|option| option.to_lowercase().starts_with(&word))
+            .map(|option| Pair {
+                display: option.to_string(),
+                replacement: format!("{} ", option),
+            })
+            .collect();
+
+        Ok((start, candidates))
+    }
+}
+
+impl Hinter for ReplHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ReplHelper {}
+
+impl Validator for ReplHelper {}
+
+impl Helper for ReplHelper {}
+
+/// 运行交互式控制
+pub async fn run(app: &mut DglabCli, device_id: Option<String>) -> Result<()> {
+    let mut editor: Editor<ReplHelper, DefaultHistory> = Editor::new().map_err(readline_error)?;
+    editor.set_helper(Some(ReplHelper {
+        device_ids: Vec::new(),
+        waveforms: app
+            .waveform_library()
+            .list()
+            .into_iter()
+            .map(|w| w.name)
+            .collect(),
+    }));
+
+    let mut current = device_id;
+
+    println!("DG-LAB interactive control. Type 'help' for commands, Tab to complete.");
+
+    loop {
+        // 设备可能被移除，回退到第一个设备
+        let device_ids = app.session_manager().list_devices().await;
+        if current.as_ref().map_or(true, |id| !device_ids.contains(id)) {
+            current = device_ids.first().cloned();
+        }
+        if let Some(helper) = editor.helper_mut() {
+            helper.device_ids = device_ids;
+        }
+
+        let prompt = format!("{} > ", status_line(app, current.as_deref()).await);
+        let line = match tokio::task::block_in_place(|| editor.readline(&prompt)) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => {
+                println!("Type 'quit' or press Ctrl-D to exit");
+                continue;
+            }
+            Err(ReadlineError::Eof) => break,
+            Err(e) => return Err(readline_error(e)),
+        };
+
+        let command = match parse_command(&line) {
+            Ok(Some(command)) => command,
+            Ok(None) => continue,
+            Err(message) => {
+                println!("{}", message);
+                continue;
+            }
+        };
+        let _ = editor.add_history_entry(line.as_str());
+
+        debug!("REPL command: {:?}", command);
+
+        if matches!(command, ReplCommand::Quit) {
+            break;
+        }
+
+        if let Err(e) = execute(app, &mut current, command).await {
+            println!("Error: {}", e);
+        }
+    }
+
+    // 退出前停止输出（失败的设备已逐个记录日志）
+    let _ = app.session_manager().stop_all().await;
+
+    Ok(())
+}
+
+/// 执行 REPL 命令
+async fn execute(
+    app: &mut DglabCli,
+    current: &mut Option<String>,
+    command: ReplCommand,
+) -> Result<()> {
+    match command {
+        ReplCommand::Help => println!("{}", HELP),
+        ReplCommand::Stats => {
+            let stats = app.session_manager().stats();
+            if stats.is_empty() {
+                println!("No devices. Use 'connect' or 'mock'.");
+            }
+            for stats in &stats {
+                super::control::print_stats(stats);
+            }
+        }
+        ReplCommand::Devices => {
+            let device_ids = app.session_manager().list_devices().await;
+            if device_ids.is_empty() {
+                println!("No devices. Use 'connect' or 'mock'.");
+            }
+            for id in device_ids {
+                let marker = if current.as_deref() == Some(id.as_str()) {
+                    "*"
+                } else {
+                    " "
+                };
+                println!("{} {}", marker, id);
+            }
+        }
+        ReplCommand::Use(id) => {
+            if app.session_manager().get_device(&id).await.is_none() {
+                return Err(CliError::DeviceNotFound(id));
+            }
+            println!("Using device {}", id);
+            *current = Some(id);
+        }
+        ReplCommand::Connect(args) => {
+            let args =
+                ConnectArgs::try_parse_from(std::iter::once("connect".to_string()).chain(args))
+                    .map_err(|e| CliError::InvalidInput(e.to_string()))?;
+            app.connect(args).await?;
+        }
+        ReplCommand::Mock => {
+            let id = format!(
+                "mock-{}",
+                app.session_manager().list_devices().await.len() + 1
+            );
+            let mut device = MockDevice::new(id.clone(), "Mock Device".to_string());
+            device.connect().await?;
+            app.session_manager().add_device(Box::new(device)).await?;
+            println!("Added mock device {}", id);
+            *current = Some(id);
+        }
+        ReplCommand::Quit => {}
+        ReplCommand::Power { channel, power } => {
+            let id = current.clone().ok_or(CliError::NoDevice)?;
+            let session = app.session_manager();
+            match channel {
+                Some(channel) => {
+                    let _ = session.set_power(&id, channel, power).await?;
+                }
+                None => {
+                    let _ = session.set_power(&id, 0, power).await?;
+                    let _ = session.set_power(&id, 1, power).await?;
+                }
+            }
+        }
+        ReplCommand::Wave { channel, name } => {
+            let id = current.clone().ok_or(CliError::NoDevice)?;
+            let waveform = app.waveform_library().resolve(&name)?;
+            app.session_manager()
+                .set_waveform(&id, channel, waveform.to_device_config())
+                .await?;
+            println!("Applied waveform {}", waveform.name);
+        }
+        ReplCommand::BatteryOverride(overridden) => {
+            let id = current.clone().ok_or(CliError::NoDevice)?;
+            app.session_manager()
+                .set_battery_override(&id, overridden)
+                .await?;
+            if overridden {
+                println!("Low battery limit overridden");
+            } else {
+                println!(
+                    "Low battery limit: {}",
+                    app.session_manager().battery_limit(&id)
+                );
+            }
+        }
+        ReplCommand::Start => {
+            let id = current.clone().ok_or(CliError::NoDevice)?;
+            app.session_manager().start(&id).await?;
+        }
+        ReplCommand::Link(link) => {
+            let id = current.clone().ok_or(CliError::NoDevice)?;
+            app.session_manager().set_channel_link(&id, link).await?;
+        }
+        ReplCommand::Stop => {
+            let id = current.clone().ok_or(CliError::NoDevice)?;
+            app.session_manager().stop(&id).await?;
+        }
+        command => {
+            let id = current.clone().ok_or(CliError::NoDevice)?;
+            let device = app
+                .session_manager()
+                .get_device(&id)
+                .await
+                .ok_or_else(|| CliError::DeviceNotFound(id.clone()))?;
+            let mut dev = device.write().await;
+
+            match command {
+                ReplCommand::Status => {
+                    let info = dev.info();
+                    println!("ID:      {}", info.id);
+                    println!("Name:    {}", info.name);
+                    println!("State:   {:?}", dev.state());
+                    super::control::print_power(&info);
+                    match dev.channel_link() {
+                        Some(link) => println!("Link:    {}", link),
+                        None => println!("Link:    off"),
+                    }
+                    println!("Battery: {}%", info.battery_level);
+                    println!("Limit:   {}", app.session_manager().battery_limit(&id));
+                    super::control::print_versions(&info);
+                }
+                ReplCommand::Channel { channel, enabled } => {
+                    dev.set_channel_enabled(channel, enabled).await?
+                }
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// 生成状态行（作为提示符显示）
+async fn status_line(app: &DglabCli, device_id: Option<&str>) -> String {
+    let Some(id) = device_id else {
+        return "[no device]".to_string();
+    };
+    let Some(device) = app.session_manager().get_device(id).await else {
+        return "[no device]".to_string();
+    };
+
+    let dev = device.read().await;
+    let info = dev.info();
+    let link = if dev.channel_link().is_some() {
+        " linked"
+    } else {
+        ""
+    };
+
+    format!(
+        "[{} {:?} A:{}/{} B:{}/{}{}]",
+        info.name,
+        dev.state(),
+        info.power_a,
+        info.max_power_a,
+        info.power_b,
+        info.max_power_b,
+        link
+    )
+}
+
+/// 转换行编辑错误
+fn readline_error(e: ReadlineError) -> CliError {
+    CliError::Other(format!("Line editor error: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_command() {
+        let cases = [
+            ("", None),
+            ("   ", None),
+            ("help", Some(ReplCommand::Help)),
+            ("? power", Some(ReplCommand::Help)),
+            ("STATUS", Some(ReplCommand::Status)),
+            ("stats", Some(ReplCommand::Stats)),
+            ("devices", Some(ReplCommand::Devices)),
+            ("use dev-1", Some(ReplCommand::Use("dev-1".to_string()))),
+            ("connect", Some(ReplCommand::Connect(Vec::new()))),
+            (
+                "connect --name 47L",
+                Some(ReplCommand::Connect(vec![
+                    "--name".to_string(),
+                    "47L".to_string(),
+                ])),
+            ),
+            ("mock", Some(ReplCommand::Mock)),
+            (
+                "power 30",
+                Some(ReplCommand::Power {
+                    channel: None,
+                    power: 30,
+                }),
+            ),
+            (
+                "power A 30",
+                Some(ReplCommand::Power {
+                    channel: Some(0),
+                    power: 30,
+                }),
+            ),
+            (
+                "power both 0",
+                Some(ReplCommand::Power {
+                    channel: None,
+                    power: 0,
+                }),
+            ),
+            (
+                "wave b Breathing",
+                Some(ReplCommand::Wave {
+                    channel: 1,
+                    name: "Breathing".to_string(),
+                }),
+            ),
+            ("link off", Some(ReplCommand::Link(None))),
+            (
+                "link 0.5:10",
+                Some(ReplCommand::Link(Some(ChannelLink::new(0.5, 10).unwrap()))),
+            ),
+            (
+                "channel a off",
+                Some(ReplCommand::Channel {
+                    channel: 0,
+                    enabled: false,
+                }),
+            ),
+            (
+                "channel 1 ON",
+                Some(ReplCommand::Channel {
+                    channel: 1,
+                    enabled: true,
+                }),
+            ),
+            ("battery override", Some(ReplCommand::BatteryOverride(true))),
+            ("battery enforce", Some(ReplCommand::BatteryOverride(false))),
+            ("start", Some(ReplCommand::Start)),
+            ("stop", Some(ReplCommand::Stop)),
+            ("quit", Some(ReplCommand::Quit)),
+            ("exit", Some(ReplCommand::Quit)),
+        ];
+        for (line, expected) in cases {
+            assert_eq!(parse_command(line), Ok(expected), "{:?}", line);
+        }
+    }
+
+    #[test]
+    fn test_parse_command_errors() {
+        let cases = [
+            ("frobnicate", "Unknown command"),
+            ("status now", "Invalid arguments"),
+            ("use", "Invalid arguments"),
+            ("power", "Invalid arguments"),
+            ("power 300", "Invalid power"),
+            ("power a -1", "Invalid power"),
+            ("power c 10", "Invalid channel"),
+            ("wave both Breathing", "Invalid channel"),
+            ("link 0.5:x", "Invalid channel link"),
+            ("channel a maybe", "Invalid channel state"),
+            ("battery", "Invalid arguments"),
+        ];
+        for (line, message) in cases {
+            match parse_command(line) {
+                Err(e) => assert!(e.contains(message), "{:?}: {}", line, e),
+                Ok(command) => panic!("{:?} parsed as {:?}", line, command),
+            }
+        }
+    }
+}
@@ -4,6 +4,12 @@ use clap::Parser;
 use std::time::Duration;
 use tracing::info;
 
+use dglab_core::device::simulator::simulated_devices;
+use dglab_protocol::ble::BleManager;
+use dglab_protocol::error::ProtocolError;
+
+use crate::progress::Progress;
+
 use super::DglabCli;
 
 /// 扫描设备参数
@@ -12,25 +18,57 @@ pub struct ScanArgs {
     /// 扫描持续时间（秒）
     #[arg(short, long, default_value = "5")]
     duration: u64,
+
+    /// 列出模拟设备（不使用蓝牙，可用 `connect --simulated` 连接）
+    #[arg(long)]
+    simulated: bool,
+
+    /// 模拟设备数量
+    #[arg(long, default_value = "1", requires = "simulated")]
+    count: usize,
+
+    /// 列出蓝牙适配器（序号和名称可用于 `--adapter`）
+    #[arg(long, conflicts_with = "simulated")]
+    pub adapters: bool,
 }
 
 /// 执行扫描命令
 pub async fn execute(app: &mut DglabCli, args: ScanArgs) -> crate::error::Result<()> {
-    info!("Starting BLE scan for {} seconds...", args.duration);
-
-    let ble_manager = app
-        .ble_manager()
-        .expect("BLE manager should be initialized");
-
-    ble_manager.start_scan().await?;
+    if args.adapters {
+        let adapters = BleManager::list_adapters().await?;
+        if adapters.is_empty() {
+            println!("No Bluetooth adapters found.");
+        }
+        for adapter in adapters {
+            println!("{}", adapter);
+        }
+        return Ok(());
+    }
 
-    // 等待扫描
-    tokio::time::sleep(Duration::from_secs(args.duration)).await;
+    let results = if args.simulated {
+        simulated_devices(args.count)
+    } else {
+        info!("Starting BLE scan for {} seconds...", args.duration);
 
-    ble_manager.stop_scan().await?;
+        let ble_manager = app.get_or_init_ble().await?;
 
-    // 获取扫描结果
-    let results = ble_manager.get_scan_results().await?;
+        // Ctrl-C 立即停止扫描
+        let progress = Progress::spinner(format!(
+            "Scanning for {}s (Ctrl-C to cancel)...",
+            args.duration
+        ));
+        match ble_manager
+            .scan_for(Duration::from_secs(args.duration), progress.token())
+            .await
+        {
+            Err(ProtocolError::Cancelled) => {
+                drop(progress);
+                println!("Scan cancelled");
+                return Ok(());
+            }
+            result => result?,
+        }
+    };
 
     println!("\nFound {} devices:", results.len());
     println!("{}", "-".repeat(60));
@@ -47,6 +85,7 @@ pub async fn execute(app: &mut DglabCli, args: ScanArgs) -> crate::error::Result
             println!("   Name:    {}", device.name);
             println!("   Address: {}", device.address);
             println!("   Signal:  {}", rssi);
+            println!("   Adapter: {}", device.adapter);
             println!();
         }
     }
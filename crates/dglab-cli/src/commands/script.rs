@@ -1,16 +1,85 @@
-//! 脚本命令（待实现）
+//! 脚本命令
+//!
+//! `.toml` / `.yaml` / `.yml` 文件按声明式波形编排执行，其它脚本格式尚未实现。
+
+use std::path::PathBuf;
+use std::time::Duration;
 
 use clap::Parser;
+use tracing::info;
+
+use dglab_core::script::{Pattern, PatternRunner};
+
+use super::DglabCli;
 
 /// 脚本参数
 #[derive(Parser, Debug)]
 pub struct ScriptArgs {
     /// 脚本文件路径
-    script_file: String,
+    script_file: PathBuf,
+
+    /// 设备 ID（如果不指定，使用第一个设备）
+    #[arg(short, long)]
+    device: Option<String>,
+
+    /// 只校验编排，不执行
+    #[arg(long)]
+    check: bool,
 }
 
 /// 执行脚本命令
-pub async fn execute(_app: &mut super::DglabCli, _args: ScriptArgs) -> crate::error::Result<()> {
-    println!("Script execution not implemented yet");
+pub async fn execute(app: &mut DglabCli, args: ScriptArgs) -> crate::error::Result<()> {
+    if !Pattern::is_pattern_file(&args.script_file) {
+        println!("Script execution not implemented yet");
+        return Ok(());
+    }
+
+    let pattern = Pattern::load(&args.script_file).await?;
+    let runner = PatternRunner::with_library(pattern, app.waveform_library())?;
+    let total = runner.steps().iter().map(|s| s.duration).sum::<Duration>();
+
+    println!(
+        "Pattern '{}': {} steps, {:.1}s",
+        runner.pattern().name,
+        runner.steps().len(),
+        total.as_secs_f64()
+    );
+
+    if args.check {
+        println!("Pattern is valid");
+        return Ok(());
+    }
+
+    // 获取设备
+    let device_ids = app.session_manager().list_devices().await;
+    let Some(device_id) = args.device.or_else(|| device_ids.first().cloned()) else {
+        println!("No connected devices. Use 'connect' command first.");
+        return Ok(());
+    };
+
+    let session = app.session_manager();
+    if session.get_device(&device_id).await.is_none() {
+        println!("Device not found: {}", device_id);
+        return Ok(());
+    }
+
+    info!("Running pattern on device {}", device_id);
+    println!("Running on '{}', press Ctrl+C to stop", device_id);
+
+    let run = runner.run(session, &device_id);
+    tokio::pin!(run);
+
+    tokio::select! {
+        result = &mut run => result?,
+        _ = tokio::signal::ctrl_c() => {
+            runner.cancel();
+            // 等待执行器归零后退出
+            run.await?;
+            println!("Pattern stopped");
+            return Ok(());
+        }
+    }
+
+    println!("Pattern finished");
     Ok(())
 }
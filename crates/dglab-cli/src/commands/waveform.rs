@@ -0,0 +1,158 @@
+//! 波形库命令
+
+use std::path::PathBuf;
+
+use clap::Parser;
+use tracing::info;
+
+use dglab_core::waveform::WaveFile;
+
+use super::DglabCli;
+use crate::error::{CliError, Result};
+
+/// 波形库子命令
+#[derive(Parser, Debug)]
+pub struct WaveformArgs {
+    #[command(subcommand)]
+    command: WaveformCommand,
+}
+
+/// 波形子命令
+#[derive(Parser, Debug)]
+enum WaveformCommand {
+    /// 列出所有波形（内置和波形库）
+    List,
+    /// 显示波形详情
+    Show { name: String },
+    /// 导入 APP 格式的 HEX 波形数组，保存为 .dgwave 文件
+    Import {
+        /// 波形名称
+        name: String,
+        /// HEX 波形（每条 16 个字符，如 0a0a0a0a64646464）
+        pulses: Vec<String>,
+        /// 从文件读取 HEX 波形（支持 JSON 数组或按空白/逗号分隔）
+        #[arg(short, long, conflicts_with = "pulses")]
+        file: Option<PathBuf>,
+        /// 波形描述
+        #[arg(short, long)]
+        description: Option<String>,
+        /// 覆盖已存在的同名波形文件
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+/// 执行波形命令
+pub async fn execute(app: &mut DglabCli, args: WaveformArgs) -> Result<()> {
+    match args.command {
+        WaveformCommand::List => {
+            let library = app.waveform_library();
+            let waveforms = library.list();
+
+            println!("\nWaveforms ({}):", waveforms.len());
+            println!("{}", "-".repeat(50));
+
+            for waveform in waveforms {
+                let source = if library.is_user_waveform(&waveform.name) {
+                    "library"
+                } else {
+                    "builtin"
+                };
+                println!("  - {} [{}]", waveform.name, source);
+                if !waveform.description.is_empty() {
+                    println!("    {}", waveform.description);
+                }
+            }
+
+            if let Some(dir) = library.dir() {
+                println!("\nLibrary directory: {}", dir.display());
+            }
+        }
+
+        WaveformCommand::Show { name } => {
+            let waveform = app.waveform_library().resolve(&name)?;
+
+            println!("\nWaveform: {}", waveform.name);
+            println!("{}", "-".repeat(50));
+            println!("Description: {}", waveform.description);
+
+            match &waveform.frames {
+                Some(frames) => {
+                    println!(
+                        "Frames:      {} ({:.1}s per cycle)",
+                        frames.len(),
+                        frames.len() as f64 * 0.1
+                    );
+                    for frame in frames {
+                        println!(
+                            "  {}  freq {:?}  intensity {:?}",
+                            frame.to_hex_string(),
+                            frame.frequency,
+                            frame.intensity
+                        );
+                    }
+                }
+                None => {
+                    let params = &waveform.params;
+                    println!("Type:        {:?}", params.waveform_type);
+                    println!("Frequency:   {} Hz", params.frequency);
+                    println!("Pulse Width: {} us", params.pulse_width);
+                    println!("Power:       {} ~ {}", params.min_power, params.max_power);
+                    println!("Period:      {} ms", params.period_ms);
+                }
+            }
+        }
+
+        WaveformCommand::Import {
+            name,
+            pulses,
+            file,
+            description,
+            force,
+        } => {
+            let pulses = match file {
+                Some(path) => tokio::fs::read_to_string(&path)
+                    .await?
+                    .split(|c: char| !c.is_ascii_alphanumeric())
+                    .filter(|s| !s.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+                None => pulses,
+            };
+            if pulses.is_empty() {
+                return Err(CliError::InvalidInput("No pulses given".to_string()));
+            }
+
+            let wave_file =
+                WaveFile::from_app_pulses(name.clone(), description.unwrap_or_default(), &pulses)?;
+            // 提前校验，避免写入无法加载的文件
+            let waveform = wave_file.to_waveform()?;
+
+            let mut library = app.waveform_library().clone();
+            let path = library
+                .user_path(&name)
+                .ok_or_else(|| CliError::Other("Waveform library has no directory".to_string()))?;
+            if path.exists() && !force {
+                return Err(CliError::InvalidInput(format!(
+                    "{} already exists, use --force to overwrite",
+                    path.display()
+                )));
+            }
+
+            wave_file.save(&path).await?;
+            info!("Saved waveform '{}' to {:?}", name, path);
+
+            library.insert(waveform);
+            app.set_waveform_library(library).await;
+
+            println!(
+                "Imported waveform '{}' ({} frames) to {}",
+                name,
+                pulses.len(),
+                path.display()
+            );
+        }
+    }
+
+    Ok(())
+}
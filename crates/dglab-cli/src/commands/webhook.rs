@@ -0,0 +1,79 @@
+//! Webhook 命令
+//!
+//! 连接指定的 BLE 设备后监听 HTTP 请求，把 `POST /trigger/{preset}`、`POST /power?a=30`
+//! 等请求转为会话操作，供 Stream Deck、IFTTT 等工具触发。路由见
+//! `dglab_core::webhook::routes`，请求需要 `[access]` 中配置的令牌。
+
+use clap::Args;
+use clap_complete::engine::ArgValueCandidates;
+
+use crate::commands::completions::device_candidates;
+use crate::commands::DglabCli;
+use crate::error::{CliError, Result};
+
+use dglab_core::webhook::{WebhookConfig, WebhookServer};
+
+/// Webhook 参数
+#[derive(Debug, Args)]
+pub struct WebhookArgs {
+    /// 监听地址（ip:port，默认使用配置文件 [webhook] 段或 127.0.0.1:8787）
+    #[arg(short, long)]
+    pub bind: Option<String>,
+
+    /// 设备名称或 ID，可重复指定；未指定时使用配置文件中的常用设备
+    #[arg(short, long, add = ArgValueCandidates::new(device_candidates))]
+    pub device: Vec<String>,
+}
+
+/// 执行 Webhook 服务
+pub async fn execute(cli: &mut DglabCli, args: WebhookArgs) -> Result<()> {
+    let app_config = cli.config();
+    let mut config = app_config.webhook.clone().unwrap_or_default();
+    if let Some(bind) = args.bind {
+        config = WebhookConfig::new(bind);
+    }
+    let server = WebhookServer::new(config, app_config.access.clone())?;
+
+    if cli.dry_run().is_none() {
+        let names = if args.device.is_empty() {
+            app_config
+                .favorite_devices
+                .iter()
+                .map(|d| d.id.clone())
+                .collect::<Vec<_>>()
+        } else {
+            args.device
+        };
+        if names.is_empty() {
+            return Err(CliError::InvalidInput(
+                "No device given, use --device or add favorite_devices to the config file"
+                    .to_string(),
+            ));
+        }
+        let _ = super::mqtt::connect_devices(cli, &names).await?;
+    }
+
+    let listener = server.bind().await?;
+    println!(
+        "Webhook: http://{} (POST /trigger/<preset>, /power?a=30, /estop ...), press Ctrl+C to stop",
+        listener.local_addr()?
+    );
+    for device_id in cli.session_manager().list_devices().await {
+        println!("  • {}", device_id);
+    }
+
+    let result = tokio::select! {
+        result = server.serve(listener, cli.session_manager(), cli.preset_manager()) => {
+            result.map_err(CliError::from)
+        }
+        _ = tokio::signal::ctrl_c() => {
+            println!();
+            println!("Stopping");
+            Ok(())
+        }
+    };
+
+    // 失败的设备已逐个记录日志
+    let _ = cli.session_manager().stop_all().await;
+    result
+}
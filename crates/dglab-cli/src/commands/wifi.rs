@@ -1,11 +1,33 @@
 //! WiFi 连接命令
 
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
 use clap::Parser;
 use qrcode::{render::unicode, QrCode};
-use tracing::{debug, info};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use tracing::{debug, info, warn};
 
 use super::DglabCli;
-use dglab_core::device::{Device, DeviceState, WsCoyoteDevice};
+use crate::error::CliError;
+use crate::progress::Progress;
+use dglab_core::device::{Device, DeviceState, WsCoyoteDevice, DRY_RUN_DEVICE_ID};
+use dglab_core::feedback::{FeedbackMapping, FeedbackRouter};
+use dglab_core::session::SessionEvent;
+use dglab_protocol::wifi::{ServerAddress, WsClient, DEFAULT_PROBE_TIMEOUT};
+
+/// 保存上次绑定的文件名（位于配置目录）
+const SAVED_BINDING_FILE: &str = "wifi_binding.json";
+
+/// 恢复绑定的等待时间
+const RESUME_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 等待服务器分配 clientId 的时间
+const CLIENT_ID_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 检查 APP 扫码绑定的间隔
+const BIND_POLL_INTERVAL: Duration = Duration::from_millis(500);
 
 /// WiFi 子命令
 #[derive(Parser, Debug)]
@@ -19,9 +41,27 @@ pub struct WifiArgs {
 enum WifiCommand {
     /// 连接 WiFi 设备（显示二维码）
     Connect {
-        /// 自定义服务器地址（可选）
+        /// 自定义服务器地址（ws://、wss:// 或 host:port，默认使用配置文件中的地址）
+        #[arg(short, long)]
+        server: Option<ServerAddress>,
+        /// 接受自签名证书（仅用于局域网 wss 服务器）
+        #[arg(long)]
+        insecure: bool,
+        /// 先以上次保存的绑定免扫码连接，失败时再显示二维码
+        #[arg(long)]
+        resume: bool,
+    },
+    /// 测试服务器连通性（连接耗时、clientId 下发和心跳往返时间）
+    Test {
+        /// 服务器地址（ws://、wss:// 或 host:port，默认使用配置文件中的地址）
         #[arg(short, long)]
-        server: Option<String>,
+        server: Option<ServerAddress>,
+        /// 接受自签名证书（仅用于局域网 wss 服务器）
+        #[arg(long)]
+        insecure: bool,
+        /// 超时（秒）
+        #[arg(short, long, default_value_t = DEFAULT_PROBE_TIMEOUT.as_secs())]
+        timeout: u64,
     },
     /// 断开 WiFi 设备
     Disconnect,
@@ -44,10 +84,66 @@ enum WifiCommand {
     },
 }
 
+/// 上次成功绑定的身份，供 `wifi connect --resume` 使用
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct SavedBinding {
+    /// 服务器地址
+    server: String,
+    /// 本端 clientId（服务器每次连接重新分配，仅作记录）
+    client_id: String,
+    /// APP 的 targetId
+    target_id: String,
+}
+
+impl SavedBinding {
+    /// 保存文件路径（与配置文件同目录）
+    fn path(app: &DglabCli) -> Option<PathBuf> {
+        app.config_manager()
+            .path()
+            .parent()
+            .map(|dir| dir.join(SAVED_BINDING_FILE))
+    }
+
+    /// 读取保存的绑定，文件不存在或内容无效时返回 `None`
+    async fn load(path: &Path) -> Option<Self> {
+        let content = tokio::fs::read_to_string(path).await.ok()?;
+        match serde_json::from_str(&content) {
+            Ok(binding) => Some(binding),
+            Err(e) => {
+                warn!("Ignoring invalid WiFi binding {:?}: {}", path, e);
+                None
+            }
+        }
+    }
+
+    /// 保存到文件
+    async fn save(&self, path: &Path) -> crate::error::Result<()> {
+        if let Some(dir) = path.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        let content =
+            serde_json::to_string_pretty(self).map_err(|e| CliError::Other(e.to_string()))?;
+        tokio::fs::write(path, content).await?;
+        Ok(())
+    }
+}
+
 /// 执行 WiFi 命令
 pub async fn execute(app: &mut DglabCli, args: WifiArgs) -> crate::error::Result<()> {
     match args.command {
-        WifiCommand::Connect { server } => {
+        WifiCommand::Connect {
+            server,
+            insecure,
+            resume,
+        } => {
+            if app.dry_run().is_some() {
+                println!(
+                    "Dry run: using device '{}', no server connection is made",
+                    DRY_RUN_DEVICE_ID
+                );
+                return Ok(());
+            }
+
             info!("Connecting to WiFi...");
 
             let device_id = uuid::Uuid::new_v4().to_string();
@@ -58,95 +154,91 @@ pub async fn execute(app: &mut DglabCli, args: WifiArgs) -> crate::error::Result
             println!("╚══════════════════════════════════════════════════════╝\n");
 
             // 先创建 WsCoyoteDevice，连接并显示二维码
-            let mut wifi_device = if let Some(srv) = &server {
-                println!("📡 正在连接到自定义服务器: {}", srv);
-                WsCoyoteDevice::with_server(device_id.clone(), device_name.clone(), srv.clone())
+            let config = app.config();
+            let srv = match server {
+                Some(srv) => srv.accept_invalid_certs(insecure),
+                None => config.server_address()?,
+            };
+            if srv == ServerAddress::official() {
+                println!("📡 正在连接到官方服务器: {}", srv);
             } else {
-                println!("📡 正在连接到官方服务器: wss://ws.dungeon-lab.cn");
-                WsCoyoteDevice::new(device_id.clone(), device_name.clone())
+                println!("📡 正在连接到自定义服务器: {}", srv);
+            }
+            let binding_path = SavedBinding::path(app);
+            let saved = match &binding_path {
+                Some(path) if resume => SavedBinding::load(path).await,
+                _ => None,
             };
-
-            // 连接到 WebSocket 服务器
-            print!("⏳ 建立 WebSocket 连接... ");
-            wifi_device.connect().await?;
-            println!("✓");
+            let mut wifi_device =
+                WsCoyoteDevice::with_server(device_id.clone(), device_name.clone(), srv);
+            wifi_device.set_reconnect_policy(config.reconnect.policy());
+
+            // 连接到 WebSocket 服务器，Ctrl+C 取消
+            let progress = Progress::spinner("建立 WebSocket 连接（Ctrl+C 取消）...");
+            let Some(result) = progress.run(wifi_device.connect()).await else {
+                drop(progress);
+                println!("⚠️  已取消");
+                let _ = wifi_device.disconnect().await;
+                return Ok(());
+            };
+            result?;
 
             // 等待获取 clientId
-            print!("⏳ 等待服务器分配 ID... ");
-            let mut retries = 0;
-            let qr_url = loop {
-                if let Some(url) = wifi_device.qr_url().await {
-                    println!("✓");
-                    break url;
+            progress.set_message("等待服务器分配 ID（Ctrl+C 取消）...");
+            let Some(qr_url) = progress.run(wait_qr_url(&wifi_device)).await else {
+                drop(progress);
+                println!("⚠️  已取消");
+                let _ = wifi_device.disconnect().await;
+                return Ok(());
+            };
+            let Some(qr_url) = qr_url else {
+                drop(progress);
+                println!("❌ 错误: 超时未收到服务器 clientId");
+                let _ = wifi_device.disconnect().await;
+                return Ok(());
+            };
+            progress.finish("✓ 服务器已分配 ID");
+
+            // 先尝试恢复上次的绑定，失败时回退到扫码
+            let resumed = match &saved {
+                _ if !resume => false,
+                None => {
+                    println!("⚠️  没有保存的绑定，改用扫码绑定");
+                    false
                 }
-                tokio::time::sleep(std::time::Duration::from_millis(200)).await;
-                retries += 1;
-                if retries > 25 {
-                    // 5 秒超时
-                    println!("✗");
-                    println!("\n❌ 错误: 超时未收到服务器 clientId");
-                    return Ok(());
+                Some(saved) if saved.server != wifi_device.server_url() => {
+                    println!("⚠️  保存的绑定属于服务器 {}，改用扫码绑定", saved.server);
+                    false
                 }
+                Some(saved) => resume_binding(&wifi_device, saved).await,
             };
-
-            // 显示二维码
-            println!("\n╔══════════════════════════════════════════════════════╗");
-            println!("║              📱 请使用 DG-LAB APP 扫码               ║");
-            println!("╚══════════════════════════════════════════════════════╝\n");
-
-            // 生成并显示 ASCII 二维码
-            if let Ok(code) = QrCode::new(&qr_url) {
-                let qr_string = code
-                    .render::<unicode::Dense1x2>()
-                    .dark_color(unicode::Dense1x2::Light)
-                    .light_color(unicode::Dense1x2::Dark)
-                    .build();
-                println!("{}", qr_string);
-            } else {
-                println!("⚠️  无法生成二维码，请手动输入以下 URL：");
+            if !resumed && !scan_bind(&wifi_device, &qr_url).await {
+                let _ = wifi_device.disconnect().await;
+                return Ok(());
             }
 
-            println!("\n🔗 连接 URL:");
-            println!("   {}\n", qr_url);
-
-            // 等待绑定
-            print!("⏳ 等待 APP 扫码绑定");
-            let mut dots = 0;
-            loop {
-                if wifi_device.is_bound().await {
-                    println!(" ✓\n");
-                    break;
-                }
-
-                // 检查设备状态
-                match wifi_device.state() {
-                    DeviceState::Connected => {
-                        // 继续等待
-                    }
-                    DeviceState::Disconnected => {
-                        println!(" ✗\n");
-                        println!("❌ 连接已断开");
-                        return Ok(());
-                    }
-                    _ => {}
-                }
-
-                // 显示动画
-                print!(".");
-                if let Err(e) = std::io::Write::flush(&mut std::io::stdout()) {
-                    debug!("Failed to flush stdout: {}", e);
-                }
-                dots += 1;
-                if dots > 60 {
-                    // 每行最多 60 个点
-                    print!("\n⏳ 仍在等待 APP 扫码绑定");
-                    dots = 0;
+            // 记录本次绑定，供下次 --resume 使用
+            if let (Some(path), Some(client_id), Some(target_id)) = (
+                &binding_path,
+                wifi_device.client_id().await,
+                wifi_device.target_id().await,
+            ) {
+                let binding = SavedBinding {
+                    server: wifi_device.server_url().to_string(),
+                    client_id,
+                    target_id,
+                };
+                if let Err(e) = binding.save(path).await {
+                    warn!("Failed to save WiFi binding: {}", e);
                 }
-
-                tokio::time::sleep(std::time::Duration::from_millis(500)).await;
             }
 
+            // 加载反馈按钮映射
+            let mapping = FeedbackMapping::load(&FeedbackMapping::default_path()?).await?;
+            let mut router = FeedbackRouter::new(mapping);
+
             // 添加到会话管理器
+            let mut events = app.session_manager().subscribe_events();
             app.session_manager()
                 .add_device(Box::new(wifi_device))
                 .await?;
@@ -160,10 +252,79 @@ pub async fn execute(app: &mut DglabCli, args: WifiArgs) -> crate::error::Result
 
             // 保持连接，等待用户中断
             println!("⚡ WiFi 连接已建立，按 Ctrl+C 退出...\n");
-            tokio::signal::ctrl_c().await?;
+            loop {
+                tokio::select! {
+                    result = tokio::signal::ctrl_c() => {
+                        result?;
+                        break;
+                    }
+                    event = events.recv() => match event {
+                        Ok(SessionEvent::Feedback(id, button)) => {
+                            match router
+                                .dispatch(app.session_manager(), app.preset_manager(), &id, button)
+                                .await
+                            {
+                                Ok(Some(action)) => println!("🔘 反馈按钮 {:?}: {}", button, action),
+                                Ok(None) => debug!("Unmapped feedback button: {:?}", button),
+                                Err(e) => warn!("Failed to handle feedback {:?}: {}", button, e),
+                            }
+                        }
+                        Ok(SessionEvent::MaxPowerChanged(_, max_a, max_b)) => {
+                            println!("📶 APP 强度上限: A={} B={}", max_a, max_b);
+                        }
+                        Ok(SessionEvent::BatteryLimited(_, action)) => {
+                            println!("🔋 低电量限制: {}", action);
+                        }
+                        Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {}
+                        Err(broadcast::error::RecvError::Closed) => {
+                            tokio::signal::ctrl_c().await?;
+                            break;
+                        }
+                    },
+                }
+            }
             println!("\n👋 正在断开连接...");
         }
 
+        WifiCommand::Test {
+            server,
+            insecure,
+            timeout,
+        } => {
+            let server = match server {
+                Some(server) => server.accept_invalid_certs(insecure),
+                None => app.config().server_address()?,
+            };
+
+            println!("\nTesting {}", server);
+            println!("{}", "-".repeat(50));
+            let report = WsClient::probe(&server, Duration::from_secs(timeout.max(1)))
+                .await
+                .map_err(|e| CliError::Other(format!("Cannot connect to {}: {}", server, e)))?;
+
+            let ms = |d: Option<Duration>| match d {
+                Some(d) => format!("{}ms", d.as_millis()),
+                None => "timeout".to_string(),
+            };
+            println!("Connect:   {}ms", report.connect_time.as_millis());
+            println!("Client ID: {}", ms(report.client_id_time));
+            println!("Heartbeat: {}", ms(report.heartbeat_rtt));
+            if let Some(error) = report.error {
+                println!("Error:     {}", error.description());
+            }
+            println!();
+
+            if report.client_id.is_none() {
+                println!("✗ Server did not assign a client ID, the APP QR code cannot bind");
+            } else if report.heartbeat_rtt.is_none() {
+                println!("⚠ Server did not answer the heartbeat, the connection may be dropped");
+            } else if report.is_ready() {
+                println!("✓ Server is ready for binding");
+            } else {
+                println!("✗ Server returned an error, binding may fail");
+            }
+        }
+
         WifiCommand::Disconnect => {
             info!("Disconnecting WiFi...");
 
@@ -214,13 +375,12 @@ pub async fn execute(app: &mut DglabCli, args: WifiArgs) -> crate::error::Result
             }
 
             let device_id = devices.first().unwrap();
-            let Some(device) = app.session_manager().get_device(device_id).await else {
+            let session = app.session_manager();
+            let Some(device) = session.get_device(device_id).await else {
                 println!("Device not found.");
                 return Ok(());
             };
 
-            let mut device = device.write().await;
-
             // 确定要操作的通道
             let channels = match channel {
                 Some(c) => match c.to_lowercase().as_str() {
@@ -234,29 +394,25 @@ pub async fn execute(app: &mut DglabCli, args: WifiArgs) -> crate::error::Result
                 None => vec![0, 1],
             };
 
-            // 执行操作
+            // 执行操作（经会话管理器写入，受校准和安全限制约束）
             for ch in channels {
                 if let Some(p) = power {
                     debug!("Setting channel {} power to {}", ch, p);
-                    device.set_power(ch, p).await?;
+                    let raw = session.set_power(device_id, ch, p).await?;
                     println!(
                         "Channel {} power set to {}",
                         if ch == 0 { "A" } else { "B" },
-                        p
+                        raw
                     );
                 } else if let Some(delta) = up {
-                    let current = device.get_power(ch);
-                    let new_power = current.saturating_add(delta).min(100);
-                    device.set_power(ch, new_power).await?;
+                    let new_power = session.adjust_power(device_id, ch, delta as i16).await?;
                     println!(
                         "Channel {} power increased to {}",
                         if ch == 0 { "A" } else { "B" },
                         new_power
                     );
                 } else if let Some(delta) = down {
-                    let current = device.get_power(ch);
-                    let new_power = current.saturating_sub(delta);
-                    device.set_power(ch, new_power).await?;
+                    let new_power = session.adjust_power(device_id, ch, -(delta as i16)).await?;
                     println!(
                         "Channel {} power decreased to {}",
                         if ch == 0 { "A" } else { "B" },
@@ -267,7 +423,7 @@ pub async fn execute(app: &mut DglabCli, args: WifiArgs) -> crate::error::Result
                     println!(
                         "Channel {} power: {}",
                         if ch == 0 { "A" } else { "B" },
-                        device.get_power(ch)
+                        device.read().await.get_power(ch)
                     );
                 }
             }
@@ -276,3 +432,85 @@ pub async fn execute(app: &mut DglabCli, args: WifiArgs) -> crate::error::Result
 
     Ok(())
 }
+
+/// 等待服务器分配 clientId，返回二维码 URL（超时返回 `None`）
+async fn wait_qr_url(device: &WsCoyoteDevice) -> Option<String> {
+    let deadline = tokio::time::Instant::now() + CLIENT_ID_TIMEOUT;
+    loop {
+        if let Some(url) = device.qr_url().await {
+            return Some(url);
+        }
+        if tokio::time::Instant::now() >= deadline {
+            return None;
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+}
+
+/// 以保存的绑定免扫码连接，成功时返回 `true`
+///
+/// APP 需仍在线（未退出 SOCKET 控制页面）且未绑定其他客户端；服务器返回错误或超时时
+/// 打印原因，由调用方回退到扫码。
+async fn resume_binding(device: &WsCoyoteDevice, saved: &SavedBinding) -> bool {
+    print!("⏳ 恢复上次的绑定 (APP {})... ", saved.target_id);
+    match device.rebind(&saved.target_id, RESUME_TIMEOUT).await {
+        Ok(_) => {
+            println!("✓\n");
+            true
+        }
+        Err(e) => {
+            println!("✗");
+            println!("⚠️  无法恢复绑定: {}，改用扫码绑定", e);
+            false
+        }
+    }
+}
+
+/// 显示二维码并等待 APP 扫码绑定，连接断开时返回 `false`
+async fn scan_bind(device: &WsCoyoteDevice, qr_url: &str) -> bool {
+    // 显示二维码
+    println!("\n╔══════════════════════════════════════════════════════╗");
+    println!("║              📱 请使用 DG-LAB APP 扫码               ║");
+    println!("╚══════════════════════════════════════════════════════╝\n");
+
+    // 生成并显示 ASCII 二维码
+    if let Ok(code) = QrCode::new(qr_url) {
+        let qr_string = code
+            .render::<unicode::Dense1x2>()
+            .dark_color(unicode::Dense1x2::Light)
+            .light_color(unicode::Dense1x2::Dark)
+            .build();
+        println!("{}", qr_string);
+    } else {
+        println!("⚠️  无法生成二维码，请手动输入以下 URL：");
+    }
+
+    println!("\n🔗 连接 URL:");
+    println!("   {}\n", qr_url);
+
+    // 等待绑定，Ctrl+C 取消
+    let progress = Progress::spinner("等待 APP 扫码绑定（Ctrl+C 取消）...");
+    loop {
+        if device.is_bound().await {
+            progress.finish("✓ APP 已绑定\n");
+            return true;
+        }
+
+        // 检查设备状态
+        if device.state() == DeviceState::Disconnected {
+            drop(progress);
+            println!("❌ 连接已断开");
+            return false;
+        }
+
+        if progress
+            .run(tokio::time::sleep(BIND_POLL_INTERVAL))
+            .await
+            .is_none()
+        {
+            drop(progress);
+            println!("⚠️  已取消绑定");
+            return false;
+        }
+    }
+}
@@ -30,6 +30,10 @@ pub enum CliError {
     #[error("No device connected")]
     NoDevice,
 
+    /// 蓝牙不可用（WiFi 命令不受影响）
+    #[error("Bluetooth is not available: {0} (WiFi commands such as `dglab wifi` still work)")]
+    BleUnavailable(String),
+
     /// 设备未找到
     #[error("Device not found: {0}")]
     DeviceNotFound(String),
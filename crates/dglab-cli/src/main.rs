@@ -1,10 +1,22 @@
 //! DG-LAB 命令行工具
 
-use clap::Parser;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use clap::{CommandFactory, Parser};
+use clap_complete::CompleteEnv;
+use dglab_core::config::ConfigManager;
+use dglab_core::device::DryRunTransport;
+use dglab_core::hooks::HookRunner;
+use dglab_core::session::{parse_duration, parse_stop_time, SessionEvent};
+use dglab_protocol::ble::traffic;
+use dglab_protocol::ble::{AdapterSelector, TrafficRecorder};
+use tokio::sync::broadcast;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 mod commands;
 mod error;
+mod progress;
 mod tui;
 
 use commands::DglabCli;
@@ -19,6 +31,33 @@ struct Cli {
     #[arg(short, long, global = true)]
     debug: bool,
 
+    /// 试运行：不连接硬件，打印将要发送的 B0/BF 帧（ble，默认）或 WebSocket 消息（--dry-run=ws）
+    #[arg(
+        long,
+        global = true,
+        value_name = "TRANSPORT",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "ble"
+    )]
+    dry_run: Option<DryRunTransport>,
+
+    /// 蓝牙适配器序号或名称（`dglab scan --adapters` 列出可用适配器）
+    #[arg(long, global = true, value_name = "ADAPTER")]
+    adapter: Option<AdapterSelector>,
+
+    /// 把原始 BLE 收发帧记录到 JSONL 文件（`dglab debug decode` 解码），也可用环境变量 DGLAB_BLE_LOG 指定
+    #[arg(long, global = true, value_name = "FILE")]
+    ble_log: Option<PathBuf>,
+
+    /// 会话最长运行时长（如 20m、1h30m），到时后强度渐变归零并停止输出
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration_arg)]
+    duration: Option<Duration>,
+
+    /// 会话停止时间（本地 HH:MM 或 RFC 3339），到时后强度渐变归零并停止输出
+    #[arg(long, value_name = "TIME", conflicts_with = "duration")]
+    until: Option<String>,
+
     /// 子命令
     #[command(subcommand)]
     command: Commands,
@@ -27,33 +66,97 @@ struct Cli {
 /// 子命令
 #[derive(Parser, Debug)]
 enum Commands {
+    /// 需要加载配置和会话的子命令
+    #[command(flatten)]
+    Session(SessionCommand),
+    /// 调试工具
+    Debug(commands::DebugArgs),
+    /// 查看和修改配置文件
+    Config(commands::ConfigArgs),
+    /// 环境诊断（蓝牙、服务器连通性、配置目录）
+    Doctor(commands::DoctorArgs),
+    /// 生成 shell 补全脚本
+    Completions(commands::CompletionsArgs),
+    /// 生成 man 手册
+    Manpages(commands::ManpagesArgs),
+}
+
+/// 需要加载配置和会话的子命令
+#[derive(Parser, Debug)]
+enum SessionCommand {
     /// 扫描设备
     Scan(commands::ScanArgs),
     /// 连接设备
     Connect(commands::ConnectArgs),
     /// 控制设备
     Control(commands::ControlArgs),
+    /// 单次输出：以指定强度和波形输出一段时间后渐变归零并断开
+    Fire(commands::FireArgs),
     /// 预设管理
     Preset(commands::PresetArgs),
+    /// APP 反馈按钮映射
+    Feedback(commands::FeedbackArgs),
     /// 运行脚本
     Script(commands::ScriptArgs),
+    /// 波形库管理
+    Waveform(commands::WaveformArgs),
     /// WiFi 连接
     Wifi(commands::WifiArgs),
     /// 桥接模式（BLE + WebSocket）
     Bridge(commands::BridgeArgs),
+    /// 加入远程会话（用 bridge --local 的邀请链接控制或查看对方的设备）
+    Remote(commands::RemoteArgs),
+    /// 会话事件日志
+    Log(commands::LogArgs),
+    /// BLE 吞吐量测试（往返延迟、发送抖动和丢帧）
+    Bench(commands::BenchArgs),
+    /// MQTT 集成（Home Assistant 等家庭自动化系统）
+    Mqtt(commands::MqttArgs),
+    /// Webhook 触发（Stream Deck、IFTTT 等通过 HTTP 请求触发预设和调节强度）
+    Webhook(commands::WebhookArgs),
     /// 启动 TUI 界面
     Tui,
 }
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    // 动态补全（COMPLETE=<shell> dglab），处理后直接退出
+    CompleteEnv::with_factory(Cli::command).complete();
+
     let cli = Cli::parse();
 
+    // 补全脚本、man 手册、配置和诊断命令不需要加载配置和会话（诊断把无效配置作为失败项报告）
+    let command = match cli.command {
+        Commands::Completions(args) => {
+            return Ok(commands::completions::completions(Cli::command(), args)?)
+        }
+        Commands::Manpages(args) => {
+            return Ok(commands::completions::manpages(Cli::command(), args)?)
+        }
+        Commands::Debug(args) => return Ok(commands::debug::execute(args)?),
+        Commands::Config(args) => return Ok(commands::config::execute(args).await?),
+        Commands::Doctor(args) => {
+            let dry_run = cli.dry_run.is_some();
+            return Ok(commands::doctor::execute(args, cli.adapter.as_ref(), dry_run).await?);
+        }
+        Commands::Session(command) => command,
+    };
+
+    // 加载配置（无效配置直接报错，不回退到默认的安全限制）
+    let config = ConfigManager::default_path()?;
+    if let Err(e) = config.load().await {
+        anyhow::bail!(
+            "Invalid config file {}: {}\nFix it with 'dglab config edit'",
+            config.path().display(),
+            e
+        );
+    }
+
     // 初始化日志
     let log_level = if cli.debug {
-        tracing::Level::DEBUG
+        "debug".to_string()
     } else {
-        tracing::Level::INFO
+        config.config().log_level
     };
 
     tracing_subscriber::registry()
@@ -64,19 +167,84 @@ async fn main() -> anyhow::Result<()> {
         .with(tracing_subscriber::fmt::layer())
         .init();
 
+    // BLE 流量记录需要在第一次收发之前安装
+    if let Some(path) = &cli.ble_log {
+        traffic::install(TrafficRecorder::create(path)?)?;
+    }
+
     // 执行命令
-    let mut app = DglabCli::new().await?;
-
-    match cli.command {
-        Commands::Scan(args) => app.scan(args).await?,
-        Commands::Connect(args) => app.connect(args).await?,
-        Commands::Control(args) => app.control(args).await?,
-        Commands::Preset(args) => app.preset(args).await?,
-        Commands::Script(args) => app.script(args).await?,
-        Commands::Wifi(args) => app.wifi(args).await?,
-        Commands::Bridge(args) => app.bridge(args).await?,
-        Commands::Tui => app.run_tui().await?,
+    let mut app = DglabCli::new(config).await?;
+    if let Some(adapter) = cli.adapter {
+        app.set_adapter(adapter);
+    }
+    if let Some(transport) = cli.dry_run {
+        app.enable_dry_run(transport).await?;
+    }
+
+    // 事件钩子：在后台执行配置文件 [hooks] 段的命令
+    let hooks = app.config().hooks;
+    if !hooks.is_default() {
+        let events = app.session_manager().subscribe_events();
+        let _ = tokio::spawn(HookRunner::new(hooks).run(events));
+    }
+
+    // 会话定时：到时后设备已由会话管理器归零并停止，直接结束当前命令
+    let timer_events = app.session_manager().subscribe_events();
+    if let Some(duration) = cli.duration {
+        app.session_manager().set_timer_duration(duration)?;
+    } else if let Some(until) = &cli.until {
+        app.session_manager().set_timer(parse_stop_time(until)?)?;
+    }
+    if let Some(stop_at) = app.session_manager().timer_deadline() {
+        println!(
+            "Session will stop at {}",
+            stop_at.with_timezone(&chrono::Local).format("%H:%M:%S")
+        );
+    }
+
+    let run = async {
+        match command {
+            SessionCommand::Scan(args) => app.scan(args).await,
+            SessionCommand::Connect(args) => app.connect(args).await,
+            SessionCommand::Control(args) => app.control(args).await,
+            SessionCommand::Fire(args) => app.fire(args).await,
+            SessionCommand::Preset(args) => app.preset(args).await,
+            SessionCommand::Feedback(args) => app.feedback(args).await,
+            SessionCommand::Script(args) => app.script(args).await,
+            SessionCommand::Waveform(args) => app.waveform(args).await,
+            SessionCommand::Wifi(args) => app.wifi(args).await,
+            SessionCommand::Bridge(args) => app.bridge(args).await,
+            SessionCommand::Remote(args) => app.remote(args).await,
+            SessionCommand::Log(args) => app.log(args).await,
+            SessionCommand::Bench(args) => app.bench(args).await,
+            SessionCommand::Mqtt(args) => app.mqtt(args).await,
+            SessionCommand::Webhook(args) => app.webhook(args).await,
+            SessionCommand::Tui => app.run_tui().await,
+        }
+    };
+
+    tokio::select! {
+        result = run => result?,
+        _ = wait_timer_expired(timer_events) => println!("Session time limit reached, devices stopped"),
     }
 
     Ok(())
 }
+
+/// 解析 `--duration` 参数
+fn parse_duration_arg(s: &str) -> Result<Duration, String> {
+    parse_duration(s).map_err(|e| e.to_string())
+}
+
+/// 打印会话定时警告，直到会话到时
+async fn wait_timer_expired(mut events: broadcast::Receiver<SessionEvent>) {
+    loop {
+        match events.recv().await {
+            Ok(SessionEvent::TimerWarning(remaining)) => {
+                println!("Session ends in {}s", remaining.as_secs());
+            }
+            Ok(SessionEvent::TimerExpired) | Err(broadcast::error::RecvError::Closed) => break,
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,85 @@
+//! 长时间操作的进度提示与取消
+//!
+//! 扫描、连接和 WiFi 绑定可能需要十几秒。[`Progress`] 在终端显示带计时的旋转指示器，
+//! 同时监听 Ctrl-C：按下后触发 [`CancellationToken`]，由操作自行停止扫描或断开半开的连接。
+//! 标准错误不是终端时不显示指示器，Ctrl-C 照常可以取消。
+
+use std::borrow::Cow;
+use std::future::Future;
+use std::time::Duration;
+
+use indicatif::{ProgressBar, ProgressStyle};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+/// 指示器刷新间隔
+const TICK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 可取消的进度指示器
+pub struct Progress {
+    /// 指示器
+    bar: ProgressBar,
+    /// 取消令牌
+    cancel: CancellationToken,
+    /// 监听 Ctrl-C 的任务
+    ctrl_c: JoinHandle<()>,
+}
+
+impl Progress {
+    /// 显示旋转指示器并开始监听 Ctrl-C
+    pub fn spinner(message: impl Into<Cow<'static, str>>) -> Self {
+        let bar = ProgressBar::new_spinner();
+        if let Ok(style) = ProgressStyle::with_template("{spinner:.cyan} {msg} [{elapsed}]") {
+            bar.set_style(style);
+        }
+        bar.set_message(message);
+        bar.enable_steady_tick(TICK_INTERVAL);
+
+        let cancel = CancellationToken::new();
+        let token = cancel.clone();
+        let ctrl_c = tokio::spawn(async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                token.cancel();
+            }
+        });
+
+        Self {
+            bar,
+            cancel,
+            ctrl_c,
+        }
+    }
+
+    /// 取消令牌，Ctrl-C 时触发
+    pub fn token(&self) -> &CancellationToken {
+        &self.cancel
+    }
+
+    /// 更新提示文字
+    pub fn set_message(&self, message: impl Into<Cow<'static, str>>) {
+        self.bar.set_message(message);
+    }
+
+    /// 运行操作直到完成或取消，取消时返回 `None`
+    pub async fn run<F: Future>(&self, future: F) -> Option<F::Output> {
+        tokio::select! {
+            output = future => Some(output),
+            _ = self.cancel.cancelled() => None,
+        }
+    }
+
+    /// 操作完成，保留一行结果
+    pub fn finish(self, message: impl Into<Cow<'static, str>>) {
+        self.bar.set_style(ProgressStyle::default_spinner());
+        self.bar.finish_with_message(message);
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        self.ctrl_c.abort();
+        if !self.bar.is_finished() {
+            self.bar.finish_and_clear();
+        }
+    }
+}
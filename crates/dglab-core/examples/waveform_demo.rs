@@ -39,6 +39,8 @@ fn main() {
                 duty_cycle: 50,
             },
             custom_points: None,
+            frames: None,
+            modulation: Vec::new(),
         };
 
         let mut gen = WaveformGenerator::with_waveform(waveform);
@@ -87,6 +89,8 @@ fn main() {
             (750, 100),
             (1000, 100),
         ]),
+        frames: None,
+        modulation: Vec::new(),
     };
 
     let mut gen = WaveformGenerator::with_waveform(custom);
@@ -123,6 +127,8 @@ fn main() {
             ..WaveformParams::default()
         },
         custom_points: None,
+        frames: None,
+        modulation: Vec::new(),
     };
 
     gen.set_waveform(sine);
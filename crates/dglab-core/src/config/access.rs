@@ -0,0 +1,235 @@
+//! 远程控制权限
+//!
+//! 网络控制入口（MQTT、Webhook 等）按令牌对应的角色限制可执行的操作：
+//!
+//! | 角色 | 权限 |
+//! |------|------|
+//! | `viewer` | 读取设备状态 |
+//! | `controller` | 另可调节强度（受安全限制截断）、切换波形、紧急停止 |
+//! | `admin` | 与 `controller` 相同，未启用访问控制时的请求视为此角色 |
+//!
+//! 令牌在配置文件 `[[access.tokens]]` 中配置；未配置任何令牌时不做限制。
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{CoreError, Result};
+
+/// 角色（按权限从低到高排序）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    /// 只读
+    Viewer,
+    /// 控制输出
+    Controller,
+    /// 管理员
+    Admin,
+}
+
+impl Role {
+    /// 是否具有指定权限
+    pub fn allows(self, permission: Permission) -> bool {
+        self >= permission.required_role()
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Viewer => "viewer",
+            Self::Controller => "controller",
+            Self::Admin => "admin",
+        };
+        f.write_str(name)
+    }
+}
+
+/// 权限
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Permission {
+    /// 读取设备状态
+    ReadState,
+    /// 控制输出（强度、波形、启停、紧急停止）
+    Control,
+}
+
+impl Permission {
+    /// 需要的最低角色
+    pub fn required_role(self) -> Role {
+        match self {
+            Self::ReadState => Role::Viewer,
+            Self::Control => Role::Controller,
+        }
+    }
+}
+
+impl fmt::Display for Permission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::ReadState => "read state",
+            Self::Control => "control output",
+        };
+        f.write_str(name)
+    }
+}
+
+/// 访问令牌
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccessToken {
+    /// 名称（用于日志）
+    pub name: String,
+    /// 令牌
+    pub token: String,
+    /// 角色
+    pub role: Role,
+}
+
+/// 访问控制配置（配置文件 `[access]` 段）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AccessConfig {
+    /// 访问令牌，为空时不限制
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tokens: Vec<AccessToken>,
+}
+
+impl AccessConfig {
+    /// 是否为默认配置（序列化时省略）
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// 是否启用访问控制
+    pub fn is_enabled(&self) -> bool {
+        !self.tokens.is_empty()
+    }
+
+    /// 校验配置（令牌和名称不能为空或重复）
+    pub fn validate(&self) -> Result<()> {
+        for (i, entry) in self.tokens.iter().enumerate() {
+            if entry.name.is_empty() || entry.token.is_empty() {
+                return Err(CoreError::ConfigError(
+                    "Access token name and token must not be empty".to_string(),
+                ));
+            }
+            let previous = &self.tokens[..i];
+            if previous.iter().any(|e| e.name == entry.name) {
+                return Err(CoreError::ConfigError(format!(
+                    "Duplicate access token name '{}'",
+                    entry.name
+                )));
+            }
+            if previous.iter().any(|e| e.token == entry.token) {
+                return Err(CoreError::ConfigError(format!(
+                    "Access token '{}' reuses another token",
+                    entry.name
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// 令牌对应的角色
+    ///
+    /// 未启用访问控制时任何请求都视为 [`Role::Admin`]；令牌缺失或无效时返回 `None`。
+    pub fn role(&self, token: Option<&str>) -> Option<Role> {
+        if !self.is_enabled() {
+            return Some(Role::Admin);
+        }
+        let token = token?;
+        self.tokens
+            .iter()
+            .find(|e| e.token == token)
+            .map(|e| e.role)
+    }
+
+    /// 检查令牌是否具有指定权限，返回其角色
+    pub fn authorize(&self, token: Option<&str>, permission: Permission) -> Result<Role> {
+        let role = self
+            .role(token)
+            .ok_or_else(|| CoreError::Rejected("Missing or invalid access token".to_string()))?;
+        check(role, permission)?;
+        Ok(role)
+    }
+}
+
+/// 检查角色是否具有指定权限
+pub fn check(role: Role, permission: Permission) -> Result<()> {
+    if role.allows(permission) {
+        Ok(())
+    } else {
+        Err(CoreError::Rejected(format!(
+            "Role '{}' is not allowed to {}",
+            role, permission
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> AccessConfig {
+        AccessConfig {
+            tokens: vec![
+                AccessToken {
+                    name: "dashboard".to_string(),
+                    token: "view".to_string(),
+                    role: Role::Viewer,
+                },
+                AccessToken {
+                    name: "home-assistant".to_string(),
+                    token: "ctrl".to_string(),
+                    role: Role::Controller,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_role_permissions() {
+        assert!(Role::Viewer.allows(Permission::ReadState));
+        assert!(!Role::Viewer.allows(Permission::Control));
+        assert!(Role::Controller.allows(Permission::Control));
+        assert!(Role::Admin.allows(Permission::Control));
+    }
+
+    #[test]
+    fn test_authorize() {
+        // 未配置令牌时不限制
+        let open = AccessConfig::default();
+        assert_eq!(
+            open.authorize(None, Permission::Control).unwrap(),
+            Role::Admin
+        );
+
+        let config = config();
+        assert_eq!(
+            config.authorize(Some("ctrl"), Permission::Control).unwrap(),
+            Role::Controller
+        );
+        assert!(matches!(
+            config.authorize(Some("view"), Permission::Control),
+            Err(CoreError::Rejected(_))
+        ));
+        assert!(config
+            .authorize(Some("wrong"), Permission::ReadState)
+            .is_err());
+        assert!(config.authorize(None, Permission::ReadState).is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(config().validate().is_ok());
+
+        let mut duplicate = config();
+        duplicate.tokens[1].token = "view".to_string();
+        assert!(duplicate.validate().is_err());
+
+        let mut empty = config();
+        empty.tokens[0].name.clear();
+        assert!(empty.validate().is_err());
+    }
+}
@@ -0,0 +1,223 @@
+//! 全局快捷键
+//!
+//! 桌面应用在后台注册全局快捷键，窗口不在前台时也能调节强度或紧急停止。
+//! 配置文件 `[hotkeys]` 段保存快捷键绑定：
+//!
+//! ```toml
+//! [hotkeys]
+//! enabled = true
+//! step = 5
+//!
+//! [[hotkeys.bindings]]
+//! shortcut = "CommandOrControl+Alt+Escape"
+//! action = "emergency_stop"
+//! ```
+//!
+//! 快捷键格式为 `修饰键+按键`（如 `Ctrl+Shift+Up`），由桌面应用解析。
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use dglab_protocol::v3::MAX_STRENGTH;
+
+use crate::error::{CoreError, Result};
+
+/// 默认每次调节的强度
+pub const DEFAULT_HOTKEY_STEP: u8 = 5;
+
+/// 快捷键动作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub enum HotkeyAction {
+    /// A 通道增加强度
+    IncreaseA,
+    /// A 通道降低强度
+    DecreaseA,
+    /// B 通道增加强度
+    IncreaseB,
+    /// B 通道降低强度
+    DecreaseB,
+    /// 暂停或恢复会话运行时
+    Pause,
+    /// 所有设备紧急停止
+    EmergencyStop,
+}
+
+impl HotkeyAction {
+    /// 强度调节动作对应的（通道, 方向），方向 1 为增加、-1 为降低
+    pub fn power_step(self) -> Option<(u8, i8)> {
+        match self {
+            Self::IncreaseA => Some((0, 1)),
+            Self::DecreaseA => Some((0, -1)),
+            Self::IncreaseB => Some((1, 1)),
+            Self::DecreaseB => Some((1, -1)),
+            Self::Pause | Self::EmergencyStop => None,
+        }
+    }
+}
+
+impl fmt::Display for HotkeyAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::IncreaseA => "increase_a",
+            Self::DecreaseA => "decrease_a",
+            Self::IncreaseB => "increase_b",
+            Self::DecreaseB => "decrease_b",
+            Self::Pause => "pause",
+            Self::EmergencyStop => "emergency_stop",
+        };
+        f.write_str(name)
+    }
+}
+
+/// 快捷键绑定
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct HotkeyBinding {
+    /// 快捷键，如 `CommandOrControl+Alt+Up`
+    pub shortcut: String,
+    /// 动作
+    pub action: HotkeyAction,
+}
+
+impl HotkeyBinding {
+    fn new(shortcut: &str, action: HotkeyAction) -> Self {
+        Self {
+            shortcut: shortcut.to_string(),
+            action,
+        }
+    }
+}
+
+/// 全局快捷键配置（配置文件 `[hotkeys]` 段）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct HotkeyConfig {
+    /// 是否注册全局快捷键
+    pub enabled: bool,
+    /// 每次调节的强度
+    pub step: u8,
+    /// 快捷键绑定
+    pub bindings: Vec<HotkeyBinding>,
+}
+
+impl Default for HotkeyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            step: DEFAULT_HOTKEY_STEP,
+            bindings: vec![
+                HotkeyBinding::new("CommandOrControl+Alt+Up", HotkeyAction::IncreaseA),
+                HotkeyBinding::new("CommandOrControl+Alt+Down", HotkeyAction::DecreaseA),
+                HotkeyBinding::new("CommandOrControl+Alt+Right", HotkeyAction::IncreaseB),
+                HotkeyBinding::new("CommandOrControl+Alt+Left", HotkeyAction::DecreaseB),
+                HotkeyBinding::new("CommandOrControl+Alt+Space", HotkeyAction::Pause),
+                HotkeyBinding::new("CommandOrControl+Alt+Escape", HotkeyAction::EmergencyStop),
+            ],
+        }
+    }
+}
+
+impl HotkeyConfig {
+    /// 是否为默认配置（序列化时省略）
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// 快捷键绑定的动作（不区分大小写和空格）
+    pub fn action(&self, shortcut: &str) -> Option<HotkeyAction> {
+        let shortcut = normalize(shortcut);
+        self.bindings
+            .iter()
+            .find(|b| normalize(&b.shortcut) == shortcut)
+            .map(|b| b.action)
+    }
+
+    /// 校验配置（快捷键不能为空或重复，步长 1~200）
+    pub fn validate(&self) -> Result<()> {
+        if self.step == 0 || self.step > MAX_STRENGTH {
+            return Err(CoreError::ConfigError(format!(
+                "Hotkey step must be 1~{}, got {}",
+                MAX_STRENGTH, self.step
+            )));
+        }
+        for (i, binding) in self.bindings.iter().enumerate() {
+            let shortcut = normalize(&binding.shortcut);
+            if shortcut.is_empty() {
+                return Err(CoreError::ConfigError(format!(
+                    "Empty shortcut for hotkey action '{}'",
+                    binding.action
+                )));
+            }
+            if self.bindings[..i]
+                .iter()
+                .any(|b| normalize(&b.shortcut) == shortcut)
+            {
+                return Err(CoreError::ConfigError(format!(
+                    "Duplicate hotkey '{}'",
+                    binding.shortcut
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 规范化快捷键用于比较
+fn normalize(shortcut: &str) -> String {
+    shortcut
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hotkey_config() {
+        let config = HotkeyConfig::default();
+        assert!(config.validate().is_ok());
+        assert!(config.is_default());
+        assert_eq!(
+            config.action("commandorcontrol + alt + escape"),
+            Some(HotkeyAction::EmergencyStop)
+        );
+        assert_eq!(config.action("Ctrl+Q"), None);
+        assert_eq!(HotkeyAction::DecreaseB.power_step(), Some((1, -1)));
+        assert_eq!(HotkeyAction::Pause.power_step(), None);
+
+        let mut duplicate = config.clone();
+        duplicate.bindings.push(HotkeyBinding::new(
+            "CommandOrControl+ALT+Up",
+            HotkeyAction::Pause,
+        ));
+        assert!(duplicate.validate().is_err());
+
+        assert!(HotkeyConfig { step: 0, ..config }.validate().is_err());
+    }
+
+    #[test]
+    fn test_hotkey_toml() {
+        let config: HotkeyConfig = toml::from_str(
+            r#"
+enabled = true
+step = 10
+
+[[bindings]]
+shortcut = "F9"
+action = "increase_a"
+"#,
+        )
+        .unwrap();
+        assert!(config.enabled);
+        assert_eq!(config.step, 10);
+        assert_eq!(config.bindings.len(), 1);
+        assert_eq!(config.action("f9"), Some(HotkeyAction::IncreaseA));
+    }
+}
@@ -0,0 +1,388 @@
+//! 按点分路径读写配置项
+//!
+//! 路径由字段名组成，如 `server.url`、`safety.max_power_a`、`hotkeys.step`；数组元素用序号，
+//! 如 `schedules.0.at`。未出现在配置文件中的段（`hotkeys`、`tempo` 等）按默认值展开，
+//! 同样可以读取和修改。
+//!
+//! 修改时值按 TOML 语法解析（`100`、`true`、`"text"`、`[1, 2]`），无法解析时视为字符串。
+//! 修改后的配置必须能通过 [`AppConfig::validate`]，且路径必须是配置中存在的字段，
+//! 拼错的字段名不会被静默忽略。
+//!
+//! [`set_toml_value`] 直接修改配置文件文本，保留注释、空行和其余配置项的写法。
+
+use toml::value::Table;
+use toml::Value;
+use toml_edit::{Document, Item, TableLike};
+
+use super::settings::AppConfig;
+use crate::error::{CoreError, Result};
+
+impl AppConfig {
+    /// 读取配置项
+    pub fn get_value(&self, key: &str) -> Result<Value> {
+        lookup(&self.to_value()?, key)
+            .cloned()
+            .ok_or_else(|| unknown_key(key))
+    }
+
+    /// 修改配置项，返回校验后的新配置（不修改自身）
+    pub fn with_value(&self, key: &str, raw: &str) -> Result<AppConfig> {
+        let value = parse_value(raw);
+        let mut root = self.to_value()?;
+        insert(&mut root, key, value.clone())?;
+
+        let config: AppConfig = root
+            .try_into()
+            .map_err(|e| CoreError::ConfigError(format!("Invalid value for '{}': {}", key, e)))?;
+        config.validate()?;
+
+        // 未知字段在反序列化时被忽略，写入后读不回来
+        match lookup(&config.to_value()?, key) {
+            Some(stored) if same_value(stored, &value) => Ok(config),
+            Some(stored) => Err(CoreError::ConfigError(format!(
+                "Invalid value for '{}': expected a value like {}",
+                key, stored
+            ))),
+            None => Err(unknown_key(key)),
+        }
+    }
+
+    /// 所有配置项（路径, 值），按路径排序；普通数组作为一项，表数组按元素展开
+    pub fn entries(&self) -> Result<Vec<(String, Value)>> {
+        let mut entries = Vec::new();
+        flatten("", &self.to_value()?, &mut entries);
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(entries)
+    }
+
+    /// 转换为 TOML 值，省略的默认段也展开
+    fn to_value(&self) -> Result<Value> {
+        let serialize = |e: toml::ser::Error| {
+            CoreError::ConfigError(format!("Failed to serialize config: {}", e))
+        };
+        let mut root = Value::try_from(self).map_err(serialize)?;
+        let sections = [
+            ("access", Value::try_from(&self.access).map_err(serialize)?),
+            ("tempo", Value::try_from(&self.tempo).map_err(serialize)?),
+            (
+                "hotkeys",
+                Value::try_from(&self.hotkeys).map_err(serialize)?,
+            ),
+            ("hooks", Value::try_from(&self.hooks).map_err(serialize)?),
+        ];
+        if let Value::Table(table) = &mut root {
+            for (name, section) in sections {
+                let _ = table.entry(name).or_insert(section);
+            }
+        }
+        Ok(root)
+    }
+}
+
+/// 按 TOML 语法解析值，失败时视为字符串
+fn parse_value(raw: &str) -> Value {
+    toml::from_str::<Table>(&format!("value = {}", raw))
+        .ok()
+        .and_then(|mut table| table.remove("value"))
+        .unwrap_or_else(|| Value::String(raw.to_string()))
+}
+
+/// 查找路径对应的值
+fn lookup<'a>(root: &'a Value, key: &str) -> Option<&'a Value> {
+    key.split('.').try_fold(root, |value, segment| match value {
+        Value::Table(table) => table.get(segment),
+        Value::Array(array) => array.get(segment.parse::<usize>().ok()?),
+        _ => None,
+    })
+}
+
+/// 写入路径对应的值，缺少的中间表自动创建
+fn insert(root: &mut Value, key: &str, value: Value) -> Result<()> {
+    let segments: Vec<&str> = key.split('.').collect();
+    if segments.iter().any(|s| s.is_empty()) {
+        return Err(unknown_key(key));
+    }
+    let (last, parents) = segments.split_last().ok_or_else(|| unknown_key(key))?;
+
+    let mut current = root;
+    for segment in parents {
+        current = match current {
+            Value::Table(table) => table
+                .entry(*segment)
+                .or_insert_with(|| Value::Table(Table::new())),
+            Value::Array(array) => segment
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| array.get_mut(index))
+                .ok_or_else(|| unknown_key(key))?,
+            _ => return Err(unknown_key(key)),
+        };
+    }
+
+    match current {
+        Value::Table(table) => {
+            let _ = table.insert(last.to_string(), value);
+        }
+        Value::Array(array) => {
+            let slot = last
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| array.get_mut(index))
+                .ok_or_else(|| unknown_key(key))?;
+            *slot = value;
+        }
+        _ => return Err(unknown_key(key)),
+    }
+    Ok(())
+}
+
+/// 值是否相同（整数和浮点数按数值比较）
+fn same_value(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Integer(i), Value::Float(f)) | (Value::Float(f), Value::Integer(i)) => {
+            *i as f64 == *f
+        }
+        _ => a == b,
+    }
+}
+
+/// 展开为（路径, 值）
+fn flatten(prefix: &str, value: &Value, entries: &mut Vec<(String, Value)>) {
+    let join = |key: &str| {
+        if prefix.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", prefix, key)
+        }
+    };
+    match value {
+        Value::Table(table) => {
+            for (key, value) in table {
+                flatten(&join(key), value, entries);
+            }
+        }
+        Value::Array(array) if !array.is_empty() && array.iter().all(Value::is_table) => {
+            for (index, value) in array.iter().enumerate() {
+                flatten(&join(&index.to_string()), value, entries);
+            }
+        }
+        _ => entries.push((prefix.to_string(), value.clone())),
+    }
+}
+
+/// 在配置文件文本中修改配置项，返回校验后的新配置和新文本（保留注释和格式）
+pub fn set_toml_value(content: &str, key: &str, raw: &str) -> Result<(AppConfig, String)> {
+    let updated = AppConfig::from_toml_str(content)?.with_value(key, raw)?;
+    let value: toml_edit::Value = updated
+        .get_value(key)?
+        .to_string()
+        .parse()
+        .map_err(|e| CoreError::ConfigError(format!("Invalid value for '{}': {}", key, e)))?;
+
+    let mut document: Document = content
+        .parse()
+        .map_err(|e| CoreError::ConfigError(format!("Invalid config TOML: {}", e)))?;
+    let segments: Vec<&str> = key.split('.').collect();
+    set_in_table(document.as_table_mut(), &segments, value).ok_or_else(|| {
+        CoreError::ConfigError(format!("Cannot update '{}' in the config file", key))
+    })?;
+
+    // 文本和配置必须一致，避免写入与预期不同的内容
+    let content = document.to_string();
+    if AppConfig::from_toml_str(&content)? != updated {
+        return Err(CoreError::ConfigError(format!(
+            "Cannot update '{}' in the config file",
+            key
+        )));
+    }
+    Ok((updated, content))
+}
+
+/// 在表中写入路径对应的值，缺少的表自动创建
+fn set_in_table(
+    table: &mut dyn TableLike,
+    segments: &[&str],
+    value: toml_edit::Value,
+) -> Option<()> {
+    let (first, rest) = segments.split_first()?;
+    if rest.is_empty() && !table.contains_key(first) {
+        let _ = table.insert(first, Item::Value(value));
+        return Some(());
+    }
+
+    let item = table.entry(first).or_insert_with(|| {
+        // 只包含子表时不输出空的表头
+        let mut table = toml_edit::Table::new();
+        table.set_implicit(true);
+        Item::Table(table)
+    });
+    set_in_item(item, rest, value)
+}
+
+fn set_in_item(item: &mut Item, segments: &[&str], value: toml_edit::Value) -> Option<()> {
+    match item {
+        Item::Table(table) => set_in_table(table, segments, value),
+        Item::ArrayOfTables(array) => {
+            let (first, rest) = segments.split_first()?;
+            set_in_table(array.get_mut(first.parse().ok()?)?, rest, value)
+        }
+        Item::Value(target) => set_in_value(target, segments, value),
+        Item::None => None,
+    }
+}
+
+fn set_in_value(
+    target: &mut toml_edit::Value,
+    segments: &[&str],
+    value: toml_edit::Value,
+) -> Option<()> {
+    let Some((first, rest)) = segments.split_first() else {
+        // 保留原值前后的空白和行尾注释
+        let decor = target.decor().clone();
+        *target = value;
+        *target.decor_mut() = decor;
+        return Some(());
+    };
+    match target {
+        toml_edit::Value::InlineTable(table) => set_in_table(table, segments, value),
+        toml_edit::Value::Array(array) => {
+            set_in_value(array.get_mut(first.parse().ok()?)?, rest, value)
+        }
+        _ => None,
+    }
+}
+
+fn unknown_key(key: &str) -> CoreError {
+    CoreError::ConfigError(format!("Unknown config key '{}'", key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_value() {
+        let config = AppConfig::default();
+        assert_eq!(
+            config.get_value("log_level").unwrap(),
+            Value::String("info".to_string())
+        );
+        assert_eq!(
+            config.get_value("safety.max_power_a").unwrap(),
+            Value::Integer(100)
+        );
+        // 省略的默认段也能读取
+        assert!(config.get_value("hotkeys.step").is_ok());
+        assert!(config.get_value("server.urll").is_err());
+        assert!(config.get_value("safety.max_power_a.x").is_err());
+    }
+
+    #[test]
+    fn test_with_value() {
+        let config = AppConfig::default();
+
+        let updated = config.with_value("safety.max_power_a", "80").unwrap();
+        assert_eq!(updated.safety.max_power_a, 80);
+        assert_eq!(config.safety.max_power_a, 100);
+
+        // 不带引号的文本按字符串处理
+        let updated = config
+            .with_value("server.url", "ws://192.168.1.20:9999")
+            .unwrap();
+        assert_eq!(updated.server.url, "ws://192.168.1.20:9999");
+        let updated = config.with_value("log_level", "\"debug\"").unwrap();
+        assert_eq!(updated.log_level, "debug");
+
+        let updated = config.with_value("hotkeys.enabled", "false").unwrap();
+        assert!(!updated.hotkeys.enabled);
+    }
+
+    #[test]
+    fn test_with_value_rejects_invalid() {
+        let config = AppConfig::default();
+        // 拼错的字段
+        assert!(config.with_value("safety.max_power", "80").is_err());
+        assert!(config.with_value("nope", "1").is_err());
+        assert!(config.with_value("safety..max_power_a", "1").is_err());
+        // 类型错误
+        assert!(config.with_value("safety.max_power_a", "high").is_err());
+        // 校验失败
+        assert!(config.with_value("safety.max_power_a", "250").is_err());
+        assert!(config.with_value("log_level", "loud").is_err());
+    }
+
+    #[test]
+    fn test_array_index() {
+        let config = AppConfig::from_toml_str(
+            r#"
+            [[favorite_devices]]
+            id = "47L121000"
+            name = "Coyote"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.get_value("favorite_devices.0.name").unwrap(),
+            Value::String("Coyote".to_string())
+        );
+        let updated = config
+            .with_value("favorite_devices.0.name", "Bedroom")
+            .unwrap();
+        assert_eq!(updated.favorite_devices[0].name, "Bedroom");
+        assert!(config.with_value("favorite_devices.1.name", "x").is_err());
+    }
+
+    #[test]
+    fn test_set_toml_value_keeps_comments() {
+        let content = r#"# 我的配置
+log_level = "info" # 调试时改成 debug
+
+[safety]
+# 睡前不超过 80
+max_power_a = 100
+max_power_b = 100
+
+[[favorite_devices]]
+id = "47L121000"
+name = "Coyote"
+"#;
+
+        let (config, updated) = set_toml_value(content, "safety.max_power_a", "80").unwrap();
+        assert_eq!(config.safety.max_power_a, 80);
+        assert_eq!(
+            updated,
+            content.replace("max_power_a = 100", "max_power_a = 80")
+        );
+
+        let (_, updated) = set_toml_value(content, "log_level", "debug").unwrap();
+        assert!(updated.contains("log_level = \"debug\" # 调试时改成 debug"));
+
+        let (config, updated) =
+            set_toml_value(content, "favorite_devices.0.name", "Bedroom").unwrap();
+        assert_eq!(config.favorite_devices[0].name, "Bedroom");
+        assert!(updated.contains("# 睡前不超过 80"));
+
+        // 文件中没有的段自动添加
+        let (config, updated) = set_toml_value(content, "hotkeys.enabled", "false").unwrap();
+        assert!(!config.hotkeys.enabled);
+        assert!(updated.starts_with("# 我的配置"));
+        assert_eq!(AppConfig::from_toml_str(&updated).unwrap(), config);
+
+        let (config, _) = set_toml_value("", "safety.max_power_b", "50").unwrap();
+        assert_eq!(config.safety.max_power_b, 50);
+
+        assert!(set_toml_value(content, "safety.max_power", "80").is_err());
+        assert!(set_toml_value(content, "safety.max_power_a", "250").is_err());
+    }
+
+    #[test]
+    fn test_entries() {
+        let entries = AppConfig::default().entries().unwrap();
+        let keys: Vec<&str> = entries.iter().map(|(key, _)| key.as_str()).collect();
+        assert!(keys.contains(&"log_level"));
+        assert!(keys.contains(&"server.url"));
+        assert!(keys.contains(&"safety.max_power_b"));
+        assert!(keys.windows(2).all(|w| w[0] <= w[1]));
+    }
+}
@@ -0,0 +1,15 @@
+//! 应用配置模块
+//!
+//! CLI 和桌面应用共用的配置文件（默认服务器、安全限制、自动重连、日志级别、常用设备、预设定时、
+//! 远程控制权限、全局快捷键），以及按点分路径读写配置项。
+
+pub mod access;
+pub mod hotkeys;
+pub mod keys;
+pub mod settings;
+
+pub use access::{AccessConfig, AccessToken, Permission, Role};
+pub use hotkeys::{HotkeyAction, HotkeyBinding, HotkeyConfig};
+pub use settings::{
+    AppConfig, ConfigManager, FavoriteDevice, ReconnectConfig, SafetyConfig, ServerConfig,
+};
@@ -0,0 +1,818 @@
+//! 应用配置
+//!
+//! CLI 和桌面应用共用的 TOML 配置文件，默认位于 `<config_dir>/dglab/config.toml`：
+//!
+//! ```toml
+//! log_level = "info"
+//!
+//! [server]
+//! url = "wss://ws.dungeon-lab.cn"
+//! accept_invalid_certs = false
+//!
+//! [safety]
+//! max_power_a = 100
+//! max_power_b = 100
+//!
+//! [safety.battery]
+//! cap_below = 20
+//! cap_power = 30
+//! pause_below = 10
+//!
+//! [reconnect]
+//! enabled = true
+//! initial_delay_ms = 1000
+//! max_delay_ms = 30000
+//!
+//! [[favorite_devices]]
+//! id = "47L121000"
+//! name = "Coyote"
+//! calibration = [[0, 0], [50, 40], [100, 120]]
+//!
+//! [[schedules]]
+//! name = "evening"
+//! preset = "Relax"
+//! at = "21:30"
+//!
+//! [mqtt]
+//! broker = "mqtt://homeassistant.local:1883"
+//! topic_prefix = "dglab"
+//!
+//! [webhook]
+//! bind = "0.0.0.0:8787"
+//!
+//! [[access.tokens]]
+//! name = "home-assistant"
+//! token = "change-me"
+//! role = "controller"
+//!
+//! [tempo]
+//! source = "midi"
+//! bpm = 120
+//!
+//! [hotkeys]
+//! enabled = true
+//! step = 5
+//!
+//! [hooks]
+//! battery_low_threshold = 20
+//! on_connect = ["notify-send DG-LAB \"$DGLAB_DEVICE connected\""]
+//! on_emergency_stop = ["~/bin/alert.sh"]
+//! ```
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::watch;
+use tracing::{debug, info, warn};
+
+use dglab_protocol::v3::MAX_STRENGTH;
+use dglab_protocol::wifi::{ReconnectPolicy, ServerAddress, OFFICIAL_SERVER};
+
+use super::access::AccessConfig;
+use super::hotkeys::HotkeyConfig;
+use crate::device::PowerCurve;
+use crate::error::{CoreError, Result};
+use crate::hooks::HooksConfig;
+use crate::mqtt::MqttConfig;
+use crate::preset::ScheduleEntry;
+use crate::session::BatteryPolicy;
+use crate::tempo::TempoConfig;
+use crate::webhook::WebhookConfig;
+
+/// 支持的日志级别
+pub const LOG_LEVELS: &[&str] = &["trace", "debug", "info", "warn", "error"];
+
+/// WebSocket 服务器配置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ServerConfig {
+    /// 默认服务器地址
+    pub url: String,
+    /// 接受自签名证书（仅用于局域网 wss 服务器）
+    pub accept_invalid_certs: bool,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            url: OFFICIAL_SERVER.to_string(),
+            accept_invalid_certs: false,
+        }
+    }
+}
+
+/// 安全限制
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SafetyConfig {
+    /// A 通道强度上限
+    pub max_power_a: u8,
+    /// B 通道强度上限
+    pub max_power_b: u8,
+    /// 低电量时的输出限制
+    pub battery: BatteryPolicy,
+}
+
+impl Default for SafetyConfig {
+    fn default() -> Self {
+        Self {
+            max_power_a: MAX_STRENGTH,
+            max_power_b: MAX_STRENGTH,
+            battery: BatteryPolicy::default(),
+        }
+    }
+}
+
+impl SafetyConfig {
+    /// 禁止输出的安全限制（强度上限为 0），配置文件无效时使用
+    pub fn locked() -> Self {
+        Self {
+            max_power_a: 0,
+            max_power_b: 0,
+            ..Self::default()
+        }
+    }
+}
+
+/// 断线重连配置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReconnectConfig {
+    /// 是否自动重连
+    pub enabled: bool,
+    /// 最大重连次数（不设置表示不限次数）
+    pub max_attempts: Option<u32>,
+    /// 首次重连前的等待时间（毫秒）
+    pub initial_delay_ms: u64,
+    /// 最长等待时间（毫秒）
+    pub max_delay_ms: u64,
+}
+
+impl Default for ReconnectConfig {
+    fn default() -> Self {
+        let policy = ReconnectPolicy::default();
+        Self {
+            enabled: true,
+            max_attempts: policy.max_attempts,
+            initial_delay_ms: policy.initial_delay.as_millis() as u64,
+            max_delay_ms: policy.max_delay.as_millis() as u64,
+        }
+    }
+}
+
+impl ReconnectConfig {
+    /// 转换为 WebSocket 重连策略
+    pub fn policy(&self) -> ReconnectPolicy {
+        if !self.enabled {
+            return ReconnectPolicy::disabled();
+        }
+
+        ReconnectPolicy {
+            max_attempts: self.max_attempts,
+            initial_delay: Duration::from_millis(self.initial_delay_ms),
+            max_delay: Duration::from_millis(self.max_delay_ms),
+            ..Default::default()
+        }
+    }
+}
+
+/// 常用设备
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FavoriteDevice {
+    /// 设备 ID 或 BLE 名称
+    pub id: String,
+    /// 显示名称
+    #[serde(default)]
+    pub name: String,
+    /// 强度校准曲线（逻辑 0~100% → 原始强度），未设置时直接使用原始强度
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub calibration: Option<PowerCurve>,
+}
+
+/// 应用配置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppConfig {
+    /// 日志级别（trace/debug/info/warn/error）
+    pub log_level: String,
+    /// WebSocket 服务器
+    pub server: ServerConfig,
+    /// 安全限制
+    pub safety: SafetyConfig,
+    /// 断线重连
+    pub reconnect: ReconnectConfig,
+    /// 常用设备
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub favorite_devices: Vec<FavoriteDevice>,
+    /// 预设定时
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub schedules: Vec<ScheduleEntry>,
+    /// MQTT 集成（未配置时不启用）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mqtt: Option<MqttConfig>,
+    /// Webhook 触发（未配置时使用默认监听地址）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub webhook: Option<WebhookConfig>,
+    /// 远程控制权限
+    #[serde(skip_serializing_if = "AccessConfig::is_default")]
+    pub access: AccessConfig,
+    /// 节拍同步
+    #[serde(skip_serializing_if = "TempoConfig::is_default")]
+    pub tempo: TempoConfig,
+    /// 全局快捷键（桌面应用）
+    #[serde(skip_serializing_if = "HotkeyConfig::is_default")]
+    pub hotkeys: HotkeyConfig,
+    /// 事件钩子
+    #[serde(skip_serializing_if = "HooksConfig::is_default")]
+    pub hooks: HooksConfig,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            log_level: "info".to_string(),
+            server: ServerConfig::default(),
+            safety: SafetyConfig::default(),
+            reconnect: ReconnectConfig::default(),
+            favorite_devices: Vec::new(),
+            schedules: Vec::new(),
+            mqtt: None,
+            webhook: None,
+            access: AccessConfig::default(),
+            tempo: TempoConfig::default(),
+            hotkeys: HotkeyConfig::default(),
+            hooks: HooksConfig::default(),
+        }
+    }
+}
+
+impl AppConfig {
+    /// 从 TOML 文本解析（缺省字段使用默认值）
+    pub fn from_toml_str(content: &str) -> Result<Self> {
+        let config: Self = toml::from_str(content)
+            .map_err(|e| CoreError::ConfigError(format!("Invalid config TOML: {}", e)))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// 序列化为 TOML 文本
+    pub fn to_toml_string(&self) -> Result<String> {
+        toml::to_string_pretty(self)
+            .map_err(|e| CoreError::ConfigError(format!("Failed to serialize config: {}", e)))
+    }
+
+    /// 校验配置
+    pub fn validate(&self) -> Result<()> {
+        if !LOG_LEVELS.contains(&self.log_level.as_str()) {
+            return Err(CoreError::ConfigError(format!(
+                "Invalid log level '{}', expected one of {}",
+                self.log_level,
+                LOG_LEVELS.join("/")
+            )));
+        }
+
+        let _ = self.server_address()?;
+
+        for limit in [self.safety.max_power_a, self.safety.max_power_b] {
+            if limit > MAX_STRENGTH {
+                return Err(CoreError::PowerOutOfRange(limit, MAX_STRENGTH));
+            }
+        }
+        self.safety.battery.validate()?;
+
+        if self.reconnect.initial_delay_ms == 0
+            || self.reconnect.initial_delay_ms > self.reconnect.max_delay_ms
+        {
+            return Err(CoreError::ConfigError(format!(
+                "Invalid reconnect delay {}~{}ms",
+                self.reconnect.initial_delay_ms, self.reconnect.max_delay_ms
+            )));
+        }
+
+        for (i, entry) in self.schedules.iter().enumerate() {
+            entry.validate()?;
+            if self.schedules[..i].iter().any(|e| e.name == entry.name) {
+                return Err(CoreError::ConfigError(format!(
+                    "Duplicate schedule name '{}'",
+                    entry.name
+                )));
+            }
+        }
+
+        if let Some(mqtt) = &self.mqtt {
+            mqtt.validate()?;
+        }
+        if let Some(webhook) = &self.webhook {
+            webhook.validate()?;
+        }
+
+        self.access.validate()?;
+        if let Some(token) = self.mqtt.as_ref().and_then(|m| m.token.as_deref()) {
+            if self.access.role(Some(token)).is_none() {
+                return Err(CoreError::ConfigError(
+                    "MQTT token is not listed in [access]".to_string(),
+                ));
+            }
+        }
+
+        self.tempo.validate()?;
+        self.hotkeys.validate()?;
+        self.hooks.validate()?;
+
+        Ok(())
+    }
+
+    /// 默认服务器地址
+    pub fn server_address(&self) -> Result<ServerAddress> {
+        ServerAddress::parse(&self.server.url)
+            .map(|address| address.accept_invalid_certs(self.server.accept_invalid_certs))
+            .map_err(|e| CoreError::ConfigError(e.to_string()))
+    }
+
+    /// 设备的强度校准曲线（按设备 ID 或 BLE 名称匹配常用设备，ID 优先）
+    pub fn calibration(&self, device_id: &str, name: &str) -> Option<&PowerCurve> {
+        let find = |key: &str| {
+            self.favorite_devices
+                .iter()
+                .find(|f| f.id == key)
+                .and_then(|f| f.calibration.as_ref())
+        };
+        find(device_id).or_else(|| find(name))
+    }
+
+    /// 保存设备的强度校准曲线，设备不在常用设备中时自动添加；`None` 清除校准
+    pub fn set_calibration(&mut self, device: &str, calibration: Option<PowerCurve>) {
+        match self.favorite_devices.iter_mut().find(|f| f.id == device) {
+            Some(favorite) => favorite.calibration = calibration,
+            None if calibration.is_some() => self.favorite_devices.push(FavoriteDevice {
+                id: device.to_string(),
+                name: String::new(),
+                calibration,
+            }),
+            None => {}
+        }
+    }
+
+    /// 按名称查找定时条目
+    pub fn schedule(&self, name: &str) -> Option<&ScheduleEntry> {
+        self.schedules.iter().find(|e| e.name == name)
+    }
+
+    /// 添加定时条目，同名条目会被替换
+    pub fn set_schedule(&mut self, entry: ScheduleEntry) {
+        match self.schedules.iter_mut().find(|e| e.name == entry.name) {
+            Some(existing) => *existing = entry,
+            None => self.schedules.push(entry),
+        }
+    }
+
+    /// 删除定时条目，返回是否存在
+    pub fn remove_schedule(&mut self, name: &str) -> bool {
+        let len = self.schedules.len();
+        self.schedules.retain(|e| e.name != name);
+        self.schedules.len() != len
+    }
+
+    /// 启用或禁用定时条目，返回是否存在
+    pub fn set_schedule_enabled(&mut self, name: &str, enabled: bool) -> bool {
+        match self.schedules.iter_mut().find(|e| e.name == name) {
+            Some(entry) => {
+                entry.enabled = enabled;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 按安全限制截断强度
+    pub fn clamp_power(&self, channel: u8, power: u8) -> u8 {
+        match channel {
+            0 => power.min(self.safety.max_power_a),
+            _ => power.min(self.safety.max_power_b),
+        }
+    }
+}
+
+/// 配置管理器
+///
+/// 持有当前配置并负责读写配置文件；通过 [`subscribe`](Self::subscribe)
+/// 获取配置变化，[`watch`](Self::watch) 在文件被外部修改时自动重新加载。
+pub struct ConfigManager {
+    /// 配置文件路径
+    path: PathBuf,
+    /// 当前配置
+    tx: watch::Sender<AppConfig>,
+    /// 最近一次读写时文件的修改时间
+    modified: std::sync::Mutex<Option<SystemTime>>,
+}
+
+impl ConfigManager {
+    /// 创建配置管理器（使用默认配置，需调用 [`load`](Self::load) 读取文件）
+    pub fn new(path: PathBuf) -> Self {
+        let (tx, _) = watch::channel(AppConfig::default());
+
+        Self {
+            path,
+            tx,
+            modified: std::sync::Mutex::new(None),
+        }
+    }
+
+    /// 使用默认路径创建配置管理器
+    pub fn default_path() -> Result<Self> {
+        Ok(Self::new(Self::default_config_path()?))
+    }
+
+    /// 获取默认配置文件路径
+    pub fn default_config_path() -> Result<PathBuf> {
+        let path = dirs::config_dir()
+            .ok_or_else(|| CoreError::Other("Could not find config directory".to_string()))?
+            .join("dglab")
+            .join("config.toml");
+
+        Ok(path)
+    }
+
+    /// 获取配置文件路径
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 获取当前配置
+    pub fn config(&self) -> AppConfig {
+        self.tx.borrow().clone()
+    }
+
+    /// 订阅配置变化
+    pub fn subscribe(&self) -> watch::Receiver<AppConfig> {
+        self.tx.subscribe()
+    }
+
+    /// 从文件加载配置（文件不存在时使用默认配置）
+    pub async fn load(&self) -> Result<()> {
+        let _ = self.reload().await?;
+        Ok(())
+    }
+
+    /// 重新读取配置文件，返回配置是否变化
+    pub async fn reload(&self) -> Result<bool> {
+        let config = if self.path.exists() {
+            let content = tokio::fs::read_to_string(&self.path).await?;
+            AppConfig::from_toml_str(&content)?
+        } else {
+            debug!("Config file {:?} not found, using defaults", self.path);
+            AppConfig::default()
+        };
+
+        self.remember_modified().await;
+        let changed = self.tx.send_if_modified(|current| {
+            if *current == config {
+                return false;
+            }
+            *current = config;
+            true
+        });
+
+        if changed {
+            info!("Loaded config from {:?}", self.path);
+        }
+        Ok(changed)
+    }
+
+    /// 校验并保存配置
+    pub async fn set(&self, config: AppConfig) -> Result<()> {
+        config.validate()?;
+
+        if let Some(dir) = self.path.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        tokio::fs::write(&self.path, config.to_toml_string()?).await?;
+        self.remember_modified().await;
+
+        info!("Saved config to {:?}", self.path);
+        let _ = self.tx.send_replace(config);
+        Ok(())
+    }
+
+    /// 校验并按原文保存配置文件（保留注释和格式），返回解析后的配置
+    pub async fn set_toml(&self, content: &str) -> Result<AppConfig> {
+        let config = AppConfig::from_toml_str(content)?;
+
+        if let Some(dir) = self.path.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        tokio::fs::write(&self.path, content).await?;
+        self.remember_modified().await;
+
+        info!("Saved config to {:?}", self.path);
+        let _ = self.tx.send_replace(config.clone());
+        Ok(config)
+    }
+
+    /// 修改并保存配置
+    pub async fn update(&self, f: impl FnOnce(&mut AppConfig)) -> Result<()> {
+        let mut config = self.config();
+        f(&mut config);
+        self.set(config).await
+    }
+
+    /// 定期检查配置文件，被外部修改时重新加载
+    ///
+    /// 无效的配置文件会记录警告并保留当前配置。
+    pub fn watch(self: &Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let manager = Arc::downgrade(self);
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                let _ = ticker.tick().await;
+
+                let Some(manager) = manager.upgrade() else {
+                    break;
+                };
+                if manager.file_modified().await == *manager.modified_guard() {
+                    continue;
+                }
+
+                match manager.reload().await {
+                    Ok(true) => info!("Config file changed, reloaded"),
+                    Ok(false) => {}
+                    Err(e) => {
+                        warn!("Failed to reload config: {}", e);
+                        // 记录修改时间，避免重复报告同一个错误
+                        manager.remember_modified().await;
+                    }
+                }
+            }
+        })
+    }
+
+    /// 读取文件修改时间
+    async fn file_modified(&self) -> Option<SystemTime> {
+        tokio::fs::metadata(&self.path)
+            .await
+            .and_then(|m| m.modified())
+            .ok()
+    }
+
+    /// 记录当前文件修改时间
+    async fn remember_modified(&self) {
+        let modified = self.file_modified().await;
+        *self.modified_guard() = modified;
+    }
+
+    fn modified_guard(&self) -> std::sync::MutexGuard<'_, Option<SystemTime>> {
+        self.modified
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Role;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_default_config_roundtrip() {
+        let config = AppConfig::default();
+        config.validate().unwrap();
+
+        let toml = config.to_toml_string().unwrap();
+        assert_eq!(AppConfig::from_toml_str(&toml).unwrap(), config);
+    }
+
+    #[test]
+    fn test_partial_config_uses_defaults() {
+        let config = AppConfig::from_toml_str(
+            r#"
+log_level = "debug"
+
+[safety]
+max_power_a = 60
+
+[[favorite_devices]]
+id = "47L121000"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.log_level, "debug");
+        assert_eq!(config.safety.max_power_a, 60);
+        assert_eq!(config.safety.max_power_b, MAX_STRENGTH);
+        assert_eq!(config.server, ServerConfig::default());
+        assert_eq!(config.favorite_devices[0].id, "47L121000");
+        assert_eq!(config.clamp_power(0, 80), 60);
+        assert_eq!(config.clamp_power(1, 80), 80);
+    }
+
+    #[test]
+    fn test_calibration() {
+        let mut config = AppConfig::from_toml_str(
+            r#"
+[[favorite_devices]]
+id = "47L121000"
+calibration = [[0, 0], [50, 40], [100, 120]]
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.calibration("47L121000", "").unwrap().to_raw(50), 40);
+        // 按 BLE 名称匹配（设备 ID 为地址时）
+        assert_eq!(
+            config
+                .calibration("AA:BB:CC:DD:EE:FF", "47L121000")
+                .unwrap()
+                .to_raw(50),
+            40
+        );
+        assert!(config.calibration("other", "other").is_none());
+
+        let curve = PowerCurve::linear(100);
+        config.set_calibration("other", Some(curve.clone()));
+        assert_eq!(config.favorite_devices.len(), 2);
+        // ID 优先于名称
+        assert_eq!(config.calibration("other", "47L121000"), Some(&curve));
+        let toml = config.to_toml_string().unwrap();
+        assert_eq!(AppConfig::from_toml_str(&toml).unwrap(), config);
+
+        config.set_calibration("47L121000", None);
+        assert!(config.calibration("47L121000", "47L121000").is_none());
+        assert!(AppConfig::from_toml_str(
+            "[[favorite_devices]]\nid = \"x\"\ncalibration = [[0, 0], [50, 0]]"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        assert!(AppConfig::from_toml_str("log_level = \"loud\"").is_err());
+        assert!(AppConfig::from_toml_str("[server]\nurl = \"ftp://x\"").is_err());
+        assert!(AppConfig::from_toml_str("[safety]\nmax_power_b = 201").is_err());
+        assert!(AppConfig::from_toml_str("[safety.battery]\ncap_below = 120").is_err());
+        assert!(AppConfig::from_toml_str("[reconnect]\ninitial_delay_ms = 0").is_err());
+        assert!(AppConfig::from_toml_str("log_level = ").is_err());
+        assert!(AppConfig::from_toml_str("[mqtt]\nbroker = \"mqtts://x\"").is_err());
+    }
+
+    #[test]
+    fn test_mqtt_config() {
+        let config = AppConfig::from_toml_str("[mqtt]\nbroker = \"10.0.0.2:1884\"").unwrap();
+        let mqtt = config.mqtt.as_ref().unwrap();
+        assert_eq!(
+            mqtt.broker_address().unwrap(),
+            ("10.0.0.2".to_string(), 1884)
+        );
+        assert_eq!(mqtt.topic_prefix, "dglab");
+
+        let toml = config.to_toml_string().unwrap();
+        assert_eq!(AppConfig::from_toml_str(&toml).unwrap(), config);
+        assert!(!AppConfig::default()
+            .to_toml_string()
+            .unwrap()
+            .contains("[mqtt]"));
+    }
+
+    #[test]
+    fn test_webhook_config() {
+        let config = AppConfig::from_toml_str("[webhook]\nbind = \"0.0.0.0:9000\"").unwrap();
+        assert_eq!(
+            config.webhook.as_ref().unwrap().bind_address().unwrap(),
+            "0.0.0.0:9000".parse().unwrap()
+        );
+        assert!(AppConfig::from_toml_str("[webhook]\nbind = \"localhost\"").is_err());
+        assert!(!AppConfig::default()
+            .to_toml_string()
+            .unwrap()
+            .contains("[webhook]"));
+    }
+
+    #[test]
+    fn test_access_config() {
+        let config = AppConfig::from_toml_str(
+            r#"
+[mqtt]
+broker = "localhost"
+token = "secret"
+
+[[access.tokens]]
+name = "home-assistant"
+token = "secret"
+role = "controller"
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.access.role(Some("secret")), Some(Role::Controller));
+
+        let toml = config.to_toml_string().unwrap();
+        assert_eq!(AppConfig::from_toml_str(&toml).unwrap(), config);
+        assert!(!AppConfig::default()
+            .to_toml_string()
+            .unwrap()
+            .contains("access"));
+
+        // MQTT 令牌必须在 [access] 中
+        assert!(AppConfig::from_toml_str(
+            "[mqtt]\ntoken = \"other\"\n\n[[access.tokens]]\nname = \"a\"\ntoken = \"secret\"\nrole = \"admin\""
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_hooks_config() {
+        let config = AppConfig::from_toml_str(
+            "[hooks]\ntimeout_secs = 5\non_feedback = [\"echo $DGLAB_BUTTON\"]",
+        )
+        .unwrap();
+        assert_eq!(config.hooks.timeout_secs, 5);
+        assert_eq!(config.hooks.on_feedback, ["echo $DGLAB_BUTTON"]);
+
+        let toml = config.to_toml_string().unwrap();
+        assert_eq!(AppConfig::from_toml_str(&toml).unwrap(), config);
+        assert!(!AppConfig::default()
+            .to_toml_string()
+            .unwrap()
+            .contains("[hooks]"));
+        assert!(AppConfig::from_toml_str("[hooks]\nbattery_low_threshold = 120").is_err());
+    }
+
+    #[test]
+    fn test_reconnect_policy() {
+        let mut config = ReconnectConfig::default();
+        assert_eq!(config.policy(), ReconnectPolicy::default());
+
+        config.enabled = false;
+        assert_eq!(config.policy().delay(0), None);
+    }
+
+    #[tokio::test]
+    async fn test_manager_load_and_set() {
+        let tmp = TempDir::new().unwrap();
+        let manager = ConfigManager::new(tmp.path().join("dglab").join("config.toml"));
+
+        // 文件不存在时使用默认配置
+        manager.load().await.unwrap();
+        assert_eq!(manager.config(), AppConfig::default());
+
+        let mut rx = manager.subscribe();
+        manager
+            .update(|config| config.server.url = "ws://localhost:9999".to_string())
+            .await
+            .unwrap();
+        assert!(rx.has_changed().unwrap());
+        assert_eq!(
+            rx.borrow_and_update().server_address().unwrap().as_str(),
+            "ws://localhost:9999"
+        );
+
+        // 重新加载不会产生变化
+        let reloaded = ConfigManager::new(manager.path().to_path_buf());
+        reloaded.load().await.unwrap();
+        assert_eq!(reloaded.config(), manager.config());
+        assert!(!manager.reload().await.unwrap());
+
+        // 无效配置不会保存
+        let mut invalid = manager.config();
+        invalid.log_level = "loud".to_string();
+        assert!(manager.set(invalid).await.is_err());
+        assert_eq!(manager.config().log_level, "info");
+    }
+
+    #[tokio::test]
+    async fn test_manager_set_toml_keeps_comments() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("config.toml");
+        let manager = ConfigManager::new(path.clone());
+
+        let content = "# 调试用\nlog_level = \"debug\"\n";
+        let config = manager.set_toml(content).await.unwrap();
+        assert_eq!(config.log_level, "debug");
+        assert_eq!(manager.config().log_level, "debug");
+        assert_eq!(tokio::fs::read_to_string(&path).await.unwrap(), content);
+
+        // 无效内容不会写入
+        assert!(manager.set_toml("log_level = \"loud\"\n").await.is_err());
+        assert_eq!(tokio::fs::read_to_string(&path).await.unwrap(), content);
+    }
+
+    #[tokio::test]
+    async fn test_manager_reload_external_change() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("config.toml");
+        let manager = ConfigManager::new(path.clone());
+        manager.load().await.unwrap();
+
+        tokio::fs::write(&path, "log_level = \"warn\"\n")
+            .await
+            .unwrap();
+        assert!(manager.reload().await.unwrap());
+        assert_eq!(manager.config().log_level, "warn");
+
+        // 无效文件保留当前配置
+        tokio::fs::write(&path, "log_level = 1\n").await.unwrap();
+        assert!(manager.reload().await.is_err());
+        assert_eq!(manager.config().log_level, "warn");
+    }
+}
@@ -1,29 +1,142 @@
 //! BLE + WebSocket 桥接设备
 //!
 //! 充当 DG-LAB APP 的替代品，允许第三方控制器通过 WebSocket 服务器远程控制设备
+//!
+//! 一个桥接设备可以带多台 BLE 主机（单元），共用一个 WebSocket 会话。
+//! 控制消息中的通道号按单元顺序编号：第 n 台主机（从 1 开始）的 A/B 通道为
+//! `2n-1` / `2n`，因此只有一台主机时与 APP 协议完全一致（1=A，2=B）。
 
-use std::sync::Arc;
+use std::any::Any;
+use std::sync::{Arc, MutexGuard};
 
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 use tokio::sync::{broadcast, Mutex};
 use tracing::{debug, error, info, warn};
 
-use dglab_protocol::wifi::{WsClient, WsEvent};
+use dglab_protocol::error::ProtocolError;
+use dglab_protocol::v3::{validate, WaveformData, MAX_STRENGTH};
+use dglab_protocol::wifi::validate::sanitize_command;
+use dglab_protocol::wifi::{
+    qr, HeartbeatConfig, PulseMessage, RateLimit, ReconnectPolicy, ServerAddress, WsClient, WsEvent,
+};
 
-use super::traits::{Device, DeviceInfo, WaveformConfig};
+use super::supervisor::RestartPolicy;
+use super::traits::{ChannelLink, Device, DeviceInfo, DeviceLimits, WaveformConfig, WifiBinding};
 use super::{BaseDevice, DeviceEvent, DeviceState};
 use crate::error::{CoreError, Result};
+use crate::metrics::DeviceMetrics;
 
 use super::CoyoteDevice;
 
+/// 转发消息广播的缓冲容量，订阅方处理不及时时丢弃最早的消息
+const FRAME_CHANNEL_CAPACITY: usize = 256;
+
 /// BLE + WebSocket 桥接设备内部状态
 struct BridgeInner {
-    /// BLE 设备
-    ble_device: Mutex<CoyoteDevice>,
+    /// BLE 设备（按单元顺序，第一个为主设备）
+    ble_devices: Vec<Mutex<CoyoteDevice>>,
     /// WebSocket 客户端
     ws_client: Mutex<Option<WsClient>>,
-    /// 服务器 URL
-    server_url: String,
+    /// 服务器地址
+    server: ServerAddress,
+    /// WebSocket 会话状态
+    status: std::sync::Mutex<BridgeStatus>,
+    /// 运行指标
+    metrics: std::sync::Mutex<BridgeMetrics>,
+    /// 转发消息广播
+    frame_tx: broadcast::Sender<BridgeFrame>,
+    /// 导出的运行指标
+    telemetry: Arc<DeviceMetrics>,
+}
+
+impl BridgeInner {
+    fn status(&self) -> MutexGuard<'_, BridgeStatus> {
+        self.status
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn metrics(&self) -> MutexGuard<'_, BridgeMetrics> {
+        self.metrics
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// 记录一条转发的消息
+    fn record_frame(&self, direction: FrameDirection, message: &str) {
+        {
+            let mut metrics = self.metrics();
+            match direction {
+                FrameDirection::Inbound => {
+                    metrics.inbound_messages += 1;
+                    if message.starts_with("strength-") {
+                        metrics.last_strength = Some(message.to_string());
+                    }
+                }
+                FrameDirection::Outbound => metrics.outbound_messages += 1,
+            }
+        }
+        // 没有订阅者时忽略
+        let _ = self.frame_tx.send(BridgeFrame {
+            direction,
+            message: message.to_string(),
+        });
+    }
+}
+
+/// 转发消息的方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FrameDirection {
+    /// 控制器 → 主机（控制指令）
+    Inbound,
+    /// 主机 → 控制器（强度同步）
+    Outbound,
+}
+
+/// 桥接转发的一条 WebSocket 消息
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BridgeFrame {
+    /// 方向
+    pub direction: FrameDirection,
+    /// 消息内容
+    pub message: String,
+}
+
+/// 桥接运行指标
+///
+/// 计数从创建桥接设备开始累计，按时间差计算速率即可得到每秒转发的消息数。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BridgeMetrics {
+    /// 收到的控制消息数
+    pub inbound_messages: u64,
+    /// 发出的强度同步消息数
+    pub outbound_messages: u64,
+    /// 最近一条来自控制器的强度指令
+    pub last_strength: Option<String>,
+    /// 各单元主机反馈的当前 (A, B) 强度，尚未收到反馈时为 `None`
+    pub unit_power: Vec<Option<(u8, u8)>>,
+    /// 最近一次 WebSocket 心跳往返时间（毫秒）
+    pub ws_latency_ms: Option<u64>,
+}
+
+/// 桥接 WebSocket 会话状态
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BridgeStatus {
+    /// WebSocket 是否已连接
+    pub ws_connected: bool,
+    /// 正在进行的重连尝试次数（未在重连时为 `None`）
+    pub reconnect_attempt: Option<u32>,
+    /// 已绑定的控制器 ID
+    pub controller: Option<String>,
+}
+
+impl BridgeStatus {
+    /// WebSocket 连接是否已失效且不再重连
+    pub fn is_lost(&self) -> bool {
+        !self.ws_connected && self.reconnect_attempt.is_none()
+    }
 }
 
 /// BLE + WebSocket 桥接设备
@@ -46,8 +159,14 @@ pub struct BleWsBridgeDevice {
     inner: Arc<BridgeInner>,
     /// WebSocket 接收任务
     ws_receive_task: Option<tokio::task::JoinHandle<()>>,
-    /// 状态同步任务
-    sync_task: Option<tokio::task::JoinHandle<()>>,
+    /// 状态同步任务（每个单元一个）
+    sync_tasks: Vec<tokio::task::JoinHandle<()>>,
+    /// 断线重连策略
+    reconnect_policy: ReconnectPolicy,
+    /// 心跳（由 WebSocket 客户端发送，回复用于测量往返时间）
+    heartbeat: HeartbeatConfig,
+    /// 二维码中使用的服务器地址（默认为连接的服务器地址）
+    qr_server: Option<ServerAddress>,
 }
 
 impl BleWsBridgeDevice {
@@ -58,7 +177,7 @@ impl BleWsBridgeDevice {
             name,
             ble_device_id,
             ble_device_name,
-            dglab_protocol::wifi::OFFICIAL_SERVER.to_string(),
+            ServerAddress::official(),
         )
     }
 
@@ -68,44 +187,183 @@ impl BleWsBridgeDevice {
         name: String,
         ble_device_id: String,
         ble_device_name: String,
-        server_url: String,
+        server: ServerAddress,
     ) -> Self {
-        let base = BaseDevice::new(id, name);
-        let ble_device = CoyoteDevice::new(ble_device_id, ble_device_name);
+        Self::from_units(id, name, vec![(ble_device_id, ble_device_name)], server)
+    }
+
+    /// 创建带多台 BLE 主机的桥接设备
+    ///
+    /// `units` 为各主机的 (设备 ID, 设备名称)，顺序决定控制消息中的通道编号。
+    /// `Device` trait 的强度、波形等操作作用于第一台主机。`units` 为空时返回错误。
+    pub fn with_units(
+        id: String,
+        name: String,
+        units: Vec<(String, String)>,
+        server: ServerAddress,
+    ) -> Result<Self> {
+        if units.is_empty() {
+            return Err(CoreError::InvalidParameter(
+                "Bridge needs at least one BLE device".to_string(),
+            ));
+        }
+        Ok(Self::from_units(id, name, units, server))
+    }
+
+    /// 由非空的主机列表创建桥接设备
+    fn from_units(
+        id: String,
+        name: String,
+        units: Vec<(String, String)>,
+        server: ServerAddress,
+    ) -> Self {
+        // 与 BLE 设备的 BF 软上限一致
+        let base = BaseDevice::with_max_power(id, name, MAX_STRENGTH);
+        let ble_devices: Vec<_> = units
+            .into_iter()
+            .map(|(id, name)| Mutex::new(CoyoteDevice::new(id, name)))
+            .collect();
+        let metrics = BridgeMetrics {
+            unit_power: vec![None; ble_devices.len()],
+            ..Default::default()
+        };
+        let (frame_tx, _) = broadcast::channel(FRAME_CHANNEL_CAPACITY);
 
         let inner = Arc::new(BridgeInner {
-            ble_device: Mutex::new(ble_device),
+            ble_devices,
             ws_client: Mutex::new(None),
-            server_url,
+            server,
+            status: std::sync::Mutex::new(BridgeStatus::default()),
+            metrics: std::sync::Mutex::new(metrics),
+            frame_tx,
+            telemetry: base.telemetry().clone(),
         });
 
         Self {
             base,
             inner,
             ws_receive_task: None,
-            sync_task: None,
+            sync_tasks: Vec::new(),
+            reconnect_policy: ReconnectPolicy::default(),
+            heartbeat: HeartbeatConfig::default(),
+            qr_server: None,
         }
     }
 
-    /// 连接 BLE 设备
+    /// 设置断线重连策略（下次连接时生效）
+    pub fn set_reconnect_policy(&mut self, policy: ReconnectPolicy) {
+        self.reconnect_policy = policy;
+    }
+
+    /// 设置心跳间隔和超时（下次连接时生效）
+    pub fn set_heartbeat(&mut self, heartbeat: HeartbeatConfig) {
+        self.heartbeat = heartbeat;
+    }
+
+    /// 设置二维码中使用的服务器地址
+    ///
+    /// 连接本机服务器（如 `ws://127.0.0.1:9999`）时，扫码方需要的是局域网地址。
+    pub fn set_qr_server(&mut self, server: ServerAddress) {
+        self.qr_server = Some(server);
+    }
+
+    /// BLE 主机数量
+    pub fn unit_count(&self) -> usize {
+        self.inner.ble_devices.len()
+    }
+
+    /// 主 BLE 设备
+    fn primary(&self) -> &Mutex<CoyoteDevice> {
+        &self.inner.ble_devices[0]
+    }
+
+    /// 连接 BLE 设备（第一台主机）
     pub async fn connect_ble(&self, protocol_device: dglab_protocol::ble::BleDevice) -> Result<()> {
-        info!("Connecting to BLE device");
+        self.connect_ble_unit(0, protocol_device).await
+    }
 
-        let mut ble_dev = self.inner.ble_device.lock().await;
+    /// 连接指定单元的 BLE 设备
+    pub async fn connect_ble_unit(
+        &self,
+        unit: usize,
+        protocol_device: dglab_protocol::ble::BleDevice,
+    ) -> Result<()> {
+        info!("Connecting to BLE device (unit {})", unit + 1);
+
+        let device = self.inner.ble_devices.get(unit).ok_or_else(|| {
+            CoreError::InvalidParameter(format!("Bridge unit {} does not exist", unit + 1))
+        })?;
+        let mut ble_dev = device.lock().await;
         ble_dev.set_protocol_device(protocol_device);
         ble_dev.connect().await?;
 
-        info!("BLE device connected");
+        // 桥接运行中重连的单元需要重新启动
+        if self.base.state() == DeviceState::Running {
+            ble_dev.start().await?;
+        }
+
+        info!("BLE device connected (unit {})", unit + 1);
         Ok(())
     }
 
+    /// 指定单元的 BLE 链路是否仍然连接
+    pub async fn is_unit_connected(&self, unit: usize) -> bool {
+        match self.inner.ble_devices.get(unit) {
+            Some(device) => device.lock().await.is_ble_connected().await,
+            None => false,
+        }
+    }
+
+    /// 将所有单元的两个通道强度归零
+    ///
+    /// 跳过未连接的单元，任一写入失败时返回最后一个错误。
+    pub async fn zero_output(&self) -> Result<()> {
+        let mut result = Ok(());
+        for (unit, device) in self.inner.ble_devices.iter().enumerate() {
+            let mut ble_dev = device.lock().await;
+            if !ble_dev.is_ble_connected().await {
+                continue;
+            }
+            for channel in 0..2 {
+                if let Err(e) = ble_dev.set_power(channel, 0).await {
+                    warn!(
+                        "Failed to zero unit {} channel {}: {}",
+                        unit + 1,
+                        channel,
+                        e
+                    );
+                    result = Err(e);
+                }
+            }
+        }
+        result
+    }
+
+    /// WebSocket 会话状态
+    pub fn status(&self) -> BridgeStatus {
+        self.inner.status().clone()
+    }
+
+    /// 运行指标
+    pub fn metrics(&self) -> BridgeMetrics {
+        self.inner.metrics().clone()
+    }
+
+    /// 订阅转发的 WebSocket 消息
+    pub fn subscribe_frames(&self) -> broadcast::Receiver<BridgeFrame> {
+        self.inner.frame_tx.subscribe()
+    }
+
     /// 获取二维码 URL（连接 WebSocket 后可用）
     pub async fn qr_url(&self) -> Option<String> {
         let client = self.inner.ws_client.lock().await;
-        if let Some(c) = client.as_ref() {
-            c.official_qr_url().await
-        } else {
-            None
+        let c = client.as_ref()?;
+        match &self.qr_server {
+            Some(server) => {
+                let client_id = c.client_id().await?;
+                Some(qr::generate_url(server.as_str(), &client_id))
+            }
+            None => c.qr_url().await,
         }
     }
 
@@ -123,24 +381,34 @@ impl BleWsBridgeDevice {
     fn start_ws_receive_task(&mut self) {
         let inner = self.inner.clone();
 
-        let handle = tokio::spawn(async move {
-            loop {
-                let mut client = inner.ws_client.lock().await;
-                let Some(c) = client.as_mut() else {
-                    break;
-                };
-
-                match c.recv_event().await {
-                    Ok(Some(event)) => {
-                        Self::handle_ws_event(&inner, event).await;
-                    }
-                    Ok(None) => {
-                        debug!("WebSocket connection closed");
-                        break;
-                    }
-                    Err(e) => {
-                        error!("WebSocket receive error: {}", e);
+        let supervisor = self.base.supervisor();
+        let handle = supervisor.spawn("WebSocket receive", RestartPolicy::default(), move || {
+            let inner = inner.clone();
+            async move {
+                loop {
+                    let mut client = inner.ws_client.lock().await;
+                    let Some(c) = client.as_mut() else {
                         break;
+                    };
+
+                    match c.recv_event().await {
+                        Ok(Some(event)) => {
+                            if matches!(event, WsEvent::Heartbeat) {
+                                if let Some(latency) = c.latency().await {
+                                    inner.metrics().ws_latency_ms =
+                                        Some(latency.as_millis() as u64);
+                                }
+                            }
+                            Self::handle_ws_event(&inner, event).await;
+                        }
+                        Ok(None) => {
+                            debug!("WebSocket connection closed");
+                            break;
+                        }
+                        Err(e) => {
+                            error!("WebSocket receive error: {}", e);
+                            break;
+                        }
                     }
                 }
             }
@@ -158,32 +426,38 @@ impl BleWsBridgeDevice {
 
     /// 启动 BLE → WebSocket 状态同步任务
     fn start_sync_task(&mut self) {
-        let inner = self.inner.clone();
-
-        let handle = tokio::spawn(async move {
-            let ble_dev = inner.ble_device.lock().await;
-            let mut event_rx = ble_dev.subscribe_events();
-            drop(ble_dev);
-
-            loop {
-                match event_rx.recv().await {
-                    Ok(event) => {
-                        Self::handle_ble_event(&inner, event).await;
-                    }
-                    Err(e) => {
-                        debug!("BLE event channel closed: {}", e);
-                        break;
+        let supervisor = self.base.supervisor();
+        for unit in 0..self.inner.ble_devices.len() {
+            let inner = self.inner.clone();
+
+            let handle = supervisor.spawn("BLE sync", RestartPolicy::default(), move || {
+                let inner = inner.clone();
+                async move {
+                    let ble_dev = inner.ble_devices[unit].lock().await;
+                    let mut event_rx = ble_dev.subscribe_events();
+                    drop(ble_dev);
+
+                    loop {
+                        match event_rx.recv().await {
+                            Ok(event) => {
+                                Self::handle_ble_event(&inner, unit, event).await;
+                            }
+                            Err(e) => {
+                                debug!("BLE event channel closed (unit {}): {}", unit + 1, e);
+                                break;
+                            }
+                        }
                     }
                 }
-            }
-        });
+            });
 
-        self.sync_task = Some(handle);
+            self.sync_tasks.push(handle);
+        }
     }
 
     /// 停止状态同步任务
     fn stop_sync_task(&mut self) {
-        if let Some(handle) = self.sync_task.take() {
+        for handle in self.sync_tasks.drain(..) {
             handle.abort();
         }
     }
@@ -196,6 +470,7 @@ impl BleWsBridgeDevice {
             }
             WsEvent::Bound(target_id) => {
                 info!("Bound to controller: {}", target_id);
+                inner.status().controller = Some(target_id);
             }
             WsEvent::Heartbeat => {
                 debug!("Received heartbeat");
@@ -210,20 +485,62 @@ impl BleWsBridgeDevice {
             }
             WsEvent::PeerDisconnected => {
                 info!("Controller disconnected");
+                inner.status().controller = None;
             }
             WsEvent::Error(code) => {
                 warn!("WebSocket error: {:?}", code);
             }
             WsEvent::Other(msg) => {
                 debug!("Received message: {}", msg.message);
-                // 解析控制指令
-                Self::handle_control_message(inner, &msg.message).await;
+                inner.record_frame(FrameDirection::Inbound, &msg.message);
+                if !msg.is_msg() {
+                    Self::handle_control_message(inner, &msg.message).await;
+                } else {
+                    // 先清理控制指令（截断强度、丢弃无效波形帧），无法修复的直接丢弃
+                    match sanitize_command(&msg.message) {
+                        Ok(command) => Self::handle_control_message(inner, &command).await,
+                        Err(e) => {
+                            warn!("Dropped malformed control message '{}': {}", msg.message, e)
+                        }
+                    }
+                }
             }
             WsEvent::BindTimeout => {
                 warn!("WebSocket bind timeout");
             }
             WsEvent::Closed => {
                 info!("WebSocket connection closed");
+                *inner.status() = BridgeStatus::default();
+            }
+            WsEvent::Reconnecting { attempt, delay } => {
+                warn!(
+                    "WebSocket disconnected, reconnecting in {:?} (attempt {})",
+                    delay, attempt
+                );
+                *inner.status() = BridgeStatus {
+                    ws_connected: false,
+                    reconnect_attempt: Some(attempt),
+                    controller: None,
+                };
+            }
+            WsEvent::Reconnected => {
+                info!("WebSocket reconnected, waiting for controller to rebind");
+                inner.telemetry.record_ws_reconnect();
+                let mut status = inner.status();
+                status.ws_connected = true;
+                status.reconnect_attempt = None;
+            }
+            WsEvent::SendFailed { message, attempts } => {
+                warn!(
+                    "Failed to deliver '{}' after {} attempts",
+                    message, attempts
+                );
+            }
+            WsEvent::Flushed => {
+                debug!("Outbound queue flushed");
+            }
+            WsEvent::ServerDelay { missed } => {
+                warn!("WebSocket server missed {} heartbeat(s)", missed);
             }
         }
     }
@@ -257,13 +574,13 @@ impl BleWsBridgeDevice {
             return;
         }
 
-        let channel = match parts[0] {
-            "1" => 0u8, // A 通道
-            "2" => 1u8, // B 通道
-            _ => {
-                warn!("Invalid channel: {}", parts[0]);
-                return;
-            }
+        let Some((unit, channel)) = parse_channel_address(parts[0]) else {
+            warn!("Invalid channel: {}", parts[0]);
+            return;
+        };
+        let Some(device) = inner.ble_devices.get(unit) else {
+            warn!("No bridge unit for channel {}", parts[0]);
+            return;
         };
 
         let mode: u8 = match parts[1].parse() {
@@ -282,75 +599,151 @@ impl BleWsBridgeDevice {
             }
         };
 
-        let mut ble_dev = inner.ble_device.lock().await;
+        let mut ble_dev = device.lock().await;
         let current_power = ble_dev.get_power(channel);
+        let info = ble_dev.info();
+        let max_power = if channel == 0 {
+            info.max_power_a
+        } else {
+            info.max_power_b
+        };
 
-        let new_power = match mode {
-            0 => current_power.saturating_sub(value),          // 减少
-            1 => current_power.saturating_add(value).min(200), // 增加
-            2 => value.min(200),                               // 设置
+        // 设置模式超过软上限视为错误，增减模式饱和到软上限
+        let checked = match mode {
+            2 => validate::check_strength(channel, value, max_power),
+            _ => validate::check_strength(channel, value, MAX_STRENGTH),
+        };
+        if let Err(e) = checked {
+            warn!("Rejected strength message '{}': {}", message, e);
+            return;
+        }
+
+        // 增减模式交给设备按实际强度增减
+        let result = match mode {
+            0 => ble_dev.adjust_power(channel, -(value as i16)).await, // 减少
+            1 => ble_dev.adjust_power(channel, value as i16).await,    // 增加
+            2 => ble_dev.set_power(channel, value).await.map(|_| value), // 设置
             _ => {
                 warn!("Unknown strength mode: {}", mode);
                 return;
             }
         };
 
-        if let Err(e) = ble_dev.set_power(channel, new_power).await {
-            error!("Failed to set power on channel {}: {}", channel, e);
-        } else {
-            debug!(
-                "Applied power {} on channel {} (was {})",
-                new_power, channel, current_power
-            );
+        match result {
+            Err(e) => error!(
+                "Failed to set power on unit {} channel {}: {}",
+                unit + 1,
+                channel,
+                e
+            ),
+            Ok(new_power) => debug!(
+                "Applied power {} on unit {} channel {} (was {})",
+                new_power,
+                unit + 1,
+                channel,
+                current_power
+            ),
         }
     }
 
     /// 解析并应用波形数据
-    async fn parse_and_apply_pulse(_inner: &Arc<BridgeInner>, message: &str) {
-        // TODO: 实现波形数据解析和应用
-        warn!("Pulse data parsing not yet implemented: {}", message);
+    ///
+    /// 与 APP 一致：帧追加到对应通道的波形队列，队列满时丢弃最早的帧。
+    async fn parse_and_apply_pulse(inner: &Arc<BridgeInner>, message: &str) {
+        let (unit, channel, frames) = match parse_pulse_message(message) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                warn!("Rejected pulse message: {}", e);
+                return;
+            }
+        };
+        let Some(device) = inner.ble_devices.get(unit) else {
+            warn!("No bridge unit {} for pulse message", unit + 1);
+            return;
+        };
+
+        let count = frames.len();
+        match device.lock().await.queue_pulses(channel, frames).await {
+            Ok(0) => debug!(
+                "Queued {} frames on unit {} channel {}",
+                count,
+                unit + 1,
+                channel
+            ),
+            Ok(dropped) => warn!(
+                "Pulse queue full on unit {} channel {}, dropped {} oldest frames",
+                unit + 1,
+                channel,
+                dropped
+            ),
+            Err(e) => error!(
+                "Failed to queue pulses on unit {} channel {}: {}",
+                unit + 1,
+                channel,
+                e
+            ),
+        }
     }
 
     /// 解析并应用清空操作
+    ///
+    /// 与 APP 一致：只丢弃通道波形队列中尚未播放的帧，不改变强度。
     async fn parse_and_apply_clear(inner: &Arc<BridgeInner>, message: &str) {
         let channel_str = message.trim_start_matches("clear-");
-        let channel = match channel_str {
-            "1" | "A" => 0u8,
-            "2" | "B" => 1u8,
-            _ => {
-                warn!("Invalid clear channel: {}", channel_str);
-                return;
-            }
+        let Some((device, unit, channel)) = parse_channel_label(channel_str)
+            .and_then(|(unit, channel)| Some((inner.ble_devices.get(unit)?, unit, channel)))
+        else {
+            warn!("Invalid clear channel: {}", channel_str);
+            return;
         };
 
-        let mut ble_dev = inner.ble_device.lock().await;
-        if let Err(e) = ble_dev.set_power(channel, 0).await {
-            error!("Failed to clear channel {}: {}", channel, e);
+        if let Err(e) = device.lock().await.clear_pulses(channel).await {
+            error!(
+                "Failed to clear unit {} channel {}: {}",
+                unit + 1,
+                channel,
+                e
+            );
         } else {
-            debug!("Cleared channel {}", channel);
+            debug!(
+                "Cleared pulse queue of unit {} channel {}",
+                unit + 1,
+                channel
+            );
         }
     }
 
     /// 处理 BLE 设备事件（同步状态到 WebSocket）
-    async fn handle_ble_event(inner: &Arc<BridgeInner>, event: DeviceEvent) {
+    async fn handle_ble_event(inner: &Arc<BridgeInner>, unit: usize, event: DeviceEvent) {
         match event {
             DeviceEvent::StatusReport { power_a, power_b } => {
-                debug!("BLE power status: A={}, B={}", power_a, power_b);
+                debug!(
+                    "BLE power status (unit {}): A={}, B={}",
+                    unit + 1,
+                    power_a,
+                    power_b
+                );
+                if let Some(power) = inner.metrics().unit_power.get_mut(unit) {
+                    *power = Some((power_a, power_b));
+                }
                 // 同步强度到 WebSocket
-                Self::sync_strength_to_ws(inner, power_a, power_b).await;
+                Self::sync_strength_to_ws(inner, unit, power_a, power_b).await;
             }
             DeviceEvent::StateChanged(state) => {
-                debug!("BLE state changed: {:?}", state);
+                debug!("BLE state changed (unit {}): {:?}", unit + 1, state);
             }
             DeviceEvent::BatteryUpdated(level) => {
-                debug!("BLE battery updated: {}%", level);
+                debug!("BLE battery updated (unit {}): {}%", unit + 1, level);
             }
             _ => {}
         }
     }
 
     /// 同步强度到 WebSocket
-    async fn sync_strength_to_ws(inner: &Arc<BridgeInner>, power_a: u8, power_b: u8) {
+    ///
+    /// 多台主机时在 APP 格式后按单元顺序追加各主机的四个字段，
+    /// 只读取前四个字段的控制器仍能得到第一台主机的状态。
+    async fn sync_strength_to_ws(inner: &Arc<BridgeInner>, unit: usize, power_a: u8, power_b: u8) {
         let client = inner.ws_client.lock().await;
         if let Some(c) = client.as_ref() {
             // 构造状态消息并发送
@@ -358,20 +751,26 @@ impl BleWsBridgeDevice {
 
             // 获取 client_id 和 target_id
             if let (Some(client_id), Some(target_id)) = (c.client_id().await, c.target_id().await) {
-                // 从 BLE 设备获取实际的强度上限
-                let (max_a, max_b) = {
-                    let ble_device = inner.ble_device.lock().await;
-                    let info = ble_device.info();
-                    (info.max_power_a, info.max_power_b)
-                };
+                // 从 BLE 设备获取实际的强度上限（事件所属单元使用上报的强度）
+                let mut fields = Vec::with_capacity(inner.ble_devices.len() * 4);
+                for (index, device) in inner.ble_devices.iter().enumerate() {
+                    let info = device.lock().await.info();
+                    let (a, b) = if index == unit {
+                        (power_a, power_b)
+                    } else {
+                        (info.power_a, info.power_b)
+                    };
+                    fields.extend([a, b, info.max_power_a, info.max_power_b]);
+                }
 
                 // 发送当前强度状态
-                // 格式: "strength-{A}+{B}+{maxA}+{maxB}"
-                let message = format!("strength-{}+{}+{}+{}", power_a, power_b, max_a, max_b);
+                // 格式: "strength-{A}+{B}+{maxA}+{maxB}[+{A2}+{B2}+{maxA2}+{maxB2}...]"
+                let message = strength_message(&fields);
                 let ws_msg = WsMessage::new(MessageType::Msg, client_id, target_id, message);
 
-                if let Err(e) = c.send(&ws_msg).await {
-                    warn!("Failed to sync strength to WebSocket: {}", e);
+                match c.send(&ws_msg).await {
+                    Ok(()) => inner.record_frame(FrameDirection::Outbound, &ws_msg.message),
+                    Err(e) => warn!("Failed to sync strength to WebSocket: {}", e),
                 }
             }
         }
@@ -380,6 +779,14 @@ impl BleWsBridgeDevice {
 
 #[async_trait]
 impl Device for BleWsBridgeDevice {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
     fn id(&self) -> &str {
         self.base.id()
     }
@@ -393,8 +800,7 @@ impl Device for BleWsBridgeDevice {
     }
 
     fn info(&self) -> DeviceInfo {
-        // 由于 info() 不是异步方法，我们无法获取锁
-        // 使用默认值，实际强度上限会在 sync_strength_to_ws 中正确获取
+        // info() 不是异步方法，无法获取 BLE 设备的锁，强度和上限取 base 中同步的值
         DeviceInfo {
             id: self.base.id().to_string(),
             name: self.base.name().to_string(),
@@ -402,10 +808,13 @@ impl Device for BleWsBridgeDevice {
             firmware_version: String::new(),
             hardware_version: String::new(),
             battery_level: 100,
+            battery_remaining_secs: None,
             power_a: self.base.power_a(),
             power_b: self.base.power_b(),
-            max_power_a: 200, // 默认值，实际值在 sync_strength_to_ws 中获取
-            max_power_b: 200, // 默认值，实际值在 sync_strength_to_ws 中获取
+            max_power_a: self.base.max_power_a(),
+            max_power_b: self.base.max_power_b(),
+            enabled_a: self.base.channel_enabled(0),
+            enabled_b: self.base.channel_enabled(1),
         }
     }
 
@@ -419,9 +828,14 @@ impl Device for BleWsBridgeDevice {
         self.base.set_state(DeviceState::Connecting);
 
         // 1. 连接 WebSocket
-        let mut client = WsClient::connect(&self.inner.server_url)
-            .await
-            .map_err(|e| CoreError::Other(format!("WebSocket connect error: {}", e)))?;
+        let mut client = WsClient::connect_with_options(
+            &self.inner.server,
+            self.reconnect_policy,
+            RateLimit::default(),
+            self.heartbeat,
+        )
+        .await
+        .map_err(|e| CoreError::ws("WebSocket connect", e))?;
 
         // 2. 等待绑定（参考 hyperzlib 项目，超时 20 秒）
         info!("Waiting for WebSocket binding...");
@@ -432,24 +846,30 @@ impl Device for BleWsBridgeDevice {
                 info!("WebSocket binding successful");
             }
             Ok(false) => {
-                let err_msg = format!(
-                    "WebSocket binding timeout after {} seconds",
+                let err = CoreError::Timeout(format!(
+                    "WebSocket binding after {} seconds",
                     bind_timeout_secs
-                );
-                error!("{}", err_msg);
-                return Err(CoreError::Other(err_msg));
+                ));
+                error!("{}", err);
+                return Err(err);
             }
             Err(e) => {
-                let err_msg = format!("WebSocket binding error: {}", e);
-                error!("{}", err_msg);
-                return Err(CoreError::Other(err_msg));
+                let err = CoreError::ws("WebSocket binding", e);
+                error!("{}", err);
+                return Err(err);
             }
         }
 
+        let controller = client.target_id().await;
         {
             let mut ws_client = self.inner.ws_client.lock().await;
             *ws_client = Some(client);
         }
+        *self.inner.status() = BridgeStatus {
+            ws_connected: true,
+            reconnect_attempt: None,
+            controller,
+        };
 
         // 3. 启动任务
         self.start_ws_receive_task();
@@ -473,12 +893,14 @@ impl Device for BleWsBridgeDevice {
         self.stop_sync_task();
 
         // 断开 BLE
-        let mut ble_dev = self.inner.ble_device.lock().await;
-        let _ = ble_dev.disconnect().await; // 忽略错误
+        for device in &self.inner.ble_devices {
+            let _ = device.lock().await.disconnect().await; // 忽略错误
+        }
 
         // 关闭 WebSocket
         let mut ws_client = self.inner.ws_client.lock().await;
         *ws_client = None;
+        *self.inner.status() = BridgeStatus::default();
 
         self.base.set_state(DeviceState::Disconnected);
 
@@ -494,8 +916,9 @@ impl Device for BleWsBridgeDevice {
         }
 
         // 启动 BLE 设备
-        let mut ble_dev = self.inner.ble_device.lock().await;
-        ble_dev.start().await?;
+        for device in &self.inner.ble_devices {
+            device.lock().await.start().await?;
+        }
 
         self.base.set_state(DeviceState::Running);
 
@@ -511,8 +934,9 @@ impl Device for BleWsBridgeDevice {
         }
 
         // 停止 BLE 设备
-        let mut ble_dev = self.inner.ble_device.lock().await;
-        ble_dev.stop().await?;
+        for device in &self.inner.ble_devices {
+            device.lock().await.stop().await?;
+        }
 
         self.base.set_state(DeviceState::Connected);
 
@@ -522,13 +946,13 @@ impl Device for BleWsBridgeDevice {
 
     async fn set_power(&mut self, channel: u8, power: u8) -> Result<()> {
         // 直接操作 BLE 设备
-        let mut ble_dev = self.inner.ble_device.lock().await;
+        let mut ble_dev = self.primary().lock().await;
         ble_dev.set_power(channel, power).await?;
+        drop(ble_dev);
 
-        // 更新 base 状态
-        self.base.set_power(channel, power)?;
-
-        Ok(())
+        // 更新 base 状态（禁用的通道为 0）
+        let power = self.base.gate_power(channel, power);
+        self.base.set_power(channel, power)
     }
 
     fn get_power(&self, channel: u8) -> u8 {
@@ -539,29 +963,342 @@ impl Device for BleWsBridgeDevice {
         }
     }
 
+    async fn set_max_power(&mut self, channel: u8, max_power: u8) -> Result<()> {
+        // 软上限写入 BLE 设备
+        let mut ble_dev = self.primary().lock().await;
+        ble_dev.set_max_power(channel, max_power).await?;
+
+        // 更新 base 状态
+        self.base.set_max_power(channel, max_power)?;
+
+        Ok(())
+    }
+
+    fn limits(&self) -> DeviceLimits {
+        self.base.limits()
+    }
+
+    async fn set_limits(&mut self, limits: DeviceLimits) -> Result<()> {
+        // BF 参数写入 BLE 设备
+        let mut ble_dev = self.primary().lock().await;
+        ble_dev.set_limits(limits).await?;
+        drop(ble_dev);
+
+        // 更新 base 状态
+        self.base.set_limits(limits)?;
+
+        Ok(())
+    }
+
+    fn channel_link(&self) -> Option<ChannelLink> {
+        self.base.channel_link()
+    }
+
+    async fn set_channel_link(&mut self, link: Option<ChannelLink>) -> Result<()> {
+        self.base.set_channel_link(link)
+    }
+
+    fn channel_enabled(&self, channel: u8) -> bool {
+        self.base.channel_enabled(channel)
+    }
+
+    async fn set_channel_enabled(&mut self, channel: u8, enabled: bool) -> Result<()> {
+        // 主设备负责屏蔽控制器发来的强度和波形
+        let mut ble_dev = self.primary().lock().await;
+        ble_dev.set_channel_enabled(channel, enabled).await?;
+        drop(ble_dev);
+
+        self.base.set_channel_enabled(channel, enabled)
+    }
+
     async fn set_waveform(&mut self, channel: u8, config: WaveformConfig) -> Result<()> {
         // 直接操作 BLE 设备
-        let mut ble_dev = self.inner.ble_device.lock().await;
+        let mut ble_dev = self.primary().lock().await;
         ble_dev.set_waveform(channel, config).await
     }
 
     async fn heartbeat(&mut self) -> Result<()> {
-        // BLE 设备自己会处理心跳
-        let mut ble_dev = self.inner.ble_device.lock().await;
-        ble_dev.heartbeat().await?;
-
-        // WebSocket 心跳
-        let client = self.inner.ws_client.lock().await;
-        if let Some(c) = client.as_ref() {
-            c.send_heartbeat()
-                .await
-                .map_err(|e| CoreError::Other(format!("WebSocket heartbeat error: {}", e)))?;
-        }
+        // WebSocket 心跳由 WsClient 的连接任务按 HeartbeatConfig 定时发送，只转发给 BLE 设备
+        self.primary().lock().await.heartbeat().await
+    }
 
-        Ok(())
+    fn wifi_binding(&self) -> Option<&dyn WifiBinding> {
+        Some(self)
     }
 
     fn subscribe_events(&self) -> broadcast::Receiver<DeviceEvent> {
         self.base.subscribe_events()
     }
+
+    fn send_event(&self, event: DeviceEvent) {
+        self.base.send_event(event);
+    }
+}
+
+#[async_trait]
+impl WifiBinding for BleWsBridgeDevice {
+    async fn qr_url(&self) -> Option<String> {
+        BleWsBridgeDevice::qr_url(self).await
+    }
+
+    async fn is_bound(&self) -> bool {
+        BleWsBridgeDevice::is_bound(self).await
+    }
+}
+
+/// 解析控制消息中的通道号，返回 (单元索引, 通道)
+///
+/// 通道号从 1 开始，第 n 台主机的 A/B 通道为 `2n-1` / `2n`。
+fn parse_channel_address(s: &str) -> Option<(usize, u8)> {
+    let number: usize = s.parse().ok()?;
+    let index = number.checked_sub(1)?;
+    Some((index / 2, (index % 2) as u8))
+}
+
+/// 解析通道标签，`A` / `B` 表示第一台主机，数字按 [`parse_channel_address`] 解析
+fn parse_channel_label(s: &str) -> Option<(usize, u8)> {
+    match s {
+        "A" => Some((0, 0)),
+        "B" => Some((0, 1)),
+        _ => parse_channel_address(s),
+    }
+}
+
+/// 解析并校验波形消息 `pulse-{通道}:["HEX",...]`，返回 (单元索引, 通道, 波形帧)
+fn parse_pulse_message(
+    message: &str,
+) -> std::result::Result<(usize, u8, Vec<WaveformData>), ProtocolError> {
+    let pulse = PulseMessage::parse(message)
+        .ok_or_else(|| ProtocolError::DecodeError(format!("Invalid pulse message: {}", message)))?;
+    let (unit, channel) = parse_channel_label(pulse.label).ok_or_else(|| {
+        ProtocolError::DecodeError(format!("Invalid pulse channel: {}", pulse.label))
+    })?;
+
+    let frames = pulse
+        .entries()
+        .map(|entry| {
+            let hex = entry.map_err(|entry| {
+                ProtocolError::DecodeError(format!("Invalid pulse entry: {}", entry))
+            })?;
+            let frame = WaveformData::from_hex_string(hex).ok_or_else(|| {
+                ProtocolError::InvalidWaveform {
+                    channel,
+                    reason: format!("bad hex frame '{}'", hex),
+                }
+            })?;
+            validate::check_waveform(channel, &frame)?;
+            Ok(frame)
+        })
+        .collect::<std::result::Result<Vec<_>, ProtocolError>>()?;
+
+    Ok((unit, channel, frames))
+}
+
+/// 构造强度同步消息（每台主机四个字段：A、B、A 上限、B 上限）
+fn strength_message(fields: &[u8]) -> String {
+    let fields: Vec<String> = fields.iter().map(u8::to_string).collect();
+    format!("strength-{}", fields.join("+"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dglab_protocol::wifi::{MessageType, WsMessage};
+
+    #[test]
+    fn test_parse_channel_address() {
+        assert_eq!(parse_channel_address("1"), Some((0, 0)));
+        assert_eq!(parse_channel_address("2"), Some((0, 1)));
+        assert_eq!(parse_channel_address("3"), Some((1, 0)));
+        assert_eq!(parse_channel_address("4"), Some((1, 1)));
+        assert_eq!(parse_channel_address("0"), None);
+        assert_eq!(parse_channel_address("A"), None);
+    }
+
+    #[test]
+    fn test_parse_pulse_message() {
+        let (unit, channel, frames) =
+            parse_pulse_message(r#"pulse-B:["0a0a0a0a00000000","0a0a0a0a64646464"]"#).unwrap();
+        assert_eq!((unit, channel, frames.len()), (0, 1, 2));
+        assert_eq!(frames[1], WaveformData::uniform(10, 100));
+
+        let (unit, channel, _) = parse_pulse_message(r#"pulse-3:["0a0a0a0a00000000"]"#).unwrap();
+        assert_eq!((unit, channel), (1, 0));
+
+        assert!(matches!(
+            parse_pulse_message(r#"pulse-A:["0a0a0a0a65656565"]"#),
+            Err(ProtocolError::InvalidWaveform { channel: 0, .. })
+        ));
+        assert!(matches!(
+            parse_pulse_message(r#"pulse-A:["zz"]"#),
+            Err(ProtocolError::InvalidWaveform { .. })
+        ));
+        assert!(parse_pulse_message("pulse-A").is_err());
+        assert!(parse_pulse_message("pulse-C:[]").is_err());
+        assert!(parse_pulse_message("pulse-A:[1,2]").is_err());
+    }
+
+    #[test]
+    fn test_strength_message() {
+        assert_eq!(
+            strength_message(&[10, 20, 100, 200]),
+            "strength-10+20+100+200"
+        );
+        assert_eq!(
+            strength_message(&[10, 20, 100, 200, 0, 5, 50, 60]),
+            "strength-10+20+100+200+0+5+50+60"
+        );
+    }
+
+    #[test]
+    fn test_with_units() {
+        let bridge = BleWsBridgeDevice::with_units(
+            "bridge".to_string(),
+            "Bridge".to_string(),
+            vec![
+                ("ble-1".to_string(), "47L121000".to_string()),
+                ("ble-2".to_string(), "47L121001".to_string()),
+            ],
+            ServerAddress::official(),
+        )
+        .unwrap();
+        assert_eq!(bridge.unit_count(), 2);
+
+        let single = BleWsBridgeDevice::new(
+            "bridge".to_string(),
+            "Bridge".to_string(),
+            "ble-1".to_string(),
+            "47L121000".to_string(),
+        );
+        assert_eq!(single.unit_count(), 1);
+
+        let empty = BleWsBridgeDevice::with_units(
+            "bridge".to_string(),
+            "Bridge".to_string(),
+            Vec::new(),
+            ServerAddress::official(),
+        );
+        assert!(matches!(empty, Err(CoreError::InvalidParameter(_))));
+    }
+
+    #[test]
+    fn test_bridge_status_is_lost() {
+        assert!(BridgeStatus::default().is_lost());
+
+        let reconnecting = BridgeStatus {
+            ws_connected: false,
+            reconnect_attempt: Some(3),
+            controller: None,
+        };
+        assert!(!reconnecting.is_lost());
+
+        let connected = BridgeStatus {
+            ws_connected: true,
+            ..Default::default()
+        };
+        assert!(!connected.is_lost());
+    }
+
+    #[tokio::test]
+    async fn test_unit_not_connected_without_ble() {
+        let bridge = BleWsBridgeDevice::new(
+            "bridge".to_string(),
+            "Bridge".to_string(),
+            "ble-1".to_string(),
+            "47L121000".to_string(),
+        );
+        assert!(!bridge.is_unit_connected(0).await);
+        assert!(!bridge.is_unit_connected(1).await);
+        assert!(bridge.zero_output().await.is_ok());
+        assert_eq!(bridge.status(), BridgeStatus::default());
+    }
+
+    #[tokio::test]
+    async fn test_metrics_hooks() {
+        let bridge = BleWsBridgeDevice::with_units(
+            "bridge".to_string(),
+            "Bridge".to_string(),
+            vec![
+                ("ble-1".to_string(), "47L121000".to_string()),
+                ("ble-2".to_string(), "47L121001".to_string()),
+            ],
+            ServerAddress::official(),
+        )
+        .unwrap();
+        assert_eq!(bridge.metrics().unit_power, vec![None, None]);
+        let mut frames = bridge.subscribe_frames();
+
+        let msg = WsMessage::new(MessageType::Msg, "app", "controller", "strength-3+2+20");
+        BleWsBridgeDevice::handle_ws_event(&bridge.inner, WsEvent::Other(msg)).await;
+        let msg = WsMessage::new(MessageType::Msg, "app", "controller", "clear-1");
+        BleWsBridgeDevice::handle_ws_event(&bridge.inner, WsEvent::Other(msg)).await;
+        BleWsBridgeDevice::handle_ble_event(
+            &bridge.inner,
+            1,
+            DeviceEvent::StatusReport {
+                power_a: 20,
+                power_b: 0,
+            },
+        )
+        .await;
+
+        let metrics = bridge.metrics();
+        assert_eq!(metrics.inbound_messages, 2);
+        assert_eq!(metrics.outbound_messages, 0);
+        assert_eq!(metrics.last_strength.as_deref(), Some("strength-3+2+20"));
+        assert_eq!(metrics.unit_power, vec![None, Some((20, 0))]);
+        assert_eq!(metrics.ws_latency_ms, None);
+
+        let frame = frames.try_recv().unwrap();
+        assert_eq!(frame.direction, FrameDirection::Inbound);
+        assert_eq!(frame.message, "strength-3+2+20");
+        assert_eq!(frames.try_recv().unwrap().message, "clear-1");
+        // 控制指令已转发到对应单元
+        assert_eq!(bridge.inner.ble_devices[1].lock().await.get_power(0), 20);
+    }
+
+    #[tokio::test]
+    async fn test_pulse_queue_and_clear() {
+        let bridge = BleWsBridgeDevice::with_units(
+            "bridge".to_string(),
+            "Bridge".to_string(),
+            vec![
+                ("ble-1".to_string(), "47L121000".to_string()),
+                ("ble-2".to_string(), "47L121001".to_string()),
+            ],
+            ServerAddress::official(),
+        )
+        .unwrap();
+        let send = |message: &str| {
+            let msg = WsMessage::new(MessageType::Msg, "app", "controller", message);
+            BleWsBridgeDevice::handle_ws_event(&bridge.inner, WsEvent::Other(msg))
+        };
+        let queued = |unit: usize, channel: u8| {
+            let device = &bridge.inner.ble_devices[unit];
+            async move { device.lock().await.queued_pulses(channel).await.unwrap() }
+        };
+
+        send(r#"pulse-A:["0a0a0a0a00000000","0a0a0a0a64646464"]"#).await;
+        send(r#"pulse-A:["0a0a0a0a32323232"]"#).await;
+        send(r#"pulse-4:["0a0a0a0a32323232"]"#).await;
+        assert_eq!(queued(0, 0).await, 3);
+        assert_eq!(queued(1, 1).await, 1);
+
+        // clear 只清空对应通道的队列，强度不变
+        send("strength-1+2+30").await;
+        send("clear-1").await;
+        assert_eq!(queued(0, 0).await, 0);
+        assert_eq!(queued(1, 1).await, 1);
+        assert_eq!(bridge.inner.ble_devices[0].lock().await.get_power(0), 30);
+
+        // 清空后重新发送的波形从头排队
+        send(r#"pulse-A:["0a0a0a0a64646464"]"#).await;
+        assert_eq!(queued(0, 0).await, 1);
+
+        // 无效的波形帧被丢弃，没有有效帧的消息整体丢弃
+        send(r#"pulse-A:["0a0a0a0a64646464","zz","0a0a0a0a65656565"]"#).await;
+        assert_eq!(queued(0, 0).await, 2);
+        send(r#"pulse-A:["zz"]"#).await;
+        assert_eq!(queued(0, 0).await, 2);
+    }
 }
@@ -0,0 +1,186 @@
+//! 强度校准曲线
+//!
+//! 设备强度 0~200 与体感并不成线性关系。校准曲线把逻辑强度 0~100% 映射到设备原始强度，
+//! 由若干校准点定义，点之间线性插值。校准保存在配置文件的常用设备中，
+//! 会话管理器在设置强度和渐变时自动换算。
+
+use std::fmt;
+
+use dglab_protocol::v3::MAX_STRENGTH;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{CoreError, Result};
+
+/// 逻辑强度上限（百分比）
+pub const MAX_PERCENT: u8 = 100;
+
+/// 强度校准曲线（逻辑百分比 → 原始强度）
+///
+/// 序列化为 `[[百分比, 原始强度], ...]`，如 `[[0, 0], [50, 40], [100, 120]]`。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "Vec<(u8, u8)>", into = "Vec<(u8, u8)>")]
+pub struct PowerCurve {
+    /// 校准点（百分比严格递增，从 0 开始到 100 结束；原始强度单调不减）
+    points: Vec<(u8, u8)>,
+}
+
+impl PowerCurve {
+    /// 由校准点创建曲线
+    pub fn new(points: Vec<(u8, u8)>) -> Result<Self> {
+        let curve = Self { points };
+        curve.validate()?;
+        Ok(curve)
+    }
+
+    /// 线性曲线：0~100% 对应 0~`max_raw`
+    pub fn linear(max_raw: u8) -> Self {
+        Self {
+            points: vec![(0, 0), (MAX_PERCENT, max_raw.min(MAX_STRENGTH))],
+        }
+    }
+
+    /// 校验校准点
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |msg: &str| Err(CoreError::InvalidParameter(format!("Calibration: {}", msg)));
+
+        let (Some(first), Some(last)) = (self.points.first(), self.points.last()) else {
+            return invalid("no points");
+        };
+        if first.0 != 0 || last.0 != MAX_PERCENT {
+            return invalid("points must start at 0% and end at 100%");
+        }
+        if let Some(&(_, raw)) = self.points.iter().find(|(_, raw)| *raw > MAX_STRENGTH) {
+            return Err(CoreError::PowerOutOfRange(raw, MAX_STRENGTH));
+        }
+        for pair in self.points.windows(2) {
+            if pair[1].0 <= pair[0].0 {
+                return invalid("percentages must be strictly increasing");
+            }
+            if pair[1].1 < pair[0].1 {
+                return invalid("strength must not decrease");
+            }
+        }
+        Ok(())
+    }
+
+    /// 校准点
+    pub fn points(&self) -> &[(u8, u8)] {
+        &self.points
+    }
+
+    /// 100% 对应的原始强度
+    pub fn max_raw(&self) -> u8 {
+        self.points.last().map_or(0, |&(_, raw)| raw)
+    }
+
+    /// 逻辑百分比换算为原始强度（超过 100% 按 100% 计）
+    pub fn to_raw(&self, percent: u8) -> u8 {
+        let percent = percent.min(MAX_PERCENT);
+        self.interpolate(percent, |&(p, r)| (p, r))
+    }
+
+    /// 原始强度换算为逻辑百分比（用于显示当前强度）
+    pub fn to_percent(&self, raw: u8) -> u8 {
+        if raw >= self.max_raw() {
+            return MAX_PERCENT;
+        }
+        self.interpolate(raw, |&(p, r)| (r, p))
+    }
+
+    /// 在 `(x, y)` 折线上按 `x` 插值
+    fn interpolate(&self, x: u8, axes: impl Fn(&(u8, u8)) -> (u8, u8)) -> u8 {
+        let points: Vec<(u8, u8)> = self.points.iter().map(axes).collect();
+        let Some(i) = points.iter().position(|&(px, _)| px >= x) else {
+            return points.last().map_or(0, |&(_, y)| y);
+        };
+        if i == 0 || points[i].0 == x {
+            // 原始强度相同的一段平台取最小百分比
+            return points
+                .iter()
+                .find(|&&(px, _)| px == x)
+                .map_or(points[i].1, |&(_, y)| y);
+        }
+
+        let (x0, y0) = (points[i - 1].0 as f32, points[i - 1].1 as f32);
+        let (x1, y1) = (points[i].0 as f32, points[i].1 as f32);
+        (y0 + (x as f32 - x0) * (y1 - y0) / (x1 - x0)).round() as u8
+    }
+}
+
+impl TryFrom<Vec<(u8, u8)>> for PowerCurve {
+    type Error = CoreError;
+
+    fn try_from(points: Vec<(u8, u8)>) -> Result<Self> {
+        Self::new(points)
+    }
+}
+
+impl From<PowerCurve> for Vec<(u8, u8)> {
+    fn from(curve: PowerCurve) -> Self {
+        curve.points
+    }
+}
+
+impl fmt::Display for PowerCurve {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let points: Vec<String> = self
+            .points
+            .iter()
+            .map(|(percent, raw)| format!("{}%={}", percent, raw))
+            .collect();
+        f.write_str(&points.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        assert!(PowerCurve::new(vec![(0, 0), (100, 200)]).is_ok());
+        assert!(PowerCurve::new(vec![]).is_err());
+        assert!(PowerCurve::new(vec![(10, 0), (100, 200)]).is_err());
+        assert!(PowerCurve::new(vec![(0, 0), (90, 200)]).is_err());
+        assert!(PowerCurve::new(vec![(0, 0), (50, 0), (50, 10), (100, 20)]).is_err());
+        assert!(PowerCurve::new(vec![(0, 0), (50, 30), (100, 20)]).is_err());
+        assert!(matches!(
+            PowerCurve::new(vec![(0, 0), (100, 201)]),
+            Err(CoreError::PowerOutOfRange(201, 200))
+        ));
+    }
+
+    #[test]
+    fn test_to_raw() {
+        let curve = PowerCurve::new(vec![(0, 0), (50, 20), (100, 120)]).unwrap();
+        assert_eq!(curve.to_raw(0), 0);
+        assert_eq!(curve.to_raw(25), 10);
+        assert_eq!(curve.to_raw(50), 20);
+        assert_eq!(curve.to_raw(75), 70);
+        assert_eq!(curve.to_raw(100), 120);
+        assert_eq!(curve.to_raw(150), 120);
+
+        assert_eq!(PowerCurve::linear(200).to_raw(30), 60);
+    }
+
+    #[test]
+    fn test_to_percent() {
+        let curve = PowerCurve::new(vec![(0, 0), (20, 10), (50, 10), (100, 120)]).unwrap();
+        assert_eq!(curve.to_percent(0), 0);
+        assert_eq!(curve.to_percent(5), 10);
+        // 平台段取最小百分比
+        assert_eq!(curve.to_percent(10), 20);
+        assert_eq!(curve.to_percent(65), 75);
+        assert_eq!(curve.to_percent(200), 100);
+    }
+
+    #[test]
+    fn test_serde() {
+        let curve = PowerCurve::new(vec![(0, 0), (50, 40), (100, 120)]).unwrap();
+        let json = serde_json::to_string(&curve).unwrap();
+        assert_eq!(json, "[[0,0],[50,40],[100,120]]");
+        assert_eq!(serde_json::from_str::<PowerCurve>(&json).unwrap(), curve);
+        assert!(serde_json::from_str::<PowerCurve>("[[0,0],[50,40]]").is_err());
+        assert_eq!(curve.to_string(), "0%=0, 50%=40, 100%=120");
+    }
+}
@@ -2,7 +2,9 @@
 //!
 //! BLE 设备使用 V3 协议（B0/BF/B1 指令），WiFi 设备使用 WebSocket JSON 协议。
 
-use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::any::Any;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicI16, AtomicU8, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -10,50 +12,382 @@ use async_trait::async_trait;
 use tokio::sync::{broadcast, Mutex};
 use tracing::{debug, error, info, warn};
 
-use dglab_protocol::ble::{BleDevice as ProtocolBleDevice, BleManager};
+use dglab_protocol::ble::throughput::validate_b0_interval;
+use dglab_protocol::ble::{
+    BleDevice as ProtocolBleDevice, BleManager, DeviceInfo as BleDeviceInfo, FirmwareVersion,
+    LinkParams, DEFAULT_B0_INTERVAL, MIN_SUPPORTED_FIRMWARE,
+};
+use dglab_protocol::transport::{LinkState, Transport};
 use dglab_protocol::v3::{
     B0Command, B1Response, BFCommand, ChannelStrengthMode, NotifyMessage, StrengthMode,
     WaveformData, MAX_STRENGTH,
 };
+use dglab_protocol::wifi::{
+    Channel as WsChannel, ClearOperation, ClientRole, HeartbeatConfig, PulseData, RateLimit,
+    ReconnectPolicy, ServerAddress, WsClient,
+};
 
-use crate::device::traits::{Device, DeviceInfo, WaveformConfig, WaveformType};
+use crate::device::dry_run::{DryRunPayload, DryRunRecorder};
+use crate::device::error_limit::{ErrorLimiter, ErrorStats};
+use crate::device::output_clock::{output_interval, OutputClock, TickStats};
+use crate::device::pulse_buffer::{PulseScheduler, DEFAULT_PULSE_LOOKAHEAD, PULSE_FRAME_DURATION};
+use crate::device::supervisor::RestartPolicy;
+use crate::device::traits::{
+    ChannelLink, Device, DeviceConfig, DeviceInfo, DeviceLimits, LatencyStats, WaveformConfig,
+    WaveformType, WifiBinding,
+};
 use crate::device::{BaseDevice, DeviceEvent, DeviceState};
 use crate::error::{CoreError, Result};
+use crate::metrics::DeviceMetrics;
 
 // ============================================================================
 // V3 BLE 输出状态（供 100ms 输出循环共享）
 // ============================================================================
 
+/// B0 发送错误的合并来源
+const B0_ERROR_SOURCE: &str = "b0";
+
+/// 通知接收错误的合并来源
+const NOTIFY_ERROR_SOURCE: &str = "notify";
+
+/// 已发送、等待 B1 反馈的强度请求
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct PendingStrength {
+    /// A 通道请求强度（未变更时为 None）
+    strength_a: Option<u8>,
+    /// B 通道请求强度（未变更时为 None）
+    strength_b: Option<u8>,
+}
+
+/// B1 反馈与请求不一致的通道
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct RejectedStrength {
+    /// 通道编号
+    channel: u8,
+    /// 请求强度
+    requested: u8,
+    /// 设备实际强度
+    actual: u8,
+}
+
+/// 单通道波形帧序列
+///
+/// 输出循环每 100ms 取下一帧，播放到末尾后从头循环。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct FrameCycle {
+    /// 波形帧（至少一帧）
+    frames: Vec<WaveformData>,
+    /// 下一帧位置
+    next: usize,
+}
+
+impl FrameCycle {
+    /// 创建帧序列，空序列按静默处理
+    pub(super) fn new(frames: Vec<WaveformData>) -> Self {
+        if frames.is_empty() {
+            return Self::single(WaveformData::silent());
+        }
+        Self { frames, next: 0 }
+    }
+
+    /// 单帧（每 100ms 重复同一帧）
+    pub(super) fn single(frame: WaveformData) -> Self {
+        Self {
+            frames: vec![frame],
+            next: 0,
+        }
+    }
+
+    /// 取出当前帧并前进
+    fn advance(&mut self) -> WaveformData {
+        let frame = self.frames[self.next];
+        self.next = (self.next + 1) % self.frames.len();
+        frame
+    }
+}
+
+/// 波形队列容量（与 APP 一致，约 50 秒）
+pub const PULSE_QUEUE_CAPACITY: usize = 500;
+
+/// 单通道待播放波形队列
+///
+/// 对应 APP 的波形队列：`pulse-` 消息追加帧，`clear-` 消息清空。队列非空时输出循环
+/// 优先播放队列中的帧，播放完后回到通道波形。超出容量时丢弃最早的帧。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(super) struct PulseQueue {
+    frames: VecDeque<WaveformData>,
+}
+
+impl PulseQueue {
+    /// 追加帧，返回因超出容量丢弃的帧数
+    pub(super) fn push(&mut self, frames: impl IntoIterator<Item = WaveformData>) -> usize {
+        self.frames.extend(frames);
+        let overflow = self.frames.len().saturating_sub(PULSE_QUEUE_CAPACITY);
+        drop(self.frames.drain(..overflow));
+        overflow
+    }
+
+    /// 清空队列
+    pub(super) fn clear(&mut self) {
+        self.frames.clear();
+    }
+
+    /// 队列中的帧数
+    pub(super) fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// 取出下一帧
+    fn pop(&mut self) -> Option<WaveformData> {
+        self.frames.pop_front()
+    }
+}
+
+/// 时延统计窗口（最近的样本数）
+const LATENCY_WINDOW: usize = 50;
+
+/// B1 往返时延跟踪
+///
+/// 记录带序列号 B0 的发送时间，收到同序号 B1 时计算耗时。序列号循环复用，
+/// 同号的旧发送时间直接覆盖。
+#[derive(Debug, Default)]
+struct LatencyTracker {
+    /// 在途序列号的发送时间
+    sent: HashMap<u8, tokio::time::Instant>,
+    /// 最近的时延样本
+    window: VecDeque<Duration>,
+    /// 累计样本数
+    samples: u64,
+}
+
+impl LatencyTracker {
+    /// 记录序列号的发送时间
+    fn sent(&mut self, sequence: u8, at: tokio::time::Instant) {
+        let _ = self.sent.insert(sequence, at);
+    }
+
+    /// 收到 B1，返回对应序列号的往返时延（未记录发送时间时返回 None）
+    fn received(&mut self, sequence: u8, at: tokio::time::Instant) -> Option<Duration> {
+        let latency = at.saturating_duration_since(self.sent.remove(&sequence)?);
+        if self.window.len() == LATENCY_WINDOW {
+            let _ = self.window.pop_front();
+        }
+        self.window.push_back(latency);
+        self.samples += 1;
+        Some(latency)
+    }
+
+    /// 丢弃在途记录（保留统计）
+    fn clear_pending(&mut self) {
+        self.sent.clear();
+    }
+
+    /// 当前统计，尚无样本时返回 None
+    fn stats(&self) -> Option<LatencyStats> {
+        let last = *self.window.back()?;
+        let count = self.window.len() as u32;
+        let total: Duration = self.window.iter().sum();
+        let jitter = if count > 1 {
+            let diffs: Duration = self
+                .window
+                .iter()
+                .zip(self.window.iter().skip(1))
+                .map(|(a, b)| a.max(b).saturating_sub(*a.min(b)))
+                .sum();
+            diffs / (count - 1)
+        } else {
+            Duration::ZERO
+        };
+
+        Some(LatencyStats {
+            samples: self.samples,
+            last,
+            min: self.window.iter().copied().min().unwrap_or_default(),
+            mean: total / count,
+            max: self.window.iter().copied().max().unwrap_or_default(),
+            jitter,
+        })
+    }
+}
+
 /// V3 协议共享输出状态
 ///
 /// 由 CoyoteDevice 和后台输出任务共同访问。
-struct V3OutputState {
+pub(super) struct V3OutputState {
     /// 目标 A 通道强度 (0~200)
-    target_strength_a: AtomicU8,
+    pub(super) target_strength_a: AtomicU8,
     /// 目标 B 通道强度 (0~200)
-    target_strength_b: AtomicU8,
+    pub(super) target_strength_b: AtomicU8,
     /// 是否需要发送 A 通道强度变更
-    pending_strength_a: AtomicBool,
+    pub(super) pending_strength_a: AtomicBool,
     /// 是否需要发送 B 通道强度变更
-    pending_strength_b: AtomicBool,
+    pub(super) pending_strength_b: AtomicBool,
+    /// A 通道是否启用（禁用时波形静默）
+    enabled_a: AtomicBool,
+    /// B 通道是否启用（禁用时波形静默）
+    enabled_b: AtomicBool,
+    /// 待发送的 A 通道相对增减量（有绝对值变更待发送时忽略）
+    pending_delta_a: AtomicI16,
+    /// 待发送的 B 通道相对增减量（有绝对值变更待发送时忽略）
+    pending_delta_b: AtomicI16,
     /// 序列号 (0~15)
     sequence: AtomicU8,
     /// 当前 A 通道波形
-    waveform_a: Mutex<WaveformData>,
+    pub(super) waveform_a: Mutex<FrameCycle>,
     /// 当前 B 通道波形
-    waveform_b: Mutex<WaveformData>,
+    pub(super) waveform_b: Mutex<FrameCycle>,
+    /// A 通道待播放波形队列
+    pub(super) queue_a: Mutex<PulseQueue>,
+    /// B 通道待播放波形队列
+    pub(super) queue_b: Mutex<PulseQueue>,
+    /// 等待 B1 反馈的请求（按发送顺序排列的序列号和请求强度）
+    outstanding: Mutex<VecDeque<(u8, PendingStrength)>>,
+    /// 下一个 B0 是否需要携带序列号用于时延探测
+    probe_pending: AtomicBool,
+    /// B1 往返时延
+    latency: std::sync::Mutex<LatencyTracker>,
+    /// 输出节拍计时
+    clock: std::sync::Mutex<OutputClock>,
+    /// 输出和接收任务的错误合并
+    errors: std::sync::Mutex<ErrorLimiter>,
+    /// 导出的运行指标
+    pub(super) telemetry: Arc<DeviceMetrics>,
 }
 
 impl V3OutputState {
-    fn new() -> Self {
+    /// 创建输出状态（指标不登记到全局注册表）
+    #[cfg(test)]
+    pub(super) fn new() -> Self {
+        Self::with_telemetry(Arc::default())
+    }
+
+    /// 创建输出状态，B1 时延和设备上报的强度写入 `telemetry`
+    pub(super) fn with_telemetry(telemetry: Arc<DeviceMetrics>) -> Self {
         Self {
             target_strength_a: AtomicU8::new(0),
             target_strength_b: AtomicU8::new(0),
             pending_strength_a: AtomicBool::new(false),
             pending_strength_b: AtomicBool::new(false),
+            enabled_a: AtomicBool::new(true),
+            enabled_b: AtomicBool::new(true),
+            pending_delta_a: AtomicI16::new(0),
+            pending_delta_b: AtomicI16::new(0),
             sequence: AtomicU8::new(0),
-            waveform_a: Mutex::new(WaveformData::silent()),
-            waveform_b: Mutex::new(WaveformData::silent()),
+            waveform_a: Mutex::new(FrameCycle::single(WaveformData::silent())),
+            waveform_b: Mutex::new(FrameCycle::single(WaveformData::silent())),
+            queue_a: Mutex::new(PulseQueue::default()),
+            queue_b: Mutex::new(PulseQueue::default()),
+            outstanding: Mutex::new(VecDeque::new()),
+            probe_pending: AtomicBool::new(false),
+            latency: std::sync::Mutex::new(LatencyTracker::default()),
+            clock: std::sync::Mutex::new(OutputClock::new(DEFAULT_B0_INTERVAL)),
+            errors: std::sync::Mutex::new(ErrorLimiter::default()),
+            telemetry,
+        }
+    }
+
+    /// 获取错误合并器（锁中毒时继续使用内部数据）
+    fn errors_guard(&self) -> std::sync::MutexGuard<'_, ErrorLimiter> {
+        self.errors
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// 记录输出或接收任务的错误，返回需要上报的消息（重复错误被合并时为 `None`）
+    pub(super) fn record_error(&self, source: &'static str, message: &str) -> Option<String> {
+        self.errors_guard()
+            .record(source, message, tokio::time::Instant::now())
+    }
+
+    /// 来源恢复正常，返回被合并错误的汇总
+    pub(super) fn clear_error(&self, source: &'static str) -> Option<String> {
+        let mut errors = self.errors_guard();
+        if !errors.is_failing(source) {
+            return None;
+        }
+        errors.clear(source, tokio::time::Instant::now())
+    }
+
+    /// 错误计数
+    pub(super) fn error_stats(&self) -> ErrorStats {
+        self.errors_guard().stats()
+    }
+
+    /// 获取节拍计时（锁中毒时继续使用内部数据）
+    fn clock_guard(&self) -> std::sync::MutexGuard<'_, OutputClock> {
+        self.clock
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// 重新开始节拍计时（输出循环启动或间隔变化时）
+    pub(super) fn reset_clock(&self, period: Duration) {
+        *self.clock_guard() = OutputClock::new(period);
+    }
+
+    /// 输出节拍统计
+    pub(super) fn tick_stats(&self) -> TickStats {
+        self.clock_guard().stats()
+    }
+
+    /// 记录一次输出节拍，错过节拍时跳过相应的波形帧，使波形进度与实际时间一致
+    pub(super) async fn tick(&self, now: tokio::time::Instant) {
+        let missed = self.clock_guard().tick(now);
+        if missed > 0 {
+            debug!("Output loop missed {} ticks, skipping frames", missed);
+            self.skip_frames(missed).await;
+        }
+    }
+
+    /// 跳过两个通道的波形帧（队列优先，队列为空时推进通道波形），最多跳过一个队列容量
+    pub(super) async fn skip_frames(&self, frames: u32) {
+        let frames = (frames as usize).min(PULSE_QUEUE_CAPACITY);
+        for (queue, waveform) in [
+            (&self.queue_a, &self.waveform_a),
+            (&self.queue_b, &self.waveform_b),
+        ] {
+            let mut queue = queue.lock().await;
+            let mut waveform = waveform.lock().await;
+            for _ in 0..frames {
+                if queue.pop().is_none() {
+                    let _ = waveform.advance();
+                }
+            }
+        }
+    }
+
+    /// 获取时延跟踪器（锁中毒时继续使用内部数据）
+    fn latency_guard(&self) -> std::sync::MutexGuard<'_, LatencyTracker> {
+        self.latency
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// B1 往返时延统计
+    pub(super) fn latency(&self) -> Option<LatencyStats> {
+        self.latency_guard().stats()
+    }
+
+    /// 请求下一个 B0 携带序列号（强度不变）用于时延探测
+    pub(super) fn request_probe(&self) {
+        self.probe_pending.store(true, Ordering::Relaxed);
+    }
+
+    /// 构建一帧独立的时延探测 B0（强度不变、波形静默），用于输出循环未运行时
+    pub(super) fn probe_b0(&self) -> B0Command {
+        let sequence = self.next_sequence();
+        self.latency_guard()
+            .sent(sequence, tokio::time::Instant::now());
+        B0Command {
+            sequence,
+            strength_mode: StrengthMode::new(
+                ChannelStrengthMode::Increase,
+                ChannelStrengthMode::NoChange,
+            ),
+            strength_a: 0,
+            strength_b: 0,
+            waveform_a: WaveformData::silent(),
+            waveform_b: WaveformData::silent(),
         }
     }
 
@@ -64,80 +398,437 @@ impl V3OutputState {
         (seq % 15) + 1
     }
 
-    /// 构建下一个 B0 指令
-    async fn build_b0(&self) -> B0Command {
-        let need_a = self.pending_strength_a.swap(false, Ordering::Relaxed);
-        let need_b = self.pending_strength_b.swap(false, Ordering::Relaxed);
+    /// 清空目标强度、待发送变更、在途请求和波形队列，波形恢复为静默
+    pub(super) async fn reset(&self) {
+        self.target_strength_a.store(0, Ordering::Relaxed);
+        self.target_strength_b.store(0, Ordering::Relaxed);
+        self.pending_strength_a.store(false, Ordering::Relaxed);
+        self.pending_strength_b.store(false, Ordering::Relaxed);
+        self.pending_delta_a.store(0, Ordering::Relaxed);
+        self.pending_delta_b.store(0, Ordering::Relaxed);
+        self.outstanding.lock().await.clear();
+        self.probe_pending.store(false, Ordering::Relaxed);
+        self.latency_guard().clear_pending();
+        *self.waveform_a.lock().await = FrameCycle::single(WaveformData::silent());
+        *self.waveform_b.lock().await = FrameCycle::single(WaveformData::silent());
+        self.queue_a.lock().await.clear();
+        self.queue_b.lock().await.clear();
+    }
+
+    /// 通道的目标强度（无效通道返回 0）
+    pub(super) fn strength(&self, channel: u8) -> u8 {
+        match channel {
+            0 => self.target_strength_a.load(Ordering::Relaxed),
+            1 => self.target_strength_b.load(Ordering::Relaxed),
+            _ => 0,
+        }
+    }
 
-        let mode_a = if need_a {
-            ChannelStrengthMode::Absolute
-        } else {
-            ChannelStrengthMode::NoChange
+    /// 通道是否启用
+    pub(super) fn channel_enabled(&self, channel: u8) -> bool {
+        match channel {
+            0 => self.enabled_a.load(Ordering::Relaxed),
+            1 => self.enabled_b.load(Ordering::Relaxed),
+            _ => false,
+        }
+    }
+
+    /// 启用或禁用通道
+    ///
+    /// 禁用时目标强度立即归零并清空波形队列，之后的 B0 中该通道波形静默。
+    pub(super) async fn set_channel_enabled(&self, channel: u8, enabled: bool) -> Result<()> {
+        let (flag, target, pending, pending_delta) = match channel {
+            0 => (
+                &self.enabled_a,
+                &self.target_strength_a,
+                &self.pending_strength_a,
+                &self.pending_delta_a,
+            ),
+            1 => (
+                &self.enabled_b,
+                &self.target_strength_b,
+                &self.pending_strength_b,
+                &self.pending_delta_b,
+            ),
+            _ => return Err(CoreError::InvalidChannel(channel)),
         };
 
-        let mode_b = if need_b {
-            ChannelStrengthMode::Absolute
+        flag.store(enabled, Ordering::Relaxed);
+        if !enabled {
+            target.store(0, Ordering::Relaxed);
+            pending.store(true, Ordering::Relaxed);
+            pending_delta.store(0, Ordering::Relaxed);
+            self.queue(channel)?.lock().await.clear();
+        }
+        Ok(())
+    }
+
+    /// 指定通道的波形队列
+    pub(super) fn queue(&self, channel: u8) -> Result<&Mutex<PulseQueue>> {
+        match channel {
+            0 => Ok(&self.queue_a),
+            1 => Ok(&self.queue_b),
+            _ => Err(CoreError::InvalidChannel(channel)),
+        }
+    }
+
+    /// 相对调整目标强度（限制在 0 到 `limit` 之间），返回调整后的目标强度
+    ///
+    /// 没有绝对值变更待发送时，下一个 B0 以增减模式发送实际变化量。
+    pub(super) fn adjust_strength(&self, channel: u8, delta: i16, limit: u8) -> Result<u8> {
+        let (target, pending, pending_delta) = match channel {
+            0 => (
+                &self.target_strength_a,
+                &self.pending_strength_a,
+                &self.pending_delta_a,
+            ),
+            1 => (
+                &self.target_strength_b,
+                &self.pending_strength_b,
+                &self.pending_delta_b,
+            ),
+            _ => return Err(CoreError::InvalidChannel(channel)),
+        };
+
+        // 禁用的通道只能保持为 0
+        let limit = if self.channel_enabled(channel) {
+            limit
         } else {
-            ChannelStrengthMode::NoChange
+            0
         };
+        let current = target.load(Ordering::Relaxed);
+        let strength = (current as i16 + delta).clamp(0, limit as i16) as u8;
+        target.store(strength, Ordering::Relaxed);
+        if !pending.load(Ordering::Relaxed) {
+            let _ = pending_delta.fetch_add(strength as i16 - current as i16, Ordering::Relaxed);
+        }
+        Ok(strength)
+    }
 
-        let sequence = if need_a || need_b {
+    /// 取出一个通道的待发送强度变更：(解读方式, 强度字段, 请求的绝对强度)
+    ///
+    /// 增减模式作用于设备的实际强度，结果与本地目标不同不视为被拒绝，因此不记录请求强度。
+    fn take_strength_change(
+        target: &AtomicU8,
+        pending: &AtomicBool,
+        pending_delta: &AtomicI16,
+    ) -> (ChannelStrengthMode, u8, Option<u8>) {
+        let strength = target.load(Ordering::Relaxed);
+        let absolute = pending.swap(false, Ordering::Relaxed);
+        let delta = pending_delta.swap(0, Ordering::Relaxed);
+
+        match delta {
+            _ if absolute => (ChannelStrengthMode::Absolute, strength, Some(strength)),
+            0 => (ChannelStrengthMode::NoChange, strength, None),
+            d if d > 0 => (
+                ChannelStrengthMode::Increase,
+                d.min(MAX_STRENGTH as i16) as u8,
+                None,
+            ),
+            d => (
+                ChannelStrengthMode::Decrease,
+                (-d).min(MAX_STRENGTH as i16) as u8,
+                None,
+            ),
+        }
+    }
+
+    /// 构建下一个 B0 指令
+    pub(super) async fn build_b0(&self) -> B0Command {
+        let (mode_a, strength_a, requested_a) = Self::take_strength_change(
+            &self.target_strength_a,
+            &self.pending_strength_a,
+            &self.pending_delta_a,
+        );
+        let (mode_b, strength_b, requested_b) = Self::take_strength_change(
+            &self.target_strength_b,
+            &self.pending_strength_b,
+            &self.pending_delta_b,
+        );
+
+        let changed = |mode| mode != ChannelStrengthMode::NoChange;
+        let probe = self.probe_pending.swap(false, Ordering::Relaxed);
+        // 强度不变时无法携带序列号，时延探测以 A 通道 +0 代替
+        let (mode_a, strength_a) = if probe && !changed(mode_a) && !changed(mode_b) {
+            (ChannelStrengthMode::Increase, 0)
+        } else {
+            (mode_a, strength_a)
+        };
+        let sequence = if changed(mode_a) || changed(mode_b) {
             self.next_sequence()
         } else {
             0
         };
 
-        let waveform_a = *self.waveform_a.lock().await;
-        let waveform_b = *self.waveform_b.lock().await;
+        if sequence != 0 {
+            // 序列号循环复用，同号的旧请求直接覆盖
+            let mut outstanding = self.outstanding.lock().await;
+            outstanding.retain(|(seq, _)| *seq != sequence);
+            outstanding.push_back((
+                sequence,
+                PendingStrength {
+                    strength_a: requested_a,
+                    strength_b: requested_b,
+                },
+            ));
+            drop(outstanding);
+            self.latency_guard()
+                .sent(sequence, tokio::time::Instant::now());
+        }
+
+        // 波形队列优先，队列为空时播放通道波形；禁用的通道照常推进但输出静默
+        let waveform_a = match self.queue_a.lock().await.pop() {
+            Some(frame) => frame,
+            None => self.waveform_a.lock().await.advance(),
+        };
+        let waveform_b = match self.queue_b.lock().await.pop() {
+            Some(frame) => frame,
+            None => self.waveform_b.lock().await.advance(),
+        };
+        let silence = |channel, frame| {
+            if self.channel_enabled(channel) {
+                frame
+            } else {
+                WaveformData::silent()
+            }
+        };
+        let (waveform_a, waveform_b) = (silence(0, waveform_a), silence(1, waveform_b));
 
         B0Command {
             sequence,
             strength_mode: StrengthMode::new(mode_a, mode_b),
-            strength_a: self.target_strength_a.load(Ordering::Relaxed),
-            strength_b: self.target_strength_b.load(Ordering::Relaxed),
+            strength_a,
+            strength_b,
             waveform_a,
             waveform_b,
         }
     }
+
+    /// 根据 B1 反馈校正强度
+    ///
+    /// 以设备实际强度更新目标强度（仍有更新的请求待发送或在途时跳过），
+    /// 并返回实际强度与请求不一致（如被软上限截断）的通道。
+    async fn reconcile_b1(&self, response: &B1Response) -> Vec<RejectedStrength> {
+        if response.sequence != 0 {
+            if let Some(latency) = self
+                .latency_guard()
+                .received(response.sequence, tokio::time::Instant::now())
+            {
+                debug!("B1 seq={} round trip {:?}", response.sequence, latency);
+                self.telemetry.observe_b1_latency(latency);
+            }
+        }
+        let (request, in_flight) = {
+            let mut outstanding = self.outstanding.lock().await;
+            let position = match response.sequence {
+                0 => None,
+                seq => outstanding.iter().position(|(s, _)| *s == seq),
+            };
+            // B1 按发送顺序返回，更早的请求已不会再有反馈，一并过期
+            let request = position.and_then(|position| {
+                if position > 0 {
+                    debug!(
+                        "{} B1 responses lost before seq={}",
+                        position, response.sequence
+                    );
+                }
+                outstanding
+                    .drain(..=position)
+                    .last()
+                    .map(|(_, request)| request)
+            });
+            (request, !outstanding.is_empty())
+        };
+
+        let channels = [
+            (
+                0u8,
+                response.strength_a,
+                request.and_then(|r| r.strength_a),
+                &self.target_strength_a,
+                &self.pending_strength_a,
+                &self.pending_delta_a,
+            ),
+            (
+                1u8,
+                response.strength_b,
+                request.and_then(|r| r.strength_b),
+                &self.target_strength_b,
+                &self.pending_strength_b,
+                &self.pending_delta_b,
+            ),
+        ];
+
+        let mut rejected = Vec::new();
+        for (channel, actual, requested, target, pending, pending_delta) in channels {
+            if let Some(requested) = requested {
+                if requested != actual {
+                    rejected.push(RejectedStrength {
+                        channel,
+                        requested,
+                        actual,
+                    });
+                }
+            }
+
+            if !in_flight
+                && !pending.load(Ordering::Relaxed)
+                && pending_delta.load(Ordering::Relaxed) == 0
+            {
+                target.store(actual, Ordering::Relaxed);
+            }
+        }
+
+        rejected
+    }
+}
+
+// ============================================================================
+// BLE 链路质量监测
+// ============================================================================
+
+/// 弱信号恢复的回差（dBm），避免在阈值附近反复告警
+const WEAK_SIGNAL_HYSTERESIS: i16 = 5;
+
+/// BLE 链路质量监测配置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LinkMonitorConfig {
+    /// RSSI 轮询间隔
+    pub interval: Duration,
+    /// 弱信号阈值（dBm），低于或恢复高于该值时发出 `WeakSignal` 事件；`None` 表示不告警
+    pub weak_threshold: Option<i16>,
+}
+
+impl Default for LinkMonitorConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(2),
+            weak_threshold: Some(-80),
+        }
+    }
+}
+
+/// 弱信号状态跟踪
+#[derive(Debug, Default)]
+struct LinkQualityTracker {
+    /// 当前是否处于弱信号状态
+    weak: bool,
+}
+
+impl LinkQualityTracker {
+    /// 更新 RSSI，弱信号状态发生变化时返回新状态
+    fn update(&mut self, rssi: i16, threshold: Option<i16>) -> Option<bool> {
+        let weak = match threshold {
+            None => false,
+            Some(threshold) if self.weak => rssi < threshold.saturating_add(WEAK_SIGNAL_HYSTERESIS),
+            Some(threshold) => rssi < threshold,
+        };
+
+        if weak == self.weak {
+            return None;
+        }
+
+        self.weak = weak;
+        Some(weak)
+    }
 }
 
 // ============================================================================
 // BLE Coyote 设备（V3 协议）
 // ============================================================================
 
+/// 检查固件版本，不受支持或无法识别时返回警告文本
+fn firmware_warning(version: &str) -> Option<String> {
+    match version.parse::<FirmwareVersion>() {
+        Ok(v) if v.is_supported() => None,
+        Ok(v) => Some(format!(
+            "Unsupported firmware version {} (minimum {})",
+            v, MIN_SUPPORTED_FIRMWARE
+        )),
+        Err(_) => Some(format!("Unrecognized firmware version: {}", version)),
+    }
+}
+
 /// Coyote BLE 设备（V3 协议）
 ///
-/// 使用 B0 指令每 100ms 发送强度和波形数据，
-/// 使用 BF 指令设置软上限，接收 B1 强度反馈。
+/// 使用 B0 指令每 100ms（可通过 [`CoyoteDevice::set_output_interval`] 调整）发送强度和波形数据，
+/// 使用 BF 指令设置软上限，接收 B1 强度反馈。帧经 [`Transport`] 收发，默认为 BLE GATT，
+/// 也可以用 [`CoyoteDevice::set_transport`] 接入其他链路。
 pub struct CoyoteDevice {
     /// 基础设备
     base: BaseDevice,
     /// BLE 管理器
     ble_manager: Option<Arc<BleManager>>,
-    /// 协议设备
+    /// 协议设备（BLE 链路时提供设备信息、RSSI 和连接参数）
     protocol_device: Option<ProtocolBleDevice>,
+    /// 收发帧的链路
+    transport: Option<Arc<dyn Transport>>,
+    /// 连接时读取的设备信息（固件/硬件版本、电量）
+    ble_info: Option<BleDeviceInfo>,
     /// V3 协议共享输出状态
     output_state: Arc<V3OutputState>,
+    /// BF 配置（软上限与平衡参数），每次连接后重新写入
+    bf_config: BFCommand,
     /// 100ms 输出任务句柄
     output_task: Option<tokio::task::JoinHandle<()>>,
     /// 接收任务句柄
     receive_task: Option<tokio::task::JoinHandle<()>>,
+    /// 链路质量监测配置
+    link_monitor: LinkMonitorConfig,
+    /// RSSI 轮询任务句柄
+    link_task: Option<tokio::task::JoinHandle<()>>,
+    /// 试运行记录器（设置后不连接 BLE，帧只记录不发送）
+    dry_run: Option<DryRunRecorder>,
+    /// B0 输出间隔（未设置时使用协议设备的设置）
+    output_interval: Option<Duration>,
 }
 
 impl CoyoteDevice {
     /// 创建新的 Coyote 设备
+    ///
+    /// 基础设备的通道上限与 BF 软上限（默认均为 [`MAX_STRENGTH`]）保持一致。
     pub fn new(id: String, name: String) -> Self {
-        let base = BaseDevice::new(id, name);
-        let output_state = Arc::new(V3OutputState::new());
+        let base = BaseDevice::with_max_power(id, name, MAX_STRENGTH);
+        let output_state = Arc::new(V3OutputState::with_telemetry(base.telemetry().clone()));
 
         Self {
             base,
             ble_manager: None,
             protocol_device: None,
+            transport: None,
+            ble_info: None,
             output_state,
+            bf_config: BFCommand::default_config(),
             output_task: None,
             receive_task: None,
+            link_monitor: LinkMonitorConfig::default(),
+            link_task: None,
+            dry_run: None,
+            output_interval: None,
+        }
+    }
+
+    /// 按设备配置创建设备，配置了输出间隔时在连接后应用到协议设备
+    pub fn from_config(config: &DeviceConfig) -> Result<Self> {
+        let mut device = Self::new(config.id.clone(), config.name.clone());
+        if config.output_interval_ms.is_some() {
+            device.set_output_interval(config.output_interval()?)?;
         }
+        Ok(device)
+    }
+
+    /// 创建试运行设备
+    ///
+    /// 连接时不访问 BLE，BF/B0 帧照常生成并交给记录器，不会发送到任何设备。
+    pub fn dry_run(id: String, name: String, recorder: DryRunRecorder) -> Self {
+        let mut device = Self::new(id, name);
+        device.dry_run = Some(recorder);
+        device
+    }
+
+    /// 是否为试运行设备
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run.is_some()
     }
 
     /// 使用 BLE 管理器创建设备
@@ -147,46 +838,276 @@ impl CoyoteDevice {
         device
     }
 
-    /// 设置协议设备
+    /// 设置协议设备（已设置输出间隔时应用到协议设备）
     pub fn set_protocol_device(&mut self, device: ProtocolBleDevice) {
+        if let Some(interval) = self.output_interval {
+            if let Err(e) = device.set_b0_interval(interval) {
+                warn!("Failed to apply output interval {:?}: {}", interval, e);
+            }
+        }
+        self.transport = Some(Arc::new(device.clone()));
         self.protocol_device = Some(device);
     }
 
-    /// 发送 BF 配置指令
+    /// 设置收发帧的链路（如串口调试或模拟器的 TCP 链路），连接时不再经过 BLE 管理器
     ///
-    /// 每次重连后必须重新发送 BF 指令设置软上限。
-    async fn send_bf_config(&self, config: &BFCommand) -> Result<()> {
-        let device = self
-            .protocol_device
+    /// 读取设备信息、RSSI 监测和连接参数只在 BLE 链路上可用，见 [`Self::set_protocol_device`]。
+    pub fn set_transport(&mut self, transport: Arc<dyn Transport>) {
+        self.protocol_device = None;
+        self.transport = Some(transport);
+    }
+
+    /// 当前 B0 输出间隔
+    pub fn output_interval(&self) -> Duration {
+        match &self.protocol_device {
+            Some(device) => device.b0_interval(),
+            None => self.output_interval.unwrap_or(DEFAULT_B0_INTERVAL),
+        }
+    }
+
+    /// 设置 B0 输出间隔（50~250ms，默认 100ms），输出循环在下一帧生效
+    ///
+    /// 每帧仍包含 4 × 25ms 波形，缩短间隔只用于测试设备对更高发送频率的表现。
+    pub fn set_output_interval(&mut self, interval: Duration) -> Result<()> {
+        let interval = validate_b0_interval(interval)
+            .map_err(|e| CoreError::InvalidParameter(e.to_string()))?;
+        if let Some(device) = &self.protocol_device {
+            device.set_b0_interval(interval)?;
+        }
+        self.output_interval = Some(interval);
+        // 试运行的输出循环不读取协议设备的设置，重新启动后生效
+        if self.dry_run.is_some() && self.output_task.is_some() {
+            self.stop_output_loop();
+            self.start_output_loop();
+        }
+        Ok(())
+    }
+
+    /// 输出节拍统计（实际间隔的漂移和因负载错过的节拍）
+    pub fn output_tick_stats(&self) -> TickStats {
+        self.output_state.tick_stats()
+    }
+
+    /// 读取设备信息，固件版本过低或无法识别时发出警告
+    ///
+    /// 读取失败不影响连接，只是设备信息中的版本字段保持为空。
+    async fn read_device_info(&mut self) {
+        let Some(device) = &self.protocol_device else {
+            return;
+        };
+        let info = match device.read_info().await {
+            Ok(info) => info,
+            Err(e) => {
+                warn!("Failed to read device info for {}: {}", self.base.id(), e);
+                return;
+            }
+        };
+
+        if let Some(version) = &info.firmware_version {
+            info!("Device {} firmware version: {}", self.base.id(), version);
+            if let Some(message) = firmware_warning(version) {
+                warn!("{}: {}", self.base.id(), message);
+                self.base.send_event(DeviceEvent::Error(message));
+            }
+        }
+        self.ble_info = Some(info);
+    }
+
+    /// 停止输出并向设备写入归零帧
+    ///
+    /// 顺序：先停止 100ms 输出循环，保证之后不会再有旧强度的 B0 写入；再清空本地输出状态，
+    /// 避免重新启动时恢复旧强度；最后发送一帧 [`B0Command::zero`]，让设备立即将两通道
+    /// 强度归零并停止波形，而不是继续循环最后一帧直到自身超时。
+    ///
+    /// 设备仍处于连接状态，之后可以重新 `start`。[`Device::disconnect`] 在断开 BLE 前会调用此方法。
+    pub async fn shutdown(&mut self) -> Result<()> {
+        info!("Shutting down Coyote V3 output: {}", self.base.id());
+
+        self.stop_output_loop();
+        self.output_state.reset().await;
+        for channel in [0, 1] {
+            self.base.set_power(channel, 0)?;
+        }
+
+        if self.base.state() == DeviceState::Running {
+            self.base.set_state(DeviceState::Connected);
+        }
+
+        if self.has_link() {
+            let data = B0Command::zero().encode();
+            debug!("Sending zero B0: {:02x?}", data);
+            self.transmit(&data).await?;
+        }
+
+        Ok(())
+    }
+
+    /// 把 B1 反馈校正后的目标强度同步到 BaseDevice（接收任务无法直接修改 BaseDevice）
+    fn sync_base_power(&mut self) {
+        for channel in [0, 1] {
+            self.base
+                .sync_power(channel, self.output_state.strength(channel));
+        }
+    }
+
+    /// 是否有可写入的链路（已连接或处于试运行）
+    fn has_link(&self) -> bool {
+        self.transport.is_some() || self.dry_run.is_some()
+    }
+
+    /// 写入数据，试运行时只记录
+    async fn transmit(&self, data: &[u8]) -> Result<()> {
+        if let Some(recorder) = &self.dry_run {
+            recorder.record(self.base.id(), DryRunPayload::Ble(data.to_vec()));
+            return Ok(());
+        }
+
+        let transport = self
+            .transport
             .as_ref()
             .ok_or(CoreError::DeviceNotConnected)?;
+        transport.send_frame(data).await?;
+        Ok(())
+    }
 
-        let data = config.encode();
-        debug!("Sending BF config: {:02x?}", data);
-        device.send(&data).await?;
+    /// 向通道的波形队列追加帧，返回因超出 [`PULSE_QUEUE_CAPACITY`] 丢弃的最早帧数
+    ///
+    /// 队列中的帧每 100ms 播放一帧，优先于 [`set_waveform`](Device::set_waveform) 设置的波形。
+    pub async fn queue_pulses(&self, channel: u8, frames: Vec<WaveformData>) -> Result<usize> {
+        let dropped = self.output_state.queue(channel)?.lock().await.push(frames);
+        if dropped > 0 {
+            debug!(
+                "Pulse queue of {} channel {} full, dropped {} oldest frames",
+                self.base.id(),
+                channel,
+                dropped
+            );
+        }
+        Ok(dropped)
+    }
 
+    /// 清空通道的波形队列
+    pub async fn clear_pulses(&self, channel: u8) -> Result<()> {
+        self.output_state.queue(channel)?.lock().await.clear();
         Ok(())
     }
 
-    /// 启动 100ms B0 输出循环
+    /// 通道波形队列中尚未播放的帧数
+    pub async fn queued_pulses(&self, channel: u8) -> Result<usize> {
+        Ok(self.output_state.queue(channel)?.lock().await.len())
+    }
+
+    /// 链路是否仍然连接
+    pub async fn is_ble_connected(&self) -> bool {
+        match &self.transport {
+            Some(transport) => transport.link_state().await == LinkState::Connected,
+            None => false,
+        }
+    }
+
+    /// 获取链路质量监测配置
+    pub fn link_monitor(&self) -> LinkMonitorConfig {
+        self.link_monitor
+    }
+
+    /// 设置链路质量监测配置（已连接时立即生效）
+    pub fn set_link_monitor(&mut self, config: LinkMonitorConfig) {
+        self.link_monitor = config;
+        if self.link_task.is_some() {
+            self.stop_link_monitor();
+            self.start_link_monitor();
+        }
+    }
+
+    /// 发送 BF 配置指令
+    ///
+    /// 每次重连后必须重新发送 BF 指令设置软上限。
+    async fn send_bf_config(&self, config: &BFCommand) -> Result<()> {
+        let data = config.encode();
+        debug!("Sending BF config: {:02x?}", data);
+        self.transmit(&data).await
+    }
+
+    /// 启动 B0 输出循环（默认 100ms，间隔由协议设备的设置决定，修改后下一帧生效）
+    ///
+    /// 负载过高错过节拍时不补发积压的帧，按错过的节拍数跳过波形帧，见 [`OutputClock`]。
     fn start_output_loop(&mut self) {
-        if let Some(device) = self.protocol_device.clone() {
+        if let Some(recorder) = self.dry_run.clone() {
             let state = self.output_state.clone();
-            let event_tx = self.base.event_tx.clone();
-
-            let handle = tokio::spawn(async move {
-                let mut interval = tokio::time::interval(Duration::from_millis(100));
+            let device_id = self.base.id().to_string();
+            let period = self.output_interval();
+
+            let supervisor = self.base.supervisor();
+            let handle = supervisor.spawn("dry-run output", RestartPolicy::default(), move || {
+                let recorder = recorder.clone();
+                let state = state.clone();
+                let device_id = device_id.clone();
+                async move {
+                    let mut interval = output_interval(period);
+                    state.reset_clock(period);
+
+                    loop {
+                        let _ = interval.tick().await;
+                        state.tick(tokio::time::Instant::now()).await;
+                        let data = state.build_b0().await.encode();
+                        recorder.record(&device_id, DryRunPayload::Ble(data.to_vec()));
+                    }
+                }
+            });
 
-                loop {
-                    interval.tick().await;
+            self.output_task = Some(handle);
+        } else if let Some(transport) = self.transport.clone() {
+            let state = self.output_state.clone();
+            let event_tx = self.base.event_tx.clone();
+            // BLE 链路按协议设备的设置（可在运行时修改），其他链路使用固定间隔
+            let ble = self.protocol_device.clone();
+            let fixed = self.output_interval();
+
+            let supervisor = self.base.supervisor();
+            let handle = supervisor.spawn("B0 output", RestartPolicy::default(), move || {
+                let transport = transport.clone();
+                let ble = ble.clone();
+                let state = state.clone();
+                let event_tx = event_tx.clone();
+                async move {
+                    let period = || ble.as_ref().map_or(fixed, ProtocolBleDevice::b0_interval);
+                    let mut interval = output_interval(period());
+                    state.reset_clock(period());
+
+                    loop {
+                        let _ = interval.tick().await;
+                        if interval.period() != period() {
+                            // 间隔变化后从下一帧开始按新间隔计时
+                            interval = output_interval(period());
+                            let _ = interval.tick().await;
+                            state.reset_clock(period());
+                        }
+                        state.tick(tokio::time::Instant::now()).await;
 
-                    let cmd = state.build_b0().await;
-                    let data = cmd.encode();
+                        let cmd = state.build_b0().await;
+                        let data = cmd.encode();
 
-                    if let Err(e) = device.send(&data).await {
-                        warn!("B0 send failed: {}", e);
-                        let _ = event_tx.send(DeviceEvent::Error(format!("B0 send failed: {}", e)));
-                        break;
+                        match transport.send_frame(&data).await {
+                            Ok(()) => {
+                                state.telemetry.record_b0_frame();
+                                if let Some(summary) = state.clear_error(B0_ERROR_SOURCE) {
+                                    warn!("{}, recovered", summary);
+                                    let _ = event_tx.send(DeviceEvent::Error(summary));
+                                }
+                            }
+                            Err(e) => {
+                                // 链路抖动时继续输出，重复的错误合并上报；设备断开后停止
+                                let message = format!("B0 send failed: {}", e);
+                                if let Some(message) = state.record_error(B0_ERROR_SOURCE, &message)
+                                {
+                                    warn!("{}", message);
+                                    let _ = event_tx.send(DeviceEvent::Error(message));
+                                }
+                                if transport.link_state().await == LinkState::Disconnected {
+                                    break;
+                                }
+                            }
+                        }
                     }
                 }
             });
@@ -204,31 +1125,51 @@ impl CoyoteDevice {
 
     /// 启动接收任务（监听 B1 强度反馈）
     fn start_receive_task(&mut self) {
-        if let Some(device) = self.protocol_device.clone() {
+        if let Some(transport) = self.transport.clone() {
+            let state = self.output_state.clone();
             let event_tx = self.base.event_tx.clone();
 
-            let handle = tokio::spawn(async move {
-                loop {
-                    match device.receive().await {
-                        Ok(data) => {
-                            debug!("Received notification: {:02x?}", data);
-                            match NotifyMessage::parse(&data) {
-                                NotifyMessage::Strength(b1) => {
-                                    Self::handle_b1_response(&b1, &event_tx);
+            let supervisor = self.base.supervisor();
+            let handle = supervisor.spawn(
+                "notification receive",
+                RestartPolicy::default(),
+                move || {
+                    let transport = transport.clone();
+                    let state = state.clone();
+                    let event_tx = event_tx.clone();
+                    async move {
+                        loop {
+                            match transport.recv_frame().await {
+                                Ok(data) => {
+                                    debug!("Received notification: {:02x?}", data);
+                                    match NotifyMessage::try_parse(&data) {
+                                        Ok(NotifyMessage::Strength(b1)) => {
+                                            Self::handle_b1_response(&state, &b1, &event_tx).await;
+                                        }
+                                        Ok(NotifyMessage::Unknown(data)) => {
+                                            debug!("Unknown notification: {:02x?}", data);
+                                        }
+                                        Err(e) => {
+                                            let message = format!("Malformed notification: {}", e);
+                                            if let Some(message) =
+                                                state.record_error(NOTIFY_ERROR_SOURCE, &message)
+                                            {
+                                                warn!("{} ({:02x?})", message, data);
+                                            }
+                                        }
+                                    }
                                 }
-                                NotifyMessage::Unknown(data) => {
-                                    debug!("Unknown notification: {:02x?}", data);
+                                Err(e) => {
+                                    error!("{} receive error: {}", transport.kind(), e);
+                                    let _ = state.record_error(NOTIFY_ERROR_SOURCE, &e.to_string());
+                                    let _ = event_tx.send(DeviceEvent::Error(e.to_string()));
+                                    break;
                                 }
                             }
                         }
-                        Err(e) => {
-                            error!("BLE receive error: {}", e);
-                            let _ = event_tx.send(DeviceEvent::Error(e.to_string()));
-                            break;
-                        }
                     }
-                }
-            });
+                },
+            );
 
             self.receive_task = Some(handle);
         }
@@ -241,19 +1182,119 @@ impl CoyoteDevice {
         }
     }
 
+    /// 启动 RSSI 轮询任务
+    fn start_link_monitor(&mut self) {
+        if let Some(device) = self.protocol_device.clone() {
+            let config = self.link_monitor;
+            let event_tx = self.base.event_tx.clone();
+            let device_id = self.base.id().to_string();
+
+            let supervisor = self.base.supervisor();
+            let handle = supervisor.spawn("link monitor", RestartPolicy::default(), move || {
+                let device = device.clone();
+                let event_tx = event_tx.clone();
+                let device_id = device_id.clone();
+                async move {
+                    let mut interval = tokio::time::interval(config.interval);
+                    let mut tracker = LinkQualityTracker::default();
+
+                    loop {
+                        interval.tick().await;
+
+                        let rssi = match device.rssi().await {
+                            Ok(Some(rssi)) => rssi,
+                            Ok(None) => continue,
+                            Err(e) => {
+                                debug!("Failed to read RSSI for {}: {}", device_id, e);
+                                continue;
+                            }
+                        };
+
+                        // 先发送状态变化，订阅方收到 LinkQuality 时弱信号状态已是最新
+                        if let Some(weak) = tracker.update(rssi, config.weak_threshold) {
+                            if weak {
+                                warn!("Weak BLE signal on {}: {} dBm", device_id, rssi);
+                            } else {
+                                info!("BLE signal recovered on {}: {} dBm", device_id, rssi);
+                            }
+                            let _ = event_tx.send(DeviceEvent::WeakSignal { rssi, weak });
+                        }
+
+                        let _ = event_tx.send(DeviceEvent::LinkQuality(rssi));
+                    }
+                }
+            });
+
+            self.link_task = Some(handle);
+        }
+    }
+
+    /// 停止 RSSI 轮询任务
+    fn stop_link_monitor(&mut self) {
+        if let Some(handle) = self.link_task.take() {
+            handle.abort();
+        }
+    }
+
     /// 处理 B1 强度反馈
-    fn handle_b1_response(response: &B1Response, event_tx: &broadcast::Sender<DeviceEvent>) {
+    pub(super) async fn handle_b1_response(
+        state: &V3OutputState,
+        response: &B1Response,
+        event_tx: &broadcast::Sender<DeviceEvent>,
+    ) {
         debug!(
             "B1 response: seq={}, strength_a={}, strength_b={}",
             response.sequence, response.strength_a, response.strength_b
         );
+
+        let before = [state.strength(0), state.strength(1)];
+        let rejected = state.reconcile_b1(response).await;
+        // 目标强度按设备反馈校正后更新指标并通知订阅方，事件与 get_power 保持一致；
+        // BaseDevice 的强度在下次使用前由 sync_base_power 校正
+        for channel in [0u8, 1] {
+            let power = state.strength(channel);
+            if power != before[channel as usize] {
+                state.telemetry.set_power(channel, power);
+                let _ = event_tx.send(DeviceEvent::PowerChanged { channel, power });
+            }
+        }
+
+        for rejected in rejected {
+            warn!(
+                "Channel {} strength {} not applied, device reports {}",
+                rejected.channel, rejected.requested, rejected.actual
+            );
+            let _ = event_tx.send(DeviceEvent::PowerRejected {
+                channel: rejected.channel,
+                requested: rejected.requested,
+                actual: rejected.actual,
+            });
+        }
+
         let _ = event_tx.send(DeviceEvent::StatusReport {
             power_a: response.strength_a,
             power_b: response.strength_b,
         });
     }
 
+    /// 将 WaveformConfig 转为 V3 波形帧序列
+    ///
+    /// `Custom` 类型的 `custom_data` 按 8 字节一帧解析（不足一帧的尾部忽略），
+    /// 其它类型生成单帧。
+    pub(super) fn waveform_config_to_frames(config: &WaveformConfig) -> Vec<WaveformData> {
+        match (&config.waveform_type, &config.custom_data) {
+            (WaveformType::Custom, Some(data)) if data.len() >= 8 => data
+                .chunks_exact(8)
+                .filter_map(WaveformData::decode)
+                .collect(),
+            _ => vec![Self::waveform_config_to_v3(config)],
+        }
+    }
+
     /// 将 WaveformConfig 转为 V3 WaveformData
+    ///
+    /// 只能生成单帧近似形状；波形库中的波形经 `Waveform::to_device_config` 按 25ms 逐点采样，
+    /// 以 `Custom` 帧序列下发，不经过这里。
     fn waveform_config_to_v3(config: &WaveformConfig) -> WaveformData {
         // V3 波形格式: 4 组 [频率, 强度]，每组 25ms
         // 简单映射: 将 WaveformConfig 的 frequency 压缩后作为频率，intensity 作为强度
@@ -309,6 +1350,14 @@ impl CoyoteDevice {
 
 #[async_trait]
 impl Device for CoyoteDevice {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
     fn id(&self) -> &str {
         self.base.id()
     }
@@ -326,13 +1375,28 @@ impl Device for CoyoteDevice {
             id: self.base.id().to_string(),
             name: self.base.name().to_string(),
             device_type: "Coyote V3".to_string(),
-            firmware_version: String::new(),
-            hardware_version: String::new(),
-            battery_level: 0, // 通过 BLE 电池特征单独读取
+            firmware_version: self
+                .ble_info
+                .as_ref()
+                .and_then(|i| i.firmware_version.clone())
+                .unwrap_or_default(),
+            hardware_version: self
+                .ble_info
+                .as_ref()
+                .and_then(|i| i.hardware_version.clone())
+                .unwrap_or_default(),
+            battery_level: self
+                .ble_info
+                .as_ref()
+                .and_then(|i| i.battery_level)
+                .unwrap_or(0),
+            battery_remaining_secs: None,
             power_a: self.output_state.target_strength_a.load(Ordering::Relaxed),
             power_b: self.output_state.target_strength_b.load(Ordering::Relaxed),
-            max_power_a: MAX_STRENGTH,
-            max_power_b: MAX_STRENGTH,
+            max_power_a: self.bf_config.soft_limit_a,
+            max_power_b: self.bf_config.soft_limit_b,
+            enabled_a: self.base.channel_enabled(0),
+            enabled_b: self.base.channel_enabled(1),
         }
     }
 
@@ -345,24 +1409,26 @@ impl Device for CoyoteDevice {
 
         self.base.set_state(DeviceState::Connecting);
 
-        // 如果还没有 protocol_device，且有 BLE 管理器，使用它连接
-        if self.protocol_device.is_none() {
+        // 如果还没有链路，且有 BLE 管理器，使用它连接（试运行不连接 BLE）
+        if self.transport.is_none() && self.dry_run.is_none() {
             if let Some(manager) = &self.ble_manager {
                 let device = manager.connect(self.base.id()).await?;
-                self.protocol_device = Some(device);
+                self.set_protocol_device(device);
             } else {
                 return Err(CoreError::DeviceNotConnected);
             }
         }
 
-        // 连接后发送 BF 配置（设置软上限为最大值）
-        let bf = BFCommand::default_config();
-        self.send_bf_config(&bf).await?;
+        self.read_device_info().await;
+
+        // 连接后发送 BF 配置（设置软上限）
+        self.send_bf_config(&self.bf_config).await?;
 
         self.base.set_state(DeviceState::Connected);
 
-        // 启动接收任务
+        // 启动接收任务和链路质量监测
         self.start_receive_task();
+        self.start_link_monitor();
 
         Ok(())
     }
@@ -370,17 +1436,24 @@ impl Device for CoyoteDevice {
     async fn disconnect(&mut self) -> Result<()> {
         info!("Disconnecting Coyote V3 device: {}", self.base.id());
 
-        self.stop_output_loop();
-        self.stop_receive_task();
-
-        if let Some(device) = &self.protocol_device {
-            device.disconnect().await?;
+        // 链路可能已经断开，归零帧发送失败不阻止断开
+        if let Err(e) = self.shutdown().await {
+            warn!("Failed to flush zero strength to {}: {}", self.base.id(), e);
         }
+        self.stop_receive_task();
+        self.stop_link_monitor();
 
+        // 链路已经断开时外设断开也可能失败，仍然清理本地状态以便之后重连
         self.protocol_device = None;
+        let result = match self.transport.take() {
+            Some(transport) => transport.close().await,
+            None => Ok(()),
+        };
+
+        self.ble_info = None;
         self.base.set_state(DeviceState::Disconnected);
 
-        Ok(())
+        result.map_err(Into::into)
     }
 
     async fn start(&mut self) -> Result<()> {
@@ -404,31 +1477,26 @@ impl Device for CoyoteDevice {
             return Ok(());
         }
 
-        // 停止输出循环
-        self.stop_output_loop();
-
-        // 重置强度和波形
-        self.output_state
-            .target_strength_a
-            .store(0, Ordering::Relaxed);
-        self.output_state
-            .target_strength_b
-            .store(0, Ordering::Relaxed);
-        *self.output_state.waveform_a.lock().await = WaveformData::silent();
-        *self.output_state.waveform_b.lock().await = WaveformData::silent();
-
-        self.base.set_state(DeviceState::Connected);
-
-        Ok(())
+        // 停止输出循环、重置强度和波形，并让设备立即归零
+        self.shutdown().await
     }
 
     async fn set_power(&mut self, channel: u8, power: u8) -> Result<()> {
         debug!("Setting V3 channel {} power to {}", channel, power);
 
-        if power > MAX_STRENGTH {
-            return Err(CoreError::PowerOutOfRange(power, MAX_STRENGTH));
+        let limit = match channel {
+            0 => self.bf_config.soft_limit_a,
+            1 => self.bf_config.soft_limit_b,
+            _ => return Err(CoreError::InvalidParameter("Invalid channel".to_string())),
+        };
+
+        if power > limit {
+            return Err(CoreError::PowerOutOfRange(power, limit));
         }
 
+        // 禁用的通道始终为 0
+        let power = self.base.gate_power(channel, power);
+
         match channel {
             0 => {
                 self.output_state
@@ -449,27 +1517,125 @@ impl Device for CoyoteDevice {
             _ => return Err(CoreError::InvalidParameter("Invalid channel".to_string())),
         }
 
-        // 更新 BaseDevice 的强度值（用于事件通知）
-        // 注意: V3 最大强度 200，但 BaseDevice 默认 max 100，需要兼容
-        let _ = self
-            .base
-            .set_power(channel, power.min(self.base.power_a().max(power)));
+        // 同步 BaseDevice 的强度并发送事件（两者上限一致）
+        self.base.set_power(channel, power)?;
 
         Ok(())
     }
 
     fn get_power(&self, channel: u8) -> u8 {
+        self.output_state.strength(channel)
+    }
+
+    async fn adjust_power(&mut self, channel: u8, delta: i16) -> Result<u8> {
+        debug!("Adjusting V3 channel {} power by {}", channel, delta);
+
+        let limit = match channel {
+            0 => self.bf_config.soft_limit_a,
+            1 => self.bf_config.soft_limit_b,
+            _ => return Err(CoreError::InvalidParameter("Invalid channel".to_string())),
+        };
+
+        // 由下一个 B0 以增减模式发送
+        let power = self.output_state.adjust_strength(channel, delta, limit)?;
+        self.base.set_power(channel, power)?;
+
+        Ok(power)
+    }
+
+    async fn set_max_power(&mut self, channel: u8, max_power: u8) -> Result<()> {
+        debug!("Setting V3 channel {} soft limit to {}", channel, max_power);
+
+        if max_power > MAX_STRENGTH {
+            return Err(CoreError::PowerOutOfRange(max_power, MAX_STRENGTH));
+        }
+
         match channel {
-            0 => self.output_state.target_strength_a.load(Ordering::Relaxed),
-            1 => self.output_state.target_strength_b.load(Ordering::Relaxed),
-            _ => 0,
+            0 => self.bf_config.soft_limit_a = max_power,
+            1 => self.bf_config.soft_limit_b = max_power,
+            _ => return Err(CoreError::InvalidParameter("Invalid channel".to_string())),
+        }
+
+        // 当前强度超过新上限时下调，之后再收紧 BaseDevice 的上限
+        self.sync_base_power();
+        if self.get_power(channel) > max_power {
+            self.set_power(channel, max_power).await?;
         }
+        self.base.set_max_power(channel, max_power)?;
+
+        if self.has_link() {
+            self.send_bf_config(&self.bf_config).await?;
+        }
+
+        Ok(())
+    }
+
+    fn limits(&self) -> DeviceLimits {
+        DeviceLimits::new(
+            self.bf_config.soft_limit_a,
+            self.bf_config.soft_limit_b,
+            self.bf_config.freq_balance_a,
+            self.bf_config.intensity_balance_a,
+        )
+    }
+
+    async fn set_limits(&mut self, limits: DeviceLimits) -> Result<()> {
+        debug!("Setting V3 limits: {:?}", limits);
+
+        limits.validate()?;
+
+        self.bf_config = BFCommand {
+            soft_limit_a: limits.soft_limit_a,
+            soft_limit_b: limits.soft_limit_b,
+            freq_balance_a: limits.freq_balance,
+            freq_balance_b: limits.freq_balance,
+            intensity_balance_a: limits.intensity_balance,
+            intensity_balance_b: limits.intensity_balance,
+        };
+
+        // 当前强度超过新上限时下调，之后再收紧 BaseDevice 的上限
+        self.sync_base_power();
+        for (channel, max_power) in [(0, limits.soft_limit_a), (1, limits.soft_limit_b)] {
+            if self.get_power(channel) > max_power {
+                self.set_power(channel, max_power).await?;
+            }
+        }
+        self.base.set_limits(limits)?;
+
+        if self.has_link() {
+            self.send_bf_config(&self.bf_config).await?;
+        }
+
+        Ok(())
+    }
+
+    fn channel_link(&self) -> Option<ChannelLink> {
+        self.base.channel_link()
+    }
+
+    async fn set_channel_link(&mut self, link: Option<ChannelLink>) -> Result<()> {
+        debug!("Setting V3 channel link: {:?}", link);
+
+        self.base.set_channel_link(link)
+    }
+
+    fn channel_enabled(&self, channel: u8) -> bool {
+        self.base.channel_enabled(channel)
+    }
+
+    async fn set_channel_enabled(&mut self, channel: u8, enabled: bool) -> Result<()> {
+        debug!("Setting V3 channel {} enabled: {}", channel, enabled);
+
+        self.output_state
+            .set_channel_enabled(channel, enabled)
+            .await?;
+        self.base.set_channel_enabled(channel, enabled)
     }
 
     async fn set_waveform(&mut self, channel: u8, config: WaveformConfig) -> Result<()> {
         debug!("Setting V3 channel {} waveform: {:?}", channel, config);
 
-        let waveform = Self::waveform_config_to_v3(&config);
+        let waveform = FrameCycle::new(Self::waveform_config_to_frames(&config));
 
         match channel {
             0 => *self.output_state.waveform_a.lock().await = waveform,
@@ -477,18 +1643,42 @@ impl Device for CoyoteDevice {
             _ => return Err(CoreError::InvalidParameter("Invalid channel".to_string())),
         }
 
-        Ok(())
+        Ok(())
+    }
+
+    async fn heartbeat(&mut self) -> Result<()> {
+        // V3 协议中，100ms B0 输出循环本身就是心跳
+        // 如果未在运行状态，发送一个 NoChange 的 B0
+        if self.base.state() == DeviceState::Connected && self.has_link() {
+            let cmd = B0Command::waveform_only(WaveformData::silent(), WaveformData::silent());
+            self.transmit(&cmd.encode()).await?;
+        }
+        Ok(())
+    }
+
+    fn latency(&self) -> Option<LatencyStats> {
+        self.output_state.latency()
     }
 
-    async fn heartbeat(&mut self) -> Result<()> {
-        // V3 协议中，100ms B0 输出循环本身就是心跳
-        // 如果未在运行状态，发送一个 NoChange 的 B0
-        if self.base.state() == DeviceState::Connected {
-            if let Some(device) = &self.protocol_device {
-                let cmd = B0Command::waveform_only(WaveformData::silent(), WaveformData::silent());
-                let data = cmd.encode();
-                device.send(&data).await?;
-            }
+    fn error_stats(&self) -> Option<ErrorStats> {
+        Some(self.output_state.error_stats())
+    }
+
+    fn link_params(&self) -> Option<LinkParams> {
+        self.protocol_device.as_ref().map(|d| d.link_params())
+    }
+
+    async fn probe_latency(&mut self) -> Result<()> {
+        if !self.has_link() {
+            return Err(CoreError::DeviceNotConnected);
+        }
+
+        // 输出循环运行时由下一个 B0 携带，否则立即发送一帧静默探测
+        if self.output_task.is_some() {
+            self.output_state.request_probe();
+        } else {
+            let cmd = self.output_state.probe_b0();
+            self.transmit(&cmd.encode()).await?;
         }
         Ok(())
     }
@@ -496,12 +1686,31 @@ impl Device for CoyoteDevice {
     fn subscribe_events(&self) -> broadcast::Receiver<DeviceEvent> {
         self.base.subscribe_events()
     }
+
+    fn send_event(&self, event: DeviceEvent) {
+        self.base.send_event(event);
+    }
 }
 
 impl Drop for CoyoteDevice {
+    /// 未经 `shutdown` 就被丢弃且仍在输出时，尽力在后台补发一帧归零 B0
     fn drop(&mut self) {
+        let was_running = self.output_task.is_some();
         self.stop_output_loop();
         self.stop_receive_task();
+        self.stop_link_monitor();
+
+        if let (true, Some(transport), Ok(handle)) = (
+            was_running,
+            self.transport.take(),
+            tokio::runtime::Handle::try_current(),
+        ) {
+            let _ = handle.spawn(async move {
+                if let Err(e) = transport.send_frame(&B0Command::zero().encode()).await {
+                    warn!("Failed to flush zero strength on drop: {}", e);
+                }
+            });
+        }
     }
 }
 
@@ -512,9 +1721,159 @@ impl Drop for CoyoteDevice {
 /// WiFi WebSocket Coyote 设备（共享状态）
 struct WsCoyoteInner {
     /// WebSocket 客户端
-    ws_client: Mutex<Option<dglab_protocol::wifi::WsClient>>,
-    /// 服务器 URL
-    server_url: String,
+    ws_client: Mutex<Option<WsClient>>,
+    /// 服务器地址
+    server: ServerAddress,
+    /// 各通道波形预发送调度器
+    pulse_streams: std::sync::Mutex<[Option<PulseScheduler>; 2]>,
+    /// APP 最近上报的强度上限 (A, B)，收到强度消息前为 `None`
+    app_max_power: std::sync::Mutex<Option<(u8, u8)>>,
+    /// APP 最近上报的强度 (A, B)
+    app_power: std::sync::Mutex<(u8, u8)>,
+    /// 通道启用状态（与 `BaseDevice` 同步，供接收任务使用）
+    enabled: std::sync::Mutex<[bool; 2]>,
+    /// 设备状态（与 `BaseDevice` 同步，供接收任务在重连后恢复）
+    state: std::sync::Mutex<DeviceState>,
+    /// 连接中断、正在重连（期间设备状态为 `Connecting`）
+    reconnecting: AtomicBool,
+    /// 导出的运行指标
+    telemetry: Arc<DeviceMetrics>,
+}
+
+impl WsCoyoteInner {
+    /// 获取波形调度器（锁中毒时继续使用内部数据）
+    fn pulse_streams(&self) -> std::sync::MutexGuard<'_, [Option<PulseScheduler>; 2]> {
+        self.pulse_streams.lock().unwrap_or_else(|p| p.into_inner())
+    }
+
+    /// APP 上报的通道强度上限（未上报时不限制）
+    fn app_max_power(&self, channel: u8) -> u8 {
+        let limits = *self.app_max_power.lock().unwrap_or_else(|p| p.into_inner());
+        match (limits, channel) {
+            (Some((max_a, _)), 0) => max_a,
+            (Some((_, max_b)), 1) => max_b,
+            _ => MAX_STRENGTH,
+        }
+    }
+
+    /// APP 上报的通道强度
+    fn app_power(&self, channel: u8) -> u8 {
+        let (power_a, power_b) = *self.app_power.lock().unwrap_or_else(|p| p.into_inner());
+        match channel {
+            0 => power_a,
+            1 => power_b,
+            _ => 0,
+        }
+    }
+
+    /// 通道是否启用
+    fn channel_enabled(&self, channel: u8) -> bool {
+        let enabled = *self.enabled.lock().unwrap_or_else(|p| p.into_inner());
+        enabled.get(channel as usize).copied().unwrap_or(false)
+    }
+
+    /// 按通道启用状态过滤强度，禁用的通道始终为 0
+    fn gate_power(&self, channel: u8, power: u8) -> u8 {
+        if self.channel_enabled(channel) {
+            power
+        } else {
+            0
+        }
+    }
+
+    /// APP 上报的强度中被调高的禁用通道（需要发送归零），观察端不干预
+    fn raised_disabled_channels(&self, event: &dglab_protocol::wifi::WsEvent) -> Vec<u8> {
+        let dglab_protocol::wifi::WsEvent::Strength(data) = event else {
+            return Vec::new();
+        };
+        if self.server.role() == ClientRole::Viewer {
+            return Vec::new();
+        }
+        [(0, data.strength_a), (1, data.strength_b)]
+            .into_iter()
+            .filter(|&(channel, strength)| strength > 0 && !self.channel_enabled(channel))
+            .map(|(channel, _)| channel)
+            .collect()
+    }
+
+    /// 记录连接中断或恢复，返回需要通知的设备状态（未变化时为 `None`）
+    fn set_reconnecting(&self, reconnecting: bool) -> Option<DeviceState> {
+        if self.reconnecting.swap(reconnecting, Ordering::Relaxed) == reconnecting {
+            return None;
+        }
+        let state = if reconnecting {
+            DeviceState::Connecting
+        } else {
+            *self.state.lock().unwrap_or_else(|p| p.into_inner())
+        };
+        self.telemetry.set_state(state);
+        Some(state)
+    }
+
+    /// 记录 APP 上报的强度上限，返回是否发生变化
+    fn update_app_max_power(&self, max_a: u8, max_b: u8) -> bool {
+        let mut limits = self.app_max_power.lock().unwrap_or_else(|p| p.into_inner());
+        let changed = *limits != Some((max_a, max_b));
+        *limits = Some((max_a, max_b));
+        changed
+    }
+}
+
+/// 波形队列消息发送器（可移入后台任务）
+///
+/// 持有 WsClient 的克隆，不占用接收任务持有的客户端锁。
+#[derive(Clone)]
+struct PulseSender {
+    /// 设备 ID（试运行记录用）
+    device_id: String,
+    /// 发送用客户端
+    client: Option<WsClient>,
+    /// 试运行记录器
+    dry_run: Option<DryRunRecorder>,
+}
+
+impl PulseSender {
+    /// 发送波形数据（超过单条消息上限时自动拆分）
+    async fn pulse(&self, pulse: PulseData) -> Result<()> {
+        if let Some(recorder) = &self.dry_run {
+            for part in pulse.split() {
+                recorder.record(&self.device_id, DryRunPayload::Ws(part.to_message()));
+            }
+            return Ok(());
+        }
+
+        if let Some(c) = &self.client {
+            c.send_pulse(pulse)
+                .await
+                .map_err(|e| CoreError::ws("WebSocket send pulse", e))?;
+        }
+        Ok(())
+    }
+
+    /// 清空 APP 端的波形队列
+    async fn clear(&self, channel: WsChannel) -> Result<()> {
+        if let Some(recorder) = &self.dry_run {
+            let message = ClearOperation::new(channel).to_message();
+            recorder.record(&self.device_id, DryRunPayload::Ws(message));
+            return Ok(());
+        }
+
+        if let Some(c) = &self.client {
+            c.send_clear(channel)
+                .await
+                .map_err(|e| CoreError::ws("WebSocket send clear", e))?;
+        }
+        Ok(())
+    }
+}
+
+/// 通道编号转为 WebSocket 通道
+fn ws_channel(channel: u8) -> Result<WsChannel> {
+    match channel {
+        0 => Ok(WsChannel::A),
+        1 => Ok(WsChannel::B),
+        _ => Err(CoreError::InvalidParameter("Invalid channel".to_string())),
+    }
 }
 
 /// WiFi WebSocket Coyote 设备
@@ -523,31 +1882,95 @@ pub struct WsCoyoteDevice {
     base: BaseDevice,
     /// 内部状态（Arc 包装，可跨任务共享）
     inner: Arc<WsCoyoteInner>,
-    /// 心跳任务句柄
-    heartbeat_task: Option<tokio::task::JoinHandle<()>>,
     /// 接收任务句柄
     receive_task: Option<tokio::task::JoinHandle<()>>,
+    /// 波形补发任务句柄
+    pulse_task: Option<tokio::task::JoinHandle<()>>,
+    /// 发送用客户端（连接后可用）
+    pulse_client: Option<WsClient>,
+    /// 波形预发送时长
+    pulse_lookahead: Duration,
+    /// 断线重连策略
+    reconnect_policy: ReconnectPolicy,
+    /// 心跳（由 WebSocket 客户端发送）
+    heartbeat: HeartbeatConfig,
+    /// 试运行记录器（设置后不连接服务器，消息只记录不发送）
+    dry_run: Option<DryRunRecorder>,
 }
 
 impl WsCoyoteDevice {
     /// 创建新的 WiFi 设备（使用官方服务器）
     pub fn new(id: String, name: String) -> Self {
-        Self::with_server(id, name, dglab_protocol::wifi::OFFICIAL_SERVER.to_string())
+        Self::with_server(id, name, ServerAddress::official())
     }
 
     /// 创建新的 WiFi 设备（使用自定义服务器）
-    pub fn with_server(id: String, name: String, server_url: String) -> Self {
-        let base = BaseDevice::new(id, name);
+    ///
+    /// 本地上限默认为 V3 的最大强度，实际上限取本地上限与 APP 上报上限中较小者。
+    pub fn with_server(id: String, name: String, server: ServerAddress) -> Self {
+        let base = BaseDevice::with_max_power(id, name, MAX_STRENGTH);
         let inner = Arc::new(WsCoyoteInner {
             ws_client: Mutex::new(None),
-            server_url,
+            server,
+            pulse_streams: std::sync::Mutex::new([None, None]),
+            app_max_power: std::sync::Mutex::new(None),
+            app_power: std::sync::Mutex::new((0, 0)),
+            enabled: std::sync::Mutex::new([true, true]),
+            state: std::sync::Mutex::new(base.state()),
+            reconnecting: AtomicBool::new(false),
+            telemetry: base.telemetry().clone(),
         });
 
         Self {
             base,
             inner,
-            heartbeat_task: None,
             receive_task: None,
+            pulse_task: None,
+            pulse_client: None,
+            pulse_lookahead: DEFAULT_PULSE_LOOKAHEAD,
+            reconnect_policy: ReconnectPolicy::default(),
+            heartbeat: HeartbeatConfig::default(),
+            dry_run: None,
+        }
+    }
+
+    /// 创建试运行设备
+    ///
+    /// 连接时不访问服务器，强度和波形消息照常生成并交给记录器。
+    pub fn dry_run(id: String, name: String, recorder: DryRunRecorder) -> Self {
+        let mut device = Self::new(id, name);
+        device.dry_run = Some(recorder);
+        device
+    }
+
+    /// 是否为试运行设备
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run.is_some()
+    }
+
+    /// 设置断线重连策略（下次连接时生效）
+    pub fn set_reconnect_policy(&mut self, policy: ReconnectPolicy) {
+        self.reconnect_policy = policy;
+    }
+
+    /// 设置心跳间隔和超时（下次连接时生效）
+    pub fn set_heartbeat(&mut self, heartbeat: HeartbeatConfig) {
+        self.heartbeat = heartbeat;
+    }
+
+    /// 波形预发送时长
+    pub fn pulse_lookahead(&self) -> Duration {
+        self.pulse_lookahead
+    }
+
+    /// 设置波形预发送时长
+    ///
+    /// 运行时始终让 APP 队列中保留这么长的待播放波形，以抵消服务器转发延迟和抖动。
+    /// 越长越平滑，但切换波形后清空队列前的延迟也越明显。
+    pub fn set_pulse_lookahead(&mut self, lookahead: Duration) {
+        self.pulse_lookahead = lookahead;
+        for scheduler in self.inner.pulse_streams().iter_mut().flatten() {
+            scheduler.set_lookahead(lookahead);
         }
     }
 
@@ -561,6 +1984,14 @@ impl WsCoyoteDevice {
         }
     }
 
+    /// 获取服务器分配的 clientId（连接后可用）
+    pub async fn client_id(&self) -> Option<String> {
+        match &self.pulse_client {
+            Some(c) => c.client_id().await,
+            None => None,
+        }
+    }
+
     /// 检查是否已绑定到 APP
     pub async fn is_bound(&self) -> bool {
         let client = self.inner.ws_client.lock().await;
@@ -571,48 +2002,127 @@ impl WsCoyoteDevice {
         }
     }
 
+    /// 获取已绑定的 APP targetId（绑定后可用）
+    pub async fn target_id(&self) -> Option<String> {
+        match &self.pulse_client {
+            Some(c) => c.target_id().await,
+            None => None,
+        }
+    }
+
+    /// 免扫码恢复与 APP 的绑定，`target_id` 为上次绑定的 APP id
+    ///
+    /// 服务器每次连接都会分配新的 clientId，因此以新 clientId 绑定原目标；
+    /// APP 已离线或已绑定其他客户端时返回错误，调用方应改用二维码绑定。
+    pub async fn rebind(&self, target_id: &str, timeout: Duration) -> Result<String> {
+        let client = self
+            .pulse_client
+            .as_ref()
+            .ok_or(CoreError::DeviceNotConnected)?;
+        client
+            .rebind(target_id, timeout)
+            .await
+            .map_err(|e| CoreError::ws("WebSocket rebind", e))
+    }
+
     /// 获取服务器 URL
     pub fn server_url(&self) -> &str {
-        &self.inner.server_url
+        self.inner.server.as_str()
     }
 
-    /// 启动心跳任务
-    fn start_heartbeat(&mut self) {
-        let inner = self.inner.clone();
-        let event_tx = self.base.event_tx.clone();
-        let state = self.base.state();
+    /// 获取服务器地址
+    pub fn server_address(&self) -> &ServerAddress {
+        &self.inner.server
+    }
 
-        let handle = tokio::spawn(async move {
-            let mut interval = tokio::time::interval(std::time::Duration::from_secs(30));
+    /// 是否以观察端连接（见 [`ServerAddress::as_viewer`]）
+    ///
+    /// 观察端只接收绑定关系中 APP 上报的强度，强度和波形操作返回 [`CoreError::Rejected`]。
+    pub fn is_viewer(&self) -> bool {
+        self.inner.server.role() == ClientRole::Viewer
+    }
 
-            loop {
-                interval.tick().await;
+    /// 观察端拒绝输出操作
+    fn check_writable(&self) -> Result<()> {
+        if self.is_viewer() {
+            return Err(CoreError::Rejected(
+                "Viewer session is read-only".to_string(),
+            ));
+        }
+        Ok(())
+    }
 
-                if state != DeviceState::Connected && state != DeviceState::Running {
-                    break;
-                }
+    /// 波形队列消息发送器
+    fn pulse_sender(&self) -> PulseSender {
+        PulseSender {
+            device_id: self.base.id().to_string(),
+            client: self.pulse_client.clone(),
+            dry_run: self.dry_run.clone(),
+        }
+    }
+
+    /// 启动波形补发任务
+    ///
+    /// 每 100ms 按时钟检查各通道预发送的波形，APP 队列不足 lookahead 时补发。
+    fn start_pulse_task(&mut self) {
+        self.stop_pulse_task();
 
-                let client = inner.ws_client.lock().await;
-                if let Some(c) = client.as_ref() {
-                    if let Err(e) = c.send_heartbeat().await {
-                        warn!("WebSocket heartbeat failed: {}", e);
-                        let _ =
-                            event_tx.send(DeviceEvent::Error(format!("Heartbeat failed: {}", e)));
+        let inner = self.inner.clone();
+        let sender = self.pulse_sender();
+
+        let supervisor = self.base.supervisor();
+        let handle = supervisor.spawn("pulse top-up", RestartPolicy::default(), move || {
+            let inner = inner.clone();
+            let sender = sender.clone();
+            async move {
+                let mut interval = tokio::time::interval(PULSE_FRAME_DURATION);
+                interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+                loop {
+                    let _ = interval.tick().await;
+
+                    for (channel, ws) in [(0, WsChannel::A), (1, WsChannel::B)] {
+                        let pulses = match &mut inner.pulse_streams()[channel] {
+                            Some(scheduler) => scheduler.top_up(tokio::time::Instant::now()),
+                            None => continue,
+                        };
+                        if pulses.is_empty() {
+                            continue;
+                        }
+                        if let Err(e) = sender.pulse(PulseData::new(ws, pulses)).await {
+                            warn!("Failed to top up channel {} pulses: {}", channel, e);
+                        }
                     }
                 }
             }
         });
 
-        self.heartbeat_task = Some(handle);
+        self.pulse_task = Some(handle);
     }
 
-    /// 停止心跳任务
-    fn stop_heartbeat(&mut self) {
-        if let Some(handle) = self.heartbeat_task.take() {
+    /// 停止波形补发任务
+    fn stop_pulse_task(&mut self) {
+        if let Some(handle) = self.pulse_task.take() {
             handle.abort();
         }
     }
 
+    /// 通道实际强度上限：本地上限与 APP 上报上限中较小者
+    fn max_power(&self, channel: u8) -> u8 {
+        let local = match channel {
+            0 => self.base.max_power_a(),
+            _ => self.base.max_power_b(),
+        };
+        local.min(self.inner.app_max_power(channel))
+    }
+
+    /// 重置各通道调度器（APP 队列已清空或连接已断开）
+    fn reset_pulse_streams(&self) {
+        for scheduler in self.inner.pulse_streams().iter_mut().flatten() {
+            scheduler.reset();
+        }
+    }
+
     /// 启动接收任务
     fn start_receive_task(&mut self) {
         let inner = self.inner.clone();
@@ -620,25 +2130,49 @@ impl WsCoyoteDevice {
         let mut power_a = self.base.power_a();
         let mut power_b = self.base.power_b();
 
-        let handle = tokio::spawn(async move {
-            loop {
-                let mut client = inner.ws_client.lock().await;
-                let Some(c) = client.as_mut() else {
-                    break;
-                };
-
-                match c.recv_event().await {
-                    Ok(Some(event)) => {
-                        Self::handle_ws_event(event, &event_tx, &mut power_a, &mut power_b);
-                    }
-                    Ok(None) => {
-                        debug!("WebSocket connection closed");
-                        break;
-                    }
-                    Err(e) => {
-                        error!("WebSocket receive error: {}", e);
-                        let _ = event_tx.send(DeviceEvent::Error(e.to_string()));
+        let supervisor = self.base.supervisor();
+        let handle = supervisor.spawn("WebSocket receive", RestartPolicy::default(), move || {
+            let inner = inner.clone();
+            let event_tx = event_tx.clone();
+            let mut power_a = power_a;
+            let mut power_b = power_b;
+            async move {
+                loop {
+                    let mut client = inner.ws_client.lock().await;
+                    let Some(c) = client.as_mut() else {
                         break;
+                    };
+
+                    match c.recv_event().await {
+                        Ok(Some(event)) => {
+                            let raised = inner.raised_disabled_channels(&event);
+                            Self::handle_ws_event(
+                                event,
+                                &inner,
+                                &event_tx,
+                                &mut power_a,
+                                &mut power_b,
+                            );
+                            // APP 调高了禁用的通道时立即归零
+                            for channel in raised {
+                                let Ok(target) = ws_channel(channel) else {
+                                    continue;
+                                };
+                                let op = dglab_protocol::wifi::StrengthOperation::set(target, 0);
+                                if let Err(e) = c.send_strength_operation(op).await {
+                                    warn!("Failed to zero disabled channel {}: {}", channel, e);
+                                }
+                            }
+                        }
+                        Ok(None) => {
+                            debug!("WebSocket connection closed");
+                            break;
+                        }
+                        Err(e) => {
+                            error!("WebSocket receive error: {}", e);
+                            let _ = event_tx.send(DeviceEvent::Error(e.to_string()));
+                            break;
+                        }
                     }
                 }
             }
@@ -654,9 +2188,19 @@ impl WsCoyoteDevice {
         }
     }
 
+    /// 设置设备状态并同步给接收任务；新建或断开连接时结束重连状态
+    fn set_state(&mut self, state: DeviceState) {
+        *self.inner.state.lock().unwrap_or_else(|p| p.into_inner()) = state;
+        if matches!(state, DeviceState::Connecting | DeviceState::Disconnected) {
+            self.inner.reconnecting.store(false, Ordering::Relaxed);
+        }
+        self.base.set_state(state);
+    }
+
     /// 处理 WebSocket 事件
     fn handle_ws_event(
         event: dglab_protocol::wifi::WsEvent,
+        inner: &WsCoyoteInner,
         event_tx: &broadcast::Sender<DeviceEvent>,
         power_a: &mut u8,
         power_b: &mut u8,
@@ -674,15 +2218,27 @@ impl WsCoyoteDevice {
                     firmware_version: String::new(),
                     hardware_version: String::new(),
                     battery_level: 100,
+                    battery_remaining_secs: None,
                     power_a: *power_a,
                     power_b: *power_b,
-                    max_power_a: 100,
-                    max_power_b: 100,
+                    max_power_a: inner.app_max_power(0),
+                    max_power_b: inner.app_max_power(1),
+                    enabled_a: inner.channel_enabled(0),
+                    enabled_b: inner.channel_enabled(1),
                 }));
             }
             dglab_protocol::wifi::WsEvent::Strength(data) => {
-                *power_a = data.strength_a;
-                *power_b = data.strength_b;
+                // 禁用的通道始终按 0 处理（APP 侧调高时由接收任务发送归零）
+                *power_a = inner.gate_power(0, data.strength_a);
+                *power_b = inner.gate_power(1, data.strength_b);
+                *inner.app_power.lock().unwrap_or_else(|p| p.into_inner()) = (*power_a, *power_b);
+                if inner.update_app_max_power(data.max_a, data.max_b) {
+                    info!("APP max strength: A={} B={}", data.max_a, data.max_b);
+                    let _ = event_tx.send(DeviceEvent::MaxPowerChanged {
+                        max_power_a: data.max_a,
+                        max_power_b: data.max_b,
+                    });
+                }
                 let _ = event_tx.send(DeviceEvent::StatusReport {
                     power_a: *power_a,
                     power_b: *power_b,
@@ -690,6 +2246,7 @@ impl WsCoyoteDevice {
             }
             dglab_protocol::wifi::WsEvent::Feedback(button) => {
                 debug!("Feedback button pressed: {:?}", button);
+                let _ = event_tx.send(DeviceEvent::Feedback(button));
             }
             dglab_protocol::wifi::WsEvent::PeerDisconnected => {
                 info!("Peer disconnected");
@@ -711,6 +2268,40 @@ impl WsCoyoteDevice {
             dglab_protocol::wifi::WsEvent::Closed => {
                 info!("WebSocket connection closed");
             }
+            dglab_protocol::wifi::WsEvent::Reconnecting { attempt, delay } => {
+                warn!(
+                    "WebSocket disconnected, reconnecting in {:?} (attempt {})",
+                    delay, attempt
+                );
+                if let Some(state) = inner.set_reconnecting(true) {
+                    let _ = event_tx.send(DeviceEvent::StateChanged(state));
+                }
+            }
+            dglab_protocol::wifi::WsEvent::Reconnected => {
+                info!("WebSocket reconnected");
+                inner.telemetry.record_ws_reconnect();
+                // 恢复断开前的状态（已连接或运行中）
+                if let Some(state) = inner.set_reconnecting(false) {
+                    let _ = event_tx.send(DeviceEvent::StateChanged(state));
+                }
+            }
+            dglab_protocol::wifi::WsEvent::SendFailed { message, attempts } => {
+                warn!(
+                    "Failed to deliver '{}' after {} attempts",
+                    message, attempts
+                );
+                let _ = event_tx.send(DeviceEvent::Error(format!(
+                    "Failed to deliver '{}'",
+                    message
+                )));
+            }
+            dglab_protocol::wifi::WsEvent::Flushed => {
+                debug!("Outbound queue flushed");
+            }
+            // 与桥接设备一致只记录日志，连接真正断开时由重连流程通知
+            dglab_protocol::wifi::WsEvent::ServerDelay { missed } => {
+                warn!("WebSocket server missed {} heartbeat(s)", missed);
+            }
         }
     }
 
@@ -719,12 +2310,17 @@ impl WsCoyoteDevice {
         &self,
         op: dglab_protocol::wifi::StrengthOperation,
     ) -> Result<()> {
+        if let Some(recorder) = &self.dry_run {
+            recorder.record(self.base.id(), DryRunPayload::Ws(op.to_message()));
+            return Ok(());
+        }
+
         let client = self.inner.ws_client.lock().await;
         let c = client.as_ref().ok_or(CoreError::DeviceNotConnected)?;
 
         c.send_strength_operation(op)
             .await
-            .map_err(|e| CoreError::Other(format!("WebSocket send error: {}", e)))?;
+            .map_err(|e| CoreError::ws("WebSocket send", e))?;
 
         Ok(())
     }
@@ -732,6 +2328,14 @@ impl WsCoyoteDevice {
 
 #[async_trait]
 impl Device for WsCoyoteDevice {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
     fn id(&self) -> &str {
         self.base.id()
     }
@@ -741,7 +2345,11 @@ impl Device for WsCoyoteDevice {
     }
 
     fn state(&self) -> DeviceState {
-        self.base.state()
+        if self.inner.reconnecting.load(Ordering::Relaxed) {
+            DeviceState::Connecting
+        } else {
+            self.base.state()
+        }
     }
 
     fn info(&self) -> DeviceInfo {
@@ -752,37 +2360,50 @@ impl Device for WsCoyoteDevice {
             firmware_version: String::new(),
             hardware_version: String::new(),
             battery_level: 100,
-            power_a: self.base.power_a(),
-            power_b: self.base.power_b(),
-            max_power_a: 100,
-            max_power_b: 100,
+            battery_remaining_secs: None,
+            power_a: self.get_power(0),
+            power_b: self.get_power(1),
+            max_power_a: self.max_power(0),
+            max_power_b: self.max_power(1),
+            enabled_a: self.base.channel_enabled(0),
+            enabled_b: self.base.channel_enabled(1),
         }
     }
 
     async fn connect(&mut self) -> Result<()> {
-        info!("Connecting to WiFi server: {}", self.inner.server_url);
+        info!("Connecting to WiFi server: {}", self.inner.server);
 
         if self.base.state() == DeviceState::Connected {
             return Ok(());
         }
 
-        self.base.set_state(DeviceState::Connecting);
+        if self.dry_run.is_some() {
+            self.set_state(DeviceState::Connected);
+            return Ok(());
+        }
 
-        // 连接 WebSocket
-        let client = dglab_protocol::wifi::WsClient::connect(&self.inner.server_url)
-            .await
-            .map_err(|e| CoreError::Other(format!("WebSocket connect error: {}", e)))?;
+        self.set_state(DeviceState::Connecting);
 
+        // 连接 WebSocket
+        let client = WsClient::connect_with_options(
+            &self.inner.server,
+            self.reconnect_policy,
+            RateLimit::default(),
+            self.heartbeat,
+        )
+        .await
+        .map_err(|e| CoreError::ws("WebSocket connect", e))?;
+
+        self.pulse_client = Some(client.clone());
         {
             let mut ws_client = self.inner.ws_client.lock().await;
             *ws_client = Some(client);
         }
 
-        self.base.set_state(DeviceState::Connected);
+        self.set_state(DeviceState::Connected);
 
         // 启动后台任务
         self.start_receive_task();
-        self.start_heartbeat();
 
         Ok(())
     }
@@ -790,8 +2411,10 @@ impl Device for WsCoyoteDevice {
     async fn disconnect(&mut self) -> Result<()> {
         info!("Disconnecting WiFi device: {}", self.base.id());
 
-        self.stop_heartbeat();
         self.stop_receive_task();
+        self.stop_pulse_task();
+        self.reset_pulse_streams();
+        self.pulse_client = None;
 
         {
             let client = self.inner.ws_client.lock().await;
@@ -805,7 +2428,7 @@ impl Device for WsCoyoteDevice {
             *ws_client = None;
         }
 
-        self.base.set_state(DeviceState::Disconnected);
+        self.set_state(DeviceState::Disconnected);
 
         Ok(())
     }
@@ -813,12 +2436,14 @@ impl Device for WsCoyoteDevice {
     async fn start(&mut self) -> Result<()> {
         info!("Starting WiFi device output: {}", self.base.id());
 
+        self.check_writable()?;
         if self.base.state() != DeviceState::Connected {
             return Err(CoreError::DeviceNotConnected);
         }
 
-        // WiFi 模式下，start 不发送特殊指令，只是更新状态
-        self.base.set_state(DeviceState::Running);
+        // WiFi 模式下，start 不发送特殊指令，只是更新状态并开始预发送波形
+        self.set_state(DeviceState::Running);
+        self.start_pulse_task();
 
         Ok(())
     }
@@ -830,11 +2455,18 @@ impl Device for WsCoyoteDevice {
             return Ok(());
         }
 
-        // 停止时将强度归零
+        // 先将强度归零，再停止补发并清空 APP 中尚未播放的波形
+        self.stop_pulse_task();
         self.set_power(0, 0).await?;
         self.set_power(1, 0).await?;
 
-        self.base.set_state(DeviceState::Connected);
+        let sender = self.pulse_sender();
+        for channel in [WsChannel::A, WsChannel::B] {
+            sender.clear(channel).await?;
+        }
+        self.reset_pulse_streams();
+
+        self.set_state(DeviceState::Connected);
 
         Ok(())
     }
@@ -842,13 +2474,26 @@ impl Device for WsCoyoteDevice {
     async fn set_power(&mut self, channel: u8, power: u8) -> Result<()> {
         debug!("Setting WiFi channel {} power to {}", channel, power);
 
-        self.base.set_power(channel, power)?;
+        self.check_writable()?;
+        let ws_channel = ws_channel(channel)?;
 
-        let ws_channel = match channel {
-            0 => dglab_protocol::wifi::Channel::A,
-            1 => dglab_protocol::wifi::Channel::B,
-            _ => return Err(CoreError::InvalidParameter("Invalid channel".to_string())),
-        };
+        // 超过 APP 上限的强度会被 APP 忽略，发送前截断；禁用的通道始终为 0
+        let requested = power;
+        let power = self
+            .base
+            .gate_power(channel, power.min(self.inner.app_max_power(channel)));
+        self.base.set_power(channel, power)?;
+        if power < requested {
+            debug!(
+                "WiFi channel {} power {} clamped to {}",
+                channel, requested, power
+            );
+            self.base.send_event(DeviceEvent::PowerRejected {
+                channel,
+                requested,
+                actual: power,
+            });
+        }
 
         let op = dglab_protocol::wifi::StrengthOperation::set(ws_channel, power);
 
@@ -861,6 +2506,9 @@ impl Device for WsCoyoteDevice {
     }
 
     fn get_power(&self, channel: u8) -> u8 {
+        if self.is_viewer() {
+            return self.inner.app_power(channel);
+        }
         match channel {
             0 => self.base.power_a(),
             1 => self.base.power_b(),
@@ -868,15 +2516,122 @@ impl Device for WsCoyoteDevice {
         }
     }
 
+    async fn adjust_power(&mut self, channel: u8, delta: i16) -> Result<u8> {
+        debug!("Adjusting WiFi channel {} power by {}", channel, delta);
+
+        self.check_writable()?;
+        let ws_channel = ws_channel(channel)?;
+
+        // 与 V3 一致：结果限制在 0 到上限之间（禁用的通道为 0），发送实际变化量
+        let current = self.get_power(channel);
+        let limit = self.base.gate_power(channel, self.max_power(channel));
+        let power = (current as i16 + delta).clamp(0, limit as i16) as u8;
+        if power == current {
+            return Ok(power);
+        }
+        self.base.set_power(channel, power)?;
+
+        let op = if power > current {
+            dglab_protocol::wifi::StrengthOperation::increase(ws_channel, power - current)
+        } else {
+            dglab_protocol::wifi::StrengthOperation::decrease(ws_channel, current - power)
+        };
+
+        if self.base.state() == DeviceState::Connected || self.base.state() == DeviceState::Running
+        {
+            self.send_strength_operation(op).await?;
+        }
+
+        Ok(power)
+    }
+
+    async fn set_max_power(&mut self, channel: u8, max_power: u8) -> Result<()> {
+        debug!(
+            "Setting WiFi channel {} max power to {}",
+            channel, max_power
+        );
+
+        if max_power > MAX_STRENGTH {
+            return Err(CoreError::PowerOutOfRange(max_power, MAX_STRENGTH));
+        }
+
+        // WiFi 协议无法修改 APP 侧上限，仅在本地限制；先下调强度再收紧上限
+        if self.get_power(channel) > max_power {
+            self.set_power(channel, max_power).await?;
+        }
+
+        self.base.set_max_power(channel, max_power)
+    }
+
+    fn limits(&self) -> DeviceLimits {
+        self.base.limits()
+    }
+
+    async fn set_limits(&mut self, limits: DeviceLimits) -> Result<()> {
+        debug!("Setting WiFi limits: {:?}", limits);
+
+        limits.validate()?;
+
+        // WiFi 协议无法修改 APP 侧上限和平衡参数，仅在本地限制；先下调强度再收紧上限
+        for (channel, max_power) in [(0, limits.soft_limit_a), (1, limits.soft_limit_b)] {
+            if self.get_power(channel) > max_power {
+                self.set_power(channel, max_power).await?;
+            }
+        }
+
+        self.base.set_limits(limits)
+    }
+
+    fn channel_link(&self) -> Option<ChannelLink> {
+        self.base.channel_link()
+    }
+
+    async fn set_channel_link(&mut self, link: Option<ChannelLink>) -> Result<()> {
+        debug!("Setting WiFi channel link: {:?}", link);
+
+        self.base.set_channel_link(link)
+    }
+
+    fn channel_enabled(&self, channel: u8) -> bool {
+        self.base.channel_enabled(channel)
+    }
+
+    async fn set_channel_enabled(&mut self, channel: u8, enabled: bool) -> Result<()> {
+        debug!("Setting WiFi channel {} enabled: {}", channel, enabled);
+
+        self.check_writable()?;
+        let ws_channel = ws_channel(channel)?;
+        self.base.set_channel_enabled(channel, enabled)?;
+        self.inner.enabled.lock().unwrap_or_else(|p| p.into_inner())[channel as usize] = enabled;
+        if enabled {
+            return Ok(());
+        }
+
+        // 停止补发并清空 APP 队列，强度归零
+        self.inner.pulse_streams()[channel as usize] = None;
+        if self.base.state() == DeviceState::Connected || self.base.state() == DeviceState::Running
+        {
+            self.pulse_sender().clear(ws_channel).await?;
+            self.send_strength_operation(dglab_protocol::wifi::StrengthOperation::set(
+                ws_channel, 0,
+            ))
+            .await?;
+        }
+        Ok(())
+    }
+
     async fn set_waveform(&mut self, channel: u8, config: WaveformConfig) -> Result<()> {
         debug!("Setting WiFi channel {} waveform: {:?}", channel, config);
 
         // WiFi 模式通过 pulse 数据发送波形
-        let ws_channel = match channel {
-            0 => dglab_protocol::wifi::Channel::A,
-            1 => dglab_protocol::wifi::Channel::B,
-            _ => return Err(CoreError::InvalidParameter("Invalid channel".to_string())),
-        };
+        self.check_writable()?;
+        let ws_channel = ws_channel(channel)?;
+
+        // 禁用的通道不发送波形
+        if !self.base.channel_enabled(channel) {
+            debug!("WiFi channel {} disabled, waveform ignored", channel);
+            return Ok(());
+        }
 
         // 创建简单的脉冲数据
         let power_a = if channel == 0 {
@@ -889,38 +2644,69 @@ impl Device for WsCoyoteDevice {
         } else {
             self.base.power_b()
         };
-        let pulse =
-            dglab_protocol::wifi::PulseData::from_strength(ws_channel, power_a, power_b, 1000);
-
-        let client = self.inner.ws_client.lock().await;
-        if let Some(c) = client.as_ref() {
-            c.send_pulse(pulse)
-                .await
-                .map_err(|e| CoreError::Other(format!("WebSocket send pulse error: {}", e)))?;
-        }
+        let pulses = match (&config.waveform_type, &config.custom_data) {
+            // 原始帧直接以 APP 的 8 字节 HEX 格式发送
+            (WaveformType::Custom, Some(data)) if data.len() >= 8 => {
+                CoyoteDevice::waveform_config_to_frames(&config)
+                    .iter()
+                    .map(WaveformData::to_hex_string)
+                    .collect()
+            }
+            _ => PulseData::from_strength(ws_channel, power_a, power_b, 100).pulses,
+        };
 
-        Ok(())
-    }
+        // 未运行时只记录波形，start 后由补发任务开始发送
+        let mut scheduler = PulseScheduler::new(pulses, self.pulse_lookahead);
+        let initial = if self.base.state() == DeviceState::Running {
+            scheduler.top_up(tokio::time::Instant::now())
+        } else {
+            Vec::new()
+        };
+        self.inner.pulse_streams()[channel as usize] = Some(scheduler);
 
-    async fn heartbeat(&mut self) -> Result<()> {
-        let client = self.inner.ws_client.lock().await;
-        if let Some(c) = client.as_ref() {
-            c.send_heartbeat()
-                .await
-                .map_err(|e| CoreError::Other(format!("Heartbeat error: {}", e)))?;
+        if initial.is_empty() {
+            return Ok(());
         }
+
+        // 切换波形时先清空 APP 队列，避免旧波形播放完才生效
+        let sender = self.pulse_sender();
+        sender.clear(ws_channel).await?;
+        sender.pulse(PulseData::new(ws_channel, initial)).await
+    }
+
+    async fn heartbeat(&mut self) -> Result<()> {
+        // WsClient 的连接任务已按 HeartbeatConfig 定时发送心跳，这里再发会让服务器收到两次
         Ok(())
     }
 
+    fn wifi_binding(&self) -> Option<&dyn WifiBinding> {
+        Some(self)
+    }
+
     fn subscribe_events(&self) -> broadcast::Receiver<DeviceEvent> {
         self.base.subscribe_events()
     }
+
+    fn send_event(&self, event: DeviceEvent) {
+        self.base.send_event(event);
+    }
+}
+
+#[async_trait]
+impl WifiBinding for WsCoyoteDevice {
+    async fn qr_url(&self) -> Option<String> {
+        WsCoyoteDevice::qr_url(self).await
+    }
+
+    async fn is_bound(&self) -> bool {
+        WsCoyoteDevice::is_bound(self).await
+    }
 }
 
 impl Drop for WsCoyoteDevice {
     fn drop(&mut self) {
-        self.stop_heartbeat();
         self.stop_receive_task();
+        self.stop_pulse_task();
     }
 }
 
@@ -995,16 +2781,423 @@ mod tests {
         assert_eq!(cmd.strength_b, 60);
     }
 
+    #[tokio::test]
+    async fn test_v3_output_state_adjust_strength() {
+        let state = V3OutputState::new();
+        state.target_strength_a.store(50, Ordering::Relaxed);
+
+        // 两次调整合并为一次增减，结果限制在上限内
+        assert_eq!(state.adjust_strength(0, 5, 60).unwrap(), 55);
+        assert_eq!(state.adjust_strength(0, 10, 60).unwrap(), 60);
+        let cmd = state.build_b0().await;
+        assert_ne!(cmd.sequence, 0);
+        assert_eq!(cmd.strength_mode.channel_a, ChannelStrengthMode::Increase);
+        assert_eq!(cmd.strength_a, 10);
+        assert_eq!(cmd.strength_mode.channel_b, ChannelStrengthMode::NoChange);
+
+        assert_eq!(state.adjust_strength(1, -5, 60).unwrap(), 0);
+        assert_eq!(state.build_b0().await.sequence, 0);
+
+        assert_eq!(state.adjust_strength(0, -20, 60).unwrap(), 40);
+        let cmd = state.build_b0().await;
+        assert_eq!(cmd.strength_mode.channel_a, ChannelStrengthMode::Decrease);
+        assert_eq!(cmd.strength_a, 20);
+
+        // 有绝对值变更待发送时以绝对值发送调整后的强度
+        state.pending_strength_a.store(true, Ordering::Relaxed);
+        assert_eq!(state.adjust_strength(0, 3, 60).unwrap(), 43);
+        let cmd = state.build_b0().await;
+        assert_eq!(cmd.strength_mode.channel_a, ChannelStrengthMode::Absolute);
+        assert_eq!(cmd.strength_a, 43);
+
+        assert!(state.adjust_strength(2, 1, 60).is_err());
+    }
+
     #[tokio::test]
     async fn test_v3_output_state_build_b0_with_waveform() {
         let state = V3OutputState::new();
         let waveform = WaveformData::uniform(50, 80);
-        *state.waveform_a.lock().await = waveform;
+        *state.waveform_a.lock().await = FrameCycle::single(waveform);
 
         let cmd = state.build_b0().await;
         assert_eq!(cmd.waveform_a, waveform);
     }
 
+    #[tokio::test]
+    async fn test_v3_output_state_cycles_frames() {
+        let state = V3OutputState::new();
+        let first = WaveformData::uniform(10, 20);
+        let second = WaveformData::uniform(30, 40);
+        *state.waveform_a.lock().await = FrameCycle::new(vec![first, second]);
+
+        assert_eq!(state.build_b0().await.waveform_a, first);
+        assert_eq!(state.build_b0().await.waveform_a, second);
+        // 播放到末尾后从头循环
+        assert_eq!(state.build_b0().await.waveform_a, first);
+        // 未设置波形的通道保持静默
+        assert_eq!(state.build_b0().await.waveform_b, WaveformData::silent());
+    }
+
+    #[tokio::test]
+    async fn test_v3_output_state_pulse_queue() {
+        let state = V3OutputState::new();
+        let looped = WaveformData::uniform(10, 20);
+        *state.waveform_a.lock().await = FrameCycle::single(looped);
+
+        let queued = [WaveformData::uniform(30, 40), WaveformData::uniform(50, 60)];
+        assert_eq!(state.queue_a.lock().await.push(queued), 0);
+
+        // 队列优先播放，播放完后回到通道波形
+        assert_eq!(state.build_b0().await.waveform_a, queued[0]);
+        assert_eq!(state.build_b0().await.waveform_a, queued[1]);
+        assert_eq!(state.build_b0().await.waveform_a, looped);
+
+        // 超出容量时丢弃最早的帧
+        let mut queue = PulseQueue::default();
+        let frames =
+            (0..PULSE_QUEUE_CAPACITY + 3).map(|i| WaveformData::uniform(10, (i % 100) as u8));
+        assert_eq!(queue.push(frames), 3);
+        assert_eq!(queue.len(), PULSE_QUEUE_CAPACITY);
+        assert_eq!(queue.pop(), Some(WaveformData::uniform(10, 3)));
+
+        // 清空后立即回到通道波形
+        let _ = state
+            .queue_b
+            .lock()
+            .await
+            .push([WaveformData::uniform(10, 90)]);
+        state.queue(1).unwrap().lock().await.clear();
+        assert_eq!(state.build_b0().await.waveform_b, WaveformData::silent());
+        assert!(state.queue(2).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_v3_output_state_skips_missed_frames() {
+        let state = V3OutputState::new();
+        let frames: Vec<_> = (0..4).map(|i| WaveformData::uniform(10, i * 10)).collect();
+        *state.waveform_a.lock().await = FrameCycle::new(frames.clone());
+        let queued = [WaveformData::uniform(50, 50), WaveformData::uniform(60, 60)];
+        let _ = state.queue_b.lock().await.push(queued);
+
+        // 错过 2 个节拍：A 通道波形前进 2 帧，B 通道队列跳过 2 帧后回到通道波形
+        let start = tokio::time::Instant::now();
+        state.reset_clock(Duration::from_millis(100));
+        state.tick(start).await;
+        state.tick(start + Duration::from_millis(300)).await;
+        assert_eq!(state.tick_stats().missed, 2);
+
+        let b0 = state.build_b0().await;
+        assert_eq!(b0.waveform_a, frames[2]);
+        assert_eq!(b0.waveform_b, WaveformData::silent());
+    }
+
+    #[tokio::test]
+    async fn test_v3_output_state_channel_enabled() {
+        let state = V3OutputState::new();
+        let waveform = WaveformData::uniform(10, 80);
+        *state.waveform_b.lock().await = FrameCycle::single(waveform);
+        state.target_strength_b.store(40, Ordering::Relaxed);
+        let _ = state.queue_b.lock().await.push([waveform]);
+
+        // 禁用时强度以绝对值归零，队列清空，波形静默
+        state.set_channel_enabled(1, false).await.unwrap();
+        assert!(!state.channel_enabled(1));
+        let cmd = state.build_b0().await;
+        assert_eq!(cmd.strength_mode.channel_b, ChannelStrengthMode::Absolute);
+        assert_eq!(cmd.strength_b, 0);
+        assert_eq!(cmd.waveform_b, WaveformData::silent());
+        assert_eq!(state.adjust_strength(1, 10, 100).unwrap(), 0);
+
+        // 重新启用后恢复通道波形
+        state.set_channel_enabled(1, true).await.unwrap();
+        assert_eq!(state.build_b0().await.waveform_b, waveform);
+        assert_eq!(state.adjust_strength(1, 10, 100).unwrap(), 10);
+        assert!(state.set_channel_enabled(2, false).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_v3_output_state_reset() {
+        let state = V3OutputState::new();
+        state.target_strength_a.store(50, Ordering::Relaxed);
+        state.target_strength_b.store(70, Ordering::Relaxed);
+        state.pending_strength_a.store(true, Ordering::Relaxed);
+        *state.waveform_b.lock().await = FrameCycle::single(WaveformData::uniform(10, 80));
+        let _ = state.build_b0().await;
+        state.pending_strength_b.store(true, Ordering::Relaxed);
+
+        state.reset().await;
+
+        assert_eq!(state.target_strength_a.load(Ordering::Relaxed), 0);
+        assert_eq!(state.target_strength_b.load(Ordering::Relaxed), 0);
+        assert!(state.outstanding.lock().await.is_empty());
+        // 没有遗留的强度变更，波形静默
+        let cmd = state.build_b0().await;
+        assert_eq!(cmd.strength_mode, StrengthMode::both_no_change());
+        assert_eq!(cmd.waveform_a, WaveformData::silent());
+        assert_eq!(cmd.waveform_b, WaveformData::silent());
+    }
+
+    #[tokio::test]
+    async fn test_v3_output_state_tracks_outstanding_sequence() {
+        let state = V3OutputState::new();
+        state.target_strength_a.store(50, Ordering::Relaxed);
+        state.pending_strength_a.store(true, Ordering::Relaxed);
+
+        let cmd = state.build_b0().await;
+        let outstanding = state.outstanding.lock().await;
+        assert_eq!(
+            outstanding.back(),
+            Some(&(
+                cmd.sequence,
+                PendingStrength {
+                    strength_a: Some(50),
+                    strength_b: None,
+                }
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_v3_output_state_reconcile_b1_expires_lost_responses() {
+        let state = V3OutputState::new();
+        state.target_strength_a.store(20, Ordering::Relaxed);
+        state.pending_strength_a.store(true, Ordering::Relaxed);
+        let lost = state.build_b0().await;
+        state.target_strength_a.store(30, Ordering::Relaxed);
+        state.pending_strength_a.store(true, Ordering::Relaxed);
+        let cmd = state.build_b0().await;
+        assert_ne!(lost.sequence, cmd.sequence);
+
+        // 第一个请求的反馈丢失，收到第二个的反馈后不再视为在途
+        let rejected = state
+            .reconcile_b1(&B1Response {
+                sequence: cmd.sequence,
+                strength_a: 30,
+                strength_b: 0,
+            })
+            .await;
+        assert!(rejected.is_empty());
+        assert!(state.outstanding.lock().await.is_empty());
+
+        // 之后设备端的主动变更可以校正目标强度
+        let _ = state
+            .reconcile_b1(&B1Response {
+                sequence: 0,
+                strength_a: 25,
+                strength_b: 0,
+            })
+            .await;
+        assert_eq!(state.target_strength_a.load(Ordering::Relaxed), 25);
+    }
+
+    #[tokio::test]
+    async fn test_v3_output_state_reconcile_b1_accepted() {
+        let state = V3OutputState::new();
+        state.target_strength_a.store(50, Ordering::Relaxed);
+        state.pending_strength_a.store(true, Ordering::Relaxed);
+        let cmd = state.build_b0().await;
+
+        let rejected = state
+            .reconcile_b1(&B1Response {
+                sequence: cmd.sequence,
+                strength_a: 50,
+                strength_b: 0,
+            })
+            .await;
+
+        assert!(rejected.is_empty());
+        assert!(state.outstanding.lock().await.is_empty());
+        assert_eq!(state.target_strength_a.load(Ordering::Relaxed), 50);
+    }
+
+    #[tokio::test]
+    async fn test_v3_output_state_reconcile_b1_clamped() {
+        let state = V3OutputState::new();
+        state.target_strength_a.store(150, Ordering::Relaxed);
+        state.pending_strength_a.store(true, Ordering::Relaxed);
+        let cmd = state.build_b0().await;
+
+        // 设备按软上限截断到 100
+        let rejected = state
+            .reconcile_b1(&B1Response {
+                sequence: cmd.sequence,
+                strength_a: 100,
+                strength_b: 0,
+            })
+            .await;
+
+        assert_eq!(
+            rejected,
+            vec![RejectedStrength {
+                channel: 0,
+                requested: 150,
+                actual: 100,
+            }]
+        );
+        assert_eq!(state.target_strength_a.load(Ordering::Relaxed), 100);
+    }
+
+    #[tokio::test]
+    async fn test_v3_output_state_reconcile_b1_keeps_pending_target() {
+        let state = V3OutputState::new();
+        state.target_strength_b.store(30, Ordering::Relaxed);
+        state.pending_strength_b.store(true, Ordering::Relaxed);
+
+        // 设备端主动变更（序列号 0），但本地仍有待发送的请求
+        let rejected = state
+            .reconcile_b1(&B1Response {
+                sequence: 0,
+                strength_a: 10,
+                strength_b: 20,
+            })
+            .await;
+
+        assert!(rejected.is_empty());
+        assert_eq!(state.target_strength_a.load(Ordering::Relaxed), 10);
+        assert_eq!(state.target_strength_b.load(Ordering::Relaxed), 30);
+    }
+
+    #[tokio::test]
+    async fn test_v3_output_state_probe_latency() {
+        let state = V3OutputState::new();
+        assert_eq!(state.latency(), None);
+
+        // 强度不变时探测以 A 通道 +0 携带序列号
+        state.request_probe();
+        let cmd = state.build_b0().await;
+        assert_ne!(cmd.sequence, 0);
+        assert_eq!(cmd.strength_mode.channel_a, ChannelStrengthMode::Increase);
+        assert_eq!(cmd.strength_a, 0);
+        assert_eq!(state.build_b0().await.sequence, 0);
+
+        let rejected = state
+            .reconcile_b1(&B1Response {
+                sequence: cmd.sequence,
+                strength_a: 0,
+                strength_b: 0,
+            })
+            .await;
+        assert!(rejected.is_empty());
+        let stats = state.latency().unwrap();
+        assert_eq!(stats.samples, 1);
+        assert_eq!(stats.jitter, Duration::ZERO);
+        assert_eq!(state.telemetry.b1_latency().count(), 1);
+
+        // 重复的 B1 不计入
+        let _ = state
+            .reconcile_b1(&B1Response {
+                sequence: cmd.sequence,
+                strength_a: 0,
+                strength_b: 0,
+            })
+            .await;
+        assert_eq!(state.latency().unwrap().samples, 1);
+    }
+
+    #[test]
+    fn test_latency_tracker_stats() {
+        let mut tracker = LatencyTracker::default();
+        let start = tokio::time::Instant::now();
+        for (seq, ms) in [(1u8, 20u64), (2, 40), (3, 30)] {
+            tracker.sent(seq, start);
+            let _ = tracker.received(seq, start + Duration::from_millis(ms));
+        }
+        assert_eq!(tracker.received(9, start), None);
+
+        let stats = tracker.stats().unwrap();
+        assert_eq!(stats.samples, 3);
+        assert_eq!(stats.last, Duration::from_millis(30));
+        assert_eq!(stats.min, Duration::from_millis(20));
+        assert_eq!(stats.mean, Duration::from_millis(30));
+        assert_eq!(stats.max, Duration::from_millis(40));
+        assert_eq!(stats.jitter, Duration::from_millis(15));
+
+        // 窗口只保留最近的样本，累计数不受影响
+        for seq in 0..LATENCY_WINDOW as u8 {
+            tracker.sent(seq, start);
+            let _ = tracker.received(seq, start + Duration::from_millis(10));
+        }
+        let stats = tracker.stats().unwrap();
+        assert_eq!(stats.samples, 3 + LATENCY_WINDOW as u64);
+        assert_eq!(stats.max, Duration::from_millis(10));
+    }
+
+    // === LinkQualityTracker 测试 ===
+
+    #[test]
+    fn test_link_quality_tracker_transitions() {
+        let mut tracker = LinkQualityTracker::default();
+        assert_eq!(tracker.update(-60, Some(-80)), None);
+        assert_eq!(tracker.update(-85, Some(-80)), Some(true));
+        // 持续弱信号不重复告警
+        assert_eq!(tracker.update(-90, Some(-80)), None);
+        // 回差范围内不视为恢复
+        assert_eq!(tracker.update(-78, Some(-80)), None);
+        assert_eq!(tracker.update(-70, Some(-80)), Some(false));
+        assert_eq!(tracker.update(-82, Some(-80)), Some(true));
+    }
+
+    #[test]
+    fn test_link_quality_tracker_disabled() {
+        let mut tracker = LinkQualityTracker::default();
+        assert_eq!(tracker.update(-100, None), None);
+
+        // 关闭告警时清除弱信号状态
+        assert_eq!(tracker.update(-100, Some(-80)), Some(true));
+        assert_eq!(tracker.update(-100, None), Some(false));
+    }
+
+    #[test]
+    fn test_coyote_set_link_monitor() {
+        let mut dev = CoyoteDevice::new("dev-1".to_string(), "Test".to_string());
+        assert_eq!(dev.link_monitor(), LinkMonitorConfig::default());
+
+        let config = LinkMonitorConfig {
+            interval: Duration::from_secs(10),
+            weak_threshold: None,
+        };
+        dev.set_link_monitor(config);
+        assert_eq!(dev.link_monitor(), config);
+        // 未连接时不启动轮询
+        assert!(dev.link_task.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_coyote_set_output_interval() {
+        let mut dev = CoyoteDevice::new("dev-1".to_string(), "Test".to_string());
+        assert_eq!(dev.output_interval(), DEFAULT_B0_INTERVAL);
+
+        dev.set_output_interval(Duration::from_millis(50)).unwrap();
+        assert_eq!(dev.output_interval(), Duration::from_millis(50));
+        assert!(matches!(
+            dev.set_output_interval(Duration::from_millis(20)),
+            Err(CoreError::InvalidParameter(_))
+        ));
+        assert_eq!(dev.output_interval(), Duration::from_millis(50));
+        assert_eq!(dev.output_tick_stats().missed, 0);
+    }
+
+    #[test]
+    fn test_coyote_from_config_output_interval() {
+        let mut config = DeviceConfig {
+            id: "dev-1".to_string(),
+            name: "Test".to_string(),
+            connection_type: "ble".to_string(),
+            address: None,
+            auto_reconnect: false,
+            safety_limit: None,
+            output_interval_ms: Some(50),
+        };
+        let dev = CoyoteDevice::from_config(&config).unwrap();
+        assert_eq!(dev.output_interval(), Duration::from_millis(50));
+
+        config.output_interval_ms = Some(500);
+        assert!(matches!(
+            CoyoteDevice::from_config(&config),
+            Err(CoreError::InvalidParameter(_))
+        ));
+    }
+
     // === CoyoteDevice 测试 ===
 
     #[test]
@@ -1025,6 +3218,16 @@ mod tests {
         assert_eq!(info.device_type, "Coyote V3");
         assert_eq!(info.max_power_a, MAX_STRENGTH);
         assert_eq!(info.max_power_b, MAX_STRENGTH);
+        assert!(info.firmware_version.is_empty());
+        assert_eq!(info.firmware_supported(), None);
+    }
+
+    #[test]
+    fn test_firmware_warning() {
+        assert_eq!(firmware_warning("1.0.0"), None);
+        assert_eq!(firmware_warning("V2.3"), None);
+        assert!(firmware_warning("0.8.1").unwrap().contains("minimum 1.0.0"));
+        assert!(firmware_warning("abc").unwrap().contains("Unrecognized"));
     }
 
     #[tokio::test]
@@ -1037,6 +3240,100 @@ mod tests {
         assert_eq!(dev.get_power(1), 150);
     }
 
+    #[tokio::test]
+    async fn test_coyote_power_events_match_get_power() {
+        let mut dev = CoyoteDevice::new("dev-1".to_string(), "Test".to_string());
+        let mut events = dev.subscribe_events();
+
+        // 超过 100 的强度同样通知订阅方
+        dev.set_power(0, 150).await.unwrap();
+        assert!(matches!(
+            events.try_recv(),
+            Ok(DeviceEvent::PowerChanged {
+                channel: 0,
+                power: 150
+            })
+        ));
+        assert_eq!(dev.base.power_a(), dev.get_power(0));
+
+        dev.set_max_power(0, 120).await.unwrap();
+        assert_eq!(dev.get_power(0), 120);
+        assert_eq!(dev.base.power_a(), 120);
+        assert_eq!(dev.base.max_power_a(), 120);
+
+        // 设备反馈校正目标强度时发送强度事件
+        while events.try_recv().is_ok() {}
+        let cmd = dev.output_state.build_b0().await;
+        let tx = dev.base.event_tx.clone();
+        CoyoteDevice::handle_b1_response(
+            &dev.output_state,
+            &B1Response {
+                sequence: cmd.sequence,
+                strength_a: 90,
+                strength_b: 0,
+            },
+            &tx,
+        )
+        .await;
+        assert_eq!(dev.get_power(0), 90);
+        assert_eq!(dev.base.telemetry().power(0), 90);
+        assert!(matches!(
+            events.try_recv(),
+            Ok(DeviceEvent::PowerChanged {
+                channel: 0,
+                power: 90
+            })
+        ));
+
+        // 放宽上限时不按旧的目标强度发送事件
+        dev.set_max_power(0, 110).await.unwrap();
+        assert_eq!(dev.base.power_a(), 90);
+        assert!(events.try_recv().is_err());
+
+        dev.shutdown().await.unwrap();
+        assert_eq!(dev.base.power_a(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_coyote_shutdown_stops_loop_and_resets() {
+        let mut dev = CoyoteDevice::new("dev-1".to_string(), "Test".to_string());
+        dev.set_power(0, 80).await.unwrap();
+        dev.set_power(1, 40).await.unwrap();
+        dev.base.set_state(DeviceState::Running);
+
+        // 用挂起任务代替输出循环，检查 shutdown 先中止循环
+        let handle = tokio::spawn(std::future::pending::<()>());
+        let abort = handle.abort_handle();
+        dev.output_task = Some(handle);
+
+        dev.shutdown().await.unwrap();
+        tokio::task::yield_now().await;
+
+        assert!(abort.is_finished());
+        assert!(dev.output_task.is_none());
+        assert_eq!(dev.state(), DeviceState::Connected);
+        assert_eq!(dev.get_power(0), 0);
+        assert_eq!(dev.get_power(1), 0);
+        // 重新启动时不会恢复旧强度
+        let cmd = dev.output_state.build_b0().await;
+        assert_eq!(cmd.strength_mode, StrengthMode::both_no_change());
+    }
+
+    #[tokio::test]
+    async fn test_coyote_stop_shuts_down() {
+        let mut dev = CoyoteDevice::new("dev-1".to_string(), "Test".to_string());
+        dev.set_power(0, 60).await.unwrap();
+        dev.base.set_state(DeviceState::Running);
+
+        dev.stop().await.unwrap();
+        assert_eq!(dev.state(), DeviceState::Connected);
+        assert_eq!(dev.get_power(0), 0);
+        assert!(!dev.output_state.pending_strength_a.load(Ordering::Relaxed));
+
+        dev.disconnect().await.unwrap();
+        assert_eq!(dev.state(), DeviceState::Disconnected);
+    }
+
     #[tokio::test]
     async fn test_coyote_set_power_triggers_pending() {
         let mut dev = CoyoteDevice::new("dev-1".to_string(), "Test".to_string());
@@ -1061,15 +3358,80 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_coyote_set_max_power() {
+        let mut dev = CoyoteDevice::new("dev-1".to_string(), "Test".to_string());
+        dev.set_power(0, 120).await.unwrap();
+
+        dev.set_max_power(0, 80).await.unwrap();
+        assert_eq!(dev.bf_config.soft_limit_a, 80);
+        assert_eq!(dev.info().max_power_a, 80);
+        assert_eq!(dev.info().max_power_b, MAX_STRENGTH);
+        // 超出新上限的强度被下调
+        assert_eq!(dev.get_power(0), 80);
+        assert!(dev.set_power(0, 81).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_coyote_set_limits() {
+        let mut dev = CoyoteDevice::new("dev-1".to_string(), "Test".to_string());
+        dev.set_power(1, 150).await.unwrap();
+
+        let limits = DeviceLimits::new(120, 100, 160, 30);
+        dev.set_limits(limits).await.unwrap();
+
+        assert_eq!(dev.bf_config.soft_limit_a, 120);
+        assert_eq!(dev.bf_config.soft_limit_b, 100);
+        assert_eq!(dev.bf_config.freq_balance_a, 160);
+        assert_eq!(dev.bf_config.freq_balance_b, 160);
+        assert_eq!(dev.bf_config.intensity_balance_a, 30);
+        assert_eq!(dev.bf_config.intensity_balance_b, 30);
+        assert_eq!(dev.limits(), limits);
+        // 超出新上限的强度被下调
+        assert_eq!(dev.get_power(1), 100);
+
+        assert!(dev
+            .set_limits(DeviceLimits::new(100, 201, 0, 0))
+            .await
+            .is_err());
+        assert_eq!(dev.limits(), limits);
+    }
+
+    #[tokio::test]
+    async fn test_coyote_set_max_power_out_of_range() {
+        let mut dev = CoyoteDevice::new("dev-1".to_string(), "Test".to_string());
+        assert!(dev.set_max_power(0, MAX_STRENGTH + 1).await.is_err());
+        assert!(dev.set_max_power(2, 50).await.is_err());
+    }
+
     #[tokio::test]
     async fn test_coyote_set_waveform() {
         let mut dev = CoyoteDevice::new("dev-1".to_string(), "Test".to_string());
         let config = WaveformConfig::default();
         dev.set_waveform(0, config).await.unwrap();
 
-        let waveform = *dev.output_state.waveform_a.lock().await;
+        let waveform = dev.output_state.waveform_a.lock().await.clone();
         // Continuous + default freq 100 → compress_frequency(100) = 100
-        assert_eq!(waveform, WaveformData::uniform(100, 50));
+        assert_eq!(waveform, FrameCycle::single(WaveformData::uniform(100, 50)));
+    }
+
+    #[tokio::test]
+    async fn test_coyote_set_waveform_frames() {
+        let mut dev = CoyoteDevice::new("dev-1".to_string(), "Test".to_string());
+        let config = WaveformConfig {
+            waveform_type: WaveformType::Custom,
+            custom_data: Some(vec![
+                10, 10, 10, 10, 20, 20, 20, 20, 30, 30, 30, 30, 40, 40, 40, 40, 99,
+            ]),
+            ..Default::default()
+        };
+        dev.set_waveform(1, config).await.unwrap();
+
+        let waveform = dev.output_state.waveform_b.lock().await.clone();
+        assert_eq!(
+            waveform.frames,
+            vec![WaveformData::uniform(10, 20), WaveformData::uniform(30, 40)]
+        );
     }
 
     #[tokio::test]
@@ -1086,6 +3448,55 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_coyote_custom_transport() {
+        use dglab_protocol::transport::MemoryTransport;
+
+        async fn recv(peer: &MemoryTransport) -> Vec<u8> {
+            tokio::time::timeout(Duration::from_secs(2), peer.recv_frame())
+                .await
+                .unwrap()
+                .unwrap()
+        }
+
+        let (link, peer) = MemoryTransport::pair();
+        let mut dev = CoyoteDevice::new("dev-1".to_string(), "Test".to_string());
+        dev.set_transport(Arc::new(link));
+        let mut events = dev.subscribe_events();
+
+        // 不经过 BLE 管理器连接，BF 配置和 B0 帧经链路发送
+        dev.connect().await.unwrap();
+        assert!(dev.is_ble_connected().await);
+        assert_eq!(
+            recv(&peer).await,
+            BFCommand::default_config().encode().to_vec()
+        );
+        assert!(dev.link_params().is_none());
+
+        dev.set_power(0, 30).await.unwrap();
+        dev.start().await.unwrap();
+        let b0 = B0Command::decode(&recv(&peer).await).unwrap();
+        assert_eq!(b0.strength_a, 30);
+
+        // 链路上的 B1 反馈照常处理
+        let b1 = B1Response {
+            sequence: 0,
+            strength_a: 12,
+            strength_b: 7,
+        };
+        peer.send_frame(&b1.encode()).await.unwrap();
+        loop {
+            if let DeviceEvent::StatusReport { power_a, power_b } = events.recv().await.unwrap() {
+                assert_eq!((power_a, power_b), (12, 7));
+                break;
+            }
+        }
+
+        dev.disconnect().await.unwrap();
+        assert!(!dev.is_ble_connected().await);
+        assert_eq!(peer.link_state().await, LinkState::Disconnected);
+    }
+
     #[test]
     fn test_coyote_get_power_invalid_channel() {
         let dev = CoyoteDevice::new("dev-1".to_string(), "Test".to_string());
@@ -1169,7 +3580,7 @@ mod tests {
         let dev = WsCoyoteDevice::with_server(
             "ws-2".to_string(),
             "Custom Server".to_string(),
-            "ws://localhost:1234".to_string(),
+            ServerAddress::parse("localhost:1234").unwrap(),
         );
         assert_eq!(dev.server_url(), "ws://localhost:1234");
     }
@@ -1181,6 +3592,256 @@ mod tests {
         assert_eq!(info.device_type, "Coyote-WiFi");
         assert_eq!(info.power_a, 0);
         assert_eq!(info.power_b, 0);
+        assert_eq!(info.max_power_a, MAX_STRENGTH);
+        assert_eq!(info.max_power_b, MAX_STRENGTH);
+    }
+
+    #[tokio::test]
+    async fn test_ws_coyote_reconnect_state() {
+        let mut dev = WsCoyoteDevice::new("ws-1".to_string(), "WiFi".to_string());
+        dev.set_state(DeviceState::Running);
+        let mut events = dev.subscribe_events();
+        let (mut power_a, mut power_b) = (0, 0);
+        let mut handle = |event| {
+            WsCoyoteDevice::handle_ws_event(
+                event,
+                &dev.inner,
+                &dev.base.event_tx,
+                &mut power_a,
+                &mut power_b,
+            )
+        };
+
+        // 多次重连尝试只通知一次
+        for attempt in 1..=2 {
+            handle(dglab_protocol::wifi::WsEvent::Reconnecting {
+                attempt,
+                delay: Duration::from_secs(1),
+            });
+        }
+        handle(dglab_protocol::wifi::WsEvent::Reconnected);
+        assert!(matches!(
+            events.try_recv(),
+            Ok(DeviceEvent::StateChanged(DeviceState::Connecting))
+        ));
+        assert!(matches!(
+            events.try_recv(),
+            Ok(DeviceEvent::StateChanged(DeviceState::Running))
+        ));
+        assert!(events.try_recv().is_err());
+        assert_eq!(dev.state(), DeviceState::Running);
+
+        handle(dglab_protocol::wifi::WsEvent::Reconnecting {
+            attempt: 1,
+            delay: Duration::from_secs(1),
+        });
+        assert_eq!(dev.state(), DeviceState::Connecting);
+        dev.set_state(DeviceState::Disconnected);
+        assert_eq!(dev.state(), DeviceState::Disconnected);
+    }
+
+    #[tokio::test]
+    async fn test_ws_coyote_app_max_power() {
+        let mut dev = WsCoyoteDevice::new("ws-1".to_string(), "WiFi".to_string());
+        let mut events = dev.subscribe_events();
+        let strength = |max_a, max_b| {
+            dglab_protocol::wifi::WsEvent::Strength(dglab_protocol::wifi::StrengthData {
+                strength_a: 0,
+                strength_b: 0,
+                max_a,
+                max_b,
+            })
+        };
+        let (mut power_a, mut power_b) = (0, 0);
+
+        WsCoyoteDevice::handle_ws_event(
+            strength(50, 80),
+            &dev.inner,
+            &dev.base.event_tx,
+            &mut power_a,
+            &mut power_b,
+        );
+        assert!(matches!(
+            events.try_recv(),
+            Ok(DeviceEvent::MaxPowerChanged {
+                max_power_a: 50,
+                max_power_b: 80
+            })
+        ));
+        assert!(events.try_recv().is_err());
+        assert_eq!(dev.info().max_power_a, 50);
+        assert_eq!(dev.info().max_power_b, 80);
+
+        // 上限未变化时不重复通知
+        WsCoyoteDevice::handle_ws_event(
+            strength(50, 80),
+            &dev.inner,
+            &dev.base.event_tx,
+            &mut power_a,
+            &mut power_b,
+        );
+        assert!(events.try_recv().is_err());
+
+        // 超过 APP 上限的强度被截断
+        dev.set_power(0, 70).await.unwrap();
+        assert_eq!(dev.get_power(0), 50);
+        assert!(matches!(
+            events.try_recv(),
+            Ok(DeviceEvent::PowerChanged {
+                channel: 0,
+                power: 50
+            })
+        ));
+        assert!(matches!(
+            events.try_recv(),
+            Ok(DeviceEvent::PowerRejected {
+                channel: 0,
+                requested: 70,
+                actual: 50
+            })
+        ));
+
+        // 本地上限更低时取本地上限
+        dev.set_max_power(1, 30).await.unwrap();
+        assert_eq!(dev.info().max_power_b, 30);
+    }
+
+    #[tokio::test]
+    async fn test_ws_coyote_heartbeat_once_per_interval() {
+        use dglab_protocol::wifi::{MessageType, WsMessage};
+        use futures::{SinkExt, StreamExt};
+        use tokio_tungstenite::tungstenite::Message;
+
+        // 分配 clientId、回复并统计心跳的服务器
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let heartbeats = Arc::new(AtomicU8::new(0));
+        let counted = heartbeats.clone();
+        let _server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            let text = |msg: WsMessage| Message::Text(serde_json::to_string(&msg).unwrap());
+            let hello = WsMessage::new(MessageType::Bind, "ctrl", "", "targetId");
+            ws.send(text(hello)).await.unwrap();
+            while let Some(Ok(Message::Text(msg))) = ws.next().await {
+                let msg: WsMessage = serde_json::from_str(&msg).unwrap();
+                if msg.is_heartbeat() {
+                    let _ = counted.fetch_add(1, Ordering::Relaxed);
+                    let reply = WsMessage::new(MessageType::Heartbeat, "ctrl", "", "200");
+                    ws.send(text(reply)).await.unwrap();
+                }
+            }
+        });
+
+        let interval = Duration::from_millis(250);
+        let mut dev = WsCoyoteDevice::with_server(
+            "ws-1".to_string(),
+            "WiFi".to_string(),
+            ServerAddress::parse(&url).unwrap(),
+        );
+        dev.set_heartbeat(HeartbeatConfig {
+            interval,
+            timeout: Duration::from_secs(2),
+        });
+        dev.connect().await.unwrap();
+
+        // 与 GUI 运行时一样按心跳间隔调用 heartbeat，服务器每个间隔只应收到一次心跳
+        for _ in 0..4 {
+            tokio::time::sleep(interval).await;
+            dev.heartbeat().await.unwrap();
+        }
+        tokio::time::sleep(interval / 2).await;
+        let count = heartbeats.load(Ordering::Relaxed);
+        assert!(
+            (3..=4).contains(&count),
+            "{} heartbeats in 4 intervals",
+            count
+        );
+        dev.disconnect().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_ws_coyote_viewer_is_read_only() {
+        let server = ServerAddress::parse("localhost:9999")
+            .unwrap()
+            .as_viewer(None);
+        let mut dev = WsCoyoteDevice::with_server("ws-1".to_string(), "WiFi".to_string(), server);
+        assert!(dev.is_viewer());
+
+        let (mut power_a, mut power_b) = (0, 0);
+        WsCoyoteDevice::handle_ws_event(
+            dglab_protocol::wifi::WsEvent::Strength(dglab_protocol::wifi::StrengthData {
+                strength_a: 12,
+                strength_b: 34,
+                max_a: 100,
+                max_b: 100,
+            }),
+            &dev.inner,
+            &dev.base.event_tx,
+            &mut power_a,
+            &mut power_b,
+        );
+        // 观察端显示 APP 上报的强度
+        assert_eq!(dev.get_power(0), 12);
+        assert_eq!(dev.info().power_b, 34);
+
+        assert!(matches!(
+            dev.set_power(0, 20).await,
+            Err(CoreError::Rejected(_))
+        ));
+        assert!(dev.adjust_power(1, 5).await.is_err());
+        assert!(dev.start().await.is_err());
+        assert_eq!(dev.get_power(0), 12);
+    }
+
+    #[tokio::test]
+    async fn test_ws_coyote_disabled_channel_ignores_app_strength() {
+        let mut dev = WsCoyoteDevice::new("ws-1".to_string(), "WiFi".to_string());
+        dev.set_channel_enabled(1, false).await.unwrap();
+
+        let event = dglab_protocol::wifi::WsEvent::Strength(dglab_protocol::wifi::StrengthData {
+            strength_a: 12,
+            strength_b: 34,
+            max_a: 100,
+            max_b: 100,
+        });
+        assert_eq!(dev.inner.raised_disabled_channels(&event), [1]);
+        let (mut power_a, mut power_b) = (0, 0);
+        WsCoyoteDevice::handle_ws_event(
+            event,
+            &dev.inner,
+            &dev.base.event_tx,
+            &mut power_a,
+            &mut power_b,
+        );
+        assert_eq!((power_a, power_b), (12, 0));
+
+        let mut events = dev.subscribe_events();
+        WsCoyoteDevice::handle_ws_event(
+            dglab_protocol::wifi::WsEvent::Bound("app".to_string()),
+            &dev.inner,
+            &dev.base.event_tx,
+            &mut power_a,
+            &mut power_b,
+        );
+        match events.try_recv() {
+            Ok(DeviceEvent::InfoUpdated(info)) => {
+                assert!(info.enabled_a);
+                assert!(!info.enabled_b);
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ws_coyote_set_max_power() {
+        let mut dev = WsCoyoteDevice::new("ws-1".to_string(), "WiFi".to_string());
+        dev.set_power(1, 70).await.unwrap();
+
+        dev.set_max_power(1, 40).await.unwrap();
+        assert_eq!(dev.info().max_power_b, 40);
+        assert_eq!(dev.get_power(1), 40);
+        assert!(dev.set_max_power(1, MAX_STRENGTH + 1).await.is_err());
     }
 
     #[tokio::test]
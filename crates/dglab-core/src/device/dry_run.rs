@@ -0,0 +1,375 @@
+//! 试运行（dry-run）
+//!
+//! 试运行模式下设备不连接硬件，也不发送任何数据：[`CoyoteDevice`](super::CoyoteDevice)
+//! 照常生成 BF/B0 帧，[`WsCoyoteDevice`](super::WsCoyoteDevice) 照常生成 WebSocket 消息，
+//! 但都交给 [`DryRunRecorder`] 记录，用于在连接设备前确认脚本或预设会发出什么。
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use tokio::time::Instant;
+use tracing::debug;
+
+use dglab_protocol::ble::traffic::describe_frame;
+
+use super::coyote::{CoyoteDevice, WsCoyoteDevice};
+use super::traits::Device;
+use crate::error::{CoreError, Result};
+
+/// 默认保留的帧数
+pub const DEFAULT_DRY_RUN_CAPACITY: usize = 1000;
+
+/// 默认试运行设备 ID
+pub const DRY_RUN_DEVICE_ID: &str = "dry-run";
+
+/// 试运行模拟的传输方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DryRunTransport {
+    /// BLE（V3 B0/BF 帧）
+    #[default]
+    Ble,
+    /// WebSocket（APP 消息）
+    Ws,
+}
+
+impl fmt::Display for DryRunTransport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ble => write!(f, "ble"),
+            Self::Ws => write!(f, "ws"),
+        }
+    }
+}
+
+impl FromStr for DryRunTransport {
+    type Err = CoreError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "ble" | "v3" => Ok(Self::Ble),
+            "ws" | "wifi" | "websocket" => Ok(Self::Ws),
+            _ => Err(CoreError::InvalidParameter(format!(
+                "Invalid dry-run transport '{}', expected ble or ws",
+                s
+            ))),
+        }
+    }
+}
+
+/// 被拦截的数据
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "transport", content = "data", rename_all = "snake_case")]
+pub enum DryRunPayload {
+    /// BLE 写入的原始字节
+    Ble(Vec<u8>),
+    /// WebSocket 消息内容（`message` 字段）
+    Ws(String),
+}
+
+/// 一条被拦截的帧
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DryRunFrame {
+    /// 设备 ID
+    pub device_id: String,
+    /// 相对于记录器创建的时间
+    pub elapsed: Duration,
+    /// 数据
+    pub payload: DryRunPayload,
+}
+
+impl fmt::Display for DryRunFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[{:>8.3}s] {} ",
+            self.elapsed.as_secs_f64(),
+            self.device_id
+        )?;
+
+        match &self.payload {
+            DryRunPayload::Ws(message) => write!(f, "WS {}", message),
+            DryRunPayload::Ble(data) => write!(f, "{}", describe_frame(data)),
+        }
+    }
+}
+
+/// 试运行记录器共享状态
+#[derive(Debug)]
+struct RecorderInner {
+    /// 创建时间
+    started: Instant,
+    /// 最近的帧
+    frames: Mutex<VecDeque<DryRunFrame>>,
+    /// 保留的帧数
+    capacity: usize,
+    /// 帧广播
+    frame_tx: broadcast::Sender<DryRunFrame>,
+}
+
+/// 试运行记录器
+///
+/// 可克隆，多个设备共享同一个记录器时按发送顺序记录。只保留最近
+/// `capacity` 帧，需要完整输出时订阅 [`subscribe`](Self::subscribe)。
+#[derive(Debug, Clone)]
+pub struct DryRunRecorder {
+    inner: Arc<RecorderInner>,
+}
+
+impl Default for DryRunRecorder {
+    fn default() -> Self {
+        Self::new(DEFAULT_DRY_RUN_CAPACITY)
+    }
+}
+
+impl DryRunRecorder {
+    /// 创建记录器
+    pub fn new(capacity: usize) -> Self {
+        let (frame_tx, _) = broadcast::channel(256);
+        Self {
+            inner: Arc::new(RecorderInner {
+                started: Instant::now(),
+                frames: Mutex::new(VecDeque::new()),
+                capacity: capacity.max(1),
+                frame_tx,
+            }),
+        }
+    }
+
+    fn frames_guard(&self) -> MutexGuard<'_, VecDeque<DryRunFrame>> {
+        self.inner
+            .frames
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// 记录一帧
+    pub fn record(&self, device_id: &str, payload: DryRunPayload) {
+        let frame = DryRunFrame {
+            device_id: device_id.to_string(),
+            elapsed: self.inner.started.elapsed(),
+            payload,
+        };
+        debug!("Dry run: {}", frame);
+
+        {
+            let mut frames = self.frames_guard();
+            if frames.len() >= self.inner.capacity {
+                let _ = frames.pop_front();
+            }
+            frames.push_back(frame.clone());
+        }
+        // 没有订阅者时忽略
+        let _ = self.inner.frame_tx.send(frame);
+    }
+
+    /// 最近记录的帧（按时间顺序）
+    pub fn frames(&self) -> Vec<DryRunFrame> {
+        self.frames_guard().iter().cloned().collect()
+    }
+
+    /// 清空已记录的帧
+    pub fn clear(&self) {
+        self.frames_guard().clear();
+    }
+
+    /// 订阅新记录的帧
+    pub fn subscribe(&self) -> broadcast::Receiver<DryRunFrame> {
+        self.inner.frame_tx.subscribe()
+    }
+}
+
+/// 创建试运行设备
+pub fn dry_run_device(
+    transport: DryRunTransport,
+    id: String,
+    name: String,
+    recorder: DryRunRecorder,
+) -> Box<dyn Device> {
+    match transport {
+        DryRunTransport::Ble => Box::new(CoyoteDevice::dry_run(id, name, recorder)),
+        DryRunTransport::Ws => Box::new(WsCoyoteDevice::dry_run(id, name, recorder)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dglab_protocol::v3::{B0Command, BFCommand, BF_HEAD};
+
+    #[test]
+    fn test_transport_parse() {
+        assert_eq!(
+            "BLE".parse::<DryRunTransport>().unwrap(),
+            DryRunTransport::Ble
+        );
+        assert_eq!(
+            "wifi".parse::<DryRunTransport>().unwrap(),
+            DryRunTransport::Ws
+        );
+        assert!("usb".parse::<DryRunTransport>().is_err());
+        assert_eq!(DryRunTransport::default().to_string(), "ble");
+    }
+
+    #[test]
+    fn test_recorder_capacity() {
+        let recorder = DryRunRecorder::new(2);
+        let mut rx = recorder.subscribe();
+        for message in ["a", "b", "c"] {
+            recorder.record("dev", DryRunPayload::Ws(message.to_string()));
+        }
+
+        let frames = recorder.frames();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].payload, DryRunPayload::Ws("b".to_string()));
+        assert_eq!(
+            rx.try_recv().unwrap().payload,
+            DryRunPayload::Ws("a".to_string())
+        );
+
+        recorder.clear();
+        assert!(recorder.frames().is_empty());
+    }
+
+    #[test]
+    fn test_frame_display() {
+        let frame = |payload| DryRunFrame {
+            device_id: "dev".to_string(),
+            elapsed: Duration::from_millis(1500),
+            payload,
+        };
+
+        let bf = frame(DryRunPayload::Ble(
+            BFCommand::default_config().encode().to_vec(),
+        ));
+        assert!(bf.to_string().contains("BF limit A="));
+
+        let b0 = frame(DryRunPayload::Ble(B0Command::zero().encode().to_vec()));
+        let text = b0.to_string();
+        assert!(text.starts_with("[   1.500s] dev B0 seq=0 A=Absolute:0"));
+
+        let ws = frame(DryRunPayload::Ws("strength-1+2+20".to_string()));
+        assert!(ws.to_string().ends_with("WS strength-1+2+20"));
+
+        let raw = frame(DryRunPayload::Ble(vec![0x12, 0x34]));
+        assert!(raw.to_string().ends_with("1234"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_coyote_dry_run_records_frames() {
+        let recorder = DryRunRecorder::default();
+        let mut device = dry_run_device(
+            DryRunTransport::Ble,
+            DRY_RUN_DEVICE_ID.to_string(),
+            "Dry run".to_string(),
+            recorder.clone(),
+        );
+
+        device.connect().await.unwrap();
+        device.start().await.unwrap();
+        device.set_power(0, 20).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(350)).await;
+        device.stop().await.unwrap();
+
+        let frames = recorder.frames();
+        assert!(matches!(&frames[0].payload, DryRunPayload::Ble(d) if d[0] == BF_HEAD));
+        let b0: Vec<B0Command> = frames
+            .iter()
+            .filter_map(|f| match &f.payload {
+                DryRunPayload::Ble(d) => B0Command::decode(d),
+                DryRunPayload::Ws(_) => None,
+            })
+            .collect();
+        assert!(b0.iter().any(|cmd| cmd.strength_a == 20));
+        assert_eq!(b0.last().unwrap(), &B0Command::zero());
+    }
+
+    #[tokio::test]
+    async fn test_ws_dry_run_records_messages() {
+        let recorder = DryRunRecorder::default();
+        let mut device = dry_run_device(
+            DryRunTransport::Ws,
+            DRY_RUN_DEVICE_ID.to_string(),
+            "Dry run".to_string(),
+            recorder.clone(),
+        );
+
+        device.connect().await.unwrap();
+        device.set_power(1, 15).await.unwrap();
+
+        let frames = recorder.frames();
+        assert_eq!(frames.len(), 1);
+        assert_eq!(
+            frames[0].payload,
+            DryRunPayload::Ws("strength-2+2+15".to_string())
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_ws_dry_run_streams_pulses() {
+        let recorder = DryRunRecorder::default();
+        let mut device = dry_run_device(
+            DryRunTransport::Ws,
+            DRY_RUN_DEVICE_ID.to_string(),
+            "Dry run".to_string(),
+            recorder.clone(),
+        );
+        let messages = || -> Vec<String> {
+            recorder
+                .frames()
+                .into_iter()
+                .filter_map(|f| match f.payload {
+                    DryRunPayload::Ws(message) => Some(message),
+                    DryRunPayload::Ble(_) => None,
+                })
+                .collect()
+        };
+        let pulse_count = |messages: &[String]| -> usize {
+            messages
+                .iter()
+                .filter(|m| m.starts_with("pulse-A:"))
+                .map(|m| m.matches('"').count() / 2)
+                .sum()
+        };
+
+        device.connect().await.unwrap();
+        device
+            .set_waveform(0, crate::device::traits::WaveformConfig::default())
+            .await
+            .unwrap();
+        // 未运行时不发送波形
+        assert!(messages().is_empty());
+
+        device.start().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        // 启动后预发送 500ms
+        assert_eq!(pulse_count(&messages()), 5);
+
+        // 切换波形时先清空队列再重新预发送
+        recorder.clear();
+        device
+            .set_waveform(0, crate::device::traits::WaveformConfig::default())
+            .await
+            .unwrap();
+        let sent = messages();
+        assert_eq!(sent[0], "clear-1");
+        assert_eq!(pulse_count(&sent), 5);
+
+        // 按时钟补发
+        recorder.clear();
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert_eq!(pulse_count(&messages()), 3);
+
+        recorder.clear();
+        device.stop().await.unwrap();
+        let sent = messages();
+        assert_eq!(&sent[..2], ["clear-1", "clear-2"]);
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        assert_eq!(pulse_count(&messages()), 0);
+    }
+}
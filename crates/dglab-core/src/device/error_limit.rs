@@ -0,0 +1,206 @@
+//! 重复错误合并
+//!
+//! BLE 链路抖动时输出循环每个节拍都可能失败。[`ErrorLimiter`] 按来源（如 `b0`、`notify`）
+//! 合并相同的错误：首次出现立即上报，窗口期内的重复错误只计数，窗口结束或错误恢复时
+//! 上报一条汇总，避免日志和事件通道被刷屏。
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+
+/// 默认合并窗口
+pub const DEFAULT_ERROR_WINDOW: Duration = Duration::from_secs(5);
+
+/// 错误计数
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorStats {
+    /// 累计错误数
+    pub errors: u64,
+    /// 被合并（未单独上报）的错误数
+    pub suppressed: u64,
+    /// 上报的汇总数
+    pub summaries: u64,
+    /// 最近一次错误
+    pub last_error: Option<String>,
+}
+
+/// 单个来源的合并状态
+#[derive(Debug)]
+struct Burst {
+    /// 当前错误信息
+    message: String,
+    /// 窗口开始时间
+    since: Instant,
+    /// 窗口内被合并的次数
+    suppressed: u64,
+}
+
+/// 重复错误合并
+#[derive(Debug)]
+pub struct ErrorLimiter {
+    /// 合并窗口
+    window: Duration,
+    /// 来源 → 合并状态
+    bursts: HashMap<&'static str, Burst>,
+    /// 计数
+    stats: ErrorStats,
+}
+
+impl ErrorLimiter {
+    /// 创建合并器
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            bursts: HashMap::new(),
+            stats: ErrorStats::default(),
+        }
+    }
+
+    /// 记录一次错误，返回需要上报的消息（`None` 表示已合并）
+    ///
+    /// 来源的首个错误或与上一条不同的错误立即上报；相同错误在窗口期内只计数，
+    /// 窗口结束后的下一次错误上报为带重复次数的汇总并开始新窗口。
+    pub fn record(&mut self, source: &'static str, message: &str, now: Instant) -> Option<String> {
+        self.stats.errors += 1;
+        self.stats.last_error = Some(message.to_string());
+
+        let repeated = self
+            .bursts
+            .get(source)
+            .is_some_and(|burst| burst.message == message);
+        let Some(burst) = self.bursts.get_mut(source).filter(|_| repeated) else {
+            let _ = self.bursts.insert(
+                source,
+                Burst {
+                    message: message.to_string(),
+                    since: now,
+                    suppressed: 0,
+                },
+            );
+            return Some(message.to_string());
+        };
+
+        let elapsed = now.saturating_duration_since(burst.since);
+        if elapsed < self.window {
+            burst.suppressed += 1;
+            self.stats.suppressed += 1;
+            return None;
+        }
+
+        let summary = summarize(burst, elapsed);
+        burst.since = now;
+        burst.suppressed = 0;
+        if summary.is_some() {
+            self.stats.summaries += 1;
+        }
+        summary.or_else(|| Some(message.to_string()))
+    }
+
+    /// 来源恢复正常，返回窗口期内被合并错误的汇总（没有被合并的错误时为 `None`）
+    pub fn clear(&mut self, source: &'static str, now: Instant) -> Option<String> {
+        let burst = self.bursts.remove(source)?;
+        let summary = summarize(&burst, now.saturating_duration_since(burst.since));
+        if summary.is_some() {
+            self.stats.summaries += 1;
+        }
+        summary
+    }
+
+    /// 来源当前是否处于错误状态
+    pub fn is_failing(&self, source: &'static str) -> bool {
+        self.bursts.contains_key(source)
+    }
+
+    /// 错误计数
+    pub fn stats(&self) -> ErrorStats {
+        self.stats.clone()
+    }
+}
+
+impl Default for ErrorLimiter {
+    fn default() -> Self {
+        Self::new(DEFAULT_ERROR_WINDOW)
+    }
+}
+
+/// 生成汇总消息（没有被合并的错误时为 `None`）
+fn summarize(burst: &Burst, elapsed: Duration) -> Option<String> {
+    (burst.suppressed > 0).then(|| {
+        format!(
+            "{} (repeated {} times in {:.1}s)",
+            burst.message,
+            burst.suppressed,
+            elapsed.as_secs_f64()
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_coalesce_repeated_errors() {
+        let start = Instant::now();
+        let mut limiter = ErrorLimiter::new(Duration::from_secs(5));
+        let at = |ms| start + Duration::from_millis(ms);
+
+        assert_eq!(
+            limiter.record("b0", "B0 send failed", at(0)).as_deref(),
+            Some("B0 send failed")
+        );
+        // 窗口期内的重复错误被合并
+        for i in 1..=10 {
+            assert_eq!(limiter.record("b0", "B0 send failed", at(i * 100)), None);
+        }
+        assert!(limiter.is_failing("b0"));
+
+        // 窗口结束后上报汇总
+        let summary = limiter.record("b0", "B0 send failed", at(5000)).unwrap();
+        assert_eq!(summary, "B0 send failed (repeated 10 times in 5.0s)");
+
+        let stats = limiter.stats();
+        assert_eq!(stats.errors, 12);
+        assert_eq!(stats.suppressed, 10);
+        assert_eq!(stats.summaries, 1);
+        assert_eq!(stats.last_error.as_deref(), Some("B0 send failed"));
+    }
+
+    #[test]
+    fn test_different_message_and_sources() {
+        let start = Instant::now();
+        let mut limiter = ErrorLimiter::default();
+
+        assert!(limiter.record("b0", "timeout", start).is_some());
+        // 不同来源互不影响
+        assert!(limiter.record("notify", "timeout", start).is_some());
+        // 错误信息变化时立即上报
+        assert!(limiter.record("b0", "not connected", start).is_some());
+        assert_eq!(limiter.record("b0", "not connected", start), None);
+    }
+
+    #[test]
+    fn test_clear_summarizes() {
+        let start = Instant::now();
+        let mut limiter = ErrorLimiter::default();
+
+        // 没有错误或没有被合并的错误时不汇总
+        assert_eq!(limiter.clear("b0", start), None);
+        let _ = limiter.record("b0", "B0 send failed", start);
+        assert_eq!(limiter.clear("b0", start), None);
+
+        let _ = limiter.record("b0", "B0 send failed", start);
+        let _ = limiter.record("b0", "B0 send failed", start + Duration::from_millis(100));
+        let _ = limiter.record("b0", "B0 send failed", start + Duration::from_millis(200));
+        assert_eq!(
+            limiter
+                .clear("b0", start + Duration::from_millis(300))
+                .as_deref(),
+            Some("B0 send failed (repeated 2 times in 0.3s)")
+        );
+        assert!(!limiter.is_failing("b0"));
+        assert_eq!(limiter.stats().summaries, 1);
+    }
+}
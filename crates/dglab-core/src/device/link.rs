@@ -0,0 +1,77 @@
+//! 通道联动
+//!
+//! 设备只保存联动配置（[`Device::channel_link`]），A 通道变化后按联动同步 B 通道由调用方执行。
+//! 会话管理器经 [`set_power_linked`] 写入强度，并传入安全限制和低电量限制下的 B 通道上限，
+//! 联动不会让 B 通道超过这些限制。
+
+use super::Device;
+use crate::error::Result;
+
+/// 设置通道强度；设置 A 通道且开启联动时同步 B 通道（见 [`sync_linked_power`]）
+pub async fn set_power_linked(
+    device: &mut dyn Device,
+    channel: u8,
+    power: u8,
+    max_power_b: u8,
+) -> Result<()> {
+    device.set_power(channel, power).await?;
+    if channel == 0 {
+        let _ = sync_linked_power(device, max_power_b).await?;
+    }
+    Ok(())
+}
+
+/// 按当前 A 通道强度同步联动的 B 通道，B 通道不超过设备上限和 `max_power_b`
+///
+/// 返回写入的 B 通道强度，未开启联动时返回 `None`。
+pub async fn sync_linked_power(device: &mut dyn Device, max_power_b: u8) -> Result<Option<u8>> {
+    let Some(link) = device.channel_link() else {
+        return Ok(None);
+    };
+    let max_power_b = device.info().max_power_b.min(max_power_b);
+    let power = link.apply(device.get_power(0), max_power_b);
+    device.set_power(1, power).await?;
+    Ok(Some(power))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device::{ChannelLink, MockDevice};
+
+    #[tokio::test]
+    async fn test_set_power_linked() {
+        let mut device = MockDevice::new("mock-001".to_string(), "Test Device".to_string());
+        device.connect().await.unwrap();
+        device.set_power(0, 20).await.unwrap();
+
+        // 未开启联动时不同步
+        assert_eq!(sync_linked_power(&mut device, u8::MAX).await.unwrap(), None);
+        assert_eq!(device.get_power(1), 0);
+
+        device
+            .set_channel_link(Some(ChannelLink::new(0.5, 5).unwrap()))
+            .await
+            .unwrap();
+        assert_eq!(
+            sync_linked_power(&mut device, u8::MAX).await.unwrap(),
+            Some(15)
+        );
+
+        set_power_linked(&mut device, 0, 60, u8::MAX).await.unwrap();
+        assert_eq!(device.get_power(1), 35);
+
+        // B 通道不超过传入的上限
+        set_power_linked(&mut device, 0, 80, 20).await.unwrap();
+        assert_eq!(device.get_power(1), 20);
+
+        // 单独设置 B 通道不影响 A 通道
+        set_power_linked(&mut device, 1, 10, u8::MAX).await.unwrap();
+        assert_eq!(device.get_power(0), 80);
+
+        // 关闭联动后 B 通道保持不变
+        device.set_channel_link(None).await.unwrap();
+        set_power_linked(&mut device, 0, 40, u8::MAX).await.unwrap();
+        assert_eq!(device.get_power(1), 10);
+    }
+}
@@ -1,12 +1,13 @@
 //! 模拟设备实现，用于测试和开发
 
+use std::any::Any;
 use std::sync::Arc;
 
 use async_trait::async_trait;
 use tokio::sync::{broadcast, RwLock};
 use tracing::{debug, info};
 
-use super::traits::{Device, DeviceInfo, WaveformConfig};
+use super::traits::{ChannelLink, Device, DeviceInfo, DeviceLimits, WaveformConfig};
 use super::{DeviceEvent, DeviceState};
 use crate::error::{CoreError, Result};
 
@@ -22,6 +23,10 @@ pub struct MockDevice {
     state: Arc<RwLock<DeviceState>>,
     /// 设备信息
     info: Arc<RwLock<DeviceInfo>>,
+    /// 平衡参数（频率，强度）
+    balance: (u8, u8),
+    /// 通道联动
+    channel_link: Option<ChannelLink>,
     /// 事件广播通道
     event_tx: broadcast::Sender<DeviceEvent>,
 }
@@ -38,10 +43,13 @@ impl MockDevice {
             firmware_version: "1.0.0".to_string(),
             hardware_version: "1.0.0".to_string(),
             battery_level: 100,
+            battery_remaining_secs: None,
             power_a: 0,
             power_b: 0,
             max_power_a: 100,
             max_power_b: 100,
+            enabled_a: true,
+            enabled_b: true,
         };
 
         Self {
@@ -49,6 +57,8 @@ impl MockDevice {
             name,
             state: Arc::new(RwLock::new(DeviceState::Disconnected)),
             info: Arc::new(RwLock::new(info)),
+            balance: (0, 0),
+            channel_link: None,
             event_tx,
         }
     }
@@ -70,6 +80,14 @@ impl MockDevice {
 
 #[async_trait]
 impl Device for MockDevice {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
     fn id(&self) -> &str {
         &self.id
     }
@@ -161,7 +179,12 @@ impl Device for MockDevice {
             _ => return Err(CoreError::InvalidChannel(channel)),
         };
 
-        let clamped_power = power.min(max_power);
+        // 禁用的通道始终为 0
+        let enabled = match channel {
+            0 => info.enabled_a,
+            _ => info.enabled_b,
+        };
+        let clamped_power = if enabled { power.min(max_power) } else { 0 };
 
         match channel {
             0 => {
@@ -202,6 +225,101 @@ impl Device for MockDevice {
         }
     }
 
+    async fn set_max_power(&mut self, channel: u8, max_power: u8) -> Result<()> {
+        let mut info = self.info.write().await;
+
+        let power = match channel {
+            0 => {
+                info.max_power_a = max_power;
+                &mut info.power_a
+            }
+            1 => {
+                info.max_power_b = max_power;
+                &mut info.power_b
+            }
+            _ => return Err(CoreError::InvalidChannel(channel)),
+        };
+
+        debug!("模拟设备设置通道 {} 最大强度: {}", channel, max_power);
+
+        if *power > max_power {
+            *power = max_power;
+            self.send_event(DeviceEvent::PowerChanged {
+                channel,
+                power: max_power,
+            });
+        }
+
+        Ok(())
+    }
+
+    fn limits(&self) -> DeviceLimits {
+        let info = futures::executor::block_on(async { self.info.read().await.clone() });
+        DeviceLimits::new(
+            info.max_power_a,
+            info.max_power_b,
+            self.balance.0,
+            self.balance.1,
+        )
+    }
+
+    async fn set_limits(&mut self, limits: DeviceLimits) -> Result<()> {
+        limits.validate()?;
+
+        self.set_max_power(0, limits.soft_limit_a).await?;
+        self.set_max_power(1, limits.soft_limit_b).await?;
+        self.balance = (limits.freq_balance, limits.intensity_balance);
+
+        Ok(())
+    }
+
+    fn channel_link(&self) -> Option<ChannelLink> {
+        self.channel_link
+    }
+
+    async fn set_channel_link(&mut self, link: Option<ChannelLink>) -> Result<()> {
+        if let Some(link) = &link {
+            link.validate()?;
+        }
+
+        debug!("模拟设备设置通道联动: {:?}", link);
+        self.channel_link = link;
+        Ok(())
+    }
+
+    fn channel_enabled(&self, channel: u8) -> bool {
+        let info = futures::executor::block_on(async { self.info.read().await.clone() });
+        match channel {
+            0 => info.enabled_a,
+            1 => info.enabled_b,
+            _ => false,
+        }
+    }
+
+    async fn set_channel_enabled(&mut self, channel: u8, enabled: bool) -> Result<()> {
+        let mut info = self.info.write().await;
+
+        let (flag, power) = match channel {
+            0 => (&mut info.enabled_a, &mut info.power_a),
+            1 => (&mut info.enabled_b, &mut info.power_b),
+            _ => return Err(CoreError::InvalidChannel(channel)),
+        };
+
+        debug!(
+            "模拟设备{}通道 {}",
+            if enabled { "启用" } else { "禁用" },
+            channel
+        );
+        *flag = enabled;
+
+        if !enabled && *power > 0 {
+            *power = 0;
+            self.send_event(DeviceEvent::PowerChanged { channel, power: 0 });
+        }
+
+        Ok(())
+    }
+
     async fn set_waveform(&mut self, channel: u8, waveform: WaveformConfig) -> Result<()> {
         let state = self.state.read().await;
         if *state != DeviceState::Connected {
@@ -235,6 +353,10 @@ impl Device for MockDevice {
     fn subscribe_events(&self) -> broadcast::Receiver<DeviceEvent> {
         self.event_tx.subscribe()
     }
+
+    fn send_event(&self, event: DeviceEvent) {
+        let _ = self.event_tx.send(event);
+    }
 }
 
 #[cfg(test)]
@@ -293,6 +415,24 @@ mod tests {
         assert_eq!(device.get_power(0), 100);
     }
 
+    #[tokio::test]
+    async fn test_mock_device_set_max_power() {
+        let mut device = MockDevice::new("mock-001".to_string(), "Test Device".to_string());
+        device.connect().await.unwrap();
+        device.set_power(0, 80).await.unwrap();
+
+        device.set_max_power(0, 30).await.unwrap();
+        assert_eq!(device.info().max_power_a, 30);
+        assert_eq!(device.get_power(0), 30);
+
+        // 新上限下设置强度会被限制
+        device.set_power(0, 90).await.unwrap();
+        assert_eq!(device.get_power(0), 30);
+
+        let result = device.set_max_power(2, 50).await;
+        assert!(matches!(result.unwrap_err(), CoreError::InvalidChannel(2)));
+    }
+
     #[tokio::test]
     async fn test_mock_device_start_stop() {
         let mut device = MockDevice::new("mock-001".to_string(), "Test Device".to_string());
@@ -397,4 +537,21 @@ mod tests {
         let event = rx.recv().await.unwrap();
         assert!(matches!(event, DeviceEvent::WaveformChanged { channel: 0 }));
     }
+
+    #[tokio::test]
+    async fn test_mock_device_set_limits() {
+        let mut device = MockDevice::new("mock-001".to_string(), "Test Device".to_string());
+        device.connect().await.unwrap();
+        device.set_power(0, 80).await.unwrap();
+
+        let limits = DeviceLimits::new(60, 150, 100, 20);
+        device.set_limits(limits).await.unwrap();
+        assert_eq!(device.limits(), limits);
+        assert_eq!(device.get_power(0), 60);
+
+        assert!(device
+            .set_limits(DeviceLimits::new(201, 0, 0, 0))
+            .await
+            .is_err());
+    }
 }
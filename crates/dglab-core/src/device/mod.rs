@@ -3,21 +3,49 @@
 //! 提供设备抽象 trait 和具体实现。
 
 pub mod bridge;
+pub mod calibration;
 pub mod coyote;
+pub mod dry_run;
+pub mod error_limit;
+pub mod link;
 pub mod mock;
+pub mod output_clock;
+pub mod pulse_buffer;
+pub mod ramp;
+#[cfg(feature = "simulator")]
+pub mod simulator;
+pub mod supervisor;
 pub mod traits;
 
+use std::sync::Arc;
+
 use serde::{Deserialize, Serialize};
 use tokio::sync::broadcast;
 use tracing::debug;
 
-pub use bridge::BleWsBridgeDevice;
-pub use coyote::{CoyoteDevice, WsCoyoteDevice};
+use crate::metrics::DeviceMetrics;
+
+pub use bridge::{BleWsBridgeDevice, BridgeFrame, BridgeMetrics, BridgeStatus, FrameDirection};
+pub use calibration::{PowerCurve, MAX_PERCENT};
+pub use coyote::{CoyoteDevice, LinkMonitorConfig, WsCoyoteDevice, PULSE_QUEUE_CAPACITY};
+pub use dry_run::{
+    dry_run_device, DryRunFrame, DryRunPayload, DryRunRecorder, DryRunTransport, DRY_RUN_DEVICE_ID,
+};
+pub use error_limit::{ErrorLimiter, ErrorStats, DEFAULT_ERROR_WINDOW};
+pub use link::{set_power_linked, sync_linked_power};
 pub use mock::MockDevice;
-pub use traits::{Device, DeviceConfig};
+pub use output_clock::{OutputClock, TickStats};
+pub use pulse_buffer::{PulseScheduler, DEFAULT_PULSE_LOOKAHEAD, MAX_PULSE_LOOKAHEAD};
+pub use ramp::{ramp_power, ramp_power_calibrated, ramp_power_with, Easing, PowerRamp, RampSpec};
+#[cfg(feature = "simulator")]
+pub use simulator::{MockCoyoteDevice, V3Simulator};
+pub use supervisor::{RestartPolicy, TaskSupervisor};
+pub use traits::{ChannelLink, Device, DeviceConfig, DeviceLimits, LatencyStats, WifiBinding};
 
 /// 设备状态
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts", ts(repr(enum = name)))]
 pub enum DeviceState {
     /// 已断开
     Disconnected,
@@ -32,7 +60,8 @@ pub enum DeviceState {
 }
 
 /// 设备事件
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
 pub enum DeviceEvent {
     /// 状态变更
     StateChanged(DeviceState),
@@ -43,6 +72,22 @@ pub enum DeviceEvent {
         /// 强度值 (0-100)
         power: u8,
     },
+    /// 强度请求未生效（设备实际强度与请求不一致，如被软上限截断）
+    PowerRejected {
+        /// 通道编号 (0=A, 1=B)
+        channel: u8,
+        /// 请求强度
+        requested: u8,
+        /// 设备实际强度
+        actual: u8,
+    },
+    /// 强度上限变更（如 APP 调整了通道强度上限）
+    MaxPowerChanged {
+        /// A 通道强度上限
+        max_power_a: u8,
+        /// B 通道强度上限
+        max_power_b: u8,
+    },
     /// 设备状态上报（两个通道强度）
     StatusReport {
         /// A 通道强度
@@ -59,16 +104,32 @@ pub enum DeviceEvent {
     InfoUpdated(crate::device::traits::DeviceInfo),
     /// 电池电量更新
     BatteryUpdated(u8),
+    /// BLE 信号强度（RSSI，dBm）
+    LinkQuality(i16),
+    /// 弱信号状态变化
+    WeakSignal {
+        /// 信号强度（dBm）
+        rssi: i16,
+        /// 是否低于弱信号阈值（false 表示已恢复）
+        weak: bool,
+    },
     /// 设备已启动
     Started,
     /// 设备已停止
     Stopped,
     /// 心跳
     Heartbeat,
+    /// APP 反馈按钮按下
+    Feedback(dglab_protocol::wifi::FeedbackButton),
+    /// 低电量限制变化（按电量和策略得到的限制），由会话管理器执行限制后发送
+    BatteryLimited(crate::session::BatteryAction),
     /// 错误
     Error(String),
 }
 
+/// [`BaseDevice::new`] 的默认通道上限
+pub const DEFAULT_MAX_POWER: u8 = 100;
+
 /// 基础设备实现
 pub struct BaseDevice {
     /// 设备 ID
@@ -85,14 +146,32 @@ pub struct BaseDevice {
     max_power_a: u8,
     /// 通道 B 最大强度
     max_power_b: u8,
+    /// 波形频率平衡参数
+    freq_balance: u8,
+    /// 波形强度平衡参数
+    intensity_balance: u8,
+    /// 通道联动
+    channel_link: Option<ChannelLink>,
+    /// 通道 A/B 是否启用
+    enabled: [bool; 2],
     /// 事件发送器
     event_tx: broadcast::Sender<DeviceEvent>,
+    /// 导出的运行指标
+    telemetry: Arc<DeviceMetrics>,
 }
 
 impl BaseDevice {
-    /// 创建新的基础设备
+    /// 创建新的基础设备，两通道上限为 [`DEFAULT_MAX_POWER`]
     pub fn new(id: String, name: String) -> Self {
+        Self::with_max_power(id, name, DEFAULT_MAX_POWER)
+    }
+
+    /// 创建指定通道上限的基础设备
+    ///
+    /// 上限应与设备实际接受的最大强度一致（如 V3 为 200），否则超出部分的强度无法记录。
+    pub fn with_max_power(id: String, name: String, max_power: u8) -> Self {
         let (event_tx, _) = broadcast::channel(32);
+        let telemetry = crate::metrics::device(&id);
 
         Self {
             id,
@@ -100,9 +179,14 @@ impl BaseDevice {
             state: DeviceState::Disconnected,
             power_a: 0,
             power_b: 0,
-            max_power_a: 100,
-            max_power_b: 100,
+            max_power_a: max_power,
+            max_power_b: max_power,
+            freq_balance: 0,
+            intensity_balance: 0,
+            channel_link: None,
+            enabled: [true, true],
             event_tx,
+            telemetry,
         }
     }
 
@@ -129,10 +213,16 @@ impl BaseDevice {
                 self.id, self.state, state
             );
             self.state = state;
+            self.telemetry.set_state(state);
             let _ = self.event_tx.send(DeviceEvent::StateChanged(state));
         }
     }
 
+    /// 导出的运行指标（按设备 ID 登记在全局注册表中）
+    pub fn telemetry(&self) -> &Arc<DeviceMetrics> {
+        &self.telemetry
+    }
+
     /// 获取通道 A 强度
     pub fn power_a(&self) -> u8 {
         self.power_a
@@ -143,6 +233,61 @@ impl BaseDevice {
         self.power_b
     }
 
+    /// 获取通道 A 最大强度
+    pub fn max_power_a(&self) -> u8 {
+        self.max_power_a
+    }
+
+    /// 获取通道 B 最大强度
+    pub fn max_power_b(&self) -> u8 {
+        self.max_power_b
+    }
+
+    /// 设置通道最大强度，当前强度超出时下调到新上限
+    pub fn set_max_power(&mut self, channel: u8, max_power: u8) -> crate::Result<()> {
+        let power = match channel {
+            0 => {
+                self.max_power_a = max_power;
+                self.power_a
+            }
+            1 => {
+                self.max_power_b = max_power;
+                self.power_b
+            }
+            _ => {
+                return Err(crate::CoreError::InvalidParameter(
+                    "Invalid channel".to_string(),
+                ))
+            }
+        };
+
+        if power > max_power {
+            self.set_power(channel, max_power)?;
+        }
+
+        Ok(())
+    }
+
+    /// 获取输出限制
+    pub fn limits(&self) -> DeviceLimits {
+        DeviceLimits::new(
+            self.max_power_a,
+            self.max_power_b,
+            self.freq_balance,
+            self.intensity_balance,
+        )
+    }
+
+    /// 设置输出限制，当前强度超出时下调到新上限
+    pub fn set_limits(&mut self, limits: DeviceLimits) -> crate::Result<()> {
+        limits.validate()?;
+        self.set_max_power(0, limits.soft_limit_a)?;
+        self.set_max_power(1, limits.soft_limit_b)?;
+        self.freq_balance = limits.freq_balance;
+        self.intensity_balance = limits.intensity_balance;
+        Ok(())
+    }
+
     /// 设置通道强度
     pub fn set_power(&mut self, channel: u8, power: u8) -> crate::Result<()> {
         let max_power = match channel {
@@ -159,11 +304,13 @@ impl BaseDevice {
             return Err(crate::CoreError::PowerOutOfRange(power, max_power));
         }
 
+        let power = self.gate_power(channel, power);
         match channel {
             0 => self.power_a = power,
             1 => self.power_b = power,
             _ => {}
         }
+        self.telemetry.set_power(channel, power);
 
         let _ = self
             .event_tx
@@ -171,6 +318,65 @@ impl BaseDevice {
         Ok(())
     }
 
+    /// 按设备反馈校正通道强度（不检查上限、不发送事件，事件由收到反馈的一方发送）
+    pub fn sync_power(&mut self, channel: u8, power: u8) {
+        match channel {
+            0 => self.power_a = power,
+            1 => self.power_b = power,
+            _ => return,
+        }
+        self.telemetry.set_power(channel, power);
+    }
+
+    /// 获取通道联动配置
+    pub fn channel_link(&self) -> Option<ChannelLink> {
+        self.channel_link
+    }
+
+    /// 设置通道联动配置
+    pub fn set_channel_link(&mut self, link: Option<ChannelLink>) -> crate::Result<()> {
+        if let Some(link) = &link {
+            link.validate()?;
+        }
+        self.channel_link = link;
+        Ok(())
+    }
+
+    /// 通道是否启用（无效通道返回 `false`）
+    pub fn channel_enabled(&self, channel: u8) -> bool {
+        self.enabled.get(channel as usize).copied().unwrap_or(false)
+    }
+
+    /// 启用或禁用通道，禁用时强度归零
+    pub fn set_channel_enabled(&mut self, channel: u8, enabled: bool) -> crate::Result<()> {
+        let flag = self
+            .enabled
+            .get_mut(channel as usize)
+            .ok_or(crate::CoreError::InvalidChannel(channel))?;
+        if *flag != enabled {
+            debug!(
+                "Device {} channel {} {}",
+                self.id,
+                channel,
+                if enabled { "enabled" } else { "disabled" }
+            );
+            *flag = enabled;
+        }
+        if !enabled {
+            self.set_power(channel, 0)?;
+        }
+        Ok(())
+    }
+
+    /// 按通道启用状态过滤强度，禁用的通道始终为 0
+    pub fn gate_power(&self, channel: u8, power: u8) -> u8 {
+        if self.channel_enabled(channel) {
+            power
+        } else {
+            0
+        }
+    }
+
     /// 获取事件接收器
     pub fn subscribe_events(&self) -> broadcast::Receiver<DeviceEvent> {
         self.event_tx.subscribe()
@@ -180,6 +386,11 @@ impl BaseDevice {
     pub fn send_event(&self, event: DeviceEvent) {
         let _ = self.event_tx.send(event);
     }
+
+    /// 后台任务监督器，任务 panic 时向本设备的订阅方发送错误事件
+    pub fn supervisor(&self) -> TaskSupervisor {
+        TaskSupervisor::new(self.id.clone(), self.event_tx.clone())
+    }
 }
 
 #[cfg(test)]
@@ -233,6 +444,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_device_event_power_rejected() {
+        let event = DeviceEvent::PowerRejected {
+            channel: 1,
+            requested: 150,
+            actual: 100,
+        };
+        if let DeviceEvent::PowerRejected {
+            channel,
+            requested,
+            actual,
+        } = event
+        {
+            assert_eq!(channel, 1);
+            assert_eq!(requested, 150);
+            assert_eq!(actual, 100);
+        } else {
+            panic!("Expected PowerRejected");
+        }
+    }
+
     #[test]
     fn test_device_event_battery_updated() {
         let event = DeviceEvent::BatteryUpdated(85);
@@ -352,6 +584,16 @@ mod tests {
         assert_eq!(dev.power_a(), 100);
     }
 
+    #[test]
+    fn test_base_device_with_max_power() {
+        let mut dev = BaseDevice::with_max_power("dev-1".to_string(), "Test".to_string(), 200);
+        assert_eq!(dev.max_power_a(), 200);
+        assert_eq!(dev.max_power_b(), 200);
+        dev.set_power(1, 150).unwrap();
+        assert_eq!(dev.power_b(), 150);
+        assert!(dev.set_power(0, 201).is_err());
+    }
+
     #[test]
     fn test_base_device_set_power_emits_event() {
         let mut dev = BaseDevice::new("dev-1".to_string(), "Test".to_string());
@@ -368,6 +610,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_base_device_set_max_power_clamps_power() {
+        let mut dev = BaseDevice::new("dev-1".to_string(), "Test".to_string());
+        dev.set_power(0, 80).unwrap();
+
+        dev.set_max_power(0, 60).unwrap();
+        assert_eq!(dev.max_power_a(), 60);
+        assert_eq!(dev.power_a(), 60);
+        assert!(dev.set_power(0, 61).is_err());
+
+        dev.set_max_power(1, 150).unwrap();
+        assert_eq!(dev.max_power_b(), 150);
+        dev.set_power(1, 150).unwrap();
+    }
+
+    #[test]
+    fn test_base_device_set_max_power_invalid_channel() {
+        let mut dev = BaseDevice::new("dev-1".to_string(), "Test".to_string());
+        assert!(dev.set_max_power(2, 50).is_err());
+    }
+
     #[test]
     fn test_base_device_send_event() {
         let dev = BaseDevice::new("dev-1".to_string(), "Test".to_string());
@@ -394,4 +657,77 @@ mod tests {
         assert!(rx1.try_recv().is_ok());
         assert!(rx2.try_recv().is_ok());
     }
+
+    #[test]
+    fn test_base_device_set_limits() {
+        let mut dev = BaseDevice::new("dev-1".to_string(), "Test".to_string());
+        dev.set_power(0, 90).unwrap();
+        dev.set_power(1, 20).unwrap();
+
+        let limits = DeviceLimits::new(50, 150, 160, 10);
+        dev.set_limits(limits).unwrap();
+        assert_eq!(dev.limits(), limits);
+        assert_eq!(dev.power_a(), 50);
+        assert_eq!(dev.power_b(), 20);
+
+        // 超过 V3 最大强度
+        assert!(matches!(
+            dev.set_limits(DeviceLimits::new(201, 100, 0, 0)),
+            Err(crate::CoreError::PowerOutOfRange(201, 200))
+        ));
+        assert_eq!(dev.limits(), limits);
+    }
+
+    #[test]
+    fn test_channel_link_apply() {
+        let link = ChannelLink::new(0.5, 10).unwrap();
+        assert_eq!(link.apply(40, 100), 30);
+        // 截断到 B 通道上限
+        assert_eq!(link.apply(200, 50), 50);
+
+        let link = ChannelLink::new(1.0, -20).unwrap();
+        assert_eq!(link.apply(10, 100), 0);
+    }
+
+    #[test]
+    fn test_channel_link_rejects_invalid_ratio() {
+        assert!(ChannelLink::new(-1.0, 0).is_err());
+        assert!(ChannelLink::new(f32::NAN, 0).is_err());
+        assert!(ChannelLink::new(f32::INFINITY, 0).is_err());
+    }
+
+    #[test]
+    fn test_channel_link_from_str() {
+        let link: ChannelLink = "0.5".parse().unwrap();
+        assert_eq!(link, ChannelLink::new(0.5, 0).unwrap());
+
+        let link: ChannelLink = "1:-10".parse().unwrap();
+        assert_eq!(link, ChannelLink::new(1.0, -10).unwrap());
+
+        assert!("abc".parse::<ChannelLink>().is_err());
+        assert!("1:x".parse::<ChannelLink>().is_err());
+        assert!("-2".parse::<ChannelLink>().is_err());
+    }
+
+    #[test]
+    fn test_base_device_channel_enabled() {
+        let mut dev = BaseDevice::new("dev-1".to_string(), "Test".to_string());
+        dev.set_power(1, 40).unwrap();
+        assert!(dev.channel_enabled(1));
+
+        // 禁用时强度归零，之后的设置被忽略
+        dev.set_channel_enabled(1, false).unwrap();
+        assert_eq!(dev.power_b(), 0);
+        dev.set_power(1, 30).unwrap();
+        assert_eq!(dev.power_b(), 0);
+        assert_eq!(dev.gate_power(1, 30), 0);
+        assert_eq!(dev.gate_power(0, 30), 30);
+
+        dev.set_channel_enabled(1, true).unwrap();
+        dev.set_power(1, 30).unwrap();
+        assert_eq!(dev.power_b(), 30);
+
+        assert!(!dev.channel_enabled(2));
+        assert!(dev.set_channel_enabled(2, false).is_err());
+    }
 }
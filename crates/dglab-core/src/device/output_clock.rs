@@ -0,0 +1,142 @@
+//! B0 输出节拍计时
+//!
+//! 输出循环使用 `interval_at` 和 [`MissedTickBehavior::Skip`]：负载过高错过节拍时不连续补发
+//! 积压的帧，而是由 [`OutputClock`] 按实际经过的时间计算错过的帧数，输出状态据此跳过相应的
+//! 波形帧，使波形进度与实际时间保持一致。
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::time::{Instant, Interval, MissedTickBehavior};
+
+/// 创建输出循环的节拍（首个节拍立即触发，错过的节拍直接跳过）
+pub fn output_interval(period: Duration) -> Interval {
+    let mut interval = tokio::time::interval_at(Instant::now(), period);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    interval
+}
+
+/// 输出节拍统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TickStats {
+    /// 节拍间隔
+    pub period: Duration,
+    /// 实际触发的节拍数
+    pub ticks: u64,
+    /// 因负载错过（已跳过波形帧补偿）的节拍数
+    pub missed: u64,
+    /// 平均漂移（实际间隔与最近的整数个节拍间隔之差的平均绝对值）
+    pub mean_drift: Duration,
+    /// 最大漂移
+    pub max_drift: Duration,
+}
+
+/// 输出节拍计时
+///
+/// 记录每次节拍的实际时间，计算漂移和错过的节拍数。
+#[derive(Debug, Clone)]
+pub struct OutputClock {
+    /// 节拍间隔
+    period: Duration,
+    /// 上一次节拍的实际时间
+    last: Option<Instant>,
+    /// 累计漂移
+    total_drift: Duration,
+    /// 统计
+    stats: TickStats,
+}
+
+impl OutputClock {
+    /// 创建节拍计时
+    pub fn new(period: Duration) -> Self {
+        Self {
+            period,
+            last: None,
+            total_drift: Duration::ZERO,
+            stats: TickStats {
+                period,
+                ..TickStats::default()
+            },
+        }
+    }
+
+    /// 节拍间隔
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+
+    /// 记录一次节拍，返回上一次节拍之后错过的节拍数（应跳过的波形帧数）
+    pub fn tick(&mut self, now: Instant) -> u32 {
+        self.stats.ticks += 1;
+        let Some(last) = self.last.replace(now) else {
+            return 0;
+        };
+
+        // 经过的节拍数按四舍五入计算，多于 1 的部分为错过的节拍
+        let elapsed = now.saturating_duration_since(last);
+        let slots = (elapsed.as_secs_f64() / self.period.as_secs_f64())
+            .round()
+            .max(1.0) as u32;
+        let expected = self.period * slots;
+        let drift = if elapsed > expected {
+            elapsed - expected
+        } else {
+            expected - elapsed
+        };
+
+        let missed = slots - 1;
+        self.stats.missed += u64::from(missed);
+        self.total_drift += drift;
+        self.stats.max_drift = self.stats.max_drift.max(drift);
+        self.stats.mean_drift = self.total_drift / (self.stats.ticks - 1) as u32;
+        missed
+    }
+
+    /// 节拍统计
+    pub fn stats(&self) -> TickStats {
+        self.stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tick_on_time() {
+        let start = Instant::now();
+        let mut clock = OutputClock::new(Duration::from_millis(100));
+        assert_eq!(clock.tick(start), 0);
+        assert_eq!(clock.tick(start + Duration::from_millis(100)), 0);
+        assert_eq!(clock.tick(start + Duration::from_millis(210)), 0);
+
+        let stats = clock.stats();
+        assert_eq!(stats.ticks, 3);
+        assert_eq!(stats.missed, 0);
+        assert_eq!(stats.max_drift, Duration::from_millis(10));
+        assert_eq!(stats.mean_drift, Duration::from_millis(5));
+    }
+
+    #[test]
+    fn test_tick_missed() {
+        let start = Instant::now();
+        let mut clock = OutputClock::new(Duration::from_millis(50));
+        assert_eq!(clock.tick(start), 0);
+        // 负载过高，下一次节拍晚了 3 个间隔
+        assert_eq!(clock.tick(start + Duration::from_millis(200)), 3);
+        assert_eq!(clock.tick(start + Duration::from_millis(250)), 0);
+        assert_eq!(clock.stats().missed, 3);
+        assert_eq!(clock.stats().period, Duration::from_millis(50));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_output_interval_skips() {
+        let mut interval = output_interval(Duration::from_millis(100));
+        let start = interval.tick().await;
+        tokio::time::sleep(Duration::from_millis(350)).await;
+        // 错过的节拍不补发，下一次节拍对齐到 400ms
+        let _ = interval.tick().await;
+        let next = interval.tick().await;
+        assert_eq!(next - start, Duration::from_millis(400));
+    }
+}
@@ -0,0 +1,167 @@
+//! WiFi 波形预发送调度
+//!
+//! 官方服务器转发的波形消息有延迟且经常成批到达，只发送当前一帧会让 APP 输出断断续续。
+//! 调度器按本地时钟估算 APP 队列中尚未播放的时长，保持预发送 `lookahead` 的波形数据，
+//! 不足时从波形序列中循环取帧补发。
+
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+/// 单帧波形时长（APP 每 100ms 播放一帧）
+pub const PULSE_FRAME_DURATION: Duration = Duration::from_millis(100);
+
+/// 默认预发送时长（5 帧）
+pub const DEFAULT_PULSE_LOOKAHEAD: Duration = Duration::from_millis(500);
+
+/// 最大预发送时长（单条消息最多 100 帧）
+pub const MAX_PULSE_LOOKAHEAD: Duration = Duration::from_secs(10);
+
+/// 单通道波形预发送调度器
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PulseScheduler {
+    /// 波形帧（APP 的 8 字节 HEX 格式），循环播放
+    pulses: Vec<String>,
+    /// 下一帧在序列中的位置
+    cursor: usize,
+    /// 已发送的帧预计全部播放完毕的时间
+    queued_until: Option<Instant>,
+    /// 预发送时长
+    lookahead: Duration,
+}
+
+impl PulseScheduler {
+    /// 创建调度器
+    ///
+    /// `lookahead` 会被限制在 [`PULSE_FRAME_DURATION`] 和 [`MAX_PULSE_LOOKAHEAD`] 之间。
+    pub fn new(pulses: Vec<String>, lookahead: Duration) -> Self {
+        Self {
+            pulses,
+            cursor: 0,
+            queued_until: None,
+            lookahead: lookahead.clamp(PULSE_FRAME_DURATION, MAX_PULSE_LOOKAHEAD),
+        }
+    }
+
+    /// 预发送时长
+    pub fn lookahead(&self) -> Duration {
+        self.lookahead
+    }
+
+    /// 修改预发送时长（下次补发时生效）
+    pub fn set_lookahead(&mut self, lookahead: Duration) {
+        self.lookahead = lookahead.clamp(PULSE_FRAME_DURATION, MAX_PULSE_LOOKAHEAD);
+    }
+
+    /// APP 队列中预计尚未播放的时长
+    pub fn buffered(&self, now: Instant) -> Duration {
+        self.queued_until
+            .map(|until| until.saturating_duration_since(now))
+            .unwrap_or_default()
+    }
+
+    /// 计算此刻需要补发的帧
+    ///
+    /// 队列已播放完（发生断流）时从 `now` 重新计时，返回的帧数使缓冲覆盖到 `now + lookahead`。
+    pub fn top_up(&mut self, now: Instant) -> Vec<String> {
+        if self.pulses.is_empty() {
+            return Vec::new();
+        }
+
+        let start = self
+            .queued_until
+            .filter(|until| *until > now)
+            .unwrap_or(now);
+        let missing = (now + self.lookahead).saturating_duration_since(start);
+        let frame_ms = PULSE_FRAME_DURATION.as_millis();
+        let count = missing.as_millis().div_ceil(frame_ms) as usize;
+        if count == 0 {
+            return Vec::new();
+        }
+
+        let pulses = (0..count)
+            .map(|i| self.pulses[(self.cursor + i) % self.pulses.len()].clone())
+            .collect();
+        self.cursor = (self.cursor + count) % self.pulses.len();
+        self.queued_until = Some(start + PULSE_FRAME_DURATION * count as u32);
+        pulses
+    }
+
+    /// APP 队列已清空，下次补发从第一帧开始重新填充
+    pub fn reset(&mut self) {
+        self.cursor = 0;
+        self.queued_until = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pulses(n: usize) -> Vec<String> {
+        (0..n).map(|i| format!("{:016x}", i)).collect()
+    }
+
+    #[test]
+    fn test_initial_fill() {
+        let now = Instant::now();
+        let mut scheduler = PulseScheduler::new(pulses(3), DEFAULT_PULSE_LOOKAHEAD);
+
+        let sent = scheduler.top_up(now);
+        assert_eq!(sent.len(), 5);
+        // 波形循环取帧
+        assert_eq!(sent[3], sent[0]);
+        assert_eq!(scheduler.buffered(now), DEFAULT_PULSE_LOOKAHEAD);
+
+        // 缓冲已满时不补发
+        assert!(scheduler.top_up(now + Duration::from_millis(50)).is_empty());
+    }
+
+    #[test]
+    fn test_top_up_follows_clock() {
+        let now = Instant::now();
+        let mut scheduler = PulseScheduler::new(pulses(3), DEFAULT_PULSE_LOOKAHEAD);
+        let _ = scheduler.top_up(now);
+
+        let sent = scheduler.top_up(now + Duration::from_millis(200));
+        assert_eq!(sent, vec![pulses(3)[2].clone(), pulses(3)[0].clone()]);
+        assert_eq!(
+            scheduler.buffered(now + Duration::from_millis(200)),
+            DEFAULT_PULSE_LOOKAHEAD
+        );
+    }
+
+    #[test]
+    fn test_underrun_restarts_clock() {
+        let now = Instant::now();
+        let mut scheduler = PulseScheduler::new(pulses(1), DEFAULT_PULSE_LOOKAHEAD);
+        let _ = scheduler.top_up(now);
+
+        let later = now + Duration::from_secs(3);
+        assert_eq!(scheduler.buffered(later), Duration::ZERO);
+        assert_eq!(scheduler.top_up(later).len(), 5);
+        assert_eq!(scheduler.buffered(later), DEFAULT_PULSE_LOOKAHEAD);
+    }
+
+    #[test]
+    fn test_reset_and_lookahead() {
+        let now = Instant::now();
+        let mut scheduler = PulseScheduler::new(pulses(4), Duration::ZERO);
+        assert_eq!(scheduler.lookahead(), PULSE_FRAME_DURATION);
+        assert_eq!(scheduler.top_up(now), vec![pulses(4)[0].clone()]);
+
+        scheduler.set_lookahead(Duration::from_secs(60));
+        assert_eq!(scheduler.lookahead(), MAX_PULSE_LOOKAHEAD);
+
+        scheduler.reset();
+        let sent = scheduler.top_up(now);
+        assert_eq!(sent.len(), 100);
+        assert_eq!(sent[0], pulses(4)[0]);
+    }
+
+    #[test]
+    fn test_empty_pulses() {
+        let mut scheduler = PulseScheduler::new(Vec::new(), DEFAULT_PULSE_LOOKAHEAD);
+        assert!(scheduler.top_up(Instant::now()).is_empty());
+    }
+}
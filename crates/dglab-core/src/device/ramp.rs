@@ -0,0 +1,382 @@
+//! 强度渐变
+//!
+//! 在给定时长内按缓动曲线把通道强度从当前值过渡到目标值。
+//! 每个 tick 只在强度变化时调用 `set_power`：V3 蓝牙设备的写入合并到 100ms 的 B0 周期，
+//! WiFi 设备对应一次 APP 强度操作。丢弃 [`ramp_power`] 返回的 future 即取消渐变，
+//! 强度停留在取消时的值。
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+use tokio::time::{Instant, MissedTickBehavior};
+use tracing::debug;
+
+use super::{set_power_linked, Device, PowerCurve};
+use crate::error::{CoreError, Result};
+
+/// 渐变步进间隔，与 V3 协议 B0 输出周期一致
+pub const RAMP_TICK: Duration = Duration::from_millis(100);
+
+/// 缓动曲线
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Easing {
+    /// 匀速
+    #[default]
+    Linear,
+    /// 先慢后快
+    EaseIn,
+    /// 先快后慢
+    EaseOut,
+    /// 两端慢中间快
+    EaseInOut,
+}
+
+impl Easing {
+    /// 将进度 `t` (0.0~1.0) 映射为曲线上的进度
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Self::Linear => t,
+            Self::EaseIn => t * t,
+            Self::EaseOut => t * (2.0 - t),
+            Self::EaseInOut => {
+                if t < 0.5 {
+                    2.0 * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(2) / 2.0
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for Easing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Linear => "linear",
+            Self::EaseIn => "ease-in",
+            Self::EaseOut => "ease-out",
+            Self::EaseInOut => "ease-in-out",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for Easing {
+    type Err = CoreError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().replace('_', "-").as_str() {
+            "linear" => Ok(Self::Linear),
+            "ease-in" => Ok(Self::EaseIn),
+            "ease-out" => Ok(Self::EaseOut),
+            "ease-in-out" => Ok(Self::EaseInOut),
+            _ => Err(CoreError::InvalidParameter(format!(
+                "Invalid easing: {}",
+                s
+            ))),
+        }
+    }
+}
+
+/// 单通道强度渐变
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PowerRamp {
+    /// 起始强度
+    pub from: u8,
+    /// 目标强度
+    pub target: u8,
+    /// 渐变时长
+    pub duration: Duration,
+    /// 缓动曲线
+    pub easing: Easing,
+}
+
+impl PowerRamp {
+    /// 创建渐变
+    pub fn new(from: u8, target: u8, duration: Duration, easing: Easing) -> Self {
+        Self {
+            from,
+            target,
+            duration,
+            easing,
+        }
+    }
+
+    /// 计算经过 `elapsed` 后的强度
+    pub fn value_at(&self, elapsed: Duration) -> u8 {
+        if self.is_finished(elapsed) {
+            return self.target;
+        }
+
+        let t = elapsed.as_secs_f32() / self.duration.as_secs_f32();
+        let delta = (self.target as f32 - self.from as f32) * self.easing.apply(t);
+        (self.from as f32 + delta).round().clamp(0.0, 255.0) as u8
+    }
+
+    /// 经过 `elapsed` 后渐变是否已结束
+    pub fn is_finished(&self, elapsed: Duration) -> bool {
+        elapsed >= self.duration
+    }
+}
+
+/// 渐变参数
+#[derive(Debug, Clone)]
+pub struct RampSpec {
+    /// 通道
+    pub channel: u8,
+    /// 目标强度（有校准曲线时为逻辑百分比）
+    pub target: u8,
+    /// 渐变时长
+    pub duration: Duration,
+    /// 缓动曲线
+    pub easing: Easing,
+    /// 校准曲线，为空时按原始强度渐变
+    pub curve: Option<PowerCurve>,
+    /// 渐变 A 通道且开启联动时 B 通道的上限（如会话的安全限制），同时不超过设备上限
+    pub max_linked_power: u8,
+}
+
+impl RampSpec {
+    /// 按原始强度渐变，联动的 B 通道只受设备上限约束
+    pub fn new(channel: u8, target: u8, duration: Duration, easing: Easing) -> Self {
+        Self {
+            channel,
+            target,
+            duration,
+            easing,
+            curve: None,
+            max_linked_power: u8::MAX,
+        }
+    }
+
+    /// 按校准曲线渐变（`None` 时按原始强度）
+    pub fn with_curve(mut self, curve: Option<PowerCurve>) -> Self {
+        self.curve = curve;
+        self
+    }
+
+    /// 限制联动的 B 通道强度
+    pub fn with_max_linked_power(mut self, max_power: u8) -> Self {
+        self.max_linked_power = max_power;
+        self
+    }
+}
+
+/// 在 `duration` 内把通道强度渐变到 `target`
+///
+/// 目标强度按通道最大强度截断。每步写入后以设备引用调用 `on_step`（持有写锁），
+/// 可用于向前端同步强度；步与步之间不持有锁。返回最终强度。
+pub async fn ramp_power<F>(
+    device: &RwLock<Box<dyn Device>>,
+    channel: u8,
+    target: u8,
+    duration: Duration,
+    easing: Easing,
+    on_step: F,
+) -> Result<u8>
+where
+    F: FnMut(&dyn Device) + Send,
+{
+    ramp_power_with(
+        device,
+        &RampSpec::new(channel, target, duration, easing),
+        on_step,
+    )
+    .await
+}
+
+/// 按校准曲线渐变：`target` 为逻辑百分比，渐变在百分比上进行，每步换算为原始强度
+///
+/// 起点由当前原始强度反算。返回最终原始强度。
+pub async fn ramp_power_calibrated<F>(
+    device: &RwLock<Box<dyn Device>>,
+    channel: u8,
+    target: u8,
+    duration: Duration,
+    easing: Easing,
+    curve: &PowerCurve,
+    on_step: F,
+) -> Result<u8>
+where
+    F: FnMut(&dyn Device) + Send,
+{
+    let spec = RampSpec::new(channel, target, duration, easing).with_curve(Some(curve.clone()));
+    ramp_power_with(device, &spec, on_step).await
+}
+
+/// 按 `spec` 渐变，A 通道开启联动时每步同步 B 通道（见 [`set_power_linked`]）
+///
+/// 返回最终原始强度。
+pub async fn ramp_power_with<F>(
+    device: &RwLock<Box<dyn Device>>,
+    spec: &RampSpec,
+    mut on_step: F,
+) -> Result<u8>
+where
+    F: FnMut(&dyn Device) + Send,
+{
+    let RampSpec {
+        channel,
+        target,
+        duration,
+        easing,
+        ref curve,
+        max_linked_power,
+    } = *spec;
+    if channel > 1 {
+        return Err(CoreError::InvalidChannel(channel));
+    }
+
+    let to_raw = |value: u8| curve.as_ref().map_or(value, |curve| curve.to_raw(value));
+    let (ramp, max_power) = {
+        let dev = device.read().await;
+        let info = dev.info();
+        let max_power = if channel == 0 {
+            info.max_power_a
+        } else {
+            info.max_power_b
+        };
+        let current = dev.get_power(channel);
+        let ramp = match curve {
+            Some(curve) => PowerRamp::new(curve.to_percent(current), target, duration, easing),
+            None => PowerRamp::new(current, target.min(max_power), duration, easing),
+        };
+        (ramp, max_power)
+    };
+    debug!("Ramping channel {}: {:?}", channel, ramp);
+
+    let mut ticker = tokio::time::interval(RAMP_TICK);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let started = Instant::now();
+    let mut last = device.read().await.get_power(channel);
+
+    loop {
+        let _ = ticker.tick().await;
+        let elapsed = started.elapsed();
+        let power = to_raw(ramp.value_at(elapsed)).min(max_power);
+
+        if power != last {
+            let mut dev = device.write().await;
+            set_power_linked(&mut **dev, channel, power, max_linked_power).await?;
+            on_step(&**dev);
+            last = power;
+        }
+
+        if ramp.is_finished(elapsed) {
+            return Ok(last);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device::MockDevice;
+
+    #[test]
+    fn test_easing_curves() {
+        for easing in [
+            Easing::Linear,
+            Easing::EaseIn,
+            Easing::EaseOut,
+            Easing::EaseInOut,
+        ] {
+            assert_eq!(easing.apply(0.0), 0.0);
+            assert_eq!(easing.apply(1.0), 1.0);
+            assert_eq!(easing.apply(2.0), 1.0);
+        }
+        assert!(Easing::EaseIn.apply(0.5) < 0.5);
+        assert!(Easing::EaseOut.apply(0.5) > 0.5);
+        assert_eq!(Easing::EaseInOut.apply(0.5), 0.5);
+    }
+
+    #[test]
+    fn test_easing_from_str() {
+        assert_eq!("ease-in".parse::<Easing>().unwrap(), Easing::EaseIn);
+        assert_eq!("EASE_IN_OUT".parse::<Easing>().unwrap(), Easing::EaseInOut);
+        assert!("bounce".parse::<Easing>().is_err());
+        assert_eq!(Easing::EaseOut.to_string(), "ease-out");
+    }
+
+    #[test]
+    fn test_power_ramp_value() {
+        let ramp = PowerRamp::new(10, 50, Duration::from_secs(2), Easing::Linear);
+        assert_eq!(ramp.value_at(Duration::ZERO), 10);
+        assert_eq!(ramp.value_at(Duration::from_secs(1)), 30);
+        assert_eq!(ramp.value_at(Duration::from_secs(3)), 50);
+
+        // 向下渐变
+        let ramp = PowerRamp::new(50, 0, Duration::from_secs(1), Easing::Linear);
+        assert_eq!(ramp.value_at(Duration::from_millis(500)), 25);
+
+        // 零时长立即到达目标
+        let ramp = PowerRamp::new(0, 80, Duration::ZERO, Easing::EaseIn);
+        assert_eq!(ramp.value_at(Duration::ZERO), 80);
+    }
+
+    #[tokio::test]
+    async fn test_ramp_power() {
+        let mut dev: Box<dyn Device> = Box::new(MockDevice::new(
+            "mock-ramp".to_string(),
+            "Mock Ramp".to_string(),
+        ));
+        dev.connect().await.unwrap();
+        let device = RwLock::new(dev);
+
+        let mut steps = Vec::new();
+        let power = ramp_power(
+            &device,
+            0,
+            150,
+            Duration::from_millis(300),
+            Easing::Linear,
+            |dev| steps.push(dev.get_power(0)),
+        )
+        .await
+        .unwrap();
+
+        // 目标按最大强度截断，强度单调上升
+        assert_eq!(power, 100);
+        assert_eq!(device.read().await.get_power(0), 100);
+        assert!(steps.windows(2).all(|w| w[0] < w[1]));
+
+        let result = ramp_power(&device, 2, 0, Duration::ZERO, Easing::Linear, |_| {}).await;
+        assert!(matches!(result, Err(CoreError::InvalidChannel(2))));
+    }
+
+    #[tokio::test]
+    async fn test_ramp_power_calibrated() {
+        let mut dev: Box<dyn Device> = Box::new(MockDevice::new(
+            "mock-ramp".to_string(),
+            "Mock Ramp".to_string(),
+        ));
+        dev.connect().await.unwrap();
+        dev.set_power(0, 10).await.unwrap();
+        let device = RwLock::new(dev);
+
+        let curve = PowerCurve::new(vec![(0, 0), (50, 20), (100, 80)]).unwrap();
+        let mut steps = Vec::new();
+        let power = ramp_power_calibrated(
+            &device,
+            0,
+            100,
+            Duration::from_millis(300),
+            Easing::Linear,
+            &curve,
+            |dev| steps.push(dev.get_power(0)),
+        )
+        .await
+        .unwrap();
+
+        // 起点 10 对应 25%，终点 100% 换算为原始强度 80
+        assert_eq!(power, 80);
+        assert_eq!(steps.last(), Some(&80));
+        assert!(steps.iter().all(|&p| p > 10));
+        assert!(steps.windows(2).all(|w| w[0] < w[1]));
+    }
+}
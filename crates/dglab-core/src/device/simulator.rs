@@ -0,0 +1,860 @@
+//! Coyote V3 设备模拟器
+//!
+//! 在没有硬件的情况下模拟 V3 协议主机：按字节处理 B0/BF 指令、
+//! 以相同序列号返回 B1 反馈、按软上限截断强度，并按输出模拟电量消耗。
+//! [`MockCoyoteDevice`] 复用 [`CoyoteDevice`](super::CoyoteDevice) 的输出状态和
+//! B1 校正逻辑，只是把 BLE 链路换成了 [`V3Simulator`]，用于 GUI/CLI 开发和集成测试。
+
+use std::any::Any;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, MutexGuard};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tokio::sync::broadcast;
+use tracing::{debug, info};
+
+use dglab_protocol::ble::ScanResult;
+use dglab_protocol::v3::{
+    B0Command, B1Response, BFCommand, ChannelStrengthMode, StrengthMode, WaveformData, B0_HEAD,
+    BF_HEAD, MAX_STRENGTH,
+};
+
+use super::coyote::{FrameCycle, V3OutputState};
+use super::supervisor::RestartPolicy;
+use super::traits::{ChannelLink, Device, DeviceInfo, DeviceLimits, LatencyStats, WaveformConfig};
+use super::{BaseDevice, CoyoteDevice, DeviceEvent, DeviceState};
+use crate::error::{CoreError, Result};
+
+/// 模拟设备 ID 前缀
+pub const SIMULATED_DEVICE_PREFIX: &str = "sim-";
+
+/// 默认每消耗 1% 电量所需的输出量
+///
+/// 输出量按每帧 `强度 × 平均波形强度` 累计，单通道满输出（200 × 100）
+/// 每秒 10 帧，约 1 分钟消耗 1%。
+pub const DEFAULT_DRAIN_PER_PERCENT: u64 = 200 * 100 * 10 * 60;
+
+/// 生成模拟扫描结果
+///
+/// 设备 ID 为 `sim-1`、`sim-2`……，可直接用于 [`MockCoyoteDevice::new`]。
+pub fn simulated_devices(count: usize) -> Vec<ScanResult> {
+    (1..=count)
+        .map(|n| ScanResult {
+            id: format!("{}{}", SIMULATED_DEVICE_PREFIX, n),
+            name: format!("47L12{:04} (simulated)", n - 1),
+            address: format!("00:00:00:00:00:{:02X}", n),
+            rssi: Some(-40),
+            adapter: "simulator".to_string(),
+        })
+        .collect()
+}
+
+/// 是否为模拟设备 ID
+pub fn is_simulated_id(id: &str) -> bool {
+    id.starts_with(SIMULATED_DEVICE_PREFIX)
+}
+
+// ============================================================================
+// V3 协议主机模拟
+// ============================================================================
+
+/// V3 协议主机模拟器
+///
+/// 只模拟主机对指令的可观察行为，不涉及时序：每次调用 [`handle_write`](Self::handle_write)
+/// 相当于主机收到一条写入。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct V3Simulator {
+    /// A 通道实际强度
+    strength_a: u8,
+    /// B 通道实际强度
+    strength_b: u8,
+    /// 当前 BF 配置
+    bf: BFCommand,
+    /// 最近一帧 A 通道波形
+    waveform_a: WaveformData,
+    /// 最近一帧 B 通道波形
+    waveform_b: WaveformData,
+    /// 电量 (0~100)
+    battery_level: u8,
+    /// 未结算的输出量
+    drained: u64,
+    /// 每消耗 1% 电量所需的输出量
+    drain_per_percent: u64,
+}
+
+impl Default for V3Simulator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl V3Simulator {
+    /// 创建满电、强度为 0 的模拟主机
+    pub fn new() -> Self {
+        Self {
+            strength_a: 0,
+            strength_b: 0,
+            bf: BFCommand::default_config(),
+            waveform_a: WaveformData::silent(),
+            waveform_b: WaveformData::silent(),
+            battery_level: 100,
+            drained: 0,
+            drain_per_percent: DEFAULT_DRAIN_PER_PERCENT,
+        }
+    }
+
+    /// 设置电量消耗速度（每 1% 电量对应的输出量，0 表示不耗电）
+    pub fn with_drain_per_percent(mut self, drain_per_percent: u64) -> Self {
+        self.drain_per_percent = drain_per_percent;
+        self
+    }
+
+    /// 当前实际强度 (A, B)
+    pub fn strength(&self) -> (u8, u8) {
+        (self.strength_a, self.strength_b)
+    }
+
+    /// 当前 BF 配置
+    pub fn limits(&self) -> &BFCommand {
+        &self.bf
+    }
+
+    /// 最近一帧波形 (A, B)
+    pub fn waveform(&self) -> (WaveformData, WaveformData) {
+        (self.waveform_a, self.waveform_b)
+    }
+
+    /// 当前电量
+    pub fn battery_level(&self) -> u8 {
+        self.battery_level
+    }
+
+    /// 设置电量
+    pub fn set_battery_level(&mut self, level: u8) {
+        self.battery_level = level.min(100);
+        self.drained = 0;
+    }
+
+    /// 处理一次写入，强度发生变化或指令带序列号时返回 B1 反馈
+    ///
+    /// 无法识别或长度不足的数据被忽略，与真实主机一致。
+    pub fn handle_write(&mut self, data: &[u8]) -> Option<B1Response> {
+        match data.first() {
+            Some(&B0_HEAD) => B0Command::decode(data).and_then(|cmd| self.handle_b0(&cmd)),
+            Some(&BF_HEAD) => BFCommand::decode(data).and_then(|cmd| self.handle_bf(&cmd)),
+            _ => {
+                debug!("Simulator ignored write: {:02x?}", data);
+                None
+            }
+        }
+    }
+
+    /// 处理 B0 指令
+    pub fn handle_b0(&mut self, cmd: &B0Command) -> Option<B1Response> {
+        let before = self.strength();
+
+        self.strength_a = apply_strength(
+            cmd.strength_mode.channel_a,
+            self.strength_a,
+            cmd.strength_a,
+            self.bf.soft_limit_a,
+        );
+        self.strength_b = apply_strength(
+            cmd.strength_mode.channel_b,
+            self.strength_b,
+            cmd.strength_b,
+            self.bf.soft_limit_b,
+        );
+
+        self.waveform_a = cmd.waveform_a;
+        self.waveform_b = cmd.waveform_b;
+        self.drain();
+
+        (cmd.sequence != 0 || self.strength() != before).then(|| self.b1(cmd.sequence))
+    }
+
+    /// 处理 BF 指令，当前强度超过新软上限时下调并返回 B1 反馈
+    pub fn handle_bf(&mut self, cmd: &BFCommand) -> Option<B1Response> {
+        let before = self.strength();
+
+        self.bf = BFCommand {
+            soft_limit_a: cmd.soft_limit_a.min(MAX_STRENGTH),
+            soft_limit_b: cmd.soft_limit_b.min(MAX_STRENGTH),
+            ..cmd.clone()
+        };
+        self.strength_a = self.strength_a.min(self.bf.soft_limit_a);
+        self.strength_b = self.strength_b.min(self.bf.soft_limit_b);
+
+        (self.strength() != before).then(|| self.b1(0))
+    }
+
+    fn b1(&self, sequence: u8) -> B1Response {
+        B1Response {
+            sequence,
+            strength_a: self.strength_a,
+            strength_b: self.strength_b,
+        }
+    }
+
+    /// 按本帧输出累计耗电
+    fn drain(&mut self) {
+        if self.drain_per_percent == 0 {
+            return;
+        }
+
+        // 无效波形帧被主机整帧丢弃，不产生输出
+        let output = |strength: u8, waveform: &WaveformData| {
+            if !waveform.is_valid() {
+                return 0;
+            }
+            let intensity: u64 = waveform.intensity.iter().map(|&i| u64::from(i)).sum();
+            u64::from(strength) * intensity / 4
+        };
+        self.drained +=
+            output(self.strength_a, &self.waveform_a) + output(self.strength_b, &self.waveform_b);
+
+        while self.drained >= self.drain_per_percent && self.battery_level > 0 {
+            self.drained -= self.drain_per_percent;
+            self.battery_level -= 1;
+        }
+    }
+}
+
+/// 按解读方式计算新强度，结果不超过软上限
+fn apply_strength(mode: ChannelStrengthMode, current: u8, value: u8, limit: u8) -> u8 {
+    let strength = match mode {
+        ChannelStrengthMode::NoChange => return current,
+        ChannelStrengthMode::Increase => current.saturating_add(value),
+        ChannelStrengthMode::Decrease => current.saturating_sub(value),
+        ChannelStrengthMode::Absolute => value,
+    };
+    strength.min(limit)
+}
+
+// ============================================================================
+// 模拟 Coyote 设备
+// ============================================================================
+
+/// 模拟 Coyote V3 设备
+///
+/// 与 [`CoyoteDevice`] 行为一致：连接时写入 BF，启动后每 100ms 发送 B0，
+/// 根据 B1 反馈校正强度并发出 `PowerRejected` / `StatusReport` 事件，电量变化时发出
+/// `BatteryUpdated`。
+pub struct MockCoyoteDevice {
+    /// 基础设备
+    base: BaseDevice,
+    /// 模拟主机
+    simulator: Arc<std::sync::Mutex<V3Simulator>>,
+    /// V3 协议共享输出状态
+    output_state: Arc<V3OutputState>,
+    /// BF 配置（软上限与平衡参数）
+    bf_config: BFCommand,
+    /// 100ms 输出循环任务
+    output_task: Option<tokio::task::JoinHandle<()>>,
+}
+
+impl MockCoyoteDevice {
+    /// 创建新的模拟设备
+    pub fn new(id: String, name: String) -> Self {
+        Self::with_simulator(id, name, V3Simulator::new())
+    }
+
+    /// 使用指定的模拟主机创建设备
+    pub fn with_simulator(id: String, name: String, simulator: V3Simulator) -> Self {
+        let base = BaseDevice::with_max_power(id, name, MAX_STRENGTH);
+        let output_state = Arc::new(V3OutputState::with_telemetry(base.telemetry().clone()));
+        Self {
+            base,
+            simulator: Arc::new(std::sync::Mutex::new(simulator)),
+            output_state,
+            bf_config: BFCommand::default_config(),
+            output_task: None,
+        }
+    }
+
+    /// 模拟主机当前状态的快照
+    pub fn simulator(&self) -> V3Simulator {
+        self.lock_simulator().clone()
+    }
+
+    /// 设置模拟主机电量
+    pub fn set_battery_level(&self, level: u8) {
+        self.lock_simulator().set_battery_level(level);
+        self.base
+            .send_event(DeviceEvent::BatteryUpdated(level.min(100)));
+    }
+
+    fn lock_simulator(&self) -> MutexGuard<'_, V3Simulator> {
+        lock(&self.simulator)
+    }
+
+    /// 向模拟主机写入数据并处理反馈
+    async fn write(
+        simulator: &std::sync::Mutex<V3Simulator>,
+        state: &V3OutputState,
+        event_tx: &broadcast::Sender<DeviceEvent>,
+        data: &[u8],
+    ) {
+        let (response, battery_before, battery_after) = {
+            let mut simulator = lock(simulator);
+            let battery_before = simulator.battery_level();
+            let response = simulator.handle_write(data);
+            (response, battery_before, simulator.battery_level())
+        };
+
+        if let Some(response) = response {
+            CoyoteDevice::handle_b1_response(state, &response, event_tx).await;
+        }
+        if battery_after != battery_before {
+            let _ = event_tx.send(DeviceEvent::BatteryUpdated(battery_after));
+        }
+    }
+
+    /// 启动 100ms B0 输出循环
+    fn start_output_loop(&mut self) {
+        let simulator = self.simulator.clone();
+        let state = self.output_state.clone();
+        let event_tx = self.base.event_tx.clone();
+        let supervisor = self.base.supervisor();
+
+        let handle = supervisor.spawn("B0 output", RestartPolicy::default(), move || {
+            let simulator = simulator.clone();
+            let state = state.clone();
+            let event_tx = event_tx.clone();
+            async move {
+                let mut interval = tokio::time::interval(Duration::from_millis(100));
+
+                loop {
+                    let _ = interval.tick().await;
+
+                    let data = state.build_b0().await.encode();
+                    state.telemetry.record_b0_frame();
+                    Self::write(&simulator, &state, &event_tx, &data).await;
+                }
+            }
+        });
+
+        self.output_task = Some(handle);
+    }
+
+    /// 停止输出循环
+    fn stop_output_loop(&mut self) {
+        if let Some(handle) = self.output_task.take() {
+            handle.abort();
+        }
+    }
+
+    /// 写入 BF 配置
+    async fn send_bf_config(&self) {
+        Self::write(
+            &self.simulator,
+            &self.output_state,
+            &self.base.event_tx,
+            &self.bf_config.encode(),
+        )
+        .await;
+    }
+}
+
+fn lock(simulator: &std::sync::Mutex<V3Simulator>) -> MutexGuard<'_, V3Simulator> {
+    simulator
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[async_trait]
+impl Device for MockCoyoteDevice {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn id(&self) -> &str {
+        self.base.id()
+    }
+
+    fn name(&self) -> &str {
+        self.base.name()
+    }
+
+    fn state(&self) -> DeviceState {
+        self.base.state()
+    }
+
+    fn info(&self) -> DeviceInfo {
+        DeviceInfo {
+            id: self.base.id().to_string(),
+            name: self.base.name().to_string(),
+            device_type: "Coyote V3 (simulated)".to_string(),
+            firmware_version: "sim".to_string(),
+            hardware_version: "sim".to_string(),
+            battery_level: self.lock_simulator().battery_level(),
+            battery_remaining_secs: None,
+            power_a: self.output_state.target_strength_a.load(Ordering::Relaxed),
+            power_b: self.output_state.target_strength_b.load(Ordering::Relaxed),
+            max_power_a: self.bf_config.soft_limit_a,
+            max_power_b: self.bf_config.soft_limit_b,
+            enabled_a: self.base.channel_enabled(0),
+            enabled_b: self.base.channel_enabled(1),
+        }
+    }
+
+    async fn connect(&mut self) -> Result<()> {
+        info!("Connecting to simulated Coyote: {}", self.base.id());
+
+        if self.base.state() == DeviceState::Connected {
+            return Ok(());
+        }
+
+        self.base.set_state(DeviceState::Connecting);
+        self.send_bf_config().await;
+        self.base.set_state(DeviceState::Connected);
+
+        let battery_level = self.lock_simulator().battery_level();
+        self.base
+            .send_event(DeviceEvent::BatteryUpdated(battery_level));
+
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> Result<()> {
+        info!("Disconnecting simulated Coyote: {}", self.base.id());
+
+        self.stop_output_loop();
+        self.base.set_state(DeviceState::Disconnected);
+
+        Ok(())
+    }
+
+    async fn start(&mut self) -> Result<()> {
+        if self.base.state() != DeviceState::Connected {
+            return Err(CoreError::DeviceNotConnected);
+        }
+
+        self.start_output_loop();
+        self.base.set_state(DeviceState::Running);
+
+        Ok(())
+    }
+
+    async fn stop(&mut self) -> Result<()> {
+        if self.base.state() != DeviceState::Running {
+            return Ok(());
+        }
+
+        self.stop_output_loop();
+
+        // 与真实设备一致：停止时强度归零并静默
+        self.output_state
+            .target_strength_a
+            .store(0, Ordering::Relaxed);
+        self.output_state
+            .target_strength_b
+            .store(0, Ordering::Relaxed);
+        for channel in [0, 1] {
+            self.base.set_power(channel, 0)?;
+        }
+        *self.output_state.waveform_a.lock().await = FrameCycle::single(WaveformData::silent());
+        *self.output_state.waveform_b.lock().await = FrameCycle::single(WaveformData::silent());
+        let data = B0Command {
+            sequence: 0,
+            strength_mode: StrengthMode::new(
+                ChannelStrengthMode::Absolute,
+                ChannelStrengthMode::Absolute,
+            ),
+            strength_a: 0,
+            strength_b: 0,
+            waveform_a: WaveformData::silent(),
+            waveform_b: WaveformData::silent(),
+        }
+        .encode();
+        Self::write(
+            &self.simulator,
+            &self.output_state,
+            &self.base.event_tx,
+            &data,
+        )
+        .await;
+
+        self.base.set_state(DeviceState::Connected);
+
+        Ok(())
+    }
+
+    async fn set_power(&mut self, channel: u8, power: u8) -> Result<()> {
+        let limit = match channel {
+            0 => self.bf_config.soft_limit_a,
+            1 => self.bf_config.soft_limit_b,
+            _ => return Err(CoreError::InvalidChannel(channel)),
+        };
+
+        if power > limit {
+            return Err(CoreError::PowerOutOfRange(power, limit));
+        }
+
+        // 禁用的通道始终为 0
+        let power = self.base.gate_power(channel, power);
+        let (target, pending) = match channel {
+            0 => (
+                &self.output_state.target_strength_a,
+                &self.output_state.pending_strength_a,
+            ),
+            _ => (
+                &self.output_state.target_strength_b,
+                &self.output_state.pending_strength_b,
+            ),
+        };
+        target.store(power, Ordering::Relaxed);
+        pending.store(true, Ordering::Relaxed);
+        self.base.set_power(channel, power)?;
+
+        Ok(())
+    }
+
+    fn get_power(&self, channel: u8) -> u8 {
+        self.output_state.strength(channel)
+    }
+
+    async fn adjust_power(&mut self, channel: u8, delta: i16) -> Result<u8> {
+        let limit = match channel {
+            0 => self.bf_config.soft_limit_a,
+            1 => self.bf_config.soft_limit_b,
+            _ => return Err(CoreError::InvalidChannel(channel)),
+        };
+
+        let power = self.output_state.adjust_strength(channel, delta, limit)?;
+        self.base.set_power(channel, power)?;
+
+        Ok(power)
+    }
+
+    async fn set_max_power(&mut self, channel: u8, max_power: u8) -> Result<()> {
+        if max_power > MAX_STRENGTH {
+            return Err(CoreError::PowerOutOfRange(max_power, MAX_STRENGTH));
+        }
+
+        match channel {
+            0 => self.bf_config.soft_limit_a = max_power,
+            1 => self.bf_config.soft_limit_b = max_power,
+            _ => return Err(CoreError::InvalidChannel(channel)),
+        }
+
+        if self.get_power(channel) > max_power {
+            self.set_power(channel, max_power).await?;
+        }
+        self.base.set_max_power(channel, max_power)?;
+
+        if self.base.state() != DeviceState::Disconnected {
+            self.send_bf_config().await;
+        }
+
+        Ok(())
+    }
+
+    fn limits(&self) -> DeviceLimits {
+        DeviceLimits::new(
+            self.bf_config.soft_limit_a,
+            self.bf_config.soft_limit_b,
+            self.bf_config.freq_balance_a,
+            self.bf_config.intensity_balance_a,
+        )
+    }
+
+    async fn set_limits(&mut self, limits: DeviceLimits) -> Result<()> {
+        limits.validate()?;
+
+        self.bf_config = BFCommand {
+            soft_limit_a: limits.soft_limit_a,
+            soft_limit_b: limits.soft_limit_b,
+            freq_balance_a: limits.freq_balance,
+            freq_balance_b: limits.freq_balance,
+            intensity_balance_a: limits.intensity_balance,
+            intensity_balance_b: limits.intensity_balance,
+        };
+
+        for (channel, max_power) in [(0, limits.soft_limit_a), (1, limits.soft_limit_b)] {
+            if self.get_power(channel) > max_power {
+                self.set_power(channel, max_power).await?;
+            }
+        }
+        self.base.set_limits(limits)?;
+
+        if self.base.state() != DeviceState::Disconnected {
+            self.send_bf_config().await;
+        }
+
+        Ok(())
+    }
+
+    fn channel_link(&self) -> Option<ChannelLink> {
+        self.base.channel_link()
+    }
+
+    async fn set_channel_link(&mut self, link: Option<ChannelLink>) -> Result<()> {
+        self.base.set_channel_link(link)
+    }
+
+    fn channel_enabled(&self, channel: u8) -> bool {
+        self.base.channel_enabled(channel)
+    }
+
+    async fn set_channel_enabled(&mut self, channel: u8, enabled: bool) -> Result<()> {
+        self.output_state
+            .set_channel_enabled(channel, enabled)
+            .await?;
+        self.base.set_channel_enabled(channel, enabled)
+    }
+
+    async fn set_waveform(&mut self, channel: u8, config: WaveformConfig) -> Result<()> {
+        let waveform = FrameCycle::new(CoyoteDevice::waveform_config_to_frames(&config));
+
+        match channel {
+            0 => *self.output_state.waveform_a.lock().await = waveform,
+            1 => *self.output_state.waveform_b.lock().await = waveform,
+            _ => return Err(CoreError::InvalidChannel(channel)),
+        }
+
+        self.base
+            .send_event(DeviceEvent::WaveformChanged { channel });
+        Ok(())
+    }
+
+    async fn heartbeat(&mut self) -> Result<()> {
+        if self.base.state() == DeviceState::Disconnected {
+            return Err(CoreError::DeviceNotConnected);
+        }
+
+        if self.base.state() == DeviceState::Connected {
+            let data =
+                B0Command::waveform_only(WaveformData::silent(), WaveformData::silent()).encode();
+            Self::write(
+                &self.simulator,
+                &self.output_state,
+                &self.base.event_tx,
+                &data,
+            )
+            .await;
+        }
+
+        Ok(())
+    }
+
+    fn latency(&self) -> Option<LatencyStats> {
+        self.output_state.latency()
+    }
+
+    async fn probe_latency(&mut self) -> Result<()> {
+        if self.base.state() == DeviceState::Disconnected {
+            return Err(CoreError::DeviceNotConnected);
+        }
+
+        if self.output_task.is_some() {
+            self.output_state.request_probe();
+        } else {
+            let data = self.output_state.probe_b0().encode();
+            Self::write(
+                &self.simulator,
+                &self.output_state,
+                &self.base.event_tx,
+                &data,
+            )
+            .await;
+        }
+        Ok(())
+    }
+
+    fn subscribe_events(&self) -> broadcast::Receiver<DeviceEvent> {
+        self.base.subscribe_events()
+    }
+
+    fn send_event(&self, event: DeviceEvent) {
+        self.base.send_event(event);
+    }
+}
+
+impl Drop for MockCoyoteDevice {
+    fn drop(&mut self) {
+        self.stop_output_loop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strength_command(sequence: u8, mode: ChannelStrengthMode, value: u8) -> B0Command {
+        B0Command {
+            sequence,
+            strength_mode: StrengthMode::new(mode, ChannelStrengthMode::NoChange),
+            strength_a: value,
+            strength_b: 0,
+            waveform_a: WaveformData::silent(),
+            waveform_b: WaveformData::silent(),
+        }
+    }
+
+    #[test]
+    fn test_b0_returns_b1_with_sequence() {
+        let mut sim = V3Simulator::new();
+
+        let b1 = sim
+            .handle_write(&strength_command(5, ChannelStrengthMode::Absolute, 30).encode())
+            .unwrap();
+        assert_eq!(
+            b1,
+            B1Response {
+                sequence: 5,
+                strength_a: 30,
+                strength_b: 0
+            }
+        );
+
+        let b1 = sim
+            .handle_b0(&strength_command(6, ChannelStrengthMode::Increase, 5))
+            .unwrap();
+        assert_eq!((b1.sequence, b1.strength_a), (6, 35));
+
+        let b1 = sim
+            .handle_b0(&strength_command(7, ChannelStrengthMode::Decrease, 50))
+            .unwrap();
+        assert_eq!((b1.sequence, b1.strength_a), (7, 0));
+
+        // 无变化且无序列号时不反馈
+        assert!(sim
+            .handle_b0(&B0Command::waveform_only(
+                WaveformData::silent(),
+                WaveformData::silent()
+            ))
+            .is_none());
+    }
+
+    #[test]
+    fn test_soft_limit_clamps_strength() {
+        let mut sim = V3Simulator::new();
+        let _ = sim.handle_b0(&strength_command(1, ChannelStrengthMode::Absolute, 80));
+
+        let b1 = sim
+            .handle_bf(&BFCommand {
+                soft_limit_a: 50,
+                ..BFCommand::default_config()
+            })
+            .unwrap();
+        assert_eq!((b1.sequence, b1.strength_a), (0, 50));
+
+        let b1 = sim
+            .handle_b0(&strength_command(2, ChannelStrengthMode::Absolute, 120))
+            .unwrap();
+        assert_eq!((b1.sequence, b1.strength_a), (2, 50));
+        assert_eq!(sim.limits().soft_limit_a, 50);
+    }
+
+    #[test]
+    fn test_ignores_invalid_writes() {
+        let mut sim = V3Simulator::new();
+        assert!(sim.handle_write(&[]).is_none());
+        assert!(sim.handle_write(&[B0_HEAD, 0x1F]).is_none());
+        assert!(sim.handle_write(&[0xAA; 20]).is_none());
+        assert_eq!(sim, V3Simulator::new());
+    }
+
+    #[test]
+    fn test_battery_drain() {
+        let mut sim = V3Simulator::new().with_drain_per_percent(200 * 100);
+        let mut cmd = strength_command(0, ChannelStrengthMode::Absolute, 200);
+        cmd.waveform_a = WaveformData::uniform(100, 100);
+
+        let _ = sim.handle_b0(&cmd);
+        assert_eq!(sim.battery_level(), 99);
+
+        // 静默波形不耗电
+        cmd.waveform_a = WaveformData::silent();
+        let _ = sim.handle_b0(&cmd);
+        assert_eq!(sim.battery_level(), 99);
+
+        sim.set_battery_level(0);
+        cmd.waveform_a = WaveformData::uniform(100, 100);
+        let _ = sim.handle_b0(&cmd);
+        assert_eq!(sim.battery_level(), 0);
+    }
+
+    #[test]
+    fn test_simulated_devices() {
+        let devices = simulated_devices(2);
+        assert_eq!(devices.len(), 2);
+        assert_eq!(devices[0].id, "sim-1");
+        assert!(is_simulated_id(&devices[1].id));
+        assert!(!is_simulated_id("C1:23:45:67:89:AB"));
+    }
+
+    #[tokio::test]
+    async fn test_mock_coyote_power_feedback() {
+        let mut device = MockCoyoteDevice::new("sim-1".to_string(), "Sim".to_string());
+        let mut events = device.subscribe_events();
+
+        device.connect().await.unwrap();
+        device.set_max_power(0, 40).await.unwrap();
+        device.start().await.unwrap();
+        device.set_power(0, 30).await.unwrap();
+        assert!(device.set_power(0, 50).await.is_err());
+
+        // 等待输出循环发送 B0 并收到 B1
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        assert_eq!(device.simulator().strength(), (30, 0));
+        assert_eq!(device.get_power(0), 30);
+
+        let mut reported = false;
+        while let Ok(event) = events.try_recv() {
+            if let DeviceEvent::StatusReport { power_a: 30, .. } = event {
+                reported = true;
+            }
+        }
+        assert!(reported);
+
+        device.stop().await.unwrap();
+        assert_eq!(device.simulator().strength(), (0, 0));
+        assert_eq!(device.state(), DeviceState::Connected);
+    }
+
+    #[tokio::test]
+    async fn test_mock_coyote_probe_latency() {
+        let mut device = MockCoyoteDevice::new("sim-1".to_string(), "Sim".to_string());
+        assert!(device.probe_latency().await.is_err());
+
+        device.connect().await.unwrap();
+        device.probe_latency().await.unwrap();
+        assert_eq!(device.latency().unwrap().samples, 1);
+        assert_eq!(device.simulator().strength(), (0, 0));
+
+        device.start().await.unwrap();
+        device.probe_latency().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        assert_eq!(device.latency().unwrap().samples, 2);
+        device.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_mock_coyote_adjust_power() {
+        let mut device = MockCoyoteDevice::new("sim-1".to_string(), "Sim".to_string());
+        device.connect().await.unwrap();
+        device.set_max_power(1, 40).await.unwrap();
+        device.start().await.unwrap();
+
+        device.set_power(1, 20).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        assert_eq!(device.adjust_power(1, 5).await.unwrap(), 25);
+        assert_eq!(device.adjust_power(1, 50).await.unwrap(), 40);
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        assert_eq!(device.simulator().strength(), (0, 40));
+
+        assert_eq!(device.adjust_power(1, -15).await.unwrap(), 25);
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        assert_eq!(device.simulator().strength(), (0, 25));
+        assert_eq!(device.get_power(1), 25);
+
+        device.stop().await.unwrap();
+    }
+}
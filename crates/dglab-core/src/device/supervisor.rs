@@ -0,0 +1,230 @@
+//! 后台任务监督
+//!
+//! 设备的输出、接收、心跳等后台任务通过 [`TaskSupervisor::spawn`] 启动。任务 panic 时
+//! 捕获 panic 并记录日志，发送带任务名称和 panic 信息的 [`DeviceEvent::Error`]，再按
+//! [`RestartPolicy`] 决定是否重新启动。任务正常结束（如设备断开后主动退出）时不重启。
+//!
+//! 任务在监督任务内部运行，对返回的 [`JoinHandle`] 调用 `abort` 会同时停止任务本身，
+//! 与直接 `tokio::spawn` 的用法一致。
+
+use std::any::Any;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::time::Duration;
+
+use futures::FutureExt;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tracing::{error, warn};
+
+use super::DeviceEvent;
+
+/// 默认最多重启次数
+pub const DEFAULT_MAX_RESTARTS: u32 = 3;
+
+/// 默认重启前的等待时间
+pub const DEFAULT_RESTART_DELAY: Duration = Duration::from_millis(500);
+
+/// 任务 panic 后的重启策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestartPolicy {
+    /// 不重启
+    Never,
+    /// 重启，超过次数后不再重启
+    Restart {
+        /// 最多重启次数
+        max_restarts: u32,
+        /// 重启前的等待时间
+        delay: Duration,
+    },
+}
+
+impl RestartPolicy {
+    /// 最多重启 `max_restarts` 次，每次等待 [`DEFAULT_RESTART_DELAY`]
+    pub const fn restart(max_restarts: u32) -> Self {
+        Self::Restart {
+            max_restarts,
+            delay: DEFAULT_RESTART_DELAY,
+        }
+    }
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self::restart(DEFAULT_MAX_RESTARTS)
+    }
+}
+
+/// 设备后台任务监督器
+#[derive(Debug, Clone)]
+pub struct TaskSupervisor {
+    /// 设备 ID（写入日志和错误事件）
+    device_id: String,
+    /// 设备事件发送器
+    event_tx: broadcast::Sender<DeviceEvent>,
+}
+
+impl TaskSupervisor {
+    /// 创建监督器
+    pub fn new(device_id: impl Into<String>, event_tx: broadcast::Sender<DeviceEvent>) -> Self {
+        Self {
+            device_id: device_id.into(),
+            event_tx,
+        }
+    }
+
+    /// 启动受监督的任务
+    ///
+    /// `task` 每次（重新）启动时调用一次，生成新的任务 future。
+    pub fn spawn<F, Fut>(
+        &self,
+        name: &'static str,
+        policy: RestartPolicy,
+        task: F,
+    ) -> JoinHandle<()>
+    where
+        F: FnMut() -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let supervisor = self.clone();
+        tokio::spawn(supervisor.run(name, policy, task))
+    }
+
+    /// 运行任务直到正常结束或不再重启
+    async fn run<F, Fut>(self, name: &'static str, policy: RestartPolicy, mut task: F)
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = ()>,
+    {
+        let mut restarts = 0;
+        loop {
+            let Err(panic) = AssertUnwindSafe(task()).catch_unwind().await else {
+                return;
+            };
+
+            let message = format!("{} task panicked: {}", name, panic_message(&*panic));
+            error!("Device {}: {}", self.device_id, message);
+            let _ = self.event_tx.send(DeviceEvent::Error(message));
+
+            match policy {
+                RestartPolicy::Restart {
+                    max_restarts,
+                    delay,
+                } if restarts < max_restarts => {
+                    restarts += 1;
+                    warn!(
+                        "Restarting {} task of device {} ({}/{})",
+                        name, self.device_id, restarts, max_restarts
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                _ => return,
+            }
+        }
+    }
+}
+
+/// panic 信息（`panic!` 的参数为字符串时）
+fn panic_message(panic: &(dyn Any + Send)) -> &str {
+    panic
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+
+    fn supervisor() -> (TaskSupervisor, broadcast::Receiver<DeviceEvent>) {
+        let (event_tx, event_rx) = broadcast::channel(16);
+        (TaskSupervisor::new("test", event_tx), event_rx)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_restart_after_panic() {
+        let (supervisor, mut events) = supervisor();
+        let runs = Arc::new(AtomicU32::new(0));
+
+        let counter = runs.clone();
+        let handle = supervisor.spawn("output", RestartPolicy::restart(2), move || {
+            let counter = counter.clone();
+            async move {
+                let _ = counter.fetch_add(1, Ordering::SeqCst);
+                panic!("boom");
+            }
+        });
+        handle.await.unwrap();
+
+        // 首次运行加两次重启
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+        for _ in 0..3 {
+            match events.recv().await.unwrap() {
+                DeviceEvent::Error(message) => assert_eq!(message, "output task panicked: boom"),
+                other => panic!("unexpected event: {:?}", other),
+            }
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_no_restart_on_normal_exit() {
+        let (supervisor, mut events) = supervisor();
+        let runs = Arc::new(AtomicU32::new(0));
+
+        let counter = runs.clone();
+        let handle = supervisor.spawn("receive", RestartPolicy::default(), move || {
+            let counter = counter.clone();
+            async move {
+                let _ = counter.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+        handle.await.unwrap();
+
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+        assert!(events.try_recv().is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_never_restart() {
+        let (supervisor, mut events) = supervisor();
+        let handle = supervisor.spawn("heartbeat", RestartPolicy::Never, || async {
+            panic!("{}", String::from("formatted"));
+        });
+        handle.await.unwrap();
+
+        match events.try_recv().unwrap() {
+            DeviceEvent::Error(message) => {
+                assert_eq!(message, "heartbeat task panicked: formatted")
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+        assert!(events.try_recv().is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_abort_stops_task() {
+        let (supervisor, _events) = supervisor();
+        let runs = Arc::new(AtomicU32::new(0));
+
+        let counter = runs.clone();
+        let handle = supervisor.spawn("link", RestartPolicy::default(), move || {
+            let counter = counter.clone();
+            async move {
+                loop {
+                    let _ = counter.fetch_add(1, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                }
+            }
+        });
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        handle.abort();
+        assert!(handle.await.unwrap_err().is_cancelled());
+
+        let stopped = runs.load(Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        assert_eq!(runs.load(Ordering::SeqCst), stopped);
+    }
+}
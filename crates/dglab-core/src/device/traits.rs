@@ -1,14 +1,24 @@
 //! 设备 trait 定义
 
+use std::any::Any;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
 use async_trait::async_trait;
+use dglab_protocol::ble::throughput::validate_b0_interval;
+use dglab_protocol::ble::{FirmwareVersion, LinkParams, DEFAULT_B0_INTERVAL};
+use dglab_protocol::v3::MAX_STRENGTH;
 use serde::{Deserialize, Serialize};
 use tokio::sync::broadcast;
 
+use super::error_limit::ErrorStats;
 use super::{DeviceEvent, DeviceState};
-use crate::error::Result;
+use crate::error::{CoreError, Result};
 
 /// 设备信息
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
 pub struct DeviceInfo {
     /// 设备 ID
     pub id: String,
@@ -22,6 +32,14 @@ pub struct DeviceInfo {
     pub hardware_version: String,
     /// 电池电量 (0-100)
     pub battery_level: u8,
+    /// 按会话电量历史估算的剩余使用时间（秒），放电数据不足时为空
+    ///
+    /// 设备自身不跟踪电量历史，由 [`SessionManager::device_info`] 填充。
+    ///
+    /// [`SessionManager::device_info`]: crate::session::SessionManager::device_info
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "ts", ts(type = "number | null"))]
+    pub battery_remaining_secs: Option<u64>,
     /// 通道 A 当前强度
     pub power_a: u8,
     /// 通道 B 当前强度
@@ -30,6 +48,30 @@ pub struct DeviceInfo {
     pub max_power_a: u8,
     /// 通道 B 最大强度
     pub max_power_b: u8,
+    /// 通道 A 是否启用
+    #[serde(default = "default_enabled")]
+    pub enabled_a: bool,
+    /// 通道 B 是否启用
+    #[serde(default = "default_enabled")]
+    pub enabled_b: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl DeviceInfo {
+    /// 固件版本是否受支持，版本未知时返回 `None`，无法识别的版本视为不受支持
+    pub fn firmware_supported(&self) -> Option<bool> {
+        if self.firmware_version.is_empty() {
+            return None;
+        }
+        Some(
+            self.firmware_version
+                .parse::<FirmwareVersion>()
+                .is_ok_and(|v| v.is_supported()),
+        )
+    }
 }
 
 /// 设备配置
@@ -47,11 +89,166 @@ pub struct DeviceConfig {
     pub auto_reconnect: bool,
     /// 安全限制（最大强度）
     pub safety_limit: Option<u8>,
+    /// B0 输出间隔（毫秒，默认 100，范围 50~250）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_interval_ms: Option<u64>,
+}
+
+impl DeviceConfig {
+    /// 校验并返回 B0 输出间隔，未设置时为默认的 100ms
+    pub fn output_interval(&self) -> Result<Duration> {
+        match self.output_interval_ms {
+            Some(ms) => validate_b0_interval(Duration::from_millis(ms))
+                .map_err(|e| CoreError::InvalidParameter(e.to_string())),
+            None => Ok(DEFAULT_B0_INTERVAL),
+        }
+    }
+}
+
+/// 通道联动配置
+///
+/// 设置 A 通道强度时按 `B = round(A × ratio) + offset` 同步更新 B 通道，
+/// 结果截断到 `0..=B 通道上限`。单独设置 B 通道不受影响。
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ChannelLink {
+    /// 比例系数
+    pub ratio: f32,
+    /// 偏移量
+    pub offset: i16,
+}
+
+impl ChannelLink {
+    /// 创建通道联动配置
+    pub fn new(ratio: f32, offset: i16) -> Result<Self> {
+        let link = Self { ratio, offset };
+        link.validate()?;
+        Ok(link)
+    }
+
+    /// 校验参数（比例必须是非负有限数）
+    pub fn validate(&self) -> Result<()> {
+        if !self.ratio.is_finite() || self.ratio < 0.0 {
+            return Err(CoreError::InvalidParameter(format!(
+                "Invalid channel link ratio: {}",
+                self.ratio
+            )));
+        }
+        Ok(())
+    }
+
+    /// 根据 A 通道强度计算 B 通道强度
+    pub fn apply(&self, power_a: u8, max_power_b: u8) -> u8 {
+        let power = (power_a as f32 * self.ratio).round() as i32 + self.offset as i32;
+        power.clamp(0, max_power_b as i32) as u8
+    }
+}
+
+impl fmt::Display for ChannelLink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "B = A x {} {:+}", self.ratio, self.offset)
+    }
+}
+
+impl FromStr for ChannelLink {
+    type Err = CoreError;
+
+    /// 解析 `RATIO` 或 `RATIO:OFFSET`，如 `0.5`、`1:-10`
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || CoreError::InvalidParameter(format!("Invalid channel link: {}", s));
+
+        let (ratio, offset) = s.trim().split_once(':').unwrap_or((s.trim(), "0"));
+        let ratio = ratio.trim().parse().map_err(|_| invalid())?;
+        let offset = offset.trim().parse().map_err(|_| invalid())?;
+
+        Self::new(ratio, offset)
+    }
+}
+
+/// 设备输出限制（对应 V3 BF 指令）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeviceLimits {
+    /// A 通道强度软上限 (0~200)
+    pub soft_limit_a: u8,
+    /// B 通道强度软上限 (0~200)
+    pub soft_limit_b: u8,
+    /// 波形频率平衡参数 (0~255)，两个通道共用
+    pub freq_balance: u8,
+    /// 波形强度平衡参数 (0~255)，两个通道共用
+    pub intensity_balance: u8,
+}
+
+impl DeviceLimits {
+    /// 创建输出限制
+    pub fn new(
+        soft_limit_a: u8,
+        soft_limit_b: u8,
+        freq_balance: u8,
+        intensity_balance: u8,
+    ) -> Self {
+        Self {
+            soft_limit_a,
+            soft_limit_b,
+            freq_balance,
+            intensity_balance,
+        }
+    }
+
+    /// 校验软上限不超过 V3 最大强度
+    pub fn validate(&self) -> Result<()> {
+        for limit in [self.soft_limit_a, self.soft_limit_b] {
+            if limit > MAX_STRENGTH {
+                return Err(CoreError::PowerOutOfRange(limit, MAX_STRENGTH));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// B1 往返时延统计
+///
+/// 从发送带序列号的 B0 到收到同序号 B1 的耗时，最小/平均/最大值和抖动按最近的
+/// 若干次样本计算。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LatencyStats {
+    /// 累计样本数
+    pub samples: u64,
+    /// 最近一次时延
+    pub last: Duration,
+    /// 最小时延
+    pub min: Duration,
+    /// 平均时延
+    pub mean: Duration,
+    /// 最大时延
+    pub max: Duration,
+    /// 抖动（相邻样本差值的平均值）
+    pub jitter: Duration,
+}
+
+/// WiFi 绑定能力
+///
+/// 通过 WebSocket 服务器与 DG-LAB APP 绑定的设备（[`WsCoyoteDevice`]、
+/// [`BleWsBridgeDevice`]）实现，经 [`Device::wifi_binding`] 获取。
+///
+/// [`WsCoyoteDevice`]: super::WsCoyoteDevice
+/// [`BleWsBridgeDevice`]: super::BleWsBridgeDevice
+#[async_trait]
+pub trait WifiBinding: Send + Sync {
+    /// 获取二维码 URL（连接服务器后可用）
+    async fn qr_url(&self) -> Option<String>;
+
+    /// 是否已绑定
+    async fn is_bound(&self) -> bool;
 }
 
 /// 设备 trait
 #[async_trait]
 pub trait Device: Send + Sync {
+    /// 转换为 [`Any`]，用于访问具体设备类型，通常通过 `<dyn Device>::downcast_ref` 使用
+    fn as_any(&self) -> &dyn Any;
+
+    /// 转换为可变的 [`Any`]，通常通过 `<dyn Device>::downcast_mut` 使用
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+
     /// 获取设备 ID
     fn id(&self) -> &str;
 
@@ -82,18 +279,117 @@ pub trait Device: Send + Sync {
     /// 获取通道强度
     fn get_power(&self, channel: u8) -> u8;
 
+    /// 相对调整通道强度，结果限制在 0 到通道上限之间，返回调整后的强度
+    ///
+    /// 默认实现读取当前强度后写入绝对值；V3 和 WiFi 设备使用协议的增减模式，
+    /// 由设备在实际强度上增减，避免与其他控制端的修改互相覆盖。
+    async fn adjust_power(&mut self, channel: u8, delta: i16) -> Result<u8> {
+        let info = self.info();
+        let max_power = match channel {
+            0 => info.max_power_a,
+            1 => info.max_power_b,
+            _ => return Err(CoreError::InvalidChannel(channel)),
+        };
+        let power = (self.get_power(channel) as i16 + delta).clamp(0, max_power as i16) as u8;
+        self.set_power(channel, power).await?;
+        Ok(power)
+    }
+
+    /// 设置通道最大强度（V3 设备对应 BF 软上限）
+    ///
+    /// 当前强度超过新上限时会被下调到上限。
+    async fn set_max_power(&mut self, channel: u8, max_power: u8) -> Result<()>;
+
+    /// 获取输出限制
+    fn limits(&self) -> DeviceLimits;
+
+    /// 设置输出限制（软上限与平衡参数）
+    ///
+    /// V3 设备写入 BF 指令；WiFi 设备无法修改 APP 侧参数，仅在本地限制强度。
+    /// 当前强度超过新上限时会被下调。
+    async fn set_limits(&mut self, limits: DeviceLimits) -> Result<()>;
+
+    /// 获取通道联动配置
+    fn channel_link(&self) -> Option<ChannelLink>;
+
+    /// 设置通道联动（`None` 关闭联动）
+    ///
+    /// 设备只保存联动配置，按联动同步 B 通道见 [`crate::device::link`]；
+    /// 经会话设置时用 [`SessionManager::set_channel_link`](crate::session::SessionManager::set_channel_link)。
+    async fn set_channel_link(&mut self, link: Option<ChannelLink>) -> Result<()>;
+
+    /// 通道是否启用（无效通道返回 `false`）
+    fn channel_enabled(&self, channel: u8) -> bool;
+
+    /// 启用或禁用通道
+    ///
+    /// 禁用后通道强度立即归零、波形静默，之后的强度和波形指令都不会让通道输出，
+    /// 直到重新启用。
+    async fn set_channel_enabled(&mut self, channel: u8, enabled: bool) -> Result<()>;
+
     /// 设置波形
     async fn set_waveform(&mut self, channel: u8, waveform: WaveformConfig) -> Result<()>;
 
     /// 发送心跳
     async fn heartbeat(&mut self) -> Result<()>;
 
+    /// B1 往返时延统计，不支持或尚无样本时返回 `None`
+    fn latency(&self) -> Option<LatencyStats> {
+        None
+    }
+
+    /// 输出和接收任务的错误计数（重复错误合并上报），不支持时返回 `None`
+    fn error_stats(&self) -> Option<ErrorStats> {
+        None
+    }
+
+    /// 协商的 BLE 连接参数，非 BLE 设备返回 `None`
+    fn link_params(&self) -> Option<LinkParams> {
+        None
+    }
+
+    /// 发起一次时延探测
+    ///
+    /// V3 设备在下一个 B0 中携带序列号（强度不变），收到对应 B1 后计入
+    /// [`latency`](Self::latency)；其它设备返回错误。
+    async fn probe_latency(&mut self) -> Result<()> {
+        Err(CoreError::Other(format!(
+            "Device {} does not support latency probes",
+            self.id()
+        )))
+    }
+
+    /// WiFi 绑定能力，不通过 WebSocket 服务器绑定的设备返回 `None`
+    fn wifi_binding(&self) -> Option<&dyn WifiBinding> {
+        None
+    }
+
     /// 订阅设备事件
     fn subscribe_events(&self) -> broadcast::Receiver<DeviceEvent>;
+
+    /// 向设备的订阅方发送事件（如会话管理器执行低电量限制后的通知）
+    fn send_event(&self, event: DeviceEvent);
+}
+
+impl dyn Device {
+    /// 是否为指定的具体设备类型
+    pub fn is<T: Device + 'static>(&self) -> bool {
+        self.as_any().is::<T>()
+    }
+
+    /// 转换为具体设备类型的引用，类型不符时返回 `None`
+    pub fn downcast_ref<T: Device + 'static>(&self) -> Option<&T> {
+        self.as_any().downcast_ref()
+    }
+
+    /// 转换为具体设备类型的可变引用，类型不符时返回 `None`
+    pub fn downcast_mut<T: Device + 'static>(&mut self) -> Option<&mut T> {
+        self.as_any_mut().downcast_mut()
+    }
 }
 
 /// 波形配置
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WaveformConfig {
     /// 波形类型
     pub waveform_type: WaveformType,
@@ -153,6 +449,7 @@ mod tests {
             firmware_version: "1.2.3".to_string(),
             hardware_version: "2.0".to_string(),
             battery_level: 85,
+            battery_remaining_secs: None,
             power_a: 30,
             power_b: 40,
             max_power_a: 100,
@@ -172,6 +469,33 @@ mod tests {
         assert_eq!(restored.power_b, 40);
     }
 
+    #[test]
+    fn test_device_info_firmware_supported() {
+        let mut info = DeviceInfo {
+            id: "dev-1".to_string(),
+            name: "Test".to_string(),
+            device_type: "ble".to_string(),
+            firmware_version: String::new(),
+            hardware_version: String::new(),
+            battery_level: 50,
+            battery_remaining_secs: None,
+            power_a: 0,
+            power_b: 0,
+            max_power_a: 100,
+            max_power_b: 100,
+        };
+        assert_eq!(info.firmware_supported(), None);
+
+        info.firmware_version = "V1.2.0".to_string();
+        assert_eq!(info.firmware_supported(), Some(true));
+
+        info.firmware_version = "0.9".to_string();
+        assert_eq!(info.firmware_supported(), Some(false));
+
+        info.firmware_version = "unknown".to_string();
+        assert_eq!(info.firmware_supported(), Some(false));
+    }
+
     #[test]
     fn test_device_info_clone() {
         let info = DeviceInfo {
@@ -181,6 +505,7 @@ mod tests {
             firmware_version: "1.0".to_string(),
             hardware_version: "1.0".to_string(),
             battery_level: 50,
+            battery_remaining_secs: None,
             power_a: 0,
             power_b: 0,
             max_power_a: 100,
@@ -203,6 +528,7 @@ mod tests {
             address: Some("AA:BB:CC:DD:EE:FF".to_string()),
             auto_reconnect: true,
             safety_limit: Some(80),
+            output_interval_ms: Some(50),
         };
 
         let json = serde_json::to_string(&config).unwrap();
@@ -213,6 +539,10 @@ mod tests {
         assert_eq!(restored.address, Some("AA:BB:CC:DD:EE:FF".to_string()));
         assert!(restored.auto_reconnect);
         assert_eq!(restored.safety_limit, Some(80));
+        assert_eq!(
+            restored.output_interval().unwrap(),
+            Duration::from_millis(50)
+        );
     }
 
     #[test]
@@ -224,6 +554,7 @@ mod tests {
             address: None,
             auto_reconnect: false,
             safety_limit: None,
+            output_interval_ms: None,
         };
 
         let json = serde_json::to_string(&config).unwrap();
@@ -232,6 +563,16 @@ mod tests {
         assert!(restored.address.is_none());
         assert!(restored.safety_limit.is_none());
         assert!(!restored.auto_reconnect);
+        assert_eq!(restored.output_interval().unwrap(), DEFAULT_B0_INTERVAL);
+
+        let invalid = DeviceConfig {
+            output_interval_ms: Some(10),
+            ..restored
+        };
+        assert!(matches!(
+            invalid.output_interval(),
+            Err(CoreError::InvalidParameter(_))
+        ));
     }
 
     // === WaveformConfig 测试 ===
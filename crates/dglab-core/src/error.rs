@@ -1,5 +1,13 @@
 //! 错误类型定义
+//!
+//! 连接和通信失败按原因区分为 [`Transport`](CoreError::Transport)、
+//! [`Timeout`](CoreError::Timeout)、[`Rejected`](CoreError::Rejected) 和
+//! [`Busy`](CoreError::Busy)，重连和安全逻辑通过 [`CoreError::is_retryable`] 决定是否重试。
 
+use std::io::ErrorKind;
+
+use dglab_protocol::error::ProtocolError;
+use dglab_protocol::wifi::{ErrorCode, WsError};
 use thiserror::Error;
 
 /// 核心库错误类型
@@ -7,7 +15,7 @@ use thiserror::Error;
 pub enum CoreError {
     /// 协议错误
     #[error("Protocol error: {0}")]
-    ProtocolError(#[from] dglab_protocol::error::ProtocolError),
+    ProtocolError(#[from] ProtocolError),
 
     /// 设备未连接
     #[error("Device not connected")]
@@ -53,11 +61,90 @@ pub enum CoreError {
     #[error("Serialization error: {0}")]
     SerializationError(#[from] serde_json::Error),
 
+    /// 配置错误
+    #[error("Config error: {0}")]
+    ConfigError(String),
+
+    /// 传输错误（连接断开、发送或接收失败）
+    #[error("Transport error: {0}")]
+    Transport(String),
+
+    /// 等待连接、绑定或响应超时
+    #[error("Timed out: {0}")]
+    Timeout(String),
+
+    /// 被服务器或对方拒绝（无效请求、绑定失败等），重试不会成功
+    #[error("Rejected: {0}")]
+    Rejected(String),
+
+    /// 暂时无法处理（对方未就绪、服务器繁忙等），稍后可重试
+    #[error("Busy: {0}")]
+    Busy(String),
+
     /// 其他错误
     #[error("Other error: {0}")]
     Other(String),
 }
 
+impl CoreError {
+    /// 转换 WebSocket 错误，`context` 说明失败的操作
+    pub fn ws(context: &str, err: WsError) -> Self {
+        let message = format!("{}: {}", context, err);
+        match err {
+            WsError::Connection(_)
+            | WsError::WebSocket(_)
+            | WsError::Io(_)
+            | WsError::Send(_)
+            | WsError::Receive(_)
+            | WsError::NotConnected => Self::Transport(message),
+            WsError::Timeout => Self::Timeout(message),
+            WsError::NotBound => Self::Busy(message),
+            WsError::Server(code) => match code {
+                ErrorCode::PeerDisconnected | ErrorCode::RecipientOffline => {
+                    Self::Transport(message)
+                }
+                ErrorCode::NoAppId => Self::Timeout(message),
+                ErrorCode::ServerError => Self::Busy(message),
+                _ => Self::Rejected(message),
+            },
+            WsError::Url(_) | WsError::InvalidAddress(_) | WsError::Certificate(_) => {
+                Self::InvalidParameter(message)
+            }
+            WsError::Protocol(_)
+            | WsError::Json(_)
+            | WsError::Tls(_)
+            | WsError::InvalidMessage(_)
+            | WsError::AlreadyConnected => Self::Rejected(message),
+            WsError::Other(_) => Self::Other(message),
+        }
+    }
+
+    /// 是否为暂时性错误，重连或稍后重试可能成功
+    ///
+    /// 参数、配置和被拒绝的请求重试也不会成功，返回 `false`。
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Transport(_) | Self::Timeout(_) | Self::Busy(_) | Self::DeviceNotConnected => {
+                true
+            }
+            Self::ProtocolError(e) => matches!(
+                e,
+                ProtocolError::ConnectionError(_) | ProtocolError::Timeout
+            ),
+            Self::IoError(e) => matches!(
+                e.kind(),
+                ErrorKind::TimedOut
+                    | ErrorKind::Interrupted
+                    | ErrorKind::WouldBlock
+                    | ErrorKind::ConnectionReset
+                    | ErrorKind::ConnectionAborted
+                    | ErrorKind::BrokenPipe
+            ),
+            _ => false,
+        }
+    }
+}
+
 /// 核心库 Result 类型
 pub type Result<T> = std::result::Result<T, CoreError>;
 
@@ -135,6 +222,48 @@ mod tests {
         assert!(err.to_string().contains("Serialization error"));
     }
 
+    #[test]
+    fn test_ws_error_taxonomy() {
+        let err = CoreError::ws("WebSocket send", WsError::Send("closed".to_string()));
+        assert!(matches!(err, CoreError::Transport(_)));
+        assert_eq!(
+            err.to_string(),
+            "Transport error: WebSocket send: Send error: closed"
+        );
+
+        assert!(matches!(
+            CoreError::ws("bind", WsError::Timeout),
+            CoreError::Timeout(_)
+        ));
+        assert!(matches!(
+            CoreError::ws("bind", WsError::Server(ErrorCode::IdAlreadyBound)),
+            CoreError::Rejected(_)
+        ));
+        assert!(matches!(
+            CoreError::ws("send", WsError::Server(ErrorCode::ServerError)),
+            CoreError::Busy(_)
+        ));
+        assert!(matches!(
+            CoreError::ws("connect", WsError::InvalidAddress("x".to_string())),
+            CoreError::InvalidParameter(_)
+        ));
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        assert!(CoreError::Transport("reset".to_string()).is_retryable());
+        assert!(CoreError::Timeout("bind".to_string()).is_retryable());
+        assert!(CoreError::Busy("server".to_string()).is_retryable());
+        assert!(CoreError::DeviceNotConnected.is_retryable());
+        assert!(CoreError::ProtocolError(ProtocolError::Timeout).is_retryable());
+        assert!(CoreError::from(std::io::Error::from(ErrorKind::ConnectionReset)).is_retryable());
+
+        assert!(!CoreError::Rejected("bound".to_string()).is_retryable());
+        assert!(!CoreError::PowerOutOfRange(150, 100).is_retryable());
+        assert!(!CoreError::from(std::io::Error::from(ErrorKind::NotFound)).is_retryable());
+        assert!(!CoreError::Other("something".to_string()).is_retryable());
+    }
+
     #[test]
     fn test_error_debug() {
         let err = CoreError::DeviceNotConnected;
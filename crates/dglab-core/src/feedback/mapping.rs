@@ -0,0 +1,270 @@
+//! APP 反馈按钮动作映射
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use dglab_protocol::wifi::FeedbackButton;
+
+use crate::error::{CoreError, Result};
+
+/// 反馈按钮触发的动作
+///
+/// 强度类动作作用于按钮所属通道（A0~A4 → A 通道，B0~B4 → B 通道）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub enum FeedbackAction {
+    /// 调整强度（正数增加，负数减少）
+    AdjustPower {
+        /// 强度变化量
+        delta: i16,
+    },
+    /// 设置强度
+    SetPower {
+        /// 目标强度
+        power: u8,
+    },
+    /// 紧急停止（两个通道归零并停止输出）
+    EmergencyStop,
+    /// 切换到下一个预设
+    NextPreset,
+    /// 切换到上一个预设
+    PreviousPreset,
+}
+
+impl fmt::Display for FeedbackAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AdjustPower { delta } => write!(f, "{:+}", delta),
+            Self::SetPower { power } => write!(f, "set:{}", power),
+            Self::EmergencyStop => write!(f, "stop"),
+            Self::NextPreset => write!(f, "next"),
+            Self::PreviousPreset => write!(f, "prev"),
+        }
+    }
+}
+
+impl FromStr for FeedbackAction {
+    type Err = CoreError;
+
+    /// 解析简写形式：`+5`、`-5`、`set:50`、`stop`、`next`、`prev`
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let invalid = || CoreError::InvalidParameter(format!("Invalid feedback action: {}", s));
+
+        match s.to_lowercase().as_str() {
+            "stop" => return Ok(Self::EmergencyStop),
+            "next" => return Ok(Self::NextPreset),
+            "prev" | "previous" => return Ok(Self::PreviousPreset),
+            _ => {}
+        }
+
+        if let Some(power) = s.strip_prefix("set:") {
+            let power = power.trim().parse().map_err(|_| invalid())?;
+            return Ok(Self::SetPower { power });
+        }
+
+        if s.starts_with('+') || s.starts_with('-') {
+            let delta = s.parse().map_err(|_| invalid())?;
+            return Ok(Self::AdjustPower { delta });
+        }
+
+        Err(invalid())
+    }
+}
+
+/// 解析按钮名称（`A0`~`A4`、`B0`~`B4`，不区分大小写）
+pub fn parse_button(s: &str) -> Result<FeedbackButton> {
+    FeedbackButton::ALL
+        .into_iter()
+        .find(|button| format!("{:?}", button).eq_ignore_ascii_case(s.trim()))
+        .ok_or_else(|| CoreError::InvalidParameter(format!("Invalid feedback button: {}", s)))
+}
+
+/// 反馈按钮 → 动作映射
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeedbackMapping {
+    /// 按钮动作
+    pub actions: HashMap<FeedbackButton, FeedbackAction>,
+}
+
+impl Default for FeedbackMapping {
+    /// 默认映射：0/1 号按钮 ±5，3 号切换预设，4 号紧急停止
+    fn default() -> Self {
+        let actions = HashMap::from([
+            (FeedbackButton::A0, FeedbackAction::AdjustPower { delta: 5 }),
+            (
+                FeedbackButton::A1,
+                FeedbackAction::AdjustPower { delta: -5 },
+            ),
+            (FeedbackButton::A3, FeedbackAction::PreviousPreset),
+            (FeedbackButton::A4, FeedbackAction::EmergencyStop),
+            (FeedbackButton::B0, FeedbackAction::AdjustPower { delta: 5 }),
+            (
+                FeedbackButton::B1,
+                FeedbackAction::AdjustPower { delta: -5 },
+            ),
+            (FeedbackButton::B3, FeedbackAction::NextPreset),
+            (FeedbackButton::B4, FeedbackAction::EmergencyStop),
+        ]);
+
+        Self { actions }
+    }
+}
+
+impl FeedbackMapping {
+    /// 空映射（忽略所有按钮）
+    pub fn empty() -> Self {
+        Self {
+            actions: HashMap::new(),
+        }
+    }
+
+    /// 获取按钮对应的动作
+    pub fn get(&self, button: FeedbackButton) -> Option<FeedbackAction> {
+        self.actions.get(&button).copied()
+    }
+
+    /// 设置按钮动作
+    pub fn set(&mut self, button: FeedbackButton, action: FeedbackAction) {
+        let _ = self.actions.insert(button, action);
+    }
+
+    /// 移除按钮动作
+    pub fn remove(&mut self, button: FeedbackButton) {
+        let _ = self.actions.remove(&button);
+    }
+
+    /// 获取默认配置文件路径
+    pub fn default_path() -> Result<PathBuf> {
+        let path = dirs::config_dir()
+            .ok_or_else(|| CoreError::Other("Could not find config directory".to_string()))?
+            .join("dglab")
+            .join("feedback.json");
+
+        Ok(path)
+    }
+
+    /// 从文件加载，文件不存在时返回默认映射
+    pub async fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = tokio::fs::read_to_string(path).await?;
+        let mapping = serde_json::from_str(&content)?;
+        Ok(mapping)
+    }
+
+    /// 保存到文件
+    pub async fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+
+        let content = serde_json::to_string_pretty(self)?;
+        tokio::fs::write(path, content).await?;
+
+        info!("Saved feedback mapping to {:?}", path);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_action_from_str() {
+        assert_eq!(
+            "+5".parse::<FeedbackAction>().unwrap(),
+            FeedbackAction::AdjustPower { delta: 5 }
+        );
+        assert_eq!(
+            "-10".parse::<FeedbackAction>().unwrap(),
+            FeedbackAction::AdjustPower { delta: -10 }
+        );
+        assert_eq!(
+            "set:40".parse::<FeedbackAction>().unwrap(),
+            FeedbackAction::SetPower { power: 40 }
+        );
+        assert_eq!(
+            "STOP".parse::<FeedbackAction>().unwrap(),
+            FeedbackAction::EmergencyStop
+        );
+        assert_eq!(
+            "next".parse::<FeedbackAction>().unwrap(),
+            FeedbackAction::NextPreset
+        );
+        assert!("jump".parse::<FeedbackAction>().is_err());
+        assert!("set:999".parse::<FeedbackAction>().is_err());
+    }
+
+    #[test]
+    fn test_action_display_roundtrip() {
+        for action in [
+            FeedbackAction::AdjustPower { delta: 5 },
+            FeedbackAction::AdjustPower { delta: -3 },
+            FeedbackAction::SetPower { power: 20 },
+            FeedbackAction::EmergencyStop,
+            FeedbackAction::NextPreset,
+            FeedbackAction::PreviousPreset,
+        ] {
+            assert_eq!(
+                action.to_string().parse::<FeedbackAction>().unwrap(),
+                action
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_button() {
+        assert_eq!(parse_button("a0").unwrap(), FeedbackButton::A0);
+        assert_eq!(parse_button("B4").unwrap(), FeedbackButton::B4);
+        assert!(parse_button("C1").is_err());
+    }
+
+    #[test]
+    fn test_default_mapping() {
+        let mapping = FeedbackMapping::default();
+        assert_eq!(
+            mapping.get(FeedbackButton::A4),
+            Some(FeedbackAction::EmergencyStop)
+        );
+        assert!(mapping.get(FeedbackButton::A2).is_none());
+    }
+
+    #[test]
+    fn test_mapping_serde_roundtrip() {
+        let mapping = FeedbackMapping::default();
+        let json = serde_json::to_string(&mapping).unwrap();
+        assert!(json.contains("\"A0\""));
+        let restored: FeedbackMapping = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, mapping);
+    }
+
+    #[tokio::test]
+    async fn test_mapping_save_and_load() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("feedback.json");
+
+        // 文件不存在时使用默认映射
+        assert_eq!(
+            FeedbackMapping::load(&path).await.unwrap(),
+            FeedbackMapping::default()
+        );
+
+        let mut mapping = FeedbackMapping::empty();
+        mapping.set(FeedbackButton::B2, FeedbackAction::SetPower { power: 10 });
+        mapping.save(&path).await.unwrap();
+
+        let loaded = FeedbackMapping::load(&path).await.unwrap();
+        assert_eq!(loaded, mapping);
+    }
+}
@@ -0,0 +1,9 @@
+//! APP 反馈按钮模块
+//!
+//! 将 APP 端反馈按钮（A0~A4、B0~B4）映射为强度调整、紧急停止、预设切换等动作。
+
+pub mod mapping;
+pub mod router;
+
+pub use mapping::{parse_button, FeedbackAction, FeedbackMapping};
+pub use router::FeedbackRouter;
@@ -0,0 +1,290 @@
+//! 反馈按钮路由
+
+use tracing::info;
+
+use dglab_protocol::wifi::{Channel, FeedbackButton};
+
+use super::mapping::{FeedbackAction, FeedbackMapping};
+use crate::error::{CoreError, Result};
+use crate::preset::PresetManager;
+use crate::session::SessionManager;
+
+/// 反馈按钮路由器
+///
+/// 按映射将 APP 反馈按钮转换为对绑定设备的操作。
+pub struct FeedbackRouter {
+    /// 按钮映射
+    mapping: FeedbackMapping,
+    /// 当前预设在列表（按名称排序）中的位置
+    preset_index: Option<usize>,
+}
+
+impl FeedbackRouter {
+    /// 创建路由器
+    pub fn new(mapping: FeedbackMapping) -> Self {
+        Self {
+            mapping,
+            preset_index: None,
+        }
+    }
+
+    /// 获取按钮映射
+    pub fn mapping(&self) -> &FeedbackMapping {
+        &self.mapping
+    }
+
+    /// 替换按钮映射
+    pub fn set_mapping(&mut self, mapping: FeedbackMapping) {
+        self.mapping = mapping;
+    }
+
+    /// 处理反馈按钮
+    ///
+    /// 返回执行的动作；按钮未映射时返回 `None`。
+    pub async fn dispatch(
+        &mut self,
+        session: &SessionManager,
+        presets: &PresetManager,
+        device_id: &str,
+        button: FeedbackButton,
+    ) -> Result<Option<FeedbackAction>> {
+        let Some(action) = self.mapping.get(button) else {
+            return Ok(None);
+        };
+
+        info!("Feedback {:?} on device {}: {}", button, device_id, action);
+
+        let channel = match button.channel() {
+            Channel::A => 0u8,
+            Channel::B => 1u8,
+        };
+        self.apply(session, presets, device_id, channel, action)
+            .await?;
+
+        Ok(Some(action))
+    }
+
+    /// 对设备执行动作，强度类动作作用于 `channel`
+    pub async fn apply(
+        &mut self,
+        session: &SessionManager,
+        presets: &PresetManager,
+        device_id: &str,
+        channel: u8,
+        action: FeedbackAction,
+    ) -> Result<()> {
+        match action {
+            FeedbackAction::AdjustPower { delta } => {
+                let _ = session.adjust_power(device_id, channel, delta).await?;
+            }
+            FeedbackAction::SetPower { power } => {
+                let _ = session.set_power(device_id, channel, power).await?;
+            }
+            FeedbackAction::EmergencyStop => {
+                session.emergency_stop(device_id).await?;
+            }
+            FeedbackAction::NextPreset | FeedbackAction::PreviousPreset => {
+                let list = presets.list_presets();
+                if list.is_empty() {
+                    return Err(CoreError::PresetNotFound(
+                        "No presets available".to_string(),
+                    ));
+                }
+
+                let len = list.len();
+                let index = match (self.preset_index, action) {
+                    (None, FeedbackAction::NextPreset) => 0,
+                    (None, _) => len - 1,
+                    (Some(i), FeedbackAction::NextPreset) => (i + 1) % len,
+                    (Some(i), _) => (i.min(len - 1) + len - 1) % len,
+                };
+
+                let preset = presets.resolve(&list[index].id)?;
+                session.apply_preset(device_id, &preset).await?;
+                self.preset_index = Some(index);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for FeedbackRouter {
+    fn default() -> Self {
+        Self::new(FeedbackMapping::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SafetyConfig;
+    use crate::device::{Device, MockDevice};
+    use crate::preset::Preset;
+    use tempfile::TempDir;
+
+    async fn session_with_device() -> SessionManager {
+        let mut device = MockDevice::new("mock-1".to_string(), "Mock".to_string());
+        device.connect().await.unwrap();
+        let session = SessionManager::new();
+        session.add_device(Box::new(device)).await.unwrap();
+        session
+    }
+
+    async fn power(session: &SessionManager, channel: u8) -> u8 {
+        let device = session.get_device("mock-1").await.unwrap();
+        let dev = device.read().await;
+        dev.get_power(channel)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_adjust_power_clamps() {
+        let session = session_with_device().await;
+        let tmp = TempDir::new().unwrap();
+        let presets = PresetManager::new(tmp.path().to_path_buf());
+        let mut router = FeedbackRouter::default();
+
+        let action = router
+            .dispatch(&session, &presets, "mock-1", FeedbackButton::A0)
+            .await
+            .unwrap();
+        assert_eq!(action, Some(FeedbackAction::AdjustPower { delta: 5 }));
+        assert_eq!(power(&session, 0).await, 5);
+        assert_eq!(power(&session, 1).await, 0);
+
+        // 减到 0 以下时截断
+        for _ in 0..3 {
+            router
+                .dispatch(&session, &presets, "mock-1", FeedbackButton::A1)
+                .await
+                .unwrap();
+        }
+        assert_eq!(power(&session, 0).await, 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_power_respects_safety_limit() {
+        let session = session_with_device().await;
+        session.set_safety(SafetyConfig {
+            max_power_a: 12,
+            ..SafetyConfig::default()
+        });
+        let tmp = TempDir::new().unwrap();
+        let presets = PresetManager::new(tmp.path().to_path_buf());
+        let mut router = FeedbackRouter::default();
+
+        router
+            .apply(
+                &session,
+                &presets,
+                "mock-1",
+                0,
+                FeedbackAction::SetPower { power: 50 },
+            )
+            .await
+            .unwrap();
+        assert_eq!(power(&session, 0).await, 12);
+
+        router
+            .apply(
+                &session,
+                &presets,
+                "mock-1",
+                0,
+                FeedbackAction::AdjustPower { delta: 5 },
+            )
+            .await
+            .unwrap();
+        assert_eq!(power(&session, 0).await, 12);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_unmapped_button_ignored() {
+        let session = session_with_device().await;
+        let tmp = TempDir::new().unwrap();
+        let presets = PresetManager::new(tmp.path().to_path_buf());
+        let mut router = FeedbackRouter::new(FeedbackMapping::empty());
+
+        let action = router
+            .dispatch(&session, &presets, "mock-1", FeedbackButton::A0)
+            .await
+            .unwrap();
+        assert!(action.is_none());
+        assert_eq!(power(&session, 0).await, 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_emergency_stop_zeroes_both_channels() {
+        let session = session_with_device().await;
+        let tmp = TempDir::new().unwrap();
+        let presets = PresetManager::new(tmp.path().to_path_buf());
+        let mut router = FeedbackRouter::default();
+
+        router
+            .dispatch(&session, &presets, "mock-1", FeedbackButton::A0)
+            .await
+            .unwrap();
+        router
+            .dispatch(&session, &presets, "mock-1", FeedbackButton::B0)
+            .await
+            .unwrap();
+        router
+            .dispatch(&session, &presets, "mock-1", FeedbackButton::B4)
+            .await
+            .unwrap();
+
+        assert_eq!(power(&session, 0).await, 0);
+        assert_eq!(power(&session, 1).await, 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_preset_cycling() {
+        let session = session_with_device().await;
+        let tmp = TempDir::new().unwrap();
+        let mut presets = PresetManager::new(tmp.path().to_path_buf());
+        for (name, min_power) in [("a", 10u8), ("b", 20u8)] {
+            let mut preset = Preset::new(name.to_string(), String::new());
+            preset.channel_a.min_power = min_power;
+            presets.add_preset(preset).unwrap();
+        }
+        let mut router = FeedbackRouter::default();
+
+        router
+            .dispatch(&session, &presets, "mock-1", FeedbackButton::B3)
+            .await
+            .unwrap();
+        assert_eq!(power(&session, 0).await, 10);
+
+        router
+            .dispatch(&session, &presets, "mock-1", FeedbackButton::B3)
+            .await
+            .unwrap();
+        assert_eq!(power(&session, 0).await, 20);
+
+        // 回绕
+        router
+            .dispatch(&session, &presets, "mock-1", FeedbackButton::B3)
+            .await
+            .unwrap();
+        assert_eq!(power(&session, 0).await, 10);
+
+        router
+            .dispatch(&session, &presets, "mock-1", FeedbackButton::A3)
+            .await
+            .unwrap();
+        assert_eq!(power(&session, 0).await, 20);
+    }
+
+    #[tokio::test]
+    async fn test_preset_cycling_without_presets() {
+        let session = SessionManager::new();
+        let tmp = TempDir::new().unwrap();
+        let presets = PresetManager::new(tmp.path().to_path_buf());
+        let mut router = FeedbackRouter::default();
+
+        let result = router
+            .dispatch(&session, &presets, "mock-1", FeedbackButton::B3)
+            .await;
+        assert!(matches!(result, Err(CoreError::PresetNotFound(_))));
+    }
+}
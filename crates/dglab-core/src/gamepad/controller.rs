@@ -0,0 +1,392 @@
+//! 手柄输入处理
+
+use tracing::{debug, info};
+
+use super::mapping::{AxisTarget, GamepadAxis, GamepadButton, GamepadMapping};
+use crate::device::traits::WaveformConfig;
+use crate::device::MAX_PERCENT;
+use crate::error::{CoreError, Result};
+use crate::feedback::{FeedbackAction, FeedbackMapping, FeedbackRouter};
+use crate::preset::PresetManager;
+use crate::session::SessionManager;
+
+/// 手柄输入事件
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GamepadEvent {
+    /// 轴位置变化（摇杆 -1.0~1.0，扳机 0.0~1.0）
+    AxisChanged(GamepadAxis, f32),
+    /// 按键按下
+    ButtonPressed(GamepadButton),
+    /// 手柄断开（轴停在最后的位置，需要归零输出）
+    Disconnected,
+}
+
+/// 手柄控制器
+///
+/// 按映射将手柄输入转换为对设备的操作。轴输出只在量化后的值变化时写入设备，
+/// 摇杆的微小抖动不会产生多余的蓝牙写入。
+pub struct GamepadController {
+    /// 输入映射
+    mapping: GamepadMapping,
+    /// 执行按键动作（复用反馈按钮的预设切换状态）
+    router: FeedbackRouter,
+    /// 两个通道当前的波形配置（波形强度轴修改其强度）
+    waveforms: [WaveformConfig; 2],
+}
+
+impl GamepadController {
+    /// 创建控制器
+    pub fn new(mapping: GamepadMapping) -> Self {
+        Self {
+            mapping,
+            router: FeedbackRouter::new(FeedbackMapping::empty()),
+            waveforms: [WaveformConfig::default(), WaveformConfig::default()],
+        }
+    }
+
+    /// 获取输入映射
+    pub fn mapping(&self) -> &GamepadMapping {
+        &self.mapping
+    }
+
+    /// 替换输入映射
+    pub fn set_mapping(&mut self, mapping: GamepadMapping) {
+        self.mapping = mapping;
+    }
+
+    /// 设置通道的波形配置，波形强度轴在此基础上调整强度
+    pub fn set_waveform(&mut self, channel: u8, waveform: WaveformConfig) -> Result<()> {
+        let slot = self
+            .waveforms
+            .get_mut(channel as usize)
+            .ok_or(CoreError::InvalidChannel(channel))?;
+        *slot = waveform;
+        Ok(())
+    }
+
+    /// 处理输入事件
+    pub async fn handle(
+        &mut self,
+        session: &SessionManager,
+        presets: &PresetManager,
+        device_id: &str,
+        event: GamepadEvent,
+    ) -> Result<()> {
+        match event {
+            GamepadEvent::AxisChanged(axis, raw) => {
+                let targets: Vec<_> = self
+                    .mapping
+                    .axes
+                    .iter()
+                    .filter(|m| m.axis == axis)
+                    .map(|m| (m.target, m.level(raw)))
+                    .collect();
+                for (target, level) in targets {
+                    apply_axis(session, device_id, target, level, &mut self.waveforms).await?;
+                }
+            }
+            GamepadEvent::ButtonPressed(button) => {
+                let Some(binding) = self.mapping.buttons.get(&button).copied() else {
+                    return Ok(());
+                };
+                info!(
+                    "Gamepad {:?} on device {}: {}",
+                    button, device_id, binding.action
+                );
+
+                let per_channel = matches!(
+                    binding.action,
+                    FeedbackAction::AdjustPower { .. } | FeedbackAction::SetPower { .. }
+                );
+                let channels = match binding.channel {
+                    Some(channel) => vec![channel],
+                    None if per_channel => vec![0, 1],
+                    None => vec![0],
+                };
+                for channel in channels {
+                    self.router
+                        .apply(session, presets, device_id, channel, binding.action)
+                        .await?;
+                }
+            }
+            GamepadEvent::Disconnected => {
+                info!("Gamepad disconnected, zeroing output of {}", device_id);
+                for channel in [0, 1] {
+                    let _ = session.set_power(device_id, channel, 0).await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// 按输出比例 (0.0~1.0) 设置轴映射的目标（手柄和传感器共用）
+///
+/// 强度目标映射到 0~通道最大强度（设备已校准时为 0~100%），受安全限制约束；
+/// 波形强度目标修改 `waveforms` 中对应通道的强度。都经会话管理器写入，
+/// 只在量化后的值变化时写入设备。
+pub(crate) async fn apply_axis(
+    session: &SessionManager,
+    device_id: &str,
+    target: AxisTarget,
+    level: f32,
+    waveforms: &mut [WaveformConfig; 2],
+) -> Result<()> {
+    let device = session
+        .get_device(device_id)
+        .await
+        .ok_or_else(|| CoreError::DeviceNotFound(device_id.to_string()))?;
+    let channel = target.channel();
+    let level = level.clamp(0.0, 1.0);
+
+    match target {
+        AxisTarget::Power { .. } => {
+            let (name, info, current) = {
+                let dev = device.read().await;
+                (dev.name().to_string(), dev.info(), dev.get_power(channel))
+            };
+            let max_power = match session.calibration(device_id, &name) {
+                Some(_) => MAX_PERCENT,
+                None if channel == 0 => info.max_power_a,
+                None => info.max_power_b,
+            };
+            let power = (level * max_power as f32).round() as u8;
+            if session.resolve_power(device_id, &name, channel, power) != current {
+                debug!("Axis power {}: {}", channel, power);
+                let _ = session.set_power(device_id, channel, power).await?;
+            }
+        }
+        AxisTarget::WaveformIntensity { .. } => {
+            let intensity = (level * 100.0).round() as u8;
+            let waveform = waveforms
+                .get_mut(channel as usize)
+                .ok_or(CoreError::InvalidChannel(channel))?;
+            if waveform.intensity != intensity {
+                debug!("Axis waveform intensity {}: {}", channel, intensity);
+                waveform.intensity = intensity;
+                session
+                    .set_waveform(device_id, channel, waveform.clone())
+                    .await?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+impl Default for GamepadController {
+    fn default() -> Self {
+        Self::new(GamepadMapping::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SafetyConfig;
+    use crate::device::{Device, MockDevice};
+    use crate::gamepad::mapping::{AxisMapping, ButtonBinding};
+    use tempfile::TempDir;
+
+    async fn session_with_device() -> SessionManager {
+        let mut device = MockDevice::new("mock-1".to_string(), "Mock".to_string());
+        device.connect().await.unwrap();
+        let session = SessionManager::new();
+        session.add_device(Box::new(device)).await.unwrap();
+        session
+    }
+
+    async fn power(session: &SessionManager, channel: u8) -> u8 {
+        let device = session.get_device("mock-1").await.unwrap();
+        let dev = device.read().await;
+        dev.get_power(channel)
+    }
+
+    #[tokio::test]
+    async fn test_trigger_sets_power() {
+        let session = session_with_device().await;
+        let tmp = TempDir::new().unwrap();
+        let presets = PresetManager::new(tmp.path().to_path_buf());
+        let mut controller = GamepadController::default();
+
+        controller
+            .handle(
+                &session,
+                &presets,
+                "mock-1",
+                GamepadEvent::AxisChanged(GamepadAxis::RightTrigger, 1.0),
+            )
+            .await
+            .unwrap();
+        assert_eq!(power(&session, 1).await, 100);
+        assert_eq!(power(&session, 0).await, 0);
+
+        // 松开扳机回到死区内
+        controller
+            .handle(
+                &session,
+                &presets,
+                "mock-1",
+                GamepadEvent::AxisChanged(GamepadAxis::RightTrigger, 0.05),
+            )
+            .await
+            .unwrap();
+        assert_eq!(power(&session, 1).await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_axis_respects_safety_limit() {
+        let session = session_with_device().await;
+        session.set_safety(SafetyConfig {
+            max_power_b: 25,
+            ..SafetyConfig::default()
+        });
+        let tmp = TempDir::new().unwrap();
+        let presets = PresetManager::new(tmp.path().to_path_buf());
+        let mut controller = GamepadController::default();
+
+        controller
+            .handle(
+                &session,
+                &presets,
+                "mock-1",
+                GamepadEvent::AxisChanged(GamepadAxis::RightTrigger, 1.0),
+            )
+            .await
+            .unwrap();
+        assert_eq!(power(&session, 1).await, 25);
+    }
+
+    #[tokio::test]
+    async fn test_button_applies_to_both_channels() {
+        let session = session_with_device().await;
+        let tmp = TempDir::new().unwrap();
+        let presets = PresetManager::new(tmp.path().to_path_buf());
+        let mut controller = GamepadController::default();
+
+        controller
+            .handle(
+                &session,
+                &presets,
+                "mock-1",
+                GamepadEvent::ButtonPressed(GamepadButton::DPadUp),
+            )
+            .await
+            .unwrap();
+        assert_eq!(power(&session, 0).await, 5);
+        assert_eq!(power(&session, 1).await, 5);
+
+        // 未绑定的按键被忽略
+        controller
+            .handle(
+                &session,
+                &presets,
+                "mock-1",
+                GamepadEvent::ButtonPressed(GamepadButton::North),
+            )
+            .await
+            .unwrap();
+        assert_eq!(power(&session, 0).await, 5);
+    }
+
+    #[tokio::test]
+    async fn test_single_channel_binding_and_stop() {
+        let session = session_with_device().await;
+        let tmp = TempDir::new().unwrap();
+        let presets = PresetManager::new(tmp.path().to_path_buf());
+        let mut mapping = GamepadMapping::default();
+        let _ = mapping.buttons.insert(
+            GamepadButton::South,
+            ButtonBinding::for_channel(FeedbackAction::SetPower { power: 30 }, 0),
+        );
+        let mut controller = GamepadController::new(mapping);
+
+        controller
+            .handle(
+                &session,
+                &presets,
+                "mock-1",
+                GamepadEvent::ButtonPressed(GamepadButton::South),
+            )
+            .await
+            .unwrap();
+        assert_eq!(power(&session, 0).await, 30);
+        assert_eq!(power(&session, 1).await, 0);
+
+        controller
+            .handle(
+                &session,
+                &presets,
+                "mock-1",
+                GamepadEvent::ButtonPressed(GamepadButton::East),
+            )
+            .await
+            .unwrap();
+        assert_eq!(power(&session, 0).await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_disconnect_zeroes_output() {
+        let session = session_with_device().await;
+        let tmp = TempDir::new().unwrap();
+        let presets = PresetManager::new(tmp.path().to_path_buf());
+        let mut controller = GamepadController::default();
+
+        controller
+            .handle(
+                &session,
+                &presets,
+                "mock-1",
+                GamepadEvent::AxisChanged(GamepadAxis::RightTrigger, 1.0),
+            )
+            .await
+            .unwrap();
+        assert_eq!(power(&session, 1).await, 100);
+
+        controller
+            .handle(&session, &presets, "mock-1", GamepadEvent::Disconnected)
+            .await
+            .unwrap();
+        assert_eq!(power(&session, 0).await, 0);
+        assert_eq!(power(&session, 1).await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_waveform_intensity_axis() {
+        let session = session_with_device().await;
+        let tmp = TempDir::new().unwrap();
+        let presets = PresetManager::new(tmp.path().to_path_buf());
+        let mut mapping = GamepadMapping::empty();
+        mapping.axes.push(AxisMapping {
+            dead_zone: 0.0,
+            ..AxisMapping::new(
+                GamepadAxis::LeftStickY,
+                AxisTarget::WaveformIntensity { channel: 0 },
+            )
+        });
+        let mut controller = GamepadController::new(mapping);
+
+        controller
+            .handle(
+                &session,
+                &presets,
+                "mock-1",
+                GamepadEvent::AxisChanged(GamepadAxis::LeftStickY, 0.8),
+            )
+            .await
+            .unwrap();
+        assert_eq!(controller.waveforms[0].intensity, 80);
+        assert_eq!(controller.waveforms[1].intensity, 50);
+
+        let result = controller
+            .handle(
+                &session,
+                &presets,
+                "missing",
+                GamepadEvent::AxisChanged(GamepadAxis::LeftStickY, 1.0),
+            )
+            .await;
+        assert!(matches!(result, Err(CoreError::DeviceNotFound(_))));
+    }
+}
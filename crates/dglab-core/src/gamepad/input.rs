@@ -0,0 +1,149 @@
+//! 手柄输入读取（gilrs）
+//!
+//! gilrs 需要在创建它的线程上轮询，因此在独立线程中读取事件，
+//! 转换为 [`GamepadEvent`] 后通过通道交给异步任务。丢弃 [`GamepadInput`] 即停止读取。
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc as std_mpsc, Arc};
+use std::thread;
+use std::time::Duration;
+
+use gilrs::{Axis, Button, EventType, Gilrs};
+use tokio::sync::mpsc;
+use tracing::{debug, info, warn};
+
+use super::controller::GamepadEvent;
+use super::mapping::{GamepadAxis, GamepadButton};
+use crate::error::{CoreError, Result};
+
+/// 轮询间隔（检查停止标志的最长等待时间）
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// 手柄输入
+pub struct GamepadInput {
+    /// 事件接收端
+    rx: mpsc::UnboundedReceiver<GamepadEvent>,
+    /// 停止标志
+    stop: Arc<AtomicBool>,
+}
+
+impl GamepadInput {
+    /// 启动输入线程
+    ///
+    /// 系统不支持手柄输入时返回错误；没有连接手柄不算错误，之后接入的手柄同样生效。
+    pub fn start() -> Result<Self> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let (init_tx, init_rx) = std_mpsc::sync_channel(1);
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = stop.clone();
+
+        let _ = thread::Builder::new()
+            .name("dglab-gamepad".to_string())
+            .spawn(move || {
+                let mut gilrs = match Gilrs::new() {
+                    Ok(gilrs) => {
+                        let _ = init_tx.send(Ok(()));
+                        gilrs
+                    }
+                    Err(e) => {
+                        let _ = init_tx.send(Err(e.to_string()));
+                        return;
+                    }
+                };
+                for (_, gamepad) in gilrs.gamepads() {
+                    info!("Gamepad found: {}", gamepad.name());
+                }
+
+                while !thread_stop.load(Ordering::Relaxed) {
+                    let Some(event) = gilrs.next_event_blocking(Some(POLL_INTERVAL)) else {
+                        continue;
+                    };
+                    let converted = match event.event {
+                        EventType::Connected => {
+                            info!("Gamepad connected: {}", gilrs.gamepad(event.id).name());
+                            None
+                        }
+                        EventType::Disconnected => {
+                            warn!("Gamepad disconnected: {}", gilrs.gamepad(event.id).name());
+                            Some(GamepadEvent::Disconnected)
+                        }
+                        EventType::AxisChanged(axis, value, _) => {
+                            convert_axis(axis).map(|axis| GamepadEvent::AxisChanged(axis, value))
+                        }
+                        // 扳机在多数手柄上报告为模拟按键
+                        EventType::ButtonChanged(Button::LeftTrigger2, value, _) => {
+                            Some(GamepadEvent::AxisChanged(GamepadAxis::LeftTrigger, value))
+                        }
+                        EventType::ButtonChanged(Button::RightTrigger2, value, _) => {
+                            Some(GamepadEvent::AxisChanged(GamepadAxis::RightTrigger, value))
+                        }
+                        EventType::ButtonPressed(button, _) => {
+                            convert_button(button).map(GamepadEvent::ButtonPressed)
+                        }
+                        _ => None,
+                    };
+
+                    if let Some(converted) = converted {
+                        debug!("Gamepad event: {:?}", converted);
+                        if tx.send(converted).is_err() {
+                            break;
+                        }
+                    }
+                }
+            })?;
+
+        match init_rx.recv() {
+            Ok(Ok(())) => Ok(Self { rx, stop }),
+            Ok(Err(e)) => Err(CoreError::Other(format!(
+                "Failed to initialize gamepad input: {}",
+                e
+            ))),
+            Err(_) => Err(CoreError::Other(
+                "Gamepad input thread exited unexpectedly".to_string(),
+            )),
+        }
+    }
+
+    /// 等待下一个输入事件
+    pub async fn next(&mut self) -> Option<GamepadEvent> {
+        self.rx.recv().await
+    }
+}
+
+impl Drop for GamepadInput {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+/// 转换 gilrs 轴
+fn convert_axis(axis: Axis) -> Option<GamepadAxis> {
+    match axis {
+        Axis::LeftStickX => Some(GamepadAxis::LeftStickX),
+        Axis::LeftStickY => Some(GamepadAxis::LeftStickY),
+        Axis::RightStickX => Some(GamepadAxis::RightStickX),
+        Axis::RightStickY => Some(GamepadAxis::RightStickY),
+        Axis::LeftZ => Some(GamepadAxis::LeftTrigger),
+        Axis::RightZ => Some(GamepadAxis::RightTrigger),
+        _ => None,
+    }
+}
+
+/// 转换 gilrs 按键
+fn convert_button(button: Button) -> Option<GamepadButton> {
+    match button {
+        Button::South => Some(GamepadButton::South),
+        Button::East => Some(GamepadButton::East),
+        Button::North => Some(GamepadButton::North),
+        Button::West => Some(GamepadButton::West),
+        Button::LeftTrigger => Some(GamepadButton::LeftBumper),
+        Button::RightTrigger => Some(GamepadButton::RightBumper),
+        Button::Select => Some(GamepadButton::Select),
+        Button::Start => Some(GamepadButton::Start),
+        Button::DPadUp => Some(GamepadButton::DPadUp),
+        Button::DPadDown => Some(GamepadButton::DPadDown),
+        Button::DPadLeft => Some(GamepadButton::DPadLeft),
+        Button::DPadRight => Some(GamepadButton::DPadRight),
+        _ => None,
+    }
+}
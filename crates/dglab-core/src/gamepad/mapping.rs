@@ -0,0 +1,422 @@
+//! 手柄输入映射
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::device::Easing;
+use crate::error::{CoreError, Result};
+use crate::feedback::FeedbackAction;
+
+/// 默认死区
+pub const DEFAULT_DEAD_ZONE: f32 = 0.1;
+
+/// 手柄轴
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GamepadAxis {
+    /// 左摇杆水平
+    LeftStickX,
+    /// 左摇杆垂直
+    LeftStickY,
+    /// 右摇杆水平
+    RightStickX,
+    /// 右摇杆垂直
+    RightStickY,
+    /// 左扳机
+    LeftTrigger,
+    /// 右扳机
+    RightTrigger,
+}
+
+impl GamepadAxis {
+    /// 所有轴
+    pub const ALL: [Self; 6] = [
+        Self::LeftStickX,
+        Self::LeftStickY,
+        Self::RightStickX,
+        Self::RightStickY,
+        Self::LeftTrigger,
+        Self::RightTrigger,
+    ];
+}
+
+impl fmt::Display for GamepadAxis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::LeftStickX => "left_stick_x",
+            Self::LeftStickY => "left_stick_y",
+            Self::RightStickX => "right_stick_x",
+            Self::RightStickY => "right_stick_y",
+            Self::LeftTrigger => "left_trigger",
+            Self::RightTrigger => "right_trigger",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for GamepadAxis {
+    type Err = CoreError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|axis| axis.to_string() == s.trim().to_lowercase().replace('-', "_"))
+            .ok_or_else(|| CoreError::InvalidParameter(format!("Invalid gamepad axis: {}", s)))
+    }
+}
+
+/// 手柄按键（按位置命名，South 即 Xbox 的 A 键）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GamepadButton {
+    /// 下方按键
+    South,
+    /// 右方按键
+    East,
+    /// 上方按键
+    North,
+    /// 左方按键
+    West,
+    /// 左肩键
+    LeftBumper,
+    /// 右肩键
+    RightBumper,
+    /// 选择键
+    Select,
+    /// 开始键
+    Start,
+    /// 方向键上
+    DPadUp,
+    /// 方向键下
+    DPadDown,
+    /// 方向键左
+    DPadLeft,
+    /// 方向键右
+    DPadRight,
+}
+
+/// 轴控制的目标
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "target", rename_all = "snake_case")]
+pub enum AxisTarget {
+    /// 通道强度（轴位置映射到 0~通道最大强度）
+    Power {
+        /// 通道编号 (0=A, 1=B)
+        channel: u8,
+    },
+    /// 波形强度（轴位置映射到 0~100）
+    WaveformIntensity {
+        /// 通道编号 (0=A, 1=B)
+        channel: u8,
+    },
+}
+
+impl AxisTarget {
+    /// 目标通道
+    pub fn channel(&self) -> u8 {
+        match self {
+            Self::Power { channel } | Self::WaveformIntensity { channel } => *channel,
+        }
+    }
+}
+
+/// 轴映射
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AxisMapping {
+    /// 轴
+    pub axis: GamepadAxis,
+    /// 控制目标
+    #[serde(flatten)]
+    pub target: AxisTarget,
+    /// 死区（0.0~1.0），轴位置不超过死区时视为零
+    #[serde(default = "default_dead_zone")]
+    pub dead_zone: f32,
+    /// 响应曲线
+    #[serde(default)]
+    pub curve: Easing,
+    /// 反向（摇杆向下推为正）
+    #[serde(default)]
+    pub invert: bool,
+}
+
+fn default_dead_zone() -> f32 {
+    DEFAULT_DEAD_ZONE
+}
+
+impl AxisMapping {
+    /// 创建轴映射（默认死区、线性曲线）
+    pub fn new(axis: GamepadAxis, target: AxisTarget) -> Self {
+        Self {
+            axis,
+            target,
+            dead_zone: DEFAULT_DEAD_ZONE,
+            curve: Easing::Linear,
+            invert: false,
+        }
+    }
+
+    /// 把轴的原始值 (-1.0~1.0) 映射为输出比例 (0.0~1.0)
+    ///
+    /// 只使用正半轴，死区外的部分重新缩放到 0~1 后按曲线变换，
+    /// 因此刚离开死区时输出从零开始连续变化。
+    pub fn level(&self, raw: f32) -> f32 {
+        let value = if self.invert { -raw } else { raw }.clamp(0.0, 1.0);
+        let dead_zone = self.dead_zone.clamp(0.0, 0.99);
+        if value <= dead_zone {
+            return 0.0;
+        }
+        // 弹性等曲线可能越界，截断到 0~1
+        self.curve
+            .apply((value - dead_zone) / (1.0 - dead_zone))
+            .clamp(0.0, 1.0)
+    }
+}
+
+/// 按键绑定
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ButtonBinding {
+    /// 动作
+    #[serde(flatten)]
+    pub action: FeedbackAction,
+    /// 强度类动作的通道（为空时作用于两个通道）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<u8>,
+}
+
+impl ButtonBinding {
+    /// 创建作用于两个通道的绑定
+    pub fn new(action: FeedbackAction) -> Self {
+        Self {
+            action,
+            channel: None,
+        }
+    }
+
+    /// 创建作用于单个通道的绑定
+    pub fn for_channel(action: FeedbackAction, channel: u8) -> Self {
+        Self {
+            action,
+            channel: Some(channel),
+        }
+    }
+}
+
+/// 手柄输入映射
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GamepadMapping {
+    /// 轴映射（同一个轴可以控制多个目标）
+    #[serde(default)]
+    pub axes: Vec<AxisMapping>,
+    /// 按键绑定
+    #[serde(default)]
+    pub buttons: HashMap<GamepadButton, ButtonBinding>,
+}
+
+impl Default for GamepadMapping {
+    /// 默认映射：左/右扳机控制 A/B 通道强度，方向键上下 ±5，
+    /// 肩键切换预设，East 键紧急停止
+    fn default() -> Self {
+        let axes = vec![
+            AxisMapping::new(GamepadAxis::LeftTrigger, AxisTarget::Power { channel: 0 }),
+            AxisMapping::new(GamepadAxis::RightTrigger, AxisTarget::Power { channel: 1 }),
+        ];
+        let buttons = HashMap::from([
+            (
+                GamepadButton::DPadUp,
+                ButtonBinding::new(FeedbackAction::AdjustPower { delta: 5 }),
+            ),
+            (
+                GamepadButton::DPadDown,
+                ButtonBinding::new(FeedbackAction::AdjustPower { delta: -5 }),
+            ),
+            (
+                GamepadButton::LeftBumper,
+                ButtonBinding::new(FeedbackAction::PreviousPreset),
+            ),
+            (
+                GamepadButton::RightBumper,
+                ButtonBinding::new(FeedbackAction::NextPreset),
+            ),
+            (
+                GamepadButton::East,
+                ButtonBinding::new(FeedbackAction::EmergencyStop),
+            ),
+        ]);
+
+        Self { axes, buttons }
+    }
+}
+
+impl GamepadMapping {
+    /// 空映射（忽略所有输入）
+    pub fn empty() -> Self {
+        Self {
+            axes: Vec::new(),
+            buttons: HashMap::new(),
+        }
+    }
+
+    /// 检查通道编号和死区
+    pub fn validate(&self) -> Result<()> {
+        for mapping in &self.axes {
+            if mapping.target.channel() > 1 {
+                return Err(CoreError::InvalidChannel(mapping.target.channel()));
+            }
+            if !(0.0..1.0).contains(&mapping.dead_zone) {
+                return Err(CoreError::InvalidParameter(format!(
+                    "Dead zone must be in 0.0~1.0: {}",
+                    mapping.dead_zone
+                )));
+            }
+        }
+        for binding in self.buttons.values() {
+            if let Some(channel) = binding.channel.filter(|c| *c > 1) {
+                return Err(CoreError::InvalidChannel(channel));
+            }
+        }
+        Ok(())
+    }
+
+    /// 获取默认配置文件路径
+    pub fn default_path() -> Result<PathBuf> {
+        let path = dirs::config_dir()
+            .ok_or_else(|| CoreError::Other("Could not find config directory".to_string()))?
+            .join("dglab")
+            .join("gamepad.json");
+
+        Ok(path)
+    }
+
+    /// 从文件加载，文件不存在时返回默认映射
+    pub async fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = tokio::fs::read_to_string(path).await?;
+        let mapping: Self = serde_json::from_str(&content)?;
+        mapping.validate()?;
+        Ok(mapping)
+    }
+
+    /// 保存到文件
+    pub async fn save(&self, path: &Path) -> Result<()> {
+        self.validate()?;
+        if let Some(dir) = path.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+
+        let content = serde_json::to_string_pretty(self)?;
+        tokio::fs::write(path, content).await?;
+
+        info!("Saved gamepad mapping to {:?}", path);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_axis_level_dead_zone() {
+        let mapping = AxisMapping::new(GamepadAxis::LeftStickY, AxisTarget::Power { channel: 0 });
+        assert_eq!(mapping.level(0.05), 0.0);
+        assert_eq!(mapping.level(0.1), 0.0);
+        assert_eq!(mapping.level(1.0), 1.0);
+        assert!((mapping.level(0.55) - 0.5).abs() < 1e-6);
+        // 负半轴视为零
+        assert_eq!(mapping.level(-1.0), 0.0);
+
+        let inverted = AxisMapping {
+            invert: true,
+            ..mapping
+        };
+        assert_eq!(inverted.level(-1.0), 1.0);
+        assert_eq!(inverted.level(1.0), 0.0);
+    }
+
+    #[test]
+    fn test_axis_level_curve() {
+        let mapping = AxisMapping {
+            dead_zone: 0.0,
+            curve: Easing::EaseIn,
+            ..AxisMapping::new(GamepadAxis::RightTrigger, AxisTarget::Power { channel: 1 })
+        };
+        assert_eq!(mapping.level(0.5), 0.25);
+        assert_eq!(mapping.level(1.0), 1.0);
+    }
+
+    #[test]
+    fn test_axis_from_str() {
+        assert_eq!(
+            "left-trigger".parse::<GamepadAxis>().unwrap(),
+            GamepadAxis::LeftTrigger
+        );
+        assert_eq!(
+            "RIGHT_STICK_Y".parse::<GamepadAxis>().unwrap(),
+            GamepadAxis::RightStickY
+        );
+        assert!("wheel".parse::<GamepadAxis>().is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(GamepadMapping::default().validate().is_ok());
+
+        let mut mapping = GamepadMapping::empty();
+        mapping.axes.push(AxisMapping::new(
+            GamepadAxis::LeftStickX,
+            AxisTarget::WaveformIntensity { channel: 2 },
+        ));
+        assert!(matches!(
+            mapping.validate(),
+            Err(CoreError::InvalidChannel(2))
+        ));
+
+        let mut mapping = GamepadMapping::default();
+        mapping.axes[0].dead_zone = 1.0;
+        assert!(mapping.validate().is_err());
+    }
+
+    #[test]
+    fn test_mapping_serde_format() {
+        let mut mapping = GamepadMapping::empty();
+        mapping.axes.push(AxisMapping::new(
+            GamepadAxis::LeftTrigger,
+            AxisTarget::Power { channel: 0 },
+        ));
+        let _ = mapping.buttons.insert(
+            GamepadButton::South,
+            ButtonBinding::for_channel(FeedbackAction::SetPower { power: 10 }, 1),
+        );
+
+        let json = serde_json::to_string(&mapping).unwrap();
+        assert!(json.contains(r#""axis":"left_trigger","target":"power","channel":0"#));
+        assert!(json.contains(r#""south":{"action":"set_power","power":10,"channel":1}"#));
+        let restored: GamepadMapping = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, mapping);
+    }
+
+    #[tokio::test]
+    async fn test_mapping_save_and_load() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("gamepad.json");
+
+        assert_eq!(
+            GamepadMapping::load(&path).await.unwrap(),
+            GamepadMapping::default()
+        );
+
+        let mut mapping = GamepadMapping::default();
+        mapping.axes[1].curve = Easing::EaseOut;
+        mapping.save(&path).await.unwrap();
+        assert_eq!(GamepadMapping::load(&path).await.unwrap(), mapping);
+    }
+}
@@ -0,0 +1,16 @@
+//! 手柄输入模块
+//!
+//! 将手柄的轴和按键映射为通道强度、波形强度、预设切换等操作，轴支持死区和响应曲线。
+//! 读取手柄需要启用 `gamepad` 特性。
+
+pub mod controller;
+#[cfg(feature = "gamepad")]
+pub mod input;
+pub mod mapping;
+
+pub use controller::{GamepadController, GamepadEvent};
+#[cfg(feature = "gamepad")]
+pub use input::GamepadInput;
+pub use mapping::{
+    AxisMapping, AxisTarget, ButtonBinding, GamepadAxis, GamepadButton, GamepadMapping,
+};
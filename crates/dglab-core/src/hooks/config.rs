@@ -0,0 +1,139 @@
+//! 钩子配置
+
+use serde::{Deserialize, Serialize};
+
+use super::runner::HookKind;
+use crate::error::{CoreError, Result};
+
+/// 默认命令超时（秒）
+pub const DEFAULT_HOOK_TIMEOUT_SECS: u64 = 10;
+
+/// 默认低电量阈值（%）
+pub const DEFAULT_BATTERY_LOW_THRESHOLD: u8 = 20;
+
+/// 钩子配置（配置文件 `[hooks]` 段）
+///
+/// 每个事件可配置多条命令，按顺序并发执行，互不等待。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HooksConfig {
+    /// 单条命令超时（秒），超时后终止命令
+    pub timeout_secs: u64,
+    /// 低电量阈值（%），电量降到阈值及以下时触发 `on_battery_low`
+    pub battery_low_threshold: u8,
+    /// 设备连接
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub on_connect: Vec<String>,
+    /// 设备断开
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub on_disconnect: Vec<String>,
+    /// APP 反馈按钮
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub on_feedback: Vec<String>,
+    /// 低电量
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub on_battery_low: Vec<String>,
+    /// 紧急停止
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub on_emergency_stop: Vec<String>,
+}
+
+impl Default for HooksConfig {
+    fn default() -> Self {
+        Self {
+            timeout_secs: DEFAULT_HOOK_TIMEOUT_SECS,
+            battery_low_threshold: DEFAULT_BATTERY_LOW_THRESHOLD,
+            on_connect: Vec::new(),
+            on_disconnect: Vec::new(),
+            on_feedback: Vec::new(),
+            on_battery_low: Vec::new(),
+            on_emergency_stop: Vec::new(),
+        }
+    }
+}
+
+impl HooksConfig {
+    /// 事件对应的命令
+    pub fn commands(&self, kind: HookKind) -> &[String] {
+        match kind {
+            HookKind::Connect => &self.on_connect,
+            HookKind::Disconnect => &self.on_disconnect,
+            HookKind::Feedback => &self.on_feedback,
+            HookKind::BatteryLow => &self.on_battery_low,
+            HookKind::EmergencyStop => &self.on_emergency_stop,
+        }
+    }
+
+    /// 是否为默认配置（序列化时省略）
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// 校验配置
+    pub fn validate(&self) -> Result<()> {
+        if self.timeout_secs == 0 {
+            return Err(CoreError::ConfigError(
+                "Hook timeout must be at least 1 second".to_string(),
+            ));
+        }
+        if self.battery_low_threshold > 100 {
+            return Err(CoreError::ConfigError(format!(
+                "Invalid battery low threshold {}%",
+                self.battery_low_threshold
+            )));
+        }
+        for kind in HookKind::ALL {
+            if self.commands(kind).iter().any(|c| c.trim().is_empty()) {
+                return Err(CoreError::ConfigError(format!(
+                    "Empty command in hooks.on_{}",
+                    kind
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hooks_config_toml() {
+        let config: HooksConfig = toml::from_str(
+            r#"
+battery_low_threshold = 15
+on_connect = ["notify-send connected"]
+on_battery_low = ["echo low", "play beep.wav"]
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.timeout_secs, DEFAULT_HOOK_TIMEOUT_SECS);
+        assert_eq!(config.battery_low_threshold, 15);
+        assert_eq!(
+            config.commands(HookKind::Connect),
+            ["notify-send connected"]
+        );
+        assert_eq!(config.commands(HookKind::BatteryLow).len(), 2);
+        assert!(config.commands(HookKind::Feedback).is_empty());
+        assert!(config.validate().is_ok());
+        assert!(!config.is_default());
+    }
+
+    #[test]
+    fn test_hooks_config_validate() {
+        let config = HooksConfig {
+            timeout_secs: 0,
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+
+        let config = HooksConfig {
+            on_feedback: vec![" ".to_string()],
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+        assert!(HooksConfig::default().is_default());
+    }
+}
@@ -0,0 +1,17 @@
+//! 事件钩子
+//!
+//! 设备连接、断开、APP 反馈按钮、低电量和紧急停止时执行配置文件 `[hooks]` 段中的 shell 命令，
+//! 或调用代码中注册的回调。命令通过环境变量获取事件信息：
+//!
+//! | 变量 | 说明 |
+//! |------|------|
+//! | `DGLAB_EVENT` | 事件名（connect/disconnect/feedback/battery_low/emergency_stop） |
+//! | `DGLAB_DEVICE` | 设备 ID |
+//! | `DGLAB_BUTTON` | 反馈按钮，如 `A0`（仅 feedback） |
+//! | `DGLAB_BATTERY` | 电量百分比（仅 battery_low） |
+
+pub mod config;
+pub mod runner;
+
+pub use config::{HooksConfig, DEFAULT_BATTERY_LOW_THRESHOLD, DEFAULT_HOOK_TIMEOUT_SECS};
+pub use runner::{run_command, HookCallback, HookEvent, HookKind, HookRunner};
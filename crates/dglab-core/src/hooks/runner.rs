@@ -0,0 +1,393 @@
+//! 钩子执行
+
+use std::collections::HashSet;
+use std::fmt;
+use std::process::{ExitStatus, Stdio};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::process::Command;
+use tokio::sync::broadcast;
+use tracing::{debug, info, warn};
+
+use dglab_protocol::wifi::FeedbackButton;
+
+use super::config::HooksConfig;
+use crate::device::DeviceState;
+use crate::error::{CoreError, Result};
+use crate::session::SessionEvent;
+
+/// 钩子事件类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HookKind {
+    /// 设备连接
+    Connect,
+    /// 设备断开
+    Disconnect,
+    /// APP 反馈按钮
+    Feedback,
+    /// 低电量
+    BatteryLow,
+    /// 紧急停止
+    EmergencyStop,
+}
+
+impl HookKind {
+    /// 全部事件类型
+    pub const ALL: [Self; 5] = [
+        Self::Connect,
+        Self::Disconnect,
+        Self::Feedback,
+        Self::BatteryLow,
+        Self::EmergencyStop,
+    ];
+
+    /// 事件名（即 `DGLAB_EVENT` 的值）
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Connect => "connect",
+            Self::Disconnect => "disconnect",
+            Self::Feedback => "feedback",
+            Self::BatteryLow => "battery_low",
+            Self::EmergencyStop => "emergency_stop",
+        }
+    }
+}
+
+impl fmt::Display for HookKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// 钩子事件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HookEvent {
+    /// 设备连接（设备 ID）
+    Connect(String),
+    /// 设备断开（设备 ID）
+    Disconnect(String),
+    /// APP 反馈按钮（设备 ID, 按钮）
+    Feedback(String, FeedbackButton),
+    /// 低电量（设备 ID, 电量百分比）
+    BatteryLow(String, u8),
+    /// 紧急停止（设备 ID）
+    EmergencyStop(String),
+}
+
+impl HookEvent {
+    /// 事件类型
+    pub fn kind(&self) -> HookKind {
+        match self {
+            Self::Connect(_) => HookKind::Connect,
+            Self::Disconnect(_) => HookKind::Disconnect,
+            Self::Feedback(..) => HookKind::Feedback,
+            Self::BatteryLow(..) => HookKind::BatteryLow,
+            Self::EmergencyStop(_) => HookKind::EmergencyStop,
+        }
+    }
+
+    /// 设备 ID
+    pub fn device_id(&self) -> &str {
+        match self {
+            Self::Connect(id)
+            | Self::Disconnect(id)
+            | Self::Feedback(id, _)
+            | Self::BatteryLow(id, _)
+            | Self::EmergencyStop(id) => id,
+        }
+    }
+
+    /// 传给命令的环境变量
+    pub fn env(&self) -> Vec<(&'static str, String)> {
+        let mut env = vec![
+            ("DGLAB_EVENT", self.kind().to_string()),
+            ("DGLAB_DEVICE", self.device_id().to_string()),
+        ];
+        match self {
+            Self::Feedback(_, button) => env.push(("DGLAB_BUTTON", format!("{:?}", button))),
+            Self::BatteryLow(_, level) => env.push(("DGLAB_BATTERY", level.to_string())),
+            _ => {}
+        }
+        env
+    }
+}
+
+/// 钩子回调
+pub type HookCallback = Arc<dyn Fn(&HookEvent) + Send + Sync>;
+
+/// 钩子执行器
+///
+/// 订阅会话事件，转换为 [`HookEvent`] 后调用注册的回调并在后台执行配置的命令。
+/// 同一设备的连接、断开和低电量只在状态变化时触发一次。
+pub struct HookRunner {
+    /// 钩子配置
+    config: HooksConfig,
+    /// 代码注册的回调
+    callbacks: Vec<(HookKind, HookCallback)>,
+    /// 已连接的设备
+    connected: HashSet<String>,
+    /// 已触发低电量的设备（电量回升到阈值以上后重新计入）
+    battery_low: HashSet<String>,
+}
+
+impl HookRunner {
+    /// 创建执行器
+    pub fn new(config: HooksConfig) -> Self {
+        Self {
+            config,
+            callbacks: Vec::new(),
+            connected: HashSet::new(),
+            battery_low: HashSet::new(),
+        }
+    }
+
+    /// 获取配置
+    pub fn config(&self) -> &HooksConfig {
+        &self.config
+    }
+
+    /// 替换配置（配置文件重新加载后调用）
+    pub fn set_config(&mut self, config: HooksConfig) {
+        self.config = config;
+    }
+
+    /// 注册回调
+    pub fn on<F>(&mut self, kind: HookKind, callback: F)
+    where
+        F: Fn(&HookEvent) + Send + Sync + 'static,
+    {
+        self.callbacks.push((kind, Arc::new(callback)));
+    }
+
+    /// 将会话事件转换为钩子事件
+    ///
+    /// 不需要触发钩子时返回 `None`。
+    pub fn trigger(&mut self, event: &SessionEvent) -> Option<HookEvent> {
+        match event {
+            // 设备在连接后才加入会话
+            SessionEvent::DeviceAdded(id) => self
+                .connected
+                .insert(id.clone())
+                .then(|| HookEvent::Connect(id.clone())),
+            SessionEvent::DeviceStateChanged(id, DeviceState::Connected | DeviceState::Running) => {
+                self.connected
+                    .insert(id.clone())
+                    .then(|| HookEvent::Connect(id.clone()))
+            }
+            SessionEvent::DeviceStateChanged(
+                id,
+                DeviceState::Disconnected | DeviceState::Error,
+            ) => self
+                .connected
+                .remove(id)
+                .then(|| HookEvent::Disconnect(id.clone())),
+            SessionEvent::DeviceRemoved(id) => {
+                let _ = self.battery_low.remove(id);
+                self.connected
+                    .remove(id)
+                    .then(|| HookEvent::Disconnect(id.clone()))
+            }
+            SessionEvent::Feedback(id, button) => Some(HookEvent::Feedback(id.clone(), *button)),
+            SessionEvent::Battery(id, level, _) => {
+                if *level > self.config.battery_low_threshold {
+                    let _ = self.battery_low.remove(id);
+                    return None;
+                }
+                self.battery_low
+                    .insert(id.clone())
+                    .then(|| HookEvent::BatteryLow(id.clone(), *level))
+            }
+            SessionEvent::EmergencyStop(id) => Some(HookEvent::EmergencyStop(id.clone())),
+            _ => None,
+        }
+    }
+
+    /// 触发钩子：依次调用回调，配置的命令在后台并发执行
+    pub fn fire(&self, event: &HookEvent) {
+        let kind = event.kind();
+        for (_, callback) in self.callbacks.iter().filter(|(k, _)| *k == kind) {
+            callback(event);
+        }
+
+        let timeout = Duration::from_secs(self.config.timeout_secs);
+        for command in self.config.commands(kind) {
+            let command = command.clone();
+            let event = event.clone();
+            let _ = tokio::spawn(async move {
+                match run_command(&command, &event, timeout).await {
+                    Ok(status) if status.success() => {}
+                    Ok(status) => warn!("Hook '{}' exited with {}", command, status),
+                    Err(e) => warn!("Hook '{}' failed: {}", command, e),
+                }
+            });
+        }
+    }
+
+    /// 处理会话事件，返回触发的钩子事件
+    pub fn handle(&mut self, event: &SessionEvent) -> Option<HookEvent> {
+        let hook = self.trigger(event)?;
+        debug!("Hook event {} for {}", hook.kind(), hook.device_id());
+        self.fire(&hook);
+        Some(hook)
+    }
+
+    /// 持续处理会话事件，直到事件通道关闭
+    pub async fn run(mut self, mut events: broadcast::Receiver<SessionEvent>) {
+        loop {
+            match events.recv().await {
+                Ok(event) => {
+                    let _ = self.handle(&event);
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!("Hook runner lagged by {} events", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    }
+}
+
+/// 通过系统 shell 执行钩子命令
+///
+/// 事件信息通过环境变量传递，超时后终止命令并返回 [`CoreError::Timeout`]。
+pub async fn run_command(
+    command: &str,
+    event: &HookEvent,
+    timeout: Duration,
+) -> Result<ExitStatus> {
+    #[cfg(windows)]
+    let (shell, flag) = ("cmd", "/C");
+    #[cfg(not(windows))]
+    let (shell, flag) = ("sh", "-c");
+
+    info!("Running {} hook: {}", event.kind(), command);
+    let mut cmd = Command::new(shell);
+    let _ = cmd
+        .arg(flag)
+        .arg(command)
+        .envs(event.env())
+        .stdin(Stdio::null())
+        .kill_on_drop(true);
+
+    let output = tokio::time::timeout(timeout, cmd.output())
+        .await
+        .map_err(|_| {
+            CoreError::Timeout(format!("hook '{}' after {}s", command, timeout.as_secs()))
+        })??;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if !stdout.trim().is_empty() {
+        debug!("Hook '{}' stdout: {}", command, stdout.trim());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !stderr.trim().is_empty() {
+        debug!("Hook '{}' stderr: {}", command, stderr.trim());
+    }
+    Ok(output.status)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    #[test]
+    fn test_connect_disconnect_once() {
+        let mut runner = HookRunner::new(HooksConfig::default());
+        let id = "dev-1".to_string();
+
+        assert_eq!(
+            runner.trigger(&SessionEvent::DeviceAdded(id.clone())),
+            Some(HookEvent::Connect(id.clone()))
+        );
+        // 运行/停止不重复触发
+        let running = SessionEvent::DeviceStateChanged(id.clone(), DeviceState::Running);
+        assert_eq!(runner.trigger(&running), None);
+
+        let lost = SessionEvent::DeviceStateChanged(id.clone(), DeviceState::Disconnected);
+        assert_eq!(
+            runner.trigger(&lost),
+            Some(HookEvent::Disconnect(id.clone()))
+        );
+        assert_eq!(
+            runner.trigger(&SessionEvent::DeviceRemoved(id.clone())),
+            None
+        );
+
+        let connected = SessionEvent::DeviceStateChanged(id.clone(), DeviceState::Connected);
+        assert_eq!(runner.trigger(&connected), Some(HookEvent::Connect(id)));
+    }
+
+    #[test]
+    fn test_battery_low_rearms() {
+        let mut runner = HookRunner::new(HooksConfig {
+            battery_low_threshold: 20,
+            ..Default::default()
+        });
+        let battery = |level| SessionEvent::Battery("dev-1".to_string(), level, None);
+
+        assert_eq!(runner.trigger(&battery(50)), None);
+        assert_eq!(
+            runner.trigger(&battery(20)),
+            Some(HookEvent::BatteryLow("dev-1".to_string(), 20))
+        );
+        assert_eq!(runner.trigger(&battery(15)), None);
+        assert_eq!(runner.trigger(&battery(80)), None);
+        assert!(runner.trigger(&battery(10)).is_some());
+    }
+
+    #[test]
+    fn test_event_env() {
+        let event = HookEvent::Feedback("dev-1".to_string(), FeedbackButton::B2);
+        assert_eq!(
+            event.env(),
+            vec![
+                ("DGLAB_EVENT", "feedback".to_string()),
+                ("DGLAB_DEVICE", "dev-1".to_string()),
+                ("DGLAB_BUTTON", "B2".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_callbacks() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let mut runner = HookRunner::new(HooksConfig::default());
+        let counter = count.clone();
+        runner.on(HookKind::EmergencyStop, move |event| {
+            assert_eq!(event.device_id(), "dev-1");
+            let _ = counter.fetch_add(1, Ordering::SeqCst);
+        });
+
+        let _ = runner.handle(&SessionEvent::EmergencyStop("dev-1".to_string()));
+        let _ = runner.handle(&SessionEvent::Feedback(
+            "dev-1".to_string(),
+            FeedbackButton::A0,
+        ));
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_command() {
+        let event = HookEvent::BatteryLow("dev-1".to_string(), 12);
+        let timeout = Duration::from_secs(5);
+
+        let status = run_command(
+            r#"test "$DGLAB_EVENT/$DGLAB_DEVICE/$DGLAB_BATTERY" = "battery_low/dev-1/12""#,
+            &event,
+            timeout,
+        )
+        .await
+        .unwrap();
+        assert!(status.success());
+
+        let status = run_command("exit 3", &event, timeout).await.unwrap();
+        assert_eq!(status.code(), Some(3));
+
+        let result = run_command("sleep 5", &event, Duration::from_millis(100)).await;
+        assert!(matches!(result, Err(CoreError::Timeout(_))));
+    }
+}
@@ -0,0 +1,154 @@
+//! 极简 HTTP/1.1 读写
+//!
+//! 供 [`metrics`](crate::metrics) 和 [`webhook`](crate::webhook) 的服务使用：每个连接只处理
+//! 一个请求，只解析请求头，请求体按 `Content-Length` 读取后丢弃。
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+use crate::error::{CoreError, Result};
+
+/// 请求头最大长度
+const MAX_HEAD_BYTES: usize = 8 * 1024;
+
+/// 丢弃的请求体最大长度
+const MAX_BODY_BYTES: usize = 64 * 1024;
+
+/// 解析后的请求头
+#[derive(Debug)]
+pub(crate) struct Request {
+    /// 方法
+    pub method: String,
+    /// 请求目标（路径加查询参数）
+    pub target: String,
+    /// 请求头（名称保持原样）
+    headers: Vec<(String, String)>,
+}
+
+impl Request {
+    /// 按名称（不区分大小写）查找请求头
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// 去掉查询参数的路径，用于日志（查询参数中可能带有令牌）
+    pub fn path(&self) -> &str {
+        self.target.split('?').next().unwrap_or_default()
+    }
+}
+
+/// 读取请求头，丢弃请求体
+pub(crate) async fn read_request(stream: &mut TcpStream) -> Result<Request> {
+    let invalid = |message: &str| CoreError::InvalidParameter(message.to_string());
+
+    let mut buffer = Vec::new();
+    let head_end = loop {
+        if let Some(end) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+            break end;
+        }
+        if buffer.len() > MAX_HEAD_BYTES {
+            return Err(invalid("Request header too large"));
+        }
+        let mut chunk = [0u8; 1024];
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Err(invalid("Connection closed before end of request header"));
+        }
+        buffer.extend_from_slice(&chunk[..n]);
+    };
+
+    let head = std::str::from_utf8(&buffer[..head_end])
+        .map_err(|_| invalid("Request header is not UTF-8"))?;
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let (Some(method), Some(target), Some(version)) = (
+        request_line.next(),
+        request_line.next(),
+        request_line.next(),
+    ) else {
+        return Err(invalid("Malformed request line"));
+    };
+    if !version.starts_with("HTTP/1.") {
+        return Err(invalid("Unsupported HTTP version"));
+    }
+
+    let headers: Vec<(String, String)> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+        .collect();
+    let request = Request {
+        method: method.to_string(),
+        target: target.to_string(),
+        headers,
+    };
+
+    let content_length: usize = match request.header("content-length") {
+        Some(value) => value
+            .parse()
+            .map_err(|_| invalid("Invalid Content-Length"))?,
+        None => 0,
+    };
+
+    // 丢弃请求体，避免关闭连接时未读数据导致客户端收到 RST
+    let mut remaining = content_length
+        .min(MAX_BODY_BYTES)
+        .saturating_sub(buffer.len() - head_end - 4);
+    let mut chunk = [0u8; 1024];
+    while remaining > 0 {
+        let n = stream.read(&mut chunk[..remaining.min(1024)]).await?;
+        if n == 0 {
+            break;
+        }
+        remaining -= n;
+    }
+
+    Ok(request)
+}
+
+/// 写入响应并关闭连接
+///
+/// `headers` 为额外的响应头（如 `Allow`），`Content-Type`、`Content-Length` 和
+/// `Connection` 自动添加。
+pub(crate) async fn write_response(
+    stream: &mut TcpStream,
+    status: u16,
+    content_type: &str,
+    headers: &[(&str, &str)],
+    body: &str,
+) -> Result<()> {
+    let mut head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+        status,
+        reason(status),
+        content_type,
+        body.len()
+    );
+    for (name, value) in headers {
+        head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    head.push_str("\r\n");
+
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(body.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+/// 状态码对应的原因短语
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        409 => "Conflict",
+        503 => "Service Unavailable",
+        _ => "Internal Server Error",
+    }
+}
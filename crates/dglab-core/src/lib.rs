@@ -4,12 +4,22 @@
 
 #![warn(missing_docs)]
 
+pub mod config;
 pub mod device;
 pub mod error;
+pub mod feedback;
+pub mod gamepad;
+pub mod hooks;
+mod http;
+pub mod metrics;
+pub mod mqtt;
 pub mod preset;
 pub mod script;
+pub mod sensor;
 pub mod session;
+pub mod tempo;
 pub mod waveform;
+pub mod webhook;
 
 pub use device::{Device, DeviceEvent, DeviceState};
 pub use error::{CoreError, Result};
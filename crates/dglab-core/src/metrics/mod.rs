@@ -0,0 +1,13 @@
+//! 运行指标导出
+//!
+//! 设备层在状态和强度变化、发送 B0、收到 B1、WebSocket 重连时更新 [`registry`] 中的指标，
+//! 会话层记录紧急停止。[`MetricsServer`] 以 Prometheus 文本格式导出，供无人值守运行的
+//! 桥接守护进程接入监控。
+
+pub mod registry;
+pub mod server;
+
+pub use registry::{
+    device, global, DeviceMetrics, LatencyHistogram, MetricsRegistry, LATENCY_BUCKETS,
+};
+pub use server::{serve, MetricsServer, DEFAULT_METRICS_BIND};
@@ -0,0 +1,402 @@
+//! 指标注册表
+//!
+//! 每台设备一组 [`DeviceMetrics`]，按设备 ID 登记在全局注册表中。设备层在创建设备时取得
+//! 自己的指标句柄，之后只做原子计数，不需要持有注册表的锁。
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicU8, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+use std::time::Duration;
+
+use crate::device::DeviceState;
+
+/// B1 往返时延直方图的桶上界（秒）
+pub const LATENCY_BUCKETS: [f64; 9] = [0.025, 0.05, 0.075, 0.1, 0.15, 0.2, 0.3, 0.5, 1.0];
+
+/// 时延直方图
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LatencyHistogram {
+    /// 各桶的样本数（不累计，`+Inf` 桶由 `count` 得出）
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    /// 样本数
+    count: u64,
+    /// 样本总和
+    sum: Duration,
+}
+
+impl LatencyHistogram {
+    /// 记录一个样本
+    pub fn observe(&mut self, latency: Duration) {
+        let seconds = latency.as_secs_f64();
+        if let Some(index) = LATENCY_BUCKETS.iter().position(|&le| seconds <= le) {
+            self.buckets[index] += 1;
+        }
+        self.count += 1;
+        self.sum += latency;
+    }
+
+    /// 样本数
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// 样本总和
+    pub fn sum(&self) -> Duration {
+        self.sum
+    }
+
+    /// 各桶上界及累计样本数（不含 `+Inf`）
+    pub fn cumulative(&self) -> Vec<(f64, u64)> {
+        let mut total = 0;
+        LATENCY_BUCKETS
+            .iter()
+            .zip(self.buckets)
+            .map(|(&le, count)| {
+                total += count;
+                (le, total)
+            })
+            .collect()
+    }
+}
+
+/// 单台设备的指标
+#[derive(Debug, Default)]
+pub struct DeviceMetrics {
+    /// 是否已连接（包括运行中）
+    connected: AtomicBool,
+    /// A 通道当前强度
+    power_a: AtomicU8,
+    /// B 通道当前强度
+    power_b: AtomicU8,
+    /// 已发送的 B0 帧数
+    b0_frames: AtomicU64,
+    /// WebSocket 重连成功次数
+    ws_reconnects: AtomicU64,
+    /// 紧急停止次数
+    emergency_stops: AtomicU64,
+    /// B1 往返时延
+    b1_latency: Mutex<LatencyHistogram>,
+}
+
+impl DeviceMetrics {
+    /// 记录设备状态（断开后强度归零，避免导出断开前的旧值）
+    pub fn set_state(&self, state: DeviceState) {
+        let connected = matches!(state, DeviceState::Connected | DeviceState::Running);
+        self.connected.store(connected, Ordering::Relaxed);
+        if state == DeviceState::Disconnected {
+            self.power_a.store(0, Ordering::Relaxed);
+            self.power_b.store(0, Ordering::Relaxed);
+        }
+    }
+
+    /// 记录通道当前强度（由 [`BaseDevice`](crate::device::BaseDevice) 在设置强度或按设备反馈校正强度时更新）
+    pub fn set_power(&self, channel: u8, power: u8) {
+        match channel {
+            0 => self.power_a.store(power, Ordering::Relaxed),
+            1 => self.power_b.store(power, Ordering::Relaxed),
+            _ => {}
+        }
+    }
+
+    /// 记录一帧成功发送的 B0
+    pub fn record_b0_frame(&self) {
+        let _ = self.b0_frames.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// 记录一次 WebSocket 重连
+    pub fn record_ws_reconnect(&self) {
+        let _ = self.ws_reconnects.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// 记录一次紧急停止
+    pub fn record_emergency_stop(&self) {
+        let _ = self.emergency_stops.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// 记录一次 B1 往返时延
+    pub fn observe_b1_latency(&self, latency: Duration) {
+        self.histogram().observe(latency);
+    }
+
+    /// 是否已连接
+    pub fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::Relaxed)
+    }
+
+    /// 通道当前强度
+    pub fn power(&self, channel: u8) -> u8 {
+        match channel {
+            0 => self.power_a.load(Ordering::Relaxed),
+            1 => self.power_b.load(Ordering::Relaxed),
+            _ => 0,
+        }
+    }
+
+    /// 已发送的 B0 帧数
+    pub fn b0_frames(&self) -> u64 {
+        self.b0_frames.load(Ordering::Relaxed)
+    }
+
+    /// WebSocket 重连次数
+    pub fn ws_reconnects(&self) -> u64 {
+        self.ws_reconnects.load(Ordering::Relaxed)
+    }
+
+    /// 紧急停止次数
+    pub fn emergency_stops(&self) -> u64 {
+        self.emergency_stops.load(Ordering::Relaxed)
+    }
+
+    /// B1 往返时延直方图
+    pub fn b1_latency(&self) -> LatencyHistogram {
+        self.histogram().clone()
+    }
+
+    fn histogram(&self) -> MutexGuard<'_, LatencyHistogram> {
+        self.b1_latency.lock().unwrap_or_else(|p| p.into_inner())
+    }
+}
+
+/// 指标注册表
+#[derive(Debug, Default)]
+pub struct MetricsRegistry {
+    /// 设备 ID → 指标
+    devices: Mutex<BTreeMap<String, Arc<DeviceMetrics>>>,
+}
+
+impl MetricsRegistry {
+    /// 创建空的注册表
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 获取设备的指标，不存在时登记
+    ///
+    /// 同一 ID 的设备（如断开后重新创建）共用一组指标，计数不会归零。
+    pub fn device(&self, device_id: &str) -> Arc<DeviceMetrics> {
+        self.devices()
+            .entry(device_id.to_string())
+            .or_default()
+            .clone()
+    }
+
+    /// 已连接的设备数
+    pub fn connected_devices(&self) -> usize {
+        self.devices()
+            .values()
+            .filter(|metrics| metrics.is_connected())
+            .count()
+    }
+
+    /// 以 Prometheus 文本格式导出
+    pub fn render(&self) -> String {
+        let devices: Vec<(String, Arc<DeviceMetrics>)> = self
+            .devices()
+            .iter()
+            .map(|(id, metrics)| (escape_label(id), metrics.clone()))
+            .collect();
+        let connected = devices.iter().filter(|(_, m)| m.is_connected()).count();
+
+        let mut out = String::new();
+        header(
+            &mut out,
+            "dglab_connected_devices",
+            "gauge",
+            "Number of connected devices",
+        );
+        let _ = writeln!(out, "dglab_connected_devices {}", connected);
+
+        header(
+            &mut out,
+            "dglab_device_connected",
+            "gauge",
+            "Whether the device is connected (1) or not (0)",
+        );
+        for (id, metrics) in &devices {
+            let _ = writeln!(
+                out,
+                "dglab_device_connected{{device=\"{}\"}} {}",
+                id,
+                u8::from(metrics.is_connected())
+            );
+        }
+
+        header(
+            &mut out,
+            "dglab_channel_strength",
+            "gauge",
+            "Current channel strength",
+        );
+        for (id, metrics) in &devices {
+            for (channel, name) in [(0, "a"), (1, "b")] {
+                let _ = writeln!(
+                    out,
+                    "dglab_channel_strength{{device=\"{}\",channel=\"{}\"}} {}",
+                    id,
+                    name,
+                    metrics.power(channel)
+                );
+            }
+        }
+
+        let counters: [(&str, &str, fn(&DeviceMetrics) -> u64); 3] = [
+            (
+                "dglab_b0_frames_sent_total",
+                "B0 frames sent over BLE",
+                DeviceMetrics::b0_frames,
+            ),
+            (
+                "dglab_ws_reconnects_total",
+                "WebSocket reconnections",
+                DeviceMetrics::ws_reconnects,
+            ),
+            (
+                "dglab_emergency_stops_total",
+                "Emergency stops",
+                DeviceMetrics::emergency_stops,
+            ),
+        ];
+        for (name, help, value) in counters {
+            header(&mut out, name, "counter", help);
+            for (id, metrics) in &devices {
+                let _ = writeln!(out, "{}{{device=\"{}\"}} {}", name, id, value(metrics));
+            }
+        }
+
+        header(
+            &mut out,
+            "dglab_b1_latency_seconds",
+            "histogram",
+            "Round trip time from B0 to the matching B1 response",
+        );
+        for (id, metrics) in &devices {
+            let histogram = metrics.b1_latency();
+            for (le, count) in histogram.cumulative() {
+                let _ = writeln!(
+                    out,
+                    "dglab_b1_latency_seconds_bucket{{device=\"{}\",le=\"{}\"}} {}",
+                    id, le, count
+                );
+            }
+            let _ = writeln!(
+                out,
+                "dglab_b1_latency_seconds_bucket{{device=\"{}\",le=\"+Inf\"}} {}",
+                id,
+                histogram.count()
+            );
+            let _ = writeln!(
+                out,
+                "dglab_b1_latency_seconds_sum{{device=\"{}\"}} {}",
+                id,
+                histogram.sum().as_secs_f64()
+            );
+            let _ = writeln!(
+                out,
+                "dglab_b1_latency_seconds_count{{device=\"{}\"}} {}",
+                id,
+                histogram.count()
+            );
+        }
+
+        out
+    }
+
+    fn devices(&self) -> MutexGuard<'_, BTreeMap<String, Arc<DeviceMetrics>>> {
+        self.devices.lock().unwrap_or_else(|p| p.into_inner())
+    }
+}
+
+/// 进程内的全局注册表
+pub fn global() -> &'static MetricsRegistry {
+    static REGISTRY: OnceLock<MetricsRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(MetricsRegistry::new)
+}
+
+/// 获取全局注册表中设备的指标
+pub fn device(device_id: &str) -> Arc<DeviceMetrics> {
+    global().device(device_id)
+}
+
+/// 写入指标的 HELP 和 TYPE 行
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+/// 转义标签值中的反斜杠、引号和换行
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram_buckets() {
+        let mut histogram = LatencyHistogram::default();
+        histogram.observe(Duration::from_millis(20));
+        histogram.observe(Duration::from_millis(90));
+        histogram.observe(Duration::from_millis(100));
+        histogram.observe(Duration::from_secs(3));
+
+        let cumulative = histogram.cumulative();
+        assert_eq!(cumulative[0], (0.025, 1));
+        assert_eq!(cumulative[3], (0.1, 3));
+        assert_eq!(cumulative.last(), Some(&(1.0, 3)));
+        assert_eq!(histogram.count(), 4);
+        assert_eq!(histogram.sum(), Duration::from_millis(3210));
+    }
+
+    #[test]
+    fn test_device_metrics() {
+        let registry = MetricsRegistry::new();
+        let metrics = registry.device("47L121000");
+        metrics.set_state(DeviceState::Running);
+        metrics.set_power(0, 30);
+        metrics.record_b0_frame();
+        metrics.record_b0_frame();
+
+        // 同一 ID 取得同一组指标
+        let again = registry.device("47L121000");
+        assert_eq!(again.b0_frames(), 2);
+        assert_eq!(again.power(0), 30);
+        assert_eq!(registry.connected_devices(), 1);
+
+        metrics.set_state(DeviceState::Disconnected);
+        assert_eq!(registry.connected_devices(), 0);
+        assert_eq!(again.power(0), 0);
+        assert_eq!(again.b0_frames(), 2);
+    }
+
+    #[test]
+    fn test_render() {
+        let registry = MetricsRegistry::new();
+        let metrics = registry.device("coyote \"1\"");
+        metrics.set_state(DeviceState::Connected);
+        metrics.set_power(1, 12);
+        metrics.record_emergency_stop();
+        metrics.record_ws_reconnect();
+        metrics.observe_b1_latency(Duration::from_millis(40));
+
+        let text = registry.render();
+        assert!(text.contains("# TYPE dglab_connected_devices gauge\ndglab_connected_devices 1\n"));
+        assert!(
+            text.contains("dglab_channel_strength{device=\"coyote \\\"1\\\"\",channel=\"b\"} 12")
+        );
+        assert!(text.contains("dglab_emergency_stops_total{device=\"coyote \\\"1\\\"\"} 1"));
+        assert!(text.contains("dglab_ws_reconnects_total{device=\"coyote \\\"1\\\"\"} 1"));
+        assert!(text.contains("dglab_b0_frames_sent_total{device=\"coyote \\\"1\\\"\"} 0"));
+        assert!(text.contains(
+            "dglab_b1_latency_seconds_bucket{device=\"coyote \\\"1\\\"\",le=\"0.05\"} 1"
+        ));
+        assert!(text.contains(
+            "dglab_b1_latency_seconds_bucket{device=\"coyote \\\"1\\\"\",le=\"+Inf\"} 1"
+        ));
+        assert!(text.contains("dglab_b1_latency_seconds_count{device=\"coyote \\\"1\\\"\"} 1"));
+    }
+}
@@ -0,0 +1,182 @@
+//! 指标 HTTP 端点
+//!
+//! 只响应 `GET /metrics`（和 `HEAD`），返回 Prometheus 文本格式。与 Prometheus 的习惯一致，
+//! 端点不鉴权，默认只监听本机地址。
+
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use futures::stream::{FuturesUnordered, StreamExt};
+use tokio::net::{TcpListener, TcpStream};
+use tracing::{debug, info, warn};
+
+use super::registry::{global, MetricsRegistry};
+use crate::error::{CoreError, Result};
+use crate::http;
+
+/// 默认监听地址（只接受本机请求）
+pub const DEFAULT_METRICS_BIND: &str = "127.0.0.1:9464";
+
+/// Prometheus 文本格式的 Content-Type
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// 读取请求的超时时间
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 指标端点
+#[derive(Debug, Clone, Copy)]
+pub struct MetricsServer {
+    /// 监听地址
+    address: SocketAddr,
+}
+
+impl MetricsServer {
+    /// 创建指标端点（`bind` 为 `ip:port`）
+    pub fn new(bind: &str) -> Result<Self> {
+        let address = bind.trim().parse().map_err(|_| {
+            CoreError::ConfigError(format!(
+                "Invalid metrics bind address '{}', expected ip:port",
+                bind
+            ))
+        })?;
+        Ok(Self { address })
+    }
+
+    /// 监听地址
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// 监听配置的地址
+    pub async fn bind(&self) -> Result<TcpListener> {
+        let listener = TcpListener::bind(self.address).await?;
+        info!(
+            "Metrics listening on http://{}/metrics",
+            listener.local_addr()?
+        );
+        Ok(listener)
+    }
+
+    /// 监听并导出全局注册表
+    ///
+    /// 持续运行，调用方通过取消 future 停止。
+    pub async fn run(&self) -> Result<()> {
+        let listener = self.bind().await?;
+        serve(listener, global()).await
+    }
+}
+
+/// 在已监听的端口上导出指定注册表
+pub async fn serve(listener: TcpListener, registry: &MetricsRegistry) -> Result<()> {
+    let mut connections = FuturesUnordered::new();
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((stream, peer)) => connections.push(handle_connection(stream, peer, registry)),
+                Err(e) => warn!("Failed to accept metrics connection: {}", e),
+            },
+            Some(()) = connections.next(), if !connections.is_empty() => {}
+        }
+    }
+}
+
+/// 处理一个连接（一个请求后关闭）
+async fn handle_connection(mut stream: TcpStream, peer: SocketAddr, registry: &MetricsRegistry) {
+    let request = tokio::time::timeout(READ_TIMEOUT, http::read_request(&mut stream)).await;
+    let (status, body) = match request {
+        Ok(Ok(request)) => {
+            debug!(
+                "Metrics {} {} from {}",
+                request.method,
+                request.path(),
+                peer
+            );
+            respond(&request.method, request.path(), registry)
+        }
+        Ok(Err(e)) => (400, format!("{}\n", e)),
+        Err(_) => (408, "Request timed out\n".to_string()),
+    };
+
+    let content_type = if status == 200 {
+        CONTENT_TYPE
+    } else {
+        "text/plain; charset=utf-8"
+    };
+    let headers: &[(&str, &str)] = if status == 405 {
+        &[("Allow", "GET, HEAD")]
+    } else {
+        &[]
+    };
+    if let Err(e) = http::write_response(&mut stream, status, content_type, headers, &body).await {
+        debug!("Failed to send metrics response to {}: {}", peer, e);
+    }
+}
+
+/// 路由请求，返回状态码和内容
+fn respond(method: &str, path: &str, registry: &MetricsRegistry) -> (u16, String) {
+    match (method, path) {
+        ("GET", "/metrics") => (200, registry.render()),
+        ("HEAD", "/metrics") => (200, String::new()),
+        (_, "/metrics") => (405, "Use GET\n".to_string()),
+        _ => (404, "Not found, try /metrics\n".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device::DeviceState;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    async fn send(address: SocketAddr, request: &str) -> (u16, String) {
+        let mut stream = TcpStream::connect(address).await.unwrap();
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        let _ = stream.read_to_string(&mut response).await.unwrap();
+
+        let status = response[9..12].parse().unwrap();
+        let body = response.split_once("\r\n\r\n").unwrap().1.to_string();
+        (status, body)
+    }
+
+    #[test]
+    fn test_new() {
+        assert_eq!(
+            MetricsServer::new(DEFAULT_METRICS_BIND).unwrap().address(),
+            "127.0.0.1:9464".parse().unwrap()
+        );
+        assert!(MetricsServer::new("localhost").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_requests() {
+        let registry = MetricsRegistry::new();
+        registry.device("mock-1").set_state(DeviceState::Running);
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+
+        let requests = async {
+            let (status, body) = send(address, "GET /metrics HTTP/1.1\r\n\r\n").await;
+            assert_eq!(status, 200);
+            assert!(body.contains("dglab_connected_devices 1\n"));
+            assert!(body.contains("dglab_device_connected{device=\"mock-1\"} 1"));
+
+            let (status, body) = send(address, "HEAD /metrics HTTP/1.1\r\n\r\n").await;
+            assert_eq!(status, 200);
+            assert!(body.is_empty());
+
+            let (status, _) = send(address, "POST /metrics HTTP/1.1\r\n\r\n").await;
+            assert_eq!(status, 405);
+            let (status, _) = send(address, "GET / HTTP/1.1\r\n\r\n").await;
+            assert_eq!(status, 404);
+            let (status, _) = send(address, "garbage\r\n\r\n").await;
+            assert_eq!(status, 400);
+        };
+
+        tokio::select! {
+            result = serve(listener, &registry) => panic!("server stopped: {:?}", result),
+            _ = requests => {}
+        }
+    }
+}
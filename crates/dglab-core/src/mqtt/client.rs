@@ -0,0 +1,303 @@
+//! MQTT 客户端
+//!
+//! 连接 broker 后订阅指令主题并转发给会话管理器，按固定间隔发布设备状态，
+//! 只发布与上次不同的内容（retain），断线后自动重连并重新订阅。
+
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use rumqttc::{AsyncClient, Event, LastWill, MqttOptions, Packet, QoS};
+use tracing::{debug, info, warn};
+
+use super::config::MqttConfig;
+use super::topics::{
+    device_key, parse_command, split_token, state_messages, status_topic, subscriptions,
+    MqttCommand, ALL_DEVICES,
+};
+use crate::config::AccessConfig;
+use crate::error::{CoreError, Result};
+use crate::session::SessionManager;
+
+/// 连接失败后的重试间隔
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// 请求队列容量
+const REQUEST_CAPACITY: usize = 64;
+
+/// MQTT 桥接
+///
+/// 把会话管理器中的设备暴露到 MQTT，供 Home Assistant 等家庭自动化系统控制和监测。
+/// 主题格式见 [`topics`](super::topics)。
+#[derive(Debug, Clone)]
+pub struct MqttBridge {
+    /// 配置
+    config: MqttConfig,
+    /// 访问控制，按每条指令携带的令牌确定角色
+    access: AccessConfig,
+}
+
+impl MqttBridge {
+    /// 创建 MQTT 桥接（校验配置，不限制权限）
+    pub fn new(config: MqttConfig) -> Result<Self> {
+        config.validate()?;
+        Ok(Self {
+            config,
+            access: AccessConfig::default(),
+        })
+    }
+
+    /// 设置访问控制，启用后指令须携带 `[access]` 中的令牌
+    pub fn with_access(mut self, access: &AccessConfig) -> Self {
+        self.access = access.clone();
+        self
+    }
+
+    /// 获取配置
+    pub fn config(&self) -> &MqttConfig {
+        &self.config
+    }
+
+    /// 是否启用访问控制
+    pub fn access_enabled(&self) -> bool {
+        self.access.is_enabled()
+    }
+
+    /// 运行桥接
+    ///
+    /// 持续运行，连接错误只记录日志并重试；调用方通过取消 future 停止。
+    pub async fn run(&self, manager: &SessionManager) -> Result<()> {
+        let (host, port) = self.config.broker_address()?;
+        let prefix = self.config.prefix().to_string();
+
+        let mut options = MqttOptions::new(self.config.client_id.clone(), host.clone(), port);
+        let _ = options
+            .set_keep_alive(Duration::from_secs(self.config.keep_alive_secs))
+            .set_last_will(LastWill::new(
+                status_topic(&prefix),
+                "offline",
+                QoS::AtLeastOnce,
+                true,
+            ));
+        if let Some(username) = &self.config.username {
+            let _ = options.set_credentials(
+                username.clone(),
+                self.config.password.clone().unwrap_or_default(),
+            );
+        }
+
+        info!("Connecting to MQTT broker {}:{}", host, port);
+        let (client, mut eventloop) = AsyncClient::new(options, REQUEST_CAPACITY);
+        let mut ticker =
+            tokio::time::interval(Duration::from_millis(self.config.publish_interval_ms));
+        let mut published = HashMap::new();
+        let mut known = HashSet::new();
+
+        loop {
+            tokio::select! {
+                event = eventloop.poll() => match event {
+                    Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                        info!("Connected to MQTT broker {}:{}", host, port);
+                        // 订阅失败只记录日志，不退出事件循环
+                        for topic in subscriptions(&prefix) {
+                            if let Err(e) = client.try_subscribe(topic.clone(), QoS::AtLeastOnce) {
+                                warn!("Failed to subscribe to {}: {}", topic, e);
+                            }
+                        }
+                        let status = status_topic(&prefix);
+                        if let Err(e) = client.try_publish(status, QoS::AtLeastOnce, true, "online") {
+                            warn!("Failed to publish MQTT status: {}", e);
+                        }
+                        // 重连后重新发布全部状态
+                        published.clear();
+                    }
+                    Ok(Event::Incoming(Packet::Publish(publish))) => {
+                        let payload = String::from_utf8_lossy(&publish.payload);
+                        debug!("MQTT message {}: {}", publish.topic, payload);
+                        if let Err(e) = self.handle_message(manager, &prefix, &publish.topic, &payload).await {
+                            warn!("Failed to handle MQTT message on {}: {}", publish.topic, e);
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        warn!("MQTT connection error: {}, retrying in {:?}", e, RETRY_DELAY);
+                        tokio::time::sleep(RETRY_DELAY).await;
+                    }
+                },
+                _ = ticker.tick() => {
+                    publish_states(&client, manager, &prefix, &mut published, &mut known).await;
+                }
+            }
+        }
+    }
+
+    /// 处理一条指令消息
+    async fn handle_message(
+        &self,
+        manager: &SessionManager,
+        prefix: &str,
+        topic: &str,
+        payload: &str,
+    ) -> Result<()> {
+        let (token, payload) = split_token(payload);
+        let Some((key, command)) = parse_command(prefix, topic, &payload)? else {
+            return Ok(());
+        };
+        let role = self
+            .access
+            .authorize(token.as_deref(), command.permission())?;
+
+        let targets: Vec<String> = manager
+            .list_devices()
+            .await
+            .into_iter()
+            .filter(|id| key == ALL_DEVICES || device_key(id) == key)
+            .collect();
+        if targets.is_empty() {
+            return Err(CoreError::DeviceNotFound(key));
+        }
+
+        for device_id in targets {
+            info!("MQTT command for {} ({}): {:?}", device_id, role, command);
+            execute(manager, &device_id, &command).await?;
+        }
+        Ok(())
+    }
+}
+
+/// 在设备上执行指令
+///
+/// 强度经会话管理器写入，受校准和安全限制约束。
+async fn execute(manager: &SessionManager, device_id: &str, command: &MqttCommand) -> Result<()> {
+    match command {
+        MqttCommand::Power { channel, power } => {
+            let _ = manager.set_power(device_id, *channel, *power).await?;
+            Ok(())
+        }
+        MqttCommand::Waveform { channel, name } => {
+            let config = manager.resolve_waveform(name).await?.to_device_config();
+            let channels = match channel {
+                Some(channel) => vec![*channel],
+                None => vec![0, 1],
+            };
+            for channel in channels {
+                manager
+                    .set_waveform(device_id, channel, config.clone())
+                    .await?;
+            }
+            Ok(())
+        }
+        MqttCommand::EmergencyStop => manager.emergency_stop(device_id).await,
+    }
+}
+
+/// 发布发生变化的设备状态；移出会话的设备发布为 disconnected
+async fn publish_states(
+    client: &AsyncClient,
+    manager: &SessionManager,
+    prefix: &str,
+    published: &mut HashMap<String, String>,
+    known: &mut HashSet<String>,
+) {
+    let mut present = HashSet::new();
+    let mut messages = Vec::new();
+
+    for device_id in manager.list_devices().await {
+        let Some(device) = manager.get_device(&device_id).await else {
+            continue;
+        };
+        let (info, state) = {
+            let device = device.read().await;
+            (device.info(), device.state())
+        };
+        messages.extend(state_messages(prefix, &info, state));
+        let _ = present.insert(device_id);
+    }
+
+    for removed in known.difference(&present) {
+        let topic = format!("{}/{}/state", prefix, device_key(removed));
+        messages.push((topic, "disconnected".to_string()));
+    }
+    *known = present;
+
+    for (topic, payload) in messages {
+        if published.get(&topic) == Some(&payload) {
+            continue;
+        }
+        match client.try_publish(topic.clone(), QoS::AtLeastOnce, true, payload.clone()) {
+            Ok(()) => {
+                let _ = published.insert(topic, payload);
+            }
+            Err(e) => debug!("Failed to publish {}: {}", topic, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{AccessToken, Role, SafetyConfig};
+    use crate::device::{Device, MockDevice};
+
+    #[tokio::test]
+    async fn test_command_requires_peer_token() {
+        let mut device = MockDevice::new("mock-1".to_string(), "Mock".to_string());
+        device.connect().await.unwrap();
+        let manager = SessionManager::new();
+        manager.add_device(Box::new(device)).await.unwrap();
+
+        let access = AccessConfig {
+            tokens: vec![
+                AccessToken {
+                    name: "dashboard".to_string(),
+                    token: "view".to_string(),
+                    role: Role::Viewer,
+                },
+                AccessToken {
+                    name: "home-assistant".to_string(),
+                    token: "ctrl".to_string(),
+                    role: Role::Controller,
+                },
+            ],
+        };
+        let bridge = MqttBridge::new(MqttConfig::default())
+            .unwrap()
+            .with_access(&access);
+        let topic = "dglab/mock-1/power/a";
+
+        // 不带令牌或令牌角色不足时拒绝
+        for payload in ["30", r#"{"token":"view","value":30}"#] {
+            let result = bridge
+                .handle_message(&manager, "dglab", topic, payload)
+                .await;
+            assert!(matches!(result, Err(CoreError::Rejected(_))));
+        }
+        let device = manager.get_device("mock-1").await.unwrap();
+        assert_eq!(device.read().await.get_power(0), 0);
+
+        bridge
+            .handle_message(&manager, "dglab", topic, r#"{"token":"ctrl","value":30}"#)
+            .await
+            .unwrap();
+        assert_eq!(device.read().await.get_power(0), 30);
+    }
+
+    #[tokio::test]
+    async fn test_power_command_respects_safety_limit() {
+        let mut device = MockDevice::new("mock-1".to_string(), "Mock".to_string());
+        device.connect().await.unwrap();
+        let manager = SessionManager::new();
+        manager.add_device(Box::new(device)).await.unwrap();
+        manager.set_safety(SafetyConfig {
+            max_power_a: 20,
+            ..SafetyConfig::default()
+        });
+
+        let command = MqttCommand::Power {
+            channel: 0,
+            power: 80,
+        };
+        execute(&manager, "mock-1", &command).await.unwrap();
+        let device = manager.get_device("mock-1").await.unwrap();
+        assert_eq!(device.read().await.get_power(0), 20);
+    }
+}
@@ -0,0 +1,173 @@
+//! MQTT 配置
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{CoreError, Result};
+
+/// MQTT 默认端口
+pub const DEFAULT_MQTT_PORT: u16 = 1883;
+
+/// 默认主题前缀
+pub const DEFAULT_TOPIC_PREFIX: &str = "dglab";
+
+/// MQTT 配置（配置文件 `[mqtt]` 段）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MqttConfig {
+    /// Broker 地址（host、host:port 或 mqtt://host:port）
+    pub broker: String,
+    /// 客户端 ID
+    pub client_id: String,
+    /// 主题前缀
+    pub topic_prefix: String,
+    /// 用户名
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    /// 密码
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    /// 保活间隔（秒）
+    pub keep_alive_secs: u64,
+    /// 状态发布间隔（毫秒），只发布发生变化的状态
+    pub publish_interval_ms: u64,
+}
+
+impl Default for MqttConfig {
+    fn default() -> Self {
+        Self {
+            broker: "localhost".to_string(),
+            client_id: "dglab".to_string(),
+            topic_prefix: DEFAULT_TOPIC_PREFIX.to_string(),
+            username: None,
+            password: None,
+            keep_alive_secs: 30,
+            publish_interval_ms: 1000,
+        }
+    }
+}
+
+impl MqttConfig {
+    /// 使用指定 broker 创建配置
+    pub fn new(broker: impl Into<String>) -> Self {
+        Self {
+            broker: broker.into(),
+            ..Default::default()
+        }
+    }
+
+    /// 校验配置
+    pub fn validate(&self) -> Result<()> {
+        let _ = self.broker_address()?;
+
+        if self.client_id.is_empty() {
+            return Err(CoreError::ConfigError(
+                "MQTT client ID must not be empty".to_string(),
+            ));
+        }
+
+        let prefix = self.topic_prefix.trim_matches('/');
+        if prefix.is_empty() || prefix.contains(['+', '#']) {
+            return Err(CoreError::ConfigError(format!(
+                "Invalid MQTT topic prefix '{}'",
+                self.topic_prefix
+            )));
+        }
+
+        if self.keep_alive_secs < 5 || self.publish_interval_ms < 100 {
+            return Err(CoreError::ConfigError(format!(
+                "MQTT keep alive must be at least 5s and publish interval at least 100ms (got {}s, {}ms)",
+                self.keep_alive_secs, self.publish_interval_ms
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// 主题前缀（去掉首尾的 `/`）
+    pub fn prefix(&self) -> &str {
+        self.topic_prefix.trim_matches('/')
+    }
+
+    /// 解析 broker 地址为 (主机, 端口)
+    ///
+    /// 支持省略协议和端口；`mqtts://` 等加密连接暂不支持。
+    pub fn broker_address(&self) -> Result<(String, u16)> {
+        let broker = self.broker.trim();
+        let rest = match broker.split_once("://") {
+            Some(("mqtt" | "tcp", rest)) => rest,
+            Some((scheme, _)) => {
+                return Err(CoreError::ConfigError(format!(
+                    "Unsupported MQTT scheme '{}', expected mqtt:// or tcp://",
+                    scheme
+                )))
+            }
+            None => broker,
+        };
+        let rest = rest.trim_end_matches('/');
+
+        let (host, port) = match rest.rsplit_once(':') {
+            Some((host, port)) => {
+                let port = port.parse().map_err(|_| {
+                    CoreError::ConfigError(format!("Invalid MQTT broker port in '{}'", broker))
+                })?;
+                (host, port)
+            }
+            None => (rest, DEFAULT_MQTT_PORT),
+        };
+
+        if host.is_empty() || host.contains('/') {
+            return Err(CoreError::ConfigError(format!(
+                "Invalid MQTT broker '{}'",
+                broker
+            )));
+        }
+
+        Ok((host.to_string(), port))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_broker_address() {
+        let parse = |broker: &str| MqttConfig::new(broker).broker_address();
+
+        assert_eq!(parse("localhost").unwrap(), ("localhost".to_string(), 1883));
+        assert_eq!(
+            parse("mqtt://10.0.0.2:1884").unwrap(),
+            ("10.0.0.2".to_string(), 1884)
+        );
+        assert_eq!(
+            parse("tcp://broker.lan/").unwrap(),
+            ("broker.lan".to_string(), 1883)
+        );
+        assert!(parse("mqtts://broker.lan").is_err());
+        assert!(parse("broker.lan:port").is_err());
+        assert!(parse("").is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(MqttConfig::default().validate().is_ok());
+
+        let config = MqttConfig {
+            topic_prefix: "home/#".to_string(),
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+
+        let config = MqttConfig {
+            publish_interval_ms: 10,
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+
+        let config = MqttConfig {
+            topic_prefix: "/home/dglab/".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(config.prefix(), "home/dglab");
+    }
+}
@@ -0,0 +1,14 @@
+//! MQTT 集成
+//!
+//! 订阅指令主题控制设备（强度、波形、紧急停止），并发布设备状态、电量和强度，
+//! 供 Home Assistant 等家庭自动化系统使用。连接 broker 需要启用 `mqtt` 特性。
+
+#[cfg(feature = "mqtt")]
+pub mod client;
+pub mod config;
+pub mod topics;
+
+#[cfg(feature = "mqtt")]
+pub use client::MqttBridge;
+pub use config::{MqttConfig, DEFAULT_MQTT_PORT, DEFAULT_TOPIC_PREFIX};
+pub use topics::{device_key, parse_command, MqttCommand};
@@ -0,0 +1,295 @@
+//! MQTT 主题与指令
+//!
+//! 主题均以配置的前缀开头，`{device}` 为 [`device_key`] 转换后的设备 ID：
+//!
+//! | 主题 | 方向 | 内容 |
+//! |------|------|------|
+//! | `{prefix}/{device}/power/a`、`.../power/b` | 订阅 | 通道强度（0~200） |
+//! | `{prefix}/{device}/waveform` | 订阅 | 波形名称（两个通道） |
+//! | `{prefix}/{device}/waveform/a`、`.../waveform/b` | 订阅 | 波形名称（单个通道） |
+//! | `{prefix}/{device}/estop` | 订阅 | 紧急停止（内容任意），`{device}` 为 `all` 时停止所有设备 |
+//! | `{prefix}/{device}/state` | 发布 | 连接状态（connected/running/disconnected/…） |
+//! | `{prefix}/{device}/battery` | 发布 | 电量（0~100） |
+//! | `{prefix}/{device}/strength` | 发布 | `{"a":20,"b":0}` |
+//! | `{prefix}/status` | 发布 | 桥接在线状态（online/offline） |
+//!
+//! 启用访问控制时，指令内容须为 `{"token":"…","value":…}`，按发送方令牌在 `[access]`
+//! 中的角色执行；未启用时也可直接发送值。
+
+use serde_json::{json, Value};
+
+use crate::config::Permission;
+use crate::device::traits::DeviceInfo;
+use crate::device::DeviceState;
+use crate::error::{CoreError, Result};
+
+/// 表示所有设备的设备段
+pub const ALL_DEVICES: &str = "all";
+
+/// MQTT 指令
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MqttCommand {
+    /// 设置通道强度
+    Power {
+        /// 通道 (0=A, 1=B)
+        channel: u8,
+        /// 强度
+        power: u8,
+    },
+    /// 设置波形
+    Waveform {
+        /// 通道，`None` 表示两个通道
+        channel: Option<u8>,
+        /// 波形名称
+        name: String,
+    },
+    /// 紧急停止
+    EmergencyStop,
+}
+
+impl MqttCommand {
+    /// 执行指令需要的权限
+    pub fn permission(&self) -> Permission {
+        match self {
+            Self::Power { .. } | Self::Waveform { .. } | Self::EmergencyStop => Permission::Control,
+        }
+    }
+}
+
+/// 设备 ID 转换为主题中的设备段（`/`、`+`、`#` 替换为 `_`）
+pub fn device_key(device_id: &str) -> String {
+    device_id
+        .chars()
+        .map(|c| if matches!(c, '/' | '+' | '#') { '_' } else { c })
+        .collect()
+}
+
+/// 需要订阅的主题
+pub fn subscriptions(prefix: &str) -> Vec<String> {
+    ["power/+", "waveform", "waveform/+", "estop"]
+        .iter()
+        .map(|suffix| format!("{}/+/{}", prefix, suffix))
+        .collect()
+}
+
+/// 桥接在线状态主题
+pub fn status_topic(prefix: &str) -> String {
+    format!("{}/status", prefix)
+}
+
+/// 解析通道段
+fn parse_channel(segment: &str) -> Result<u8> {
+    match segment.to_ascii_lowercase().as_str() {
+        "a" => Ok(0),
+        "b" => Ok(1),
+        _ => Err(CoreError::InvalidParameter(format!(
+            "Invalid channel '{}', expected a or b",
+            segment
+        ))),
+    }
+}
+
+/// 拆出指令内容中的令牌，返回 (令牌, 值)
+///
+/// 内容为带 `token` 字段的 JSON 对象时取 `value` 字段作为值（缺省为空），否则整体作为值。
+pub fn split_token(payload: &str) -> (Option<String>, String) {
+    let Ok(Value::Object(mut object)) = serde_json::from_str::<Value>(payload.trim()) else {
+        return (None, payload.to_string());
+    };
+    let Some(Value::String(token)) = object.remove("token") else {
+        return (None, payload.to_string());
+    };
+    let value = match object.remove("value") {
+        Some(Value::String(value)) => value,
+        Some(Value::Null) | None => String::new(),
+        Some(value) => value.to_string(),
+    };
+    (Some(token), value)
+}
+
+/// 解析收到的消息，返回 (设备段, 指令)
+///
+/// 不属于本前缀或不是指令主题时返回 `Ok(None)`，内容无效时返回错误。
+pub fn parse_command(
+    prefix: &str,
+    topic: &str,
+    payload: &str,
+) -> Result<Option<(String, MqttCommand)>> {
+    let Some(rest) = topic
+        .strip_prefix(prefix)
+        .and_then(|rest| rest.strip_prefix('/'))
+    else {
+        return Ok(None);
+    };
+    let segments: Vec<&str> = rest.split('/').collect();
+    let payload = payload.trim();
+
+    let command = match segments[1..] {
+        ["power", channel] => {
+            let power = payload.parse().map_err(|_| {
+                CoreError::InvalidParameter(format!("Invalid power '{}' on {}", payload, topic))
+            })?;
+            MqttCommand::Power {
+                channel: parse_channel(channel)?,
+                power,
+            }
+        }
+        ["waveform"] | ["waveform", _] => {
+            if payload.is_empty() {
+                return Err(CoreError::InvalidParameter(format!(
+                    "Empty waveform name on {}",
+                    topic
+                )));
+            }
+            MqttCommand::Waveform {
+                channel: segments.get(2).map(|c| parse_channel(c)).transpose()?,
+                name: payload.to_string(),
+            }
+        }
+        ["estop"] => MqttCommand::EmergencyStop,
+        _ => return Ok(None),
+    };
+
+    Ok(Some((segments[0].to_string(), command)))
+}
+
+/// 设备状态对应的发布消息 (主题, 内容)
+pub fn state_messages(
+    prefix: &str,
+    info: &DeviceInfo,
+    state: DeviceState,
+) -> Vec<(String, String)> {
+    let base = format!("{}/{}", prefix, device_key(&info.id));
+    vec![
+        (format!("{}/state", base), state_name(state).to_string()),
+        (format!("{}/battery", base), info.battery_level.to_string()),
+        (
+            format!("{}/strength", base),
+            json!({ "a": info.power_a, "b": info.power_b }).to_string(),
+        ),
+    ]
+}
+
+/// 设备状态名称
+pub fn state_name(state: DeviceState) -> &'static str {
+    match state {
+        DeviceState::Disconnected => "disconnected",
+        DeviceState::Connecting => "connecting",
+        DeviceState::Connected => "connected",
+        DeviceState::Running => "running",
+        DeviceState::Error => "error",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_device_key() {
+        assert_eq!(device_key("hci0/dev_AA_BB"), "hci0_dev_AA_BB");
+        assert_eq!(device_key("47L121000"), "47L121000");
+    }
+
+    #[test]
+    fn test_subscriptions() {
+        let topics = subscriptions("dglab");
+        assert!(topics.contains(&"dglab/+/power/+".to_string()));
+        assert!(topics.contains(&"dglab/+/estop".to_string()));
+        assert_eq!(status_topic("home/dglab"), "home/dglab/status");
+    }
+
+    #[test]
+    fn test_parse_command() {
+        let parse = |topic: &str, payload: &str| parse_command("dglab", topic, payload);
+
+        assert_eq!(
+            parse("dglab/dev1/power/a", " 20 ").unwrap(),
+            Some((
+                "dev1".to_string(),
+                MqttCommand::Power {
+                    channel: 0,
+                    power: 20
+                }
+            ))
+        );
+        assert_eq!(
+            parse("dglab/dev1/waveform", "Breathing").unwrap(),
+            Some((
+                "dev1".to_string(),
+                MqttCommand::Waveform {
+                    channel: None,
+                    name: "Breathing".to_string()
+                }
+            ))
+        );
+        assert_eq!(
+            parse("dglab/dev1/waveform/B", "Pulse").unwrap().unwrap().1,
+            MqttCommand::Waveform {
+                channel: Some(1),
+                name: "Pulse".to_string()
+            }
+        );
+        assert_eq!(
+            parse("dglab/all/estop", "").unwrap(),
+            Some(("all".to_string(), MqttCommand::EmergencyStop))
+        );
+
+        assert!(parse("dglab/dev1/power/c", "10").is_err());
+        assert!(parse("dglab/dev1/power/a", "high").is_err());
+        assert!(parse("dglab/dev1/waveform", "").is_err());
+        assert_eq!(parse("dglab/dev1/state", "running").unwrap(), None);
+        assert_eq!(parse("other/dev1/estop", "").unwrap(), None);
+        assert_eq!(parse("dglabx/dev1/estop", "").unwrap(), None);
+    }
+
+    #[test]
+    fn test_split_token() {
+        assert_eq!(
+            split_token(r#"{"token":"ctrl","value":20}"#),
+            (Some("ctrl".to_string()), "20".to_string())
+        );
+        assert_eq!(
+            split_token(r#"{"token":"ctrl","value":"Breathing"}"#),
+            (Some("ctrl".to_string()), "Breathing".to_string())
+        );
+        assert_eq!(
+            split_token(r#"{"token":"ctrl"}"#),
+            (Some("ctrl".to_string()), String::new())
+        );
+        assert_eq!(split_token("20"), (None, "20".to_string()));
+        assert_eq!(split_token(r#"{"a":1}"#), (None, r#"{"a":1}"#.to_string()));
+    }
+
+    #[test]
+    fn test_state_messages() {
+        let info = DeviceInfo {
+            id: "hci0/dev".to_string(),
+            name: "Coyote".to_string(),
+            device_type: "Coyote V3".to_string(),
+            firmware_version: String::new(),
+            hardware_version: String::new(),
+            battery_level: 80,
+            battery_remaining_secs: None,
+            power_a: 20,
+            power_b: 0,
+            max_power_a: 200,
+            max_power_b: 200,
+            enabled_a: true,
+            enabled_b: true,
+        };
+
+        let messages = state_messages("dglab", &info, DeviceState::Running);
+        assert_eq!(
+            messages,
+            vec![
+                ("dglab/hci0_dev/state".to_string(), "running".to_string()),
+                ("dglab/hci0_dev/battery".to_string(), "80".to_string()),
+                (
+                    "dglab/hci0_dev/strength".to_string(),
+                    r#"{"a":20,"b":0}"#.to_string()
+                ),
+            ]
+        );
+    }
+}
@@ -1,5 +1,12 @@
 //! 预设管理模块
 
+pub mod schedule;
+pub mod share;
 pub mod storage;
 
-pub use storage::{Preset, PresetChannelConfig, PresetManager};
+pub use schedule::{PresetScheduler, ScheduleEntry, ScheduleEvent, ScheduleTrigger};
+pub use share::{SharedPattern, SHARE_CODE_PREFIX};
+pub use storage::{
+    Preset, PresetChannelConfig, PresetIssue, PresetManager, PresetQuery, PresetSafety,
+    INHERITABLE_FIELDS,
+};
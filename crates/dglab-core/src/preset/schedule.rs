@@ -0,0 +1,560 @@
+//! 预设定时
+//!
+//! 按配置文件中的定时条目在每天的固定时间或每隔一段时间自动应用预设：
+//!
+//! ```toml
+//! [[schedules]]
+//! name = "evening"
+//! preset = "Relax"
+//! at = "21:30"
+//!
+//! [[schedules]]
+//! name = "tease"
+//! preset = "Tease"
+//! every = "20m"
+//! device = "47L121000"
+//! enabled = false
+//! ```
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use chrono::{DateTime, Local, NaiveTime};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{broadcast, watch, RwLock};
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use super::{Preset, PresetManager};
+use crate::config::AppConfig;
+use crate::error::{CoreError, Result};
+use crate::session::{parse_duration, SessionManager};
+
+/// 定时触发方式
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduleTrigger {
+    /// 每天的本地时间 `HH:MM`
+    At(String),
+    /// 固定间隔（如 `90s`、`20m`、`1h30m`），从调度开始或上次触发起算；
+    /// 错过的触发（如系统休眠）不补发
+    Every(String),
+}
+
+impl ScheduleTrigger {
+    /// 校验触发时间
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            Self::At(time) => parse_time_of_day(time).map(|_| ()),
+            Self::Every(interval) => parse_interval(interval).map(|_| ()),
+        }
+    }
+
+    /// 计算 `after` 之后的下一次触发时间（`after` 为上次触发或调度开始的时间）
+    pub fn next_after(&self, after: DateTime<Local>) -> Result<DateTime<Local>> {
+        match self {
+            Self::At(time) => {
+                let time = parse_time_of_day(time)?;
+                let mut date = after.date_naive();
+                // 向后找到第一个存在的本地时间（跳过夏令时空缺）
+                for _ in 0..3 {
+                    if let Some(next) = date.and_time(time).and_local_timezone(Local).earliest() {
+                        if next > after {
+                            return Ok(next);
+                        }
+                    }
+                    date = date.succ_opt().unwrap_or(date);
+                }
+                Err(CoreError::InvalidParameter(format!(
+                    "No upcoming local time for {}",
+                    time
+                )))
+            }
+            Self::Every(interval) => {
+                let interval = chrono::Duration::from_std(parse_interval(interval)?)
+                    .map_err(|e| CoreError::InvalidParameter(format!("Invalid interval: {}", e)))?;
+                Ok(after + interval)
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for ScheduleTrigger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::At(time) => write!(f, "daily at {}", time),
+            Self::Every(interval) => write!(f, "every {}", interval),
+        }
+    }
+}
+
+/// 定时条目
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScheduleEntry {
+    /// 条目名称（唯一）
+    pub name: String,
+    /// 要应用的预设名称
+    pub preset: String,
+    /// 目标设备 ID（不设置表示会话中的所有设备）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device: Option<String>,
+    /// 触发方式
+    #[serde(flatten)]
+    pub trigger: ScheduleTrigger,
+    /// 是否启用
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl ScheduleEntry {
+    /// 创建启用的定时条目（应用到所有设备）
+    pub fn new(name: String, preset: String, trigger: ScheduleTrigger) -> Self {
+        Self {
+            name,
+            preset,
+            device: None,
+            trigger,
+            enabled: true,
+        }
+    }
+
+    /// 校验条目
+    pub fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            return Err(CoreError::ConfigError("Schedule name is empty".to_string()));
+        }
+        if self.preset.trim().is_empty() {
+            return Err(CoreError::ConfigError(format!(
+                "Schedule '{}' has no preset",
+                self.name
+            )));
+        }
+        self.trigger
+            .validate()
+            .map_err(|e| CoreError::ConfigError(format!("Schedule '{}': {}", self.name, e)))
+    }
+}
+
+/// 解析每天的本地时间 `HH:MM`
+fn parse_time_of_day(s: &str) -> Result<NaiveTime> {
+    NaiveTime::parse_from_str(s.trim(), "%H:%M")
+        .map_err(|_| CoreError::InvalidParameter(format!("Invalid time of day: {}", s)))
+}
+
+/// 解析触发间隔（不能为零）
+fn parse_interval(s: &str) -> Result<Duration> {
+    let interval = parse_duration(s)?;
+    if interval.is_zero() {
+        return Err(CoreError::InvalidParameter(format!(
+            "Interval must be positive: {}",
+            s
+        )));
+    }
+    Ok(interval)
+}
+
+/// 定时事件
+#[derive(Debug, Clone)]
+pub enum ScheduleEvent {
+    /// 定时已触发并应用预设
+    Fired {
+        /// 条目名称
+        schedule: String,
+        /// 预设名称
+        preset: String,
+        /// 已应用预设的设备
+        devices: Vec<String>,
+    },
+    /// 定时已触发但应用预设失败
+    Failed {
+        /// 条目名称
+        schedule: String,
+        /// 预设名称
+        preset: String,
+        /// 错误信息
+        error: String,
+    },
+}
+
+/// 预设调度器
+///
+/// 跟随配置变化重新计算各条目的下一次触发时间，到时后把预设应用到设备，
+/// 并通过 [`subscribe`](Self::subscribe) 发送 [`ScheduleEvent`]。
+pub struct PresetScheduler {
+    /// 事件发送器
+    event_tx: broadcast::Sender<ScheduleEvent>,
+    /// 调度任务
+    task: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl PresetScheduler {
+    /// 创建调度器（需调用 [`start`](Self::start) 开始调度）
+    pub fn new() -> Self {
+        let (event_tx, _) = broadcast::channel(16);
+
+        Self {
+            event_tx,
+            task: Mutex::new(None),
+        }
+    }
+
+    /// 开始调度，替换已有的调度任务
+    pub fn start(
+        &self,
+        config: watch::Receiver<AppConfig>,
+        session: Arc<RwLock<SessionManager>>,
+        presets: Arc<RwLock<PresetManager>>,
+    ) {
+        info!("Preset scheduler started");
+        let task = tokio::spawn(run_shared(config, session, presets, self.event_tx.clone()));
+        if let Some(previous) = self.task().replace(task) {
+            previous.abort();
+        }
+    }
+
+    /// 停止调度，返回调度是否在运行
+    pub fn stop(&self) -> bool {
+        match self.task().take() {
+            Some(task) if !task.is_finished() => {
+                info!("Preset scheduler stopped");
+                task.abort();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// 在当前任务中运行调度，调用方通过取消 future 停止
+    ///
+    /// 与 [`start`](Self::start) 相同，但直接使用会话和预设管理器，供命令行使用。
+    pub async fn run(
+        &self,
+        config: watch::Receiver<AppConfig>,
+        session: &SessionManager,
+        presets: &PresetManager,
+    ) {
+        info!("Preset scheduler running");
+        schedule_loop(config, &self.event_tx, move |entry| async move {
+            let preset = resolve_preset(&entry, presets)?;
+            fire(&entry, &preset, session).await
+        })
+        .await;
+    }
+
+    /// 调度是否在运行
+    pub fn is_running(&self) -> bool {
+        self.task().as_ref().is_some_and(|task| !task.is_finished())
+    }
+
+    /// 订阅定时事件
+    pub fn subscribe(&self) -> broadcast::Receiver<ScheduleEvent> {
+        self.event_tx.subscribe()
+    }
+
+    /// 获取调度任务（锁中毒时继续使用内部数据）
+    fn task(&self) -> MutexGuard<'_, Option<JoinHandle<()>>> {
+        self.task
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Default for PresetScheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for PresetScheduler {
+    fn drop(&mut self) {
+        if let Some(task) = self.task().take() {
+            task.abort();
+        }
+    }
+}
+
+/// 计算所有启用条目中最早的下一次触发
+///
+/// `last` 记录各条目上次触发（或首次出现）的时间，移除已删除或禁用的条目。
+fn next_due(
+    entries: &[ScheduleEntry],
+    last: &mut HashMap<String, DateTime<Local>>,
+    now: DateTime<Local>,
+) -> Option<(DateTime<Local>, ScheduleEntry)> {
+    last.retain(|name, _| entries.iter().any(|e| e.enabled && e.name == *name));
+
+    entries
+        .iter()
+        .filter(|entry| entry.enabled)
+        .filter_map(|entry| {
+            let after = *last.entry(entry.name.clone()).or_insert(now);
+            match entry.trigger.next_after(after) {
+                Ok(at) => Some((at, entry.clone())),
+                Err(e) => {
+                    warn!("Skipping schedule '{}': {}", entry.name, e);
+                    None
+                }
+            }
+        })
+        .min_by_key(|(at, _)| *at)
+}
+
+/// 本次触发后记录的时间
+///
+/// 下一次触发已经过去（如系统休眠后醒来）时从现在起算，跳过错过的触发，避免连续补发。
+fn skip_missed(
+    trigger: &ScheduleTrigger,
+    at: DateTime<Local>,
+    now: DateTime<Local>,
+) -> DateTime<Local> {
+    match trigger.next_after(at) {
+        Ok(next) if next > now => at,
+        _ => now,
+    }
+}
+
+/// 使用共享的会话和预设管理器运行调度（每次触发时加锁）
+async fn run_shared(
+    config: watch::Receiver<AppConfig>,
+    session: Arc<RwLock<SessionManager>>,
+    presets: Arc<RwLock<PresetManager>>,
+    event_tx: broadcast::Sender<ScheduleEvent>,
+) {
+    schedule_loop(config, &event_tx, |entry| {
+        let session = session.clone();
+        let presets = presets.clone();
+        async move {
+            let preset = resolve_preset(&entry, &presets.read().await)?;
+            fire(&entry, &preset, &session.read().await).await
+        }
+    })
+    .await;
+}
+
+/// 调度循环：等待最早的条目到时后调用 `fire`，配置变化时重新计算
+async fn schedule_loop<F, Fut>(
+    mut config: watch::Receiver<AppConfig>,
+    event_tx: &broadcast::Sender<ScheduleEvent>,
+    mut fire: F,
+) where
+    F: FnMut(ScheduleEntry) -> Fut,
+    Fut: Future<Output = Result<Vec<String>>>,
+{
+    let mut last = HashMap::new();
+
+    loop {
+        let entries = config.borrow_and_update().schedules.clone();
+        let Some((at, entry)) = next_due(&entries, &mut last, Local::now()) else {
+            if config.changed().await.is_err() {
+                break;
+            }
+            continue;
+        };
+
+        let wait = (at - Local::now()).to_std().unwrap_or_default();
+        tokio::select! {
+            changed = config.changed() => {
+                if changed.is_err() {
+                    break;
+                }
+                continue;
+            }
+            _ = tokio::time::sleep(wait) => {}
+        }
+
+        let _ = last.insert(
+            entry.name.clone(),
+            skip_missed(&entry.trigger, at, Local::now()),
+        );
+        let event = match fire(entry.clone()).await {
+            Ok(devices) => {
+                info!(
+                    "Schedule '{}' applied preset '{}' to {:?}",
+                    entry.name, entry.preset, devices
+                );
+                ScheduleEvent::Fired {
+                    schedule: entry.name,
+                    preset: entry.preset,
+                    devices,
+                }
+            }
+            Err(e) => {
+                warn!("Schedule '{}' failed: {}", entry.name, e);
+                ScheduleEvent::Failed {
+                    schedule: entry.name,
+                    preset: entry.preset,
+                    error: e.to_string(),
+                }
+            }
+        };
+        let _ = event_tx.send(event);
+    }
+}
+
+/// 查找条目中的预设（合并继承后的有效配置）
+fn resolve_preset(entry: &ScheduleEntry, presets: &PresetManager) -> Result<Preset> {
+    let preset = presets
+        .find_preset_by_name(&entry.preset)
+        .ok_or_else(|| CoreError::Other(format!("Preset not found: {}", entry.preset)))?;
+    presets.resolve(&preset.id)
+}
+
+/// 应用预设，返回已应用的设备
+///
+/// 未指定设备时应用到会话中的所有设备，任一设备失败即返回错误。
+async fn fire(
+    entry: &ScheduleEntry,
+    preset: &Preset,
+    session: &SessionManager,
+) -> Result<Vec<String>> {
+    let devices = match &entry.device {
+        Some(device) => vec![device.clone()],
+        None => session.list_devices().await,
+    };
+    if devices.is_empty() {
+        return Err(CoreError::Other("No connected devices".to_string()));
+    }
+
+    for device in &devices {
+        session.apply_preset(device, preset).await?;
+    }
+    Ok(devices)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn local(h: u32, m: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2030, 1, 15, h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn test_trigger_next_after() {
+        let at = ScheduleTrigger::At("21:30".to_string());
+        assert_eq!(at.next_after(local(20, 0)).unwrap(), local(21, 30));
+        // 已过当天时间则取次日
+        let next = at.next_after(local(21, 30)).unwrap();
+        assert_eq!(next - local(21, 30), chrono::Duration::days(1));
+
+        let every = ScheduleTrigger::Every("1h30m".to_string());
+        assert_eq!(every.next_after(local(8, 0)).unwrap(), local(9, 30));
+
+        for trigger in [
+            ScheduleTrigger::At("25:00".to_string()),
+            ScheduleTrigger::Every("0".to_string()),
+            ScheduleTrigger::Every("soon".to_string()),
+        ] {
+            assert!(trigger.validate().is_err(), "{}", trigger);
+        }
+    }
+
+    #[test]
+    fn test_entry_toml() {
+        let config = AppConfig::from_toml_str(
+            r#"
+[[schedules]]
+name = "evening"
+preset = "Relax"
+at = "21:30"
+
+[[schedules]]
+name = "tease"
+preset = "Tease"
+every = "20m"
+device = "47L121000"
+enabled = false
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.schedules.len(), 2);
+        assert_eq!(
+            config.schedules[0].trigger,
+            ScheduleTrigger::At("21:30".to_string())
+        );
+        assert!(config.schedules[0].enabled);
+        assert_eq!(config.schedules[1].device.as_deref(), Some("47L121000"));
+        assert!(!config.schedules[1].enabled);
+
+        let toml = config.to_toml_string().unwrap();
+        assert_eq!(AppConfig::from_toml_str(&toml).unwrap(), config);
+
+        assert!(AppConfig::from_toml_str(
+            "[[schedules]]\nname = \"x\"\npreset = \"p\"\nat = \"7pm\"\n"
+        )
+        .is_err());
+
+        // 名称不能重复
+        let mut duplicate = config.clone();
+        duplicate.set_schedule(ScheduleEntry::new(
+            "other".to_string(),
+            "Relax".to_string(),
+            ScheduleTrigger::Every("1h".to_string()),
+        ));
+        duplicate.schedules[2].name = "evening".to_string();
+        assert!(duplicate.validate().is_err());
+
+        let mut config = config;
+        assert!(config.set_schedule_enabled("tease", true));
+        assert!(config.schedule("tease").unwrap().enabled);
+        assert!(config.remove_schedule("evening"));
+        assert!(!config.remove_schedule("evening"));
+        assert!(!config.set_schedule_enabled("evening", false));
+    }
+
+    #[test]
+    fn test_next_due() {
+        let mut entries = vec![
+            ScheduleEntry::new(
+                "daily".to_string(),
+                "A".to_string(),
+                ScheduleTrigger::At("09:00".to_string()),
+            ),
+            ScheduleEntry::new(
+                "often".to_string(),
+                "B".to_string(),
+                ScheduleTrigger::Every("10m".to_string()),
+            ),
+        ];
+        let mut last = HashMap::new();
+
+        let (at, entry) = next_due(&entries, &mut last, local(8, 0)).unwrap();
+        assert_eq!((at, entry.name.as_str()), (local(8, 10), "often"));
+
+        // 间隔从上次触发起算
+        let _ = last.insert("often".to_string(), local(8, 55));
+        let (at, entry) = next_due(&entries, &mut last, local(8, 56)).unwrap();
+        assert_eq!((at, entry.name.as_str()), (local(9, 0), "daily"));
+
+        // 禁用的条目不再触发，其记录被清除
+        entries[1].enabled = false;
+        let (_, entry) = next_due(&entries, &mut last, local(8, 56)).unwrap();
+        assert_eq!(entry.name, "daily");
+        assert!(!last.contains_key("often"));
+
+        entries[0].enabled = false;
+        assert!(next_due(&entries, &mut last, local(8, 56)).is_none());
+    }
+
+    #[test]
+    fn test_skip_missed() {
+        let every = ScheduleTrigger::Every("10m".to_string());
+        // 按时触发时从计划时间起算，不累积延迟
+        assert_eq!(skip_missed(&every, local(8, 0), local(8, 1)), local(8, 0));
+        // 休眠错过了后续触发时从现在起算
+        assert_eq!(skip_missed(&every, local(8, 0), local(8, 35)), local(8, 35));
+
+        let daily = ScheduleTrigger::At("09:00".to_string());
+        assert_eq!(skip_missed(&daily, local(9, 0), local(9, 5)), local(9, 0));
+        let now = local(9, 0) + chrono::Duration::days(3);
+        assert_eq!(skip_missed(&daily, local(9, 0), now), now);
+    }
+}
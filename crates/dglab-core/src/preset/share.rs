@@ -0,0 +1,224 @@
+//! 预设与波形分享码
+//!
+//! 分享码为 `dglab1:` 前缀加 deflate 压缩后的 JSON，按 URL 安全字符集 base64 编码（无填充），
+//! 可直接在聊天中发送，较短的分享码也可以生成二维码。
+//!
+//! 分享预设时，按名称引用的波形库波形会内嵌到预设中，接收方不需要拥有同名波形。
+
+use std::io::{Read, Write};
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+
+use super::storage::Preset;
+use crate::error::{CoreError, Result};
+use crate::waveform::{WaveFile, Waveform, WaveformLibrary};
+
+/// 分享码前缀（包含格式版本）
+pub const SHARE_CODE_PREFIX: &str = "dglab1:";
+
+/// 解压后 JSON 的最大长度，防止恶意分享码占用过多内存
+const MAX_DECODED_SIZE: u64 = 256 * 1024;
+
+/// 可分享的内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "data", rename_all = "snake_case")]
+pub enum SharedPattern {
+    /// 预设（波形已内嵌）
+    Preset(Preset),
+    /// 波形（`.dgwave` 文件内容）
+    Waveform(WaveFile),
+}
+
+impl SharedPattern {
+    /// 分享预设，按名称引用的波形从波形库解析后内嵌
+    ///
+    /// 继承其他预设的预设应传入有效配置（见 [`PresetManager::resolve`]），分享码中不保留父预设。
+    ///
+    /// [`PresetManager::resolve`]: super::PresetManager::resolve
+    pub fn preset(preset: &Preset, library: &WaveformLibrary) -> Result<Self> {
+        let mut preset = preset.clone();
+        preset.parent = None;
+        preset.overrides.clear();
+        for config in [&mut preset.channel_a, &mut preset.channel_b] {
+            if config.waveform.is_none() {
+                if let Some(name) = config.waveform_name.take() {
+                    config.waveform = Some(library.resolve(&name)?);
+                }
+            }
+        }
+        Ok(Self::Preset(preset))
+    }
+
+    /// 分享波形（调制层不保存）
+    pub fn waveform(waveform: &Waveform) -> Self {
+        Self::Waveform(WaveFile::from_waveform(waveform))
+    }
+
+    /// 预设或波形名称
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Preset(preset) => &preset.name,
+            Self::Waveform(file) => &file.name,
+        }
+    }
+
+    /// 编码为分享码
+    pub fn encode(&self) -> Result<String> {
+        let json = serde_json::to_vec(self)?;
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::best());
+        encoder.write_all(&json)?;
+        let compressed = encoder.finish()?;
+
+        Ok(format!(
+            "{}{}",
+            SHARE_CODE_PREFIX,
+            URL_SAFE_NO_PAD.encode(compressed)
+        ))
+    }
+
+    /// 解析分享码
+    ///
+    /// 忽略分享码中的空白（聊天软件可能自动换行）。导入的预设使用新的 ID 和时间，
+    /// 预设和波形都会经过校验。
+    pub fn decode(code: &str) -> Result<Self> {
+        let code: String = code.split_whitespace().collect();
+        let payload = code.strip_prefix(SHARE_CODE_PREFIX).ok_or_else(|| {
+            CoreError::InvalidParameter(format!(
+                "Share code must start with '{}'",
+                SHARE_CODE_PREFIX
+            ))
+        })?;
+        let compressed = URL_SAFE_NO_PAD
+            .decode(payload)
+            .map_err(|e| CoreError::InvalidParameter(format!("Invalid share code: {}", e)))?;
+
+        let mut json = Vec::new();
+        let _ = DeflateDecoder::new(compressed.as_slice())
+            .take(MAX_DECODED_SIZE + 1)
+            .read_to_end(&mut json)
+            .map_err(|e| CoreError::InvalidParameter(format!("Invalid share code: {}", e)))?;
+        if json.len() as u64 > MAX_DECODED_SIZE {
+            return Err(CoreError::InvalidParameter(format!(
+                "Share code content exceeds {} bytes",
+                MAX_DECODED_SIZE
+            )));
+        }
+
+        let mut pattern: Self = serde_json::from_slice(&json)?;
+        match &mut pattern {
+            Self::Preset(preset) => {
+                preset.validate()?;
+                // 接收方没有对应的父预设
+                preset.parent = None;
+                preset.overrides.clear();
+                let imported = Preset::new(preset.name.clone(), String::new());
+                preset.id = imported.id;
+                preset.created_at = imported.created_at;
+                preset.updated_at = imported.updated_at;
+            }
+            Self::Waveform(file) => {
+                let _ = file.to_waveform()?;
+            }
+        }
+        Ok(pattern)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dglab_protocol::v3::WaveformData;
+
+    #[test]
+    fn test_preset_roundtrip() {
+        let mut preset = Preset::new("Shared".to_string(), "From a friend".to_string());
+        preset.channel_a.max_power = 40;
+        preset.channel_a.waveform_name = Some("breathing".to_string());
+        preset.channel_b.enabled = false;
+
+        let pattern = SharedPattern::preset(&preset, &WaveformLibrary::builtin()).unwrap();
+        let code = pattern.encode().unwrap();
+        assert!(code.starts_with(SHARE_CODE_PREFIX));
+
+        let SharedPattern::Preset(imported) = SharedPattern::decode(&code).unwrap() else {
+            panic!("expected preset");
+        };
+        assert_eq!(imported.name, "Shared");
+        assert_ne!(imported.id, preset.id);
+        assert_eq!(imported.channel_a.max_power, 40);
+        assert!(!imported.channel_b.enabled);
+        // 波形库波形已内嵌
+        assert_eq!(imported.channel_a.waveform_name, None);
+        assert!(imported
+            .channel_a
+            .waveform
+            .unwrap()
+            .name
+            .eq_ignore_ascii_case("breathing"));
+
+        preset.channel_a.waveform_name = Some("missing".to_string());
+        assert!(SharedPattern::preset(&preset, &WaveformLibrary::builtin()).is_err());
+    }
+
+    #[test]
+    fn test_waveform_roundtrip() {
+        let waveform = Waveform {
+            name: "Ramp".to_string(),
+            frames: Some(vec![
+                WaveformData::uniform(10, 0),
+                WaveformData::uniform(10, 50),
+            ]),
+            ..Default::default()
+        };
+        let code = SharedPattern::waveform(&waveform).encode().unwrap();
+
+        // 聊天软件插入的换行和空格被忽略
+        let wrapped = format!("  {}\n{}  ", &code[..20], &code[20..]);
+        let pattern = SharedPattern::decode(&wrapped).unwrap();
+        assert_eq!(pattern.name(), "Ramp");
+        let SharedPattern::Waveform(file) = pattern else {
+            panic!("expected waveform");
+        };
+        assert_eq!(file.to_waveform().unwrap().frames, waveform.frames);
+    }
+
+    #[test]
+    fn test_reject_invalid_codes() {
+        assert!(SharedPattern::decode("").is_err());
+        assert!(SharedPattern::decode("hello").is_err());
+        assert!(SharedPattern::decode("dglab1:!!!").is_err());
+        // base64 有效但不是压缩数据
+        assert!(
+            SharedPattern::decode(&format!("dglab1:{}", URL_SAFE_NO_PAD.encode("{}"))).is_err()
+        );
+
+        // 预设校验失败
+        let mut preset = Preset::new("Bad".to_string(), String::new());
+        preset.channel_a.min_power = 80;
+        preset.channel_a.max_power = 20;
+        let code = SharedPattern::Preset(preset).encode().unwrap();
+        assert!(SharedPattern::decode(&code).is_err());
+    }
+
+    #[test]
+    fn test_reject_oversized() {
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::best());
+        encoder
+            .write_all(&vec![b' '; MAX_DECODED_SIZE as usize + 1])
+            .unwrap();
+        let code = format!(
+            "{}{}",
+            SHARE_CODE_PREFIX,
+            URL_SAFE_NO_PAD.encode(encoder.finish().unwrap())
+        );
+        assert!(matches!(
+            SharedPattern::decode(&code),
+            Err(CoreError::InvalidParameter(_))
+        ));
+    }
+}
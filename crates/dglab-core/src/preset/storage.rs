@@ -2,12 +2,15 @@
 
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 use tracing::{debug, info};
 
+use dglab_protocol::v3::MAX_STRENGTH;
+
 use crate::error::{CoreError, Result};
-use crate::waveform::Waveform;
+use crate::waveform::{Modulation, Waveform};
 
 /// 通道配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,6 +23,12 @@ pub struct PresetChannelConfig {
     pub max_power: u8,
     /// 波形
     pub waveform: Option<Waveform>,
+    /// 波形库中的波形名称（`waveform` 为空时使用）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub waveform_name: Option<String>,
+    /// 调制层（叠加在波形自带的调制层之后）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub modulation: Vec<Modulation>,
 }
 
 impl Default for PresetChannelConfig {
@@ -29,7 +38,105 @@ impl Default for PresetChannelConfig {
             min_power: 0,
             max_power: 50,
             waveform: None,
+            waveform_name: None,
+            modulation: Vec::new(),
+        }
+    }
+}
+
+/// 预设安全设置
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PresetSafety {
+    /// 强度绝对上限，应用时两个通道的最大强度和初始强度都不超过该值
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_power: Option<u8>,
+    /// 强度最大上升速率（每秒），应用时初始强度从当前强度按此速率渐变上升
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_ramp_rate: Option<u8>,
+    /// 高强度预设，应用前需要用户确认
+    pub require_confirmation: bool,
+}
+
+impl PresetSafety {
+    /// 是否为默认设置（序列化时省略）
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// 校验设置
+    pub fn validate(&self) -> Result<()> {
+        if let Some(max_power) = self.max_power {
+            if max_power > MAX_STRENGTH {
+                return Err(CoreError::PowerOutOfRange(max_power, MAX_STRENGTH));
+            }
+        }
+        if self.max_ramp_rate == Some(0) {
+            return Err(CoreError::InvalidParameter(
+                "Preset max ramp rate must be positive".to_string(),
+            ));
         }
+        Ok(())
+    }
+
+    /// 按绝对上限截断强度
+    pub fn clamp(&self, power: u8) -> u8 {
+        self.max_power.map_or(power, |max| power.min(max))
+    }
+
+    /// 强度从 `from` 上升到 `to` 的最短时长
+    ///
+    /// 未限制速率或强度不上升时返回 `None`（可以直接设置）。
+    pub fn ramp_duration(&self, from: u8, to: u8) -> Option<Duration> {
+        let rate = self.max_ramp_rate.filter(|r| *r > 0)?;
+        (to > from).then(|| Duration::from_secs_f64(f64::from(to - from) / f64::from(rate)))
+    }
+}
+
+/// 可以从父预设继承的字段
+///
+/// `channel_x.waveform` 同时包括内嵌波形和波形库名称。`overrides` 中也可以写整个分组
+/// （如 `channel_a`、`safety`），表示覆盖该分组下的所有字段。
+pub const INHERITABLE_FIELDS: [&str; 13] = [
+    "channel_a.enabled",
+    "channel_a.min_power",
+    "channel_a.max_power",
+    "channel_a.waveform",
+    "channel_a.modulation",
+    "channel_b.enabled",
+    "channel_b.min_power",
+    "channel_b.max_power",
+    "channel_b.waveform",
+    "channel_b.modulation",
+    "safety.max_power",
+    "safety.max_ramp_rate",
+    "safety.require_confirmation",
+];
+
+/// 预设校验问题
+///
+/// `field` 为出错字段的路径（如 `channel_a.max_power`），便于界面定位到对应输入框。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct PresetIssue {
+    /// 字段路径
+    pub field: String,
+    /// 问题描述
+    pub message: String,
+}
+
+impl PresetIssue {
+    fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for PresetIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
     }
 }
 
@@ -52,6 +159,21 @@ pub struct Preset {
     pub channel_b: PresetChannelConfig,
     /// 全局设置
     pub settings: HashMap<String, String>,
+    /// 安全设置
+    #[serde(default, skip_serializing_if = "PresetSafety::is_default")]
+    pub safety: PresetSafety,
+    /// 标签（如 `relax`、`training`），用于筛选
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// 分类
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    /// 父预设 ID，未覆盖的字段从父预设继承（见 [`PresetManager::resolve`]）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    /// 覆盖父预设的字段路径（见 [`INHERITABLE_FIELDS`]），没有父预设时忽略
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub overrides: Vec<String>,
 }
 
 impl Preset {
@@ -67,6 +189,232 @@ impl Preset {
             channel_a: PresetChannelConfig::default(),
             channel_b: PresetChannelConfig::default(),
             settings: HashMap::new(),
+            safety: PresetSafety::default(),
+            tags: Vec::new(),
+            category: None,
+            parent: None,
+            overrides: Vec::new(),
+        }
+    }
+
+    /// 设置标签，去掉首尾空白和空标签，忽略大小写去重后排序
+    pub fn set_tags<I, S>(&mut self, tags: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut normalized: Vec<String> = Vec::new();
+        for tag in tags {
+            let tag = tag.as_ref().trim();
+            if !tag.is_empty() && !normalized.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                normalized.push(tag.to_string());
+            }
+        }
+        normalized.sort_by_key(|t| t.to_lowercase());
+        self.tags = normalized;
+        self.touch();
+    }
+
+    /// 是否带有指定标签（忽略大小写）
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag.trim()))
+    }
+
+    /// 预设的最高强度：启用通道的最大强度，按安全上限截断
+    pub fn peak_power(&self) -> u8 {
+        [&self.channel_a, &self.channel_b]
+            .into_iter()
+            .filter(|config| config.enabled)
+            .map(|config| self.safety.clamp(config.max_power))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// 字段是否使用自己的值（没有父预设时所有字段都使用自己的值）
+    pub fn overrides_field(&self, field: &str) -> bool {
+        self.parent.is_none()
+            || self.overrides.iter().any(|o| {
+                field == o
+                    || field
+                        .strip_prefix(o.as_str())
+                        .is_some_and(|rest| rest.starts_with('.'))
+            })
+    }
+
+    /// 标记字段覆盖父预设（没有父预设时不记录）
+    pub fn set_override(&mut self, field: &str) {
+        if !self.overrides_field(field) {
+            self.overrides.push(field.to_string());
+        }
+    }
+
+    /// 在父预设的有效配置上叠加自己覆盖的字段和设置
+    ///
+    /// `parent` 应当是已解析的父预设，返回的预设保留自己的 ID、名称、描述、标签和分类。
+    pub fn inherit(&self, parent: &Preset) -> Preset {
+        let mut effective = self.clone();
+        for field in INHERITABLE_FIELDS {
+            if !self.overrides_field(field) {
+                effective.copy_field(parent, field);
+            }
+        }
+        effective.settings = parent.settings.clone();
+        effective.settings.extend(self.settings.clone());
+        effective
+    }
+
+    /// 记录与父预设不同的字段为覆盖
+    ///
+    /// 界面编辑的是有效配置（见 [`PresetManager::resolve`]），保存前与父预设的有效配置比较，
+    /// 修改过的继承字段才不会在下次解析时被父预设的值替换。与父预设相同的设置项不再重复保存。
+    pub fn record_overrides(&mut self, parent: &Preset) {
+        if self.parent.is_none() {
+            return;
+        }
+        for field in INHERITABLE_FIELDS {
+            if !self.overrides_field(field) && self.field_value(field) != parent.field_value(field)
+            {
+                self.overrides.push(field.to_string());
+            }
+        }
+        self.settings
+            .retain(|key, value| parent.settings.get(key) != Some(value));
+    }
+
+    /// 字段的值（用于比较）
+    fn field_value(&self, field: &str) -> serde_json::Value {
+        let (section, name) = field.split_once('.').unwrap_or((field, ""));
+        let value = match (section, name) {
+            ("safety", "max_power") => serde_json::to_value(self.safety.max_power),
+            ("safety", "max_ramp_rate") => serde_json::to_value(self.safety.max_ramp_rate),
+            ("safety", "require_confirmation") => {
+                serde_json::to_value(self.safety.require_confirmation)
+            }
+            ("channel_a" | "channel_b", _) => {
+                let config = if section == "channel_a" {
+                    &self.channel_a
+                } else {
+                    &self.channel_b
+                };
+                match name {
+                    "enabled" => serde_json::to_value(config.enabled),
+                    "min_power" => serde_json::to_value(config.min_power),
+                    "max_power" => serde_json::to_value(config.max_power),
+                    "waveform" => serde_json::to_value((&config.waveform, &config.waveform_name)),
+                    "modulation" => serde_json::to_value(&config.modulation),
+                    _ => Ok(serde_json::Value::Null),
+                }
+            }
+            _ => Ok(serde_json::Value::Null),
+        };
+        value.unwrap_or_default()
+    }
+
+    /// 从另一个预设复制字段
+    fn copy_field(&mut self, source: &Preset, field: &str) {
+        let (section, name) = field.split_once('.').unwrap_or((field, ""));
+        if section == "safety" {
+            match name {
+                "max_power" => self.safety.max_power = source.safety.max_power,
+                "max_ramp_rate" => self.safety.max_ramp_rate = source.safety.max_ramp_rate,
+                "require_confirmation" => {
+                    self.safety.require_confirmation = source.safety.require_confirmation
+                }
+                _ => {}
+            }
+            return;
+        }
+
+        let (target, source) = match section {
+            "channel_a" => (&mut self.channel_a, &source.channel_a),
+            "channel_b" => (&mut self.channel_b, &source.channel_b),
+            _ => return,
+        };
+        match name {
+            "enabled" => target.enabled = source.enabled,
+            "min_power" => target.min_power = source.min_power,
+            "max_power" => target.max_power = source.max_power,
+            "waveform" => {
+                target.waveform = source.waveform.clone();
+                target.waveform_name = source.waveform_name.clone();
+            }
+            "modulation" => target.modulation = source.modulation.clone(),
+            _ => {}
+        }
+    }
+
+    /// 应用前是否需要用户确认
+    pub fn requires_confirmation(&self) -> bool {
+        self.safety.require_confirmation
+    }
+
+    /// 检查预设的所有问题（空列表表示可以保存）
+    pub fn issues(&self) -> Vec<PresetIssue> {
+        let mut issues = Vec::new();
+        if self.name.trim().is_empty() {
+            issues.push(PresetIssue::new("name", "Preset name is empty"));
+        }
+        for (field, config) in [
+            ("channel_a", &self.channel_a),
+            ("channel_b", &self.channel_b),
+        ] {
+            if config.max_power > MAX_STRENGTH {
+                issues.push(PresetIssue::new(
+                    format!("{}.max_power", field),
+                    format!("Max power {} exceeds {}", config.max_power, MAX_STRENGTH),
+                ));
+            }
+            if config.min_power > config.max_power {
+                issues.push(PresetIssue::new(
+                    format!("{}.min_power", field),
+                    format!(
+                        "Min power {} is greater than max power {}",
+                        config.min_power, config.max_power
+                    ),
+                ));
+            }
+        }
+        if let Some(max_power) = self.safety.max_power {
+            if max_power > MAX_STRENGTH {
+                issues.push(PresetIssue::new(
+                    "safety.max_power",
+                    format!("Max power {} exceeds {}", max_power, MAX_STRENGTH),
+                ));
+            }
+        }
+        if self.safety.max_ramp_rate == Some(0) {
+            issues.push(PresetIssue::new(
+                "safety.max_ramp_rate",
+                "Max ramp rate must be positive",
+            ));
+        }
+        if self.parent.as_deref() == Some(self.id.as_str()) {
+            issues.push(PresetIssue::new(
+                "parent",
+                "Preset cannot inherit from itself",
+            ));
+        }
+        for field in &self.overrides {
+            let known = ["channel_a", "channel_b", "safety"].contains(&field.as_str())
+                || INHERITABLE_FIELDS.contains(&field.as_str());
+            if !known {
+                issues.push(PresetIssue::new(
+                    "overrides",
+                    format!("Unknown field '{}'", field),
+                ));
+            }
+        }
+        issues
+    }
+
+    /// 校验预设，返回第一个问题
+    pub fn validate(&self) -> Result<()> {
+        match self.issues().into_iter().next() {
+            Some(issue) => Err(CoreError::InvalidParameter(format!(
+                "Invalid preset '{}': {}",
+                self.name, issue
+            ))),
+            None => Ok(()),
         }
     }
 
@@ -75,13 +423,14 @@ impl Preset {
         self.updated_at = chrono::Utc::now();
     }
 
-    /// 设置通道配置
+    /// 设置通道配置（有父预设时覆盖整个通道）
     pub fn set_channel(&mut self, channel: u8, config: PresetChannelConfig) {
         match channel {
             0 => self.channel_a = config,
             1 => self.channel_b = config,
-            _ => {}
+            _ => return,
         }
+        self.set_override(channel_field(channel));
         self.touch();
     }
 
@@ -90,8 +439,9 @@ impl Preset {
         match channel {
             0 => self.channel_a.waveform = Some(waveform),
             1 => self.channel_b.waveform = Some(waveform),
-            _ => {}
+            _ => return,
         }
+        self.set_override(&format!("{}.waveform", channel_field(channel)));
         self.touch();
     }
 
@@ -100,12 +450,69 @@ impl Preset {
         match channel {
             0 => self.channel_a.max_power = power,
             1 => self.channel_b.max_power = power,
-            _ => {}
+            _ => return,
         }
+        self.set_override(&format!("{}.max_power", channel_field(channel)));
         self.touch();
     }
 }
 
+/// 通道对应的字段名
+fn channel_field(channel: u8) -> &'static str {
+    if channel == 0 {
+        "channel_a"
+    } else {
+        "channel_b"
+    }
+}
+
+/// 预设搜索条件
+///
+/// 各条件同时满足时匹配，未设置的条件不参与筛选；名称、标签和分类都忽略大小写。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PresetQuery {
+    /// 名称或描述包含的文本
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    /// 必须带有的标签（全部满足）
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// 分类
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    /// 最高强度下限（见 [`Preset::peak_power`]）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_power: Option<u8>,
+    /// 最高强度上限
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_power: Option<u8>,
+}
+
+impl PresetQuery {
+    /// 预设是否满足条件
+    pub fn matches(&self, preset: &Preset) -> bool {
+        let text_matches = self.text.as_deref().map_or(true, |text| {
+            let text = text.trim().to_lowercase();
+            preset.name.to_lowercase().contains(&text)
+                || preset.description.to_lowercase().contains(&text)
+        });
+        let category_matches = self.category.as_deref().map_or(true, |category| {
+            preset
+                .category
+                .as_deref()
+                .is_some_and(|c| c.eq_ignore_ascii_case(category.trim()))
+        });
+        let peak = preset.peak_power();
+
+        text_matches
+            && category_matches
+            && self.tags.iter().all(|tag| preset.has_tag(tag))
+            && self.min_power.map_or(true, |min| peak >= min)
+            && self.max_power.map_or(true, |max| peak <= max)
+    }
+}
+
 /// 预设管理器
 pub struct PresetManager {
     /// 预设存储目录
@@ -235,6 +642,29 @@ impl PresetManager {
         presets
     }
 
+    /// 搜索预设（按名称排序，强度条件按继承后的有效配置判断）
+    pub fn search(&self, query: &PresetQuery) -> Vec<&Preset> {
+        let mut presets = self.list_presets();
+        presets.retain(|preset| match self.resolve(&preset.id) {
+            Ok(effective) => query.matches(&effective),
+            Err(_) => query.matches(preset),
+        });
+        presets
+    }
+
+    /// 所有预设用到的分类（忽略大小写去重，排序）
+    pub fn categories(&self) -> Vec<String> {
+        let mut categories: Vec<String> = Vec::new();
+        let presets = self.list_presets();
+        for category in presets.iter().filter_map(|p| p.category.as_deref()) {
+            if !categories.iter().any(|c| c.eq_ignore_ascii_case(category)) {
+                categories.push(category.to_string());
+            }
+        }
+        categories.sort_by_key(|c| c.to_lowercase());
+        categories
+    }
+
     /// 获取预设
     pub fn get_preset(&self, id: &str) -> Option<&Preset> {
         self.presets.get(id)
@@ -247,11 +677,87 @@ impl PresetManager {
             .find(|p| p.name.to_lowercase() == name.to_lowercase())
     }
 
+    /// 获取预设的有效配置（逐级合并父预设）
+    ///
+    /// 应用预设、分享和界面显示都应使用有效配置。父预设不存在或继承成环时返回错误。
+    pub fn resolve(&self, id: &str) -> Result<Preset> {
+        let mut chain = vec![self
+            .presets
+            .get(id)
+            .ok_or_else(|| CoreError::PresetNotFound(id.to_string()))?];
+        while let Some(parent_id) = chain[chain.len() - 1].parent.as_deref() {
+            if let Some(start) = chain.iter().position(|p| p.id == parent_id) {
+                let names: Vec<_> = chain[start..].iter().map(|p| p.name.as_str()).collect();
+                return Err(CoreError::InvalidParameter(format!(
+                    "Preset inheritance cycle: {} -> {}",
+                    names.join(" -> "),
+                    chain[start].name
+                )));
+            }
+            let parent = self
+                .presets
+                .get(parent_id)
+                .ok_or_else(|| CoreError::PresetNotFound(parent_id.to_string()))?;
+            chain.push(parent);
+        }
+
+        // 从根预设开始逐级叠加
+        let mut effective = chain[chain.len() - 1].clone();
+        for child in chain.iter().rev().skip(1) {
+            effective = child.inherit(&effective);
+        }
+        Ok(effective)
+    }
+
+    /// 按名称获取预设的有效配置
+    pub fn resolve_by_name(&self, name: &str) -> Result<Preset> {
+        let preset = self
+            .find_preset_by_name(name)
+            .ok_or_else(|| CoreError::PresetNotFound(name.to_string()))?;
+        self.resolve(&preset.id)
+    }
+
+    /// 按父预设的有效配置记录编辑后预设的覆盖字段（见 [`Preset::record_overrides`]）
+    pub fn record_overrides(&self, preset: &mut Preset) -> Result<()> {
+        if let Some(parent_id) = preset.parent.as_deref() {
+            let parent = self.resolve(parent_id)?;
+            preset.record_overrides(&parent);
+        }
+        Ok(())
+    }
+
+    /// 直接继承指定预设的预设（按名称排序）
+    pub fn children(&self, id: &str) -> Vec<&Preset> {
+        let mut children = self.list_presets();
+        children.retain(|p| p.parent.as_deref() == Some(id));
+        children
+    }
+
+    /// 检查预设的父预设存在且不会形成继承环
+    pub fn check_parent(&self, preset: &Preset) -> Result<()> {
+        let mut parent_id = preset.parent.as_deref();
+        while let Some(id) = parent_id {
+            if id == preset.id {
+                return Err(CoreError::InvalidParameter(format!(
+                    "Preset '{}' cannot inherit from its own descendant",
+                    preset.name
+                )));
+            }
+            let parent = self
+                .presets
+                .get(id)
+                .ok_or_else(|| CoreError::PresetNotFound(id.to_string()))?;
+            parent_id = parent.parent.as_deref();
+        }
+        Ok(())
+    }
+
     /// 添加预设
     pub fn add_preset(&mut self, preset: Preset) -> Result<()> {
         if self.presets.contains_key(&preset.id) {
             return Err(CoreError::PresetAlreadyExists(preset.id));
         }
+        self.check_parent(&preset)?;
         self.presets.insert(preset.id.clone(), preset);
         Ok(())
     }
@@ -261,15 +767,23 @@ impl PresetManager {
         if !self.presets.contains_key(&preset.id) {
             return Err(CoreError::PresetNotFound(preset.id));
         }
+        self.check_parent(&preset)?;
         self.presets.insert(preset.id.clone(), preset);
         Ok(())
     }
 
-    /// 删除预设（仅从内存中）
+    /// 删除预设（仅从内存中），仍被其他预设继承时拒绝
     pub fn remove_preset(&mut self, id: &str) -> Result<()> {
-        if self.presets.remove(id).is_none() {
+        let Some(preset) = self.presets.get(id) else {
             return Err(CoreError::PresetNotFound(id.to_string()));
+        };
+        if let Some(child) = self.children(id).first() {
+            return Err(CoreError::Rejected(format!(
+                "Preset '{}' is inherited by '{}'",
+                preset.name, child.name
+            )));
         }
+        let _ = self.presets.remove(id);
         Ok(())
     }
 
@@ -307,6 +821,32 @@ impl PresetManager {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_preset_issues() {
+        let preset = Preset::new("Test".to_string(), String::new());
+        assert!(preset.issues().is_empty());
+        assert!(preset.validate().is_ok());
+
+        let mut preset = Preset::new(" ".to_string(), String::new());
+        preset.channel_a.min_power = 60;
+        preset.channel_b.max_power = 250;
+        preset.safety.max_ramp_rate = Some(0);
+        let fields: Vec<_> = preset.issues().into_iter().map(|i| i.field).collect();
+        assert_eq!(
+            fields,
+            [
+                "name",
+                "channel_a.min_power",
+                "channel_b.max_power",
+                "safety.max_ramp_rate"
+            ]
+        );
+        assert!(matches!(
+            preset.validate(),
+            Err(CoreError::InvalidParameter(_))
+        ));
+    }
+
     // === PresetChannelConfig 测试 ===
 
     #[test]
@@ -325,6 +865,8 @@ mod tests {
             min_power: 10,
             max_power: 80,
             waveform: None,
+            waveform_name: None,
+            modulation: Vec::new(),
         };
         let json = serde_json::to_string(&config).unwrap();
         let restored: PresetChannelConfig = serde_json::from_str(&json).unwrap();
@@ -333,6 +875,47 @@ mod tests {
         assert_eq!(restored.max_power, 80);
     }
 
+    // === PresetSafety 测试 ===
+
+    #[test]
+    fn test_safety_clamp_and_ramp() {
+        let safety = PresetSafety {
+            max_power: Some(40),
+            max_ramp_rate: Some(10),
+            require_confirmation: true,
+        };
+        assert!(safety.validate().is_ok());
+        assert_eq!(safety.clamp(80), 40);
+        assert_eq!(safety.clamp(20), 20);
+        assert_eq!(
+            safety.ramp_duration(0, 25),
+            Some(Duration::from_millis(2500))
+        );
+        assert_eq!(safety.ramp_duration(30, 10), None);
+        assert_eq!(PresetSafety::default().ramp_duration(0, 100), None);
+
+        let invalid = PresetSafety {
+            max_ramp_rate: Some(0),
+            ..Default::default()
+        };
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn test_safety_serde() {
+        // 旧版预设文件没有 safety 字段
+        let mut preset = Preset::new("Test".to_string(), String::new());
+        let json = serde_json::to_string(&preset).unwrap();
+        assert!(!json.contains("safety"));
+        let restored: Preset = serde_json::from_str(&json).unwrap();
+        assert!(restored.safety.is_default());
+
+        preset.safety.require_confirmation = true;
+        let json = serde_json::to_string(&preset).unwrap();
+        let restored: Preset = serde_json::from_str(&json).unwrap();
+        assert!(restored.requires_confirmation());
+    }
+
     // === Preset 测试 ===
 
     #[test]
@@ -371,6 +954,8 @@ mod tests {
             min_power: 5,
             max_power: 95,
             waveform: None,
+            waveform_name: None,
+            modulation: Vec::new(),
         };
         preset.set_channel(0, config);
         assert!(!preset.channel_a.enabled);
@@ -386,6 +971,8 @@ mod tests {
             min_power: 20,
             max_power: 60,
             waveform: None,
+            waveform_name: None,
+            modulation: Vec::new(),
         };
         preset.set_channel(1, config);
         assert_eq!(preset.channel_b.min_power, 20);
@@ -402,6 +989,8 @@ mod tests {
             min_power: 99,
             max_power: 99,
             waveform: None,
+            waveform_name: None,
+            modulation: Vec::new(),
         };
         preset.set_channel(2, config);
         assert_eq!(preset.channel_a.max_power, original_a);
@@ -444,8 +1033,240 @@ mod tests {
         assert_eq!(restored.settings.get("key").unwrap(), "value");
     }
 
+    #[test]
+    fn test_preset_tags() {
+        let mut preset = Preset::new("Tagged".to_string(), String::new());
+        assert!(!serde_json::to_string(&preset).unwrap().contains("tags"));
+
+        preset.set_tags([" relax ", "Night", "", "RELAX"]);
+        assert_eq!(preset.tags, vec!["Night", "relax"]);
+        assert!(preset.has_tag("night"));
+        assert!(!preset.has_tag("training"));
+
+        // 旧版本预设文件没有标签和分类
+        let json = r#"{"id":"1","name":"Old","description":"","created_at":"2024-01-01T00:00:00Z",
+            "updated_at":"2024-01-01T00:00:00Z","channel_a":{"enabled":true,"min_power":0,"max_power":30,
+            "waveform":null},"channel_b":{"enabled":true,"min_power":0,"max_power":30,"waveform":null},
+            "settings":{}}"#;
+        let old: Preset = serde_json::from_str(json).unwrap();
+        assert!(old.tags.is_empty());
+        assert_eq!(old.category, None);
+    }
+
+    #[test]
+    fn test_preset_peak_power() {
+        let mut preset = Preset::new("Peak".to_string(), String::new());
+        preset.channel_a.max_power = 40;
+        preset.channel_b.max_power = 90;
+        assert_eq!(preset.peak_power(), 90);
+
+        preset.safety.max_power = Some(60);
+        assert_eq!(preset.peak_power(), 60);
+
+        preset.channel_b.enabled = false;
+        assert_eq!(preset.peak_power(), 40);
+    }
+
     // === PresetManager 测试 ===
 
+    #[test]
+    fn test_manager_search() {
+        let manager = &mut PresetManager::new(PathBuf::from("/tmp/test"));
+        let mut gentle = Preset::new("Gentle Evening".to_string(), "Slow waves".to_string());
+        gentle.channel_a.max_power = 20;
+        gentle.channel_b.max_power = 20;
+        gentle.set_tags(["relax", "night"]);
+        gentle.category = Some("Relax".to_string());
+        let mut strong = Preset::new("Strong".to_string(), "Workout pulses".to_string());
+        strong.channel_a.max_power = 100;
+        strong.set_tags(["training"]);
+        strong.category = Some("relax".to_string());
+        manager.add_preset(gentle).unwrap();
+        manager.add_preset(strong).unwrap();
+        manager
+            .add_preset(Preset::new("Plain".to_string(), String::new()))
+            .unwrap();
+
+        let names = |query: PresetQuery| -> Vec<String> {
+            manager
+                .search(&query)
+                .into_iter()
+                .map(|p| p.name.clone())
+                .collect()
+        };
+
+        assert_eq!(names(PresetQuery::default()).len(), 3);
+        assert_eq!(
+            names(PresetQuery {
+                text: Some("evening".to_string()),
+                ..Default::default()
+            }),
+            vec!["Gentle Evening"]
+        );
+        // 描述也参与文本搜索
+        assert_eq!(
+            names(PresetQuery {
+                text: Some("WORKOUT".to_string()),
+                ..Default::default()
+            }),
+            vec!["Strong"]
+        );
+        assert_eq!(
+            names(PresetQuery {
+                tags: vec!["Relax".to_string(), "night".to_string()],
+                ..Default::default()
+            }),
+            vec!["Gentle Evening"]
+        );
+        assert_eq!(
+            names(PresetQuery {
+                category: Some("RELAX".to_string()),
+                min_power: Some(50),
+                ..Default::default()
+            }),
+            vec!["Strong"]
+        );
+        assert_eq!(
+            names(PresetQuery {
+                max_power: Some(50),
+                ..Default::default()
+            }),
+            vec!["Gentle Evening", "Plain"]
+        );
+
+        // 分类忽略大小写去重
+        assert_eq!(manager.categories(), vec!["Relax"]);
+    }
+
+    /// 创建继承 `parent` 的预设
+    fn child_of(parent: &Preset, name: &str) -> Preset {
+        let mut preset = Preset::new(name.to_string(), String::new());
+        preset.parent = Some(parent.id.clone());
+        preset
+    }
+
+    #[test]
+    fn test_preset_inheritance() {
+        let manager = &mut PresetManager::new(PathBuf::from("/tmp/test"));
+        let mut medium = Preset::new("Medium".to_string(), String::new());
+        medium.channel_a.max_power = 50;
+        medium.channel_b.max_power = 50;
+        medium.channel_b.waveform_name = Some("wave".to_string());
+        medium.safety.max_ramp_rate = Some(10);
+        let _ = medium
+            .settings
+            .insert("mode".to_string(), "loop".to_string());
+        let _ = medium.settings.insert("speed".to_string(), "1".to_string());
+
+        let mut strong = child_of(&medium, "Strong");
+        strong.set_max_power(0, 80);
+        strong.set_max_power(1, 80);
+        let _ = strong.settings.insert("speed".to_string(), "2".to_string());
+        assert_eq!(
+            strong.overrides,
+            ["channel_a.max_power", "channel_b.max_power"]
+        );
+
+        let mut extreme = child_of(&strong, "Extreme");
+        extreme.overrides = vec!["channel_a".to_string()];
+        extreme.channel_a.max_power = 100;
+        extreme.channel_a.enabled = false;
+
+        let (strong_id, extreme_id) = (strong.id.clone(), extreme.id.clone());
+        manager.add_preset(medium).unwrap();
+        manager.add_preset(strong).unwrap();
+        manager.add_preset(extreme).unwrap();
+
+        let effective = manager.resolve_by_name("strong").unwrap();
+        assert_eq!(effective.id, strong_id);
+        assert_eq!(effective.channel_a.max_power, 80);
+        assert_eq!(effective.channel_b.waveform_name.as_deref(), Some("wave"));
+        assert_eq!(effective.safety.max_ramp_rate, Some(10));
+        assert_eq!(effective.settings["mode"], "loop");
+        assert_eq!(effective.settings["speed"], "2");
+
+        // 整个通道 A 被覆盖，通道 B 经过两级继承
+        let effective = manager.resolve(&extreme_id).unwrap();
+        assert!(!effective.channel_a.enabled);
+        assert_eq!(effective.channel_a.max_power, 100);
+        assert_eq!(effective.channel_b.max_power, 80);
+
+        // 强度筛选使用有效配置
+        let query = PresetQuery {
+            min_power: Some(80),
+            ..Default::default()
+        };
+        let names: Vec<_> = manager.search(&query).iter().map(|p| &p.name).collect();
+        assert_eq!(names, ["Extreme", "Strong"]);
+    }
+
+    #[test]
+    fn test_preset_inheritance_errors() {
+        let manager = &mut PresetManager::new(PathBuf::from("/tmp/test"));
+        let base = Preset::new("Base".to_string(), String::new());
+        let mut child = child_of(&base, "Child");
+        let base_id = base.id.clone();
+
+        // 父预设不存在
+        assert!(matches!(
+            manager.add_preset(child.clone()),
+            Err(CoreError::PresetNotFound(_))
+        ));
+        manager.add_preset(base.clone()).unwrap();
+        manager.add_preset(child.clone()).unwrap();
+
+        // 父预设改为继承子预设会形成环
+        let mut looped = base.clone();
+        looped.parent = Some(child.id.clone());
+        assert!(manager.update_preset(looped.clone()).is_err());
+        let _ = manager.presets.insert(base_id.clone(), looped);
+        let err = manager.resolve(&child.id).unwrap_err().to_string();
+        assert!(err.contains("cycle"), "{}", err);
+        let _ = manager.presets.insert(base_id.clone(), base);
+
+        // 仍被继承的预设不能删除
+        assert!(matches!(
+            manager.remove_preset(&base_id),
+            Err(CoreError::Rejected(_))
+        ));
+
+        child.parent = Some(child.id.clone());
+        child.overrides = vec!["channel_c".to_string()];
+        let fields: Vec<_> = child.issues().into_iter().map(|i| i.field).collect();
+        assert_eq!(fields, ["parent", "overrides"]);
+    }
+
+    #[test]
+    fn test_record_overrides() {
+        let manager = &mut PresetManager::new(PathBuf::from("/tmp/test"));
+        let mut base = Preset::new("Base".to_string(), String::new());
+        base.channel_b.waveform_name = Some("wave".to_string());
+        let _ = base.settings.insert("mode".to_string(), "loop".to_string());
+        let child = child_of(&base, "Child");
+        let child_id = child.id.clone();
+        manager.add_preset(base).unwrap();
+        manager.add_preset(child).unwrap();
+
+        // 在有效配置上编辑，未修改的字段仍然继承
+        let mut edited = manager.resolve(&child_id).unwrap();
+        edited.channel_a.max_power = 70;
+        edited.channel_b.waveform_name = None;
+        let _ = edited.settings.insert("speed".to_string(), "2".to_string());
+        manager.record_overrides(&mut edited).unwrap();
+        assert_eq!(
+            edited.overrides,
+            ["channel_a.max_power", "channel_b.waveform"]
+        );
+        assert_eq!(edited.settings.len(), 1);
+        manager.update_preset(edited).unwrap();
+
+        let effective = manager.resolve(&child_id).unwrap();
+        assert_eq!(effective.channel_a.max_power, 70);
+        assert_eq!(effective.channel_b.waveform_name, None);
+        assert_eq!(effective.settings["mode"], "loop");
+        assert_eq!(effective.settings["speed"], "2");
+    }
+
     #[test]
     fn test_manager_new() {
         let dir = PathBuf::from("/tmp/test_presets");
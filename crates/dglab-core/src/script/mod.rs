@@ -1,8 +1,14 @@
-//! 脚本引擎模块（待实现）
+//! 脚本引擎模块
+//!
+//! 完整脚本引擎待实现；目前提供声明式波形编排（TOML/YAML）及其执行器。
 
 pub mod engine;
+pub mod pattern;
+pub mod runner;
 
 pub use engine::ScriptError;
+pub use pattern::{Pattern, PatternChannel, PatternEntry, PatternStep};
+pub use runner::PatternRunner;
 
 /// 脚本引擎（占位符）
 pub struct ScriptEngine;
@@ -0,0 +1,491 @@
+//! 声明式波形编排格式
+//!
+//! 比完整脚本语言更轻量的 TOML/YAML 格式，由若干步骤和循环组成：
+//!
+//! ```toml
+//! name = "Warmup"
+//! repeat = 2
+//!
+//! [[steps]]
+//! duration_ms = 2000
+//! channel = "a"
+//! power = 20
+//! waveform = "Breathing"
+//!
+//! [[steps]]
+//! repeat = 3
+//! steps = [
+//!     { duration_ms = 500, power = 40 },
+//!     { duration_ms = 500, power = 10 },
+//! ]
+//! ```
+//!
+//! 步骤可以带 `stereo`，在步骤时长内以 A/B 通道为左右两路播放立体声编排（见
+//! [`StereoPattern`]），`power` 为峰值强度：
+//!
+//! ```toml
+//! [[steps]]
+//! duration_ms = 8000
+//! power = 40
+//! stereo = { mode = "ping-pong", period_ms = 1000, crossfade = 30 }
+//! ```
+
+use std::path::Path;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use dglab_protocol::v3::MAX_STRENGTH;
+
+use crate::error::{CoreError, Result};
+use crate::waveform::{StereoPattern, Waveform, WaveformLibrary};
+
+/// 单步最长持续时间（毫秒）
+pub const MAX_STEP_DURATION_MS: u64 = 10 * 60 * 1000;
+
+/// 单个循环的最大重复次数
+pub const MAX_REPEAT: u32 = 1000;
+
+/// 循环最大嵌套深度
+pub const MAX_NESTING_DEPTH: usize = 8;
+
+/// 展开后的最大步骤数
+pub const MAX_EXPANDED_STEPS: usize = 10_000;
+
+/// 步骤作用的通道
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PatternChannel {
+    /// A 通道
+    A,
+    /// B 通道
+    B,
+    /// 两个通道
+    #[default]
+    Both,
+}
+
+impl PatternChannel {
+    /// 对应的通道编号
+    pub fn channels(&self) -> &'static [u8] {
+        match self {
+            Self::A => &[0],
+            Self::B => &[1],
+            Self::Both => &[0, 1],
+        }
+    }
+}
+
+/// 单个步骤
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PatternStep {
+    /// 持续时间（毫秒）
+    pub duration_ms: u64,
+    /// 作用通道（默认两个通道）
+    #[serde(default)]
+    pub channel: PatternChannel,
+    /// 强度
+    pub power: u8,
+    /// 波形名称（对应内置预设波形）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub waveform: Option<String>,
+    /// 立体声编排（A 通道为左路、B 通道为右路，`power` 为峰值）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stereo: Option<StereoPattern>,
+}
+
+/// 编排条目：步骤或循环
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum PatternEntry {
+    /// 循环执行一组条目
+    Loop {
+        /// 重复次数
+        repeat: u32,
+        /// 循环体
+        steps: Vec<PatternEntry>,
+    },
+    /// 单个步骤
+    Step(PatternStep),
+}
+
+/// 波形编排
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Pattern {
+    /// 名称
+    pub name: String,
+    /// 描述
+    #[serde(default)]
+    pub description: String,
+    /// 整体重复次数
+    #[serde(default = "default_repeat")]
+    pub repeat: u32,
+    /// 条目
+    pub steps: Vec<PatternEntry>,
+}
+
+fn default_repeat() -> u32 {
+    1
+}
+
+/// 展开并解析后的步骤
+#[derive(Debug, Clone)]
+pub struct ResolvedStep {
+    /// 持续时间
+    pub duration: Duration,
+    /// 作用通道
+    pub channel: PatternChannel,
+    /// 强度
+    pub power: u8,
+    /// 波形
+    pub waveform: Option<Waveform>,
+    /// 立体声编排
+    pub stereo: Option<StereoPattern>,
+}
+
+impl Pattern {
+    /// 从 TOML 文本解析
+    pub fn from_toml_str(content: &str) -> Result<Self> {
+        toml::from_str(content)
+            .map_err(|e| CoreError::ScriptError(format!("Invalid pattern TOML: {}", e)))
+    }
+
+    /// 从 YAML 文本解析
+    pub fn from_yaml_str(content: &str) -> Result<Self> {
+        serde_yaml::from_str(content)
+            .map_err(|e| CoreError::ScriptError(format!("Invalid pattern YAML: {}", e)))
+    }
+
+    /// 判断文件扩展名是否为编排格式
+    pub fn is_pattern_file(path: &Path) -> bool {
+        matches!(
+            path.extension().and_then(|e| e.to_str()),
+            Some("toml" | "yaml" | "yml")
+        )
+    }
+
+    /// 从文件加载（按扩展名选择 TOML 或 YAML）
+    pub async fn load(path: &Path) -> Result<Self> {
+        let content = tokio::fs::read_to_string(path).await?;
+
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => Self::from_toml_str(&content),
+            Some("yaml" | "yml") => Self::from_yaml_str(&content),
+            _ => Err(CoreError::ScriptError(format!(
+                "Unsupported pattern file: {}",
+                path.display()
+            ))),
+        }
+    }
+
+    /// 校验并展开为顺序执行的步骤（仅使用内置波形）
+    pub fn resolve(&self) -> Result<Vec<ResolvedStep>> {
+        self.resolve_with(&WaveformLibrary::builtin())
+    }
+
+    /// 校验并展开为顺序执行的步骤，波形名称从波形库查找
+    pub fn resolve_with(&self, library: &WaveformLibrary) -> Result<Vec<ResolvedStep>> {
+        let mut body = Vec::new();
+        resolve_entries(&self.steps, library, 1, &mut body)?;
+
+        check_repeat(self.repeat)?;
+        if body.len().saturating_mul(self.repeat as usize) > MAX_EXPANDED_STEPS {
+            return Err(too_many_steps());
+        }
+
+        let mut steps = Vec::with_capacity(body.len() * self.repeat as usize);
+        for _ in 0..self.repeat {
+            steps.extend(body.iter().cloned());
+        }
+
+        Ok(steps)
+    }
+
+    /// 校验编排
+    pub fn validate(&self) -> Result<()> {
+        self.resolve().map(|_| ())
+    }
+
+    /// 计算总时长
+    pub fn total_duration(&self) -> Result<Duration> {
+        Ok(self.resolve()?.iter().map(|s| s.duration).sum())
+    }
+}
+
+/// 递归展开条目
+fn resolve_entries(
+    entries: &[PatternEntry],
+    library: &WaveformLibrary,
+    depth: usize,
+    out: &mut Vec<ResolvedStep>,
+) -> Result<()> {
+    if depth > MAX_NESTING_DEPTH {
+        return Err(CoreError::ScriptError(format!(
+            "Pattern loops nested deeper than {}",
+            MAX_NESTING_DEPTH
+        )));
+    }
+    if entries.is_empty() {
+        return Err(CoreError::ScriptError(
+            "Pattern contains an empty step list".to_string(),
+        ));
+    }
+
+    for entry in entries {
+        match entry {
+            PatternEntry::Step(step) => {
+                out.push(resolve_step(step, library)?);
+            }
+            PatternEntry::Loop { repeat, steps } => {
+                check_repeat(*repeat)?;
+
+                let mut body = Vec::new();
+                resolve_entries(steps, library, depth + 1, &mut body)?;
+
+                let expanded = body.len().saturating_mul(*repeat as usize);
+                if out.len().saturating_add(expanded) > MAX_EXPANDED_STEPS {
+                    return Err(too_many_steps());
+                }
+                for _ in 0..*repeat {
+                    out.extend(body.iter().cloned());
+                }
+            }
+        }
+
+        if out.len() > MAX_EXPANDED_STEPS {
+            return Err(too_many_steps());
+        }
+    }
+
+    Ok(())
+}
+
+/// 校验并解析单个步骤
+fn resolve_step(step: &PatternStep, library: &WaveformLibrary) -> Result<ResolvedStep> {
+    if step.duration_ms == 0 || step.duration_ms > MAX_STEP_DURATION_MS {
+        return Err(CoreError::ScriptError(format!(
+            "Step duration {}ms out of range (1~{}ms)",
+            step.duration_ms, MAX_STEP_DURATION_MS
+        )));
+    }
+
+    if step.power > MAX_STRENGTH {
+        return Err(CoreError::PowerOutOfRange(step.power, MAX_STRENGTH));
+    }
+
+    if let Some(stereo) = &step.stereo {
+        stereo
+            .validate()
+            .map_err(|e| CoreError::ScriptError(e.to_string()))?;
+        if step.channel != PatternChannel::Both {
+            return Err(CoreError::ScriptError(
+                "Stereo steps use both channels".to_string(),
+            ));
+        }
+    }
+
+    let waveform = match &step.waveform {
+        Some(name) => Some(
+            library
+                .get(name)
+                .ok_or_else(|| CoreError::ScriptError(format!("Unknown waveform: {}", name)))?,
+        ),
+        None => None,
+    };
+
+    Ok(ResolvedStep {
+        duration: Duration::from_millis(step.duration_ms),
+        channel: step.channel,
+        power: step.power,
+        waveform,
+        stereo: step.stereo,
+    })
+}
+
+fn check_repeat(repeat: u32) -> Result<()> {
+    if repeat == 0 || repeat > MAX_REPEAT {
+        return Err(CoreError::ScriptError(format!(
+            "Repeat count {} out of range (1~{})",
+            repeat, MAX_REPEAT
+        )));
+    }
+    Ok(())
+}
+
+fn too_many_steps() -> CoreError {
+    CoreError::ScriptError(format!(
+        "Pattern expands to more than {} steps",
+        MAX_EXPANDED_STEPS
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOML_PATTERN: &str = r#"
+name = "Warmup"
+repeat = 2
+
+[[steps]]
+duration_ms = 2000
+channel = "a"
+power = 20
+waveform = "breathing"
+
+[[steps]]
+repeat = 3
+steps = [
+    { duration_ms = 500, power = 40 },
+    { duration_ms = 500, channel = "b", power = 10 },
+]
+"#;
+
+    const YAML_PATTERN: &str = r#"
+name: Warmup
+steps:
+  - duration_ms: 1000
+    power: 30
+  - repeat: 2
+    steps:
+      - { duration_ms: 250, channel: b, power: 50, waveform: Pulse }
+"#;
+
+    #[test]
+    fn test_parse_toml() {
+        let pattern = Pattern::from_toml_str(TOML_PATTERN).unwrap();
+        assert_eq!(pattern.name, "Warmup");
+        assert_eq!(pattern.repeat, 2);
+        assert_eq!(pattern.steps.len(), 2);
+        assert!(matches!(
+            pattern.steps[1],
+            PatternEntry::Loop { repeat: 3, .. }
+        ));
+    }
+
+    #[test]
+    fn test_parse_yaml() {
+        let pattern = Pattern::from_yaml_str(YAML_PATTERN).unwrap();
+        assert_eq!(pattern.repeat, 1);
+
+        let steps = pattern.resolve().unwrap();
+        assert_eq!(steps.len(), 3);
+        assert_eq!(steps[1].channel, PatternChannel::B);
+        assert_eq!(steps[1].waveform.as_ref().unwrap().name, "Pulse");
+    }
+
+    #[test]
+    fn test_resolve_expands_loops() {
+        let pattern = Pattern::from_toml_str(TOML_PATTERN).unwrap();
+        let steps = pattern.resolve().unwrap();
+
+        // (1 + 3 * 2) * 2
+        assert_eq!(steps.len(), 14);
+        assert_eq!(steps[0].channel, PatternChannel::A);
+        assert_eq!(steps[0].waveform.as_ref().unwrap().name, "Breathing");
+        assert_eq!(steps[1].channel, PatternChannel::Both);
+        assert_eq!(
+            pattern.total_duration().unwrap(),
+            Duration::from_millis((2000 + 3 * 1000) * 2)
+        );
+    }
+
+    #[test]
+    fn test_reject_power_out_of_range() {
+        let pattern =
+            Pattern::from_yaml_str("name: x\nsteps:\n  - { duration_ms: 100, power: 201 }\n")
+                .unwrap();
+        assert!(matches!(
+            pattern.validate(),
+            Err(CoreError::PowerOutOfRange(201, MAX_STRENGTH))
+        ));
+    }
+
+    #[test]
+    fn test_reject_unknown_waveform() {
+        let pattern = Pattern::from_yaml_str(
+            "name: x\nsteps:\n  - { duration_ms: 100, power: 1, waveform: nope }\n",
+        )
+        .unwrap();
+        assert!(pattern.validate().is_err());
+    }
+
+    #[test]
+    fn test_resolve_with_library() {
+        let pattern = Pattern::from_yaml_str(
+            "name: x\nsteps:\n  - { duration_ms: 100, power: 1, waveform: mine }\n",
+        )
+        .unwrap();
+
+        let mut library = WaveformLibrary::builtin();
+        library.insert(Waveform {
+            name: "Mine".to_string(),
+            ..Default::default()
+        });
+
+        let steps = pattern.resolve_with(&library).unwrap();
+        assert_eq!(steps[0].waveform.as_ref().unwrap().name, "Mine");
+    }
+
+    #[test]
+    fn test_reject_zero_duration_and_repeat() {
+        let pattern =
+            Pattern::from_yaml_str("name: x\nsteps:\n  - { duration_ms: 0, power: 1 }\n").unwrap();
+        assert!(pattern.validate().is_err());
+
+        let pattern = Pattern::from_yaml_str(
+            "name: x\nrepeat: 0\nsteps:\n  - { duration_ms: 10, power: 1 }\n",
+        )
+        .unwrap();
+        assert!(pattern.validate().is_err());
+    }
+
+    #[test]
+    fn test_reject_too_many_steps() {
+        let pattern = Pattern::from_yaml_str(
+            "name: x\nsteps:\n  - repeat: 1000\n    steps:\n      - repeat: 1000\n        steps:\n          - { duration_ms: 10, power: 1 }\n",
+        )
+        .unwrap();
+        assert!(pattern.validate().is_err());
+    }
+
+    #[test]
+    fn test_reject_unknown_step_field() {
+        let result = Pattern::from_yaml_str("name: x\nsteps:\n  - { duration_ms: 10, powr: 1 }\n");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_stereo_step() {
+        let pattern = Pattern::from_yaml_str(
+            "name: x\nsteps:\n  - { duration_ms: 1000, power: 40, stereo: { mode: chase, period_ms: 500, phase_offset: 90 } }\n",
+        )
+        .unwrap();
+        let steps = pattern.resolve().unwrap();
+        let stereo = steps[0].stereo.unwrap();
+        assert_eq!(stereo.mode, crate::waveform::StereoMode::Chase);
+        assert_eq!(stereo.phase_offset, 90);
+        assert_eq!(stereo.crossfade, 0);
+
+        // 单通道步骤不能使用立体声
+        let pattern = Pattern::from_yaml_str(
+            "name: x\nsteps:\n  - { duration_ms: 1000, channel: a, power: 40, stereo: { period_ms: 500 } }\n",
+        )
+        .unwrap();
+        assert!(pattern.validate().is_err());
+
+        let pattern = Pattern::from_yaml_str(
+            "name: x\nsteps:\n  - { duration_ms: 1000, power: 40, stereo: { period_ms: 50 } }\n",
+        )
+        .unwrap();
+        assert!(pattern.validate().is_err());
+    }
+
+    #[test]
+    fn test_is_pattern_file() {
+        assert!(Pattern::is_pattern_file(Path::new("a.toml")));
+        assert!(Pattern::is_pattern_file(Path::new("a.yml")));
+        assert!(!Pattern::is_pattern_file(Path::new("a.lua")));
+    }
+}
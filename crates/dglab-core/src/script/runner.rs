@@ -0,0 +1,301 @@
+//! 波形编排执行器
+
+use tokio::sync::watch;
+use tracing::{debug, info, warn};
+
+use super::pattern::{Pattern, ResolvedStep};
+use crate::error::Result;
+use crate::session::SessionManager;
+use crate::waveform::stereo::STEREO_TICK;
+use crate::waveform::{StereoPattern, WaveformLibrary};
+
+/// 波形编排执行器
+///
+/// 按顺序在设备上执行编排步骤，结束或取消后将两个通道强度归零。
+pub struct PatternRunner {
+    /// 编排
+    pattern: Pattern,
+    /// 已校验并展开的步骤
+    steps: Vec<ResolvedStep>,
+    /// 取消信号
+    cancel_tx: watch::Sender<bool>,
+}
+
+impl PatternRunner {
+    /// 创建执行器（会先校验编排）
+    pub fn new(pattern: Pattern) -> Result<Self> {
+        Self::with_library(pattern, &WaveformLibrary::builtin())
+    }
+
+    /// 创建执行器，波形名称从波形库查找
+    pub fn with_library(pattern: Pattern, library: &WaveformLibrary) -> Result<Self> {
+        let steps = pattern.resolve_with(library)?;
+        let (cancel_tx, _) = watch::channel(false);
+
+        Ok(Self {
+            pattern,
+            steps,
+            cancel_tx,
+        })
+    }
+
+    /// 获取编排
+    pub fn pattern(&self) -> &Pattern {
+        &self.pattern
+    }
+
+    /// 获取展开后的步骤
+    pub fn steps(&self) -> &[ResolvedStep] {
+        &self.steps
+    }
+
+    /// 取消正在执行的编排
+    pub fn cancel(&self) {
+        let _ = self.cancel_tx.send_replace(true);
+    }
+
+    /// 在会话中的设备上执行编排
+    ///
+    /// 强度和波形经会话管理器写入，受校准和安全限制约束；等待期间不阻塞其他操作。
+    pub async fn run(&self, session: &SessionManager, device_id: &str) -> Result<()> {
+        info!(
+            "Running pattern '{}' ({} steps)",
+            self.pattern.name,
+            self.steps.len()
+        );
+
+        let _ = self.cancel_tx.send_replace(false);
+        let mut cancel_rx = self.cancel_tx.subscribe();
+
+        let result = self.run_steps(session, device_id, &mut cancel_rx).await;
+
+        // 结束或出错后归零
+        for channel in 0..2u8 {
+            if let Err(e) = session.set_power(device_id, channel, 0).await {
+                warn!("Failed to reset channel {} after pattern: {}", channel, e);
+            }
+        }
+
+        result
+    }
+
+    /// 顺序执行步骤
+    async fn run_steps(
+        &self,
+        session: &SessionManager,
+        device_id: &str,
+        cancel_rx: &mut watch::Receiver<bool>,
+    ) -> Result<()> {
+        for (index, step) in self.steps.iter().enumerate() {
+            if *cancel_rx.borrow_and_update() {
+                info!("Pattern '{}' cancelled", self.pattern.name);
+                return Ok(());
+            }
+
+            debug!(
+                "Pattern step {}: {:?} power={} for {:?}",
+                index, step.channel, step.power, step.duration
+            );
+
+            for &channel in step.channel.channels() {
+                if let Some(waveform) = &step.waveform {
+                    session
+                        .set_waveform(device_id, channel, waveform.to_device_config())
+                        .await?;
+                }
+                if step.stereo.is_none() {
+                    let _ = session.set_power(device_id, channel, step.power).await?;
+                }
+            }
+
+            // 立体声步骤在步骤时长内持续更新强度，普通步骤只等待
+            let play = async {
+                match &step.stereo {
+                    Some(stereo) => play_stereo(session, device_id, stereo, step.power).await,
+                    None => std::future::pending().await,
+                }
+            };
+            tokio::select! {
+                result = tokio::time::timeout(step.duration, play) => {
+                    if let Ok(Err(e)) = result {
+                        return Err(e);
+                    }
+                }
+                _ = cancel_rx.changed() => {
+                    info!("Pattern '{}' cancelled", self.pattern.name);
+                    return Ok(());
+                }
+            }
+        }
+
+        info!("Pattern '{}' finished", self.pattern.name);
+        Ok(())
+    }
+}
+
+/// 以 A/B 通道为左右两路播放立体声编排，直到调用方取消 future
+async fn play_stereo(
+    session: &SessionManager,
+    device_id: &str,
+    stereo: &StereoPattern,
+    power: u8,
+) -> Result<()> {
+    let start = tokio::time::Instant::now();
+    let mut interval = tokio::time::interval(STEREO_TICK);
+    // 只在强度变化时写入
+    let mut written = [None; 2];
+    loop {
+        let _ = interval.tick().await;
+        let (left, right) = stereo.levels(start.elapsed().as_millis() as u64, power);
+        for (channel, level) in [(0u8, left), (1u8, right)] {
+            if written[channel as usize] != Some(level) {
+                let _ = session.set_power(device_id, channel, level).await?;
+                written[channel as usize] = Some(level);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use super::*;
+    use crate::config::SafetyConfig;
+    use crate::device::{Device, MockDevice};
+
+    async fn session_with_device() -> SessionManager {
+        let mut device = MockDevice::new("mock-1".to_string(), "Mock".to_string());
+        device.connect().await.unwrap();
+        let session = SessionManager::new();
+        session.add_device(Box::new(device)).await.unwrap();
+        session
+    }
+
+    async fn power(session: &SessionManager, channel: u8) -> u8 {
+        let device = session.get_device("mock-1").await.unwrap();
+        let dev = device.read().await;
+        dev.get_power(channel)
+    }
+
+    fn pattern(yaml: &str) -> Pattern {
+        Pattern::from_yaml_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn test_new_rejects_invalid_pattern() {
+        let result = PatternRunner::new(pattern(
+            "name: x\nsteps:\n  - { duration_ms: 0, power: 10 }\n",
+        ));
+        assert!(result.is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_run_applies_steps_and_resets() {
+        let session = session_with_device().await;
+        let runner = PatternRunner::new(pattern(
+            "name: x\nsteps:\n  - { duration_ms: 100, channel: a, power: 30 }\n",
+        ))
+        .unwrap();
+
+        let device = session.get_device("mock-1").await.unwrap();
+        let mut rx = device.read().await.subscribe_events();
+        runner.run(&session, "mock-1").await.unwrap();
+
+        // 连接后的首个强度事件来自编排步骤
+        let mut powers = Vec::new();
+        while let Ok(event) = rx.try_recv() {
+            if let crate::device::DeviceEvent::PowerChanged { channel, power } = event {
+                powers.push((channel, power));
+            }
+        }
+        assert_eq!(powers.first(), Some(&(0, 30)));
+
+        let dev = device.read().await;
+        assert_eq!(dev.get_power(0), 0);
+        assert_eq!(dev.get_power(1), 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_run_stereo_step() {
+        let session = Arc::new(session_with_device().await);
+        let runner = Arc::new(
+            PatternRunner::new(pattern(
+                "name: x\nsteps:\n  - { duration_ms: 2000, power: 30, stereo: { period_ms: 1000 } }\n",
+            ))
+            .unwrap(),
+        );
+
+        let task = {
+            let session = session.clone();
+            let runner = runner.clone();
+            tokio::spawn(async move { runner.run(&session, "mock-1").await })
+        };
+
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        assert_eq!(
+            (power(&session, 0).await, power(&session, 1).await),
+            (30, 0)
+        );
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        assert_eq!(
+            (power(&session, 0).await, power(&session, 1).await),
+            (0, 30)
+        );
+
+        task.await.unwrap().unwrap();
+        assert_eq!(power(&session, 1).await, 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_cancel_stops_run() {
+        let session = Arc::new(session_with_device().await);
+        let runner = Arc::new(
+            PatternRunner::new(pattern(
+                "name: x\nsteps:\n  - { duration_ms: 600000, power: 20 }\n  - { duration_ms: 600000, power: 40 }\n",
+            ))
+            .unwrap(),
+        );
+
+        let task = {
+            let session = session.clone();
+            let runner = runner.clone();
+            tokio::spawn(async move { runner.run(&session, "mock-1").await })
+        };
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert_eq!(power(&session, 0).await, 20);
+
+        runner.cancel();
+        task.await.unwrap().unwrap();
+        assert_eq!(power(&session, 0).await, 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_run_respects_safety_limit() {
+        let session = Arc::new(session_with_device().await);
+        session.set_safety(SafetyConfig {
+            max_power_a: 15,
+            ..SafetyConfig::default()
+        });
+        let runner = Arc::new(
+            PatternRunner::new(pattern(
+                "name: x\nsteps:\n  - { duration_ms: 600000, channel: a, power: 60 }\n",
+            ))
+            .unwrap(),
+        );
+
+        let task = {
+            let session = session.clone();
+            let runner = runner.clone();
+            tokio::spawn(async move { runner.run(&session, "mock-1").await })
+        };
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert_eq!(power(&session, 0).await, 15);
+
+        runner.cancel();
+        task.await.unwrap().unwrap();
+    }
+}
@@ -0,0 +1,193 @@
+//! 传感器读数处理
+
+use dglab_protocol::ble::{SensorDevice, SensorReading};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use super::mapping::SensorMapping;
+use crate::device::traits::WaveformConfig;
+use crate::error::{CoreError, Result};
+use crate::gamepad::controller::apply_axis;
+use crate::session::SessionManager;
+
+/// 传感器事件
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SensorEvent {
+    /// 传感器设备 ID
+    pub sensor_id: String,
+    /// 读数
+    pub reading: SensorReading,
+}
+
+/// 读取传感器读数并广播为 [`SensorEvent`]，连接断开后任务结束
+pub fn spawn_listener(
+    sensor: SensorDevice,
+    events: broadcast::Sender<SensorEvent>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        info!("Listening to sensor {}", sensor.id());
+        loop {
+            match sensor.next_reading().await {
+                Ok(reading) => {
+                    debug!("Sensor {}: {:?}", sensor.id(), reading);
+                    // 没有订阅者时丢弃读数
+                    let _ = events.send(SensorEvent {
+                        sensor_id: sensor.id().to_string(),
+                        reading,
+                    });
+                }
+                Err(e) => {
+                    warn!("Sensor {} stopped: {}", sensor.id(), e);
+                    break;
+                }
+            }
+        }
+    })
+}
+
+/// 传感器控制器
+///
+/// 按映射把传感器读数转换为对设备的操作，与手柄轴共用同样的控制目标。
+/// 只在量化后的值变化时写入设备，读数抖动不会产生多余的蓝牙写入。
+pub struct SensorController {
+    /// 输入映射
+    mappings: Vec<SensorMapping>,
+    /// 两个通道当前的波形配置（波形强度目标修改其强度）
+    waveforms: [WaveformConfig; 2],
+}
+
+impl SensorController {
+    /// 创建控制器（校验映射）
+    pub fn new(mappings: Vec<SensorMapping>) -> Result<Self> {
+        for mapping in &mappings {
+            mapping.validate()?;
+        }
+        Ok(Self {
+            mappings,
+            waveforms: [WaveformConfig::default(), WaveformConfig::default()],
+        })
+    }
+
+    /// 获取输入映射
+    pub fn mappings(&self) -> &[SensorMapping] {
+        &self.mappings
+    }
+
+    /// 设置通道的波形配置，波形强度目标在此基础上调整强度
+    pub fn set_waveform(&mut self, channel: u8, waveform: WaveformConfig) -> Result<()> {
+        let slot = self
+            .waveforms
+            .get_mut(channel as usize)
+            .ok_or(CoreError::InvalidChannel(channel))?;
+        *slot = waveform;
+        Ok(())
+    }
+
+    /// 处理读数，应用到设备
+    pub async fn handle(
+        &mut self,
+        session: &SessionManager,
+        device_id: &str,
+        reading: &SensorReading,
+    ) -> Result<()> {
+        let targets: Vec<_> = self
+            .mappings
+            .iter()
+            .filter_map(|m| Some((m.target, m.level(reading)?)))
+            .collect();
+        for (target, level) in targets {
+            apply_axis(session, device_id, target, level, &mut self.waveforms).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device::{Device, MockDevice};
+    use crate::gamepad::AxisTarget;
+    use crate::sensor::SensorInput;
+
+    async fn session_with_device() -> SessionManager {
+        let mut device = MockDevice::new("mock-1".to_string(), "Mock".to_string());
+        device.connect().await.unwrap();
+        let session = SessionManager::new();
+        session.add_device(Box::new(device)).await.unwrap();
+        session
+    }
+
+    async fn power(session: &SessionManager, channel: u8) -> u8 {
+        let device = session.get_device("mock-1").await.unwrap();
+        let dev = device.read().await;
+        dev.get_power(channel)
+    }
+
+    #[tokio::test]
+    async fn test_analog_sets_power() {
+        let session = session_with_device().await;
+        let mut controller = SensorController::new(vec![SensorMapping::new(
+            SensorInput::Analog,
+            AxisTarget::Power { channel: 0 },
+        )])
+        .unwrap();
+
+        controller
+            .handle(&session, "mock-1", &SensorReading::Analog { value: 4095 })
+            .await
+            .unwrap();
+        assert_eq!(power(&session, 0).await, 100);
+        assert_eq!(power(&session, 1).await, 0);
+
+        // 其它输入的读数被忽略
+        controller
+            .handle(
+                &session,
+                "mock-1",
+                &SensorReading::Button { pressed: false },
+            )
+            .await
+            .unwrap();
+        assert_eq!(power(&session, 0).await, 100);
+
+        controller
+            .handle(&session, "mock-1", &SensorReading::Analog { value: 0 })
+            .await
+            .unwrap();
+        assert_eq!(power(&session, 0).await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_button_and_waveform_intensity() {
+        let session = session_with_device().await;
+        let mut controller = SensorController::new(vec![SensorMapping::new(
+            SensorInput::Button,
+            AxisTarget::WaveformIntensity { channel: 1 },
+        )])
+        .unwrap();
+
+        controller
+            .handle(&session, "mock-1", &SensorReading::Button { pressed: true })
+            .await
+            .unwrap();
+        assert_eq!(controller.waveforms[1].intensity, 100);
+
+        controller
+            .handle(
+                &session,
+                "mock-1",
+                &SensorReading::Button { pressed: false },
+            )
+            .await
+            .unwrap();
+        assert_eq!(controller.waveforms[1].intensity, 0);
+    }
+
+    #[test]
+    fn test_new_validates() {
+        let invalid = SensorMapping::new(SensorInput::Motion, AxisTarget::Power { channel: 3 });
+        assert!(SensorController::new(vec![invalid]).is_err());
+    }
+}
@@ -0,0 +1,241 @@
+//! 传感器输入映射
+
+use std::fmt;
+use std::str::FromStr;
+
+use dglab_protocol::ble::sensor::ANALOG_MAX;
+use dglab_protocol::ble::SensorReading;
+use serde::{Deserialize, Serialize};
+
+use crate::device::Easing;
+use crate::error::{CoreError, Result};
+use crate::gamepad::AxisTarget;
+
+/// 传感器输入
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SensorInput {
+    /// 按键（按下为 1，松开为 0）
+    Button,
+    /// 模拟量（0~4095）
+    Analog,
+    /// 加速度的模（mg，静止时约为 1000）
+    Motion,
+}
+
+impl SensorInput {
+    /// 所有输入
+    pub const ALL: [Self; 3] = [Self::Button, Self::Analog, Self::Motion];
+
+    /// 从读数中取该输入的原始值，读数不属于该输入时返回 `None`
+    pub fn value(&self, reading: &SensorReading) -> Option<f32> {
+        match (self, reading) {
+            (Self::Button, SensorReading::Button { pressed }) => {
+                Some(if *pressed { 1.0 } else { 0.0 })
+            }
+            (Self::Analog, SensorReading::Analog { value }) => Some(f32::from(*value)),
+            (Self::Motion, _) => reading.magnitude(),
+            _ => None,
+        }
+    }
+
+    /// 默认输入范围（映射到 0.0~1.0 的原始值区间）
+    pub fn default_range(&self) -> (f32, f32) {
+        match self {
+            Self::Button => (0.0, 1.0),
+            Self::Analog => (0.0, f32::from(ANALOG_MAX)),
+            // 去掉重力的 1g，晃动到 3g 时输出最大
+            Self::Motion => (1000.0, 3000.0),
+        }
+    }
+}
+
+impl fmt::Display for SensorInput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Button => "button",
+            Self::Analog => "analog",
+            Self::Motion => "motion",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for SensorInput {
+    type Err = CoreError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|input| input.to_string() == s.trim().to_lowercase())
+            .ok_or_else(|| CoreError::InvalidParameter(format!("Invalid sensor input: {}", s)))
+    }
+}
+
+/// 传感器映射
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SensorMapping {
+    /// 输入
+    pub input: SensorInput,
+    /// 控制目标
+    #[serde(flatten)]
+    pub target: AxisTarget,
+    /// 输入范围下限，不超过下限时输出为零
+    pub min: f32,
+    /// 输入范围上限，达到上限时输出最大
+    pub max: f32,
+    /// 响应曲线
+    #[serde(default)]
+    pub curve: Easing,
+    /// 反向（输入越大输出越小）
+    #[serde(default)]
+    pub invert: bool,
+}
+
+impl SensorMapping {
+    /// 创建映射（默认输入范围、线性曲线）
+    pub fn new(input: SensorInput, target: AxisTarget) -> Self {
+        let (min, max) = input.default_range();
+        Self {
+            input,
+            target,
+            min,
+            max,
+            curve: Easing::Linear,
+            invert: false,
+        }
+    }
+
+    /// 把读数映射为输出比例 (0.0~1.0)，读数不属于该输入时返回 `None`
+    pub fn level(&self, reading: &SensorReading) -> Option<f32> {
+        let value = self.input.value(reading)?;
+        let t = ((value - self.min) / (self.max - self.min)).clamp(0.0, 1.0);
+        let t = if self.invert { 1.0 - t } else { t };
+        Some(self.curve.apply(t).clamp(0.0, 1.0))
+    }
+
+    /// 检查通道编号和输入范围
+    pub fn validate(&self) -> Result<()> {
+        if self.target.channel() > 1 {
+            return Err(CoreError::InvalidChannel(self.target.channel()));
+        }
+        if !(self.min.is_finite() && self.max.is_finite() && self.min < self.max) {
+            return Err(CoreError::InvalidParameter(format!(
+                "Sensor range min {} must be less than max {}",
+                self.min, self.max
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for SensorMapping {
+    type Err = CoreError;
+
+    /// 解析 `输入:目标:通道[:下限:上限]`，如 `motion:power:a`、`analog:intensity:b:500:3500`
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || {
+            CoreError::InvalidParameter(format!(
+                "Invalid sensor mapping '{}', expected input:target:channel[:min:max]",
+                s
+            ))
+        };
+
+        let parts: Vec<&str> = s.split(':').map(str::trim).collect();
+        let (input, target, channel, range) = match parts.as_slice() {
+            [input, target, channel] => (input, target, channel, None),
+            [input, target, channel, min, max] => (input, target, channel, Some((min, max))),
+            _ => return Err(invalid()),
+        };
+
+        let channel = match channel.to_ascii_lowercase().as_str() {
+            "a" | "0" => 0,
+            "b" | "1" => 1,
+            _ => return Err(invalid()),
+        };
+        let target = match target.to_ascii_lowercase().as_str() {
+            "power" | "p" => AxisTarget::Power { channel },
+            "intensity" | "i" => AxisTarget::WaveformIntensity { channel },
+            _ => return Err(invalid()),
+        };
+        let mut mapping = Self::new(input.parse()?, target);
+        if let Some((min, max)) = range {
+            mapping.min = min.parse().map_err(|_| invalid())?;
+            mapping.max = max.parse().map_err(|_| invalid())?;
+        }
+        mapping.validate()?;
+        Ok(mapping)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_value() {
+        let pressed = SensorReading::Button { pressed: true };
+        let analog = SensorReading::Analog { value: 2000 };
+        let motion = SensorReading::Motion {
+            x: 0,
+            y: 0,
+            z: 2000,
+        };
+        assert_eq!(SensorInput::Button.value(&pressed), Some(1.0));
+        assert_eq!(SensorInput::Button.value(&analog), None);
+        assert_eq!(SensorInput::Analog.value(&analog), Some(2000.0));
+        assert_eq!(SensorInput::Motion.value(&motion), Some(2000.0));
+        assert_eq!(SensorInput::Motion.value(&pressed), None);
+    }
+
+    #[test]
+    fn test_mapping_level() {
+        let mapping = SensorMapping::new(SensorInput::Motion, AxisTarget::Power { channel: 0 });
+        let motion = |z| SensorReading::Motion { x: 0, y: 0, z };
+        // 静止（1g）时为零，3g 及以上为最大
+        assert_eq!(mapping.level(&motion(1000)), Some(0.0));
+        assert_eq!(mapping.level(&motion(2000)), Some(0.5));
+        assert_eq!(mapping.level(&motion(5000)), Some(1.0));
+        assert_eq!(
+            mapping.level(&SensorReading::Button { pressed: true }),
+            None
+        );
+
+        let inverted = SensorMapping {
+            invert: true,
+            curve: Easing::EaseIn,
+            ..SensorMapping::new(SensorInput::Analog, AxisTarget::Power { channel: 1 })
+        };
+        assert_eq!(
+            inverted.level(&SensorReading::Analog { value: 0 }),
+            Some(1.0)
+        );
+        assert_eq!(
+            inverted.level(&SensorReading::Analog { value: ANALOG_MAX }),
+            Some(0.0)
+        );
+    }
+
+    #[test]
+    fn test_validate_and_parse() {
+        let mut mapping = SensorMapping::new(
+            SensorInput::Analog,
+            AxisTarget::WaveformIntensity { channel: 0 },
+        );
+        assert!(mapping.validate().is_ok());
+        mapping.max = mapping.min;
+        assert!(mapping.validate().is_err());
+
+        let mapping = SensorMapping::new(SensorInput::Button, AxisTarget::Power { channel: 2 });
+        assert!(matches!(
+            mapping.validate(),
+            Err(CoreError::InvalidChannel(2))
+        ));
+
+        assert_eq!(
+            "Motion".parse::<SensorInput>().unwrap(),
+            SensorInput::Motion
+        );
+        assert!("pressure".parse::<SensorInput>().is_err());
+    }
+}
@@ -0,0 +1,13 @@
+//! 无线传感器模块
+//!
+//! 读取无线传感器（47L120100）的读数并广播为 [`SensorEvent`]，
+//! 按映射把按键、模拟量或加速度转换为通道强度或波形强度，作为调制源使用。
+//!
+//! 传感器的通知格式未经官方文档确认，该功能为实验性功能，
+//! 见 [`dglab_protocol::ble::sensor`]。
+
+pub mod controller;
+pub mod mapping;
+
+pub use controller::{spawn_listener, SensorController, SensorEvent};
+pub use mapping::{SensorInput, SensorMapping};
@@ -0,0 +1,291 @@
+//! 电量历史
+//!
+//! 记录会话中每个设备的电量变化，并按放电速率估算剩余使用时间。
+//! 电量只按 1% 变化，所以只在电量变化时记录样本；电量上升（充电或换电池）后从头估算。
+//!
+//! 电量过低时输出可能不稳定，[`BatteryPolicy`] 按电量决定限制强度上限或暂停输出，
+//! 由会话管理器在电量更新时执行。
+
+use std::fmt;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+
+use dglab_protocol::v3::MAX_STRENGTH;
+
+use crate::error::{CoreError, Result};
+
+/// 每个设备最多保留的样本数
+pub const MAX_BATTERY_SAMPLES: usize = 512;
+
+/// 估算剩余时间至少需要的电量下降（%），下降太少时速率误差太大
+const MIN_ESTIMATE_DROP: u8 = 2;
+
+/// 低电量时的输出限制
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BatteryPolicy {
+    /// 电量低于该值（%）时限制强度上限，不设置表示不限制
+    pub cap_below: Option<u8>,
+    /// 限制后的强度上限
+    pub cap_power: u8,
+    /// 电量低于该值（%）时暂停输出（强度归零），不设置表示不暂停
+    pub pause_below: Option<u8>,
+}
+
+impl Default for BatteryPolicy {
+    fn default() -> Self {
+        Self {
+            cap_below: None,
+            cap_power: 30,
+            pause_below: None,
+        }
+    }
+}
+
+impl BatteryPolicy {
+    /// 校验阈值和强度上限
+    pub fn validate(&self) -> Result<()> {
+        for threshold in [self.cap_below, self.pause_below].into_iter().flatten() {
+            if threshold > 100 {
+                return Err(CoreError::InvalidParameter(format!(
+                    "Battery threshold {}% out of range 0~100",
+                    threshold
+                )));
+            }
+        }
+        if self.cap_power > MAX_STRENGTH {
+            return Err(CoreError::PowerOutOfRange(self.cap_power, MAX_STRENGTH));
+        }
+        Ok(())
+    }
+
+    /// 电量对应的限制
+    pub fn action(&self, level: u8) -> BatteryAction {
+        if self.pause_below.is_some_and(|below| level < below) {
+            BatteryAction::Pause
+        } else if self.cap_below.is_some_and(|below| level < below) {
+            BatteryAction::Cap(self.cap_power)
+        } else {
+            BatteryAction::None
+        }
+    }
+}
+
+/// 低电量限制
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub enum BatteryAction {
+    /// 不限制
+    #[default]
+    None,
+    /// 限制强度上限
+    Cap(u8),
+    /// 暂停输出
+    Pause,
+}
+
+impl BatteryAction {
+    /// 限制的强度上限，不限制时返回 `None`
+    pub fn limit(self) -> Option<u8> {
+        match self {
+            Self::None => None,
+            Self::Cap(power) => Some(power),
+            Self::Pause => Some(0),
+        }
+    }
+
+    /// 是否比 `other` 更严格（强度上限更低）
+    pub fn is_stricter_than(self, other: Self) -> bool {
+        match (self.limit(), other.limit()) {
+            (Some(limit), Some(other)) => limit < other,
+            (Some(_), None) => true,
+            (None, _) => false,
+        }
+    }
+}
+
+impl fmt::Display for BatteryAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::None => f.write_str("none"),
+            Self::Cap(power) => write!(f, "cap {}", power),
+            Self::Pause => f.write_str("pause"),
+        }
+    }
+}
+
+/// 电量样本
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatterySample {
+    /// 距开始记录的时长（毫秒）
+    pub elapsed_ms: u64,
+    /// 电量 (0-100)
+    pub level: u8,
+}
+
+/// 单设备电量历史
+#[derive(Debug, Clone)]
+pub struct BatteryHistory {
+    /// 开始记录的时间
+    started: Instant,
+    /// 电量变化样本（按时间顺序）
+    samples: Vec<BatterySample>,
+    /// 当前放电区间的第一个样本下标
+    discharge_start: usize,
+}
+
+impl BatteryHistory {
+    /// 从当前时刻开始记录
+    pub fn new(now: Instant) -> Self {
+        Self {
+            started: now,
+            samples: Vec::new(),
+            discharge_start: 0,
+        }
+    }
+
+    /// 记录电量，电量与上一个样本相同时忽略，返回是否记录
+    pub fn record(&mut self, level: u8, now: Instant) -> bool {
+        let level = level.min(100);
+        let last = self.samples.last().map(|s| s.level);
+        if last == Some(level) {
+            return false;
+        }
+
+        if self.samples.len() == MAX_BATTERY_SAMPLES {
+            let _ = self.samples.remove(0);
+            self.discharge_start = self.discharge_start.saturating_sub(1);
+        }
+        if last.is_some_and(|last| level > last) {
+            self.discharge_start = self.samples.len();
+        }
+        self.samples.push(BatterySample {
+            elapsed_ms: now.saturating_duration_since(self.started).as_millis() as u64,
+            level,
+        });
+        true
+    }
+
+    /// 所有样本
+    pub fn samples(&self) -> &[BatterySample] {
+        &self.samples
+    }
+
+    /// 最近的电量
+    pub fn level(&self) -> Option<u8> {
+        self.samples.last().map(|s| s.level)
+    }
+
+    /// 当前放电速率（% / 小时），放电区间电量下降不足时返回 `None`
+    pub fn drain_per_hour(&self) -> Option<f64> {
+        let run = &self.samples[self.discharge_start..];
+        let (first, last) = (run.first()?, run.last()?);
+        if first.level.saturating_sub(last.level) < MIN_ESTIMATE_DROP {
+            return None;
+        }
+
+        // 最小二乘拟合电量随时间的斜率
+        let n = run.len() as f64;
+        let hours = |s: &BatterySample| s.elapsed_ms as f64 / 3_600_000.0;
+        let mean_t = run.iter().map(hours).sum::<f64>() / n;
+        let mean_l = run.iter().map(|s| f64::from(s.level)).sum::<f64>() / n;
+        let (mut cov, mut var) = (0.0, 0.0);
+        for sample in run {
+            let dt = hours(sample) - mean_t;
+            cov += dt * (f64::from(sample.level) - mean_l);
+            var += dt * dt;
+        }
+        let slope = cov / var;
+        (slope.is_finite() && slope < 0.0).then_some(-slope)
+    }
+
+    /// 按当前放电速率估算的剩余使用时间（从 `now` 算起）
+    pub fn estimate_remaining(&self, now: Instant) -> Option<Duration> {
+        let rate = self.drain_per_hour()?;
+        let last = self.samples.last()?;
+        let remaining = Duration::from_secs_f64(f64::from(last.level) / rate * 3600.0);
+        let since_last = now
+            .saturating_duration_since(self.started)
+            .saturating_sub(Duration::from_millis(last.elapsed_ms));
+        Some(remaining.saturating_sub(since_last))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_remaining() {
+        let start = Instant::now();
+        let mut history = BatteryHistory::new(start);
+        let minute = Duration::from_secs(60);
+
+        assert!(history.record(80, start));
+        assert!(!history.record(80, start + minute));
+        assert!(history.record(79, start + minute * 6));
+        // 下降不足时不估算
+        assert_eq!(history.estimate_remaining(start + minute * 6), None);
+
+        // 每 6 分钟 1%：10%/小时，剩余 78% 约 7.8 小时
+        assert!(history.record(78, start + minute * 12));
+        let rate = history.drain_per_hour().unwrap();
+        assert!((rate - 10.0).abs() < 1e-6, "rate {}", rate);
+        let remaining = history.estimate_remaining(start + minute * 12).unwrap();
+        assert!((remaining.as_secs_f64() - 78.0 * 360.0).abs() < 1.0);
+        // 距上次样本过去的时间从估算中扣除
+        let later = history.estimate_remaining(start + minute * 15).unwrap();
+        assert_eq!(remaining - later, minute * 3);
+
+        // 电量上升后重新开始估算
+        assert!(history.record(100, start + minute * 20));
+        assert_eq!(history.drain_per_hour(), None);
+        assert_eq!(history.samples().len(), 4);
+        assert_eq!(history.level(), Some(100));
+    }
+
+    #[test]
+    fn test_battery_policy() {
+        let policy = BatteryPolicy {
+            cap_below: Some(20),
+            cap_power: 40,
+            pause_below: Some(5),
+        };
+        policy.validate().unwrap();
+        assert_eq!(policy.action(50), BatteryAction::None);
+        assert_eq!(policy.action(19), BatteryAction::Cap(40));
+        assert_eq!(policy.action(4), BatteryAction::Pause);
+        assert_eq!(policy.action(4).limit(), Some(0));
+        assert_eq!(BatteryPolicy::default().action(0), BatteryAction::None);
+        assert!(BatteryAction::Pause.is_stricter_than(BatteryAction::Cap(40)));
+        assert!(BatteryAction::Cap(20).is_stricter_than(BatteryAction::Cap(40)));
+        assert!(!BatteryAction::Cap(40).is_stricter_than(BatteryAction::Cap(40)));
+        assert!(!BatteryAction::None.is_stricter_than(BatteryAction::Cap(40)));
+
+        assert!(BatteryPolicy {
+            cap_below: Some(101),
+            ..policy
+        }
+        .validate()
+        .is_err());
+        assert!(BatteryPolicy {
+            cap_power: 201,
+            ..policy
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn test_sample_limit() {
+        let start = Instant::now();
+        let mut history = BatteryHistory::new(start);
+        for i in 0..MAX_BATTERY_SAMPLES + 10 {
+            let _ = history.record((i % 2) as u8, start + Duration::from_secs(i as u64));
+        }
+        assert_eq!(history.samples().len(), MAX_BATTERY_SAMPLES);
+        assert_eq!(history.samples()[0].elapsed_ms, 10_000);
+    }
+}
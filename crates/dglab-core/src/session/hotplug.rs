@@ -0,0 +1,65 @@
+//! 设备热插拔
+//!
+//! 把 BLE 适配器事件转换为会话事件：会话中的设备断开时清理连接状态，
+//! 重新出现时自动重连。
+
+use std::sync::Arc;
+
+use dglab_protocol::ble::AdapterEvent;
+use tokio::sync::{broadcast, mpsc, RwLock};
+use tracing::{info, warn};
+
+use super::manager::{DeviceMap, SessionEvent};
+use crate::device::DeviceState;
+
+/// 处理适配器事件，直到事件通道关闭
+pub(super) async fn run(
+    mut events: mpsc::Receiver<AdapterEvent>,
+    devices: Arc<RwLock<DeviceMap>>,
+    event_tx: broadcast::Sender<SessionEvent>,
+) {
+    while let Some(event) = events.recv().await {
+        match event {
+            AdapterEvent::Discovered(result) => {
+                let _ = event_tx.send(SessionEvent::DeviceDiscovered(
+                    result.id.clone(),
+                    result.name.clone(),
+                ));
+
+                let Some(device) = devices.read().await.get(&result.id).cloned() else {
+                    continue;
+                };
+                let mut dev = device.write().await;
+                if !matches!(dev.state(), DeviceState::Disconnected | DeviceState::Error) {
+                    continue;
+                }
+
+                info!("Device {} reappeared, reconnecting", result.id);
+                if let Err(e) = dev.connect().await {
+                    warn!("Failed to reconnect {}: {}", result.id, e);
+                    let _ = event_tx.send(SessionEvent::Error(format!(
+                        "Failed to reconnect {}: {}",
+                        result.id, e
+                    )));
+                }
+            }
+            AdapterEvent::Disconnected(device_id) => {
+                let _ = event_tx.send(SessionEvent::DeviceLost(device_id.clone()));
+
+                let Some(device) = devices.read().await.get(&device_id).cloned() else {
+                    continue;
+                };
+                let mut dev = device.write().await;
+                if dev.state() == DeviceState::Disconnected {
+                    continue;
+                }
+
+                // 链路已断开，只清理本地状态，断开失败不影响后续重连
+                warn!("Device {} lost, waiting for it to reappear", device_id);
+                if let Err(e) = dev.disconnect().await {
+                    warn!("Failed to clean up {}: {}", device_id, e);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,449 @@
+//! 会话事件日志
+//!
+//! 每个会话对应数据目录下一个只追加的 JSONL 文件，记录设备连接、强度变化、紧急停止和错误，
+//! 用于事后安全审计。文件在第一条记录写入时创建；每条记录单独一行、写入后立即刷新，
+//! 紧急停止和错误额外同步到磁盘。在 tokio 运行时中记录时，写入放到 `spawn_blocking`
+//! 中按顺序进行，不阻塞异步任务。读取时跳过无法解析的行（如崩溃时写了一半的最后一行）。
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::device::DeviceState;
+use crate::error::{CoreError, Result};
+
+/// 日志文件扩展名
+const LOG_EXTENSION: &str = "jsonl";
+
+/// 日志事件
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum LogEvent {
+    /// 设备加入会话
+    DeviceAdded,
+    /// 设备移出会话
+    DeviceRemoved,
+    /// 设备状态变更
+    StateChanged {
+        /// 新状态
+        state: DeviceState,
+    },
+    /// 通道强度变更
+    PowerChanged {
+        /// 通道编号 (0=A, 1=B)
+        channel: u8,
+        /// 强度值
+        power: u8,
+    },
+    /// 紧急停止
+    EmergencyStop,
+    /// 会话定时到时
+    TimerExpired,
+    /// 错误
+    Error {
+        /// 错误信息
+        message: String,
+    },
+}
+
+impl LogEvent {
+    /// 是否为需要立即同步到磁盘的安全相关事件
+    fn is_critical(&self) -> bool {
+        matches!(
+            self,
+            Self::EmergencyStop | Self::TimerExpired | Self::Error { .. }
+        )
+    }
+}
+
+impl fmt::Display for LogEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DeviceAdded => write!(f, "device added"),
+            Self::DeviceRemoved => write!(f, "device removed"),
+            Self::StateChanged { state } => write!(f, "state {:?}", state),
+            Self::PowerChanged { channel, power } => {
+                let channel = if *channel == 0 { "A" } else { "B" };
+                write!(f, "power {}={}", channel, power)
+            }
+            Self::EmergencyStop => write!(f, "EMERGENCY STOP"),
+            Self::TimerExpired => write!(f, "session timer expired"),
+            Self::Error { message } => write!(f, "error: {}", message),
+        }
+    }
+}
+
+/// 日志记录
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogEntry {
+    /// 时间
+    pub timestamp: DateTime<Utc>,
+    /// 会话 ID
+    pub session_id: String,
+    /// 设备 ID（会话级事件为空）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device_id: Option<String>,
+    /// 事件
+    #[serde(flatten)]
+    pub event: LogEvent,
+}
+
+/// 日志查询条件
+#[derive(Debug, Clone, Default)]
+pub struct LogFilter {
+    /// 只保留该设备的记录
+    pub device_id: Option<String>,
+    /// 只保留该时间之后的记录
+    pub since: Option<DateTime<Utc>>,
+    /// 只保留最后 N 条
+    pub limit: Option<usize>,
+}
+
+impl LogFilter {
+    /// 记录是否满足条件（不含数量限制）
+    fn matches(&self, entry: &LogEntry) -> bool {
+        self.device_id
+            .as_deref()
+            .map_or(true, |id| entry.device_id.as_deref() == Some(id))
+            && self.since.map_or(true, |since| entry.timestamp >= since)
+    }
+}
+
+/// 会话事件日志
+pub struct EventLog {
+    /// 会话 ID
+    session_id: String,
+    /// 写入端（写入任务共享）
+    writer: Arc<LogWriter>,
+}
+
+/// 待写入的记录
+#[derive(Default)]
+struct LogQueue {
+    /// 按记录顺序排列的条目
+    entries: VecDeque<LogEntry>,
+    /// 是否已有写入任务在处理队列
+    draining: bool,
+}
+
+/// 日志文件写入端
+struct LogWriter {
+    /// 日志文件路径
+    path: PathBuf,
+    /// 日志文件（首次写入时创建）
+    file: Mutex<Option<File>>,
+    /// 待写入的记录
+    queue: Mutex<LogQueue>,
+}
+
+impl EventLog {
+    /// 为会话创建日志，文件名为 `{创建时间}-{会话 ID 前 8 位}.jsonl`
+    pub fn new(dir: &Path, session_id: &str) -> Self {
+        let short_id: String = session_id.chars().take(8).collect();
+        let name = format!(
+            "{}-{}.{}",
+            Utc::now().format("%Y%m%d-%H%M%S"),
+            short_id,
+            LOG_EXTENSION
+        );
+
+        Self {
+            session_id: session_id.to_string(),
+            writer: Arc::new(LogWriter {
+                path: dir.join(name),
+                file: Mutex::new(None),
+                queue: Mutex::new(LogQueue::default()),
+            }),
+        }
+    }
+
+    /// 获取默认日志目录
+    pub fn default_dir() -> Result<PathBuf> {
+        let dir = dirs::data_dir()
+            .ok_or_else(|| CoreError::Other("Could not find data directory".to_string()))?
+            .join("dglab")
+            .join("logs");
+
+        Ok(dir)
+    }
+
+    /// 获取日志文件路径
+    pub fn path(&self) -> &Path {
+        &self.writer.path
+    }
+
+    /// 追加一条记录（写入失败只记录警告，不影响调用方）
+    ///
+    /// 在 tokio 运行时中由 `spawn_blocking` 任务写入，否则在当前线程写入。
+    pub fn record(&self, device_id: Option<&str>, event: LogEvent) {
+        let entry = LogEntry {
+            timestamp: Utc::now(),
+            session_id: self.session_id.clone(),
+            device_id: device_id.map(str::to_string),
+            event,
+        };
+
+        let mut queue = self.writer.lock_queue();
+        queue.entries.push_back(entry);
+        if queue.draining {
+            return;
+        }
+        queue.draining = true;
+        drop(queue);
+
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                let writer = self.writer.clone();
+                let _ = handle.spawn_blocking(move || writer.drain());
+            }
+            Err(_) => self.writer.drain(),
+        }
+    }
+
+    /// 列出目录下的日志文件（按创建时间从旧到新）
+    pub fn list(dir: &Path) -> Result<Vec<PathBuf>> {
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut paths = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == LOG_EXTENSION) {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+
+        Ok(paths)
+    }
+
+    /// 读取日志文件中满足条件的记录
+    pub fn read(path: &Path, filter: &LogFilter) -> Result<Vec<LogEntry>> {
+        let reader = BufReader::new(File::open(path)?);
+        let mut entries = Vec::new();
+
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<LogEntry>(&line) {
+                Ok(entry) if filter.matches(&entry) => entries.push(entry),
+                Ok(_) => {}
+                Err(e) => warn!("Skipping malformed line {} in {:?}: {}", index + 1, path, e),
+            }
+        }
+
+        if let Some(limit) = filter.limit {
+            let skip = entries.len().saturating_sub(limit);
+            let _ = entries.drain(..skip);
+        }
+
+        Ok(entries)
+    }
+}
+
+impl LogWriter {
+    /// 锁定待写入队列
+    fn lock_queue(&self) -> MutexGuard<'_, LogQueue> {
+        self.queue
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// 按顺序写入队列中的记录，直到队列为空
+    fn drain(&self) {
+        loop {
+            let entry = {
+                let mut queue = self.lock_queue();
+                match queue.entries.pop_front() {
+                    Some(entry) => entry,
+                    None => {
+                        queue.draining = false;
+                        return;
+                    }
+                }
+            };
+            if let Err(e) = self.append(&entry) {
+                warn!("Failed to write event log {:?}: {}", self.path, e);
+            }
+        }
+    }
+
+    /// 写入一行并刷新
+    fn append(&self, entry: &LogEntry) -> Result<()> {
+        let mut line = serde_json::to_string(entry)?;
+        line.push('\n');
+
+        let mut file = self
+            .file
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if file.is_none() {
+            if let Some(dir) = self.path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            *file = Some(
+                OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&self.path)?,
+            );
+        }
+
+        let Some(file) = file.as_mut() else {
+            return Ok(());
+        };
+        file.write_all(line.as_bytes())?;
+        file.flush()?;
+        if entry.event.is_critical() {
+            file.sync_data()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_read() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = EventLog::new(dir.path(), "0123456789abcdef");
+        assert!(!log.path().exists());
+        assert!(log
+            .path()
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .ends_with("-01234567.jsonl"));
+
+        log.record(Some("dev-1"), LogEvent::DeviceAdded);
+        log.record(
+            Some("dev-1"),
+            LogEvent::PowerChanged {
+                channel: 0,
+                power: 20,
+            },
+        );
+        log.record(Some("dev-2"), LogEvent::EmergencyStop);
+        log.record(None, LogEvent::TimerExpired);
+
+        let entries = EventLog::read(log.path(), &LogFilter::default()).unwrap();
+        assert_eq!(entries.len(), 4);
+        assert_eq!(entries[0].session_id, "0123456789abcdef");
+        assert_eq!(entries[2].event, LogEvent::EmergencyStop);
+        assert_eq!(entries[3].device_id, None);
+
+        let filter = LogFilter {
+            device_id: Some("dev-1".to_string()),
+            limit: Some(1),
+            ..Default::default()
+        };
+        let entries = EventLog::read(log.path(), &filter).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(
+            entries[0].event,
+            LogEvent::PowerChanged {
+                channel: 0,
+                power: 20
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_record_in_runtime_keeps_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = EventLog::new(dir.path(), "session");
+        for power in 0..20 {
+            log.record(None, LogEvent::PowerChanged { channel: 0, power });
+        }
+
+        // 写入在 spawn_blocking 任务中进行
+        let mut entries = Vec::new();
+        for _ in 0..50 {
+            if log.path().exists() {
+                entries = EventLog::read(log.path(), &LogFilter::default()).unwrap();
+                if entries.len() == 20 {
+                    break;
+                }
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        let powers: Vec<u8> = entries
+            .iter()
+            .filter_map(|entry| match entry.event {
+                LogEvent::PowerChanged { power, .. } => Some(power),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(powers, (0..20).collect::<Vec<u8>>());
+    }
+
+    #[test]
+    fn test_read_skips_truncated_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = EventLog::new(dir.path(), "session");
+        log.record(None, LogEvent::DeviceAdded);
+
+        // 模拟崩溃时写了一半的记录
+        let mut file = OpenOptions::new().append(true).open(log.path()).unwrap();
+        file.write_all(br#"{"timestamp":"2026-01-01T00:00"#)
+            .unwrap();
+
+        let entries = EventLog::read(log.path(), &LogFilter::default()).unwrap();
+        assert_eq!(entries.len(), 1);
+    }
+
+    #[test]
+    fn test_list() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(EventLog::list(&dir.path().join("missing"))
+            .unwrap()
+            .is_empty());
+
+        for name in [
+            "20260102-000000-b.jsonl",
+            "20260101-000000-a.jsonl",
+            "notes.txt",
+        ] {
+            fs::write(dir.path().join(name), "").unwrap();
+        }
+        let names: Vec<_> = EventLog::list(dir.path())
+            .unwrap()
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(
+            names,
+            vec!["20260101-000000-a.jsonl", "20260102-000000-b.jsonl"]
+        );
+    }
+
+    #[test]
+    fn test_entry_format() {
+        let entry = LogEntry {
+            timestamp: DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z")
+                .unwrap()
+                .with_timezone(&Utc),
+            session_id: "s".to_string(),
+            device_id: None,
+            event: LogEvent::StateChanged {
+                state: DeviceState::Connected,
+            },
+        };
+        let json = serde_json::to_string(&entry).unwrap();
+        assert!(json.contains(r#""event":"state_changed""#));
+        assert!(!json.contains("device_id"));
+        assert_eq!(serde_json::from_str::<LogEntry>(&json).unwrap(), entry);
+    }
+}
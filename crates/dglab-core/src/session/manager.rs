@@ -1,21 +1,83 @@
 //! 会话管理器
 
+use std::any::Any;
 use std::collections::HashMap;
-use std::sync::Arc;
-
-use tokio::sync::{broadcast, RwLock};
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use dglab_protocol::ble::AdapterEvent;
+use dglab_protocol::wifi::FeedbackButton;
+use futures::future::BoxFuture;
+use serde::Serialize;
+use tokio::sync::{broadcast, mpsc, RwLock};
+use tokio::task::JoinHandle;
 use tracing::{debug, info, warn};
 
-use crate::device::{Device, DeviceEvent, DeviceState};
+use super::battery::BatteryAction;
+use super::log::{EventLog, LogEvent};
+use super::snapshot::{DeviceSnapshot, SessionSnapshot, SnapshotTransport};
+use super::stats::{DeviceStats, StatsCollector};
+use super::{hotplug, timer};
+use crate::config::{AppConfig, SafetyConfig};
+use crate::device::traits::{DeviceInfo, WaveformConfig};
+use crate::device::{
+    ramp_power_with, set_power_linked, sync_linked_power, ChannelLink, CoyoteDevice, Device,
+    DeviceEvent, DeviceState, Easing, PowerCurve, RampSpec, WifiBinding, WsCoyoteDevice,
+};
 use crate::error::{CoreError, Result};
+use crate::metrics;
+use crate::preset::{Preset, PresetChannelConfig};
+use crate::waveform::{Waveform, WaveformLibrary};
 
 /// 设备包装类型
 type DeviceBox = Box<dyn Device>;
 /// 设备映射
-type DeviceMap = HashMap<String, Arc<RwLock<DeviceBox>>>;
+pub(super) type DeviceMap = HashMap<String, Arc<RwLock<DeviceBox>>>;
+/// 强度渐变任务映射（设备 ID, 通道）→ 任务句柄
+pub(super) type RampMap = HashMap<(String, u8), JoinHandle<()>>;
+
+/// 波形映射（设备 ID, 通道）→ 最近设置的波形
+type WaveformMap = HashMap<(String, u8), WaveformConfig>;
+
+/// 设备的低电量限制状态
+#[derive(Debug, Clone, Copy, Default)]
+struct BatteryLimit {
+    /// 按当前电量和策略得到的限制
+    action: BatteryAction,
+    /// 用户忽略的限制，限制变得更严格后失效
+    overridden: Option<BatteryAction>,
+}
+
+impl BatteryLimit {
+    /// 更新按电量得到的限制，比用户忽略的限制更严格时取消忽略
+    ///
+    /// 返回限制是否变化。
+    fn update(&mut self, action: BatteryAction) -> bool {
+        let changed = self.action != action;
+        self.action = action;
+        if self
+            .overridden
+            .is_some_and(|overridden| action.is_stricter_than(overridden))
+        {
+            self.overridden = None;
+        }
+        changed
+    }
+
+    /// 生效的限制
+    fn effective(&self) -> BatteryAction {
+        match self.overridden {
+            Some(overridden) if !self.action.is_stricter_than(overridden) => BatteryAction::None,
+            _ => self.action,
+        }
+    }
+}
 
 /// 会话事件
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
 pub enum SessionEvent {
     /// 设备已添加
     DeviceAdded(String),
@@ -23,17 +85,55 @@ pub enum SessionEvent {
     DeviceRemoved(String),
     /// 设备连接状态变更
     DeviceStateChanged(String, DeviceState),
+    /// 适配器发现 DG-LAB 设备（设备 ID, 名称），可能不在会话中
+    DeviceDiscovered(String, String),
+    /// 适配器报告设备断开（设备 ID），会话中的设备重新出现时自动重连
+    DeviceLost(String),
+    /// 设备收到 APP 反馈按钮
+    Feedback(String, FeedbackButton),
+    /// 设备 BLE 信号强度（RSSI，dBm）
+    LinkQuality(String, i16),
+    /// 设备弱信号状态变化（true 表示低于阈值，false 表示已恢复）
+    WeakSignal(String, bool),
+    /// 设备电量更新（设备 ID, 电量百分比, 按放电速率估算的剩余使用时间）
+    Battery(
+        String,
+        u8,
+        #[cfg_attr(feature = "ts", ts(type = "{ secs: number, nanos: number } | null"))]
+        Option<Duration>,
+    ),
+    /// 设备强度上限变更（设备 ID, A 通道上限, B 通道上限），如 APP 调整了上限
+    MaxPowerChanged(String, u8, u8),
+    /// 设备低电量限制变化（设备 ID, 按电量和策略得到的限制），用户忽略限制时同样发送
+    BatteryLimited(String, BatteryAction),
+    /// 设备已紧急停止
+    EmergencyStop(String),
+    /// 会话即将到时（剩余时长）
+    TimerWarning(
+        #[cfg_attr(feature = "ts", ts(type = "{ secs: number, nanos: number }"))] Duration,
+    ),
+    /// 会话已到时，所有设备已归零并停止
+    TimerExpired,
     /// 会话错误
     Error(String),
 }
 
+/// 批量操作中每个设备的默认超时
+pub const DEFAULT_BULK_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// 批量操作结果（设备 ID → 该设备的结果）
+pub type BulkResults = HashMap<String, Result<()>>;
+
+/// 批量操作中对单个设备执行的操作
+type DeviceOp = for<'a> fn(&'a mut dyn Device) -> BoxFuture<'a, Result<()>>;
+
 /// 会话信息
 #[derive(Debug, Clone)]
 pub struct SessionInfo {
     /// 会话 ID
     pub id: String,
     /// 会话创建时间
-    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub created_at: DateTime<Utc>,
     /// 活动设备数量
     pub active_devices: usize,
     /// 总设备数量
@@ -49,7 +149,27 @@ pub struct SessionManager {
     /// 事件发送器
     event_tx: broadcast::Sender<SessionEvent>,
     /// 创建时间
-    created_at: chrono::DateTime<chrono::Utc>,
+    created_at: DateTime<Utc>,
+    /// 波形库（解析预设中引用的波形名称）
+    waveform_library: RwLock<WaveformLibrary>,
+    /// 进行中的强度渐变
+    ramps: Arc<Mutex<RampMap>>,
+    /// 各设备通道最近设置的波形（会话快照使用）
+    waveforms: Mutex<WaveformMap>,
+    /// 会话定时（停止时间，任务句柄）
+    timer: Mutex<Option<(DateTime<Utc>, JoinHandle<()>)>>,
+    /// 事件日志
+    event_log: Option<Arc<EventLog>>,
+    /// 会话统计
+    stats: Arc<StatsCollector>,
+    /// 强度校准曲线（设备 ID 或 BLE 名称 → 曲线）
+    calibrations: Mutex<HashMap<String, PowerCurve>>,
+    /// 安全限制（原始强度上限）
+    safety: Arc<Mutex<SafetyConfig>>,
+    /// 低电量限制（设备 ID → 限制状态）
+    battery_limits: Arc<Mutex<HashMap<String, BatteryLimit>>>,
+    /// 批量操作中每个设备的超时
+    bulk_timeout: Duration,
 }
 
 impl SessionManager {
@@ -61,10 +181,49 @@ impl SessionManager {
             session_id: uuid::Uuid::new_v4().to_string(),
             devices: Arc::new(RwLock::new(HashMap::new())),
             event_tx,
-            created_at: chrono::Utc::now(),
+            created_at: Utc::now(),
+            waveform_library: RwLock::new(WaveformLibrary::builtin()),
+            ramps: Arc::new(Mutex::new(HashMap::new())),
+            waveforms: Mutex::new(HashMap::new()),
+            timer: Mutex::new(None),
+            event_log: None,
+            stats: Arc::new(StatsCollector::new()),
+            calibrations: Mutex::new(HashMap::new()),
+            safety: Arc::new(Mutex::new(SafetyConfig::default())),
+            battery_limits: Arc::new(Mutex::new(HashMap::new())),
+            bulk_timeout: DEFAULT_BULK_TIMEOUT,
+        }
+    }
+
+    /// 创建会话管理器，并把事件日志写入 `log_dir` 下的会话日志文件
+    pub fn with_event_log(log_dir: &Path) -> Self {
+        let mut manager = Self::new();
+        manager.event_log = Some(Arc::new(EventLog::new(log_dir, &manager.session_id)));
+        manager
+    }
+
+    /// 获取事件日志
+    pub fn event_log(&self) -> Option<&EventLog> {
+        self.event_log.as_deref()
+    }
+
+    /// 写入事件日志（未启用时忽略）
+    fn record(&self, device_id: Option<&str>, event: LogEvent) {
+        if let Some(log) = &self.event_log {
+            log.record(device_id, event);
         }
     }
 
+    /// 获取所有设备的会话统计（包括已移出会话的设备）
+    pub fn stats(&self) -> Vec<DeviceStats> {
+        self.stats.all()
+    }
+
+    /// 获取单个设备的会话统计
+    pub fn device_stats(&self, device_id: &str) -> Option<DeviceStats> {
+        self.stats.get(device_id)
+    }
+
     /// 获取会话 ID
     pub fn session_id(&self) -> &str {
         &self.session_id
@@ -105,20 +264,137 @@ impl SessionManager {
         // 订阅设备事件
         let mut events = device.subscribe_events();
         let event_tx = self.event_tx.clone();
+        let event_log = self.event_log.clone();
+        let stats = self.stats.clone();
+        let safety = self.safety.clone();
+        let battery_limits = self.battery_limits.clone();
+        let ramps = self.ramps.clone();
         let device_id_clone = device_id.clone();
+        self.stats
+            .track(&device_id, device.get_power(0), device.get_power(1));
+        let device = Arc::new(RwLock::new(device));
+        // 事件任务不持有设备，设备移出会话后事件通道随之关闭
+        let weak_device = Arc::downgrade(&device);
 
         tokio::spawn(async move {
             while let Ok(event) = events.recv().await {
-                if let DeviceEvent::StateChanged(state) = event {
-                    let _ = event_tx.send(SessionEvent::DeviceStateChanged(
-                        device_id_clone.clone(),
-                        state,
-                    ));
+                if let DeviceEvent::PowerChanged { channel, power } = &event {
+                    stats.record_power(&device_id_clone, *channel, *power);
+                }
+                if let Some(log) = &event_log {
+                    let entry = match &event {
+                        DeviceEvent::StateChanged(state) => {
+                            Some(LogEvent::StateChanged { state: *state })
+                        }
+                        DeviceEvent::PowerChanged { channel, power } => {
+                            Some(LogEvent::PowerChanged {
+                                channel: *channel,
+                                power: *power,
+                            })
+                        }
+                        DeviceEvent::Error(message) => Some(LogEvent::Error {
+                            message: message.clone(),
+                        }),
+                        _ => None,
+                    };
+                    if let Some(entry) = entry {
+                        log.record(Some(&device_id_clone), entry);
+                    }
+                }
+
+                match event {
+                    DeviceEvent::StateChanged(state) => {
+                        let _ = event_tx.send(SessionEvent::DeviceStateChanged(
+                            device_id_clone.clone(),
+                            state,
+                        ));
+                    }
+                    DeviceEvent::Feedback(button) => {
+                        let _ =
+                            event_tx.send(SessionEvent::Feedback(device_id_clone.clone(), button));
+                    }
+                    DeviceEvent::LinkQuality(rssi) => {
+                        let _ =
+                            event_tx.send(SessionEvent::LinkQuality(device_id_clone.clone(), rssi));
+                    }
+                    DeviceEvent::WeakSignal { weak, .. } => {
+                        let _ =
+                            event_tx.send(SessionEvent::WeakSignal(device_id_clone.clone(), weak));
+                    }
+                    DeviceEvent::BatteryUpdated(level) => {
+                        let remaining = stats.record_battery(&device_id_clone, level);
+                        let _ = event_tx.send(SessionEvent::Battery(
+                            device_id_clone.clone(),
+                            level,
+                            remaining,
+                        ));
+
+                        let action = safety
+                            .lock()
+                            .unwrap_or_else(|poisoned| poisoned.into_inner())
+                            .battery
+                            .action(level);
+                        let (changed, effective) = {
+                            let mut limits = battery_limits
+                                .lock()
+                                .unwrap_or_else(|poisoned| poisoned.into_inner());
+                            let limit = limits.entry(device_id_clone.clone()).or_default();
+                            (limit.update(action), limit.effective())
+                        };
+                        if !changed {
+                            continue;
+                        }
+                        info!(
+                            "Battery {}% on {}, low battery limit: {}",
+                            level, device_id_clone, action
+                        );
+                        let Some(device) = weak_device.upgrade() else {
+                            continue;
+                        };
+                        if let Some(limit) = effective.limit() {
+                            if let Err(e) = Self::enforce_battery_limit(
+                                &device,
+                                &ramps,
+                                &device_id_clone,
+                                limit,
+                            )
+                            .await
+                            {
+                                warn!(
+                                    "Failed to apply low battery limit on {}: {}",
+                                    device_id_clone, e
+                                );
+                            }
+                        }
+                        // 通过设备事件通知订阅方，会话事件在下面转发
+                        device
+                            .read()
+                            .await
+                            .send_event(DeviceEvent::BatteryLimited(action));
+                    }
+                    DeviceEvent::BatteryLimited(action) => {
+                        let _ = event_tx.send(SessionEvent::BatteryLimited(
+                            device_id_clone.clone(),
+                            action,
+                        ));
+                    }
+                    DeviceEvent::MaxPowerChanged {
+                        max_power_a,
+                        max_power_b,
+                    } => {
+                        let _ = event_tx.send(SessionEvent::MaxPowerChanged(
+                            device_id_clone.clone(),
+                            max_power_a,
+                            max_power_b,
+                        ));
+                    }
+                    _ => {}
                 }
             }
         });
 
-        devices.insert(device_id.clone(), Arc::new(RwLock::new(device)));
+        devices.insert(device_id.clone(), device);
+        self.record(Some(&device_id), LogEvent::DeviceAdded);
         let _ = self.event_tx.send(SessionEvent::DeviceAdded(device_id));
 
         Ok(())
@@ -128,11 +404,19 @@ impl SessionManager {
     pub async fn remove_device(&self, device_id: &str) -> Result<()> {
         info!("Removing device: {}", device_id);
 
+        for channel in 0..2 {
+            let _ = self.cancel_ramp(device_id, channel);
+            let _ = self.waveforms().remove(&(device_id.to_string(), channel));
+        }
+        let _ = self.battery_limits().remove(device_id);
+
         let mut devices = self.devices.write().await;
 
         if let Some(device) = devices.remove(device_id) {
             let mut dev = device.write().await;
             let _ = dev.disconnect().await;
+            self.stats.untrack(device_id);
+            self.record(Some(device_id), LogEvent::DeviceRemoved);
         }
 
         let _ = self
@@ -148,80 +432,977 @@ impl SessionManager {
         devices.get(device_id).cloned()
     }
 
+    /// 获取设备信息，附带按会话电量历史估算的剩余使用时间
+    pub async fn device_info(&self, device_id: &str) -> Option<DeviceInfo> {
+        let device = self.get_device(device_id).await?;
+        let mut info = device.read().await.info();
+        info.battery_remaining_secs = self.stats.battery_remaining(device_id).map(|d| d.as_secs());
+        Some(info)
+    }
+
+    /// 获取 WiFi 设备或桥接设备的二维码 URL（连接服务器后可用）
+    ///
+    /// 设备不通过 WebSocket 服务器绑定时返回 [`CoreError::InvalidParameter`]。
+    pub async fn qr_url(&self, device_id: &str) -> Result<Option<String>> {
+        let device = self.require_device(device_id).await?;
+        let dev = device.read().await;
+        Ok(Self::wifi_binding(&**dev)?.qr_url().await)
+    }
+
+    /// WiFi 设备或桥接设备是否已与 APP 或控制器绑定
+    ///
+    /// 设备不通过 WebSocket 服务器绑定时返回 [`CoreError::InvalidParameter`]。
+    pub async fn is_bound(&self, device_id: &str) -> Result<bool> {
+        let device = self.require_device(device_id).await?;
+        let dev = device.read().await;
+        Ok(Self::wifi_binding(&**dev)?.is_bound().await)
+    }
+
+    /// 获取设备的 WiFi 绑定能力
+    fn wifi_binding(dev: &dyn Device) -> Result<&dyn WifiBinding> {
+        dev.wifi_binding().ok_or_else(|| {
+            CoreError::InvalidParameter(format!(
+                "Device {} is not bound through a WebSocket server",
+                dev.id()
+            ))
+        })
+    }
+
     /// 获取所有设备 ID
     pub async fn list_devices(&self) -> Vec<String> {
         let devices = self.devices.read().await;
         devices.keys().cloned().collect()
     }
 
-    /// 连接所有设备
-    pub async fn connect_all(&self) -> Result<()> {
+    /// 并发连接所有设备，返回每个设备的结果
+    pub async fn connect_all(&self) -> BulkResults {
         info!("Connecting all devices");
+        self.for_each_device("connect", |dev| dev.connect()).await
+    }
 
-        let devices = self.devices.read().await;
+    /// 并发断开所有设备，返回每个设备的结果
+    pub async fn disconnect_all(&self) -> BulkResults {
+        info!("Disconnecting all devices");
+        self.for_each_device("disconnect", |dev| dev.disconnect())
+            .await
+    }
 
-        for (id, device) in devices.iter() {
-            debug!("Connecting device: {}", id);
-            let mut dev = device.write().await;
-            if let Err(e) = dev.connect().await {
-                warn!("Failed to connect device {}: {}", id, e);
+    /// 并发启动所有设备，返回每个设备的结果
+    pub async fn start_all(&self) -> BulkResults {
+        info!("Starting all devices");
+        self.for_each_device("start", |dev| dev.start()).await
+    }
+
+    /// 并发停止所有设备，返回每个设备的结果
+    pub async fn stop_all(&self) -> BulkResults {
+        info!("Stopping all devices");
+        self.for_each_device("stop", |dev| dev.stop()).await
+    }
+
+    /// 对每个设备并发执行 `op`
+    ///
+    /// 每个设备（含等待设备锁）最多 [`Self::bulk_timeout`]，超时记为 [`CoreError::Timeout`]，
+    /// 一个设备卡住不影响其他设备。
+    async fn for_each_device(&self, name: &str, op: DeviceOp) -> BulkResults {
+        let devices: Vec<(String, Arc<RwLock<DeviceBox>>)> = self
+            .devices
+            .read()
+            .await
+            .iter()
+            .map(|(id, device)| (id.clone(), device.clone()))
+            .collect();
+
+        let timeout = self.bulk_timeout;
+        let tasks = devices.into_iter().map(|(id, device)| async move {
+            debug!("{} device: {}", name, id);
+            let result = tokio::time::timeout(timeout, async {
+                let mut dev = device.write().await;
+                op(&mut **dev).await
+            })
+            .await
+            .unwrap_or_else(|_| {
+                Err(CoreError::Timeout(format!(
+                    "{} {} after {:?}",
+                    name, id, timeout
+                )))
+            });
+            if let Err(e) = &result {
+                warn!("Failed to {} device {}: {}", name, id, e);
+            }
+            (id, result)
+        });
+
+        futures::future::join_all(tasks).await.into_iter().collect()
+    }
+
+    /// 批量操作中每个设备的超时
+    pub fn bulk_timeout(&self) -> Duration {
+        self.bulk_timeout
+    }
+
+    /// 设置批量操作中每个设备的超时
+    pub fn set_bulk_timeout(&mut self, timeout: Duration) {
+        self.bulk_timeout = timeout;
+    }
+
+    /// 紧急停止：取消进行中的渐变，两个通道归零并停止输出
+    pub async fn emergency_stop(&self, device_id: &str) -> Result<()> {
+        warn!("Emergency stop for device {}", device_id);
+        for channel in 0..2 {
+            let _ = self.cancel_ramp(device_id, channel);
+        }
+
+        let device = self.require_device(device_id).await?;
+        self.record(Some(device_id), LogEvent::EmergencyStop);
+        self.stats.record_emergency_stop(device_id);
+        metrics::device(device_id).record_emergency_stop();
+        let _ = self
+            .event_tx
+            .send(SessionEvent::EmergencyStop(device_id.to_string()));
+
+        let mut dev = device.write().await;
+        for channel in 0..2 {
+            if let Err(e) = dev.set_power(channel, 0).await {
+                warn!("Failed to zero channel {} of {}: {}", channel, device_id, e);
+            }
+        }
+        dev.stop().await
+    }
+
+    /// 设置设备的强度校准曲线（`None` 清除）
+    ///
+    /// `device` 为设备 ID 或 BLE 名称。校准后 [`Self::set_power`] 和 [`Self::start_ramp`]
+    /// 的强度参数为逻辑百分比 0~100。
+    pub fn set_calibration(&self, device: &str, curve: Option<PowerCurve>) {
+        let mut calibrations = self.calibrations();
+        match curve {
+            Some(curve) => {
+                info!("Power calibration for {}: {}", device, curve);
+                let _ = calibrations.insert(device.to_string(), curve);
+            }
+            None => {
+                let _ = calibrations.remove(device);
+            }
+        }
+    }
+
+    /// 从配置文件的常用设备加载全部校准曲线（替换现有校准）
+    pub fn load_calibrations(&self, config: &AppConfig) {
+        let mut calibrations = self.calibrations();
+        calibrations.clear();
+        calibrations.extend(config.favorite_devices.iter().filter_map(|favorite| {
+            favorite
+                .calibration
+                .clone()
+                .map(|curve| (favorite.id.clone(), curve))
+        }));
+    }
+
+    /// 设备的强度校准曲线（按设备 ID 或名称匹配）
+    pub fn calibration(&self, device_id: &str, name: &str) -> Option<PowerCurve> {
+        let calibrations = self.calibrations();
+        calibrations
+            .get(device_id)
+            .or_else(|| calibrations.get(name))
+            .cloned()
+    }
+
+    /// 加载配置文件中的强度校准和安全限制
+    pub fn load_config(&self, config: &AppConfig) {
+        self.load_calibrations(config);
+        self.set_safety(config.safety);
+    }
+
+    /// 设置安全限制，之后经会话设置的强度和渐变目标都不会超过该上限
+    pub fn set_safety(&self, safety: SafetyConfig) {
+        *self
+            .safety
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = safety;
+    }
+
+    /// 当前安全限制
+    pub fn safety(&self) -> SafetyConfig {
+        *self
+            .safety
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// 获取低电量限制表（锁中毒时继续使用内部数据）
+    fn battery_limits(&self) -> MutexGuard<'_, HashMap<String, BatteryLimit>> {
+        self.battery_limits
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// 设备当前生效的低电量限制（用户已忽略时为 [`BatteryAction::None`]）
+    pub fn battery_limit(&self, device_id: &str) -> BatteryAction {
+        self.battery_limits()
+            .get(device_id)
+            .map(BatteryLimit::effective)
+            .unwrap_or_default()
+    }
+
+    /// 忽略或恢复设备的低电量限制
+    ///
+    /// 忽略当前的限制后不再按电量限制强度，直到恢复、限制变得更严格（如从限制上限变为暂停）
+    /// 或设备移出会话；恢复时立即按当前电量执行限制。
+    pub async fn set_battery_override(&self, device_id: &str, overridden: bool) -> Result<()> {
+        let device = self.require_device(device_id).await?;
+        let limit = {
+            let mut limits = self.battery_limits();
+            let limit = limits.entry(device_id.to_string()).or_default();
+            limit.overridden = overridden.then_some(limit.action);
+            limit.effective().limit()
+        };
+        info!(
+            "Low battery limit on {} {}",
+            device_id,
+            if overridden { "overridden" } else { "restored" }
+        );
+
+        match limit {
+            Some(limit) => {
+                Self::enforce_battery_limit(&device, &self.ramps, device_id, limit).await
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// 执行低电量限制：取消进行中的渐变，把超过上限的通道强度降到上限
+    async fn enforce_battery_limit(
+        device: &RwLock<DeviceBox>,
+        ramps: &Mutex<RampMap>,
+        device_id: &str,
+        limit: u8,
+    ) -> Result<()> {
+        {
+            let mut ramps = ramps
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            for channel in 0..2 {
+                if let Some(task) = ramps.remove(&(device_id.to_string(), channel)) {
+                    task.abort();
+                }
             }
         }
 
+        let mut dev = device.write().await;
+        for channel in 0..2 {
+            if dev.get_power(channel) > limit {
+                warn!(
+                    "Lowering {} channel {} to {} for low battery",
+                    device_id, channel, limit
+                );
+                dev.set_power(channel, limit).await?;
+            }
+        }
         Ok(())
     }
 
-    /// 断开所有设备
-    pub async fn disconnect_all(&self) -> Result<()> {
-        info!("Disconnecting all devices");
+    /// 按安全限制和低电量限制截断原始强度
+    fn limit_power(&self, device_id: &str, channel: u8, raw: u8) -> u8 {
+        let safety = self.safety();
+        let limit = match channel {
+            0 => safety.max_power_a,
+            _ => safety.max_power_b,
+        };
+        if raw > limit {
+            warn!(
+                "Power {} on {} channel {} limited to {} by safety config",
+                raw, device_id, channel, limit
+            );
+        }
+        let raw = raw.min(limit);
+
+        match self.battery_limit(device_id).limit() {
+            Some(battery) if raw > battery => {
+                warn!(
+                    "Power {} on {} channel {} limited to {} by low battery",
+                    raw, device_id, channel, battery
+                );
+                battery
+            }
+            _ => raw,
+        }
+    }
 
-        let devices = self.devices.read().await;
+    /// 联动时 B 通道的上限：安全限制和低电量限制中较小者
+    fn link_cap(&self, device_id: &str) -> u8 {
+        let cap = self.safety().max_power_b;
+        match self.battery_limit(device_id).limit() {
+            Some(battery) => cap.min(battery),
+            None => cap,
+        }
+    }
 
-        for (id, device) in devices.iter() {
-            debug!("Disconnecting device: {}", id);
-            let mut dev = device.write().await;
-            if let Err(e) = dev.disconnect().await {
-                warn!("Failed to disconnect device {}: {}", id, e);
+    /// 获取会话中的设备，不存在时返回 [`CoreError::DeviceNotFound`]
+    async fn require_device(&self, device_id: &str) -> Result<Arc<RwLock<DeviceBox>>> {
+        self.get_device(device_id)
+            .await
+            .ok_or_else(|| CoreError::DeviceNotFound(device_id.to_string()))
+    }
+
+    /// 把强度换算为写入设备的原始强度：设备已校准时按校准曲线换算，再按安全限制截断
+    ///
+    /// `name` 为设备的 BLE 名称，用于按名称匹配校准曲线。
+    pub fn resolve_power(&self, device_id: &str, name: &str, channel: u8, power: u8) -> u8 {
+        let raw = match self.calibration(device_id, name) {
+            Some(curve) => curve.to_raw(power),
+            None => power,
+        };
+        self.limit_power(device_id, channel, raw)
+    }
+
+    /// 设置通道强度，设备已校准时把逻辑百分比换算为原始强度，再按安全限制截断
+    ///
+    /// 设置 A 通道且开启联动时同步 B 通道，B 通道同样受安全限制。返回实际写入的原始强度。
+    pub async fn set_power(&self, device_id: &str, channel: u8, power: u8) -> Result<u8> {
+        if channel > 1 {
+            return Err(CoreError::InvalidChannel(channel));
+        }
+        let device = self.require_device(device_id).await?;
+
+        let mut dev = device.write().await;
+        let raw = self.resolve_power(device_id, dev.name(), channel, power);
+        set_power_linked(&mut **dev, channel, raw, self.link_cap(device_id)).await?;
+        Ok(raw)
+    }
+
+    /// 设置通道原始强度（不经过校准曲线，如校准时试用），仍按安全限制截断
+    ///
+    /// 返回实际写入的原始强度。
+    pub async fn set_raw_power(&self, device_id: &str, channel: u8, raw: u8) -> Result<u8> {
+        if channel > 1 {
+            return Err(CoreError::InvalidChannel(channel));
+        }
+        let device = self.require_device(device_id).await?;
+
+        let raw = self.limit_power(device_id, channel, raw);
+        let mut dev = device.write().await;
+        set_power_linked(&mut **dev, channel, raw, self.link_cap(device_id)).await?;
+        Ok(raw)
+    }
+
+    /// 相对调整通道强度，结果限制在 0 到通道上限之间，再按安全限制截断
+    ///
+    /// 设备已校准时 `delta` 为逻辑百分比。未校准且未触及安全限制时使用设备的增减模式
+    /// （见 [`Device::adjust_power`]）。调整 A 通道且开启联动时同步 B 通道。
+    /// 返回实际写入的原始强度。
+    pub async fn adjust_power(&self, device_id: &str, channel: u8, delta: i16) -> Result<u8> {
+        if channel > 1 {
+            return Err(CoreError::InvalidChannel(channel));
+        }
+        let device = self.require_device(device_id).await?;
+
+        let mut dev = device.write().await;
+        let info = dev.info();
+        let max_power = match channel {
+            0 => info.max_power_a,
+            _ => info.max_power_b,
+        };
+        let current = dev.get_power(channel);
+        let raw = match self.calibration(device_id, dev.name()) {
+            Some(curve) => {
+                let percent = (curve.to_percent(current) as i16 + delta).clamp(0, 100) as u8;
+                let raw = self
+                    .limit_power(device_id, channel, curve.to_raw(percent))
+                    .min(max_power);
+                dev.set_power(channel, raw).await?;
+                raw
+            }
+            None => {
+                let target = (current as i16 + delta).clamp(0, max_power as i16) as u8;
+                let raw = self.limit_power(device_id, channel, target);
+                if raw < target {
+                    dev.set_power(channel, raw).await?;
+                    raw
+                } else {
+                    dev.adjust_power(channel, delta).await?
+                }
             }
+        };
+        if channel == 0 {
+            let _ = sync_linked_power(&mut **dev, self.link_cap(device_id)).await?;
         }
+        Ok(raw)
+    }
 
+    /// 设置设备的通道联动，开启时立即按当前 A 通道强度同步 B 通道
+    ///
+    /// 联动的 B 通道受安全限制和低电量限制约束。
+    pub async fn set_channel_link(&self, device_id: &str, link: Option<ChannelLink>) -> Result<()> {
+        let device = self.require_device(device_id).await?;
+        let mut dev = device.write().await;
+        dev.set_channel_link(link).await?;
+        if link.is_some() {
+            let _ = sync_linked_power(&mut **dev, self.link_cap(device_id)).await?;
+        }
         Ok(())
     }
 
-    /// 启动所有设备
-    pub async fn start_all(&self) -> Result<()> {
-        info!("Starting all devices");
+    /// 设置通道波形
+    pub async fn set_waveform(
+        &self,
+        device_id: &str,
+        channel: u8,
+        waveform: WaveformConfig,
+    ) -> Result<()> {
+        if channel > 1 {
+            return Err(CoreError::InvalidChannel(channel));
+        }
+        let device = self.require_device(device_id).await?;
+        let mut dev = device.write().await;
+        dev.set_waveform(channel, waveform.clone()).await?;
+        let _ = self
+            .waveforms()
+            .insert((device_id.to_string(), channel), waveform);
+        Ok(())
+    }
 
-        let devices = self.devices.read().await;
+    /// 获取波形表（锁中毒时继续使用内部数据）
+    fn waveforms(&self) -> MutexGuard<'_, WaveformMap> {
+        self.waveforms
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
 
-        for (id, device) in devices.iter() {
-            debug!("Starting device: {}", id);
-            let mut dev = device.write().await;
-            if let Err(e) = dev.start().await {
-                warn!("Failed to start device {}: {}", id, e);
+    /// 开始设备输出
+    pub async fn start(&self, device_id: &str) -> Result<()> {
+        let device = self.require_device(device_id).await?;
+        info!("Starting device {}", device_id);
+        let mut dev = device.write().await;
+        dev.start().await
+    }
+
+    /// 停止设备输出，同时取消进行中的渐变
+    pub async fn stop(&self, device_id: &str) -> Result<()> {
+        let device = self.require_device(device_id).await?;
+        for channel in 0..2 {
+            let _ = self.cancel_ramp(device_id, channel);
+        }
+        info!("Stopping device {}", device_id);
+        let mut dev = device.write().await;
+        dev.stop().await
+    }
+
+    /// 获取校准曲线表（锁中毒时继续使用内部数据）
+    fn calibrations(&self) -> MutexGuard<'_, HashMap<String, PowerCurve>> {
+        self.calibrations
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// 在后台把设备通道强度渐变到 `target`
+    ///
+    /// 同一通道上进行中的渐变会被取消并由新渐变接替。`on_step` 在每次强度变化后调用。
+    /// 设备已校准时 `target` 为逻辑百分比，渐变按校准曲线进行。
+    pub async fn start_ramp<F>(
+        &self,
+        device_id: &str,
+        channel: u8,
+        target: u8,
+        duration: Duration,
+        easing: Easing,
+        on_step: F,
+    ) -> Result<()>
+    where
+        F: FnMut(&dyn Device) + Send + 'static,
+    {
+        if channel > 1 {
+            return Err(CoreError::InvalidChannel(channel));
+        }
+        let device = self.require_device(device_id).await?;
+
+        let name = device.read().await.name().to_string();
+        let curve = self.calibration(device_id, &name);
+        // 目标按安全限制截断；已校准时取换算后不超过上限的最大百分比
+        let target = match &curve {
+            Some(curve) => {
+                let raw = curve.to_raw(target);
+                let limited = self.limit_power(device_id, channel, raw);
+                if limited < raw {
+                    (0..=target)
+                        .rev()
+                        .find(|&percent| curve.to_raw(percent) <= limited)
+                        .unwrap_or(0)
+                } else {
+                    target
+                }
+            }
+            None => self.limit_power(device_id, channel, target),
+        };
+
+        info!(
+            "Ramping device {} channel {} to {} over {:?} ({})",
+            device_id, channel, target, duration, easing
+        );
+        let spec = RampSpec::new(channel, target, duration, easing)
+            .with_curve(curve)
+            .with_max_linked_power(self.link_cap(device_id));
+        let task = Self::spawn_ramp(device, spec, on_step);
+        self.track_ramp(device_id, channel, task);
+        Ok(())
+    }
+
+    /// 在后台把设备通道原始强度渐变到 `target`（不经过校准曲线，如预设的初始强度），
+    /// 仍按安全限制截断
+    pub async fn start_raw_ramp<F>(
+        &self,
+        device_id: &str,
+        channel: u8,
+        target: u8,
+        duration: Duration,
+        easing: Easing,
+        on_step: F,
+    ) -> Result<()>
+    where
+        F: FnMut(&dyn Device) + Send + 'static,
+    {
+        if channel > 1 {
+            return Err(CoreError::InvalidChannel(channel));
+        }
+        let device = self.require_device(device_id).await?;
+        let target = self.limit_power(device_id, channel, target);
+
+        info!(
+            "Ramping device {} channel {} to raw {} over {:?} ({})",
+            device_id, channel, target, duration, easing
+        );
+        let spec = RampSpec::new(channel, target, duration, easing)
+            .with_max_linked_power(self.link_cap(device_id));
+        let task = Self::spawn_ramp(device, spec, on_step);
+        self.track_ramp(device_id, channel, task);
+        Ok(())
+    }
+
+    /// 启动渐变任务
+    fn spawn_ramp<F>(device: Arc<RwLock<DeviceBox>>, spec: RampSpec, on_step: F) -> JoinHandle<()>
+    where
+        F: FnMut(&dyn Device) + Send + 'static,
+    {
+        tokio::spawn(async move {
+            if let Err(e) = ramp_power_with(&device, &spec, on_step).await {
+                let id = device.read().await.id().to_string();
+                warn!(
+                    "Power ramp on {} channel {} failed: {}",
+                    id, spec.channel, e
+                );
             }
+        })
+    }
+
+    /// 登记渐变任务，取消同一通道上进行中的渐变
+    fn track_ramp(&self, device_id: &str, channel: u8, task: JoinHandle<()>) {
+        let previous = self.ramps().insert((device_id.to_string(), channel), task);
+        if let Some(previous) = previous {
+            previous.abort();
+        }
+    }
+
+    /// 取消通道上进行中的强度渐变，强度停留在当前值
+    ///
+    /// 返回是否有渐变被取消。
+    pub fn cancel_ramp(&self, device_id: &str, channel: u8) -> bool {
+        match self.ramps().remove(&(device_id.to_string(), channel)) {
+            Some(task) if !task.is_finished() => {
+                debug!("Cancelling power ramp on {} channel {}", device_id, channel);
+                task.abort();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// 通道上是否有进行中的强度渐变
+    pub fn is_ramping(&self, device_id: &str, channel: u8) -> bool {
+        self.ramps()
+            .get(&(device_id.to_string(), channel))
+            .is_some_and(|task| !task.is_finished())
+    }
+
+    /// 获取渐变任务表（锁中毒时继续使用内部数据）
+    fn ramps(&self) -> MutexGuard<'_, RampMap> {
+        self.ramps
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// 设置会话停止时间
+    ///
+    /// 到时后所有设备强度渐变归零并停止输出，之前发送 [`SessionEvent::TimerWarning`]，
+    /// 完成后发送 [`SessionEvent::TimerExpired`]。替换已有的定时。
+    pub fn set_timer(&self, stop_at: DateTime<Utc>) -> Result<()> {
+        if stop_at <= Utc::now() {
+            return Err(CoreError::InvalidParameter(format!(
+                "Stop time {} is in the past",
+                stop_at.to_rfc3339()
+            )));
         }
 
+        info!("Session will stop at {}", stop_at.to_rfc3339());
+        let task = tokio::spawn(timer::run(
+            stop_at,
+            self.devices.clone(),
+            self.ramps.clone(),
+            self.event_tx.clone(),
+            self.event_log.clone(),
+        ));
+
+        let previous = self.timer().replace((stop_at, task));
+        if let Some((_, previous)) = previous {
+            previous.abort();
+        }
         Ok(())
     }
 
-    /// 停止所有设备
-    pub async fn stop_all(&self) -> Result<()> {
-        info!("Stopping all devices");
+    /// 设置会话最长运行时长（从现在起算）
+    pub fn set_timer_duration(&self, duration: Duration) -> Result<()> {
+        let duration = chrono::Duration::from_std(duration)
+            .map_err(|e| CoreError::InvalidParameter(format!("Invalid duration: {}", e)))?;
+        self.set_timer(Utc::now() + duration)
+    }
 
+    /// 取消会话定时，返回是否有定时被取消
+    pub fn cancel_timer(&self) -> bool {
+        match self.timer().take() {
+            Some((_, task)) if !task.is_finished() => {
+                info!("Session timer cancelled");
+                task.abort();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// 获取会话停止时间（未设置或已到时返回 `None`）
+    pub fn timer_deadline(&self) -> Option<DateTime<Utc>> {
+        self.timer()
+            .as_ref()
+            .filter(|(_, task)| !task.is_finished())
+            .map(|(stop_at, _)| *stop_at)
+    }
+
+    /// 获取定时状态（锁中毒时继续使用内部数据）
+    fn timer(&self) -> MutexGuard<'_, Option<(DateTime<Utc>, JoinHandle<()>)>> {
+        self.timer
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// 生成会话快照（设备、通道强度、最近设置的波形和会话定时）
+    ///
+    /// 已校准设备的强度换算为逻辑百分比，与 [`Self::set_power`] 一致。
+    pub async fn snapshot(&self) -> SessionSnapshot {
+        let waveforms = self.waveforms().clone();
         let devices = self.devices.read().await;
 
+        let mut snapshots = Vec::with_capacity(devices.len());
         for (id, device) in devices.iter() {
-            debug!("Stopping device: {}", id);
-            let mut dev = device.write().await;
-            if let Err(e) = dev.stop().await {
-                warn!("Failed to stop device {}: {}", id, e);
+            let dev = device.read().await;
+            let curve = self.calibration(id, dev.name());
+            let power = [0u8, 1].map(|channel| {
+                let raw = dev.get_power(channel);
+                curve.as_ref().map_or(raw, |curve| curve.to_percent(raw))
+            });
+            snapshots.push(DeviceSnapshot {
+                id: id.clone(),
+                name: dev.name().to_string(),
+                device_type: dev.info().device_type,
+                transport: snapshot_transport(dev.as_any()),
+                power,
+                waveforms: [0u8, 1].map(|channel| waveforms.get(&(id.clone(), channel)).cloned()),
+                running: dev.state() == DeviceState::Running,
+            });
+        }
+        snapshots.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let saved_at = Utc::now();
+        SessionSnapshot {
+            session_id: self.session_id.clone(),
+            created_at: self.created_at,
+            saved_at,
+            devices: snapshots,
+            timer_remaining_secs: self
+                .timer_deadline()
+                .map(|deadline| (deadline - saved_at).num_seconds().max(0) as u64),
+        }
+    }
+
+    /// 按快照恢复会话（已取得用户确认）
+    ///
+    /// 快照中的设备需已重新连接并加入会话。恢复波形后，保存时正在输出的设备重新开始输出，
+    /// 强度从当前值（重连后为 0）在 `ramp` 内渐变到保存的强度，仍受安全限制约束；
+    /// 会话定时按剩余时长重新设置。不在会话中的设备返回 [`CoreError::DeviceNotFound`]。
+    /// `on_step` 在每次强度变化后调用。
+    pub async fn restore_snapshot<F>(
+        &self,
+        snapshot: &SessionSnapshot,
+        ramp: Duration,
+        on_step: F,
+    ) -> BulkResults
+    where
+        F: FnMut(&dyn Device) + Clone + Send + 'static,
+    {
+        info!(
+            "Restoring session {} ({} devices)",
+            snapshot.session_id,
+            snapshot.devices.len()
+        );
+
+        let mut results = HashMap::new();
+        for device in &snapshot.devices {
+            let result = self.restore_device(device, ramp, on_step.clone()).await;
+            if let Err(e) = &result {
+                warn!("Failed to restore device {}: {}", device.id, e);
             }
+            let _ = results.insert(device.id.clone(), result);
+        }
+
+        if let Some(secs) = snapshot.timer_remaining_secs.filter(|&secs| secs > 0) {
+            if let Err(e) = self.set_timer_duration(Duration::from_secs(secs)) {
+                warn!("Failed to restore session timer: {}", e);
+            }
+        }
+        results
+    }
+
+    /// 恢复单个设备的波形、输出状态和强度
+    async fn restore_device<F>(
+        &self,
+        snapshot: &DeviceSnapshot,
+        ramp: Duration,
+        on_step: F,
+    ) -> Result<()>
+    where
+        F: FnMut(&dyn Device) + Clone + Send + 'static,
+    {
+        for (channel, waveform) in (0u8..).zip(&snapshot.waveforms) {
+            if let Some(waveform) = waveform {
+                self.set_waveform(&snapshot.id, channel, waveform.clone())
+                    .await?;
+            }
+        }
+        if snapshot.running {
+            self.start(&snapshot.id).await?;
+        }
+        for (channel, power) in (0u8..).zip(snapshot.power) {
+            self.start_ramp(
+                &snapshot.id,
+                channel,
+                power,
+                ramp,
+                Easing::Linear,
+                on_step.clone(),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// 设置波形库
+    pub async fn set_waveform_library(&self, library: WaveformLibrary) {
+        *self.waveform_library.write().await = library;
+    }
+
+    /// 当前波形库（副本）
+    pub async fn waveform_library(&self) -> WaveformLibrary {
+        self.waveform_library.read().await.clone()
+    }
+
+    /// 按名称从波形库查找波形
+    pub async fn resolve_waveform(&self, name: &str) -> Result<Waveform> {
+        self.waveform_library.read().await.resolve(name)
+    }
+
+    /// 列出波形库中的所有波形（内置和用户波形，按名称排序）
+    pub async fn list_waveforms(&self) -> Vec<Waveform> {
+        self.waveform_library.read().await.list()
+    }
+
+    /// 将预设应用到设备
+    ///
+    /// 依次为两个通道设置最大强度、波形和初始强度（启用通道为 `min_power`，禁用通道归零）。
+    /// 通道未内嵌波形时按 `waveform_name` 从波形库查找。
+    /// 全程持有设备写锁；写入中途失败时恢复设备原有的最大强度和强度。
+    ///
+    /// 需要确认的预设（[`Preset::requires_confirmation`]）返回 [`CoreError::Rejected`]，
+    /// 取得用户确认后使用 [`apply_preset_confirmed`](Self::apply_preset_confirmed)。
+    pub async fn apply_preset(&self, device_id: &str, preset: &Preset) -> Result<()> {
+        if preset.requires_confirmation() {
+            return Err(CoreError::Rejected(format!(
+                "Preset '{}' requires confirmation",
+                preset.name
+            )));
+        }
+        self.apply_preset_confirmed(device_id, preset).await
+    }
+
+    /// 将预设应用到设备（已取得用户确认）
+    ///
+    /// 按预设安全设置截断强度；限制了上升速率时，初始强度在后台从当前强度渐变上升。
+    pub async fn apply_preset_confirmed(&self, device_id: &str, preset: &Preset) -> Result<()> {
+        info!("Applying preset '{}' to device {}", preset.name, device_id);
+
+        preset.safety.validate()?;
+
+        for (channel, config) in [(0u8, &preset.channel_a), (1u8, &preset.channel_b)] {
+            if config.min_power > config.max_power {
+                return Err(CoreError::InvalidParameter(format!(
+                    "Channel {} min power {} exceeds max power {}",
+                    channel, config.min_power, config.max_power
+                )));
+            }
+        }
+
+        let waveforms = [
+            self.preset_waveform(&preset.channel_a).await?,
+            self.preset_waveform(&preset.channel_b).await?,
+        ];
+
+        let device = self.require_device(device_id).await?;
+        let mut dev = device.write().await;
+
+        let previous = dev.info();
+        let applied = waveforms.clone();
+        let (limits, battery) = (self.safety(), self.battery_limit(device_id).limit());
+        let ramps = match Self::write_preset(&mut dev, preset, waveforms, limits, battery).await {
+            Ok(ramps) => ramps,
+            Err(e) => {
+                warn!(
+                    "Failed to apply preset '{}' to device {}: {}, rolling back",
+                    preset.name, device_id, e
+                );
+                for (channel, max_power, power, enabled) in [
+                    (
+                        0u8,
+                        previous.max_power_a,
+                        previous.power_a,
+                        previous.enabled_a,
+                    ),
+                    (
+                        1u8,
+                        previous.max_power_b,
+                        previous.power_b,
+                        previous.enabled_b,
+                    ),
+                ] {
+                    let _ = dev.set_channel_enabled(channel, enabled).await;
+                    let _ = dev.set_max_power(channel, max_power).await;
+                    let _ = dev.set_power(channel, power.min(max_power)).await;
+                }
+                return Err(e);
+            }
+        };
+        drop(dev);
+
+        {
+            let mut recorded = self.waveforms();
+            for (channel, waveform) in (0u8..).zip(applied) {
+                if let Some(waveform) = waveform {
+                    let _ = recorded.insert((device_id.to_string(), channel), waveform);
+                }
+            }
+        }
+
+        // 预设强度为原始强度，渐变不经过校准曲线
+        for (channel, target, duration) in ramps {
+            self.start_raw_ramp(device_id, channel, target, duration, Easing::Linear, |_| {})
+                .await?;
         }
 
         Ok(())
     }
 
+    /// 获取通道配置的波形（内嵌波形优先，其次为波形库中的名称）
+    ///
+    /// 通道的调制层叠加在波形自带的调制层之后；只有调制层没有波形时调制默认波形。
+    async fn preset_waveform(
+        &self,
+        config: &PresetChannelConfig,
+    ) -> Result<Option<WaveformConfig>> {
+        let waveform = match (&config.waveform, &config.waveform_name) {
+            (Some(waveform), _) => Some(waveform.clone()),
+            (None, Some(name)) if config.enabled => Some(self.resolve_waveform(name).await?),
+            _ if config.enabled && !config.modulation.is_empty() => Some(Waveform::default()),
+            _ => None,
+        };
+
+        let Some(mut waveform) = waveform else {
+            return Ok(None);
+        };
+        waveform
+            .modulation
+            .extend(config.modulation.iter().copied());
+        for modulation in &waveform.modulation {
+            modulation.validate()?;
+        }
+        Ok(Some(waveform.to_device_config()))
+    }
+
+    /// 按通道写入预设配置
+    ///
+    /// 初始强度同时受低电量限制（`battery`）约束。
+    /// 返回受上升速率限制、需要渐变到初始强度的通道（通道, 目标强度, 时长）。
+    async fn write_preset(
+        dev: &mut DeviceBox,
+        preset: &Preset,
+        waveforms: [Option<WaveformConfig>; 2],
+        limits: SafetyConfig,
+        battery: Option<u8>,
+    ) -> Result<Vec<(u8, u8, Duration)>> {
+        let safety = &preset.safety;
+        let mut ramps = Vec::new();
+
+        for ((channel, config), waveform) in [(0u8, &preset.channel_a), (1u8, &preset.channel_b)]
+            .into_iter()
+            .zip(waveforms)
+        {
+            // 预设上限同时受会话安全限制约束
+            let limit = match channel {
+                0 => limits.max_power_a,
+                _ => limits.max_power_b,
+            };
+            let max_power = safety.clamp(config.max_power).min(limit);
+            dev.set_max_power(channel, max_power).await?;
+
+            // 禁用的通道强度归零、波形静默，直到重新启用
+            dev.set_channel_enabled(channel, config.enabled).await?;
+            if !config.enabled {
+                continue;
+            }
+
+            if let Some(waveform) = waveform {
+                dev.set_waveform(channel, waveform).await?;
+            }
+
+            let target = safety
+                .clamp(config.min_power)
+                .min(max_power)
+                .min(battery.unwrap_or(u8::MAX));
+            let current = dev.get_power(channel);
+            match safety.ramp_duration(current, target) {
+                Some(duration) => ramps.push((channel, target, duration)),
+                None => dev.set_power(channel, target).await?,
+            }
+        }
+
+        Ok(ramps)
+    }
+
+    /// 监听 BLE 适配器事件（见 [`BleManager::watch`](dglab_protocol::ble::BleManager::watch)）
+    ///
+    /// 发送 [`SessionEvent::DeviceDiscovered`] 和 [`SessionEvent::DeviceLost`]；
+    /// 会话中的设备断开后清理其连接状态，重新出现时自动重连。
+    pub fn watch_adapter(&self, events: mpsc::Receiver<AdapterEvent>) -> JoinHandle<()> {
+        tokio::spawn(hotplug::run(
+            events,
+            self.devices.clone(),
+            self.event_tx.clone(),
+        ))
+    }
+
     /// 订阅会话事件
     pub fn subscribe_events(&self) -> broadcast::Receiver<SessionEvent> {
         self.event_tx.subscribe()
@@ -234,10 +1415,28 @@ impl Default for SessionManager {
     }
 }
 
+/// 按设备类型判断快照中的连接方式
+fn snapshot_transport(device: &dyn Any) -> SnapshotTransport {
+    if let Some(coyote) = device.downcast_ref::<CoyoteDevice>() {
+        if !coyote.is_dry_run() {
+            return SnapshotTransport::Ble;
+        }
+    } else if let Some(ws) = device.downcast_ref::<WsCoyoteDevice>() {
+        if !ws.is_dry_run() {
+            return SnapshotTransport::Wifi;
+        }
+    }
+    SnapshotTransport::Other
+}
+
 #[cfg(test)]
 mod tests {
+    use std::any::Any;
+
     use super::*;
-    use crate::device::traits::{DeviceInfo, WaveformConfig};
+    use crate::device::traits::{ChannelLink, DeviceLimits};
+    use crate::session::timer::TIMER_WARNINGS;
+    use dglab_protocol::ble::ScanResult;
 
     /// 用于测试的 Mock 设备
     struct MockDevice {
@@ -246,6 +1445,11 @@ mod tests {
         state: DeviceState,
         power_a: u8,
         power_b: u8,
+        max_power_a: u8,
+        max_power_b: u8,
+        waveforms: [Option<WaveformConfig>; 2],
+        channel_link: Option<ChannelLink>,
+        enabled: [bool; 2],
         event_tx: broadcast::Sender<DeviceEvent>,
     }
 
@@ -258,6 +1462,11 @@ mod tests {
                 state: DeviceState::Disconnected,
                 power_a: 0,
                 power_b: 0,
+                max_power_a: 100,
+                max_power_b: 100,
+                waveforms: [None, None],
+                channel_link: None,
+                enabled: [true, true],
                 event_tx,
             }
         }
@@ -265,8 +1474,16 @@ mod tests {
 
     #[async_trait::async_trait]
     impl Device for MockDevice {
-        fn id(&self) -> &str {
-            &self.id
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn as_any_mut(&mut self) -> &mut dyn Any {
+            self
+        }
+
+        fn id(&self) -> &str {
+            &self.id
         }
 
         fn name(&self) -> &str {
@@ -285,10 +1502,13 @@ mod tests {
                 firmware_version: "1.0".to_string(),
                 hardware_version: "1.0".to_string(),
                 battery_level: 100,
+                battery_remaining_secs: None,
                 power_a: self.power_a,
                 power_b: self.power_b,
-                max_power_a: 100,
-                max_power_b: 100,
+                max_power_a: self.max_power_a,
+                max_power_b: self.max_power_b,
+                enabled_a: self.enabled[0],
+                enabled_b: self.enabled[1],
             }
         }
 
@@ -325,11 +1545,19 @@ mod tests {
         }
 
         async fn set_power(&mut self, channel: u8, power: u8) -> Result<()> {
+            let power = if self.channel_enabled(channel) {
+                power
+            } else {
+                0
+            };
             match channel {
                 0 => self.power_a = power,
                 1 => self.power_b = power,
                 _ => return Err(CoreError::InvalidParameter("Invalid channel".to_string())),
             }
+            let _ = self
+                .event_tx
+                .send(DeviceEvent::PowerChanged { channel, power });
             Ok(())
         }
 
@@ -341,7 +1569,62 @@ mod tests {
             }
         }
 
-        async fn set_waveform(&mut self, _channel: u8, _waveform: WaveformConfig) -> Result<()> {
+        async fn set_max_power(&mut self, channel: u8, max_power: u8) -> Result<()> {
+            if max_power > 200 {
+                return Err(CoreError::PowerOutOfRange(max_power, 200));
+            }
+            match channel {
+                0 => {
+                    self.max_power_a = max_power;
+                    self.power_a = self.power_a.min(max_power);
+                }
+                1 => {
+                    self.max_power_b = max_power;
+                    self.power_b = self.power_b.min(max_power);
+                }
+                _ => return Err(CoreError::InvalidParameter("Invalid channel".to_string())),
+            }
+            Ok(())
+        }
+
+        fn limits(&self) -> DeviceLimits {
+            DeviceLimits::new(self.max_power_a, self.max_power_b, 0, 0)
+        }
+
+        async fn set_limits(&mut self, limits: DeviceLimits) -> Result<()> {
+            self.set_max_power(0, limits.soft_limit_a).await?;
+            self.set_max_power(1, limits.soft_limit_b).await
+        }
+
+        fn channel_link(&self) -> Option<ChannelLink> {
+            self.channel_link
+        }
+
+        async fn set_channel_link(&mut self, link: Option<ChannelLink>) -> Result<()> {
+            self.channel_link = link;
+            Ok(())
+        }
+
+        fn channel_enabled(&self, channel: u8) -> bool {
+            self.enabled.get(channel as usize).copied().unwrap_or(false)
+        }
+
+        async fn set_channel_enabled(&mut self, channel: u8, enabled: bool) -> Result<()> {
+            match channel {
+                0 | 1 => self.enabled[channel as usize] = enabled,
+                _ => return Err(CoreError::InvalidChannel(channel)),
+            }
+            if !enabled {
+                self.set_power(channel, 0).await?;
+            }
+            Ok(())
+        }
+
+        async fn set_waveform(&mut self, channel: u8, waveform: WaveformConfig) -> Result<()> {
+            match channel {
+                0 | 1 => self.waveforms[channel as usize] = Some(waveform),
+                _ => return Err(CoreError::InvalidParameter("Invalid channel".to_string())),
+            }
             Ok(())
         }
 
@@ -352,6 +1635,10 @@ mod tests {
         fn subscribe_events(&self) -> broadcast::Receiver<DeviceEvent> {
             self.event_tx.subscribe()
         }
+
+        fn send_event(&self, event: DeviceEvent) {
+            let _ = self.event_tx.send(event);
+        }
     }
 
     // === SessionManager 测试 ===
@@ -456,6 +1743,51 @@ mod tests {
         assert!(dev.is_none());
     }
 
+    #[tokio::test]
+    async fn test_device_downcast_and_wifi_binding() {
+        use crate::device::WsCoyoteDevice;
+
+        let manager = SessionManager::new();
+        manager
+            .add_device(Box::new(MockDevice::new("dev-1", "Test")))
+            .await
+            .unwrap();
+        manager
+            .add_device(Box::new(WsCoyoteDevice::new(
+                "ws-1".to_string(),
+                "WiFi".to_string(),
+            )))
+            .await
+            .unwrap();
+
+        let device = manager.get_device("dev-1").await.unwrap();
+        let mut dev = device.write().await;
+        assert!(dev.is::<MockDevice>());
+        assert!(dev.downcast_ref::<WsCoyoteDevice>().is_none());
+        dev.downcast_mut::<MockDevice>().unwrap().power_a = 7;
+        assert_eq!(dev.get_power(0), 7);
+        drop(dev);
+
+        let device = manager.get_device("ws-1").await.unwrap();
+        assert!(device
+            .read()
+            .await
+            .downcast_ref::<WsCoyoteDevice>()
+            .is_some());
+
+        // 未连接服务器时没有二维码，也未绑定
+        assert_eq!(manager.qr_url("ws-1").await.unwrap(), None);
+        assert!(!manager.is_bound("ws-1").await.unwrap());
+        assert!(matches!(
+            manager.is_bound("dev-1").await,
+            Err(CoreError::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            manager.qr_url("missing").await,
+            Err(CoreError::DeviceNotFound(_))
+        ));
+    }
+
     #[tokio::test]
     async fn test_list_devices_multiple() {
         let manager = SessionManager::new();
@@ -515,7 +1847,7 @@ mod tests {
             .await
             .unwrap();
 
-        manager.connect_all().await.unwrap();
+        assert!(manager.connect_all().await.values().all(Result::is_ok));
 
         // 验证设备已连接
         let dev = manager.get_device("dev-1").await.unwrap();
@@ -531,8 +1863,8 @@ mod tests {
             .await
             .unwrap();
 
-        manager.connect_all().await.unwrap();
-        manager.disconnect_all().await.unwrap();
+        assert!(manager.connect_all().await.values().all(Result::is_ok));
+        assert!(manager.disconnect_all().await.values().all(Result::is_ok));
 
         let dev = manager.get_device("dev-1").await.unwrap();
         let d = dev.read().await;
@@ -547,7 +1879,7 @@ mod tests {
             .await
             .unwrap();
 
-        manager.start_all().await.unwrap();
+        assert!(manager.start_all().await.values().all(Result::is_ok));
 
         let dev = manager.get_device("dev-1").await.unwrap();
         let d = dev.read().await;
@@ -562,14 +1894,790 @@ mod tests {
             .await
             .unwrap();
 
-        manager.start_all().await.unwrap();
-        manager.stop_all().await.unwrap();
+        assert!(manager.start_all().await.values().all(Result::is_ok));
+        assert!(manager.stop_all().await.values().all(Result::is_ok));
 
         let dev = manager.get_device("dev-1").await.unwrap();
         let d = dev.read().await;
         assert_eq!(d.state(), DeviceState::Connected);
     }
 
+    #[tokio::test]
+    async fn test_bulk_results_and_timeout() {
+        let mut manager = SessionManager::new();
+        manager.set_bulk_timeout(Duration::from_millis(100));
+        manager
+            .add_device(Box::new(MockDevice::new("dev-1", "D1")))
+            .await
+            .unwrap();
+        manager
+            .add_device(Box::new(MockDevice::new("dev-2", "D2")))
+            .await
+            .unwrap();
+
+        // dev-2 被占用时超时，不影响 dev-1
+        let busy = manager.get_device("dev-2").await.unwrap();
+        let guard = busy.write().await;
+        let results = manager.connect_all().await;
+        drop(guard);
+
+        assert_eq!(results.len(), 2);
+        assert!(results["dev-1"].is_ok());
+        assert!(matches!(results["dev-2"], Err(CoreError::Timeout(_))));
+
+        let dev = manager.get_device("dev-1").await.unwrap();
+        assert_eq!(dev.read().await.state(), DeviceState::Connected);
+    }
+
+    // === 预设应用测试 ===
+
+    fn test_preset() -> Preset {
+        let mut preset = Preset::new("Test".to_string(), String::new());
+        preset.channel_a.min_power = 10;
+        preset.channel_a.max_power = 60;
+        preset.channel_a.waveform = Some(crate::waveform::Waveform::default());
+        preset.channel_b.enabled = false;
+        preset.channel_b.max_power = 80;
+        preset
+    }
+
+    #[tokio::test]
+    async fn test_apply_preset() {
+        let manager = SessionManager::new();
+        let mut device = MockDevice::new("dev-1", "D1");
+        device.power_b = 30;
+        manager.add_device(Box::new(device)).await.unwrap();
+
+        manager.apply_preset("dev-1", &test_preset()).await.unwrap();
+
+        let dev = manager.get_device("dev-1").await.unwrap();
+        let d = dev.read().await;
+        let info = d.info();
+        assert_eq!(info.max_power_a, 60);
+        assert_eq!(info.max_power_b, 80);
+        assert_eq!(info.power_a, 10);
+        // 禁用通道归零，之后的强度指令被忽略
+        assert_eq!(info.power_b, 0);
+        assert!(info.enabled_a);
+        assert!(!info.enabled_b);
+        drop(d);
+        dev.write().await.set_power(1, 40).await.unwrap();
+        assert_eq!(dev.read().await.get_power(1), 0);
+    }
+
+    #[tokio::test]
+    async fn test_apply_preset_device_not_found() {
+        let manager = SessionManager::new();
+        let result = manager.apply_preset("missing", &test_preset()).await;
+        assert!(matches!(result, Err(CoreError::DeviceNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_apply_preset_invalid_range() {
+        let manager = SessionManager::new();
+        manager
+            .add_device(Box::new(MockDevice::new("dev-1", "D1")))
+            .await
+            .unwrap();
+
+        let mut preset = test_preset();
+        preset.channel_a.min_power = 70;
+        let result = manager.apply_preset("dev-1", &preset).await;
+        assert!(matches!(result, Err(CoreError::InvalidParameter(_))));
+
+        let dev = manager.get_device("dev-1").await.unwrap();
+        assert_eq!(dev.read().await.info().max_power_a, 100);
+    }
+
+    #[tokio::test]
+    async fn test_apply_preset_rolls_back_on_failure() {
+        let manager = SessionManager::new();
+        let mut device = MockDevice::new("dev-1", "D1");
+        device.power_a = 40;
+        manager.add_device(Box::new(device)).await.unwrap();
+
+        // B 通道上限超出设备范围，A 通道的修改应被回滚
+        let mut preset = test_preset();
+        preset.channel_b.max_power = 250;
+        assert!(manager.apply_preset("dev-1", &preset).await.is_err());
+
+        let dev = manager.get_device("dev-1").await.unwrap();
+        let info = dev.read().await.info();
+        assert_eq!(info.max_power_a, 100);
+        assert_eq!(info.power_a, 40);
+    }
+
+    #[tokio::test]
+    async fn test_apply_preset_waveform_name() {
+        let manager = SessionManager::new();
+        manager
+            .add_device(Box::new(MockDevice::new("dev-1", "D1")))
+            .await
+            .unwrap();
+
+        let mut preset = test_preset();
+        preset.channel_a.waveform = None;
+        preset.channel_a.waveform_name = Some("breathing".to_string());
+        manager.apply_preset("dev-1", &preset).await.unwrap();
+
+        // 未知波形名称在写入设备前报错
+        preset.channel_a.waveform_name = Some("missing".to_string());
+        preset.channel_a.max_power = 90;
+        let result = manager.apply_preset("dev-1", &preset).await;
+        assert!(matches!(result, Err(CoreError::InvalidParameter(_))));
+
+        let dev = manager.get_device("dev-1").await.unwrap();
+        assert_eq!(dev.read().await.info().max_power_a, 60);
+    }
+
+    #[tokio::test]
+    async fn test_apply_preset_requires_confirmation() {
+        let manager = SessionManager::new();
+        manager
+            .add_device(Box::new(MockDevice::new("dev-1", "D1")))
+            .await
+            .unwrap();
+
+        let mut preset = test_preset();
+        preset.safety.require_confirmation = true;
+        let result = manager.apply_preset("dev-1", &preset).await;
+        assert!(matches!(result, Err(CoreError::Rejected(_))));
+
+        let dev = manager.get_device("dev-1").await.unwrap();
+        assert_eq!(dev.read().await.info().max_power_a, 100);
+
+        manager
+            .apply_preset_confirmed("dev-1", &preset)
+            .await
+            .unwrap();
+        assert_eq!(dev.read().await.info().max_power_a, 60);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_apply_preset_safety_limits() {
+        let manager = SessionManager::new();
+        manager
+            .add_device(Box::new(MockDevice::new("dev-1", "D1")))
+            .await
+            .unwrap();
+
+        let mut preset = test_preset();
+        preset.channel_a.min_power = 30;
+        preset.safety.max_power = Some(20);
+        preset.safety.max_ramp_rate = Some(10);
+        manager.apply_preset("dev-1", &preset).await.unwrap();
+
+        let dev = manager.get_device("dev-1").await.unwrap();
+        let info = dev.read().await.info();
+        assert_eq!(info.max_power_a, 20);
+        // 初始强度按 10/s 渐变上升，不会直接跳到目标
+        assert!(info.power_a < 20);
+
+        tokio::time::sleep(Duration::from_secs(3)).await;
+        assert_eq!(dev.read().await.get_power(0), 20);
+    }
+
+    #[tokio::test]
+    async fn test_apply_preset_ramp_ignores_calibration() {
+        let manager = SessionManager::new();
+        manager
+            .add_device(Box::new(MockDevice::new("dev-1", "D1")))
+            .await
+            .unwrap();
+        // 预设强度为原始强度，渐变目标不按校准曲线换算
+        manager.set_calibration("D1", Some(PowerCurve::linear(50)));
+
+        let mut preset = test_preset();
+        preset.channel_a.min_power = 20;
+        preset.safety.max_ramp_rate = Some(100);
+        manager.apply_preset("dev-1", &preset).await.unwrap();
+
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        let dev = manager.get_device("dev-1").await.unwrap();
+        assert_eq!(dev.read().await.get_power(0), 20);
+    }
+
+    // === 强度渐变测试 ===
+
+    #[tokio::test]
+    async fn test_start_ramp() {
+        let manager = SessionManager::new();
+        manager
+            .add_device(Box::new(MockDevice::new("dev-1", "D1")))
+            .await
+            .unwrap();
+
+        let steps = Arc::new(Mutex::new(Vec::new()));
+        let recorded = steps.clone();
+        manager
+            .start_ramp(
+                "dev-1",
+                1,
+                30,
+                Duration::from_millis(200),
+                Easing::EaseIn,
+                move |dev| recorded.lock().unwrap().push(dev.get_power(1)),
+            )
+            .await
+            .unwrap();
+        assert!(manager.is_ramping("dev-1", 1));
+
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        assert!(!manager.is_ramping("dev-1", 1));
+        let dev = manager.get_device("dev-1").await.unwrap();
+        assert_eq!(dev.read().await.get_power(1), 30);
+        assert_eq!(steps.lock().unwrap().last(), Some(&30));
+
+        let result = manager
+            .start_ramp("missing", 0, 10, Duration::ZERO, Easing::Linear, |_| {})
+            .await;
+        assert!(matches!(result, Err(CoreError::DeviceNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_ramp() {
+        let manager = SessionManager::new();
+        manager
+            .add_device(Box::new(MockDevice::new("dev-1", "D1")))
+            .await
+            .unwrap();
+
+        manager
+            .start_ramp(
+                "dev-1",
+                0,
+                100,
+                Duration::from_secs(10),
+                Easing::Linear,
+                |_| {},
+            )
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        assert!(manager.cancel_ramp("dev-1", 0));
+        assert!(!manager.cancel_ramp("dev-1", 0));
+
+        // 取消后强度停留在当前值
+        let dev = manager.get_device("dev-1").await.unwrap();
+        let power = dev.read().await.get_power(0);
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        assert!(power < 100);
+        assert_eq!(dev.read().await.get_power(0), power);
+    }
+
+    #[tokio::test]
+    async fn test_calibrated_power() {
+        let manager = SessionManager::new();
+        manager
+            .add_device(Box::new(MockDevice::new("dev-1", "D1")))
+            .await
+            .unwrap();
+
+        // 未校准时直接使用原始强度
+        assert_eq!(manager.set_power("dev-1", 0, 50).await.unwrap(), 50);
+
+        // 按名称匹配校准
+        let curve = PowerCurve::new(vec![(0, 0), (100, 60)]).unwrap();
+        manager.set_calibration("D1", Some(curve.clone()));
+        assert_eq!(manager.calibration("dev-1", "D1"), Some(curve));
+        assert_eq!(manager.set_power("dev-1", 0, 50).await.unwrap(), 30);
+
+        manager
+            .start_ramp(
+                "dev-1",
+                1,
+                100,
+                Duration::from_millis(200),
+                Easing::Linear,
+                |_| {},
+            )
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        let dev = manager.get_device("dev-1").await.unwrap();
+        assert_eq!(dev.read().await.get_power(1), 60);
+
+        // 重新加载配置时清除未配置的校准
+        manager.load_calibrations(&AppConfig::default());
+        assert!(manager.calibration("dev-1", "D1").is_none());
+        assert_eq!(manager.set_power("dev-1", 0, 50).await.unwrap(), 50);
+    }
+
+    /// 等待下一个低电量限制事件
+    async fn next_limit(events: &mut broadcast::Receiver<SessionEvent>) -> BatteryAction {
+        loop {
+            if let SessionEvent::BatteryLimited(id, action) = events.recv().await.unwrap() {
+                assert_eq!(id, "dev-1");
+                return action;
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_battery_limit() {
+        let manager = SessionManager::new();
+        let device = MockDevice::new("dev-1", "D1");
+        let device_tx = device.event_tx.clone();
+        manager.add_device(Box::new(device)).await.unwrap();
+
+        let mut config = AppConfig::default();
+        config.safety.battery.cap_below = Some(20);
+        config.safety.battery.cap_power = 30;
+        config.safety.battery.pause_below = Some(5);
+        manager.load_config(&config);
+        assert_eq!(manager.set_power("dev-1", 0, 80).await.unwrap(), 80);
+
+        let mut events = manager.subscribe_events();
+        let report = |level| {
+            let _ = device_tx.send(DeviceEvent::BatteryUpdated(level));
+        };
+
+        // 低于阈值时降低当前强度，之后的设置也被截断
+        report(15);
+        assert_eq!(next_limit(&mut events).await, BatteryAction::Cap(30));
+        let dev = manager.get_device("dev-1").await.unwrap();
+        assert_eq!(dev.read().await.get_power(0), 30);
+        assert_eq!(manager.set_power("dev-1", 1, 50).await.unwrap(), 30);
+
+        // 用户忽略后不再限制，恢复时立即执行
+        manager.set_battery_override("dev-1", true).await.unwrap();
+        assert_eq!(manager.battery_limit("dev-1"), BatteryAction::None);
+        assert_eq!(manager.set_power("dev-1", 0, 50).await.unwrap(), 50);
+        manager.set_battery_override("dev-1", false).await.unwrap();
+        assert_eq!(dev.read().await.get_power(0), 30);
+
+        report(4);
+        assert_eq!(next_limit(&mut events).await, BatteryAction::Pause);
+        assert_eq!(dev.read().await.get_power(0), 0);
+        assert_eq!(dev.read().await.get_power(1), 0);
+
+        report(90);
+        assert_eq!(next_limit(&mut events).await, BatteryAction::None);
+        assert_eq!(manager.set_power("dev-1", 0, 80).await.unwrap(), 80);
+        assert!(manager.set_battery_override("missing", true).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_battery_override_expires_when_stricter() {
+        let manager = SessionManager::new();
+        let device = MockDevice::new("dev-1", "D1");
+        let device_tx = device.event_tx.clone();
+        let mut device_events = device.subscribe_events();
+        manager.add_device(Box::new(device)).await.unwrap();
+
+        let mut config = AppConfig::default();
+        config.safety.battery.cap_below = Some(20);
+        config.safety.battery.pause_below = Some(5);
+        manager.load_config(&config);
+
+        let mut events = manager.subscribe_events();
+        let report = |level| {
+            let _ = device_tx.send(DeviceEvent::BatteryUpdated(level));
+        };
+
+        report(15);
+        assert_eq!(next_limit(&mut events).await, BatteryAction::Cap(30));
+        manager.set_battery_override("dev-1", true).await.unwrap();
+        assert_eq!(manager.set_power("dev-1", 0, 80).await.unwrap(), 80);
+
+        // 同一级别内电量继续下降，忽略仍然有效
+        report(10);
+        assert_eq!(manager.set_power("dev-1", 0, 80).await.unwrap(), 80);
+
+        // 限制变为暂停时取消忽略
+        report(4);
+        assert_eq!(next_limit(&mut events).await, BatteryAction::Pause);
+        assert_eq!(manager.battery_limit("dev-1"), BatteryAction::Pause);
+        let dev = manager.get_device("dev-1").await.unwrap();
+        assert_eq!(dev.read().await.get_power(0), 0);
+
+        // 限制同时作为设备事件发送
+        loop {
+            if let DeviceEvent::BatteryLimited(action) = device_events.recv().await.unwrap() {
+                assert_eq!(action, BatteryAction::Cap(30));
+                break;
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_safety_limited_power() {
+        let manager = SessionManager::new();
+        manager
+            .add_device(Box::new(MockDevice::new("dev-1", "D1")))
+            .await
+            .unwrap();
+
+        let mut config = AppConfig::default();
+        config.safety.max_power_a = 40;
+        manager.load_config(&config);
+        assert_eq!(manager.safety().max_power_a, 40);
+
+        assert_eq!(manager.set_power("dev-1", 0, 80).await.unwrap(), 40);
+        assert_eq!(manager.set_power("dev-1", 1, 80).await.unwrap(), 80);
+        assert!(matches!(
+            manager.set_power("dev-1", 2, 10).await,
+            Err(CoreError::InvalidChannel(2))
+        ));
+
+        // 已校准时渐变目标取不超过上限的百分比
+        let curve = PowerCurve::new(vec![(0, 0), (100, 100)]).unwrap();
+        manager.set_calibration("D1", Some(curve));
+        manager
+            .start_ramp(
+                "dev-1",
+                0,
+                100,
+                Duration::from_millis(100),
+                Easing::Linear,
+                |_| {},
+            )
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        let dev = manager.get_device("dev-1").await.unwrap();
+        assert_eq!(dev.read().await.get_power(0), 40);
+
+        manager.start("dev-1").await.unwrap();
+        assert_eq!(dev.read().await.state(), DeviceState::Running);
+        manager
+            .set_waveform("dev-1", 1, WaveformConfig::default())
+            .await
+            .unwrap();
+        manager.stop("dev-1").await.unwrap();
+        assert_ne!(dev.read().await.state(), DeviceState::Running);
+        assert!(matches!(
+            manager.start("missing").await,
+            Err(CoreError::DeviceNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_channel_link_limited() {
+        let manager = SessionManager::new();
+        let device = MockDevice::new("dev-1", "D1");
+        let device_tx = device.event_tx.clone();
+        manager.add_device(Box::new(device)).await.unwrap();
+        let dev = manager.get_device("dev-1").await.unwrap();
+
+        // 安全限制锁定时联动不能提高 B 通道
+        let mut config = AppConfig::default();
+        config.safety = SafetyConfig::locked();
+        manager.load_config(&config);
+        let link = ChannelLink::new(1.0, 10).unwrap();
+        manager.set_channel_link("dev-1", Some(link)).await.unwrap();
+        assert_eq!(dev.read().await.channel_link(), Some(link));
+        assert_eq!(dev.read().await.get_power(1), 0);
+        let _ = manager.set_power("dev-1", 0, 50).await.unwrap();
+        let _ = manager.adjust_power("dev-1", 0, 10).await.unwrap();
+        assert_eq!(dev.read().await.get_power(1), 0);
+
+        // 联动的 B 通道按安全限制截断
+        config.safety = SafetyConfig::default();
+        config.safety.max_power_b = 25;
+        manager.load_config(&config);
+        assert_eq!(manager.set_power("dev-1", 0, 10).await.unwrap(), 10);
+        assert_eq!(dev.read().await.get_power(1), 20);
+        assert_eq!(manager.adjust_power("dev-1", 0, 5).await.unwrap(), 15);
+        assert_eq!(dev.read().await.get_power(1), 25);
+        manager
+            .start_ramp(
+                "dev-1",
+                0,
+                60,
+                Duration::from_millis(100),
+                Easing::Linear,
+                |_| {},
+            )
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert_eq!(dev.read().await.get_power(0), 60);
+        assert_eq!(dev.read().await.get_power(1), 25);
+
+        // 低电量限制同样作用于联动的 B 通道
+        config.safety.max_power_b = 100;
+        config.safety.battery.cap_below = Some(20);
+        config.safety.battery.cap_power = 30;
+        manager.load_config(&config);
+        let mut events = manager.subscribe_events();
+        let _ = device_tx.send(DeviceEvent::BatteryUpdated(15));
+        assert_eq!(next_limit(&mut events).await, BatteryAction::Cap(30));
+        manager
+            .set_channel_link("dev-1", Some(ChannelLink::new(2.0, 0).unwrap()))
+            .await
+            .unwrap();
+        assert_eq!(dev.read().await.get_power(1), 30);
+        assert_eq!(manager.set_raw_power("dev-1", 0, 20).await.unwrap(), 20);
+        assert_eq!(dev.read().await.get_power(1), 30);
+
+        // 关闭联动后 B 通道保持不变
+        manager.set_channel_link("dev-1", None).await.unwrap();
+        let _ = manager.set_power("dev-1", 0, 5).await.unwrap();
+        assert_eq!(dev.read().await.get_power(1), 30);
+        assert!(manager.set_channel_link("missing", None).await.is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_snapshot_restore() {
+        let manager = SessionManager::new();
+        manager
+            .add_device(Box::new(MockDevice::new("dev-1", "D1")))
+            .await
+            .unwrap();
+
+        let waveform = WaveformConfig {
+            frequency: 50,
+            ..WaveformConfig::default()
+        };
+        manager
+            .set_waveform("dev-1", 1, waveform.clone())
+            .await
+            .unwrap();
+        manager.set_power("dev-1", 0, 30).await.unwrap();
+        manager.start("dev-1").await.unwrap();
+        manager
+            .set_timer_duration(Duration::from_secs(600))
+            .unwrap();
+
+        let snapshot = manager.snapshot().await;
+        assert_eq!(snapshot.session_id, manager.session_id());
+        assert!(snapshot.timer_remaining_secs.is_some());
+        let device = snapshot.device("dev-1").unwrap();
+        assert_eq!(device.power, [30, 0]);
+        assert_eq!(device.waveforms, [None, Some(waveform.clone())]);
+        assert!(device.running);
+        assert_eq!(device.transport, SnapshotTransport::Other);
+
+        // 新会话中重新连接后从 0 渐变恢复
+        let restored = SessionManager::new();
+        restored
+            .add_device(Box::new(MockDevice::new("dev-1", "D1")))
+            .await
+            .unwrap();
+        let mut missing = snapshot.clone();
+        missing.devices[0].id = "missing".to_string();
+        missing.devices.extend(snapshot.devices.clone());
+
+        let results = restored
+            .restore_snapshot(&missing, Duration::from_secs(1), |_| {})
+            .await;
+        assert!(results["dev-1"].is_ok());
+        assert!(matches!(
+            results["missing"],
+            Err(CoreError::DeviceNotFound(_))
+        ));
+        assert!(restored.is_ramping("dev-1", 0));
+
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        let dev = restored.get_device("dev-1").await.unwrap();
+        let dev = dev.read().await;
+        assert_eq!(dev.get_power(0), 30);
+        assert_eq!(dev.state(), DeviceState::Running);
+        assert!(restored.timer_deadline().is_some());
+        assert_eq!(
+            restored.snapshot().await.devices[0].waveforms[1],
+            Some(waveform)
+        );
+    }
+
+    #[test]
+    fn test_snapshot_transport() {
+        let ble = CoyoteDevice::new("dev-1".to_string(), "47L121000".to_string());
+        assert_eq!(snapshot_transport(ble.as_any()), SnapshotTransport::Ble);
+        let wifi = WsCoyoteDevice::new("ws-1".to_string(), "WiFi".to_string());
+        assert_eq!(snapshot_transport(wifi.as_any()), SnapshotTransport::Wifi);
+
+        // 试运行设备不能按地址重连
+        let recorder = crate::device::DryRunRecorder::new(10);
+        let dry_run = CoyoteDevice::dry_run("dry-run".to_string(), "Dry".to_string(), recorder);
+        assert_eq!(
+            snapshot_transport(dry_run.as_any()),
+            SnapshotTransport::Other
+        );
+    }
+
+    // === 事件日志测试 ===
+
+    #[tokio::test]
+    async fn test_event_log() {
+        use crate::session::log::{EventLog, LogEvent, LogFilter};
+
+        let dir = tempfile::tempdir().unwrap();
+        let manager = SessionManager::with_event_log(dir.path());
+        let mut device = MockDevice::new("dev-1", "D1");
+        device.connect().await.unwrap();
+        manager.add_device(Box::new(device)).await.unwrap();
+
+        let dev = manager.get_device("dev-1").await.unwrap();
+        dev.write().await.set_power(0, 20).await.unwrap();
+        manager.emergency_stop("dev-1").await.unwrap();
+        assert_eq!(dev.read().await.get_power(0), 0);
+        manager.remove_device("dev-1").await.unwrap();
+
+        // 设备事件由后台任务写入
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let path = manager.event_log().unwrap().path();
+        let events: Vec<_> = EventLog::read(path, &LogFilter::default())
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.event)
+            .collect();
+        assert_eq!(events.first(), Some(&LogEvent::DeviceAdded));
+        assert!(events.contains(&LogEvent::PowerChanged {
+            channel: 0,
+            power: 20
+        }));
+        assert!(events.contains(&LogEvent::EmergencyStop));
+        assert!(events.contains(&LogEvent::DeviceRemoved));
+
+        assert!(matches!(
+            manager.emergency_stop("missing").await,
+            Err(CoreError::DeviceNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_emergency_stop_metrics() {
+        let manager = SessionManager::new();
+        let mut device = MockDevice::new("metrics-estop", "D1");
+        device.connect().await.unwrap();
+        manager.add_device(Box::new(device)).await.unwrap();
+
+        manager.emergency_stop("metrics-estop").await.unwrap();
+        assert_eq!(metrics::device("metrics-estop").emergency_stops(), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_stats() {
+        let manager = SessionManager::new();
+        let mut device = MockDevice::new("dev-1", "D1");
+        device.power_b = 10;
+        manager.add_device(Box::new(device)).await.unwrap();
+
+        let dev = manager.get_device("dev-1").await.unwrap();
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        dev.write().await.set_power(0, 40).await.unwrap();
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        manager.emergency_stop("dev-1").await.unwrap();
+        manager.remove_device("dev-1").await.unwrap();
+
+        let stats = manager.device_stats("dev-1").unwrap();
+        assert_eq!(stats.emergency_stops, 1);
+        assert_eq!(stats.channels[0].peak_power, 40);
+        assert_eq!(stats.channels[0].time_at_power.get(&40), Some(&1000));
+        assert!((stats.channels[0].average_power - 20.0).abs() < 1e-9);
+        assert_eq!(stats.channels[1].peak_power, 10);
+        assert_eq!(stats.tracked_ms, 2000);
+
+        // 移出后统计冻结但仍可查询
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        assert_eq!(manager.stats(), vec![stats]);
+        assert!(manager.device_stats("missing").is_none());
+    }
+
+    // === 会话定时测试 ===
+
+    #[tokio::test(start_paused = true)]
+    async fn test_session_timer() {
+        let manager = SessionManager::new();
+        let mut device = MockDevice::new("dev-1", "D1");
+        device.power_a = 40;
+        device.power_b = 20;
+        manager.add_device(Box::new(device)).await.unwrap();
+        assert!(manager.start_all().await.values().all(Result::is_ok));
+
+        let mut events = manager.subscribe_events();
+        manager.set_timer_duration(Duration::from_secs(70)).unwrap();
+        assert!(manager.timer_deadline().is_some());
+
+        let mut received = Vec::new();
+        loop {
+            match events.recv().await.unwrap() {
+                SessionEvent::TimerWarning(remaining) => received.push(remaining),
+                SessionEvent::TimerExpired => break,
+                _ => {}
+            }
+        }
+        assert_eq!(received, TIMER_WARNINGS.to_vec());
+
+        let dev = manager.get_device("dev-1").await.unwrap();
+        let dev = dev.read().await;
+        assert_eq!((dev.get_power(0), dev.get_power(1)), (0, 0));
+        assert_eq!(dev.state(), DeviceState::Connected);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_session_timer() {
+        let manager = SessionManager::new();
+        assert!(manager
+            .set_timer(Utc::now() - chrono::Duration::seconds(1))
+            .is_err());
+
+        manager
+            .set_timer_duration(Duration::from_secs(600))
+            .unwrap();
+        assert!(manager.cancel_timer());
+        assert!(!manager.cancel_timer());
+        assert!(manager.timer_deadline().is_none());
+    }
+
+    // === 热插拔测试 ===
+
+    #[tokio::test]
+    async fn test_watch_adapter() {
+        let manager = SessionManager::new();
+        manager
+            .add_device(Box::new(MockDevice::new("dev-1", "D1")))
+            .await
+            .unwrap();
+        assert!(manager.connect_all().await.values().all(Result::is_ok));
+
+        let mut events = manager.subscribe_events();
+        let (tx, rx) = mpsc::channel(4);
+        let task = manager.watch_adapter(rx);
+        let device = manager.get_device("dev-1").await.unwrap();
+
+        tx.send(AdapterEvent::Disconnected("dev-1".to_string()))
+            .await
+            .unwrap();
+        loop {
+            if let SessionEvent::DeviceLost(id) = events.recv().await.unwrap() {
+                assert_eq!(id, "dev-1");
+                break;
+            }
+        }
+        loop {
+            if let SessionEvent::DeviceStateChanged(_, DeviceState::Disconnected) =
+                events.recv().await.unwrap()
+            {
+                break;
+            }
+        }
+
+        // 重新出现后自动重连
+        tx.send(AdapterEvent::Discovered(ScanResult {
+            id: "dev-1".to_string(),
+            name: "D1".to_string(),
+            address: String::new(),
+            rssi: None,
+            adapter: String::new(),
+        }))
+        .await
+        .unwrap();
+        loop {
+            if let SessionEvent::DeviceStateChanged(_, DeviceState::Connected) =
+                events.recv().await.unwrap()
+            {
+                break;
+            }
+        }
+        assert_eq!(device.read().await.state(), DeviceState::Connected);
+
+        drop(tx);
+        task.await.unwrap();
+    }
+
     // === SessionEvent 测试 ===
 
     #[test]
@@ -596,7 +2704,7 @@ mod tests {
     fn test_session_info_debug() {
         let info = SessionInfo {
             id: "test-id".to_string(),
-            created_at: chrono::Utc::now(),
+            created_at: Utc::now(),
             active_devices: 1,
             total_devices: 2,
         };
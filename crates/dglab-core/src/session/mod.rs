@@ -1,5 +1,18 @@
 //! 会话管理模块
 
+pub mod battery;
+mod hotplug;
+pub mod log;
 pub mod manager;
+pub mod remote;
+pub mod snapshot;
+pub mod stats;
+pub mod timer;
 
-pub use manager::SessionManager;
+pub use battery::{BatteryAction, BatteryHistory, BatteryPolicy, BatterySample};
+pub use log::{EventLog, LogEntry, LogEvent, LogFilter};
+pub use manager::{BulkResults, SessionEvent, SessionManager, DEFAULT_BULK_TIMEOUT};
+pub use remote::{RemoteInvite, RemoteRole, DEFAULT_JOIN_TIMEOUT};
+pub use snapshot::{DeviceSnapshot, SessionSnapshot, SnapshotTransport, DEFAULT_RESTORE_RAMP};
+pub use stats::{ChannelStats, DeviceStats, StatsCollector};
+pub use timer::{parse_duration, parse_stop_time};
@@ -0,0 +1,277 @@
+//! 远程会话
+//!
+//! `dglab bridge --local` 在本机启动服务器并充当 APP，它显示的二维码链接就是邀请链接。
+//! 局域网中的另一个实例凭邀请链接加入，得到一个 [`WsCoyoteDevice`]：
+//!
+//! - 控制端（[`RemoteRole::Controller`]）：以新 clientId 绑定桥接，通过 [`Device`] 接口
+//!   控制对方的主机，与扫码连接的 WiFi 设备用法相同
+//! - 观察端（[`RemoteRole::Viewer`]）：以 `?role=viewer` 连接，只接收对方主机上报的强度，
+//!   强度和波形操作返回 [`CoreError::Rejected`]
+//!
+//! 远程会话不另设协议：类型化的接口只到 [`Device`] 为止，线上仍是 APP 协议的 `msg` 消息
+//! （由 [`StrengthOperation`]、[`PulseData`] 等生成），桥接按 APP 协议处理，
+//! 因此任何 APP 协议的控制端都能绑定同一个桥接。
+//!
+//! 服务器启用 PIN 时，控制端和观察端都必须带 PIN：桥接自身不带 PIN 连接，
+//! 绑定关系中至少一方已授权才会转发控制消息。
+//!
+//! [`StrengthOperation`]: dglab_protocol::wifi::StrengthOperation
+//! [`PulseData`]: dglab_protocol::wifi::PulseData
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use dglab_protocol::wifi::{qr, ServerAddress};
+
+use crate::device::{Device, WsCoyoteDevice};
+use crate::error::{CoreError, Result};
+
+/// 加入会话的默认超时（连接、分配 clientId 和绑定）
+pub const DEFAULT_JOIN_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 等待 clientId 的轮询间隔
+const CLIENT_ID_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 加入会话的角色
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RemoteRole {
+    /// 控制对方的主机
+    #[default]
+    Controller,
+    /// 只查看对方主机的强度
+    Viewer,
+}
+
+impl fmt::Display for RemoteRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Controller => "controller",
+            Self::Viewer => "viewer",
+        })
+    }
+}
+
+impl FromStr for RemoteRole {
+    type Err = CoreError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "controller" => Ok(Self::Controller),
+            "viewer" => Ok(Self::Viewer),
+            other => Err(CoreError::InvalidParameter(format!(
+                "Unknown remote role '{}', expected controller or viewer",
+                other
+            ))),
+        }
+    }
+}
+
+/// 会话邀请：桥接所在的服务器和桥接的 clientId
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteInvite {
+    /// 服务器地址
+    server: ServerAddress,
+    /// 桥接（APP 端）的 clientId
+    target_id: String,
+}
+
+impl RemoteInvite {
+    /// 解析邀请链接（桥接的二维码内容，或 `ws://host:port/<clientId>`）
+    pub fn parse(link: &str) -> Result<Self> {
+        let (server, target_id) = qr::parse_url(link)
+            .map_err(|e| CoreError::InvalidParameter(format!("Invalid invite link: {}", e)))?;
+        Ok(Self { server, target_id })
+    }
+
+    /// 设置服务器 PIN
+    pub fn with_pin(mut self, pin: impl Into<String>) -> Self {
+        self.server = self.server.with_auth_token(pin);
+        self
+    }
+
+    /// 接受自签名证书（仅对 `wss` 生效）
+    pub fn accept_invalid_certs(mut self, accept: bool) -> Self {
+        self.server = self.server.accept_invalid_certs(accept);
+        self
+    }
+
+    /// 服务器地址
+    pub fn server(&self) -> &ServerAddress {
+        &self.server
+    }
+
+    /// 桥接的 clientId
+    pub fn target_id(&self) -> &str {
+        &self.target_id
+    }
+
+    /// 本地设备 ID，如 `remote-3ab0fb8a`、`viewer-3ab0fb8a`
+    pub fn device_id(&self, role: RemoteRole) -> String {
+        let short = self.target_id.split('-').next().unwrap_or_default();
+        match role {
+            RemoteRole::Controller => format!("remote-{}", short),
+            RemoteRole::Viewer => format!("viewer-{}", short),
+        }
+    }
+}
+
+impl FromStr for RemoteInvite {
+    type Err = CoreError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+/// 加入会话，返回已连接的设备
+///
+/// 控制端等待绑定成功后返回；桥接已离线或已被其他控制端绑定时返回错误。
+/// 观察端连接后立即返回，收到桥接上报的强度前强度为 0。
+pub async fn join(
+    invite: &RemoteInvite,
+    role: RemoteRole,
+    timeout: Duration,
+) -> Result<WsCoyoteDevice> {
+    let server = match role {
+        RemoteRole::Controller => invite.server.clone(),
+        RemoteRole::Viewer => invite
+            .server
+            .clone()
+            .as_viewer(Some(invite.target_id.clone())),
+    };
+    let device_id = invite.device_id(role);
+    info!("Joining remote session {} as {}", invite.server, role);
+
+    let mut device = WsCoyoteDevice::with_server(device_id.clone(), device_id, server);
+    tokio::time::timeout(timeout, device.connect())
+        .await
+        .map_err(|_| CoreError::Timeout(format!("Connecting to {}", invite.server)))??;
+    if role == RemoteRole::Viewer {
+        return Ok(device);
+    }
+
+    let bound = tokio::time::timeout(timeout, async {
+        // 服务器分配 clientId 后才能绑定
+        while device.client_id().await.is_none() {
+            tokio::time::sleep(CLIENT_ID_POLL_INTERVAL).await;
+        }
+        device.rebind(&invite.target_id, timeout).await
+    })
+    .await;
+    match bound {
+        Ok(Ok(target_id)) => {
+            info!("Joined remote session, bound to {}", target_id);
+            Ok(device)
+        }
+        Ok(Err(e)) => {
+            let _ = device.disconnect().await;
+            Err(e)
+        }
+        Err(_) => {
+            let _ = device.disconnect().await;
+            Err(CoreError::Timeout(format!(
+                "Binding to {}",
+                invite.target_id
+            )))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dglab_protocol::wifi::{MessageType, WsClient, WsEvent, WsMessage, WsServer};
+
+    const BRIDGE: &str = "3ab0fb8a-3c84-4f58-9a54-6d8e2f4c1b11";
+
+    #[test]
+    fn test_invite_parse() {
+        let invite: RemoteInvite = qr::generate_url("ws://192.168.1.10:9999", BRIDGE)
+            .parse()
+            .unwrap();
+        assert_eq!(invite.server().as_str(), "ws://192.168.1.10:9999");
+        assert_eq!(invite.target_id(), BRIDGE);
+        assert_eq!(invite.device_id(RemoteRole::Controller), "remote-3ab0fb8a");
+        assert_eq!(invite.device_id(RemoteRole::Viewer), "viewer-3ab0fb8a");
+
+        let invite = invite.with_pin("4821");
+        assert_eq!(invite.server().auth_token(), Some("4821"));
+
+        assert!(RemoteInvite::parse("ws://192.168.1.10:9999").is_err());
+        assert_eq!("Viewer".parse::<RemoteRole>().unwrap(), RemoteRole::Viewer);
+        assert!("owner".parse::<RemoteRole>().is_err());
+    }
+
+    /// 等待 APP 端收到 `msg` 消息
+    async fn recv_msg(app: &mut WsClient) -> WsMessage {
+        loop {
+            match app.recv_event().await.unwrap() {
+                Some(WsEvent::Other(msg)) if msg.is_msg() => return msg,
+                Some(_) => {}
+                None => panic!("app connection closed"),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_join_controller_and_viewer() {
+        let server = WsServer::new("127.0.0.1:0".to_string());
+        let listener = server.bind().await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let _server = tokio::spawn(async move { server.serve(listener).await });
+
+        // 桥接（APP 端）
+        let mut app = WsClient::connect(&url).await.unwrap();
+        let app_id = loop {
+            if let Some(WsEvent::ClientId(id)) = app.recv_event().await.unwrap() {
+                break id;
+            }
+        };
+        let invite = RemoteInvite::parse(&qr::generate_url(&url, &app_id)).unwrap();
+
+        // 控制端绑定后通过 Device 接口控制
+        let mut controller = join(&invite, RemoteRole::Controller, DEFAULT_JOIN_TIMEOUT)
+            .await
+            .unwrap();
+        let controller_id = controller.client_id().await.unwrap();
+        controller.set_power(0, 10).await.unwrap();
+        assert_eq!(recv_msg(&mut app).await.message, "strength-1+2+10");
+
+        // 观察端收到 APP 上报的强度，不能控制
+        let mut viewer = join(&invite, RemoteRole::Viewer, DEFAULT_JOIN_TIMEOUT)
+            .await
+            .unwrap();
+        assert!(viewer.is_viewer());
+        assert!(viewer.set_power(0, 20).await.is_err());
+        let report = WsMessage::new(
+            MessageType::Msg,
+            controller_id.as_str(),
+            app_id.as_str(),
+            "strength-10+0+100+100",
+        );
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(2);
+        while viewer.get_power(0) != 10 {
+            app.send(&report).await.unwrap();
+            assert!(
+                tokio::time::Instant::now() < deadline,
+                "viewer got no report"
+            );
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+
+        // 已被绑定的桥接不能再加入控制端
+        assert!(
+            join(&invite, RemoteRole::Controller, Duration::from_secs(2))
+                .await
+                .is_err()
+        );
+
+        viewer.disconnect().await.unwrap();
+        controller.disconnect().await.unwrap();
+    }
+}
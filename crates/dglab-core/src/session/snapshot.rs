@@ -0,0 +1,214 @@
+//! 会话快照
+//!
+//! 应用退出时把会话中的设备、通道强度、波形和会话定时写入配置目录下的
+//! `session_snapshot.json`，重启后重新连接设备，经用户确认后由
+//! [`SessionManager::restore_snapshot`] 从零渐变恢复强度。
+//!
+//! [`SessionManager::restore_snapshot`]: crate::session::SessionManager::restore_snapshot
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info};
+
+use crate::device::traits::WaveformConfig;
+use crate::error::{CoreError, Result};
+
+/// 快照文件名（与配置文件同目录）
+const SNAPSHOT_FILE: &str = "session_snapshot.json";
+
+/// 恢复强度时的默认渐变时长
+pub const DEFAULT_RESTORE_RAMP: Duration = Duration::from_secs(10);
+
+/// 设备的连接方式，决定重启后如何重连
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SnapshotTransport {
+    /// 直连的蓝牙设备，可按地址重连
+    Ble,
+    /// WiFi 设备，需在 APP 中重新扫码
+    Wifi,
+    /// 模拟器、桥接、试运行等需手动重新创建的设备
+    #[default]
+    Other,
+}
+
+/// 快照中的设备
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeviceSnapshot {
+    /// 设备 ID
+    pub id: String,
+    /// 设备名称
+    pub name: String,
+    /// 设备类型（如 `Coyote V3`、`Coyote-WiFi`）
+    pub device_type: String,
+    /// 连接方式（旧快照没有该字段，按 [`SnapshotTransport::Other`] 处理）
+    #[serde(default)]
+    pub transport: SnapshotTransport,
+    /// A、B 通道强度
+    pub power: [u8; 2],
+    /// A、B 通道最近设置的波形
+    #[serde(default)]
+    pub waveforms: [Option<WaveformConfig>; 2],
+    /// 保存时是否正在输出
+    #[serde(default)]
+    pub running: bool,
+}
+
+impl DeviceSnapshot {
+    /// 是否为可直接按地址重连的蓝牙设备（WiFi 设备需在 APP 中重新扫码）
+    pub fn is_ble(&self) -> bool {
+        self.transport == SnapshotTransport::Ble
+    }
+}
+
+/// 会话快照
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionSnapshot {
+    /// 原会话 ID
+    pub session_id: String,
+    /// 原会话创建时间
+    pub created_at: DateTime<Utc>,
+    /// 保存时间
+    pub saved_at: DateTime<Utc>,
+    /// 会话中的设备
+    pub devices: Vec<DeviceSnapshot>,
+    /// 保存时会话定时的剩余时长（秒，未设置定时为空）
+    #[serde(default)]
+    pub timer_remaining_secs: Option<u64>,
+}
+
+impl SessionSnapshot {
+    /// 默认快照文件路径
+    pub fn default_path() -> Result<PathBuf> {
+        let path = dirs::config_dir()
+            .ok_or_else(|| CoreError::Other("Could not find config directory".to_string()))?
+            .join("dglab")
+            .join(SNAPSHOT_FILE);
+
+        Ok(path)
+    }
+
+    /// 快照是否没有设备（无需恢复）
+    pub fn is_empty(&self) -> bool {
+        self.devices.is_empty()
+    }
+
+    /// 会话已运行的时长（创建到保存）
+    pub fn elapsed(&self) -> Duration {
+        (self.saved_at - self.created_at)
+            .to_std()
+            .unwrap_or_default()
+    }
+
+    /// 快照中的设备
+    pub fn device(&self, device_id: &str) -> Option<&DeviceSnapshot> {
+        self.devices.iter().find(|d| d.id == device_id)
+    }
+
+    /// 从文件加载，文件不存在时返回 `None`
+    pub async fn load(path: &Path) -> Result<Option<Self>> {
+        if !path.exists() {
+            debug!("No session snapshot at {:?}", path);
+            return Ok(None);
+        }
+
+        let content = tokio::fs::read_to_string(path).await?;
+        let snapshot = serde_json::from_str(&content)?;
+        Ok(Some(snapshot))
+    }
+
+    /// 保存到文件
+    pub async fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+
+        let content = serde_json::to_string_pretty(self)?;
+        tokio::fs::write(path, content).await?;
+
+        info!(
+            "Saved session snapshot ({} devices) to {:?}",
+            self.devices.len(),
+            path
+        );
+        Ok(())
+    }
+
+    /// 删除快照文件（已恢复或用户放弃恢复），文件不存在时忽略
+    pub async fn remove(path: &Path) -> Result<()> {
+        match tokio::fs::remove_file(path).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn sample() -> SessionSnapshot {
+        let created_at = Utc::now();
+        SessionSnapshot {
+            session_id: "session-1".to_string(),
+            created_at,
+            saved_at: created_at + chrono::Duration::seconds(90),
+            devices: vec![DeviceSnapshot {
+                id: "dev-1".to_string(),
+                name: "47L121000".to_string(),
+                device_type: "Coyote V3".to_string(),
+                transport: SnapshotTransport::Ble,
+                power: [30, 12],
+                waveforms: [Some(WaveformConfig::default()), None],
+                running: true,
+            }],
+            timer_remaining_secs: Some(600),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_save_load_remove() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("nested").join(SNAPSHOT_FILE);
+        assert_eq!(SessionSnapshot::load(&path).await.unwrap(), None);
+
+        let snapshot = sample();
+        snapshot.save(&path).await.unwrap();
+        let loaded = SessionSnapshot::load(&path).await.unwrap().unwrap();
+        assert_eq!(loaded, snapshot);
+        assert_eq!(loaded.elapsed(), Duration::from_secs(90));
+        assert_eq!(loaded.device("dev-1").unwrap().power, [30, 12]);
+        assert!(loaded.device("missing").is_none());
+        assert!(loaded.devices[0].is_ble());
+
+        SessionSnapshot::remove(&path).await.unwrap();
+        assert!(!path.exists());
+        // 重复删除不报错
+        SessionSnapshot::remove(&path).await.unwrap();
+    }
+
+    #[test]
+    fn test_optional_fields_default() {
+        let json = r#"{
+            "session_id": "s",
+            "created_at": "2024-01-01T00:00:00Z",
+            "saved_at": "2024-01-01T00:10:00Z",
+            "devices": [
+                {"id": "dev-1", "name": "D1", "device_type": "Coyote-WiFi", "power": [5, 0]}
+            ]
+        }"#;
+        let snapshot: SessionSnapshot = serde_json::from_str(json).unwrap();
+        let device = &snapshot.devices[0];
+        assert_eq!(device.waveforms, [None, None]);
+        assert!(!device.running);
+        assert_eq!(device.transport, SnapshotTransport::Other);
+        assert!(!device.is_ble());
+        assert_eq!(snapshot.timer_remaining_secs, None);
+        assert_eq!(snapshot.elapsed(), Duration::from_secs(600));
+    }
+}
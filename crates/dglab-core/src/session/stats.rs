@@ -0,0 +1,402 @@
+//! 会话统计
+//!
+//! 按设备累计两个通道在各强度下的停留时长、峰值强度、时间加权平均强度和紧急停止次数，
+//! 并记录电量历史（见 [`BatteryHistory`]）。
+//! 设备加入会话时开始计时，移出后统计冻结但仍可查询，重新加入时继续累计。
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+
+use super::battery::{BatteryHistory, BatterySample};
+
+/// 单通道统计
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChannelStats {
+    /// 峰值强度
+    pub peak_power: u8,
+    /// 时间加权平均强度
+    pub average_power: f64,
+    /// 各强度下的累计时长（强度 → 毫秒），只包含停留过的强度
+    pub time_at_power: BTreeMap<u8, u64>,
+}
+
+/// 设备统计
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeviceStats {
+    /// 设备 ID
+    pub device_id: String,
+    /// 累计统计时长（毫秒）
+    pub tracked_ms: u64,
+    /// 紧急停止次数
+    pub emergency_stops: u32,
+    /// A、B 通道统计
+    pub channels: [ChannelStats; 2],
+    /// 电量变化历史
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub battery: Vec<BatterySample>,
+    /// 按放电速率估算的剩余使用时间（毫秒）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub battery_remaining_ms: Option<u64>,
+}
+
+/// 单通道计时状态
+#[derive(Debug)]
+struct ChannelTracker {
+    /// 当前强度
+    power: u8,
+    /// 当前强度的开始时间
+    since: Instant,
+    /// 峰值强度
+    peak: u8,
+    /// 已结束区间的累计时长
+    time_at_power: BTreeMap<u8, Duration>,
+}
+
+impl ChannelTracker {
+    fn new(power: u8, now: Instant) -> Self {
+        Self {
+            power,
+            since: now,
+            peak: power,
+            time_at_power: BTreeMap::new(),
+        }
+    }
+
+    /// 结束当前区间并切换到新强度
+    fn set(&mut self, power: u8, now: Instant) {
+        self.close(now);
+        self.power = power;
+        self.peak = self.peak.max(power);
+    }
+
+    /// 把当前区间累计到当前强度下
+    fn close(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.since);
+        if !elapsed.is_zero() {
+            *self.time_at_power.entry(self.power).or_default() += elapsed;
+        }
+        self.since = now;
+    }
+
+    /// 生成统计（`now` 为 `None` 表示已冻结，不计入当前区间）
+    fn snapshot(&self, now: Option<Instant>) -> ChannelStats {
+        let mut time_at_power = self.time_at_power.clone();
+        if let Some(now) = now {
+            let elapsed = now.saturating_duration_since(self.since);
+            if !elapsed.is_zero() {
+                *time_at_power.entry(self.power).or_default() += elapsed;
+            }
+        }
+
+        let total: f64 = time_at_power.values().map(Duration::as_secs_f64).sum();
+        let weighted: f64 = time_at_power
+            .iter()
+            .map(|(&power, d)| power as f64 * d.as_secs_f64())
+            .sum();
+        let average_power = if total > 0.0 {
+            weighted / total
+        } else {
+            self.power as f64
+        };
+
+        ChannelStats {
+            peak_power: self.peak,
+            average_power,
+            time_at_power: time_at_power
+                .into_iter()
+                .map(|(power, d)| (power, d.as_millis() as u64))
+                .collect(),
+        }
+    }
+}
+
+/// 单设备计时状态
+#[derive(Debug)]
+struct DeviceTracker {
+    /// 通道计时
+    channels: [ChannelTracker; 2],
+    /// 紧急停止次数
+    emergency_stops: u32,
+    /// 电量历史
+    battery: BatteryHistory,
+    /// 设备是否仍在会话中（移出后冻结）
+    active: bool,
+}
+
+impl DeviceTracker {
+    fn snapshot(&self, device_id: &str, now: Instant) -> DeviceStats {
+        let now = self.active.then_some(now);
+        let channels = [
+            self.channels[0].snapshot(now),
+            self.channels[1].snapshot(now),
+        ];
+        // 两个通道计时区间相同，取 A 通道的总时长
+        let tracked_ms = channels[0].time_at_power.values().sum();
+
+        DeviceStats {
+            device_id: device_id.to_string(),
+            tracked_ms,
+            emergency_stops: self.emergency_stops,
+            channels,
+            battery: self.battery.samples().to_vec(),
+            battery_remaining_ms: self
+                .battery
+                .estimate_remaining(now.unwrap_or_else(Instant::now))
+                .map(|d| d.as_millis() as u64),
+        }
+    }
+}
+
+/// 会话统计收集器
+#[derive(Debug, Default)]
+pub struct StatsCollector {
+    /// 设备 ID → 计时状态
+    devices: Mutex<HashMap<String, DeviceTracker>>,
+}
+
+impl StatsCollector {
+    /// 创建空收集器
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 开始统计设备（已统计过的设备继续累计）
+    pub fn track(&self, device_id: &str, power_a: u8, power_b: u8) {
+        let now = Instant::now();
+        let mut devices = self.devices();
+        match devices.get_mut(device_id) {
+            Some(tracker) => {
+                for (channel, power) in tracker.channels.iter_mut().zip([power_a, power_b]) {
+                    channel.since = now;
+                    channel.set(power, now);
+                }
+                tracker.active = true;
+            }
+            None => {
+                let _ = devices.insert(
+                    device_id.to_string(),
+                    DeviceTracker {
+                        channels: [
+                            ChannelTracker::new(power_a, now),
+                            ChannelTracker::new(power_b, now),
+                        ],
+                        emergency_stops: 0,
+                        battery: BatteryHistory::new(now),
+                        active: true,
+                    },
+                );
+            }
+        }
+    }
+
+    /// 停止统计设备，已有统计保留
+    pub fn untrack(&self, device_id: &str) {
+        let now = Instant::now();
+        if let Some(tracker) = self.devices().get_mut(device_id) {
+            for channel in &mut tracker.channels {
+                channel.close(now);
+            }
+            tracker.active = false;
+        }
+    }
+
+    /// 记录通道强度变化
+    pub fn record_power(&self, device_id: &str, channel: u8, power: u8) {
+        let now = Instant::now();
+        if let Some(tracker) = self
+            .devices()
+            .get_mut(device_id)
+            .filter(|t| t.active)
+            .and_then(|t| t.channels.get_mut(channel as usize))
+        {
+            tracker.set(power, now);
+        }
+    }
+
+    /// 记录电量，返回按放电速率估算的剩余使用时间
+    pub fn record_battery(&self, device_id: &str, level: u8) -> Option<Duration> {
+        let now = Instant::now();
+        let mut devices = self.devices();
+        let tracker = devices.get_mut(device_id).filter(|t| t.active)?;
+        let _ = tracker.battery.record(level, now);
+        tracker.battery.estimate_remaining(now)
+    }
+
+    /// 按放电速率估算的剩余使用时间
+    pub fn battery_remaining(&self, device_id: &str) -> Option<Duration> {
+        let now = Instant::now();
+        self.devices()
+            .get(device_id)
+            .and_then(|t| t.battery.estimate_remaining(now))
+    }
+
+    /// 记录一次紧急停止
+    pub fn record_emergency_stop(&self, device_id: &str) {
+        if let Some(tracker) = self.devices().get_mut(device_id) {
+            tracker.emergency_stops += 1;
+        }
+    }
+
+    /// 获取设备统计
+    pub fn get(&self, device_id: &str) -> Option<DeviceStats> {
+        let now = Instant::now();
+        self.devices()
+            .get(device_id)
+            .map(|tracker| tracker.snapshot(device_id, now))
+    }
+
+    /// 获取所有设备统计（按设备 ID 排序）
+    pub fn all(&self) -> Vec<DeviceStats> {
+        let now = Instant::now();
+        let mut stats: Vec<_> = self
+            .devices()
+            .iter()
+            .map(|(id, tracker)| tracker.snapshot(id, now))
+            .collect();
+        stats.sort_by(|a, b| a.device_id.cmp(&b.device_id));
+        stats
+    }
+
+    /// 获取计时状态表（锁中毒时继续使用内部数据）
+    fn devices(&self) -> MutexGuard<'_, HashMap<String, DeviceTracker>> {
+        self.devices
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::time::advance;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_time_at_power_and_average() {
+        let stats = StatsCollector::new();
+        stats.track("dev", 0, 0);
+
+        advance(Duration::from_secs(1)).await;
+        stats.record_power("dev", 0, 20);
+        advance(Duration::from_secs(3)).await;
+        stats.record_power("dev", 0, 40);
+        advance(Duration::from_secs(1)).await;
+        stats.record_power("dev", 0, 0);
+
+        let s = stats.get("dev").unwrap();
+        let a = &s.channels[0];
+        assert_eq!(a.peak_power, 40);
+        assert_eq!(a.time_at_power.get(&0), Some(&1000));
+        assert_eq!(a.time_at_power.get(&20), Some(&3000));
+        assert_eq!(a.time_at_power.get(&40), Some(&1000));
+        // (20×3 + 40×1) / 5
+        assert!((a.average_power - 20.0).abs() < 1e-9);
+        assert_eq!(s.tracked_ms, 5000);
+
+        // 当前区间随时间增长
+        advance(Duration::from_secs(5)).await;
+        let s = stats.get("dev").unwrap();
+        assert_eq!(s.channels[0].time_at_power.get(&0), Some(&6000));
+        assert!((s.channels[0].average_power - 10.0).abs() < 1e-9);
+
+        let b = &s.channels[1];
+        assert_eq!(b.peak_power, 0);
+        assert_eq!(b.average_power, 0.0);
+        assert_eq!(b.time_at_power.get(&0), Some(&10000));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_untrack_freezes_and_track_resumes() {
+        let stats = StatsCollector::new();
+        stats.track("dev", 10, 0);
+        advance(Duration::from_secs(2)).await;
+        stats.untrack("dev");
+
+        // 移出后的强度变化和时间不计入
+        stats.record_power("dev", 0, 100);
+        advance(Duration::from_secs(10)).await;
+        let s = stats.get("dev").unwrap();
+        assert_eq!(s.tracked_ms, 2000);
+        assert_eq!(s.channels[0].peak_power, 10);
+
+        stats.track("dev", 30, 0);
+        advance(Duration::from_secs(1)).await;
+        let s = stats.get("dev").unwrap();
+        assert_eq!(s.tracked_ms, 3000);
+        assert_eq!(s.channels[0].peak_power, 30);
+        assert_eq!(s.channels[0].time_at_power.get(&30), Some(&1000));
+    }
+
+    #[tokio::test]
+    async fn test_emergency_stops_and_unknown_device() {
+        let stats = StatsCollector::new();
+        stats.record_emergency_stop("missing");
+        stats.record_power("missing", 0, 50);
+        assert!(stats.get("missing").is_none());
+
+        stats.track("b", 0, 0);
+        stats.track("a", 0, 0);
+        stats.record_emergency_stop("a");
+        stats.record_emergency_stop("a");
+        // 无效通道被忽略
+        stats.record_power("a", 5, 50);
+
+        let all = stats.all();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].device_id, "a");
+        assert_eq!(all[0].emergency_stops, 2);
+        assert_eq!(all[1].emergency_stops, 0);
+        assert_eq!(all[0].channels[0].peak_power, 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_battery_history() {
+        let stats = StatsCollector::new();
+        assert_eq!(stats.record_battery("dev", 90), None);
+
+        stats.track("dev", 0, 0);
+        assert_eq!(stats.record_battery("dev", 90), None);
+        advance(Duration::from_secs(600)).await;
+        assert_eq!(stats.record_battery("dev", 89), None);
+        advance(Duration::from_secs(600)).await;
+        // 每 10 分钟 1%：剩余 88% 需要 880 分钟
+        let remaining = stats.record_battery("dev", 88).unwrap();
+        assert!((remaining.as_secs_f64() - 880.0 * 60.0).abs() < 1.0);
+
+        let s = stats.get("dev").unwrap();
+        assert_eq!(s.battery.len(), 3);
+        assert_eq!(s.battery[2].elapsed_ms, 1_200_000);
+        assert!(s.battery_remaining_ms.is_some());
+    }
+
+    #[test]
+    fn test_stats_serialize() {
+        let stats = DeviceStats {
+            device_id: "dev".to_string(),
+            tracked_ms: 1000,
+            emergency_stops: 1,
+            channels: [
+                ChannelStats {
+                    peak_power: 20,
+                    average_power: 20.0,
+                    time_at_power: BTreeMap::from([(20, 1000)]),
+                },
+                ChannelStats {
+                    peak_power: 0,
+                    average_power: 0.0,
+                    time_at_power: BTreeMap::from([(0, 1000)]),
+                },
+            ],
+            battery: Vec::new(),
+            battery_remaining_ms: None,
+        };
+        let json = serde_json::to_string(&stats).unwrap();
+        assert!(json.contains("\"time_at_power\":{\"20\":1000}"));
+        assert!(!json.contains("battery"));
+        let parsed: DeviceStats = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, stats);
+    }
+}
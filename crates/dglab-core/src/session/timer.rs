@@ -0,0 +1,169 @@
+//! 会话定时
+//!
+//! 到达最长运行时长或指定停止时间后，所有设备的强度渐变归零并停止输出，
+//! 结束前 60 秒和 10 秒各发送一次警告事件。
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Local, NaiveTime, Utc};
+use tokio::sync::{broadcast, RwLock};
+use tokio::time::Instant;
+use tracing::{info, warn};
+
+use super::log::{EventLog, LogEvent};
+use super::manager::{DeviceMap, RampMap, SessionEvent};
+use crate::device::{ramp_power, Device, Easing};
+use crate::error::{CoreError, Result};
+
+/// 结束前发送警告的时间点
+pub const TIMER_WARNINGS: [Duration; 2] = [Duration::from_secs(60), Duration::from_secs(10)];
+
+/// 到时后强度归零的渐变时长
+pub const SHUTDOWN_RAMP: Duration = Duration::from_secs(3);
+
+/// 共享设备
+type SharedDevice = Arc<RwLock<Box<dyn Device>>>;
+
+/// 解析时长，如 `90s`、`20m`、`1h30m`，纯数字按秒计
+pub fn parse_duration(s: &str) -> Result<Duration> {
+    let invalid = || CoreError::InvalidParameter(format!("Invalid duration: {}", s));
+    let s = s.trim();
+    if s.is_empty() {
+        return Err(invalid());
+    }
+    if let Ok(secs) = s.parse::<u64>() {
+        return Ok(Duration::from_secs(secs));
+    }
+
+    let mut total = 0u64;
+    let mut digits = String::new();
+    for c in s.chars() {
+        if c.is_ascii_digit() {
+            digits.push(c);
+            continue;
+        }
+        let unit = match c.to_ascii_lowercase() {
+            'h' => 3600,
+            'm' => 60,
+            's' => 1,
+            _ => return Err(invalid()),
+        };
+        let value: u64 = digits.parse().map_err(|_| invalid())?;
+        total = value
+            .checked_mul(unit)
+            .and_then(|v| total.checked_add(v))
+            .ok_or_else(invalid)?;
+        digits.clear();
+    }
+    if !digits.is_empty() {
+        return Err(invalid());
+    }
+
+    Ok(Duration::from_secs(total))
+}
+
+/// 解析停止时间：RFC 3339 时间戳，或本地时间 `HH:MM`（已过则取次日）
+pub fn parse_stop_time(s: &str) -> Result<DateTime<Utc>> {
+    let s = s.trim();
+    if let Ok(time) = DateTime::parse_from_rfc3339(s) {
+        return Ok(time.with_timezone(&Utc));
+    }
+
+    let time = NaiveTime::parse_from_str(s, "%H:%M")
+        .map_err(|_| CoreError::InvalidParameter(format!("Invalid stop time: {}", s)))?;
+    let now = Local::now();
+    let mut date = now.date_naive();
+    if time <= now.time() {
+        date = date.succ_opt().unwrap_or(date);
+    }
+    date.and_time(time)
+        .and_local_timezone(Local)
+        .earliest()
+        .map(|time| time.with_timezone(&Utc))
+        .ok_or_else(|| CoreError::InvalidParameter(format!("Invalid local time: {}", s)))
+}
+
+/// 等待到 `stop_at`，期间发送警告事件，到时后取消进行中的渐变并关闭所有设备
+pub(super) async fn run(
+    stop_at: DateTime<Utc>,
+    devices: Arc<RwLock<DeviceMap>>,
+    ramps: Arc<Mutex<RampMap>>,
+    event_tx: broadcast::Sender<SessionEvent>,
+    event_log: Option<Arc<EventLog>>,
+) {
+    let remaining = (stop_at - Utc::now()).to_std().unwrap_or_default();
+    let deadline = Instant::now() + remaining;
+
+    for warning in TIMER_WARNINGS {
+        if remaining <= warning {
+            continue;
+        }
+        tokio::time::sleep_until(deadline - warning).await;
+        info!("Session ends in {:?}", warning);
+        let _ = event_tx.send(SessionEvent::TimerWarning(warning));
+    }
+
+    tokio::time::sleep_until(deadline).await;
+    info!("Session time limit reached, shutting down devices");
+    if let Some(log) = &event_log {
+        log.record(None, LogEvent::TimerExpired);
+    }
+    for (_, task) in ramps
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .drain()
+    {
+        task.abort();
+    }
+    let devices: Vec<SharedDevice> = devices.read().await.values().cloned().collect();
+    let _ = futures::future::join_all(devices.iter().map(shutdown)).await;
+    let _ = event_tx.send(SessionEvent::TimerExpired);
+}
+
+/// 双通道强度渐变归零后停止输出，渐变失败时直接归零
+async fn shutdown(device: &SharedDevice) {
+    let (a, b) = tokio::join!(
+        ramp_power(device, 0, 0, SHUTDOWN_RAMP, Easing::Linear, |_| {}),
+        ramp_power(device, 1, 0, SHUTDOWN_RAMP, Easing::Linear, |_| {}),
+    );
+
+    let mut dev = device.write().await;
+    if let Err(e) = a.and(b) {
+        warn!("Failed to ramp down {}: {}, zeroing", dev.id(), e);
+        let _ = dev.set_power(0, 0).await;
+        let _ = dev.set_power(1, 0).await;
+    }
+    if let Err(e) = dev.stop().await {
+        warn!("Failed to stop {}: {}", dev.id(), e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("90").unwrap(), Duration::from_secs(90));
+        assert_eq!(parse_duration("20m").unwrap(), Duration::from_secs(1200));
+        assert_eq!(parse_duration("1h30m").unwrap(), Duration::from_secs(5400));
+        assert_eq!(parse_duration("2H5s").unwrap(), Duration::from_secs(7205));
+        for s in ["", "m", "10x", "5m3", "1.5h"] {
+            assert!(parse_duration(s).is_err(), "{}", s);
+        }
+    }
+
+    #[test]
+    fn test_parse_stop_time() {
+        let time = parse_stop_time("2030-01-01T12:00:00+08:00").unwrap();
+        assert_eq!(time.to_rfc3339(), "2030-01-01T04:00:00+00:00");
+
+        // 本地时间总是落在未来 24 小时内
+        let time = parse_stop_time("00:00").unwrap();
+        let ahead = time - Utc::now();
+        assert!(ahead > chrono::Duration::zero() && ahead <= chrono::Duration::days(1));
+
+        assert!(parse_stop_time("25:00").is_err());
+    }
+}
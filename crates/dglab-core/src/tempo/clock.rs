@@ -0,0 +1,150 @@
+//! 节拍时钟
+//!
+//! 按配置的来源创建时钟，节拍状态通过 watch 通道发布；时钟释放后外部连接随之关闭。
+
+use tokio::sync::watch;
+use tracing::info;
+
+use super::{TempoConfig, TempoSource, TempoState};
+use crate::error::{CoreError, Result};
+
+/// 节拍时钟
+#[derive(Debug)]
+pub struct TempoClock {
+    /// 节拍来源
+    source: TempoSource,
+    /// 状态发布端（内部时钟和 MIDI 线程共用）
+    tx: watch::Sender<TempoState>,
+    /// 状态接收端（保持通道打开）
+    rx: watch::Receiver<TempoState>,
+    /// Link 轮询任务
+    task: Option<tokio::task::JoinHandle<()>>,
+}
+
+impl TempoClock {
+    /// 固定 BPM 的内部时钟，从当前时刻第 0 拍开始
+    pub fn internal(bpm: f64) -> Self {
+        let (tx, rx) = watch::channel(TempoState::new(bpm));
+        Self {
+            source: TempoSource::Internal,
+            tx,
+            rx,
+            task: None,
+        }
+    }
+
+    /// 按配置创建时钟
+    ///
+    /// 外部来源所需的特性未启用或连接失败时返回错误。
+    pub fn start(config: &TempoConfig) -> Result<Self> {
+        config.validate()?;
+        let bpm = f64::from(config.bpm);
+
+        match config.source {
+            TempoSource::Internal => {
+                info!("Using internal tempo {} BPM", config.bpm);
+                Ok(Self::internal(bpm))
+            }
+            #[cfg(feature = "midi")]
+            TempoSource::Midi => {
+                let mut clock = Self::internal(bpm);
+                clock.source = TempoSource::Midi;
+                let port =
+                    super::midi::connect(config.midi_port.as_deref(), bpm, clock.tx.clone())?;
+                info!("Following MIDI clock on '{}'", port);
+                Ok(clock)
+            }
+            #[cfg(feature = "link")]
+            TempoSource::Link => {
+                let mut clock = Self::internal(bpm);
+                clock.source = TempoSource::Link;
+                clock.task = Some(super::link::spawn(
+                    bpm,
+                    f64::from(config.beats_per_bar),
+                    clock.tx.clone(),
+                ));
+                info!("Joined Ableton Link session");
+                Ok(clock)
+            }
+            #[allow(unreachable_patterns)]
+            source => Err(CoreError::ConfigError(format!(
+                "Tempo source '{}' requires the '{}' feature",
+                source, source
+            ))),
+        }
+    }
+
+    /// 节拍来源
+    pub fn source(&self) -> TempoSource {
+        self.source
+    }
+
+    /// 当前节拍状态
+    pub fn state(&self) -> TempoState {
+        *self.rx.borrow()
+    }
+
+    /// 订阅节拍状态变化
+    pub fn subscribe(&self) -> watch::Receiver<TempoState> {
+        self.tx.subscribe()
+    }
+
+    /// 修改内部时钟的速度（保持当前节拍位置）
+    pub fn set_bpm(&self, bpm: f64) -> Result<()> {
+        if self.source != TempoSource::Internal {
+            return Err(CoreError::InvalidParameter(format!(
+                "Tempo follows the {} clock and cannot be set",
+                self.source
+            )));
+        }
+        let now = std::time::Instant::now();
+        self.tx.send_modify(|state| {
+            *state = TempoState {
+                bpm,
+                beat: state.beat_at(now),
+                at: now,
+                running: state.running,
+            };
+        });
+        Ok(())
+    }
+}
+
+impl Drop for TempoClock {
+    fn drop(&mut self) {
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_internal_clock() {
+        let clock = TempoClock::internal(120.0);
+        assert_eq!(clock.source(), TempoSource::Internal);
+        let mut rx = clock.subscribe();
+
+        clock.set_bpm(90.0).unwrap();
+        assert!(rx.has_changed().unwrap());
+        assert_eq!(rx.borrow_and_update().bpm, 90.0);
+        assert_eq!(clock.state().bpm, 90.0);
+    }
+
+    #[test]
+    fn test_unavailable_source() {
+        let config = TempoConfig {
+            source: TempoSource::Link,
+            ..Default::default()
+        };
+        if cfg!(not(feature = "link")) {
+            assert!(matches!(
+                TempoClock::start(&config),
+                Err(CoreError::ConfigError(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,61 @@
+//! Ableton Link 会话
+//!
+//! 加入局域网内的 Link 会话，定期读取会话速度和节拍位置（需要启用 `link` 特性）。
+
+use std::time::{Duration, Instant};
+
+use rusty_link::{AblLink, SessionState};
+use tokio::sync::watch;
+use tracing::debug;
+
+use super::TempoState;
+
+/// 读取会话状态的间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// 速度变化超过该值时才发布（节拍位置由接收端外推）
+const BPM_EPSILON: f64 = 0.01;
+
+/// 节拍位置偏离外推值超过该值时发布（其他设备调整了相位）
+const BEAT_EPSILON: f64 = 0.02;
+
+/// 加入 Link 会话并启动轮询任务
+///
+/// `bpm` 为会话中没有其他成员时的初始速度，`quantum` 为每小节拍数。
+pub fn spawn(bpm: f64, quantum: f64, tx: watch::Sender<TempoState>) -> tokio::task::JoinHandle<()> {
+    let link = AblLink::new(bpm);
+    link.enable(true);
+
+    tokio::spawn(async move {
+        let mut session = SessionState::new();
+        let mut ticker = tokio::time::interval(POLL_INTERVAL);
+        loop {
+            let _ = ticker.tick().await;
+            if tx.is_closed() {
+                break;
+            }
+
+            link.capture_app_session_state(&mut session);
+            let now = Instant::now();
+            let state = TempoState {
+                bpm: session.tempo(),
+                beat: session.beat_at_time(link.clock_micros(), quantum),
+                at: now,
+                running: true,
+            };
+
+            let current = *tx.borrow();
+            if (current.bpm - state.bpm).abs() > BPM_EPSILON
+                || (current.beat_at(now) - state.beat).abs() > BEAT_EPSILON
+            {
+                debug!(
+                    "Link tempo {:.2} BPM, {} peer(s)",
+                    state.bpm,
+                    link.num_peers()
+                );
+                let _ = tx.send(state);
+            }
+        }
+        link.enable(false);
+    })
+}
@@ -0,0 +1,225 @@
+//! MIDI 时钟
+//!
+//! [`MidiClockTracker`] 解析 MIDI 实时消息并估算速度（不依赖 MIDI 库，便于测试）；
+//! 启用 `midi` 特性后 [`connect`] 通过 midir 打开输入端口。
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use super::TempoState;
+
+/// 每拍的时钟消息数（MIDI 规范固定为 24 PPQN）
+pub const PPQN: u32 = 24;
+
+/// 时钟消息
+const CLOCK: u8 = 0xF8;
+/// 开始（从第 0 拍开始）
+const START: u8 = 0xFA;
+/// 继续（从停止的位置继续）
+const CONTINUE: u8 = 0xFB;
+/// 停止
+const STOP: u8 = 0xFC;
+
+/// 估算速度使用的时钟间隔数（一拍）
+const WINDOW: usize = PPQN as usize;
+
+/// MIDI 时钟跟踪
+///
+/// 速度取最近一拍内时钟间隔的平均值；位置按收到的时钟数计算，每个时钟更新一次状态。
+#[derive(Debug, Clone)]
+pub struct MidiClockTracker {
+    /// 当前速度（收到足够的时钟前为回退值）
+    bpm: f64,
+    /// 已走过的时钟数
+    ticks: u64,
+    /// 最近的时钟间隔
+    intervals: VecDeque<Duration>,
+    /// 上一个时钟的时刻
+    last_tick: Option<Instant>,
+    /// 是否在走
+    running: bool,
+}
+
+impl MidiClockTracker {
+    /// 创建跟踪器，收到时钟前使用 `fallback_bpm`
+    pub fn new(fallback_bpm: f64) -> Self {
+        Self {
+            bpm: fallback_bpm,
+            ticks: 0,
+            intervals: VecDeque::with_capacity(WINDOW),
+            last_tick: None,
+            running: true,
+        }
+    }
+
+    /// 当前速度
+    pub fn bpm(&self) -> f64 {
+        self.bpm
+    }
+
+    /// 处理一条 MIDI 消息，状态变化时返回新状态（非实时消息忽略）
+    pub fn handle(&mut self, message: &[u8], at: Instant) -> Option<TempoState> {
+        match message.first()? {
+            &CLOCK => {
+                if let Some(last) = self.last_tick.replace(at) {
+                    if self.intervals.len() == WINDOW {
+                        let _ = self.intervals.pop_front();
+                    }
+                    self.intervals.push_back(at.saturating_duration_since(last));
+                    let total: Duration = self.intervals.iter().sum();
+                    let mean = total.as_secs_f64() / self.intervals.len() as f64;
+                    if mean > 0.0 {
+                        self.bpm = 60.0 / (mean * f64::from(PPQN));
+                    }
+                }
+                let state = self.state(at);
+                if self.running {
+                    self.ticks += 1;
+                }
+                Some(state)
+            }
+            &START => {
+                self.ticks = 0;
+                self.running = true;
+                Some(self.state(at))
+            }
+            &CONTINUE => {
+                self.running = true;
+                Some(self.state(at))
+            }
+            &STOP => {
+                self.running = false;
+                Some(self.state(at))
+            }
+            _ => None,
+        }
+    }
+
+    fn state(&self, at: Instant) -> TempoState {
+        TempoState {
+            bpm: self.bpm,
+            beat: self.ticks as f64 / f64::from(PPQN),
+            at,
+            running: self.running,
+        }
+    }
+}
+
+/// 打开 MIDI 输入端口，把时钟状态发布到 `tx`
+///
+/// `port` 按名称部分匹配（不区分大小写），`None` 时使用第一个端口。
+/// 连接由后台线程持有，`tx` 的所有接收端关闭后断开。
+#[cfg(feature = "midi")]
+pub fn connect(
+    port: Option<&str>,
+    fallback_bpm: f64,
+    tx: tokio::sync::watch::Sender<TempoState>,
+) -> crate::error::Result<String> {
+    use crate::error::CoreError;
+
+    let mut input = midir::MidiInput::new("dglab").map_err(midi_error)?;
+    input.ignore(midir::Ignore::None);
+
+    let ports = input.ports();
+    let names: Vec<String> = ports
+        .iter()
+        .map(|p| input.port_name(p).unwrap_or_default())
+        .collect();
+    let index = match port {
+        Some(wanted) => {
+            let wanted = wanted.to_lowercase();
+            names
+                .iter()
+                .position(|n| n.to_lowercase().contains(&wanted))
+        }
+        None => (!ports.is_empty()).then_some(0),
+    }
+    .ok_or_else(|| {
+        CoreError::DeviceNotFound(format!(
+            "MIDI input '{}' not found, available: {}",
+            port.unwrap_or("*"),
+            names.join(", ")
+        ))
+    })?;
+    let name = names[index].clone();
+
+    let (ready_tx, ready_rx) = std::sync::mpsc::channel();
+    let selected = ports[index].clone();
+    let _ = std::thread::Builder::new()
+        .name("dglab-midi-clock".to_string())
+        .spawn(move || {
+            let mut tracker = MidiClockTracker::new(fallback_bpm);
+            let callback_tx = tx.clone();
+            let connection = input.connect(
+                &selected,
+                "dglab-clock",
+                move |_, message, _| {
+                    if let Some(state) = tracker.handle(message, Instant::now()) {
+                        let _ = callback_tx.send(state);
+                    }
+                },
+                (),
+            );
+            match connection {
+                Ok(connection) => {
+                    let _ = ready_tx.send(Ok(()));
+                    // 保持连接直到所有接收端关闭
+                    while !tx.is_closed() {
+                        std::thread::sleep(Duration::from_millis(200));
+                    }
+                    let _ = connection.close();
+                }
+                Err(e) => {
+                    let _ = ready_tx.send(Err(midi_error(e)));
+                }
+            }
+        })?;
+
+    ready_rx
+        .recv()
+        .map_err(|_| CoreError::Other("MIDI clock thread exited".to_string()))??;
+    Ok(name)
+}
+
+#[cfg(feature = "midi")]
+fn midi_error(e: impl std::fmt::Display) -> crate::error::CoreError {
+    crate::error::CoreError::Other(format!("MIDI error: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_midi_clock_tempo_and_position() {
+        let start = Instant::now();
+        let mut tracker = MidiClockTracker::new(100.0);
+        // 120 BPM：每拍 500ms，每个时钟约 20.833ms
+        let tick = Duration::from_micros(500_000 / u64::from(PPQN));
+
+        let state = tracker.handle(&[START], start).unwrap();
+        assert_eq!(state.beat, 0.0);
+        assert_eq!(state.bpm, 100.0);
+
+        let mut last = None;
+        for i in 0..=PPQN * 2 {
+            last = tracker.handle(&[CLOCK], start + tick * i);
+        }
+        let state = last.unwrap();
+        assert!((state.bpm - 120.0).abs() < 0.1, "bpm {}", state.bpm);
+        assert_eq!(state.beat, 2.0);
+        assert!(state.running);
+
+        // 停止后位置不再前进
+        let state = tracker.handle(&[STOP], start + tick * 49).unwrap();
+        assert!(!state.running);
+        let _ = tracker.handle(&[CLOCK], start + tick * 50);
+        let state = tracker.handle(&[CONTINUE], start + tick * 51).unwrap();
+        assert!(state.running);
+        assert!((state.beat - (2.0 + 1.0 / f64::from(PPQN))).abs() < 1e-9);
+
+        // 非实时消息忽略
+        assert!(tracker.handle(&[0x90, 60, 100], start).is_none());
+        assert!(tracker.handle(&[], start).is_none());
+    }
+}
@@ -0,0 +1,240 @@
+//! 节拍同步
+//!
+//! 按外部节拍播放波形：波形周期锁定为若干拍，并在小节边界切换波形，用于配合音乐的会话。
+//! 节拍来源在配置文件 `[tempo]` 段选择：
+//!
+//! - `internal`：固定 BPM 的内部时钟
+//! - `midi`：MIDI 时钟（每拍 24 个 0xF8 消息，Start/Stop/Continue 控制走停），需要启用 `midi` 特性
+//! - `link`：加入局域网内的 Ableton Link 会话，需要启用 `link` 特性
+//!
+//! ```toml
+//! [tempo]
+//! source = "midi"
+//! bpm = 120
+//! midi_port = "IAC"
+//! beats_per_bar = 4
+//! ```
+
+pub mod clock;
+#[cfg(feature = "link")]
+pub mod link;
+pub mod midi;
+pub mod player;
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{CoreError, Result};
+
+pub use clock::TempoClock;
+pub use midi::MidiClockTracker;
+pub use player::{lock_period, TempoPattern};
+
+/// 允许的 BPM 范围
+pub const BPM_RANGE: std::ops::RangeInclusive<u16> = 20..=300;
+
+/// 节拍来源
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TempoSource {
+    /// 固定 BPM 的内部时钟
+    #[default]
+    Internal,
+    /// MIDI 时钟输入
+    Midi,
+    /// Ableton Link 会话
+    Link,
+}
+
+impl FromStr for TempoSource {
+    type Err = CoreError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "internal" => Ok(Self::Internal),
+            "midi" => Ok(Self::Midi),
+            "link" => Ok(Self::Link),
+            _ => Err(CoreError::InvalidParameter(format!(
+                "Invalid tempo source '{}', expected internal, midi or link",
+                s
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for TempoSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Internal => "internal",
+            Self::Midi => "midi",
+            Self::Link => "link",
+        };
+        f.write_str(name)
+    }
+}
+
+/// 节拍同步配置（配置文件 `[tempo]` 段）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TempoConfig {
+    /// 节拍来源
+    pub source: TempoSource,
+    /// 内部时钟的 BPM，外部时钟到达前也使用该值
+    pub bpm: u16,
+    /// MIDI 输入端口（名称部分匹配，未指定时使用第一个端口）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub midi_port: Option<String>,
+    /// 每小节拍数（Link 的 quantum）
+    pub beats_per_bar: u8,
+}
+
+impl Default for TempoConfig {
+    fn default() -> Self {
+        Self {
+            source: TempoSource::Internal,
+            bpm: 120,
+            midi_port: None,
+            beats_per_bar: 4,
+        }
+    }
+}
+
+impl TempoConfig {
+    /// 是否为默认配置（序列化时省略）
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// 校验配置
+    pub fn validate(&self) -> Result<()> {
+        if !BPM_RANGE.contains(&self.bpm) {
+            return Err(CoreError::ConfigError(format!(
+                "Tempo {} BPM out of range {}~{}",
+                self.bpm,
+                BPM_RANGE.start(),
+                BPM_RANGE.end()
+            )));
+        }
+        if !(1..=16).contains(&self.beats_per_bar) {
+            return Err(CoreError::ConfigError(format!(
+                "Beats per bar must be 1~16, got {}",
+                self.beats_per_bar
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// 某一时刻的节拍状态
+///
+/// 节拍位置在 `at` 时刻为 `beat`，运行中按 `bpm` 向后外推，时钟源只需在速度或位置变化时更新。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TempoState {
+    /// 速度（BPM）
+    pub bpm: f64,
+    /// `at` 时刻的节拍位置（从 0 开始）
+    pub beat: f64,
+    /// 参考时刻
+    pub at: Instant,
+    /// 是否在走（MIDI Stop 后停止）
+    pub running: bool,
+}
+
+impl TempoState {
+    /// 从当前时刻第 0 拍开始
+    pub fn new(bpm: f64) -> Self {
+        Self {
+            bpm,
+            beat: 0.0,
+            at: Instant::now(),
+            running: true,
+        }
+    }
+
+    /// 每拍时长
+    pub fn beat_duration(&self) -> Duration {
+        Duration::from_secs_f64(60.0 / self.bpm)
+    }
+
+    /// 指定时刻的节拍位置
+    pub fn beat_at(&self, now: Instant) -> f64 {
+        if !self.running {
+            return self.beat;
+        }
+        let elapsed = now.saturating_duration_since(self.at).as_secs_f64();
+        self.beat + elapsed * self.bpm / 60.0
+    }
+
+    /// 指定时刻之后的下一个 `quantum` 拍边界（正好在边界上时返回当前位置）
+    pub fn next_boundary(&self, quantum: f64, now: Instant) -> f64 {
+        let beat = self.beat_at(now);
+        // 容忍调度误差，避免刚过边界又等待一整个 quantum
+        ((beat - 1e-6) / quantum).ceil() * quantum
+    }
+
+    /// 到达指定节拍位置还需的时间，已停止时返回 `None`
+    pub fn time_until(&self, beat: f64, now: Instant) -> Option<Duration> {
+        if !self.running {
+            return None;
+        }
+        let remaining = (beat - self.beat_at(now)).max(0.0);
+        Some(Duration::from_secs_f64(remaining * 60.0 / self.bpm))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tempo_state() {
+        let start = Instant::now();
+        let state = TempoState {
+            bpm: 120.0,
+            beat: 0.0,
+            at: start,
+            running: true,
+        };
+        assert_eq!(state.beat_duration(), Duration::from_millis(500));
+
+        let now = start + Duration::from_millis(1250);
+        assert!((state.beat_at(now) - 2.5).abs() < 1e-9);
+        assert_eq!(state.next_boundary(4.0, now), 4.0);
+        assert_eq!(state.next_boundary(1.0, now), 3.0);
+        assert_eq!(state.time_until(4.0, now), Some(Duration::from_millis(750)));
+
+        // 正好在边界上
+        let now = start + Duration::from_secs(2);
+        assert_eq!(state.next_boundary(4.0, now), 4.0);
+
+        let stopped = TempoState {
+            running: false,
+            ..state
+        };
+        assert_eq!(stopped.beat_at(now), 0.0);
+        assert_eq!(stopped.time_until(4.0, now), None);
+    }
+
+    #[test]
+    fn test_tempo_config() {
+        assert!(TempoConfig::default().validate().is_ok());
+        assert!(TempoConfig {
+            bpm: 10,
+            ..Default::default()
+        }
+        .validate()
+        .is_err());
+        assert!(TempoConfig {
+            beats_per_bar: 0,
+            ..Default::default()
+        }
+        .validate()
+        .is_err());
+
+        assert_eq!("MIDI".parse::<TempoSource>().unwrap(), TempoSource::Midi);
+        assert!("osc".parse::<TempoSource>().is_err());
+    }
+}
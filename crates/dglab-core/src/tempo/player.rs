@@ -0,0 +1,210 @@
+//! 节拍同步播放
+//!
+//! 波形周期锁定为若干拍（速度变化时重新下发），按小节边界依次切换波形。
+
+use tokio::sync::watch;
+use tracing::{debug, info};
+
+use super::TempoState;
+use crate::error::{CoreError, Result};
+use crate::session::SessionManager;
+use crate::waveform::{Waveform, SAMPLE_INTERVAL_MS};
+
+/// 锁定后的周期取整到整帧（每帧 4 个采样点）
+const FRAME_MS: f64 = (SAMPLE_INTERVAL_MS * 4) as f64;
+
+/// 速度变化超过该值（BPM）时重新下发锁定周期的波形
+const RELOCK_BPM: f64 = 1.0;
+
+/// 把波形周期锁定为 `beats` 拍
+///
+/// 周期取整到 100ms 帧，至少一帧；带原始帧的波形（如导入的 APP 波形）保持不变。
+pub fn lock_period(waveform: &Waveform, bpm: f64, beats: f64) -> Waveform {
+    let mut locked = waveform.clone();
+    if waveform.frames.as_ref().is_some_and(|f| !f.is_empty()) {
+        return locked;
+    }
+    let period = beats * 60_000.0 / bpm;
+    let frames = (period / FRAME_MS).round().max(1.0);
+    locked.params.period_ms = (frames * FRAME_MS) as u32;
+    locked
+}
+
+/// 节拍同步的波形序列
+#[derive(Debug, Clone)]
+pub struct TempoPattern {
+    /// 依次循环播放的波形
+    pub waveforms: Vec<Waveform>,
+    /// 波形周期（拍）
+    pub beats_per_cycle: f64,
+    /// 每个波形播放的小节数
+    pub bars_per_waveform: u32,
+    /// 每小节拍数
+    pub beats_per_bar: u32,
+}
+
+impl TempoPattern {
+    /// 校验参数
+    pub fn validate(&self) -> Result<()> {
+        if self.waveforms.is_empty() {
+            return Err(CoreError::InvalidParameter(
+                "Tempo pattern needs at least one waveform".to_string(),
+            ));
+        }
+        if !(self.beats_per_cycle > 0.0 && self.beats_per_cycle <= 64.0) {
+            return Err(CoreError::InvalidParameter(format!(
+                "Beats per cycle must be in (0, 64], got {}",
+                self.beats_per_cycle
+            )));
+        }
+        if self.bars_per_waveform == 0 || self.beats_per_bar == 0 {
+            return Err(CoreError::InvalidParameter(
+                "Bars per waveform and beats per bar must be positive".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// 每个波形播放的拍数
+    fn beats_per_waveform(&self) -> f64 {
+        f64::from(self.bars_per_waveform * self.beats_per_bar)
+    }
+
+    /// 在设备通道上播放，直到出错或调用方取消 future
+    ///
+    /// 第一个波形从下一个小节开始；外部时钟停止时保持当前波形，重新开始（位置回退）时
+    /// 从下一个小节重新对齐。
+    pub async fn run(
+        &self,
+        manager: &SessionManager,
+        device_id: &str,
+        channels: &[u8],
+        mut tempo: watch::Receiver<TempoState>,
+    ) -> Result<()> {
+        self.validate()?;
+        let bar = f64::from(self.beats_per_bar);
+        let now = std::time::Instant::now();
+        let mut target = tempo.borrow_and_update().next_boundary(bar, now);
+        let mut index = 0;
+        let mut applied: Option<(usize, f64)> = None;
+
+        loop {
+            let state = *tempo.borrow_and_update();
+            let now = std::time::Instant::now();
+            let beat = state.beat_at(now);
+
+            // 位置回退（MIDI Start、Link 相位调整）时重新对齐到下一个小节
+            if beat + self.beats_per_waveform() + bar < target {
+                debug!("Tempo position jumped back to beat {:.2}, realigning", beat);
+                target = state.next_boundary(bar, now);
+            }
+
+            if state.running && state.time_until(target, now).is_some_and(|d| d.is_zero()) {
+                let waveform = &self.waveforms[index];
+                info!(
+                    "Beat {:.0}: switching to '{}' at {:.1} BPM",
+                    target, waveform.name, state.bpm
+                );
+                self.apply(manager, device_id, channels, index, state.bpm)
+                    .await?;
+                applied = Some((index, state.bpm));
+                index = (index + 1) % self.waveforms.len();
+                target += self.beats_per_waveform();
+                continue;
+            }
+
+            // 速度变化时保持当前波形，重新锁定周期
+            if let Some((current, bpm)) = applied {
+                if (state.bpm - bpm).abs() >= RELOCK_BPM {
+                    debug!("Tempo changed to {:.1} BPM, relocking", state.bpm);
+                    self.apply(manager, device_id, channels, current, state.bpm)
+                        .await?;
+                    applied = Some((current, state.bpm));
+                }
+            }
+
+            let changed = tempo.changed();
+            match state.time_until(target, now) {
+                Some(wait) => {
+                    tokio::select! {
+                        _ = tokio::time::sleep(wait) => {}
+                        result = changed => result.map_err(closed)?,
+                    }
+                }
+                None => changed.await.map_err(closed)?,
+            }
+        }
+    }
+
+    /// 下发锁定周期后的波形
+    async fn apply(
+        &self,
+        manager: &SessionManager,
+        device_id: &str,
+        channels: &[u8],
+        index: usize,
+        bpm: f64,
+    ) -> Result<()> {
+        let config =
+            lock_period(&self.waveforms[index], bpm, self.beats_per_cycle).to_device_config();
+        for &channel in channels {
+            manager
+                .set_waveform(device_id, channel, config.clone())
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+fn closed(_: watch::error::RecvError) -> CoreError {
+    CoreError::Other("Tempo clock closed".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::waveform::WaveformGenerator;
+
+    #[test]
+    fn test_lock_period() {
+        let waveform = WaveformGenerator::preset_waveforms()[0].clone();
+
+        // 120 BPM 一拍 500ms
+        assert_eq!(lock_period(&waveform, 120.0, 1.0).params.period_ms, 500);
+        // 128 BPM 两拍 937.5ms，取整到 900ms
+        assert_eq!(lock_period(&waveform, 128.0, 2.0).params.period_ms, 900);
+        // 至少一帧
+        assert_eq!(lock_period(&waveform, 300.0, 0.25).params.period_ms, 100);
+
+        let mut raw = waveform.clone();
+        raw.frames = Some(vec![dglab_protocol::v3::WaveformData::uniform(10, 50)]);
+        assert_eq!(
+            lock_period(&raw, 60.0, 4.0).params.period_ms,
+            raw.params.period_ms
+        );
+    }
+
+    #[test]
+    fn test_pattern_validate() {
+        let pattern = TempoPattern {
+            waveforms: Vec::new(),
+            beats_per_cycle: 1.0,
+            bars_per_waveform: 4,
+            beats_per_bar: 4,
+        };
+        assert!(pattern.validate().is_err());
+
+        let pattern = TempoPattern {
+            waveforms: WaveformGenerator::preset_waveforms(),
+            ..pattern
+        };
+        assert!(pattern.validate().is_ok());
+        assert_eq!(pattern.beats_per_waveform(), 16.0);
+        assert!(TempoPattern {
+            beats_per_cycle: 0.0,
+            ..pattern
+        }
+        .validate()
+        .is_err());
+    }
+}
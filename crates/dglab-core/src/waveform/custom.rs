@@ -0,0 +1,412 @@
+//! 自定义曲线波形
+//!
+//! 由若干控制点（时间、频率、强度）组成的折线，点之间线性插值。
+//! 作为图形化波形编辑器的数据模型：支持插入、移动、删除控制点，
+//! 并按 25ms 网格（V3 B0 帧的 4 个小节）或 100ms 网格（每帧一个值）重采样输出。
+//!
+//! ```json
+//! { "name": "Ramp", "points": [
+//!     { "time_ms": 0, "frequency": 10, "intensity": 0 },
+//!     { "time_ms": 1000, "frequency": 10, "intensity": 80 }
+//! ] }
+//! ```
+
+use dglab_protocol::v3::{
+    WaveformData, MAX_WAVE_FREQUENCY, MAX_WAVE_INTENSITY, MIN_WAVE_FREQUENCY, SLOTS_PER_FRAME,
+    SLOT_MS,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{CoreError, Result};
+
+/// 每帧时长（毫秒）
+pub const FRAME_MS: u32 = SLOT_MS * SLOTS_PER_FRAME as u32;
+
+/// 控制点最大时间（毫秒），即波形最长 60 秒（2400 个 25ms 小节）
+pub const MAX_CUSTOM_DURATION_MS: u32 = 60_000;
+
+/// 曲线控制点
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CurvePoint {
+    /// 时间（毫秒，相对于波形开始）
+    pub time_ms: u32,
+    /// 波形频率（10~240）
+    pub frequency: u8,
+    /// 波形强度（0~100）
+    pub intensity: u8,
+}
+
+impl CurvePoint {
+    /// 创建控制点
+    pub fn new(time_ms: u32, frequency: u8, intensity: u8) -> Self {
+        Self {
+            time_ms,
+            frequency,
+            intensity,
+        }
+    }
+
+    /// 校验时间、频率和强度范围
+    fn validate(&self) -> Result<()> {
+        if self.time_ms > MAX_CUSTOM_DURATION_MS {
+            return Err(CoreError::InvalidParameter(format!(
+                "Point at {}ms exceeds the maximum duration {}ms",
+                self.time_ms, MAX_CUSTOM_DURATION_MS
+            )));
+        }
+        if !(MIN_WAVE_FREQUENCY..=MAX_WAVE_FREQUENCY).contains(&self.frequency) {
+            return Err(CoreError::InvalidParameter(format!(
+                "Point at {}ms: frequency {} out of range {}~{}",
+                self.time_ms, self.frequency, MIN_WAVE_FREQUENCY, MAX_WAVE_FREQUENCY
+            )));
+        }
+        if self.intensity > MAX_WAVE_INTENSITY {
+            return Err(CoreError::InvalidParameter(format!(
+                "Point at {}ms: intensity {} exceeds {}",
+                self.time_ms, self.intensity, MAX_WAVE_INTENSITY
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// 自定义曲线波形
+///
+/// 控制点按时间严格递增排列；第一个点之前保持第一个点的值，
+/// 波形时长为最后一个点的时间（向上取整到采样网格），循环播放。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomWaveform {
+    /// 波形名称
+    pub name: String,
+    /// 波形描述
+    #[serde(default)]
+    pub description: String,
+    /// 控制点
+    pub points: Vec<CurvePoint>,
+}
+
+impl CustomWaveform {
+    /// 创建空波形
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            description: String::new(),
+            points: Vec::new(),
+        }
+    }
+
+    /// 从 JSON 文本解析（并校验）
+    pub fn from_json_str(content: &str) -> Result<Self> {
+        let waveform: Self = serde_json::from_str(content)?;
+        waveform.validate()?;
+        Ok(waveform)
+    }
+
+    /// 序列化为 JSON 文本
+    pub fn to_json_string(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// 校验波形
+    ///
+    /// 至少一个控制点，时间严格递增且不超过 [`MAX_CUSTOM_DURATION_MS`]，
+    /// 频率和强度在设备有效范围内。
+    pub fn validate(&self) -> Result<()> {
+        if self.points.is_empty() {
+            return Err(CoreError::InvalidParameter(format!(
+                "Waveform '{}' has no points",
+                self.name
+            )));
+        }
+
+        for point in &self.points {
+            point.validate()?;
+        }
+
+        if let Some(pair) = self
+            .points
+            .windows(2)
+            .find(|pair| pair[1].time_ms <= pair[0].time_ms)
+        {
+            return Err(CoreError::InvalidParameter(format!(
+                "Waveform '{}': point times must be strictly increasing ({}ms after {}ms)",
+                self.name, pair[1].time_ms, pair[0].time_ms
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// 插入控制点，返回插入位置
+    ///
+    /// 按时间排序插入，与已有控制点时间相同时报错。
+    pub fn insert_point(&mut self, point: CurvePoint) -> Result<usize> {
+        point.validate()?;
+        match self
+            .points
+            .binary_search_by_key(&point.time_ms, |p| p.time_ms)
+        {
+            Ok(_) => Err(CoreError::InvalidParameter(format!(
+                "A point already exists at {}ms",
+                point.time_ms
+            ))),
+            Err(index) => {
+                self.points.insert(index, point);
+                Ok(index)
+            }
+        }
+    }
+
+    /// 移动控制点（修改时间和数值）
+    ///
+    /// 新时间必须位于相邻两个控制点之间，不能改变点的先后顺序。
+    pub fn move_point(&mut self, index: usize, point: CurvePoint) -> Result<()> {
+        point.validate()?;
+        let len = self.points.len();
+        if index >= len {
+            return Err(CoreError::InvalidParameter(format!(
+                "Point index {} out of range (0~{})",
+                index,
+                len.saturating_sub(1)
+            )));
+        }
+
+        let after_prev = index == 0 || self.points[index - 1].time_ms < point.time_ms;
+        let before_next = index + 1 == len || point.time_ms < self.points[index + 1].time_ms;
+        if !after_prev || !before_next {
+            return Err(CoreError::InvalidParameter(format!(
+                "Cannot move point {} to {}ms: it would pass a neighbouring point",
+                index, point.time_ms
+            )));
+        }
+
+        self.points[index] = point;
+        Ok(())
+    }
+
+    /// 删除控制点
+    ///
+    /// 至少保留一个控制点。
+    pub fn remove_point(&mut self, index: usize) -> Result<CurvePoint> {
+        if index >= self.points.len() {
+            return Err(CoreError::InvalidParameter(format!(
+                "Point index {} out of range (0~{})",
+                index,
+                self.points.len().saturating_sub(1)
+            )));
+        }
+        if self.points.len() == 1 {
+            return Err(CoreError::InvalidParameter(
+                "Cannot remove the last point".to_string(),
+            ));
+        }
+        Ok(self.points.remove(index))
+    }
+
+    /// 波形时长（毫秒，最后一个控制点的时间）
+    pub fn duration_ms(&self) -> u32 {
+        self.points.last().map_or(0, |p| p.time_ms)
+    }
+
+    /// 计算指定时刻的（频率, 强度）
+    ///
+    /// 两点之间线性插值，超出首尾时保持端点的值。
+    pub fn value_at(&self, time_ms: u32) -> Option<(u8, u8)> {
+        let first = self.points.first()?;
+        let index = self.points.partition_point(|p| p.time_ms <= time_ms);
+        if index == 0 {
+            return Some((first.frequency, first.intensity));
+        }
+
+        let left = self.points[index - 1];
+        let Some(right) = self.points.get(index) else {
+            return Some((left.frequency, left.intensity));
+        };
+
+        let span = (right.time_ms - left.time_ms) as f64;
+        let t = (time_ms - left.time_ms) as f64 / span;
+        let lerp = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+        Some((
+            lerp(left.frequency, right.frequency),
+            lerp(left.intensity, right.intensity),
+        ))
+    }
+
+    /// 按固定步长重采样，返回每个采样点的（频率, 强度）
+    ///
+    /// 采样覆盖 `[0, 时长)`，时长不足一个步长时输出一个采样点。
+    pub fn sample(&self, step_ms: u32) -> Result<Vec<(u8, u8)>> {
+        self.validate()?;
+        if step_ms == 0 {
+            return Err(CoreError::InvalidParameter(
+                "Sample step must be positive".to_string(),
+            ));
+        }
+
+        let count = self.duration_ms().div_ceil(step_ms).max(1);
+        Ok((0..count)
+            .filter_map(|i| self.value_at(i * step_ms))
+            .collect())
+    }
+
+    /// 按 25ms 网格转换为 V3 波形帧（每帧 4 个小节）
+    ///
+    /// 最后一帧不足 4 个小节时重复最后一个采样值补齐。
+    pub fn to_frames(&self) -> Result<Vec<WaveformData>> {
+        let slots = self.sample(SLOT_MS)?;
+        Ok(slots
+            .chunks(SLOTS_PER_FRAME)
+            .map(|chunk| {
+                let slot = |i: usize| chunk[i.min(chunk.len() - 1)];
+                WaveformData::new(
+                    std::array::from_fn(|i| slot(i).0),
+                    std::array::from_fn(|i| slot(i).1),
+                )
+            })
+            .collect())
+    }
+
+    /// 按 100ms 网格转换为波形帧（每帧 4 个小节取同一个值）
+    ///
+    /// 适合只按帧调节波形的场景，数据量与帧数相同但细节较粗。
+    pub fn to_uniform_frames(&self) -> Result<Vec<WaveformData>> {
+        Ok(self
+            .sample(FRAME_MS)?
+            .into_iter()
+            .map(|(frequency, intensity)| WaveformData::uniform(frequency, intensity))
+            .collect())
+    }
+
+    /// 转换为 WebSocket / APP 格式 HEX 波形数组（25ms 网格）
+    pub fn to_pulses(&self) -> Result<Vec<String>> {
+        Ok(self
+            .to_frames()?
+            .iter()
+            .map(WaveformData::to_hex_string)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ramp() -> CustomWaveform {
+        CustomWaveform {
+            name: "Ramp".to_string(),
+            description: String::new(),
+            points: vec![CurvePoint::new(0, 10, 0), CurvePoint::new(200, 50, 80)],
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(ramp().validate().is_ok());
+        assert!(CustomWaveform::new("Empty").validate().is_err());
+
+        let mut unordered = ramp();
+        unordered.points.push(CurvePoint::new(200, 10, 0));
+        assert!(unordered.validate().is_err());
+
+        let mut out_of_range = ramp();
+        out_of_range.points[0].frequency = 5;
+        assert!(out_of_range.validate().is_err());
+        out_of_range.points[0] = CurvePoint::new(0, 10, 101);
+        assert!(out_of_range.validate().is_err());
+
+        let mut too_long = ramp();
+        too_long.points[1].time_ms = MAX_CUSTOM_DURATION_MS;
+        assert!(too_long.validate().is_ok());
+        too_long.points[1].time_ms = MAX_CUSTOM_DURATION_MS + 1;
+        assert!(too_long.validate().is_err());
+        assert!(ramp()
+            .insert_point(CurvePoint::new(u32::MAX, 10, 0))
+            .is_err());
+    }
+
+    #[test]
+    fn test_point_editing() {
+        let mut waveform = ramp();
+        assert_eq!(
+            waveform.insert_point(CurvePoint::new(100, 20, 40)).unwrap(),
+            1
+        );
+        assert!(waveform.insert_point(CurvePoint::new(100, 20, 40)).is_err());
+        assert!(waveform
+            .insert_point(CurvePoint::new(300, 20, 120))
+            .is_err());
+
+        waveform
+            .move_point(1, CurvePoint::new(150, 30, 60))
+            .unwrap();
+        assert_eq!(waveform.points[1], CurvePoint::new(150, 30, 60));
+        assert!(waveform
+            .move_point(1, CurvePoint::new(250, 30, 60))
+            .is_err());
+        assert!(waveform.move_point(1, CurvePoint::new(0, 30, 60)).is_err());
+        assert!(waveform.move_point(5, CurvePoint::new(10, 30, 60)).is_err());
+
+        assert_eq!(waveform.remove_point(1).unwrap().time_ms, 150);
+        let _ = waveform.remove_point(0).unwrap();
+        assert!(waveform.remove_point(0).is_err());
+        assert!(waveform.validate().is_ok());
+    }
+
+    #[test]
+    fn test_value_at() {
+        let mut waveform = ramp();
+        waveform.points[0].time_ms = 100;
+        waveform.points[1].time_ms = 300;
+
+        assert_eq!(waveform.value_at(0), Some((10, 0)));
+        assert_eq!(waveform.value_at(200), Some((30, 40)));
+        assert_eq!(waveform.value_at(400), Some((50, 80)));
+        assert_eq!(CustomWaveform::new("Empty").value_at(0), None);
+    }
+
+    #[test]
+    fn test_to_frames() {
+        let frames = ramp().to_frames().unwrap();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].intensity, [0, 10, 20, 30]);
+        assert_eq!(frames[0].frequency, [10, 15, 20, 25]);
+        assert_eq!(frames[1].intensity, [40, 50, 60, 70]);
+        assert!(frames.iter().all(WaveformData::is_valid));
+
+        // 不足一帧时补齐
+        let mut short = ramp();
+        short.points[1].time_ms = 50;
+        let frames = short.to_frames().unwrap();
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].intensity, [0, 40, 40, 40]);
+
+        // 单点波形输出一帧
+        let mut single = ramp();
+        single.points.truncate(1);
+        assert_eq!(single.to_frames().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_uniform_frames_and_pulses() {
+        let frames = ramp().to_uniform_frames().unwrap();
+        assert_eq!(
+            frames,
+            vec![WaveformData::uniform(10, 0), WaveformData::uniform(30, 40)]
+        );
+
+        let pulses = ramp().to_pulses().unwrap();
+        assert_eq!(pulses.len(), 2);
+        assert_eq!(pulses[0], "0a0f1419000a141e");
+        assert!(ramp().sample(0).is_err());
+    }
+
+    #[test]
+    fn test_json_roundtrip() {
+        let json = ramp().to_json_string().unwrap();
+        let parsed = CustomWaveform::from_json_str(&json).unwrap();
+        assert_eq!(parsed, ramp());
+
+        let invalid = r#"{ "name": "Bad", "points": [] }"#;
+        assert!(CustomWaveform::from_json_str(invalid).is_err());
+    }
+}
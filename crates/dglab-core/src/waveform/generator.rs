@@ -1,7 +1,20 @@
 //! 波形生成器
 
+use dglab_protocol::v3::{compress_frequency, decompress_frequency, WaveformData};
 use serde::{Deserialize, Serialize};
 
+use super::modulation::{modulate_frequency, modulate_intensity, Modulation};
+use crate::device::traits::{WaveformConfig, WaveformType as DeviceWaveformType};
+
+/// 预览最多返回的采样点数
+pub const MAX_PREVIEW_POINTS: usize = 1000;
+
+/// 波形采样间隔（V3 帧内每组 25ms）
+pub const SAMPLE_INTERVAL_MS: u64 = 25;
+
+/// 采样生成的最大帧数（60 秒），更长的周期只截取开头部分
+pub const MAX_SAMPLED_FRAMES: usize = 600;
+
 /// 波形类型
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum WaveformType {
@@ -69,6 +82,12 @@ pub struct Waveform {
     pub params: WaveformParams,
     /// 自定义数据点
     pub custom_points: Option<Vec<(u32, u8)>>,
+    /// V3 原始帧（每帧 100ms，循环播放），设置后优先于波形参数
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frames: Option<Vec<WaveformData>>,
+    /// 调制层（依次叠加在基础波形上）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub modulation: Vec<Modulation>,
 }
 
 impl Default for Waveform {
@@ -78,10 +97,128 @@ impl Default for Waveform {
             description: "Default waveform".to_string(),
             params: WaveformParams::default(),
             custom_points: None,
+            frames: None,
+            modulation: Vec::new(),
         }
     }
 }
 
+impl Waveform {
+    /// 转换为设备波形配置
+    ///
+    /// 连续波输出单帧即可；其它类型按 [`Self::to_frames`] 逐帧采样，输出 `Custom` 类型，
+    /// `custom_data` 为依次编码的 8 字节帧，设备循环播放整个周期。
+    pub fn to_device_config(&self) -> WaveformConfig {
+        if self.frames.as_ref().is_some_and(|f| !f.is_empty())
+            || self.params.waveform_type != WaveformType::Continuous
+            || !self.modulation.is_empty()
+        {
+            return WaveformConfig {
+                waveform_type: DeviceWaveformType::Custom,
+                frequency: self.params.frequency,
+                pulse_width: self.params.pulse_width,
+                intensity: self.params.max_power.min(100),
+                custom_data: Some(self.to_frames().iter().flat_map(|f| f.encode()).collect()),
+            };
+        }
+
+        WaveformConfig {
+            waveform_type: DeviceWaveformType::Continuous,
+            frequency: self.params.frequency,
+            pulse_width: self.params.pulse_width,
+            intensity: self.params.max_power.min(100),
+            custom_data: None,
+        }
+    }
+
+    /// 转换为 V3 波形帧序列（每帧 100ms，循环播放）
+    ///
+    /// 带原始帧时直接返回；否则按 [`SAMPLE_INTERVAL_MS`] 采样一个完整周期，
+    /// 每 4 个采样点组成一帧，周期不足整帧时按相位回绕补齐，
+    /// 最多生成 [`MAX_SAMPLED_FRAMES`] 帧。
+    ///
+    /// 有调制层时采样长度为基础周期与各调制周期的最小公倍数（同样受帧数上限约束），
+    /// 每个采样点分别调制强度和频率。
+    pub fn to_frames(&self) -> Vec<WaveformData> {
+        let raw = self.frames.as_ref().filter(|f| !f.is_empty());
+        if self.modulation.is_empty() {
+            if let Some(frames) = raw {
+                return frames.clone();
+            }
+        }
+
+        let period = match raw {
+            Some(frames) => frames.len() as u64 * SAMPLE_INTERVAL_MS * 4,
+            None => u64::from(self.params.period_ms).max(SAMPLE_INTERVAL_MS),
+        };
+        let cycle = self.modulation.iter().fold(period, |cycle, m| {
+            lcm(cycle, u64::from(m.rate_ms.max(1)))
+                .min(MAX_SAMPLED_FRAMES as u64 * SAMPLE_INTERVAL_MS * 4)
+        });
+        let frame_count =
+            (cycle.div_ceil(SAMPLE_INTERVAL_MS * 4) as usize).clamp(1, MAX_SAMPLED_FRAMES);
+
+        let mut generator = WaveformGenerator::with_waveform(self.clone());
+        let mut sample = |index: u64| {
+            let elapsed = index * SAMPLE_INTERVAL_MS;
+            let (frequency, intensity) = match raw {
+                Some(frames) => {
+                    let frame = &frames[(index / 4) as usize % frames.len()];
+                    let i = (index % 4) as usize;
+                    (decompress_frequency(frame.frequency[i]), frame.intensity[i])
+                }
+                None => {
+                    generator.phase = (elapsed % period) as f64 / period as f64;
+                    (self.params.frequency, generator.base_power())
+                }
+            };
+            (
+                compress_frequency(modulate_frequency(&self.modulation, frequency, elapsed)),
+                modulate_intensity(&self.modulation, intensity, elapsed).min(100),
+            )
+        };
+
+        (0..frame_count as u64)
+            .map(|frame| {
+                let samples = [0, 1, 2, 3].map(|i| sample(frame * 4 + i));
+                WaveformData::new(samples.map(|s| s.0), samples.map(|s| s.1))
+            })
+            .collect()
+    }
+
+    /// 采样波形用于预览，返回 `(时间 ms, 强度)`
+    ///
+    /// 带原始帧时按帧内 4 组强度（每组 25ms）采样，否则按波形参数生成。
+    /// 采样点超过 [`MAX_PREVIEW_POINTS`] 时自动加大采样间隔。
+    pub fn preview(&self, duration_ms: u64, step_ms: u64) -> Vec<(u64, u8)> {
+        let max_steps = (MAX_PREVIEW_POINTS - 1) as u64;
+        let step_ms = step_ms.max(1).max(duration_ms.div_ceil(max_steps));
+        let times = (0..=duration_ms / step_ms).map(|i| i * step_ms);
+
+        if let Some(frames) = self.frames.as_ref().filter(|f| !f.is_empty()) {
+            return times
+                .map(|t| {
+                    let frame = &frames[(t / 100) as usize % frames.len()];
+                    let intensity = frame.intensity[(t % 100 / 25) as usize];
+                    (t, modulate_intensity(&self.modulation, intensity, t))
+                })
+                .collect();
+        }
+
+        let mut generator = WaveformGenerator::with_waveform(self.clone());
+        times
+            .map(|t| {
+                let power = if t == 0 {
+                    generator.current_power()
+                } else {
+                    generator.update(step_ms)
+                };
+                (t, power)
+            })
+            .collect()
+    }
+}
+
 /// 波形生成器
 pub struct WaveformGenerator {
     /// 当前波形
@@ -90,6 +227,8 @@ pub struct WaveformGenerator {
     start_time: Option<std::time::Instant>,
     /// 当前相位
     phase: f64,
+    /// 已生成时长（毫秒，用于调制层）
+    elapsed_ms: u64,
 }
 
 impl WaveformGenerator {
@@ -99,6 +238,7 @@ impl WaveformGenerator {
             current_waveform: Waveform::default(),
             start_time: None,
             phase: 0.0,
+            elapsed_ms: 0,
         }
     }
 
@@ -108,6 +248,7 @@ impl WaveformGenerator {
             current_waveform: waveform,
             start_time: None,
             phase: 0.0,
+            elapsed_ms: 0,
         }
     }
 
@@ -116,6 +257,7 @@ impl WaveformGenerator {
         self.current_waveform = waveform;
         self.start_time = None;
         self.phase = 0.0;
+        self.elapsed_ms = 0;
     }
 
     /// 获取当前波形
@@ -127,6 +269,7 @@ impl WaveformGenerator {
     pub fn start(&mut self) {
         self.start_time = Some(std::time::Instant::now());
         self.phase = 0.0;
+        self.elapsed_ms = 0;
     }
 
     /// 停止生成
@@ -138,10 +281,26 @@ impl WaveformGenerator {
     pub fn reset(&mut self) {
         self.start_time = None;
         self.phase = 0.0;
+        self.elapsed_ms = 0;
     }
 
-    /// 获取当前强度值
+    /// 获取当前强度值（已应用强度调制）
     pub fn current_power(&mut self) -> u8 {
+        let power = self.base_power();
+        modulate_intensity(&self.current_waveform.modulation, power, self.elapsed_ms)
+    }
+
+    /// 获取当前脉冲频率（Hz，已应用频率调制）
+    pub fn current_frequency(&self) -> u16 {
+        modulate_frequency(
+            &self.current_waveform.modulation,
+            self.current_waveform.params.frequency,
+            self.elapsed_ms,
+        )
+    }
+
+    /// 基础波形在当前相位的强度（不含调制）
+    fn base_power(&self) -> u8 {
         let params = &self.current_waveform.params;
 
         match params.waveform_type {
@@ -166,6 +325,7 @@ impl WaveformGenerator {
         if self.phase >= 1.0 {
             self.phase -= 1.0;
         }
+        self.elapsed_ms += delta_ms;
 
         self.current_power()
     }
@@ -294,6 +454,8 @@ impl WaveformGenerator {
                     duty_cycle: 100,
                 },
                 custom_points: None,
+                frames: None,
+                modulation: Vec::new(),
             },
             Waveform {
                 name: "Pulse".to_string(),
@@ -308,6 +470,8 @@ impl WaveformGenerator {
                     duty_cycle: 30,
                 },
                 custom_points: None,
+                frames: None,
+                modulation: Vec::new(),
             },
             Waveform {
                 name: "Breathing".to_string(),
@@ -322,6 +486,8 @@ impl WaveformGenerator {
                     duty_cycle: 50,
                 },
                 custom_points: None,
+                frames: None,
+                modulation: Vec::new(),
             },
             Waveform {
                 name: "Sawtooth".to_string(),
@@ -336,6 +502,8 @@ impl WaveformGenerator {
                     duty_cycle: 50,
                 },
                 custom_points: None,
+                frames: None,
+                modulation: Vec::new(),
             },
             Waveform {
                 name: "Fade".to_string(),
@@ -350,11 +518,22 @@ impl WaveformGenerator {
                     duty_cycle: 50,
                 },
                 custom_points: None,
+                frames: None,
+                modulation: Vec::new(),
             },
         ]
     }
 }
 
+/// 最小公倍数
+fn lcm(a: u64, b: u64) -> u64 {
+    let (mut x, mut y) = (a, b);
+    while y != 0 {
+        (x, y) = (y, x % y);
+    }
+    a / x.max(1) * b
+}
+
 impl Default for WaveformGenerator {
     fn default() -> Self {
         Self::new()
@@ -405,6 +584,8 @@ mod tests {
             description: "Test wave".to_string(),
             params: WaveformParams::default(),
             custom_points: Some(vec![(0, 0), (500, 100), (1000, 0)]),
+            frames: None,
+            modulation: Vec::new(),
         };
         let json = serde_json::to_string(&wf).unwrap();
         let deserialized: Waveform = serde_json::from_str(&json).unwrap();
@@ -412,6 +593,150 @@ mod tests {
         assert_eq!(deserialized.custom_points.unwrap().len(), 3);
     }
 
+    #[test]
+    fn test_waveform_to_device_config() {
+        let wf = Waveform {
+            params: WaveformParams {
+                frequency: 50,
+                pulse_width: 300,
+                max_power: 80,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let config = wf.to_device_config();
+        assert_eq!(config.waveform_type, DeviceWaveformType::Continuous);
+        assert_eq!(config.frequency, 50);
+        assert_eq!(config.pulse_width, 300);
+        assert_eq!(config.intensity, 80);
+        assert!(config.custom_data.is_none());
+    }
+
+    #[test]
+    fn test_waveform_modulation_frames() {
+        let wf = Waveform {
+            params: WaveformParams {
+                max_power: 80,
+                period_ms: 500,
+                ..Default::default()
+            },
+            modulation: vec![
+                "intensity:square:1000:50".parse().unwrap(),
+                "frequency:square:1000:20".parse().unwrap(),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            wf.to_device_config().waveform_type,
+            DeviceWaveformType::Custom
+        );
+
+        // 采样长度取基础周期和调制周期的最小公倍数（1 秒 = 10 帧）
+        let frames = wf.to_frames();
+        assert_eq!(frames.len(), 10);
+        assert_eq!(frames[0].intensity, [80; 4]);
+        assert_eq!(frames[0].frequency, [compress_frequency(120); 4]);
+        assert_eq!(frames[9].intensity, [40; 4]);
+        assert_eq!(frames[9].frequency, [compress_frequency(80); 4]);
+
+        // 生成器同样按已生成时长调制
+        let mut gen = WaveformGenerator::with_waveform(wf.clone());
+        assert_eq!(gen.current_power(), 80);
+        assert_eq!(gen.update(600), 40);
+        assert_eq!(gen.current_frequency(), 80);
+
+        // 原始帧同样可以调制
+        let raw = Waveform {
+            frames: Some(vec![WaveformData::uniform(100, 60)]),
+            modulation: vec!["intensity:square:200:50".parse().unwrap()],
+            ..Default::default()
+        };
+        let frames = raw.to_frames();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].intensity, [60; 4]);
+        assert_eq!(frames[1].intensity, [30; 4]);
+    }
+
+    #[test]
+    fn test_waveform_to_device_config_samples_shape() {
+        let wf = Waveform {
+            params: WaveformParams {
+                waveform_type: WaveformType::Pulse,
+                frequency: 50,
+                max_power: 80,
+                period_ms: 1000,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let config = wf.to_device_config();
+        assert_eq!(config.waveform_type, DeviceWaveformType::Custom);
+        assert_eq!(config.intensity, 80);
+
+        // 1 秒周期 = 10 帧，前一半占空比输出
+        let data = config.custom_data.unwrap();
+        assert_eq!(data.len(), 10 * 8);
+        assert_eq!(&data[..8], &[50, 50, 50, 50, 80, 80, 80, 80]);
+        assert_eq!(&data[72..], &[50, 50, 50, 50, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_to_frames_breathing() {
+        let wf = Waveform {
+            params: WaveformParams {
+                waveform_type: WaveformType::Breathing,
+                period_ms: 2000,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let frames = wf.to_frames();
+        assert_eq!(frames.len(), 20);
+
+        let intensity: Vec<u8> = frames.iter().flat_map(|f| f.intensity).collect();
+        assert_eq!(intensity[0], 0);
+        assert_eq!(intensity[40], 100);
+        // 缓慢上升段逐点不减
+        assert!(intensity[..40].windows(2).all(|w| w[0] <= w[1]));
+        assert!(intensity[40] > intensity[79]);
+    }
+
+    #[test]
+    fn test_to_frames_limits() {
+        let mut wf = Waveform::default();
+        wf.params.waveform_type = WaveformType::Sine;
+
+        // 周期短于一帧时按相位回绕
+        wf.params.period_ms = 50;
+        let frames = wf.to_frames();
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].intensity[0], frames[0].intensity[2]);
+
+        wf.params.period_ms = 0;
+        assert_eq!(wf.to_frames().len(), 1);
+
+        wf.params.period_ms = u32::MAX;
+        assert_eq!(wf.to_frames().len(), MAX_SAMPLED_FRAMES);
+    }
+
+    #[test]
+    fn test_waveform_to_device_config_with_frames() {
+        let wf = Waveform {
+            frames: Some(vec![
+                WaveformData::uniform(10, 20),
+                WaveformData::new([10, 20, 30, 40], [0, 50, 100, 50]),
+            ]),
+            ..Default::default()
+        };
+
+        let config = wf.to_device_config();
+        assert_eq!(config.waveform_type, DeviceWaveformType::Custom);
+        assert_eq!(
+            config.custom_data.unwrap(),
+            vec![10, 10, 10, 10, 20, 20, 20, 20, 10, 20, 30, 40, 0, 50, 100, 50]
+        );
+    }
+
     // === WaveformGenerator 基础测试 ===
 
     #[test]
@@ -435,6 +760,8 @@ mod tests {
             description: "Custom wave".to_string(),
             params: WaveformParams::default(),
             custom_points: None,
+            frames: None,
+            modulation: Vec::new(),
         };
         let gen = WaveformGenerator::with_waveform(wf);
         assert_eq!(gen.waveform().name, "Custom");
@@ -732,4 +1059,50 @@ mod tests {
         names.dedup();
         assert_eq!(names.len(), before, "预设波形名称应该唯一");
     }
+
+    #[test]
+    fn test_preview_params() {
+        let waveform = Waveform {
+            params: WaveformParams {
+                waveform_type: WaveformType::Sawtooth,
+                min_power: 0,
+                max_power: 100,
+                period_ms: 1000,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let points = waveform.preview(1000, 250);
+        assert_eq!(
+            points,
+            vec![(0, 0), (250, 25), (500, 50), (750, 75), (1000, 0)]
+        );
+    }
+
+    #[test]
+    fn test_preview_frames() {
+        let waveform = Waveform {
+            frames: Some(vec![
+                WaveformData::new([10; 4], [0, 10, 20, 30]),
+                WaveformData::new([10; 4], [100; 4]),
+            ]),
+            ..Default::default()
+        };
+
+        let points = waveform.preview(250, 25);
+        let intensity: Vec<u8> = points.iter().map(|p| p.1).collect();
+        assert_eq!(
+            intensity,
+            vec![0, 10, 20, 30, 100, 100, 100, 100, 0, 10, 20]
+        );
+    }
+
+    #[test]
+    fn test_preview_limits_points() {
+        let points = Waveform::default().preview(3_600_000, 1);
+        assert!(points.len() <= MAX_PREVIEW_POINTS);
+        assert_eq!(points[0].0, 0);
+        assert!(points.last().unwrap().0 <= 3_600_000);
+    }
 }
@@ -0,0 +1,763 @@
+//! 波形库（`.dgwave` 文件）
+//!
+//! `.dgwave` 是 JSON 文件，按 `format` 字段区分四种写法：
+//!
+//! ```json
+//! { "name": "Wave", "format": "params", "params": { ... }, "custom_points": [[0, 0], [500, 80]] }
+//! { "name": "Ramp", "format": "frames", "frames": [{ "frequency": [10, 10, 10, 10], "intensity": [0, 10, 20, 30] }] }
+//! { "name": "App",  "format": "pulses", "pulses": ["0a0a0a0a00000000", "0a0a0a0a14141414"] }
+//! { "name": "Edit", "format": "curve", "points": [{ "time_ms": 0, "frequency": 10, "intensity": 0 }] }
+//! ```
+//!
+//! `pulses` 为 APP 使用的 8 字节 HEX 波形数组（4 字节频率 + 4 字节强度，每条 100ms）。
+//! `curve` 为波形编辑器保存的控制点曲线，加载时按 25ms 网格转换为原始帧。
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use dglab_protocol::v3::WaveformData;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+use super::custom::{CurvePoint, CustomWaveform};
+use super::generator::{Waveform, WaveformGenerator, WaveformParams, WaveformType};
+use crate::error::{CoreError, Result};
+
+/// 波形文件扩展名
+pub const WAVE_FILE_EXTENSION: &str = "dgwave";
+
+/// `.dgwave` 文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WaveFile {
+    /// 波形名称
+    pub name: String,
+    /// 波形描述
+    #[serde(default)]
+    pub description: String,
+    /// 波形数据
+    #[serde(flatten)]
+    pub data: WaveFileData,
+}
+
+/// 波形文件数据
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "format", rename_all = "snake_case")]
+pub enum WaveFileData {
+    /// 波形参数（可带自定义数据点）
+    Params {
+        /// 波形参数
+        params: WaveformParams,
+        /// 自定义数据点
+        #[serde(default)]
+        custom_points: Option<Vec<(u32, u8)>>,
+    },
+    /// V3 原始帧
+    Frames {
+        /// 波形帧（每帧 100ms）
+        frames: Vec<WaveformData>,
+    },
+    /// APP 格式 8 字节 HEX 数组
+    Pulses {
+        /// HEX 字符串（每条 16 个字符）
+        pulses: Vec<String>,
+    },
+    /// 自定义曲线控制点
+    Curve {
+        /// 控制点（时间严格递增）
+        points: Vec<CurvePoint>,
+    },
+}
+
+impl WaveFile {
+    /// 从 JSON 文本解析
+    pub fn from_json_str(content: &str) -> Result<Self> {
+        Ok(serde_json::from_str(content)?)
+    }
+
+    /// 从文件加载
+    pub async fn load(path: &Path) -> Result<Self> {
+        let content = tokio::fs::read_to_string(path).await?;
+        Self::from_json_str(&content)
+    }
+
+    /// 保存到文件
+    pub async fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+
+        let content = serde_json::to_string_pretty(self)?;
+        tokio::fs::write(path, content).await?;
+        Ok(())
+    }
+
+    /// 从 APP 格式 HEX 数组创建（转换为原始帧）
+    pub fn from_app_pulses(name: String, description: String, pulses: &[String]) -> Result<Self> {
+        Ok(Self {
+            name,
+            description,
+            data: WaveFileData::Frames {
+                frames: parse_app_pulses(pulses)?,
+            },
+        })
+    }
+
+    /// 从自定义曲线创建
+    pub fn from_custom(curve: &CustomWaveform) -> Self {
+        Self {
+            name: curve.name.clone(),
+            description: curve.description.clone(),
+            data: WaveFileData::Curve {
+                points: curve.points.clone(),
+            },
+        }
+    }
+
+    /// 从波形创建（有原始帧时保存原始帧，否则保存波形参数；调制层不保存）
+    pub fn from_waveform(waveform: &Waveform) -> Self {
+        let data = match &waveform.frames {
+            Some(frames) => WaveFileData::Frames {
+                frames: frames.clone(),
+            },
+            None => WaveFileData::Params {
+                params: waveform.params.clone(),
+                custom_points: waveform.custom_points.clone(),
+            },
+        };
+        Self {
+            name: waveform.name.clone(),
+            description: waveform.description.clone(),
+            data,
+        }
+    }
+
+    /// 转换为波形（校验帧数据）
+    pub fn to_waveform(&self) -> Result<Waveform> {
+        let (params, custom_points, frames) = match &self.data {
+            WaveFileData::Params {
+                params,
+                custom_points,
+            } => (params.clone(), custom_points.clone(), None),
+            WaveFileData::Frames { frames } => {
+                validate_frames(&self.name, frames)?;
+                (frame_params(), None, Some(frames.clone()))
+            }
+            WaveFileData::Pulses { pulses } => {
+                let frames = parse_app_pulses(pulses)?;
+                validate_frames(&self.name, &frames)?;
+                (frame_params(), None, Some(frames))
+            }
+            WaveFileData::Curve { points } => {
+                let curve = CustomWaveform {
+                    name: self.name.clone(),
+                    description: self.description.clone(),
+                    points: points.clone(),
+                };
+                (frame_params(), None, Some(curve.to_frames()?))
+            }
+        };
+
+        Ok(Waveform {
+            name: self.name.clone(),
+            description: self.description.clone(),
+            params,
+            custom_points,
+            frames,
+            modulation: Vec::new(),
+        })
+    }
+}
+
+/// 原始帧波形的参数（仅用于显示，输出以帧为准）
+pub(super) fn frame_params() -> WaveformParams {
+    WaveformParams {
+        waveform_type: WaveformType::Custom,
+        ..Default::default()
+    }
+}
+
+/// 校验帧数据非空且在设备有效范围内
+pub(super) fn validate_frames(name: &str, frames: &[WaveformData]) -> Result<()> {
+    if frames.is_empty() {
+        return Err(CoreError::InvalidParameter(format!(
+            "Waveform '{}' has no frames",
+            name
+        )));
+    }
+
+    if let Some(index) = frames.iter().position(|f| !f.is_valid()) {
+        return Err(CoreError::InvalidParameter(format!(
+            "Waveform '{}' frame {} is out of range (frequency 10~240, intensity 0~100)",
+            name, index
+        )));
+    }
+
+    Ok(())
+}
+
+/// 解析 APP 格式 HEX 波形（16 个十六进制字符）
+pub fn parse_app_pulse(pulse: &str) -> Result<WaveformData> {
+    let pulse = pulse.trim();
+    pulse
+        .is_ascii()
+        .then(|| WaveformData::from_hex_string(pulse))
+        .flatten()
+        .ok_or_else(|| CoreError::InvalidParameter(format!("Invalid pulse hex: {}", pulse)))
+}
+
+/// 解析 APP 格式 HEX 波形数组
+pub fn parse_app_pulses(pulses: &[String]) -> Result<Vec<WaveformData>> {
+    pulses.iter().map(|p| parse_app_pulse(p)).collect()
+}
+
+/// 内置波形图库：仿官方 APP 同名波形的原始帧序列
+///
+/// 按 APP 中波形的节奏和起伏近似还原，频率和强度为相对值，实际强度仍由通道强度决定。
+pub fn gallery_waveforms() -> Vec<Waveform> {
+    let patterns: [(&str, &str, Vec<WaveformData>); 16] = [
+        (
+            "呼吸",
+            "Breath: slow rise to full, short hold and pause",
+            [ramp(10, 0, 100, 10), hold(10, 100, 2), hold(10, 0, 2)].concat(),
+        ),
+        (
+            "潮汐",
+            "Tide: slow swell with rising frequency, then ebb",
+            [sweep(10, 40, 0, 100, 12), sweep(40, 10, 100, 0, 12)].concat(),
+        ),
+        (
+            "连击",
+            "Combo: burst of quick hits followed by a pause",
+            [
+                [hold(10, 100, 1), hold(10, 0, 1)].concat().repeat(4),
+                hold(10, 0, 4),
+            ]
+            .concat(),
+        ),
+        (
+            "快速按捏",
+            "Quick pinch: alternating full and silent frames",
+            [hold(10, 0, 1), hold(10, 100, 1)].concat(),
+        ),
+        (
+            "按捏渐强",
+            "Pinch crescendo: pinches growing stronger each time",
+            (1..=5)
+                .flat_map(|step| [hold(10, step * 20, 1), hold(10, 0, 1)].concat())
+                .collect(),
+        ),
+        (
+            "心跳节奏",
+            "Heartbeat: two beats and a rest",
+            [
+                ramp(30, 100, 40, 1),
+                hold(30, 0, 1),
+                ramp(30, 80, 30, 1),
+                hold(30, 0, 5),
+            ]
+            .concat(),
+        ),
+        (
+            "压缩",
+            "Compress: frequency sweeps from high to low at full intensity",
+            sweep(240, 10, 100, 100, 15),
+        ),
+        (
+            "节奏步伐",
+            "Rhythm steps: intensity climbs in even steps",
+            (0..=5).flat_map(|step| hold(20, step * 20, 2)).collect(),
+        ),
+        (
+            "颗粒摩擦",
+            "Grain: high frequency texture with random-feeling gaps",
+            [
+                WaveformData::new([120, 120, 120, 120], [100, 0, 100, 0]),
+                WaveformData::new([140, 140, 140, 140], [0, 100, 100, 0]),
+                WaveformData::new([110, 110, 110, 110], [100, 100, 0, 100]),
+                WaveformData::new([160, 160, 160, 160], [0, 0, 100, 100]),
+            ]
+            .to_vec(),
+        ),
+        (
+            "渐变弹跳",
+            "Bounce: decaying bounces",
+            [100u8, 75, 50, 25]
+                .into_iter()
+                .flat_map(|peak| [ramp(15, peak, 0, 1), hold(15, 0, 1)].concat())
+                .collect(),
+        ),
+        (
+            "波浪涟漪",
+            "Ripple: small waves riding a large one",
+            (0..16u8)
+                .map(|frame| {
+                    // 大波 30~70，每帧叠加一个 ±20 的小波
+                    let base = 70 - 5 * frame.abs_diff(8);
+                    WaveformData::new([20; 4], [base - 20, base, base + 20, base])
+                })
+                .collect(),
+        ),
+        (
+            "雨水冲刷",
+            "Rain: scattered drops over a light wash",
+            [
+                hold(60, 30, 2),
+                vec![WaveformData::new([60; 4], [30, 100, 30, 30])],
+                hold(60, 30, 1),
+                vec![WaveformData::new([60; 4], [30, 30, 100, 30])],
+                vec![WaveformData::new([60; 4], [100, 30, 30, 100])],
+                hold(60, 30, 2),
+            ]
+            .concat(),
+        ),
+        (
+            "变速敲击",
+            "Variable tapping: taps speeding up, then slowing down",
+            [4usize, 3, 2, 1, 1, 2, 3]
+                .into_iter()
+                .flat_map(|gap| [hold(25, 100, 1), hold(25, 0, gap)].concat())
+                .collect(),
+        ),
+        (
+            "信号灯",
+            "Signal: three levels in turn like a traffic light",
+            [hold(50, 100, 3), hold(50, 50, 3), hold(50, 0, 3)].concat(),
+        ),
+        (
+            "挑逗1",
+            "Tease 1: gentle rise cut off before the peak",
+            [ramp(10, 0, 70, 6), hold(10, 0, 3)].concat(),
+        ),
+        (
+            "挑逗2",
+            "Tease 2: fast flutter with occasional strong hits",
+            [
+                [hold(200, 40, 1), hold(200, 0, 1)].concat().repeat(3),
+                hold(30, 100, 2),
+                hold(30, 0, 2),
+            ]
+            .concat(),
+        ),
+    ];
+
+    patterns
+        .into_iter()
+        .map(|(name, description, frames)| Waveform {
+            name: name.to_string(),
+            description: description.to_string(),
+            params: frame_params(),
+            custom_points: None,
+            frames: Some(frames),
+            modulation: Vec::new(),
+        })
+        .collect()
+}
+
+/// 全部内置波形：参数波形和波形图库
+pub fn builtin_waveforms() -> Vec<Waveform> {
+    let mut waveforms = WaveformGenerator::preset_waveforms();
+    waveforms.extend(gallery_waveforms());
+    waveforms
+}
+
+/// 固定频率和强度的若干帧
+fn hold(frequency: u8, intensity: u8, frames: usize) -> Vec<WaveformData> {
+    vec![WaveformData::uniform(frequency, intensity); frames]
+}
+
+/// 固定频率，强度在若干帧内线性变化（按 25ms 插值）
+fn ramp(frequency: u8, from: u8, to: u8, frames: usize) -> Vec<WaveformData> {
+    sweep(frequency, frequency, from, to, frames)
+}
+
+/// 频率和强度在若干帧内同时线性变化（按 25ms 插值）
+fn sweep(
+    from_frequency: u8,
+    to_frequency: u8,
+    from: u8,
+    to: u8,
+    frames: usize,
+) -> Vec<WaveformData> {
+    let samples = (frames * 4).max(2) - 1;
+    let lerp = |a: u8, b: u8, i: usize| {
+        (a as i32 + (b as i32 - a as i32) * i as i32 / samples as i32) as u8
+    };
+    (0..frames)
+        .map(|frame| {
+            let at = |offset: usize| frame * 4 + offset;
+            WaveformData::new(
+                std::array::from_fn(|j| lerp(from_frequency, to_frequency, at(j))),
+                std::array::from_fn(|j| lerp(from, to, at(j))),
+            )
+        })
+        .collect()
+}
+
+/// 波形库
+///
+/// 包含内置波形（[`builtin_waveforms`]）和用户目录下的 `.dgwave` 文件，按名称（不区分大小写）查找，
+/// 用户波形与内置波形同名时覆盖内置波形。
+#[derive(Debug, Clone)]
+pub struct WaveformLibrary {
+    /// 用户波形目录
+    dir: Option<PathBuf>,
+    /// 用户波形（小写名称 → 波形）
+    waveforms: HashMap<String, Waveform>,
+}
+
+impl WaveformLibrary {
+    /// 创建波形库（需调用 [`load`](Self::load) 加载用户波形）
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir: Some(dir),
+            waveforms: HashMap::new(),
+        }
+    }
+
+    /// 仅包含内置波形的波形库
+    pub fn builtin() -> Self {
+        Self {
+            dir: None,
+            waveforms: HashMap::new(),
+        }
+    }
+
+    /// 使用默认目录创建波形库
+    pub fn default_dir() -> Result<Self> {
+        Ok(Self::new(Self::default_storage_dir()?))
+    }
+
+    /// 获取默认波形目录
+    pub fn default_storage_dir() -> Result<PathBuf> {
+        let dir = dirs::config_dir()
+            .ok_or_else(|| CoreError::Other("Could not find config directory".to_string()))?
+            .join("dglab")
+            .join("waveforms");
+
+        Ok(dir)
+    }
+
+    /// 获取用户波形目录
+    pub fn dir(&self) -> Option<&Path> {
+        self.dir.as_deref()
+    }
+
+    /// 用户波形的文件路径（由名称生成文件名），没有用户目录时返回 `None`
+    pub fn user_path(&self, name: &str) -> Option<PathBuf> {
+        let stem: String = name
+            .trim()
+            .chars()
+            .map(|c| {
+                if c.is_alphanumeric() {
+                    c.to_ascii_lowercase()
+                } else {
+                    '-'
+                }
+            })
+            .collect();
+        self.dir
+            .as_ref()
+            .map(|dir| dir.join(format!("{}.{}", stem, WAVE_FILE_EXTENSION)))
+    }
+
+    /// 加载用户目录下的所有 `.dgwave` 文件
+    ///
+    /// 目录不存在时视为空；无效文件记录警告后跳过。
+    pub async fn load(&mut self) -> Result<()> {
+        self.waveforms.clear();
+
+        let Some(dir) = &self.dir else {
+            return Ok(());
+        };
+        if !dir.exists() {
+            return Ok(());
+        }
+
+        let mut entries = tokio::fs::read_dir(dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some(WAVE_FILE_EXTENSION) {
+                continue;
+            }
+
+            match WaveFile::load(&path)
+                .await
+                .and_then(|file| file.to_waveform())
+            {
+                Ok(waveform) => {
+                    debug!("Loaded waveform '{}' from {:?}", waveform.name, path);
+                    let _ = self
+                        .waveforms
+                        .insert(waveform.name.to_lowercase(), waveform);
+                }
+                Err(e) => warn!("Skipping invalid waveform file {:?}: {}", path, e),
+            }
+        }
+
+        info!("Loaded {} waveforms from {:?}", self.waveforms.len(), dir);
+        Ok(())
+    }
+
+    /// 添加用户波形（同名覆盖）
+    pub fn insert(&mut self, waveform: Waveform) {
+        let _ = self
+            .waveforms
+            .insert(waveform.name.to_lowercase(), waveform);
+    }
+
+    /// 按名称查找波形
+    pub fn get(&self, name: &str) -> Option<Waveform> {
+        self.waveforms
+            .get(&name.to_lowercase())
+            .cloned()
+            .or_else(|| {
+                builtin_waveforms()
+                    .into_iter()
+                    .find(|w| w.name.eq_ignore_ascii_case(name))
+            })
+    }
+
+    /// 按名称查找波形，不存在时返回错误
+    pub fn resolve(&self, name: &str) -> Result<Waveform> {
+        self.get(name)
+            .ok_or_else(|| CoreError::InvalidParameter(format!("Unknown waveform: {}", name)))
+    }
+
+    /// 列出所有波形（按名称排序）
+    pub fn list(&self) -> Vec<Waveform> {
+        let mut waveforms: Vec<_> = builtin_waveforms()
+            .into_iter()
+            .filter(|w| !self.waveforms.contains_key(&w.name.to_lowercase()))
+            .chain(self.waveforms.values().cloned())
+            .collect();
+        waveforms.sort_by_key(|w| w.name.to_lowercase());
+        waveforms
+    }
+
+    /// 是否为用户波形
+    pub fn is_user_waveform(&self, name: &str) -> bool {
+        self.waveforms.contains_key(&name.to_lowercase())
+    }
+}
+
+impl Default for WaveformLibrary {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_params_file() {
+        let file = WaveFile::from_json_str(
+            r#"{
+                "name": "Slow",
+                "format": "params",
+                "params": {
+                    "waveform_type": "Sine",
+                    "frequency": 20,
+                    "pulse_width": 200,
+                    "min_power": 0,
+                    "max_power": 60,
+                    "period_ms": 4000,
+                    "duty_cycle": 50
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let waveform = file.to_waveform().unwrap();
+        assert_eq!(waveform.name, "Slow");
+        assert_eq!(waveform.params.waveform_type, WaveformType::Sine);
+        assert!(waveform.frames.is_none());
+    }
+
+    #[test]
+    fn test_parse_frames_file() {
+        let file = WaveFile::from_json_str(
+            r#"{
+                "name": "Ramp",
+                "format": "frames",
+                "frames": [
+                    { "frequency": [10, 10, 10, 10], "intensity": [0, 10, 20, 30] },
+                    { "frequency": [10, 10, 10, 10], "intensity": [40, 50, 60, 70] }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let waveform = file.to_waveform().unwrap();
+        let frames = waveform.frames.unwrap();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[1].intensity, [40, 50, 60, 70]);
+    }
+
+    #[test]
+    fn test_parse_curve_file() {
+        let file = WaveFile::from_json_str(
+            r#"{
+                "name": "Edit",
+                "format": "curve",
+                "points": [
+                    { "time_ms": 0, "frequency": 10, "intensity": 0 },
+                    { "time_ms": 100, "frequency": 10, "intensity": 40 }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let waveform = file.to_waveform().unwrap();
+        let frames = waveform.frames.unwrap();
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].intensity, [0, 10, 20, 30]);
+
+        let unordered = WaveFile {
+            data: WaveFileData::Curve {
+                points: vec![CurvePoint::new(100, 10, 0), CurvePoint::new(0, 10, 0)],
+            },
+            ..file
+        };
+        assert!(unordered.to_waveform().is_err());
+    }
+
+    #[test]
+    fn test_parse_pulses_file() {
+        let file = WaveFile::from_json_str(
+            r#"{ "name": "App", "format": "pulses", "pulses": ["0A0A0A0A00000000", "0a0a0a0a64646464"] }"#,
+        )
+        .unwrap();
+
+        let frames = file.to_waveform().unwrap().frames.unwrap();
+        assert_eq!(frames[0], WaveformData::uniform(10, 0));
+        assert_eq!(frames[1], WaveformData::uniform(10, 100));
+    }
+
+    #[test]
+    fn test_reject_invalid_frames() {
+        let empty = WaveFile::from_json_str(r#"{ "name": "E", "format": "frames", "frames": [] }"#)
+            .unwrap();
+        assert!(empty.to_waveform().is_err());
+
+        // 强度超过 100
+        let out_of_range = WaveFile::from_json_str(
+            r#"{ "name": "X", "format": "pulses", "pulses": ["0a0a0a0a65656565"] }"#,
+        )
+        .unwrap();
+        assert!(out_of_range.to_waveform().is_err());
+
+        assert!(WaveFile::from_json_str(r#"{ "name": "U", "format": "unknown" }"#).is_err());
+    }
+
+    #[test]
+    fn test_parse_app_pulse() {
+        assert_eq!(
+            parse_app_pulse(" 0a0b0c0d01020304 ").unwrap(),
+            WaveformData::new([10, 11, 12, 13], [1, 2, 3, 4])
+        );
+        assert!(parse_app_pulse("0a0b0c0d010203").is_err());
+        assert!(parse_app_pulse("zz0b0c0d01020304").is_err());
+        assert!(parse_app_pulse("0a0b0c0d010203é").is_err());
+    }
+
+    #[test]
+    fn test_from_app_pulses_roundtrip() {
+        let pulses = vec![
+            "0a0a0a0a00000000".to_string(),
+            "1414141432323232".to_string(),
+        ];
+        let file =
+            WaveFile::from_app_pulses("Imported".to_string(), String::new(), &pulses).unwrap();
+
+        let json = serde_json::to_string(&file).unwrap();
+        assert!(json.contains(r#""format":"frames""#));
+
+        let restored = WaveFile::from_json_str(&json).unwrap();
+        let frames = restored.to_waveform().unwrap().frames.unwrap();
+        let hex: Vec<String> = frames.iter().map(WaveformData::to_hex_string).collect();
+        assert_eq!(hex, pulses);
+    }
+
+    #[test]
+    fn test_builtin_library() {
+        let library = WaveformLibrary::builtin();
+        assert!(library.get("breathing").is_some());
+        assert!(library.resolve("nope").is_err());
+        assert_eq!(
+            library.list().len(),
+            WaveformGenerator::preset_waveforms().len() + gallery_waveforms().len()
+        );
+        assert!(library.get("潮汐").unwrap().frames.is_some());
+    }
+
+    #[test]
+    fn test_gallery_waveforms_valid() {
+        let gallery = gallery_waveforms();
+        let mut names: Vec<_> = gallery.iter().map(|w| w.name.to_lowercase()).collect();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), gallery.len());
+
+        for waveform in &gallery {
+            validate_frames(&waveform.name, waveform.frames.as_ref().unwrap()).unwrap();
+            assert!(WaveformGenerator::preset_waveforms()
+                .iter()
+                .all(|w| !w.name.eq_ignore_ascii_case(&waveform.name)));
+        }
+    }
+
+    #[test]
+    fn test_gallery_helpers() {
+        assert_eq!(
+            ramp(10, 0, 100, 1),
+            vec![WaveformData::new([10; 4], [0, 33, 66, 100])]
+        );
+        let frames = sweep(240, 10, 100, 100, 2);
+        assert_eq!(frames[0].frequency[0], 240);
+        assert_eq!(frames[1].frequency[3], 10);
+        assert_eq!(hold(10, 0, 3).len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_load_user_waveforms() {
+        let tmp = TempDir::new().unwrap();
+        let pulses = vec!["0a0a0a0a32323232".to_string()];
+        WaveFile::from_app_pulses("Custom One".to_string(), String::new(), &pulses)
+            .unwrap()
+            .save(&tmp.path().join("custom.dgwave"))
+            .await
+            .unwrap();
+        // 覆盖内置波形
+        WaveFile::from_app_pulses("Pulse".to_string(), String::new(), &pulses)
+            .unwrap()
+            .save(&tmp.path().join("pulse.dgwave"))
+            .await
+            .unwrap();
+        // 无效文件和其它扩展名被忽略
+        tokio::fs::write(tmp.path().join("broken.dgwave"), "{")
+            .await
+            .unwrap();
+        tokio::fs::write(tmp.path().join("notes.json"), "{}")
+            .await
+            .unwrap();
+
+        let mut library = WaveformLibrary::new(tmp.path().to_path_buf());
+        library.load().await.unwrap();
+
+        assert!(library.is_user_waveform("custom one"));
+        assert!(library.get("CUSTOM ONE").unwrap().frames.is_some());
+        assert!(library.get("pulse").unwrap().frames.is_some());
+        assert_eq!(library.list().len(), builtin_waveforms().len() + 1);
+    }
+
+    #[tokio::test]
+    async fn test_load_missing_dir() {
+        let tmp = TempDir::new().unwrap();
+        let mut library = WaveformLibrary::new(tmp.path().join("missing"));
+        library.load().await.unwrap();
+        assert!(library.get("continuous").is_some());
+    }
+}
@@ -1,5 +1,21 @@
 //! 波形生成模块
 
+pub mod custom;
 pub mod generator;
+pub mod library;
+pub mod modulation;
+pub mod pulse_file;
+pub mod stereo;
 
-pub use generator::{Waveform, WaveformGenerator, WaveformParams, WaveformType};
+pub use custom::{CurvePoint, CustomWaveform};
+pub use generator::{
+    Waveform, WaveformGenerator, WaveformParams, WaveformType, MAX_PREVIEW_POINTS,
+    MAX_SAMPLED_FRAMES, SAMPLE_INTERVAL_MS,
+};
+pub use library::{
+    builtin_waveforms, gallery_waveforms, WaveFile, WaveFileData, WaveformLibrary,
+    WAVE_FILE_EXTENSION,
+};
+pub use modulation::{LfoShape, Modulation, ModulationTarget};
+pub use pulse_file::PulseFile;
+pub use stereo::{StereoMode, StereoOutput, StereoPattern};
@@ -0,0 +1,256 @@
+//! 波形调制（LFO）
+//!
+//! 在基础波形之上叠加低频振荡器，按深度和周期调制输出强度或脉冲频率。
+//! 多个调制层依次叠加（相乘），每个通道独立配置。
+//!
+//! 文本格式为 `目标:形状:周期ms:深度`，例如 `intensity:sine:4000:50`。
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{CoreError, Result};
+
+/// 调制周期下限（一帧）
+pub const MIN_MODULATION_RATE_MS: u32 = 100;
+
+/// 调制周期上限
+pub const MAX_MODULATION_RATE_MS: u32 = 60_000;
+
+/// 脉冲频率范围（Hz，与 V3 频率压缩的输入范围一致）
+const FREQUENCY_RANGE: (f64, f64) = (10.0, 1000.0);
+
+/// 调制目标
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModulationTarget {
+    /// 波形强度
+    Intensity,
+    /// 脉冲频率
+    Frequency,
+}
+
+/// LFO 形状
+///
+/// 相位 0 时输出 1，取值范围 0~1。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LfoShape {
+    /// 正弦
+    #[default]
+    Sine,
+    /// 三角
+    Triangle,
+    /// 方波（前半周期为 1）
+    Square,
+    /// 锯齿（从 1 线性下降到 0）
+    Sawtooth,
+}
+
+impl LfoShape {
+    /// 相位 `phase`（0~1）处的输出值（0~1）
+    pub fn value(self, phase: f64) -> f64 {
+        match self {
+            Self::Sine => 0.5 + 0.5 * (phase * 2.0 * std::f64::consts::PI).cos(),
+            Self::Triangle => (1.0 - 2.0 * phase).abs(),
+            Self::Square => {
+                if phase < 0.5 {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            Self::Sawtooth => 1.0 - phase,
+        }
+    }
+}
+
+/// 一个调制层
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Modulation {
+    /// 调制目标
+    pub target: ModulationTarget,
+    /// LFO 形状
+    #[serde(default)]
+    pub shape: LfoShape,
+    /// LFO 周期（毫秒）
+    pub rate_ms: u32,
+    /// 调制深度（0~100%）
+    ///
+    /// 强度调制时输出在 `(1 - 深度)` 到 1 倍之间变化；频率调制时在 `1 ± 深度` 倍之间变化。
+    pub depth: u8,
+}
+
+impl Modulation {
+    /// 校验周期和深度
+    pub fn validate(&self) -> Result<()> {
+        if !(MIN_MODULATION_RATE_MS..=MAX_MODULATION_RATE_MS).contains(&self.rate_ms) {
+            return Err(CoreError::InvalidParameter(format!(
+                "Modulation rate {}ms out of range {}-{}ms",
+                self.rate_ms, MIN_MODULATION_RATE_MS, MAX_MODULATION_RATE_MS
+            )));
+        }
+        if self.depth > 100 {
+            return Err(CoreError::InvalidParameter(format!(
+                "Modulation depth {}% exceeds 100%",
+                self.depth
+            )));
+        }
+        Ok(())
+    }
+
+    /// 时间 `elapsed_ms` 处的 LFO 输出（0~1）
+    pub fn lfo(&self, elapsed_ms: u64) -> f64 {
+        let rate = u64::from(self.rate_ms.max(1));
+        self.shape.value((elapsed_ms % rate) as f64 / rate as f64)
+    }
+
+    /// 时间 `elapsed_ms` 处的调制系数
+    fn factor(&self, elapsed_ms: u64) -> f64 {
+        let depth = f64::from(self.depth.min(100)) / 100.0;
+        let lfo = self.lfo(elapsed_ms);
+        match self.target {
+            ModulationTarget::Intensity => 1.0 - depth * (1.0 - lfo),
+            ModulationTarget::Frequency => 1.0 + depth * (2.0 * lfo - 1.0),
+        }
+    }
+}
+
+impl fmt::Display for Modulation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let target = match self.target {
+            ModulationTarget::Intensity => "intensity",
+            ModulationTarget::Frequency => "frequency",
+        };
+        let shape = match self.shape {
+            LfoShape::Sine => "sine",
+            LfoShape::Triangle => "triangle",
+            LfoShape::Square => "square",
+            LfoShape::Sawtooth => "sawtooth",
+        };
+        write!(f, "{}:{}:{}:{}", target, shape, self.rate_ms, self.depth)
+    }
+}
+
+impl FromStr for Modulation {
+    type Err = CoreError;
+
+    /// 解析 `目标:形状:周期ms:深度`（目标可简写为 `i`/`f`）
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || {
+            CoreError::InvalidParameter(format!(
+                "Invalid modulation '{}', expected target:shape:rate_ms:depth",
+                s
+            ))
+        };
+
+        let parts: Vec<&str> = s.split(':').map(str::trim).collect();
+        let [target, shape, rate, depth] = parts.as_slice() else {
+            return Err(invalid());
+        };
+
+        let target = match target.to_ascii_lowercase().as_str() {
+            "intensity" | "i" => ModulationTarget::Intensity,
+            "frequency" | "freq" | "f" => ModulationTarget::Frequency,
+            _ => return Err(invalid()),
+        };
+        let shape = match shape.to_ascii_lowercase().as_str() {
+            "sine" => LfoShape::Sine,
+            "triangle" => LfoShape::Triangle,
+            "square" => LfoShape::Square,
+            "sawtooth" | "saw" => LfoShape::Sawtooth,
+            _ => return Err(invalid()),
+        };
+        let modulation = Self {
+            target,
+            shape,
+            rate_ms: rate.parse().map_err(|_| invalid())?,
+            depth: depth.trim_end_matches('%').parse().map_err(|_| invalid())?,
+        };
+        modulation.validate()?;
+        Ok(modulation)
+    }
+}
+
+/// 按调制层调制强度（0~100）
+pub fn modulate_intensity(layers: &[Modulation], intensity: u8, elapsed_ms: u64) -> u8 {
+    let value = layers
+        .iter()
+        .filter(|m| m.target == ModulationTarget::Intensity)
+        .fold(f64::from(intensity), |value, m| {
+            value * m.factor(elapsed_ms)
+        });
+    value.round().clamp(0.0, 255.0) as u8
+}
+
+/// 按调制层调制脉冲频率（Hz，结果限制在 10~1000）
+pub fn modulate_frequency(layers: &[Modulation], frequency: u16, elapsed_ms: u64) -> u16 {
+    let value = layers
+        .iter()
+        .filter(|m| m.target == ModulationTarget::Frequency)
+        .fold(f64::from(frequency), |value, m| {
+            value * m.factor(elapsed_ms)
+        });
+    value.round().clamp(FREQUENCY_RANGE.0, FREQUENCY_RANGE.1) as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lfo_shapes() {
+        for shape in [
+            LfoShape::Sine,
+            LfoShape::Triangle,
+            LfoShape::Square,
+            LfoShape::Sawtooth,
+        ] {
+            assert!((shape.value(0.0) - 1.0).abs() < 1e-9);
+        }
+        assert!(LfoShape::Sine.value(0.5).abs() < 1e-9);
+        assert!(LfoShape::Triangle.value(0.5).abs() < 1e-9);
+        assert_eq!(LfoShape::Square.value(0.75), 0.0);
+        assert!((LfoShape::Sawtooth.value(0.25) - 0.75).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_modulate_intensity_and_frequency() {
+        let layers = [
+            "intensity:square:1000:50".parse::<Modulation>().unwrap(),
+            "frequency:square:1000:20".parse::<Modulation>().unwrap(),
+        ];
+
+        // 前半周期 LFO 为 1：强度不变，频率上调 20%
+        assert_eq!(modulate_intensity(&layers, 80, 0), 80);
+        assert_eq!(modulate_frequency(&layers, 100, 0), 120);
+        // 后半周期 LFO 为 0：强度减半，频率下调 20%
+        assert_eq!(modulate_intensity(&layers, 80, 600), 40);
+        assert_eq!(modulate_frequency(&layers, 100, 600), 80);
+
+        // 多层强度调制依次相乘
+        let stacked = [
+            "i:square:1000:50".parse::<Modulation>().unwrap(),
+            "i:square:2000:50".parse::<Modulation>().unwrap(),
+        ];
+        assert_eq!(modulate_intensity(&stacked, 80, 1500), 20);
+
+        // 频率结果不超出范围
+        assert_eq!(modulate_frequency(&layers, 1000, 0), 1000);
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        let m: Modulation = "Frequency:saw:2000:30%".parse().unwrap();
+        assert_eq!(m.target, ModulationTarget::Frequency);
+        assert_eq!(m.shape, LfoShape::Sawtooth);
+        assert_eq!(m.to_string(), "frequency:sawtooth:2000:30");
+        assert_eq!(m.to_string().parse::<Modulation>().unwrap(), m);
+
+        assert!("intensity:sine:50:30".parse::<Modulation>().is_err());
+        assert!("intensity:sine:1000:101".parse::<Modulation>().is_err());
+        assert!("volume:sine:1000:50".parse::<Modulation>().is_err());
+        assert!("intensity:sine:1000".parse::<Modulation>().is_err());
+    }
+}
@@ -0,0 +1,207 @@
+//! APP 格式波形 JSON 文件
+//!
+//! 官方 APP 生态以 8 字节 HEX 字符串数组分享波形（4 字节频率 + 4 字节强度，每条 100ms）。
+//! 支持以下三种写法：
+//!
+//! ```json
+//! ["0a0a0a0a00000000", "0a0a0a0a64646464"]
+//! { "name": "Wave", "pulses": ["0a0a0a0a00000000", "0a0a0a0a64646464"] }
+//! { "name": "Stereo", "A": ["0a0a0a0a64646464"], "B": ["1414141432323232"] }
+//! ```
+//!
+//! 只有一组数据时两个通道使用相同的帧；按通道分开时缺少的通道不输出。
+//! 解析后的帧既可作为 V3 [`WaveformData`] 播放，也可转换为 WiFi [`PulseData`]。
+
+use std::path::Path;
+
+use dglab_protocol::v3::WaveformData;
+use dglab_protocol::wifi::{Channel as WsChannel, PulseData};
+use serde::Deserialize;
+
+use super::generator::Waveform;
+use super::library::{frame_params, parse_app_pulses, validate_frames};
+use crate::error::{CoreError, Result};
+
+/// 文件中的原始写法
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RawPulseFile {
+    /// 纯 HEX 数组
+    List(Vec<String>),
+    /// 带名称的单组数据
+    Single {
+        #[serde(default)]
+        name: Option<String>,
+        #[serde(alias = "data")]
+        pulses: Vec<String>,
+    },
+    /// 按通道分开的数据
+    Channels {
+        #[serde(default)]
+        name: Option<String>,
+        #[serde(default, rename = "A", alias = "a")]
+        channel_a: Option<Vec<String>>,
+        #[serde(default, rename = "B", alias = "b")]
+        channel_b: Option<Vec<String>>,
+    },
+}
+
+/// APP 格式波形文件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PulseFile {
+    /// 文件中的波形名称
+    pub name: Option<String>,
+    /// A 通道帧（为空表示不输出）
+    pub channel_a: Vec<WaveformData>,
+    /// B 通道帧（为空表示不输出）
+    pub channel_b: Vec<WaveformData>,
+}
+
+impl PulseFile {
+    /// 从 JSON 文本解析并校验帧数据
+    pub fn from_json_str(content: &str) -> Result<Self> {
+        let raw: RawPulseFile = serde_json::from_str(content)
+            .map_err(|e| CoreError::InvalidParameter(format!("Invalid pulse file: {}", e)))?;
+
+        let (name, channel_a, channel_b) = match raw {
+            RawPulseFile::List(pulses) => {
+                let frames = parse_app_pulses(&pulses)?;
+                (None, frames.clone(), frames)
+            }
+            RawPulseFile::Single { name, pulses } => {
+                let frames = parse_app_pulses(&pulses)?;
+                (name, frames.clone(), frames)
+            }
+            RawPulseFile::Channels {
+                name,
+                channel_a,
+                channel_b,
+            } => (
+                name,
+                parse_app_pulses(&channel_a.unwrap_or_default())?,
+                parse_app_pulses(&channel_b.unwrap_or_default())?,
+            ),
+        };
+
+        let label = name.as_deref().unwrap_or("pulse file");
+        if channel_a.is_empty() && channel_b.is_empty() {
+            return Err(CoreError::InvalidParameter(format!(
+                "Waveform '{}' has no frames",
+                label
+            )));
+        }
+        for frames in [&channel_a, &channel_b] {
+            if !frames.is_empty() {
+                validate_frames(label, frames)?;
+            }
+        }
+
+        Ok(Self {
+            name,
+            channel_a,
+            channel_b,
+        })
+    }
+
+    /// 从文件加载
+    pub async fn load(path: &Path) -> Result<Self> {
+        let content = tokio::fs::read_to_string(path).await?;
+        Self::from_json_str(&content)
+    }
+
+    /// 通道的帧（为空表示该通道不输出）
+    pub fn frames(&self, channel: u8) -> Result<&[WaveformData]> {
+        match channel {
+            0 => Ok(&self.channel_a),
+            1 => Ok(&self.channel_b),
+            _ => Err(CoreError::InvalidChannel(channel)),
+        }
+    }
+
+    /// 通道的波形（循环播放原始帧），通道没有数据时返回 `None`
+    pub fn to_waveform(&self, channel: u8, name: &str) -> Result<Option<Waveform>> {
+        let frames = self.frames(channel)?;
+        if frames.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(Waveform {
+            name: name.to_string(),
+            description: format!("Imported APP pulses ({} frames)", frames.len()),
+            params: frame_params(),
+            custom_points: None,
+            frames: Some(frames.to_vec()),
+            modulation: Vec::new(),
+        }))
+    }
+
+    /// 通道的 WiFi 波形数据，通道没有数据时返回 `None`
+    ///
+    /// 帧数可能超过单条消息上限，发送前用 [`PulseData::split`] 拆分。
+    pub fn to_pulse_data(&self, channel: u8) -> Result<Option<PulseData>> {
+        let ws_channel = match channel {
+            0 => WsChannel::A,
+            1 => WsChannel::B,
+            _ => return Err(CoreError::InvalidChannel(channel)),
+        };
+        let frames = self.frames(channel)?;
+        Ok((!frames.is_empty()).then(|| {
+            PulseData::new(
+                ws_channel,
+                frames.iter().map(WaveformData::to_hex_string).collect(),
+            )
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOW: &str = "0a0a0a0a00000000";
+    const HIGH: &str = "0a0a0a0a64646464";
+
+    #[test]
+    fn test_parse_formats() {
+        let list = PulseFile::from_json_str(&format!("[\"{}\", \"{}\"]", LOW, HIGH)).unwrap();
+        assert_eq!(list.name, None);
+        assert_eq!(list.channel_a.len(), 2);
+        assert_eq!(list.channel_a, list.channel_b);
+
+        let single =
+            PulseFile::from_json_str(&format!("{{\"name\": \"Wave\", \"data\": [\"{}\"]}}", HIGH))
+                .unwrap();
+        assert_eq!(single.name.as_deref(), Some("Wave"));
+        assert_eq!(single.channel_b, vec![WaveformData::uniform(10, 100)]);
+
+        let stereo =
+            PulseFile::from_json_str(&format!("{{\"A\": [\"{}\", \"{}\"]}}", LOW, HIGH)).unwrap();
+        assert_eq!(stereo.channel_a.len(), 2);
+        assert!(stereo.channel_b.is_empty());
+        assert!(stereo.to_waveform(1, "Stereo").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(PulseFile::from_json_str("[]").is_err());
+        assert!(PulseFile::from_json_str("{\"A\": [], \"B\": []}").is_err());
+        assert!(PulseFile::from_json_str("[\"0a0a\"]").is_err());
+        // 频率超出范围
+        assert!(PulseFile::from_json_str("[\"0101010100000000\"]").is_err());
+        assert!(PulseFile::from_json_str("{\"pulses\": 1}").is_err());
+    }
+
+    #[test]
+    fn test_to_waveform_and_pulse_data() {
+        let file = PulseFile::from_json_str(&format!("[\"{}\", \"{}\"]", LOW, HIGH)).unwrap();
+
+        let waveform = file.to_waveform(0, "Imported").unwrap().unwrap();
+        assert_eq!(waveform.name, "Imported");
+        assert_eq!(waveform.frames.as_deref(), Some(file.channel_a.as_slice()));
+
+        let pulse = file.to_pulse_data(1).unwrap().unwrap();
+        assert_eq!(pulse.channel, WsChannel::B);
+        assert_eq!(pulse.pulses, vec![LOW.to_string(), HIGH.to_string()]);
+
+        assert!(file.to_pulse_data(2).is_err());
+    }
+}
@@ -0,0 +1,383 @@
+//! 立体声编排
+//!
+//! 两路输出（两台设备，或同一设备的 A/B 通道）由同一个相位发生器驱动，播放互补的强度包络：
+//!
+//! - `ping-pong`：两路交替输出，`crossfade` 控制切换时的交叉淡入淡出
+//! - `rotate`：强度按正弦在两路之间平滑转移
+//! - `chase`：两路播放同一锯齿包络，后一路落后前一路，`crossfade` 控制包络回落的长度
+//!
+//! 右路相位落后左路 `phase_offset` 度，默认 180°（完全互补）。波形由调用方统一下发，
+//! 编排只调节各路强度，峰值为给定强度。
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use super::SAMPLE_INTERVAL_MS;
+use crate::error::{CoreError, Result};
+use crate::session::SessionManager;
+
+/// 周期下限（两帧）
+pub const MIN_STEREO_PERIOD_MS: u32 = 200;
+
+/// 周期上限
+pub const MAX_STEREO_PERIOD_MS: u32 = 60_000;
+
+/// 默认相位差（度）
+pub const DEFAULT_PHASE_OFFSET: u16 = 180;
+
+/// 强度更新间隔（一帧）
+pub const STEREO_TICK: Duration = Duration::from_millis(SAMPLE_INTERVAL_MS * 4);
+
+/// 立体声模式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum StereoMode {
+    /// 两路交替
+    #[default]
+    PingPong,
+    /// 正弦转移
+    Rotate,
+    /// 追逐
+    Chase,
+}
+
+impl StereoMode {
+    /// 相位 `phase`（0~1）处的强度系数（0~1），`crossfade` 为 0~1
+    pub fn gain(self, phase: f64, crossfade: f64) -> f64 {
+        match self {
+            Self::PingPong => {
+                // 前半周期为 1 的方波，边沿按交叉淡化宽度线性过渡
+                let width = crossfade * 0.25;
+                if width <= 0.0 {
+                    return if phase < 0.5 { 1.0 } else { 0.0 };
+                }
+                let distance = (phase - 0.25).abs();
+                let distance = distance.min(1.0 - distance);
+                ((0.25 + width - distance) / (2.0 * width)).clamp(0.0, 1.0)
+            }
+            Self::Rotate => 0.5 + 0.5 * (phase * 2.0 * std::f64::consts::PI).sin(),
+            Self::Chase => {
+                // 锯齿上升，周期末尾按交叉淡化宽度回落
+                let fall = crossfade * 0.5;
+                if phase < 1.0 - fall {
+                    phase / (1.0 - fall)
+                } else {
+                    (1.0 - phase) / fall
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for StereoMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::PingPong => "ping-pong",
+            Self::Rotate => "rotate",
+            Self::Chase => "chase",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for StereoMode {
+    type Err = CoreError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().replace('_', "-").as_str() {
+            "ping-pong" | "pingpong" => Ok(Self::PingPong),
+            "rotate" => Ok(Self::Rotate),
+            "chase" => Ok(Self::Chase),
+            _ => Err(CoreError::InvalidParameter(format!(
+                "Invalid stereo mode '{}', expected ping-pong, rotate or chase",
+                s
+            ))),
+        }
+    }
+}
+
+/// 立体声编排参数
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StereoPattern {
+    /// 模式
+    #[serde(default)]
+    pub mode: StereoMode,
+    /// 周期（毫秒）
+    pub period_ms: u32,
+    /// 右路落后左路的相位（0~359 度）
+    #[serde(default = "default_phase_offset")]
+    pub phase_offset: u16,
+    /// 交叉淡化（0~100%，`rotate` 模式不使用）
+    #[serde(default)]
+    pub crossfade: u8,
+}
+
+fn default_phase_offset() -> u16 {
+    DEFAULT_PHASE_OFFSET
+}
+
+impl StereoPattern {
+    /// 使用默认相位差、不淡化
+    pub fn new(mode: StereoMode, period_ms: u32) -> Self {
+        Self {
+            mode,
+            period_ms,
+            phase_offset: DEFAULT_PHASE_OFFSET,
+            crossfade: 0,
+        }
+    }
+
+    /// 校验参数
+    pub fn validate(&self) -> Result<()> {
+        if !(MIN_STEREO_PERIOD_MS..=MAX_STEREO_PERIOD_MS).contains(&self.period_ms) {
+            return Err(CoreError::InvalidParameter(format!(
+                "Stereo period {}ms out of range {}-{}ms",
+                self.period_ms, MIN_STEREO_PERIOD_MS, MAX_STEREO_PERIOD_MS
+            )));
+        }
+        if self.phase_offset >= 360 {
+            return Err(CoreError::InvalidParameter(format!(
+                "Stereo phase offset {}° out of range 0-359°",
+                self.phase_offset
+            )));
+        }
+        if self.crossfade > 100 {
+            return Err(CoreError::InvalidParameter(format!(
+                "Stereo crossfade {}% exceeds 100%",
+                self.crossfade
+            )));
+        }
+        Ok(())
+    }
+
+    /// 时间 `elapsed_ms` 处左右两路的强度系数（0~1）
+    pub fn gains(&self, elapsed_ms: u64) -> (f64, f64) {
+        let period = u64::from(self.period_ms.max(1));
+        let phase = (elapsed_ms % period) as f64 / period as f64;
+        let offset = f64::from(self.phase_offset % 360) / 360.0;
+        let crossfade = f64::from(self.crossfade.min(100)) / 100.0;
+        (
+            self.mode.gain(phase, crossfade),
+            self.mode.gain((phase - offset).rem_euclid(1.0), crossfade),
+        )
+    }
+
+    /// 时间 `elapsed_ms` 处左右两路的强度，峰值为 `power`
+    pub fn levels(&self, elapsed_ms: u64, power: u8) -> (u8, u8) {
+        let (left, right) = self.gains(elapsed_ms);
+        let scale = |gain: f64| (f64::from(power) * gain).round().clamp(0.0, 255.0) as u8;
+        (scale(left), scale(right))
+    }
+
+    /// 经会话在两路输出上播放，直到出错或调用方取消 future
+    ///
+    /// 强度经会话换算校准并按安全限制截断；只在强度变化时写入。取消后强度停留在当前值，
+    /// 由调用方归零。
+    pub async fn run(
+        &self,
+        manager: &SessionManager,
+        outputs: &[StereoOutput; 2],
+        power: u8,
+    ) -> Result<()> {
+        self.validate()?;
+        info!(
+            "Playing {} stereo pattern on {} / {} (period {}ms, offset {}°)",
+            self.mode, outputs[0], outputs[1], self.period_ms, self.phase_offset
+        );
+
+        let start = tokio::time::Instant::now();
+        let mut interval = tokio::time::interval(STEREO_TICK);
+        let mut applied: [Option<u8>; 2] = [None, None];
+
+        loop {
+            let _ = interval.tick().await;
+            let elapsed = start.elapsed().as_millis() as u64;
+            let (left, right) = self.levels(elapsed, power);
+
+            for ((output, level), last) in outputs.iter().zip([left, right]).zip(&mut applied) {
+                if *last == Some(level) {
+                    continue;
+                }
+                for &channel in output.channels() {
+                    let _ = manager.set_power(&output.device_id, channel, level).await?;
+                }
+                *last = Some(level);
+            }
+        }
+    }
+}
+
+/// 立体声的一路输出
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StereoOutput {
+    /// 设备 ID
+    pub device_id: String,
+    /// 通道（`None` 表示设备的两个通道）
+    pub channel: Option<u8>,
+}
+
+impl StereoOutput {
+    /// 设备的单个通道
+    pub fn channel(device_id: impl Into<String>, channel: u8) -> Self {
+        Self {
+            device_id: device_id.into(),
+            channel: Some(channel),
+        }
+    }
+
+    /// 设备的两个通道
+    pub fn device(device_id: impl Into<String>) -> Self {
+        Self {
+            device_id: device_id.into(),
+            channel: None,
+        }
+    }
+
+    /// 同一设备的 A/B 通道作为左右两路
+    pub fn split(device_id: &str) -> [Self; 2] {
+        [Self::channel(device_id, 0), Self::channel(device_id, 1)]
+    }
+
+    /// 输出的通道编号
+    pub fn channels(&self) -> &'static [u8] {
+        match self.channel {
+            Some(0) => &[0],
+            Some(_) => &[1],
+            None => &[0, 1],
+        }
+    }
+}
+
+impl fmt::Display for StereoOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.channel {
+            Some(0) => write!(f, "{}:A", self.device_id),
+            Some(_) => write!(f, "{}:B", self.device_id),
+            None => f.write_str(&self.device_id),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device::{Device, MockDevice};
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn test_ping_pong() {
+        let pattern = StereoPattern::new(StereoMode::PingPong, 1000);
+        assert_eq!(pattern.levels(0, 40), (40, 0));
+        assert_eq!(pattern.levels(499, 40), (40, 0));
+        assert_eq!(pattern.levels(500, 40), (0, 40));
+
+        // 完全淡化时为三角波，两路之和保持不变
+        let pattern = StereoPattern {
+            crossfade: 100,
+            ..pattern
+        };
+        for elapsed in (0..1000).step_by(50) {
+            let (left, right) = pattern.gains(elapsed);
+            assert!(close(left + right, 1.0));
+        }
+        assert_eq!(pattern.levels(250, 40), (40, 0));
+        assert_eq!(pattern.levels(500, 40), (20, 20));
+    }
+
+    #[test]
+    fn test_rotate() {
+        let pattern = StereoPattern::new(StereoMode::Rotate, 2000);
+        assert_eq!(pattern.levels(0, 100), (50, 50));
+        assert_eq!(pattern.levels(500, 100), (100, 0));
+        assert_eq!(pattern.levels(1500, 100), (0, 100));
+    }
+
+    #[test]
+    fn test_chase() {
+        let pattern = StereoPattern {
+            phase_offset: 90,
+            ..StereoPattern::new(StereoMode::Chase, 1000)
+        };
+        // 右路落后四分之一周期
+        assert_eq!(pattern.levels(500, 100), (50, 25));
+        assert_eq!(pattern.levels(250, 100), (25, 0));
+
+        let pattern = StereoPattern {
+            crossfade: 40,
+            ..pattern
+        };
+        // 最后 20% 周期回落
+        assert_eq!(pattern.levels(800, 100), (100, 69));
+        assert_eq!(pattern.levels(900, 100), (50, 81));
+    }
+
+    #[test]
+    fn test_validate_and_parse() {
+        assert!(StereoPattern::new(StereoMode::Rotate, 1000)
+            .validate()
+            .is_ok());
+        assert!(StereoPattern::new(StereoMode::Rotate, 100)
+            .validate()
+            .is_err());
+        assert!(StereoPattern {
+            phase_offset: 360,
+            ..StereoPattern::new(StereoMode::Chase, 1000)
+        }
+        .validate()
+        .is_err());
+        assert!(StereoPattern {
+            crossfade: 101,
+            ..StereoPattern::new(StereoMode::PingPong, 1000)
+        }
+        .validate()
+        .is_err());
+
+        assert_eq!(
+            "Ping_Pong".parse::<StereoMode>().unwrap(),
+            StereoMode::PingPong
+        );
+        assert_eq!(
+            StereoMode::Chase.to_string().parse::<StereoMode>().unwrap(),
+            StereoMode::Chase
+        );
+        assert!("spin".parse::<StereoMode>().is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_run_on_channels() {
+        let manager = SessionManager::new();
+        let mut device = MockDevice::new("mock-1".to_string(), "Mock".to_string());
+        device.connect().await.unwrap();
+        manager.add_device(Box::new(device)).await.unwrap();
+
+        let pattern = StereoPattern::new(StereoMode::PingPong, 1000);
+        let outputs = StereoOutput::split("mock-1");
+        let device = manager.get_device("mock-1").await.unwrap();
+
+        let run = pattern.run(&manager, &outputs, 30);
+        tokio::pin!(run);
+        tokio::select! {
+            _ = &mut run => panic!("stereo pattern stopped"),
+            _ = tokio::time::sleep(Duration::from_millis(250)) => {}
+        }
+        {
+            let dev = device.read().await;
+            assert_eq!((dev.get_power(0), dev.get_power(1)), (30, 0));
+        }
+
+        tokio::select! {
+            _ = &mut run => panic!("stereo pattern stopped"),
+            _ = tokio::time::sleep(Duration::from_millis(500)) => {}
+        }
+        let dev = device.read().await;
+        assert_eq!((dev.get_power(0), dev.get_power(1)), (0, 30));
+    }
+}
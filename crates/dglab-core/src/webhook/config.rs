@@ -0,0 +1,65 @@
+//! Webhook 配置
+
+use std::net::SocketAddr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{CoreError, Result};
+
+/// 默认监听地址（只接受本机请求）
+pub const DEFAULT_WEBHOOK_BIND: &str = "127.0.0.1:8787";
+
+/// Webhook 配置（配置文件 `[webhook]` 段）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebhookConfig {
+    /// 监听地址，局域网内的其他设备访问时改为 `0.0.0.0:端口`
+    pub bind: String,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            bind: DEFAULT_WEBHOOK_BIND.to_string(),
+        }
+    }
+}
+
+impl WebhookConfig {
+    /// 使用指定监听地址创建配置
+    pub fn new(bind: impl Into<String>) -> Self {
+        Self { bind: bind.into() }
+    }
+
+    /// 校验配置
+    pub fn validate(&self) -> Result<()> {
+        let _ = self.bind_address()?;
+        Ok(())
+    }
+
+    /// 解析监听地址
+    pub fn bind_address(&self) -> Result<SocketAddr> {
+        self.bind.trim().parse().map_err(|_| {
+            CoreError::ConfigError(format!(
+                "Invalid webhook bind address '{}', expected ip:port",
+                self.bind
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bind_address() {
+        assert_eq!(
+            WebhookConfig::default().bind_address().unwrap(),
+            "127.0.0.1:8787".parse().unwrap()
+        );
+        assert!(WebhookConfig::new("0.0.0.0:9000").validate().is_ok());
+        assert!(WebhookConfig::new("localhost").validate().is_err());
+        assert!(WebhookConfig::new("127.0.0.1").validate().is_err());
+    }
+}
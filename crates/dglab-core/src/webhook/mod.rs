@@ -0,0 +1,13 @@
+//! Webhook 触发
+//!
+//! 监听 HTTP 请求，把 `POST /trigger/{preset}`、`POST /power?a=30` 等请求转为会话操作，
+//! 供 Stream Deck、IFTTT 等工具触发预设和调节强度。请求按 `[access]` 中的令牌鉴权，
+//! 路由见 [`routes`]。
+
+pub mod config;
+pub mod routes;
+pub mod server;
+
+pub use config::{WebhookConfig, DEFAULT_WEBHOOK_BIND};
+pub use routes::{route, Route, WebhookCommand, WebhookRequest};
+pub use server::WebhookServer;
@@ -0,0 +1,286 @@
+//! Webhook 路由
+//!
+//! 所有请求都需要令牌，放在 `Authorization: Bearer <令牌>` 头或 `token` 查询参数中。
+//! `device` 查询参数指定设备 ID，不指定时作用于会话中的所有设备：
+//!
+//! | 请求 | 操作 |
+//! |------|------|
+//! | `GET /status` | 设备状态（JSON） |
+//! | `POST /trigger/{preset}` | 应用预设（名称或 ID） |
+//! | `POST /power?a=30&b=10` | 设置通道强度，`power=` 同时设置两个通道 |
+//! | `POST /waveform/{name}?channel=a` | 切换波形，不指定 `channel` 时两个通道 |
+//! | `POST /start`、`POST /stop` | 开始、停止输出 |
+//! | `POST /estop` | 紧急停止 |
+//!
+//! 路径段和查询参数按 URL 编码解码，预设和波形名称可以包含空格和中文。
+
+use crate::config::Permission;
+use crate::error::{CoreError, Result};
+
+/// Webhook 指令
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WebhookCommand {
+    /// 读取设备状态
+    Status,
+    /// 应用预设
+    Preset {
+        /// 预设名称或 ID
+        name: String,
+    },
+    /// 设置通道强度
+    Power {
+        /// A 通道强度
+        a: Option<u8>,
+        /// B 通道强度
+        b: Option<u8>,
+    },
+    /// 设置波形
+    Waveform {
+        /// 通道，`None` 表示两个通道
+        channel: Option<u8>,
+        /// 波形名称
+        name: String,
+    },
+    /// 开始输出
+    Start,
+    /// 停止输出
+    Stop,
+    /// 紧急停止
+    EmergencyStop,
+}
+
+impl WebhookCommand {
+    /// 执行指令需要的权限
+    pub fn permission(&self) -> Permission {
+        match self {
+            Self::Status => Permission::ReadState,
+            Self::Preset { .. }
+            | Self::Power { .. }
+            | Self::Waveform { .. }
+            | Self::Start
+            | Self::Stop
+            | Self::EmergencyStop => Permission::Control,
+        }
+    }
+}
+
+/// 解析后的请求
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebhookRequest {
+    /// 指令
+    pub command: WebhookCommand,
+    /// 目标设备，`None` 表示所有设备
+    pub device: Option<String>,
+    /// 查询参数中的令牌
+    pub token: Option<String>,
+}
+
+/// 路由结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Route {
+    /// 有效请求
+    Request(WebhookRequest),
+    /// 路径不存在
+    NotFound,
+    /// 路径存在但方法不对，附带允许的方法
+    MethodNotAllowed(&'static str),
+}
+
+/// 按方法和请求目标（路径加查询参数）路由
+///
+/// 路径不存在或方法不对时返回对应的 [`Route`]，参数无效时返回错误。
+pub fn route(method: &str, target: &str) -> Result<Route> {
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let segments = path
+        .trim_matches('/')
+        .split('/')
+        .map(percent_decode)
+        .collect::<Result<Vec<_>>>()?;
+    let params = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            Ok((
+                percent_decode(&key.replace('+', " "))?,
+                percent_decode(&value.replace('+', " "))?,
+            ))
+        })
+        .collect::<Result<Vec<(String, String)>>>()?;
+    let param = |name: &str| {
+        params
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    };
+
+    let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+    let (command, allowed) = match segments.as_slice() {
+        ["status"] => (WebhookCommand::Status, "GET"),
+        ["trigger", name] if !name.is_empty() => (
+            WebhookCommand::Preset {
+                name: name.to_string(),
+            },
+            "POST",
+        ),
+        ["power"] => {
+            let both = param("power").map(parse_power).transpose()?;
+            let a = param("a").map(parse_power).transpose()?.or(both);
+            let b = param("b").map(parse_power).transpose()?.or(both);
+            if a.is_none() && b.is_none() {
+                return Err(CoreError::InvalidParameter(
+                    "Power request needs a, b or power".to_string(),
+                ));
+            }
+            (WebhookCommand::Power { a, b }, "POST")
+        }
+        ["waveform", name] if !name.is_empty() => (
+            WebhookCommand::Waveform {
+                channel: param("channel").map(parse_channel).transpose()?,
+                name: name.to_string(),
+            },
+            "POST",
+        ),
+        ["start"] => (WebhookCommand::Start, "POST"),
+        ["stop"] => (WebhookCommand::Stop, "POST"),
+        ["estop"] => (WebhookCommand::EmergencyStop, "POST"),
+        _ => return Ok(Route::NotFound),
+    };
+    if !method.eq_ignore_ascii_case(allowed) {
+        return Ok(Route::MethodNotAllowed(allowed));
+    }
+
+    Ok(Route::Request(WebhookRequest {
+        command,
+        device: param("device")
+            .filter(|device| !device.is_empty())
+            .map(str::to_string),
+        token: param("token").map(str::to_string),
+    }))
+}
+
+/// 解析强度参数
+fn parse_power(value: &str) -> Result<u8> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| CoreError::InvalidParameter(format!("Invalid power '{}'", value)))
+}
+
+/// 解析通道参数
+fn parse_channel(value: &str) -> Result<u8> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "a" => Ok(0),
+        "b" => Ok(1),
+        _ => Err(CoreError::InvalidParameter(format!(
+            "Invalid channel '{}', expected a or b",
+            value
+        ))),
+    }
+}
+
+/// URL 解码（`%XX`）
+fn percent_decode(s: &str) -> Result<String> {
+    let invalid = || CoreError::InvalidParameter(format!("Invalid URL encoding in '{}'", s));
+
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = s.get(i + 1..i + 3).ok_or_else(invalid)?;
+            decoded.push(u8::from_str_radix(hex, 16).map_err(|_| invalid())?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).map_err(|_| invalid())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(method: &str, target: &str) -> WebhookRequest {
+        match route(method, target).unwrap() {
+            Route::Request(request) => request,
+            other => panic!("unexpected route {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_route_commands() {
+        assert_eq!(request("GET", "/status").command, WebhookCommand::Status);
+        assert_eq!(
+            request("POST", "/trigger/%E6%94%BE%E6%9D%BE%20mode").command,
+            WebhookCommand::Preset {
+                name: "放松 mode".to_string()
+            }
+        );
+        assert_eq!(
+            request("POST", "/power?a=30").command,
+            WebhookCommand::Power {
+                a: Some(30),
+                b: None
+            }
+        );
+        assert_eq!(
+            request("POST", "/power?power=20&b=5").command,
+            WebhookCommand::Power {
+                a: Some(20),
+                b: Some(5)
+            }
+        );
+        assert_eq!(
+            request("post", "/waveform/Deep+Pulse?channel=B").command,
+            WebhookCommand::Waveform {
+                channel: Some(1),
+                name: "Deep+Pulse".to_string()
+            }
+        );
+        assert_eq!(
+            request("POST", "/estop/").command,
+            WebhookCommand::EmergencyStop
+        );
+    }
+
+    #[test]
+    fn test_route_params() {
+        let request = request("POST", "/stop?device=47L121000&token=a%2Bb");
+        assert_eq!(request.command, WebhookCommand::Stop);
+        assert_eq!(request.device.as_deref(), Some("47L121000"));
+        assert_eq!(request.token.as_deref(), Some("a+b"));
+    }
+
+    #[test]
+    fn test_route_errors() {
+        assert_eq!(route("GET", "/").unwrap(), Route::NotFound);
+        assert_eq!(route("POST", "/trigger").unwrap(), Route::NotFound);
+        assert_eq!(route("POST", "/nope").unwrap(), Route::NotFound);
+        assert_eq!(
+            route("GET", "/estop").unwrap(),
+            Route::MethodNotAllowed("POST")
+        );
+        assert_eq!(
+            route("POST", "/status").unwrap(),
+            Route::MethodNotAllowed("GET")
+        );
+
+        assert!(route("POST", "/power").is_err());
+        assert!(route("POST", "/power?a=high").is_err());
+        assert!(route("POST", "/power?a=300").is_err());
+        assert!(route("POST", "/waveform/Pulse?channel=c").is_err());
+        assert!(route("POST", "/trigger/%zz").is_err());
+    }
+
+    #[test]
+    fn test_permission() {
+        assert_eq!(WebhookCommand::Status.permission(), Permission::ReadState);
+        assert_eq!(
+            WebhookCommand::EmergencyStop.permission(),
+            Permission::Control
+        );
+    }
+}
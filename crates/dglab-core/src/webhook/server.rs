@@ -0,0 +1,448 @@
+//! Webhook HTTP 服务
+//!
+//! 极简的 HTTP/1.1 服务：每个连接处理一个请求后关闭，只读取请求头，请求体被丢弃。
+//! 请求按 [`routes`](super::routes) 路由，令牌按 `[access]` 中的角色鉴权。
+//! 同时处理的连接超过 [`MAX_CONNECTIONS`] 时，新连接直接返回 503。
+
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use futures::stream::{FuturesUnordered, StreamExt};
+use serde_json::{json, Value};
+use tokio::net::{TcpListener, TcpStream};
+use tracing::{debug, info, warn};
+
+use super::config::WebhookConfig;
+use super::routes::{route, Route, WebhookCommand, WebhookRequest};
+use crate::config::access::{self, AccessConfig};
+use crate::error::{CoreError, Result};
+use crate::http::{self, Request};
+use crate::preset::PresetManager;
+use crate::session::SessionManager;
+
+/// 读取请求的超时时间
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 同时处理的最大连接数
+pub const MAX_CONNECTIONS: usize = 16;
+
+/// 拒绝连接（读取请求并返回 503）的超时时间，在接受连接的循环中进行，不能等待太久
+const REJECT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// HTTP 响应
+struct HttpResponse {
+    /// 状态码
+    status: u16,
+    /// JSON 内容
+    body: Value,
+    /// 405 时允许的方法
+    allow: Option<&'static str>,
+}
+
+impl HttpResponse {
+    fn ok(body: Value) -> Self {
+        Self {
+            status: 200,
+            body,
+            allow: None,
+        }
+    }
+
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            body: json!({ "ok": false, "error": message.into() }),
+            allow: None,
+        }
+    }
+}
+
+/// Webhook 服务
+///
+/// 把 `POST /trigger/{preset}`、`POST /power?a=30` 等请求转为会话操作，供 Stream Deck、
+/// IFTTT 等工具触发。必须在 `[access]` 中配置令牌，未配置时拒绝启动。
+#[derive(Debug, Clone)]
+pub struct WebhookServer {
+    /// 配置
+    config: WebhookConfig,
+    /// 访问令牌
+    access: AccessConfig,
+}
+
+impl WebhookServer {
+    /// 创建 Webhook 服务（校验配置，要求已配置访问令牌）
+    pub fn new(config: WebhookConfig, access: AccessConfig) -> Result<Self> {
+        config.validate()?;
+        access.validate()?;
+        if !access.is_enabled() {
+            return Err(CoreError::ConfigError(
+                "Webhooks need access tokens, add [[access.tokens]] to the config file".to_string(),
+            ));
+        }
+        Ok(Self { config, access })
+    }
+
+    /// 获取配置
+    pub fn config(&self) -> &WebhookConfig {
+        &self.config
+    }
+
+    /// 监听配置的地址
+    pub async fn bind(&self) -> Result<TcpListener> {
+        let address = self.config.bind_address()?;
+        let listener = TcpListener::bind(address).await?;
+        info!("Webhook listening on http://{}", listener.local_addr()?);
+        Ok(listener)
+    }
+
+    /// 监听并处理请求
+    ///
+    /// 持续运行，单个请求出错只返回错误响应；调用方通过取消 future 停止。
+    pub async fn run(&self, manager: &SessionManager, presets: &PresetManager) -> Result<()> {
+        let listener = self.bind().await?;
+        self.serve(listener, manager, presets).await
+    }
+
+    /// 在已监听的端口上处理请求
+    pub async fn serve(
+        &self,
+        listener: TcpListener,
+        manager: &SessionManager,
+        presets: &PresetManager,
+    ) -> Result<()> {
+        let mut connections = FuturesUnordered::new();
+        loop {
+            tokio::select! {
+                accepted = listener.accept() => match accepted {
+                    Ok((mut stream, peer)) if connections.len() >= MAX_CONNECTIONS => {
+                        warn!("Too many webhook connections, rejecting {}", peer);
+                        let response = HttpResponse::error(503, "Too many connections");
+                        // 读完请求再响应，避免关闭时未读数据导致客户端收到 RST
+                        let reject = async {
+                            let _ = http::read_request(&mut stream).await;
+                            write_response(&mut stream, &response).await
+                        };
+                        let _ = tokio::time::timeout(REJECT_TIMEOUT, reject).await;
+                    }
+                    Ok((stream, peer)) => {
+                        connections.push(self.handle_connection(stream, peer, manager, presets));
+                    }
+                    Err(e) => warn!("Failed to accept webhook connection: {}", e),
+                },
+                Some(()) = connections.next(), if !connections.is_empty() => {}
+            }
+        }
+    }
+
+    /// 处理一个连接
+    async fn handle_connection(
+        &self,
+        mut stream: TcpStream,
+        peer: SocketAddr,
+        manager: &SessionManager,
+        presets: &PresetManager,
+    ) {
+        let response =
+            match tokio::time::timeout(READ_TIMEOUT, http::read_request(&mut stream)).await {
+                Ok(Ok(request)) => {
+                    // 查询参数中可能带有令牌，只记录路径
+                    debug!(
+                        "Webhook {} {} from {}",
+                        request.method,
+                        request.path(),
+                        peer
+                    );
+                    self.respond(&request, manager, presets).await
+                }
+                Ok(Err(e)) => HttpResponse::error(400, e.to_string()),
+                Err(_) => HttpResponse::error(408, "Request timed out"),
+            };
+
+        if response.status >= 400 {
+            info!(
+                "Webhook request from {} failed ({}): {}",
+                peer, response.status, response.body["error"]
+            );
+        }
+        if let Err(e) = write_response(&mut stream, &response).await {
+            debug!("Failed to send webhook response to {}: {}", peer, e);
+        }
+    }
+
+    /// 路由、鉴权并执行请求
+    async fn respond(
+        &self,
+        request: &Request,
+        manager: &SessionManager,
+        presets: &PresetManager,
+    ) -> HttpResponse {
+        let webhook = match route(&request.method, &request.target) {
+            Ok(Route::Request(webhook)) => webhook,
+            Ok(Route::NotFound) => return HttpResponse::error(404, "Not found"),
+            Ok(Route::MethodNotAllowed(allowed)) => {
+                return HttpResponse {
+                    allow: Some(allowed),
+                    ..HttpResponse::error(405, format!("Use {}", allowed))
+                }
+            }
+            Err(e) => return HttpResponse::error(400, e.to_string()),
+        };
+
+        let bearer = request
+            .header("authorization")
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(str::trim);
+        let token = bearer.or(webhook.token.as_deref());
+        let Some(role) = self.access.role(token) else {
+            return HttpResponse::error(401, "Missing or invalid token");
+        };
+        if let Err(e) = access::check(role, webhook.command.permission()) {
+            return HttpResponse::error(403, e.to_string());
+        }
+
+        match execute(manager, presets, &webhook).await {
+            Ok(body) => HttpResponse::ok(body),
+            Err(e) => HttpResponse::error(error_status(&e), e.to_string()),
+        }
+    }
+}
+
+/// 执行请求，返回响应内容
+async fn execute(
+    manager: &SessionManager,
+    presets: &PresetManager,
+    request: &WebhookRequest,
+) -> Result<Value> {
+    let targets: Vec<String> = match &request.device {
+        Some(device) => {
+            if manager.get_device(device).await.is_none() {
+                return Err(CoreError::DeviceNotFound(device.clone()));
+            }
+            vec![device.clone()]
+        }
+        None => manager.list_devices().await,
+    };
+
+    if request.command == WebhookCommand::Status {
+        let mut devices = Vec::new();
+        for device_id in &targets {
+            let Some(device) = manager.get_device(device_id).await else {
+                continue;
+            };
+            let state = device.read().await.state();
+            if let Some(info) = manager.device_info(device_id).await {
+                devices.push(json!({ "state": state, "info": info }));
+            }
+        }
+        return Ok(json!({ "ok": true, "devices": devices }));
+    }
+
+    if targets.is_empty() {
+        return Err(CoreError::DeviceNotFound(
+            "no devices in session".to_string(),
+        ));
+    }
+
+    for device_id in &targets {
+        info!("Webhook command for {}: {:?}", device_id, request.command);
+        match &request.command {
+            WebhookCommand::Status => {}
+            WebhookCommand::Preset { name } => {
+                let preset = presets
+                    .find_preset_by_name(name)
+                    .or_else(|| presets.get_preset(name))
+                    .ok_or_else(|| CoreError::PresetNotFound(name.clone()))?;
+                let preset = presets.resolve(&preset.id)?;
+                manager.apply_preset(device_id, &preset).await?;
+            }
+            WebhookCommand::Power { a, b } => {
+                for (channel, power) in [(0, a), (1, b)] {
+                    if let Some(power) = power {
+                        let _ = manager.set_power(device_id, channel, *power).await?;
+                    }
+                }
+            }
+            WebhookCommand::Waveform { channel, name } => {
+                let config = manager.resolve_waveform(name).await?.to_device_config();
+                let channels = match channel {
+                    Some(channel) => vec![*channel],
+                    None => vec![0, 1],
+                };
+                for channel in channels {
+                    manager
+                        .set_waveform(device_id, channel, config.clone())
+                        .await?;
+                }
+            }
+            WebhookCommand::Start => manager.start(device_id).await?,
+            WebhookCommand::Stop => manager.stop(device_id).await?,
+            WebhookCommand::EmergencyStop => manager.emergency_stop(device_id).await?,
+        }
+    }
+
+    Ok(json!({ "ok": true, "devices": targets }))
+}
+
+/// 错误对应的状态码
+fn error_status(error: &CoreError) -> u16 {
+    match error {
+        CoreError::DeviceNotFound(_) | CoreError::PresetNotFound(_) => 404,
+        CoreError::Rejected(_) => 409,
+        CoreError::InvalidParameter(_)
+        | CoreError::InvalidChannel(_)
+        | CoreError::PowerOutOfRange(..) => 400,
+        _ => 500,
+    }
+}
+
+/// 写入 JSON 响应并关闭连接
+async fn write_response(stream: &mut TcpStream, response: &HttpResponse) -> Result<()> {
+    let mut headers = Vec::new();
+    if response.status == 401 {
+        headers.push(("WWW-Authenticate", "Bearer"));
+    }
+    if let Some(allow) = response.allow {
+        headers.push(("Allow", allow));
+    }
+    http::write_response(
+        stream,
+        response.status,
+        "application/json",
+        &headers,
+        &response.body.to_string(),
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::access::{AccessToken, Role};
+    use crate::device::{Device, MockDevice};
+    use crate::preset::Preset;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    fn access() -> AccessConfig {
+        AccessConfig {
+            tokens: vec![
+                AccessToken {
+                    name: "deck".to_string(),
+                    token: "secret".to_string(),
+                    role: Role::Controller,
+                },
+                AccessToken {
+                    name: "dashboard".to_string(),
+                    token: "look".to_string(),
+                    role: Role::Viewer,
+                },
+            ],
+        }
+    }
+
+    async fn send(address: SocketAddr, request: &str) -> (u16, Value) {
+        let mut stream = TcpStream::connect(address).await.unwrap();
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        let _ = stream.read_to_string(&mut response).await.unwrap();
+
+        let status = response[9..12].parse().unwrap();
+        let body = response.split_once("\r\n\r\n").unwrap().1;
+        (status, serde_json::from_str(body).unwrap())
+    }
+
+    #[test]
+    fn test_requires_access_tokens() {
+        assert!(WebhookServer::new(WebhookConfig::default(), AccessConfig::default()).is_err());
+        assert!(WebhookServer::new(WebhookConfig::new("nope"), access()).is_err());
+        assert!(WebhookServer::new(WebhookConfig::default(), access()).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_requests() {
+        let manager = SessionManager::new();
+        let mut device = MockDevice::new("mock-1".to_string(), "Mock".to_string());
+        device.connect().await.unwrap();
+        manager.add_device(Box::new(device)).await.unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut presets = PresetManager::new(dir.path().to_path_buf());
+        let mut preset = Preset::new("Relax".to_string(), String::new());
+        preset.channel_a.min_power = 15;
+        presets.add_preset(preset).unwrap();
+
+        let server = WebhookServer::new(WebhookConfig::new("127.0.0.1:0"), access()).unwrap();
+        let listener = server.bind().await.unwrap();
+        let address = listener.local_addr().unwrap();
+
+        let requests = async {
+            // 缺少令牌
+            let (status, _) = send(address, "POST /power?a=30 HTTP/1.1\r\n\r\n").await;
+            assert_eq!(status, 401);
+
+            // 只读令牌不能控制
+            let (status, _) = send(
+                address,
+                "POST /power?a=30 HTTP/1.1\r\nAuthorization: Bearer look\r\n\r\n",
+            )
+            .await;
+            assert_eq!(status, 403);
+
+            let (status, body) = send(
+                address,
+                "POST /power?a=30&token=secret HTTP/1.1\r\nContent-Length: 2\r\n\r\n{}",
+            )
+            .await;
+            assert_eq!(status, 200, "{}", body);
+            assert_eq!(body["devices"][0], "mock-1");
+
+            let (status, body) = send(
+                address,
+                "GET /status HTTP/1.1\r\nAuthorization: Bearer look\r\n\r\n",
+            )
+            .await;
+            assert_eq!(status, 200);
+            assert_eq!(body["devices"][0]["info"]["power_a"], 30);
+
+            let (status, _) = send(
+                address,
+                "POST /trigger/relax HTTP/1.1\r\nAuthorization: Bearer secret\r\n\r\n",
+            )
+            .await;
+            assert_eq!(status, 200);
+
+            let (status, _) = send(
+                address,
+                "POST /trigger/Missing HTTP/1.1\r\nAuthorization: Bearer secret\r\n\r\n",
+            )
+            .await;
+            assert_eq!(status, 404);
+
+            let (status, _) = send(
+                address,
+                "GET /estop HTTP/1.1\r\nAuthorization: Bearer secret\r\n\r\n",
+            )
+            .await;
+            assert_eq!(status, 405);
+
+            let (status, _) = send(address, "garbage\r\n\r\n").await;
+            assert_eq!(status, 400);
+
+            // 连接数达到上限时拒绝新连接
+            let mut idle = Vec::new();
+            for _ in 0..MAX_CONNECTIONS {
+                idle.push(TcpStream::connect(address).await.unwrap());
+            }
+            let (status, _) = send(address, "GET /status HTTP/1.1\r\n\r\n").await;
+            assert_eq!(status, 503);
+            drop(idle);
+        };
+
+        tokio::select! {
+            result = server.serve(listener, &manager, &presets) => panic!("server stopped: {:?}", result),
+            _ = requests => {}
+        }
+
+        let device = manager.get_device("mock-1").await.unwrap();
+        assert_eq!(device.read().await.get_power(0), 15);
+    }
+}
@@ -1,6 +1,10 @@
 //! GUI 应用状态
 
 use eframe::egui;
+use tokio::runtime::Runtime;
+
+use crate::backend::Backend;
+use crate::ui;
 
 /// 当前标签页
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -21,6 +25,8 @@ pub enum Tab {
 
 /// GUI 应用
 pub struct DglabApp {
+    /// 异步后端
+    backend: Backend,
     /// 当前标签页
     current_tab: Tab,
     /// 设备面板
@@ -31,18 +37,25 @@ pub struct DglabApp {
     control_panel: ui::control_panel::ControlPanel,
     /// 波形编辑器
     waveform_editor: ui::waveform_editor::WaveformEditor,
+    /// 预设面板
+    preset_panel: ui::preset_panel::PresetPanel,
     /// 设置面板
     settings_panel: ui::settings_panel::SettingsPanel,
 }
 
-impl Default for DglabApp {
-    fn default() -> Self {
+impl DglabApp {
+    /// 创建应用，在 `runtime` 中启动异步后端
+    pub fn new(cc: &eframe::CreationContext<'_>, runtime: Runtime) -> Self {
+        let backend = Backend::new(runtime, cc.egui_ctx.clone());
+
         Self {
+            backend,
             current_tab: Tab::Devices,
             device_panel: ui::device_panel::DevicePanel::default(),
             wifi_panel: ui::wifi_panel::WifiPanel::default(),
             control_panel: ui::control_panel::ControlPanel::default(),
             waveform_editor: ui::waveform_editor::WaveformEditor::default(),
+            preset_panel: ui::preset_panel::PresetPanel::default(),
             settings_panel: ui::settings_panel::SettingsPanel::default(),
         }
     }
@@ -50,6 +63,13 @@ impl Default for DglabApp {
 
 impl eframe::App for DglabApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        // 处理后端结果
+        for update in self.backend.poll() {
+            self.device_panel.handle(&update);
+            self.control_panel.handle(&update, &self.backend, ctx);
+            self.preset_panel.handle(&update);
+        }
+
         // 顶部标签栏
         egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
             ui.horizontal(|ui| {
@@ -65,7 +85,7 @@ impl eframe::App for DglabApp {
         // 主内容区
         egui::CentralPanel::default().show(ctx, |ui| match self.current_tab {
             Tab::Devices => {
-                self.device_panel.ui(ui);
+                self.device_panel.ui(ui, &self.backend);
             }
             Tab::Wifi => {
                 self.wifi_panel.ui(ui);
@@ -77,8 +97,7 @@ impl eframe::App for DglabApp {
                 self.waveform_editor.ui(ui);
             }
             Tab::Presets => {
-                ui.heading("Presets");
-                ui.label("Preset management coming soon...");
+                self.preset_panel.ui(ui, &self.backend);
             }
             Tab::Settings => {
                 self.settings_panel.ui(ui);
@@ -89,7 +108,11 @@ impl eframe::App for DglabApp {
         egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
             ui.horizontal(|ui| {
                 ui.label("Status:");
-                ui.colored_label(egui::Color32::YELLOW, "Disconnected");
+                match self.device_panel.connected_count() {
+                    0 => ui.colored_label(egui::Color32::YELLOW, "Disconnected"),
+                    1 => ui.colored_label(egui::Color32::GREEN, "1 device connected"),
+                    n => ui.colored_label(egui::Color32::GREEN, format!("{} devices connected", n)),
+                };
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                     ui.label("DG-LAB Controller v0.1.0");
                 });
@@ -0,0 +1,438 @@
+//! 异步后端
+//!
+//! egui 的 `update` 在 UI 线程同步调用，不能等待蓝牙操作。后端在独立的 tokio 运行时中
+//! 运行一个任务，UI 通过 [`Command`] 发送请求，每帧用 [`Backend::poll`] 取回 [`Update`]，
+//! 后端有新结果时请求重绘。设备统一加入共享的 [`SessionManager`]，其他面板直接使用。
+//! 预设和波形库在首次请求时从默认目录加载，由后端任务持有。
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use eframe::egui;
+use tokio::runtime::Runtime;
+use tokio::sync::mpsc;
+use tracing::{error, info, warn};
+
+use dglab_core::device::traits::DeviceInfo;
+use dglab_core::device::{CoyoteDevice, Device, DeviceState};
+use dglab_core::preset::{Preset, PresetManager};
+use dglab_core::session::{SessionEvent, SessionManager};
+use dglab_core::waveform::WaveformLibrary;
+use dglab_protocol::ble::{BleManager, ScanResult};
+
+/// 默认扫描时长
+pub const DEFAULT_SCAN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// UI 发给后端的请求
+#[derive(Debug, Clone)]
+pub enum Command {
+    /// 扫描 BLE 设备
+    Scan {
+        /// 扫描时长
+        timeout: Duration,
+    },
+    /// 连接扫描到的设备
+    Connect {
+        /// 设备 ID
+        id: String,
+        /// 设备名称
+        name: String,
+    },
+    /// 断开设备并移出会话
+    Disconnect {
+        /// 设备 ID
+        id: String,
+    },
+    /// 加载预设和波形库
+    LoadPresets,
+    /// 保存预设（新建或覆盖同 ID 预设）
+    SavePreset(Preset),
+    /// 删除预设
+    DeletePreset {
+        /// 预设 ID
+        id: String,
+    },
+}
+
+/// 后端发给 UI 的结果
+#[derive(Debug, Clone)]
+pub enum Update {
+    /// 扫描完成
+    ScanFinished(Vec<ScanResult>),
+    /// 设备已连接并加入会话
+    Connected(DeviceInfo),
+    /// 设备已断开
+    Disconnected(String),
+    /// 设备状态变更
+    StateChanged {
+        /// 设备 ID
+        id: String,
+        /// 新状态
+        state: DeviceState,
+    },
+    /// 预设和波形库已加载
+    PresetsLoaded {
+        /// 所有预设（按名称排序）
+        presets: Vec<Preset>,
+        /// 波形库中的波形名称（内置和用户波形）
+        waveforms: Vec<String>,
+    },
+    /// 预设已保存
+    PresetSaved(Preset),
+    /// 预设已删除
+    PresetDeleted(String),
+    /// 预设操作失败
+    PresetError(String),
+    /// 操作失败
+    Error {
+        /// 相关设备 ID（扫描等全局操作为空）
+        id: Option<String>,
+        /// 错误信息
+        message: String,
+    },
+}
+
+/// 创建后端使用的 tokio 运行时
+pub fn build_runtime() -> std::io::Result<Runtime> {
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .thread_name("dglab-backend")
+        .build()
+}
+
+/// 异步后端句柄
+pub struct Backend {
+    /// tokio 运行时（随句柄一起销毁，后台任务随之结束）
+    runtime: Runtime,
+    /// 会话管理器
+    session_manager: Arc<SessionManager>,
+    /// 请求发送端
+    command_tx: mpsc::UnboundedSender<Command>,
+    /// 结果接收端
+    update_rx: mpsc::UnboundedReceiver<Update>,
+}
+
+impl Backend {
+    /// 在 `runtime` 中启动后端任务，有新结果时请求 `ctx` 重绘
+    pub fn new(runtime: Runtime, ctx: egui::Context) -> Self {
+        let session_manager = Arc::new(SessionManager::new());
+        let (command_tx, command_rx) = mpsc::unbounded_channel();
+        let (update_tx, update_rx) = mpsc::unbounded_channel();
+
+        let notifier = Notifier { tx: update_tx, ctx };
+        let _ = runtime.spawn(forward_session_events(
+            session_manager.clone(),
+            notifier.clone(),
+        ));
+        let _ = runtime.spawn(run(session_manager.clone(), command_rx, notifier));
+
+        Self {
+            runtime,
+            session_manager,
+            command_tx,
+            update_rx,
+        }
+    }
+
+    /// 发送请求
+    pub fn send(&self, command: Command) {
+        if self.command_tx.send(command).is_err() {
+            error!("Backend task is not running");
+        }
+    }
+
+    /// 取出所有待处理的结果（每帧调用）
+    pub fn poll(&mut self) -> Vec<Update> {
+        let mut updates = Vec::new();
+        while let Ok(update) = self.update_rx.try_recv() {
+            updates.push(update);
+        }
+        updates
+    }
+
+    /// 获取共享的会话管理器
+    pub fn session_manager(&self) -> &Arc<SessionManager> {
+        &self.session_manager
+    }
+
+    /// 后端运行时句柄，供面板启动自己的订阅任务
+    pub fn runtime(&self) -> &tokio::runtime::Handle {
+        self.runtime.handle()
+    }
+}
+
+/// 向 UI 发送结果并请求重绘
+#[derive(Clone)]
+struct Notifier {
+    /// 结果发送端
+    tx: mpsc::UnboundedSender<Update>,
+    /// egui 上下文
+    ctx: egui::Context,
+}
+
+impl Notifier {
+    fn send(&self, update: Update) {
+        let _ = self.tx.send(update);
+        self.ctx.request_repaint();
+    }
+
+    fn error(&self, id: Option<String>, message: String) {
+        error!("{}", message);
+        self.send(Update::Error { id, message });
+    }
+
+    fn preset_error(&self, message: String) {
+        error!("{}", message);
+        self.send(Update::PresetError(message));
+    }
+}
+
+/// 后端任务：依次处理 UI 请求，BLE 管理器首次使用时创建
+async fn run(
+    session_manager: Arc<SessionManager>,
+    mut command_rx: mpsc::UnboundedReceiver<Command>,
+    notifier: Notifier,
+) {
+    let mut ble_manager: Option<Arc<BleManager>> = None;
+    let mut preset_manager: Option<PresetManager> = None;
+
+    while let Some(command) = command_rx.recv().await {
+        match command {
+            Command::Scan { timeout } => {
+                let manager = match ble(&mut ble_manager).await {
+                    Ok(manager) => manager,
+                    Err(message) => {
+                        notifier.error(None, message);
+                        continue;
+                    }
+                };
+                match scan(&manager, timeout).await {
+                    Ok(results) => {
+                        info!("Found {} devices", results.len());
+                        notifier.send(Update::ScanFinished(results));
+                    }
+                    Err(message) => notifier.error(None, message),
+                }
+            }
+
+            Command::Connect { id, name } => {
+                let manager = match ble(&mut ble_manager).await {
+                    Ok(manager) => manager,
+                    Err(message) => {
+                        notifier.error(Some(id), message);
+                        continue;
+                    }
+                };
+                match connect(&session_manager, manager, &id, &name).await {
+                    Ok(info) => notifier.send(Update::Connected(info)),
+                    Err(message) => notifier.error(Some(id), message),
+                }
+            }
+
+            Command::Disconnect { id } => match session_manager.remove_device(&id).await {
+                Ok(()) => notifier.send(Update::Disconnected(id)),
+                Err(e) => notifier.error(Some(id), format!("Failed to disconnect: {}", e)),
+            },
+
+            Command::LoadPresets => {
+                match load_presets(&session_manager, &mut preset_manager).await {
+                    Ok(update) => notifier.send(update),
+                    Err(message) => notifier.preset_error(message),
+                }
+            }
+
+            Command::SavePreset(preset) => {
+                let Some(presets) = preset_manager.as_mut() else {
+                    notifier.preset_error("Presets are not loaded".to_string());
+                    continue;
+                };
+                match save_preset(presets, preset).await {
+                    Ok(preset) => notifier.send(Update::PresetSaved(preset)),
+                    Err(message) => notifier.preset_error(message),
+                }
+            }
+
+            Command::DeletePreset { id } => {
+                let Some(presets) = preset_manager.as_mut() else {
+                    notifier.preset_error("Presets are not loaded".to_string());
+                    continue;
+                };
+                let removed = presets.get_preset(&id).cloned();
+                let result = match presets.remove_preset(&id) {
+                    Ok(()) => presets.delete_preset_file(&id).await,
+                    Err(e) => Err(e),
+                };
+                // 删除文件失败时放回内存，与磁盘保持一致
+                if let (Err(_), Some(preset)) = (&result, removed) {
+                    let _ = presets.add_preset(preset);
+                }
+                match result {
+                    Ok(()) => notifier.send(Update::PresetDeleted(id)),
+                    Err(e) => notifier.preset_error(format!("Failed to delete preset: {}", e)),
+                }
+            }
+        }
+    }
+}
+
+/// 获取或创建 BLE 管理器
+async fn ble(manager: &mut Option<Arc<BleManager>>) -> Result<Arc<BleManager>, String> {
+    if let Some(manager) = manager {
+        return Ok(manager.clone());
+    }
+
+    let created = Arc::new(
+        BleManager::new()
+            .await
+            .map_err(|e| format!("Failed to create BLE manager: {}. Is Bluetooth enabled?", e))?,
+    );
+    *manager = Some(created.clone());
+    Ok(created)
+}
+
+/// 扫描 `timeout` 后返回结果
+async fn scan(manager: &BleManager, timeout: Duration) -> Result<Vec<ScanResult>, String> {
+    manager
+        .start_scan()
+        .await
+        .map_err(|e| format!("Failed to start scan: {}", e))?;
+    tokio::time::sleep(timeout).await;
+
+    let results = manager
+        .get_scan_results()
+        .await
+        .map_err(|e| format!("Failed to get scan results: {}", e));
+    if let Err(e) = manager.stop_scan().await {
+        warn!("Failed to stop scan: {}", e);
+    }
+    results
+}
+
+/// 连接设备并加入会话
+async fn connect(
+    session_manager: &SessionManager,
+    manager: Arc<BleManager>,
+    id: &str,
+    name: &str,
+) -> Result<DeviceInfo, String> {
+    info!("Connecting to device: {} ({})", name, id);
+
+    let ble_device = manager
+        .connect(id)
+        .await
+        .map_err(|e| format!("Failed to connect {}: {}", name, e))?;
+    let mut coyote = CoyoteDevice::with_manager(id.to_string(), name.to_string(), manager);
+    coyote.set_protocol_device(ble_device);
+    coyote
+        .connect()
+        .await
+        .map_err(|e| format!("Failed to initialize {}: {}", name, e))?;
+
+    let info = coyote.info();
+    session_manager
+        .add_device(Box::new(coyote))
+        .await
+        .map_err(|e| format!("Failed to add {} to session: {}", name, e))?;
+
+    Ok(info)
+}
+
+/// 加载预设（首次加载时初始化目录并创建默认预设）和波形库，波形库同时交给会话管理器
+async fn load_presets(
+    session_manager: &SessionManager,
+    preset_manager: &mut Option<PresetManager>,
+) -> Result<Update, String> {
+    // 重新加载失败时丢弃，下次从头初始化
+    let presets = match preset_manager.take() {
+        Some(mut presets) => {
+            presets
+                .load_all()
+                .await
+                .map_err(|e| format!("Failed to load presets: {}", e))?;
+            presets
+        }
+        None => {
+            let mut presets = PresetManager::default_dir()
+                .map_err(|e| format!("Failed to open preset directory: {}", e))?;
+            presets
+                .initialize()
+                .await
+                .map_err(|e| format!("Failed to load presets: {}", e))?;
+            presets
+        }
+    };
+    let presets = preset_manager.insert(presets);
+
+    let mut library = WaveformLibrary::default_dir()
+        .map_err(|e| format!("Failed to open waveform directory: {}", e))?;
+    if let Err(e) = library.load().await {
+        warn!("Failed to load waveform library: {}", e);
+    }
+    let waveforms = library.list().into_iter().map(|w| w.name).collect();
+    session_manager.set_waveform_library(library).await;
+
+    // 界面显示和编辑有效配置，保存时再记录覆盖的字段
+    let mut list: Vec<Preset> = presets
+        .list_presets()
+        .into_iter()
+        .map(|p| presets.resolve(&p.id).unwrap_or_else(|_| p.clone()))
+        .collect();
+    list.sort_by_key(|p| p.name.to_lowercase());
+    info!("Loaded {} presets", list.len());
+    Ok(Update::PresetsLoaded {
+        presets: list,
+        waveforms,
+    })
+}
+
+/// 校验并保存预设，返回保存后的有效配置
+///
+/// 传入的是编辑后的有效配置，与父预设不同的字段记入 `overrides`。
+async fn save_preset(presets: &mut PresetManager, mut preset: Preset) -> Result<Preset, String> {
+    if let Some(issue) = preset.issues().first() {
+        return Err(format!("Invalid preset: {}", issue));
+    }
+    presets
+        .record_overrides(&mut preset)
+        .map_err(|e| format!("Invalid preset: {}", e))?;
+    if presets
+        .find_preset_by_name(&preset.name)
+        .is_some_and(|other| other.id != preset.id)
+    {
+        return Err(format!("Preset name '{}' is already used", preset.name));
+    }
+
+    preset.touch();
+    let result = match presets.get_preset(&preset.id) {
+        Some(existing) => {
+            preset.created_at = existing.created_at;
+            presets.update_preset(preset.clone())
+        }
+        None => presets.add_preset(preset.clone()),
+    };
+    result.map_err(|e| format!("Failed to save preset: {}", e))?;
+    presets
+        .save_preset(&preset.id)
+        .await
+        .map_err(|e| format!("Failed to write preset file: {}", e))?;
+
+    info!("Saved preset '{}' ({})", preset.name, preset.id);
+    Ok(presets.resolve(&preset.id).unwrap_or(preset))
+}
+
+/// 把会话中的设备状态变更转发给 UI（包括其他面板引起的变更和意外断开）
+async fn forward_session_events(session_manager: Arc<SessionManager>, notifier: Notifier) {
+    let mut events = session_manager.subscribe_events();
+    loop {
+        match events.recv().await {
+            Ok(SessionEvent::DeviceStateChanged(id, state)) => {
+                notifier.send(Update::StateChanged { id, state });
+            }
+            Ok(_) => {}
+            Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                warn!("Skipped {} session events", skipped);
+            }
+            Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+        }
+    }
+}
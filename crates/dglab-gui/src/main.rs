@@ -1,19 +1,24 @@
 //! DG-LAB GUI 应用
 
+use anyhow::Context;
 use eframe::egui;
 use tracing::info;
 
 mod app;
+mod backend;
 mod ui;
 
 use app::DglabApp;
 
-fn main() -> eframe::Result<()> {
+fn main() -> anyhow::Result<()> {
     // 初始化日志
     tracing_subscriber::fmt::init();
 
     info!("Starting DG-LAB GUI");
 
+    // 运行时在窗口创建前启动，失败时直接报错退出
+    let runtime = backend::build_runtime().context("Failed to start async runtime")?;
+
     let options = eframe::NativeOptions {
         initial_window_size: Some(egui::vec2(1000.0, 700.0)),
         default_theme: eframe::Theme::Dark,
@@ -23,6 +28,7 @@ fn main() -> eframe::Result<()> {
     eframe::run_native(
         "DG-LAB Controller",
         options,
-        Box::new(|_cc| Box::<DglabApp>::default()),
+        Box::new(move |cc| Box::new(DglabApp::new(cc, runtime))),
     )
+    .map_err(|e| anyhow::anyhow!("Failed to run GUI: {}", e))
 }
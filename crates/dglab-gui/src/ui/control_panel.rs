@@ -2,6 +2,9 @@
 
 use eframe::egui;
 
+use super::power_chart::PowerChart;
+use crate::backend::{Backend, Update};
+
 /// 控制面板
 pub struct ControlPanel {
     /// 通道 A 强度
@@ -20,6 +23,8 @@ pub struct ControlPanel {
     running: bool,
     /// 同步两个通道
     sync: bool,
+    /// 实际强度曲线
+    power_chart: PowerChart,
 }
 
 impl Default for ControlPanel {
@@ -33,11 +38,26 @@ impl Default for ControlPanel {
             enabled_b: true,
             running: false,
             sync: false,
+            power_chart: PowerChart::default(),
         }
     }
 }
 
 impl ControlPanel {
+    /// 处理后端结果：曲线跟随最近连接的设备，设备断开时取消订阅
+    pub fn handle(&mut self, update: &Update, backend: &Backend, ctx: &egui::Context) {
+        match update {
+            Update::Connected(info) => {
+                self.power_chart
+                    .subscribe(backend, info.id.clone(), ctx.clone());
+            }
+            Update::Disconnected(id) if self.power_chart.device_id() == Some(id.as_str()) => {
+                self.power_chart.unsubscribe();
+            }
+            _ => {}
+        }
+    }
+
     /// 渲染 UI
     pub fn ui(&mut self, ui: &mut egui::Ui) {
         ui.heading("Device Control");
@@ -135,6 +155,13 @@ impl ControlPanel {
             });
         });
 
+        ui.add_space(15.0);
+        ui.separator();
+        ui.add_space(10.0);
+
+        // 实际强度曲线
+        self.power_chart.ui(ui);
+
         // 同步逻辑
         if self.sync {
             self.power_b = self.power_a;
@@ -2,6 +2,11 @@
 
 use eframe::egui;
 
+use dglab_core::device::DeviceState;
+use dglab_protocol::ble::ScanResult;
+
+use crate::backend::{Backend, Command, Update, DEFAULT_SCAN_TIMEOUT};
+
 /// 设备信息
 #[derive(Debug, Clone)]
 pub struct DeviceInfo {
@@ -11,11 +16,26 @@ pub struct DeviceInfo {
     pub name: String,
     /// 信号强度
     pub rssi: Option<i16>,
-    /// 是否已连接
-    pub connected: bool,
+    /// 设备状态
+    pub state: DeviceState,
+    /// 最近一次操作的错误
+    pub error: Option<String>,
+}
+
+impl From<ScanResult> for DeviceInfo {
+    fn from(result: ScanResult) -> Self {
+        Self {
+            id: result.id,
+            name: result.name,
+            rssi: result.rssi,
+            state: DeviceState::Disconnected,
+            error: None,
+        }
+    }
 }
 
 /// 设备面板
+#[derive(Default)]
 pub struct DevicePanel {
     /// 扫描中
     scanning: bool,
@@ -23,32 +43,91 @@ pub struct DevicePanel {
     devices: Vec<DeviceInfo>,
     /// 选中的设备
     selected_device: Option<usize>,
+    /// 扫描错误
+    error: Option<String>,
 }
 
-impl Default for DevicePanel {
-    fn default() -> Self {
-        Self {
-            scanning: false,
-            devices: Vec::new(),
-            selected_device: None,
+impl DevicePanel {
+    /// 处理后端结果
+    pub fn handle(&mut self, update: &Update) {
+        match update {
+            Update::ScanFinished(results) => {
+                self.scanning = false;
+                self.error = None;
+                // 保留已连接的设备，其余按扫描结果刷新
+                self.devices
+                    .retain(|d| d.state != DeviceState::Disconnected);
+                for result in results {
+                    if !self.devices.iter().any(|d| d.id == result.id) {
+                        self.devices.push(result.clone().into());
+                    }
+                }
+                self.selected_device = self.selected_device.filter(|&i| i < self.devices.len());
+            }
+            Update::Connected(info) => {
+                if let Some(device) = self.device_mut(&info.id) {
+                    device.state = info.state;
+                    device.error = None;
+                }
+            }
+            Update::Disconnected(id) => {
+                if let Some(device) = self.device_mut(id) {
+                    device.state = DeviceState::Disconnected;
+                }
+            }
+            Update::StateChanged { id, state } => {
+                if let Some(device) = self.device_mut(id) {
+                    device.state = *state;
+                }
+            }
+            Update::Error {
+                id: Some(id),
+                message,
+            } => {
+                if let Some(device) = self.device_mut(id) {
+                    device.state = DeviceState::Disconnected;
+                    device.error = Some(message.clone());
+                }
+            }
+            Update::Error { id: None, message } => {
+                self.scanning = false;
+                self.error = Some(message.clone());
+            }
+            Update::PresetsLoaded { .. }
+            | Update::PresetSaved(_)
+            | Update::PresetDeleted(_)
+            | Update::PresetError(_) => {}
         }
     }
-}
 
-impl DevicePanel {
+    /// 已连接的设备数量
+    pub fn connected_count(&self) -> usize {
+        self.devices
+            .iter()
+            .filter(|d| matches!(d.state, DeviceState::Connected | DeviceState::Running))
+            .count()
+    }
+
+    /// 按 ID 查找设备
+    fn device_mut(&mut self, id: &str) -> Option<&mut DeviceInfo> {
+        self.devices.iter_mut().find(|d| d.id == id)
+    }
+
     /// 渲染 UI
-    pub fn ui(&mut self, ui: &mut egui::Ui) {
+    pub fn ui(&mut self, ui: &mut egui::Ui, backend: &Backend) {
         ui.heading("Device Manager");
         ui.add_space(10.0);
 
         // 扫描按钮
         ui.horizontal(|ui| {
-            if ui.button(if self.scanning { "⏹ Stop Scan" } else { "🔍 Scan for Devices" }).clicked() {
-                self.scanning = !self.scanning;
-                if self.scanning {
-                    self.devices.clear();
-                    self.simulate_scan();
-                }
+            if ui
+                .add_enabled(!self.scanning, egui::Button::new("🔍 Scan for Devices"))
+                .clicked()
+            {
+                self.scanning = true;
+                backend.send(Command::Scan {
+                    timeout: DEFAULT_SCAN_TIMEOUT,
+                });
             }
 
             if self.scanning {
@@ -57,6 +136,10 @@ impl DevicePanel {
             }
         });
 
+        if let Some(error) = &self.error {
+            ui.colored_label(egui::Color32::RED, error);
+        }
+
         ui.add_space(10.0);
         ui.separator();
         ui.add_space(10.0);
@@ -84,7 +167,13 @@ impl DevicePanel {
                         if let Some(rssi) = device.rssi {
                             ui.label(format!("Signal: {} dBm", rssi));
                         }
-                        ui.label(if device.connected { "Status: Connected" } else { "Status: Disconnected" });
+                        ui.horizontal(|ui| {
+                            ui.label("Status:");
+                            ui.colored_label(state_color(device.state), state_label(device.state));
+                        });
+                        if let Some(error) = &device.error {
+                            ui.colored_label(egui::Color32::RED, error);
+                        }
                     });
                     ui.add_space(5.0);
                 }
@@ -95,42 +184,67 @@ impl DevicePanel {
         ui.separator();
 
         // 连接按钮
+        let selected_state = self
+            .selected_device
+            .and_then(|i| self.devices.get(i))
+            .map(|d| d.state);
         ui.horizontal(|ui| {
-            let has_selection = self.selected_device.is_some();
-
-            if ui.add_enabled(has_selection, egui::Button::new("🔌 Connect")).clicked() {
-                if let Some(i) = self.selected_device {
-                    if let Some(device) = self.devices.get_mut(i) {
-                        device.connected = true;
-                    }
+            let can_connect = selected_state == Some(DeviceState::Disconnected);
+            let can_disconnect = matches!(
+                selected_state,
+                Some(DeviceState::Connected | DeviceState::Running | DeviceState::Error)
+            );
+
+            if ui
+                .add_enabled(can_connect, egui::Button::new("🔌 Connect"))
+                .clicked()
+            {
+                if let Some(device) = self.selected_device.and_then(|i| self.devices.get_mut(i)) {
+                    device.state = DeviceState::Connecting;
+                    device.error = None;
+                    backend.send(Command::Connect {
+                        id: device.id.clone(),
+                        name: device.name.clone(),
+                    });
                 }
             }
 
-            if ui.add_enabled(has_selection, egui::Button::new("⏏️ Disconnect")).clicked() {
-                if let Some(i) = self.selected_device {
-                    if let Some(device) = self.devices.get_mut(i) {
-                        device.connected = false;
-                    }
+            if ui
+                .add_enabled(can_disconnect, egui::Button::new("⏏️ Disconnect"))
+                .clicked()
+            {
+                if let Some(device) = self.selected_device.and_then(|i| self.devices.get(i)) {
+                    backend.send(Command::Disconnect {
+                        id: device.id.clone(),
+                    });
                 }
             }
+
+            if selected_state == Some(DeviceState::Connecting) {
+                ui.spinner();
+                ui.label("Connecting...");
+            }
         });
     }
+}
+
+/// 状态显示文本
+fn state_label(state: DeviceState) -> &'static str {
+    match state {
+        DeviceState::Disconnected => "Disconnected",
+        DeviceState::Connecting => "Connecting",
+        DeviceState::Connected => "Connected",
+        DeviceState::Running => "Running",
+        DeviceState::Error => "Error",
+    }
+}
 
-    /// 模拟扫描（演示用）
-    fn simulate_scan(&mut self) {
-        self.devices = vec![
-            DeviceInfo {
-                id: "device_001".to_string(),
-                name: "DG-LAB Coyote".to_string(),
-                rssi: Some(-65),
-                connected: false,
-            },
-            DeviceInfo {
-                id: "device_002".to_string(),
-                name: "DG-LAB 2.0".to_string(),
-                rssi: Some(-78),
-                connected: false,
-            },
-        ];
+/// 状态显示颜色
+fn state_color(state: DeviceState) -> egui::Color32 {
+    match state {
+        DeviceState::Disconnected => egui::Color32::GRAY,
+        DeviceState::Connecting => egui::Color32::YELLOW,
+        DeviceState::Connected | DeviceState::Running => egui::Color32::GREEN,
+        DeviceState::Error => egui::Color32::RED,
     }
 }
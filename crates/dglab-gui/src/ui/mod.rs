@@ -2,6 +2,8 @@
 
 pub mod device_panel;
 pub mod control_panel;
+pub mod power_chart;
+pub mod preset_panel;
 pub mod waveform_editor;
 pub mod settings_panel;
 pub mod wifi_panel;
@@ -0,0 +1,221 @@
+//! 双通道强度实时曲线
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use eframe::egui;
+use egui_plot::{Legend, Line, Plot, PlotPoints};
+use tokio::sync::broadcast;
+use tracing::debug;
+
+use dglab_core::device::DeviceEvent;
+
+use crate::backend::Backend;
+
+/// 曲线显示的时间窗口
+const WINDOW: Duration = Duration::from_secs(60);
+
+/// 强度上限（V3 协议 0~200）
+const MAX_STRENGTH: f64 = 200.0;
+
+/// 强度采样
+#[derive(Debug, Clone, Copy)]
+struct PowerSample {
+    /// 采样时间
+    at: Instant,
+    /// A 通道实际强度
+    strength_a: u8,
+    /// B 通道实际强度
+    strength_b: u8,
+}
+
+/// 最近 60 秒的强度采样
+#[derive(Debug, Default)]
+struct PowerHistory {
+    /// 按时间排序的采样
+    samples: VecDeque<PowerSample>,
+}
+
+impl PowerHistory {
+    /// 记录采样并丢弃窗口外的数据
+    fn push(&mut self, at: Instant, strength_a: u8, strength_b: u8) {
+        self.samples.push_back(PowerSample {
+            at,
+            strength_a,
+            strength_b,
+        });
+        self.prune(at);
+    }
+
+    /// 丢弃窗口外的采样（保留一个窗口外的点，使曲线从左边界开始）
+    fn prune(&mut self, now: Instant) {
+        while self.samples.len() > 1 && now.duration_since(self.samples[1].at) > WINDOW {
+            let _ = self.samples.pop_front();
+        }
+    }
+
+    /// 生成曲线点（x 为相对当前时间的秒数，范围 -60~0）
+    ///
+    /// 强度在两次上报之间保持不变，按阶梯绘制。
+    fn points(&self, now: Instant, channel: fn(&PowerSample) -> u8) -> Vec<[f64; 2]> {
+        let mut points = Vec::with_capacity(self.samples.len() * 2 + 1);
+        let mut last = None;
+
+        for sample in &self.samples {
+            let x =
+                -(now.saturating_duration_since(sample.at).as_secs_f64()).min(WINDOW.as_secs_f64());
+            let y = channel(sample) as f64;
+            if let Some(prev) = last {
+                points.push([x, prev]);
+            }
+            points.push([x, y]);
+            last = Some(y);
+        }
+
+        if let Some(y) = last {
+            points.push([0.0, y]);
+        }
+
+        points
+    }
+}
+
+/// 双通道强度实时曲线
+///
+/// 数据来自设备上报的实际强度（V3 B1 反馈 / WiFi 强度消息），
+/// 用于对比波形设定与设备实际输出。
+pub struct PowerChart {
+    /// 采样（后台订阅任务写入）
+    history: Arc<Mutex<PowerHistory>>,
+    /// 订阅的设备 ID
+    device_id: Option<String>,
+    /// 订阅任务句柄
+    task: Option<tokio::task::JoinHandle<()>>,
+}
+
+impl Default for PowerChart {
+    fn default() -> Self {
+        Self {
+            history: Arc::new(Mutex::new(PowerHistory::default())),
+            device_id: None,
+            task: None,
+        }
+    }
+}
+
+impl PowerChart {
+    /// 订阅会话中设备的事件（替换之前的订阅并清空曲线）
+    ///
+    /// 收到强度上报时记录采样并请求重绘。
+    pub fn subscribe(&mut self, backend: &Backend, device_id: String, ctx: egui::Context) {
+        self.unsubscribe();
+        self.clear();
+
+        let history = self.history.clone();
+        let session_manager = backend.session_manager().clone();
+        let id = device_id.clone();
+        self.task = Some(backend.runtime().spawn(async move {
+            let Some(device) = session_manager.get_device(&id).await else {
+                debug!("Power chart device {} is not in the session", id);
+                return;
+            };
+            let mut events = device.read().await.subscribe_events();
+            loop {
+                match events.recv().await {
+                    Ok(DeviceEvent::StatusReport { power_a, power_b }) => {
+                        if let Ok(mut history) = history.lock() {
+                            history.push(Instant::now(), power_a, power_b);
+                        }
+                        ctx.request_repaint();
+                    }
+                    Ok(_) => {}
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        debug!("Power chart lagged by {} events", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        }));
+        self.device_id = Some(device_id);
+    }
+
+    /// 取消订阅
+    pub fn unsubscribe(&mut self) {
+        self.device_id = None;
+        if let Some(handle) = self.task.take() {
+            handle.abort();
+        }
+    }
+
+    /// 订阅的设备 ID
+    pub fn device_id(&self) -> Option<&str> {
+        self.device_id.as_deref()
+    }
+
+    /// 清空曲线
+    pub fn clear(&self) {
+        if let Ok(mut history) = self.history.lock() {
+            history.samples.clear();
+        }
+    }
+
+    /// 渲染 UI
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        let now = Instant::now();
+        let (points_a, points_b) = match self.history.lock() {
+            Ok(mut history) => {
+                history.prune(now);
+                (
+                    history.points(now, |s| s.strength_a),
+                    history.points(now, |s| s.strength_b),
+                )
+            }
+            Err(_) => (Vec::new(), Vec::new()),
+        };
+
+        ui.horizontal(|ui| {
+            ui.label("Actual Strength (last 60s)");
+            if self.task.is_none() {
+                ui.colored_label(egui::Color32::GRAY, "no device");
+            }
+            if ui.small_button("Clear").clicked() {
+                self.clear();
+            }
+        });
+
+        Plot::new("power_chart")
+            .legend(Legend::default())
+            .height(180.0)
+            .include_x(-WINDOW.as_secs_f64())
+            .include_x(0.0)
+            .include_y(0.0)
+            .include_y(MAX_STRENGTH)
+            .allow_drag(false)
+            .allow_zoom(false)
+            .allow_scroll(false)
+            .show(ui, |plot_ui| {
+                plot_ui.line(
+                    Line::new(PlotPoints::from(points_a))
+                        .name("A")
+                        .color(egui::Color32::from_rgb(0x4f, 0xc3, 0xf7)),
+                );
+                plot_ui.line(
+                    Line::new(PlotPoints::from(points_b))
+                        .name("B")
+                        .color(egui::Color32::from_rgb(0xff, 0xb7, 0x4d)),
+                );
+            });
+
+        // 曲线随时间左移，有数据时持续刷新
+        if self.task.is_some() {
+            ui.ctx().request_repaint_after(Duration::from_millis(200));
+        }
+    }
+}
+
+impl Drop for PowerChart {
+    fn drop(&mut self) {
+        self.unsubscribe();
+    }
+}
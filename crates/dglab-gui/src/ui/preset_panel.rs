@@ -0,0 +1,319 @@
+//! 预设编辑面板
+
+use eframe::egui;
+
+use dglab_core::preset::{Preset, PresetChannelConfig};
+use dglab_protocol::v3::MAX_STRENGTH;
+
+use crate::backend::{Backend, Command, Update};
+
+/// 预设编辑面板
+#[derive(Default)]
+pub struct PresetPanel {
+    /// 已请求加载
+    requested: bool,
+    /// 等待后端结果
+    busy: bool,
+    /// 所有预设的有效配置（按名称排序）
+    presets: Vec<Preset>,
+    /// 波形库中的波形名称
+    waveforms: Vec<String>,
+    /// 正在编辑的预设（未保存的修改）
+    draft: Option<Preset>,
+    /// 等待确认删除
+    confirm_delete: bool,
+    /// 最近一次操作的错误
+    error: Option<String>,
+}
+
+impl PresetPanel {
+    /// 处理后端结果
+    pub fn handle(&mut self, update: &Update) {
+        match update {
+            Update::PresetsLoaded { presets, waveforms } => {
+                self.busy = false;
+                self.error = None;
+                // 正在编辑的预设已在别处被删除时放弃编辑，新建的预设保留
+                if let Some(draft) = &self.draft {
+                    let removed = !self.is_new(draft) && !presets.iter().any(|p| p.id == draft.id);
+                    if removed {
+                        self.draft = None;
+                    }
+                }
+                self.presets = presets.clone();
+                self.waveforms = waveforms.clone();
+            }
+            Update::PresetSaved(preset) => {
+                self.busy = false;
+                self.error = None;
+                match self.presets.iter_mut().find(|p| p.id == preset.id) {
+                    Some(existing) => *existing = preset.clone(),
+                    None => self.presets.push(preset.clone()),
+                }
+                self.presets.sort_by_key(|p| p.name.to_lowercase());
+                self.draft = Some(preset.clone());
+            }
+            Update::PresetDeleted(id) => {
+                self.busy = false;
+                self.error = None;
+                self.presets.retain(|p| &p.id != id);
+                if self.draft.as_ref().is_some_and(|d| &d.id == id) {
+                    self.draft = None;
+                }
+            }
+            Update::PresetError(message) => {
+                self.busy = false;
+                self.error = Some(message.clone());
+            }
+            _ => {}
+        }
+    }
+
+    /// 预设尚未保存过
+    fn is_new(&self, preset: &Preset) -> bool {
+        !self.presets.iter().any(|p| p.id == preset.id)
+    }
+
+    /// 渲染 UI
+    pub fn ui(&mut self, ui: &mut egui::Ui, backend: &Backend) {
+        if !self.requested {
+            self.requested = true;
+            self.busy = true;
+            backend.send(Command::LoadPresets);
+        }
+
+        ui.horizontal(|ui| {
+            ui.heading("Presets");
+            if ui
+                .add_enabled(!self.busy, egui::Button::new("🔄 Reload"))
+                .clicked()
+            {
+                self.busy = true;
+                backend.send(Command::LoadPresets);
+            }
+            if self.busy {
+                ui.spinner();
+            }
+        });
+        if let Some(error) = &self.error {
+            ui.colored_label(egui::Color32::RED, error);
+        }
+        ui.add_space(10.0);
+
+        ui.columns(2, |columns| {
+            self.list_ui(&mut columns[0]);
+            self.editor_ui(&mut columns[1], backend);
+        });
+    }
+
+    /// 左侧：预设列表
+    fn list_ui(&mut self, ui: &mut egui::Ui) {
+        ui.group(|ui| {
+            ui.horizontal(|ui| {
+                ui.label(format!("{} presets", self.presets.len()));
+                if ui.button("➕ New").clicked() {
+                    self.draft = Some(Preset::new("New Preset".to_string(), String::new()));
+                    self.confirm_delete = false;
+                }
+            });
+            ui.separator();
+
+            egui::ScrollArea::vertical()
+                .id_source("preset_list")
+                .show(ui, |ui| {
+                    for preset in &self.presets {
+                        let selected = self.draft.as_ref().is_some_and(|d| d.id == preset.id);
+                        if ui.selectable_label(selected, &preset.name).clicked() && !selected {
+                            self.draft = Some(preset.clone());
+                            self.confirm_delete = false;
+                        }
+                    }
+                });
+        });
+    }
+
+    /// 右侧：编辑选中的预设
+    fn editor_ui(&mut self, ui: &mut egui::Ui, backend: &Backend) {
+        let is_new = self.draft.as_ref().is_some_and(|d| self.is_new(d));
+        let Some(draft) = &mut self.draft else {
+            ui.centered_and_justified(|ui| {
+                ui.label("Select a preset or click 'New'");
+            });
+            return;
+        };
+
+        let mut discard = false;
+        ui.group(|ui| {
+            egui::Grid::new("preset_fields")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    ui.label("Name:");
+                    ui.text_edit_singleline(&mut draft.name);
+                    ui.end_row();
+
+                    ui.label("Description:");
+                    ui.add(egui::TextEdit::multiline(&mut draft.description).desired_rows(2));
+                    ui.end_row();
+
+                    if let Some(parent) = &draft.parent {
+                        let parent = self
+                            .presets
+                            .iter()
+                            .find(|p| &p.id == parent)
+                            .map_or(parent.as_str(), |p| p.name.as_str());
+                        ui.label("Inherits:");
+                        ui.label(parent);
+                        ui.end_row();
+                    }
+                });
+
+            // 显示的是有效配置，修改过的继承字段记为覆盖
+            for (channel, label, section) in [
+                (0u8, "Channel A", "channel_a"),
+                (1u8, "Channel B", "channel_b"),
+            ] {
+                ui.add_space(10.0);
+                ui.separator();
+                let inherited: Vec<&str> = CHANNEL_FIELDS
+                    .into_iter()
+                    .filter(|field| !draft.overrides_field(&format!("{}.{}", section, field)))
+                    .collect();
+                let config = match channel {
+                    0 => &mut draft.channel_a,
+                    _ => &mut draft.channel_b,
+                };
+                let changed = channel_ui(ui, label, channel, config, &self.waveforms, &inherited);
+                for field in changed {
+                    draft.set_override(&format!("{}.{}", section, field));
+                }
+            }
+
+            // 保存前按核心库的规则校验
+            let issues = draft.issues();
+            ui.add_space(10.0);
+            for issue in &issues {
+                ui.colored_label(egui::Color32::RED, issue.to_string());
+            }
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                let can_save = issues.is_empty() && !self.busy;
+                if ui
+                    .add_enabled(can_save, egui::Button::new("💾 Save"))
+                    .clicked()
+                {
+                    self.busy = true;
+                    backend.send(Command::SavePreset(draft.clone()));
+                }
+
+                if is_new {
+                    discard = ui.button("✖ Discard").clicked();
+                    return;
+                }
+
+                if self.confirm_delete {
+                    ui.label("Delete this preset?");
+                    if ui.button("Yes").clicked() {
+                        self.busy = true;
+                        self.confirm_delete = false;
+                        backend.send(Command::DeletePreset {
+                            id: draft.id.clone(),
+                        });
+                    }
+                    if ui.button("No").clicked() {
+                        self.confirm_delete = false;
+                    }
+                } else if ui
+                    .add_enabled(!self.busy, egui::Button::new("🗑 Delete"))
+                    .clicked()
+                {
+                    self.confirm_delete = true;
+                }
+            });
+        });
+
+        if discard {
+            self.draft = None;
+        }
+    }
+}
+
+/// 编辑器中可以继承的通道字段
+const CHANNEL_FIELDS: [&str; 4] = ["enabled", "max_power", "min_power", "waveform"];
+
+/// 单个通道的编辑控件，返回修改过的字段
+///
+/// `inherited` 中的字段显示为继承自父预设。
+fn channel_ui(
+    ui: &mut egui::Ui,
+    label: &str,
+    channel: u8,
+    config: &mut PresetChannelConfig,
+    waveforms: &[String],
+    inherited: &[&str],
+) -> Vec<&'static str> {
+    let mut changed = Vec::new();
+    let hint = |field: &str| {
+        if inherited.iter().any(|f| *f == field) {
+            " (inherited)"
+        } else {
+            ""
+        }
+    };
+
+    ui.horizontal(|ui| {
+        ui.strong(label);
+        let enabled = format!("Enabled{}", hint("enabled"));
+        if ui.checkbox(&mut config.enabled, enabled).changed() {
+            changed.push("enabled");
+        }
+    });
+
+    ui.add_enabled_ui(config.enabled, |ui| {
+        let text = format!("Max Power{}", hint("max_power"));
+        let slider = egui::Slider::new(&mut config.max_power, 0..=MAX_STRENGTH).text(text);
+        if ui.add(slider).changed() {
+            changed.push("max_power");
+        }
+        let text = format!("Min Power{}", hint("min_power"));
+        let slider = egui::Slider::new(&mut config.min_power, 0..=config.max_power).text(text);
+        if ui.add(slider).changed() {
+            changed.push("min_power");
+        }
+
+        // 内嵌波形优先于波形库中的名称
+        let selected = match (&config.waveform, &config.waveform_name) {
+            (Some(waveform), _) => format!("{} (embedded)", waveform.name),
+            (None, Some(name)) => name.clone(),
+            (None, None) => "Default".to_string(),
+        };
+        egui::ComboBox::from_id_source(("preset_waveform", channel))
+            .selected_text(format!("{}{}", selected, hint("waveform")))
+            .show_ui(ui, |ui| {
+                if ui
+                    .selectable_label(
+                        config.waveform.is_none() && config.waveform_name.is_none(),
+                        "Default",
+                    )
+                    .clicked()
+                {
+                    config.waveform = None;
+                    config.waveform_name = None;
+                    changed.push("waveform");
+                }
+                for name in waveforms {
+                    let current = config.waveform.is_none()
+                        && config
+                            .waveform_name
+                            .as_ref()
+                            .is_some_and(|n| n.eq_ignore_ascii_case(name));
+                    if ui.selectable_label(current, name).clicked() {
+                        config.waveform = None;
+                        config.waveform_name = Some(name.clone());
+                        changed.push("waveform");
+                    }
+                }
+            });
+    });
+    changed
+}
@@ -0,0 +1,121 @@
+//! 流式热路径的内存分配对比
+//!
+//! 用计数分配器统计每条消息的分配次数，对比拥有所有权的解析（`WsMessage`、逐帧 HEX 字符串）
+//! 和借用解析（`WsMessageRef`、`PulseMessage`、`PulseData::frames_message`），
+//! 并按每 100ms 一条消息折算为每秒分配次数。
+//!
+//! 运行：`cargo bench -p dglab-protocol --bench hot_path`
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use dglab_protocol::v3::WaveformData;
+use dglab_protocol::wifi::{Channel, PulseData, PulseMessage, WsEvent, WsMessage, WsMessageRef};
+
+/// 统计分配次数的分配器
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let _ = ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+/// 每个场景的迭代次数
+const ITERATIONS: usize = 100_000;
+
+/// 流式输出每秒的消息数（每 100ms 一条）
+const MESSAGES_PER_SEC: f64 = 10.0;
+
+/// 运行场景，打印每次的分配次数、每秒分配次数和耗时
+fn bench(name: &str, mut f: impl FnMut()) {
+    // 预热
+    for _ in 0..1000 {
+        f();
+    }
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    let elapsed = start.elapsed();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+    let per_op = allocations as f64 / ITERATIONS as f64;
+    println!(
+        "{:<40} {:>6.2} allocs/op {:>8.1} allocs/s {:>8.0} ns/op",
+        name,
+        per_op,
+        per_op * MESSAGES_PER_SEC,
+        elapsed.as_nanos() as f64 / ITERATIONS as f64
+    );
+}
+
+fn main() {
+    let strength = r#"{"type":"msg","clientId":"3ab0773d","targetId":"8d1f28bc","message":"strength-20+35+200+200"}"#;
+    let heartbeat =
+        r#"{"type":"heartbeat","clientId":"3ab0773d","targetId":"8d1f28bc","message":"200"}"#;
+    let frames: Vec<WaveformData> = (0..10)
+        .map(|i| WaveformData::new([10, 20, 30, 40], [0, i * 10, 50, 100]))
+        .collect();
+    let pulse = PulseData::frames_message(Channel::A, &frames);
+
+    println!("== Receive ==");
+    bench("strength: WsMessage", || {
+        let msg: WsMessage = serde_json::from_str(black_box(strength)).unwrap();
+        let _ = black_box(WsEvent::from_message(&msg));
+    });
+    bench("strength: WsMessageRef", || {
+        let msg = WsMessageRef::parse(black_box(strength)).unwrap();
+        let _ = black_box(WsEvent::from_ref(&msg));
+    });
+    bench("heartbeat: WsMessage", || {
+        let msg: WsMessage = serde_json::from_str(black_box(heartbeat)).unwrap();
+        let _ = black_box(WsEvent::from_message(&msg));
+    });
+    bench("heartbeat: WsMessageRef", || {
+        let msg = WsMessageRef::parse(black_box(heartbeat)).unwrap();
+        let _ = black_box(WsEvent::from_ref(&msg));
+    });
+    bench("pulse frames: Vec<String>", || {
+        let body = black_box(pulse.as_str()).split_once(':').unwrap().1;
+        let hex: Vec<String> = serde_json::from_str(body).unwrap();
+        for h in &hex {
+            let _ = black_box(WaveformData::from_hex_string(h));
+        }
+    });
+    bench("pulse frames: PulseMessage", || {
+        let message = PulseMessage::parse(black_box(pulse.as_str())).unwrap();
+        for frame in message.frames() {
+            let _ = black_box(frame);
+        }
+    });
+
+    println!("== Send ==");
+    bench("pulse: hex strings + to_message", || {
+        let hex = black_box(&frames)
+            .iter()
+            .map(WaveformData::to_hex_string)
+            .collect();
+        let _ = black_box(PulseData::new(Channel::A, hex).to_message());
+    });
+    bench("pulse: frames_message", || {
+        let _ = black_box(PulseData::frames_message(Channel::A, black_box(&frames)));
+    });
+}
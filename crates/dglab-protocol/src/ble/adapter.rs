@@ -0,0 +1,275 @@
+//! 蓝牙适配器选择
+//!
+//! 同时有内置蓝牙和 USB 蓝牙适配器时，可以按序号或名称选择使用哪一个，
+//! 未指定时使用系统返回的第一个适配器。
+
+use std::fmt;
+use std::str::FromStr;
+
+use btleplug::api::{Central, Manager as _};
+use btleplug::platform::{Adapter, Manager};
+use serde::{Deserialize, Serialize};
+
+use super::BleManager;
+use crate::error::{ProtocolError, Result};
+
+/// 蓝牙适配器信息
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdapterInfo {
+    /// 序号（系统返回的顺序，从 0 开始）
+    pub index: usize,
+    /// 适配器名称（平台相关，如 `hci0 (usb:v1D6Bp0246d0537)`）
+    pub name: String,
+}
+
+impl fmt::Display for AdapterInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.index, self.name)
+    }
+}
+
+/// 适配器选择方式
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AdapterSelector {
+    /// 按序号
+    Index(usize),
+    /// 按名称（不区分大小写的部分匹配）
+    Name(String),
+}
+
+impl FromStr for AdapterSelector {
+    type Err = ProtocolError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(ProtocolError::BleError(
+                "Adapter selector must not be empty".to_string(),
+            ));
+        }
+        Ok(match s.parse() {
+            Ok(index) => Self::Index(index),
+            Err(_) => Self::Name(s.to_string()),
+        })
+    }
+}
+
+impl fmt::Display for AdapterSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Index(index) => write!(f, "{}", index),
+            Self::Name(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+impl AdapterSelector {
+    /// 在适配器列表中查找
+    pub fn find<'a>(&self, adapters: &'a [AdapterInfo]) -> Option<&'a AdapterInfo> {
+        match self {
+            Self::Index(index) => adapters.iter().find(|a| a.index == *index),
+            Self::Name(name) => {
+                let name = name.to_lowercase();
+                adapters
+                    .iter()
+                    .find(|a| a.name.to_lowercase().contains(&name))
+            }
+        }
+    }
+}
+
+/// 蓝牙可用性
+///
+/// 没有蓝牙的机器上 BLE 功能不可用，WiFi 功能不受影响，界面据此禁用蓝牙相关操作。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum BleAvailability {
+    /// 有可用的蓝牙适配器
+    Available {
+        /// 适配器数量
+        adapters: usize,
+    },
+    /// 蓝牙后端正常，但没有适配器
+    NoAdapter,
+    /// 蓝牙后端不可用（如 BlueZ 未运行或没有蓝牙权限）
+    Unavailable {
+        /// 原因
+        reason: String,
+    },
+}
+
+impl BleAvailability {
+    /// 由适配器枚举结果得到可用性
+    fn from_probe(probe: Result<usize>) -> Self {
+        match probe {
+            Ok(0) => Self::NoAdapter,
+            Ok(adapters) => Self::Available { adapters },
+            Err(e) => Self::Unavailable {
+                reason: e.to_string(),
+            },
+        }
+    }
+
+    /// 是否可以使用 BLE
+    pub fn is_available(&self) -> bool {
+        matches!(self, Self::Available { .. })
+    }
+}
+
+impl fmt::Display for BleAvailability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Available { adapters } => write!(f, "available ({} adapter(s))", adapters),
+            Self::NoAdapter => write!(f, "no Bluetooth adapter found"),
+            Self::Unavailable { reason } => write!(f, "unavailable: {}", reason),
+        }
+    }
+}
+
+/// 枚举适配器及其信息
+async fn adapters() -> Result<Vec<(Adapter, AdapterInfo)>> {
+    let manager = Manager::new()
+        .await
+        .map_err(|e| ProtocolError::BleError(format!("Failed to create manager: {}", e)))?;
+
+    let adapters = manager
+        .adapters()
+        .await
+        .map_err(|e| ProtocolError::BleError(format!("Failed to get adapters: {}", e)))?;
+
+    let mut result = Vec::with_capacity(adapters.len());
+    for (index, adapter) in adapters.into_iter().enumerate() {
+        // 部分平台不提供名称，使用序号代替
+        let name = adapter
+            .adapter_info()
+            .await
+            .unwrap_or_else(|_| format!("adapter{}", index));
+        result.push((adapter, AdapterInfo { index, name }));
+    }
+    Ok(result)
+}
+
+impl BleManager {
+    /// 列出可用的蓝牙适配器
+    pub async fn list_adapters() -> Result<Vec<AdapterInfo>> {
+        Ok(adapters()
+            .await?
+            .into_iter()
+            .map(|(_, info)| info)
+            .collect())
+    }
+
+    /// 探测蓝牙可用性（不创建 BLE 管理器）
+    pub async fn availability() -> BleAvailability {
+        BleAvailability::from_probe(adapters().await.map(|adapters| adapters.len()))
+    }
+
+    /// 使用指定适配器创建 BLE 管理器
+    ///
+    /// `selector` 为 `None` 时使用第一个适配器。
+    pub async fn with_adapter(selector: Option<&AdapterSelector>) -> Result<Self> {
+        let mut adapters = adapters().await?;
+        if adapters.is_empty() {
+            return Err(ProtocolError::BleError(
+                "No Bluetooth adapter found".to_string(),
+            ));
+        }
+
+        let index = match selector {
+            Some(selector) => {
+                let infos: Vec<AdapterInfo> = adapters.iter().map(|(_, i)| i.clone()).collect();
+                selector.find(&infos).map(|i| i.index).ok_or_else(|| {
+                    ProtocolError::BleError(format!(
+                        "Bluetooth adapter '{}' not found, available: {}",
+                        selector,
+                        infos
+                            .iter()
+                            .map(ToString::to_string)
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ))
+                })?
+            }
+            None => 0,
+        };
+
+        let (adapter, info) = adapters.swap_remove(index);
+        Ok(Self::from_adapter(adapter, info))
+    }
+
+    /// 当前使用的适配器
+    pub fn adapter_info(&self) -> &AdapterInfo {
+        &self.adapter_info
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_adapters() -> Vec<AdapterInfo> {
+        vec![
+            AdapterInfo {
+                index: 0,
+                name: "hci0 (Intel Wireless)".to_string(),
+            },
+            AdapterInfo {
+                index: 1,
+                name: "hci1 (usb:v0A12p0001)".to_string(),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_parse_selector() {
+        assert_eq!(
+            "1".parse::<AdapterSelector>().unwrap(),
+            AdapterSelector::Index(1)
+        );
+        assert_eq!(
+            " hci1 ".parse::<AdapterSelector>().unwrap(),
+            AdapterSelector::Name("hci1".to_string())
+        );
+        assert!("".parse::<AdapterSelector>().is_err());
+    }
+
+    #[test]
+    fn test_find_adapter() {
+        let adapters = sample_adapters();
+        let find = |s: &str| {
+            s.parse::<AdapterSelector>()
+                .unwrap()
+                .find(&adapters)
+                .cloned()
+        };
+
+        assert_eq!(find("1").unwrap().index, 1);
+        assert_eq!(find("USB").unwrap().index, 1);
+        assert_eq!(find("intel").unwrap().index, 0);
+        assert!(find("2").is_none());
+        assert!(find("hci2").is_none());
+    }
+
+    #[test]
+    fn test_availability_from_probe() {
+        assert_eq!(
+            BleAvailability::from_probe(Ok(2)),
+            BleAvailability::Available { adapters: 2 }
+        );
+        assert_eq!(
+            BleAvailability::from_probe(Ok(0)),
+            BleAvailability::NoAdapter
+        );
+
+        let unavailable = BleAvailability::from_probe(Err(ProtocolError::BleError(
+            "Failed to create manager: org.bluez not found".to_string(),
+        )));
+        assert!(!unavailable.is_available());
+        assert!(unavailable.to_string().contains("org.bluez"));
+        assert_eq!(
+            serde_json::to_value(&unavailable).unwrap()["status"],
+            "unavailable"
+        );
+    }
+}
@@ -1,14 +1,24 @@
 //! BLE 设备实现
 
-use std::sync::Arc;
+use std::sync::{Arc, Mutex as StdMutex, MutexGuard};
+use std::time::Duration;
 
 use btleplug::api::{Characteristic, Peripheral as _, WriteType};
 use btleplug::platform::Peripheral;
 use futures_util::StreamExt;
 use tokio::sync::{mpsc, Mutex};
+use tokio::time::Instant;
 use tracing::{debug, info};
+use uuid::Uuid;
 
+use super::link::LinkParams;
+use super::throughput::{
+    validate_b0_interval, WriteMode, WriteStats, WriteTiming, DEFAULT_B0_INTERVAL,
+};
+use super::traffic::{self, Direction};
+use super::uuids;
 use crate::error::{ProtocolError, Result};
+use crate::v3::B0_HEAD;
 
 /// 设备信息
 #[derive(Debug, Clone)]
@@ -40,6 +50,21 @@ pub struct BleDevice {
     data_tx: mpsc::Sender<Vec<u8>>,
     /// 数据接收通道
     data_rx: Arc<Mutex<mpsc::Receiver<Vec<u8>>>>,
+    /// 写入设置（各克隆共享）
+    write: Arc<StdMutex<WriteSettings>>,
+}
+
+/// 写入设置、连接参数与 B0 发送统计
+#[derive(Debug, Default)]
+struct WriteSettings {
+    /// 连接参数调优结果
+    link: LinkParams,
+    /// 写入方式
+    mode: WriteMode,
+    /// B0 发送间隔（`None` 表示默认间隔）
+    b0_interval: Option<Duration>,
+    /// B0 发送统计
+    timing: WriteTiming,
 }
 
 impl BleDevice {
@@ -59,6 +84,7 @@ impl BleDevice {
             notify_char,
             data_tx,
             data_rx: Arc::new(Mutex::new(data_rx)),
+            write: Arc::default(),
         };
 
         // 启动通知监听任务
@@ -69,6 +95,7 @@ impl BleDevice {
 
     /// 启动通知监听
     fn start_notification_listener(&self) {
+        let id = self.id.clone();
         let peripheral = self.peripheral.clone();
         let notify_char = self.notify_char.clone();
         let data_tx = self.data_tx.clone();
@@ -78,6 +105,7 @@ impl BleDevice {
                 while let Some(data) = notifications.next().await {
                     if data.uuid == notify_char.uuid {
                         debug!("Received notification: {:02x?}", data.value);
+                        traffic::record(&id, Direction::Rx, &data.value);
                         let _ = data_tx.send(data.value).await;
                     }
                 }
@@ -91,17 +119,90 @@ impl BleDevice {
     }
 
     /// 发送数据到设备
+    ///
+    /// 使用当前的 [`WriteMode`]；B0 帧的发送间隔和写入耗时计入 [`write_stats`](Self::write_stats)。
     pub async fn send(&self, data: &[u8]) -> Result<()> {
         debug!("Sending data: {:02x?}", data);
+        traffic::record(&self.id, Direction::Tx, data);
 
-        self.peripheral
-            .write(&self.write_char, data, WriteType::WithoutResponse)
+        let write_type = match self.write_mode() {
+            WriteMode::WithoutResponse => WriteType::WithoutResponse,
+            WriteMode::WithResponse => WriteType::WithResponse,
+        };
+        let is_b0 = data.first() == Some(&B0_HEAD);
+        let sent_at = Instant::now();
+
+        let result = self
+            .peripheral
+            .write(&self.write_char, data, write_type)
             .await
-            .map_err(|e| ProtocolError::BleError(format!("Failed to write: {}", e)))?;
+            .map_err(|e| ProtocolError::BleError(format!("Failed to write: {}", e)));
 
+        if is_b0 {
+            let mut write = self.write_settings();
+            let target = write.b0_interval.unwrap_or(DEFAULT_B0_INTERVAL);
+            match &result {
+                Ok(()) => write.timing.record(sent_at, sent_at.elapsed(), target),
+                Err(_) => write.timing.record_failure(),
+            }
+        }
+
+        result
+    }
+
+    /// 当前写入方式
+    pub fn write_mode(&self) -> WriteMode {
+        self.write_settings().mode
+    }
+
+    /// 设置写入方式（对所有克隆生效）
+    pub fn set_write_mode(&self, mode: WriteMode) {
+        info!("Device {} write mode: {}", self.id, mode);
+        self.write_settings().mode = mode;
+    }
+
+    /// 当前 B0 发送间隔
+    pub fn b0_interval(&self) -> Duration {
+        self.write_settings()
+            .b0_interval
+            .unwrap_or(DEFAULT_B0_INTERVAL)
+    }
+
+    /// 设置 B0 发送间隔（50~250ms），输出循环在下一帧生效
+    pub fn set_b0_interval(&self, interval: Duration) -> Result<()> {
+        let interval = validate_b0_interval(interval)?;
+        info!("Device {} B0 interval: {:?}", self.id, interval);
+        self.write_settings().b0_interval = Some(interval);
         Ok(())
     }
 
+    /// 连接时协商的参数（MTU、连接优先级）
+    pub fn link_params(&self) -> LinkParams {
+        self.write_settings().link
+    }
+
+    /// 记录连接参数
+    pub(crate) fn set_link_params(&self, params: LinkParams) {
+        self.write_settings().link = params;
+    }
+
+    /// B0 发送统计
+    pub fn write_stats(&self) -> WriteStats {
+        self.write_settings().timing.stats()
+    }
+
+    /// 清空 B0 发送统计
+    pub fn reset_write_stats(&self) {
+        self.write_settings().timing = WriteTiming::default();
+    }
+
+    /// 获取写入设置（锁中毒时继续使用内部数据）
+    fn write_settings(&self) -> MutexGuard<'_, WriteSettings> {
+        self.write
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
     /// 接收设备数据
     pub async fn receive(&self) -> Result<Vec<u8>> {
         let mut rx = self.data_rx.lock().await;
@@ -139,6 +240,71 @@ impl BleDevice {
         Ok(())
     }
 
+    /// 读取当前信号强度（dBm）
+    ///
+    /// 平台未上报时返回 `None`。
+    pub async fn rssi(&self) -> Result<Option<i16>> {
+        let properties =
+            self.peripheral.properties().await.map_err(|e| {
+                ProtocolError::BleError(format!("Failed to read properties: {}", e))
+            })?;
+
+        Ok(properties.and_then(|p| p.rssi))
+    }
+
+    /// 读取设备信息
+    ///
+    /// 固件和硬件版本来自标准设备信息服务，电量来自电池特征；
+    /// 设备未提供或读取失败的字段为 `None`。需要在发现服务之后调用。
+    pub async fn read_info(&self) -> Result<DeviceInfo> {
+        let properties =
+            self.peripheral.properties().await.map_err(|e| {
+                ProtocolError::BleError(format!("Failed to read properties: {}", e))
+            })?;
+        let name = properties
+            .and_then(|p| p.local_name)
+            .unwrap_or_else(|| self.id.clone());
+
+        let info = DeviceInfo {
+            id: self.id.clone(),
+            name,
+            firmware_version: self.read_string(uuids::FIRMWARE_REVISION_CHAR_UUID).await,
+            hardware_version: self.read_string(uuids::HARDWARE_REVISION_CHAR_UUID).await,
+            battery_level: self
+                .read_characteristic(uuids::BATTERY_CHAR_UUID)
+                .await
+                .and_then(|value| value.first().copied()),
+        };
+        debug!("Device info for {}: {:?}", self.id, info);
+        Ok(info)
+    }
+
+    /// 读取特征值（特征不存在或读取失败时返回 `None`）
+    async fn read_characteristic(&self, uuid: Uuid) -> Option<Vec<u8>> {
+        let characteristic = self
+            .peripheral
+            .characteristics()
+            .into_iter()
+            .find(|c| c.uuid == uuid)?;
+        match self.peripheral.read(&characteristic).await {
+            Ok(value) => Some(value),
+            Err(e) => {
+                debug!("Failed to read characteristic {}: {}", uuid, e);
+                None
+            }
+        }
+    }
+
+    /// 读取字符串特征（去掉末尾的 NUL 和空白，空字符串视为未提供）
+    async fn read_string(&self, uuid: Uuid) -> Option<String> {
+        let value = self.read_characteristic(uuid).await?;
+        let text = String::from_utf8_lossy(&value)
+            .trim_end_matches('\0')
+            .trim()
+            .to_string();
+        (!text.is_empty()).then_some(text)
+    }
+
     /// 检查是否已连接
     pub async fn is_connected(&self) -> Result<bool> {
         self.peripheral
@@ -0,0 +1,132 @@
+//! 固件版本解析与兼容性检查
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::ProtocolError;
+
+/// 已验证可用的最低固件版本，低于此版本的主机可能不支持 V3 协议的全部指令
+pub const MIN_SUPPORTED_FIRMWARE: FirmwareVersion = FirmwareVersion::new(1, 0, 0);
+
+/// 固件版本号
+///
+/// 接受 `1.2.3`、`1.2`、`V1.2.3`、`1.2.3-beta` 等写法，缺省的部分按 0 处理，
+/// 数字之后的后缀被忽略。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FirmwareVersion {
+    /// 主版本号
+    pub major: u32,
+    /// 次版本号
+    pub minor: u32,
+    /// 修订号
+    pub patch: u32,
+}
+
+impl FirmwareVersion {
+    /// 创建版本号
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// 是否不低于 [`MIN_SUPPORTED_FIRMWARE`]
+    pub fn is_supported(&self) -> bool {
+        *self >= MIN_SUPPORTED_FIRMWARE
+    }
+}
+
+impl PartialOrd for FirmwareVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for FirmwareVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch).cmp(&(other.major, other.minor, other.patch))
+    }
+}
+
+impl fmt::Display for FirmwareVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+impl FromStr for FirmwareVersion {
+    type Err = ProtocolError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ProtocolError::DecodeError(format!("Invalid firmware version: {}", s));
+        let trimmed = s.trim().trim_start_matches(['v', 'V']);
+        let end = trimmed
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(trimmed.len());
+        let numeric = trimmed[..end].trim_end_matches('.');
+        if numeric.is_empty() {
+            return Err(invalid());
+        }
+
+        let mut parts = [0u32; 3];
+        for (i, part) in numeric.split('.').enumerate() {
+            let slot = parts.get_mut(i).ok_or_else(invalid)?;
+            *slot = part.parse().map_err(|_| invalid())?;
+        }
+
+        Ok(Self::new(parts[0], parts[1], parts[2]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            "1.2.3".parse::<FirmwareVersion>().unwrap(),
+            FirmwareVersion::new(1, 2, 3)
+        );
+        assert_eq!(
+            "V2.1".parse::<FirmwareVersion>().unwrap(),
+            FirmwareVersion::new(2, 1, 0)
+        );
+        assert_eq!(
+            " v3 ".parse::<FirmwareVersion>().unwrap(),
+            FirmwareVersion::new(3, 0, 0)
+        );
+        assert_eq!(
+            "1.4.0-beta".parse::<FirmwareVersion>().unwrap(),
+            FirmwareVersion::new(1, 4, 0)
+        );
+        assert_eq!(
+            "1.5.\0\0".parse::<FirmwareVersion>().unwrap(),
+            FirmwareVersion::new(1, 5, 0)
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!("".parse::<FirmwareVersion>().is_err());
+        assert!("beta".parse::<FirmwareVersion>().is_err());
+        assert!("1..2".parse::<FirmwareVersion>().is_err());
+        assert!("1.2.3.4".parse::<FirmwareVersion>().is_err());
+    }
+
+    #[test]
+    fn test_ordering_and_support() {
+        let v = |s: &str| s.parse::<FirmwareVersion>().unwrap();
+        assert!(v("1.10.0") > v("1.9.9"));
+        assert!(v("2.0") > v("1.99.99"));
+        assert_eq!(v("1.0"), v("1.0.0"));
+
+        assert!(v("1.0.0").is_supported());
+        assert!(v("3.2.1").is_supported());
+        assert!(!v("0.9.9").is_supported());
+        assert_eq!(MIN_SUPPORTED_FIRMWARE.to_string(), "1.0.0");
+    }
+}
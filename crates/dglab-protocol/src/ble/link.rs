@@ -0,0 +1,480 @@
+//! 连接参数调优
+//!
+//! 部分 Linux 适配器在连接间隔较长时会丢弃 20 字节的 B0 无响应写入。连接时按
+//! [`ConnectionPriority`] 请求更短的连接间隔，并读取协商后的 ATT MTU 供诊断使用。
+//! btleplug 没有提供 MTU 请求和连接参数接口，按平台处理：
+//!
+//! - Linux：BlueZ 在连接时自动发起 MTU 交换（上限由 `/etc/bluetooth/main.conf` 的
+//!   `ExchangeMTU` 决定），连接后通过 D-Bus 读取写入特征的 MTU；连接间隔在连接前写入 debugfs 的
+//!   `conn_min_interval` / `conn_max_interval`（需要 root 且挂载了 debugfs），连接后恢复原值。
+//! - 其它平台：MTU 和连接间隔由系统决定，无法读取或调整。
+//!
+//! MTU 由系统在连接时发起交换，本模块不主动请求，只读取协商结果：BlueZ 是否交换以及请求的上限
+//! 取决于 `main.conf` 的 `ExchangeMTU`（`dglab doctor` 会显示该设置）。
+//!
+//! 默认使用 [`ConnectionPriority::Balanced`]，不修改系统设置。启用
+//! [`LinkOptions::write_fallback`] 后，Linux 上无法按 [`ConnectionPriority::High`] 调整连接间隔时
+//! 改用有响应写入，由链路层流控保证 B0 不被丢弃。
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use super::throughput::WriteMode;
+use crate::error::ProtocolError;
+
+/// BLE 默认 ATT MTU（未协商时）
+pub const DEFAULT_ATT_MTU: u16 = 23;
+
+/// ATT 写入请求的头部长度（操作码 + 句柄）
+const ATT_WRITE_HEADER: u16 = 3;
+
+/// 连接优先级
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ConnectionPriority {
+    /// 使用系统默认连接参数（默认）
+    #[default]
+    Balanced,
+    /// 短连接间隔（7.5~15ms），减少丢帧和时延
+    High,
+    /// 长连接间隔（100~125ms），省电但时延较大
+    LowPower,
+}
+
+impl ConnectionPriority {
+    /// 连接间隔范围（单位 1.25ms），`Balanced` 不调整
+    pub fn interval_units(self) -> Option<(u16, u16)> {
+        match self {
+            Self::Balanced => None,
+            Self::High => Some((6, 12)),
+            Self::LowPower => Some((80, 100)),
+        }
+    }
+}
+
+impl fmt::Display for ConnectionPriority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Balanced => write!(f, "balanced"),
+            Self::High => write!(f, "high"),
+            Self::LowPower => write!(f, "low-power"),
+        }
+    }
+}
+
+impl FromStr for ConnectionPriority {
+    type Err = ProtocolError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "balanced" | "default" => Ok(Self::Balanced),
+            "high" => Ok(Self::High),
+            "low-power" | "low_power" | "low" => Ok(Self::LowPower),
+            _ => Err(ProtocolError::DecodeError(format!(
+                "Invalid connection priority '{}', expected balanced, high or low-power",
+                s
+            ))),
+        }
+    }
+}
+
+/// 连接参数选项
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkOptions {
+    /// 连接优先级
+    pub priority: ConnectionPriority,
+    /// Linux 上无法按高优先级调整连接间隔时改用有响应写入（需显式开启）
+    pub write_fallback: bool,
+}
+
+impl LinkOptions {
+    /// 按调优结果选择写入方式（`None` 表示保持默认）
+    pub fn fallback_write_mode(&self, params: &LinkParams) -> Option<WriteMode> {
+        needs_fallback(self, params, cfg!(target_os = "linux")).then_some(WriteMode::WithResponse)
+    }
+}
+
+/// 调优结果，通过 [`BleDevice::link_params`](super::BleDevice::link_params) 获取
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkParams {
+    /// 协商的 ATT MTU（平台未提供时为 `None`）
+    pub mtu: Option<u16>,
+    /// 请求的连接优先级
+    pub priority: ConnectionPriority,
+    /// 连接间隔是否已按优先级设置
+    pub priority_applied: bool,
+    /// 是否因无法调整连接间隔改用了有响应写入
+    pub write_fallback: bool,
+}
+
+impl LinkParams {
+    /// 单次写入的最大长度（MTU 未知时按默认 MTU 计算）
+    pub fn max_write_len(&self) -> usize {
+        usize::from(
+            self.mtu
+                .unwrap_or(DEFAULT_ATT_MTU)
+                .saturating_sub(ATT_WRITE_HEADER),
+        )
+    }
+}
+
+impl fmt::Display for LinkParams {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.mtu {
+            Some(mtu) => write!(f, "MTU {}", mtu)?,
+            None => write!(f, "MTU unknown")?,
+        }
+        write!(
+            f,
+            ", priority {} ({})",
+            self.priority,
+            if self.priority_applied {
+                "applied"
+            } else {
+                "system default"
+            }
+        )?;
+        if self.write_fallback {
+            write!(f, ", with-response writes")?;
+        }
+        Ok(())
+    }
+}
+
+/// 是否需要改用有响应写入
+fn needs_fallback(options: &LinkOptions, params: &LinkParams, linux: bool) -> bool {
+    options.write_fallback
+        && linux
+        && options.priority == ConnectionPriority::High
+        && !params.priority_applied
+}
+
+/// 连接前设置的连接间隔，连接后调用 [`IntervalOverride::restore`] 恢复
+#[derive(Debug)]
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub(crate) struct IntervalOverride {
+    #[cfg(target_os = "linux")]
+    inner: linux::DebugfsInterval,
+}
+
+impl IntervalOverride {
+    /// 按优先级设置设备所在适配器的连接间隔，不支持或没有权限时返回 `None`
+    #[cfg(target_os = "linux")]
+    pub(crate) async fn apply(device_id: &str, priority: ConnectionPriority) -> Option<Self> {
+        let units = priority.interval_units()?;
+        let inner = linux::DebugfsInterval::apply(device_id, units).await?;
+        Some(Self { inner })
+    }
+
+    /// 按优先级设置设备所在适配器的连接间隔，不支持或没有权限时返回 `None`
+    #[cfg(not(target_os = "linux"))]
+    pub(crate) async fn apply(_device_id: &str, _priority: ConnectionPriority) -> Option<Self> {
+        None
+    }
+
+    /// 恢复适配器原来的连接间隔
+    pub(crate) async fn restore(self) {
+        #[cfg(target_os = "linux")]
+        self.inner.restore().await;
+    }
+}
+
+/// 能否调整连接间隔（需要挂载 debugfs 且有写权限）
+#[cfg(target_os = "linux")]
+pub async fn priority_supported() -> bool {
+    linux::debugfs_writable().await
+}
+
+/// 能否调整连接间隔（平台不支持）
+#[cfg(not(target_os = "linux"))]
+pub async fn priority_supported() -> bool {
+    false
+}
+
+/// BlueZ `main.conf` 中的 `ExchangeMTU`（未设置或无法读取时为 `None`）
+#[cfg(target_os = "linux")]
+pub async fn exchange_mtu_setting() -> Option<u16> {
+    let config = tokio::fs::read_to_string("/etc/bluetooth/main.conf")
+        .await
+        .ok()?;
+    parse_exchange_mtu(&config)
+}
+
+/// BlueZ `main.conf` 中的 `ExchangeMTU`（平台不支持）
+#[cfg(not(target_os = "linux"))]
+pub async fn exchange_mtu_setting() -> Option<u16> {
+    None
+}
+
+/// 从 `main.conf` 内容中解析 `ExchangeMTU`（注释行忽略）
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_exchange_mtu(config: &str) -> Option<u16> {
+    config.lines().find_map(|line| {
+        let (key, value) = line.trim().split_once('=')?;
+        (key.trim() == "ExchangeMTU")
+            .then(|| value.trim().parse().ok())
+            .flatten()
+    })
+}
+
+/// 读取写入特征协商的 ATT MTU
+#[cfg(target_os = "linux")]
+pub(crate) async fn negotiated_mtu(device_id: &str) -> Option<u16> {
+    linux::negotiated_mtu(device_id).await
+}
+
+/// 读取写入特征协商的 ATT MTU（平台不支持）
+#[cfg(not(target_os = "linux"))]
+pub(crate) async fn negotiated_mtu(_device_id: &str) -> Option<u16> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::path::{Path, PathBuf};
+
+    use bluez_async::{BluetoothError, BluetoothSession};
+    use tokio::sync::OnceCell;
+    use tracing::{debug, info};
+
+    use crate::ble::uuids;
+
+    /// debugfs 中的适配器目录
+    const DEBUGFS_BLUETOOTH: &str = "/sys/kernel/debug/bluetooth";
+
+    /// 已修改的适配器连接间隔
+    #[derive(Debug)]
+    pub(super) struct DebugfsInterval {
+        /// 适配器目录
+        dir: PathBuf,
+        /// 修改前的 (min, max)
+        previous: (u16, u16),
+    }
+
+    impl DebugfsInterval {
+        /// 写入连接间隔，返回恢复用的原值
+        pub(super) async fn apply(device_id: &str, units: (u16, u16)) -> Option<Self> {
+            // 设备 ID 形如 `hci0/dev_XX_XX_XX_XX_XX_XX`
+            let adapter = device_id
+                .split('/')
+                .next()
+                .filter(|a| a.starts_with("hci"))?;
+            let dir = Path::new(DEBUGFS_BLUETOOTH).join(adapter);
+            let previous = read_interval(&dir).await?;
+
+            match write_interval(&dir, previous, units).await {
+                Ok(()) => {
+                    info!(
+                        "Connection interval on {} set to {:?} (was {:?})",
+                        adapter, units, previous
+                    );
+                    Some(Self { dir, previous })
+                }
+                Err(e) => {
+                    debug!("Failed to set connection interval on {}: {}", adapter, e);
+                    None
+                }
+            }
+        }
+
+        /// 恢复原值
+        pub(super) async fn restore(self) {
+            let current = read_interval(&self.dir).await.unwrap_or(self.previous);
+            if let Err(e) = write_interval(&self.dir, current, self.previous).await {
+                debug!(
+                    "Failed to restore connection interval in {}: {}",
+                    self.dir.display(),
+                    e
+                );
+            }
+        }
+    }
+
+    /// 是否有适配器的连接间隔可写
+    pub(super) async fn debugfs_writable() -> bool {
+        let Ok(mut entries) = tokio::fs::read_dir(DEBUGFS_BLUETOOTH).await else {
+            return false;
+        };
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            if !entry.file_name().to_string_lossy().starts_with("hci") {
+                continue;
+            }
+            let writable = tokio::fs::OpenOptions::new()
+                .write(true)
+                .open(entry.path().join("conn_min_interval"))
+                .await
+                .is_ok();
+            if writable {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// 读取 (min, max)，没有 debugfs 或没有权限时返回 `None`
+    async fn read_interval(dir: &Path) -> Option<(u16, u16)> {
+        let read = |name: &'static str| async move {
+            let value = tokio::fs::read_to_string(dir.join(name)).await.ok()?;
+            value.trim().parse::<u16>().ok()
+        };
+        Some((
+            read("conn_min_interval").await?,
+            read("conn_max_interval").await?,
+        ))
+    }
+
+    /// 写入 (min, max)，内核要求任何时刻 min ≤ max，按需调整写入顺序
+    async fn write_interval(
+        dir: &Path,
+        current: (u16, u16),
+        (min, max): (u16, u16),
+    ) -> std::io::Result<()> {
+        let min_path = dir.join("conn_min_interval");
+        let max_path = dir.join("conn_max_interval");
+        if min > current.1 {
+            tokio::fs::write(&max_path, max.to_string()).await?;
+            tokio::fs::write(&min_path, min.to_string()).await
+        } else {
+            tokio::fs::write(&min_path, min.to_string()).await?;
+            tokio::fs::write(&max_path, max.to_string()).await
+        }
+    }
+
+    /// BlueZ D-Bus 会话（首次读取 MTU 时建立，之后复用）
+    async fn session() -> Result<&'static BluetoothSession, BluetoothError> {
+        static SESSION: OnceCell<BluetoothSession> = OnceCell::const_new();
+        SESSION
+            .get_or_try_init(|| async {
+                // 连接任务由 bluez-async 在后台运行，会话存活期间一直保持
+                let (_, session) = BluetoothSession::new().await?;
+                Ok(session)
+            })
+            .await
+    }
+
+    /// 通过 BlueZ D-Bus 接口读取写入特征的 MTU
+    pub(super) async fn negotiated_mtu(device_id: &str) -> Option<u16> {
+        let result = async {
+            let session = session().await?;
+            let Some(device) = session
+                .get_devices()
+                .await?
+                .into_iter()
+                .find(|d| d.id.to_string() == device_id)
+            else {
+                return Ok(None);
+            };
+            let characteristic = session
+                .get_service_characteristic_by_uuid(
+                    &device.id,
+                    uuids::SERVICE_UUID,
+                    uuids::WRITE_CHAR_UUID,
+                )
+                .await?;
+            Ok::<_, BluetoothError>(characteristic.mtu)
+        }
+        .await;
+
+        match result {
+            Ok(mtu) => mtu,
+            Err(e) => {
+                debug!("Failed to read MTU of {}: {}", device_id, e);
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_priority_parse() {
+        assert_eq!(
+            "high".parse::<ConnectionPriority>().unwrap(),
+            ConnectionPriority::High
+        );
+        assert_eq!(
+            "Low-Power".parse::<ConnectionPriority>().unwrap(),
+            ConnectionPriority::LowPower
+        );
+        assert!("fast".parse::<ConnectionPriority>().is_err());
+        for priority in [
+            ConnectionPriority::Balanced,
+            ConnectionPriority::High,
+            ConnectionPriority::LowPower,
+        ] {
+            assert_eq!(
+                priority.to_string().parse::<ConnectionPriority>().unwrap(),
+                priority
+            );
+        }
+        assert_eq!(ConnectionPriority::Balanced.interval_units(), None);
+    }
+
+    #[test]
+    fn test_max_write_len() {
+        assert_eq!(LinkParams::default().max_write_len(), 20);
+        let params = LinkParams {
+            mtu: Some(247),
+            ..Default::default()
+        };
+        assert_eq!(params.max_write_len(), 244);
+    }
+
+    #[test]
+    fn test_needs_fallback() {
+        // 默认不调整连接间隔，也不改用有响应写入
+        let params = LinkParams::default();
+        assert!(!needs_fallback(&LinkOptions::default(), &params, true));
+
+        let options = LinkOptions {
+            priority: ConnectionPriority::High,
+            write_fallback: true,
+        };
+        assert!(needs_fallback(&options, &params, true));
+        assert!(!needs_fallback(&options, &params, false));
+
+        let applied = LinkParams {
+            priority_applied: true,
+            ..params
+        };
+        assert!(!needs_fallback(&options, &applied, true));
+
+        let disabled = LinkOptions {
+            write_fallback: false,
+            ..options
+        };
+        assert!(!needs_fallback(&disabled, &params, true));
+
+        let balanced = LinkOptions {
+            priority: ConnectionPriority::Balanced,
+            ..options
+        };
+        assert!(!needs_fallback(&balanced, &params, true));
+    }
+
+    #[test]
+    fn test_parse_exchange_mtu() {
+        let config = "[GATT]\n#ExchangeMTU = 23\nExchangeMTU = 517\n";
+        assert_eq!(parse_exchange_mtu(config), Some(517));
+        assert_eq!(parse_exchange_mtu("[GATT]\n#ExchangeMTU = 23\n"), None);
+    }
+
+    #[test]
+    fn test_display() {
+        let params = LinkParams {
+            mtu: Some(23),
+            priority: ConnectionPriority::High,
+            priority_applied: false,
+            write_fallback: true,
+        };
+        assert_eq!(
+            params.to_string(),
+            "MTU 23, priority high (system default), with-response writes"
+        );
+    }
+}
@@ -2,21 +2,39 @@
 //!
 //! 提供 BLE 设备扫描、连接和通信功能。
 
+pub mod adapter;
 pub mod device;
+pub mod firmware;
+pub mod link;
 pub mod scanner;
+pub mod sensor;
+pub mod throughput;
+pub mod traffic;
+pub mod watcher;
 
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::Duration;
 
-use btleplug::api::{Central, Manager as _, Peripheral as _, ScanFilter};
-use btleplug::platform::{Adapter, Manager, Peripheral};
+use btleplug::api::{Central, Peripheral as _, ScanFilter};
+use btleplug::platform::{Adapter, Peripheral};
 use tokio::sync::Mutex;
-use tracing::{debug, info};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
 
+pub use adapter::{AdapterInfo, AdapterSelector, BleAvailability};
 pub use device::{BleDevice, DeviceInfo};
+pub use firmware::{FirmwareVersion, MIN_SUPPORTED_FIRMWARE};
+pub use link::{ConnectionPriority, LinkOptions, LinkParams, DEFAULT_ATT_MTU};
 pub use scanner::{BleScanner, ScanResult};
+pub use sensor::{SensorDevice, SensorReading};
+pub use throughput::{WriteMode, WriteStats, DEFAULT_B0_INTERVAL};
+pub use traffic::{TrafficRecord, TrafficRecorder};
+pub use watcher::AdapterEvent;
 
 use crate::error::{ProtocolError, Result};
+use crate::v3::B0_LENGTH;
+use link::IntervalOverride;
 
 /// DG-LAB 设备相关 UUID（V3 协议）
 ///
@@ -38,40 +56,61 @@ pub mod uuids {
 
     /// 电池电量特征 UUID (0x1500) - 读/通知，1 字节
     pub const BATTERY_CHAR_UUID: Uuid = Uuid::from_u128(0x00001500_0000_1000_8000_00805f9b34fb);
+
+    /// 固件版本特征 UUID (0x2A26) - 标准设备信息服务，UTF-8 字符串
+    pub const FIRMWARE_REVISION_CHAR_UUID: Uuid =
+        Uuid::from_u128(0x00002a26_0000_1000_8000_00805f9b34fb);
+
+    /// 硬件版本特征 UUID (0x2A27) - 标准设备信息服务，UTF-8 字符串
+    pub const HARDWARE_REVISION_CHAR_UUID: Uuid =
+        Uuid::from_u128(0x00002a27_0000_1000_8000_00805f9b34fb);
 }
 
 /// BLE 管理器
 pub struct BleManager {
     /// 蓝牙适配器
     adapter: Adapter,
+    /// 适配器信息
+    adapter_info: AdapterInfo,
     /// 已发现的设备
     discovered_devices: Arc<Mutex<HashMap<String, Peripheral>>>,
     /// 已连接的设备
     connected_devices: Arc<Mutex<HashMap<String, BleDevice>>>,
+    /// 连接参数选项
+    link_options: StdMutex<LinkOptions>,
 }
 
 impl BleManager {
-    /// 创建新的 BLE 管理器
+    /// 创建新的 BLE 管理器（使用第一个适配器）
     pub async fn new() -> Result<Self> {
-        let manager = Manager::new()
-            .await
-            .map_err(|e| ProtocolError::BleError(format!("Failed to create manager: {}", e)))?;
-
-        let adapters = manager
-            .adapters()
-            .await
-            .map_err(|e| ProtocolError::BleError(format!("Failed to get adapters: {}", e)))?;
-
-        let adapter = adapters
-            .into_iter()
-            .next()
-            .ok_or_else(|| ProtocolError::BleError("No Bluetooth adapter found".to_string()))?;
+        Self::with_adapter(None).await
+    }
 
-        Ok(Self {
+    fn from_adapter(adapter: Adapter, adapter_info: AdapterInfo) -> Self {
+        info!("Using Bluetooth adapter {}", adapter_info);
+        Self {
             adapter,
+            adapter_info,
             discovered_devices: Arc::new(Mutex::new(HashMap::new())),
             connected_devices: Arc::new(Mutex::new(HashMap::new())),
-        })
+            link_options: StdMutex::new(LinkOptions::default()),
+        }
+    }
+
+    /// 当前连接参数选项
+    pub fn link_options(&self) -> LinkOptions {
+        *self
+            .link_options
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// 设置连接参数选项（之后的连接生效）
+    pub fn set_link_options(&self, options: LinkOptions) {
+        *self
+            .link_options
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = options;
     }
 
     /// 开始扫描设备
@@ -101,6 +140,28 @@ impl BleManager {
         Ok(())
     }
 
+    /// 扫描指定时长后返回扫描结果
+    ///
+    /// `cancel` 触发时立即停止扫描并返回 [`ProtocolError::Cancelled`]。
+    pub async fn scan_for(
+        &self,
+        duration: Duration,
+        cancel: &CancellationToken,
+    ) -> Result<Vec<ScanResult>> {
+        self.start_scan().await?;
+        let cancelled = tokio::select! {
+            _ = tokio::time::sleep(duration) => false,
+            _ = cancel.cancelled() => true,
+        };
+        self.stop_scan().await?;
+
+        if cancelled {
+            info!("BLE scan cancelled");
+            return Err(ProtocolError::Cancelled);
+        }
+        self.get_scan_results().await
+    }
+
     /// 获取扫描结果
     pub async fn get_scan_results(&self) -> Result<Vec<ScanResult>> {
         let mut results = Vec::new();
@@ -112,49 +173,12 @@ impl BleManager {
         debug!("Found {} peripherals", peripherals.len());
 
         for peripheral in peripherals {
-            if let Some(properties) = peripheral
-                .properties()
-                .await
-                .map_err(|e| ProtocolError::BleError(format!("Failed to get properties: {}", e)))?
-            {
-                let local_name = properties
-                    .local_name
-                    .unwrap_or_else(|| "Unknown".to_string());
-
-                debug!(
-                    "Device: {} ({}), RSSI: {:?}, Services: {:?}",
-                    local_name,
-                    properties.address,
-                    properties.rssi,
-                    properties.services.len()
-                );
-
-                // 检查是否是 DG-LAB 设备
-                // 脉冲主机 3.0 蓝牙名称: 47L121000
-                // 无线传感器蓝牙名称: 47L120100
-                // 2.0 设备名称前缀: D-LAB
-                if local_name.starts_with("47L121")
-                    || local_name.starts_with("47L120")
-                    || local_name.starts_with("47")  // 更宽松的前缀匹配
-                    || local_name.starts_with("D-LAB")
-                    || local_name.to_lowercase().contains("dglab")
-                    || local_name.to_lowercase().contains("coyote")
-                    || properties.services.contains(&uuids::SERVICE_UUID)
-                {
-                    info!(
-                        "Found DG-LAB device: {} ({})",
-                        local_name, properties.address
-                    );
-                    results.push(ScanResult {
-                        id: peripheral.id().to_string(),
-                        name: local_name,
-                        address: properties.address.to_string(),
-                        rssi: properties.rssi,
-                    });
-
-                    let mut discovered = self.discovered_devices.lock().await;
-                    discovered.insert(peripheral.id().to_string(), peripheral);
-                }
+            if let Some(result) = scan_result(&peripheral, &self.adapter_info.name).await? {
+                info!("Found DG-LAB device: {} ({})", result.name, result.address);
+                results.push(result);
+
+                let mut discovered = self.discovered_devices.lock().await;
+                discovered.insert(peripheral.id().to_string(), peripheral);
             }
         }
 
@@ -163,19 +187,25 @@ impl BleManager {
     }
 
     /// 连接到设备
+    ///
+    /// 按 [`link_options`](Self::link_options) 调整连接参数，结果见 [`BleDevice::link_params`]。
     pub async fn connect(&self, device_id: &str) -> Result<BleDevice> {
         info!("Connecting to device: {}", device_id);
 
-        let discovered = self.discovered_devices.lock().await;
-        let peripheral = discovered
-            .get(device_id)
-            .ok_or_else(|| ProtocolError::DeviceNotFound(device_id.to_string()))?;
+        let peripheral = self.discovered_peripheral(device_id).await?;
+        let options = self.link_options();
 
-        // 连接设备
-        peripheral
+        // 连接间隔只在建立连接时协商，连接前修改适配器设置，连接后立即恢复
+        let interval = IntervalOverride::apply(device_id, options.priority).await;
+        let priority_applied = interval.is_some();
+        let connected = peripheral
             .connect()
             .await
-            .map_err(|e| ProtocolError::ConnectionError(format!("Failed to connect: {}", e)))?;
+            .map_err(|e| ProtocolError::ConnectionError(format!("Failed to connect: {}", e)));
+        if let Some(interval) = interval {
+            interval.restore().await;
+        }
+        connected?;
 
         // 发现服务
         peripheral.discover_services().await.map_err(|e| {
@@ -206,12 +236,25 @@ impl BleManager {
             .await
             .map_err(|e| ProtocolError::ConnectionError(format!("Failed to subscribe: {}", e)))?;
 
-        let device = BleDevice::new(
-            device_id.to_string(),
-            peripheral.clone(),
-            write_char,
-            notify_char,
-        );
+        let device = BleDevice::new(device_id.to_string(), peripheral, write_char, notify_char);
+        let mut params = LinkParams {
+            mtu: link::negotiated_mtu(device_id).await,
+            priority: options.priority,
+            priority_applied,
+            write_fallback: false,
+        };
+        if let Some(mode) = options.fallback_write_mode(&params) {
+            params.write_fallback = true;
+            device.set_write_mode(mode);
+        }
+        if params.max_write_len() < B0_LENGTH {
+            warn!(
+                "MTU {:?} of {} is too small for B0 frames",
+                params.mtu, device_id
+            );
+        }
+        info!("Link parameters for {}: {}", device_id, params);
+        device.set_link_params(params);
 
         // 保存连接
         let mut connected = self.connected_devices.lock().await;
@@ -220,6 +263,59 @@ impl BleManager {
         Ok(device)
     }
 
+    /// 连接到设备，`cancel` 触发时中止连接
+    ///
+    /// 取消时断开已建立的底层连接并返回 [`ProtocolError::Cancelled`]，不会留下半开的连接。
+    pub async fn connect_cancellable(
+        &self,
+        device_id: &str,
+        cancel: &CancellationToken,
+    ) -> Result<BleDevice> {
+        tokio::select! {
+            result = self.connect(device_id) => result,
+            _ = cancel.cancelled() => {
+                info!("Connection to {} cancelled", device_id);
+                self.abort_connect(device_id).await;
+                Err(ProtocolError::Cancelled)
+            }
+        }
+    }
+
+    /// 断开连接中途被取消的设备
+    async fn abort_connect(&self, device_id: &str) {
+        if let Some(device) = self.connected_devices.lock().await.remove(device_id) {
+            if let Err(e) = device.disconnect().await {
+                warn!("Failed to disconnect {}: {}", device_id, e);
+            }
+            return;
+        }
+        let Ok(peripheral) = self.discovered_peripheral(device_id).await else {
+            return;
+        };
+        if peripheral.is_connected().await.unwrap_or(false) {
+            if let Err(e) = peripheral.disconnect().await {
+                warn!("Failed to disconnect {}: {}", device_id, e);
+            }
+        }
+    }
+
+    /// 获取已发现的外设
+    async fn discovered_peripheral(&self, device_id: &str) -> Result<Peripheral> {
+        self.discovered_devices
+            .lock()
+            .await
+            .get(device_id)
+            .cloned()
+            .ok_or_else(|| ProtocolError::DeviceNotFound(device_id.to_string()))
+    }
+
+    /// 连接到无线传感器
+    ///
+    /// 传感器与脉冲主机使用相同的特征，连接后订阅通知特征接收读数。
+    pub async fn connect_sensor(&self, device_id: &str) -> Result<SensorDevice> {
+        Ok(SensorDevice::new(self.connect(device_id).await?))
+    }
+
     /// 断开设备连接
     pub async fn disconnect(&self, device_id: &str) -> Result<()> {
         info!("Disconnecting device: {}", device_id);
@@ -232,3 +328,49 @@ impl BleManager {
         Ok(())
     }
 }
+
+/// 读取外设属性，是 DG-LAB 设备时返回扫描结果（标记所在适配器）
+async fn scan_result(peripheral: &Peripheral, adapter: &str) -> Result<Option<ScanResult>> {
+    let Some(properties) = peripheral
+        .properties()
+        .await
+        .map_err(|e| ProtocolError::BleError(format!("Failed to get properties: {}", e)))?
+    else {
+        return Ok(None);
+    };
+
+    let local_name = properties
+        .local_name
+        .unwrap_or_else(|| "Unknown".to_string());
+
+    debug!(
+        "Device: {} ({}), RSSI: {:?}, Services: {:?}",
+        local_name,
+        properties.address,
+        properties.rssi,
+        properties.services.len()
+    );
+
+    // 检查是否是 DG-LAB 设备
+    // 脉冲主机 3.0 蓝牙名称: 47L121000
+    // 无线传感器蓝牙名称: 47L120100
+    // 2.0 设备名称前缀: D-LAB
+    let is_dglab = local_name.starts_with("47L121")
+        || local_name.starts_with("47L120")
+        || local_name.starts_with("47")  // 更宽松的前缀匹配
+        || local_name.starts_with("D-LAB")
+        || local_name.to_lowercase().contains("dglab")
+        || local_name.to_lowercase().contains("coyote")
+        || properties.services.contains(&uuids::SERVICE_UUID);
+    if !is_dglab {
+        return Ok(None);
+    }
+
+    Ok(Some(ScanResult {
+        id: peripheral.id().to_string(),
+        name: local_name,
+        address: properties.address.to_string(),
+        rssi: properties.rssi,
+        adapter: adapter.to_string(),
+    }))
+}
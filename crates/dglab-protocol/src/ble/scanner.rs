@@ -11,6 +11,15 @@ pub struct ScanResult {
     pub address: String,
     /// 信号强度
     pub rssi: Option<i16>,
+    /// 扫描到该设备的蓝牙适配器名称
+    pub adapter: String,
+}
+
+impl ScanResult {
+    /// 是否为无线传感器（47L120100）
+    pub fn is_sensor(&self) -> bool {
+        super::sensor::is_sensor_name(&self.name)
+    }
 }
 
 /// BLE 扫描器
@@ -76,6 +85,7 @@ mod tests {
             name: name.to_string(),
             address: address.to_string(),
             rssi,
+            adapter: "hci0".to_string(),
         }
     }
 
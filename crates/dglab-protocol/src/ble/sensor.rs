@@ -0,0 +1,205 @@
+//! 无线传感器（47L120100）
+//!
+//! 传感器与脉冲主机使用相同的服务和特征（0x180C / 0x150A / 0x150B），
+//! 读数通过通知特征上报，每条通知为一个读数：
+//!
+//! | 头部 | 长度 | 内容 |
+//! |------|------|------|
+//! | `0x51` | 2 | 按键状态（0 松开，1 按下） |
+//! | `0x52` | 3 | 模拟量，大端 u16（0~[`ANALOG_MAX`]） |
+//! | `0x53` | 7 | 三轴加速度，大端 i16 × 3（单位 mg） |
+//!
+//! 未知头部的通知保留原始数据，不影响后续读数。
+//!
+//! **实验性**：官方没有公开传感器协议，上述格式为抓包推测的结果，未经官方文档确认，
+//! 不同固件版本可能不同。
+
+use serde::{Deserialize, Serialize};
+
+use super::device::{BleDevice, DeviceInfo};
+use crate::error::Result;
+
+/// 无线传感器蓝牙名称前缀（完整名称如 `47L120100`）
+pub const SENSOR_NAME_PREFIX: &str = "47L120";
+
+/// 按键状态头部
+pub const SENSOR_BUTTON_HEAD: u8 = 0x51;
+
+/// 模拟量头部
+pub const SENSOR_ANALOG_HEAD: u8 = 0x52;
+
+/// 加速度头部
+pub const SENSOR_MOTION_HEAD: u8 = 0x53;
+
+/// 模拟量最大值（12 位 ADC）
+pub const ANALOG_MAX: u16 = 4095;
+
+/// 是否为无线传感器的蓝牙名称
+pub fn is_sensor_name(name: &str) -> bool {
+    name.starts_with(SENSOR_NAME_PREFIX)
+}
+
+/// 传感器读数
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SensorReading {
+    /// 按键状态
+    Button {
+        /// 是否按下
+        pressed: bool,
+    },
+    /// 模拟量（0~[`ANALOG_MAX`]）
+    Analog {
+        /// 原始值
+        value: u16,
+    },
+    /// 三轴加速度（mg）
+    Motion {
+        /// X 轴
+        x: i16,
+        /// Y 轴
+        y: i16,
+        /// Z 轴
+        z: i16,
+    },
+    /// 无法识别的通知（原始数据）
+    Unknown {
+        /// 原始数据
+        data: Vec<u8>,
+    },
+}
+
+impl SensorReading {
+    /// 解析通知数据，长度不足或头部未知时返回 [`SensorReading::Unknown`]
+    pub fn decode(data: &[u8]) -> Self {
+        let i16_at = |i: usize| i16::from_be_bytes([data[i], data[i + 1]]);
+        match data {
+            [SENSOR_BUTTON_HEAD, state, ..] => Self::Button {
+                pressed: *state != 0,
+            },
+            [SENSOR_ANALOG_HEAD, hi, lo, ..] => Self::Analog {
+                value: u16::from_be_bytes([*hi, *lo]).min(ANALOG_MAX),
+            },
+            [SENSOR_MOTION_HEAD, ..] if data.len() >= 7 => Self::Motion {
+                x: i16_at(1),
+                y: i16_at(3),
+                z: i16_at(5),
+            },
+            _ => Self::Unknown {
+                data: data.to_vec(),
+            },
+        }
+    }
+
+    /// 加速度的模（mg），其它读数为 `None`
+    pub fn magnitude(&self) -> Option<f32> {
+        match *self {
+            Self::Motion { x, y, z } => {
+                let (x, y, z) = (f32::from(x), f32::from(y), f32::from(z));
+                Some((x * x + y * y + z * z).sqrt())
+            }
+            _ => None,
+        }
+    }
+}
+
+/// 无线传感器
+///
+/// 包装已连接的 [`BleDevice`]（通知特征已订阅），把通知解析为 [`SensorReading`]。
+#[derive(Clone)]
+pub struct SensorDevice {
+    /// 底层 BLE 设备
+    ble: BleDevice,
+}
+
+impl SensorDevice {
+    /// 包装已连接的 BLE 设备
+    pub fn new(ble: BleDevice) -> Self {
+        Self { ble }
+    }
+
+    /// 设备 ID
+    pub fn id(&self) -> &str {
+        self.ble.id()
+    }
+
+    /// 等待下一条读数
+    pub async fn next_reading(&self) -> Result<SensorReading> {
+        let data = self.ble.receive().await?;
+        Ok(SensorReading::decode(&data))
+    }
+
+    /// 读取设备信息（固件版本、电量）
+    pub async fn read_info(&self) -> Result<DeviceInfo> {
+        self.ble.read_info().await
+    }
+
+    /// 断开连接
+    pub async fn disconnect(&self) -> Result<()> {
+        self.ble.disconnect().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sensor_name() {
+        assert!(is_sensor_name("47L120100"));
+        assert!(!is_sensor_name("47L121000"));
+        assert!(!is_sensor_name("D-LAB ESTIM01"));
+    }
+
+    #[test]
+    fn test_decode_readings() {
+        assert_eq!(
+            SensorReading::decode(&[0x51, 0x01]),
+            SensorReading::Button { pressed: true }
+        );
+        assert_eq!(
+            SensorReading::decode(&[0x51, 0x00]),
+            SensorReading::Button { pressed: false }
+        );
+        assert_eq!(
+            SensorReading::decode(&[0x52, 0x08, 0x00]),
+            SensorReading::Analog { value: 2048 }
+        );
+        // 超出范围的模拟量按最大值处理
+        assert_eq!(
+            SensorReading::decode(&[0x52, 0xff, 0xff]),
+            SensorReading::Analog { value: ANALOG_MAX }
+        );
+
+        let motion = SensorReading::decode(&[0x53, 0x00, 0x00, 0xfc, 0x18, 0x00, 0x00]);
+        assert_eq!(
+            motion,
+            SensorReading::Motion {
+                x: 0,
+                y: -1000,
+                z: 0
+            }
+        );
+        assert_eq!(motion.magnitude(), Some(1000.0));
+        assert_eq!(SensorReading::Analog { value: 1 }.magnitude(), None);
+    }
+
+    #[test]
+    fn test_decode_unknown() {
+        // 长度不足
+        assert_eq!(
+            SensorReading::decode(&[0x53, 0x00, 0x01]),
+            SensorReading::Unknown {
+                data: vec![0x53, 0x00, 0x01]
+            }
+        );
+        assert!(matches!(
+            SensorReading::decode(&[0xb1, 0x00, 0x10, 0x20]),
+            SensorReading::Unknown { .. }
+        ));
+        assert!(matches!(
+            SensorReading::decode(&[]),
+            SensorReading::Unknown { .. }
+        ));
+    }
+}
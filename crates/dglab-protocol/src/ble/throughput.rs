@@ -0,0 +1,187 @@
+//! 写入方式与 B0 发送节奏
+//!
+//! 部分蓝牙适配器在无响应写入下会丢帧，可以改用有响应写入；
+//! B0 间隔可在一定范围内调整，并统计实际发送间隔的抖动和写入耗时。
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+
+use crate::error::ProtocolError;
+
+/// 默认 B0 发送间隔（每帧包含 4 × 25ms 波形）
+pub const DEFAULT_B0_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 允许的最短 B0 发送间隔
+pub const MIN_B0_INTERVAL: Duration = Duration::from_millis(50);
+
+/// 允许的最长 B0 发送间隔
+pub const MAX_B0_INTERVAL: Duration = Duration::from_millis(250);
+
+/// BLE 写入方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WriteMode {
+    /// 无响应写入（默认，吞吐量高）
+    #[default]
+    WithoutResponse,
+    /// 有响应写入（每次写入等待确认，适合容易丢帧的适配器）
+    WithResponse,
+}
+
+impl fmt::Display for WriteMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::WithoutResponse => write!(f, "without-response"),
+            Self::WithResponse => write!(f, "with-response"),
+        }
+    }
+}
+
+impl FromStr for WriteMode {
+    type Err = ProtocolError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "without-response" | "without_response" | "command" => Ok(Self::WithoutResponse),
+            "with-response" | "with_response" | "request" => Ok(Self::WithResponse),
+            _ => Err(ProtocolError::DecodeError(format!(
+                "Invalid write mode '{}', expected with-response or without-response",
+                s
+            ))),
+        }
+    }
+}
+
+/// 校验 B0 发送间隔
+pub fn validate_b0_interval(interval: Duration) -> Result<Duration, ProtocolError> {
+    if !(MIN_B0_INTERVAL..=MAX_B0_INTERVAL).contains(&interval) {
+        return Err(ProtocolError::EncodeError(format!(
+            "B0 interval {:?} out of range {:?}~{:?}",
+            interval, MIN_B0_INTERVAL, MAX_B0_INTERVAL
+        )));
+    }
+    Ok(interval)
+}
+
+/// B0 发送统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WriteStats {
+    /// 成功发送的 B0 帧数
+    pub frames: u64,
+    /// 写入失败次数
+    pub failed: u64,
+    /// 平均发送间隔
+    pub mean_interval: Duration,
+    /// 抖动（实际间隔与目标间隔之差的平均绝对值）
+    pub jitter: Duration,
+    /// 最长发送间隔
+    pub max_interval: Duration,
+    /// 平均写入耗时
+    pub mean_write: Duration,
+    /// 最长写入耗时
+    pub max_write: Duration,
+}
+
+/// B0 发送节奏统计器
+#[derive(Debug, Clone, Default)]
+pub struct WriteTiming {
+    /// 上一帧的发送时间
+    last_sent: Option<Instant>,
+    /// 已记录的间隔数
+    intervals: u64,
+    /// 间隔总和
+    interval_sum: Duration,
+    /// 与目标间隔偏差的总和
+    deviation_sum: Duration,
+    /// 统计结果
+    stats: WriteStats,
+    /// 写入耗时总和
+    write_sum: Duration,
+}
+
+impl WriteTiming {
+    /// 记录一次成功发送的 B0 帧
+    ///
+    /// `sent_at` 为开始写入的时间，`target` 为当前的目标间隔。
+    pub fn record(&mut self, sent_at: Instant, write: Duration, target: Duration) {
+        if let Some(last) = self.last_sent.replace(sent_at) {
+            let interval = sent_at.saturating_duration_since(last);
+            self.intervals += 1;
+            self.interval_sum += interval;
+            self.deviation_sum += interval.max(target) - interval.min(target);
+            self.stats.max_interval = self.stats.max_interval.max(interval);
+            self.stats.mean_interval = self.interval_sum / self.intervals as u32;
+            self.stats.jitter = self.deviation_sum / self.intervals as u32;
+        }
+
+        self.stats.frames += 1;
+        self.write_sum += write;
+        self.stats.max_write = self.stats.max_write.max(write);
+        self.stats.mean_write = self.write_sum / self.stats.frames as u32;
+    }
+
+    /// 记录一次写入失败
+    pub fn record_failure(&mut self) {
+        self.stats.failed += 1;
+    }
+
+    /// 获取统计结果
+    pub fn stats(&self) -> WriteStats {
+        self.stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_mode_parse() {
+        assert_eq!(
+            "with-response".parse::<WriteMode>().unwrap(),
+            WriteMode::WithResponse
+        );
+        assert_eq!(
+            "Without_Response".parse::<WriteMode>().unwrap(),
+            WriteMode::WithoutResponse
+        );
+        assert!("sometimes".parse::<WriteMode>().is_err());
+        assert_eq!(WriteMode::default().to_string(), "without-response");
+    }
+
+    #[test]
+    fn test_validate_b0_interval() {
+        assert!(validate_b0_interval(DEFAULT_B0_INTERVAL).is_ok());
+        assert!(validate_b0_interval(Duration::from_millis(40)).is_err());
+        assert!(validate_b0_interval(Duration::from_millis(300)).is_err());
+    }
+
+    #[test]
+    fn test_write_timing() {
+        let start = Instant::now();
+        let target = Duration::from_millis(100);
+        let mut timing = WriteTiming::default();
+
+        for (offset, write) in [(0, 5), (110, 15), (200, 10)] {
+            timing.record(
+                start + Duration::from_millis(offset),
+                Duration::from_millis(write),
+                target,
+            );
+        }
+        timing.record_failure();
+
+        let stats = timing.stats();
+        assert_eq!(stats.frames, 3);
+        assert_eq!(stats.failed, 1);
+        assert_eq!(stats.mean_interval, Duration::from_millis(100));
+        assert_eq!(stats.max_interval, Duration::from_millis(110));
+        assert_eq!(stats.jitter, Duration::from_millis(10));
+        assert_eq!(stats.mean_write, Duration::from_millis(10));
+        assert_eq!(stats.max_write, Duration::from_millis(15));
+    }
+}
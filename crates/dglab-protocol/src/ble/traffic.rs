@@ -0,0 +1,267 @@
+//! BLE 原始流量记录
+//!
+//! 把每一帧发送/接收的 BLE 数据（时间戳、方向、设备 ID、HEX 数据）按行写入
+//! JSONL 文件，用于排查适配器丢帧和协议问题。通过 [`install`] 或环境变量
+//! [`TRAFFIC_LOG_ENV`] 启用；记录文件可用 [`read_log`] 读回，并用
+//! [`describe_frame`] 按 V3 协议解码显示。
+
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::error::{ProtocolError, Result};
+use crate::v3::{B0Command, BFCommand, NotifyMessage, WaveformData, B0_HEAD, BF_HEAD};
+
+/// 启用流量记录的环境变量（值为记录文件路径）
+pub const TRAFFIC_LOG_ENV: &str = "DGLAB_BLE_LOG";
+
+/// 全局记录器（首次使用时从环境变量初始化）
+static RECORDER: OnceLock<Option<TrafficRecorder>> = OnceLock::new();
+
+/// 数据方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    /// 写入设备
+    Tx,
+    /// 设备通知
+    Rx,
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tx => write!(f, "TX"),
+            Self::Rx => write!(f, "RX"),
+        }
+    }
+}
+
+/// 一条流量记录（JSONL 中的一行）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrafficRecord {
+    /// 时间戳（Unix 毫秒）
+    pub timestamp_ms: u64,
+    /// 设备 ID
+    pub device: String,
+    /// 数据方向
+    pub direction: Direction,
+    /// 原始数据（HEX）
+    pub data: String,
+}
+
+impl TrafficRecord {
+    /// 解码 HEX 数据
+    pub fn bytes(&self) -> Result<Vec<u8>> {
+        hex::decode(&self.data)
+            .map_err(|e| ProtocolError::DecodeError(format!("Invalid hex data: {}", e)))
+    }
+}
+
+/// 流量记录器
+///
+/// 可克隆，各克隆写入同一个文件；每条记录写入后立即刷新，进程异常退出时不丢失。
+#[derive(Debug, Clone)]
+pub struct TrafficRecorder {
+    writer: Arc<Mutex<BufWriter<File>>>,
+}
+
+impl TrafficRecorder {
+    /// 创建记录器（追加写入）
+    pub fn create(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        info!("Recording BLE traffic to {}", path.display());
+        Ok(Self {
+            writer: Arc::new(Mutex::new(BufWriter::new(file))),
+        })
+    }
+
+    /// 记录一帧数据（写入失败只记录警告）
+    pub fn record(&self, device: &str, direction: Direction, data: &[u8]) {
+        let record = TrafficRecord {
+            timestamp_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_millis() as u64),
+            device: device.to_string(),
+            direction,
+            data: hex::encode(data),
+        };
+        if let Err(e) = self.write(&record) {
+            warn!("Failed to record BLE traffic: {}", e);
+        }
+    }
+
+    fn write(&self, record: &TrafficRecord) -> Result<()> {
+        let line =
+            serde_json::to_string(record).map_err(|e| ProtocolError::EncodeError(e.to_string()))?;
+        let mut writer = self.writer_guard();
+        writeln!(writer, "{}", line)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// 获取写入器（锁中毒时继续使用内部数据）
+    fn writer_guard(&self) -> MutexGuard<'_, BufWriter<File>> {
+        self.writer
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// 安装全局记录器
+///
+/// 需要在第一次 BLE 收发之前调用；已经安装（或已从环境变量初始化）时返回错误。
+pub fn install(recorder: TrafficRecorder) -> Result<()> {
+    RECORDER
+        .set(Some(recorder))
+        .map_err(|_| ProtocolError::Other("BLE traffic recorder already installed".to_string()))
+}
+
+/// 当前全局记录器
+///
+/// 未调用 [`install`] 时按环境变量 [`TRAFFIC_LOG_ENV`] 初始化，未设置则不记录。
+pub fn recorder() -> Option<&'static TrafficRecorder> {
+    RECORDER
+        .get_or_init(|| {
+            let path = std::env::var_os(TRAFFIC_LOG_ENV)?;
+            match TrafficRecorder::create(&path) {
+                Ok(recorder) => Some(recorder),
+                Err(e) => {
+                    warn!("Failed to open BLE traffic log: {}", e);
+                    None
+                }
+            }
+        })
+        .as_ref()
+}
+
+/// 向全局记录器写入一帧（未启用时什么都不做）
+pub fn record(device: &str, direction: Direction, data: &[u8]) {
+    if let Some(recorder) = recorder() {
+        recorder.record(device, direction, data);
+    }
+}
+
+/// 读取记录文件
+///
+/// 空行被跳过，无法解析的行返回带行号的错误。
+pub fn read_log(path: impl AsRef<Path>) -> Result<Vec<TrafficRecord>> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut records = Vec::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record = serde_json::from_str(&line)
+            .map_err(|e| ProtocolError::DecodeError(format!("Line {}: {}", index + 1, e)))?;
+        records.push(record);
+    }
+    Ok(records)
+}
+
+/// 按 V3 协议描述一帧数据
+///
+/// B0/BF 指令和 B1 回应解码为字段，无法识别的数据显示为 HEX。
+pub fn describe_frame(data: &[u8]) -> String {
+    match data.first() {
+        Some(&B0_HEAD) => match B0Command::decode(data) {
+            Some(cmd) => format!(
+                "B0 seq={} A={:?}:{} B={:?}:{} wave A={} B={}",
+                cmd.sequence,
+                cmd.strength_mode.channel_a,
+                cmd.strength_a,
+                cmd.strength_mode.channel_b,
+                cmd.strength_b,
+                wave(&cmd.waveform_a),
+                wave(&cmd.waveform_b)
+            ),
+            None => hex::encode(data),
+        },
+        Some(&BF_HEAD) => match BFCommand::decode(data) {
+            Some(cmd) => format!(
+                "BF limit A={} B={} freq balance {}/{} intensity balance {}/{}",
+                cmd.soft_limit_a,
+                cmd.soft_limit_b,
+                cmd.freq_balance_a,
+                cmd.freq_balance_b,
+                cmd.intensity_balance_a,
+                cmd.intensity_balance_b
+            ),
+            None => hex::encode(data),
+        },
+        _ => match NotifyMessage::parse(data) {
+            NotifyMessage::Strength(resp) => format!(
+                "B1 seq={} A={} B={}",
+                resp.sequence, resp.strength_a, resp.strength_b
+            ),
+            NotifyMessage::Unknown(_) => hex::encode(data),
+        },
+    }
+}
+
+/// 波形数据显示为 `频率/强度` 两段 HEX，静默帧显示为 `-`
+fn wave(data: &WaveformData) -> String {
+    if *data == WaveformData::silent() {
+        return "-".to_string();
+    }
+    let hex = data.to_hex_string();
+    format!("{}/{}", &hex[..8], &hex[8..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::v3::B1Response;
+
+    #[test]
+    fn test_record_and_read_log() {
+        let path = std::env::temp_dir().join(format!("dglab-traffic-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let recorder = TrafficRecorder::create(&path).unwrap();
+        recorder.record("dev", Direction::Tx, &B0Command::zero().encode());
+        recorder.record("dev", Direction::Rx, &[0xB1, 0x01, 0x10, 0x20]);
+
+        let records = read_log(&path).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].direction, Direction::Tx);
+        assert_eq!(records[0].bytes().unwrap(), B0Command::zero().encode());
+        assert_eq!(records[1].direction, Direction::Rx);
+        assert_eq!(records[1].data, "b1011020");
+
+        std::fs::write(&path, "{\"bad\":1}\n").unwrap();
+        let err = read_log(&path).unwrap_err();
+        assert!(err.to_string().contains("Line 1"));
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_describe_frame() {
+        let b0 = describe_frame(&B0Command::zero().encode());
+        assert!(b0.starts_with("B0 seq=0 A=Absolute:0"));
+
+        let bf = describe_frame(&BFCommand::default_config().encode());
+        assert!(bf.starts_with("BF limit A=200 B=200"));
+
+        let b1 = B1Response {
+            sequence: 3,
+            strength_a: 10,
+            strength_b: 20,
+        };
+        assert_eq!(describe_frame(&b1.encode()), "B1 seq=3 A=10 B=20");
+
+        assert_eq!(describe_frame(&[0x12, 0x34]), "1234");
+    }
+}
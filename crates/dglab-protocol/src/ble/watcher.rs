@@ -0,0 +1,92 @@
+//! 适配器事件监听
+//!
+//! 订阅 btleplug 的 `CentralEvent`，把 DG-LAB 设备的出现和断开转换为 [`AdapterEvent`]，
+//! 无需重新扫描即可感知设备的热插拔。设备出现事件需要适配器处于扫描状态。
+
+use std::collections::HashSet;
+
+use btleplug::api::{Central, CentralEvent};
+use futures_util::StreamExt;
+use tokio::sync::mpsc;
+use tracing::{debug, info};
+
+use super::{scan_result, BleManager, ScanResult};
+use crate::error::{ProtocolError, Result};
+
+/// 适配器事件
+#[derive(Debug, Clone)]
+pub enum AdapterEvent {
+    /// 发现 DG-LAB 设备（首次发现或断开后重新出现）
+    Discovered(ScanResult),
+    /// 设备断开连接（设备 ID）
+    Disconnected(String),
+}
+
+impl BleManager {
+    /// 监听适配器事件
+    ///
+    /// 发现的设备会加入已发现列表，可以直接 [`connect`](Self::connect)；
+    /// 断开的设备从已连接列表中移除。接收端被丢弃后监听任务退出。
+    pub async fn watch(&self) -> Result<mpsc::Receiver<AdapterEvent>> {
+        let mut events =
+            self.adapter.events().await.map_err(|e| {
+                ProtocolError::BleError(format!("Failed to get adapter events: {}", e))
+            })?;
+
+        let (tx, rx) = mpsc::channel(32);
+        let adapter = self.adapter.clone();
+        let adapter_name = self.adapter_info.name.clone();
+        let discovered = self.discovered_devices.clone();
+        let connected = self.connected_devices.clone();
+
+        tokio::spawn(async move {
+            // 已报告出现、尚未断开的设备
+            let mut present = HashSet::new();
+
+            while let Some(event) = events.next().await {
+                let event = match event {
+                    CentralEvent::DeviceDiscovered(id) | CentralEvent::DeviceUpdated(id) => {
+                        let key = id.to_string();
+                        if present.contains(&key) {
+                            continue;
+                        }
+                        let Ok(peripheral) = adapter.peripheral(&id).await else {
+                            continue;
+                        };
+                        let result = match scan_result(&peripheral, &adapter_name).await {
+                            Ok(Some(result)) => result,
+                            Ok(None) => continue,
+                            Err(e) => {
+                                debug!("Failed to inspect peripheral {}: {}", key, e);
+                                continue;
+                            }
+                        };
+
+                        info!("Device appeared: {} ({})", result.name, key);
+                        let _ = present.insert(key.clone());
+                        let _ = discovered.lock().await.insert(key, peripheral);
+                        AdapterEvent::Discovered(result)
+                    }
+                    CentralEvent::DeviceDisconnected(id) => {
+                        let key = id.to_string();
+                        let was_connected = connected.lock().await.remove(&key).is_some();
+                        if !present.remove(&key) && !was_connected {
+                            continue;
+                        }
+
+                        info!("Device disconnected: {}", key);
+                        AdapterEvent::Disconnected(key)
+                    }
+                    _ => continue,
+                };
+
+                if tx.send(event).await.is_err() {
+                    break;
+                }
+            }
+            debug!("Adapter event watcher stopped");
+        });
+
+        Ok(rx)
+    }
+}
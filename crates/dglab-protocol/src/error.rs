@@ -33,6 +33,48 @@ pub enum ProtocolError {
     #[error("Timeout error")]
     Timeout,
 
+    /// 操作已取消（如用户按 Ctrl-C）
+    #[error("Operation cancelled")]
+    Cancelled,
+
+    /// 帧长度错误
+    #[error("Bad frame length: expected {expected} bytes, got {actual}")]
+    BadLength {
+        /// 期望长度
+        expected: usize,
+        /// 实际长度
+        actual: usize,
+    },
+
+    /// 帧头错误
+    #[error("Bad frame head: expected 0x{expected:02X}, got 0x{actual:02X}")]
+    BadHead {
+        /// 期望帧头
+        expected: u8,
+        /// 实际帧头
+        actual: u8,
+    },
+
+    /// 强度超出范围
+    #[error("Strength {value} out of range on channel {channel} (max {max})")]
+    StrengthOutOfRange {
+        /// 通道编号 (0=A, 1=B)
+        channel: u8,
+        /// 强度值
+        value: u8,
+        /// 允许的最大值
+        max: u8,
+    },
+
+    /// 波形数据无效
+    #[error("Invalid waveform on channel {channel}: {reason}")]
+    InvalidWaveform {
+        /// 通道编号 (0=A, 1=B)
+        channel: u8,
+        /// 原因
+        reason: String,
+    },
+
     /// IO 错误
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
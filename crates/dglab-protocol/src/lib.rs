@@ -7,6 +7,7 @@
 //! - [`v3`] - V3 BLE 协议（推荐使用）
 //! - [`wifi`] - WebSocket 通信协议
 //! - [`ble`] - BLE 设备扫描和连接管理
+//! - [`transport`] - 传输层抽象，统一 BLE、WebSocket 等链路的收发帧接口
 //! - [`packet`] - 旧版数据包格式（已弃用，请使用 [`v3`]）
 
 #![warn(missing_docs)]
@@ -14,6 +15,7 @@
 pub mod ble;
 pub mod error;
 pub mod packet;
+pub mod transport;
 pub mod v3;
 pub mod wifi;
 
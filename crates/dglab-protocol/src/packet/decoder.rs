@@ -1,7 +1,10 @@
 //! 数据包解码器
 
 use crate::error::{ProtocolError, Result};
-use crate::packet::types::{CommandType, DeviceInfo, Packet, WorkMode, PACKET_HEADER, PACKET_TAIL};
+use crate::packet::types::{
+    CommandType, DeviceInfo, Packet, WaveformType, WorkMode, PACKET_HEADER, PACKET_TAIL,
+};
+use crate::packet::v2::V2Wave;
 
 /// 数据包解码器
 pub struct PacketDecoder {
@@ -143,6 +146,33 @@ impl PacketDecoder {
             work_mode,
         })
     }
+
+    /// 解码自定义波形的设置波形命令，返回 (通道, V2 波形参数)
+    ///
+    /// 用 [`V2Wave::to_waveform`] 转换为 V3 波形帧。
+    pub fn decode_wave(packet: &Packet) -> Result<(u8, V2Wave)> {
+        let channel = match packet.command {
+            CommandType::SetWaveA => 0,
+            CommandType::SetWaveB => 1,
+            _ => {
+                return Err(ProtocolError::DecodeError(
+                    "Not a set wave packet".to_string(),
+                ))
+            }
+        };
+
+        match packet.data.split_first() {
+            Some((&kind, params)) if WaveformType::from(kind) == WaveformType::Custom => {
+                let wave = V2Wave::decode(params).ok_or_else(|| {
+                    ProtocolError::DecodeError("Invalid V2 wave parameters".to_string())
+                })?;
+                Ok((channel, wave))
+            }
+            _ => Err(ProtocolError::DecodeError(
+                "Not a custom waveform".to_string(),
+            )),
+        }
+    }
 }
 
 impl Default for PacketDecoder {
@@ -467,4 +497,24 @@ mod tests {
         let result = PacketDecoder::decode_device_info(&packet);
         assert!(result.is_err());
     }
+
+    // === decode_wave 测试 ===
+
+    #[test]
+    fn test_decode_wave_round_trip() {
+        let frame = crate::v3::WaveformData::uniform(100, 100);
+        let bytes = PacketEncoder::encode_set_wave_frame(1, &frame).unwrap();
+        let mut decoder = PacketDecoder::new();
+        decoder.feed(&bytes);
+        let packet = decoder.try_decode().unwrap().unwrap();
+
+        let (channel, wave) = PacketDecoder::decode_wave(&packet).unwrap();
+        assert_eq!(channel, 1);
+        assert_eq!(wave.to_waveform(), frame);
+
+        let preset = Packet::new(CommandType::SetWaveA, vec![WaveformType::Sine.into(), 1]);
+        assert!(PacketDecoder::decode_wave(&preset).is_err());
+        let power = Packet::new(CommandType::SetPowerA, vec![10]);
+        assert!(PacketDecoder::decode_wave(&power).is_err());
+    }
 }
@@ -1,7 +1,9 @@
 //! 数据包编码器
 
 use crate::error::{ProtocolError, Result};
-use crate::packet::types::{CommandType, Packet, PACKET_HEADER, PACKET_TAIL};
+use crate::packet::types::{CommandType, Packet, WaveformType, PACKET_HEADER, PACKET_TAIL};
+use crate::packet::v2::V2Wave;
+use crate::v3::WaveformData;
 
 /// 数据包编码器
 pub struct PacketEncoder;
@@ -51,6 +53,12 @@ impl PacketEncoder {
         Self::encode(&packet)
     }
 
+    /// 编码 V3 波形帧为设置波形命令（自定义波形，载荷为 V2 波形参数）
+    pub fn encode_set_wave_frame(channel: u8, frame: &WaveformData) -> Result<Vec<u8>> {
+        let wave = V2Wave::from_waveform(frame);
+        Self::encode_set_wave(channel, WaveformType::Custom.into(), &wave.encode())
+    }
+
     /// 编码设置模式命令
     pub fn encode_set_mode(mode: u8) -> Result<Vec<u8>> {
         let packet = Packet::new(CommandType::SetMode, vec![mode]);
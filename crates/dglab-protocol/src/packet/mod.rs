@@ -4,12 +4,15 @@
 //! 请使用 [`crate::v3`] 模块中的 [`crate::v3::B0Command`]、[`crate::v3::BFCommand`]、
 //! [`crate::v3::B1Response`] 等类型。
 //!
-//! 本模块保留仅用于向后兼容。
+//! 本模块保留仅用于向后兼容。[`v2`] 子模块在 V3 波形帧与 V2 波形参数之间转换，
+//! 用于 `SetWaveA`/`SetWaveB` 载荷。
 
 pub mod decoder;
 pub mod encoder;
 pub mod types;
+pub mod v2;
 
 pub use decoder::PacketDecoder;
 pub use encoder::PacketEncoder;
 pub use types::*;
+pub use v2::V2Wave;
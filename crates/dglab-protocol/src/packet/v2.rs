@@ -0,0 +1,181 @@
+//! V2 波形转换
+//!
+//! Coyote 2.0 每 100ms 播放一组 `(X, Y, Z)` 波形参数：X 为一组中连续的脉冲数 (0~31)，
+//! Y 为脉冲组之间的间隔 (0~1023 ms)，Z 为脉冲宽度 (0~31，单位 5µs)，脉冲周期为 `X + Y` 毫秒。
+//!
+//! 本模块在 V3 的 [`WaveformData`]（4 组 25ms 的频率/强度）与 V2 参数之间转换，并编码为
+//! 旧版数据包 `SetWaveA`/`SetWaveB` 的载荷，使预设和波形生成器在 V2 设备上输出一致的波形。
+//! V2 一组参数对应 V3 一帧（100ms），4 组的周期和强度取平均，因此转换是有损的。
+
+use serde::{Deserialize, Serialize};
+
+use crate::v3::{compress_frequency, decompress_frequency, WaveformData, MAX_WAVE_INTENSITY};
+
+/// X（脉冲数）最大值
+pub const MAX_PULSE_COUNT: u8 = 31;
+
+/// Y（间隔，毫秒）最大值
+pub const MAX_PULSE_INTERVAL: u16 = 1023;
+
+/// Z（脉冲宽度）最大值
+pub const MAX_PULSE_WIDTH: u8 = 31;
+
+/// 载荷长度（24 位参数）
+pub const V2_WAVE_LENGTH: usize = 3;
+
+/// V2 波形参数（100ms）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct V2Wave {
+    /// 脉冲数 (0~31)，为 0 时不输出
+    pub x: u8,
+    /// 脉冲组间隔 (0~1023 ms)
+    pub y: u16,
+    /// 脉冲宽度 (0~31)
+    pub z: u8,
+}
+
+impl V2Wave {
+    /// 创建波形参数
+    pub fn new(x: u8, y: u16, z: u8) -> Self {
+        Self { x, y, z }
+    }
+
+    /// 参数是否在有效范围内
+    pub fn is_valid(&self) -> bool {
+        self.x <= MAX_PULSE_COUNT && self.y <= MAX_PULSE_INTERVAL && self.z <= MAX_PULSE_WIDTH
+    }
+
+    /// 脉冲周期（毫秒）
+    pub fn period_ms(&self) -> u16 {
+        u16::from(self.x) + self.y
+    }
+
+    /// 按脉冲周期（毫秒）和波形强度 (0~100) 生成参数
+    ///
+    /// 与官方 APP 一致，`X = round(sqrt(周期 / 1000) × 15)`，其余为间隔 Y。
+    pub fn from_period(period_ms: u16, intensity: u8) -> Self {
+        let period = period_ms.clamp(1, MAX_PULSE_INTERVAL + u16::from(MAX_PULSE_COUNT));
+        let x = ((f64::from(period) / 1000.0).sqrt() * 15.0).round() as u16;
+        let x = x.clamp(1, u16::from(MAX_PULSE_COUNT)).min(period);
+        let intensity = u16::from(intensity.min(MAX_WAVE_INTENSITY));
+        let z = (intensity * u16::from(MAX_PULSE_WIDTH) + 50) / 100;
+        Self {
+            x: x as u8,
+            y: (period - x).min(MAX_PULSE_INTERVAL),
+            z: z as u8,
+        }
+    }
+
+    /// 由 V3 波形帧转换，4 组的周期和强度取平均；静默帧转换为不输出的参数
+    pub fn from_waveform(frame: &WaveformData) -> Self {
+        if !frame.is_valid() {
+            return Self::default();
+        }
+        let period = frame
+            .frequency
+            .iter()
+            .map(|&f| u32::from(decompress_frequency(f)))
+            .sum::<u32>()
+            / 4;
+        let intensity = frame.intensity.iter().map(|&i| u32::from(i)).sum::<u32>() / 4;
+        Self::from_period(period as u16, intensity as u8)
+    }
+
+    /// 转换为 V3 波形帧（4 组相同），不输出的参数转换为静默帧
+    pub fn to_waveform(&self) -> WaveformData {
+        if self.x == 0 {
+            return WaveformData::silent();
+        }
+        let period = self.period_ms().clamp(10, 1000);
+        let intensity = (u16::from(self.z.min(MAX_PULSE_WIDTH)) * 100
+            + u16::from(MAX_PULSE_WIDTH) / 2)
+            / u16::from(MAX_PULSE_WIDTH);
+        WaveformData::uniform(compress_frequency(period), intensity as u8)
+    }
+
+    /// 编码为 24 位载荷（低字节在前）：位 19~15 为 Z，位 14~5 为 Y，位 4~0 为 X
+    pub fn encode(&self) -> [u8; V2_WAVE_LENGTH] {
+        let value = (u32::from(self.z.min(MAX_PULSE_WIDTH)) << 15)
+            | (u32::from(self.y.min(MAX_PULSE_INTERVAL)) << 5)
+            | u32::from(self.x.min(MAX_PULSE_COUNT));
+        let bytes = value.to_le_bytes();
+        [bytes[0], bytes[1], bytes[2]]
+    }
+
+    /// 解码 24 位载荷，长度不足或保留位非 0 时返回 `None`
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        let bytes: [u8; V2_WAVE_LENGTH] = data.get(..V2_WAVE_LENGTH)?.try_into().ok()?;
+        let value = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], 0]);
+        if value >> 20 != 0 {
+            return None;
+        }
+        Some(Self {
+            x: (value & 0x1F) as u8,
+            y: ((value >> 5) & 0x3FF) as u16,
+            z: ((value >> 15) & 0x1F) as u8,
+        })
+    }
+}
+
+impl From<WaveformData> for V2Wave {
+    fn from(frame: WaveformData) -> Self {
+        Self::from_waveform(&frame)
+    }
+}
+
+impl From<V2Wave> for WaveformData {
+    fn from(wave: V2Wave) -> Self {
+        wave.to_waveform()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_period() {
+        // 100ms：X = round(sqrt(0.1) × 15) = 5
+        let wave = V2Wave::from_period(100, 100);
+        assert_eq!(wave, V2Wave::new(5, 95, 31));
+        assert_eq!(wave.period_ms(), 100);
+        assert!(wave.is_valid());
+
+        // 10ms 的周期仍保留至少一个脉冲
+        let fast = V2Wave::from_period(10, 0);
+        assert_eq!(fast.period_ms(), 10);
+        assert_eq!(fast.z, 0);
+    }
+
+    #[test]
+    fn test_waveform_round_trip() {
+        let frame = WaveformData::uniform(100, 100);
+        let wave = V2Wave::from(frame);
+        assert_eq!(WaveformData::from(wave), frame);
+
+        // 4 组取平均：周期 (10 + 30) / 2，强度 (40 + 60) / 2
+        let mixed = WaveformData::new([10, 10, 30, 30], [40, 40, 60, 60]);
+        let wave = V2Wave::from(mixed);
+        assert_eq!(wave.period_ms(), 20);
+        let back = wave.to_waveform();
+        assert_eq!(back.frequency, [20; 4]);
+        assert!(back.intensity[0].abs_diff(50) <= 2);
+
+        // 静默帧不输出
+        assert_eq!(V2Wave::from(WaveformData::silent()).x, 0);
+        assert_eq!(V2Wave::default().to_waveform(), WaveformData::silent());
+    }
+
+    #[test]
+    fn test_encode_decode() {
+        let wave = V2Wave::new(5, 95, 20);
+        let bytes = wave.encode();
+        assert_eq!(V2Wave::decode(&bytes), Some(wave));
+
+        let value = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], 0]);
+        assert_eq!(value, (20 << 15) | (95 << 5) | 5);
+
+        assert_eq!(V2Wave::decode(&bytes[..2]), None);
+        assert_eq!(V2Wave::decode(&[0, 0, 0x10]), None);
+    }
+}
@@ -0,0 +1,373 @@
+//! 传输层抽象
+//!
+//! [`Transport`] 统一了收发帧和查询链路状态的接口，上层按帧收发，不关心链路是 BLE GATT
+//! 还是 WebSocket：
+//!
+//! | 实现 | 帧的含义 |
+//! |------|----------|
+//! | [`BleDevice`] | V3 协议的二进制帧（写入 0x150A，通知来自 0x150B） |
+//! | [`WsTransport`] | APP 协议 `msg` 消息的内容（如 `strength-10+0+200+200`），UTF-8 编码 |
+//! | [`MemoryTransport`] | 任意字节，成对使用，用于测试和模拟器 |
+//!
+//! dglab-core 的 V3 设备经 `dyn Transport` 收发帧，新的传输方式（串口调试、模拟器 TCP 等）
+//! 实现该 trait 即可接入。trait 方法返回 [`BoxFuture`]，可以作为 `dyn Transport` 使用。
+
+use std::fmt;
+use std::sync::{Arc, Mutex as StdMutex, MutexGuard};
+
+use futures_util::future::BoxFuture;
+use futures_util::FutureExt;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc, Mutex};
+
+use crate::ble::BleDevice;
+use crate::error::{ProtocolError, Result};
+use crate::wifi::{FeedbackButton, MessageType, WsClient, WsEvent, WsMessage};
+
+/// 链路状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkState {
+    /// 已断开
+    Disconnected,
+    /// 连接中（如 WebSocket 已连接服务器但尚未绑定）
+    Connecting,
+    /// 已连接，可以收发帧
+    Connected,
+}
+
+impl fmt::Display for LinkState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Disconnected => "disconnected",
+            Self::Connecting => "connecting",
+            Self::Connected => "connected",
+        })
+    }
+}
+
+/// 传输层
+pub trait Transport: Send + Sync {
+    /// 传输方式名称（如 `ble`、`ws`），用于日志
+    fn kind(&self) -> &'static str;
+
+    /// 发送一帧
+    fn send_frame<'a>(&'a self, frame: &'a [u8]) -> BoxFuture<'a, Result<()>>;
+
+    /// 等待下一帧，链路关闭时返回错误
+    fn recv_frame(&self) -> BoxFuture<'_, Result<Vec<u8>>>;
+
+    /// 当前链路状态
+    fn link_state(&self) -> BoxFuture<'_, LinkState>;
+
+    /// 关闭链路
+    fn close(&self) -> BoxFuture<'_, Result<()>>;
+}
+
+impl Transport for BleDevice {
+    fn kind(&self) -> &'static str {
+        "ble"
+    }
+
+    fn send_frame<'a>(&'a self, frame: &'a [u8]) -> BoxFuture<'a, Result<()>> {
+        self.send(frame).boxed()
+    }
+
+    fn recv_frame(&self) -> BoxFuture<'_, Result<Vec<u8>>> {
+        self.receive().boxed()
+    }
+
+    fn link_state(&self) -> BoxFuture<'_, LinkState> {
+        async move {
+            match self.is_connected().await {
+                Ok(true) => LinkState::Connected,
+                _ => LinkState::Disconnected,
+            }
+        }
+        .boxed()
+    }
+
+    fn close(&self) -> BoxFuture<'_, Result<()>> {
+        self.disconnect().boxed()
+    }
+}
+
+/// WebSocket 传输
+///
+/// 帧为发给（或来自）已绑定 APP 的 `msg` 消息内容。接收时强度和反馈消息还原为原始文本，
+/// 心跳、绑定、重连等控制事件只影响链路状态，不作为帧返回。
+pub struct WsTransport {
+    /// 发送用的客户端句柄
+    sender: WsClient,
+    /// 接收事件的客户端
+    receiver: Mutex<WsClient>,
+}
+
+impl WsTransport {
+    /// 包装已连接的客户端
+    pub fn new(client: WsClient) -> Self {
+        Self {
+            sender: client.clone(),
+            receiver: Mutex::new(client),
+        }
+    }
+
+    /// 发送用的客户端（查询 clientId、二维码等）
+    pub fn client(&self) -> &WsClient {
+        &self.sender
+    }
+
+    /// 把事件还原为帧，控制事件返回 `None`
+    fn event_frame(event: WsEvent) -> Option<Result<Vec<u8>>> {
+        match event {
+            WsEvent::Strength(data) => Some(Ok(format!(
+                "strength-{}+{}+{}+{}",
+                data.strength_a, data.strength_b, data.max_a, data.max_b
+            )
+            .into_bytes())),
+            WsEvent::Feedback(button) => FeedbackButton::ALL
+                .iter()
+                .position(|b| *b == button)
+                .map(|index| Ok(format!("feedback-{}", index).into_bytes())),
+            WsEvent::Other(msg) if msg.message_type() == MessageType::Msg => {
+                Some(Ok(msg.message.into_bytes()))
+            }
+            WsEvent::Closed => Some(Err(ProtocolError::ConnectionError(
+                "WebSocket connection closed".to_string(),
+            ))),
+            _ => None,
+        }
+    }
+}
+
+impl Transport for WsTransport {
+    fn kind(&self) -> &'static str {
+        "ws"
+    }
+
+    fn send_frame<'a>(&'a self, frame: &'a [u8]) -> BoxFuture<'a, Result<()>> {
+        async move {
+            let message = std::str::from_utf8(frame)
+                .map_err(|e| ProtocolError::EncodeError(format!("Frame is not UTF-8: {}", e)))?;
+            let (Some(client_id), Some(target_id)) =
+                (self.sender.client_id().await, self.sender.target_id().await)
+            else {
+                return Err(ProtocolError::ConnectionError(
+                    "WebSocket client is not bound".to_string(),
+                ));
+            };
+            let msg = WsMessage::new(MessageType::Msg, client_id, target_id, message);
+            self.sender
+                .send(&msg)
+                .await
+                .map_err(|e| ProtocolError::WifiError(e.to_string()))
+        }
+        .boxed()
+    }
+
+    fn recv_frame(&self) -> BoxFuture<'_, Result<Vec<u8>>> {
+        async move {
+            let mut receiver = self.receiver.lock().await;
+            loop {
+                let event = receiver
+                    .recv_event()
+                    .await
+                    .map_err(|e| ProtocolError::WifiError(e.to_string()))?
+                    .ok_or_else(|| {
+                        ProtocolError::ConnectionError("Event channel closed".to_string())
+                    })?;
+                if let Some(frame) = Self::event_frame(event) {
+                    return frame;
+                }
+            }
+        }
+        .boxed()
+    }
+
+    fn link_state(&self) -> BoxFuture<'_, LinkState> {
+        async move {
+            if !self.sender.is_connected().await {
+                LinkState::Disconnected
+            } else if self.sender.is_bound().await {
+                LinkState::Connected
+            } else {
+                LinkState::Connecting
+            }
+        }
+        .boxed()
+    }
+
+    fn close(&self) -> BoxFuture<'_, Result<()>> {
+        async move {
+            self.sender
+                .close()
+                .await
+                .map_err(|e| ProtocolError::WifiError(e.to_string()))
+        }
+        .boxed()
+    }
+}
+
+/// 内存传输
+///
+/// 由 [`MemoryTransport::pair`] 成对创建，一端发送的帧由另一端接收。任一端关闭或被丢弃后，
+/// 两端的链路状态都变为 [`LinkState::Disconnected`]。
+pub struct MemoryTransport {
+    /// 发往对端的通道（关闭后为 `None`）
+    tx: StdMutex<Option<mpsc::UnboundedSender<Vec<u8>>>>,
+    /// 来自对端的通道
+    rx: Mutex<mpsc::UnboundedReceiver<Vec<u8>>>,
+    /// 两端共享的关闭标记
+    closed: Arc<StdMutex<bool>>,
+}
+
+impl MemoryTransport {
+    /// 创建一对相连的传输
+    pub fn pair() -> (Self, Self) {
+        let (a_tx, b_rx) = mpsc::unbounded_channel();
+        let (b_tx, a_rx) = mpsc::unbounded_channel();
+        let closed = Arc::new(StdMutex::new(false));
+        let end = |tx, rx| Self {
+            tx: StdMutex::new(Some(tx)),
+            rx: Mutex::new(rx),
+            closed: closed.clone(),
+        };
+        (end(a_tx, a_rx), end(b_tx, b_rx))
+    }
+
+    fn tx_guard(&self) -> MutexGuard<'_, Option<mpsc::UnboundedSender<Vec<u8>>>> {
+        self.tx
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn closed_guard(&self) -> MutexGuard<'_, bool> {
+        self.closed
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Transport for MemoryTransport {
+    fn kind(&self) -> &'static str {
+        "memory"
+    }
+
+    fn send_frame<'a>(&'a self, frame: &'a [u8]) -> BoxFuture<'a, Result<()>> {
+        let sent = self
+            .tx_guard()
+            .as_ref()
+            .is_some_and(|tx| tx.send(frame.to_vec()).is_ok());
+        let result = if sent {
+            Ok(())
+        } else {
+            Err(ProtocolError::ConnectionError(
+                "Memory transport closed".to_string(),
+            ))
+        };
+        futures_util::future::ready(result).boxed()
+    }
+
+    fn recv_frame(&self) -> BoxFuture<'_, Result<Vec<u8>>> {
+        async move {
+            self.rx.lock().await.recv().await.ok_or_else(|| {
+                ProtocolError::ConnectionError("Memory transport closed".to_string())
+            })
+        }
+        .boxed()
+    }
+
+    fn link_state(&self) -> BoxFuture<'_, LinkState> {
+        let closed = *self.closed_guard()
+            || self
+                .tx_guard()
+                .as_ref()
+                .map_or(true, mpsc::UnboundedSender::is_closed);
+        let state = if closed {
+            LinkState::Disconnected
+        } else {
+            LinkState::Connected
+        };
+        futures_util::future::ready(state).boxed()
+    }
+
+    fn close(&self) -> BoxFuture<'_, Result<()>> {
+        *self.closed_guard() = true;
+        let _ = self.tx_guard().take();
+        futures_util::future::ready(Ok(())).boxed()
+    }
+}
+
+impl Drop for MemoryTransport {
+    fn drop(&mut self) {
+        *self.closed_guard() = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wifi::StrengthData;
+
+    #[tokio::test]
+    async fn test_memory_transport_roundtrip() {
+        let (a, b) = MemoryTransport::pair();
+        let (a, b): (Box<dyn Transport>, Box<dyn Transport>) = (Box::new(a), Box::new(b));
+        assert_eq!(a.kind(), "memory");
+        assert_eq!(a.link_state().await, LinkState::Connected);
+
+        a.send_frame(&[0xb0, 0x00]).await.unwrap();
+        b.send_frame(b"pong").await.unwrap();
+        assert_eq!(b.recv_frame().await.unwrap(), vec![0xb0, 0x00]);
+        assert_eq!(a.recv_frame().await.unwrap(), b"pong".to_vec());
+
+        // 一端关闭后两端都断开，已发出的帧仍可读完
+        b.send_frame(b"last").await.unwrap();
+        b.close().await.unwrap();
+        assert_eq!(a.link_state().await, LinkState::Disconnected);
+        assert_eq!(b.link_state().await, LinkState::Disconnected);
+        assert!(b.send_frame(b"late").await.is_err());
+        assert_eq!(a.recv_frame().await.unwrap(), b"last".to_vec());
+        assert!(a.recv_frame().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_memory_transport_drop_disconnects() {
+        let (a, b) = MemoryTransport::pair();
+        drop(b);
+        assert_eq!(a.link_state().await, LinkState::Disconnected);
+        assert!(a.send_frame(b"x").await.is_err());
+    }
+
+    #[test]
+    fn test_ws_event_frames() {
+        let strength = WsEvent::Strength(StrengthData {
+            strength_a: 10,
+            strength_b: 0,
+            max_a: 200,
+            max_b: 150,
+        });
+        assert_eq!(
+            WsTransport::event_frame(strength).unwrap().unwrap(),
+            b"strength-10+0+200+150".to_vec()
+        );
+        assert_eq!(
+            WsTransport::event_frame(WsEvent::Feedback(FeedbackButton::B1))
+                .unwrap()
+                .unwrap(),
+            b"feedback-6".to_vec()
+        );
+
+        let other = WsMessage::new(MessageType::Msg, "c", "t", "custom-1");
+        assert_eq!(
+            WsTransport::event_frame(WsEvent::Other(other))
+                .unwrap()
+                .unwrap(),
+            b"custom-1".to_vec()
+        );
+
+        // 控制事件不作为帧返回，连接关闭时返回错误
+        assert!(WsTransport::event_frame(WsEvent::Heartbeat).is_none());
+        assert!(WsTransport::event_frame(WsEvent::Closed).unwrap().is_err());
+    }
+}
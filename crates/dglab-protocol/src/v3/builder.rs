@@ -0,0 +1,291 @@
+//! V3 波形帧构建
+//!
+//! 每个 [`WaveformData`] 覆盖 100ms，内含 4 组 25ms 的（频率, 强度）。
+//! [`WaveformFrameBuilder`] 接受按脉冲频率 (Hz) 描述的波形段，完成频率换算与压缩、
+//! 强度钳制和 25ms 槽位切分，直接产出可写入 B0 指令的波形帧序列。
+//!
+//! # 示例
+//!
+//! ```
+//! use dglab_protocol::v3::WaveformFrameBuilder;
+//!
+//! // 100Hz 脉冲，强度从 0 逐步升到 30，每级 25ms
+//! let frames = WaveformFrameBuilder::new()
+//!     .segment(100, 0, 25)
+//!     .segment(100, 10, 25)
+//!     .segment(100, 20, 25)
+//!     .segment(100, 30, 25)
+//!     .build();
+//!
+//! assert_eq!(frames.len(), 1);
+//! assert_eq!(frames[0].to_hex_string(), "0a0a0a0a000a141e");
+//! ```
+
+use super::{pulse_hz_to_value, WaveformData, MAX_WAVE_INTENSITY};
+
+/// 单个槽位时长（毫秒）
+pub const SLOT_MS: u32 = 25;
+
+/// 每帧槽位数
+pub const SLOTS_PER_FRAME: usize = 4;
+
+/// 脉冲频率最小值 (Hz)，对应波形频率 1000ms
+pub const MIN_PULSE_HZ: u16 = 1;
+
+/// 脉冲频率最大值 (Hz)，对应波形频率 10ms
+pub const MAX_PULSE_HZ: u16 = 100;
+
+/// 波形段
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Segment {
+    /// 脉冲频率 (Hz)
+    hz: u16,
+    /// 波形强度 (0~100)
+    intensity: u8,
+    /// 持续时间（毫秒）
+    duration_ms: u32,
+}
+
+/// 波形帧构建器
+///
+/// 按段累计时长，并在 25ms 的整数倍处切分槽位。段边界不在槽位边界上时按四舍五入归属，
+/// 误差不会随段数累积。最后不满 4 个槽位的帧用强度 0 补齐，不会延长输出。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WaveformFrameBuilder {
+    /// 已添加的波形段
+    segments: Vec<Segment>,
+}
+
+impl WaveformFrameBuilder {
+    /// 创建空构建器
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 添加一段波形
+    ///
+    /// `hz` 钳制到 1~100Hz（波形频率 10~1000ms），`intensity` 钳制到 0~100。
+    /// 时长为 0 的段被忽略。
+    pub fn segment(mut self, hz: u16, intensity: u8, duration_ms: u32) -> Self {
+        self.push(hz, intensity, duration_ms);
+        self
+    }
+
+    /// 添加一段波形（非链式）
+    pub fn push(&mut self, hz: u16, intensity: u8, duration_ms: u32) {
+        if duration_ms == 0 {
+            return;
+        }
+        self.segments.push(Segment {
+            hz: hz.clamp(MIN_PULSE_HZ, MAX_PULSE_HZ),
+            intensity: intensity.min(MAX_WAVE_INTENSITY),
+            duration_ms,
+        });
+    }
+
+    /// 总时长（毫秒）
+    pub fn duration_ms(&self) -> u32 {
+        self.segments.iter().map(|s| s.duration_ms).sum()
+    }
+
+    /// 是否没有任何波形段
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// 生成 25ms 槽位序列（压缩后的频率, 强度）
+    pub fn slots(&self) -> Vec<(u8, u8)> {
+        let mut slots = Vec::new();
+        let mut end_ms = 0u32;
+
+        for segment in &self.segments {
+            end_ms = end_ms.saturating_add(segment.duration_ms);
+            let target = ((end_ms + SLOT_MS / 2) / SLOT_MS) as usize;
+            let value = (pulse_hz_to_value(segment.hz), segment.intensity);
+            slots.resize(target.max(slots.len()), value);
+        }
+
+        slots
+    }
+
+    /// 生成波形帧序列
+    pub fn build(&self) -> Vec<WaveformData> {
+        self.slots()
+            .chunks(SLOTS_PER_FRAME)
+            .map(|chunk| {
+                let (last_frequency, _) = chunk[chunk.len() - 1];
+                let mut frequency = [last_frequency; SLOTS_PER_FRAME];
+                let mut intensity = [0; SLOTS_PER_FRAME];
+                for (i, &(f, v)) in chunk.iter().enumerate() {
+                    frequency[i] = f;
+                    intensity[i] = v;
+                }
+                WaveformData::new(frequency, intensity)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(frames: &[WaveformData]) -> Vec<String> {
+        frames.iter().map(|f| f.to_hex_string()).collect()
+    }
+
+    #[test]
+    fn test_empty() {
+        let builder = WaveformFrameBuilder::new();
+        assert!(builder.is_empty());
+        assert!(builder.build().is_empty());
+        assert_eq!(builder.duration_ms(), 0);
+    }
+
+    #[test]
+    fn test_official_ble_example() {
+        // 官方 V3 协议文档：频率 10，强度 0/10/20/30
+        let frames = WaveformFrameBuilder::new()
+            .segment(100, 0, 25)
+            .segment(100, 10, 25)
+            .segment(100, 20, 25)
+            .segment(100, 30, 25)
+            .build();
+        assert_eq!(frames, vec![WaveformData::new([10; 4], [0, 10, 20, 30])]);
+    }
+
+    #[test]
+    fn test_official_breath_example() {
+        // 官方 APP 示例波形"呼吸"
+        let mut builder = WaveformFrameBuilder::new();
+        for intensity in [0, 20, 40, 60, 80] {
+            builder.push(100, intensity, 100);
+        }
+        builder.push(100, 100, 300);
+        builder.push(100, 0, 400);
+
+        assert_eq!(
+            hex(&builder.build()),
+            vec![
+                "0a0a0a0a00000000",
+                "0a0a0a0a14141414",
+                "0a0a0a0a28282828",
+                "0a0a0a0a3c3c3c3c",
+                "0a0a0a0a50505050",
+                "0a0a0a0a64646464",
+                "0a0a0a0a64646464",
+                "0a0a0a0a64646464",
+                "0a0a0a0a00000000",
+                "0a0a0a0a00000000",
+                "0a0a0a0a00000000",
+                "0a0a0a0a00000000",
+            ]
+        );
+        assert_eq!(builder.duration_ms(), 1200);
+    }
+
+    #[test]
+    fn test_frequency_conversion() {
+        let frames = WaveformFrameBuilder::new()
+            .segment(100, 50, 25) // 10ms
+            .segment(10, 50, 25) // 100ms
+            .segment(5, 50, 25) // 200ms -> 120
+            .segment(1, 50, 25) // 1000ms -> 240
+            .build();
+        assert_eq!(frames[0].frequency, [10, 100, 120, 240]);
+        assert!(frames[0].is_valid());
+    }
+
+    #[test]
+    fn test_clamping() {
+        let frames = WaveformFrameBuilder::new()
+            .segment(0, 150, 25)
+            .segment(500, 255, 25)
+            .segment(u16::MAX, 100, 50)
+            .build();
+        assert_eq!(frames[0].frequency, [240, 10, 10, 10]);
+        assert_eq!(frames[0].intensity, [100, 100, 100, 100]);
+        assert!(frames[0].is_valid());
+    }
+
+    #[test]
+    fn test_partial_frame_padded_with_silence() {
+        let frames = WaveformFrameBuilder::new().segment(20, 40, 50).build();
+        assert_eq!(frames, vec![WaveformData::new([50; 4], [40, 40, 0, 0])]);
+        assert!(frames[0].is_valid());
+    }
+
+    #[test]
+    fn test_multi_frame_split() {
+        let frames = WaveformFrameBuilder::new()
+            .segment(100, 10, 75)
+            .segment(50, 20, 100)
+            .build();
+        assert_eq!(
+            frames,
+            vec![
+                WaveformData::new([10, 10, 10, 20], [10, 10, 10, 20]),
+                WaveformData::new([20, 20, 20, 20], [20, 20, 20, 0]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unaligned_durations_do_not_drift() {
+        // 10 段 10ms，共 100ms，恰好 4 个槽位
+        let mut builder = WaveformFrameBuilder::new();
+        for i in 0..10 {
+            builder.push(100, i * 10, 10);
+        }
+        let slots = builder.slots();
+        assert_eq!(slots.len(), 4);
+        // 每个槽位取结束时间四舍五入后首次到达该槽位的段
+        assert_eq!(
+            slots.iter().map(|&(_, v)| v).collect::<Vec<_>>(),
+            vec![10, 30, 60, 80]
+        );
+    }
+
+    #[test]
+    fn test_short_segment_rounding() {
+        // 不足半个槽位的段不占槽位
+        let frames = WaveformFrameBuilder::new()
+            .segment(100, 80, 10)
+            .segment(100, 30, 90)
+            .build();
+        assert_eq!(frames[0].intensity, [30, 30, 30, 30]);
+
+        // 达到半个槽位的段占一个槽位
+        let slots = WaveformFrameBuilder::new().segment(100, 80, 13).slots();
+        assert_eq!(slots, vec![(10, 80)]);
+
+        // 单独的过短段不产生帧
+        assert!(WaveformFrameBuilder::new()
+            .segment(100, 80, 12)
+            .build()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_zero_duration_ignored() {
+        let builder = WaveformFrameBuilder::new()
+            .segment(100, 50, 0)
+            .segment(100, 20, 100);
+        assert_eq!(builder.build(), vec![WaveformData::uniform(10, 20)]);
+    }
+
+    #[test]
+    fn test_all_frames_valid() {
+        let mut builder = WaveformFrameBuilder::new();
+        for hz in [0, 1, 3, 7, 10, 33, 99, 100, 101, 1000] {
+            for intensity in [0, 1, 50, 100, 101, 200] {
+                builder.push(hz, intensity, 37);
+            }
+        }
+        let slots = builder.slots();
+        let frames = builder.build();
+        assert_eq!(slots.len(), (builder.duration_ms() as usize + 12) / 25);
+        assert_eq!(frames.len(), slots.len().div_ceil(SLOTS_PER_FRAME));
+        assert!(frames.iter().all(WaveformData::is_valid));
+    }
+}
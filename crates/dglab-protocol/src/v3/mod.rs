@@ -11,6 +11,9 @@
 //! 设备回应通过 Notify 特征返回：
 //! - **B1 消息**：强度变化反馈
 //!
+//! `decode` 只做尽力解码；需要区分畸形数据时使用 `parse` / [`NotifyMessage::try_parse`]，
+//! 它们通过 [`validate`] 模块校验并返回带类型的错误。
+//!
 //! # 波形频率转换
 //!
 //! 输入值范围 (10 ~ 1000) 通过 [`compress_frequency`] 压缩为发送值 (10 ~ 240)。
@@ -47,8 +50,15 @@
 //! assert_eq!(compress_frequency(800), 220); // 601-1000 压缩
 //! ```
 
+mod builder;
+pub mod validate;
+
 use serde::{Deserialize, Serialize};
 
+use crate::error::{ProtocolError, Result};
+
+pub use builder::WaveformFrameBuilder;
+
 /// B0 指令头部
 pub const B0_HEAD: u8 = 0xB0;
 
@@ -214,23 +224,57 @@ impl WaveformData {
         })
     }
 
+    /// 编码为 16 个小写 HEX 字符（不分配内存）
+    pub fn to_hex(&self) -> [u8; 16] {
+        const DIGITS: &[u8; 16] = b"0123456789abcdef";
+        let mut hex = [0u8; 16];
+        for (i, byte) in self.encode().into_iter().enumerate() {
+            hex[i * 2] = DIGITS[(byte >> 4) as usize];
+            hex[i * 2 + 1] = DIGITS[(byte & 0x0f) as usize];
+        }
+        hex
+    }
+
+    /// 把 16 字符 HEX 追加到 `out`（流式构造波形消息时复用缓冲区）
+    pub fn write_hex(&self, out: &mut String) {
+        let hex = self.to_hex();
+        // HEX 数字都是 ASCII
+        out.push_str(std::str::from_utf8(&hex).unwrap_or_default());
+    }
+
     /// 编码为 16 字符的 HEX 字符串（用于 WebSocket 协议）
     pub fn to_hex_string(&self) -> String {
-        let bytes = self.encode();
-        bytes.iter().map(|b| format!("{b:02x}")).collect()
+        let mut out = String::with_capacity(16);
+        self.write_hex(&mut out);
+        out
     }
 
-    /// 从 16 字符 HEX 字符串解码
-    pub fn from_hex_string(hex: &str) -> Option<Self> {
+    /// 从 16 字符 HEX 解码（大小写均可），直接解码到定长数组，不分配内存
+    pub fn from_hex(hex: &[u8]) -> Option<Self> {
         if hex.len() != 16 {
             return None;
         }
         let mut bytes = [0u8; 8];
-        for (i, byte) in bytes.iter_mut().enumerate() {
-            *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).ok()?;
+        for (byte, pair) in bytes.iter_mut().zip(hex.chunks_exact(2)) {
+            *byte = hex_value(pair[0])? << 4 | hex_value(pair[1])?;
         }
         Self::decode(&bytes)
     }
+
+    /// 从 16 字符 HEX 字符串解码
+    pub fn from_hex_string(hex: &str) -> Option<Self> {
+        Self::from_hex(hex.as_bytes())
+    }
+}
+
+/// 单个 HEX 字符的值
+fn hex_value(c: u8) -> Option<u8> {
+    match c {
+        b'0'..=b'9' => Some(c - b'0'),
+        b'a'..=b'f' => Some(c - b'a' + 10),
+        b'A'..=b'F' => Some(c - b'A' + 10),
+        _ => None,
+    }
 }
 
 impl Default for WaveformData {
@@ -280,6 +324,23 @@ impl B0Command {
         }
     }
 
+    /// 创建一个两通道强度绝对归零、波形静默的 B0 指令
+    ///
+    /// 用于停止输出前的最后一帧，避免设备继续循环上一帧非零波形。
+    pub fn zero() -> Self {
+        Self {
+            sequence: 0,
+            strength_mode: StrengthMode::new(
+                ChannelStrengthMode::Absolute,
+                ChannelStrengthMode::Absolute,
+            ),
+            strength_a: 0,
+            strength_b: 0,
+            waveform_a: WaveformData::silent(),
+            waveform_b: WaveformData::silent(),
+        }
+    }
+
     /// 创建一个仅修改 A 通道强度的 B0 指令（绝对值）
     pub fn set_strength_a(value: u8, sequence: u8) -> Self {
         Self {
@@ -334,6 +395,19 @@ impl B0Command {
         buf
     }
 
+    /// 校验并解码 B0 指令
+    ///
+    /// 要求长度恰好为 20 字节，强度值不超过 200，非静默波形在有效范围内。
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        validate::check_frame(data, B0_HEAD, B0_LENGTH)?;
+        let cmd = Self::decode(data).ok_or(ProtocolError::BadLength {
+            expected: B0_LENGTH,
+            actual: data.len(),
+        })?;
+        validate::check_b0(&cmd, None)?;
+        Ok(cmd)
+    }
+
     /// 从 20 字节解码
     pub fn decode(data: &[u8]) -> Option<Self> {
         if data.len() < B0_LENGTH || data[0] != B0_HEAD {
@@ -414,6 +488,17 @@ impl BFCommand {
         ]
     }
 
+    /// 校验并解码 BF 指令
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        validate::check_frame(data, BF_HEAD, BF_LENGTH)?;
+        let cmd = Self::decode(data).ok_or(ProtocolError::BadLength {
+            expected: BF_LENGTH,
+            actual: data.len(),
+        })?;
+        validate::check_bf(&cmd)?;
+        Ok(cmd)
+    }
+
     /// 从 7 字节解码
     pub fn decode(data: &[u8]) -> Option<Self> {
         if data.len() < BF_LENGTH || data[0] != BF_HEAD {
@@ -452,6 +537,17 @@ pub struct B1Response {
 }
 
 impl B1Response {
+    /// 校验并解码 B1 回应
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        validate::check_frame(data, B1_HEAD, B1_LENGTH)?;
+        let response = Self::decode(data).ok_or(ProtocolError::BadLength {
+            expected: B1_LENGTH,
+            actual: data.len(),
+        })?;
+        validate::check_b1(&response)?;
+        Ok(response)
+    }
+
     /// 从字节数据解码
     pub fn decode(data: &[u8]) -> Option<Self> {
         if data.len() < B1_LENGTH || data[0] != B1_HEAD {
@@ -481,7 +577,23 @@ pub enum NotifyMessage {
 }
 
 impl NotifyMessage {
+    /// 校验并解析 Notify 数据
+    ///
+    /// 空数据和畸形的 B1 消息返回错误，未知帧头返回 [`NotifyMessage::Unknown`]。
+    pub fn try_parse(data: &[u8]) -> Result<Self> {
+        match data.first() {
+            None => Err(crate::ProtocolError::BadLength {
+                expected: B1_LENGTH,
+                actual: 0,
+            }),
+            Some(&B1_HEAD) => B1Response::parse(data).map(Self::Strength),
+            Some(_) => Ok(Self::Unknown(data.to_vec())),
+        }
+    }
+
     /// 从字节数据解析
+    ///
+    /// 畸形数据按 [`NotifyMessage::Unknown`] 处理，需要错误信息时使用 [`try_parse`](Self::try_parse)。
     pub fn parse(data: &[u8]) -> Self {
         if data.is_empty() {
             return Self::Unknown(Vec::new());
@@ -665,6 +777,12 @@ mod tests {
         assert!(WaveformData::from_hex_string("").is_none());
         assert!(WaveformData::from_hex_string("0a141e28000a14").is_none()); // 14 chars
         assert!(WaveformData::from_hex_string("zz141e28000a141e").is_none()); // invalid hex
+                                                                              // 16 字节但含多字节字符
+        assert!(WaveformData::from_hex_string("0éééééééa").is_none());
+        assert_eq!(
+            WaveformData::from_hex(b"0A141E28000A141E"),
+            WaveformData::from_hex_string("0a141e28000a141e")
+        );
     }
 
     // ==================== B0Command 测试 ====================
@@ -700,6 +818,19 @@ mod tests {
         assert_eq!(cmd.strength_b, 0);
     }
 
+    #[test]
+    fn test_b0_zero() {
+        let cmd = B0Command::zero();
+        assert_eq!(cmd.strength_mode.encode(), 0b1111);
+        assert_eq!(
+            cmd.encode(),
+            [
+                0xB0, 0x0F, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x65, 0x00, 0x00,
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x65,
+            ]
+        );
+    }
+
     #[test]
     fn test_b0_set_strength_a() {
         let cmd = B0Command::set_strength_a(150, 3);
@@ -909,6 +1040,57 @@ mod tests {
         assert!(matches!(msg, NotifyMessage::Unknown(_)));
     }
 
+    #[test]
+    fn test_notify_message_try_parse() {
+        assert!(matches!(
+            NotifyMessage::try_parse(&[0xB1, 0x02, 0x0F, 0x1E]),
+            Ok(NotifyMessage::Strength(_))
+        ));
+        assert!(matches!(
+            NotifyMessage::try_parse(&[0xCC, 0x01]),
+            Ok(NotifyMessage::Unknown(_))
+        ));
+        assert!(matches!(
+            NotifyMessage::try_parse(&[]),
+            Err(crate::ProtocolError::BadLength { .. })
+        ));
+        assert!(matches!(
+            NotifyMessage::try_parse(&[0xB1, 0x02, 0x0F]),
+            Err(crate::ProtocolError::BadLength {
+                expected: 4,
+                actual: 3
+            })
+        ));
+        assert!(matches!(
+            NotifyMessage::try_parse(&[0xB1, 0x02, 0xFF, 0x00]),
+            Err(crate::ProtocolError::StrengthOutOfRange { channel: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn test_b0_parse() {
+        let cmd = B0Command::set_strength_a(50, 3);
+        assert_eq!(B0Command::parse(&cmd.encode()).unwrap(), cmd);
+
+        let mut data = cmd.encode();
+        data[2] = 201;
+        assert!(matches!(
+            B0Command::parse(&data),
+            Err(crate::ProtocolError::StrengthOutOfRange { value: 201, .. })
+        ));
+        assert!(matches!(
+            B0Command::parse(&data[..19]),
+            Err(crate::ProtocolError::BadLength { .. })
+        ));
+
+        let bf = BFCommand::default_config();
+        assert_eq!(BFCommand::parse(&bf.encode()).unwrap(), bf);
+        assert!(matches!(
+            BFCommand::parse(&cmd.encode()),
+            Err(crate::ProtocolError::BadHead { .. })
+        ));
+    }
+
     // ==================== 频率转换测试 ====================
 
     #[test]
@@ -0,0 +1,239 @@
+//! V3 帧校验
+//!
+//! 在解码前后检查帧头、长度和取值范围，返回带类型的 [`ProtocolError`]，
+//! 避免畸形数据被静默当作未知消息或无效指令发给设备。
+//!
+//! B0 指令中强度值 > 100 的波形是协议约定的"放弃该通道数据"写法（见
+//! [`WaveformData::silent`]），校验时视为静默；其余超出范围的波形视为错误。
+
+use super::{
+    B0Command, B1Response, BFCommand, ChannelStrengthMode, WaveformData, MAX_STRENGTH,
+    MAX_WAVE_FREQUENCY, MAX_WAVE_INTENSITY, MIN_WAVE_FREQUENCY,
+};
+use crate::error::{ProtocolError, Result};
+
+/// 检查帧头和长度
+pub fn check_frame(data: &[u8], head: u8, length: usize) -> Result<()> {
+    let Some(&actual) = data.first() else {
+        return Err(ProtocolError::BadLength {
+            expected: length,
+            actual: 0,
+        });
+    };
+    if actual != head {
+        return Err(ProtocolError::BadHead {
+            expected: head,
+            actual,
+        });
+    }
+    if data.len() != length {
+        return Err(ProtocolError::BadLength {
+            expected: length,
+            actual: data.len(),
+        });
+    }
+    Ok(())
+}
+
+/// 检查强度不超过上限
+pub fn check_strength(channel: u8, value: u8, max: u8) -> Result<()> {
+    if value > max {
+        return Err(ProtocolError::StrengthOutOfRange {
+            channel,
+            value,
+            max,
+        });
+    }
+    Ok(())
+}
+
+/// 检查波形帧的频率和强度均在有效范围内
+pub fn check_waveform(channel: u8, waveform: &WaveformData) -> Result<()> {
+    if let Some(f) = waveform
+        .frequency
+        .iter()
+        .find(|f| !(MIN_WAVE_FREQUENCY..=MAX_WAVE_FREQUENCY).contains(*f))
+    {
+        return Err(ProtocolError::InvalidWaveform {
+            channel,
+            reason: format!(
+                "frequency {} not in {}~{}",
+                f, MIN_WAVE_FREQUENCY, MAX_WAVE_FREQUENCY
+            ),
+        });
+    }
+    if let Some(i) = waveform.intensity.iter().find(|i| **i > MAX_WAVE_INTENSITY) {
+        return Err(ProtocolError::InvalidWaveform {
+            channel,
+            reason: format!("intensity {} exceeds {}", i, MAX_WAVE_INTENSITY),
+        });
+    }
+    Ok(())
+}
+
+/// 波形帧是否为静默标记（存在强度值 > 100）
+pub fn is_silent(waveform: &WaveformData) -> bool {
+    waveform.intensity.iter().any(|&i| i > MAX_WAVE_INTENSITY)
+}
+
+/// 检查 B0 指令
+///
+/// `limits` 为当前 BF 配置时，绝对强度还需不超过对应通道的软上限。
+pub fn check_b0(cmd: &B0Command, limits: Option<&BFCommand>) -> Result<()> {
+    let channels = [
+        (
+            0,
+            cmd.strength_mode.channel_a,
+            cmd.strength_a,
+            limits.map(|l| l.soft_limit_a),
+            &cmd.waveform_a,
+        ),
+        (
+            1,
+            cmd.strength_mode.channel_b,
+            cmd.strength_b,
+            limits.map(|l| l.soft_limit_b),
+            &cmd.waveform_b,
+        ),
+    ];
+
+    for (channel, mode, value, soft_limit, waveform) in channels {
+        match mode {
+            ChannelStrengthMode::NoChange => {}
+            ChannelStrengthMode::Absolute => check_strength(
+                channel,
+                value,
+                soft_limit.unwrap_or(MAX_STRENGTH).min(MAX_STRENGTH),
+            )?,
+            ChannelStrengthMode::Increase | ChannelStrengthMode::Decrease => {
+                check_strength(channel, value, MAX_STRENGTH)?
+            }
+        }
+
+        if !is_silent(waveform) {
+            check_waveform(channel, waveform)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// 检查 BF 指令的软上限
+pub fn check_bf(cmd: &BFCommand) -> Result<()> {
+    check_strength(0, cmd.soft_limit_a, MAX_STRENGTH)?;
+    check_strength(1, cmd.soft_limit_b, MAX_STRENGTH)
+}
+
+/// 检查 B1 回应中的实际强度
+pub fn check_b1(response: &B1Response) -> Result<()> {
+    check_strength(0, response.strength_a, MAX_STRENGTH)?;
+    check_strength(1, response.strength_b, MAX_STRENGTH)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::v3::{StrengthMode, B0_HEAD, B0_LENGTH, B1_HEAD};
+
+    #[test]
+    fn test_check_frame() {
+        assert!(check_frame(&[B1_HEAD, 0, 0, 0], B1_HEAD, 4).is_ok());
+        assert!(matches!(
+            check_frame(&[], B1_HEAD, 4),
+            Err(ProtocolError::BadLength {
+                expected: 4,
+                actual: 0
+            })
+        ));
+        assert!(matches!(
+            check_frame(&[B0_HEAD, 0, 0, 0], B1_HEAD, 4),
+            Err(ProtocolError::BadHead {
+                expected: B1_HEAD,
+                actual: B0_HEAD
+            })
+        ));
+        assert!(matches!(
+            check_frame(&[B1_HEAD, 0, 0, 0, 0], B1_HEAD, 4),
+            Err(ProtocolError::BadLength { actual: 5, .. })
+        ));
+    }
+
+    #[test]
+    fn test_check_waveform() {
+        assert!(check_waveform(0, &WaveformData::uniform(10, 100)).is_ok());
+        assert!(matches!(
+            check_waveform(1, &WaveformData::uniform(5, 10)),
+            Err(ProtocolError::InvalidWaveform { channel: 1, .. })
+        ));
+        assert!(check_waveform(0, &WaveformData::silent()).is_err());
+        assert!(is_silent(&WaveformData::silent()));
+    }
+
+    #[test]
+    fn test_check_b0() {
+        let mut cmd = B0Command::set_strength_a(150, 1);
+        assert!(check_b0(&cmd, None).is_ok());
+
+        let limits = BFCommand {
+            soft_limit_a: 100,
+            ..BFCommand::default_config()
+        };
+        assert!(matches!(
+            check_b0(&cmd, Some(&limits)),
+            Err(ProtocolError::StrengthOutOfRange {
+                channel: 0,
+                value: 150,
+                max: 100
+            })
+        ));
+
+        // 不改变强度时忽略强度字段
+        cmd.strength_mode = StrengthMode::both_no_change();
+        cmd.strength_a = 255;
+        assert!(check_b0(&cmd, Some(&limits)).is_ok());
+
+        // 频率越界但强度有效的波形是错误，静默标记不是
+        cmd.waveform_b = WaveformData::new([0, 10, 10, 10], [10, 10, 10, 10]);
+        assert!(matches!(
+            check_b0(&cmd, None),
+            Err(ProtocolError::InvalidWaveform { channel: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_check_bf_and_b1() {
+        assert!(check_bf(&BFCommand::default_config()).is_ok());
+        assert!(check_bf(&BFCommand {
+            soft_limit_b: 201,
+            ..BFCommand::default_config()
+        })
+        .is_err());
+
+        assert!(check_b1(&B1Response {
+            sequence: 1,
+            strength_a: 200,
+            strength_b: 0
+        })
+        .is_ok());
+        assert!(check_b1(&B1Response {
+            sequence: 1,
+            strength_a: 0,
+            strength_b: 255
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_rejects_garbage() {
+        // 任意长度、任意内容的输入都不会 panic
+        for len in 0..=B0_LENGTH + 1 {
+            for byte in [0x00, B0_HEAD, 0xBF, B1_HEAD, 0xFF] {
+                let data = vec![byte; len];
+                let _ = B0Command::parse(&data);
+                let _ = BFCommand::parse(&data);
+                let _ = B1Response::parse(&data);
+                let _ = crate::v3::NotifyMessage::try_parse(&data);
+            }
+        }
+    }
+}
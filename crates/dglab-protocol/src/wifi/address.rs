@@ -0,0 +1,275 @@
+//! WebSocket 服务器地址
+
+use std::fmt;
+use std::str::FromStr;
+
+use url::Url;
+
+use super::error::{WsError, WsResult};
+use super::{ClientRole, OFFICIAL_SERVER};
+
+/// TLS 连接选项
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TlsOptions {
+    /// 接受无效证书（自签名证书、主机名不匹配），仅用于局域网自建服务器
+    pub accept_invalid_certs: bool,
+}
+
+/// 已校验的 WebSocket 服务器地址
+///
+/// 支持以下写法：
+/// - `ws://host:port/path`、`wss://host/path`
+/// - `http://` / `https://`，分别转换为 `ws://` / `wss://`
+/// - 不带协议的 `host:port`，按 `ws://` 处理
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerAddress {
+    /// 规范化后的 URL
+    url: Url,
+    /// 去掉末尾 `/` 的地址字符串（用于拼接二维码 URL）
+    address: String,
+    /// TLS 选项
+    tls: TlsOptions,
+    /// 内置服务器授权凭据（握手时作为 `Authorization: Bearer` 请求头发送）
+    auth_token: Option<String>,
+    /// 连接内置服务器时的角色
+    role: ClientRole,
+    /// 观察端只观察的客户端
+    watch: Option<String>,
+}
+
+impl ServerAddress {
+    /// 解析并校验服务器地址
+    pub fn parse(input: &str) -> WsResult<Self> {
+        let input = input.trim();
+        if input.is_empty() {
+            return Err(WsError::InvalidAddress(
+                "Server address is empty".to_string(),
+            ));
+        }
+
+        let with_scheme = match input.split_once("://") {
+            Some((scheme, rest)) => {
+                let scheme = match scheme.to_ascii_lowercase().as_str() {
+                    "ws" | "http" => "ws",
+                    "wss" | "https" => "wss",
+                    other => {
+                        return Err(WsError::InvalidAddress(format!(
+                            "Unsupported scheme '{}', expected ws or wss",
+                            other
+                        )))
+                    }
+                };
+                format!("{}://{}", scheme, rest)
+            }
+            None => format!("ws://{}", input),
+        };
+
+        let url = Url::parse(&with_scheme)
+            .map_err(|e| WsError::InvalidAddress(format!("{}: {}", input, e)))?;
+
+        if url.host_str().map_or(true, str::is_empty) {
+            return Err(WsError::InvalidAddress(format!(
+                "Missing host in '{}'",
+                input
+            )));
+        }
+        if url.query().is_some() || url.fragment().is_some() {
+            return Err(WsError::InvalidAddress(format!(
+                "Query and fragment are not allowed in '{}'",
+                input
+            )));
+        }
+        if !url.username().is_empty() || url.password().is_some() {
+            return Err(WsError::InvalidAddress(format!(
+                "Credentials are not allowed in '{}'",
+                input
+            )));
+        }
+
+        let address = url.as_str().trim_end_matches('/').to_string();
+
+        Ok(Self {
+            url,
+            address,
+            tls: TlsOptions::default(),
+            auth_token: None,
+            role: ClientRole::Participant,
+            watch: None,
+        })
+    }
+
+    /// 官方服务器地址
+    pub fn official() -> Self {
+        Self::parse(OFFICIAL_SERVER).expect("official server address is valid")
+    }
+
+    /// 设置是否接受无效证书（仅对 `wss` 生效）
+    pub fn accept_invalid_certs(mut self, accept: bool) -> Self {
+        self.tls.accept_invalid_certs = accept;
+        self
+    }
+
+    /// 设置连接内置服务器时使用的授权令牌或 PIN
+    pub fn with_auth_token(mut self, token: impl Into<String>) -> Self {
+        self.auth_token = Some(token.into());
+        self
+    }
+
+    /// 授权令牌
+    pub fn auth_token(&self) -> Option<&str> {
+        self.auth_token.as_deref()
+    }
+
+    /// 以只读观察端连接内置服务器，`watch` 为只观察的控制端或 APP 的 clientId
+    pub fn as_viewer(mut self, watch: Option<String>) -> Self {
+        self.role = ClientRole::Viewer;
+        self.watch = watch;
+        self
+    }
+
+    /// 连接时的角色
+    pub fn role(&self) -> ClientRole {
+        self.role
+    }
+
+    /// 握手使用的 URL（观察端带 `role` 和 `watch` 查询参数）
+    pub fn connect_url(&self) -> Url {
+        let mut url = self.url.clone();
+        if self.role == ClientRole::Viewer {
+            let mut query = url.query_pairs_mut();
+            let _ = query.append_pair("role", "viewer");
+            if let Some(watch) = &self.watch {
+                let _ = query.append_pair("watch", watch);
+            }
+        }
+        url
+    }
+
+    /// 获取 URL
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    /// 获取地址字符串（不含末尾 `/`）
+    pub fn as_str(&self) -> &str {
+        &self.address
+    }
+
+    /// 是否使用 TLS
+    pub fn is_secure(&self) -> bool {
+        self.url.scheme() == "wss"
+    }
+
+    /// 获取 TLS 选项
+    pub fn tls(&self) -> TlsOptions {
+        self.tls
+    }
+}
+
+impl Default for ServerAddress {
+    fn default() -> Self {
+        Self::official()
+    }
+}
+
+impl FromStr for ServerAddress {
+    type Err = WsError;
+
+    fn from_str(s: &str) -> WsResult<Self> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for ServerAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.address)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ws_and_wss() {
+        let addr = ServerAddress::parse("ws://192.168.1.10:9999").unwrap();
+        assert_eq!(addr.as_str(), "ws://192.168.1.10:9999");
+        assert!(!addr.is_secure());
+
+        let addr = ServerAddress::parse("wss://ws.dungeon-lab.cn/").unwrap();
+        assert_eq!(addr.as_str(), "wss://ws.dungeon-lab.cn");
+        assert!(addr.is_secure());
+    }
+
+    #[test]
+    fn test_parse_bare_host_port() {
+        let addr = ServerAddress::parse("localhost:9999").unwrap();
+        assert_eq!(addr.as_str(), "ws://localhost:9999");
+        assert_eq!(addr.url().port(), Some(9999));
+
+        let addr = ServerAddress::parse(" 10.0.0.2 ").unwrap();
+        assert_eq!(addr.as_str(), "ws://10.0.0.2");
+    }
+
+    #[test]
+    fn test_parse_http_upgrade() {
+        assert_eq!(
+            ServerAddress::parse("http://lan:8080/ws").unwrap().as_str(),
+            "ws://lan:8080/ws"
+        );
+        assert_eq!(
+            ServerAddress::parse("HTTPS://example.com")
+                .unwrap()
+                .as_str(),
+            "wss://example.com"
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid() {
+        assert!(ServerAddress::parse("").is_err());
+        assert!(ServerAddress::parse("ftp://example.com").is_err());
+        assert!(ServerAddress::parse("ws://").is_err());
+        assert!(ServerAddress::parse("ws://host:99999").is_err());
+        assert!(ServerAddress::parse("ws://host/?a=1").is_err());
+        assert!(ServerAddress::parse("ws://user:pass@host").is_err());
+    }
+
+    #[test]
+    fn test_official_and_tls_options() {
+        let addr = ServerAddress::default();
+        assert_eq!(addr.as_str(), OFFICIAL_SERVER);
+        assert!(!addr.tls().accept_invalid_certs);
+
+        let addr = addr.accept_invalid_certs(true);
+        assert!(addr.tls().accept_invalid_certs);
+
+        assert_eq!(addr.auth_token(), None);
+        let addr = addr.with_auth_token("1234");
+        assert_eq!(addr.auth_token(), Some("1234"));
+        // 凭据不出现在地址字符串（二维码）中
+        assert_eq!(addr.as_str(), OFFICIAL_SERVER);
+    }
+
+    #[test]
+    fn test_viewer_connect_url() {
+        let addr = ServerAddress::parse("ws://lan:9999").unwrap();
+        assert_eq!(addr.role(), ClientRole::Participant);
+        assert_eq!(addr.connect_url().as_str(), "ws://lan:9999/");
+
+        let addr = addr.as_viewer(Some("abc".to_string()));
+        assert_eq!(addr.role(), ClientRole::Viewer);
+        assert_eq!(
+            addr.connect_url().as_str(),
+            "ws://lan:9999/?role=viewer&watch=abc"
+        );
+        // 查询参数不出现在地址字符串（二维码）中
+        assert_eq!(addr.as_str(), "ws://lan:9999");
+    }
+
+    #[test]
+    fn test_from_str_and_display() {
+        let addr: ServerAddress = "127.0.0.1:9999".parse().unwrap();
+        assert_eq!(addr.to_string(), "ws://127.0.0.1:9999");
+    }
+}
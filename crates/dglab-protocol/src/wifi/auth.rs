@@ -0,0 +1,147 @@
+//! 内置服务器的远程控制授权
+//!
+//! 默认任何能访问服务器端口的客户端都可以绑定并转发控制消息。启用授权后，
+//! 客户端在 WebSocket 握手时携带凭据（令牌或 PIN）：
+//!
+//! - 请求头 `Authorization: Bearer <凭据>`（[`WsClient`] 使用
+//!   [`ServerAddress::with_auth_token`] 设置）
+//! - 或连接 URL 的查询参数 `?token=<凭据>` / `?pin=<凭据>`
+//!
+//! 凭据错误的连接在握手阶段被拒绝（HTTP 401）。官方 APP 扫码连接时无法携带凭据，
+//! 因此不带凭据的连接仍可接入，但 `msg` 消息只在至少一方已授权的绑定关系中转发。
+//! APP 只能通过授权控制端生成的二维码得知其 clientId，未授权的客户端无法控制设备。
+//!
+//! [`WsClient`]: super::WsClient
+//! [`ServerAddress::with_auth_token`]: super::ServerAddress::with_auth_token
+
+use std::collections::HashSet;
+
+use tokio_tungstenite::tungstenite::handshake::server::Request;
+
+/// 携带凭据的请求头
+pub const AUTH_HEADER: &str = "Authorization";
+
+/// 服务器授权方式
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ServerAuth {
+    /// 不需要授权（默认，与官方服务器行为一致）
+    #[default]
+    Open,
+    /// 每个控制端使用各自的令牌
+    Tokens(HashSet<String>),
+    /// 所有控制端共用一个 PIN
+    Pin(String),
+}
+
+/// 握手时的凭据校验结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthStatus {
+    /// 凭据有效（或服务器不需要授权）
+    Authorized,
+    /// 未携带凭据，可以连接但不能转发控制消息
+    Anonymous,
+    /// 凭据无效，拒绝连接
+    Denied,
+}
+
+impl ServerAuth {
+    /// 使用令牌列表授权
+    pub fn tokens<I, S>(tokens: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::Tokens(tokens.into_iter().map(Into::into).collect())
+    }
+
+    /// 使用 PIN 授权
+    pub fn pin(pin: impl Into<String>) -> Self {
+        Self::Pin(pin.into())
+    }
+
+    /// 是否不需要授权
+    pub fn is_open(&self) -> bool {
+        matches!(self, Self::Open)
+    }
+
+    /// 校验凭据
+    pub fn check(&self, credential: Option<&str>) -> AuthStatus {
+        let valid = match (self, credential) {
+            (Self::Open, _) => return AuthStatus::Authorized,
+            (_, None) => return AuthStatus::Anonymous,
+            (Self::Tokens(tokens), Some(credential)) => {
+                tokens.iter().any(|token| secure_eq(token, credential))
+            }
+            (Self::Pin(pin), Some(credential)) => secure_eq(pin, credential),
+        };
+        if valid {
+            AuthStatus::Authorized
+        } else {
+            AuthStatus::Denied
+        }
+    }
+
+    /// 从握手请求中提取凭据（请求头优先于查询参数）
+    pub(super) fn credential(request: &Request) -> Option<String> {
+        let header = request
+            .headers()
+            .get(AUTH_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(|value| value.trim().to_string());
+
+        header.or_else(|| {
+            request.uri().query().and_then(|query| {
+                query
+                    .split('&')
+                    .filter_map(|pair| pair.split_once('='))
+                    .find(|(key, _)| *key == "token" || *key == "pin")
+                    .map(|(_, value)| value.to_string())
+            })
+        })
+    }
+}
+
+/// 与内容无关的耗时比较，避免通过响应时间猜测凭据
+fn secure_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0u8, |acc, (x, y)| acc | (x ^ y))
+            == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check() {
+        assert_eq!(ServerAuth::Open.check(None), AuthStatus::Authorized);
+
+        let auth = ServerAuth::tokens(["alice", "bob"]);
+        assert_eq!(auth.check(Some("bob")), AuthStatus::Authorized);
+        assert_eq!(auth.check(Some("eve")), AuthStatus::Denied);
+        assert_eq!(auth.check(None), AuthStatus::Anonymous);
+
+        let auth = ServerAuth::pin("1234");
+        assert_eq!(auth.check(Some("1234")), AuthStatus::Authorized);
+        assert_eq!(auth.check(Some("12345")), AuthStatus::Denied);
+    }
+
+    #[test]
+    fn test_credential_from_request() {
+        let request = Request::builder().uri("/?pin=1234").body(()).unwrap();
+        assert_eq!(ServerAuth::credential(&request).as_deref(), Some("1234"));
+
+        let request = Request::builder()
+            .uri("/?token=query")
+            .header(AUTH_HEADER, "Bearer header")
+            .body(())
+            .unwrap();
+        assert_eq!(ServerAuth::credential(&request).as_deref(), Some("header"));
+
+        let request = Request::builder().uri("/").body(()).unwrap();
+        assert_eq!(ServerAuth::credential(&request), None);
+    }
+}
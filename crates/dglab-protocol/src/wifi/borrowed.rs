@@ -0,0 +1,152 @@
+//! 借用解析
+//!
+//! 流式输出时每 100ms 都要收发消息，[`WsMessageRef`] 和 [`PulseMessage`] 直接借用接收到的文本，
+//! 心跳、强度同步和波形帧的解析不再为每条消息分配字符串。
+
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+
+use super::{MessageType, WsMessage};
+use crate::v3::WaveformData;
+
+/// 借用的 WebSocket 消息
+///
+/// 字段不含转义字符时直接借用原始 JSON 文本，否则退回到分配（与 [`WsMessage`] 结果相同）。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WsMessageRef<'a> {
+    /// 指令类型
+    #[serde(rename = "type", borrow)]
+    pub msg_type: Cow<'a, str>,
+    /// 发送方客户端 ID
+    #[serde(rename = "clientId", borrow)]
+    pub client_id: Cow<'a, str>,
+    /// 接收方客户端 ID
+    #[serde(rename = "targetId", borrow)]
+    pub target_id: Cow<'a, str>,
+    /// 消息内容
+    #[serde(borrow)]
+    pub message: Cow<'a, str>,
+}
+
+impl<'a> WsMessageRef<'a> {
+    /// 解析 JSON 文本
+    pub fn parse(text: &'a str) -> serde_json::Result<Self> {
+        serde_json::from_str(text)
+    }
+
+    /// 获取消息类型
+    pub fn message_type(&self) -> MessageType {
+        MessageType::from(self.msg_type.as_ref())
+    }
+
+    /// 转换为拥有所有权的消息
+    pub fn to_owned_message(&self) -> WsMessage {
+        WsMessage {
+            msg_type: self.msg_type.to_string(),
+            client_id: self.client_id.to_string(),
+            target_id: self.target_id.to_string(),
+            message: self.message.to_string(),
+        }
+    }
+}
+
+impl<'a> From<&'a WsMessage> for WsMessageRef<'a> {
+    fn from(msg: &'a WsMessage) -> Self {
+        Self {
+            msg_type: Cow::Borrowed(&msg.msg_type),
+            client_id: Cow::Borrowed(&msg.client_id),
+            target_id: Cow::Borrowed(&msg.target_id),
+            message: Cow::Borrowed(&msg.message),
+        }
+    }
+}
+
+/// 借用的波形消息 `pulse-{通道}:["HEX",...]`
+///
+/// 只做语法拆分，不校验通道标签和波形范围；HEX 条目须为不含转义的 JSON 字符串。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PulseMessage<'a> {
+    /// 通道标签（`A`、`B` 或桥接器的通道编号）
+    pub label: &'a str,
+    /// 方括号内的数组内容
+    body: &'a str,
+}
+
+impl<'a> PulseMessage<'a> {
+    /// 拆分波形消息，格式不符时返回 `None`
+    pub fn parse(message: &'a str) -> Option<Self> {
+        let (label, array) = message.strip_prefix("pulse-")?.split_once(':')?;
+        let body = array.trim().strip_prefix('[')?.strip_suffix(']')?;
+        Some(Self { label, body })
+    }
+
+    /// 依次返回数组条目：`Ok` 为去掉引号的 HEX，`Err` 为不是字符串的原始条目
+    pub fn entries(&self) -> impl Iterator<Item = Result<&'a str, &'a str>> {
+        let body = self.body.trim();
+        body.split(',')
+            .filter(move |_| !body.is_empty())
+            .map(|entry| {
+                let entry = entry.trim();
+                entry
+                    .strip_prefix('"')
+                    .and_then(|s| s.strip_suffix('"'))
+                    .filter(|s| !s.contains('\\'))
+                    .map(str::trim)
+                    .ok_or(entry)
+            })
+    }
+
+    /// 依次解码波形帧，`Err` 为无法解码的原始条目
+    pub fn frames(&self) -> impl Iterator<Item = Result<WaveformData, &'a str>> {
+        self.entries().map(|entry| {
+            let hex = entry?;
+            WaveformData::from_hex_string(hex).ok_or(hex)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_ref_borrows() {
+        let text = r#"{"type":"msg","clientId":"c1","targetId":"t1","message":"strength-1+2+3+4"}"#;
+        let msg = WsMessageRef::parse(text).unwrap();
+        assert!(matches!(msg.message, Cow::Borrowed(_)));
+        assert_eq!(msg.message_type(), MessageType::Msg);
+
+        let owned: WsMessage = serde_json::from_str(text).unwrap();
+        assert_eq!(msg.to_owned_message(), owned);
+        assert_eq!(WsMessageRef::from(&owned), msg);
+
+        // 含转义时退回到分配
+        let escaped = r#"{"type":"msg","clientId":"c1","targetId":"t1","message":"a\"b"}"#;
+        let msg = WsMessageRef::parse(escaped).unwrap();
+        assert_eq!(msg.message, "a\"b");
+        assert!(matches!(msg.message, Cow::Owned(_)));
+    }
+
+    #[test]
+    fn test_pulse_message() {
+        let pulse =
+            PulseMessage::parse(r#"pulse-B:["0a0a0a0a00000000", "0a0a0a0a64646464"]"#).unwrap();
+        assert_eq!(pulse.label, "B");
+        let frames: Vec<_> = pulse.frames().collect();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[1], Ok(WaveformData::uniform(10, 100)));
+
+        assert_eq!(
+            PulseMessage::parse("pulse-A:[]").unwrap().entries().count(),
+            0
+        );
+        let bad = PulseMessage::parse(r#"pulse-A:[1,"zz"]"#).unwrap();
+        assert_eq!(bad.entries().collect::<Vec<_>>(), [Err("1"), Ok("zz")]);
+        assert_eq!(bad.frames().collect::<Vec<_>>(), [Err("1"), Err("zz")]);
+
+        assert!(PulseMessage::parse("pulse-A").is_none());
+        assert!(PulseMessage::parse(r#"pulse-A:"0a""#).is_none());
+        assert!(PulseMessage::parse("strength-1+2+3").is_none());
+    }
+}
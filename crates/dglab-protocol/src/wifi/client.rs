@@ -1,14 +1,203 @@
 //! WebSocket 客户端实现
 
 use futures_util::{SinkExt, StreamExt};
+use std::collections::VecDeque;
 use std::sync::Arc;
-use tokio::sync::{mpsc, Mutex};
-use tokio_tungstenite::{connect_async, tungstenite::protocol::Message as TungsteniteMessage};
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot, Mutex};
+use tokio::time::Instant;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::HeaderValue;
+use tokio_tungstenite::{
+    connect_async_tls_with_config, tungstenite::protocol::Message as TungsteniteMessage, Connector,
+};
 use tracing::{debug, error, info, warn};
-use url::Url;
 
+use super::limit::{PulseBatch, RateLimiter};
 use super::*;
 
+/// 待发送的出站消息
+enum Outgoing {
+    /// 原始 WebSocket 消息
+    Raw(TungsteniteMessage),
+    /// 协议消息（`msg` 类型进入出站队列，其它类型直接发送）
+    Message(WsMessage),
+    /// 波形数据（按合并窗口合并、按长度拆分后发送）
+    Pulse(PulseData),
+}
+
+/// 出站队列中的消息
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Outbound {
+    /// 消息（地址在发送时填写）
+    message: WsMessage,
+    /// 已尝试发送的次数
+    attempts: u32,
+    /// 入队时间
+    queued_at: Instant,
+}
+
+impl Outbound {
+    /// 放弃发送时产生的事件
+    fn failed(self) -> WsEvent {
+        WsEvent::SendFailed {
+            message: self.message.message,
+            attempts: self.attempts,
+        }
+    }
+}
+
+/// 发往 APP 的出站队列
+///
+/// `msg` 类型的消息按顺序排队，发送时按当前 clientId/targetId 填写地址，因此断线重连并
+/// 重新绑定后可以继续发送。发送失败的消息留在队首，重连后优先重试，超过
+/// [`MAX_SEND_ATTEMPTS`] 次、排队超过 [`OUTBOX_TTL`] 或队列溢出时丢弃。
+/// 队列跨连接保留，由连接任务持有。
+#[derive(Debug, Default)]
+struct Outbox {
+    /// 待发送的消息
+    queue: VecDeque<Outbound>,
+    /// 出现过发送失败，队列清空时需要通知
+    degraded: bool,
+}
+
+impl Outbox {
+    /// 追加消息，队列已满时返回被挤出的最早消息
+    fn push(&mut self, message: WsMessage) -> Option<Outbound> {
+        let dropped = if self.queue.len() >= OUTBOX_CAPACITY {
+            self.degraded = true;
+            self.queue.pop_front()
+        } else {
+            None
+        };
+        self.queue.push_back(Outbound {
+            message,
+            attempts: 0,
+            queued_at: Instant::now(),
+        });
+        dropped
+    }
+
+    /// 取出排队超过 `ttl` 的消息
+    fn expire(&mut self, ttl: Duration) -> Vec<Outbound> {
+        let now = Instant::now();
+        let (expired, queue): (Vec<_>, VecDeque<_>) = self
+            .queue
+            .drain(..)
+            .partition(|outbound| now.duration_since(outbound.queued_at) > ttl);
+        self.queue = queue;
+        if !expired.is_empty() {
+            self.degraded = true;
+        }
+        expired
+    }
+
+    /// 取出队首消息并计入一次发送
+    fn pop(&mut self) -> Option<Outbound> {
+        let mut outbound = self.queue.pop_front()?;
+        outbound.attempts += 1;
+        Some(outbound)
+    }
+
+    /// 发送失败，消息放回队首；达到最大次数时返回该消息
+    fn retry(&mut self, outbound: Outbound) -> Option<Outbound> {
+        self.degraded = true;
+        if outbound.attempts >= MAX_SEND_ATTEMPTS {
+            return Some(outbound);
+        }
+        self.queue.push_front(outbound);
+        None
+    }
+
+    /// 发送成功后检查队列是否已恢复（出现过失败且已清空）
+    fn recovered(&mut self) -> bool {
+        let recovered = self.degraded && self.queue.is_empty();
+        if recovered {
+            self.degraded = false;
+        }
+        recovered
+    }
+
+    /// 取出全部消息（连接关闭时）
+    fn drain(&mut self) -> Vec<Outbound> {
+        self.degraded = false;
+        self.queue.drain(..).collect()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+}
+
+/// 等待响应的操作
+///
+/// 由连接任务在收到对应消息时完成：绑定等待 `bind 200`，强度操作等待 APP 回传的强度数据。
+/// 服务器错误消息完成所有等待中的操作；连接断开时直接丢弃，等待方得到 [`WsError::NotConnected`]。
+enum PendingResponse {
+    /// 等待绑定，成功时返回 targetId
+    Bind(oneshot::Sender<Result<String, ErrorCode>>),
+    /// 等待 APP 回传强度
+    Strength(oneshot::Sender<Result<StrengthData, ErrorCode>>),
+}
+
+impl PendingResponse {
+    /// 等待方是否已放弃（超时或取消）
+    fn is_closed(&self) -> bool {
+        match self {
+            Self::Bind(tx) => tx.is_closed(),
+            Self::Strength(tx) => tx.is_closed(),
+        }
+    }
+}
+
+/// 用收到的事件完成匹配的等待操作，未匹配的操作保留
+fn settle_pending(pending: &mut Vec<PendingResponse>, event: &WsEvent) {
+    let bind = match event {
+        WsEvent::Bound(target_id) => Some(Ok(target_id.clone())),
+        WsEvent::Error(code) => Some(Err(*code)),
+        // 官方服务器以 bind 类型下发绑定失败的错误码
+        WsEvent::Other(msg) if msg.is_bind() => match ErrorCode::parse(&msg.message) {
+            ErrorCode::Success | ErrorCode::Unknown(_) => None,
+            code => Some(Err(code)),
+        },
+        _ => None,
+    };
+    let strength = match event {
+        WsEvent::Strength(data) => Some(Ok(*data)),
+        WsEvent::Error(code) => Some(Err(*code)),
+        WsEvent::PeerDisconnected => Some(Err(ErrorCode::PeerDisconnected)),
+        _ => None,
+    };
+    if bind.is_none() && strength.is_none() {
+        return;
+    }
+
+    for response in std::mem::take(pending) {
+        match (response, &bind, &strength) {
+            (PendingResponse::Bind(tx), Some(result), _) => {
+                let _ = tx.send(result.clone());
+            }
+            (PendingResponse::Strength(tx), _, Some(result)) => {
+                let _ = tx.send(*result);
+            }
+            (response, _, _) => pending.push(response),
+        }
+    }
+}
+
+/// 等待操作完成
+async fn wait_response<T>(
+    rx: oneshot::Receiver<Result<T, ErrorCode>>,
+    timeout: Duration,
+) -> WsResult<T> {
+    match tokio::time::timeout(timeout, rx).await {
+        Ok(Ok(Ok(value))) => Ok(value),
+        Ok(Ok(Err(code))) => Err(WsError::Server(code)),
+        Ok(Err(_)) => Err(WsError::NotConnected),
+        Err(_) => Err(WsError::Timeout),
+    }
+}
+
 /// WebSocket 客户端内部状态
 #[derive(Default)]
 struct ClientState {
@@ -18,13 +207,120 @@ struct ClientState {
     target_id: Option<String>,
     /// 是否已连接
     connected: bool,
+    /// 是否已关闭（主动关闭或放弃重连，不再重连）
+    closed: bool,
+    /// 等待响应的操作
+    pending: Vec<PendingResponse>,
+    /// 最近一次发出心跳的时间（收到回复前）
+    heartbeat_sent: Option<Instant>,
+    /// 最近一次心跳往返时间
+    latency: Option<Duration>,
+}
+
+/// WebSocket 流类型
+type WsStream =
+    tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>;
+
+/// 断线重连策略（指数退避）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReconnectPolicy {
+    /// 最大重连次数（`None` 表示不限次数，`Some(0)` 表示不重连）
+    pub max_attempts: Option<u32>,
+    /// 首次重连前的等待时间
+    pub initial_delay: Duration,
+    /// 最长等待时间
+    pub max_delay: Duration,
+    /// 每次失败后等待时间的倍数
+    pub multiplier: f64,
+}
+
+impl ReconnectPolicy {
+    /// 不自动重连
+    pub fn disabled() -> Self {
+        Self {
+            max_attempts: Some(0),
+            ..Default::default()
+        }
+    }
+
+    /// 第 `attempt` 次重连（从 0 开始）前的等待时间，超过最大次数返回 `None`
+    pub fn delay(&self, attempt: u32) -> Option<Duration> {
+        if self.max_attempts.is_some_and(|max| attempt >= max) {
+            return None;
+        }
+
+        let factor = self.multiplier.max(1.0).powi(attempt.min(32) as i32);
+        let delay = self.initial_delay.as_secs_f64() * factor;
+        Some(Duration::from_secs_f64(
+            delay.min(self.max_delay.as_secs_f64()),
+        ))
+    }
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: None,
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(30),
+            multiplier: 2.0,
+        }
+    }
+}
+
+/// 客户端心跳
+///
+/// 连接任务按 `interval` 发送 `heartbeat` 消息，发出后 `timeout` 内未收到服务器的心跳回复时
+/// 产生 [`WsEvent::ServerDelay`]。回复同时用于测量往返时间（[`WsClient::latency`]）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeartbeatConfig {
+    /// 发送间隔（零表示不发送心跳）
+    pub interval: Duration,
+    /// 等待回复的时间
+    pub timeout: Duration,
+}
+
+impl HeartbeatConfig {
+    /// 不发送心跳
+    pub fn disabled() -> Self {
+        Self {
+            interval: Duration::ZERO,
+            ..Default::default()
+        }
+    }
+
+    /// 是否发送心跳
+    pub fn is_enabled(&self) -> bool {
+        !self.interval.is_zero()
+    }
+}
+
+impl Default for HeartbeatConfig {
+    /// 间隔 [`HEARTBEAT_INTERVAL`] 秒，超时 [`HEARTBEAT_TIMEOUT`] 秒
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(HEARTBEAT_INTERVAL),
+            timeout: Duration::from_secs(HEARTBEAT_TIMEOUT),
+        }
+    }
+}
+
+/// 心跳消息，同时记录发送时间用于测量往返时间
+fn heartbeat_message(state: &mut ClientState) -> WsMessage {
+    state.heartbeat_sent = Some(Instant::now());
+    WsMessage::new(
+        MessageType::Heartbeat,
+        state.client_id.clone().unwrap_or_default(),
+        state.target_id.clone().unwrap_or_default(),
+        "200",
+    )
 }
 
 /// 可克隆的 WsClient 句柄
 #[derive(Clone)]
 pub struct WsClientHandle {
     /// 发送消息的通道
-    tx: mpsc::Sender<TungsteniteMessage>,
+    tx: mpsc::Sender<Outgoing>,
     /// 客户端状态
     state: Arc<Mutex<ClientState>>,
     /// 服务器 URL
@@ -95,96 +391,508 @@ impl WsClient {
     /// 连接到指定的 WebSocket 服务器
     ///
     /// # 参数
-    /// - `server_url`: WebSocket 服务器 URL，例如 "wss://ws.dungeon-lab.cn"
+    /// - `server_url`: WebSocket 服务器地址，例如 "wss://ws.dungeon-lab.cn"，
+    ///   格式见 [`ServerAddress`]
     pub async fn connect(server_url: &str) -> WsResult<Self> {
-        let url = Url::parse(server_url)?;
+        Self::connect_to(&ServerAddress::parse(server_url)?).await
+    }
 
-        debug!("Connecting to WebSocket server: {}", url);
+    /// 连接到已校验的服务器地址（使用默认重连策略）
+    pub async fn connect_to(address: &ServerAddress) -> WsResult<Self> {
+        Self::connect_with_policy(address, ReconnectPolicy::default()).await
+    }
 
-        let (ws_stream, response) = connect_async(url).await?;
-        debug!("WebSocket connected: {:?}", response.status());
+    /// 连接到已校验的服务器地址，并指定断线重连策略
+    ///
+    /// 首次连接失败直接返回错误；连接建立后意外断开时按策略自动重连，
+    /// 期间依次产生 [`WsEvent::Reconnecting`] 和 [`WsEvent::Reconnected`] 事件。
+    /// 重连后服务器会分配新的 clientId（产生 [`WsEvent::ClientId`]，二维码随之变化），
+    /// 若断开前已绑定，会尝试以新 clientId 重新绑定原目标。
+    /// 放弃重连或主动关闭时产生 [`WsEvent::Closed`]。
+    pub async fn connect_with_policy(
+        address: &ServerAddress,
+        policy: ReconnectPolicy,
+    ) -> WsResult<Self> {
+        Self::connect_with_options(
+            address,
+            policy,
+            RateLimit::default(),
+            HeartbeatConfig::default(),
+        )
+        .await
+    }
 
-        let (mut write, mut read) = ws_stream.split();
+    /// 连接到已校验的服务器地址，并指定断线重连策略、出站流量限制和心跳
+    ///
+    /// 发送速率超过限制时消息在队列中等待；波形数据按 [`RateLimit::pulse_batch_window`]
+    /// 合并，并拆分为不超过 [`MAX_MESSAGE_LENGTH`] 的消息。心跳不受流量限制。
+    pub async fn connect_with_options(
+        address: &ServerAddress,
+        policy: ReconnectPolicy,
+        limit: RateLimit,
+        heartbeat: HeartbeatConfig,
+    ) -> WsResult<Self> {
+        let ws_stream = Self::open_stream(address).await?;
 
-        let (tx, mut internal_rx) = mpsc::channel(32);
+        let (tx, internal_rx) = mpsc::channel(32);
         let (event_tx, event_rx) = mpsc::channel(32);
 
         let state = Arc::new(Mutex::new(ClientState {
-            client_id: None,
-            target_id: None,
             connected: true,
+            ..Default::default()
         }));
 
-        let state_clone = state.clone();
+        tokio::spawn(Self::run_connection(
+            address.clone(),
+            policy,
+            limit,
+            heartbeat,
+            ws_stream,
+            internal_rx,
+            event_tx,
+            state.clone(),
+        ));
+
+        let handle = WsClientHandle {
+            tx,
+            state,
+            server_url: address.as_str().to_string(),
+        };
+
+        Ok(Self {
+            handle,
+            rx: event_rx,
+        })
+    }
+
+    /// 建立 WebSocket 连接
+    async fn open_stream(address: &ServerAddress) -> WsResult<WsStream> {
+        debug!("Connecting to WebSocket server: {}", address);
+
+        // 局域网自建服务器可能使用自签名证书
+        let connector = if address.is_secure() && address.tls().accept_invalid_certs {
+            warn!("TLS certificate verification disabled for {}", address);
+            let tls = native_tls::TlsConnector::builder()
+                .danger_accept_invalid_certs(true)
+                .danger_accept_invalid_hostnames(true)
+                .build()?;
+            Some(Connector::NativeTls(tls))
+        } else {
+            None
+        };
+
+        let mut request = address.connect_url().as_str().into_client_request()?;
+        if let Some(token) = address.auth_token() {
+            let value = HeaderValue::from_str(&format!("Bearer {}", token))
+                .map_err(|_| WsError::InvalidAddress("Invalid auth token".to_string()))?;
+            let _ = request.headers_mut().insert(AUTH_HEADER, value);
+        }
+
+        let (ws_stream, response) =
+            connect_async_tls_with_config(request, None, false, connector).await?;
+        debug!("WebSocket connected: {:?}", response.status());
+
+        Ok(ws_stream)
+    }
+
+    /// 连接任务：收发消息，断开后按策略重连
+    async fn run_connection(
+        address: ServerAddress,
+        policy: ReconnectPolicy,
+        limit: RateLimit,
+        heartbeat: HeartbeatConfig,
+        mut ws_stream: WsStream,
+        mut internal_rx: mpsc::Receiver<Outgoing>,
+        event_tx: mpsc::Sender<WsEvent>,
+        state: Arc<Mutex<ClientState>>,
+    ) {
+        let mut rebind_target = None;
+        let mut outbox = Outbox::default();
+
+        loop {
+            let closed = Self::pump(
+                ws_stream,
+                &limit,
+                &heartbeat,
+                &mut internal_rx,
+                &mut outbox,
+                &event_tx,
+                &state,
+                rebind_target.take(),
+            )
+            .await;
+
+            // 断开后旧的 clientId 失效，记录绑定目标以便重新绑定
+            let closed = {
+                let mut state = state.lock().await;
+                state.connected = false;
+                state.client_id = None;
+                state.pending.clear();
+                rebind_target = state.target_id.take();
+                state.closed |= closed;
+                state.closed
+            };
+            if closed {
+                break;
+            }
 
-        // 发送任务
-        tokio::spawn(async move {
-            while let Some(msg) = internal_rx.recv().await {
-                if let Err(e) = write.send(msg).await {
-                    error!("Failed to send message: {}", e);
+            match Self::reconnect(
+                &address,
+                &policy,
+                &mut internal_rx,
+                &mut outbox,
+                &event_tx,
+                &state,
+            )
+            .await
+            {
+                Some(stream) => ws_stream = stream,
+                None => {
+                    state.lock().await.closed = true;
                     break;
                 }
             }
-            let _ = write.close().await;
-        });
+        }
 
-        // 接收任务
-        tokio::spawn(async move {
-            while let Some(msg_result) = read.next().await {
-                match msg_result {
-                    Ok(msg) => {
-                        if let TungsteniteMessage::Text(text) = msg {
+        for outbound in outbox.drain() {
+            let _ = event_tx.send(outbound.failed()).await;
+        }
+        let _ = event_tx.send(WsEvent::Closed).await;
+    }
+
+    /// 合并中的波形排入出站队列
+    async fn flush_batch(
+        batch: &mut PulseBatch,
+        outbox: &mut Outbox,
+        event_tx: &mpsc::Sender<WsEvent>,
+    ) {
+        for pulse in batch.drain() {
+            let msg = WsMessage::new(MessageType::Msg, "", "", pulse.to_message());
+            if let Some(dropped) = outbox.push(msg) {
+                warn!("Outbound queue full, dropping oldest message");
+                let _ = event_tx.send(dropped.failed()).await;
+            }
+        }
+    }
+
+    /// 按策略重连，放弃或被主动关闭时返回 `None`
+    ///
+    /// 等待和连接期间继续接收出站消息（见 [`queue_offline`](Self::queue_offline)），
+    /// 发送方不会因通道已满而阻塞。
+    async fn reconnect(
+        address: &ServerAddress,
+        policy: &ReconnectPolicy,
+        internal_rx: &mut mpsc::Receiver<Outgoing>,
+        outbox: &mut Outbox,
+        event_tx: &mpsc::Sender<WsEvent>,
+        state: &Arc<Mutex<ClientState>>,
+    ) -> Option<WsStream> {
+        let mut attempt = 0;
+
+        while let Some(delay) = policy.delay(attempt) {
+            attempt += 1;
+            info!(
+                "WebSocket disconnected, reconnecting in {:?} (attempt {})",
+                delay, attempt
+            );
+            let _ = event_tx
+                .send(WsEvent::Reconnecting { attempt, delay })
+                .await;
+
+            let attempt_stream = async {
+                tokio::time::sleep(delay).await;
+                if state.lock().await.closed {
+                    return None;
+                }
+                Some(Self::open_stream(address).await)
+            };
+            tokio::pin!(attempt_stream);
+            let result = loop {
+                tokio::select! {
+                    result = &mut attempt_stream => break result,
+                    outgoing = internal_rx.recv() => {
+                        // 所有句柄已释放或收到关闭请求
+                        let Some(outgoing) = outgoing else {
+                            return None;
+                        };
+                        if Self::queue_offline(outgoing, outbox, event_tx).await {
+                            return None;
+                        }
+                    }
+                }
+            };
+
+            match result? {
+                Ok(stream) => {
+                    info!("WebSocket reconnected to {}", address);
+                    state.lock().await.connected = true;
+                    let _ = event_tx.send(WsEvent::Reconnected).await;
+                    return Some(stream);
+                }
+                Err(e) => warn!("WebSocket reconnect attempt {} failed: {}", attempt, e),
+            }
+        }
+
+        error!("WebSocket reconnect gave up after {} attempts", attempt);
+        None
+    }
+
+    /// 断线期间收到的出站消息：`msg` 类型和波形进入出站队列，重连并绑定后发送，
+    /// 其它消息对新连接无意义，直接丢弃。返回是否为关闭请求
+    async fn queue_offline(
+        outgoing: Outgoing,
+        outbox: &mut Outbox,
+        event_tx: &mpsc::Sender<WsEvent>,
+    ) -> bool {
+        let messages = match outgoing {
+            Outgoing::Raw(TungsteniteMessage::Close(_)) => return true,
+            Outgoing::Message(msg) if msg.message_type() == MessageType::Msg => vec![msg],
+            Outgoing::Pulse(pulse) => pulse
+                .split()
+                .into_iter()
+                .map(|pulse| WsMessage::new(MessageType::Msg, "", "", pulse.to_message()))
+                .collect(),
+            _ => {
+                debug!("Dropping outbound message while disconnected");
+                return false;
+            }
+        };
+        for msg in messages {
+            if let Some(dropped) = outbox.push(msg) {
+                warn!("Outbound queue full, dropping oldest message");
+                let _ = event_tx.send(dropped.failed()).await;
+            }
+        }
+        false
+    }
+
+    /// 收发消息直到连接断开，返回是否为主动关闭
+    ///
+    /// 出站消息受令牌桶限流：令牌不足时暂停发送（关闭帧除外），入站消息照常处理。
+    /// 出站队列中的消息只在已绑定（clientId 和 targetId 都已知）时发送。
+    /// 心跳按配置的间隔直接发送，每次连接重新计数未回复的心跳。
+    async fn pump(
+        ws_stream: WsStream,
+        limit: &RateLimit,
+        heartbeat: &HeartbeatConfig,
+        internal_rx: &mut mpsc::Receiver<Outgoing>,
+        outbox: &mut Outbox,
+        event_tx: &mpsc::Sender<WsEvent>,
+        state: &Arc<Mutex<ClientState>>,
+        mut rebind_target: Option<String>,
+    ) -> bool {
+        let (mut write, mut read) = ws_stream.split();
+        let mut limiter = RateLimiter::new(limit);
+        let mut batch = PulseBatch::default();
+        // 未启用心跳时计时器不参与 select
+        let period = heartbeat.interval.max(Duration::from_millis(100));
+        let mut ticker = tokio::time::interval_at(Instant::now() + period, period);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let mut reply_deadline: Option<Instant> = None;
+        let mut missed = 0u32;
+
+        loop {
+            let wait = limiter.wait_time();
+            let ready = wait.is_zero();
+            let batch_deadline = batch.deadline();
+            let address = if outbox.is_empty() {
+                None
+            } else {
+                let state = state.lock().await;
+                state.client_id.clone().zip(state.target_id.clone())
+            };
+            let addressable = address.is_some();
+
+            tokio::select! {
+                _ = tokio::time::sleep(wait), if !ready => {}
+                _ = tokio::time::sleep_until(batch_deadline.unwrap_or_else(Instant::now)),
+                    if batch_deadline.is_some() =>
+                {
+                    Self::flush_batch(&mut batch, outbox, event_tx).await;
+                }
+                _ = ticker.tick(), if heartbeat.is_enabled() => {
+                    let msg = heartbeat_message(&mut *state.lock().await);
+                    if let Ok(text) = serde_json::to_string(&msg) {
+                        if let Err(e) = write.send(TungsteniteMessage::Text(text)).await {
+                            error!("Failed to send heartbeat: {}", e);
+                            return false;
+                        }
+                    }
+                    // 上一次心跳仍在等待回复时不推迟截止时间
+                    let _ = reply_deadline
+                        .get_or_insert(Instant::now() + heartbeat.timeout);
+                }
+                _ = tokio::time::sleep_until(reply_deadline.unwrap_or_else(Instant::now)),
+                    if reply_deadline.is_some() =>
+                {
+                    reply_deadline = None;
+                    missed += 1;
+                    warn!(
+                        "No heartbeat reply within {:?} ({} missed)",
+                        heartbeat.timeout, missed
+                    );
+                    let _ = event_tx.send(WsEvent::ServerDelay { missed }).await;
+                }
+                Some((client_id, target_id)) = async { address }, if ready && addressable => {
+                    for expired in outbox.expire(OUTBOX_TTL) {
+                        warn!("Dropping stale outbound message: {}", expired.message.message);
+                        let _ = event_tx.send(expired.failed()).await;
+                    }
+                    let Some(mut outbound) = outbox.pop() else {
+                        continue;
+                    };
+                    outbound.message.client_id = client_id;
+                    outbound.message.target_id = target_id;
+                    let text = match serde_json::to_string(&outbound.message) {
+                        Ok(text) => text,
+                        Err(e) => {
+                            error!("Failed to encode message: {}", e);
+                            continue;
+                        }
+                    };
+
+                    limiter.consume();
+                    if let Err(e) = write.send(TungsteniteMessage::Text(text)).await {
+                        error!(
+                            "Failed to send message (attempt {}): {}",
+                            outbound.attempts, e
+                        );
+                        if let Some(failed) = outbox.retry(outbound) {
+                            let _ = event_tx.send(failed.failed()).await;
+                        }
+                        return false;
+                    }
+                    if outbox.recovered() {
+                        info!("Outbound queue flushed");
+                        let _ = event_tx.send(WsEvent::Flushed).await;
+                    }
+                }
+                outgoing = internal_rx.recv(), if ready => {
+                    // 所有句柄已释放，视为主动关闭
+                    let Some(outgoing) = outgoing else {
+                        let _ = write.close().await;
+                        return true;
+                    };
+
+                    let msg = match outgoing {
+                        Outgoing::Raw(msg) => msg,
+                        Outgoing::Message(msg) if msg.message_type() == MessageType::Msg => {
+                            // 先排入合并中的波形，避免清空等消息越过之前的波形
+                            Self::flush_batch(&mut batch, outbox, event_tx).await;
+                            if let Some(dropped) = outbox.push(msg) {
+                                warn!("Outbound queue full, dropping oldest message");
+                                let _ = event_tx.send(dropped.failed()).await;
+                            }
+                            continue;
+                        }
+                        Outgoing::Message(msg) => match serde_json::to_string(&msg) {
+                            Ok(text) => TungsteniteMessage::Text(text),
+                            Err(e) => {
+                                error!("Failed to encode message: {}", e);
+                                continue;
+                            }
+                        },
+                        Outgoing::Pulse(pulse) => {
+                            batch.push(pulse, limit.pulse_batch_window);
+                            continue;
+                        }
+                    };
+
+                    let is_close = matches!(msg, TungsteniteMessage::Close(_));
+                    if !is_close {
+                        limiter.consume();
+                    }
+                    if let Err(e) = write.send(msg).await {
+                        error!("Failed to send message: {}", e);
+                        return is_close;
+                    }
+                    if is_close {
+                        return true;
+                    }
+                }
+                incoming = read.next() => {
+                    let msg = match incoming {
+                        Some(Ok(msg)) => msg,
+                        Some(Err(e)) => {
+                            error!("WebSocket error: {}", e);
+                            return false;
+                        }
+                        None => {
+                            info!("WebSocket stream ended");
+                            return false;
+                        }
+                    };
+
+                    match msg {
+                        TungsteniteMessage::Text(text) => {
                             debug!("Received message: {}", text);
-                            match serde_json::from_str::<WsMessage>(&text) {
-                                Ok(ws_msg) => {
-                                    let event = WsEvent::from_message(&ws_msg);
-
-                                    // 更新状态
-                                    let mut state = state_clone.lock().await;
-                                    match &event {
-                                        WsEvent::ClientId(id) => {
-                                            state.client_id = Some(id.clone());
-                                        }
-                                        WsEvent::Bound(target_id) => {
-                                            state.target_id = Some(target_id.clone());
+                            // 借用解析，心跳和强度同步不分配字符串
+                            let event = match WsMessageRef::parse(&text) {
+                                Ok(ws_msg) => WsEvent::from_ref(&ws_msg),
+                                Err(e) => {
+                                    warn!("Failed to parse message: {}", e);
+                                    continue;
+                                }
+                            };
+
+                            // 更新状态
+                            {
+                                let mut state = state.lock().await;
+                                match &event {
+                                    WsEvent::ClientId(id) => {
+                                        state.client_id = Some(id.clone());
+                                    }
+                                    WsEvent::Bound(target_id) => {
+                                        state.target_id = Some(target_id.clone());
+                                    }
+                                    WsEvent::Heartbeat => {
+                                        if let Some(sent) = state.heartbeat_sent.take() {
+                                            state.latency = Some(sent.elapsed());
                                         }
-                                        _ => {}
                                     }
+                                    _ => {}
+                                }
+                                settle_pending(&mut state.pending, &event);
+                            }
+                            if matches!(event, WsEvent::Heartbeat) {
+                                reply_deadline = None;
+                                missed = 0;
+                            }
 
-                                    if let Err(e) = event_tx.send(event).await {
-                                        warn!("Failed to send event: {}", e);
+                            // 重连后拿到新 clientId，尝试重新绑定原目标
+                            if let (WsEvent::ClientId(id), Some(target_id)) =
+                                (&event, rebind_target.take())
+                            {
+                                info!("Rebinding to {} as {}", target_id, id);
+                                let bind = WsMessage::new(
+                                    MessageType::Bind,
+                                    id.clone(),
+                                    target_id,
+                                    MessageDataHead::DgLab.as_str(),
+                                );
+                                if let Ok(text) = serde_json::to_string(&bind) {
+                                    if let Err(e) =
+                                        write.send(TungsteniteMessage::Text(text)).await
+                                    {
+                                        error!("Failed to send rebind message: {}", e);
+                                        return false;
                                     }
                                 }
-                                Err(e) => {
-                                    warn!("Failed to parse message: {}", e);
-                                }
                             }
-                        } else if let TungsteniteMessage::Close(_) = msg {
+
+                            if let Err(e) = event_tx.send(event).await {
+                                warn!("Failed to send event: {}", e);
+                            }
+                        }
+                        TungsteniteMessage::Close(_) => {
                             info!("Received close frame");
-                            break;
+                            return false;
                         }
-                    }
-                    Err(e) => {
-                        error!("WebSocket error: {}", e);
-                        break;
+                        _ => {}
                     }
                 }
             }
-
-            let mut state = state_clone.lock().await;
-            state.connected = false;
-        });
-
-        let handle = WsClientHandle {
-            tx,
-            state,
-            server_url: server_url.to_string(),
-        };
-
-        Ok(Self {
-            handle,
-            rx: event_rx,
-        })
+        }
     }
 
     /// 连接到官方 WebSocket 服务器
@@ -233,29 +941,41 @@ impl WsClient {
     pub async fn send_raw(&self, msg: TungsteniteMessage) -> WsResult<()> {
         self.handle
             .tx
-            .send(msg)
+            .send(Outgoing::Raw(msg))
             .await
             .map_err(|e| WsError::Send(e.to_string()))
     }
 
     /// 发送 WsMessage
+    ///
+    /// message 超过 [`MAX_MESSAGE_LENGTH`] 时返回错误，避免被服务器拒绝。
+    /// `msg` 类型的消息进入出站队列按顺序发送，返回 `Ok` 只表示已入队：
+    /// 最终未能送出时产生 [`WsEvent::SendFailed`]。
     pub async fn send(&self, msg: &WsMessage) -> WsResult<()> {
-        let text = serde_json::to_string(msg)?;
-        self.send_raw(TungsteniteMessage::Text(text)).await
+        if msg.message.len() > MAX_MESSAGE_LENGTH {
+            return Err(WsError::Protocol(format!(
+                "Message too long: {} > {}",
+                msg.message.len(),
+                MAX_MESSAGE_LENGTH
+            )));
+        }
+        self.handle
+            .tx
+            .send(Outgoing::Message(msg.clone()))
+            .await
+            .map_err(|e| WsError::Send(e.to_string()))
+    }
+
+    /// 最近一次心跳往返时间（尚未收到心跳回复时为 `None`）
+    pub async fn latency(&self) -> Option<Duration> {
+        self.handle.state.lock().await.latency
     }
 
     /// 发送心跳包
+    ///
+    /// 连接任务已按 [`HeartbeatConfig`] 定时发送心跳，只在需要立即测量往返时间时调用。
     pub async fn send_heartbeat(&self) -> WsResult<()> {
-        let state = self.handle.state.lock().await;
-        let client_id = state.client_id.clone().unwrap_or_default();
-        let target_id = state.target_id.clone().unwrap_or_default();
-
-        let msg = WsMessage::new(
-            MessageType::Heartbeat,
-            client_id,
-            target_id,
-            "200".to_string(),
-        );
+        let msg = heartbeat_message(&mut *self.handle.state.lock().await);
         self.send(&msg).await
     }
 
@@ -304,6 +1024,82 @@ impl WsClient {
         }
     }
 
+    /// 等待 APP 扫码绑定，返回 APP 的 targetId
+    ///
+    /// 已绑定时立即返回。绑定失败时返回 [`WsError::Server`]，超时返回 [`WsError::Timeout`]，
+    /// 连接断开返回 [`WsError::NotConnected`]。与 [`wait_for_bind`](Self::wait_for_bind) 不同，
+    /// 不会消费事件通道中的事件。
+    pub async fn bind(&self, timeout: Duration) -> WsResult<String> {
+        let rx = {
+            let mut state = self.handle.state.lock().await;
+            if let Some(target_id) = &state.target_id {
+                return Ok(target_id.clone());
+            }
+            if state.closed {
+                return Err(WsError::NotConnected);
+            }
+            let (tx, rx) = oneshot::channel();
+            state.pending.retain(|p| !p.is_closed());
+            state.pending.push(PendingResponse::Bind(tx));
+            rx
+        };
+        wait_response(rx, timeout).await
+    }
+
+    /// 以当前 clientId 主动绑定之前记录的 APP targetId，返回绑定的 targetId
+    ///
+    /// 用于免扫码恢复上次的绑定：APP 仍在线且未绑定其他客户端时服务器返回 `bind 200`。
+    /// 已绑定时立即返回当前目标。目标不存在或已被绑定时返回 [`WsError::Server`]，
+    /// 超时返回 [`WsError::Timeout`]。
+    pub async fn rebind(&self, target_id: &str, timeout: Duration) -> WsResult<String> {
+        let (client_id, rx) = {
+            let mut state = self.handle.state.lock().await;
+            if let Some(bound) = &state.target_id {
+                return Ok(bound.clone());
+            }
+            let client_id = state.client_id.clone().ok_or(WsError::NotConnected)?;
+            // 先登记再发送，避免回复先于登记到达
+            let (tx, rx) = oneshot::channel();
+            state.pending.retain(|p| !p.is_closed());
+            state.pending.push(PendingResponse::Bind(tx));
+            (client_id, rx)
+        };
+
+        let msg = WsMessage::new(
+            MessageType::Bind,
+            client_id,
+            target_id,
+            MessageDataHead::DgLab.as_str(),
+        );
+        self.send(&msg).await?;
+        wait_response(rx, timeout).await
+    }
+
+    /// 发送强度操作并等待 APP 回传的强度
+    ///
+    /// 返回发送后收到的第一条强度数据，即 APP 应用操作后的强度（可能被 APP 侧上限截断）。
+    /// 服务器返回错误码或对方断开时返回 [`WsError::Server`]。
+    pub async fn send_strength(
+        &self,
+        op: StrengthOperation,
+        timeout: Duration,
+    ) -> WsResult<StrengthData> {
+        let (client_id, target_id, rx) = {
+            let mut state = self.handle.state.lock().await;
+            let client_id = state.client_id.clone().ok_or(WsError::NotConnected)?;
+            let target_id = state.target_id.clone().ok_or(WsError::NotBound)?;
+            // 先登记再发送，避免回传先于登记到达
+            let (tx, rx) = oneshot::channel();
+            state.pending.retain(|p| !p.is_closed());
+            state.pending.push(PendingResponse::Strength(tx));
+            (client_id, target_id, rx)
+        };
+
+        let msg = WsMessage::new(MessageType::Msg, client_id, target_id, op.to_message());
+        self.send(&msg).await?;
+        wait_response(rx, timeout).await
+    }
+
     /// 发送强度操作
     pub async fn send_strength_operation(&self, op: StrengthOperation) -> WsResult<()> {
         let state = self.handle.state.lock().await;
@@ -316,19 +1112,23 @@ impl WsClient {
     }
 
     /// 发送波形数据
+    ///
+    /// 波形帧在合并窗口内与同一通道的其它波形合并，超长时自动拆分为多条消息。
     pub async fn send_pulse(&self, pulse: PulseData) -> WsResult<()> {
         let state = self.handle.state.lock().await;
-        let client_id = state.client_id.clone().ok_or(WsError::NotConnected)?;
-        let target_id = state.target_id.clone().ok_or(WsError::NotBound)?;
-        drop(state);
-
-        let message = pulse.to_message();
-        if message.len() > 1950 {
-            return Err(WsError::Protocol("Message too long".to_string()));
+        if state.client_id.is_none() {
+            return Err(WsError::NotConnected);
         }
+        if state.target_id.is_none() {
+            return Err(WsError::NotBound);
+        }
+        drop(state);
 
-        let msg = WsMessage::new(MessageType::Msg, client_id, target_id, message);
-        self.send(&msg).await
+        self.handle
+            .tx
+            .send(Outgoing::Pulse(pulse))
+            .await
+            .map_err(|e| WsError::Send(e.to_string()))
     }
 
     /// 发送清空队列操作
@@ -353,47 +1153,15 @@ impl WsClient {
         self.recv().await
     }
 
-    /// 启动自动心跳任务
-    ///
-    /// 每分钟发送一次心跳包。
-    ///
-    /// # 参数
-    /// - `interval_secs`: 心跳间隔（秒），默认 60 秒
-    pub async fn start_heartbeat(&self, interval_secs: Option<u64>) {
-        let interval = std::time::Duration::from_secs(interval_secs.unwrap_or(60));
-        let tx = self.handle.tx.clone();
-        let state = self.handle.state.clone();
-
-        tokio::spawn(async move {
-            let mut interval = tokio::time::interval(interval);
-            loop {
-                interval.tick().await;
-
-                let state_guard = state.lock().await;
-                if !state_guard.connected {
-                    break;
-                }
-
-                let client_id = state_guard.client_id.clone().unwrap_or_default();
-                let target_id = state_guard.target_id.clone().unwrap_or_default();
-                drop(state_guard);
-
-                let ws_msg = WsMessage::new(MessageType::Heartbeat, client_id, target_id, "");
-                if let Ok(text) = serde_json::to_string(&ws_msg) {
-                    if tx.send(TungsteniteMessage::Text(text)).await.is_err() {
-                        break;
-                    }
-                }
-            }
-        });
-    }
-
     /// 关闭连接
     pub async fn close(&self) -> WsResult<()> {
-        self.send_raw(TungsteniteMessage::Close(None)).await?;
-        let mut state = self.handle.state.lock().await;
-        state.connected = false;
-        Ok(())
+        {
+            let mut state = self.handle.state.lock().await;
+            state.connected = false;
+            state.closed = true;
+            state.pending.clear();
+        }
+        self.send_raw(TungsteniteMessage::Close(None)).await
     }
 }
 
@@ -401,11 +1169,203 @@ impl WsClient {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_heartbeat_config() {
+        let heartbeat = HeartbeatConfig::default();
+        assert_eq!(heartbeat.interval, Duration::from_secs(HEARTBEAT_INTERVAL));
+        assert_eq!(heartbeat.timeout, Duration::from_secs(HEARTBEAT_TIMEOUT));
+        assert!(heartbeat.is_enabled());
+        assert!(!HeartbeatConfig::disabled().is_enabled());
+
+        let mut state = ClientState {
+            client_id: Some("a".to_string()),
+            ..Default::default()
+        };
+        let msg = heartbeat_message(&mut state);
+        assert!(msg.is_heartbeat());
+        assert_eq!(msg.client_id, "a");
+        assert_eq!(msg.target_id, "");
+        assert!(state.heartbeat_sent.is_some());
+    }
+
     #[test]
     fn test_client_state_default() {
         let state = ClientState::default();
         assert!(state.client_id.is_none());
         assert!(state.target_id.is_none());
         assert!(!state.connected);
+        assert!(!state.closed);
+        assert!(state.pending.is_empty());
+        assert!(state.latency.is_none());
+    }
+
+    fn strength(a: u8) -> StrengthData {
+        StrengthData {
+            strength_a: a,
+            strength_b: 0,
+            max_a: 200,
+            max_b: 200,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_settle_pending_by_kind() {
+        let (bind_tx, bind_rx) = oneshot::channel();
+        let (strength_tx, strength_rx) = oneshot::channel();
+        let mut pending = vec![
+            PendingResponse::Bind(bind_tx),
+            PendingResponse::Strength(strength_tx),
+        ];
+
+        // 无关事件不影响等待中的操作
+        settle_pending(&mut pending, &WsEvent::Heartbeat);
+        assert_eq!(pending.len(), 2);
+
+        settle_pending(&mut pending, &WsEvent::Strength(strength(10)));
+        assert_eq!(pending.len(), 1);
+        assert_eq!(strength_rx.await.unwrap(), Ok(strength(10)));
+
+        settle_pending(&mut pending, &WsEvent::Bound("app".to_string()));
+        assert!(pending.is_empty());
+        assert_eq!(bind_rx.await.unwrap(), Ok("app".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_settle_pending_errors() {
+        let (bind_tx, bind_rx) = oneshot::channel();
+        let (strength_tx, strength_rx) = oneshot::channel();
+        let mut pending = vec![
+            PendingResponse::Bind(bind_tx),
+            PendingResponse::Strength(strength_tx),
+        ];
+
+        // bind 类型的错误码只影响绑定
+        let bind_error = WsMessage::new(MessageType::Bind, "a", "b", "401");
+        settle_pending(&mut pending, &WsEvent::Other(bind_error));
+        assert_eq!(bind_rx.await.unwrap(), Err(ErrorCode::TargetNotFound));
+        assert_eq!(pending.len(), 1);
+
+        settle_pending(&mut pending, &WsEvent::PeerDisconnected);
+        assert_eq!(strength_rx.await.unwrap(), Err(ErrorCode::PeerDisconnected));
+
+        let (tx, rx) = oneshot::channel();
+        let mut pending = vec![PendingResponse::Strength(tx)];
+        settle_pending(&mut pending, &WsEvent::Error(ErrorCode::NotBound));
+        assert!(pending.is_empty());
+        assert!(matches!(
+            wait_response(rx, Duration::from_secs(1)).await,
+            Err(WsError::Server(ErrorCode::NotBound))
+        ));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_wait_response_timeout_and_drop() {
+        let (_tx, rx) = oneshot::channel::<Result<String, ErrorCode>>();
+        assert!(matches!(
+            wait_response(rx, Duration::from_secs(1)).await,
+            Err(WsError::Timeout)
+        ));
+
+        let (tx, rx) = oneshot::channel::<Result<String, ErrorCode>>();
+        drop(tx);
+        assert!(matches!(
+            wait_response(rx, Duration::from_secs(1)).await,
+            Err(WsError::NotConnected)
+        ));
+    }
+
+    fn message(text: &str) -> WsMessage {
+        WsMessage::new(MessageType::Msg, "", "", text)
+    }
+
+    #[test]
+    fn test_outbox_retry_and_recovery() {
+        let mut outbox = Outbox::default();
+        assert!(outbox.push(message("strength-1+2+10")).is_none());
+        assert!(outbox.push(message("strength-1+2+20")).is_none());
+
+        // 发送失败的消息回到队首，顺序不变
+        let first = outbox.pop().unwrap();
+        assert_eq!(first.attempts, 1);
+        assert!(outbox.retry(first).is_none());
+        let first = outbox.pop().unwrap();
+        assert_eq!(first.message.message, "strength-1+2+10");
+        assert_eq!(first.attempts, 2);
+        assert!(!outbox.recovered());
+
+        let second = outbox.pop().unwrap();
+        assert_eq!(second.message.message, "strength-1+2+20");
+        assert!(outbox.recovered());
+        // 只通知一次
+        assert!(!outbox.recovered());
+    }
+
+    #[test]
+    fn test_outbox_gives_up() {
+        let mut outbox = Outbox::default();
+        let _ = outbox.push(message("clear-1"));
+        for _ in 1..MAX_SEND_ATTEMPTS {
+            let outbound = outbox.pop().unwrap();
+            assert!(outbox.retry(outbound).is_none());
+        }
+        let outbound = outbox.pop().unwrap();
+        let failed = outbox.retry(outbound).unwrap().failed();
+        assert!(matches!(
+            failed,
+            WsEvent::SendFailed { ref message, attempts }
+                if message == "clear-1" && attempts == MAX_SEND_ATTEMPTS
+        ));
+        assert!(outbox.is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_outbox_expires_stale_messages() {
+        let mut outbox = Outbox::default();
+        let _ = outbox.push(message("strength-1+2+10"));
+        tokio::time::advance(OUTBOX_TTL).await;
+        let _ = outbox.push(message("strength-1+2+20"));
+        assert!(outbox.expire(OUTBOX_TTL).is_empty());
+
+        tokio::time::advance(Duration::from_millis(1)).await;
+        let expired = outbox.expire(OUTBOX_TTL);
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].message.message, "strength-1+2+10");
+        assert_eq!(outbox.pop().unwrap().message.message, "strength-1+2+20");
+        assert!(outbox.recovered());
+    }
+
+    #[test]
+    fn test_outbox_overflow_drops_oldest() {
+        let mut outbox = Outbox::default();
+        for n in 0..OUTBOX_CAPACITY {
+            assert!(outbox.push(message(&n.to_string())).is_none());
+        }
+        let dropped = outbox.push(message("new")).unwrap();
+        assert_eq!(dropped.message.message, "0");
+        assert_eq!(dropped.attempts, 0);
+        assert_eq!(outbox.drain().len(), OUTBOX_CAPACITY);
+    }
+
+    #[test]
+    fn test_reconnect_policy_backoff() {
+        let policy = ReconnectPolicy::default();
+        assert_eq!(policy.delay(0), Some(Duration::from_secs(1)));
+        assert_eq!(policy.delay(1), Some(Duration::from_secs(2)));
+        assert_eq!(policy.delay(3), Some(Duration::from_secs(8)));
+        // 不超过最长等待时间
+        assert_eq!(policy.delay(10), Some(Duration::from_secs(30)));
+        assert_eq!(policy.delay(u32::MAX), Some(Duration::from_secs(30)));
+    }
+
+    #[test]
+    fn test_reconnect_policy_limits() {
+        assert_eq!(ReconnectPolicy::disabled().delay(0), None);
+
+        let policy = ReconnectPolicy {
+            max_attempts: Some(2),
+            ..Default::default()
+        };
+        assert!(policy.delay(1).is_some());
+        assert_eq!(policy.delay(2), None);
     }
 }
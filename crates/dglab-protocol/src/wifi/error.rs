@@ -21,6 +21,18 @@ pub enum WsError {
     #[error("URL error: {0}")]
     Url(#[from] url::ParseError),
 
+    /// 无效的服务器地址
+    #[error("Invalid server address: {0}")]
+    InvalidAddress(String),
+
+    /// TLS 配置错误
+    #[error("TLS error: {0}")]
+    Tls(#[from] native_tls::Error),
+
+    /// 服务器证书或私钥无效
+    #[error("TLS certificate error: {0}")]
+    Certificate(String),
+
     /// WebSocket 协议错误
     #[error("WebSocket error: {0}")]
     WebSocket(#[from] tokio_tungstenite::tungstenite::Error),
@@ -53,6 +65,10 @@ pub enum WsError {
     #[error("Timeout")]
     Timeout,
 
+    /// 服务器或对方返回的错误码
+    #[error("Server error: {}", .0.description())]
+    Server(super::ErrorCode),
+
     /// 无效消息
     #[error("Invalid message: {0}")]
     InvalidMessage(String),
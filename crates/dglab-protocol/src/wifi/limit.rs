@@ -0,0 +1,196 @@
+//! 出站消息限流与波形合并
+//!
+//! 官方服务器拒绝 message 超过 1950 字符的消息，并可能断开发送过于频繁的客户端。
+//! [`WsClient`](super::WsClient) 按 [`RateLimit`] 以令牌桶限制发送速率，
+//! 并把合并窗口内同一通道的波形帧合并为尽量少的消息。
+
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+use super::{Channel, PulseData};
+
+/// 出站流量限制
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    /// 每秒最多发送的消息数（0 表示不限制）
+    pub messages_per_sec: u32,
+    /// 允许连续突发的消息数
+    pub burst: u32,
+    /// 波形帧合并窗口，窗口内同一通道的波形帧合并发送（零表示不合并）
+    pub pulse_batch_window: Duration,
+}
+
+impl RateLimit {
+    /// 不限流、不合并
+    pub fn unlimited() -> Self {
+        Self {
+            messages_per_sec: 0,
+            burst: 0,
+            pulse_batch_window: Duration::ZERO,
+        }
+    }
+}
+
+impl Default for RateLimit {
+    fn default() -> Self {
+        Self {
+            messages_per_sec: 10,
+            burst: 20,
+            pulse_batch_window: Duration::from_millis(500),
+        }
+    }
+}
+
+/// 令牌桶限流器
+#[derive(Debug)]
+pub(super) struct RateLimiter {
+    /// 每秒补充的令牌数（0 表示不限制）
+    rate: f64,
+    /// 桶容量
+    capacity: f64,
+    /// 当前令牌数
+    tokens: f64,
+    /// 上次补充时间
+    last: Instant,
+}
+
+impl RateLimiter {
+    /// 按限制创建限流器（初始为满桶）
+    pub(super) fn new(limit: &RateLimit) -> Self {
+        let capacity = limit.burst.max(1) as f64;
+        Self {
+            rate: limit.messages_per_sec as f64,
+            capacity,
+            tokens: capacity,
+            last: Instant::now(),
+        }
+    }
+
+    /// 距离下一个令牌可用的等待时间（可立即发送时为零）
+    pub(super) fn wait_time(&mut self) -> Duration {
+        if self.rate <= 0.0 {
+            return Duration::ZERO;
+        }
+
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+        self.last = now;
+
+        if self.tokens >= 1.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64((1.0 - self.tokens) / self.rate)
+        }
+    }
+
+    /// 消耗一个令牌
+    pub(super) fn consume(&mut self) {
+        if self.rate > 0.0 {
+            self.tokens -= 1.0;
+        }
+    }
+}
+
+/// 按通道合并待发送的波形帧
+#[derive(Debug, Default)]
+pub(super) struct PulseBatch {
+    /// A、B 通道待发送的波形帧
+    pending: [Vec<String>; 2],
+    /// 合并窗口结束时间
+    deadline: Option<Instant>,
+}
+
+impl PulseBatch {
+    /// 加入波形帧，首次加入时开始计时
+    pub(super) fn push(&mut self, pulse: PulseData, window: Duration) {
+        let index = match pulse.channel {
+            Channel::A => 0,
+            Channel::B => 1,
+        };
+        self.pending[index].extend(pulse.pulses);
+        let _ = self.deadline.get_or_insert_with(|| Instant::now() + window);
+    }
+
+    /// 合并窗口结束时间（无待发送波形时为 `None`）
+    pub(super) fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    /// 取出所有待发送的波形，按消息长度限制拆分
+    pub(super) fn drain(&mut self) -> Vec<PulseData> {
+        self.deadline = None;
+        [Channel::A, Channel::B]
+            .into_iter()
+            .zip(self.pending.iter_mut())
+            .filter(|(_, pulses)| !pulses.is_empty())
+            .flat_map(|(channel, pulses)| PulseData::new(channel, std::mem::take(pulses)).split())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limiter() {
+        let mut limiter = RateLimiter::new(&RateLimit {
+            messages_per_sec: 10,
+            burst: 2,
+            pulse_batch_window: Duration::ZERO,
+        });
+
+        // 突发额度用完后需要等待
+        for _ in 0..2 {
+            assert_eq!(limiter.wait_time(), Duration::ZERO);
+            limiter.consume();
+        }
+        let wait = limiter.wait_time();
+        assert!(wait > Duration::ZERO && wait <= Duration::from_millis(100));
+
+        tokio::time::advance(Duration::from_millis(100)).await;
+        assert_eq!(limiter.wait_time(), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_rate_limiter_unlimited() {
+        let mut limiter = RateLimiter::new(&RateLimit::unlimited());
+        for _ in 0..100 {
+            limiter.consume();
+        }
+        assert_eq!(limiter.wait_time(), Duration::ZERO);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_pulse_batch() {
+        let mut batch = PulseBatch::default();
+        assert!(batch.deadline().is_none());
+
+        let frame = "0a0a0a0a64646464".to_string();
+        batch.push(
+            PulseData::new(Channel::A, vec![frame.clone()]),
+            Duration::from_millis(500),
+        );
+        let deadline = batch.deadline().unwrap();
+        batch.push(
+            PulseData::new(Channel::A, vec![frame.clone(); 2]),
+            Duration::from_millis(500),
+        );
+        batch.push(
+            PulseData::new(Channel::B, vec![frame.clone()]),
+            Duration::from_millis(500),
+        );
+        // 后续加入不延长窗口
+        assert_eq!(batch.deadline(), Some(deadline));
+
+        let pulses = batch.drain();
+        assert_eq!(pulses.len(), 2);
+        assert_eq!(pulses[0].channel, Channel::A);
+        assert_eq!(pulses[0].pulses.len(), 3);
+        assert_eq!(pulses[1].channel, Channel::B);
+        assert!(batch.deadline().is_none());
+        assert!(batch.drain().is_empty());
+    }
+}
@@ -29,13 +29,27 @@
 
 use serde::{Deserialize, Serialize};
 
-pub use client::WsClient;
+pub use address::{ServerAddress, TlsOptions};
+pub use auth::{AuthStatus, ServerAuth, AUTH_HEADER};
+pub use borrowed::{PulseMessage, WsMessageRef};
+pub use client::{HeartbeatConfig, ReconnectPolicy, WsClient};
 pub use error::{WsError, WsResult};
-pub use server::{ServerEvent, WsServer};
-
+pub use limit::RateLimit;
+pub use probe::{ProbeReport, DEFAULT_PROBE_TIMEOUT};
+pub use server::{ClientRole, ServerEvent, WsServer};
+pub use tls::ServerTls;
+pub use validate::{MessageError, MessageValidation};
+
+mod address;
+mod auth;
+mod borrowed;
 mod client;
 mod error;
+mod limit;
+mod probe;
 mod server;
+mod tls;
+pub mod validate;
 
 /// 官方 WebSocket 服务器地址
 pub const OFFICIAL_SERVER: &str = "wss://ws.dungeon-lab.cn";
@@ -46,6 +60,22 @@ pub const HEARTBEAT_INTERVAL: u64 = 20;
 /// 心跳超时（秒）- 根据 hyperzlib 项目实现
 pub const HEARTBEAT_TIMEOUT: u64 = 20;
 
+/// 服务器允许的 message 最大长度（字符）
+pub const MAX_MESSAGE_LENGTH: usize = 1950;
+
+/// 单条波形消息最多包含的波形帧数（10 秒）
+pub const MAX_PULSES_PER_MESSAGE: usize = 100;
+
+/// 发往 APP 的消息最多发送次数（含首次），超过后丢弃并产生 [`WsEvent::SendFailed`]
+pub const MAX_SEND_ATTEMPTS: u32 = 3;
+
+/// 出站队列容量（条），溢出时丢弃最早的消息
+pub const OUTBOX_CAPACITY: usize = 64;
+
+/// 出站队列中消息的有效期，超过后丢弃并产生 [`WsEvent::SendFailed`]，
+/// 避免重连后补发过时的强度和波形
+pub const OUTBOX_TTL: std::time::Duration = std::time::Duration::from_secs(2);
+
 /// 返回码 (RetCode) - 根据 hyperzlib 项目实现
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum RetCode {
@@ -233,7 +263,7 @@ impl From<MessageDataHead> for String {
 }
 
 /// WebSocket 消息
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct WsMessage {
     /// 指令类型
     #[serde(rename = "type")]
@@ -311,21 +341,16 @@ pub struct StrengthData {
 impl StrengthData {
     /// 从消息字符串解析
     pub fn parse(message: &str) -> Option<Self> {
-        if !message.starts_with("strength-") {
-            return None;
-        }
-
-        let parts: Vec<&str> = message.trim_start_matches("strength-").split('+').collect();
-        if parts.len() != 4 {
-            return None;
-        }
-
-        Some(Self {
-            strength_a: parts[0].parse().ok()?,
-            strength_b: parts[1].parse().ok()?,
-            max_a: parts[2].parse().ok()?,
-            max_b: parts[3].parse().ok()?,
-        })
+        let mut parts = message.strip_prefix("strength-")?.split('+');
+        let mut next = || parts.next()?.parse().ok();
+        let data = Self {
+            strength_a: next()?,
+            strength_b: next()?,
+            max_a: next()?,
+            max_b: next()?,
+        };
+        // 多余的字段视为格式错误
+        parts.next().is_none().then_some(data)
     }
 }
 
@@ -444,9 +469,83 @@ impl PulseData {
             Channel::A => "A",
             Channel::B => "B",
         };
-        let quoted_pulses: Vec<String> = self.pulses.iter().map(|p| format!("\"{p}\"")).collect();
-        let pulses = quoted_pulses.join(",");
-        format!("pulse-{channel}:[{pulses}]")
+        let length = self.pulses.iter().map(|p| p.len() + 3).sum::<usize>();
+        let mut message = String::with_capacity(length + 10);
+        message.push_str("pulse-");
+        message.push_str(channel);
+        message.push_str(":[");
+        for (i, pulse) in self.pulses.iter().enumerate() {
+            if i > 0 {
+                message.push(',');
+            }
+            message.push('"');
+            message.push_str(pulse);
+            message.push('"');
+        }
+        message.push(']');
+        message
+    }
+
+    /// 直接从波形帧生成消息字符串，不为每帧创建 HEX 字符串
+    ///
+    /// 结果与先转换为 HEX 再调用 [`PulseData::to_message`] 相同，不做拆分。
+    pub fn frames_message(channel: Channel, frames: &[crate::v3::WaveformData]) -> String {
+        let channel = match channel {
+            Channel::A => "A",
+            Channel::B => "B",
+        };
+        Self::labeled_frames_message(channel, frames)
+    }
+
+    /// 按通道标签（`A`、`B` 或桥接器的通道编号）生成波形消息字符串
+    pub(crate) fn labeled_frames_message(
+        channel: &str,
+        frames: &[crate::v3::WaveformData],
+    ) -> String {
+        let mut message = String::with_capacity(frames.len() * 19 + 10);
+        message.push_str("pulse-");
+        message.push_str(channel);
+        message.push_str(":[");
+        for (i, frame) in frames.iter().enumerate() {
+            if i > 0 {
+                message.push(',');
+            }
+            message.push('"');
+            frame.write_hex(&mut message);
+            message.push('"');
+        }
+        message.push(']');
+        message
+    }
+
+    /// 按帧数和消息长度限制拆分为多条波形数据
+    ///
+    /// 每条不超过 [`MAX_PULSES_PER_MESSAGE`] 帧，且消息长度不超过 [`MAX_MESSAGE_LENGTH`]。
+    pub fn split(&self) -> Vec<Self> {
+        // `pulse-A:[` + `]`
+        const OVERHEAD: usize = 10;
+
+        let mut chunks = Vec::new();
+        let mut current = Vec::new();
+        let mut length = OVERHEAD;
+
+        for pulse in &self.pulses {
+            // 引号和分隔逗号
+            let cost = pulse.len() + 3;
+            if !current.is_empty()
+                && (current.len() >= MAX_PULSES_PER_MESSAGE || length + cost > MAX_MESSAGE_LENGTH)
+            {
+                chunks.push(Self::new(self.channel, std::mem::take(&mut current)));
+                length = OVERHEAD;
+            }
+            current.push(pulse.clone());
+            length += cost;
+        }
+        if !current.is_empty() {
+            chunks.push(Self::new(self.channel, current));
+        }
+
+        chunks
     }
 }
 
@@ -474,7 +573,8 @@ impl ClearOperation {
 }
 
 /// APP 反馈按钮
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
 pub enum FeedbackButton {
     /// A 通道按钮 0
     A0,
@@ -499,6 +599,28 @@ pub enum FeedbackButton {
 }
 
 impl FeedbackButton {
+    /// 全部按钮
+    pub const ALL: [Self; 10] = [
+        Self::A0,
+        Self::A1,
+        Self::A2,
+        Self::A3,
+        Self::A4,
+        Self::B0,
+        Self::B1,
+        Self::B2,
+        Self::B3,
+        Self::B4,
+    ];
+
+    /// 按钮所属通道
+    pub fn channel(&self) -> Channel {
+        match self {
+            Self::A0 | Self::A1 | Self::A2 | Self::A3 | Self::A4 => Channel::A,
+            Self::B0 | Self::B1 | Self::B2 | Self::B3 | Self::B4 => Channel::B,
+        }
+    }
+
     /// 从索引解析
     pub fn from_index(index: u8) -> Option<Self> {
         match index {
@@ -618,8 +740,31 @@ pub enum WsEvent {
     Error(ErrorCode),
     /// 绑定超时
     BindTimeout,
-    /// 连接关闭
+    /// 连接关闭（主动关闭或放弃重连）
     Closed,
+    /// 连接意外断开，等待后重连
+    Reconnecting {
+        /// 第几次重连（从 1 开始）
+        attempt: u32,
+        /// 本次重连前的等待时间
+        delay: std::time::Duration,
+    },
+    /// 已重新连接（随后会收到新的 clientId）
+    Reconnected,
+    /// 发往 APP 的消息未能送出，已丢弃（多次发送失败、队列溢出或连接关闭）
+    SendFailed {
+        /// 消息内容，如 `strength-1+2+30`
+        message: String,
+        /// 已尝试发送的次数
+        attempts: u32,
+    },
+    /// 出现发送失败后，出站队列中的消息已全部送出
+    Flushed,
+    /// 心跳超时未收到服务器回复（服务器或网络延迟），连接可能即将断开
+    ServerDelay {
+        /// 连续未回复的心跳次数（收到回复后重新计数）
+        missed: u32,
+    },
     /// 其他消息
     Other(WsMessage),
 }
@@ -627,15 +772,20 @@ pub enum WsEvent {
 impl WsEvent {
     /// 从 WsMessage 解析事件
     pub fn from_message(msg: &WsMessage) -> Self {
+        Self::from_ref(&WsMessageRef::from(msg))
+    }
+
+    /// 从借用的消息解析事件，只有需要保留的字段（ID、未识别的消息）才会分配
+    pub fn from_ref(msg: &WsMessageRef<'_>) -> Self {
         match msg.message_type() {
             MessageType::Heartbeat => Self::Heartbeat,
             MessageType::Bind => {
                 if msg.message == "200" {
-                    Self::Bound(msg.target_id.clone())
+                    Self::Bound(msg.target_id.to_string())
                 } else if msg.message == "targetId" {
-                    Self::ClientId(msg.client_id.clone())
+                    Self::ClientId(msg.client_id.to_string())
                 } else {
-                    Self::Other(msg.clone())
+                    Self::Other(msg.to_owned_message())
                 }
             }
             MessageType::Msg => {
@@ -644,12 +794,12 @@ impl WsEvent {
                 } else if let Some(button) = FeedbackButton::parse(&msg.message) {
                     Self::Feedback(button)
                 } else {
-                    Self::Other(msg.clone())
+                    Self::Other(msg.to_owned_message())
                 }
             }
             MessageType::Break => Self::PeerDisconnected,
             MessageType::Error => Self::Error(ErrorCode::parse(&msg.message)),
-            MessageType::Unknown(_) => Self::Other(msg.clone()),
+            MessageType::Unknown(_) => Self::Other(msg.to_owned_message()),
         }
     }
 }
@@ -658,10 +808,29 @@ impl WsEvent {
 pub mod qr {
     use super::*;
 
+    /// 二维码内容中 WebSocket 地址前的标记
+    const SOCKET_MARKER: &str = "#DGLAB-SOCKET#";
+
     /// 生成二维码内容 URL
     pub fn generate_url(server_url: &str, client_id: &str) -> String {
         let ws_url = format!("{server_url}/{client_id}");
-        format!("https://www.dungeon-lab.com/app-download.php#DGLAB-SOCKET#{ws_url}")
+        format!("https://www.dungeon-lab.com/app-download.php{SOCKET_MARKER}{ws_url}")
+    }
+
+    /// 解析二维码内容 URL（或其中的 `ws://server/<clientId>` 部分），返回服务器地址和 clientId
+    pub fn parse_url(url: &str) -> WsResult<(ServerAddress, String)> {
+        let url = url.trim();
+        let ws_url = url
+            .rsplit_once(SOCKET_MARKER)
+            .map_or(url, |(_, ws_url)| ws_url);
+        let invalid = || WsError::InvalidAddress(format!("Not a DG-LAB SOCKET link: '{}'", url));
+
+        let (server, client_id) = ws_url.rsplit_once('/').ok_or_else(invalid)?;
+        if server.ends_with(':') || server.ends_with('/') {
+            return Err(invalid());
+        }
+        validate::check_id("clientId", client_id, false).map_err(|_| invalid())?;
+        Ok((ServerAddress::parse(server)?, client_id.to_string()))
     }
 
     /// 使用官方服务器生成二维码内容 URL
@@ -697,6 +866,7 @@ mod tests {
 
         assert!(StrengthData::parse("invalid").is_none());
         assert!(StrengthData::parse("strength-1+2").is_none());
+        assert!(StrengthData::parse("strength-1+2+3+4+5").is_none());
     }
 
     #[test]
@@ -738,6 +908,15 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_feedback_button_channel() {
+        assert_eq!(FeedbackButton::A3.channel(), Channel::A);
+        assert_eq!(FeedbackButton::B0.channel(), Channel::B);
+        for (index, button) in FeedbackButton::ALL.iter().enumerate() {
+            assert_eq!(FeedbackButton::from_index(index as u8), Some(*button));
+        }
+    }
+
     #[test]
     fn test_error_code() {
         assert_eq!(ErrorCode::from(200), ErrorCode::Success);
@@ -754,11 +933,63 @@ mod tests {
         assert!(url.starts_with("https://www.dungeon-lab.com/"));
     }
 
+    #[test]
+    fn test_qr_parse_url() {
+        let id = "3ab0fb8a-3c84-4f58-9a54-6d8e2f4c1b11";
+        let (server, client_id) =
+            qr::parse_url(&qr::generate_url("ws://192.168.1.10:9999", id)).unwrap();
+        assert_eq!(server.as_str(), "ws://192.168.1.10:9999");
+        assert_eq!(client_id, id);
+
+        let (server, _) = qr::parse_url(&format!(" wss://lan/ws/{} ", id)).unwrap();
+        assert_eq!(server.as_str(), "wss://lan/ws");
+
+        assert!(qr::parse_url("ws://lan:9999").is_err());
+        assert!(qr::parse_url("ws://lan:9999/not-a-uuid").is_err());
+        assert!(qr::parse_url(&format!("ws://{}", id)).is_err());
+    }
+
     #[test]
     fn test_pulse_data() {
         let pulse = PulseData::from_strength(Channel::A, 50, 30, 1000);
         assert_eq!(pulse.pulses.len(), 10);
         let msg = pulse.to_message();
         assert!(msg.starts_with("pulse-A:["));
+
+        let frames = [
+            crate::v3::WaveformData::uniform(10, 0),
+            crate::v3::WaveformData::uniform(10, 100),
+        ];
+        let hex = frames.iter().map(|f| f.to_hex_string()).collect();
+        let expected = PulseData::new(Channel::B, hex).to_message();
+        assert_eq!(
+            expected,
+            r#"pulse-B:["0a0a0a0a00000000","0a0a0a0a64646464"]"#
+        );
+        assert_eq!(PulseData::frames_message(Channel::B, &frames), expected);
+    }
+
+    #[test]
+    fn test_pulse_data_split() {
+        let frame = "0a0a0a0a64646464".to_string();
+        let pulse = PulseData::new(Channel::B, vec![frame.clone(); 250]);
+        let chunks = pulse.split();
+        assert_eq!(
+            chunks.iter().map(|c| c.pulses.len()).collect::<Vec<_>>(),
+            vec![100, 100, 50]
+        );
+        assert!(chunks
+            .iter()
+            .all(|c| c.channel == Channel::B && c.to_message().len() <= MAX_MESSAGE_LENGTH));
+
+        // 超长的帧按长度拆分
+        let long = PulseData::new(Channel::A, vec!["0".repeat(600); 4]);
+        let chunks = long.split();
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks
+            .iter()
+            .all(|c| c.to_message().len() <= MAX_MESSAGE_LENGTH));
+
+        assert!(PulseData::new(Channel::A, vec![]).split().is_empty());
     }
 }
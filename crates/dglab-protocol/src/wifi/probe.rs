@@ -0,0 +1,177 @@
+//! 服务器连通性探测
+//!
+//! 用于排查“APP 扫码后一直不绑定”一类问题：依次测量 WebSocket 连接耗时、
+//! 服务器下发 clientId（二维码可用）的耗时和心跳往返时间。
+
+use std::fmt;
+use std::time::Duration;
+
+use tokio::time::Instant;
+use tracing::debug;
+
+use super::{ErrorCode, ReconnectPolicy, ServerAddress, WsClient, WsError, WsEvent, WsResult};
+
+/// 默认探测超时
+pub const DEFAULT_PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 探测结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProbeReport {
+    /// 服务器地址
+    pub server: String,
+    /// WebSocket 连接（含 TLS 握手）耗时
+    pub connect_time: Duration,
+    /// 从连接到收到 clientId 的耗时；`None` 表示超时未下发，APP 扫码将无法绑定
+    pub client_id_time: Option<Duration>,
+    /// 服务器分配的 clientId
+    pub client_id: Option<String>,
+    /// 心跳往返时间；`None` 表示未收到心跳回复
+    pub heartbeat_rtt: Option<Duration>,
+    /// 服务器返回的错误码
+    pub error: Option<ErrorCode>,
+}
+
+impl ProbeReport {
+    /// 服务器是否可以接受 APP 绑定
+    pub fn is_ready(&self) -> bool {
+        self.client_id.is_some() && self.error.is_none()
+    }
+}
+
+impl fmt::Display for ProbeReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ms = |d: Option<Duration>| match d {
+            Some(d) => format!("{}ms", d.as_millis()),
+            None => "timeout".to_string(),
+        };
+        write!(
+            f,
+            "{}: connect {}ms, clientId {}, heartbeat {}",
+            self.server,
+            self.connect_time.as_millis(),
+            ms(self.client_id_time),
+            ms(self.heartbeat_rtt)
+        )?;
+        if let Some(error) = self.error {
+            write!(f, ", error {}", error.description())?;
+        }
+        Ok(())
+    }
+}
+
+impl WsClient {
+    /// 探测服务器连通性
+    ///
+    /// 建立连接（不自动重连）后等待服务器下发 clientId，再发送一次心跳测量往返时间，
+    /// 每个阶段共享 `timeout`。连接失败返回错误，其余阶段超时记录在报告中。
+    pub async fn probe(address: &ServerAddress, timeout: Duration) -> WsResult<ProbeReport> {
+        let start = Instant::now();
+        let deadline = start + timeout;
+
+        let mut client = tokio::time::timeout(
+            timeout,
+            Self::connect_with_policy(address, ReconnectPolicy::disabled()),
+        )
+        .await
+        .map_err(|_| WsError::Timeout)??;
+
+        let mut report = ProbeReport {
+            server: address.to_string(),
+            connect_time: start.elapsed(),
+            client_id_time: None,
+            client_id: None,
+            heartbeat_rtt: None,
+            error: None,
+        };
+        debug!(
+            "Probe connected to {} in {:?}",
+            address, report.connect_time
+        );
+
+        let connected = Instant::now();
+        while report.client_id.is_none() {
+            match tokio::time::timeout_at(deadline, client.recv_event()).await {
+                Ok(Ok(Some(WsEvent::ClientId(id)))) => {
+                    report.client_id_time = Some(connected.elapsed());
+                    report.client_id = Some(id);
+                }
+                Ok(Ok(Some(WsEvent::Error(code)))) => report.error = Some(code),
+                Ok(Ok(Some(_))) => {}
+                Ok(Ok(None)) | Ok(Err(_)) | Err(_) => break,
+            }
+        }
+
+        if report.client_id.is_some() {
+            client.send_heartbeat().await?;
+            loop {
+                match tokio::time::timeout_at(deadline, client.recv_event()).await {
+                    Ok(Ok(Some(WsEvent::Heartbeat))) => {
+                        report.heartbeat_rtt = client.latency().await;
+                        break;
+                    }
+                    Ok(Ok(Some(WsEvent::Error(code)))) => report.error = Some(code),
+                    Ok(Ok(Some(_))) => {}
+                    Ok(Ok(None)) | Ok(Err(_)) | Err(_) => break,
+                }
+            }
+        }
+
+        let _ = client.close().await;
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::wifi::WsServer;
+
+    #[tokio::test]
+    async fn test_probe_local_server() {
+        let server = Arc::new(WsServer::new("127.0.0.1:0".to_string()));
+        let listener = server.bind().await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let serving = server.clone();
+        let _ = tokio::spawn(async move { serving.serve(listener).await });
+
+        let address = ServerAddress::parse(&url).unwrap();
+        let report = WsClient::probe(&address, Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert!(report.is_ready());
+        assert!(report.client_id_time.is_some());
+        assert!(report.heartbeat_rtt.is_some());
+        assert!(report.to_string().starts_with(&url));
+    }
+
+    #[tokio::test]
+    async fn test_probe_connection_refused() {
+        // 绑定后立即释放端口，连接会被拒绝
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        drop(listener);
+
+        let address = ServerAddress::parse(&format!("127.0.0.1:{}", port)).unwrap();
+        assert!(WsClient::probe(&address, Duration::from_secs(2))
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn test_report_display() {
+        let report = ProbeReport {
+            server: "ws://localhost:8765".to_string(),
+            connect_time: Duration::from_millis(12),
+            client_id_time: Some(Duration::from_millis(3)),
+            client_id: Some("id".to_string()),
+            heartbeat_rtt: None,
+            error: None,
+        };
+        assert_eq!(
+            report.to_string(),
+            "ws://localhost:8765: connect 12ms, clientId 3ms, heartbeat timeout"
+        );
+    }
+}
@@ -1,17 +1,59 @@
 //! WebSocket 服务器实现
 //!
-//! 根据 hyperzlib/DG-Lab-Coyote-Game-Hub 项目的实现逻辑，提供 WebSocket 服务器功能。
+//! 按官方 DG-LAB SOCKET 服务器的逻辑实现，可在局域网内代替公共服务器，
+//! 官方 APP 和 [`WsClient`] 无需任何改动即可连接。
 //!
 //! # 架构
 //!
 //! ```text
-//! 网页前端 → WebSocket → 服务器 ← WebSocket ← DG-LAB APP ← BLE ← 主机
+//! 控制端 → WebSocket → 服务器 ← WebSocket ← DG-LAB APP ← BLE ← 主机
 //! ```
 //!
-//! # 连接 URL 格式
+//! # 连接流程
 //!
-//! - DG-LAB APP: `ws://server:port/dglab/{clientId}`
-//! - 网页前端: `ws://server:port/web/{clientId}`
+//! 1. 客户端连接后，服务器分配 UUID 作为 clientId，发送
+//!    `{"type":"bind","clientId":"<id>","targetId":"","message":"targetId"}`
+//! 2. 控制端把 `ws://server:port/<clientId>` 做成二维码，APP 扫码连接后
+//!    发送 `{"type":"bind","clientId":"<控制端 id>","targetId":"<APP id>","message":"DGLAB"}`
+//! 3. 双方都在线且都未绑定时建立关系，双方收到 `message` 为 `200` 的 bind 消息
+//! 4. 此后 `msg` 消息在双方之间转发；任一方断开时另一方收到 `break`（`209`）
+//!
+//! # 心跳
+//!
+//! 服务器每 [`HEARTBEAT_INTERVAL`] 秒向所有客户端发送 `heartbeat` 消息和 WebSocket Ping。
+//! 客户端超过一个心跳间隔加 [`HEARTBEAT_TIMEOUT`] 秒没有发来任何数据（包括 Pong）时
+//! 视为失联：服务器关闭连接、解除绑定，并向另一方发送 `break`（`209`）。
+//!
+//! 出错时向发送方回复 `error` 消息，`message` 为 [`RetCode`]。
+//!
+//! # 授权
+//!
+//! 默认不需要授权。通过 [`WsServer::with_auth`] 启用令牌或 PIN 后，凭据错误的连接在握手时被拒绝，
+//! `msg` 消息只在至少一方已授权的绑定关系中转发，否则回复 `402` 并发出
+//! [`ServerEvent::AuthDenied`]。详见 [`ServerAuth`]。
+//!
+//! # TLS
+//!
+//! 通过 [`WsServer::with_tls`] 配置证书后，服务器只接受 `wss://` 连接，见 [`ServerTls`]。
+//! TLS 握手失败的连接直接关闭，不分配 clientId。
+//!
+//! # 消息校验
+//!
+//! 默认按官方服务器的逻辑只检查发送方和长度。通过 [`WsServer::with_validation`] 可以在转发前
+//! 检查消息类型、ID 格式（UUID）和 `msg` 指令语法，不合法时回复对应的错误码并发出
+//! [`ServerEvent::MessageRejected`]，或先清理（截断强度、丢弃无效波形帧）再转发，
+//! 见 [`MessageValidation`]。
+//!
+//! # 观察端
+//!
+//! 连接 URL 带查询参数 `?role=viewer` 的客户端为只读观察端（[`ClientRole::Viewer`]），
+//! 用于把一个 APP 的状态同时推送给多个网页界面：
+//!
+//! - 绑定关系中转发的 `strength-`、`pulse-`、`clear-`、`feedback-` 消息和关系断开的 `break`
+//!   会抄送给观察端，消息内容与转发给另一方的相同
+//! - `watch=<clientId>` 只观察该控制端或 APP 所在的关系，未指定时观察所有关系
+//! - 观察端只能发送心跳，其他消息回复 `402`，也不能作为绑定的任一方
+//! - 启用授权时观察端必须携带有效凭据，否则在握手时被拒绝
 //!
 //! # 示例
 //!
@@ -20,29 +62,323 @@
 //!
 //! # #[tokio::main]
 //! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
-//! let server = WsServer::new("127.0.0.1:8080".to_string());
+//! let server = WsServer::new("0.0.0.0:9999".to_string());
 //! server.start().await?;
 //! # Ok(())
 //! # }
 //! ```
+//!
+//! [`WsClient`]: super::WsClient
 
 use std::collections::HashMap;
+use std::net::SocketAddr;
 use std::sync::Arc;
-use tokio::net::{TcpListener, TcpStream};
-use tokio::sync::{broadcast, RwLock};
-use tokio_tungstenite::{accept_async, tungstenite::Message as TungsteniteMessage};
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpListener;
+use tokio::sync::{broadcast, mpsc, Notify, RwLock};
+use tokio::time::{Instant, MissedTickBehavior};
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::http::StatusCode;
+use tokio_tungstenite::{accept_hdr_async, tungstenite::Message as TungsteniteMessage};
 use tracing::{debug, error, info, warn};
 
 use super::*;
 
+/// 在线客户端
+struct Client {
+    /// 消息发送通道
+    tx: mpsc::Sender<TungsteniteMessage>,
+    /// 最近一次收到数据的时间
+    last_seen: Instant,
+    /// 通知连接任务关闭连接
+    close: Arc<Notify>,
+    /// 是否已授权转发控制消息
+    authorized: bool,
+    /// 客户端角色
+    role: ClientRole,
+    /// 观察端只观察该客户端所在的关系（为空时观察所有关系）
+    watch: Option<String>,
+}
+
+/// 客户端角色
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ClientRole {
+    /// 控制端或 APP，可以绑定并转发控制消息
+    #[default]
+    Participant,
+    /// 只读观察端，接收绑定关系中的状态消息，不能绑定或发送控制消息
+    Viewer,
+}
+
+impl ClientRole {
+    /// 从握手请求的查询参数中解析角色（`role=viewer`）和观察的客户端（`watch=<clientId>`）
+    fn from_request(request: &Request) -> (Self, Option<String>) {
+        let mut role = Self::Participant;
+        let mut watch = None;
+        let pairs = request
+            .uri()
+            .query()
+            .into_iter()
+            .flat_map(|query| query.split('&'))
+            .filter_map(|pair| pair.split_once('='));
+        for (key, value) in pairs {
+            match key {
+                "role" if value == "viewer" => role = Self::Viewer,
+                "watch" if !value.is_empty() => watch = Some(value.to_string()),
+                _ => {}
+            }
+        }
+        (role, watch)
+    }
+}
+
+/// 是否为抄送给观察端的状态消息（强度、波形、清空波形、按钮反馈）
+fn is_state_message(message: &str) -> bool {
+    let head = message.split_once('-').map_or(message, |(head, _)| head);
+    matches!(
+        head.parse::<MessageDataHead>(),
+        Ok(MessageDataHead::Strength
+            | MessageDataHead::Pulse
+            | MessageDataHead::Clear
+            | MessageDataHead::Feedback)
+    )
+}
+
+/// 客户端 ID → 客户端
+type ClientMap = HashMap<String, Client>;
+
+/// 服务器共享状态
+#[derive(Default)]
+struct ServerState {
+    /// 在线客户端
+    clients: RwLock<ClientMap>,
+    /// 绑定关系（控制端 clientId → APP targetId）
+    relations: RwLock<HashMap<String, String>>,
+}
+
+impl ServerState {
+    /// 向客户端发送消息（客户端不在线时忽略）
+    async fn send(&self, client_id: &str, msg: &WsMessage) -> bool {
+        let Ok(text) = serde_json::to_string(msg) else {
+            return false;
+        };
+        let tx = self
+            .clients
+            .read()
+            .await
+            .get(client_id)
+            .map(|c| c.tx.clone());
+        match tx {
+            Some(tx) => tx.send(TungsteniteMessage::Text(text)).await.is_ok(),
+            None => false,
+        }
+    }
+
+    /// 向客户端回复错误
+    async fn send_error(&self, client_id: &str, code: RetCode) {
+        let msg = WsMessage::new(MessageType::Error, "", "", code.as_str());
+        let _ = self.send(client_id, &msg).await;
+    }
+
+    /// 注册客户端，返回关闭通知
+    async fn register(
+        &self,
+        client_id: &str,
+        tx: mpsc::Sender<TungsteniteMessage>,
+        authorized: bool,
+        role: ClientRole,
+        watch: Option<String>,
+    ) -> Arc<Notify> {
+        let close = Arc::new(Notify::new());
+        let client = Client {
+            tx,
+            last_seen: Instant::now(),
+            close: close.clone(),
+            authorized,
+            role,
+            watch,
+        };
+        let _ = self
+            .clients
+            .write()
+            .await
+            .insert(client_id.to_string(), client);
+        close
+    }
+
+    /// 客户端角色（不在线时为空）
+    async fn role(&self, client_id: &str) -> Option<ClientRole> {
+        self.clients.read().await.get(client_id).map(|c| c.role)
+    }
+
+    /// 把关系中的消息抄送给观察该关系的观察端
+    ///
+    /// 使用 `try_send`，发送队列已满的观察端丢弃本条消息，不阻塞转发。
+    async fn publish(&self, msg: &WsMessage) {
+        let Ok(text) = serde_json::to_string(msg) else {
+            return;
+        };
+        let relation = [msg.client_id.as_str(), msg.target_id.as_str()];
+        let viewers: Vec<mpsc::Sender<TungsteniteMessage>> = self
+            .clients
+            .read()
+            .await
+            .values()
+            .filter(|c| c.role == ClientRole::Viewer)
+            .filter(|c| {
+                c.watch
+                    .as_deref()
+                    .map_or(true, |watch| relation.contains(&watch))
+            })
+            .map(|c| c.tx.clone())
+            .collect();
+
+        for tx in viewers {
+            let _ = tx.try_send(TungsteniteMessage::Text(text.clone()));
+        }
+    }
+
+    /// 任一客户端已授权
+    async fn any_authorized(&self, ids: [&str; 2]) -> bool {
+        let clients = self.clients.read().await;
+        ids.iter()
+            .any(|id| clients.get(*id).is_some_and(|c| c.authorized))
+    }
+
+    /// 记录收到客户端数据
+    async fn touch(&self, client_id: &str) {
+        if let Some(client) = self.clients.write().await.get_mut(client_id) {
+            client.last_seen = Instant::now();
+        }
+    }
+
+    /// 移除客户端并解除绑定，返回客户端是否在线
+    async fn remove(&self, client_id: &str) -> bool {
+        self.unbind(client_id).await;
+        self.clients.write().await.remove(client_id).is_some()
+    }
+
+    /// 向所有客户端发送心跳消息和 Ping
+    ///
+    /// 使用 `try_send`，发送队列已满的客户端跳过本次心跳，不阻塞服务器。
+    async fn send_heartbeats(&self) {
+        let clients: Vec<(String, mpsc::Sender<TungsteniteMessage>)> = self
+            .clients
+            .read()
+            .await
+            .iter()
+            .map(|(id, c)| (id.clone(), c.tx.clone()))
+            .collect();
+
+        for (client_id, tx) in clients {
+            let peer = self.peer_id(&client_id).await.unwrap_or_default();
+            let msg = WsMessage::new(
+                MessageType::Heartbeat,
+                client_id.as_str(),
+                peer,
+                RetCode::Success.as_str(),
+            );
+            let Ok(text) = serde_json::to_string(&msg) else {
+                continue;
+            };
+            if tx.try_send(TungsteniteMessage::Text(text)).is_ok() {
+                let _ = tx.try_send(TungsteniteMessage::Ping(Vec::new()));
+            }
+        }
+    }
+
+    /// 移除超过 `timeout` 没有发来数据的客户端，返回被移除的客户端 ID
+    ///
+    /// 另一方会收到 `break`（`209`），被移除客户端的连接随后关闭。
+    async fn expire_stale(&self, timeout: Duration) -> Vec<String> {
+        let stale: Vec<(String, Arc<Notify>)> = self
+            .clients
+            .read()
+            .await
+            .iter()
+            .filter(|(_, c)| c.last_seen.elapsed() > timeout)
+            .map(|(id, c)| (id.clone(), c.close.clone()))
+            .collect();
+
+        let mut removed = Vec::with_capacity(stale.len());
+        for (client_id, close) in stale {
+            if self.remove(&client_id).await {
+                warn!("Client {} timed out, closing connection", client_id);
+                close.notify_one();
+                removed.push(client_id);
+            }
+        }
+        removed
+    }
+
+    /// 客户端绑定的另一方
+    async fn peer_id(&self, client_id: &str) -> Option<String> {
+        self.peer_of(client_id).await.map(|(controller, app)| {
+            if controller == client_id {
+                app
+            } else {
+                controller
+            }
+        })
+    }
+
+    /// 查找客户端的绑定对象
+    async fn peer_of(&self, client_id: &str) -> Option<(String, String)> {
+        let relations = self.relations.read().await;
+        if let Some(target_id) = relations.get(client_id) {
+            return Some((client_id.to_string(), target_id.clone()));
+        }
+        relations
+            .iter()
+            .find(|(_, target_id)| *target_id == client_id)
+            .map(|(id, target_id)| (id.clone(), target_id.clone()))
+    }
+
+    /// 解除客户端的绑定并通知另一方
+    async fn unbind(&self, client_id: &str) {
+        let Some((controller, app)) = self.peer_of(client_id).await else {
+            return;
+        };
+        let _ = self.relations.write().await.remove(&controller);
+
+        let peer = if controller == client_id {
+            &app
+        } else {
+            &controller
+        };
+        let msg = WsMessage::new(
+            MessageType::Break,
+            controller.as_str(),
+            app.as_str(),
+            RetCode::ClientDisconnected.as_str(),
+        );
+        let _ = self.send(peer, &msg).await;
+        self.publish(&msg).await;
+        info!("Relation {} -> {} closed", controller, app);
+    }
+}
+
 /// WebSocket 服务器
 pub struct WsServer {
     /// 监听地址
     bind_addr: String,
-    /// 客户端管理器
-    clients: Arc<RwLock<HashMap<String, Arc<WsClientConnection>>>>,
+    /// 客户端与绑定关系
+    state: Arc<ServerState>,
     /// 事件广播
     event_tx: broadcast::Sender<ServerEvent>,
+    /// 心跳间隔
+    heartbeat_interval: Duration,
+    /// 心跳超时
+    heartbeat_timeout: Duration,
+    /// 授权方式
+    auth: Arc<ServerAuth>,
+    /// TLS 配置（为空时使用明文 ws://）
+    tls: Option<ServerTls>,
+    /// 消息校验方式
+    validation: MessageValidation,
 }
 
 /// 服务器事件
@@ -54,11 +390,25 @@ pub enum ServerEvent {
     ClientDisconnected(String),
     /// 客户端已绑定
     ClientBound {
-        /// 客户端 ID
+        /// 客户端 ID（控制端）
         client_id: String,
-        /// 目标 ID
+        /// 目标 ID（APP）
         target_id: String,
     },
+    /// 授权失败：凭据错误的连接被拒绝，或未授权的关系尝试转发控制消息
+    AuthDenied {
+        /// 客户端 ID（握手阶段被拒绝时为对端地址）
+        client: String,
+        /// 原因
+        reason: String,
+    },
+    /// 消息未通过校验被拒绝
+    MessageRejected {
+        /// 发送方
+        client: String,
+        /// 原因
+        reason: String,
+    },
     /// 收到消息
     MessageReceived {
         /// 发送方
@@ -70,312 +420,1189 @@ pub enum ServerEvent {
     },
 }
 
-/// 客户端连接
-pub struct WsClientConnection {
-    /// 客户端 ID
-    #[allow(dead_code)]
-    client_id: String,
-    /// 绑定的目标 ID
-    target_id: Arc<RwLock<Option<String>>>,
-    /// 消息发送通道
-    tx: tokio::sync::mpsc::Sender<TungsteniteMessage>,
-}
-
 impl WsServer {
     /// 创建新的服务器
     pub fn new(bind_addr: String) -> Self {
         let (event_tx, _) = broadcast::channel(100);
         Self {
             bind_addr,
-            clients: Arc::new(RwLock::new(HashMap::new())),
+            state: Arc::new(ServerState::default()),
             event_tx,
+            heartbeat_interval: Duration::from_secs(HEARTBEAT_INTERVAL),
+            heartbeat_timeout: Duration::from_secs(HEARTBEAT_TIMEOUT),
+            auth: Arc::new(ServerAuth::Open),
+            tls: None,
+            validation: MessageValidation::Off,
         }
     }
 
+    /// 启用 TLS，只接受 `wss://` 连接
+    pub fn with_tls(mut self, tls: ServerTls) -> Self {
+        self.tls = Some(tls);
+        self
+    }
+
+    /// 是否启用了 TLS
+    pub fn is_secure(&self) -> bool {
+        self.tls.is_some()
+    }
+
+    /// 连接地址的 scheme（`ws` 或 `wss`）
+    pub fn scheme(&self) -> &'static str {
+        if self.is_secure() {
+            "wss"
+        } else {
+            "ws"
+        }
+    }
+
+    /// 设置授权方式（默认 [`ServerAuth::Open`]）
+    pub fn with_auth(mut self, auth: ServerAuth) -> Self {
+        self.auth = Arc::new(auth);
+        self
+    }
+
+    /// 设置消息校验方式（默认 [`MessageValidation::Off`]）
+    pub fn with_validation(mut self, validation: MessageValidation) -> Self {
+        self.validation = validation;
+        self
+    }
+
+    /// 设置心跳间隔和超时（默认 [`HEARTBEAT_INTERVAL`] / [`HEARTBEAT_TIMEOUT`] 秒）
+    ///
+    /// 客户端超过 `interval + timeout` 没有发来数据时被断开。
+    pub fn with_heartbeat(mut self, interval: Duration, timeout: Duration) -> Self {
+        self.heartbeat_interval = interval.max(Duration::from_millis(100));
+        self.heartbeat_timeout = timeout;
+        self
+    }
+
     /// 订阅服务器事件
     pub fn subscribe_events(&self) -> broadcast::Receiver<ServerEvent> {
         self.event_tx.subscribe()
     }
 
-    /// 启动服务器
-    pub async fn start(&self) -> WsResult<()> {
-        let listener = TcpListener::bind(&self.bind_addr)
-            .await
-            .map_err(|e| WsError::Connection(e.to_string()))?;
+    /// 在线客户端数量
+    pub async fn client_count(&self) -> usize {
+        self.state.clients.read().await.len()
+    }
 
-        info!("WebSocket server listening on {}", self.bind_addr);
+    /// 在线观察端数量
+    pub async fn viewer_count(&self) -> usize {
+        self.state
+            .clients
+            .read()
+            .await
+            .values()
+            .filter(|c| c.role == ClientRole::Viewer)
+            .count()
+    }
 
-        loop {
-            match listener.accept().await {
-                Ok((stream, addr)) => {
-                    debug!("New connection from {}", addr);
-                    let clients = self.clients.clone();
-                    let event_tx = self.event_tx.clone();
-
-                    tokio::spawn(async move {
-                        if let Err(e) = Self::handle_connection(stream, clients, event_tx).await {
-                            error!("Connection error: {}", e);
-                        }
-                    });
-                }
-                Err(e) => {
-                    error!("Failed to accept connection: {}", e);
-                }
-            }
-        }
+    /// 客户端角色（不在线时为空）
+    pub async fn client_role(&self, client_id: &str) -> Option<ClientRole> {
+        self.state.role(client_id).await
     }
 
-    /// 处理新连接
-    async fn handle_connection(
-        stream: TcpStream,
-        clients: Arc<RwLock<HashMap<String, Arc<WsClientConnection>>>>,
-        event_tx: broadcast::Sender<ServerEvent>,
-    ) -> WsResult<()> {
-        let ws_stream = accept_async(stream)
+    /// 当前绑定关系（控制端 clientId, APP targetId）
+    pub async fn relations(&self) -> Vec<(String, String)> {
+        self.state
+            .relations
+            .read()
             .await
-            .map_err(|e| WsError::Connection(e.to_string()))?;
+            .iter()
+            .map(|(client_id, target_id)| (client_id.clone(), target_id.clone()))
+            .collect()
+    }
 
-        let (mut ws_sender, mut ws_receiver) = ws_stream.split();
-        use futures_util::sink::SinkExt;
-        use futures_util::stream::StreamExt;
+    /// 监听地址并启动服务器
+    pub async fn start(&self) -> WsResult<()> {
+        let listener = self.bind().await?;
+        self.serve(listener).await
+    }
 
-        // 等待客户端发送第一条消息（应该包含 clientId）
-        let first_msg = ws_receiver
-            .next()
+    /// 绑定监听地址（端口为 0 时由系统分配，可通过 `local_addr` 获取）
+    pub async fn bind(&self) -> WsResult<TcpListener> {
+        TcpListener::bind(&self.bind_addr)
             .await
-            .ok_or_else(|| WsError::Connection("Connection closed".to_string()))?
-            .map_err(|e| WsError::Connection(e.to_string()))?;
-
-        let client_id = match first_msg {
-            TungsteniteMessage::Text(text) => {
-                // 尝试从 URL 路径中提取 clientId
-                // 或者从消息中解析
-                // 这里简化处理，假设第一条消息就是 clientId
-                text.trim().to_string()
-            }
-            _ => {
-                return Err(WsError::InvalidMessage(
-                    "Expected text message with clientId".to_string(),
-                ));
-            }
-        };
-
-        // 验证 clientId
-        if client_id.is_empty() {
-            let error_msg = WsMessage::new(
-                MessageType::Error,
-                "",
-                "",
-                RetCode::InvalidClientId.as_str(),
-            );
-            let _ = ws_sender
-                .send(TungsteniteMessage::Text(
-                    serde_json::to_string(&error_msg).unwrap(),
-                ))
-                .await;
-            return Err(WsError::InvalidMessage("Invalid client ID".to_string()));
-        }
+            .map_err(|e| WsError::Connection(format!("Failed to bind {}: {}", self.bind_addr, e)))
+    }
 
-        // 检查 ID 是否已被占用
-        {
-            let clients_read = clients.read().await;
-            if clients_read.contains_key(&client_id) {
-                let error_msg = WsMessage::new(
-                    MessageType::Error,
-                    &client_id,
-                    "",
-                    RetCode::IdAlreadyBound.as_str(),
-                );
-                let _ = ws_sender
-                    .send(TungsteniteMessage::Text(
-                        serde_json::to_string(&error_msg).unwrap(),
-                    ))
-                    .await;
-                return Err(WsError::Other("ID already bound".to_string()));
-            }
-        }
+    /// 在已绑定的监听器上接受连接
+    pub async fn serve(&self, listener: TcpListener) -> WsResult<()> {
+        let local_addr: Option<SocketAddr> = listener.local_addr().ok();
+        info!(
+            "WebSocket server listening on {}://{}",
+            self.scheme(),
+            local_addr.map_or_else(|| self.bind_addr.clone(), |a| a.to_string())
+        );
 
-        info!("Client connected: {}", client_id);
+        let mut heartbeat = tokio::time::interval_at(
+            Instant::now() + self.heartbeat_interval,
+            self.heartbeat_interval,
+        );
+        heartbeat.set_missed_tick_behavior(MissedTickBehavior::Delay);
 
-        // 创建消息发送通道
-        let (tx, mut rx) = tokio::sync::mpsc::channel::<TungsteniteMessage>(100);
+        loop {
+            tokio::select! {
+                accepted = listener.accept() => match accepted {
+                    Ok((stream, addr)) => {
+                        debug!("New connection from {}", addr);
+                        let state = self.state.clone();
+                        let event_tx = self.event_tx.clone();
+                        let auth = self.auth.clone();
+                        let tls = self.tls.clone();
+                        let validation = self.validation;
 
-        // 创建客户端连接对象
-        let client_conn = Arc::new(WsClientConnection {
-            client_id: client_id.clone(),
-            target_id: Arc::new(RwLock::new(None)),
-            tx,
-        });
+                        tokio::spawn(async move {
+                            let result = match tls {
+                                Some(tls) => match tls.acceptor().accept(stream).await {
+                                    Ok(stream) => {
+                                        Self::handle_connection(
+                                            stream, addr, state, event_tx, auth, validation,
+                                        )
+                                            .await
+                                    }
+                                    Err(e) => {
+                                        warn!("TLS handshake with {} failed: {}", addr, e);
+                                        return;
+                                    }
+                                },
+                                None => {
+                                    Self::handle_connection(
+                                            stream, addr, state, event_tx, auth, validation,
+                                        )
+                                        .await
+                                }
+                            };
+                            if let Err(e) = result {
+                                error!("Connection error: {}", e);
+                            }
+                        });
+                    }
+                    Err(e) => {
+                        error!("Failed to accept connection: {}", e);
+                    }
+                },
+                _ = heartbeat.tick() => self.check_heartbeats().await,
+            }
+        }
+    }
 
-        // 注册客户端
-        {
-            let mut clients_write = clients.write().await;
-            clients_write.insert(client_id.clone(), client_conn.clone());
+    /// 断开失联的客户端并发送心跳
+    async fn check_heartbeats(&self) {
+        let timeout = self.heartbeat_interval + self.heartbeat_timeout;
+        for client_id in self.state.expire_stale(timeout).await {
+            let _ = self
+                .event_tx
+                .send(ServerEvent::ClientDisconnected(client_id));
         }
+        self.state.send_heartbeats().await;
+    }
 
-        // 触发连接事件
-        let _ = event_tx.send(ServerEvent::ClientConnected(client_id.clone()));
+    /// 处理新连接
+    async fn handle_connection<S>(
+        stream: S,
+        addr: SocketAddr,
+        state: Arc<ServerState>,
+        event_tx: broadcast::Sender<ServerEvent>,
+        auth: Arc<ServerAuth>,
+        validation: MessageValidation,
+    ) -> WsResult<()>
+    where
+        S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    {
+        // 握手时校验凭据，错误的凭据直接拒绝；启用授权时观察端必须携带凭据
+        let mut status = AuthStatus::Authorized;
+        let mut role = ClientRole::Participant;
+        let mut watch = None;
+        let check = |request: &Request, response: Response| {
+            status = auth.check(ServerAuth::credential(request).as_deref());
+            (role, watch) = ClientRole::from_request(request);
+            if role == ClientRole::Viewer && status == AuthStatus::Anonymous {
+                status = AuthStatus::Denied;
+            }
+            if status == AuthStatus::Denied {
+                let mut denied = ErrorResponse::new(Some("Invalid credentials".to_string()));
+                *denied.status_mut() = StatusCode::UNAUTHORIZED;
+                return Err(denied);
+            }
+            Ok(response)
+        };
+        let accepted = accept_hdr_async(stream, check).await;
+        if status == AuthStatus::Denied {
+            warn!("Rejected connection from {}: invalid credentials", addr);
+            let _ = event_tx.send(ServerEvent::AuthDenied {
+                client: addr.to_string(),
+                reason: "invalid credentials".to_string(),
+            });
+            return Ok(());
+        }
+        let ws_stream = accepted.map_err(|e| WsError::Connection(e.to_string()))?;
+        let (mut ws_sender, mut ws_receiver) = ws_stream.split();
 
-        // 发送 BIND 请求，要求客户端提供 targetId
-        let bind_msg = WsMessage::new(
-            MessageType::Bind,
-            "",
-            "",
-            MessageDataHead::TargetId.as_str(),
-        );
-        let _ = ws_sender
-            .send(TungsteniteMessage::Text(
-                serde_json::to_string(&bind_msg).unwrap(),
-            ))
+        // 分配 clientId 并注册
+        let client_id = uuid::Uuid::new_v4().to_string();
+        let (tx, mut rx) = mpsc::channel::<TungsteniteMessage>(100);
+        let authorized = status == AuthStatus::Authorized;
+        let close = state
+            .register(&client_id, tx, authorized, role, watch)
             .await;
+        info!("Client connected: {} ({:?})", client_id, role);
+        let _ = event_tx.send(ServerEvent::ClientConnected(client_id.clone()));
 
-        // 启动发送任务
-        let client_id_clone = client_id.clone();
-        tokio::spawn(async move {
+        // 发送任务
+        let client_id_for_send = client_id.clone();
+        let send_task = tokio::spawn(async move {
             while let Some(msg) = rx.recv().await {
                 if let Err(e) = ws_sender.send(msg).await {
-                    error!("Failed to send message to {}: {}", client_id_clone, e);
+                    error!("Failed to send message to {}: {}", client_id_for_send, e);
                     break;
                 }
             }
         });
 
-        // 启动接收任务
-        let client_id_for_recv = client_id.clone();
-        let clients_for_recv = clients.clone();
-        let event_tx_for_recv = event_tx.clone();
-        let client_conn_for_recv = client_conn.clone();
+        // 告知客户端分配到的 clientId
+        let hello = WsMessage::new(
+            MessageType::Bind,
+            client_id.as_str(),
+            "",
+            MessageDataHead::TargetId.as_str(),
+        );
+        let _ = state.send(&client_id, &hello).await;
+
+        loop {
+            let msg = tokio::select! {
+                msg = ws_receiver.next() => match msg {
+                    Some(msg) => msg,
+                    None => break,
+                },
+                _ = close.notified() => break,
+            };
+            if msg.is_ok() {
+                state.touch(&client_id).await;
+            }
 
-        while let Some(msg) = ws_receiver.next().await {
             match msg {
                 Ok(TungsteniteMessage::Text(text)) => {
-                    if let Err(e) = Self::handle_message(
-                        &text,
-                        &client_id_for_recv,
-                        &clients_for_recv,
-                        &event_tx_for_recv,
-                        &client_conn_for_recv,
-                    )
-                    .await
-                    {
-                        error!("Failed to handle message: {}", e);
-                    }
+                    Self::handle_message(&text, &client_id, &state, &event_tx, validation).await;
                 }
                 Ok(TungsteniteMessage::Close(_)) => {
-                    info!("Client {} closed connection", client_id_for_recv);
+                    info!("Client {} closed connection", client_id);
                     break;
                 }
                 Err(e) => {
-                    error!("WebSocket error for {}: {}", client_id_for_recv, e);
+                    warn!("WebSocket error for {}: {}", client_id, e);
                     break;
                 }
                 _ => {}
             }
         }
 
-        // 清理客户端
-        {
-            let mut clients_write = clients.write().await;
-            clients_write.remove(&client_id);
+        // 清理客户端（心跳超时时已被移除）
+        let online = state.remove(&client_id).await;
+        send_task.abort();
+        if online {
+            let _ = event_tx.send(ServerEvent::ClientDisconnected(client_id));
         }
 
-        // 触发断开事件
-        let _ = event_tx.send(ServerEvent::ClientDisconnected(client_id));
-
         Ok(())
     }
 
     /// 处理客户端消息
     async fn handle_message(
         text: &str,
-        client_id: &str,
-        clients: &Arc<RwLock<HashMap<String, Arc<WsClientConnection>>>>,
+        sender: &str,
+        state: &ServerState,
         event_tx: &broadcast::Sender<ServerEvent>,
-        client_conn: &Arc<WsClientConnection>,
-    ) -> WsResult<()> {
-        let msg: WsMessage = serde_json::from_str(text)
-            .map_err(|e| WsError::InvalidMessage(format!("JSON parse error: {}", e)))?;
+        validation: MessageValidation,
+    ) {
+        let msg: WsMessage = match serde_json::from_str(text) {
+            Ok(msg) => msg,
+            Err(e) => {
+                debug!("Non-JSON message from {}: {}", sender, e);
+                state.send_error(sender, RetCode::NonJsonContent).await;
+                return;
+            }
+        };
 
         debug!(
-            "Received message from {}: type={}, target={}, message={}",
-            client_id, msg.msg_type, msg.target_id, msg.message
+            "Received message from {}: type={}, client={}, target={}, message={}",
+            sender, msg.msg_type, msg.client_id, msg.target_id, msg.message
         );
 
-        match msg.message_type() {
-            MessageType::Bind => {
-                // 客户端响应绑定请求
-                if msg.message == MessageDataHead::DgLab.as_str() {
-                    // 客户端确认绑定
-                    let mut target_id_write = client_conn.target_id.write().await;
-                    *target_id_write = Some(msg.target_id.clone());
-
-                    info!("Client {} bound to {}", client_id, msg.target_id);
-
-                    // 触发绑定事件
-                    let _ = event_tx.send(ServerEvent::ClientBound {
-                        client_id: client_id.to_string(),
-                        target_id: msg.target_id.clone(),
-                    });
+        // 观察端只能发送心跳
+        if !msg.is_heartbeat() && state.role(sender).await == Some(ClientRole::Viewer) {
+            debug!("Dropped message from viewer {}", sender);
+            state
+                .send_error(sender, RetCode::IncompatibleRelationship)
+                .await;
+            return;
+        }
 
-                    // 发送绑定成功响应
-                    let response =
-                        WsMessage::new(MessageType::Bind, "", "", RetCode::Success.as_str());
-                    let _ = client_conn
-                        .tx
-                        .send(TungsteniteMessage::Text(
-                            serde_json::to_string(&response).unwrap(),
-                        ))
-                        .await;
-                }
+        let msg = match validation.apply(msg) {
+            Ok(msg) => msg,
+            Err(e) => {
+                debug!("Rejected message from {}: {}", sender, e);
+                state.send_error(sender, e.ret_code()).await;
+                let _ = event_tx.send(ServerEvent::MessageRejected {
+                    client: sender.to_string(),
+                    reason: e.to_string(),
+                });
+                return;
             }
+        };
+
+        // 发送方必须是消息中的一方
+        if msg.client_id != sender && msg.target_id != sender {
+            state.send_error(sender, RetCode::RecipientNotFound).await;
+            return;
+        }
+        if msg.message.len() > MAX_MESSAGE_LENGTH {
+            state.send_error(sender, RetCode::MessageTooLong).await;
+            return;
+        }
+
+        match msg.message_type() {
+            MessageType::Bind => Self::handle_bind(&msg, sender, state, event_tx).await,
             MessageType::Heartbeat => {
-                // 响应心跳
+                let peer = state.peer_id(sender).await;
                 let response = WsMessage::new(
                     MessageType::Heartbeat,
-                    "",
-                    "",
-                    MessageDataHead::DgLab.as_str(),
+                    sender,
+                    peer.unwrap_or_default(),
+                    RetCode::Success.as_str(),
                 );
-                let _ = client_conn
-                    .tx
-                    .send(TungsteniteMessage::Text(
-                        serde_json::to_string(&response).unwrap(),
-                    ))
-                    .await;
+                let _ = state.send(sender, &response).await;
             }
-            MessageType::Msg => {
-                // 转发消息到目标客户端
-                let target_id = &msg.target_id;
-                if target_id.is_empty() {
-                    warn!("Message from {} has no target", client_id);
-                    return Ok(());
+            MessageType::Break => {
+                info!("Client {} requested disconnect", sender);
+                state.unbind(sender).await;
+            }
+            _ => {
+                // 只在已绑定的双方之间转发
+                let bound =
+                    state.relations.read().await.get(&msg.client_id) == Some(&msg.target_id);
+                if !bound {
+                    state
+                        .send_error(sender, RetCode::IncompatibleRelationship)
+                        .await;
+                    return;
                 }
-
-                let clients_read = clients.read().await;
-                if let Some(target_conn) = clients_read.get(target_id) {
-                    let _ = target_conn
-                        .tx
-                        .send(TungsteniteMessage::Text(text.to_string()))
+                // 绑定双方都未授权时不转发控制消息
+                if !state
+                    .any_authorized([msg.client_id.as_str(), msg.target_id.as_str()])
+                    .await
+                {
+                    warn!("Dropped message from unauthorized client {}", sender);
+                    state
+                        .send_error(sender, RetCode::IncompatibleRelationship)
                         .await;
-
-                    // 触发消息事件
-                    let _ = event_tx.send(ServerEvent::MessageReceived {
-                        from: client_id.to_string(),
-                        to: target_id.clone(),
-                        message: msg.message.clone(),
+                    let _ = event_tx.send(ServerEvent::AuthDenied {
+                        client: sender.to_string(),
+                        reason: "relation is not authorized".to_string(),
                     });
+                    return;
+                }
+
+                let recipient = if msg.client_id == sender {
+                    &msg.target_id
                 } else {
-                    warn!("Target client {} not found", target_id);
+                    &msg.client_id
+                };
+                if !state.send(recipient, &msg).await {
+                    state.send_error(sender, RetCode::RecipientNotFound).await;
+                    return;
                 }
+
+                if is_state_message(&msg.message) {
+                    state.publish(&msg).await;
+                }
+
+                let _ = event_tx.send(ServerEvent::MessageReceived {
+                    from: sender.to_string(),
+                    to: recipient.clone(),
+                    message: msg.message.clone(),
+                });
             }
-            MessageType::Break => {
-                info!("Client {} requested disconnect", client_id);
+        }
+    }
+
+    /// 处理绑定请求
+    async fn handle_bind(
+        msg: &WsMessage,
+        sender: &str,
+        state: &ServerState,
+        event_tx: &broadcast::Sender<ServerEvent>,
+    ) {
+        let (client_id, target_id) = (msg.client_id.as_str(), msg.target_id.as_str());
+        {
+            // 观察端不能作为绑定的任一方
+            let clients = state.clients.read().await;
+            let participant = |id: &str| {
+                clients
+                    .get(id)
+                    .is_some_and(|c| c.role == ClientRole::Participant)
+            };
+            if client_id == target_id || !participant(client_id) || !participant(target_id) {
+                drop(clients);
+                state
+                    .send_error(sender, RetCode::TargetClientNotFound)
+                    .await;
+                return;
             }
-            _ => {
-                debug!("Unhandled message type: {:?}", msg.message_type());
+        }
+
+        {
+            let mut relations = state.relations.write().await;
+            let taken = relations.iter().any(|(c, t)| {
+                [client_id, target_id].contains(&c.as_str())
+                    || [client_id, target_id].contains(&t.as_str())
+            });
+            if taken {
+                drop(relations);
+                state.send_error(sender, RetCode::IdAlreadyBound).await;
+                return;
             }
+            let _ = relations.insert(client_id.to_string(), target_id.to_string());
         }
 
-        Ok(())
+        info!("Client {} bound to {}", client_id, target_id);
+        let response = WsMessage::new(
+            MessageType::Bind,
+            client_id,
+            target_id,
+            RetCode::Success.as_str(),
+        );
+        let _ = state.send(client_id, &response).await;
+        let _ = state.send(target_id, &response).await;
+        let _ = event_tx.send(ServerEvent::ClientBound {
+            client_id: client_id.to_string(),
+            target_id: target_id.to_string(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tokio::net::TcpStream;
+    use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+
+    type TestStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+    /// 在随机端口启动服务器
+    async fn start_server() -> (Arc<WsServer>, String) {
+        serve_on_random_port(WsServer::new("127.0.0.1:0".to_string())).await
+    }
+
+    async fn serve_on_random_port(server: WsServer) -> (Arc<WsServer>, String) {
+        let server = Arc::new(server);
+        let listener = server.bind().await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let serving = server.clone();
+        tokio::spawn(async move { serving.serve(listener).await });
+        (server, url)
+    }
+
+    async fn recv(stream: &mut TestStream) -> WsMessage {
+        let msg = tokio::time::timeout(Duration::from_secs(2), stream.next())
+            .await
+            .expect("timed out waiting for message")
+            .unwrap()
+            .unwrap();
+        serde_json::from_str(msg.to_text().unwrap()).unwrap()
+    }
+
+    async fn send(stream: &mut TestStream, msg: &WsMessage) {
+        stream
+            .send(TungsteniteMessage::Text(
+                serde_json::to_string(msg).unwrap(),
+            ))
+            .await
+            .unwrap();
+    }
+
+    /// 连接并返回分配到的 clientId
+    async fn connect(url: &str) -> (TestStream, String) {
+        let (mut stream, _) = connect_async(url).await.unwrap();
+        let hello = recv(&mut stream).await;
+        assert_eq!(hello.message_type(), MessageType::Bind);
+        assert_eq!(hello.message, "targetId");
+        assert!(!hello.client_id.is_empty());
+        (stream, hello.client_id)
+    }
+
+    /// APP 端按二维码绑定控制端
+    async fn bind(app: &mut TestStream, controller_id: &str, app_id: &str) {
+        let msg = WsMessage::new(MessageType::Bind, controller_id, app_id, "DGLAB");
+        send(app, &msg).await;
+    }
+
+    #[tokio::test]
+    async fn test_bind_forward_and_break() {
+        let (server, url) = start_server().await;
+        let (mut controller, controller_id) = connect(&url).await;
+        let (mut app, app_id) = connect(&format!("{}/{}", url, controller_id)).await;
+        assert_ne!(controller_id, app_id);
+
+        bind(&mut app, &controller_id, &app_id).await;
+        for stream in [&mut controller, &mut app] {
+            let response = recv(stream).await;
+            assert_eq!(response.message_type(), MessageType::Bind);
+            assert_eq!(response.message, "200");
+            assert_eq!(response.client_id, controller_id);
+            assert_eq!(response.target_id, app_id);
+        }
+        assert_eq!(
+            server.relations().await,
+            vec![(controller_id.clone(), app_id.clone())]
+        );
+
+        // 控制端 → APP
+        let cmd = WsMessage::new(MessageType::Msg, &controller_id, &app_id, "strength-1+2+5");
+        send(&mut controller, &cmd).await;
+        assert_eq!(recv(&mut app).await, cmd);
+
+        // APP → 控制端
+        let report = WsMessage::new(MessageType::Msg, &controller_id, &app_id, "feedback-0");
+        send(&mut app, &report).await;
+        assert_eq!(recv(&mut controller).await, report);
+
+        // APP 断开后控制端收到 break
+        app.close(None).await.unwrap();
+        let notice = recv(&mut controller).await;
+        assert_eq!(notice.message_type(), MessageType::Break);
+        assert_eq!(notice.message, "209");
+        assert!(server.relations().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_errors() {
+        let (_server, url) = start_server().await;
+        let (mut a, a_id) = connect(&url).await;
+        let (mut b, b_id) = connect(&url).await;
+        let (mut c, c_id) = connect(&url).await;
+
+        // 非 JSON
+        a.send(TungsteniteMessage::Text("hello".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(recv(&mut a).await.message, "403");
+
+        // 冒充其他客户端
+        send(&mut a, &WsMessage::new(MessageType::Msg, &b_id, &c_id, "x")).await;
+        assert_eq!(recv(&mut a).await.message, "404");
+
+        // 绑定不存在的目标
+        bind(&mut b, "missing", &b_id).await;
+        assert_eq!(recv(&mut b).await.message, "401");
+
+        // 未绑定时转发
+        send(&mut a, &WsMessage::new(MessageType::Msg, &a_id, &b_id, "x")).await;
+        assert_eq!(recv(&mut a).await.message, "402");
+
+        // 已绑定的 ID 不能再绑定
+        bind(&mut b, &a_id, &b_id).await;
+        assert_eq!(recv(&mut a).await.message, "200");
+        assert_eq!(recv(&mut b).await.message, "200");
+        bind(&mut c, &a_id, &c_id).await;
+        let error = recv(&mut c).await;
+        assert_eq!(error.message_type(), MessageType::Error);
+        assert_eq!(error.message, "400");
+
+        // 超长消息
+        let long = "x".repeat(MAX_MESSAGE_LENGTH + 1);
+        send(
+            &mut a,
+            &WsMessage::new(MessageType::Msg, &a_id, &b_id, long),
+        )
+        .await;
+        assert_eq!(recv(&mut a).await.message, "405");
+    }
+
+    #[tokio::test]
+    async fn test_validation() {
+        for validation in [MessageValidation::Strict, MessageValidation::Sanitize] {
+            let server = WsServer::new("127.0.0.1:0".to_string()).with_validation(validation);
+            let mut events = server.subscribe_events();
+            let (_server, url) = serve_on_random_port(server).await;
+            let (mut controller, controller_id) = connect(&url).await;
+            let (mut app, app_id) = connect(&format!("{}/{}", url, controller_id)).await;
+
+            // ID 不是 UUID
+            bind(&mut app, "missing", &app_id).await;
+            assert_eq!(recv(&mut app).await.message, "210");
+
+            bind(&mut app, &controller_id, &app_id).await;
+            assert_eq!(recv(&mut controller).await.message, "200");
+            assert_eq!(recv(&mut app).await.message, "200");
+
+            // 模式错误的指令无法修复
+            let bad = WsMessage::new(MessageType::Msg, &controller_id, &app_id, "strength-1+9+5");
+            send(&mut controller, &bad).await;
+            assert_eq!(recv(&mut controller).await.message, "403");
+            loop {
+                if let ServerEvent::MessageRejected { client, reason } =
+                    events.recv().await.unwrap()
+                {
+                    assert_eq!(client, controller_id);
+                    assert!(reason.contains("strength"), "{}", reason);
+                    break;
+                }
+            }
+
+            // 强度超出上限：严格模式拒绝，清理模式截断后转发
+            let cmd = WsMessage::new(
+                MessageType::Msg,
+                &controller_id,
+                &app_id,
+                "strength-1+2+250",
+            );
+            send(&mut controller, &cmd).await;
+            match validation {
+                MessageValidation::Sanitize => {
+                    assert_eq!(recv(&mut app).await.message, "strength-1+2+200");
+                }
+                _ => assert_eq!(recv(&mut controller).await.message, "403"),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_auth() {
+        let server = WsServer::new("127.0.0.1:0".to_string()).with_auth(ServerAuth::pin("1234"));
+        let mut events = server.subscribe_events();
+        let (_server, url) = serve_on_random_port(server).await;
+
+        // 错误的 PIN 在握手时被拒绝
+        assert!(connect_async(format!("{}/?pin=0000", url)).await.is_err());
+        let address = ServerAddress::parse(&url).unwrap().with_auth_token("0000");
+        assert!(
+            WsClient::connect_with_policy(&address, ReconnectPolicy::disabled())
+                .await
+                .is_err()
+        );
+        loop {
+            if let ServerEvent::AuthDenied { reason, .. } = events.recv().await.unwrap() {
+                assert_eq!(reason, "invalid credentials");
+                break;
+            }
+        }
+
+        // 已授权的控制端和扫码的 APP 可以转发控制消息
+        let (mut controller, controller_id) = connect(&format!("{}/?pin=1234", url)).await;
+        let (mut app, app_id) = connect(&format!("{}/{}", url, controller_id)).await;
+        bind(&mut app, &controller_id, &app_id).await;
+        assert_eq!(recv(&mut controller).await.message, "200");
+        assert_eq!(recv(&mut app).await.message, "200");
+        let cmd = WsMessage::new(MessageType::Msg, &controller_id, &app_id, "strength-1+2+5");
+        send(&mut controller, &cmd).await;
+        assert_eq!(recv(&mut app).await, cmd);
+
+        // 双方都未授权时拒绝转发
+        let (mut a, a_id) = connect(&url).await;
+        let (mut b, b_id) = connect(&url).await;
+        bind(&mut b, &a_id, &b_id).await;
+        assert_eq!(recv(&mut a).await.message, "200");
+        assert_eq!(recv(&mut b).await.message, "200");
+        send(&mut a, &WsMessage::new(MessageType::Msg, &a_id, &b_id, "x")).await;
+        assert_eq!(recv(&mut a).await.message, "402");
+        loop {
+            if let ServerEvent::AuthDenied { client, reason } = events.recv().await.unwrap() {
+                assert_eq!(client, a_id);
+                assert_eq!(reason, "relation is not authorized");
+                break;
+            }
+        }
+
+        // 观察端必须携带凭据
+        assert!(connect_async(format!("{}/?role=viewer", url))
+            .await
+            .is_err());
+        let _ = connect(&format!("{}/?role=viewer&pin=1234", url)).await;
+
+        // 请求头携带 PIN
+        let address = ServerAddress::parse(&url).unwrap().with_auth_token("1234");
+        let mut client = WsClient::connect_with_policy(&address, ReconnectPolicy::disabled())
+            .await
+            .unwrap();
+        loop {
+            if let Some(WsEvent::ClientId(_)) = client.recv_event().await.unwrap() {
+                break;
+            }
+        }
+        let _ = client.close().await;
+    }
+
+    /// 生成 localhost 的自签名证书
+    fn self_signed() -> ServerTls {
+        let cert = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+        ServerTls::from_pem(
+            cert.serialize_pem().unwrap().as_bytes(),
+            cert.serialize_private_key_pem().as_bytes(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_tls_invalid_pem() {
+        let cert = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+        let cert_pem = cert.serialize_pem().unwrap();
+        let key_pem = cert.serialize_private_key_pem();
+
+        assert!(matches!(
+            ServerTls::from_pem(b"", key_pem.as_bytes()),
+            Err(WsError::Certificate(_))
+        ));
+        assert!(matches!(
+            ServerTls::from_pem(cert_pem.as_bytes(), cert_pem.as_bytes()),
+            Err(WsError::Certificate(_))
+        ));
+        assert!(matches!(
+            ServerTls::from_files("/nonexistent/cert.pem", "/nonexistent/key.pem"),
+            Err(WsError::Certificate(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_tls() {
+        let server = WsServer::new("127.0.0.1:0".to_string()).with_tls(self_signed());
+        assert!(server.is_secure());
+        assert_eq!(server.scheme(), "wss");
+        let (server, url) = serve_on_random_port(server).await;
+        let port = url.rsplit(':').next().unwrap();
+
+        // 明文连接在 TLS 握手时失败
+        assert!(connect_async(&url).await.is_err());
+
+        // 不信任自签名证书时拒绝连接
+        let address = ServerAddress::parse(&format!("wss://localhost:{}", port)).unwrap();
+        assert!(
+            WsClient::connect_with_policy(&address, ReconnectPolicy::disabled())
+                .await
+                .is_err()
+        );
+
+        // 接受自签名证书后正常分配 clientId
+        let address = address.accept_invalid_certs(true);
+        let mut client = WsClient::connect_with_policy(&address, ReconnectPolicy::disabled())
+            .await
+            .unwrap();
+        loop {
+            if let Some(WsEvent::ClientId(_)) = client.recv_event().await.unwrap() {
+                break;
+            }
+        }
+        assert_eq!(server.client_count().await, 1);
+        let _ = client.close().await;
+    }
+
+    #[tokio::test]
+    async fn test_viewers() {
+        let (server, url) = start_server().await;
+        let (mut controller, controller_id) = connect(&url).await;
+        let (mut app, app_id) = connect(&format!("{}/{}", url, controller_id)).await;
+        let (mut viewer, viewer_id) = connect(&format!("{}/?role=viewer", url)).await;
+        let (mut other, _) = connect(&format!("{}/?role=viewer&watch=someone", url)).await;
+        assert_eq!(server.viewer_count().await, 2);
+        assert_eq!(
+            server.client_role(&viewer_id).await,
+            Some(ClientRole::Viewer)
+        );
+        assert_eq!(
+            server.client_role(&app_id).await,
+            Some(ClientRole::Participant)
+        );
+
+        // 观察端不能绑定
+        bind(&mut app, &viewer_id, &app_id).await;
+        assert_eq!(recv(&mut app).await.message, "401");
+        bind(&mut app, &controller_id, &app_id).await;
+        assert_eq!(recv(&mut controller).await.message, "200");
+        assert_eq!(recv(&mut app).await.message, "200");
+
+        // 状态消息抄送给观察端，其他关系的观察端收不到
+        let cmd = WsMessage::new(MessageType::Msg, &controller_id, &app_id, "strength-1+2+5");
+        send(&mut controller, &cmd).await;
+        assert_eq!(recv(&mut app).await, cmd);
+        assert_eq!(recv(&mut viewer).await, cmd);
+        let report = WsMessage::new(MessageType::Msg, &controller_id, &app_id, "feedback-0");
+        send(&mut app, &report).await;
+        assert_eq!(recv(&mut controller).await, report);
+        assert_eq!(recv(&mut viewer).await, report);
+        assert!(
+            tokio::time::timeout(Duration::from_millis(200), other.next())
+                .await
+                .is_err()
+        );
+
+        // 观察端不能发送控制消息，可以发送心跳
+        send(
+            &mut viewer,
+            &WsMessage::new(
+                MessageType::Msg,
+                &controller_id,
+                &viewer_id,
+                "strength-1+2+5",
+            ),
+        )
+        .await;
+        assert_eq!(recv(&mut viewer).await.message, "402");
+        send(
+            &mut viewer,
+            &WsMessage::new(MessageType::Heartbeat, &viewer_id, "", "DGLAB"),
+        )
+        .await;
+        assert!(recv(&mut viewer).await.is_heartbeat());
+
+        // 关系断开时观察端收到 break
+        app.close(None).await.unwrap();
+        assert_eq!(recv(&mut controller).await.message, "209");
+        let notice = recv(&mut viewer).await;
+        assert_eq!(notice.message_type(), MessageType::Break);
+        assert_eq!(notice.target_id, app_id);
+    }
+
+    #[test]
+    fn test_state_message() {
+        assert!(is_state_message("strength-1+2+5"));
+        assert!(is_state_message(r#"pulse-A:["0a0a0a0a00000000"]"#));
+        assert!(is_state_message("clear-1"));
+        assert!(is_state_message("feedback-3"));
+        assert!(!is_state_message("DGLAB"));
+        assert!(!is_state_message("custom-1"));
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat() {
+        let (_server, url) = start_server().await;
+        let (mut a, a_id) = connect(&url).await;
+        send(
+            &mut a,
+            &WsMessage::new(MessageType::Heartbeat, &a_id, "", "DGLAB"),
+        )
+        .await;
+        let response = recv(&mut a).await;
+        assert!(response.is_heartbeat());
+        assert_eq!(response.client_id, a_id);
+        assert_eq!(response.message, "200");
+    }
+
+    #[tokio::test]
+    async fn test_ws_client_binds_through_server() {
+        let (_server, url) = start_server().await;
+        let mut client = WsClient::connect(&url).await.unwrap();
+        let client_id = loop {
+            if let Some(WsEvent::ClientId(id)) = client.recv_event().await.unwrap() {
+                break id;
+            }
+        };
+        assert_eq!(
+            client.qr_url().await.unwrap(),
+            qr::generate_url(&url, &client_id)
+        );
+
+        let (mut app, app_id) = connect(&format!("{}/{}", url, client_id)).await;
+        bind(&mut app, &client_id, &app_id).await;
+        assert!(client.wait_for_bind(2).await.unwrap());
+        assert_eq!(client.target_id().await, Some(app_id));
+        let _ = client.close().await;
+    }
+
+    #[tokio::test]
+    async fn test_ws_client_rebind_saved_target() {
+        let (_server, url) = start_server().await;
+        let client = WsClient::connect(&url).await.unwrap();
+        let (mut app, app_id) = connect(&url).await;
+        while client.client_id().await.is_none() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        // 目标不存在
+        assert!(matches!(
+            client.rebind("missing", Duration::from_secs(2)).await,
+            Err(WsError::Server(ErrorCode::TargetNotFound))
+        ));
+
+        assert_eq!(
+            client
+                .rebind(&app_id, Duration::from_secs(2))
+                .await
+                .unwrap(),
+            app_id
+        );
+        assert_eq!(recv(&mut app).await.message, "200");
+        assert_eq!(client.target_id().await, Some(app_id));
+        let _ = client.close().await;
+    }
+
+    #[tokio::test]
+    async fn test_ws_client_heartbeat_latency() {
+        let (_server, url) = start_server().await;
+        let mut client = WsClient::connect(&url).await.unwrap();
+        while !matches!(
+            client.recv_event().await.unwrap(),
+            Some(WsEvent::ClientId(_))
+        ) {}
+        assert!(client.latency().await.is_none());
+
+        client.send_heartbeat().await.unwrap();
+        while !matches!(client.recv_event().await.unwrap(), Some(WsEvent::Heartbeat)) {}
+        assert!(client.latency().await.unwrap() < Duration::from_secs(2));
+        let _ = client.close().await;
+    }
+
+    #[tokio::test]
+    async fn test_ws_client_automatic_heartbeat() {
+        let (_server, url) = start_server().await;
+        let heartbeat = HeartbeatConfig {
+            interval: Duration::from_millis(100),
+            timeout: Duration::from_secs(2),
+        };
+        let mut client = WsClient::connect_with_options(
+            &ServerAddress::parse(&url).unwrap(),
+            ReconnectPolicy::disabled(),
+            RateLimit::default(),
+            heartbeat,
+        )
+        .await
+        .unwrap();
+
+        // 服务器自身的心跳间隔远大于测试时长，收到的心跳即为回复
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while !matches!(client.recv_event().await.unwrap(), Some(WsEvent::Heartbeat)) {}
+        })
+        .await
+        .unwrap();
+        assert!(client.latency().await.is_some());
+        let _ = client.close().await;
+    }
+
+    #[tokio::test]
+    async fn test_ws_client_missed_heartbeat() {
+        // 只接受连接、从不回复的服务器
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let _silent = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            while ws.next().await.is_some() {}
+        });
+
+        let heartbeat = HeartbeatConfig {
+            interval: Duration::from_millis(100),
+            timeout: Duration::from_millis(100),
+        };
+        let mut client = WsClient::connect_with_options(
+            &ServerAddress::parse(&url).unwrap(),
+            ReconnectPolicy::disabled(),
+            RateLimit::default(),
+            heartbeat,
+        )
+        .await
+        .unwrap();
+
+        let missed = tokio::time::timeout(Duration::from_secs(2), async {
+            loop {
+                if let Some(WsEvent::ServerDelay { missed }) = client.recv_event().await.unwrap() {
+                    break missed;
+                }
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(missed, 1);
+        assert!(client.latency().await.is_none());
+        let _ = client.close().await;
+    }
+
+    #[tokio::test]
+    async fn test_ws_client_correlated_requests() {
+        let (_server, url) = start_server().await;
+        let mut client = WsClient::connect(&url).await.unwrap();
+        let client_id = loop {
+            if let Some(WsEvent::ClientId(id)) = client.recv_event().await.unwrap() {
+                break id;
+            }
+        };
+
+        // 未绑定时强度操作直接失败
+        assert!(matches!(
+            client
+                .send_strength(
+                    StrengthOperation::set(Channel::A, 10),
+                    Duration::from_secs(1)
+                )
+                .await,
+            Err(WsError::NotBound)
+        ));
+
+        let (mut app, app_id) = connect(&url).await;
+        let pending_bind = {
+            let client = client.clone();
+            tokio::spawn(async move { client.bind(Duration::from_secs(2)).await })
+        };
+        bind(&mut app, &client_id, &app_id).await;
+        assert_eq!(recv(&mut app).await.message, "200");
+        assert_eq!(pending_bind.await.unwrap().unwrap(), app_id);
+        // 已绑定时立即返回
+        assert_eq!(client.bind(Duration::ZERO).await.unwrap(), app_id);
+
+        // APP 收到强度操作后回传当前强度
+        let pending_strength = {
+            let client = client.clone();
+            tokio::spawn(async move {
+                client
+                    .send_strength(
+                        StrengthOperation::set(Channel::A, 20),
+                        Duration::from_secs(2),
+                    )
+                    .await
+            })
+        };
+        let op = recv(&mut app).await;
+        assert_eq!(op.message, "strength-1+2+20");
+        send(
+            &mut app,
+            &WsMessage::new(
+                MessageType::Msg,
+                &client_id,
+                &app_id,
+                "strength-20+0+200+200",
+            ),
+        )
+        .await;
+        let strength = pending_strength.await.unwrap().unwrap();
+        assert_eq!(strength.strength_a, 20);
+        assert_eq!(strength.max_b, 200);
+
+        // 对方断开时等待中的操作以错误结束
+        let pending_strength = {
+            let client = client.clone();
+            tokio::spawn(async move {
+                client
+                    .send_strength(
+                        StrengthOperation::set(Channel::A, 30),
+                        Duration::from_secs(2),
+                    )
+                    .await
+            })
+        };
+        let _ = recv(&mut app).await;
+        drop(app);
+        assert!(matches!(
+            pending_strength.await.unwrap(),
+            Err(WsError::Server(ErrorCode::PeerDisconnected))
+        ));
+        let _ = client.close().await;
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_stale_client_expires() {
+        let state = ServerState::default();
+        let (a_tx, _a_rx) = mpsc::channel(10);
+        let (b_tx, mut b_rx) = mpsc::channel(10);
+        let a_close = state.register("a", a_tx, true).await;
+        let _b_close = state.register("b", b_tx, true).await;
+        let _ = state
+            .relations
+            .write()
+            .await
+            .insert("a".to_string(), "b".to_string());
+
+        tokio::time::advance(Duration::from_secs(30)).await;
+        state.touch("b").await;
+        tokio::time::advance(Duration::from_secs(15)).await;
+
+        // a 已 45 秒无数据，b 为 15 秒
+        assert!(state.expire_stale(Duration::from_secs(50)).await.is_empty());
+        assert_eq!(
+            state.expire_stale(Duration::from_secs(40)).await,
+            vec!["a".to_string()]
+        );
+        assert!(state.relations.read().await.is_empty());
+        assert_eq!(state.clients.read().await.len(), 1);
+
+        // 另一方收到 break，被移除的连接收到关闭通知
+        let TungsteniteMessage::Text(text) = b_rx.try_recv().unwrap() else {
+            panic!("expected text message");
+        };
+        let notice: WsMessage = serde_json::from_str(&text).unwrap();
+        assert_eq!(notice.message_type(), MessageType::Break);
+        assert_eq!(notice.message, "209");
+        tokio::time::timeout(Duration::from_millis(1), a_close.notified())
+            .await
+            .unwrap();
+
+        // 已移除的客户端不会重复报告
+        assert!(!state.remove("a").await);
+    }
+
+    #[tokio::test]
+    async fn test_send_heartbeats() {
+        let state = ServerState::default();
+        let (a_tx, mut a_rx) = mpsc::channel(10);
+        let (b_tx, _b_rx) = mpsc::channel(1);
+        let _a_close = state.register("a", a_tx, true).await;
+        let _b_close = state.register("b", b_tx, true).await;
+        let _ = state
+            .relations
+            .write()
+            .await
+            .insert("a".to_string(), "b".to_string());
+
+        state.send_heartbeats().await;
+        let TungsteniteMessage::Text(text) = a_rx.try_recv().unwrap() else {
+            panic!("expected text message");
+        };
+        let heartbeat: WsMessage = serde_json::from_str(&text).unwrap();
+        assert!(heartbeat.is_heartbeat());
+        assert_eq!(heartbeat.client_id, "a");
+        assert_eq!(heartbeat.target_id, "b");
+        assert_eq!(heartbeat.message, "200");
+        assert!(matches!(a_rx.try_recv(), Ok(TungsteniteMessage::Ping(_))));
+
+        // 队列已满的客户端跳过，不阻塞
+        state.send_heartbeats().await;
+    }
+
+    #[tokio::test]
+    async fn test_unresponsive_client_is_dropped() {
+        let server = WsServer::new("127.0.0.1:0".to_string())
+            .with_heartbeat(Duration::from_millis(100), Duration::from_millis(100));
+        let (server, url) = serve_on_random_port(server).await;
+        let mut events = server.subscribe_events();
+        let (mut controller, controller_id) = connect(&url).await;
+        let (mut app, app_id) = connect(&url).await;
+        bind(&mut app, &controller_id, &app_id).await;
+        assert_eq!(recv(&mut controller).await.message, "200");
+
+        // APP 不再读取（不回复 Pong），控制端持续读取
+        let notice = tokio::time::timeout(Duration::from_secs(2), async {
+            loop {
+                let msg = controller.next().await.unwrap().unwrap();
+                if let TungsteniteMessage::Text(text) = msg {
+                    let msg: WsMessage = serde_json::from_str(&text).unwrap();
+                    if msg.message_type() == MessageType::Break {
+                        break msg;
+                    }
+                    assert!(msg.is_heartbeat());
+                }
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(notice.message, "209");
+        assert!(server.relations().await.is_empty());
+        assert_eq!(server.client_count().await, 1);
+
+        let dropped = loop {
+            if let ServerEvent::ClientDisconnected(id) = events.recv().await.unwrap() {
+                break id;
+            }
+        };
+        assert_eq!(dropped, app_id);
+        drop(app);
     }
 }
@@ -0,0 +1,74 @@
+//! 内置服务器的 TLS（wss://）
+//!
+//! 部分网络（或 APP 所在环境）只允许安全 WebSocket。为 [`WsServer`] 配置证书和私钥后，
+//! 服务器在 WebSocket 握手前完成 TLS 握手，二维码中的地址相应变为 `wss://`。
+//!
+//! 证书和私钥均为 PEM 格式，证书文件可以包含完整证书链；私钥支持 PKCS#8、PKCS#1 和 SEC1。
+//! 局域网内使用自签名证书时，[`WsClient`] 需要设置
+//! [`ServerAddress::accept_invalid_certs`]，APP 所在设备需要信任该证书。
+//!
+//! [`WsServer`]: super::WsServer
+//! [`WsClient`]: super::WsClient
+//! [`ServerAddress::accept_invalid_certs`]: super::ServerAddress::accept_invalid_certs
+
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+
+use tokio_rustls::rustls::ServerConfig;
+use tokio_rustls::TlsAcceptor;
+
+use super::{WsError, WsResult};
+
+/// 服务器 TLS 配置
+#[derive(Clone)]
+pub struct ServerTls {
+    /// TLS 握手
+    acceptor: TlsAcceptor,
+}
+
+impl ServerTls {
+    /// 从 PEM 格式的证书链和私钥创建
+    pub fn from_pem(cert_pem: &[u8], key_pem: &[u8]) -> WsResult<Self> {
+        let certs = rustls_pemfile::certs(&mut &cert_pem[..])
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| WsError::Certificate(format!("Invalid certificate: {}", e)))?;
+        if certs.is_empty() {
+            return Err(WsError::Certificate(
+                "No certificate found in PEM".to_string(),
+            ));
+        }
+        let key = rustls_pemfile::private_key(&mut &key_pem[..])
+            .map_err(|e| WsError::Certificate(format!("Invalid private key: {}", e)))?
+            .ok_or_else(|| WsError::Certificate("No private key found in PEM".to_string()))?;
+
+        let config = ServerConfig::builder()
+            .with_no_client_auth()
+            .with_single_cert(certs, key)
+            .map_err(|e| WsError::Certificate(e.to_string()))?;
+        Ok(Self {
+            acceptor: TlsAcceptor::from(Arc::new(config)),
+        })
+    }
+
+    /// 从证书文件和私钥文件创建
+    pub fn from_files(cert_path: impl AsRef<Path>, key_path: impl AsRef<Path>) -> WsResult<Self> {
+        let read = |path: &Path| {
+            std::fs::read(path).map_err(|e| {
+                WsError::Certificate(format!("Failed to read {}: {}", path.display(), e))
+            })
+        };
+        Self::from_pem(&read(cert_path.as_ref())?, &read(key_path.as_ref())?)
+    }
+
+    /// TLS 握手
+    pub(super) fn acceptor(&self) -> &TlsAcceptor {
+        &self.acceptor
+    }
+}
+
+impl fmt::Debug for ServerTls {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ServerTls").finish_non_exhaustive()
+    }
+}
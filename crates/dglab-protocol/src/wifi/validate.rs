@@ -0,0 +1,526 @@
+//! WebSocket 消息校验与清理
+//!
+//! 服务器和客户端按原样接受 `message` 字段中的任意字符串。本模块按协议语法检查消息：
+//!
+//! - `type` 必须是 `heartbeat`、`bind`、`msg`、`break`、`error` 之一
+//! - `clientId` / `targetId` 必须是 UUID（心跳、分配 clientId 的 bind 消息允许为空）
+//! - `bind` 消息内容为 `targetId`、`DGLAB` 或返回码，`break` / `error` 为返回码
+//! - `msg` 消息必须是以下指令之一（通道可以是 `A`/`B` 或桥接器的通道编号）：
+//!   - `strength-{通道}+{模式 0~2}+{数值}`，或 APP 上报的 `strength-{A}+{B}+{A 上限}+{B 上限}`（可多组）
+//!   - `pulse-{通道}:["HEX",...]`，每帧 8 字节，最多 [`MAX_PULSES_PER_MESSAGE`] 帧
+//!   - `clear-{通道}`
+//!   - `feedback-{0~9}`
+//!
+//! [`sanitize`] 在校验前先清理可以修复的问题：去掉首尾空白、统一 `type` 大小写、
+//! 强度截断到 [`MAX_STRENGTH`]、丢弃无效的波形帧和超出数量的帧。
+
+use thiserror::Error;
+
+use super::{
+    FeedbackButton, MessageDataHead, MessageType, PulseData, PulseMessage, RetCode, WsMessage,
+    MAX_MESSAGE_LENGTH, MAX_PULSES_PER_MESSAGE,
+};
+use crate::v3::{validate::check_waveform, WaveformData, MAX_STRENGTH};
+
+/// 消息校验错误
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum MessageError {
+    /// 未知的消息类型
+    #[error("Unknown message type '{0}'")]
+    UnknownType(String),
+
+    /// 客户端 ID 格式错误
+    #[error("Invalid {field} '{value}', expected a UUID")]
+    InvalidId {
+        /// 字段名（`clientId` 或 `targetId`）
+        field: &'static str,
+        /// 原始值
+        value: String,
+    },
+
+    /// 消息过长
+    #[error("Message is {0} bytes, limit is {}", MAX_MESSAGE_LENGTH)]
+    TooLong(usize),
+
+    /// 消息内容与类型不符
+    #[error("Unexpected content '{message}' in {msg_type} message")]
+    UnexpectedContent {
+        /// 消息类型
+        msg_type: String,
+        /// 消息内容
+        message: String,
+    },
+
+    /// 未知的 `msg` 指令
+    #[error("Unknown command '{0}'")]
+    UnknownCommand(String),
+
+    /// 指令格式错误
+    #[error("Invalid {command} command: {reason}")]
+    InvalidCommand {
+        /// 指令（`strength`、`pulse`、`clear`、`feedback`）
+        command: &'static str,
+        /// 原因
+        reason: String,
+    },
+}
+
+impl MessageError {
+    /// 回复给发送方的返回码
+    pub fn ret_code(&self) -> RetCode {
+        match self {
+            Self::InvalidId { .. } => RetCode::InvalidClientId,
+            Self::TooLong(_) => RetCode::MessageTooLong,
+            _ => RetCode::NonJsonContent,
+        }
+    }
+
+    fn command(command: &'static str, reason: impl Into<String>) -> Self {
+        Self::InvalidCommand {
+            command,
+            reason: reason.into(),
+        }
+    }
+}
+
+/// 消息校验方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MessageValidation {
+    /// 不校验，按原样转发（与官方服务器一致）
+    #[default]
+    Off,
+    /// 不符合语法的消息被拒绝
+    Strict,
+    /// 先清理再校验，清理后仍不符合的消息被拒绝
+    Sanitize,
+}
+
+impl MessageValidation {
+    /// 按校验方式处理消息，返回可以转发的消息
+    pub fn apply(self, msg: WsMessage) -> Result<WsMessage, MessageError> {
+        match self {
+            Self::Off => Ok(msg),
+            Self::Strict => check_message(&msg).map(|()| msg),
+            Self::Sanitize => sanitize(&msg),
+        }
+    }
+}
+
+impl std::fmt::Display for MessageValidation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Off => "off",
+            Self::Strict => "strict",
+            Self::Sanitize => "sanitize",
+        })
+    }
+}
+
+impl std::str::FromStr for MessageValidation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "off" => Ok(Self::Off),
+            "strict" => Ok(Self::Strict),
+            "sanitize" => Ok(Self::Sanitize),
+            other => Err(format!(
+                "Unknown validation mode '{}', expected off, strict or sanitize",
+                other
+            )),
+        }
+    }
+}
+
+/// 检查完整消息
+pub fn check_message(msg: &WsMessage) -> Result<(), MessageError> {
+    if msg.message.len() > MAX_MESSAGE_LENGTH {
+        return Err(MessageError::TooLong(msg.message.len()));
+    }
+
+    let msg_type = match msg.msg_type.as_str() {
+        "heartbeat" | "bind" | "msg" | "break" | "error" => msg.message_type(),
+        _ => return Err(MessageError::UnknownType(msg.msg_type.clone())),
+    };
+    let unexpected = || MessageError::UnexpectedContent {
+        msg_type: msg.msg_type.clone(),
+        message: msg.message.clone(),
+    };
+
+    // 心跳和分配 clientId 的 bind 消息可能还没有对方的 ID
+    let assigning = msg_type == MessageType::Bind && msg.message == "targetId";
+    let optional_ids = msg_type == MessageType::Heartbeat || assigning;
+    check_id(
+        "clientId",
+        &msg.client_id,
+        msg_type == MessageType::Heartbeat,
+    )?;
+    check_id("targetId", &msg.target_id, optional_ids)?;
+
+    match msg_type {
+        MessageType::Heartbeat if msg.message.is_empty() || is_ret_code(&msg.message) => Ok(()),
+        MessageType::Bind if assigning || msg.message == "DGLAB" || is_ret_code(&msg.message) => {
+            Ok(())
+        }
+        MessageType::Break | MessageType::Error if is_ret_code(&msg.message) => Ok(()),
+        MessageType::Msg => check_command(&msg.message),
+        _ => Err(unexpected()),
+    }
+}
+
+/// 检查客户端 ID，`optional` 时允许为空
+pub fn check_id(field: &'static str, id: &str, optional: bool) -> Result<(), MessageError> {
+    // 只接受带连字符的标准格式，与服务器分配的 ID 一致
+    let valid =
+        (optional && id.is_empty()) || (id.len() == 36 && uuid::Uuid::try_parse(id).is_ok());
+    if !valid {
+        return Err(MessageError::InvalidId {
+            field,
+            value: id.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// 检查 `msg` 消息的指令
+pub fn check_command(message: &str) -> Result<(), MessageError> {
+    let Some((head, body)) = message.split_once('-') else {
+        return Err(MessageError::UnknownCommand(message.to_string()));
+    };
+    match head.parse::<MessageDataHead>() {
+        Ok(MessageDataHead::Strength) => check_strength(body),
+        Ok(MessageDataHead::Pulse) => check_pulse(message),
+        Ok(MessageDataHead::Clear) => parse_label(body)
+            .map(|_| ())
+            .ok_or_else(|| MessageError::command("clear", format!("bad channel '{}'", body))),
+        Ok(MessageDataHead::Feedback)
+            if body.len() == 1 && FeedbackButton::parse(message).is_some() =>
+        {
+            Ok(())
+        }
+        Ok(MessageDataHead::Feedback) => Err(MessageError::command(
+            "feedback",
+            format!("bad button '{}'", body),
+        )),
+        _ => Err(MessageError::UnknownCommand(message.to_string())),
+    }
+}
+
+/// 清理消息后校验，返回清理后的消息
+pub fn sanitize(msg: &WsMessage) -> Result<WsMessage, MessageError> {
+    let mut sanitized = WsMessage {
+        msg_type: msg.msg_type.trim().to_ascii_lowercase(),
+        client_id: msg.client_id.trim().to_string(),
+        target_id: msg.target_id.trim().to_string(),
+        message: msg.message.trim().to_string(),
+    };
+    if sanitized.is_msg() {
+        sanitized.message = sanitize_command(&sanitized.message)?;
+    }
+    check_message(&sanitized)?;
+    Ok(sanitized)
+}
+
+/// 清理 `msg` 指令：强度截断到上限，丢弃无效或超出数量的波形帧
+///
+/// 无法修复（如通道错误、没有有效波形帧）时返回错误。
+pub fn sanitize_command(message: &str) -> Result<String, MessageError> {
+    let message = message.trim();
+    if let Some(body) = message.strip_prefix("strength-") {
+        let fields = body
+            .split('+')
+            .map(|field| {
+                field.trim().parse::<u32>().map_err(|_| {
+                    MessageError::command("strength", format!("bad number '{}'", field))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        // 操作指令的通道和模式不截断
+        let fixed = if fields.len() == 3 { 2 } else { 0 };
+        let fields: Vec<String> = fields
+            .iter()
+            .enumerate()
+            .map(|(i, value)| {
+                let value = if i < fixed {
+                    *value
+                } else {
+                    (*value).min(u32::from(MAX_STRENGTH))
+                };
+                value.to_string()
+            })
+            .collect();
+        return Ok(format!("strength-{}", fields.join("+")));
+    }
+
+    if message.starts_with("pulse-") {
+        let pulse = PulseMessage::parse(message)
+            .ok_or_else(|| MessageError::command("pulse", "malformed pulse array"))?;
+        let channel = parse_label(pulse.label).ok_or_else(|| {
+            MessageError::command("pulse", format!("bad channel '{}'", pulse.label))
+        })?;
+        let total = pulse.entries().count();
+        let frames: Vec<WaveformData> = pulse
+            .frames()
+            .filter_map(|frame| frame.ok())
+            .filter(|frame| check_waveform(channel, frame).is_ok())
+            .take(MAX_PULSES_PER_MESSAGE)
+            .collect();
+        if frames.is_empty() && total > 0 {
+            return Err(MessageError::command("pulse", "no valid frames"));
+        }
+        return Ok(PulseData::labeled_frames_message(pulse.label, &frames));
+    }
+
+    Ok(message.to_string())
+}
+
+/// 检查强度指令（操作或上报）
+fn check_strength(body: &str) -> Result<(), MessageError> {
+    let fields: Vec<&str> = body.split('+').collect();
+    let number = |field: &str| {
+        field
+            .parse::<u8>()
+            .map_err(|_| MessageError::command("strength", format!("bad number '{}'", field)))
+    };
+    let in_range = |value: u8| {
+        if value > MAX_STRENGTH {
+            return Err(MessageError::command(
+                "strength",
+                format!("value {} exceeds {}", value, MAX_STRENGTH),
+            ));
+        }
+        Ok(())
+    };
+
+    match fields.as_slice() {
+        // 操作：通道 + 模式 + 数值
+        [channel, mode, value] => {
+            if parse_address(channel).is_none() {
+                return Err(MessageError::command(
+                    "strength",
+                    format!("bad channel '{}'", channel),
+                ));
+            }
+            if number(mode)? > 2 {
+                return Err(MessageError::command(
+                    "strength",
+                    format!("bad mode '{}'", mode),
+                ));
+            }
+            in_range(number(value)?)
+        }
+        // 上报：每台主机 A、B、A 上限、B 上限
+        fields if !fields.is_empty() && fields.len() % 4 == 0 => {
+            fields.iter().try_for_each(|field| in_range(number(field)?))
+        }
+        _ => Err(MessageError::command(
+            "strength",
+            format!("expected 3 or a multiple of 4 fields, got {}", fields.len()),
+        )),
+    }
+}
+
+/// 检查波形指令
+fn check_pulse(message: &str) -> Result<(), MessageError> {
+    let pulse = PulseMessage::parse(message)
+        .ok_or_else(|| MessageError::command("pulse", "malformed pulse array"))?;
+    let channel = parse_label(pulse.label)
+        .ok_or_else(|| MessageError::command("pulse", format!("bad channel '{}'", pulse.label)))?;
+
+    let mut count = 0;
+    for (index, frame) in pulse.frames().enumerate() {
+        let frame = frame.map_err(|entry| {
+            MessageError::command(
+                "pulse",
+                format!("frame {} '{}' is not 8-byte hex", index, entry),
+            )
+        })?;
+        check_waveform(channel, &frame)
+            .map_err(|e| MessageError::command("pulse", format!("frame {}: {}", index, e)))?;
+        count += 1;
+    }
+    if count > MAX_PULSES_PER_MESSAGE {
+        return Err(MessageError::command(
+            "pulse",
+            format!("{} frames, limit is {}", count, MAX_PULSES_PER_MESSAGE),
+        ));
+    }
+    Ok(())
+}
+
+/// 解析通道标签，返回通道（0=A, 1=B）：`A` / `B` 或从 1 开始的通道编号（奇数为 A）
+fn parse_label(label: &str) -> Option<u8> {
+    match label {
+        "A" => Some(0),
+        "B" => Some(1),
+        _ => parse_address(label),
+    }
+}
+
+/// 解析从 1 开始的通道编号（桥接多台主机时第 n 台的 A/B 为 2n-1 / 2n）
+fn parse_address(address: &str) -> Option<u8> {
+    if !address.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let number: u32 = address.parse().ok()?;
+    let index = number.checked_sub(1)?;
+    Some((index % 2) as u8)
+}
+
+/// 是否为返回码（三位数字）
+fn is_ret_code(message: &str) -> bool {
+    message.len() == 3 && message.bytes().all(|b| b.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTROLLER: &str = "3ab0fb8a-3c84-4f58-9a54-6d8e2f4c1b11";
+    const APP: &str = "8d1e4c27-5b0a-4a8e-bd4c-0f4b7c9e2a22";
+
+    fn msg(msg_type: &str, message: &str) -> WsMessage {
+        WsMessage {
+            msg_type: msg_type.to_string(),
+            client_id: CONTROLLER.to_string(),
+            target_id: APP.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_check_message() {
+        for (msg_type, message) in [
+            ("msg", "strength-1+2+30"),
+            ("msg", "strength-3+0+5"),
+            ("msg", "strength-10+20+100+200"),
+            ("msg", r#"pulse-A:["0a0a0a0a00000000","0a0a0a0a64646464"]"#),
+            ("msg", "pulse-B:[]"),
+            ("msg", "clear-2"),
+            ("msg", "feedback-9"),
+            ("bind", "DGLAB"),
+            ("bind", "200"),
+            ("break", "209"),
+            ("error", "402"),
+            ("heartbeat", "200"),
+        ] {
+            assert_eq!(
+                check_message(&msg(msg_type, message)),
+                Ok(()),
+                "{}",
+                message
+            );
+        }
+
+        // 分配 clientId 时没有 targetId，心跳可以没有 ID
+        let mut hello = msg("bind", "targetId");
+        hello.target_id.clear();
+        assert!(check_message(&hello).is_ok());
+        let heartbeat = WsMessage::new(MessageType::Heartbeat, "", "", "");
+        assert!(check_message(&heartbeat).is_ok());
+    }
+
+    #[test]
+    fn test_check_message_errors() {
+        let check = |msg_type: &str, message: &str| check_message(&msg(msg_type, message));
+
+        assert!(matches!(
+            check("ping", "200"),
+            Err(MessageError::UnknownType(_))
+        ));
+        assert!(matches!(
+            check("msg", "hello"),
+            Err(MessageError::UnknownCommand(_))
+        ));
+        assert!(matches!(
+            check("bind", "hello"),
+            Err(MessageError::UnexpectedContent { .. })
+        ));
+        for message in [
+            "strength-0+2+30",
+            "strength-1+3+30",
+            "strength-1+2+201",
+            "strength-1+2",
+            "strength-1+2+3+4+5",
+            "pulse-C:[]",
+            r#"pulse-A:["zz"]"#,
+            r#"pulse-A:["0a0a0a0a65656565"]"#,
+            "clear-0",
+            "feedback-10",
+        ] {
+            assert!(
+                matches!(
+                    check("msg", message),
+                    Err(MessageError::InvalidCommand { .. })
+                ),
+                "{}",
+                message
+            );
+        }
+        let frames = vec![r#""0a0a0a0a00000000""#; MAX_PULSES_PER_MESSAGE + 1].join(",");
+        assert!(check("msg", &format!("pulse-A:[{}]", frames)).is_err());
+
+        let mut spoofed = msg("msg", "clear-1");
+        spoofed.client_id = "missing".to_string();
+        let err = check_message(&spoofed).unwrap_err();
+        assert_eq!(err.ret_code(), RetCode::InvalidClientId);
+
+        let long = check("msg", &"x".repeat(MAX_MESSAGE_LENGTH + 1)).unwrap_err();
+        assert_eq!(long.ret_code(), RetCode::MessageTooLong);
+    }
+
+    #[test]
+    fn test_sanitize() {
+        let mut dirty = msg(" MSG ", " strength-1+2+250 ");
+        dirty.client_id = format!(" {} ", CONTROLLER);
+        let clean = sanitize(&dirty).unwrap();
+        assert_eq!(clean, msg("msg", "strength-1+2+200"));
+
+        let clean = sanitize(&msg("msg", "strength-5+250+100+300")).unwrap();
+        assert_eq!(clean.message, "strength-5+200+100+200");
+
+        // 丢弃无效帧
+        let pulse =
+            r#"pulse-B:["0a0a0a0a64646464", 1, "zz", "0a0a0a0a65656565", "0A0A0A0A00000000"]"#;
+        let clean = sanitize(&msg("msg", pulse)).unwrap();
+        assert_eq!(
+            clean.message,
+            r#"pulse-B:["0a0a0a0a64646464","0a0a0a0a00000000"]"#
+        );
+        assert!(sanitize(&msg("msg", r#"pulse-B:["zz"]"#)).is_err());
+
+        // 超出数量的帧被截断
+        let frames = vec![r#""0a0a0a0a00000000""#; 150].join(",");
+        let clean = sanitize(&msg("msg", &format!("pulse-3:[{}]", frames))).unwrap();
+        assert!(check_message(&clean).is_ok());
+        assert_eq!(
+            PulseMessage::parse(&clean.message)
+                .unwrap()
+                .entries()
+                .count(),
+            100
+        );
+
+        // 无法修复的消息仍被拒绝
+        assert!(sanitize(&msg("msg", "clear-C")).is_err());
+        assert!(sanitize(&msg("msg", "strength-1+x+2")).is_err());
+    }
+
+    #[test]
+    fn test_validation_mode() {
+        assert_eq!("Strict".parse(), Ok(MessageValidation::Strict));
+        assert_eq!(MessageValidation::Sanitize.to_string(), "sanitize");
+        assert!("loose".parse::<MessageValidation>().is_err());
+
+        let dirty = msg("msg", " clear-1 ");
+        assert_eq!(
+            MessageValidation::Off.apply(dirty.clone()),
+            Ok(dirty.clone())
+        );
+        assert!(MessageValidation::Strict.apply(dirty.clone()).is_err());
+        assert_eq!(
+            MessageValidation::Sanitize.apply(dirty).unwrap().message,
+            "clear-1"
+        );
+    }
+}